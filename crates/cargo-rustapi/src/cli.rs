@@ -3,13 +3,20 @@
 #[cfg(feature = "replay")]
 use crate::commands::ReplayArgs;
 use crate::commands::{
-    self, AddArgs, BenchArgs, ClientArgs, DeployArgs, DoctorArgs, GenerateArgs, LogoutArgs,
-    MigrateArgs, NewArgs, ObservabilityArgs, RunArgs, WatchArgs, WhoamiArgs,
+    self, AddArgs, BenchArgs, ClientArgs, DeployArgs, DoctorArgs, ErrorsArgs, GenerateArgs,
+    LogoutArgs, MigrateArgs, NewArgs, ObservabilityArgs, OpenapiArgs, RoutesArgs, RunArgs,
+    SeedArgs, WatchArgs, WhoamiArgs,
 };
 
 #[cfg(feature = "cloud")]
 use crate::commands::LoginArgs;
 
+#[cfg(feature = "remote-spec")]
+use crate::commands::ExportArgs;
+
+#[cfg(feature = "admin")]
+use crate::commands::LogLevelArgs;
+
 #[cfg(feature = "mcp")]
 use crate::commands::McpGenerateArgs;
 use clap::{Parser, Subcommand};
@@ -41,7 +48,7 @@ enum Commands {
     /// Add a feature or dependency
     Add(AddArgs),
 
-    /// Run the benchmark workflow
+    /// Run the benchmark workflow, or load test routes against a stored baseline
     Bench(BenchArgs),
 
     /// Check environment health
@@ -58,6 +65,9 @@ enum Commands {
     #[command(subcommand)]
     Migrate(MigrateArgs),
 
+    /// Populate the database with seed data (SQL files and/or a Rust seeder)
+    Seed(SeedArgs),
+
     /// Open API documentation in browser
     Docs {
         /// Port to check for running server
@@ -68,6 +78,20 @@ enum Commands {
     /// Generate API client from OpenAPI spec
     Client(ClientArgs),
 
+    /// Dump the error code catalog of the current project
+    Errors(ErrorsArgs),
+
+    /// Export the OpenAPI spec of the current project or diff it against a baseline
+    #[command(subcommand)]
+    Openapi(OpenapiArgs),
+
+    /// Print the route table of the current project (method, path, handler, tags, middleware)
+    Routes(RoutesArgs),
+
+    /// Prerender the HTML pages of a running app to static files
+    #[cfg(feature = "remote-spec")]
+    Export(ExportArgs),
+
     /// MCP tools — turn any OpenAPI spec into an MCP server for agents.
     ///
     /// If no --spec/--url/--api is given, it will automatically generate
@@ -99,6 +123,10 @@ enum Commands {
     #[cfg(feature = "replay")]
     #[command(subcommand)]
     Replay(ReplayArgs),
+
+    /// Change the log filter of a running server through its admin API
+    #[cfg(feature = "admin")]
+    LogLevel(LogLevelArgs),
 }
 
 impl Cli {
@@ -114,8 +142,14 @@ impl Cli {
             Commands::Observability(args) => commands::observability(args).await,
             Commands::Generate(args) => commands::generate(args).await,
             Commands::Migrate(args) => commands::migrate(args).await,
+            Commands::Seed(args) => commands::seed(args).await,
             Commands::Docs { port } => commands::open_docs(port).await,
             Commands::Client(args) => commands::client(args).await,
+            Commands::Errors(args) => commands::errors(args).await,
+            Commands::Openapi(args) => commands::openapi(args).await,
+            Commands::Routes(args) => commands::routes(args).await,
+            #[cfg(feature = "remote-spec")]
+            Commands::Export(args) => commands::export(args).await,
             #[cfg(feature = "mcp")]
             Commands::Mcp(McpCommands::Generate(args)) => commands::mcp_generate(args).await,
             #[cfg(feature = "cloud")]
@@ -127,6 +161,8 @@ impl Cli {
             Commands::Deploy(args) => commands::deploy(args).await,
             #[cfg(feature = "replay")]
             Commands::Replay(args) => commands::replay(args).await,
+            #[cfg(feature = "admin")]
+            Commands::LogLevel(args) => commands::log_level(args).await,
         }
     }
 }
@@ -1,26 +1,362 @@
-//! Benchmark workflow command.
+//! Benchmark command.
+//!
+//! With routes (`cargo rustapi bench /health /users`) the app is built and
+//! started in release mode and every route is load tested with the built-in
+//! HTTP load generator. Results can be saved as a baseline and later runs are
+//! compared against it to flag regressions. Without routes the repository
+//! benchmark workflow (`scripts/bench.ps1`) runs instead.
 
+use super::loadgen::{self, LoadConfig, RouteStats};
 use anyhow::{bail, Context, Result};
 use clap::Args;
 use console::style;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::net::SocketAddr;
 use std::path::{Path, PathBuf};
-use tokio::process::Command;
+use std::process::Stdio;
+use std::time::{Duration, Instant};
+use tokio::net::TcpStream;
+use tokio::process::{Child, Command};
 
-/// Run the repository benchmark workflow.
+/// Default number of measured requests per route
+const DEFAULT_REQUESTS: u32 = 1000;
+
+/// Run the benchmark workflow, or load test routes of the app.
 #[derive(Args, Debug, Clone)]
 pub struct BenchArgs {
+    /// Routes to load test, e.g. `/health /users?page=1` (omit to run the benchmark workflow)
+    #[arg(value_name = "ROUTE")]
+    pub routes: Vec<String>,
     /// Project or workspace path to inspect.
     #[arg(long, default_value = ".", value_name = "PATH")]
     pub path: PathBuf,
-    /// Override performance snapshot warmup iterations.
+    /// Warmup requests per route (default: 10% of --iterations), or snapshot warmup iterations.
     #[arg(long)]
     pub warmup: Option<u32>,
-    /// Override performance snapshot measured iterations.
-    #[arg(long)]
+    /// Measured requests per route (default: 1000), or snapshot measured iterations.
+    #[arg(long, short = 'n')]
     pub iterations: Option<u32>,
+    /// Concurrent connections used by the load generator.
+    #[arg(long, short = 'c', default_value = "32")]
+    pub concurrency: usize,
+    /// Benchmark an already running server instead of starting the app.
+    #[arg(long, value_name = "URL")]
+    pub url: Option<String>,
+    /// Baseline file to compare against, relative to the project path.
+    #[arg(long, default_value = "bench-baseline.json", value_name = "FILE")]
+    pub baseline: PathBuf,
+    /// Store this run's results in the baseline file.
+    #[arg(long)]
+    pub save_baseline: bool,
+    /// Allowed slowdown against the baseline, in percent.
+    #[arg(long, default_value = "10")]
+    pub threshold: f64,
+    /// Package to run (for workspace projects).
+    #[arg(short = 'P', long)]
+    pub package: Option<String>,
+    /// Additional features to enable.
+    #[arg(short, long, value_delimiter = ',')]
+    pub features: Option<Vec<String>>,
+    /// Seconds to wait for the app to accept connections after it was built.
+    #[arg(long, default_value = "60")]
+    pub startup_timeout: u64,
+}
+
+/// Stored results of a benchmark run, keyed by route
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct Baseline {
+    routes: BTreeMap<String, RouteStats>,
 }
 
 pub async fn bench(args: BenchArgs) -> Result<()> {
+    if args.routes.is_empty() {
+        return run_workflow(&args).await;
+    }
+
+    let project = resolve_path(&args.path)?;
+    for route in &args.routes {
+        if !route.starts_with('/') {
+            bail!("Route `{route}` must start with `/`");
+        }
+    }
+
+    let (addr, host, _app) = match &args.url {
+        Some(url) => {
+            let (addr, host) = resolve_url(url).await?;
+            (addr, host, None)
+        }
+        None => {
+            let (addr, app) = start_app(&args, &project).await?;
+            (addr, addr.to_string(), Some(app))
+        }
+    };
+
+    let requests = args.iterations.unwrap_or(DEFAULT_REQUESTS).max(1) as usize;
+    let warmup = args
+        .warmup
+        .map(|warmup| warmup as usize)
+        .unwrap_or(requests / 10);
+
+    println!(
+        "{} {} requests per route, {} connections against {}",
+        style("Benchmarking").bold(),
+        requests,
+        args.concurrency,
+        style(&host).cyan()
+    );
+    println!();
+
+    let mut results = BTreeMap::new();
+    for route in &args.routes {
+        let config = LoadConfig {
+            addr,
+            host: host.clone(),
+            path: route.clone(),
+            requests: warmup,
+            concurrency: args.concurrency,
+            timeout: Duration::from_secs(30),
+        };
+        if warmup > 0 {
+            loadgen::run(&config).await?;
+        }
+        let stats = loadgen::run(&LoadConfig { requests, ..config }).await?;
+        if stats.errors == stats.requests {
+            bail!("Every request to {route} failed. Does the route exist and answer GET?");
+        }
+        results.insert(route.clone(), stats);
+    }
+
+    print_results(&results);
+
+    let baseline_path = project.join(&args.baseline);
+    if args.save_baseline {
+        let mut baseline = read_baseline(&baseline_path)?.unwrap_or_default();
+        baseline.routes.extend(results);
+        fs::write(
+            &baseline_path,
+            serde_json::to_string_pretty(&baseline)? + "\n",
+        )
+        .with_context(|| format!("Failed to write {}", baseline_path.display()))?;
+        println!();
+        println!(
+            "{} {}",
+            style("Baseline saved to").green(),
+            args.baseline.display()
+        );
+        return Ok(());
+    }
+
+    let Some(baseline) = read_baseline(&baseline_path)? else {
+        println!();
+        println!(
+            "{}",
+            style("No baseline to compare against. Run with --save-baseline to record one.").dim()
+        );
+        return Ok(());
+    };
+
+    let regressions = compare(&baseline, &results, args.threshold);
+    println!();
+    if regressions.is_empty() {
+        println!(
+            "{}",
+            style(format!(
+                "No regressions against {} (threshold {}%)",
+                args.baseline.display(),
+                args.threshold
+            ))
+            .green()
+        );
+        return Ok(());
+    }
+
+    println!(
+        "{}",
+        style("Regressions against the baseline:").red().bold()
+    );
+    for regression in &regressions {
+        println!("  {} {}", style("✗").red(), regression);
+    }
+    bail!(
+        "{} metric(s) regressed by more than {}%",
+        regressions.len(),
+        args.threshold
+    );
+}
+
+/// Build the app in release mode, start it on a free port and wait until it
+/// accepts connections. The process is killed when the returned child drops.
+async fn start_app(args: &BenchArgs, project: &Path) -> Result<(SocketAddr, Child)> {
+    let cargo = |subcommand: &str| {
+        let mut command = Command::new("cargo");
+        command
+            .arg(subcommand)
+            .arg("--release")
+            .current_dir(project);
+        if let Some(package) = &args.package {
+            command.arg("-p").arg(package);
+        }
+        if let Some(features) = &args.features {
+            command.arg("--features").arg(features.join(","));
+        }
+        command
+    };
+
+    println!("{}", style("Building the app in release mode...").bold());
+    let status = cargo("build")
+        .status()
+        .await
+        .context("Failed to run cargo build")?;
+    if !status.success() {
+        bail!("Build failed");
+    }
+
+    let port = std::net::TcpListener::bind("127.0.0.1:0")
+        .and_then(|listener| listener.local_addr())
+        .context("Failed to find a free port for the app")?
+        .port();
+    let addr = SocketAddr::from(([127, 0, 0, 1], port));
+
+    // Request logs would skew the numbers and flood the output
+    let mut app = cargo("run")
+        .arg("--quiet")
+        .env("HOST", "127.0.0.1")
+        .env("PORT", port.to_string())
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::inherit())
+        .kill_on_drop(true)
+        .spawn()
+        .context("Failed to start the app")?;
+
+    let deadline = Instant::now() + Duration::from_secs(args.startup_timeout);
+    while TcpStream::connect(addr).await.is_err() {
+        if let Some(status) = app.try_wait()? {
+            bail!("The app exited before accepting connections ({status})");
+        }
+        if Instant::now() > deadline {
+            bail!(
+                "The app did not accept connections on {addr} within {}s. \
+                 Does it listen on the HOST and PORT environment variables?",
+                args.startup_timeout
+            );
+        }
+        tokio::time::sleep(Duration::from_millis(100)).await;
+    }
+
+    Ok((addr, app))
+}
+
+/// Socket address and `Host` header for an `http://host[:port]` URL
+async fn resolve_url(url: &str) -> Result<(SocketAddr, String)> {
+    let Some(rest) = url.strip_prefix("http://") else {
+        bail!("Only http:// URLs can be benchmarked, got `{url}`");
+    };
+    let host = rest.split('/').next().unwrap_or_default().to_string();
+    let authority = if host.contains(':') {
+        host.clone()
+    } else {
+        format!("{host}:80")
+    };
+
+    let addr = tokio::net::lookup_host(&authority)
+        .await
+        .with_context(|| format!("Failed to resolve {host}"))?
+        .next()
+        .with_context(|| format!("{host} did not resolve to an address"))?;
+    Ok((addr, host))
+}
+
+fn read_baseline(path: &Path) -> Result<Option<Baseline>> {
+    if !path.exists() {
+        return Ok(None);
+    }
+    let content =
+        fs::read_to_string(path).with_context(|| format!("Failed to read {}", path.display()))?;
+    let baseline = serde_json::from_str(&content)
+        .with_context(|| format!("{} is not a benchmark baseline", path.display()))?;
+    Ok(Some(baseline))
+}
+
+/// Metrics that got worse than the baseline by more than `threshold` percent
+fn compare(
+    baseline: &Baseline,
+    results: &BTreeMap<String, RouteStats>,
+    threshold: f64,
+) -> Vec<String> {
+    let allowed = 1.0 + threshold / 100.0;
+    let mut regressions = Vec::new();
+
+    for (route, current) in results {
+        let Some(previous) = baseline.routes.get(route) else {
+            continue;
+        };
+
+        for (metric, before, now) in [
+            ("p50", previous.p50_ms, current.p50_ms),
+            ("p90", previous.p90_ms, current.p90_ms),
+            ("p99", previous.p99_ms, current.p99_ms),
+        ] {
+            if before > 0.0 && now > before * allowed {
+                regressions.push(format!(
+                    "{route} {metric}: {now:.2}ms vs {before:.2}ms ({:+.0}%)",
+                    (now / before - 1.0) * 100.0
+                ));
+            }
+        }
+
+        let (before, now) = (previous.requests_per_sec, current.requests_per_sec);
+        if now * allowed < before {
+            regressions.push(format!(
+                "{route} throughput: {now:.0} req/s vs {before:.0} req/s ({:+.0}%)",
+                (now / before - 1.0) * 100.0
+            ));
+        }
+
+        let error_rate = |stats: &RouteStats| stats.errors as f64 / stats.requests.max(1) as f64;
+        if error_rate(current) > error_rate(previous) {
+            regressions.push(format!(
+                "{route} errors: {} of {} requests failed",
+                current.errors, current.requests
+            ));
+        }
+    }
+
+    regressions
+}
+
+fn print_results(results: &BTreeMap<String, RouteStats>) {
+    let width = results.keys().map(String::len).max().unwrap_or(0).max(5);
+    println!(
+        "{}",
+        style(format!(
+            "{:<width$}  {:>9}  {:>9}  {:>9}  {:>9}  {:>9}  {:>6}",
+            "Route", "req/s", "p50", "p90", "p99", "max", "errors"
+        ))
+        .bold()
+    );
+    for (route, stats) in results {
+        let errors = format!("{:>6}", stats.errors);
+        println!(
+            "{:<width$}  {:>9.0}  {:>7.2}ms  {:>7.2}ms  {:>7.2}ms  {:>7.2}ms  {}",
+            route,
+            stats.requests_per_sec,
+            stats.p50_ms,
+            stats.p90_ms,
+            stats.p99_ms,
+            stats.max_ms,
+            if stats.errors > 0 {
+                style(errors).red()
+            } else {
+                style(errors)
+            }
+        );
+    }
+}
+
+async fn run_workflow(args: &BenchArgs) -> Result<()> {
     let inspect_path = resolve_path(&args.path)?;
     let workspace_root = find_workspace_root(&inspect_path)
         .with_context(|| format!("No Cargo.toml found above {}", inspect_path.display()))?;
@@ -28,7 +364,8 @@ pub async fn bench(args: BenchArgs) -> Result<()> {
 
     if !script_path.exists() {
         bail!(
-            "Benchmark script was not found at {}",
+            "Benchmark script was not found at {}. Pass routes to load test the app instead, \
+             e.g. `cargo rustapi bench /health`",
             script_path.display()
         );
     }
@@ -95,3 +432,49 @@ fn find_workspace_root(start: &Path) -> Option<PathBuf> {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stats(p50_ms: f64, p99_ms: f64, requests_per_sec: f64, errors: usize) -> RouteStats {
+        RouteStats {
+            requests: 1000,
+            errors,
+            requests_per_sec,
+            p50_ms,
+            p90_ms: p50_ms,
+            p99_ms,
+            max_ms: p99_ms,
+        }
+    }
+
+    #[test]
+    fn compare_flags_slowdowns_beyond_threshold() {
+        let baseline = Baseline {
+            routes: BTreeMap::from([
+                ("/fast".to_string(), stats(1.0, 2.0, 10_000.0, 0)),
+                ("/slow".to_string(), stats(1.0, 2.0, 10_000.0, 0)),
+            ]),
+        };
+        let results = BTreeMap::from([
+            ("/fast".to_string(), stats(1.05, 2.1, 9_500.0, 0)),
+            ("/slow".to_string(), stats(1.0, 3.0, 7_000.0, 3)),
+            ("/new".to_string(), stats(50.0, 90.0, 10.0, 0)),
+        ]);
+
+        let regressions = compare(&baseline, &results, 10.0);
+        assert_eq!(regressions.len(), 3, "{regressions:?}");
+        assert!(regressions[0].starts_with("/slow p99: 3.00ms vs 2.00ms (+50%)"));
+        assert!(regressions[1].starts_with("/slow throughput"));
+        assert!(regressions[2].starts_with("/slow errors: 3 of 1000"));
+    }
+
+    #[tokio::test]
+    async fn resolve_url_keeps_host_header() {
+        let (addr, host) = resolve_url("http://127.0.0.1:3000/api").await.unwrap();
+        assert_eq!(addr, SocketAddr::from(([127, 0, 0, 1], 3000)));
+        assert_eq!(host, "127.0.0.1:3000");
+        assert!(resolve_url("https://example.com").await.is_err());
+    }
+}
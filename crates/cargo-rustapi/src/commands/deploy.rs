@@ -10,6 +10,7 @@ use std::fs;
 use std::path::PathBuf;
 use std::time::Duration;
 
+use super::manifests::{self, KubernetesOptions, ProjectProfile};
 use crate::config::load_config;
 
 /// Arguments for deployment commands
@@ -26,6 +27,9 @@ pub enum DeployArgs {
     /// Generate a Dockerfile for the project
     Docker(DockerArgs),
 
+    /// Generate Kubernetes Deployment/Service/HPA manifests or a Helm chart
+    Kubernetes(KubernetesArgs),
+
     /// Deploy to Fly.io
     Fly(FlyArgs),
 
@@ -76,9 +80,40 @@ pub struct DockerArgs {
     #[arg(short, long)]
     pub binary: Option<String>,
 
-    /// Port to expose
-    #[arg(short, long, default_value = "8080")]
-    pub port: u16,
+    /// Port to expose (defaults to the project's PORT)
+    #[arg(short, long)]
+    pub port: Option<u16>,
+}
+
+#[derive(Args, Debug)]
+pub struct KubernetesArgs {
+    /// Output directory
+    #[arg(short, long, default_value = "./k8s")]
+    pub output: PathBuf,
+
+    /// Container image (defaults to `<package name>:latest`)
+    #[arg(short, long)]
+    pub image: Option<String>,
+
+    /// Replicas when autoscaling is off
+    #[arg(long, default_value_t = 2)]
+    pub replicas: u32,
+
+    /// Minimum replicas of the HorizontalPodAutoscaler
+    #[arg(long, default_value_t = 2)]
+    pub min_replicas: u32,
+
+    /// Maximum replicas of the HorizontalPodAutoscaler
+    #[arg(long, default_value_t = 10)]
+    pub max_replicas: u32,
+
+    /// Target average CPU utilization in percent
+    #[arg(long, default_value_t = 75)]
+    pub cpu_target: u32,
+
+    /// Generate a Helm chart instead of plain manifests
+    #[arg(long)]
+    pub helm: bool,
 }
 
 #[derive(Args, Debug)]
@@ -126,6 +161,7 @@ pub async fn deploy(args: DeployArgs) -> Result<()> {
         #[cfg(feature = "cloud")]
         DeployArgs::Status(status_args) => deploy_status(status_args).await,
         DeployArgs::Docker(docker_args) => generate_dockerfile(docker_args).await,
+        DeployArgs::Kubernetes(kubernetes_args) => generate_kubernetes(kubernetes_args).await,
         DeployArgs::Fly(fly_args) => deploy_fly(fly_args).await,
         DeployArgs::Railway(railway_args) => deploy_railway(railway_args).await,
         DeployArgs::Shuttle(shuttle_args) => deploy_shuttle(shuttle_args).await,
@@ -135,14 +171,28 @@ pub async fn deploy(args: DeployArgs) -> Result<()> {
 async fn generate_dockerfile(args: DockerArgs) -> Result<()> {
     println!("🐳 Generating Dockerfile...");
 
-    // Try to get package name from Cargo.toml
-    let binary_name = args
-        .binary
-        .unwrap_or_else(|| get_package_name().unwrap_or_else(|_| "app".to_string()));
+    let profile = detect_profile();
+    let binary_name = args.binary.unwrap_or_else(|| profile.name.clone());
+    let port = args.port.unwrap_or(profile.port);
+
+    let assets = profile
+        .assets
+        .iter()
+        .map(|dir| format!("COPY --from=builder /app/{dir} ./{dir}\n"))
+        .collect::<String>();
+    let mut env = manifests::container_config(&profile);
+    if !env.iter().any(|(key, _)| key == "RUST_LOG") {
+        env.push(("RUST_LOG".to_string(), "info".to_string()));
+    }
+    let env = env
+        .into_iter()
+        .filter(|(key, value)| key != "PORT" && !value.is_empty())
+        .map(|(key, value)| format!("ENV {key}={value}\n"))
+        .collect::<String>();
 
     let dockerfile = format!(
         r#"# Build stage
-FROM rust:{rust_version}-slim as builder
+FROM rust:{rust_version}-slim AS builder
 
 # Install build dependencies
 RUN apt-get update && apt-get install -y \
@@ -151,21 +201,13 @@ RUN apt-get update && apt-get install -y \
     && rm -rf /var/lib/apt/lists/*
 
 WORKDIR /app
-
-# Copy manifests first for dependency caching
-COPY Cargo.toml Cargo.lock* ./
-COPY crates ./crates
-
-# Build dependencies (this layer will be cached)
-RUN mkdir src && echo "fn main() {{}}" > src/main.rs
-RUN cargo build --release
-RUN rm -rf src
-
-# Copy actual source code
 COPY . .
 
-# Build the application
-RUN cargo build --release
+# Cache the registry and target directory between builds (BuildKit)
+RUN --mount=type=cache,target=/usr/local/cargo/registry \
+    --mount=type=cache,target=/app/target \
+    cargo build --release --bin {binary_name} \
+    && cp target/release/{binary_name} /app/server
 
 # Runtime stage
 FROM debian:bookworm-slim
@@ -176,44 +218,147 @@ RUN apt-get update && apt-get install -y \
     libssl3 \
     && rm -rf /var/lib/apt/lists/*
 
+RUN useradd --system --uid 10001 app
 WORKDIR /app
 
-# Copy the binary from builder
-COPY --from=builder /app/target/release/{binary_name} /usr/local/bin/app
-
+# Copy the binary and assets from builder
+COPY --from=builder /app/server /usr/local/bin/app
+{assets}
+# Set environment variables (secrets are provided at runtime)
+ENV PORT={port}
+{env}
 # Expose port
 EXPOSE {port}
 
-# Set environment variables
-ENV RUST_LOG=info
-ENV PORT={port}
+USER app
 
 # Run the application
 CMD ["app"]
 "#,
         rust_version = args.rust_version,
-        binary_name = binary_name,
-        port = args.port
     );
 
     fs::write(&args.output, dockerfile).context("Failed to write Dockerfile")?;
 
     println!("✅ Dockerfile generated at: {}", args.output.display());
+    print_secret_hint(&profile, "docker run -e");
     println!();
     println!("Build and run with:");
-    println!("  docker build -t myapp .");
-    println!("  docker run -p {}:{} myapp", args.port, args.port);
+    println!("  docker build -t {} .", profile.resource_name());
+    println!("  docker run -p {port}:{port} {}", profile.resource_name());
 
     Ok(())
 }
 
+async fn generate_kubernetes(args: KubernetesArgs) -> Result<()> {
+    println!("☸️  Generating Kubernetes manifests...");
+
+    let profile = detect_profile();
+    let options = KubernetesOptions {
+        image: args
+            .image
+            .unwrap_or_else(|| format!("{}:latest", profile.resource_name())),
+        replicas: args.replicas,
+        min_replicas: args.min_replicas,
+        max_replicas: args.max_replicas.max(args.min_replicas),
+        cpu_target: args.cpu_target,
+    };
+
+    let files = if args.helm {
+        manifests::helm_chart(&profile, &options)
+    } else {
+        manifests::kubernetes_manifests(&profile, &options)
+    };
+
+    for (path, content) in &files {
+        let path = args.output.join(path);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)
+                .with_context(|| format!("Failed to create {}", parent.display()))?;
+        }
+        fs::write(&path, content).with_context(|| format!("Failed to write {}", path.display()))?;
+        println!("  {}", path.display());
+    }
+
+    println!(
+        "✅ {} generated in: {}",
+        if args.helm { "Helm chart" } else { "Manifests" },
+        args.output.display()
+    );
+    match &profile.health {
+        Some(health) => println!(
+            "   Probes: readiness {}, liveness {}",
+            health.readiness_path(),
+            health.liveness_path()
+        ),
+        None => println!(
+            "⚠️  No health route found; using a TCP liveness probe. Add `.health_endpoints()` for HTTP probes."
+        ),
+    }
+    println!();
+    println!("Apply with:");
+    if args.helm {
+        println!(
+            "  helm install {} {}",
+            profile.resource_name(),
+            args.output.display()
+        );
+    } else {
+        println!("  kubectl apply -f {}", args.output.display());
+    }
+
+    Ok(())
+}
+
+/// Inspect the current project, falling back to defaults outside a project
+fn detect_profile() -> ProjectProfile {
+    ProjectProfile::detect(std::path::Path::new(".")).unwrap_or_else(|_| ProjectProfile {
+        name: "app".to_string(),
+        port: manifests::DEFAULT_PORT,
+        health: None,
+        env: Vec::new(),
+        assets: Vec::new(),
+    })
+}
+
+fn print_secret_hint(profile: &ProjectProfile, how: &str) {
+    let secrets: Vec<&str> = profile.secret_vars().map(|v| v.name.as_str()).collect();
+    if !secrets.is_empty() {
+        println!(
+            "🔑 Provide these secrets at runtime ({how}): {}",
+            secrets.join(", ")
+        );
+    }
+}
+
 async fn deploy_fly(args: FlyArgs) -> Result<()> {
     println!("✈️  Deploying to Fly.io...");
 
+    let profile = detect_profile();
     let app_name = args
         .app
         .unwrap_or_else(|| get_package_name().unwrap_or_else(|_| "rustapi-app".to_string()));
 
+    let env = manifests::container_config(&profile)
+        .into_iter()
+        .filter(|(_, value)| !value.is_empty())
+        .map(|(key, value)| format!("  {key} = \"{value}\"\n"))
+        .collect::<String>();
+    let checks = match &profile.health {
+        Some(health) => format!(
+            r#"
+[[http_service.checks]]
+  grace_period = "10s"
+  interval = "15s"
+  method = "GET"
+  path = "{}"
+  timeout = "5s"
+"#,
+            health.readiness_path()
+        ),
+        None => String::new(),
+    };
+
     // Generate fly.toml
     let fly_toml = format!(
         r#"# Fly.io configuration
@@ -225,25 +370,29 @@ primary_region = "{region}"
 [build]
   dockerfile = "Dockerfile"
 
+[env]
+{env}
 [http_service]
-  internal_port = 8080
+  internal_port = {port}
   force_https = true
   auto_stop_machines = true
   auto_start_machines = true
   min_machines_running = 0
-
+{checks}
 [[vm]]
   memory = "256mb"
   cpu_kind = "shared"
   cpus = 1
 "#,
         app_name = app_name,
-        region = args.region
+        region = args.region,
+        port = profile.port,
     );
 
     fs::write("fly.toml", &fly_toml).context("Failed to write fly.toml")?;
 
     println!("✅ fly.toml generated");
+    print_secret_hint(&profile, "fly secrets set NAME=value");
 
     if args.init_only {
         println!();
@@ -269,8 +418,21 @@ async fn deploy_railway(args: RailwayArgs) -> Result<()> {
         .project
         .unwrap_or_else(|| get_package_name().unwrap_or_else(|_| "rustapi-app".to_string()));
 
+    let profile = detect_profile();
+    let healthcheck = profile
+        .health
+        .as_ref()
+        .map(|health| {
+            format!(
+                "healthcheckPath = \"{}\"\nhealthcheckTimeout = 100\n",
+                health.readiness_path()
+            )
+        })
+        .unwrap_or_default();
+
     // Generate railway.toml
-    let railway_toml = r#"# Railway configuration
+    let railway_toml = format!(
+        r#"# Railway configuration
 # Generated by RustAPI CLI
 
 [build]
@@ -279,16 +441,15 @@ dockerfilePath = "Dockerfile"
 
 [deploy]
 numReplicas = 1
-healthcheckPath = "/health"
-healthcheckTimeout = 100
-restartPolicyType = "on_failure"
+{healthcheck}restartPolicyType = "on_failure"
 restartPolicyMaxRetries = 3
 "#
-    .to_string();
+    );
 
     fs::write("railway.toml", &railway_toml).context("Failed to write railway.toml")?;
 
     println!("✅ railway.toml generated for: {}", project_name);
+    print_secret_hint(&profile, "railway variables --set NAME=value");
     println!();
     println!("To deploy:");
     println!("  1. Install Railway CLI: npm i -g @railway/cli");
@@ -0,0 +1,219 @@
+//! Request-holding proxy for `cargo rustapi watch --proxy`
+//!
+//! The proxy owns the public port and forwards every connection to the app,
+//! which listens on an internal port. While the app is rebuilding, new
+//! connections wait instead of being refused and are forwarded once the new
+//! server accepts connections. If the build fails they get a `502` with the
+//! reason, and if the app does not come back in time a `503`.
+
+use anyhow::{Context, Result};
+use std::net::SocketAddr;
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::watch;
+
+/// Pause between connection attempts while the app is starting
+const RETRY_INTERVAL: Duration = Duration::from_millis(100);
+
+/// What the proxied app is currently doing
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AppStatus {
+    /// Being (re)built; connections are held
+    Building,
+    /// Started; connections are forwarded as soon as it accepts them
+    Running,
+    /// Build failed or the process exited; connections get a `502`
+    Failed(String),
+}
+
+/// Handle to a running proxy; dropping it stops accepting connections
+pub struct DevProxy {
+    local_addr: SocketAddr,
+    status: watch::Sender<AppStatus>,
+    accept_task: tokio::task::JoinHandle<()>,
+}
+
+impl DevProxy {
+    /// Listen on `public` and forward to `app`, holding connections for at
+    /// most `hold` while the app is unavailable
+    pub async fn bind(public: SocketAddr, app: SocketAddr, hold: Duration) -> Result<Self> {
+        let listener = TcpListener::bind(public)
+            .await
+            .with_context(|| format!("Failed to bind proxy on {public}"))?;
+        let local_addr = listener.local_addr()?;
+        let (status, receiver) = watch::channel(AppStatus::Building);
+
+        let accept_task = tokio::spawn(async move {
+            while let Ok((client, _)) = listener.accept().await {
+                tokio::spawn(forward(client, app, receiver.clone(), hold));
+            }
+        });
+
+        Ok(Self {
+            local_addr,
+            status,
+            accept_task,
+        })
+    }
+
+    /// Address the proxy listens on
+    pub fn local_addr(&self) -> SocketAddr {
+        self.local_addr
+    }
+
+    /// Update the app status seen by held and new connections
+    pub fn set_status(&self, status: AppStatus) {
+        self.status.send_replace(status);
+    }
+}
+
+impl Drop for DevProxy {
+    fn drop(&mut self) {
+        self.accept_task.abort();
+    }
+}
+
+async fn forward(
+    mut client: TcpStream,
+    app: SocketAddr,
+    mut status: watch::Receiver<AppStatus>,
+    hold: Duration,
+) {
+    let mut upstream = match tokio::time::timeout(hold, connect_when_ready(app, &mut status)).await
+    {
+        Ok(Ok(upstream)) => upstream,
+        Ok(Err(reason)) => return respond(client, "502 Bad Gateway", &reason).await,
+        Err(_) => {
+            let reason = format!(
+                "The app did not accept connections within {}s.",
+                hold.as_secs()
+            );
+            return respond(client, "503 Service Unavailable", &reason).await;
+        }
+    };
+
+    let _ = tokio::io::copy_bidirectional(&mut client, &mut upstream).await;
+}
+
+/// Wait until the app is running and accepts a connection
+async fn connect_when_ready(
+    app: SocketAddr,
+    status: &mut watch::Receiver<AppStatus>,
+) -> std::result::Result<TcpStream, String> {
+    let stopped = || "cargo rustapi watch is shutting down.".to_string();
+
+    loop {
+        let current = status.borrow_and_update().clone();
+        match current {
+            AppStatus::Building => status.changed().await.map_err(|_| stopped())?,
+            AppStatus::Failed(reason) => return Err(reason),
+            AppStatus::Running => {
+                if let Ok(upstream) = TcpStream::connect(app).await {
+                    return Ok(upstream);
+                }
+                tokio::select! {
+                    changed = status.changed() => changed.map_err(|_| stopped())?,
+                    _ = tokio::time::sleep(RETRY_INTERVAL) => {}
+                }
+            }
+        }
+    }
+}
+
+/// Answer a held connection with a plain-text error page
+async fn respond(mut client: TcpStream, status_line: &str, body: &str) {
+    // Read the request first so closing the socket does not reset it
+    let mut request = [0u8; 8192];
+    let _ = tokio::time::timeout(RETRY_INTERVAL, client.read(&mut request)).await;
+
+    let response = format!(
+        "HTTP/1.1 {status_line}\r\ncontent-type: text/plain; charset=utf-8\r\n\
+         content-length: {}\r\nretry-after: 1\r\nconnection: close\r\n\r\n{body}",
+        body.len()
+    );
+    let _ = client.write_all(response.as_bytes()).await;
+    let _ = client.shutdown().await;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const REQUEST: &[u8] = b"GET / HTTP/1.1\r\nhost: localhost\r\n\r\n";
+
+    fn loopback() -> SocketAddr {
+        SocketAddr::from(([127, 0, 0, 1], 0))
+    }
+
+    async fn free_addr() -> SocketAddr {
+        TcpListener::bind(loopback())
+            .await
+            .unwrap()
+            .local_addr()
+            .unwrap()
+    }
+
+    async fn send(proxy: SocketAddr) -> String {
+        let mut stream = TcpStream::connect(proxy).await.unwrap();
+        stream.write_all(REQUEST).await.unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).await.unwrap();
+        response
+    }
+
+    #[tokio::test]
+    async fn holds_requests_until_the_app_is_back() {
+        let app = free_addr().await;
+        let proxy = DevProxy::bind(loopback(), app, Duration::from_secs(10))
+            .await
+            .unwrap();
+        let request = tokio::spawn(send(proxy.local_addr()));
+
+        // The "rebuild" finishes after the request arrived
+        tokio::time::sleep(Duration::from_millis(200)).await;
+        proxy.set_status(AppStatus::Running);
+        tokio::time::sleep(Duration::from_millis(200)).await;
+        let listener = TcpListener::bind(app).await.unwrap();
+        let (mut server, _) = listener.accept().await.unwrap();
+        let mut buf = vec![0u8; REQUEST.len()];
+        server.read_exact(&mut buf).await.unwrap();
+        assert_eq!(buf, REQUEST);
+        server
+            .write_all(b"HTTP/1.1 200 OK\r\ncontent-length: 2\r\nconnection: close\r\n\r\nok")
+            .await
+            .unwrap();
+        drop(server);
+
+        let response = request.await.unwrap();
+        assert!(response.starts_with("HTTP/1.1 200 OK"));
+        assert!(response.ends_with("ok"));
+    }
+
+    #[tokio::test]
+    async fn failed_builds_are_reported() {
+        let proxy = DevProxy::bind(loopback(), free_addr().await, Duration::from_secs(10))
+            .await
+            .unwrap();
+        let request = tokio::spawn(send(proxy.local_addr()));
+
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        proxy.set_status(AppStatus::Failed(
+            "error[E0308]: mismatched types".to_string(),
+        ));
+
+        let response = request.await.unwrap();
+        assert!(response.starts_with("HTTP/1.1 502 Bad Gateway"));
+        assert!(response.contains("mismatched types"));
+    }
+
+    #[tokio::test]
+    async fn gives_up_after_the_hold_timeout() {
+        let proxy = DevProxy::bind(loopback(), free_addr().await, Duration::from_millis(200))
+            .await
+            .unwrap();
+
+        let response = send(proxy.local_addr()).await;
+        assert!(response.starts_with("HTTP/1.1 503 Service Unavailable"));
+    }
+}
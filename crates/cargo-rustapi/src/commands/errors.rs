@@ -0,0 +1,117 @@
+//! Errors command - dump the error code catalog of a RustAPI project
+
+use anyhow::{bail, Context, Result};
+use clap::{Args, ValueEnum};
+use console::style;
+use std::path::PathBuf;
+
+/// Output format for the error catalog
+#[derive(Debug, Clone, Copy, Default, ValueEnum)]
+pub enum ErrorsFormat {
+    /// Human-readable table
+    #[default]
+    Table,
+    /// Raw JSON (`{"errors": [...]}`)
+    Json,
+}
+
+/// Dump the error code catalog of the current project
+#[derive(Args, Debug)]
+pub struct ErrorsArgs {
+    /// Output format
+    #[arg(short, long, value_enum, default_value_t = ErrorsFormat::Table)]
+    pub format: ErrorsFormat,
+
+    /// Write the catalog JSON to a file instead of stdout
+    #[arg(short, long)]
+    pub output: Option<PathBuf>,
+}
+
+/// Build and run the project with `RUSTAPI_DUMP_ERROR_CATALOG=1` and print the catalog.
+pub async fn errors(args: ErrorsArgs) -> Result<()> {
+    eprintln!(
+        "{} Spawning `cargo run` with RUSTAPI_DUMP_ERROR_CATALOG=1 (no server binding)...",
+        style("→").cyan()
+    );
+
+    let output = tokio::process::Command::new("cargo")
+        .args(["run", "--quiet"])
+        .env("RUSTAPI_DUMP_ERROR_CATALOG", "1")
+        .stdout(std::process::Stdio::piped())
+        .stderr(std::process::Stdio::inherit())
+        .output()
+        .await
+        .context("Failed to execute `cargo run`. Are you inside a RustAPI project?")?;
+
+    if !output.status.success() {
+        bail!("Failed to build/run the project to extract the error catalog");
+    }
+
+    let stdout = String::from_utf8(output.stdout).context("Catalog output was not valid UTF-8")?;
+    let catalog = extract_catalog(&stdout)?;
+
+    if let Some(path) = args.output {
+        let pretty = serde_json::to_string_pretty(&catalog)?;
+        tokio::fs::write(&path, pretty)
+            .await
+            .with_context(|| format!("Failed to write {}", path.display()))?;
+        println!(
+            "{} Error catalog written to {}",
+            style("✓").green(),
+            style(path.display()).cyan()
+        );
+        return Ok(());
+    }
+
+    match args.format {
+        ErrorsFormat::Json => println!("{}", serde_json::to_string_pretty(&catalog)?),
+        ErrorsFormat::Table => print_table(&catalog),
+    }
+
+    Ok(())
+}
+
+/// Find the catalog JSON in the program output (the app may print before dumping).
+fn extract_catalog(stdout: &str) -> Result<serde_json::Value> {
+    let start = stdout
+        .rfind(r#""errors""#)
+        .and_then(|idx| stdout[..idx].rfind('{'))
+        .context("No error catalog found in program output")?;
+    let end = stdout
+        .rfind('}')
+        .context("Truncated error catalog output")?;
+
+    serde_json::from_str(&stdout[start..=end]).context("Failed to parse error catalog JSON")
+}
+
+fn print_table(catalog: &serde_json::Value) {
+    let entries = catalog["errors"].as_array().cloned().unwrap_or_default();
+    let width = entries
+        .iter()
+        .filter_map(|e| e["code"].as_str())
+        .map(str::len)
+        .max()
+        .unwrap_or(4)
+        .max(4);
+
+    println!(
+        "{}",
+        style(format!(
+            "{:<width$}  STATUS  DESCRIPTION",
+            "CODE",
+            width = width
+        ))
+        .bold()
+    );
+    for entry in &entries {
+        println!(
+            "{:<width$}  {:<6}  {}",
+            entry["code"].as_str().unwrap_or_default(),
+            entry["status"].as_u64().unwrap_or_default(),
+            entry["description"].as_str().unwrap_or_default(),
+            width = width
+        );
+    }
+    println!();
+    println!("{} error codes", entries.len());
+}
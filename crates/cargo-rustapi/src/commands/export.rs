@@ -0,0 +1,217 @@
+//! Export command - prerender HTML pages of a running app to static files
+
+use anyhow::{bail, Context, Result};
+use clap::Args;
+use console::style;
+use std::path::{Path, PathBuf};
+
+/// Prerender the HTML pages of a running app for static hosting
+#[derive(Args, Debug)]
+pub struct ExportArgs {
+    /// Base URL of the running app
+    #[arg(long, default_value = "http://localhost:8080")]
+    pub url: String,
+
+    /// Output directory
+    #[arg(short, long, default_value = "dist")]
+    pub out: PathBuf,
+
+    /// Extra page to render, e.g. `/posts/hello` for a parameterized route (repeatable)
+    #[arg(short, long = "page", value_name = "PATH")]
+    pub pages: Vec<String>,
+
+    /// File with extra pages to render, one path per line
+    #[arg(long, value_name = "FILE")]
+    pub pages_file: Option<PathBuf>,
+
+    /// Asset directory to copy into the output (e.g. the `Assets::build` output)
+    #[arg(long, value_name = "DIR")]
+    pub assets: Option<PathBuf>,
+
+    /// Path of the copied assets inside the output directory
+    #[arg(long, default_value = "assets", value_name = "PATH")]
+    pub assets_path: String,
+}
+
+/// Fetch every HTML page and write it to `<out>/<path>/index.html`.
+///
+/// Pages are the GET routes without path parameters whose documented
+/// response is `text/html` (e.g. handlers returning `View`), read from the
+/// app's `/openapi.json`, plus the pages given on the command line.
+pub async fn export(args: ExportArgs) -> Result<()> {
+    let base = args.url.trim_end_matches('/');
+    let client = reqwest::Client::new();
+
+    let spec: serde_json::Value = client
+        .get(format!("{}/openapi.json", base))
+        .send()
+        .await
+        .with_context(|| format!("Failed to reach {}. Is the app running?", base))?
+        .error_for_status()
+        .context("Failed to fetch /openapi.json")?
+        .json()
+        .await
+        .context("Failed to parse /openapi.json")?;
+
+    let mut pages = html_pages(&spec);
+    pages.extend(args.pages.iter().cloned());
+    if let Some(file) = &args.pages_file {
+        let list = tokio::fs::read_to_string(file)
+            .await
+            .with_context(|| format!("Failed to read {}", file.display()))?;
+        pages.extend(
+            list.lines()
+                .map(str::trim)
+                .filter(|line| !line.is_empty() && !line.starts_with('#'))
+                .map(String::from),
+        );
+    }
+    for page in &mut pages {
+        if !page.starts_with('/') {
+            page.insert(0, '/');
+        }
+    }
+    pages.sort();
+    pages.dedup();
+
+    if pages.is_empty() {
+        bail!("No HTML pages found. Add pages with --page or --pages-file");
+    }
+
+    println!(
+        "{} Exporting {} pages from {} to {}",
+        style("→").cyan(),
+        pages.len(),
+        base,
+        style(args.out.display()).cyan()
+    );
+
+    let mut failed = 0;
+    for page in &pages {
+        let response = client
+            .get(format!("{}{}", base, page))
+            .send()
+            .await
+            .with_context(|| format!("Failed to fetch {}", page))?;
+        let status = response.status();
+        if !status.is_success() {
+            println!("  {} {} ({})", style("✗").red(), page, status);
+            failed += 1;
+            continue;
+        }
+
+        let html = response.bytes().await?;
+        let target = args.out.join(output_path(page));
+        if let Some(parent) = target.parent() {
+            tokio::fs::create_dir_all(parent).await?;
+        }
+        tokio::fs::write(&target, &html)
+            .await
+            .with_context(|| format!("Failed to write {}", target.display()))?;
+        println!("  {} {}", style("✓").green(), page);
+    }
+
+    if let Some(assets) = &args.assets {
+        let target = args.out.join(args.assets_path.trim_matches('/'));
+        let copied = copy_dir(assets, &target)
+            .with_context(|| format!("Failed to copy assets from {}", assets.display()))?;
+        println!("  {} {} asset files", style("✓").green(), copied);
+    }
+
+    if failed > 0 {
+        bail!("{} of {} pages failed to render", failed, pages.len());
+    }
+    println!("{} Export complete", style("✓").green());
+    Ok(())
+}
+
+/// GET paths without parameters that document a `text/html` response
+fn html_pages(spec: &serde_json::Value) -> Vec<String> {
+    let Some(paths) = spec["paths"].as_object() else {
+        return Vec::new();
+    };
+    paths
+        .iter()
+        .filter(|(path, _)| !path.contains('{'))
+        .filter(|(_, item)| {
+            item["get"]["responses"]
+                .as_object()
+                .is_some_and(|responses| {
+                    responses
+                        .values()
+                        .any(|response| response["content"].get("text/html").is_some())
+                })
+        })
+        .map(|(path, _)| path.clone())
+        .collect()
+}
+
+/// `/` -> `index.html`, `/about` -> `about/index.html`, `/feed.xml` -> `feed.xml`
+fn output_path(page: &str) -> PathBuf {
+    let path = page.split(['?', '#']).next().unwrap_or(page);
+    let path = path.trim_matches('/');
+    if path.is_empty() {
+        return PathBuf::from("index.html");
+    }
+
+    let mut out: PathBuf = path
+        .split('/')
+        .filter(|segment| !segment.is_empty() && *segment != "." && *segment != "..")
+        .collect();
+    if out.extension().is_none() {
+        out.push("index.html");
+    }
+    out
+}
+
+/// Copy a directory tree, returning the number of files copied
+fn copy_dir(src: &Path, dst: &Path) -> Result<usize> {
+    std::fs::create_dir_all(dst)?;
+    let mut copied = 0;
+    for entry in std::fs::read_dir(src)? {
+        let entry = entry?;
+        let target = dst.join(entry.file_name());
+        if entry.file_type()?.is_dir() {
+            copied += copy_dir(&entry.path(), &target)?;
+        } else {
+            std::fs::copy(entry.path(), target)?;
+            copied += 1;
+        }
+    }
+    Ok(copied)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn finds_html_pages_without_params() {
+        let html = json!({ "200": { "content": { "text/html": {} } } });
+        let spec = json!({
+            "paths": {
+                "/": { "get": { "responses": html } },
+                "/about": { "get": { "responses": html } },
+                "/posts/{slug}": { "get": { "responses": html } },
+                "/api/users": { "get": { "responses": { "200": { "content": { "application/json": {} } } } } },
+                "/contact": { "post": { "responses": html } }
+            }
+        });
+
+        let mut pages = html_pages(&spec);
+        pages.sort();
+        assert_eq!(pages, vec!["/", "/about"]);
+    }
+
+    #[test]
+    fn maps_pages_to_files() {
+        assert_eq!(output_path("/"), PathBuf::from("index.html"));
+        assert_eq!(
+            output_path("/posts/hello/"),
+            Path::new("posts").join("hello").join("index.html")
+        );
+        assert_eq!(output_path("/feed.xml?page=1"), PathBuf::from("feed.xml"));
+        assert_eq!(output_path("/../etc"), Path::new("etc").join("index.html"));
+    }
+}
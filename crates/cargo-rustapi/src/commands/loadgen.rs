@@ -0,0 +1,305 @@
+//! HTTP/1.1 load generator for `cargo rustapi bench`
+//!
+//! Each worker keeps one keep-alive connection open and sends `GET` requests
+//! back to back until the shared request budget is used up, so the measured
+//! latency is dominated by the server rather than by client overhead.
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::net::SocketAddr;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader};
+use tokio::net::TcpStream;
+
+/// What to load and how hard
+#[derive(Debug, Clone)]
+pub struct LoadConfig {
+    /// Server address
+    pub addr: SocketAddr,
+    /// Value of the `Host` header
+    pub host: String,
+    /// Request path, including any query string
+    pub path: String,
+    /// Total number of requests
+    pub requests: usize,
+    /// Number of concurrent connections
+    pub concurrency: usize,
+    /// Per-request timeout
+    pub timeout: Duration,
+}
+
+/// Results for one route; also the format stored in baseline files
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RouteStats {
+    pub requests: usize,
+    /// Requests that failed or did not answer with a 2xx/3xx status
+    pub errors: usize,
+    pub requests_per_sec: f64,
+    pub p50_ms: f64,
+    pub p90_ms: f64,
+    pub p99_ms: f64,
+    pub max_ms: f64,
+}
+
+/// Send `config.requests` requests and collect latency statistics
+pub async fn run(config: &LoadConfig) -> Result<RouteStats> {
+    let request = Arc::new(format!(
+        "GET {} HTTP/1.1\r\nhost: {}\r\nuser-agent: cargo-rustapi-bench\r\naccept: */*\r\n\r\n",
+        config.path, config.host
+    ));
+    let remaining = Arc::new(AtomicUsize::new(config.requests));
+    let started = Instant::now();
+
+    let workers: Vec<_> = (0..config.concurrency.clamp(1, config.requests.max(1)))
+        .map(|_| {
+            let config = config.clone();
+            let request = request.clone();
+            let remaining = remaining.clone();
+            tokio::spawn(async move { worker(&config, &request, &remaining).await })
+        })
+        .collect();
+
+    let mut latencies = Vec::with_capacity(config.requests);
+    let mut errors = 0;
+    for worker in workers {
+        let (worker_latencies, worker_errors) = worker.await.context("Load worker panicked")?;
+        latencies.extend(worker_latencies);
+        errors += worker_errors;
+    }
+    let elapsed = started.elapsed();
+
+    latencies.sort_unstable();
+    let ms = |latency: Duration| latency.as_secs_f64() * 1000.0;
+    Ok(RouteStats {
+        requests: config.requests,
+        errors,
+        requests_per_sec: config.requests as f64 / elapsed.as_secs_f64().max(f64::EPSILON),
+        p50_ms: ms(percentile(&latencies, 50.0)),
+        p90_ms: ms(percentile(&latencies, 90.0)),
+        p99_ms: ms(percentile(&latencies, 99.0)),
+        max_ms: ms(latencies.last().copied().unwrap_or_default()),
+    })
+}
+
+/// Nearest-rank percentile of sorted samples
+pub fn percentile(sorted: &[Duration], percentile: f64) -> Duration {
+    if sorted.is_empty() {
+        return Duration::ZERO;
+    }
+    let rank = (percentile / 100.0 * sorted.len() as f64).ceil() as usize;
+    sorted[rank.clamp(1, sorted.len()) - 1]
+}
+
+/// Latencies of successful requests and the number of errors
+async fn worker(
+    config: &LoadConfig,
+    request: &str,
+    remaining: &AtomicUsize,
+) -> (Vec<Duration>, usize) {
+    let mut latencies = Vec::new();
+    let mut errors = 0;
+    let mut connection: Option<BufReader<TcpStream>> = None;
+
+    while take_ticket(remaining) {
+        let started = Instant::now();
+        let outcome = tokio::time::timeout(config.timeout, async {
+            if connection.is_none() {
+                connection = Some(BufReader::new(TcpStream::connect(config.addr).await?));
+            }
+            let stream = connection.as_mut().expect("connected above");
+            stream.get_mut().write_all(request.as_bytes()).await?;
+            read_response(stream).await
+        })
+        .await;
+
+        match outcome {
+            Ok(Ok(response)) => {
+                if (200..400).contains(&response.status) {
+                    latencies.push(started.elapsed());
+                } else {
+                    errors += 1;
+                }
+                if response.close {
+                    connection = None;
+                }
+            }
+            _ => {
+                errors += 1;
+                connection = None;
+            }
+        }
+    }
+
+    (latencies, errors)
+}
+
+fn take_ticket(remaining: &AtomicUsize) -> bool {
+    remaining
+        .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |left| {
+            left.checked_sub(1)
+        })
+        .is_ok()
+}
+
+struct Response {
+    status: u16,
+    /// The server will close the connection after this response
+    close: bool,
+}
+
+/// Read one response, discarding the body
+async fn read_response(stream: &mut BufReader<TcpStream>) -> std::io::Result<Response> {
+    let invalid =
+        |what: &str| std::io::Error::new(std::io::ErrorKind::InvalidData, what.to_string());
+
+    let mut line = String::new();
+    if stream.read_line(&mut line).await? == 0 {
+        return Err(std::io::ErrorKind::UnexpectedEof.into());
+    }
+    let status: u16 = line
+        .split_whitespace()
+        .nth(1)
+        .and_then(|status| status.parse().ok())
+        .ok_or_else(|| invalid("malformed status line"))?;
+
+    let mut content_length = None;
+    let mut chunked = false;
+    let mut close = line.starts_with("HTTP/1.0");
+    loop {
+        line.clear();
+        if stream.read_line(&mut line).await? == 0 {
+            return Err(std::io::ErrorKind::UnexpectedEof.into());
+        }
+        let header = line.trim_end();
+        if header.is_empty() {
+            break;
+        }
+        let Some((name, value)) = header.split_once(':') else {
+            continue;
+        };
+        let value = value.trim();
+        if name.eq_ignore_ascii_case("content-length") {
+            content_length = Some(value.parse().map_err(|_| invalid("bad content-length"))?);
+        } else if name.eq_ignore_ascii_case("transfer-encoding") {
+            chunked = value.to_ascii_lowercase().contains("chunked");
+        } else if name.eq_ignore_ascii_case("connection") {
+            close = value.eq_ignore_ascii_case("close");
+        }
+    }
+
+    if status == 204 || status == 304 || (100..200).contains(&status) {
+        // No body
+    } else if chunked {
+        loop {
+            line.clear();
+            stream.read_line(&mut line).await?;
+            let size = line.trim_end().split(';').next().unwrap_or_default();
+            let size = u64::from_str_radix(size, 16).map_err(|_| invalid("bad chunk size"))?;
+            if size == 0 {
+                // Trailers end with an empty line
+                loop {
+                    line.clear();
+                    if stream.read_line(&mut line).await? == 0 || line.trim_end().is_empty() {
+                        break;
+                    }
+                }
+                break;
+            }
+            discard(stream, size + 2).await?;
+        }
+    } else if let Some(length) = content_length {
+        discard(stream, length).await?;
+    } else {
+        // Body delimited by the end of the connection
+        tokio::io::copy(stream, &mut tokio::io::sink()).await?;
+        close = true;
+    }
+
+    Ok(Response { status, close })
+}
+
+async fn discard(stream: &mut BufReader<TcpStream>, length: u64) -> std::io::Result<()> {
+    let copied = tokio::io::copy(&mut stream.take(length), &mut tokio::io::sink()).await?;
+    if copied < length {
+        return Err(std::io::ErrorKind::UnexpectedEof.into());
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::net::TcpListener;
+
+    /// Keep-alive server answering every request with `response`
+    async fn serve(response: &'static [u8]) -> SocketAddr {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            while let Ok((stream, _)) = listener.accept().await {
+                tokio::spawn(async move {
+                    let mut stream = BufReader::new(stream);
+                    let mut line = String::new();
+                    loop {
+                        line.clear();
+                        match stream.read_line(&mut line).await {
+                            Ok(0) | Err(_) => return,
+                            Ok(_) if line == "\r\n" => {
+                                if stream.get_mut().write_all(response).await.is_err() {
+                                    return;
+                                }
+                            }
+                            Ok(_) => {}
+                        }
+                    }
+                });
+            }
+        });
+        addr
+    }
+
+    fn config(addr: SocketAddr) -> LoadConfig {
+        LoadConfig {
+            addr,
+            host: addr.to_string(),
+            path: "/".to_string(),
+            requests: 200,
+            concurrency: 4,
+            timeout: Duration::from_secs(5),
+        }
+    }
+
+    #[test]
+    fn percentile_uses_nearest_rank() {
+        let samples: Vec<_> = (1..=100).map(Duration::from_millis).collect();
+        assert_eq!(percentile(&samples, 50.0), Duration::from_millis(50));
+        assert_eq!(percentile(&samples, 99.0), Duration::from_millis(99));
+        assert_eq!(percentile(&samples[..1], 99.0), Duration::from_millis(1));
+        assert_eq!(percentile(&[], 50.0), Duration::ZERO);
+    }
+
+    #[tokio::test]
+    async fn measures_content_length_and_chunked_responses() {
+        let addr = serve(b"HTTP/1.1 200 OK\r\ncontent-length: 5\r\n\r\nhello").await;
+        let stats = run(&config(addr)).await.unwrap();
+        assert_eq!(stats.requests, 200);
+        assert_eq!(stats.errors, 0);
+        assert!(stats.p50_ms <= stats.p99_ms && stats.p99_ms <= stats.max_ms);
+
+        let addr =
+            serve(b"HTTP/1.1 200 OK\r\ntransfer-encoding: chunked\r\n\r\n5\r\nhello\r\n0\r\n\r\n")
+                .await;
+        let stats = run(&config(addr)).await.unwrap();
+        assert_eq!(stats.errors, 0);
+    }
+
+    #[tokio::test]
+    async fn counts_error_statuses() {
+        let addr = serve(b"HTTP/1.1 500 Internal Server Error\r\ncontent-length: 0\r\n\r\n").await;
+        let stats = run(&config(addr)).await.unwrap();
+        assert_eq!(stats.errors, 200);
+        assert_eq!(stats.max_ms, 0.0);
+    }
+}
@@ -0,0 +1,186 @@
+//! CLI command to change the log filter of a running server.
+//!
+//! Talks to the `/log-level` endpoint of the admin API (`extras-admin`) via
+//! HTTP. Does not import `rustapi-extras` directly.
+
+use anyhow::{Context, Result};
+use clap::{Args, Subcommand};
+use console::style;
+use serde_json::{json, Value};
+use std::time::Duration;
+
+/// Change the log filter of a running server
+#[derive(Args, Debug)]
+pub struct LogLevelArgs {
+    #[command(subcommand)]
+    pub action: LogLevelAction,
+
+    /// Server URL
+    #[arg(short, long, global = true, default_value = "http://localhost:8080")]
+    pub server: String,
+
+    /// Path the admin API is mounted under
+    #[arg(long, global = true, default_value = "/admin")]
+    pub prefix: String,
+
+    /// Admin bearer token
+    #[arg(short, long, global = true)]
+    pub token: Option<String>,
+}
+
+/// What to do with the log filter
+#[derive(Subcommand, Debug)]
+pub enum LogLevelAction {
+    /// Show the active filter
+    Show,
+
+    /// Replace the filter, e.g. `info,my_app=debug`
+    Set {
+        /// Filter directives (`RUST_LOG` syntax)
+        filter: String,
+
+        /// Restore the previous filter after this long (`90s`, `10m`, `1h`)
+        #[arg(long = "for", value_parser = parse_duration)]
+        duration: Option<Duration>,
+    },
+
+    /// Add one directive, e.g. `my_app::orders=debug`
+    Add {
+        /// Directive to add
+        directive: String,
+
+        /// Restore the previous filter after this long (`90s`, `10m`, `1h`)
+        #[arg(long = "for", value_parser = parse_duration)]
+        duration: Option<Duration>,
+    },
+
+    /// Go back to the filter the server started with
+    Reset,
+}
+
+/// Execute the log-level command.
+pub async fn log_level(args: LogLevelArgs) -> Result<()> {
+    let token = args
+        .token
+        .context("An admin token is required (--token <TOKEN>)")?;
+    let url = format!(
+        "{}/{}/log-level",
+        args.server.trim_end_matches('/'),
+        args.prefix.trim_matches('/')
+    );
+    let client = reqwest::Client::new();
+
+    let request = match args.action {
+        LogLevelAction::Show => client.get(&url),
+        LogLevelAction::Set { filter, duration } => {
+            client.put(&url).json(&body("filter", &filter, duration))
+        }
+        LogLevelAction::Add {
+            directive,
+            duration,
+        } => client
+            .put(&url)
+            .json(&body("directive", &directive, duration)),
+        LogLevelAction::Reset => client.delete(&url),
+    };
+
+    let resp = request
+        .bearer_auth(token)
+        .send()
+        .await
+        .context("Failed to connect to server")?;
+    let status = resp.status();
+    let body: Value = resp.json().await.context("Failed to parse response")?;
+
+    if !status.is_success() {
+        let msg = body["error"]["message"]
+            .as_str()
+            .or_else(|| body["message"].as_str())
+            .unwrap_or("Unknown error");
+        anyhow::bail!("Server returned {}: {}", status, msg);
+    }
+
+    println!(
+        "{} {}",
+        style("Log filter").bold().cyan(),
+        body["filter"].as_str().unwrap_or("-")
+    );
+    if let Some(secs) = body["expires_in_secs"].as_u64() {
+        println!(
+            "  {} in {}",
+            style("Reverts").dim(),
+            format_duration(Duration::from_secs(secs))
+        );
+    }
+    println!(
+        "  {}  {}",
+        style("Initial").dim(),
+        body["initial"].as_str().unwrap_or("-")
+    );
+    Ok(())
+}
+
+fn body(key: &str, value: &str, duration: Option<Duration>) -> Value {
+    let mut body = json!({ key: value });
+    if let Some(duration) = duration {
+        body["duration_secs"] = json!(duration.as_secs());
+    }
+    body
+}
+
+/// `90`, `90s`, `10m` or `1h`
+fn parse_duration(value: &str) -> Result<Duration, String> {
+    let value = value.trim();
+    let (number, unit) = match value.find(|c: char| !c.is_ascii_digit()) {
+        Some(split) => value.split_at(split),
+        None => (value, "s"),
+    };
+    let number: u64 = number
+        .parse()
+        .map_err(|_| format!("invalid duration '{}'", value))?;
+    let secs = match unit {
+        "s" => number,
+        "m" => number * 60,
+        "h" => number * 3600,
+        _ => {
+            return Err(format!(
+                "invalid duration unit in '{}' (use s, m or h)",
+                value
+            ))
+        }
+    };
+    Ok(Duration::from_secs(secs))
+}
+
+fn format_duration(duration: Duration) -> String {
+    let secs = duration.as_secs();
+    match secs {
+        0..=59 => format!("{}s", secs),
+        60..=3599 => format!("{}m {}s", secs / 60, secs % 60),
+        _ => format!("{}h {}m", secs / 3600, secs % 3600 / 60),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn durations_take_seconds_minutes_and_hours() {
+        assert_eq!(parse_duration("90"), Ok(Duration::from_secs(90)));
+        assert_eq!(parse_duration("90s"), Ok(Duration::from_secs(90)));
+        assert_eq!(parse_duration("10m"), Ok(Duration::from_secs(600)));
+        assert_eq!(parse_duration("1h"), Ok(Duration::from_secs(3600)));
+        assert!(parse_duration("10d").is_err());
+        assert!(parse_duration("m").is_err());
+    }
+
+    #[test]
+    fn temporary_changes_send_the_duration_in_seconds() {
+        let body = body("directive", "app=debug", Some(Duration::from_secs(600)));
+        assert_eq!(
+            body,
+            json!({ "directive": "app=debug", "duration_secs": 600 })
+        );
+    }
+}
@@ -0,0 +1,838 @@
+//! Deployment manifest generation
+//!
+//! Inspects the current project (Cargo.toml, `.env.example`/`.env`, `src/`) to
+//! find its port, health routes and environment variables, and renders
+//! deployment manifests seeded with them: Kubernetes Deployment/Service/HPA
+//! (plain manifests or a Helm chart). The Dockerfile, Fly.io and Railway
+//! configs generated by `deploy` use the same profile.
+
+use anyhow::{Context, Result};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+/// Port used when the project does not configure one
+pub const DEFAULT_PORT: u16 = 8080;
+
+/// Variable names that are set by the generated manifests themselves
+const MANAGED_VARS: [&str; 3] = ["HOST", "PORT", "RUSTAPI_ENV"];
+
+/// Name fragments marking a variable as a secret
+const SECRET_MARKERS: [&str; 7] = [
+    "SECRET",
+    "PASSWORD",
+    "TOKEN",
+    "KEY",
+    "DATABASE_URL",
+    "REDIS_URL",
+    "CREDENTIALS",
+];
+
+/// Directories copied into the runtime image when present
+const ASSET_DIRS: [&str; 4] = ["templates", "static", "migrations", "locales"];
+
+/// Health routes served by the app
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HealthRoutes {
+    /// Aggregated health endpoint
+    pub health: String,
+    /// Readiness endpoint, if the app serves one
+    pub readiness: Option<String>,
+    /// Liveness endpoint, if the app serves one
+    pub liveness: Option<String>,
+}
+
+impl HealthRoutes {
+    /// Path for readiness checks (falls back to the health endpoint)
+    pub fn readiness_path(&self) -> &str {
+        self.readiness.as_deref().unwrap_or(&self.health)
+    }
+
+    /// Path for liveness checks (falls back to the health endpoint)
+    pub fn liveness_path(&self) -> &str {
+        self.liveness.as_deref().unwrap_or(&self.health)
+    }
+}
+
+/// An environment variable read by the app
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EnvVar {
+    pub name: String,
+    /// Value from `.env.example`/`.env` or the default in code
+    pub default: Option<String>,
+    /// Whether the value belongs in a secret store rather than plain config
+    pub secret: bool,
+}
+
+/// What the deployment manifests need to know about a project
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProjectProfile {
+    pub name: String,
+    pub port: u16,
+    pub health: Option<HealthRoutes>,
+    pub env: Vec<EnvVar>,
+    /// Asset directories that must ship next to the binary
+    pub assets: Vec<String>,
+}
+
+impl ProjectProfile {
+    /// Inspect the project in `dir`
+    pub fn detect(dir: &Path) -> Result<Self> {
+        let cargo_toml = fs::read_to_string(dir.join("Cargo.toml"))
+            .context("Failed to read Cargo.toml. Are you inside a RustAPI project?")?;
+        let name = package_name(&cargo_toml).unwrap_or_else(|| "rustapi-app".to_string());
+
+        let dotenv = read_dotenv(dir);
+        let source = read_sources(&dir.join("src"));
+
+        let port = dotenv
+            .get("PORT")
+            .and_then(|p| p.parse().ok())
+            .or_else(|| env_default_in_source(&source, "PORT").and_then(|p| p.parse().ok()))
+            .or_else(|| bound_port_in_source(&source))
+            .unwrap_or(DEFAULT_PORT);
+
+        let assets = ASSET_DIRS
+            .iter()
+            .filter(|d| dir.join(d).is_dir())
+            .map(|d| d.to_string())
+            .collect();
+
+        Ok(Self {
+            name,
+            port,
+            health: detect_health(&source),
+            env: collect_env(&dotenv, &source),
+            assets,
+        })
+    }
+
+    /// Kubernetes-safe resource name
+    pub fn resource_name(&self) -> String {
+        let name: String = self
+            .name
+            .to_ascii_lowercase()
+            .chars()
+            .map(|c| if c.is_ascii_alphanumeric() { c } else { '-' })
+            .collect();
+        name.trim_matches('-').to_string()
+    }
+
+    /// Variables safe to put in plain config
+    pub fn config_vars(&self) -> impl Iterator<Item = &EnvVar> {
+        self.env.iter().filter(|v| !v.secret)
+    }
+
+    /// Variables that belong in a secret store
+    pub fn secret_vars(&self) -> impl Iterator<Item = &EnvVar> {
+        self.env.iter().filter(|v| v.secret)
+    }
+}
+
+/// Options for the Kubernetes manifests
+#[derive(Debug, Clone)]
+pub struct KubernetesOptions {
+    pub image: String,
+    pub replicas: u32,
+    pub min_replicas: u32,
+    pub max_replicas: u32,
+    pub cpu_target: u32,
+}
+
+/// Render `deployment.yaml`, `service.yaml`, `hpa.yaml`, `configmap.yaml`
+/// and (when there are secret variables) `secret.yaml`.
+pub fn kubernetes_manifests(
+    profile: &ProjectProfile,
+    options: &KubernetesOptions,
+) -> Vec<(&'static str, String)> {
+    let name = profile.resource_name();
+    let port = profile.port;
+    let has_secrets = profile.secret_vars().next().is_some();
+
+    let env_from = if has_secrets {
+        format!(
+            "          envFrom:\n            - configMapRef:\n                name: {name}-config\n            - secretRef:\n                name: {name}-secrets\n"
+        )
+    } else {
+        format!("          envFrom:\n            - configMapRef:\n                name: {name}-config\n")
+    };
+
+    let deployment = format!(
+        r#"apiVersion: apps/v1
+kind: Deployment
+metadata:
+  name: {name}
+  labels:
+    app.kubernetes.io/name: {name}
+spec:
+  replicas: {replicas}
+  selector:
+    matchLabels:
+      app.kubernetes.io/name: {name}
+  template:
+    metadata:
+      labels:
+        app.kubernetes.io/name: {name}
+    spec:
+      containers:
+        - name: {name}
+          image: {image}
+          ports:
+            - name: http
+              containerPort: {port}
+{env_from}{probes}          resources:
+            requests:
+              cpu: 100m
+              memory: 64Mi
+            limits:
+              memory: 256Mi
+"#,
+        replicas = options.replicas,
+        image = options.image,
+        probes = probes(profile.health.as_ref(), "          "),
+    );
+
+    let service = format!(
+        r#"apiVersion: v1
+kind: Service
+metadata:
+  name: {name}
+  labels:
+    app.kubernetes.io/name: {name}
+spec:
+  selector:
+    app.kubernetes.io/name: {name}
+  ports:
+    - name: http
+      port: 80
+      targetPort: http
+"#
+    );
+
+    let hpa = format!(
+        r#"apiVersion: autoscaling/v2
+kind: HorizontalPodAutoscaler
+metadata:
+  name: {name}
+spec:
+  scaleTargetRef:
+    apiVersion: apps/v1
+    kind: Deployment
+    name: {name}
+  minReplicas: {min}
+  maxReplicas: {max}
+  metrics:
+    - type: Resource
+      resource:
+        name: cpu
+        target:
+          type: Utilization
+          averageUtilization: {cpu}
+"#,
+        min = options.min_replicas,
+        max = options.max_replicas,
+        cpu = options.cpu_target,
+    );
+
+    let config = format!(
+        "apiVersion: v1\nkind: ConfigMap\nmetadata:\n  name: {name}-config\ndata:\n{}",
+        yaml_entries(&container_config(profile), "  ")
+    );
+
+    let mut files = vec![
+        ("deployment.yaml", deployment),
+        ("service.yaml", service),
+        ("hpa.yaml", hpa),
+        ("configmap.yaml", config),
+    ];
+
+    if has_secrets {
+        let secrets = profile
+            .secret_vars()
+            .map(|v| (v.name.clone(), "change-me".to_string()))
+            .collect::<Vec<_>>();
+        files.push((
+            "secret.yaml",
+            format!(
+                "# Replace the placeholders, or create the secret out of band:\n#   kubectl create secret generic {name}-secrets{}\napiVersion: v1\nkind: Secret\nmetadata:\n  name: {name}-secrets\ntype: Opaque\nstringData:\n{}",
+                profile
+                    .secret_vars()
+                    .map(|v| format!(" --from-literal={}=...", v.name))
+                    .collect::<String>(),
+                yaml_entries(&secrets, "  ")
+            ),
+        ));
+    }
+
+    files
+}
+
+/// Render a Helm chart as `(relative path, content)` pairs.
+pub fn helm_chart(
+    profile: &ProjectProfile,
+    options: &KubernetesOptions,
+) -> Vec<(&'static str, String)> {
+    let name = profile.resource_name();
+    let (repository, tag) = match options.image.rsplit_once(':') {
+        Some((repo, tag)) if !tag.contains('/') => (repo.to_string(), tag.to_string()),
+        _ => (options.image.clone(), "latest".to_string()),
+    };
+
+    let chart = format!(
+        r#"apiVersion: v2
+name: {name}
+description: Helm chart for {name}
+type: application
+version: 0.1.0
+appVersion: "{tag}"
+"#
+    );
+
+    let secrets = profile
+        .secret_vars()
+        .map(|v| (v.name.clone(), String::new()))
+        .collect::<Vec<_>>();
+    let health = match &profile.health {
+        Some(h) => format!(
+            "health:\n  readinessPath: {}\n  livenessPath: {}\n",
+            h.readiness_path(),
+            h.liveness_path()
+        ),
+        None => "health: {}\n".to_string(),
+    };
+    let values = format!(
+        r#"replicaCount: {replicas}
+
+image:
+  repository: {repository}
+  tag: "{tag}"
+  pullPolicy: IfNotPresent
+
+service:
+  type: ClusterIP
+  port: 80
+
+containerPort: {port}
+
+{health}
+autoscaling:
+  enabled: true
+  minReplicas: {min}
+  maxReplicas: {max}
+  targetCPUUtilizationPercentage: {cpu}
+
+resources:
+  requests:
+    cpu: 100m
+    memory: 64Mi
+  limits:
+    memory: 256Mi
+
+# Plain configuration (ConfigMap)
+config:
+{config}
+# Secret values (Secret); set them with --set or an untracked values file
+secrets:{secrets}
+"#,
+        replicas = options.replicas,
+        port = profile.port,
+        min = options.min_replicas,
+        max = options.max_replicas,
+        cpu = options.cpu_target,
+        config = yaml_entries(&container_config(profile), "  "),
+        secrets = if secrets.is_empty() {
+            " {}".to_string()
+        } else {
+            format!("\n{}", yaml_entries(&secrets, "  ").trim_end())
+        },
+    );
+
+    let deployment = r#"apiVersion: apps/v1
+kind: Deployment
+metadata:
+  name: {{ .Release.Name }}
+  labels:
+    app.kubernetes.io/name: {{ .Chart.Name }}
+    app.kubernetes.io/instance: {{ .Release.Name }}
+spec:
+  {{- if not .Values.autoscaling.enabled }}
+  replicas: {{ .Values.replicaCount }}
+  {{- end }}
+  selector:
+    matchLabels:
+      app.kubernetes.io/name: {{ .Chart.Name }}
+      app.kubernetes.io/instance: {{ .Release.Name }}
+  template:
+    metadata:
+      labels:
+        app.kubernetes.io/name: {{ .Chart.Name }}
+        app.kubernetes.io/instance: {{ .Release.Name }}
+      annotations:
+        checksum/config: {{ include (print $.Template.BasePath "/configmap.yaml") . | sha256sum }}
+    spec:
+      containers:
+        - name: {{ .Chart.Name }}
+          image: "{{ .Values.image.repository }}:{{ .Values.image.tag }}"
+          imagePullPolicy: {{ .Values.image.pullPolicy }}
+          ports:
+            - name: http
+              containerPort: {{ .Values.containerPort }}
+          envFrom:
+            - configMapRef:
+                name: {{ .Release.Name }}-config
+            {{- if .Values.secrets }}
+            - secretRef:
+                name: {{ .Release.Name }}-secrets
+            {{- end }}
+          {{- if .Values.health.readinessPath }}
+          readinessProbe:
+            httpGet:
+              path: {{ .Values.health.readinessPath }}
+              port: http
+            periodSeconds: 10
+          {{- end }}
+          {{- if .Values.health.livenessPath }}
+          livenessProbe:
+            httpGet:
+              path: {{ .Values.health.livenessPath }}
+              port: http
+            initialDelaySeconds: 5
+            periodSeconds: 20
+          {{- else }}
+          livenessProbe:
+            tcpSocket:
+              port: http
+            periodSeconds: 20
+          {{- end }}
+          resources:
+            {{- toYaml .Values.resources | nindent 12 }}
+"#
+    .to_string();
+
+    let service = r#"apiVersion: v1
+kind: Service
+metadata:
+  name: {{ .Release.Name }}
+  labels:
+    app.kubernetes.io/name: {{ .Chart.Name }}
+    app.kubernetes.io/instance: {{ .Release.Name }}
+spec:
+  type: {{ .Values.service.type }}
+  selector:
+    app.kubernetes.io/name: {{ .Chart.Name }}
+    app.kubernetes.io/instance: {{ .Release.Name }}
+  ports:
+    - name: http
+      port: {{ .Values.service.port }}
+      targetPort: http
+"#
+    .to_string();
+
+    let hpa = r#"{{- if .Values.autoscaling.enabled }}
+apiVersion: autoscaling/v2
+kind: HorizontalPodAutoscaler
+metadata:
+  name: {{ .Release.Name }}
+spec:
+  scaleTargetRef:
+    apiVersion: apps/v1
+    kind: Deployment
+    name: {{ .Release.Name }}
+  minReplicas: {{ .Values.autoscaling.minReplicas }}
+  maxReplicas: {{ .Values.autoscaling.maxReplicas }}
+  metrics:
+    - type: Resource
+      resource:
+        name: cpu
+        target:
+          type: Utilization
+          averageUtilization: {{ .Values.autoscaling.targetCPUUtilizationPercentage }}
+{{- end }}
+"#
+    .to_string();
+
+    let configmap = r#"apiVersion: v1
+kind: ConfigMap
+metadata:
+  name: {{ .Release.Name }}-config
+data:
+  {{- range $key, $value := .Values.config }}
+  {{ $key }}: {{ $value | quote }}
+  {{- end }}
+"#
+    .to_string();
+
+    let secret = r#"{{- if .Values.secrets }}
+apiVersion: v1
+kind: Secret
+metadata:
+  name: {{ .Release.Name }}-secrets
+type: Opaque
+stringData:
+  {{- range $key, $value := .Values.secrets }}
+  {{ $key }}: {{ $value | quote }}
+  {{- end }}
+{{- end }}
+"#
+    .to_string();
+
+    vec![
+        ("Chart.yaml", chart),
+        ("values.yaml", values),
+        ("templates/deployment.yaml", deployment),
+        ("templates/service.yaml", service),
+        ("templates/hpa.yaml", hpa),
+        ("templates/configmap.yaml", configmap),
+        ("templates/secret.yaml", secret),
+    ]
+}
+
+/// Non-secret configuration for containers: the managed variables plus the
+/// app's own plain variables.
+pub fn container_config(profile: &ProjectProfile) -> Vec<(String, String)> {
+    let mut config = vec![
+        ("HOST".to_string(), "0.0.0.0".to_string()),
+        ("PORT".to_string(), profile.port.to_string()),
+        ("RUSTAPI_ENV".to_string(), "production".to_string()),
+    ];
+    config.extend(
+        profile
+            .config_vars()
+            .map(|v| (v.name.clone(), v.default.clone().unwrap_or_default())),
+    );
+    config
+}
+
+fn probes(health: Option<&HealthRoutes>, indent: &str) -> String {
+    match health {
+        Some(health) => format!(
+            "{indent}readinessProbe:\n{indent}  httpGet:\n{indent}    path: {}\n{indent}    port: http\n{indent}  periodSeconds: 10\n{indent}livenessProbe:\n{indent}  httpGet:\n{indent}    path: {}\n{indent}    port: http\n{indent}  initialDelaySeconds: 5\n{indent}  periodSeconds: 20\n",
+            health.readiness_path(),
+            health.liveness_path()
+        ),
+        // Without a health route, at least restart containers that stop accepting connections
+        None => format!(
+            "{indent}livenessProbe:\n{indent}  tcpSocket:\n{indent}    port: http\n{indent}  periodSeconds: 20\n"
+        ),
+    }
+}
+
+fn yaml_entries(entries: &[(String, String)], indent: &str) -> String {
+    entries
+        .iter()
+        .map(|(k, v)| format!("{indent}{k}: {}\n", yaml_quote(v)))
+        .collect()
+}
+
+/// Double-quoted YAML scalar
+fn yaml_quote(value: &str) -> String {
+    format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+fn package_name(cargo_toml: &str) -> Option<String> {
+    let mut in_package = false;
+    for line in cargo_toml.lines() {
+        let line = line.trim();
+        if line.starts_with('[') {
+            in_package = line == "[package]";
+            continue;
+        }
+        if in_package {
+            if let Some(value) = line.strip_prefix("name") {
+                let value = value.trim_start();
+                if let Some(value) = value.strip_prefix('=') {
+                    return Some(value.trim().trim_matches('"').to_string());
+                }
+            }
+        }
+    }
+    None
+}
+
+/// `KEY=value` pairs from `.env.example`, overlaid by `.env`
+fn read_dotenv(dir: &Path) -> BTreeMap<String, String> {
+    let mut vars = read_env_file(&dir.join(".env.example"));
+    vars.extend(read_env_file(&dir.join(".env")));
+    vars
+}
+
+/// `KEY=value` pairs from a single dotenv file (empty if it is missing)
+pub(super) fn read_env_file(path: &Path) -> BTreeMap<String, String> {
+    let mut vars = BTreeMap::new();
+    let Ok(content) = fs::read_to_string(path) else {
+        return vars;
+    };
+    for line in content.lines() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let line = line.strip_prefix("export ").unwrap_or(line);
+        if let Some((key, value)) = line.split_once('=') {
+            let value = value.trim().trim_matches('"').trim_matches('\'');
+            vars.insert(key.trim().to_string(), value.to_string());
+        }
+    }
+    vars
+}
+
+/// Concatenated `.rs` sources under `dir`
+fn read_sources(dir: &Path) -> String {
+    let mut source = String::new();
+    let Ok(entries) = fs::read_dir(dir) else {
+        return source;
+    };
+    let mut paths: Vec<_> = entries.filter_map(|e| e.ok().map(|e| e.path())).collect();
+    paths.sort();
+    for path in paths {
+        if path.is_dir() {
+            source.push_str(&read_sources(&path));
+        } else if path.extension().is_some_and(|e| e == "rs") {
+            if let Ok(content) = fs::read_to_string(&path) {
+                source.push_str(&content);
+                source.push('\n');
+            }
+        }
+    }
+    source
+}
+
+/// String literal arguments following each occurrence of `pattern`
+/// (`pattern` must end right before the opening quote).
+fn literal_args<'a>(source: &'a str, pattern: &str) -> Vec<&'a str> {
+    source
+        .match_indices(pattern)
+        .filter_map(|(idx, _)| {
+            let rest = &source[idx + pattern.len()..];
+            let end = rest.find('"')?;
+            Some(&rest[..end])
+        })
+        .collect()
+}
+
+/// First string literal argument of each call to `function`, allowing a
+/// turbofish (`env_parse::<u16>("PORT")`)
+pub(super) fn call_args<'a>(source: &'a str, function: &str) -> Vec<&'a str> {
+    source
+        .match_indices(function)
+        .filter_map(|(idx, _)| {
+            let mut rest = &source[idx + function.len()..];
+            if let Some(generics) = rest.strip_prefix("::<") {
+                rest = &generics[generics.find('>')? + 1..];
+            }
+            let rest = rest.strip_prefix("(\"")?;
+            Some(&rest[..rest.find('"')?])
+        })
+        .collect()
+}
+
+/// Default given in code for `name`, e.g. `env_or("PORT", "3000")` or
+/// `env::var("PORT").unwrap_or_else(|_| "3000".to_string())`
+fn env_default_in_source(source: &str, name: &str) -> Option<String> {
+    let needle = format!("\"{name}\"");
+    source.match_indices(&needle).find_map(|(idx, _)| {
+        let statement = &source[idx + needle.len()..];
+        let statement = &statement[..statement.find(';').unwrap_or(statement.len())];
+        let start = statement.find('"')? + 1;
+        let end = statement[start..].find('"')? + start;
+        Some(statement[start..end].to_string())
+    })
+}
+
+/// Port of a literal bind address such as `.run("0.0.0.0:3000")`
+fn bound_port_in_source(source: &str) -> Option<u16> {
+    [".run(\"", "run_rustapi_and_grpc_multiplexed(app, \""]
+        .iter()
+        .flat_map(|pattern| literal_args(source, pattern))
+        .find_map(|addr| addr.rsplit_once(':')?.1.parse().ok())
+}
+
+fn detect_health(source: &str) -> Option<HealthRoutes> {
+    let custom = |setter: &str| literal_args(source, &format!(".{setter}(\"")).pop();
+
+    if source.contains(".health_endpoints")
+        || source.contains(".production_defaults")
+        || source.contains("HealthEndpointConfig")
+    {
+        return Some(HealthRoutes {
+            health: custom("health_path").unwrap_or("/health").to_string(),
+            readiness: Some(custom("readiness_path").unwrap_or("/ready").to_string()),
+            liveness: Some(custom("liveness_path").unwrap_or("/live").to_string()),
+        });
+    }
+
+    let routes = literal_args(source, ".route(\"");
+    let find = |candidates: &[&str]| {
+        candidates
+            .iter()
+            .find(|c| routes.contains(c))
+            .map(|c| c.to_string())
+    };
+    let health = find(&["/health", "/healthz", "/api/health"])?;
+    Some(HealthRoutes {
+        health,
+        readiness: find(&["/ready", "/readyz"]),
+        liveness: find(&["/live", "/livez"]),
+    })
+}
+
+/// Variables from the dotenv files and from `extras::config` helpers
+/// (`require_env`, `try_require_env`, `env_or`, `env_parse`) or `env::var` in code.
+fn collect_env(dotenv: &BTreeMap<String, String>, source: &str) -> Vec<EnvVar> {
+    let mut vars: BTreeMap<String, Option<String>> = dotenv
+        .iter()
+        .map(|(k, v)| (k.clone(), Some(v.clone())))
+        .collect();
+
+    for function in ["require_env", "env_or", "env_parse", "env::var"] {
+        for name in call_args(source, function) {
+            if is_env_name(name) && !vars.contains_key(name) {
+                vars.insert(name.to_string(), env_default_in_source(source, name));
+            }
+        }
+    }
+
+    vars.into_iter()
+        .filter(|(name, _)| !MANAGED_VARS.contains(&name.as_str()))
+        .map(|(name, default)| EnvVar {
+            secret: SECRET_MARKERS.iter().any(|m| name.contains(m)),
+            name,
+            default,
+        })
+        .collect()
+}
+
+pub(super) fn is_env_name(name: &str) -> bool {
+    !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_ascii_uppercase() || c.is_ascii_digit() || c == '_')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn project(files: &[(&str, &str)]) -> tempfile::TempDir {
+        let dir = tempfile::tempdir().unwrap();
+        for (path, content) in files {
+            let path = dir.path().join(path);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, content).unwrap();
+        }
+        dir
+    }
+
+    #[test]
+    fn detects_port_health_and_env() {
+        let dir = project(&[
+            (
+                "Cargo.toml",
+                "[package]\nname = \"Shop_API\"\n\n[dependencies]\nname = \"x\"\n",
+            ),
+            (
+                ".env.example",
+                "# comment\nPORT=3000\nRUST_LOG=info\nJWT_SECRET=dev\n",
+            ),
+            (
+                "src/main.rs",
+                r#"
+                let db = require_env("DATABASE_URL");
+                let workers: usize = env_parse::<usize>("WORKERS").unwrap_or(4);
+                let region = env_or("REGION", "eu-west-1");
+                RustApi::new().health_endpoints().readiness_path("/readyz").run("0.0.0.0:9999")
+                "#,
+            ),
+            ("templates/index.html", ""),
+        ]);
+
+        let profile = ProjectProfile::detect(dir.path()).unwrap();
+        assert_eq!(profile.name, "Shop_API");
+        assert_eq!(profile.resource_name(), "shop-api");
+        assert_eq!(profile.port, 3000);
+        assert_eq!(
+            profile.health,
+            Some(HealthRoutes {
+                health: "/health".to_string(),
+                readiness: Some("/readyz".to_string()),
+                liveness: Some("/live".to_string()),
+            })
+        );
+        assert_eq!(profile.assets, vec!["templates".to_string()]);
+
+        let names: Vec<(&str, bool)> = profile
+            .env
+            .iter()
+            .map(|v| (v.name.as_str(), v.secret))
+            .collect();
+        assert_eq!(
+            names,
+            vec![
+                ("DATABASE_URL", true),
+                ("JWT_SECRET", true),
+                ("REGION", false),
+                ("RUST_LOG", false),
+                ("WORKERS", false),
+            ]
+        );
+        let region = profile.env.iter().find(|v| v.name == "REGION").unwrap();
+        assert_eq!(region.default.as_deref(), Some("eu-west-1"));
+    }
+
+    #[test]
+    fn falls_back_to_literal_routes_and_bind_address() {
+        let dir = project(&[
+            ("Cargo.toml", "[package]\nname = \"plain\"\n"),
+            (
+                "src/main.rs",
+                r#"RustApi::new().route("/healthz", get(h)).run("127.0.0.1:4000").await"#,
+            ),
+        ]);
+
+        let profile = ProjectProfile::detect(dir.path()).unwrap();
+        assert_eq!(profile.port, 4000);
+        let health = profile.health.unwrap();
+        assert_eq!(health.readiness_path(), "/healthz");
+        assert_eq!(health.liveness_path(), "/healthz");
+        assert!(profile.env.is_empty());
+    }
+
+    #[test]
+    fn kubernetes_manifests_use_profile() {
+        let profile = ProjectProfile {
+            name: "shop".to_string(),
+            port: 3000,
+            health: None,
+            env: vec![EnvVar {
+                name: "API_KEY".to_string(),
+                default: None,
+                secret: true,
+            }],
+            assets: Vec::new(),
+        };
+        let options = KubernetesOptions {
+            image: "ghcr.io/acme/shop:1.2.0".to_string(),
+            replicas: 2,
+            min_replicas: 2,
+            max_replicas: 5,
+            cpu_target: 70,
+        };
+
+        let files: BTreeMap<_, _> = kubernetes_manifests(&profile, &options)
+            .into_iter()
+            .collect();
+        let deployment = &files["deployment.yaml"];
+        assert!(deployment.contains("containerPort: 3000"));
+        assert!(deployment.contains("image: ghcr.io/acme/shop:1.2.0"));
+        assert!(deployment.contains("tcpSocket"));
+        assert!(deployment.contains("name: shop-secrets"));
+        assert!(files["configmap.yaml"].contains("PORT: \"3000\""));
+        assert!(files["secret.yaml"].contains("API_KEY: \"change-me\""));
+        assert!(files["hpa.yaml"].contains("maxReplicas: 5"));
+
+        let chart: BTreeMap<_, _> = helm_chart(&profile, &options).into_iter().collect();
+        assert!(chart["values.yaml"].contains("repository: ghcr.io/acme/shop"));
+        assert!(chart["values.yaml"].contains("tag: \"1.2.0\""));
+        assert!(chart["values.yaml"].contains("API_KEY: \"\""));
+    }
+}
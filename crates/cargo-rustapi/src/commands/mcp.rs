@@ -362,54 +362,8 @@ fn sanitize_name(s: &str) -> String {
 /// we auto-extract the OpenAPI by running the project with a special env var
 /// that makes RustApi print the spec and exit (before binding any port).
 async fn auto_generate_and_get_spec_path() -> Result<String> {
-    println!("    Spawning `cargo run` with RUSTAPI_DUMP_OPENAPI=1 to extract spec (no server binding)...");
-
-    let output = std::process::Command::new("cargo")
-        .arg("run")
-        .arg("--quiet")
-        .env("RUSTAPI_DUMP_OPENAPI", "1")
-        .stdout(std::process::Stdio::piped())
-        .stderr(std::process::Stdio::inherit())
-        .output()
-        .context(
-            "Failed to execute `cargo run` for spec dump. Are you inside a RustAPI project?",
-        )?;
-
-    if !output.status.success() {
-        anyhow::bail!(
-            "Failed to build/run the project to extract OpenAPI.\n\
-             Try running `cargo run` manually first to ensure it compiles, then retry `cargo rustapi mcp generate`."
-        );
-    }
-
-    let stdout =
-        String::from_utf8(output.stdout).context("Captured OpenAPI output was not valid UTF-8")?;
-
-    // The dump prints the JSON (possibly after some startup prints from the app).
-    // Find the last occurrence of a top-level OpenAPI object for robustness.
-    let json_str = if let Some(idx) = stdout.rfind(r#""openapi": "3."#) {
-        // Go back to the opening { of that object
-        let start = stdout[..idx].rfind('{').unwrap_or(0);
-        let candidate = &stdout[start..];
-        // cut at the last }
-        if let Some(end) = candidate.rfind('}') {
-            candidate[..=end].trim().to_string()
-        } else {
-            candidate.trim().to_string()
-        }
-    } else if let Some(idx) = stdout.find('{') {
-        let candidate = &stdout[idx..];
-        candidate.trim().to_string()
-    } else {
-        stdout.trim().to_string()
-    };
-
-    if json_str.is_empty() || !json_str.starts_with('{') {
-        anyhow::bail!(
-            "Could not extract a valid OpenAPI JSON from the project dump.\n\
-             Make sure your main uses RustApi::auto() or similar."
-        );
-    }
+    let spec = super::openapi::dump_project_spec().await?;
+    let json_str = serde_json::to_string_pretty(&spec)?;
 
     // Write to a temp file so load_openapi_spec can handle it uniformly
     let temp_path =
@@ -1,13 +1,16 @@
 //! Database migration commands
 //!
 //! Provides a wrapper around sqlx-cli for database migrations.
-//! Supports creating, running, reverting, and checking migration status.
+//! Supports creating, running, reverting, and checking migration status, and
+//! generating candidate migrations from `#[rustapi_rs::model]` structs.
 
+use super::manifests;
+use super::model_diff::{self, Dialect};
 use anyhow::{Context, Result};
 use clap::{Args, Subcommand};
 use console::{style, Emoji};
 use indicatif::{ProgressBar, ProgressStyle};
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::time::Duration;
 use tokio::fs;
 use tokio::process::Command;
@@ -33,6 +36,9 @@ pub enum MigrateArgs {
     /// Create a new migration
     Create(MigrateCreateArgs),
 
+    /// Generate a migration from the differences between model structs and the database
+    Generate(MigrateGenerateArgs),
+
     /// Reset database (drop, create, run all migrations)
     Reset(MigrateResetArgs),
 }
@@ -100,6 +106,33 @@ pub struct MigrateCreateArgs {
     pub timestamp: bool,
 }
 
+#[derive(Args, Debug)]
+pub struct MigrateGenerateArgs {
+    /// Migration name
+    #[arg(default_value = "sync_models")]
+    pub name: String,
+
+    /// Diff `#[rustapi_rs::model]` structs against the current database schema
+    #[arg(long, required = true)]
+    pub from_models: bool,
+
+    /// Directory scanned for model structs
+    #[arg(long, default_value = "src")]
+    pub models: PathBuf,
+
+    /// Database URL (overrides DATABASE_URL env var)
+    #[arg(long)]
+    pub database_url: Option<String>,
+
+    /// Migrations directory
+    #[arg(long, default_value = "migrations")]
+    pub source: String,
+
+    /// Print the SQL instead of writing a migration file
+    #[arg(long)]
+    pub dry_run: bool,
+}
+
 #[derive(Args, Debug)]
 pub struct MigrateResetArgs {
     /// Database URL (overrides DATABASE_URL env var)
@@ -131,6 +164,7 @@ pub async fn migrate(args: MigrateArgs) -> Result<()> {
             show_status(args).await
         }
         MigrateArgs::Create(args) => create_migration(args).await, // No sqlx needed
+        MigrateArgs::Generate(args) => generate_migration(args).await, // Uses the database CLI client
         MigrateArgs::Reset(args) => {
             ensure_sqlx_installed().await?;
             reset_database(args).await
@@ -403,6 +437,89 @@ async fn create_migration(args: MigrateCreateArgs) -> Result<()> {
     Ok(())
 }
 
+/// Generate a migration from model structs
+async fn generate_migration(args: MigrateGenerateArgs) -> Result<()> {
+    println!(
+        "{} {} Diffing models in {} against the database...",
+        DB,
+        style("migrate generate").cyan().bold(),
+        args.models.display()
+    );
+    println!();
+
+    let database_url = match args.database_url {
+        Some(url) => url,
+        None => std::env::var("DATABASE_URL")
+            .ok()
+            .or_else(|| manifests::read_env_file(Path::new(".env")).remove("DATABASE_URL"))
+            .context("No database configured. Set DATABASE_URL or pass --database-url")?,
+    };
+    let dialect = Dialect::from_url(&database_url)?;
+
+    let models = model_diff::parse_models(&args.models)?;
+    if models.is_empty() {
+        println!(
+            "{} {} No #[rustapi_rs::model] structs found in {}",
+            WARN,
+            style("Warning:").yellow(),
+            args.models.display()
+        );
+        return Ok(());
+    }
+    for model in &models {
+        println!(
+            "   {} {} -> {}",
+            ARROW,
+            model.struct_name,
+            style(&model.table).cyan()
+        );
+    }
+    println!();
+
+    let columns = model_diff::introspect(&database_url).await?;
+    let statements = model_diff::diff(&models, &columns, dialect);
+    if statements.is_empty() {
+        println!(
+            "{} Database schema matches the models, nothing to generate",
+            CHECK
+        );
+        return Ok(());
+    }
+
+    let timestamp = chrono_timestamp();
+    let content = format!(
+        "-- Migration: {}\n-- Created at: {}\n-- Generated from model structs by `cargo rustapi migrate generate --from-models`.\n-- Review before running: destructive changes are commented out.\n\n{}\n",
+        args.name,
+        timestamp,
+        statements.join("\n\n")
+    );
+
+    if args.dry_run {
+        println!("{content}");
+        return Ok(());
+    }
+
+    if !Path::new(&args.source).exists() {
+        fs::create_dir_all(&args.source).await?;
+    }
+    let migration_file = format!("{}/{}_{}.sql", args.source, timestamp, args.name);
+    fs::write(&migration_file, content).await?;
+
+    println!(
+        "{} Created migration ({} change(s)):",
+        CHECK,
+        statements.len()
+    );
+    println!("   {} {}", ARROW, style(&migration_file).cyan());
+    println!();
+    println!(
+        "{}",
+        style("Review it, then apply with: cargo rustapi migrate run").dim()
+    );
+
+    Ok(())
+}
+
 /// Reset database
 async fn reset_database(args: MigrateResetArgs) -> Result<()> {
     println!(
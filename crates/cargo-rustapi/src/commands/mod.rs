@@ -6,16 +6,24 @@ mod client;
 mod deploy;
 #[cfg(feature = "cloud")]
 mod deploys;
+mod dev_proxy;
 mod docs;
 mod doctor;
+mod errors;
 mod generate;
+mod loadgen;
 #[cfg(feature = "cloud")]
 mod login;
 mod logout;
+mod manifests;
 mod migrate;
+mod model_diff;
 mod new;
 mod observability;
+mod openapi;
+mod routes;
 mod run;
+mod seed;
 mod watch;
 mod whoami;
 
@@ -27,6 +35,7 @@ pub use deploy::{deploy, DeployArgs};
 pub use deploys::deploys_list;
 pub use docs::open_docs;
 pub use doctor::{doctor, DoctorArgs};
+pub use errors::{errors, ErrorsArgs};
 pub use generate::{generate, GenerateArgs};
 #[cfg(feature = "cloud")]
 pub use login::{login, LoginArgs};
@@ -34,7 +43,10 @@ pub use logout::{logout, LogoutArgs};
 pub use migrate::{migrate, MigrateArgs};
 pub use new::{new_project, NewArgs};
 pub use observability::{observability, ObservabilityArgs};
+pub use openapi::{openapi, OpenapiArgs};
+pub use routes::{routes, RoutesArgs};
 pub use run::{run_dev, RunArgs};
+pub use seed::{seed, SeedArgs};
 pub use watch::{watch, WatchArgs};
 pub use whoami::{whoami, WhoamiArgs};
 
@@ -43,6 +55,16 @@ mod replay;
 #[cfg(feature = "replay")]
 pub use replay::{replay, ReplayArgs};
 
+#[cfg(feature = "admin")]
+mod log_level;
+#[cfg(feature = "admin")]
+pub use log_level::{log_level, LogLevelArgs};
+
+#[cfg(feature = "remote-spec")]
+mod export;
+#[cfg(feature = "remote-spec")]
+pub use export::{export, ExportArgs};
+
 #[cfg(feature = "mcp")]
 mod mcp;
 #[cfg(feature = "mcp")]
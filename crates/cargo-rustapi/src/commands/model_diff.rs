@@ -0,0 +1,769 @@
+//! Model-to-schema diffing for `cargo rustapi migrate generate --from-models`
+//!
+//! Model structs are the ones marked with `#[rustapi_rs::model]`. Their
+//! fields are compared with the columns the database reports, and the
+//! differences become SQL statements for a migration. Anything that could
+//! lose data (dropping columns, changing types) is emitted commented out so
+//! it is only applied after review.
+
+use super::seed;
+use anyhow::{bail, Context, Result};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+use std::process::Stdio;
+use tokio::io::AsyncWriteExt;
+use tokio::process::Command;
+
+/// SQL dialect of the target database
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Dialect {
+    Postgres,
+    MySql,
+    Sqlite,
+}
+
+impl Dialect {
+    pub fn from_url(database_url: &str) -> Result<Self> {
+        match database_url.split(':').next().unwrap_or_default() {
+            "postgres" | "postgresql" => Ok(Self::Postgres),
+            "mysql" | "mariadb" => Ok(Self::MySql),
+            "sqlite" => Ok(Self::Sqlite),
+            scheme => bail!("Unsupported database `{scheme}` (expected postgres, mysql or sqlite)"),
+        }
+    }
+
+    /// Columns of every table in the current schema: table, column, type, nullable
+    fn columns_query(self) -> &'static str {
+        match self {
+            Self::Postgres => {
+                "SELECT table_name, column_name, data_type, is_nullable \
+                 FROM information_schema.columns WHERE table_schema = current_schema() \
+                 ORDER BY table_name, ordinal_position;"
+            }
+            Self::MySql => {
+                "SELECT table_name, column_name, column_type, is_nullable \
+                 FROM information_schema.columns WHERE table_schema = DATABASE() \
+                 ORDER BY table_name, ordinal_position;"
+            }
+            Self::Sqlite => {
+                "SELECT m.name, p.name, p.type, \
+                 CASE WHEN p.\"notnull\" = 1 OR p.pk > 0 THEN 'NO' ELSE 'YES' END \
+                 FROM sqlite_master m JOIN pragma_table_info(m.name) p \
+                 WHERE m.type = 'table' AND m.name NOT LIKE 'sqlite_%' \
+                 ORDER BY m.name, p.cid;"
+            }
+        }
+    }
+
+    /// Client flags for unaligned, header-less, tab-separated output
+    fn output_args(self) -> [&'static str; 3] {
+        match self {
+            Self::Postgres => ["--no-align", "--tuples-only", "--field-separator=\t"],
+            Self::MySql => ["--batch", "--skip-column-names", "--raw"],
+            Self::Sqlite => ["-noheader", "-separator", "\t"],
+        }
+    }
+}
+
+/// Column type as far as the diff is concerned
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SqlType {
+    SmallInt,
+    Int,
+    BigInt,
+    Real,
+    Double,
+    Decimal,
+    Bool,
+    Text,
+    Uuid,
+    TimestampTz,
+    Timestamp,
+    Date,
+    Time,
+    Json,
+    Bytes,
+}
+
+/// Broad type family used to compare model and database types
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Family {
+    Integer,
+    Float,
+    Decimal,
+    Bool,
+    Text,
+    Uuid,
+    Timestamp,
+    Date,
+    Time,
+    Json,
+    Bytes,
+    Other,
+}
+
+impl SqlType {
+    fn ddl(self, dialect: Dialect) -> &'static str {
+        use Dialect::*;
+        match (self, dialect) {
+            (Self::SmallInt, Sqlite) | (Self::Int, Sqlite) | (Self::BigInt, Sqlite) => "INTEGER",
+            (Self::SmallInt, _) => "SMALLINT",
+            (Self::Int, MySql) => "INT",
+            (Self::Int, _) => "INTEGER",
+            (Self::BigInt, _) => "BIGINT",
+            (Self::Real, MySql) => "FLOAT",
+            (Self::Real, _) => "REAL",
+            (Self::Double, Postgres) => "DOUBLE PRECISION",
+            (Self::Double, MySql) => "DOUBLE",
+            (Self::Double, Sqlite) => "REAL",
+            (Self::Decimal, MySql) => "DECIMAL(20, 6)",
+            (Self::Decimal, _) => "NUMERIC",
+            (Self::Bool, _) => "BOOLEAN",
+            (Self::Text, _) => "TEXT",
+            (Self::Uuid, Postgres) => "UUID",
+            (Self::Uuid, MySql) => "CHAR(36)",
+            (Self::Uuid, Sqlite) => "TEXT",
+            (Self::TimestampTz, Postgres) => "TIMESTAMPTZ",
+            (Self::TimestampTz, MySql) => "TIMESTAMP",
+            (Self::Timestamp, Postgres) => "TIMESTAMP",
+            (Self::Timestamp, MySql) => "DATETIME",
+            (Self::TimestampTz | Self::Timestamp, Sqlite) => "TEXT",
+            (Self::Date, Sqlite) | (Self::Time, Sqlite) => "TEXT",
+            (Self::Date, _) => "DATE",
+            (Self::Time, _) => "TIME",
+            (Self::Json, Postgres) => "JSONB",
+            (Self::Json, MySql) => "JSON",
+            (Self::Json, Sqlite) => "TEXT",
+            (Self::Bytes, Postgres) => "BYTEA",
+            (Self::Bytes, _) => "BLOB",
+        }
+    }
+
+    /// Column definition of an auto-generated primary key, if this type has one
+    fn serial_primary_key(self, dialect: Dialect) -> Option<&'static str> {
+        match (self, dialect) {
+            (Self::Int | Self::BigInt, Dialect::Sqlite) => {
+                Some("INTEGER PRIMARY KEY AUTOINCREMENT")
+            }
+            (Self::Int, Dialect::Postgres) => Some("SERIAL PRIMARY KEY"),
+            (Self::BigInt, Dialect::Postgres) => Some("BIGSERIAL PRIMARY KEY"),
+            (Self::Int, Dialect::MySql) => Some("INT AUTO_INCREMENT PRIMARY KEY"),
+            (Self::BigInt, Dialect::MySql) => Some("BIGINT AUTO_INCREMENT PRIMARY KEY"),
+            _ => None,
+        }
+    }
+
+    fn family(self) -> Family {
+        match self {
+            Self::SmallInt | Self::Int | Self::BigInt => Family::Integer,
+            Self::Real | Self::Double => Family::Float,
+            Self::Decimal => Family::Decimal,
+            Self::Bool => Family::Bool,
+            Self::Text => Family::Text,
+            Self::Uuid => Family::Uuid,
+            Self::TimestampTz | Self::Timestamp => Family::Timestamp,
+            Self::Date => Family::Date,
+            Self::Time => Family::Time,
+            Self::Json => Family::Json,
+            Self::Bytes => Family::Bytes,
+        }
+    }
+
+    /// Whether a column reported as `db_type` can hold this type. Storage
+    /// fallbacks (UUIDs and dates as text, booleans as integers) count.
+    fn accepts(self, db_type: &str) -> bool {
+        let db = db_family(db_type);
+        let model = self.family();
+        db == model
+            || db == Family::Other
+            || (db == Family::Text
+                && matches!(
+                    model,
+                    Family::Uuid | Family::Timestamp | Family::Date | Family::Time | Family::Json
+                ))
+            || (db == Family::Integer && model == Family::Bool)
+    }
+}
+
+fn db_family(db_type: &str) -> Family {
+    let t = db_type.to_ascii_lowercase();
+    if t.starts_with("interval") || t == "point" {
+        Family::Other
+    } else if t.starts_with("bool") || t == "tinyint(1)" {
+        Family::Bool
+    } else if t.contains("int") || t.contains("serial") {
+        Family::Integer
+    } else if t.contains("uuid") {
+        Family::Uuid
+    } else if t.contains("json") {
+        Family::Json
+    } else if t.starts_with("timestamp") || t.starts_with("datetime") {
+        Family::Timestamp
+    } else if t == "date" {
+        Family::Date
+    } else if t.starts_with("time") {
+        Family::Time
+    } else if t.contains("char") || t.contains("text") || t.contains("clob") {
+        Family::Text
+    } else if t.contains("real") || t.contains("double") || t.contains("float") {
+        Family::Float
+    } else if t.contains("numeric") || t.contains("decimal") {
+        Family::Decimal
+    } else if t.contains("bytea") || t.contains("blob") || t.contains("binary") {
+        Family::Bytes
+    } else {
+        Family::Other
+    }
+}
+
+/// A `#[rustapi_rs::model]` struct
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Model {
+    pub struct_name: String,
+    pub table: String,
+    columns: Vec<ModelColumn>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct ModelColumn {
+    name: String,
+    /// Rust type as written, for messages
+    rust_type: String,
+    /// `None` when the Rust type has no obvious SQL counterpart
+    sql_type: Option<SqlType>,
+    nullable: bool,
+}
+
+impl ModelColumn {
+    fn is_primary_key(&self) -> bool {
+        self.name == "id"
+    }
+
+    fn ddl_type(&self, dialect: Dialect) -> &'static str {
+        self.sql_type.map_or("TEXT", |t| t.ddl(dialect))
+    }
+
+    /// Column definition for `CREATE TABLE`
+    fn definition(&self, dialect: Dialect) -> String {
+        if self.is_primary_key() {
+            if let Some(serial) = self.sql_type.and_then(|t| t.serial_primary_key(dialect)) {
+                return format!("{} {}", self.name, serial);
+            }
+            return format!("{} {} PRIMARY KEY", self.name, self.ddl_type(dialect));
+        }
+        let mut definition = format!("{} {}", self.name, self.ddl_type(dialect));
+        if !self.nullable {
+            definition.push_str(" NOT NULL");
+        }
+        definition
+    }
+}
+
+/// A column reported by the database
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DbColumn {
+    pub table: String,
+    pub name: String,
+    pub data_type: String,
+    pub nullable: bool,
+}
+
+/// Model structs found in the `.rs` files under `dir`
+pub fn parse_models(dir: &Path) -> Result<Vec<Model>> {
+    let mut models = Vec::new();
+    collect_models(dir, &mut models)?;
+    models.sort_by(|a, b| a.table.cmp(&b.table));
+    Ok(models)
+}
+
+fn collect_models(dir: &Path, models: &mut Vec<Model>) -> Result<()> {
+    let entries = fs::read_dir(dir).with_context(|| format!("Failed to read {}", dir.display()))?;
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.is_dir() {
+            collect_models(&path, models)?;
+        } else if path.extension().is_some_and(|ext| ext == "rs") {
+            let source = fs::read_to_string(&path)?;
+            let file = syn::parse_file(&source)
+                .with_context(|| format!("Failed to parse {}", path.display()))?;
+            collect_item_models(&file.items, models)?;
+        }
+    }
+    Ok(())
+}
+
+fn collect_item_models(items: &[syn::Item], models: &mut Vec<Model>) -> Result<()> {
+    for item in items {
+        match item {
+            syn::Item::Struct(item) => {
+                if let Some(model) = parse_model(item)? {
+                    models.push(model);
+                }
+            }
+            syn::Item::Mod(module) => {
+                if let Some((_, items)) = &module.content {
+                    collect_item_models(items, models)?;
+                }
+            }
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+fn parse_model(item: &syn::ItemStruct) -> Result<Option<Model>> {
+    let Some(marker) = item.attrs.iter().find(|attr| {
+        attr.path()
+            .segments
+            .last()
+            .is_some_and(|segment| segment.ident == "model")
+    }) else {
+        return Ok(None);
+    };
+
+    let struct_name = item.ident.to_string();
+    let mut table = None;
+    if matches!(marker.meta, syn::Meta::List(_)) {
+        marker
+            .parse_nested_meta(|meta| {
+                if meta.path.is_ident("table") {
+                    let value: syn::LitStr = meta.value()?.parse()?;
+                    table = Some(value.value());
+                    Ok(())
+                } else {
+                    Err(meta.error("expected `table = \"...\"`"))
+                }
+            })
+            .with_context(|| format!("Invalid #[model] attribute on {struct_name}"))?;
+    }
+
+    let mut columns = Vec::new();
+    for field in &item.fields {
+        let Some(ident) = &field.ident else {
+            bail!("Model {struct_name} must have named fields");
+        };
+        let attrs = SqlxFieldAttrs::parse(&field.attrs)
+            .with_context(|| format!("Invalid #[sqlx] attribute on {struct_name}.{ident}"))?;
+        if attrs.skip {
+            continue;
+        }
+
+        let (inner, nullable) = unwrap_option(&field.ty);
+        let sql_type = if attrs.json {
+            Some(SqlType::Json)
+        } else {
+            sql_type_of(inner)
+        };
+        columns.push(ModelColumn {
+            name: attrs
+                .rename
+                .unwrap_or_else(|| ident.to_string().trim_start_matches("r#").to_string()),
+            rust_type: type_name(&field.ty),
+            sql_type,
+            nullable,
+        });
+    }
+
+    Ok(Some(Model {
+        table: table.unwrap_or_else(|| format!("{}s", snake_case(&struct_name))),
+        struct_name,
+        columns,
+    }))
+}
+
+/// The `#[sqlx(...)]` field options that affect the column
+#[derive(Default)]
+struct SqlxFieldAttrs {
+    rename: Option<String>,
+    skip: bool,
+    json: bool,
+}
+
+impl SqlxFieldAttrs {
+    fn parse(attrs: &[syn::Attribute]) -> syn::Result<Self> {
+        let mut parsed = Self::default();
+        for attr in attrs.iter().filter(|attr| attr.path().is_ident("sqlx")) {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("rename") {
+                    parsed.rename = Some(meta.value()?.parse::<syn::LitStr>()?.value());
+                } else if meta.path.is_ident("skip") || meta.path.is_ident("flatten") {
+                    parsed.skip = true;
+                } else if meta.path.is_ident("json") {
+                    parsed.json = true;
+                } else if meta.input.peek(syn::Token![=]) {
+                    // Options that do not change the column (default, try_from, ...)
+                    meta.value()?.parse::<syn::Expr>()?;
+                }
+                Ok(())
+            })?;
+        }
+        Ok(parsed)
+    }
+}
+
+/// `Option<T>` → (`T`, true)
+fn unwrap_option(ty: &syn::Type) -> (&syn::Type, bool) {
+    match generic_args(ty) {
+        Some((name, args)) if name == "Option" && args.len() == 1 => (args[0], true),
+        _ => (ty, false),
+    }
+}
+
+/// Last path segment of a type and its type arguments
+fn generic_args(ty: &syn::Type) -> Option<(String, Vec<&syn::Type>)> {
+    let syn::Type::Path(path) = ty else {
+        return None;
+    };
+    let segment = path.path.segments.last()?;
+    let args = match &segment.arguments {
+        syn::PathArguments::AngleBracketed(args) => args
+            .args
+            .iter()
+            .filter_map(|arg| match arg {
+                syn::GenericArgument::Type(ty) => Some(ty),
+                _ => None,
+            })
+            .collect(),
+        _ => Vec::new(),
+    };
+    Some((segment.ident.to_string(), args))
+}
+
+fn sql_type_of(ty: &syn::Type) -> Option<SqlType> {
+    if let syn::Type::Reference(reference) = ty {
+        return sql_type_of(&reference.elem);
+    }
+    let (name, args) = generic_args(ty)?;
+    Some(match name.as_str() {
+        "i8" | "i16" | "u8" => SqlType::SmallInt,
+        "i32" | "u16" => SqlType::Int,
+        "i64" | "u32" | "u64" | "isize" | "usize" => SqlType::BigInt,
+        "f32" => SqlType::Real,
+        "f64" => SqlType::Double,
+        "Decimal" | "BigDecimal" => SqlType::Decimal,
+        "bool" => SqlType::Bool,
+        "String" | "str" | "Cow" => SqlType::Text,
+        "Uuid" => SqlType::Uuid,
+        "DateTime" | "OffsetDateTime" => SqlType::TimestampTz,
+        "NaiveDateTime" | "PrimitiveDateTime" => SqlType::Timestamp,
+        "NaiveDate" | "Date" => SqlType::Date,
+        "NaiveTime" | "Time" => SqlType::Time,
+        "Json" | "Value" | "JsonValue" => SqlType::Json,
+        "Vec"
+            if args
+                .first()
+                .and_then(|arg| generic_args(arg))
+                .is_some_and(|(n, _)| n == "u8") =>
+        {
+            SqlType::Bytes
+        }
+        _ => return None,
+    })
+}
+
+/// Compact rendering of a type for messages, e.g. `Option<DateTime<Utc>>`
+fn type_name(ty: &syn::Type) -> String {
+    match ty {
+        syn::Type::Reference(reference) => format!("&{}", type_name(&reference.elem)),
+        _ => match generic_args(ty) {
+            Some((name, args)) if args.is_empty() => name,
+            Some((name, args)) => format!(
+                "{name}<{}>",
+                args.into_iter()
+                    .map(type_name)
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
+            None => "?".to_string(),
+        },
+    }
+}
+
+fn snake_case(name: &str) -> String {
+    let mut snake = String::new();
+    for (i, c) in name.chars().enumerate() {
+        if c.is_ascii_uppercase() {
+            if i > 0 {
+                snake.push('_');
+            }
+            snake.push(c.to_ascii_lowercase());
+        } else {
+            snake.push(c);
+        }
+    }
+    snake
+}
+
+/// Columns of the database at `database_url`, read with its CLI client
+pub async fn introspect(database_url: &str) -> Result<Vec<DbColumn>> {
+    let dialect = Dialect::from_url(database_url)?;
+    let (program, mut args) = seed::sql_client(database_url)?;
+    args.splice(0..0, dialect.output_args().map(str::to_string));
+
+    let mut child = Command::new(program)
+        .args(&args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .with_context(|| format!("Failed to run `{program}`. Is it installed and on PATH?"))?;
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin.write_all(dialect.columns_query().as_bytes()).await?;
+    }
+    let output = child.wait_with_output().await?;
+    if !output.status.success() {
+        bail!(
+            "Failed to read the database schema: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.split('\t');
+            Some(DbColumn {
+                table: fields.next()?.to_string(),
+                name: fields.next()?.to_string(),
+                data_type: fields.next()?.to_string(),
+                nullable: fields.next()?.trim() == "YES",
+            })
+        })
+        .collect())
+}
+
+/// SQL that brings the database in line with the models; empty when they match
+pub fn diff(models: &[Model], database: &[DbColumn], dialect: Dialect) -> Vec<String> {
+    let mut tables: BTreeMap<&str, Vec<&DbColumn>> = BTreeMap::new();
+    for column in database {
+        tables.entry(&column.table).or_default().push(column);
+    }
+
+    let mut statements = Vec::new();
+    for model in models {
+        let Some(existing) = tables.get(model.table.as_str()) else {
+            statements.push(create_table(model, dialect));
+            continue;
+        };
+
+        for column in &model.columns {
+            match existing.iter().find(|c| c.name == column.name) {
+                None => statements.push(add_column(&model.table, column, dialect)),
+                Some(db) => statements.extend(alter_column(&model.table, column, db, dialect)),
+            }
+        }
+
+        for db in existing {
+            if !model.columns.iter().any(|c| c.name == db.name) {
+                statements.push(format!(
+                    "-- {}.{} is not a field of {}. Uncomment to drop it:\n-- ALTER TABLE {} DROP COLUMN {};",
+                    model.table, db.name, model.struct_name, model.table, db.name
+                ));
+            }
+        }
+    }
+    statements
+}
+
+fn create_table(model: &Model, dialect: Dialect) -> String {
+    let mut sql = format!("-- {}\n", model.struct_name);
+    for column in model.columns.iter().filter(|c| c.sql_type.is_none()) {
+        sql.push_str(&format!(
+            "-- Review: no SQL type known for {} ({}), using TEXT\n",
+            column.name, column.rust_type
+        ));
+    }
+    let columns: Vec<String> = model
+        .columns
+        .iter()
+        .map(|column| format!("    {}", column.definition(dialect)))
+        .collect();
+    sql.push_str(&format!(
+        "CREATE TABLE {} (\n{}\n);",
+        model.table,
+        columns.join(",\n")
+    ));
+    sql
+}
+
+fn add_column(table: &str, column: &ModelColumn, dialect: Dialect) -> String {
+    let mut sql = String::new();
+    if !column.nullable {
+        sql.push_str("-- Review: existing rows need a value; add a DEFAULT or backfill first\n");
+    }
+    if column.sql_type.is_none() {
+        sql.push_str(&format!(
+            "-- Review: no SQL type known for {}, using TEXT\n",
+            column.rust_type
+        ));
+    }
+    sql.push_str(&format!(
+        "ALTER TABLE {table} ADD COLUMN {};",
+        column.definition(dialect)
+    ));
+    sql
+}
+
+fn alter_column(table: &str, column: &ModelColumn, db: &DbColumn, dialect: Dialect) -> Vec<String> {
+    let mut statements = Vec::new();
+    let name = &column.name;
+
+    if let Some(sql_type) = column.sql_type {
+        if !sql_type.accepts(&db.data_type) {
+            let ddl = sql_type.ddl(dialect);
+            let change = match dialect {
+                Dialect::Postgres => {
+                    format!("-- ALTER TABLE {table} ALTER COLUMN {name} TYPE {ddl} USING {name}::{ddl};")
+                }
+                Dialect::MySql => format!("-- ALTER TABLE {table} MODIFY COLUMN {name} {ddl};"),
+                Dialect::Sqlite => {
+                    "-- SQLite cannot change column types; rebuild the table".to_string()
+                }
+            };
+            statements.push(format!(
+                "-- {table}.{name} is {} in the database but {} in the model. Uncomment to convert it:\n{change}",
+                db.data_type, column.rust_type
+            ));
+        }
+    }
+
+    if column.nullable != db.nullable && !column.is_primary_key() {
+        let not_null = if column.nullable { "DROP" } else { "SET" };
+        statements.push(match dialect {
+            Dialect::Postgres => {
+                format!("ALTER TABLE {table} ALTER COLUMN {name} {not_null} NOT NULL;")
+            }
+            Dialect::MySql => format!(
+                "ALTER TABLE {table} MODIFY COLUMN {name} {}{};",
+                db.data_type.to_ascii_uppercase(),
+                if column.nullable { " NULL" } else { " NOT NULL" }
+            ),
+            Dialect::Sqlite => format!(
+                "-- {table}.{name} should be {}; SQLite cannot change this in place, rebuild the table",
+                if column.nullable { "nullable" } else { "NOT NULL" }
+            ),
+        });
+    }
+
+    statements
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    const MODELS: &str = r#"
+        use rustapi_rs::prelude::*;
+
+        #[rustapi_rs::model(table = "todos")]
+        #[derive(Debug, sqlx::FromRow)]
+        pub struct Todo {
+            pub id: i64,
+            pub title: String,
+            #[sqlx(rename = "is_done")]
+            pub done: bool,
+            pub due: Option<chrono::NaiveDate>,
+            #[sqlx(skip)]
+            pub cached: Vec<String>,
+        }
+
+        pub mod accounts {
+            #[rustapi_rs::model]
+            pub struct UserProfile {
+                pub id: uuid::Uuid,
+                pub settings: serde_json::Value,
+            }
+        }
+
+        pub struct NotAModel {
+            pub id: i64,
+        }
+    "#;
+
+    fn models() -> Vec<Model> {
+        let dir = tempdir().unwrap();
+        fs::write(dir.path().join("models.rs"), MODELS).unwrap();
+        parse_models(dir.path()).unwrap()
+    }
+
+    fn db(table: &str, name: &str, data_type: &str, nullable: bool) -> DbColumn {
+        DbColumn {
+            table: table.to_string(),
+            name: name.to_string(),
+            data_type: data_type.to_string(),
+            nullable,
+        }
+    }
+
+    #[test]
+    fn parses_marked_structs() {
+        let models = models();
+        assert_eq!(models.len(), 2);
+
+        let todo = models.iter().find(|m| m.table == "todos").unwrap();
+        let names: Vec<_> = todo.columns.iter().map(|c| c.name.as_str()).collect();
+        assert_eq!(names, ["id", "title", "is_done", "due"]);
+        assert!(todo.columns[3].nullable);
+        assert_eq!(todo.columns[3].sql_type, Some(SqlType::Date));
+
+        let profile = models
+            .iter()
+            .find(|m| m.struct_name == "UserProfile")
+            .unwrap();
+        assert_eq!(profile.table, "user_profiles");
+    }
+
+    #[test]
+    fn creates_missing_tables() {
+        let statements = diff(&models(), &[], Dialect::Postgres);
+        assert_eq!(statements.len(), 2);
+        assert!(statements[0].contains(
+            "CREATE TABLE todos (\n    id BIGSERIAL PRIMARY KEY,\n    title TEXT NOT NULL,\n    is_done BOOLEAN NOT NULL,\n    due DATE\n);"
+        ));
+        assert!(statements[1].contains("id UUID PRIMARY KEY"));
+        assert!(statements[1].contains("settings JSONB NOT NULL"));
+
+        let statements = diff(&models(), &[], Dialect::Sqlite);
+        assert!(statements[0].contains("id INTEGER PRIMARY KEY AUTOINCREMENT"));
+    }
+
+    #[test]
+    fn diffs_existing_tables() {
+        let database = vec![
+            db("todos", "id", "bigint", false),
+            db("todos", "title", "text", true),
+            db("todos", "due", "integer", true),
+            db("todos", "legacy", "text", true),
+            db("user_profiles", "id", "uuid", false),
+            db("user_profiles", "settings", "jsonb", false),
+        ];
+
+        let statements = diff(&models(), &database, Dialect::Postgres);
+        assert_eq!(statements.len(), 4, "{statements:#?}");
+        assert_eq!(
+            statements[0],
+            "ALTER TABLE todos ALTER COLUMN title SET NOT NULL;"
+        );
+        assert!(statements[1].ends_with("ALTER TABLE todos ADD COLUMN is_done BOOLEAN NOT NULL;"));
+        assert!(statements[1].starts_with("-- Review: existing rows need a value"));
+        assert!(
+            statements[2].contains("todos.due is integer in the database but Option<NaiveDate>")
+        );
+        assert!(statements[2]
+            .ends_with("-- ALTER TABLE todos ALTER COLUMN due TYPE DATE USING due::DATE;"));
+        assert!(statements[3].ends_with("-- ALTER TABLE todos DROP COLUMN legacy;"));
+    }
+
+    #[test]
+    fn storage_fallbacks_are_not_type_changes() {
+        assert!(SqlType::Uuid.accepts("char(36)"));
+        assert!(SqlType::Bool.accepts("tinyint(1)"));
+        assert!(SqlType::Bool.accepts("INTEGER"));
+        assert!(SqlType::TimestampTz.accepts("timestamp with time zone"));
+        assert!(SqlType::Text.accepts("character varying"));
+        assert!(!SqlType::Text.accepts("integer"));
+        assert!(!SqlType::Date.accepts("timestamp without time zone"));
+    }
+}
@@ -100,6 +100,10 @@ pub async fn new_project(mut args: NewArgs) -> Result<()> {
             "api - REST API with CRUD",
             "web - Web app with templates",
             "full - Full-featured app",
+            "sqlx-postgres - REST API on PostgreSQL with SQLx migrations",
+            "auth-jwt - API with JWT login and protected routes",
+            "fullstack-ssr - Server-rendered app with forms and static assets",
+            "grpc - gRPC service and HTTP API on one port",
         ];
         let selection = Select::with_theme(&theme)
             .with_prompt("Select a template")
@@ -112,6 +116,10 @@ pub async fn new_project(mut args: NewArgs) -> Result<()> {
             1 => ProjectTemplate::Api,
             2 => ProjectTemplate::Web,
             3 => ProjectTemplate::Full,
+            4 => ProjectTemplate::SqlxPostgres,
+            5 => ProjectTemplate::AuthJwt,
+            6 => ProjectTemplate::FullstackSsr,
+            7 => ProjectTemplate::Grpc,
             _ => ProjectTemplate::Minimal,
         }
     };
@@ -149,9 +157,15 @@ pub async fn new_project(mut args: NewArgs) -> Result<()> {
             ProjectTemplate::Full => vec![
                 true, true, true, true, false, false, false, false, false, false, false,
             ],
-            ProjectTemplate::Web => vec![
+            ProjectTemplate::Web | ProjectTemplate::FullstackSsr => vec![
                 false, false, false, false, false, false, false, false, false, true, false,
             ],
+            ProjectTemplate::AuthJwt => vec![
+                true, false, false, false, false, false, false, false, false, false, false,
+            ],
+            ProjectTemplate::Grpc => vec![
+                false, false, false, false, false, false, false, false, false, false, true,
+            ],
             _ => vec![false; available.len()],
         };
 
@@ -245,8 +259,17 @@ pub async fn new_project(mut args: NewArgs) -> Result<()> {
     println!();
     println!("Next steps:");
     println!("  {} {}", style("cd").cyan(), name);
+    if template == ProjectTemplate::SqlxPostgres {
+        println!("  {} compose up -d db", style("docker").cyan());
+    }
+    println!("  {} test", style("cargo").cyan());
     println!("  {} run", style("cargo").cyan());
     println!();
+    println!(
+        "Or run everything in containers with {}.",
+        style("docker compose up --build").cyan()
+    );
+    println!();
     println!(
         "Then open {} in your browser.",
         style("http://localhost:8080").cyan()
@@ -0,0 +1,220 @@
+//! OpenAPI command - export the spec of a RustAPI project and check it for breaking changes
+
+use anyhow::{bail, Context, Result};
+use clap::{Args, Subcommand, ValueEnum};
+use console::style;
+use rustapi_openapi::diff::{ChangeKind, SpecDiff};
+use std::path::{Path, PathBuf};
+
+/// OpenAPI spec commands
+#[derive(Subcommand, Debug)]
+pub enum OpenapiArgs {
+    /// Write the OpenAPI spec of the current project to a file
+    Export(OpenapiExportArgs),
+
+    /// Compare the spec against a baseline and fail on breaking changes
+    Diff(OpenapiDiffArgs),
+}
+
+#[derive(Args, Debug)]
+pub struct OpenapiExportArgs {
+    /// Output file (`.yaml`/`.yml` writes YAML, anything else JSON; `-` for stdout)
+    #[arg(short, long, default_value = "openapi.json")]
+    pub output: PathBuf,
+}
+
+/// Output format for the diff
+#[derive(Debug, Clone, Copy, Default, ValueEnum)]
+pub enum DiffFormat {
+    /// One line per change
+    #[default]
+    Text,
+    /// Raw JSON (`{"changes": [...]}`)
+    Json,
+}
+
+#[derive(Args, Debug)]
+pub struct OpenapiDiffArgs {
+    /// Baseline spec (JSON or YAML), e.g. exported from the main branch
+    #[arg(long)]
+    pub against: PathBuf,
+
+    /// Spec to check (default: exported from the current project)
+    #[arg(long)]
+    pub spec: Option<PathBuf>,
+
+    /// Output format
+    #[arg(short, long, value_enum, default_value_t = DiffFormat::Text)]
+    pub format: DiffFormat,
+
+    /// Exit successfully even if there are breaking changes
+    #[arg(long)]
+    pub allow_breaking: bool,
+}
+
+/// Run an OpenAPI subcommand
+pub async fn openapi(args: OpenapiArgs) -> Result<()> {
+    match args {
+        OpenapiArgs::Export(args) => export(args).await,
+        OpenapiArgs::Diff(args) => diff(args).await,
+    }
+}
+
+async fn export(args: OpenapiExportArgs) -> Result<()> {
+    let spec = dump_project_spec().await?;
+
+    if args.output.as_os_str() == "-" {
+        println!("{}", serde_json::to_string_pretty(&spec)?);
+        return Ok(());
+    }
+
+    let content = if is_yaml(&args.output) {
+        serde_yaml::to_string(&spec)?
+    } else {
+        serde_json::to_string_pretty(&spec)?
+    };
+    tokio::fs::write(&args.output, content)
+        .await
+        .with_context(|| format!("Failed to write {}", args.output.display()))?;
+    println!(
+        "{} OpenAPI spec written to {}",
+        style("✓").green(),
+        style(args.output.display()).cyan()
+    );
+    Ok(())
+}
+
+async fn diff(args: OpenapiDiffArgs) -> Result<()> {
+    let old = read_spec(&args.against).await?;
+    let new = match &args.spec {
+        Some(path) => read_spec(path).await?,
+        None => dump_project_spec().await?,
+    };
+
+    let diff = SpecDiff::new(&old, &new);
+    match args.format {
+        DiffFormat::Json => println!("{}", serde_json::to_string_pretty(&diff.to_json())?),
+        DiffFormat::Text => print_diff(&diff),
+    }
+
+    let breaking = diff.breaking().count();
+    if breaking > 0 && !args.allow_breaking {
+        bail!(
+            "{} breaking change(s) against {}",
+            breaking,
+            args.against.display()
+        );
+    }
+    Ok(())
+}
+
+fn print_diff(diff: &SpecDiff) {
+    if diff.is_empty() {
+        println!("{} No API changes", style("✓").green());
+        return;
+    }
+    for change in diff.changes() {
+        let marker = match change.kind {
+            ChangeKind::Breaking => style("✗ breaking    ").red(),
+            ChangeKind::NonBreaking => style("• non-breaking").dim(),
+        };
+        println!(
+            "{} {}: {}",
+            marker,
+            style(&change.operation).bold(),
+            change.message
+        );
+    }
+    println!();
+    println!(
+        "{} changes, {} breaking",
+        diff.changes().len(),
+        diff.breaking().count()
+    );
+}
+
+fn is_yaml(path: &Path) -> bool {
+    matches!(
+        path.extension().and_then(|e| e.to_str()),
+        Some("yaml" | "yml")
+    )
+}
+
+async fn read_spec(path: &Path) -> Result<serde_json::Value> {
+    let content = tokio::fs::read_to_string(path)
+        .await
+        .with_context(|| format!("Failed to read spec file: {}", path.display()))?;
+    parse_spec(&content, is_yaml(path))
+        .with_context(|| format!("Failed to parse {} as an OpenAPI document", path.display()))
+}
+
+fn parse_spec(content: &str, yaml: bool) -> Result<serde_json::Value> {
+    let spec = if yaml {
+        serde_yaml::from_str(content)?
+    } else {
+        serde_json::from_str(content).or_else(|_| serde_yaml::from_str(content))?
+    };
+    Ok(spec)
+}
+
+/// Build and run the project with `RUSTAPI_DUMP_OPENAPI=1` and return its spec.
+pub(crate) async fn dump_project_spec() -> Result<serde_json::Value> {
+    eprintln!(
+        "{} Spawning `cargo run` with RUSTAPI_DUMP_OPENAPI=1 (no server binding)...",
+        style("→").cyan()
+    );
+
+    let output = tokio::process::Command::new("cargo")
+        .args(["run", "--quiet"])
+        .env("RUSTAPI_DUMP_OPENAPI", "1")
+        .stdout(std::process::Stdio::piped())
+        .stderr(std::process::Stdio::inherit())
+        .output()
+        .await
+        .context("Failed to execute `cargo run`. Are you inside a RustAPI project?")?;
+
+    if !output.status.success() {
+        bail!(
+            "Failed to build/run the project to extract OpenAPI.\n\
+             Try running `cargo run` manually first to ensure it compiles."
+        );
+    }
+
+    let stdout =
+        String::from_utf8(output.stdout).context("Captured OpenAPI output was not valid UTF-8")?;
+    extract_spec(&stdout)
+}
+
+/// Find the spec JSON in the program output (the app may print before dumping).
+fn extract_spec(stdout: &str) -> Result<serde_json::Value> {
+    let start = stdout
+        .rfind(r#""openapi": "3."#)
+        .and_then(|idx| stdout[..idx].rfind('{'))
+        .context(
+            "Could not find an OpenAPI document in the program output.\n\
+             Make sure your main uses RustApi::auto() or similar.",
+        )?;
+    let end = stdout.rfind('}').context("Truncated OpenAPI output")?;
+
+    serde_json::from_str(&stdout[start..=end]).context("Failed to parse OpenAPI JSON")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn extracts_spec_after_other_output() {
+        let stdout = "booting {app}\n{\n  \"openapi\": \"3.1.0\",\n  \"paths\": {}\n}\n";
+        let spec = extract_spec(stdout).unwrap();
+        assert_eq!(spec["openapi"], "3.1.0");
+    }
+
+    #[test]
+    fn parses_json_and_yaml_specs() {
+        let json = parse_spec(r#"{"openapi": "3.1.0", "paths": {}}"#, false).unwrap();
+        let yaml = parse_spec("openapi: 3.1.0\npaths: {}\n", true).unwrap();
+        assert_eq!(json, yaml);
+        assert!(parse_spec("openapi: [", true).is_err());
+    }
+}
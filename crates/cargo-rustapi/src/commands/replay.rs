@@ -296,6 +296,9 @@ async fn cmd_show(args: ReplayShowArgs) -> Result<()> {
     if let Some(req_id) = meta["request_id"].as_str() {
         println!("    Request ID: {}", req_id);
     }
+    if let Some(trace_id) = meta["trace_id"].as_str() {
+        println!("    Trace ID: {}", trace_id);
+    }
 
     println!();
     Ok(())
@@ -344,6 +347,7 @@ async fn cmd_run(args: ReplayRunArgs) -> Result<()> {
         style("Replayed:").bold(),
         body["replayed_response"]["status"]
     );
+    print_trace_ids(&body);
 
     if let Some(body_str) = body["replayed_response"]["body"].as_str() {
         println!();
@@ -391,6 +395,7 @@ async fn cmd_diff(args: ReplayDiffArgs) -> Result<()> {
     let has_diff = diff["has_diff"].as_bool().unwrap_or(false);
 
     println!();
+    print_trace_ids(&body);
 
     if !has_diff {
         println!("  {} No differences found!", style("MATCH").green().bold());
@@ -452,6 +457,16 @@ async fn cmd_diff(args: ReplayDiffArgs) -> Result<()> {
     Ok(())
 }
 
+/// Print the recorded and replay trace IDs of a run or diff response.
+fn print_trace_ids(body: &serde_json::Value) {
+    if let Some(trace_id) = body["original_trace_id"].as_str() {
+        println!("  {} {}", style("Recorded trace:").bold(), trace_id);
+    }
+    if let Some(trace_id) = body["replay_trace_id"].as_str() {
+        println!("  {} {}", style("Replay trace:").bold(), trace_id);
+    }
+}
+
 /// Pretty-print a JSON string with indentation.
 fn print_json_indented(json_str: &str, indent: usize) {
     let prefix = " ".repeat(indent);
@@ -0,0 +1,204 @@
+//! Routes command - print the route table of a RustAPI project
+
+use anyhow::{bail, Context, Result};
+use clap::{Args, ValueEnum};
+use console::style;
+
+/// Output format for the route table
+#[derive(Debug, Clone, Copy, Default, ValueEnum)]
+pub enum RoutesFormat {
+    /// Human-readable table
+    #[default]
+    Table,
+    /// Raw JSON (`{"routes": [...], "middleware": [...]}`)
+    Json,
+}
+
+/// Print the route table of the current project
+#[derive(Args, Debug)]
+pub struct RoutesArgs {
+    /// Output format
+    #[arg(short, long, value_enum, default_value_t = RoutesFormat::Table)]
+    pub format: RoutesFormat,
+
+    /// Only show routes with this method (e.g. GET)
+    #[arg(short, long)]
+    pub method: Option<String>,
+
+    /// Only show routes whose path contains this text
+    #[arg(short, long)]
+    pub path: Option<String>,
+}
+
+/// Build and run the project with `RUSTAPI_DUMP_ROUTES=1` and print its routes.
+pub async fn routes(args: RoutesArgs) -> Result<()> {
+    eprintln!(
+        "{} Spawning `cargo run` with RUSTAPI_DUMP_ROUTES=1 (no server binding)...",
+        style("→").cyan()
+    );
+
+    let output = tokio::process::Command::new("cargo")
+        .args(["run", "--quiet"])
+        .env("RUSTAPI_DUMP_ROUTES", "1")
+        .stdout(std::process::Stdio::piped())
+        .stderr(std::process::Stdio::inherit())
+        .output()
+        .await
+        .context("Failed to execute `cargo run`. Are you inside a RustAPI project?")?;
+
+    if !output.status.success() {
+        bail!("Failed to build/run the project to extract the route table");
+    }
+
+    let stdout = String::from_utf8(output.stdout).context("Route output was not valid UTF-8")?;
+    let mut table = extract_table(&stdout)?;
+    filter_routes(&mut table, args.method.as_deref(), args.path.as_deref());
+
+    match args.format {
+        RoutesFormat::Json => println!("{}", serde_json::to_string_pretty(&table)?),
+        RoutesFormat::Table => print_table(&table),
+    }
+
+    Ok(())
+}
+
+/// Find the route table JSON in the program output (the app may print before dumping).
+fn extract_table(stdout: &str) -> Result<serde_json::Value> {
+    let start = stdout
+        .rfind(r#""routes""#)
+        .and_then(|idx| stdout[..idx].rfind('{'))
+        .context("No route table found in program output")?;
+    let end = stdout.rfind('}').context("Truncated route table output")?;
+
+    serde_json::from_str(&stdout[start..=end]).context("Failed to parse route table JSON")
+}
+
+fn filter_routes(table: &mut serde_json::Value, method: Option<&str>, path: Option<&str>) {
+    if let Some(routes) = table["routes"].as_array_mut() {
+        routes.retain(|route| {
+            method.is_none_or(|m| {
+                route["method"]
+                    .as_str()
+                    .is_some_and(|r| r.eq_ignore_ascii_case(m))
+            }) && path.is_none_or(|p| route["path"].as_str().is_some_and(|r| r.contains(p)))
+        });
+    }
+}
+
+fn print_table(table: &serde_json::Value) {
+    let routes = table["routes"].as_array().cloned().unwrap_or_default();
+    let path_width = routes
+        .iter()
+        .filter_map(|r| r["path"].as_str())
+        .map(str::len)
+        .max()
+        .unwrap_or(4)
+        .max(4);
+    let handler_width = routes
+        .iter()
+        .map(|r| r["handler"].as_str().unwrap_or("-").len())
+        .max()
+        .unwrap_or(7)
+        .max(7);
+
+    println!(
+        "{}",
+        style(format!(
+            "{:<7}  {:<path_width$}  {:<handler_width$}  TAGS",
+            "METHOD", "PATH", "HANDLER"
+        ))
+        .bold()
+    );
+    for route in &routes {
+        let tags: Vec<&str> = route["tags"]
+            .as_array()
+            .map(|tags| tags.iter().filter_map(|t| t.as_str()).collect())
+            .unwrap_or_default();
+        println!(
+            "{:<7}  {:<path_width$}  {:<handler_width$}  {}",
+            route["method"].as_str().unwrap_or_default(),
+            route["path"].as_str().unwrap_or_default(),
+            route["handler"].as_str().unwrap_or("-"),
+            tags.join(", ")
+        );
+    }
+    println!();
+    println!("{} routes", routes.len());
+
+    let middleware = table["middleware"].as_array().cloned().unwrap_or_default();
+    if !middleware.is_empty() {
+        println!();
+        println!(
+            "{}",
+            style("Middleware (outermost first, applies to every route)").bold()
+        );
+        for (i, layer) in middleware.iter().enumerate() {
+            println!(
+                "  {}. {}",
+                i + 1,
+                short_type_name(layer.as_str().unwrap_or_default())
+            );
+        }
+    }
+}
+
+/// Strip module paths from a type name, keeping generic arguments
+/// (`rustapi_core::middleware::TracingLayer` → `TracingLayer`).
+fn short_type_name(name: &str) -> String {
+    let mut short = String::with_capacity(name.len());
+    let mut segment_start = 0;
+    let mut chars = name.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c == ':' && chars.peek() == Some(&':') {
+            chars.next();
+            short.truncate(segment_start);
+            continue;
+        }
+        short.push(c);
+        if !(c.is_alphanumeric() || c == '_') {
+            segment_start = short.len();
+        }
+    }
+    short
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn extracts_table_after_other_output() {
+        let stdout = "starting up {ignored}\n{\n  \"routes\": [{\"method\": \"GET\", \"path\": \"/a\", \"handler\": \"app::main::{{closure}}\"}],\n  \"middleware\": []\n}\n";
+        let table = extract_table(stdout).unwrap();
+        assert_eq!(table["routes"][0]["handler"], "app::main::{{closure}}");
+    }
+
+    #[test]
+    fn filters_by_method_and_path() {
+        let mut table = serde_json::json!({
+            "routes": [
+                {"method": "GET", "path": "/users"},
+                {"method": "POST", "path": "/users"},
+                {"method": "GET", "path": "/health"},
+            ],
+            "middleware": [],
+        });
+        filter_routes(&mut table, Some("get"), Some("user"));
+        assert_eq!(
+            table["routes"],
+            serde_json::json!([{"method": "GET", "path": "/users"}])
+        );
+    }
+
+    #[test]
+    fn shortens_type_names() {
+        assert_eq!(
+            short_type_name("rustapi_core::middleware::tracing::TracingLayer"),
+            "TracingLayer"
+        );
+        assert_eq!(
+            short_type_name("rustapi_extras::cors::CorsLayer<alloc::string::String>"),
+            "CorsLayer<String>"
+        );
+    }
+}
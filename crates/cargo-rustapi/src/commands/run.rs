@@ -72,6 +72,8 @@ pub async fn run_dev(args: RunArgs) -> Result<()> {
             features: args.features,
             release: args.release,
             package: args.package,
+            proxy: None,
+            proxy_timeout: 60,
         };
         watch::watch(watch_args).await
     } else {
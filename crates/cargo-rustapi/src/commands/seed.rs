@@ -0,0 +1,380 @@
+//! Seed data command
+//!
+//! Populates a database with reproducible data for local and development
+//! environments. Seeds are `*.sql` files in the `seeds/` directory (run in
+//! file name order with the database's CLI client) followed by an optional
+//! Rust seeder: a `seed` binary, or a `[[bin]]` whose path is `seeds.rs`.
+
+use super::manifests;
+use anyhow::{bail, Context, Result};
+use clap::Args;
+use console::{style, Emoji};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use tokio::process::Command;
+
+static CHECK: Emoji<'_, '_> = Emoji("✅ ", "+ ");
+static SEED: Emoji<'_, '_> = Emoji("🌱 ", "* ");
+static ARROW: Emoji<'_, '_> = Emoji("➡️  ", "-> ");
+
+/// Name of the Rust seeder binary looked up by default
+const SEED_BIN: &str = "seed";
+
+#[derive(Args, Debug)]
+pub struct SeedArgs {
+    /// Database URL (overrides DATABASE_URL env var and .env)
+    #[arg(long)]
+    pub database_url: Option<String>,
+
+    /// Directory containing `*.sql` seed files
+    #[arg(long, default_value = "seeds")]
+    pub source: PathBuf,
+
+    /// Environment being seeded (default: RUSTAPI_ENV, or development)
+    #[arg(long, value_name = "NAME")]
+    pub env: Option<String>,
+
+    /// Allow seeding a production environment
+    #[arg(long)]
+    pub allow_production: bool,
+
+    /// Skip the confirmation prompt when seeding production
+    #[arg(short, long)]
+    pub yes: bool,
+
+    /// Show what would run without touching the database
+    #[arg(long)]
+    pub dry_run: bool,
+}
+
+/// What `seed` will run, in order
+#[derive(Debug, Default, PartialEq, Eq)]
+struct SeedPlan {
+    sql_files: Vec<PathBuf>,
+    /// Cargo binary target of the Rust seeder
+    binary: Option<String>,
+}
+
+/// Run the seeds of the current project
+pub async fn seed(args: SeedArgs) -> Result<()> {
+    let project = Path::new(".");
+    let dotenv = manifests::read_env_file(&project.join(".env"));
+    let lookup = |name: &str| {
+        std::env::var(name)
+            .ok()
+            .or_else(|| dotenv.get(name).cloned())
+    };
+
+    let environment = args
+        .env
+        .clone()
+        .or_else(|| lookup("RUSTAPI_ENV"))
+        .unwrap_or_else(|| "development".to_string());
+
+    println!(
+        "{} {} Seeding the {} environment",
+        SEED,
+        style("seed").cyan().bold(),
+        style(&environment).bold()
+    );
+    println!();
+
+    if is_production(&environment) {
+        if !args.allow_production {
+            bail!(
+                "Refusing to seed the {environment} environment. \
+                 Pass --allow-production if this is really what you want."
+            );
+        }
+        if !args.yes && !args.dry_run {
+            use dialoguer::{theme::ColorfulTheme, Confirm};
+
+            if !Confirm::with_theme(&ColorfulTheme::default())
+                .with_prompt(format!("Seed the {environment} database?"))
+                .default(false)
+                .interact()?
+            {
+                println!("{}", style("Aborted").yellow());
+                return Ok(());
+            }
+        }
+    }
+
+    let plan = plan_seeds(project, &args.source)?;
+    if plan.sql_files.is_empty() && plan.binary.is_none() {
+        println!(
+            "{}",
+            style(format!(
+                "No seeds found. Add *.sql files to {}/ or a `{SEED_BIN}` binary (src/bin/{SEED_BIN}.rs).",
+                args.source.display()
+            ))
+            .yellow()
+        );
+        return Ok(());
+    }
+
+    let database_url = args
+        .database_url
+        .clone()
+        .or_else(|| lookup("DATABASE_URL"))
+        .context("No database configured. Set DATABASE_URL or pass --database-url")?;
+
+    for file in &plan.sql_files {
+        println!(
+            "{} {}",
+            ARROW,
+            file.strip_prefix(project).unwrap_or(file).display()
+        );
+        if !args.dry_run {
+            run_sql_file(&database_url, file).await?;
+        }
+    }
+
+    if let Some(binary) = &plan.binary {
+        println!("{} cargo run --bin {}", ARROW, binary);
+        if !args.dry_run {
+            let status = Command::new("cargo")
+                .args(["run", "--quiet", "--bin", binary])
+                .env("DATABASE_URL", &database_url)
+                .env("RUSTAPI_ENV", &environment)
+                .status()
+                .await
+                .context("Failed to run cargo")?;
+            if !status.success() {
+                bail!("Seeder `{binary}` failed");
+            }
+        }
+    }
+
+    println!();
+    if args.dry_run {
+        println!("{}", style("Dry run: nothing was executed").yellow());
+    } else {
+        println!("{} Database seeded!", CHECK);
+    }
+
+    Ok(())
+}
+
+/// Same rule as `RUSTAPI_ENV` detection in the framework
+fn is_production(environment: &str) -> bool {
+    matches!(
+        environment.to_ascii_lowercase().as_str(),
+        "production" | "prod"
+    )
+}
+
+fn plan_seeds(project: &Path, source: &Path) -> Result<SeedPlan> {
+    let source = project.join(source);
+    let mut sql_files = Vec::new();
+    if source.is_dir() {
+        for entry in
+            fs::read_dir(&source).with_context(|| format!("Failed to read {}", source.display()))?
+        {
+            let path = entry?.path();
+            if path.is_file() && path.extension().is_some_and(|ext| ext == "sql") {
+                sql_files.push(path);
+            }
+        }
+    }
+    sql_files.sort();
+
+    Ok(SeedPlan {
+        sql_files,
+        binary: seed_binary(project)?,
+    })
+}
+
+/// The Cargo binary target acting as the Rust seeder, if any
+fn seed_binary(project: &Path) -> Result<Option<String>> {
+    let declared: Vec<(String, String)> = fs::read_to_string(project.join("Cargo.toml"))
+        .ok()
+        .and_then(|content| toml::from_str::<toml::Table>(&content).ok())
+        .and_then(|manifest| manifest.get("bin")?.as_array().cloned())
+        .unwrap_or_default()
+        .iter()
+        .filter_map(|bin| {
+            let name = bin.get("name")?.as_str()?.to_string();
+            let path = bin
+                .get("path")
+                .and_then(|path| path.as_str())
+                .unwrap_or_default()
+                .to_string();
+            Some((name, path))
+        })
+        .collect();
+
+    if let Some((name, _)) = declared
+        .iter()
+        .find(|(name, path)| name == SEED_BIN || Path::new(path) == Path::new("seeds.rs"))
+    {
+        return Ok(Some(name.clone()));
+    }
+    if project
+        .join("src/bin")
+        .join(format!("{SEED_BIN}.rs"))
+        .is_file()
+    {
+        return Ok(Some(SEED_BIN.to_string()));
+    }
+    if project.join("seeds.rs").is_file() {
+        bail!(
+            "Found seeds.rs but no binary target for it. Add this to Cargo.toml:\n\n\
+             [[bin]]\nname = \"{SEED_BIN}\"\npath = \"seeds.rs\""
+        );
+    }
+
+    Ok(None)
+}
+
+/// Run one SQL file with the client matching the database URL
+async fn run_sql_file(database_url: &str, file: &Path) -> Result<()> {
+    let (program, args) = sql_client(database_url)?;
+    let input =
+        fs::File::open(file).with_context(|| format!("Failed to open {}", file.display()))?;
+
+    let status = Command::new(program)
+        .args(&args)
+        .stdin(Stdio::from(input))
+        .status()
+        .await
+        .with_context(|| format!("Failed to run `{program}`. Is it installed and on PATH?"))?;
+    if !status.success() {
+        bail!("Seed {} failed", file.display());
+    }
+
+    Ok(())
+}
+
+/// Client program and arguments that execute SQL from stdin and stop at the
+/// first error
+pub(super) fn sql_client(database_url: &str) -> Result<(&'static str, Vec<String>)> {
+    let (scheme, rest) = database_url
+        .split_once(':')
+        .context("DATABASE_URL is not a URL")?;
+
+    match scheme {
+        "postgres" | "postgresql" => Ok((
+            "psql",
+            vec![
+                database_url.to_string(),
+                "--quiet".to_string(),
+                "--set".to_string(),
+                "ON_ERROR_STOP=1".to_string(),
+            ],
+        )),
+        "mysql" | "mariadb" => {
+            let rest = rest.trim_start_matches("//");
+            let (authority, database) = rest.split_once('/').unwrap_or((rest, ""));
+            let database = database.split('?').next().unwrap_or_default();
+            let (credentials, host) = authority.rsplit_once('@').unwrap_or(("", authority));
+            let (user, password) = credentials.split_once(':').unwrap_or((credentials, ""));
+            let (host, port) = host.rsplit_once(':').unwrap_or((host, "3306"));
+
+            let mut args = vec![
+                format!("--host={host}"),
+                format!("--port={port}"),
+                "--protocol=tcp".to_string(),
+            ];
+            if !user.is_empty() {
+                args.push(format!("--user={user}"));
+            }
+            if !password.is_empty() {
+                args.push(format!("--password={password}"));
+            }
+            if !database.is_empty() {
+                args.push(database.to_string());
+            }
+            Ok(("mysql", args))
+        }
+        "sqlite" => {
+            let path = rest.trim_start_matches("//");
+            let path = path.split('?').next().unwrap_or_default();
+            Ok(("sqlite3", vec!["-bail".to_string(), path.to_string()]))
+        }
+        _ => bail!("Unsupported database `{scheme}` (expected postgres, mysql or sqlite)"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn production_is_detected_case_insensitively() {
+        assert!(is_production("production"));
+        assert!(is_production("PROD"));
+        assert!(!is_production("development"));
+        assert!(!is_production("staging"));
+    }
+
+    #[test]
+    fn plan_orders_sql_files_and_finds_seeder() {
+        let dir = tempdir().unwrap();
+        fs::create_dir_all(dir.path().join("seeds")).unwrap();
+        fs::write(dir.path().join("seeds/02_posts.sql"), "").unwrap();
+        fs::write(dir.path().join("seeds/01_users.sql"), "").unwrap();
+        fs::write(dir.path().join("seeds/README.md"), "").unwrap();
+        fs::write(
+            dir.path().join("Cargo.toml"),
+            "[package]\nname = \"demo\"\n\n[[bin]]\nname = \"populate\"\npath = \"seeds.rs\"\n",
+        )
+        .unwrap();
+
+        let plan = plan_seeds(dir.path(), Path::new("seeds")).unwrap();
+        let names: Vec<_> = plan
+            .sql_files
+            .iter()
+            .map(|p| p.file_name().unwrap().to_str().unwrap())
+            .collect();
+        assert_eq!(names, ["01_users.sql", "02_posts.sql"]);
+        assert_eq!(plan.binary.as_deref(), Some("populate"));
+    }
+
+    #[test]
+    fn undeclared_seeds_rs_is_an_error() {
+        let dir = tempdir().unwrap();
+        fs::write(
+            dir.path().join("Cargo.toml"),
+            "[package]\nname = \"demo\"\n",
+        )
+        .unwrap();
+        fs::write(dir.path().join("seeds.rs"), "fn main() {}\n").unwrap();
+
+        let err = seed_binary(dir.path()).unwrap_err().to_string();
+        assert!(err.contains("[[bin]]"));
+
+        fs::create_dir_all(dir.path().join("src/bin")).unwrap();
+        fs::write(dir.path().join("src/bin/seed.rs"), "fn main() {}\n").unwrap();
+        assert_eq!(seed_binary(dir.path()).unwrap().as_deref(), Some("seed"));
+    }
+
+    #[test]
+    fn sql_client_matches_scheme() {
+        let (program, args) = sql_client("postgres://app:pw@localhost/app").unwrap();
+        assert_eq!(program, "psql");
+        assert!(args.contains(&"ON_ERROR_STOP=1".to_string()));
+
+        let (program, args) = sql_client("mysql://root:pw@db:3307/app?ssl-mode=off").unwrap();
+        assert_eq!(program, "mysql");
+        assert_eq!(
+            args,
+            [
+                "--host=db",
+                "--port=3307",
+                "--protocol=tcp",
+                "--user=root",
+                "--password=pw",
+                "app"
+            ]
+        );
+
+        let (program, args) = sql_client("sqlite://data/app.db?mode=rwc").unwrap();
+        assert_eq!(program, "sqlite3");
+        assert_eq!(args, ["-bail", "data/app.db"]);
+
+        assert!(sql_client("mongodb://localhost").is_err());
+    }
+}
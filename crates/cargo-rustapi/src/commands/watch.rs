@@ -3,8 +3,11 @@
 //! Uses a std-only polling watcher by default and the `notify` crate when the
 //! `native-watch` feature is enabled.
 //! Detects file changes, rebuilds the project, and restarts the server
-//! automatically — no external tools (cargo-watch) required.
+//! automatically — no external tools (cargo-watch) required. With `--proxy`,
+//! requests that arrive during a rebuild are held instead of refused.
 
+use super::dev_proxy::{AppStatus, DevProxy};
+use super::manifests::{self, ProjectProfile};
 use anyhow::{Context, Result};
 use clap::Args;
 use console::{style, Emoji};
@@ -12,6 +15,7 @@ use console::{style, Emoji};
 use notify_debouncer_mini::{new_debouncer, DebouncedEventKind};
 use std::collections::HashMap;
 use std::fs;
+use std::net::SocketAddr;
 use std::path::{Path, PathBuf};
 #[cfg(feature = "native-watch")]
 use std::sync::mpsc;
@@ -77,6 +81,16 @@ pub struct WatchArgs {
     /// Package to run (for workspace projects)
     #[arg(short = 'p', long)]
     pub package: Option<String>,
+
+    /// Serve on this port through a proxy that holds requests while the app
+    /// rebuilds (default: the project's PORT). The app is started with PORT
+    /// set to a free internal port, so it must read its port from PORT.
+    #[arg(long, value_name = "PORT", num_args = 0..=1, default_missing_value = "0")]
+    pub proxy: Option<u16>,
+
+    /// How long the proxy holds a request while the app is unavailable (in seconds)
+    #[arg(long, default_value = "60")]
+    pub proxy_timeout: u64,
 }
 
 /// Check if a path has a watched extension
@@ -287,8 +301,8 @@ async fn build_project(args: &WatchArgs) -> (bool, Duration, String) {
     }
 }
 
-/// Start the server process
-async fn start_server(args: &WatchArgs) -> Result<Child> {
+/// Start the server process, on `app_port` when running behind the proxy
+async fn start_server(args: &WatchArgs, app_port: Option<u16>) -> Result<Child> {
     let mut cmd = Command::new("cargo");
     cmd.arg("run");
 
@@ -307,6 +321,10 @@ async fn start_server(args: &WatchArgs) -> Result<Child> {
     // Mark process as being watched so .hot_reload() can detect it
     cmd.env("RUSTAPI_HOT_RELOAD", "1");
     cmd.env("RUSTAPI_ENV", "development");
+    if let Some(port) = app_port {
+        cmd.env("HOST", "127.0.0.1");
+        cmd.env("PORT", port.to_string());
+    }
 
     cmd.stdout(std::process::Stdio::inherit())
         .stderr(std::process::Stdio::inherit())
@@ -317,6 +335,51 @@ async fn start_server(args: &WatchArgs) -> Result<Child> {
     Ok(child)
 }
 
+/// Start the request-holding proxy and pick the internal port for the app
+async fn start_proxy(args: &WatchArgs, port: u16) -> Result<(DevProxy, u16)> {
+    let public_port = match port {
+        0 => std::env::var("PORT")
+            .ok()
+            .and_then(|port| port.parse().ok())
+            .or_else(|| ProjectProfile::detect(Path::new(".")).ok().map(|p| p.port))
+            .unwrap_or(manifests::DEFAULT_PORT),
+        port => port,
+    };
+    let app_port = std::net::TcpListener::bind("127.0.0.1:0")
+        .and_then(|listener| listener.local_addr())
+        .context("Failed to find a free port for the app")?
+        .port();
+
+    let proxy = DevProxy::bind(
+        SocketAddr::from(([127, 0, 0, 1], public_port)),
+        SocketAddr::from(([127, 0, 0, 1], app_port)),
+        Duration::from_secs(args.proxy_timeout),
+    )
+    .await?;
+
+    Ok((proxy, app_port))
+}
+
+fn set_proxy_status(proxy: &Option<(DevProxy, u16)>, status: AppStatus) {
+    if let Some((proxy, _)) = proxy {
+        proxy.set_status(status);
+    }
+}
+
+/// Compiler errors from a failed build, as shown by the proxy
+fn build_errors(output: &str) -> String {
+    let errors: Vec<&str> = output
+        .lines()
+        .filter(|line| line.contains("error"))
+        .take(20)
+        .collect();
+    if errors.is_empty() {
+        "Build failed.".to_string()
+    } else {
+        format!("Build failed:\n\n{}", errors.join("\n"))
+    }
+}
+
 /// Gracefully stop the server process
 async fn stop_server(mut child: Child) {
     let _ = child.kill().await;
@@ -402,6 +465,25 @@ pub async fn watch(args: WatchArgs) -> Result<()> {
         }
     };
 
+    // ─── Request-holding proxy ──────────────────────────────────────────
+    let proxy = match args.proxy {
+        Some(port) => Some(start_proxy(&args, port).await?),
+        None => None,
+    };
+    let app_port = proxy.as_ref().map(|(_, app_port)| *app_port);
+    if let Some((proxy, app_port)) = &proxy {
+        if !args.quiet {
+            println!(
+                "{} {} http://{} -> 127.0.0.1:{}",
+                GEAR,
+                style("Proxy:").bold(),
+                proxy.local_addr(),
+                app_port
+            );
+            println!();
+        }
+    }
+
     // ─── Initial build & start ──────────────────────────────────────────
     if !args.quiet {
         println!("{} {}", ROCKET, style("Initial build...").green().bold());
@@ -425,6 +507,7 @@ pub async fn watch(args: WatchArgs) -> Result<()> {
         if args.no_restart_on_fail {
             anyhow::bail!("Initial build failed");
         }
+        set_proxy_status(&proxy, AppStatus::Failed(build_errors(&output)));
         println!("\n{}", style("Watching for changes to retry...").dim());
     }
 
@@ -439,7 +522,9 @@ pub async fn watch(args: WatchArgs) -> Result<()> {
             println!("{} {}", ROCKET, style("Starting server...").green().bold());
             println!();
         }
-        Some(start_server(&args).await?)
+        let child = start_server(&args, app_port).await?;
+        set_proxy_status(&proxy, AppStatus::Running);
+        Some(child)
     } else {
         None
     };
@@ -468,6 +553,7 @@ pub async fn watch(args: WatchArgs) -> Result<()> {
                 }
 
                 // Stop current server
+                set_proxy_status(&proxy, AppStatus::Building);
                 if let Some(child) = server.take() {
                     stop_server(child).await;
                 }
@@ -490,7 +576,8 @@ pub async fn watch(args: WatchArgs) -> Result<()> {
                         );
                         println!();
                     }
-                    server = Some(start_server(&args).await?);
+                    server = Some(start_server(&args, app_port).await?);
+                    set_proxy_status(&proxy, AppStatus::Running);
                 } else {
                     println!(
                         "{} {} ({:.1}s)",
@@ -505,6 +592,7 @@ pub async fn watch(args: WatchArgs) -> Result<()> {
                             }
                         }
                     }
+                    set_proxy_status(&proxy, AppStatus::Failed(build_errors(&output)));
                     if !args.quiet {
                         println!(
                             "\n{}",
@@ -522,6 +610,10 @@ pub async fn watch(args: WatchArgs) -> Result<()> {
                 }
             } => {
                 server = None;
+                set_proxy_status(
+                    &proxy,
+                    AppStatus::Failed("The server process exited. Save a file to restart it.".to_string()),
+                );
                 if !args.quiet {
                     println!(
                         "\n{} {}",
@@ -625,6 +717,8 @@ mod tests {
             features: None,
             release: false,
             package: None,
+            proxy: None,
+            proxy_timeout: 60,
         };
 
         assert_eq!(args.command, "run");
@@ -632,6 +726,16 @@ mod tests {
         assert!(!args.clear);
     }
 
+    #[test]
+    fn test_build_errors_keeps_error_lines() {
+        let output = "warning: unused variable\nerror[E0308]: mismatched types\n --> src/main.rs:3:5\nerror: could not compile `demo`\n";
+        assert_eq!(
+            build_errors(output),
+            "Build failed:\n\nerror[E0308]: mismatched types\nerror: could not compile `demo`"
+        );
+        assert_eq!(build_errors(""), "Build failed.");
+    }
+
     #[test]
     fn test_extension_parsing() {
         let extensions = "rs,toml,html,css";
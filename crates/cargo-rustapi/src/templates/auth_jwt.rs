@@ -0,0 +1,248 @@
+//! JWT authentication project template
+
+use super::common;
+use anyhow::Result;
+use tokio::fs;
+
+pub async fn generate(name: &str, features: &[String]) -> Result<()> {
+    let crate_name = common::crate_ident(name);
+    let all_features = common::with_features(features, &["extras-jwt"]);
+
+    // Cargo.toml
+    let cargo_toml = format!(
+        r#"[package]
+name = "{name}"
+version = "0.1.0"
+edition = "2021"
+
+[dependencies]
+rustapi-rs = {{ version = "0.1"{features} }}
+tokio = {{ version = "1", features = ["full"] }}
+serde = {{ version = "1", features = ["derive"] }}
+dotenvy = "0.15"
+tracing = "0.1"
+tracing-subscriber = {{ version = "0.3", features = ["env-filter"] }}
+
+[dev-dependencies]
+rustapi-testing = "0.1"
+"#,
+        name = name,
+        features = common::features_to_cargo(&all_features),
+    );
+    fs::write(format!("{name}/Cargo.toml"), cargo_toml).await?;
+
+    // Create directories
+    fs::create_dir_all(format!("{name}/src")).await?;
+    fs::create_dir_all(format!("{name}/tests")).await?;
+
+    // lib.rs
+    let lib_rs = r#"use rustapi_rs::extras::jwt::{create_token, AuthUser, JwtLayer};
+use rustapi_rs::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Lifetime of issued tokens
+const TOKEN_TTL_SECS: u64 = 3600;
+
+/// Claims stored in the token
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Claims {
+    pub sub: String,
+    pub exp: u64,
+}
+
+/// Signing configuration shared with the login handler
+#[derive(Clone)]
+pub struct AuthConfig {
+    pub secret: String,
+}
+
+/// Login credentials
+#[derive(Debug, Serialize, Deserialize, Schema)]
+pub struct LoginRequest {
+    pub username: String,
+    pub password: String,
+}
+
+/// Issued access token
+#[derive(Debug, Serialize, Deserialize, Schema)]
+pub struct TokenResponse {
+    pub access_token: String,
+    pub token_type: String,
+    pub expires_in: u64,
+}
+
+/// The authenticated user
+#[derive(Debug, Serialize, Deserialize, Schema)]
+pub struct Profile {
+    pub username: String,
+}
+
+/// Health check response
+#[derive(Debug, Serialize, Schema)]
+pub struct Health {
+    pub status: String,
+}
+
+async fn health() -> Json<Health> {
+    Json(Health {
+        status: "ok".to_string(),
+    })
+}
+
+async fn login(
+    State(config): State<AuthConfig>,
+    Json(body): Json<LoginRequest>,
+) -> Result<Json<TokenResponse>> {
+    // Replace with a lookup in your user store (and hashed passwords)
+    if body.username != "demo" || body.password != "demo" {
+        return Err(ApiError::unauthorized("Invalid username or password"));
+    }
+
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default();
+    let claims = Claims {
+        sub: body.username,
+        exp: now + TOKEN_TTL_SECS,
+    };
+    let access_token = create_token(&claims, &config.secret)
+        .map_err(|e| ApiError::internal(e.to_string()))?;
+
+    Ok(Json(TokenResponse {
+        access_token,
+        token_type: "Bearer".to_string(),
+        expires_in: TOKEN_TTL_SECS,
+    }))
+}
+
+async fn me(AuthUser(claims): AuthUser<Claims>) -> Json<Profile> {
+    Json(Profile {
+        username: claims.sub,
+    })
+}
+
+/// Build the application (shared by `main` and the tests)
+///
+/// Every route except the public ones requires a valid `Authorization: Bearer` token.
+pub fn app(secret: &str) -> RustApi {
+    RustApi::new()
+        .state(AuthConfig {
+            secret: secret.to_string(),
+        })
+        .layer(JwtLayer::<Claims>::new(secret).skip_paths(vec!["/health", "/auth/login", "/docs"]))
+        .route("/health", get(health))
+        .route("/auth/login", post(login))
+        .route("/me", get(me))
+        .docs("/docs")
+}
+"#;
+    fs::write(format!("{name}/src/lib.rs"), lib_rs).await?;
+
+    // main.rs
+    let main_rs = format!(
+        r#"#[rustapi_rs::main]
+async fn main() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {{
+    dotenvy::dotenv().ok();
+    tracing_subscriber::fmt()
+        .with_env_filter(
+            tracing_subscriber::EnvFilter::from_default_env()
+                .add_directive("info".parse().unwrap()),
+        )
+        .init();
+
+    let secret = std::env::var("JWT_SECRET").map_err(|_| "JWT_SECRET must be set")?;
+
+    let host = std::env::var("HOST").unwrap_or_else(|_| "127.0.0.1".to_string());
+    let port = std::env::var("PORT").unwrap_or_else(|_| "8080".to_string());
+    let addr = format!("{{}}:{{}}", host, port);
+
+    tracing::info!("🚀 Server running at http://{{}}", addr);
+    tracing::info!("📚 API docs at http://{{}}/docs", addr);
+
+    {crate_name}::app(&secret).run(&addr).await
+}}
+"#
+    );
+    fs::write(format!("{name}/src/main.rs"), main_rs).await?;
+
+    // tests/auth.rs
+    let tests_rs = format!(
+        r#"use rustapi_rs::prelude::StatusCode;
+use rustapi_testing::{{TestClient, TestRequest}};
+use {crate_name}::{{LoginRequest, Profile, TokenResponse}};
+
+const SECRET: &str = "test-secret";
+
+fn client() -> TestClient {{
+    TestClient::new({crate_name}::app(SECRET))
+}}
+
+async fn login(client: &TestClient) -> String {{
+    let response = client
+        .post_json(
+            "/auth/login",
+            &LoginRequest {{
+                username: "demo".to_string(),
+                password: "demo".to_string(),
+            }},
+        )
+        .await;
+    response.assert_status(StatusCode::OK);
+    let token: TokenResponse = response.json().unwrap();
+    token.access_token
+}}
+
+#[tokio::test]
+async fn login_rejects_wrong_password() {{
+    client()
+        .post_json(
+            "/auth/login",
+            &LoginRequest {{
+                username: "demo".to_string(),
+                password: "wrong".to_string(),
+            }},
+        )
+        .await
+        .assert_status(StatusCode::UNAUTHORIZED);
+}}
+
+#[tokio::test]
+async fn protected_route_requires_token() {{
+    client()
+        .get("/me")
+        .await
+        .assert_status(StatusCode::UNAUTHORIZED);
+}}
+
+#[tokio::test]
+async fn protected_route_accepts_issued_token() {{
+    let client = client();
+    let token = login(&client).await;
+
+    let response = client
+        .request(TestRequest::get("/me").header("Authorization", &format!("Bearer {{}}", token)))
+        .await;
+    response.assert_status(StatusCode::OK);
+    let profile: Profile = response.json().unwrap();
+    assert_eq!(profile.username, "demo");
+}}
+"#
+    );
+    fs::write(format!("{name}/tests/auth.rs"), tests_rs).await?;
+
+    let env = r#"HOST=127.0.0.1
+PORT=8080
+RUST_LOG=info
+
+# Secret used to sign tokens; use a long random value in production
+JWT_SECRET=change-me-to-a-long-random-secret
+"#;
+    common::generate_env_files(name, env).await?;
+    common::generate_compose(name, "", "", "").await?;
+    common::generate_dockerfile(name, name, &[]).await?;
+    common::generate_gitignore(name).await?;
+
+    Ok(())
+}
@@ -0,0 +1,339 @@
+//! Server-side rendered fullstack project template
+
+use super::common;
+use anyhow::Result;
+use tokio::fs;
+
+pub async fn generate(name: &str, features: &[String]) -> Result<()> {
+    let crate_name = common::crate_ident(name);
+    let all_features = common::with_features(features, &["protocol-view"]);
+
+    // Cargo.toml
+    let cargo_toml = format!(
+        r#"[package]
+name = "{name}"
+version = "0.1.0"
+edition = "2021"
+
+[dependencies]
+rustapi-rs = {{ version = "0.1"{features} }}
+tokio = {{ version = "1", features = ["full"] }}
+serde = {{ version = "1", features = ["derive"] }}
+dotenvy = "0.15"
+tracing = "0.1"
+tracing-subscriber = {{ version = "0.3", features = ["env-filter"] }}
+
+[dev-dependencies]
+rustapi-testing = "0.1"
+"#,
+        name = name,
+        features = common::features_to_cargo(&all_features),
+    );
+    fs::write(format!("{name}/Cargo.toml"), cargo_toml).await?;
+
+    // Create directories
+    fs::create_dir_all(format!("{name}/src")).await?;
+    fs::create_dir_all(format!("{name}/templates")).await?;
+    fs::create_dir_all(format!("{name}/static")).await?;
+    fs::create_dir_all(format!("{name}/tests")).await?;
+
+    // lib.rs
+    let lib_rs = r#"use rustapi_rs::prelude::*;
+use rustapi_rs::protocol::view::{Templates, View};
+use rustapi_rs::Validate;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use tokio::sync::RwLock;
+
+/// A guestbook entry
+#[derive(Debug, Clone, Serialize)]
+pub struct Message {
+    pub author: String,
+    pub text: String,
+}
+
+/// Messages shown on the home page
+pub type Guestbook = Arc<RwLock<Vec<Message>>>;
+
+/// Form posted from the home page
+#[derive(Debug, Deserialize, Validate, Schema)]
+pub struct MessageForm {
+    #[validate(sanitize(trim))]
+    #[validate(length(min = 1, max = 40))]
+    pub author: String,
+    #[validate(sanitize(trim))]
+    #[validate(length(min = 1, max = 500))]
+    pub text: String,
+}
+
+#[derive(Serialize)]
+struct HomePage {
+    title: String,
+    messages: Vec<Message>,
+}
+
+#[derive(Serialize)]
+struct AboutPage {
+    title: String,
+    version: String,
+}
+
+async fn home(
+    State(templates): State<Templates>,
+    State(guestbook): State<Guestbook>,
+) -> View<HomePage> {
+    let messages = guestbook.read().await.iter().rev().cloned().collect();
+    View::render(
+        &templates,
+        "index.html",
+        HomePage {
+            title: "Guestbook".to_string(),
+            messages,
+        },
+    )
+    .await
+}
+
+async fn about(State(templates): State<Templates>) -> View<AboutPage> {
+    View::render(
+        &templates,
+        "about.html",
+        AboutPage {
+            title: "About".to_string(),
+            version: env!("CARGO_PKG_VERSION").to_string(),
+        },
+    )
+    .await
+}
+
+async fn post_message(
+    State(guestbook): State<Guestbook>,
+    ValidatedForm(form): ValidatedForm<MessageForm>,
+) -> Redirect {
+    guestbook.write().await.push(Message {
+        author: form.author,
+        text: form.text,
+    });
+    // Post/Redirect/Get: reloading the page does not resubmit the form
+    Redirect::to("/")
+}
+
+/// Build the application (shared by `main` and the tests)
+///
+/// Templates are loaded from `templates/` and assets served from `static/`,
+/// relative to the working directory.
+pub fn app() -> Result<RustApi, Box<dyn std::error::Error + Send + Sync>> {
+    let templates = Templates::new("templates/**/*.html")?;
+    let guestbook: Guestbook = Arc::default();
+
+    Ok(RustApi::new()
+        .state(templates)
+        .state(guestbook)
+        .route("/", get(home))
+        .route("/about", get(about))
+        .route("/messages", post(post_message))
+        .serve_static("/static", "./static"))
+}
+"#;
+    fs::write(format!("{name}/src/lib.rs"), lib_rs).await?;
+
+    // main.rs
+    let main_rs = format!(
+        r#"#[rustapi_rs::main]
+async fn main() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {{
+    dotenvy::dotenv().ok();
+    tracing_subscriber::fmt()
+        .with_env_filter(
+            tracing_subscriber::EnvFilter::from_default_env()
+                .add_directive("info".parse().unwrap()),
+        )
+        .init();
+
+    let host = std::env::var("HOST").unwrap_or_else(|_| "127.0.0.1".to_string());
+    let port = std::env::var("PORT").unwrap_or_else(|_| "8080".to_string());
+    let addr = format!("{{}}:{{}}", host, port);
+
+    tracing::info!("🚀 Server running at http://{{}}", addr);
+
+    {crate_name}::app()?.run(&addr).await
+}}
+"#
+    );
+    fs::write(format!("{name}/src/main.rs"), main_rs).await?;
+
+    // templates/base.html
+    let base_html = r#"<!DOCTYPE html>
+<html lang="en">
+<head>
+    <meta charset="UTF-8">
+    <meta name="viewport" content="width=device-width, initial-scale=1.0">
+    <title>{% block title %}{{ title }}{% endblock %} - RustAPI</title>
+    <link rel="stylesheet" href="/static/style.css">
+</head>
+<body>
+    <nav>
+        <a href="/">Guestbook</a>
+        <a href="/about">About</a>
+    </nav>
+
+    <main>
+        {% block content %}{% endblock %}
+    </main>
+
+    <footer>
+        <p>Built with RustAPI</p>
+    </footer>
+</body>
+</html>
+"#;
+    fs::write(format!("{name}/templates/base.html"), base_html).await?;
+
+    // templates/index.html
+    let index_html = r#"{% extends "base.html" %}
+
+{% block content %}
+<h1>Guestbook</h1>
+
+<form method="post" action="/messages">
+    <label>Name <input name="author" maxlength="40" required></label>
+    <label>Message <textarea name="text" maxlength="500" required></textarea></label>
+    <button type="submit">Sign</button>
+</form>
+
+{% if messages %}
+<ul class="messages">
+    {% for message in messages %}
+    <li><strong>{{ message.author }}</strong>: {{ message.text }}</li>
+    {% endfor %}
+</ul>
+{% else %}
+<p>No messages yet. Be the first to sign!</p>
+{% endif %}
+{% endblock %}
+"#;
+    fs::write(format!("{name}/templates/index.html"), index_html).await?;
+
+    // templates/about.html
+    let about_html = r#"{% extends "base.html" %}
+
+{% block content %}
+<h1>About</h1>
+<p>Version: {{ version }}</p>
+<p>Server-side rendered with Tera templates and RustAPI.</p>
+{% endblock %}
+"#;
+    fs::write(format!("{name}/templates/about.html"), about_html).await?;
+
+    // static/style.css
+    let style_css = r#"* {
+    box-sizing: border-box;
+}
+
+body {
+    font-family: -apple-system, BlinkMacSystemFont, 'Segoe UI', Roboto, sans-serif;
+    line-height: 1.6;
+    color: #333;
+    max-width: 720px;
+    margin: 0 auto;
+    padding: 20px;
+}
+
+nav a {
+    margin-right: 1rem;
+    color: #0066cc;
+}
+
+form {
+    display: grid;
+    gap: 0.75rem;
+    margin: 1.5rem 0;
+}
+
+input, textarea {
+    width: 100%;
+    padding: 0.5rem;
+}
+
+.messages li {
+    margin-bottom: 0.5rem;
+}
+
+footer {
+    margin-top: 3rem;
+    color: #999;
+}
+"#;
+    fs::write(format!("{name}/static/style.css"), style_css).await?;
+
+    // tests/pages.rs
+    let tests_rs = format!(
+        r#"use rustapi_rs::prelude::StatusCode;
+use rustapi_testing::{{TestClient, TestRequest}};
+
+fn client() -> TestClient {{
+    TestClient::new({crate_name}::app().expect("templates should load"))
+}}
+
+fn form(body: &str) -> TestRequest {{
+    TestRequest::post("/messages")
+        .content_type("application/x-www-form-urlencoded")
+        .body(body.to_string())
+}}
+
+#[tokio::test]
+async fn home_page_renders() {{
+    client()
+        .get("/")
+        .await
+        .assert_status(StatusCode::OK)
+        .assert_body_contains("<h1>Guestbook</h1>")
+        .assert_body_contains("No messages yet");
+}}
+
+#[tokio::test]
+async fn posted_message_is_listed() {{
+    let client = client();
+
+    client
+        .request(form("author=Ann&text=Hello+there"))
+        .await
+        .assert_status(StatusCode::FOUND)
+        .assert_header("location", "/");
+
+    client
+        .get("/")
+        .await
+        .assert_body_contains("<strong>Ann</strong>: Hello there");
+}}
+
+#[tokio::test]
+async fn empty_message_is_rejected() {{
+    client()
+        .request(form("author=Ann&text=+++"))
+        .await
+        .assert_status(StatusCode::UNPROCESSABLE_ENTITY);
+}}
+
+#[tokio::test]
+async fn static_assets_are_served() {{
+    client()
+        .get("/static/style.css")
+        .await
+        .assert_status(StatusCode::OK)
+        .assert_body_contains("font-family");
+}}
+"#
+    );
+    fs::write(format!("{name}/tests/pages.rs"), tests_rs).await?;
+
+    let env = r#"HOST=127.0.0.1
+PORT=8080
+RUST_LOG=info
+"#;
+    common::generate_env_files(name, env).await?;
+    common::generate_compose(name, "", "", "").await?;
+    common::generate_dockerfile(name, name, &["templates", "static"]).await?;
+    common::generate_gitignore(name).await?;
+
+    Ok(())
+}
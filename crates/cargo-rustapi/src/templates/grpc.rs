@@ -0,0 +1,223 @@
+//! gRPC + HTTP project template
+
+use super::common;
+use anyhow::Result;
+use tokio::fs;
+
+pub async fn generate(name: &str, features: &[String]) -> Result<()> {
+    let crate_name = common::crate_ident(name);
+    let all_features = common::with_features(features, &["protocol-grpc"]);
+
+    // Cargo.toml
+    let cargo_toml = format!(
+        r#"[package]
+name = "{name}"
+version = "0.1.0"
+edition = "2021"
+
+[dependencies]
+rustapi-rs = {{ version = "0.1"{features} }}
+tokio = {{ version = "1", features = ["full"] }}
+serde = {{ version = "1", features = ["derive"] }}
+tonic = "0.14"
+tonic-prost = "0.14"
+prost = "0.14"
+dotenvy = "0.15"
+tracing = "0.1"
+tracing-subscriber = {{ version = "0.3", features = ["env-filter"] }}
+
+[build-dependencies]
+tonic-prost-build = "0.14"
+protoc-bin-vendored = "3"
+
+[dev-dependencies]
+rustapi-testing = {{ version = "0.1", features = ["grpc"] }}
+"#,
+        name = name,
+        features = common::features_to_cargo(&all_features),
+    );
+    fs::write(format!("{name}/Cargo.toml"), cargo_toml).await?;
+
+    // Create directories
+    fs::create_dir_all(format!("{name}/src")).await?;
+    fs::create_dir_all(format!("{name}/proto")).await?;
+    fs::create_dir_all(format!("{name}/tests")).await?;
+
+    // build.rs
+    let build_rs = r#"fn main() -> Result<(), Box<dyn std::error::Error>> {
+    // Use the bundled protoc so no system install is needed
+    if std::env::var_os("PROTOC").is_none() {
+        std::env::set_var("PROTOC", protoc_bin_vendored::protoc_bin_path()?);
+    }
+    tonic_prost_build::compile_protos("proto/greeter.proto")?;
+    Ok(())
+}
+"#;
+    fs::write(format!("{name}/build.rs"), build_rs).await?;
+
+    // proto/greeter.proto
+    let proto = r#"syntax = "proto3";
+
+package greeter;
+
+service Greeter {
+  rpc SayHello (HelloRequest) returns (HelloReply);
+}
+
+message HelloRequest {
+  string name = 1;
+}
+
+message HelloReply {
+  string message = 1;
+}
+"#;
+    fs::write(format!("{name}/proto/greeter.proto"), proto).await?;
+
+    // lib.rs
+    let lib_rs = r#"use rustapi_rs::prelude::*;
+use rustapi_rs::protocol::grpc::tonic::service::Routes;
+use serde::Serialize;
+use tonic::{Request, Response, Status};
+
+/// Code generated from `proto/greeter.proto`
+pub mod pb {
+    tonic::include_proto!("greeter");
+}
+
+use pb::greeter_server::{Greeter, GreeterServer};
+use pb::{HelloReply, HelloRequest};
+
+/// Greeting shared by the gRPC service and the HTTP API
+pub fn greeting(name: &str) -> String {
+    format!("Hello, {}!", name)
+}
+
+/// The `greeter.Greeter` gRPC service
+#[derive(Debug, Default)]
+pub struct GreeterService;
+
+#[tonic::async_trait]
+impl Greeter for GreeterService {
+    async fn say_hello(
+        &self,
+        request: Request<HelloRequest>,
+    ) -> Result<Response<HelloReply>, Status> {
+        let name = request.into_inner().name;
+        if name.is_empty() {
+            return Err(Status::invalid_argument("name must not be empty"));
+        }
+        Ok(Response::new(HelloReply {
+            message: greeting(&name),
+        }))
+    }
+}
+
+/// HTTP greeting response
+#[derive(Debug, Serialize, Schema)]
+pub struct Greeting {
+    pub message: String,
+}
+
+async fn hello(Path(name): Path<String>) -> Json<Greeting> {
+    Json(Greeting {
+        message: greeting(&name),
+    })
+}
+
+/// The HTTP side of the application
+pub fn app() -> RustApi {
+    RustApi::new().route("/hello/{name}", get(hello)).docs("/docs")
+}
+
+/// The gRPC services, served next to [`app`] on the same port
+pub fn grpc_services() -> Routes {
+    Routes::new(GreeterServer::new(GreeterService))
+}
+"#;
+    fs::write(format!("{name}/src/lib.rs"), lib_rs).await?;
+
+    // main.rs
+    let main_rs = format!(
+        r#"use rustapi_rs::protocol::grpc::run_rustapi_and_grpc_multiplexed;
+
+#[rustapi_rs::main]
+async fn main() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {{
+    dotenvy::dotenv().ok();
+    tracing_subscriber::fmt()
+        .with_env_filter(
+            tracing_subscriber::EnvFilter::from_default_env()
+                .add_directive("info".parse().unwrap()),
+        )
+        .init();
+
+    let host = std::env::var("HOST").unwrap_or_else(|_| "127.0.0.1".to_string());
+    let port = std::env::var("PORT").unwrap_or_else(|_| "8080".to_string());
+    let addr = format!("{{}}:{{}}", host, port);
+
+    tracing::info!("🚀 HTTP and gRPC running at http://{{}}", addr);
+
+    run_rustapi_and_grpc_multiplexed({crate_name}::app(), &addr, {crate_name}::grpc_services()).await
+}}
+"#
+    );
+    fs::write(format!("{name}/src/main.rs"), main_rs).await?;
+
+    // tests/greeter.rs
+    let tests_rs = format!(
+        r#"use rustapi_rs::prelude::StatusCode;
+use rustapi_testing::{{TestClient, TestGrpc}};
+use {crate_name}::pb::greeter_client::GreeterClient;
+use {crate_name}::pb::HelloRequest;
+
+#[tokio::test]
+async fn grpc_say_hello() {{
+    let server = TestGrpc::start({crate_name}::app(), {crate_name}::grpc_services()).await;
+    let mut client = GreeterClient::new(server.channel());
+
+    let reply = client
+        .say_hello(HelloRequest {{
+            name: "Ann".to_string(),
+        }})
+        .await
+        .unwrap();
+    assert_eq!(reply.into_inner().message, "Hello, Ann!");
+}}
+
+#[tokio::test]
+async fn grpc_rejects_empty_name() {{
+    let server = TestGrpc::services({crate_name}::grpc_services()).await;
+    let mut client = GreeterClient::new(server.channel());
+
+    let status = client
+        .say_hello(HelloRequest {{
+            name: String::new(),
+        }})
+        .await
+        .unwrap_err();
+    assert_eq!(status.code(), tonic::Code::InvalidArgument);
+}}
+
+#[tokio::test]
+async fn http_hello() {{
+    TestClient::new({crate_name}::app())
+        .get("/hello/Ann")
+        .await
+        .assert_status(StatusCode::OK)
+        .assert_body_contains("Hello, Ann!");
+}}
+"#
+    );
+    fs::write(format!("{name}/tests/greeter.rs"), tests_rs).await?;
+
+    let env = r#"HOST=127.0.0.1
+PORT=8080
+RUST_LOG=info
+"#;
+    common::generate_env_files(name, env).await?;
+    common::generate_compose(name, "", "", "").await?;
+    common::generate_dockerfile(name, name, &[]).await?;
+    common::generate_gitignore(name).await?;
+
+    Ok(())
+}
@@ -5,6 +5,8 @@ use anyhow::Result;
 use tokio::fs;
 
 pub async fn generate(name: &str, features: &[String]) -> Result<()> {
+    let crate_name = common::crate_ident(name);
+
     // Cargo.toml
     let cargo_toml = format!(
         r#"[package]
@@ -16,20 +18,25 @@ edition = "2021"
 rustapi-rs = {{ version = "0.1"{features} }}
 tokio = {{ version = "1", features = ["full"] }}
 serde = {{ version = "1", features = ["derive"] }}
+dotenvy = "0.15"
 tracing-subscriber = "0.3"
+
+[dev-dependencies]
+rustapi-testing = "0.1"
 "#,
         name = name,
         features = common::features_to_cargo(features),
     );
     fs::create_dir_all(format!("{name}/src")).await?;
+    fs::create_dir_all(format!("{name}/tests")).await?;
 
-    // main.rs
-    let main_rs = r#"use rustapi_rs::prelude::*;
+    // lib.rs
+    let lib_rs = r#"use rustapi_rs::prelude::*;
 use serde::Serialize;
 
 #[derive(Serialize, Schema)]
-struct Hello {
-    message: String,
+pub struct Hello {
+    pub message: String,
 }
 
 async fn hello() -> Json<Hello> {
@@ -38,21 +45,51 @@ async fn hello() -> Json<Hello> {
     })
 }
 
-#[rustapi_rs::main]
-async fn main() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+/// Build the application (shared by `main` and the tests)
+pub fn app() -> RustApi {
+    RustApi::new().route("/", get(hello)).docs("/docs")
+}
+"#;
+
+    // main.rs
+    let main_rs = format!(
+        r#"#[rustapi_rs::main]
+async fn main() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {{
+    dotenvy::dotenv().ok();
     tracing_subscriber::fmt::init();
 
+    let host = std::env::var("HOST").unwrap_or_else(|_| "127.0.0.1".to_string());
     let port = std::env::var("PORT").unwrap_or_else(|_| "8080".to_string());
-    let addr = format!("127.0.0.1:{}", port);
+    let addr = format!("{{}}:{{}}", host, port);
+
+    println!("🚀 Server running at http://{{}}", addr);
+
+    {crate_name}::app().run(&addr).await
+}}
+"#
+    );
 
-    println!("🚀 Server running at http://{}", addr);
+    // tests/api.rs
+    let tests_rs = format!(
+        r#"use rustapi_rs::prelude::StatusCode;
+use rustapi_testing::TestClient;
 
-    RustApi::new()
-        .route("/", get(hello))
-        .docs("/docs")
-        .run(&addr)
+#[tokio::test]
+async fn hello_returns_greeting() {{
+    let client = TestClient::new({crate_name}::app());
+
+    client
+        .get("/")
         .await
-}
+        .assert_status(StatusCode::OK)
+        .assert_body_contains("Hello, World!");
+}}
+"#
+    );
+
+    let env = r#"HOST=127.0.0.1
+PORT=8080
+RUST_LOG=info
 "#;
 
     // Write files in parallel for better performance
@@ -62,13 +99,26 @@ async fn main() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
             .map_err(anyhow::Error::from)
     };
     let f2 = async {
+        fs::write(format!("{name}/src/lib.rs"), lib_rs)
+            .await
+            .map_err(anyhow::Error::from)
+    };
+    let f3 = async {
         fs::write(format!("{name}/src/main.rs"), main_rs)
             .await
             .map_err(anyhow::Error::from)
     };
-    let f3 = common::generate_gitignore(name);
+    let f4 = async {
+        fs::write(format!("{name}/tests/api.rs"), tests_rs)
+            .await
+            .map_err(anyhow::Error::from)
+    };
+    let f5 = common::generate_gitignore(name);
+    let f6 = common::generate_env_files(name, env);
+    let f7 = common::generate_dockerfile(name, name, &[]);
+    let f8 = common::generate_compose(name, "", "", "");
 
-    tokio::try_join!(f1, f2, f3)?;
+    tokio::try_join!(f1, f2, f3, f4, f5, f6, f7, f8)?;
 
     Ok(())
 }
@@ -1,8 +1,12 @@
 //! Project templates
 
 mod api;
+mod auth_jwt;
 mod full;
+mod fullstack_ssr;
+mod grpc;
 mod minimal;
+mod sqlx_postgres;
 mod web;
 
 use anyhow::Result;
@@ -19,6 +23,17 @@ pub enum ProjectTemplate {
     Web,
     /// Full-featured template with all batteries
     Full,
+    /// REST API backed by PostgreSQL through SQLx, with migrations
+    #[value(name = "sqlx-postgres")]
+    SqlxPostgres,
+    /// API with JWT login and protected routes
+    #[value(name = "auth-jwt")]
+    AuthJwt,
+    /// Server-rendered web app with forms and static assets
+    #[value(name = "fullstack-ssr")]
+    FullstackSsr,
+    /// gRPC service and HTTP API on a single port
+    Grpc,
 }
 
 /// Opinionated feature presets layered on top of project templates.
@@ -82,6 +97,10 @@ pub async fn generate_project(
         ProjectTemplate::Api => api::generate(name, features).await,
         ProjectTemplate::Web => web::generate(name, features).await,
         ProjectTemplate::Full => full::generate(name, features).await,
+        ProjectTemplate::SqlxPostgres => sqlx_postgres::generate(name, features).await,
+        ProjectTemplate::AuthJwt => auth_jwt::generate(name, features).await,
+        ProjectTemplate::FullstackSsr => fullstack_ssr::generate(name, features).await,
+        ProjectTemplate::Grpc => grpc::generate(name, features).await,
     }
 }
 
@@ -137,6 +156,85 @@ RUST_LOG=info
         Ok(())
     }
 
+    /// Write `.env` (local settings, ignored by git) and a matching `.env.example`
+    pub async fn generate_env_files(path: &str, content: &str) -> Result<()> {
+        fs::write(format!("{path}/.env"), content).await?;
+        fs::write(format!("{path}/.env.example"), content).await?;
+        Ok(())
+    }
+
+    /// Multi-stage Dockerfile building the project binary
+    ///
+    /// `assets` are directories copied next to the binary (templates, static files).
+    pub async fn generate_dockerfile(path: &str, name: &str, assets: &[&str]) -> Result<()> {
+        let assets = assets
+            .iter()
+            .map(|dir| format!("COPY --from=builder /app/{dir} ./{dir}\n"))
+            .collect::<String>();
+        let content = format!(
+            r#"# Build stage
+FROM rust:1-slim AS builder
+WORKDIR /app
+COPY . .
+RUN cargo build --release
+
+# Runtime stage
+FROM debian:bookworm-slim
+RUN apt-get update && apt-get install -y ca-certificates && rm -rf /var/lib/apt/lists/*
+WORKDIR /app
+COPY --from=builder /app/target/release/{name} /usr/local/bin/app
+{assets}ENV HOST=0.0.0.0
+ENV PORT=8080
+EXPOSE 8080
+CMD ["app"]
+"#
+        );
+        fs::write(format!("{path}/Dockerfile"), content).await?;
+        fs::write(format!("{path}/.dockerignore"), "target/\n.env\n").await?;
+        Ok(())
+    }
+
+    /// `docker-compose.yml` with the app service and any extra `services`
+    ///
+    /// `app_extra` is appended to the app service (indented by four spaces),
+    /// `volumes` is the top-level volumes section.
+    pub async fn generate_compose(
+        path: &str,
+        services: &str,
+        app_extra: &str,
+        volumes: &str,
+    ) -> Result<()> {
+        let content = format!(
+            r#"services:
+{services}  app:
+    build: .
+    env_file: .env
+    environment:
+      HOST: 0.0.0.0
+{app_extra}    ports:
+      - "8080:8080"
+{volumes}"#
+        );
+        fs::write(format!("{path}/docker-compose.yml"), content).await?;
+        Ok(())
+    }
+
+    /// `features` plus `required`, without duplicates
+    pub fn with_features(features: &[String], required: &[&str]) -> Vec<String> {
+        let mut all = features.to_vec();
+        for feature in required {
+            if !all.iter().any(|f| f == feature) {
+                all.push(feature.to_string());
+            }
+        }
+        all
+    }
+
+    /// The name of the project's library crate (`my-app` → `my_app`)
+    pub fn crate_ident(name: &str) -> String {
+        name.replace('-', "_")
+    }
+
     pub fn features_to_cargo(features: &[String]) -> String {
         if features.is_empty() {
             String::new()
@@ -76,6 +76,76 @@ mod new_command {
         assert!(project_path.join("src/models/mod.rs").exists());
     }
 
+    #[test]
+    fn test_new_templates_include_docker_env_and_tests() {
+        let cases = [
+            ("minimal", "tests/api.rs", "rustapi-rs"),
+            ("sqlx-postgres", "tests/todos.rs", "extras-sqlx"),
+            ("auth-jwt", "tests/auth.rs", "extras-jwt"),
+            ("fullstack-ssr", "tests/pages.rs", "protocol-view"),
+            ("grpc", "tests/greeter.rs", "protocol-grpc"),
+        ];
+
+        for (template, test_file, feature) in cases {
+            let dir = tempdir().expect("Failed to create temp dir");
+            let project_name = format!("test-{template}-project");
+            let project_path = dir.path().join(&project_name);
+
+            cargo_rustapi()
+                .current_dir(dir.path())
+                .args(["new", &project_name, "--template", template, "--yes"])
+                .assert()
+                .success();
+
+            for file in [
+                "src/lib.rs",
+                "src/main.rs",
+                test_file,
+                "Dockerfile",
+                "docker-compose.yml",
+                ".env",
+                ".env.example",
+            ] {
+                assert!(
+                    project_path.join(file).exists(),
+                    "{template}: {file} should exist"
+                );
+            }
+
+            let cargo_content = fs::read_to_string(project_path.join("Cargo.toml"))
+                .expect("Failed to read Cargo.toml");
+            assert!(cargo_content.contains(feature), "{template}: {feature}");
+            assert!(cargo_content.contains("rustapi-testing"));
+
+            let main_rs = fs::read_to_string(project_path.join("src/main.rs")).unwrap();
+            assert!(
+                main_rs.contains(&format!("{}::app", project_name.replace('-', "_"))),
+                "{template}: main should use the library's app"
+            );
+        }
+    }
+
+    #[test]
+    fn test_new_sqlx_postgres_template_has_migrations_and_db_service() {
+        let dir = tempdir().expect("Failed to create temp dir");
+        let project_path = dir.path().join("todo-db");
+
+        cargo_rustapi()
+            .current_dir(dir.path())
+            .args(["new", "todo-db", "--template", "sqlx-postgres", "--yes"])
+            .assert()
+            .success();
+
+        assert!(project_path
+            .join("migrations/0001_create_todos.sql")
+            .exists());
+        let compose = fs::read_to_string(project_path.join("docker-compose.yml")).unwrap();
+        assert!(compose.contains("postgres:16-alpine"));
+        assert!(compose.contains("condition: service_healthy"));
+        let env = fs::read_to_string(project_path.join(".env")).unwrap();
+        assert!(env.contains("DATABASE_URL=postgres://"));
+    }
+
     #[test]
     fn test_new_with_features() {
         let dir = tempdir().expect("Failed to create temp dir");
@@ -227,6 +297,32 @@ mod doctor_command {
             "Doctor should check for Rust compiler"
         );
     }
+
+    #[test]
+    fn test_doctor_fails_on_missing_feature_and_env() {
+        let dir = tempdir().unwrap();
+        fs::write(
+            dir.path().join("Cargo.toml"),
+            "[package]\nname = \"demo\"\nversion = \"0.1.0\"\n\n[dependencies]\nrustapi-rs = \"0.1\"\n",
+        )
+        .unwrap();
+        fs::create_dir_all(dir.path().join("src")).unwrap();
+        fs::write(
+            dir.path().join("src/main.rs"),
+            "use rustapi_rs::protocol::toon::Toon;\nfn main() { let _ = rustapi_rs::require_env(\"DOCTOR_TEST_SECRET\"); }\n",
+        )
+        .unwrap();
+
+        cargo_rustapi()
+            .arg("doctor")
+            .arg("--path")
+            .arg(dir.path())
+            .assert()
+            .failure()
+            .stdout(predicate::str::contains("Project configuration"))
+            .stdout(predicate::str::contains("DOCTOR_TEST_SECRET not set"))
+            .stdout(predicate::str::contains("protocol-toon"));
+    }
 }
 
 mod bench_command {
@@ -238,7 +334,136 @@ mod bench_command {
             .args(["bench", "--help"])
             .assert()
             .success()
-            .stdout(predicate::str::contains("benchmark workflow"));
+            .stdout(predicate::str::contains("benchmark workflow"))
+            .stdout(predicate::str::contains("--save-baseline"));
+    }
+
+    #[test]
+    fn test_bench_rejects_relative_routes() {
+        cargo_rustapi()
+            .args(["bench", "health", "--url", "http://127.0.0.1:1"])
+            .assert()
+            .failure()
+            .stderr(predicate::str::contains("must start with `/`"));
+    }
+}
+
+mod errors_command {
+    use super::*;
+
+    #[test]
+    fn test_errors_help() {
+        cargo_rustapi()
+            .args(["errors", "--help"])
+            .assert()
+            .success()
+            .stdout(predicate::str::contains("error code catalog"));
+    }
+}
+
+mod openapi_command {
+    use super::*;
+
+    const BASELINE: &str = r#"{"openapi": "3.1.0", "paths": {
+        "/users": {"get": {"responses": {"200": {"description": "OK"}}}}
+    }}"#;
+
+    #[test]
+    fn test_openapi_help() {
+        cargo_rustapi()
+            .args(["openapi", "--help"])
+            .assert()
+            .success()
+            .stdout(predicate::str::contains("export"))
+            .stdout(predicate::str::contains("diff"));
+    }
+
+    #[test]
+    fn test_openapi_diff_passes_on_compatible_changes() {
+        let dir = tempdir().expect("Failed to create temp dir");
+        fs::write(dir.path().join("main.json"), BASELINE).unwrap();
+        fs::write(
+            dir.path().join("current.yaml"),
+            "openapi: 3.1.0\npaths:\n  /users:\n    get:\n      responses:\n        '200': {description: OK}\n    post:\n      responses:\n        '201': {description: Created}\n",
+        )
+        .unwrap();
+
+        cargo_rustapi()
+            .current_dir(dir.path())
+            .args([
+                "openapi",
+                "diff",
+                "--against",
+                "main.json",
+                "--spec",
+                "current.yaml",
+            ])
+            .assert()
+            .success()
+            .stdout(predicate::str::contains("POST /users"))
+            .stdout(predicate::str::contains("operation added"));
+    }
+
+    #[test]
+    fn test_openapi_diff_fails_on_breaking_changes() {
+        let dir = tempdir().expect("Failed to create temp dir");
+        fs::write(dir.path().join("main.json"), BASELINE).unwrap();
+        fs::write(
+            dir.path().join("current.json"),
+            r#"{"openapi": "3.1.0", "paths": {}}"#,
+        )
+        .unwrap();
+
+        let diff = |extra: &[&str]| {
+            let mut cmd = cargo_rustapi();
+            cmd.current_dir(dir.path()).args([
+                "openapi",
+                "diff",
+                "--against",
+                "main.json",
+                "--spec",
+                "current.json",
+            ]);
+            cmd.args(extra);
+            cmd
+        };
+
+        diff(&[])
+            .assert()
+            .failure()
+            .stdout(predicate::str::contains("GET /users"))
+            .stderr(predicate::str::contains("1 breaking change(s)"));
+        diff(&["--allow-breaking"]).assert().success();
+        diff(&["--format", "json", "--allow-breaking"])
+            .assert()
+            .success()
+            .stdout(predicate::str::contains(r#""kind": "breaking""#));
+    }
+}
+
+mod routes_command {
+    use super::*;
+
+    #[test]
+    fn test_routes_help() {
+        cargo_rustapi()
+            .args(["routes", "--help"])
+            .assert()
+            .success()
+            .stdout(predicate::str::contains("route table"));
+    }
+}
+
+mod export_command {
+    use super::*;
+
+    #[test]
+    fn test_export_help() {
+        cargo_rustapi()
+            .args(["export", "--help"])
+            .assert()
+            .success()
+            .stdout(predicate::str::contains("Prerender the HTML pages"));
     }
 }
 
@@ -278,6 +503,20 @@ mod replay_command {
     }
 }
 
+#[cfg(feature = "admin")]
+mod log_level_command {
+    use super::*;
+
+    #[test]
+    fn test_log_level_help() {
+        cargo_rustapi()
+            .args(["log-level", "--help"])
+            .assert()
+            .success()
+            .stdout(predicate::str::contains("Change the log filter"));
+    }
+}
+
 mod generate_command {
     use super::*;
 
@@ -389,6 +628,82 @@ mod watch_command {
     }
 }
 
+mod seed_command {
+    use super::*;
+
+    fn project_with_seeds() -> tempfile::TempDir {
+        let dir = tempdir().unwrap();
+        fs::write(
+            dir.path().join("Cargo.toml"),
+            "[package]\nname = \"demo\"\nversion = \"0.1.0\"\n",
+        )
+        .unwrap();
+        fs::create_dir_all(dir.path().join("seeds")).unwrap();
+        fs::write(dir.path().join("seeds/02_posts.sql"), "SELECT 2;\n").unwrap();
+        fs::write(dir.path().join("seeds/01_users.sql"), "SELECT 1;\n").unwrap();
+        dir
+    }
+
+    #[test]
+    fn test_seed_help() {
+        cargo_rustapi()
+            .args(["seed", "--help"])
+            .assert()
+            .success()
+            .stdout(predicate::str::contains("--allow-production"));
+    }
+
+    #[test]
+    fn test_seed_dry_run_lists_files_in_order() {
+        let dir = project_with_seeds();
+
+        cargo_rustapi()
+            .current_dir(dir.path())
+            .args([
+                "seed",
+                "--dry-run",
+                "--database-url",
+                "postgres://localhost/demo",
+            ])
+            .env_remove("RUSTAPI_ENV")
+            .assert()
+            .success()
+            .stdout(predicate::str::is_match("(?s)01_users\\.sql.*02_posts\\.sql").unwrap())
+            .stdout(predicate::str::contains("nothing was executed"));
+    }
+
+    #[test]
+    fn test_seed_refuses_production() {
+        let dir = project_with_seeds();
+
+        cargo_rustapi()
+            .current_dir(dir.path())
+            .args([
+                "seed",
+                "--dry-run",
+                "--database-url",
+                "postgres://localhost/demo",
+            ])
+            .env("RUSTAPI_ENV", "production")
+            .assert()
+            .failure()
+            .stderr(predicate::str::contains("Refusing to seed"));
+
+        cargo_rustapi()
+            .current_dir(dir.path())
+            .args([
+                "seed",
+                "--dry-run",
+                "--allow-production",
+                "--database-url",
+                "postgres://localhost/demo",
+            ])
+            .env("RUSTAPI_ENV", "production")
+            .assert()
+            .success();
+    }
+}
+
 mod migrate_command {
     use super::*;
 
@@ -419,6 +734,21 @@ mod migrate_command {
             .stdout(predicate::str::contains("migration status"));
     }
 
+    #[test]
+    fn test_migrate_generate_requires_from_models() {
+        cargo_rustapi()
+            .args(["migrate", "generate", "--help"])
+            .assert()
+            .success()
+            .stdout(predicate::str::contains("--from-models"));
+
+        cargo_rustapi()
+            .args(["migrate", "generate"])
+            .assert()
+            .failure()
+            .stderr(predicate::str::contains("--from-models"));
+    }
+
     #[test]
     fn test_migrate_create_help() {
         cargo_rustapi()
@@ -467,6 +797,108 @@ mod migrate_command {
     }
 }
 
+mod deploy_manifests_command {
+    use super::*;
+
+    fn sample_project() -> tempfile::TempDir {
+        let dir = tempdir().expect("Failed to create temp dir");
+        fs::write(
+            dir.path().join("Cargo.toml"),
+            "[package]\nname = \"shop-api\"\nversion = \"0.1.0\"\n",
+        )
+        .unwrap();
+        fs::write(
+            dir.path().join(".env.example"),
+            "PORT=3000\nRUST_LOG=info\nDATABASE_URL=postgres://localhost/shop\n",
+        )
+        .unwrap();
+        fs::create_dir_all(dir.path().join("src")).unwrap();
+        fs::write(
+            dir.path().join("src/main.rs"),
+            "fn main() { RustApi::new().health_endpoints(); }\n",
+        )
+        .unwrap();
+        dir
+    }
+
+    #[test]
+    fn test_deploy_kubernetes_help() {
+        cargo_rustapi()
+            .args(["deploy", "kubernetes", "--help"])
+            .assert()
+            .success()
+            .stdout(predicate::str::contains("Helm chart"));
+    }
+
+    #[test]
+    fn test_deploy_kubernetes_seeds_port_health_and_env() {
+        let dir = sample_project();
+
+        cargo_rustapi()
+            .current_dir(dir.path())
+            .args(["deploy", "kubernetes", "--image", "ghcr.io/acme/shop:1.0"])
+            .assert()
+            .success();
+
+        let k8s = dir.path().join("k8s");
+        let deployment = fs::read_to_string(k8s.join("deployment.yaml")).unwrap();
+        assert!(deployment.contains("image: ghcr.io/acme/shop:1.0"));
+        assert!(deployment.contains("containerPort: 3000"));
+        assert!(deployment.contains("path: /ready"));
+        assert!(deployment.contains("path: /live"));
+        assert!(k8s.join("service.yaml").exists());
+        assert!(k8s.join("hpa.yaml").exists());
+
+        let config = fs::read_to_string(k8s.join("configmap.yaml")).unwrap();
+        assert!(config.contains("RUST_LOG: \"info\""));
+        assert!(!config.contains("DATABASE_URL"));
+        let secret = fs::read_to_string(k8s.join("secret.yaml")).unwrap();
+        assert!(secret.contains("DATABASE_URL"));
+    }
+
+    #[test]
+    fn test_deploy_kubernetes_helm_chart() {
+        let dir = sample_project();
+
+        cargo_rustapi()
+            .current_dir(dir.path())
+            .args(["deploy", "kubernetes", "--helm", "--output", "chart"])
+            .assert()
+            .success();
+
+        let chart = dir.path().join("chart");
+        assert!(chart.join("Chart.yaml").exists());
+        assert!(chart.join("templates/deployment.yaml").exists());
+        let values = fs::read_to_string(chart.join("values.yaml")).unwrap();
+        assert!(values.contains("containerPort: 3000"));
+        assert!(values.contains("readinessPath: /ready"));
+    }
+
+    #[test]
+    fn test_deploy_docker_and_fly_use_project_port() {
+        let dir = sample_project();
+
+        cargo_rustapi()
+            .current_dir(dir.path())
+            .args(["deploy", "docker"])
+            .assert()
+            .success()
+            .stdout(predicate::str::contains("DATABASE_URL"));
+        let dockerfile = fs::read_to_string(dir.path().join("Dockerfile")).unwrap();
+        assert!(dockerfile.contains("EXPOSE 3000"));
+        assert!(dockerfile.contains("--bin shop-api"));
+
+        cargo_rustapi()
+            .current_dir(dir.path())
+            .args(["deploy", "fly", "--init-only"])
+            .assert()
+            .success();
+        let fly = fs::read_to_string(dir.path().join("fly.toml")).unwrap();
+        assert!(fly.contains("internal_port = 3000"));
+        assert!(fly.contains("path = \"/ready\""));
+    }
+}
+
 #[cfg(feature = "cloud")]
 mod deploy_command {
     use super::*;
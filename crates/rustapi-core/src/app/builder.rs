@@ -12,35 +12,57 @@ use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt, EnvFilte
 impl RustApi {
     /// Create a new RustAPI application
     pub fn new() -> Self {
-        // Initialize tracing if not already done
-        let _ = tracing_subscriber::registry()
-            .with(
-                EnvFilter::try_from_default_env()
-                    .unwrap_or_else(|_| EnvFilter::new("info,rustapi=debug")),
-            )
-            .with(tracing_subscriber::fmt::layer())
-            .try_init();
+        // Initialize tracing if not already done, in the profile's log format
+        // The filter is reloadable so it can be changed at runtime (see `LogFilter`)
+        let json_logs = crate::Profile::current().json_logs;
+        let (filter, log_filter) = crate::log_filter::reloadable(
+            EnvFilter::try_from_default_env()
+                .unwrap_or_else(|_| EnvFilter::new("info,rustapi=debug")),
+        );
+        let installed =
+            tracing_subscriber::registry()
+                .with(filter)
+                .with(json_logs.then(|| {
+                    tracing_subscriber::fmt::layer().event_format(crate::profile::JsonLines)
+                }))
+                .with((!json_logs).then(tracing_subscriber::fmt::layer))
+                .try_init();
+        if installed.is_ok() {
+            crate::log_filter::install(log_filter);
+        }
+
+        let error_catalog = crate::error_catalog::ErrorCatalog::collect();
+        let mut openapi_spec = rustapi_openapi::OpenApiSpec::new("RustAPI Application", "1.0.0")
+            .register::<rustapi_openapi::ErrorSchema>()
+            .register::<rustapi_openapi::ErrorBodySchema>()
+            .register::<rustapi_openapi::ValidationErrorSchema>()
+            .register::<rustapi_openapi::ValidationErrorBodySchema>()
+            .register::<rustapi_openapi::FieldErrorSchema>();
+        error_catalog.apply_to_openapi(&mut openapi_spec);
 
         Self {
             router: Router::new(),
-            openapi_spec: rustapi_openapi::OpenApiSpec::new("RustAPI Application", "1.0.0")
-                .register::<rustapi_openapi::ErrorSchema>()
-                .register::<rustapi_openapi::ErrorBodySchema>()
-                .register::<rustapi_openapi::ValidationErrorSchema>()
-                .register::<rustapi_openapi::ValidationErrorBodySchema>()
-                .register::<rustapi_openapi::FieldErrorSchema>(),
+            openapi_spec,
+            error_catalog,
             layers: LayerStack::new(),
             body_limit: Some(DEFAULT_BODY_LIMIT), // Default 1MB limit
             interceptors: InterceptorChain::new(),
             lifecycle_hooks: LifecycleHooks::new(),
             hot_reload: false,
+            startup_report: false,
+            handover: None,
             #[cfg(feature = "http3")]
             http3_config: None,
             health_check: None,
             health_endpoint_config: None,
+            readiness_gate: None,
             status_config: None,
             #[cfg(feature = "dashboard")]
             dashboard_config: None,
+            route_origins: std::collections::HashMap::new(),
+            duplicate_routes: Vec::new(),
+            duplicate_route_policy: crate::route_table::DuplicateRoutePolicy::default(),
+            operation_id_strategy: None,
         }
     }
 
@@ -355,6 +377,67 @@ impl RustApi {
         self
     }
 
+    /// Report the addresses, environment, features, middleware and routes at
+    /// startup
+    ///
+    /// Printed as a banner in development and as one JSON line when the
+    /// [`Profile`](crate::Profile) logs JSON. See [`crate::startup`].
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// RustApi::new()
+    ///     .startup_report(true)
+    ///     .route("/users", get(list_users))
+    ///     .run("127.0.0.1:8080")
+    ///     .await
+    /// ```
+    pub fn startup_report(mut self, enabled: bool) -> Self {
+        self.startup_report = enabled;
+        self
+    }
+
+    /// Restart without dropping connections
+    ///
+    /// `run` and `run_with_shutdown` take over a listener passed by the
+    /// parent process or bind with `SO_REUSEPORT`, and after the shutdown
+    /// signal wait for open connections to finish. See [`Handover`].
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// RustApi::new()
+    ///     .handover(Handover::new().drain_timeout(Duration::from_secs(30)))
+    ///     .route("/", get(hello))
+    ///     .run_with_shutdown("0.0.0.0:8080", shutdown_signal())
+    ///     .await
+    /// ```
+    ///
+    /// [`Handover`]: crate::Handover
+    pub fn handover(mut self, handover: crate::handover::Handover) -> Self {
+        self.handover = Some(handover);
+        self
+    }
+
+    /// Choose what `run` does when two handlers share a method and path
+    ///
+    /// Duplicates are easy to create with auto-registered routes, since any
+    /// linked module can contribute a `#[rustapi::get]` handler. By default
+    /// the app refuses to start and lists both handlers with their source
+    /// locations; [`DuplicateRoutePolicy::Warn`] logs the same information
+    /// and serves the first registration.
+    ///
+    /// [`DuplicateRoutePolicy::Warn`]: crate::DuplicateRoutePolicy::Warn
+    pub fn on_duplicate_route(mut self, policy: crate::route_table::DuplicateRoutePolicy) -> Self {
+        self.duplicate_route_policy = policy;
+        self
+    }
+
+    /// Duplicate routes found so far, in registration order
+    pub fn duplicate_routes(&self) -> &[crate::route_table::DuplicateRoute] {
+        &self.duplicate_routes
+    }
+
     /// Get the inner router (for testing or advanced usage)
     pub fn into_router(self) -> Router {
         self.router
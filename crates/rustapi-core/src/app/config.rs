@@ -1,15 +1,23 @@
-﻿use super::types::RustApi;
+use super::types::RustApi;
 use crate::middleware::{LayerStack, MiddlewareLayer};
 
 /// Configuration builder for RustAPI with auto-routes
 pub struct RustApiConfig {
     docs_path: Option<String>,
-    docs_enabled: bool,
+    docs_enabled: Option<bool>,
+    environment: Option<crate::Environment>,
+    mask_internal_errors: Option<bool>,
+    json_logs: Option<bool>,
+    strict_cors: Option<bool>,
+    replay_recording: Option<bool>,
     api_title: String,
     api_version: String,
     api_description: Option<String>,
     body_limit: Option<usize>,
     layers: LayerStack,
+    duplicate_route_policy: crate::route_table::DuplicateRoutePolicy,
+    operation_id_strategy: Option<crate::operation_id::OperationIdStrategy>,
+    startup_report: bool,
 }
 
 impl Default for RustApiConfig {
@@ -22,12 +30,20 @@ impl RustApiConfig {
     pub fn new() -> Self {
         Self {
             docs_path: Some("/docs".to_string()),
-            docs_enabled: true,
+            docs_enabled: None,
+            environment: None,
+            mask_internal_errors: None,
+            json_logs: None,
+            strict_cors: None,
+            replay_recording: None,
             api_title: "RustAPI".to_string(),
             api_version: "1.0.0".to_string(),
             api_description: None,
             body_limit: None,
             layers: LayerStack::new(),
+            duplicate_route_policy: crate::route_table::DuplicateRoutePolicy::default(),
+            operation_id_strategy: None,
+            startup_report: false,
         }
     }
 
@@ -37,12 +53,84 @@ impl RustApiConfig {
         self
     }
 
-    /// Enable or disable docs (default: true)
+    /// Enable or disable docs (default: the profile's `expose_docs`)
     pub fn docs_enabled(mut self, enabled: bool) -> Self {
-        self.docs_enabled = enabled;
+        self.docs_enabled = Some(enabled);
         self
     }
 
+    /// Derive defaults from an environment
+    ///
+    /// Installs [`Profile::for_environment`](crate::Profile::for_environment)
+    /// when the app is built. Without it the defaults follow
+    /// [`get_environment`](crate::get_environment). Individual settings can be
+    /// overridden with [`mask_internal_errors`](Self::mask_internal_errors),
+    /// [`docs_enabled`](Self::docs_enabled), [`json_logs`](Self::json_logs),
+    /// [`strict_cors`](Self::strict_cors) and
+    /// [`replay_recording`](Self::replay_recording), in any order.
+    pub fn profile(mut self, environment: crate::Environment) -> Self {
+        self.environment = Some(environment);
+        self
+    }
+
+    /// Override whether 5xx error details are masked
+    pub fn mask_internal_errors(mut self, mask: bool) -> Self {
+        self.mask_internal_errors = Some(mask);
+        self
+    }
+
+    /// Override whether logs are written as JSON lines
+    pub fn json_logs(mut self, json: bool) -> Self {
+        self.json_logs = Some(json);
+        self
+    }
+
+    /// Override whether CORS defaults are restrictive
+    pub fn strict_cors(mut self, strict: bool) -> Self {
+        self.strict_cors = Some(strict);
+        self
+    }
+
+    /// Override whether replay layers may record traffic
+    pub fn replay_recording(mut self, enabled: bool) -> Self {
+        self.replay_recording = Some(enabled);
+        self
+    }
+
+    /// The profile `build` installs, if any setting was chosen
+    fn resolve_profile(&self) -> Option<crate::Profile> {
+        let overridden = self.environment.is_some()
+            || self.docs_enabled.is_some()
+            || self.mask_internal_errors.is_some()
+            || self.json_logs.is_some()
+            || self.strict_cors.is_some()
+            || self.replay_recording.is_some();
+        if !overridden {
+            return None;
+        }
+
+        let mut profile = match self.environment {
+            Some(environment) => crate::Profile::for_environment(environment),
+            None => crate::Profile::current(),
+        };
+        if let Some(mask) = self.mask_internal_errors {
+            profile = profile.mask_internal_errors(mask);
+        }
+        if let Some(json) = self.json_logs {
+            profile = profile.json_logs(json);
+        }
+        if let Some(strict) = self.strict_cors {
+            profile = profile.strict_cors(strict);
+        }
+        if let Some(enabled) = self.replay_recording {
+            profile = profile.replay_recording(enabled);
+        }
+        if let Some(enabled) = self.docs_enabled {
+            profile = profile.expose_docs(enabled);
+        }
+        Some(profile)
+    }
+
     /// Set OpenAPI info
     pub fn openapi_info(
         mut self,
@@ -62,6 +150,24 @@ impl RustApiConfig {
         self
     }
 
+    /// Choose what `run` does with duplicate routes (default: refuse to start)
+    pub fn on_duplicate_route(mut self, policy: crate::route_table::DuplicateRoutePolicy) -> Self {
+        self.duplicate_route_policy = policy;
+        self
+    }
+
+    /// Generate operation ids for routes without an explicit one
+    pub fn operation_ids(mut self, strategy: crate::operation_id::OperationIdStrategy) -> Self {
+        self.operation_id_strategy = Some(strategy);
+        self
+    }
+
+    /// Report addresses, features, middleware and routes at startup
+    pub fn startup_report(mut self, enabled: bool) -> Self {
+        self.startup_report = enabled;
+        self
+    }
+
     /// Add a middleware layer
     pub fn layer<L>(mut self, layer: L) -> Self
     where
@@ -73,7 +179,18 @@ impl RustApiConfig {
 
     /// Build the RustApi instance
     pub fn build(self) -> RustApi {
-        let mut app = RustApi::new().mount_auto_routes_grouped();
+        let profile = self.resolve_profile();
+        if let Some(profile) = profile {
+            profile.install();
+        }
+
+        let mut app = RustApi::new()
+            .on_duplicate_route(self.duplicate_route_policy)
+            .startup_report(self.startup_report);
+        if let Some(strategy) = self.operation_id_strategy {
+            app = app.operation_ids(strategy);
+        }
+        let mut app = app.mount_auto_routes_grouped();
 
         // Apply configuration
         if let Some(limit) = self.body_limit {
@@ -87,7 +204,7 @@ impl RustApiConfig {
         );
 
         #[cfg(feature = "swagger-ui")]
-        if self.docs_enabled {
+        if profile.unwrap_or_else(crate::Profile::current).expose_docs {
             if let Some(path) = self.docs_path {
                 app = app.docs(&path);
             }
@@ -29,6 +29,7 @@ impl RequestDispatcher {
     /// This replicates the logic used by the normal HTTP server.
     pub async fn dispatch(&self, request: Request) -> Response {
         let req = self.interceptors.intercept_request(request);
+        let accept = req.headers().get(http::header::ACCEPT).cloned();
 
         let path = req.path().to_owned();
         let method = req.method().clone();
@@ -50,6 +51,7 @@ impl RequestDispatcher {
             self.layers.execute(req, routing_handler).await
         };
 
-        self.interceptors.intercept_response(response)
+        let response = self.interceptors.intercept_response(response);
+        crate::error_format::negotiate_error_response(accept.as_ref(), response)
     }
 }
@@ -49,6 +49,15 @@ impl RustApi {
         self
     }
 
+    /// Answer `503` on all routes except the health endpoints until `gate` opens
+    ///
+    /// The gate's checks start running when the server starts. While it is
+    /// closed, the readiness endpoint reports the pending checks.
+    pub fn readiness_gate(mut self, gate: crate::health::ReadinessGate) -> Self {
+        self.readiness_gate = Some(gate);
+        self
+    }
+
     /// Apply a one-call production baseline preset.
     ///
     /// This enables:
@@ -118,9 +127,16 @@ impl RustApi {
 
             let readiness_handler = {
                 let health_check = health_check.clone();
+                let gate = self.readiness_gate.clone();
                 move || {
                     let health_check = health_check.clone();
-                    async move { crate::health::readiness_response(health_check).await }
+                    let gate = gate.clone();
+                    async move {
+                        match gate {
+                            Some(gate) if !gate.is_open() => gate.closed_response(),
+                            _ => crate::health::readiness_response(health_check).await,
+                        }
+                    }
                 }
             };
 
@@ -133,4 +149,23 @@ impl RustApi {
                 .route(&liveness_path, get(liveness_handler));
         }
     }
+
+    /// Start the readiness checks and hold back traffic until they pass
+    pub(super) fn apply_readiness_gate(&mut self) {
+        if let Some(gate) = &self.readiness_gate {
+            let health_paths = self
+                .health_endpoint_config
+                .as_ref()
+                .map(|config| {
+                    vec![
+                        config.health_path.clone(),
+                        config.readiness_path.clone(),
+                        config.liveness_path.clone(),
+                    ]
+                })
+                .unwrap_or_default();
+            self.layers.prepend(Box::new(gate.layer(health_paths)));
+            tokio::spawn(gate.clone().wait_for_checks());
+        }
+    }
 }
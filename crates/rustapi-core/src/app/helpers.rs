@@ -22,7 +22,6 @@ pub(super) fn openapi_tags_for_route(
     tags.into_iter().collect()
 }
 
-#[cfg(feature = "dashboard")]
 pub(super) fn operation_for_method<'a>(
     path_item: &'a rustapi_openapi::PathItem,
     method: &http::Method,
@@ -114,6 +113,8 @@ pub(super) fn add_path_params_to_operation(
             required: true,
             description: None,
             deprecated: None,
+            style: None,
+            explode: None,
             schema: Some(schema),
         });
     }
@@ -23,6 +23,58 @@ impl RustApi {
         &self.openapi_spec
     }
 
+    /// Generate OpenAPI operation ids for routes without an explicit one
+    ///
+    /// Applies to routes registered before and after this call. Ids set with
+    /// `#[rustapi::operation_id]` or `Route::operation_id` are kept. Call it
+    /// before [`docs`](Self::docs), which serves the spec as it is at that point.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// RustApi::new()
+    ///     .operation_ids(OperationIdStrategy::HandlerName)
+    ///     .mount_route(route!(list_pins))
+    ///     .docs("/docs")
+    /// ```
+    pub fn operation_ids(mut self, strategy: crate::operation_id::OperationIdStrategy) -> Self {
+        self.operation_id_strategy = Some(strategy);
+
+        let method_routers = self.router.method_routers();
+        let mut routes = Vec::new();
+        for (matchit_path, info) in self.router.registered_routes() {
+            for method in &info.methods {
+                let handler = method_routers
+                    .get(matchit_path)
+                    .and_then(|mr| mr.handler_names.get(method).copied());
+                routes.push((info.path.clone(), method.clone(), handler));
+            }
+        }
+        // Registration order is lost; sort so duplicate ids resolve the same way every run
+        routes.sort_by(|a, b| (&a.0, a.1.as_str()).cmp(&(&b.0, b.1.as_str())));
+        for (path, method, handler) in routes {
+            self.assign_operation_id(&path, &method, handler);
+        }
+        self
+    }
+
+    /// Give a registered operation an id from the configured strategy
+    pub(super) fn assign_operation_id(
+        &mut self,
+        path: &str,
+        method: &http::Method,
+        handler: Option<&str>,
+    ) {
+        if let Some(strategy) = &self.operation_id_strategy {
+            let route = crate::operation_id::OperationIdContext {
+                method: method.as_str(),
+                path,
+                handler,
+            };
+            crate::operation_id::assign(&mut self.openapi_spec, strategy, &route);
+        }
+    }
+
     /// If RUSTAPI_DUMP_OPENAPI=1 (or true), print the generated OpenAPI spec as JSON
     /// to stdout and exit immediately. Used by `cargo rustapi mcp generate` to
     /// extract the spec without needing a running HTTP server.
@@ -40,6 +92,82 @@ impl RustApi {
             }
         }
     }
+
+    /// Register an error code produced by hand-written `ApiError::new(...)` calls.
+    ///
+    /// Codes from the built-in constructors and from `#[derive(ApiError)]` enums
+    /// are registered automatically. The OpenAPI `ApiErrorCode` schema is updated.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// RustApi::new()
+    ///     .error_code("quota_exceeded", 429, "The account exceeded its request quota")
+    /// ```
+    pub fn error_code(mut self, code: &str, status: u16, description: &str) -> Self {
+        self.error_catalog
+            .insert(crate::error_catalog::ErrorCodeEntry::new(
+                code.to_string(),
+                status,
+                description.to_string(),
+            ));
+        self.error_catalog.apply_to_openapi(&mut self.openapi_spec);
+        self
+    }
+
+    /// Get the current error code catalog.
+    pub fn error_codes(&self) -> &crate::error_catalog::ErrorCatalog {
+        &self.error_catalog
+    }
+
+    /// Serve the error code catalog as JSON at the given path.
+    ///
+    /// Like [`docs`](Self::docs), the catalog is captured when this method is
+    /// called, so register custom codes with [`error_code`](Self::error_code) first.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// RustApi::auto()
+    ///     .error_catalog("/errors")
+    ///     .run("127.0.0.1:8080")
+    ///     .await
+    /// ```
+    pub fn error_catalog(self, path: &str) -> Self {
+        use crate::router::get;
+
+        let catalog_json = serde_json::to_string_pretty(&self.error_catalog.to_json())
+            .unwrap_or_else(|e| {
+                tracing::error!("Failed to serialize error catalog: {}", e);
+                "{\"errors\":[]}".to_string()
+            });
+
+        let handler = move || {
+            let json = catalog_json.clone();
+            async move {
+                http::Response::builder()
+                    .status(http::StatusCode::OK)
+                    .header(http::header::CONTENT_TYPE, "application/json")
+                    .body(crate::response::Body::from(json))
+                    .unwrap()
+            }
+        };
+
+        self.route(path, get(handler))
+    }
+
+    /// The error code catalog as pretty JSON if RUSTAPI_DUMP_ERROR_CATALOG=1 (or
+    /// true) is set. Used by `cargo rustapi errors`; the `run` methods print it
+    /// and return without binding a listener.
+    pub(super) fn requested_error_catalog(&self) -> Option<String> {
+        let val = std::env::var("RUSTAPI_DUMP_ERROR_CATALOG").ok()?;
+        if !matches!(val.as_str(), "1" | "true" | "yes") {
+            return None;
+        }
+        let json = self.error_catalog.to_json();
+        Some(serde_json::to_string_pretty(&json).unwrap_or_else(|_| json.to_string()))
+    }
+
     #[cfg(feature = "swagger-ui")]
     pub fn docs(self, path: &str) -> Self {
         let title = self.openapi_spec.info.title.clone();
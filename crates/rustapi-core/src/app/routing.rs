@@ -1,7 +1,10 @@
-use super::helpers::{add_path_params_to_operation, normalize_prefix_for_openapi};
+use super::helpers::{
+    add_path_params_to_operation, normalize_prefix_for_openapi, operation_for_method,
+};
 use super::types::RustApi;
 use crate::response::IntoResponse;
-use crate::router::{MethodRouter, Router};
+use crate::route_table::{DuplicateRoute, RouteOrigin};
+use crate::router::{convert_path_params, normalize_path_for_comparison, MethodRouter, Router};
 use std::collections::BTreeMap;
 
 impl RustApi {
@@ -38,28 +41,44 @@ impl RustApi {
                 path: route_path,
                 method,
                 handler,
+                handler_name,
                 operation,
                 component_registrar,
+                layers,
+                documented,
+                location,
                 ..
             } = route;
 
-            let method_enum = match method {
-                "GET" => http::Method::GET,
-                "POST" => http::Method::POST,
-                "PUT" => http::Method::PUT,
-                "DELETE" => http::Method::DELETE,
-                "PATCH" => http::Method::PATCH,
-                _ => http::Method::GET,
-            };
-
             let path = if route_path.starts_with('/') {
                 route_path.to_string()
             } else {
                 format!("/{}", route_path)
             };
 
+            let methods = crate::handler::route_methods(method);
+            let origin = RouteOrigin {
+                handler: handler_name,
+                location,
+            };
+            if !self.claim_route(&path, &methods, origin) {
+                continue;
+            }
+
+            let handler = crate::handler::layered_handler(handler, layers);
             let entry = by_path.entry(path).or_default();
-            entry.insert_boxed_with_operation(method_enum, handler, operation, component_registrar);
+            for method_enum in methods {
+                entry.insert_boxed_with_operation(
+                    method_enum.clone(),
+                    handler.clone(),
+                    handler_name,
+                    operation.clone(),
+                    component_registrar,
+                );
+                if !documented {
+                    entry.operations.remove(&method_enum);
+                }
+            }
         }
 
         #[cfg(feature = "tracing")]
@@ -68,7 +87,7 @@ impl RustApi {
         let path_count = by_path.len();
 
         for (path, method_router) in by_path {
-            self = self.route(&path, method_router);
+            self = self.add_route(&path, method_router);
         }
 
         crate::trace_info!(
@@ -93,7 +112,17 @@ impl RustApi {
     ///     .route("/users", get(list_users).post(create_user))
     ///     .route("/users/{id}", get(get_user).delete(delete_user))
     /// ```
+    #[track_caller]
     pub fn route(mut self, path: &str, method_router: MethodRouter) -> Self {
+        let origins = method_origins(&method_router, std::panic::Location::caller());
+        if !self.claim_route_origins(path, &origins) {
+            return self;
+        }
+        self.add_route(path, method_router)
+    }
+
+    /// Register a route whose methods were already claimed
+    fn add_route(mut self, path: &str, method_router: MethodRouter) -> Self {
         for register_components in &method_router.component_registrars {
             register_components(&mut self.openapi_spec);
         }
@@ -102,7 +131,10 @@ impl RustApi {
         for (method, op) in &method_router.operations {
             let mut op = op.clone();
             add_path_params_to_operation(path, &mut op, &BTreeMap::new());
+            crate::authz::register_security_scheme(&mut self.openapi_spec, &op);
             self.openapi_spec = self.openapi_spec.path(path, method.as_str(), op);
+            let handler = method_router.handler_names.get(method).copied();
+            self.assign_operation_id(path, method, handler);
         }
 
         self.router = self.router.route(path, method_router);
@@ -110,6 +142,7 @@ impl RustApi {
     }
 
     /// Add a typed route
+    #[track_caller]
     pub fn typed<P: crate::typed_path::TypedPath>(self, method_router: MethodRouter) -> Self {
         self.route(P::PATH, method_router)
     }
@@ -118,6 +151,7 @@ impl RustApi {
     ///
     /// Alias for `.route(path, method_router)` for a single handler.
     #[deprecated(note = "Use route() directly or mount_route() for macro-based routing")]
+    #[track_caller]
     pub fn mount(self, path: &str, method_router: MethodRouter) -> Self {
         self.route(path, method_router)
     }
@@ -140,31 +174,81 @@ impl RustApi {
     ///     .await
     /// ```
     pub fn mount_route(mut self, route: crate::handler::Route) -> Self {
-        let method_enum = match route.method {
-            "GET" => http::Method::GET,
-            "POST" => http::Method::POST,
-            "PUT" => http::Method::PUT,
-            "DELETE" => http::Method::DELETE,
-            "PATCH" => http::Method::PATCH,
-            _ => http::Method::GET,
+        let methods = crate::handler::route_methods(route.method);
+        let origin = RouteOrigin {
+            handler: route.handler_name,
+            location: route.location,
         };
+        if !self.claim_route(route.path, &methods, origin) {
+            return self;
+        }
+
+        if route.documented {
+            (route.component_registrar)(&mut self.openapi_spec);
+
+            // Register operation in OpenAPI spec
+            let mut op = route.operation;
+            add_path_params_to_operation(route.path, &mut op, &route.param_schemas);
+            crate::authz::register_security_scheme(&mut self.openapi_spec, &op);
+            for method in &methods {
+                self.openapi_spec = self
+                    .openapi_spec
+                    .path(route.path, method.as_str(), op.clone());
+                self.assign_operation_id(route.path, method, Some(route.handler_name));
+            }
+        }
 
-        (route.component_registrar)(&mut self.openapi_spec);
+        let handler = crate::handler::layered_handler(route.handler, route.layers);
+        self.route_with_methods(route.path, methods, handler, route.handler_name)
+    }
 
-        // Register operation in OpenAPI spec
-        let mut op = route.operation;
-        add_path_params_to_operation(route.path, &mut op, &route.param_schemas);
-        self.openapi_spec = self.openapi_spec.path(route.path, route.method, op);
+    /// Record where each method of a route was registered
+    ///
+    /// Returns `false`, recording the duplicates, if any of the methods is
+    /// already registered on an equivalent path.
+    fn claim_route(&mut self, path: &str, methods: &[http::Method], origin: RouteOrigin) -> bool {
+        let origins: Vec<_> = methods
+            .iter()
+            .map(|method| (method.clone(), origin))
+            .collect();
+        self.claim_route_origins(path, &origins)
+    }
 
-        self.route_with_method(route.path, method_enum, route.handler)
+    /// [`claim_route`](Self::claim_route) with a separate origin per method
+    fn claim_route_origins(&mut self, path: &str, origins: &[(http::Method, RouteOrigin)]) -> bool {
+        let normalized = normalize_path_for_comparison(&convert_path_params(path));
+        let duplicates: Vec<DuplicateRoute> = origins
+            .iter()
+            .filter_map(|(method, origin)| {
+                self.route_origins
+                    .get(&(method.to_string(), normalized.clone()))
+                    .map(|first| DuplicateRoute {
+                        method: method.to_string(),
+                        path: path.to_string(),
+                        first: *first,
+                        second: *origin,
+                    })
+            })
+            .collect();
+
+        if !duplicates.is_empty() {
+            self.duplicate_routes.extend(duplicates);
+            return false;
+        }
+        for (method, origin) in origins {
+            self.route_origins
+                .insert((method.to_string(), normalized.clone()), *origin);
+        }
+        true
     }
 
-    /// Helper to mount a single method handler
-    fn route_with_method(
+    /// Helper to mount one handler under one or more methods
+    fn route_with_methods(
         self,
         path: &str,
-        method: http::Method,
+        methods: Vec<http::Method>,
         handler: crate::handler::BoxedHandler,
+        handler_name: &'static str,
     ) -> Self {
         use crate::router::MethodRouter;
         // use http::Method; // Removed
@@ -190,10 +274,15 @@ impl RustApi {
         // Limitations: strictly sequential mounting for now.
 
         let mut handlers = std::collections::HashMap::new();
-        handlers.insert(method, handler);
+        for method in &methods {
+            handlers.insert(method.clone(), handler.clone());
+        }
 
-        let method_router = MethodRouter::from_boxed(handlers);
-        self.route(&path, method_router)
+        let mut method_router = MethodRouter::from_boxed(handlers);
+        for method in methods {
+            method_router.handler_names.insert(method, handler_name);
+        }
+        self.add_route(&path, method_router)
     }
 
     /// Nest a router under a prefix
@@ -211,10 +300,34 @@ impl RustApi {
     /// RustApi::new()
     ///     .nest("/api/v1", api_v1)
     /// ```
-    pub fn nest(mut self, prefix: &str, router: Router) -> Self {
+    #[track_caller]
+    pub fn nest(mut self, prefix: &str, mut router: Router) -> Self {
         // Normalize the prefix for OpenAPI paths
         let normalized_prefix = normalize_prefix_for_openapi(prefix);
 
+        // Drop nested routes that duplicate an existing method and path
+        let location = std::panic::Location::caller();
+        let mut nested: Vec<(String, String)> = router
+            .registered_routes()
+            .iter()
+            .map(|(matchit_path, info)| (matchit_path.clone(), info.path.clone()))
+            .collect();
+        nested.sort();
+        for (matchit_path, display_path) in nested {
+            let prefixed_path = if display_path == "/" {
+                normalized_prefix.clone()
+            } else {
+                format!("{}{}", normalized_prefix, display_path)
+            };
+            let Some(method_router) = router.method_routers().get(&matchit_path) else {
+                continue;
+            };
+            let origins = method_origins(method_router, location);
+            if !self.claim_route_origins(&prefixed_path, &origins) {
+                router.remove_route(&matchit_path);
+            }
+        }
+
         // Propagate OpenAPI operations from nested router with prefixed paths
         // We need to do this before calling router.nest() because it consumes the router
         for (matchit_path, method_router) in router.method_routers() {
@@ -241,6 +354,8 @@ impl RustApi {
                 let mut op = op.clone();
                 add_path_params_to_operation(&prefixed_path, &mut op, &BTreeMap::new());
                 self.openapi_spec = self.openapi_spec.path(&prefixed_path, method.as_str(), op);
+                let handler = method_router.handler_names.get(method).copied();
+                self.assign_operation_id(&prefixed_path, method, handler);
             }
         }
 
@@ -263,6 +378,7 @@ impl RustApi {
     ///
     /// - Automatic MIME type detection
     /// - ETag and Last-Modified headers for caching
+    /// - Precompressed `.br`/`.gz` siblings (opt-in via `StaticFileConfig::precompressed`)
     /// - Index file serving for directories
     /// - Path traversal prevention
     ///
@@ -309,11 +425,22 @@ impl RustApi {
             std::sync::Arc::new(move |req: crate::Request| {
                 let config = config.clone();
                 let path = req.uri().path().to_string();
+                let accept_encoding = req
+                    .headers()
+                    .get(http::header::ACCEPT_ENCODING)
+                    .and_then(|v| v.to_str().ok())
+                    .map(str::to_string);
 
                 Box::pin(async move {
                     let relative_path = path.strip_prefix(&config.prefix).unwrap_or(&path);
 
-                    match crate::static_files::StaticFile::serve(relative_path, &config).await {
+                    match crate::static_files::StaticFile::serve_encoded(
+                        relative_path,
+                        &config,
+                        accept_encoding.as_deref(),
+                    )
+                    .await
+                    {
                         Ok(response) => response,
                         Err(err) => err.into_response(),
                     }
@@ -368,4 +495,87 @@ impl RustApi {
     pub fn compression_with_config(self, config: crate::middleware::CompressionConfig) -> Self {
         self.layer(crate::middleware::CompressionLayer::with_config(config))
     }
+
+    /// The routes registered so far, with their handlers, OpenAPI tags and
+    /// the middleware stack.
+    ///
+    /// Routes added when the server starts (health endpoints, status page,
+    /// dashboard) are not included yet; `cargo rustapi routes` reports those
+    /// too.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// let table = app.route_table();
+    /// for route in table.routes() {
+    ///     println!("{} {}", route.method, route.path);
+    /// }
+    /// ```
+    pub fn route_table(&self) -> crate::route_table::RouteTable {
+        use crate::route_table::{RouteTable, RouteTableEntry};
+
+        let method_routers = self.router.method_routers();
+        let mut routes = Vec::new();
+        for (matchit_path, info) in self.router.registered_routes() {
+            let method_router = method_routers.get(matchit_path);
+            for method in &info.methods {
+                let operation = self
+                    .openapi_spec
+                    .paths
+                    .get(&info.path)
+                    .and_then(|item| operation_for_method(item, method))
+                    .or_else(|| method_router.and_then(|mr| mr.operations.get(method)));
+                routes.push(RouteTableEntry {
+                    method: method.to_string(),
+                    path: info.path.clone(),
+                    handler: method_router
+                        .and_then(|mr| mr.handler_names.get(method))
+                        .map(|name| name.to_string()),
+                    tags: operation.map(|op| op.tags.clone()).unwrap_or_default(),
+                    operation_id: operation.and_then(|op| op.operation_id.clone()),
+                });
+            }
+        }
+
+        let middleware = self
+            .layers
+            .names()
+            .into_iter()
+            .map(str::to_string)
+            .collect();
+        RouteTable::new(routes, middleware)
+    }
+
+    /// The route table as pretty JSON if RUSTAPI_DUMP_ROUTES=1 (or true) is
+    /// set. Used by `cargo rustapi routes`; the `run` methods print it and
+    /// return without binding a listener.
+    pub(super) fn requested_route_dump(&self) -> Option<String> {
+        let val = std::env::var("RUSTAPI_DUMP_ROUTES").ok()?;
+        if !matches!(val.as_str(), "1" | "true" | "yes") {
+            return None;
+        }
+        let json = self.route_table().to_json();
+        Some(serde_json::to_string_pretty(&json).unwrap_or_else(|_| json.to_string()))
+    }
+}
+
+/// The origin of each handler of a method router, in method order
+fn method_origins(
+    method_router: &MethodRouter,
+    location: &'static std::panic::Location<'static>,
+) -> Vec<(http::Method, RouteOrigin)> {
+    let mut origins: Vec<_> = method_router
+        .handlers
+        .keys()
+        .map(|method| {
+            let handler = method_router
+                .handler_names
+                .get(method)
+                .copied()
+                .unwrap_or("<unnamed>");
+            (method.clone(), RouteOrigin { handler, location })
+        })
+        .collect();
+    origins.sort_by(|(a, _), (b, _)| a.as_str().cmp(b.as_str()));
+    origins
 }
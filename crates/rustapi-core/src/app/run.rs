@@ -9,8 +9,21 @@ use crate::response::IntoResponse;
 use crate::server::Server;
 
 impl RustApi {
-    async fn prepare_for_serve(&mut self, addr: &str) {
+    /// Set the app up for serving on `addr`
+    ///
+    /// Returns `false` when only a dump of the error catalog or the route
+    /// table was requested; it has been printed and the app should not be
+    /// served.
+    async fn prepare_for_serve(
+        &mut self,
+        addr: &str,
+    ) -> Result<bool, crate::route_table::DuplicateRouteError> {
+        self.check_duplicate_routes()?;
         self.maybe_dump_openapi();
+        if let Some(catalog) = self.requested_error_catalog() {
+            println!("{}", catalog);
+            return Ok(false);
+        }
         self.print_hot_reload_banner(addr);
         self.apply_health_endpoints();
         self.apply_status_page();
@@ -19,9 +32,65 @@ impl RustApi {
         if let Some(limit) = self.body_limit {
             self.layers.prepend(Box::new(BodyLimitLayer::new(limit)));
         }
+        self.apply_readiness_gate();
+        if let Some(routes) = self.requested_route_dump() {
+            println!("{}", routes);
+            return Ok(false);
+        }
+        if self.startup_report {
+            self.collect_startup_report(&[addr])
+                .emit(crate::Profile::current().json_logs);
+        }
         for hook in std::mem::take(&mut self.lifecycle_hooks.on_start) {
             hook().await;
         }
+        Ok(true)
+    }
+
+    /// Fail, or warn, about handlers sharing a method and path
+    fn check_duplicate_routes(&self) -> Result<(), crate::route_table::DuplicateRouteError> {
+        use crate::route_table::{DuplicateRouteError, DuplicateRoutePolicy};
+
+        if self.duplicate_routes.is_empty() {
+            return Ok(());
+        }
+        match self.duplicate_route_policy {
+            DuplicateRoutePolicy::Error => Err(DuplicateRouteError {
+                duplicates: self.duplicate_routes.clone(),
+            }),
+            DuplicateRoutePolicy::Warn => {
+                for duplicate in &self.duplicate_routes {
+                    tracing::warn!(
+                        method = %duplicate.method,
+                        path = %duplicate.path,
+                        first = %duplicate.first,
+                        second = %duplicate.second,
+                        "Duplicate route; serving the first handler"
+                    );
+                }
+                Ok(())
+            }
+        }
+    }
+
+    /// The startup report of the app serving on `addresses`
+    pub(super) fn collect_startup_report(&self, addresses: &[&str]) -> crate::StartupReport {
+        let table = self.route_table();
+        let environment = if crate::Profile::current().environment.is_production() {
+            "production"
+        } else {
+            "development"
+        };
+        crate::StartupReport {
+            name: self.openapi_spec.info.title.clone(),
+            version: self.openapi_spec.info.version.clone(),
+            rustapi_version: env!("CARGO_PKG_VERSION").to_string(),
+            environment: environment.to_string(),
+            addresses: addresses.iter().map(|addr| addr.to_string()).collect(),
+            features: crate::startup::enabled_features(),
+            middleware: table.middleware().to_vec(),
+            routes: table.routes().to_vec(),
+        }
     }
 
     pub(super) fn print_hot_reload_banner(&self, addr: &str) -> Option<bool> {
@@ -199,15 +268,48 @@ impl RustApi {
     }
 
     pub async fn run(mut self, addr: &str) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
-        self.prepare_for_serve(addr).await;
+        if !self.prepare_for_serve(addr).await? {
+            return Ok(());
+        }
 
         let shutdown_hooks = std::mem::take(&mut self.lifecycle_hooks.on_shutdown);
-        let server = Server::new(self.router, self.layers, self.interceptors);
+        let server = Server::new(self.router, self.layers, self.interceptors)
+            .with_handover(self.handover.take());
         let result = server.run(addr).await;
         Self::run_shutdown_hooks(shutdown_hooks).await;
         result
     }
 
+    /// Prepare the app for serving and convert it into a hyper service
+    ///
+    /// Applies the same setup as [`run`](Self::run) (health endpoints, body
+    /// limit, `on_start` hooks, ...) without binding a listener. `addr` is
+    /// only used for startup logging. Call [`RustApiService::shutdown`] to
+    /// run the `on_shutdown` hooks.
+    ///
+    /// Fails on duplicate routes unless
+    /// [`on_duplicate_route`](Self::on_duplicate_route) allows them. Returns
+    /// `None` when only a dump of the error catalog or route table was
+    /// requested, like [`run`](Self::run) returns without serving.
+    ///
+    /// [`RustApiService::shutdown`]: crate::RustApiService::shutdown
+    pub async fn into_service(
+        mut self,
+        addr: &str,
+    ) -> Result<Option<crate::RustApiService>, crate::route_table::DuplicateRouteError> {
+        if !self.prepare_for_serve(addr).await? {
+            return Ok(None);
+        }
+
+        let shutdown_hooks = std::mem::take(&mut self.lifecycle_hooks.on_shutdown);
+        Ok(Some(crate::RustApiService::new(
+            self.router,
+            self.layers,
+            self.interceptors,
+            shutdown_hooks,
+        )))
+    }
+
     /// Run the server with graceful shutdown signal
     pub async fn run_with_shutdown<F>(
         mut self,
@@ -217,10 +319,13 @@ impl RustApi {
     where
         F: std::future::Future<Output = ()> + Send + 'static,
     {
-        self.prepare_for_serve(addr.as_ref()).await;
+        if !self.prepare_for_serve(addr.as_ref()).await? {
+            return Ok(());
+        }
 
         let shutdown_hooks = std::mem::take(&mut self.lifecycle_hooks.on_shutdown);
-        let server = Server::new(self.router, self.layers, self.interceptors);
+        let server = Server::new(self.router, self.layers, self.interceptors)
+            .with_handover(self.handover.take());
         server.run_with_shutdown(addr.as_ref(), signal).await?;
         Self::run_shutdown_hooks(shutdown_hooks).await;
         Ok(())
@@ -239,7 +344,9 @@ impl RustApi {
         use std::sync::Arc;
 
         let addr = config.socket_addr();
-        self.prepare_for_serve(&addr).await;
+        if !self.prepare_for_serve(&addr).await? {
+            return Ok(());
+        }
 
         let shutdown_hooks = std::mem::take(&mut self.lifecycle_hooks.on_shutdown);
         let server = crate::http3::Http3Server::new(
@@ -263,7 +370,9 @@ impl RustApi {
         use std::sync::Arc;
 
         let addr = config.socket_addr();
-        self.prepare_for_serve(&addr).await;
+        if !self.prepare_for_serve(&addr).await? {
+            return Ok(());
+        }
 
         let shutdown_hooks = std::mem::take(&mut self.lifecycle_hooks.on_shutdown);
         let server = crate::http3::Http3Server::new(
@@ -291,7 +400,9 @@ impl RustApi {
     {
         use std::sync::Arc;
 
-        self.prepare_for_serve(addr).await;
+        if !self.prepare_for_serve(addr).await? {
+            return Ok(());
+        }
 
         let shutdown_hooks = std::mem::take(&mut self.lifecycle_hooks.on_shutdown);
         let server = crate::http3::Http3Server::new_with_self_signed(
@@ -314,7 +425,9 @@ impl RustApi {
     ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
         use std::sync::Arc;
 
-        self.prepare_for_serve(addr).await;
+        if !self.prepare_for_serve(addr).await? {
+            return Ok(());
+        }
 
         let shutdown_hooks = std::mem::take(&mut self.lifecycle_hooks.on_shutdown);
         let server = crate::http3::Http3Server::new_with_self_signed(
@@ -363,7 +476,9 @@ impl RustApi {
         config.port = http_socket.port();
         let http_addr = http_socket.to_string();
 
-        self.prepare_for_serve(&http_addr).await;
+        if !self.prepare_for_serve(&http_addr).await? {
+            return Ok(());
+        }
 
         let shutdown_hooks = std::mem::take(&mut self.lifecycle_hooks.on_shutdown);
         let router = Arc::new(self.router);
@@ -426,7 +541,9 @@ impl RustApi {
         config.port = http_socket.port();
         let http_addr = http_socket.to_string();
 
-        self.prepare_for_serve(&http_addr).await;
+        if !self.prepare_for_serve(&http_addr).await? {
+            return Ok(());
+        }
 
         let shutdown_hooks = std::mem::take(&mut self.lifecycle_hooks.on_shutdown);
         let router = Arc::new(self.router);
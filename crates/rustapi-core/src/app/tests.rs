@@ -639,6 +639,77 @@ fn test_openapi_spec_empty_without_routes() {
     );
 }
 
+#[test]
+fn test_route_table_lists_handlers_tags_and_middleware() {
+    async fn list_users() -> &'static str {
+        "list users"
+    }
+    async fn create_user() -> &'static str {
+        "create user"
+    }
+    async fn get_item() -> &'static str {
+        "get item"
+    }
+
+    let users = get(list_users)
+        .post(create_user)
+        .operation(Method::GET, |op| op.tags.push("users".to_string()));
+    let app = RustApi::new()
+        .layer(crate::middleware::RequestIdLayer::new())
+        .route("/users", users)
+        .nest("/api", Router::new().route("/items/{id}", get(get_item)));
+
+    let table = app.route_table();
+    let routes: Vec<(&str, &str)> = table
+        .routes()
+        .iter()
+        .map(|r| (r.method.as_str(), r.path.as_str()))
+        .collect();
+    assert_eq!(
+        routes,
+        vec![
+            ("GET", "/api/items/{id}"),
+            ("GET", "/users"),
+            ("POST", "/users")
+        ]
+    );
+
+    let list = table.get("GET", "/users").unwrap();
+    assert!(list.handler.as_deref().unwrap().ends_with("list_users"));
+    assert_eq!(list.tags, vec!["users".to_string()]);
+    assert!(table
+        .get("GET", "/api/items/{id}")
+        .unwrap()
+        .handler
+        .as_deref()
+        .unwrap()
+        .ends_with("get_item"));
+
+    assert_eq!(table.middleware().len(), 1);
+    assert!(table.middleware()[0].ends_with("RequestIdLayer"));
+}
+
+#[test]
+fn test_startup_report_describes_the_served_app() {
+    async fn list_orders() -> &'static str {
+        "orders"
+    }
+
+    let app = RustApi::new()
+        .openapi_info("orders", "2.0.0", None)
+        .layer(crate::middleware::RequestIdLayer::new())
+        .route("/orders", get(list_orders));
+
+    let report = app.collect_startup_report(&["127.0.0.1:3000"]);
+    assert_eq!(report.name, "orders");
+    assert_eq!(report.version, "2.0.0");
+    assert_eq!(report.addresses, vec!["127.0.0.1:3000".to_string()]);
+    assert!(report.features.contains(&"tracing".to_string()));
+    assert!(report.middleware[0].ends_with("RequestIdLayer"));
+    assert_eq!(report.routes.len(), 1);
+    assert!(report.pretty().contains("GET     /orders  list_orders"));
+}
+
 /// Unit test: Verify RustApi::nest delegates correctly to Router::nest
 ///
 /// **Feature: router-nesting, Property 13: RustApi Integration**
@@ -6,16 +6,26 @@ use crate::router::Router;
 pub struct RustApi {
     pub(super) router: Router,
     pub(super) openapi_spec: rustapi_openapi::OpenApiSpec,
+    pub(super) error_catalog: crate::error_catalog::ErrorCatalog,
     pub(super) layers: LayerStack,
     pub(super) body_limit: Option<usize>,
     pub(super) interceptors: InterceptorChain,
     pub(super) lifecycle_hooks: LifecycleHooks,
     pub(super) hot_reload: bool,
+    pub(super) startup_report: bool,
+    pub(super) handover: Option<crate::handover::Handover>,
     #[cfg(feature = "http3")]
     pub(super) http3_config: Option<crate::http3::Http3Config>,
     pub(super) health_check: Option<crate::health::HealthCheck>,
     pub(super) health_endpoint_config: Option<crate::health::HealthEndpointConfig>,
+    pub(super) readiness_gate: Option<crate::health::ReadinessGate>,
     pub(super) status_config: Option<crate::status::StatusConfig>,
     #[cfg(feature = "dashboard")]
     pub(super) dashboard_config: Option<crate::dashboard::DashboardConfig>,
+    /// Origin of each `(method, normalized path)` mounted from a `Route`
+    pub(super) route_origins:
+        std::collections::HashMap<(String, String), crate::route_table::RouteOrigin>,
+    pub(super) duplicate_routes: Vec<crate::route_table::DuplicateRoute>,
+    pub(super) duplicate_route_policy: crate::route_table::DuplicateRoutePolicy,
+    pub(super) operation_id_strategy: Option<crate::operation_id::OperationIdStrategy>,
 }
@@ -0,0 +1,288 @@
+//! Role and scope requirements
+//!
+//! Authentication layers record what the caller may do as [`Grants`] in the
+//! request extensions (`JwtLayer` does this from the token's claims).
+//! [`RequireGrant`] checks them before the handler runs:
+//!
+//! - no grants at all: `401 Unauthorized`
+//! - grants without the required role or scope: `403 Forbidden`
+//!
+//! [`Route::require_role`](crate::Route::require_role) and
+//! [`Route::require_scope`](crate::Route::require_scope) add the layer and
+//! document the requirement in the route's OpenAPI `security` section.
+//! `#[rustapi_rs::require_role("admin")]` and
+//! `#[rustapi_rs::require_scope("pins:write")]` expand to those calls.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! #[rustapi_rs::delete("/pins/{id}")]
+//! #[rustapi_rs::require_scope("pins:write")]
+//! async fn delete_pin(Path(id): Path<u64>) -> NoContent {
+//!     NoContent
+//! }
+//! ```
+
+use crate::error::{ApiError, Result};
+use crate::extract::FromRequestParts;
+use crate::middleware::{BoxedNext, MiddlewareLayer};
+use crate::request::Request;
+use crate::response::{IntoResponse, Response};
+use rustapi_openapi::{OpenApiSpec, Operation, OperationModifier, SecurityScheme};
+use std::future::Future;
+use std::pin::Pin;
+
+/// Security scheme that role and scope requirements refer to in OpenAPI
+///
+/// Registered as HTTP bearer (JWT) authentication unless the spec already
+/// defines a scheme with this name.
+pub const DEFAULT_SECURITY_SCHEME: &str = "bearerAuth";
+
+/// Roles and scopes granted to the caller of a request
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Grants {
+    roles: Vec<String>,
+    scopes: Vec<String>,
+}
+
+impl Grants {
+    /// No roles and no scopes
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Read grants from token claims
+    ///
+    /// Roles come from `role` (a string) and `roles` (an array). Scopes come
+    /// from `scope` (space-separated, as in OAuth 2.0), `scp`, `scopes` and
+    /// `permissions` (arrays or space-separated strings).
+    pub fn from_claims(claims: &serde_json::Value) -> Self {
+        let mut grants = Self::new();
+        for key in ["role", "roles"] {
+            grants.roles.extend(strings(&claims[key]));
+        }
+        for key in ["scope", "scp", "scopes", "permissions"] {
+            grants.scopes.extend(strings(&claims[key]));
+        }
+        grants
+    }
+
+    /// Add a role
+    pub fn role(mut self, role: impl Into<String>) -> Self {
+        self.roles.push(role.into());
+        self
+    }
+
+    /// Add a scope
+    pub fn scope(mut self, scope: impl Into<String>) -> Self {
+        self.scopes.push(scope.into());
+        self
+    }
+
+    /// Granted roles
+    pub fn roles(&self) -> &[String] {
+        &self.roles
+    }
+
+    /// Granted scopes
+    pub fn scopes(&self) -> &[String] {
+        &self.scopes
+    }
+
+    /// Whether the role is granted
+    pub fn has_role(&self, role: &str) -> bool {
+        self.roles.iter().any(|r| r == role)
+    }
+
+    /// Whether the scope is granted
+    pub fn has_scope(&self, scope: &str) -> bool {
+        self.scopes.iter().any(|s| s == scope)
+    }
+}
+
+/// Claims of the authenticated token, as JSON
+///
+/// Stored in the request extensions next to [`Grants`] by token-based
+/// authentication layers such as `JwtLayer`, for code that needs claims
+/// without knowing the application's claims type (e.g.
+/// [`TenantLayer::from_claim`](crate::tenant::TenantLayer::from_claim)).
+#[derive(Debug, Clone, PartialEq)]
+pub struct TokenClaims(pub serde_json::Value);
+
+impl TokenClaims {
+    /// A top-level claim
+    pub fn get(&self, name: &str) -> Option<&serde_json::Value> {
+        self.0.get(name)
+    }
+}
+
+/// Strings of a claim that is a string (split on spaces) or an array
+fn strings(value: &serde_json::Value) -> Vec<String> {
+    match value {
+        serde_json::Value::String(s) => s.split_whitespace().map(str::to_string).collect(),
+        serde_json::Value::Array(items) => items
+            .iter()
+            .filter_map(|item| item.as_str().map(str::to_string))
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+impl FromRequestParts for Grants {
+    fn from_request_parts(req: &Request) -> Result<Self> {
+        req.extensions()
+            .get::<Grants>()
+            .cloned()
+            .ok_or_else(|| ApiError::unauthorized("Authentication required"))
+    }
+}
+
+impl OperationModifier for Grants {
+    fn update_operation(_op: &mut Operation) {}
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Requirement {
+    Role(String),
+    Scope(String),
+}
+
+/// Middleware rejecting requests whose [`Grants`] lack a role or scope
+#[derive(Debug, Clone)]
+pub struct RequireGrant {
+    requirement: Requirement,
+}
+
+impl RequireGrant {
+    /// Require a role
+    pub fn role(role: impl Into<String>) -> Self {
+        Self {
+            requirement: Requirement::Role(role.into()),
+        }
+    }
+
+    /// Require a scope
+    pub fn scope(scope: impl Into<String>) -> Self {
+        Self {
+            requirement: Requirement::Scope(scope.into()),
+        }
+    }
+
+    /// The role or scope name
+    pub fn name(&self) -> &str {
+        match &self.requirement {
+            Requirement::Role(name) | Requirement::Scope(name) => name,
+        }
+    }
+
+    fn check(&self, grants: Option<&Grants>) -> Result<()> {
+        let grants = grants.ok_or_else(|| ApiError::unauthorized("Authentication required"))?;
+        match &self.requirement {
+            Requirement::Role(role) if !grants.has_role(role) => {
+                Err(ApiError::forbidden(format!("Required role: {}", role)))
+            }
+            Requirement::Scope(scope) if !grants.has_scope(scope) => {
+                Err(ApiError::forbidden(format!("Required scope: {}", scope)))
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+impl MiddlewareLayer for RequireGrant {
+    fn call(
+        &self,
+        req: Request,
+        next: BoxedNext,
+    ) -> Pin<Box<dyn Future<Output = Response> + Send + 'static>> {
+        let checked = self.check(req.extensions().get::<Grants>());
+        Box::pin(async move {
+            match checked {
+                Ok(()) => next(req).await,
+                Err(err) => err.into_response(),
+            }
+        })
+    }
+
+    fn clone_box(&self) -> Box<dyn MiddlewareLayer> {
+        Box::new(self.clone())
+    }
+}
+
+/// Add `name` to the operation's requirement for [`DEFAULT_SECURITY_SCHEME`]
+pub(crate) fn document_requirement(op: &mut Operation, name: &str) {
+    if op.security.is_empty() {
+        op.security.push(Default::default());
+    }
+    for requirement in &mut op.security {
+        let names = requirement
+            .entry(DEFAULT_SECURITY_SCHEME.to_string())
+            .or_default();
+        if !names.iter().any(|n| n == name) {
+            names.push(name.to_string());
+        }
+    }
+}
+
+/// Define [`DEFAULT_SECURITY_SCHEME`] if an operation refers to it
+pub(crate) fn register_security_scheme(spec: &mut OpenApiSpec, op: &Operation) {
+    let referenced = op
+        .security
+        .iter()
+        .any(|requirement| requirement.contains_key(DEFAULT_SECURITY_SCHEME));
+    if referenced {
+        spec.components
+            .get_or_insert_with(Default::default)
+            .security_schemes
+            .entry(DEFAULT_SECURITY_SCHEME.to_string())
+            .or_insert_with(|| SecurityScheme::Http {
+                scheme: "bearer".to_string(),
+                bearer_format: Some("JWT".to_string()),
+                description: None,
+            });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use http::StatusCode;
+
+    #[test]
+    fn grants_from_claims() {
+        let grants = Grants::from_claims(&serde_json::json!({
+            "sub": "42",
+            "role": "admin",
+            "roles": ["editor"],
+            "scope": "pins:read pins:write",
+            "permissions": ["users.read"],
+        }));
+        assert!(grants.has_role("admin"));
+        assert!(grants.has_role("editor"));
+        assert!(grants.has_scope("pins:write"));
+        assert!(grants.has_scope("users.read"));
+        assert!(!grants.has_scope("admin"));
+    }
+
+    #[test]
+    fn missing_grants_are_401_and_missing_roles_403() {
+        let require = RequireGrant::role("admin");
+        assert_eq!(
+            require.check(None).unwrap_err().status,
+            StatusCode::UNAUTHORIZED
+        );
+        assert_eq!(
+            require
+                .check(Some(&Grants::new().role("user")))
+                .unwrap_err()
+                .status,
+            StatusCode::FORBIDDEN
+        );
+        assert!(require.check(Some(&Grants::new().role("admin"))).is_ok());
+
+        let require = RequireGrant::scope("pins:write");
+        assert!(require
+            .check(Some(&Grants::new().scope("pins:write")))
+            .is_ok());
+    }
+}
@@ -0,0 +1,391 @@
+//! Canary routing
+//!
+//! A [`Canary`] splits the traffic of a route between two handlers: the
+//! stable one and a candidate, typically a rewrite. A share of requests goes
+//! to the candidate, and the share can be raised as confidence grows.
+//!
+//! Each request is routed to one arm:
+//!
+//! 1. by the override header, if [`Canary::header`] is set (`canary`, `1` or
+//!    `true` picks the candidate; `stable`, `0` or `false` the stable arm)
+//! 2. by the value of the sticky header, if [`Canary::sticky`] is set, so a
+//!    client (e.g. by user ID) keeps getting the same arm
+//! 3. otherwise spread evenly by [`Canary::percent`]
+//!
+//! Every arm counts its requests, 5xx responses and time spent
+//! ([`Canary::stats`]). The chosen [`CanaryArm`] is added to the request and
+//! the response extensions; with the `metrics` feature, `MetricsLayer` reports
+//! it as `http_requests_by_canary_arm_total{canary, arm, status}`.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! let orders = Canary::new("orders-v2", get(list_orders), get(list_orders_v2))
+//!     .percent(5.0)
+//!     .header("X-Canary")
+//!     .sticky("X-User-Id");
+//!
+//! let app = RustApi::new().route("/orders", orders.clone().into());
+//!
+//! // Later, e.g. from an admin endpoint
+//! let stats = orders.stats();
+//! ```
+
+use crate::handler::BoxedHandler;
+use crate::request::Request;
+use crate::response::Response;
+use crate::router::MethodRouter;
+use std::collections::hash_map::DefaultHasher;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// Which handler of a [`Canary`] served a request
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Arm {
+    /// The current handler
+    Stable,
+    /// The handler being rolled out
+    Canary,
+}
+
+impl Arm {
+    /// Lowercase name, as used in metrics labels
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Arm::Stable => "stable",
+            Arm::Canary => "canary",
+        }
+    }
+}
+
+impl fmt::Display for Arm {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// The arm a request was routed to, stored in request and response extensions
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CanaryArm {
+    /// Name of the [`Canary`]
+    pub name: Arc<str>,
+    /// The chosen arm
+    pub arm: Arm,
+}
+
+/// Statistics of one arm of a [`Canary`]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ArmStats {
+    /// Requests routed to the arm
+    pub requests: u64,
+    /// Responses with a 5xx status
+    pub errors: u64,
+    /// Total time spent in the arm's handler
+    pub total_duration: Duration,
+}
+
+impl ArmStats {
+    /// Share of requests that ended with a 5xx status
+    pub fn error_rate(&self) -> f64 {
+        if self.requests == 0 {
+            0.0
+        } else {
+            self.errors as f64 / self.requests as f64
+        }
+    }
+
+    /// Average time spent in the handler
+    pub fn average_duration(&self) -> Duration {
+        self.total_duration
+            .checked_div(self.requests as u32)
+            .unwrap_or_default()
+    }
+}
+
+/// Snapshot of a [`Canary`]'s statistics
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CanaryStats {
+    /// The stable arm
+    pub stable: ArmStats,
+    /// The canary arm
+    pub canary: ArmStats,
+}
+
+#[derive(Default)]
+struct ArmCounters {
+    requests: AtomicU64,
+    errors: AtomicU64,
+    nanos: AtomicU64,
+}
+
+impl ArmCounters {
+    fn record(&self, response: &Response, elapsed: Duration) {
+        self.requests.fetch_add(1, Ordering::Relaxed);
+        if response.status().is_server_error() {
+            self.errors.fetch_add(1, Ordering::Relaxed);
+        }
+        self.nanos
+            .fetch_add(elapsed.as_nanos() as u64, Ordering::Relaxed);
+    }
+
+    fn snapshot(&self) -> ArmStats {
+        ArmStats {
+            requests: self.requests.load(Ordering::Relaxed),
+            errors: self.errors.load(Ordering::Relaxed),
+            total_duration: Duration::from_nanos(self.nanos.load(Ordering::Relaxed)),
+        }
+    }
+}
+
+#[derive(Default)]
+struct Counters {
+    seen: AtomicU64,
+    stable: ArmCounters,
+    canary: ArmCounters,
+}
+
+/// A route split between a stable and a canary handler
+///
+/// Turn it into a [`MethodRouter`] with `.into()` to register it. Clones
+/// share the statistics.
+#[derive(Clone)]
+pub struct Canary {
+    name: Arc<str>,
+    stable: MethodRouter,
+    canary: MethodRouter,
+    percent: f64,
+    header: Option<String>,
+    sticky: Option<String>,
+    counters: Arc<Counters>,
+}
+
+impl Canary {
+    /// Split a route between `stable` and `canary`
+    ///
+    /// `name` identifies the rollout in statistics and metrics. No traffic
+    /// goes to the canary until [`Canary::percent`] or [`Canary::header`] is
+    /// set. Methods only one of the routers handles are always served by it.
+    pub fn new(name: impl Into<String>, stable: MethodRouter, canary: MethodRouter) -> Self {
+        Self {
+            name: name.into().into(),
+            stable,
+            canary,
+            percent: 0.0,
+            header: None,
+            sticky: None,
+            counters: Arc::new(Counters::default()),
+        }
+    }
+
+    /// Share of requests sent to the canary, from 0 to 100
+    pub fn percent(mut self, percent: f64) -> Self {
+        self.percent = percent.clamp(0.0, 100.0);
+        self
+    }
+
+    /// Let a request header pick the arm, e.g. `X-Canary: 1`
+    pub fn header(mut self, name: impl Into<String>) -> Self {
+        self.header = Some(name.into());
+        self
+    }
+
+    /// Route requests with the same value of a header to the same arm
+    ///
+    /// Requests without the header are spread by [`Canary::percent`].
+    pub fn sticky(mut self, name: impl Into<String>) -> Self {
+        self.sticky = Some(name.into());
+        self
+    }
+
+    /// Name of the rollout
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Current statistics
+    pub fn stats(&self) -> CanaryStats {
+        CanaryStats {
+            stable: self.counters.stable.snapshot(),
+            canary: self.counters.canary.snapshot(),
+        }
+    }
+
+    /// The arm for a request
+    fn choose(&self, req: &Request) -> Arm {
+        let header = |name: &Option<String>| {
+            name.as_deref()
+                .and_then(|name| req.headers().get(name))
+                .and_then(|value| value.to_str().ok())
+        };
+
+        let forced = header(&self.header).and_then(|value| {
+            match value.trim().to_ascii_lowercase().as_str() {
+                "canary" | "1" | "true" => Some(Arm::Canary),
+                "stable" | "0" | "false" => Some(Arm::Stable),
+                _ => None,
+            }
+        });
+        if let Some(arm) = forced {
+            return arm;
+        }
+
+        let threshold = self.percent / 100.0;
+        let in_canary = match header(&self.sticky) {
+            Some(key) => {
+                let mut hasher = DefaultHasher::new();
+                (self.name.as_ref(), key).hash(&mut hasher);
+                (hasher.finish() % 10_000) as f64 / 10_000.0 < threshold
+            }
+            None => {
+                let n = self.counters.seen.fetch_add(1, Ordering::Relaxed) as f64;
+                ((n + 1.0) * threshold).floor() > (n * threshold).floor()
+            }
+        };
+        if in_canary {
+            Arm::Canary
+        } else {
+            Arm::Stable
+        }
+    }
+
+    /// Handler routing between the two arms
+    fn split(&self, stable: BoxedHandler, canary: BoxedHandler) -> BoxedHandler {
+        let this = self.clone();
+        Arc::new(move |mut req: Request| {
+            let arm = this.choose(&req);
+            let handler = match arm {
+                Arm::Stable => stable.clone(),
+                Arm::Canary => canary.clone(),
+            };
+            let counters = this.counters.clone();
+            let tag = CanaryArm {
+                name: this.name.clone(),
+                arm,
+            };
+            req.extensions_mut().insert(tag.clone());
+
+            Box::pin(async move {
+                let start = Instant::now();
+                let mut response = handler(req).await;
+                let arm_counters = match arm {
+                    Arm::Stable => &counters.stable,
+                    Arm::Canary => &counters.canary,
+                };
+                arm_counters.record(&response, start.elapsed());
+                response.extensions_mut().insert(tag);
+                response
+            })
+        })
+    }
+}
+
+impl From<Canary> for MethodRouter {
+    fn from(canary: Canary) -> Self {
+        let mut router = canary.stable.clone();
+        for (method, candidate) in &canary.canary.handlers {
+            let handler = match router.handlers.get(method) {
+                Some(stable) => canary.split(stable.clone(), candidate.clone()),
+                None => {
+                    if let Some(op) = canary.canary.operations.get(method) {
+                        router.operations.insert(method.clone(), op.clone());
+                    }
+                    if let Some(name) = canary.canary.handler_names.get(method) {
+                        router.handler_names.insert(method.clone(), name);
+                    }
+                    candidate.clone()
+                }
+            };
+            router.handlers.insert(method.clone(), handler);
+        }
+        router
+            .component_registrars
+            .extend(canary.canary.component_registrars.iter().copied());
+        router
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::{ApiError, Result};
+    use crate::router::get;
+    use bytes::Bytes;
+    use http::Method;
+
+    async fn stable() -> &'static str {
+        "stable"
+    }
+
+    async fn candidate() -> Result<&'static str> {
+        Err(ApiError::internal("not ready"))
+    }
+
+    /// The arm that served a request, or `None` if it was not split
+    async fn call(router: &MethodRouter, method: Method, headers: &[(&str, &str)]) -> Option<Arm> {
+        let mut builder = http::Request::builder().method(method.clone()).uri("/");
+        for (name, value) in headers {
+            builder = builder.header(*name, *value);
+        }
+        let req = Request::from_http_request(builder.body(()).unwrap(), Bytes::new());
+        let response = router.get_handler(&method).unwrap()(req).await;
+        response.extensions().get::<CanaryArm>().map(|tag| tag.arm)
+    }
+
+    #[tokio::test]
+    async fn splits_by_percent_and_counts_per_arm() {
+        let canary = Canary::new("orders", get(stable), get(candidate)).percent(25.0);
+        let router: MethodRouter = canary.clone().into();
+
+        let mut served = Vec::new();
+        for _ in 0..8 {
+            served.push(call(&router, Method::GET, &[]).await.unwrap());
+        }
+        assert_eq!(served.iter().filter(|arm| **arm == Arm::Canary).count(), 2);
+        assert_eq!(served[3], Arm::Canary);
+
+        let stats = canary.stats();
+        assert_eq!(stats.stable.requests, 6);
+        assert_eq!(stats.stable.errors, 0);
+        assert_eq!(stats.canary.requests, 2);
+        assert_eq!(stats.canary.error_rate(), 1.0);
+    }
+
+    #[tokio::test]
+    async fn header_overrides_and_sticky_keys_pick_the_arm() {
+        let router: MethodRouter = Canary::new("orders", get(stable), get(candidate))
+            .percent(50.0)
+            .header("X-Canary")
+            .sticky("X-User-Id")
+            .into();
+
+        let forced = call(&router, Method::GET, &[("x-canary", "1")]).await;
+        assert_eq!(forced, Some(Arm::Canary));
+        let forced = call(&router, Method::GET, &[("x-canary", "stable")]).await;
+        assert_eq!(forced, Some(Arm::Stable));
+
+        for user in ["1", "2", "3", "4", "5"] {
+            let first = call(&router, Method::GET, &[("x-user-id", user)]).await;
+            for _ in 0..3 {
+                let again = call(&router, Method::GET, &[("x-user-id", user)]).await;
+                assert_eq!(again, first);
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn methods_of_one_arm_are_served_directly() {
+        async fn create() -> &'static str {
+            "created"
+        }
+
+        let router: MethodRouter = Canary::new("orders", get(stable), get(candidate).post(create))
+            .percent(100.0)
+            .into();
+        assert_eq!(call(&router, Method::GET, &[]).await, Some(Arm::Canary));
+        assert_eq!(call(&router, Method::POST, &[]).await, None);
+        assert!(router.operations.contains_key(&Method::POST));
+    }
+}
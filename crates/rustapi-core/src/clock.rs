@@ -0,0 +1,156 @@
+//! Clock abstraction for time-dependent behavior
+//!
+//! Components that expire or schedule things (rate limiting, response
+//! caching, JWT expiry, replay retention, job scheduling) read the time from
+//! a [`Clock`] instead of calling `Instant::now()` directly. In production
+//! this is the system clock; in tests a manual clock can be advanced
+//! explicitly, so expiry can be tested without sleeping.
+//!
+//! Middleware looks up the clock in the request extensions (falling back to
+//! the system clock), which is how `rustapi-testing`'s
+//! `TestClient::advance_time` reaches layers built by the application.
+//! Handlers can take a `Clock` as an extractor for the same reason.
+//!
+//! ```rust
+//! use rustapi_core::Clock;
+//! use std::time::Duration;
+//!
+//! let clock = Clock::manual();
+//! let start = clock.instant();
+//!
+//! clock.advance(Duration::from_secs(60));
+//! assert_eq!(clock.instant() - start, Duration::from_secs(60));
+//! ```
+
+use crate::error::Result;
+use crate::extract::FromRequestParts;
+use crate::request::Request;
+use rustapi_openapi::{Operation, OperationModifier};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+/// Source of the current time
+///
+/// Cloning a manual clock shares its time, so advancing one clone advances
+/// all of them.
+#[derive(Clone, Default)]
+pub struct Clock {
+    manual: Option<Arc<Mutex<ManualTime>>>,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct ManualTime {
+    instant: Instant,
+    system: SystemTime,
+}
+
+impl Clock {
+    /// The system clock
+    pub fn system() -> Self {
+        Self { manual: None }
+    }
+
+    /// A manual clock, starting at the current time and standing still until
+    /// [`advance`](Self::advance)d
+    pub fn manual() -> Self {
+        Self {
+            manual: Some(Arc::new(Mutex::new(ManualTime {
+                instant: Instant::now(),
+                system: SystemTime::now(),
+            }))),
+        }
+    }
+
+    /// Whether this is a manual clock
+    pub fn is_manual(&self) -> bool {
+        self.manual.is_some()
+    }
+
+    /// The current wall-clock time
+    pub fn now(&self) -> SystemTime {
+        match &self.manual {
+            Some(time) => time.lock().unwrap_or_else(|e| e.into_inner()).system,
+            None => SystemTime::now(),
+        }
+    }
+
+    /// The current monotonic time
+    pub fn instant(&self) -> Instant {
+        match &self.manual {
+            Some(time) => time.lock().unwrap_or_else(|e| e.into_inner()).instant,
+            None => Instant::now(),
+        }
+    }
+
+    /// Time since the Unix epoch
+    pub fn unix(&self) -> Duration {
+        self.now().duration_since(UNIX_EPOCH).unwrap_or_default()
+    }
+
+    /// Move a manual clock forward
+    ///
+    /// # Panics
+    ///
+    /// Panics on the system clock.
+    pub fn advance(&self, by: Duration) {
+        let time = self
+            .manual
+            .as_ref()
+            .expect("Clock::advance called on the system clock; use Clock::manual()");
+        let mut time = time.lock().unwrap_or_else(|e| e.into_inner());
+        time.instant += by;
+        time.system += by;
+    }
+
+    /// The clock of a request: the one in its extensions, or the system clock
+    pub fn of(req: &Request) -> Self {
+        req.extensions().get::<Clock>().cloned().unwrap_or_default()
+    }
+}
+
+impl std::fmt::Debug for Clock {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.manual {
+            Some(_) => f.debug_tuple("Clock::Manual").field(&self.now()).finish(),
+            None => f.write_str("Clock::System"),
+        }
+    }
+}
+
+impl FromRequestParts for Clock {
+    fn from_request_parts(req: &Request) -> Result<Self> {
+        Ok(Self::of(req))
+    }
+}
+
+impl OperationModifier for Clock {
+    fn update_operation(_op: &mut Operation) {}
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn manual_clock_advances_all_clones() {
+        let clock = Clock::manual();
+        let shared = clock.clone();
+        let (instant, now) = (clock.instant(), clock.now());
+
+        std::thread::sleep(Duration::from_millis(5));
+        assert_eq!(clock.instant(), instant);
+
+        shared.advance(Duration::from_secs(30));
+        assert_eq!(clock.instant() - instant, Duration::from_secs(30));
+        assert_eq!(
+            clock.now().duration_since(now).unwrap(),
+            Duration::from_secs(30)
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "system clock")]
+    fn system_clock_cannot_advance() {
+        Clock::system().advance(Duration::from_secs(1));
+    }
+}
@@ -0,0 +1,182 @@
+//! Request deadlines
+//!
+//! A request with a timeout has a fixed point in time by which its response is
+//! due. Calls a handler makes to databases or other services should only get
+//! what is left of that budget, not their own full timeouts: when the request
+//! has 200 ms left, waiting 5 s for a downstream call only keeps resources busy
+//! for a response nobody receives.
+//!
+//! `TimeoutLayer` stores a [`Deadline`] in the request. Handlers take it as an
+//! extractor and bound their calls with [`Deadline::remaining`],
+//! [`Deadline::bound`] or [`Deadline::run`]; the outbound `HttpClient` and the
+//! sqlx helpers in `rustapi-extras` accept it directly.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! async fn report(deadline: Deadline, State(db): State<Db>) -> Result<Json<Report>> {
+//!     let rows = deadline.run(db.load_report()).await??;
+//!     Ok(Json(rows))
+//! }
+//! ```
+
+use crate::error::{ApiError, Result};
+use crate::extract::FromRequestParts;
+use crate::request::Request;
+use http::StatusCode;
+use std::fmt;
+use std::future::Future;
+use std::time::Duration;
+use tokio::time::Instant;
+
+/// The point in time by which a request should be answered
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Deadline {
+    at: Instant,
+}
+
+impl Deadline {
+    /// A deadline at `at`
+    pub fn at(at: Instant) -> Self {
+        Self { at }
+    }
+
+    /// A deadline `timeout` from now
+    pub fn after(timeout: Duration) -> Self {
+        Self::at(Instant::now() + timeout)
+    }
+
+    /// The point in time of the deadline
+    pub fn instant(&self) -> Instant {
+        self.at
+    }
+
+    /// Time left until the deadline, zero once it has passed
+    pub fn remaining(&self) -> Duration {
+        self.at.saturating_duration_since(Instant::now())
+    }
+
+    /// Whether the deadline has passed
+    pub fn is_expired(&self) -> bool {
+        self.remaining().is_zero()
+    }
+
+    /// The shorter of `timeout` and the remaining time
+    pub fn bound(&self, timeout: Duration) -> Duration {
+        timeout.min(self.remaining())
+    }
+
+    /// Run `future`, giving up when the deadline passes
+    pub async fn run<F: Future>(
+        &self,
+        future: F,
+    ) -> std::result::Result<F::Output, DeadlineExceeded> {
+        tokio::time::timeout_at(self.at, future)
+            .await
+            .map_err(|_| DeadlineExceeded)
+    }
+
+    /// The deadline of a request, if a layer set one
+    pub fn of(req: &Request) -> Option<&Deadline> {
+        req.extensions().get::<Deadline>()
+    }
+
+    /// Set the deadline of a request and return the one in effect
+    ///
+    /// An earlier deadline already set, e.g. by an outer timeout layer, is
+    /// kept.
+    pub fn apply(self, req: &mut Request) -> Deadline {
+        let deadline = match Deadline::of(req) {
+            Some(existing) => self.min(*existing),
+            None => self,
+        };
+        req.extensions_mut().insert(deadline);
+        deadline
+    }
+}
+
+impl FromRequestParts for Deadline {
+    fn from_request_parts(req: &Request) -> Result<Self> {
+        Deadline::of(req).copied().ok_or_else(|| {
+            ApiError::internal("Deadline not found. Did you forget to add TimeoutLayer middleware?")
+        })
+    }
+}
+
+impl rustapi_openapi::OperationModifier for Deadline {
+    fn update_operation(_op: &mut rustapi_openapi::Operation) {}
+}
+
+/// Error returned when a deadline passed before work finished
+///
+/// Converts into a `504 Gateway Timeout` [`ApiError`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DeadlineExceeded;
+
+impl fmt::Display for DeadlineExceeded {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("Request deadline exceeded")
+    }
+}
+
+impl std::error::Error for DeadlineExceeded {}
+
+impl From<DeadlineExceeded> for ApiError {
+    fn from(e: DeadlineExceeded) -> Self {
+        ApiError::new(
+            StatusCode::GATEWAY_TIMEOUT,
+            crate::error::codes::DEADLINE_EXCEEDED,
+            e.to_string(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bytes::Bytes;
+
+    #[tokio::test(start_paused = true)]
+    async fn remaining_budget_shrinks_and_bounds_work() {
+        let deadline = Deadline::after(Duration::from_millis(500));
+        assert_eq!(
+            deadline.bound(Duration::from_secs(5)),
+            Duration::from_millis(500)
+        );
+        assert_eq!(
+            deadline.bound(Duration::from_millis(100)),
+            Duration::from_millis(100)
+        );
+
+        tokio::time::advance(Duration::from_millis(300)).await;
+        assert_eq!(deadline.remaining(), Duration::from_millis(200));
+        assert_eq!(deadline.run(async { 7 }).await, Ok(7));
+
+        let slow = tokio::time::sleep(Duration::from_secs(1));
+        assert_eq!(deadline.run(slow).await, Err(DeadlineExceeded));
+        assert!(deadline.is_expired());
+        assert_eq!(
+            ApiError::from(DeadlineExceeded).status,
+            StatusCode::GATEWAY_TIMEOUT
+        );
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn apply_keeps_the_earlier_deadline() {
+        let req = http::Request::builder().uri("/").body(()).unwrap();
+        let mut req = Request::from_http_request(req, Bytes::new());
+        assert!(Deadline::from_request_parts(&req).is_err());
+
+        let outer = Deadline::after(Duration::from_secs(1));
+        outer.apply(&mut req);
+        assert_eq!(
+            Deadline::after(Duration::from_secs(5)).apply(&mut req),
+            outer
+        );
+        assert_eq!(Deadline::of(&req), Some(&outer));
+
+        let inner = Deadline::after(Duration::from_millis(200));
+        inner.apply(&mut req);
+        assert_eq!(Deadline::from_request_parts(&req).unwrap(), inner);
+    }
+}
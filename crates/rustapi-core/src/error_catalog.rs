@@ -0,0 +1,329 @@
+//! Error code catalog
+//!
+//! Every error a RustAPI application returns carries a stable machine-readable
+//! code in `error.type`. The catalog collects those codes in one place so
+//! client teams can program against them:
+//!
+//! - The built-in [`ApiError`](crate::ApiError) constructors are always present.
+//! - Each variant of an enum deriving `ApiError` registers its code at
+//!   link time (via `linkme`), using `#[error(description = "...")]` or the
+//!   variant message as description.
+//! - Codes produced by hand-written `ApiError::new(...)` calls can be added with
+//!   [`RustApi::error_code`](crate::RustApi::error_code).
+//!
+//! The catalog is exposed in three ways:
+//!
+//! - As an `ApiErrorCode` schema in the OpenAPI document (added automatically).
+//! - As JSON via [`RustApi::error_catalog`](crate::RustApi::error_catalog).
+//! - On stdout when the app is started with `RUSTAPI_DUMP_ERROR_CATALOG=1`
+//!   (used by `cargo rustapi errors`); `run` then returns without serving.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! use rustapi_rs::prelude::*;
+//!
+//! #[derive(Debug, ApiError)]
+//! enum OrderError {
+//!     #[error(status = 404, code = "order_not_found", message = "Order not found")]
+//!     NotFound,
+//!     #[error(status = 409, code = "order_locked", message = "Order is locked",
+//!             description = "The order is being processed and cannot be modified")]
+//!     Locked { order_id: u64 },
+//! }
+//!
+//! RustApi::auto()
+//!     .error_code("quota_exceeded", 429, "The account exceeded its request quota")
+//!     .error_catalog("/errors")
+//!     .run("127.0.0.1:8080")
+//!     .await
+//! ```
+
+use crate::error::codes;
+use linkme::distributed_slice;
+use rustapi_openapi::schema::JsonSchema2020;
+use rustapi_openapi::OpenApiSpec;
+use serde::Serialize;
+use std::borrow::Cow;
+
+/// Name of the OpenAPI component schema describing all catalogued error codes.
+pub const ERROR_CODE_SCHEMA_NAME: &str = "ApiErrorCode";
+
+/// A single entry of the error catalog.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ErrorCodeEntry {
+    /// Stable machine-readable code (the `error.type` field of error responses)
+    pub code: Cow<'static, str>,
+    /// HTTP status code returned together with this error
+    pub status: u16,
+    /// Human-readable description of when this error occurs
+    pub description: Cow<'static, str>,
+    /// Where the code is defined (e.g. `OrderError::NotFound`)
+    pub source: Cow<'static, str>,
+}
+
+impl ErrorCodeEntry {
+    /// Create an entry from static strings (usable in `static` items).
+    pub const fn new_static(
+        code: &'static str,
+        status: u16,
+        description: &'static str,
+        source: &'static str,
+    ) -> Self {
+        Self {
+            code: Cow::Borrowed(code),
+            status,
+            description: Cow::Borrowed(description),
+            source: Cow::Borrowed(source),
+        }
+    }
+
+    /// Create an entry from owned or borrowed strings.
+    pub fn new(
+        code: impl Into<Cow<'static, str>>,
+        status: u16,
+        description: impl Into<Cow<'static, str>>,
+    ) -> Self {
+        Self {
+            code: code.into(),
+            status,
+            description: description.into(),
+            source: Cow::Borrowed("application"),
+        }
+    }
+
+    /// Set where this code is defined.
+    pub fn source(mut self, source: impl Into<Cow<'static, str>>) -> Self {
+        self.source = source.into();
+        self
+    }
+}
+
+/// Distributed slice containing all error codes registered by `#[derive(ApiError)]`.
+#[distributed_slice]
+pub static ERROR_CODES: [ErrorCodeEntry];
+
+/// Error codes emitted by the built-in `ApiError` constructors, extractors and middleware.
+const BUILTIN_ERROR_CODES: &[ErrorCodeEntry] = &[
+    ErrorCodeEntry::new_static(
+        codes::BAD_REQUEST,
+        400,
+        "The request is malformed or could not be parsed",
+        "ApiError::bad_request",
+    ),
+    ErrorCodeEntry::new_static(
+        codes::UNAUTHORIZED,
+        401,
+        "Authentication is required or the credentials are invalid",
+        "ApiError::unauthorized",
+    ),
+    ErrorCodeEntry::new_static(
+        codes::FORBIDDEN,
+        403,
+        "The caller is not allowed to perform this operation",
+        "ApiError::forbidden",
+    ),
+    ErrorCodeEntry::new_static(
+        codes::NOT_FOUND,
+        404,
+        "The requested resource does not exist",
+        "ApiError::not_found",
+    ),
+    ErrorCodeEntry::new_static(
+        codes::METHOD_NOT_ALLOWED,
+        405,
+        "The route exists but does not accept this method; see the `Allow` header",
+        "Router",
+    ),
+    ErrorCodeEntry::new_static(
+        codes::CONFLICT,
+        409,
+        "The request conflicts with the current state of the resource",
+        "ApiError::conflict",
+    ),
+    ErrorCodeEntry::new_static(
+        codes::PAYLOAD_TOO_LARGE,
+        413,
+        "The request body or an uploaded field exceeds the configured size limit",
+        "BodyLimitLayer",
+    ),
+    ErrorCodeEntry::new_static(
+        codes::VALIDATION_ERROR,
+        422,
+        "Request validation failed; see `error.fields` for details",
+        "ApiError::validation",
+    ),
+    ErrorCodeEntry::new_static(
+        codes::INTERNAL_ERROR,
+        500,
+        "An unexpected server error occurred",
+        "ApiError::internal",
+    ),
+    ErrorCodeEntry::new_static(
+        codes::SERVICE_UNAVAILABLE,
+        503,
+        "The service is temporarily unavailable",
+        "ApiError::service_unavailable",
+    ),
+    ErrorCodeEntry::new_static(
+        codes::DEADLINE_EXCEEDED,
+        504,
+        "The request deadline passed before the handler finished",
+        "DeadlineExceeded",
+    ),
+];
+
+/// Collection of all error codes an application can return.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ErrorCatalog {
+    errors: Vec<ErrorCodeEntry>,
+}
+
+impl ErrorCatalog {
+    /// Create an empty catalog.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Collect the built-in codes and every code registered by `#[derive(ApiError)]`.
+    pub fn collect() -> Self {
+        let mut catalog = Self::new();
+        for entry in BUILTIN_ERROR_CODES.iter().chain(ERROR_CODES.iter()) {
+            catalog.insert(entry.clone());
+        }
+        catalog
+    }
+
+    /// Add an entry, keeping the catalog sorted by code and status.
+    ///
+    /// Identical entries are ignored; the same code may appear with several
+    /// statuses or sources.
+    pub fn insert(&mut self, entry: ErrorCodeEntry) {
+        if self.errors.contains(&entry) {
+            return;
+        }
+        let idx = self
+            .errors
+            .partition_point(|e| (&e.code, e.status) <= (&entry.code, entry.status));
+        self.errors.insert(idx, entry);
+    }
+
+    /// All entries, sorted by code.
+    pub fn entries(&self) -> &[ErrorCodeEntry] {
+        &self.errors
+    }
+
+    /// Look up the first entry for a code.
+    pub fn get(&self, code: &str) -> Option<&ErrorCodeEntry> {
+        self.errors.iter().find(|e| e.code == code)
+    }
+
+    /// Number of entries in the catalog.
+    pub fn len(&self) -> usize {
+        self.errors.len()
+    }
+
+    /// Whether the catalog is empty.
+    pub fn is_empty(&self) -> bool {
+        self.errors.is_empty()
+    }
+
+    /// Serialize the catalog as `{"errors": [...]}`.
+    pub fn to_json(&self) -> serde_json::Value {
+        serde_json::to_value(self).unwrap_or_else(|_| serde_json::json!({ "errors": [] }))
+    }
+
+    /// Add (or replace) the `ApiErrorCode` component schema in the given spec.
+    ///
+    /// The schema is a string with one `oneOf` branch per code, each carrying
+    /// the status and description of that code.
+    pub fn apply_to_openapi(&self, spec: &mut OpenApiSpec) {
+        let mut variants: Vec<JsonSchema2020> = Vec::new();
+        for entry in &self.errors {
+            let description = format!("{} ({})", entry.description, entry.status);
+            if let Some(existing) = variants
+                .iter_mut()
+                .find(|v| v.const_value.as_ref().and_then(|c| c.as_str()) == Some(&entry.code))
+            {
+                let merged = format!(
+                    "{}; {}",
+                    existing.description.as_deref().unwrap_or_default(),
+                    description
+                );
+                existing.description = Some(merged);
+                continue;
+            }
+            variants.push(JsonSchema2020 {
+                const_value: Some(serde_json::Value::String(entry.code.to_string())),
+                description: Some(description),
+                ..Default::default()
+            });
+        }
+
+        let schema = JsonSchema2020 {
+            description: Some(
+                "Stable error codes returned in the `error.type` field of error responses"
+                    .to_string(),
+            ),
+            one_of: Some(variants),
+            ..JsonSchema2020::string()
+        };
+
+        spec.components
+            .get_or_insert_with(Default::default)
+            .schemas
+            .insert(ERROR_CODE_SCHEMA_NAME.to_string(), schema);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_collect_contains_builtin_codes() {
+        let catalog = ErrorCatalog::collect();
+        for code in [
+            "bad_request",
+            "not_found",
+            "validation_error",
+            "internal_error",
+            "method_not_allowed",
+            "payload_too_large",
+            "deadline_exceeded",
+        ] {
+            assert!(catalog.get(code).is_some(), "missing builtin code {}", code);
+        }
+        assert_eq!(catalog.get("not_found").unwrap().status, 404);
+    }
+
+    #[test]
+    fn test_insert_keeps_sorted_and_deduplicates() {
+        let mut catalog = ErrorCatalog::new();
+        catalog.insert(ErrorCodeEntry::new("zeta", 400, "z"));
+        catalog.insert(ErrorCodeEntry::new("alpha", 409, "a"));
+        catalog.insert(ErrorCodeEntry::new("alpha", 404, "a"));
+        catalog.insert(ErrorCodeEntry::new("alpha", 404, "a"));
+
+        let codes: Vec<_> = catalog
+            .entries()
+            .iter()
+            .map(|e| (e.code.as_ref(), e.status))
+            .collect();
+        assert_eq!(codes, vec![("alpha", 404), ("alpha", 409), ("zeta", 400)]);
+    }
+
+    #[test]
+    fn test_apply_to_openapi_adds_code_schema() {
+        let mut catalog = ErrorCatalog::new();
+        catalog.insert(ErrorCodeEntry::new("order_locked", 409, "Order is locked"));
+        let mut spec = OpenApiSpec::new("Test", "1.0.0");
+        catalog.apply_to_openapi(&mut spec);
+
+        let json = spec.to_json();
+        let schema = &json["components"]["schemas"][ERROR_CODE_SCHEMA_NAME];
+        assert_eq!(schema["type"], "string");
+        assert_eq!(schema["oneOf"][0]["const"], "order_locked");
+        assert_eq!(schema["oneOf"][0]["description"], "Order is locked (409)");
+    }
+}
@@ -0,0 +1,319 @@
+//! Content negotiation for error responses
+//!
+//! [`ApiError`](crate::ApiError) responses are JSON by default. When the
+//! request's `Accept` header prefers another format, the server re-encodes the
+//! error body before it is sent:
+//!
+//! - `application/json` (and `*/*`): the regular error body
+//! - `application/problem+json`: an RFC 9457 problem document
+//! - `text/html`: a small error page, in development only (see
+//!   [`Profile`](crate::Profile)), so browser hits are readable
+//! - any media type of a registered [`ErrorFormatter`]; `rustapi-toon` adds
+//!   TOON this way
+//!
+//! Ties go to JSON, and a header that accepts none of these still gets JSON.
+//! Only responses produced by `ApiError` are affected, and only while their
+//! `Content-Type` is still `application/json`.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! use rustapi_core::error_format::{register_error_formatter, ErrorFormatter};
+//! use std::sync::Arc;
+//!
+//! struct PlainText;
+//!
+//! impl ErrorFormatter for PlainText {
+//!     fn media_types(&self) -> Vec<&'static str> {
+//!         vec!["text/plain"]
+//!     }
+//!
+//!     fn format(&self, _media_type: &str, error: &serde_json::Value) -> Option<Vec<u8>> {
+//!         Some(error["error"]["message"].as_str()?.as_bytes().to_vec())
+//!     }
+//! }
+//!
+//! register_error_formatter(Arc::new(PlainText));
+//! ```
+
+use crate::response::{Body, Response};
+use http::{header, HeaderValue};
+use std::sync::{Arc, RwLock};
+
+/// Media type of RFC 9457 problem documents
+pub const PROBLEM_JSON_CONTENT_TYPE: &str = "application/problem+json";
+
+/// Encodes error bodies into an additional media type
+///
+/// The error is handed over as the JSON error body
+/// (`{"error": {"type", "message", "fields"}, "error_id", "request_id"}`).
+pub trait ErrorFormatter: Send + Sync {
+    /// Media types this formatter produces, most preferred first
+    fn media_types(&self) -> Vec<&'static str>;
+
+    /// Encode the error for `media_type`; `None` keeps the JSON body
+    fn format(&self, media_type: &str, error: &serde_json::Value) -> Option<Vec<u8>>;
+}
+
+static FORMATTERS: RwLock<Vec<Arc<dyn ErrorFormatter>>> = RwLock::new(Vec::new());
+
+/// Register an error formatter
+///
+/// Formatters are offered after the built-in JSON, problem+json and HTML
+/// formats, in registration order.
+pub fn register_error_formatter(formatter: Arc<dyn ErrorFormatter>) {
+    FORMATTERS
+        .write()
+        .unwrap_or_else(|e| e.into_inner())
+        .push(formatter);
+}
+
+/// A media type offered for an error, with the formatter producing it
+type Candidate<'a> = (&'static str, Option<&'a Arc<dyn ErrorFormatter>>);
+
+/// JSON error body attached to responses built from `ApiError`
+#[derive(Clone)]
+pub(crate) struct NegotiableError(pub(crate) Arc<serde_json::Value>);
+
+/// Re-encode an `ApiError` response in the format preferred by `accept`
+///
+/// Every request pipeline calls this with the request's `Accept` header right
+/// before the response is sent. Other responses are returned unchanged.
+pub fn negotiate_error_response(accept: Option<&HeaderValue>, response: Response) -> Response {
+    let Some(accept) = accept.and_then(|value| value.to_str().ok()) else {
+        return response;
+    };
+    let Some(NegotiableError(error)) = response.extensions().get::<NegotiableError>().cloned()
+    else {
+        return response;
+    };
+    let still_json = response
+        .headers()
+        .get(header::CONTENT_TYPE)
+        .is_some_and(|value| value == "application/json");
+    if !still_json {
+        return response;
+    }
+
+    let ranges = parse_accept(accept);
+    let html = crate::Profile::current().environment.is_development();
+    let formatters = FORMATTERS.read().unwrap_or_else(|e| e.into_inner()).clone();
+
+    let mut candidates: Vec<Candidate<'_>> = vec![
+        ("application/json", None),
+        (PROBLEM_JSON_CONTENT_TYPE, None),
+    ];
+    if html {
+        candidates.push(("text/html", None));
+    }
+    for formatter in &formatters {
+        for media_type in formatter.media_types() {
+            candidates.push((media_type, Some(formatter)));
+        }
+    }
+
+    let mut best: Option<(Candidate<'_>, f32)> = None;
+    for candidate in candidates {
+        let quality = quality_of(&ranges, candidate.0);
+        if quality > 0.0 && best.is_none_or(|(_, q)| quality > q) {
+            best = Some((candidate, quality));
+        }
+    }
+    let Some(((media_type, formatter), _)) = best else {
+        return response;
+    };
+
+    let body = match (media_type, formatter) {
+        (PROBLEM_JSON_CONTENT_TYPE, None) => {
+            serde_json::to_vec(&problem_document(response.status(), &error)).ok()
+        }
+        ("text/html", None) => Some(html_page(response.status(), &error).into_bytes()),
+        (media_type, Some(formatter)) => formatter.format(media_type, &error),
+        _ => None,
+    };
+    let Some(body) = body else {
+        return response;
+    };
+    let content_type = if media_type == "text/html" {
+        "text/html; charset=utf-8"
+    } else {
+        media_type
+    };
+
+    let (mut parts, _) = response.into_parts();
+    parts.headers.remove(header::CONTENT_LENGTH);
+    parts
+        .headers
+        .insert(header::CONTENT_TYPE, HeaderValue::from_static(content_type));
+    parts
+        .headers
+        .append(header::VARY, HeaderValue::from_static("accept"));
+    Response::from_parts(parts, Body::from(body))
+}
+
+/// Media ranges with their quality, in header order
+fn parse_accept(accept: &str) -> Vec<(String, f32)> {
+    accept
+        .split(',')
+        .filter_map(|part| {
+            let mut params = part.split(';');
+            let range = params.next()?.trim().to_ascii_lowercase();
+            if range.is_empty() {
+                return None;
+            }
+            // A malformed or out-of-range q makes the range not acceptable
+            let quality = params
+                .filter_map(|param| param.split_once('='))
+                .find(|(name, _)| name.trim().eq_ignore_ascii_case("q"))
+                .map(|(_, value)| {
+                    value
+                        .trim()
+                        .parse::<f32>()
+                        .ok()
+                        .filter(|q| (0.0..=1.0).contains(q))
+                        .unwrap_or(0.0)
+                })
+                .unwrap_or(1.0);
+            Some((range, quality))
+        })
+        .collect()
+}
+
+/// Quality of the most specific range matching `media_type`
+fn quality_of(ranges: &[(String, f32)], media_type: &str) -> f32 {
+    let main_type = media_type.split('/').next().unwrap_or_default();
+    ranges
+        .iter()
+        .filter_map(|(range, quality)| {
+            let specificity = if range == media_type {
+                2
+            } else if range.strip_suffix("/*") == Some(main_type) {
+                1
+            } else if range == "*/*" {
+                0
+            } else {
+                return None;
+            };
+            Some((specificity, *quality))
+        })
+        .max_by_key(|(specificity, _)| *specificity)
+        .map(|(_, quality)| quality)
+        .unwrap_or(0.0)
+}
+
+/// RFC 9457 problem document for a JSON error body
+fn problem_document(status: http::StatusCode, error: &serde_json::Value) -> serde_json::Value {
+    let mut problem = serde_json::json!({
+        "type": "about:blank",
+        "title": status.canonical_reason().unwrap_or("Error"),
+        "status": status.as_u16(),
+        "detail": error["error"]["message"],
+        "code": error["error"]["type"],
+        "error_id": error["error_id"],
+    });
+    if let Some(fields) = error["error"].get("fields") {
+        problem["errors"] = fields.clone();
+    }
+    if let Some(request_id) = error.get("request_id") {
+        problem["request_id"] = request_id.clone();
+    }
+    problem
+}
+
+fn html_page(status: http::StatusCode, error: &serde_json::Value) -> String {
+    let text = |value: &serde_json::Value| escape(value.as_str().unwrap_or_default());
+    let mut fields = String::new();
+    if let Some(list) = error["error"]["fields"].as_array() {
+        fields.push_str("<ul>");
+        for field in list {
+            fields.push_str(&format!(
+                "<li><code>{}</code>: {}</li>",
+                text(&field["field"]),
+                text(&field["message"])
+            ));
+        }
+        fields.push_str("</ul>");
+    }
+    format!(
+        "<!DOCTYPE html>\n<html><head><meta charset=\"utf-8\"><title>{status}</title></head>\
+         <body><h1>{status}</h1><p><code>{code}</code>: {message}</p>{fields}\
+         <p><small>{error_id}</small></p></body></html>\n",
+        status = status,
+        code = text(&error["error"]["type"]),
+        message = text(&error["error"]["message"]),
+        error_id = text(&error["error_id"]),
+    )
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::ApiError;
+    use crate::response::IntoResponse;
+    use http_body_util::BodyExt;
+
+    async fn negotiated(accept: &str) -> (String, serde_json::Value) {
+        let response = ApiError::not_found("No such <order>").into_response();
+        let response =
+            negotiate_error_response(Some(&HeaderValue::from_str(accept).unwrap()), response);
+        let content_type = response.headers()[header::CONTENT_TYPE]
+            .to_str()
+            .unwrap()
+            .to_string();
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        let body = serde_json::from_slice(&body)
+            .unwrap_or_else(|_| serde_json::Value::String(String::from_utf8_lossy(&body).into()));
+        (content_type, body)
+    }
+
+    #[tokio::test]
+    async fn problem_json_is_negotiated() {
+        let (content_type, body) = negotiated("application/problem+json").await;
+        assert_eq!(content_type, PROBLEM_JSON_CONTENT_TYPE);
+        assert_eq!(body["status"], 404);
+        assert_eq!(body["title"], "Not Found");
+        assert_eq!(body["detail"], "No such <order>");
+        assert_eq!(body["code"], "not_found");
+    }
+
+    #[tokio::test]
+    async fn json_wins_ties_and_unknown_types() {
+        let (content_type, body) = negotiated("*/*").await;
+        assert_eq!(content_type, "application/json");
+        assert_eq!(body["error"]["type"], "not_found");
+
+        let (content_type, _) = negotiated("image/png").await;
+        assert_eq!(content_type, "application/json");
+    }
+
+    #[tokio::test]
+    async fn browsers_get_an_escaped_html_page_in_development() {
+        let (content_type, body) = negotiated("text/html,application/xhtml+xml,*/*;q=0.8").await;
+        assert_eq!(content_type, "text/html; charset=utf-8");
+        let page = body.as_str().unwrap();
+        assert!(page.contains("No such &lt;order&gt;"), "{page}");
+    }
+
+    #[test]
+    fn most_specific_range_decides() {
+        let ranges = parse_accept("application/*;q=0.2, application/json;q=0, */*");
+        assert_eq!(quality_of(&ranges, "application/json"), 0.0);
+        assert_eq!(quality_of(&ranges, PROBLEM_JSON_CONTENT_TYPE), 0.2);
+        assert_eq!(quality_of(&ranges, "text/html"), 1.0);
+    }
+
+    #[test]
+    fn malformed_quality_is_not_acceptable() {
+        let ranges = parse_accept("text/html;q=abc, application/xml;q=2, */*;q=0.5");
+        assert_eq!(quality_of(&ranges, "text/html"), 0.0);
+        assert_eq!(quality_of(&ranges, "application/xml"), 0.0);
+        assert_eq!(quality_of(&ranges, "application/json"), 0.5);
+    }
+}
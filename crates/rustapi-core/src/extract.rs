@@ -10,6 +10,7 @@
 //! |-----------|-------------|---------------|
 //! | [`Json<T>`] | Parse JSON request body | Yes |
 //! | [`ValidatedJson<T>`] | Parse and validate JSON body | Yes |
+//! | [`ValidatedForm<T>`] | Parse and validate URL-encoded form body | Yes |
 //! | [`Query<T>`] | Parse query string parameters | No |
 //! | [`Path<T>`] | Extract path parameters | No |
 //! | [`State<T>`] | Access shared application state | No |
@@ -62,7 +63,7 @@ use crate::stream::{StreamingBody, StreamingConfig};
 use crate::validation::Validatable;
 use bytes::Bytes;
 use http::{header, StatusCode};
-use rustapi_validate::v2::{AsyncValidate, ValidationContext};
+use rustapi_validate::v2::{AsyncValidate, Sanitize, ValidationContext};
 
 use rustapi_openapi::schema::{RustApiSchema, SchemaCtx, SchemaRef};
 use serde::de::DeserializeOwned;
@@ -223,6 +224,8 @@ impl<T: Serialize> IntoResponse for Json<T> {
 /// using the `Validate` trait. Returns a 422 Unprocessable Entity error with
 /// detailed field-level validation errors if validation fails.
 ///
+/// Fields annotated with `#[validate(sanitize(...))]` are normalized before validation.
+///
 /// # Example
 ///
 /// ```rust,ignore
@@ -265,10 +268,12 @@ impl<T: DeserializeOwned + Validatable + Send> FromRequest for ValidatedJson<T>
             .take_body()
             .ok_or_else(|| ApiError::internal("Body already consumed"))?;
 
-        let value: T = json::from_slice(&body)?;
+        let mut value: T = json::from_slice(&body)?;
 
-        // Then, validate it using the unified Validatable trait
-        value.do_validate()?;
+        // Normalize #[validate(sanitize(...))] fields, then validate using the unified Validatable trait
+        value.do_sanitize();
+        let locale = crate::locale::Locale::configured(req);
+        value.do_validate_localized(locale.as_ref().map(|l| l.as_str()))?;
 
         Ok(ValidatedJson(value))
     }
@@ -300,10 +305,89 @@ impl<T: Serialize> IntoResponse for ValidatedJson<T> {
     }
 }
 
+/// Validated form body extractor
+///
+/// Parses an `application/x-www-form-urlencoded` request body into type `T`,
+/// applies `#[validate(sanitize(...))]` field sanitizers, and validates using the
+/// `Validate` trait. Returns a 400 Bad Request if the body cannot be decoded
+/// and a 422 Unprocessable Entity with field-level errors if validation fails.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// use rustapi_rs::prelude::*;
+///
+/// #[derive(Deserialize, Validate)]
+/// struct Login {
+///     #[validate(sanitize(trim, lowercase))]
+///     #[validate(email)]
+///     email: String,
+///     #[validate(length(min = 8))]
+///     password: String,
+/// }
+///
+/// async fn login(ValidatedForm(form): ValidatedForm<Login>) -> impl IntoResponse {
+///     // form.email is trimmed, lowercased and validated
+/// }
+/// ```
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ValidatedForm<T>(pub T);
+
+impl<T> ValidatedForm<T> {
+    /// Create a new ValidatedForm wrapper
+    pub fn new(value: T) -> Self {
+        Self(value)
+    }
+
+    /// Get the inner value
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+impl<T: DeserializeOwned + Validatable + Send> FromRequest for ValidatedForm<T> {
+    async fn from_request(req: &mut Request) -> Result<Self> {
+        req.load_body().await?;
+        let body = req
+            .take_body()
+            .ok_or_else(|| ApiError::internal("Body already consumed"))?;
+
+        let mut value: T = serde_urlencoded::from_bytes(&body)
+            .map_err(|e| ApiError::bad_request(format!("Invalid form body: {}", e)))?;
+
+        value.do_sanitize();
+        let locale = crate::locale::Locale::configured(req);
+        value.do_validate_localized(locale.as_ref().map(|l| l.as_str()))?;
+
+        Ok(ValidatedForm(value))
+    }
+}
+
+impl<T> Deref for ValidatedForm<T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<T> DerefMut for ValidatedForm<T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+impl<T> From<T> for ValidatedForm<T> {
+    fn from(value: T) -> Self {
+        ValidatedForm(value)
+    }
+}
+
 /// Async validated JSON body extractor
 ///
-/// Parses the request body as JSON, deserializes into type `T`, and validates
-/// using the `AsyncValidate` trait from `rustapi-validate`.
+/// Parses the request body as JSON, deserializes into type `T`, applies
+/// `#[validate(sanitize(...))]` field sanitizers, and validates using the
+/// `AsyncValidate` trait from `rustapi-validate`.
 ///
 /// This extractor supports async validation rules, such as database uniqueness checks.
 ///
@@ -367,7 +451,9 @@ impl<T: Serialize> IntoResponse for AsyncValidatedJson<T> {
     }
 }
 
-impl<T: DeserializeOwned + AsyncValidate + Send + Sync> FromRequest for AsyncValidatedJson<T> {
+impl<T: DeserializeOwned + AsyncValidate + Sanitize + Send + Sync> FromRequest
+    for AsyncValidatedJson<T>
+{
     async fn from_request(req: &mut Request) -> Result<Self> {
         req.load_body().await?;
 
@@ -375,7 +461,8 @@ impl<T: DeserializeOwned + AsyncValidate + Send + Sync> FromRequest for AsyncVal
             .take_body()
             .ok_or_else(|| ApiError::internal("Body already consumed"))?;
 
-        let value: T = json::from_slice(&body)?;
+        let mut value: T = json::from_slice(&body)?;
+        value.sanitize();
 
         // Create validation context from request
         // Check if validators are configured in App State
@@ -387,21 +474,11 @@ impl<T: DeserializeOwned + AsyncValidate + Send + Sync> FromRequest for AsyncVal
 
         // Perform full validation (sync + async)
         if let Err(errors) = value.validate_full(&ctx).await {
-            // Convert v2 ValidationErrors to ApiError
-            let field_errors: Vec<crate::error::FieldError> = errors
-                .fields
-                .iter()
-                .flat_map(|(field, errs)| {
-                    let field_name = field.to_string();
-                    errs.iter().map(move |e| crate::error::FieldError {
-                        field: field_name.clone(),
-                        code: e.code.to_string(),
-                        message: e.message.clone(),
-                    })
-                })
-                .collect();
-
-            return Err(ApiError::validation(field_errors));
+            let locale = crate::locale::Locale::configured(req);
+            return Err(crate::validation::convert_v2_errors_localized(
+                errors,
+                locale.as_ref().map(|l| l.as_str()),
+            ));
         }
 
         Ok(AsyncValidatedJson(value))
@@ -431,7 +508,7 @@ pub struct Query<T>(pub T);
 impl<T: DeserializeOwned> FromRequestParts for Query<T> {
     fn from_request_parts(req: &Request) -> Result<Self> {
         let query = req.query_string().unwrap_or("");
-        let value: T = serde_urlencoded::from_str(query)
+        let value: T = crate::query::from_str(query)
             .map_err(|e| ApiError::bad_request(format!("Invalid query string: {}", e)))?;
         Ok(Query(value))
     }
@@ -601,24 +678,48 @@ impl Deref for Body {
 }
 
 /// Streaming body extractor
+///
+/// Yields the request body chunk by chunk without buffering it. Limits,
+/// progress reporting and the over-limit error come from a
+/// [`StreamingConfig`] in app state (10MB limit by default). A declared
+/// `Content-Length` above the limit is rejected before any of the body is
+/// read; otherwise the stream yields the error as soon as the limit is
+/// crossed and then ends.
 pub struct BodyStream(pub StreamingBody);
 
 impl FromRequest for BodyStream {
     async fn from_request(req: &mut Request) -> Result<Self> {
-        let config = StreamingConfig::default();
+        let config = req
+            .state()
+            .get::<StreamingConfig>()
+            .cloned()
+            .unwrap_or_default();
+
+        let content_length = req
+            .headers()
+            .get(http::header::CONTENT_LENGTH)
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.parse::<u64>().ok());
+        if let (Some(length), Some(limit)) = (content_length, config.max_body_size) {
+            if length > limit as u64 {
+                return Err(config.limit_exceeded(limit));
+            }
+        }
 
-        if let Some(stream) = req.take_stream() {
-            Ok(BodyStream(StreamingBody::new(stream, config.max_body_size)))
+        let body = if let Some(stream) = req.take_stream() {
+            StreamingBody::new(stream, config.max_body_size)
         } else if let Some(bytes) = req.take_body() {
             // Handle buffered body as stream
             let stream = futures_util::stream::once(async move { Ok(bytes) });
-            Ok(BodyStream(StreamingBody::from_stream(
-                stream,
-                config.max_body_size,
-            )))
+            StreamingBody::from_stream(stream, config.max_body_size)
         } else {
-            Err(ApiError::internal("Body already consumed"))
-        }
+            return Err(ApiError::internal("Body already consumed"));
+        };
+
+        Ok(BodyStream(
+            body.with_config(&config)
+                .with_content_length(content_length),
+        ))
     }
 }
 
@@ -722,6 +823,86 @@ impl Deref for Headers {
     }
 }
 
+/// Typed headers extractor
+///
+/// Deserializes the request headers into a struct. Each field is a header;
+/// use serde's `rename` or `rename_all = "kebab-case"` to give fields their
+/// header names, which are matched case-insensitively. A header sent more
+/// than once fills a `Vec` field, and `Option` fields are optional headers.
+/// The fields are documented as header parameters in OpenAPI.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// #[derive(Deserialize, Schema)]
+/// #[serde(rename_all = "kebab-case")]
+/// struct ClientHeaders {
+///     x_api_version: u32,
+///     #[serde(rename = "X-Tenant")]
+///     tenant: Option<String>,
+/// }
+///
+/// async fn handler(HeaderStruct(h): HeaderStruct<ClientHeaders>) -> String {
+///     format!("v{} for {:?}", h.x_api_version, h.tenant)
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct HeaderStruct<T>(pub T);
+
+impl<T> HeaderStruct<T> {
+    /// Get the inner value
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+impl<T: DeserializeOwned> FromRequestParts for HeaderStruct<T> {
+    fn from_request_parts(req: &Request) -> Result<Self> {
+        let value: T = crate::query::from_headers(req.headers())
+            .map_err(|e| ApiError::bad_request(format!("Invalid headers: {}", e)))?;
+        Ok(HeaderStruct(value))
+    }
+}
+
+impl<T: RustApiSchema> OperationModifier for HeaderStruct<T> {
+    fn update_operation(op: &mut Operation) {
+        let mut ctx = SchemaCtx::new();
+        let Some(fields) = T::field_schemas(&mut ctx) else {
+            return;
+        };
+        T::schema(&mut ctx);
+        let required = ctx
+            .components
+            .get(T::name().as_ref())
+            .and_then(|schema| schema.required.clone())
+            .unwrap_or_default();
+
+        op.parameters
+            .extend(fields.into_iter().map(|(name, schema)| Parameter {
+                required: required.contains(&name),
+                name,
+                location: "header".to_string(),
+                deprecated: None,
+                description: None,
+                style: None,
+                explode: None,
+                schema: Some(schema),
+            }));
+    }
+
+    fn register_components(spec: &mut rustapi_openapi::OpenApiSpec) {
+        spec.register_in_place::<T>();
+    }
+}
+
+impl<T> Deref for HeaderStruct<T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
 /// Single header value extractor
 ///
 /// Extracts a specific header value by name. Returns an error if the header is missing.
@@ -921,24 +1102,30 @@ impl Cookies {
 #[cfg(feature = "cookies")]
 impl FromRequestParts for Cookies {
     fn from_request_parts(req: &Request) -> Result<Self> {
-        let mut jar = cookie::CookieJar::new();
-
-        if let Some(cookie_header) = req.headers().get(header::COOKIE) {
-            if let Ok(cookie_str) = cookie_header.to_str() {
-                // Parse each cookie from the header
-                for cookie_part in cookie_str.split(';') {
-                    let trimmed = cookie_part.trim();
-                    if !trimmed.is_empty() {
-                        if let Ok(cookie) = cookie::Cookie::parse(trimmed.to_string()) {
-                            jar.add_original(cookie.into_owned());
-                        }
+        Ok(Cookies(parse_cookie_jar(req.headers())))
+    }
+}
+
+/// Parse the `Cookie` header into a jar of original cookies
+#[cfg(feature = "cookies")]
+pub(crate) fn parse_cookie_jar(headers: &http::HeaderMap) -> cookie::CookieJar {
+    let mut jar = cookie::CookieJar::new();
+
+    if let Some(cookie_header) = headers.get(header::COOKIE) {
+        if let Ok(cookie_str) = cookie_header.to_str() {
+            // Parse each cookie from the header
+            for cookie_part in cookie_str.split(';') {
+                let trimmed = cookie_part.trim();
+                if !trimmed.is_empty() {
+                    if let Ok(cookie) = cookie::Cookie::parse(trimmed.to_string()) {
+                        jar.add_original(cookie.into_owned());
                     }
                 }
             }
         }
-
-        Ok(Cookies(jar))
     }
+
+    jar
 }
 
 #[cfg(feature = "cookies")]
@@ -1025,6 +1212,57 @@ impl<T: RustApiSchema> OperationModifier for ValidatedJson<T> {
     }
 }
 
+// ValidatedForm - Adds form request body + 422 response
+impl<T: RustApiSchema> OperationModifier for ValidatedForm<T> {
+    fn update_operation(op: &mut Operation) {
+        let mut ctx = SchemaCtx::new();
+        let schema_ref = T::schema(&mut ctx);
+
+        let mut content = BTreeMap::new();
+        content.insert(
+            "application/x-www-form-urlencoded".to_string(),
+            MediaType {
+                schema: Some(schema_ref),
+                example: None,
+            },
+        );
+
+        op.request_body = Some(RequestBody {
+            description: None,
+            required: Some(true),
+            content,
+        });
+
+        // Add 422 Validation Error response
+        let mut responses_content = BTreeMap::new();
+        responses_content.insert(
+            "application/json".to_string(),
+            MediaType {
+                schema: Some(SchemaRef::Ref {
+                    reference: "#/components/schemas/ValidationErrorSchema".to_string(),
+                }),
+                example: None,
+            },
+        );
+
+        op.responses.insert(
+            "422".to_string(),
+            ResponseSpec {
+                description: "Validation Error".to_string(),
+                content: responses_content,
+                headers: BTreeMap::new(),
+            },
+        );
+    }
+
+    fn register_components(spec: &mut rustapi_openapi::OpenApiSpec) {
+        spec.register_in_place::<T>();
+        spec.register_in_place::<rustapi_openapi::ValidationErrorSchema>();
+        spec.register_in_place::<rustapi_openapi::ValidationErrorBodySchema>();
+        spec.register_in_place::<rustapi_openapi::FieldErrorSchema>();
+    }
+}
+
 // AsyncValidatedJson - Adds request body + 422 response (same as ValidatedJson)
 impl<T: RustApiSchema> OperationModifier for AsyncValidatedJson<T> {
     fn update_operation(op: &mut Operation) {
@@ -1121,12 +1359,16 @@ impl<T: RustApiSchema> OperationModifier for Query<T> {
             let new_params: Vec<Parameter> = fields
                 .into_iter()
                 .map(|(name, schema)| {
+                    // Lists are sent as repeated keys (`tag=a&tag=b`)
+                    let is_array = is_array_schema(&schema);
                     Parameter {
                         name,
                         location: "query".to_string(),
                         required: false, // Assume optional
                         deprecated: None,
                         description: None,
+                        style: is_array.then(|| "form".to_string()),
+                        explode: is_array.then_some(true),
                         schema: Some(schema),
                     }
                 })
@@ -1141,6 +1383,18 @@ impl<T: RustApiSchema> OperationModifier for Query<T> {
     }
 }
 
+/// Whether a schema is a list, also when nullable (`["array", "null"]`)
+fn is_array_schema(schema: &SchemaRef) -> bool {
+    let Ok(value) = serde_json::to_value(schema) else {
+        return false;
+    };
+    match &value["type"] {
+        serde_json::Value::String(ty) => ty == "array",
+        serde_json::Value::Array(types) => types.iter().any(|ty| ty == "array"),
+        _ => false,
+    }
+}
+
 // State - No op
 impl<T> OperationModifier for State<T> {
     fn update_operation(_op: &mut Operation) {}
@@ -1236,6 +1490,12 @@ impl<T: RustApiSchema> RustApiSchema for ValidatedJson<T> {
     }
 }
 
+impl<T: RustApiSchema> RustApiSchema for ValidatedForm<T> {
+    fn schema(ctx: &mut SchemaCtx) -> SchemaRef {
+        T::schema(ctx)
+    }
+}
+
 impl<T: RustApiSchema> RustApiSchema for AsyncValidatedJson<T> {
     fn schema(ctx: &mut SchemaCtx) -> SchemaRef {
         T::schema(ctx)
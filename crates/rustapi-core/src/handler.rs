@@ -55,6 +55,7 @@
 //! ```
 
 use crate::extract::FromRequest;
+use crate::middleware::{LayerStack, MiddlewareLayer, PhaseTimer};
 use crate::request::Request;
 use crate::response::{IntoResponse, Response};
 use rustapi_openapi::{Operation, OperationModifier, ResponseModifier};
@@ -118,8 +119,9 @@ where
 {
     type Future = Pin<Box<dyn Future<Output = Response> + Send>>;
 
-    fn call(self, _req: Request) -> Self::Future {
-        Box::pin(async move { self().await.into_response() })
+    fn call(self, req: Request) -> Self::Future {
+        let timer = PhaseTimer::start(&req);
+        Box::pin(async move { timer.finish(self()).await })
     }
 
     fn update_operation(op: &mut Operation) {
@@ -143,11 +145,12 @@ where
 
     fn call(self, mut req: Request) -> Self::Future {
         Box::pin(async move {
+            let timer = PhaseTimer::start(&req);
             let t1 = match T1::from_request(&mut req).await {
                 Ok(v) => v,
                 Err(e) => return e.into_response(),
             };
-            self(t1).await.into_response()
+            timer.finish(self(t1)).await
         })
     }
 
@@ -175,6 +178,7 @@ where
 
     fn call(self, mut req: Request) -> Self::Future {
         Box::pin(async move {
+            let timer = PhaseTimer::start(&req);
             let t1 = match T1::from_request(&mut req).await {
                 Ok(v) => v,
                 Err(e) => return e.into_response(),
@@ -183,7 +187,7 @@ where
                 Ok(v) => v,
                 Err(e) => return e.into_response(),
             };
-            self(t1, t2).await.into_response()
+            timer.finish(self(t1, t2)).await
         })
     }
 
@@ -214,6 +218,7 @@ where
 
     fn call(self, mut req: Request) -> Self::Future {
         Box::pin(async move {
+            let timer = PhaseTimer::start(&req);
             let t1 = match T1::from_request(&mut req).await {
                 Ok(v) => v,
                 Err(e) => return e.into_response(),
@@ -226,7 +231,7 @@ where
                 Ok(v) => v,
                 Err(e) => return e.into_response(),
             };
-            self(t1, t2, t3).await.into_response()
+            timer.finish(self(t1, t2, t3)).await
         })
     }
 
@@ -260,6 +265,7 @@ where
 
     fn call(self, mut req: Request) -> Self::Future {
         Box::pin(async move {
+            let timer = PhaseTimer::start(&req);
             let t1 = match T1::from_request(&mut req).await {
                 Ok(v) => v,
                 Err(e) => return e.into_response(),
@@ -276,7 +282,7 @@ where
                 Ok(v) => v,
                 Err(e) => return e.into_response(),
             };
-            self(t1, t2, t3, t4).await.into_response()
+            timer.finish(self(t1, t2, t3, t4)).await
         })
     }
 
@@ -313,6 +319,7 @@ where
 
     fn call(self, mut req: Request) -> Self::Future {
         Box::pin(async move {
+            let timer = PhaseTimer::start(&req);
             let t1 = match T1::from_request(&mut req).await {
                 Ok(v) => v,
                 Err(e) => return e.into_response(),
@@ -333,7 +340,7 @@ where
                 Ok(v) => v,
                 Err(e) => return e.into_response(),
             };
-            self(t1, t2, t3, t4, t5).await.into_response()
+            timer.finish(self(t1, t2, t3, t4, t5)).await
         })
     }
 
@@ -360,6 +367,14 @@ where
 pub(crate) type BoxedHandler =
     std::sync::Arc<dyn Fn(Request) -> Pin<Box<dyn Future<Output = Response> + Send>> + Send + Sync>;
 
+/// Wrap a handler in route-level middleware
+pub(crate) fn layered_handler(handler: BoxedHandler, layers: LayerStack) -> BoxedHandler {
+    if layers.is_empty() {
+        return handler;
+    }
+    std::sync::Arc::new(move |req| layers.execute(req, handler.clone()))
+}
+
 /// Create a boxed handler from any Handler
 pub(crate) fn into_boxed_handler<H, T>(handler: H) -> BoxedHandler
 where
@@ -388,6 +403,7 @@ pub struct Route {
     pub(crate) path: &'static str,
     pub(crate) method: &'static str,
     pub(crate) handler: BoxedHandler,
+    pub(crate) handler_name: &'static str,
     pub(crate) operation: Operation,
     pub(crate) component_registrar: fn(&mut rustapi_openapi::OpenApiSpec),
     /// Custom parameter schemas for OpenAPI (param_name -> schema_type)
@@ -395,10 +411,17 @@ pub struct Route {
     pub(crate) param_schemas: std::collections::BTreeMap<String, String>,
     /// Custom error responses for OpenAPI (status_code -> description)
     pub(crate) error_responses: Vec<(u16, String)>,
+    /// Middleware that wraps only this route
+    pub(crate) layers: LayerStack,
+    /// Whether the route appears in the OpenAPI spec
+    pub(crate) documented: bool,
+    /// Where the route was created, for duplicate route reports
+    pub(crate) location: &'static std::panic::Location<'static>,
 }
 
 impl Route {
     /// Create a new route from a handler with path and method
+    #[track_caller]
     pub fn new<H, T>(path: &'static str, method: &'static str, handler: H) -> Self
     where
         H: Handler<T>,
@@ -411,10 +434,14 @@ impl Route {
             path,
             method,
             handler: into_boxed_handler(handler),
+            handler_name: std::any::type_name::<H>(),
             operation,
             component_registrar: <H as Handler<T>>::register_components,
             param_schemas: std::collections::BTreeMap::new(),
             error_responses: Vec::new(),
+            layers: LayerStack::new(),
+            documented: true,
+            location: std::panic::Location::caller(),
         }
     }
     /// Set the operation summary
@@ -429,6 +456,14 @@ impl Route {
         self
     }
 
+    /// Set the OpenAPI operation id
+    ///
+    /// Takes precedence over ids generated by [`RustApi::operation_ids`](crate::RustApi::operation_ids).
+    pub fn operation_id(mut self, id: impl Into<String>) -> Self {
+        self.operation.operation_id = Some(id.into());
+        self
+    }
+
     /// Add a tag to the operation
     pub fn tag(mut self, tag: impl Into<String>) -> Self {
         self.operation.tags.push(tag.into());
@@ -442,6 +477,77 @@ impl Route {
         self
     }
 
+    /// Wrap this route in a middleware layer
+    ///
+    /// Route layers run inside the app-wide layers, after routing, in the
+    /// order they are added (outermost first). `#[rustapi::middleware(...)]`
+    /// expands to these calls.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// get_route("/admin", dashboard)
+    ///     .layer(JwtLayer::<Claims>::new(secret))
+    ///     .layer(RateLimitLayer::new(5, Duration::from_secs(60)))
+    /// ```
+    pub fn layer<L>(mut self, layer: L) -> Self
+    where
+        L: MiddlewareLayer,
+    {
+        self.layers.push(Box::new(layer));
+        self
+    }
+
+    /// Require a role before the handler runs
+    ///
+    /// Adds a [`RequireGrant`](crate::authz::RequireGrant) layer and lists the
+    /// role under the route's `bearerAuth` security requirement.
+    /// `#[rustapi::require_role("admin")]` expands to this call.
+    pub fn require_role(self, role: impl Into<String>) -> Self {
+        self.require(crate::authz::RequireGrant::role(role))
+    }
+
+    /// Require an OAuth-style scope before the handler runs
+    ///
+    /// Like [`require_role`](Self::require_role), for scopes.
+    /// `#[rustapi::require_scope("pins:write")]` expands to this call.
+    pub fn require_scope(self, scope: impl Into<String>) -> Self {
+        self.require(crate::authz::RequireGrant::scope(scope))
+    }
+
+    fn require(mut self, grant: crate::authz::RequireGrant) -> Self {
+        crate::authz::document_requirement(&mut self.operation, grant.name());
+        if !self.operation.responses.contains_key("401") {
+            self = self.error_response(401, "Unauthorized");
+        }
+        if !self.operation.responses.contains_key("403") {
+            self = self.error_response(403, "Forbidden");
+        }
+        self.layer(grant)
+    }
+
+    /// Leave this route out of the OpenAPI spec
+    ///
+    /// For endpoints OpenAPI cannot describe, such as WebSocket upgrades
+    /// (`#[rustapi::ws]` sets this).
+    pub fn undocumented(mut self) -> Self {
+        self.documented = false;
+        self
+    }
+
+    /// Whether the route appears in the OpenAPI spec
+    pub fn is_documented(&self) -> bool {
+        self.documented
+    }
+
+    /// Source location where the route was created
+    ///
+    /// For macro routes this is the route attribute; otherwise the
+    /// `Route::new` or `*_route` call.
+    pub fn location(&self) -> &'static std::panic::Location<'static> {
+        self.location
+    }
+
     /// Get the route path
     pub fn path(&self) -> &str {
         self.path
@@ -545,6 +651,7 @@ macro_rules! route {
 }
 
 /// Create a GET route
+#[track_caller]
 pub fn get_route<H, T>(path: &'static str, handler: H) -> Route
 where
     H: Handler<T>,
@@ -554,6 +661,7 @@ where
 }
 
 /// Create a POST route
+#[track_caller]
 pub fn post_route<H, T>(path: &'static str, handler: H) -> Route
 where
     H: Handler<T>,
@@ -563,6 +671,7 @@ where
 }
 
 /// Create a PUT route
+#[track_caller]
 pub fn put_route<H, T>(path: &'static str, handler: H) -> Route
 where
     H: Handler<T>,
@@ -572,6 +681,7 @@ where
 }
 
 /// Create a PATCH route
+#[track_caller]
 pub fn patch_route<H, T>(path: &'static str, handler: H) -> Route
 where
     H: Handler<T>,
@@ -581,6 +691,7 @@ where
 }
 
 /// Create a DELETE route
+#[track_caller]
 pub fn delete_route<H, T>(path: &'static str, handler: H) -> Route
 where
     H: Handler<T>,
@@ -588,3 +699,57 @@ where
 {
     Route::new(path, "DELETE", handler)
 }
+
+/// Create a HEAD route
+#[track_caller]
+pub fn head_route<H, T>(path: &'static str, handler: H) -> Route
+where
+    H: Handler<T>,
+    T: 'static,
+{
+    Route::new(path, "HEAD", handler)
+}
+
+/// Create an OPTIONS route
+#[track_caller]
+pub fn options_route<H, T>(path: &'static str, handler: H) -> Route
+where
+    H: Handler<T>,
+    T: 'static,
+{
+    Route::new(path, "OPTIONS", handler)
+}
+
+/// Create a route that answers every standard method
+///
+/// Covers GET, HEAD, POST, PUT, PATCH, DELETE, OPTIONS and TRACE. The route
+/// reports its method as `"ANY"`; when mounted, it is registered (and
+/// documented) once per method.
+#[track_caller]
+pub fn any_route<H, T>(path: &'static str, handler: H) -> Route
+where
+    H: Handler<T>,
+    T: 'static,
+{
+    Route::new(path, "ANY", handler)
+}
+
+/// Methods served by [`any_route`]
+const ANY_METHODS: [http::Method; 8] = [
+    http::Method::GET,
+    http::Method::HEAD,
+    http::Method::POST,
+    http::Method::PUT,
+    http::Method::PATCH,
+    http::Method::DELETE,
+    http::Method::OPTIONS,
+    http::Method::TRACE,
+];
+
+/// Methods a route with the given method name is registered under
+pub(crate) fn route_methods(method: &str) -> Vec<http::Method> {
+    if method == "ANY" {
+        return ANY_METHODS.to_vec();
+    }
+    vec![http::Method::from_bytes(method.as_bytes()).unwrap_or(http::Method::GET)]
+}
@@ -0,0 +1,271 @@
+//! Zero-downtime restarts
+//!
+//! Deploying a new binary without dropping connections takes two things: the
+//! new process must listen on the port before the old one stops, and the old
+//! one must finish the requests it already accepted. With a [`Handover`]
+//! configured through [`RustApi::handover`], the server:
+//!
+//! - takes over a listening socket passed by the parent process, either with
+//!   systemd socket activation (`LISTEN_FDS`/`LISTEN_PID`) or as a file
+//!   descriptor number in `RUSTAPI_LISTEN_FD`; the descriptor must be a
+//!   listening TCP socket, and is taken over at most once per process. The
+//!   environment is left as it is, so remove `RUSTAPI_LISTEN_FD` from
+//!   processes the app spawns itself (`Command::env_remove`)
+//! - otherwise binds with `SO_REUSEPORT`, so old and new process can listen on
+//!   the same port while the old one drains
+//! - on shutdown stops accepting, lets open connections finish their current
+//!   request and waits for them up to the drain timeout
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! use rustapi_rs::prelude::*;
+//! use rustapi_rs::Handover;
+//!
+//! RustApi::new()
+//!     .handover(Handover::new().drain_timeout(Duration::from_secs(30)))
+//!     .route("/", get(index))
+//!     .run_with_shutdown("0.0.0.0:8080", async {
+//!         // systemd sends SIGTERM to the old process once the new one runs
+//!         let _ = tokio::signal::ctrl_c().await;
+//!     })
+//!     .await
+//! ```
+//!
+//! [`RustApi::handover`]: crate::RustApi::handover
+
+use std::io;
+use std::net::SocketAddr;
+#[cfg(unix)]
+use std::sync::atomic::{AtomicBool, Ordering};
+#[cfg(unix)]
+use std::sync::OnceLock;
+use std::time::Duration;
+use tokio::net::TcpListener;
+
+/// Environment variable holding an inherited listener's file descriptor
+pub const LISTEN_FD_ENV: &str = "RUSTAPI_LISTEN_FD";
+
+/// Default time open connections get to finish after shutdown
+pub const DEFAULT_DRAIN_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// First file descriptor passed by systemd socket activation
+#[cfg(unix)]
+const SD_LISTEN_FDS_START: i32 = 3;
+
+/// How the server binds its listener and drains on shutdown
+#[derive(Debug, Clone)]
+pub struct Handover {
+    inherit_listener: bool,
+    reuse_port: bool,
+    drain_timeout: Duration,
+}
+
+impl Default for Handover {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Handover {
+    /// Inherit a listener if one is passed, bind with `SO_REUSEPORT`
+    /// otherwise, and drain for [`DEFAULT_DRAIN_TIMEOUT`]
+    pub fn new() -> Self {
+        Self {
+            inherit_listener: true,
+            reuse_port: true,
+            drain_timeout: DEFAULT_DRAIN_TIMEOUT,
+        }
+    }
+
+    /// Take over a listener from `LISTEN_FDS` or `RUSTAPI_LISTEN_FD`
+    pub fn inherit_listener(mut self, inherit: bool) -> Self {
+        self.inherit_listener = inherit;
+        self
+    }
+
+    /// Bind with `SO_REUSEPORT` when no listener is inherited
+    ///
+    /// Only has an effect on Unix platforms that support the option.
+    pub fn reuse_port(mut self, reuse: bool) -> Self {
+        self.reuse_port = reuse;
+        self
+    }
+
+    /// Maximum time to wait for open connections after shutdown
+    pub fn drain_timeout(mut self, timeout: Duration) -> Self {
+        self.drain_timeout = timeout;
+        self
+    }
+
+    pub(crate) fn drain_timeout_value(&self) -> Duration {
+        self.drain_timeout
+    }
+
+    /// The listener to serve on, and whether it was inherited
+    pub(crate) fn listen(&self, addr: SocketAddr) -> io::Result<(TcpListener, bool)> {
+        if self.inherit_listener {
+            if let Some(listener) = inherited_listener()? {
+                return Ok((listener, true));
+            }
+        }
+
+        let socket = if addr.is_ipv4() {
+            tokio::net::TcpSocket::new_v4()?
+        } else {
+            tokio::net::TcpSocket::new_v6()?
+        };
+        socket.set_reuseaddr(true)?;
+        #[cfg(all(unix, not(target_os = "solaris"), not(target_os = "illumos")))]
+        if self.reuse_port {
+            socket.set_reuseport(true)?;
+        }
+        socket.bind(addr)?;
+        Ok((socket.listen(1024)?, false))
+    }
+}
+
+/// The listener passed by the parent process, if any
+#[cfg(unix)]
+fn inherited_listener() -> io::Result<Option<TcpListener>> {
+    use std::os::unix::io::FromRawFd;
+
+    // The environment is read once; the descriptor can only be owned once
+    static FD: OnceLock<Option<i32>> = OnceLock::new();
+    static TAKEN: AtomicBool = AtomicBool::new(false);
+
+    let fd = *FD.get_or_init(|| {
+        inherited_fd(
+            std::env::var("LISTEN_FDS").ok().as_deref(),
+            std::env::var("LISTEN_PID").ok().as_deref(),
+            std::env::var(LISTEN_FD_ENV).ok().as_deref(),
+            std::process::id(),
+        )
+    });
+    let Some(fd) = fd else {
+        return Ok(None);
+    };
+    if TAKEN.swap(true, Ordering::SeqCst) {
+        return Ok(None);
+    }
+    check_listener_fd(fd)?;
+
+    // SAFETY: the parent passed this descriptor for us to own, and it is a
+    // listening stream socket
+    let listener = unsafe { std::net::TcpListener::from_raw_fd(fd) };
+    listener.set_nonblocking(true)?;
+    TcpListener::from_std(listener).map(Some)
+}
+
+/// Fail unless `fd` is a listening stream socket
+#[cfg(unix)]
+fn check_listener_fd(fd: i32) -> io::Result<()> {
+    let option = |name: libc::c_int| -> io::Result<libc::c_int> {
+        let mut value: libc::c_int = 0;
+        let mut len = std::mem::size_of::<libc::c_int>() as libc::socklen_t;
+        // SAFETY: `value` and `len` describe a writable c_int
+        let rc = unsafe {
+            libc::getsockopt(
+                fd,
+                libc::SOL_SOCKET,
+                name,
+                &mut value as *mut libc::c_int as *mut libc::c_void,
+                &mut len,
+            )
+        };
+        if rc == 0 {
+            Ok(value)
+        } else {
+            Err(io::Error::last_os_error())
+        }
+    };
+    let invalid = |what: &str| {
+        io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("inherited file descriptor {} is not {}", fd, what),
+        )
+    };
+
+    match option(libc::SO_TYPE) {
+        Ok(libc::SOCK_STREAM) => {}
+        Ok(_) => return Err(invalid("a stream socket")),
+        Err(e) => return Err(invalid(&format!("a socket ({})", e))),
+    }
+    if option(libc::SO_ACCEPTCONN)? == 0 {
+        return Err(invalid("listening"));
+    }
+    Ok(())
+}
+
+#[cfg(not(unix))]
+fn inherited_listener() -> io::Result<Option<TcpListener>> {
+    Ok(None)
+}
+
+/// The inherited descriptor named by the environment
+///
+/// systemd's variables only apply when `LISTEN_PID` is this process, so they
+/// are not picked up by children that inherited the environment.
+#[cfg(unix)]
+fn inherited_fd(
+    listen_fds: Option<&str>,
+    listen_pid: Option<&str>,
+    listen_fd: Option<&str>,
+    pid: u32,
+) -> Option<i32> {
+    let activated = listen_pid.and_then(|p| p.parse::<u32>().ok()) == Some(pid)
+        && listen_fds.and_then(|n| n.parse::<u32>().ok()).unwrap_or(0) > 0;
+    if activated {
+        return Some(SD_LISTEN_FDS_START);
+    }
+    listen_fd
+        .and_then(|fd| fd.parse::<i32>().ok())
+        .filter(|fd| *fd >= 0)
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn inherited_fd_from_environment() {
+        assert_eq!(inherited_fd(Some("1"), Some("42"), None, 42), Some(3));
+        assert_eq!(inherited_fd(Some("1"), Some("41"), None, 42), None);
+        assert_eq!(inherited_fd(Some("0"), Some("42"), Some("7"), 42), Some(7));
+        assert_eq!(inherited_fd(None, None, Some("x"), 42), None);
+        assert_eq!(inherited_fd(None, None, None, 42), None);
+    }
+
+    #[test]
+    fn only_listening_stream_sockets_are_taken_over() {
+        use std::os::unix::io::AsRawFd;
+
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        assert!(check_listener_fd(listener.as_raw_fd()).is_ok());
+
+        let udp = std::net::UdpSocket::bind("127.0.0.1:0").unwrap();
+        let err = check_listener_fd(udp.as_raw_fd()).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+
+        let stream = std::net::TcpStream::connect(listener.local_addr().unwrap()).unwrap();
+        assert!(check_listener_fd(stream.as_raw_fd()).is_err());
+
+        let file = std::fs::File::open(std::env::current_exe().unwrap()).unwrap();
+        assert!(check_listener_fd(file.as_raw_fd()).is_err());
+    }
+
+    #[cfg(not(any(target_os = "solaris", target_os = "illumos")))]
+    #[tokio::test]
+    async fn reuse_port_lets_two_listeners_share_a_port() {
+        let handover = Handover::new().inherit_listener(false);
+        let (old, inherited) = handover.listen("127.0.0.1:0".parse().unwrap()).unwrap();
+        assert!(!inherited);
+        let addr = old.local_addr().unwrap();
+
+        let (new, _) = handover.listen(addr).unwrap();
+        assert_eq!(new.local_addr().unwrap(), addr);
+
+        let plain = Handover::new().inherit_listener(false).reuse_port(false);
+        assert!(plain.listen(addr).is_err());
+    }
+}
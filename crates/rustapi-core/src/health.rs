@@ -25,6 +25,7 @@
 //! }
 //! ```
 
+use crate::middleware::{BoxedNext, MiddlewareLayer};
 use crate::response::{Body, IntoResponse, Response};
 use http::{header, StatusCode};
 use rustapi_openapi::{MediaType, Operation, ResponseModifier, ResponseSpec, SchemaRef};
@@ -33,7 +34,9 @@ use serde_json::json;
 use std::collections::HashMap;
 use std::future::Future;
 use std::pin::Pin;
-use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
 
 /// Health status of a component
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -385,6 +388,195 @@ impl Default for HealthCheckBuilder {
     }
 }
 
+/// Holds traffic back until startup checks pass
+///
+/// While the gate is closed every route except the health endpoints answers
+/// `503 Service Unavailable` with `Retry-After`, and the readiness endpoint
+/// lists the checks that have not passed yet. The server runs the checks
+/// when it starts, repeating the failing ones every poll interval, and opens
+/// the gate once none is unhealthy. The gate never closes again; later
+/// outages are reported by the regular health checks.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// let startup = HealthCheckBuilder::new(false)
+///     .add_check("migrations", move || migrations_applied(db.clone()))
+///     .add_check("cache", move || cache_warm(cache.clone()))
+///     .build();
+///
+/// RustApi::new()
+///     .health_endpoints()
+///     .readiness_gate(ReadinessGate::new(startup))
+///     .run("0.0.0.0:8080")
+///     .await
+/// ```
+///
+/// A gate without checks ([`ReadinessGate::manual`]) stays closed until a
+/// clone of it is [`open`](ReadinessGate::open)ed.
+#[derive(Clone)]
+pub struct ReadinessGate {
+    ready: Arc<AtomicBool>,
+    pending: Arc<Mutex<Vec<String>>>,
+    checks: Option<HealthCheck>,
+    poll_interval: Duration,
+    allowed_paths: Vec<String>,
+}
+
+impl ReadinessGate {
+    /// A gate that opens once all `checks` pass
+    pub fn new(checks: HealthCheck) -> Self {
+        let pending = checks.checks.keys().cloned().collect();
+        Self {
+            checks: Some(checks),
+            ..Self::manual()
+        }
+        .with_pending(pending)
+    }
+
+    /// A gate that opens when [`open`](Self::open) is called
+    pub fn manual() -> Self {
+        Self {
+            ready: Arc::new(AtomicBool::new(false)),
+            pending: Arc::new(Mutex::new(Vec::new())),
+            checks: None,
+            poll_interval: Duration::from_secs(1),
+            allowed_paths: Vec::new(),
+        }
+    }
+
+    fn with_pending(self, mut pending: Vec<String>) -> Self {
+        pending.sort();
+        *self.pending.lock().unwrap_or_else(|e| e.into_inner()) = pending;
+        self
+    }
+
+    /// How often failing checks are repeated (default: 1 second)
+    pub fn poll_interval(mut self, interval: Duration) -> Self {
+        self.poll_interval = interval;
+        self
+    }
+
+    /// Also serve paths starting with `prefix` while the gate is closed
+    ///
+    /// The health endpoints are always served.
+    pub fn allow_path(mut self, prefix: impl Into<String>) -> Self {
+        self.allowed_paths.push(prefix.into());
+        self
+    }
+
+    /// Start serving all routes
+    pub fn open(&self) {
+        self.ready.store(true, Ordering::Release);
+    }
+
+    /// Whether all routes are served
+    pub fn is_open(&self) -> bool {
+        self.ready.load(Ordering::Acquire)
+    }
+
+    /// Names of the checks that have not passed yet
+    pub fn pending(&self) -> Vec<String> {
+        self.pending
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone()
+    }
+
+    /// Run the checks until none is unhealthy, then open the gate
+    pub(crate) async fn wait_for_checks(self) {
+        let Some(checks) = &self.checks else {
+            return;
+        };
+        while !self.is_open() {
+            let result = checks.execute().await;
+            let mut pending: Vec<String> = result
+                .checks
+                .into_iter()
+                .filter(|(_, status)| status.is_unhealthy())
+                .map(|(name, _)| name)
+                .collect();
+            pending.sort();
+            let passed = pending.is_empty();
+            *self.pending.lock().unwrap_or_else(|e| e.into_inner()) = pending;
+            if passed {
+                tracing::info!("Readiness checks passed; serving traffic");
+                self.open();
+            } else {
+                tokio::time::sleep(self.poll_interval).await;
+            }
+        }
+    }
+
+    /// The readiness endpoint response while the gate is closed
+    pub(crate) fn closed_response(&self) -> HealthResponse {
+        HealthResponse::new(
+            StatusCode::SERVICE_UNAVAILABLE,
+            json!({
+                "status": { "unhealthy": { "reason": "waiting for startup checks" } },
+                "pending": self.pending(),
+            }),
+        )
+    }
+
+    /// Middleware rejecting requests while the gate is closed
+    pub(crate) fn layer(&self, health_paths: Vec<String>) -> ReadinessGateLayer {
+        ReadinessGateLayer {
+            ready: self.ready.clone(),
+            health_paths: Arc::new(health_paths),
+            allowed_paths: Arc::new(self.allowed_paths.clone()),
+        }
+    }
+}
+
+impl std::fmt::Debug for ReadinessGate {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ReadinessGate")
+            .field("open", &self.is_open())
+            .field("pending", &self.pending())
+            .finish()
+    }
+}
+
+/// Answers `503` until its [`ReadinessGate`] opens
+#[derive(Clone)]
+pub(crate) struct ReadinessGateLayer {
+    ready: Arc<AtomicBool>,
+    health_paths: Arc<Vec<String>>,
+    allowed_paths: Arc<Vec<String>>,
+}
+
+impl MiddlewareLayer for ReadinessGateLayer {
+    fn call(
+        &self,
+        req: crate::Request,
+        next: BoxedNext,
+    ) -> Pin<Box<dyn Future<Output = Response> + Send + 'static>> {
+        let path = req.uri().path();
+        let allowed = self.ready.load(Ordering::Acquire)
+            || self.health_paths.iter().any(|health| health == path)
+            || self
+                .allowed_paths
+                .iter()
+                .any(|prefix| path.starts_with(prefix.as_str()));
+        if allowed {
+            return Box::pin(async move { next(req).await });
+        }
+        Box::pin(async move {
+            let mut response =
+                crate::error::ApiError::service_unavailable("Service is starting").into_response();
+            response
+                .headers_mut()
+                .insert(header::RETRY_AFTER, http::HeaderValue::from_static("1"));
+            response
+        })
+    }
+
+    fn clone_box(&self) -> Box<dyn MiddlewareLayer> {
+        Box::new(self.clone())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -453,4 +645,66 @@ mod tests {
 
         assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
     }
+
+    #[tokio::test(start_paused = true)]
+    async fn readiness_gate_opens_once_checks_pass() {
+        let warm = Arc::new(AtomicBool::new(false));
+        let checks = {
+            let warm = warm.clone();
+            HealthCheckBuilder::new(false)
+                .add_check("cache", move || {
+                    let warm = warm.load(Ordering::SeqCst);
+                    async move {
+                        if warm {
+                            HealthStatus::healthy()
+                        } else {
+                            HealthStatus::unhealthy("warming up")
+                        }
+                    }
+                })
+                .build()
+        };
+        let gate = ReadinessGate::new(checks).poll_interval(Duration::from_millis(100));
+        assert_eq!(gate.pending(), vec!["cache".to_string()]);
+
+        let task = tokio::spawn(gate.clone().wait_for_checks());
+        tokio::time::sleep(Duration::from_millis(250)).await;
+        assert!(!gate.is_open());
+        assert_eq!(
+            gate.closed_response().into_response().status(),
+            StatusCode::SERVICE_UNAVAILABLE
+        );
+
+        warm.store(true, Ordering::SeqCst);
+        task.await.unwrap();
+        assert!(gate.is_open());
+        assert!(gate.pending().is_empty());
+    }
+
+    #[tokio::test]
+    async fn closed_gate_only_serves_health_and_allowed_paths() {
+        use crate::middleware::LayerStack;
+
+        let gate = ReadinessGate::manual().allow_path("/metrics");
+        let mut stack = LayerStack::new();
+        stack.push(Box::new(gate.layer(vec!["/ready".to_string()])));
+
+        let status = |path: &'static str| {
+            let req = http::Request::builder().uri(path).body(()).unwrap();
+            let req = crate::Request::from_http_request(req, bytes::Bytes::new());
+            let next: BoxedNext = Arc::new(|_req: crate::Request| {
+                Box::pin(async { "ok".into_response() })
+                    as Pin<Box<dyn Future<Output = Response> + Send + 'static>>
+            });
+            let response = stack.execute(req, next);
+            async move { response.await.status() }
+        };
+
+        assert_eq!(status("/pins").await, StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(status("/ready").await, StatusCode::OK);
+        assert_eq!(status("/metrics/app").await, StatusCode::OK);
+
+        gate.open();
+        assert_eq!(status("/pins").await, StatusCode::OK);
+    }
 }
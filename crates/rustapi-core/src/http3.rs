@@ -270,6 +270,7 @@ impl Http3Server {
 
         // Convert to our Request type
         let (parts, _) = req.into_parts();
+        let accept = parts.headers.get(header::ACCEPT).cloned();
         let request = Request::new(
             parts,
             crate::request::BodyVariant::Buffered(Bytes::from(body_bytes)),
@@ -302,7 +303,7 @@ impl Http3Server {
                         let allowed_str: Vec<&str> = allowed.iter().map(|m| m.as_str()).collect();
                         let mut response = ApiError::new(
                             StatusCode::METHOD_NOT_ALLOWED,
-                            "method_not_allowed",
+                            crate::error::codes::METHOD_NOT_ALLOWED,
                             format!("Method {} not allowed for {}", method, path),
                         )
                         .into_response();
@@ -327,6 +328,7 @@ impl Http3Server {
 
         // Apply response interceptors
         let response = interceptors.intercept_response(response);
+        let response = crate::error_format::negotiate_error_response(accept.as_ref(), response);
 
         // Log request
         let elapsed = start.elapsed();
@@ -40,6 +40,7 @@
 //!
 //! - `metrics` - Enable Prometheus metrics middleware
 //! - `cookies` - Enable cookie parsing extractor
+//! - `cookies-secure` - Enable signed and private cookie jars
 //! - `test-utils` - Enable testing utilities like `TestClient`
 //! - `swagger-ui` - Enable Swagger UI documentation endpoint
 //! - `http3` - Enable HTTP/3 (QUIC) support
@@ -51,36 +52,56 @@
 //! full framework experience with all features and re-exports.
 
 mod app;
+pub mod authz;
 mod auto_route;
 pub use auto_route::{auto_route_count, collect_auto_routes};
 mod auto_schema;
 pub use auto_schema::apply_auto_schemas;
+pub mod canary;
+pub mod clock;
 #[cfg(feature = "dashboard")]
 pub mod dashboard;
+pub mod deadline;
 mod error;
+pub mod error_catalog;
+pub mod error_format;
 pub mod events;
 mod extract;
 mod handler;
+pub mod handover;
 pub mod hateoas;
 pub mod health;
 #[cfg(feature = "http3")]
 pub mod http3;
 pub mod interceptor;
 pub(crate) mod json;
+pub mod locale;
+pub mod log_filter;
+pub mod long_poll;
 pub mod middleware;
 pub mod multipart;
+pub mod operation_id;
 pub(crate) mod path_params;
 pub(crate) mod path_validation;
+pub mod profile;
+pub mod query;
 #[cfg(feature = "replay")]
 pub mod replay;
 mod request;
 mod response;
+pub mod route_table;
 mod router;
+#[cfg(feature = "cookies-secure")]
+pub mod secure_cookies;
 mod server;
 pub mod sse;
+pub mod startup;
 pub mod static_files;
 pub mod status;
 pub mod stream;
+pub mod tenant;
+#[cfg(feature = "cookies")]
+pub mod typed_cookie;
 pub mod typed_path;
 pub mod validation;
 #[macro_use]
@@ -92,7 +113,7 @@ mod tracing_macros;
 ///
 /// It re-exports:
 /// - `linkme` (for `#[distributed_slice]` attributes generated by our route macros)
-/// - The two distributed slices (`AUTO_ROUTES` and `AUTO_SCHEMAS`)
+/// - The distributed slices (`AUTO_ROUTES`, `AUTO_SCHEMAS` and `ERROR_CODES`)
 /// - Other internal crates needed by the generated code
 ///
 /// **Nothing in this module is part of the public API.** It is subject to change
@@ -115,6 +136,7 @@ pub mod __private {
 
     pub use crate::auto_route::AUTO_ROUTES;
     pub use crate::auto_schema::AUTO_SCHEMAS;
+    pub use crate::error_catalog::ERROR_CODES;
 
     pub use rustapi_openapi;
     pub use rustapi_validate;
@@ -122,49 +144,74 @@ pub mod __private {
 
 // Public API
 pub use app::{ProductionDefaultsConfig, RequestDispatcher, RustApi, RustApiConfig};
+pub use authz::{Grants, RequireGrant, TokenClaims};
+pub use canary::{Arm, ArmStats, Canary, CanaryArm, CanaryStats};
+pub use clock::Clock;
 #[cfg(feature = "dashboard")]
 pub use dashboard::{DashboardConfig, DashboardMetrics, DashboardSnapshot};
+pub use deadline::{Deadline, DeadlineExceeded};
 pub use error::{get_environment, ApiError, Environment, FieldError, Result};
+pub use error_catalog::{ErrorCatalog, ErrorCodeEntry};
 pub use events::EventBus;
 #[cfg(feature = "cookies")]
 pub use extract::Cookies;
 pub use extract::{
     AsyncValidatedJson, Body, BodyStream, ClientIp, CursorPaginate, Extension, FromRequest,
-    FromRequestParts, HeaderValue, Headers, Json, Paginate, Path, Query, State, Typed,
-    ValidatedJson,
+    FromRequestParts, HeaderStruct, HeaderValue, Headers, Json, Paginate, Path, Query, State,
+    Typed, ValidatedForm, ValidatedJson,
 };
 pub use handler::{
-    delete_route, get_route, patch_route, post_route, put_route, Handler, HandlerService, Route,
-    RouteHandler,
+    any_route, delete_route, get_route, head_route, options_route, patch_route, post_route,
+    put_route, Handler, HandlerService, Route, RouteHandler,
 };
+pub use handover::Handover;
 pub use hateoas::{
     CursorPaginated, Link, LinkOrArray, Linkable, PageInfo, Paginated, Resource, ResourceCollection,
 };
 pub use health::{
     HealthCheck, HealthCheckBuilder, HealthCheckResult, HealthEndpointConfig, HealthResponse,
-    HealthStatus,
+    HealthStatus, ReadinessGate,
 };
 pub use http::StatusCode;
 #[cfg(feature = "http3")]
 pub use http3::{Http3Config, Http3Server};
 pub use interceptor::{InterceptorChain, RequestInterceptor, ResponseInterceptor};
+pub use locale::{Locale, LocaleConfig};
+pub use log_filter::{LogFilter, LogFilterError};
+pub use long_poll::{LongPoll, LongPollResponse};
 #[cfg(feature = "compression")]
 pub use middleware::CompressionLayer;
-pub use middleware::{BodyLimitLayer, RequestId, RequestIdLayer, TracingLayer, DEFAULT_BODY_LIMIT};
+pub use middleware::{
+    BodyLimitLayer, BodyTransformLayer, LogSampling, RequestId, RequestIdLayer, ServerTiming,
+    ServerTimingLayer, TracingLayer, DEFAULT_BODY_LIMIT,
+};
 #[cfg(feature = "metrics")]
 pub use middleware::{MetricsLayer, MetricsResponse};
 pub use multipart::{
-    Multipart, MultipartConfig, MultipartField, StreamingMultipart, StreamingMultipartField,
-    UploadedFile,
+    FieldRule, Multipart, MultipartConfig, MultipartField, StreamingMultipart,
+    StreamingMultipartField, UploadedFile,
 };
+pub use operation_id::{OperationIdContext, OperationIdStrategy};
 pub use path_params::PathParams;
+pub use profile::Profile;
 pub use request::{BodyVariant, Request};
 pub use response::{
     Body as ResponseBody, Created, Html, IntoResponse, NoContent, Redirect, Response, WithStatus,
 };
+pub use route_table::{
+    DuplicateRoute, DuplicateRouteError, DuplicateRoutePolicy, RouteOrigin, RouteTable,
+    RouteTableEntry,
+};
 pub use router::{delete, get, patch, post, put, MethodRouter, RouteMatch, Router};
-pub use sse::{sse_from_iter, sse_response, KeepAlive, Sse, SseEvent};
-pub use static_files::{serve_dir, StaticFile, StaticFileConfig};
-pub use stream::{StreamBody, StreamingBody, StreamingConfig};
+pub use server::RustApiService;
+pub use sse::{
+    sse_from_broadcast, sse_from_iter, sse_response, KeepAlive, LastEventId, Sse, SseEvent,
+};
+pub use startup::StartupReport;
+pub use static_files::{serve_dir, DirectoryListing, ListingSort, StaticFile, StaticFileConfig};
+pub use stream::{
+    BodyProgress, LimitErrorFn, ProgressCallback, StreamBody, StreamingBody, StreamingConfig,
+};
+pub use tenant::{Tenant, TenantLayer, TenantMap, TenantResolver};
 pub use typed_path::TypedPath;
 pub use validation::Validatable;
@@ -0,0 +1,283 @@
+//! Request locale
+//!
+//! The [`Locale`] extractor picks the language of a response from the
+//! locales the app supports. In order, it uses:
+//!
+//! 1. the override header, if [`LocaleConfig::header`] is set (e.g. `X-Locale: tr`)
+//! 2. the override cookie, if [`LocaleConfig::cookie`] is set (e.g. `lang=tr`)
+//! 3. `Accept-Language`, by quality weight
+//! 4. the default locale
+//!
+//! Overrides naming an unsupported locale are ignored. `en-US` matches a
+//! supported `en`.
+//!
+//! Add a [`LocaleConfig`] to app state to choose the supported locales. It
+//! also localizes the messages of `ValidatedJson`, `ValidatedForm` and
+//! `AsyncValidatedJson` errors, and `rustapi-view` templates use the same
+//! locale. Without one, the locales of `rustapi-validate` are supported and
+//! validation messages are left as they are.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! let app = RustApi::new()
+//!     .state(LocaleConfig::new(["en", "tr", "de"]).cookie("lang").header("X-Locale"))
+//!     .route("/greeting", get(greeting));
+//!
+//! async fn greeting(locale: Locale) -> String {
+//!     match locale.as_str() {
+//!         "tr" => "Merhaba".to_string(),
+//!         "de" => "Hallo".to_string(),
+//!         _ => "Hello".to_string(),
+//!     }
+//! }
+//! ```
+
+use crate::error::Result;
+use crate::extract::FromRequestParts;
+use crate::request::Request;
+use rustapi_openapi::{Operation, OperationModifier, Parameter, SchemaRef};
+
+/// Locale of a request, chosen from the supported locales
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Locale(pub String);
+
+impl Locale {
+    /// The locale tag (e.g. `en`)
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// The best supported locale for an `Accept-Language` value, if any
+    pub fn negotiate(accept_language: &str, supported: &[String]) -> Option<Self> {
+        let mut ranges: Vec<(&str, f32)> = accept_language
+            .split(',')
+            .filter_map(|range| {
+                let mut parts = range.split(';');
+                let tag = parts.next()?.trim();
+                let quality = parts
+                    .find_map(|param| param.trim().strip_prefix("q="))
+                    .and_then(|q| q.parse().ok())
+                    .unwrap_or(1.0);
+                (!tag.is_empty() && quality > 0.0).then_some((tag, quality))
+            })
+            .collect();
+        // Stable sort keeps header order for equal weights
+        ranges.sort_by(|a, b| b.1.total_cmp(&a.1));
+        ranges.iter().find_map(|(tag, _)| matching(tag, supported))
+    }
+
+    /// The locale of a request with `config`
+    pub fn resolve(req: &Request, config: &LocaleConfig) -> Self {
+        let header_override = config
+            .header
+            .as_deref()
+            .and_then(|name| req.headers().get(name))
+            .and_then(|value| value.to_str().ok());
+        let cookie_override = config
+            .cookie
+            .as_deref()
+            .and_then(|name| cookie_value(req, name));
+
+        header_override
+            .and_then(|tag| matching(tag.trim(), &config.supported))
+            .or_else(|| cookie_override.and_then(|tag| matching(tag, &config.supported)))
+            .or_else(|| {
+                let accept_language = req
+                    .headers()
+                    .get(http::header::ACCEPT_LANGUAGE)
+                    .and_then(|value| value.to_str().ok())?;
+                Self::negotiate(accept_language, &config.supported)
+            })
+            .unwrap_or_else(|| config.fallback())
+    }
+
+    /// The locale of a request, if the app state has a [`LocaleConfig`]
+    pub fn configured(req: &Request) -> Option<Self> {
+        let config = req.state().get::<LocaleConfig>()?;
+        Some(Self::resolve(req, config))
+    }
+}
+
+impl AsRef<str> for Locale {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl std::fmt::Display for Locale {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl FromRequestParts for Locale {
+    fn from_request_parts(req: &Request) -> Result<Self> {
+        Ok(match req.state().get::<LocaleConfig>() {
+            Some(config) => Self::resolve(req, config),
+            None => Self::resolve(req, &LocaleConfig::default()),
+        })
+    }
+}
+
+impl OperationModifier for Locale {
+    fn update_operation(op: &mut Operation) {
+        op.parameters.push(Parameter {
+            name: "Accept-Language".to_string(),
+            location: "header".to_string(),
+            description: Some("Preferred languages for the response".to_string()),
+            required: false,
+            deprecated: None,
+            style: None,
+            explode: None,
+            schema: Some(SchemaRef::Inline(serde_json::json!({ "type": "string" }))),
+        });
+    }
+}
+
+/// Supported locales and overrides for [`Locale`]
+///
+/// Add it to app state with `RustApi::state`.
+#[derive(Debug, Clone)]
+pub struct LocaleConfig {
+    supported: Vec<String>,
+    default: Option<String>,
+    header: Option<String>,
+    cookie: Option<String>,
+}
+
+impl LocaleConfig {
+    /// Support `locales`, in order of preference
+    pub fn new<I, S>(locales: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        Self {
+            supported: locales.into_iter().map(Into::into).collect(),
+            default: None,
+            header: None,
+            cookie: None,
+        }
+    }
+
+    /// Locale used when nothing else matches
+    ///
+    /// Defaults to `en` if supported, else the first supported locale.
+    pub fn default_locale(mut self, locale: impl Into<String>) -> Self {
+        self.default = Some(locale.into());
+        self
+    }
+
+    /// Let a request header choose the locale, ahead of `Accept-Language`
+    pub fn header(mut self, name: impl Into<String>) -> Self {
+        self.header = Some(name.into());
+        self
+    }
+
+    /// Let a cookie choose the locale, ahead of `Accept-Language`
+    pub fn cookie(mut self, name: impl Into<String>) -> Self {
+        self.cookie = Some(name.into());
+        self
+    }
+
+    /// The supported locales
+    pub fn supported(&self) -> &[String] {
+        &self.supported
+    }
+
+    fn fallback(&self) -> Locale {
+        self.default
+            .clone()
+            .map(Locale)
+            .or_else(|| matching("en", &self.supported))
+            .or_else(|| self.supported.first().cloned().map(Locale))
+            .unwrap_or_else(|| Locale("en".to_string()))
+    }
+}
+
+impl Default for LocaleConfig {
+    /// The locales `rustapi-validate` has translations for
+    fn default() -> Self {
+        Self::new(rustapi_validate::v2::i18n::available_locales())
+    }
+}
+
+/// The supported locale for `tag`, trying its primary language as well
+fn matching(tag: &str, supported: &[String]) -> Option<Locale> {
+    let find = |tag: &str| {
+        supported
+            .iter()
+            .find(|locale| locale.eq_ignore_ascii_case(tag))
+            .cloned()
+            .map(Locale)
+    };
+    find(tag).or_else(|| find(tag.split(['-', '_']).next().unwrap_or(tag)))
+}
+
+fn cookie_value<'a>(req: &'a Request, name: &str) -> Option<&'a str> {
+    req.headers()
+        .get_all(http::header::COOKIE)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(';'))
+        .filter_map(|pair| pair.trim().split_once('='))
+        .find(|(key, _)| *key == name)
+        .map(|(_, value)| value.trim_matches('"'))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bytes::Bytes;
+
+    fn supported() -> Vec<String> {
+        vec!["en".to_string(), "tr".to_string(), "de".to_string()]
+    }
+
+    fn request(headers: &[(&str, &str)]) -> Request {
+        let mut builder = http::Request::builder().uri("/");
+        for (name, value) in headers {
+            builder = builder.header(*name, *value);
+        }
+        Request::from_http_request(builder.body(()).unwrap(), Bytes::new())
+    }
+
+    #[test]
+    fn negotiates_by_quality_and_primary_language() {
+        let locale = Locale::negotiate("fr-FR, tr;q=0.8, en;q=0.5", &supported());
+        assert_eq!(locale.unwrap().as_str(), "tr");
+
+        let locale = Locale::negotiate("tr-TR;q=0.4, de-AT;q=0.9", &supported());
+        assert_eq!(locale.unwrap().as_str(), "de");
+
+        assert_eq!(Locale::negotiate("fr, en;q=0", &supported()), None);
+    }
+
+    #[test]
+    fn overrides_take_precedence_when_supported() {
+        let config = LocaleConfig::new(supported())
+            .header("X-Locale")
+            .cookie("lang")
+            .default_locale("de");
+
+        let req = request(&[
+            ("accept-language", "en"),
+            ("cookie", "theme=dark; lang=tr"),
+            ("x-locale", "de-CH"),
+        ]);
+        assert_eq!(Locale::resolve(&req, &config).as_str(), "de");
+
+        let req = request(&[("accept-language", "en"), ("cookie", "lang=tr")]);
+        assert_eq!(Locale::resolve(&req, &config).as_str(), "tr");
+
+        let req = request(&[("accept-language", "en"), ("x-locale", "fr")]);
+        assert_eq!(Locale::resolve(&req, &config).as_str(), "en");
+
+        assert_eq!(Locale::resolve(&request(&[]), &config).as_str(), "de");
+        assert_eq!(
+            Locale::resolve(&request(&[]), &LocaleConfig::new(["tr", "en"])).as_str(),
+            "en"
+        );
+    }
+}
@@ -0,0 +1,264 @@
+//! Runtime log filter
+//!
+//! [`RustApi::new`](crate::RustApi::new) installs the tracing subscriber with a
+//! reloadable [`EnvFilter`]. [`LogFilter::global`] returns a handle to it, so
+//! the filter can be changed while the server runs, e.g. to turn on debug
+//! logs for one module while investigating an incident.
+//!
+//! Filters use the `RUST_LOG` directive syntax: `info,my_app::orders=debug`.
+//! Changes can be temporary: after the given duration the filter goes back to
+//! what it was before, unless it was changed again in the meantime.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! use rustapi_core::log_filter::LogFilter;
+//! use std::time::Duration;
+//!
+//! if let Some(filter) = LogFilter::global() {
+//!     // Debug logs for one module, for the next 10 minutes
+//!     filter.add_directive_for("my_app::orders=debug", Duration::from_secs(600))?;
+//! }
+//! ```
+
+use std::fmt;
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::Duration;
+use tokio::time::Instant;
+use tracing_subscriber::{reload, EnvFilter, Registry};
+
+static GLOBAL: OnceLock<LogFilter> = OnceLock::new();
+
+/// Error from changing the log filter
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LogFilterError {
+    /// The directives could not be parsed
+    Invalid(String),
+    /// The subscriber holding the filter is gone
+    Unavailable(String),
+}
+
+impl fmt::Display for LogFilterError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Invalid(msg) => write!(f, "Invalid log filter: {}", msg),
+            Self::Unavailable(msg) => write!(f, "Log filter unavailable: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for LogFilterError {}
+
+/// Handle to the reloadable log filter of the global subscriber
+///
+/// Clones control the same filter.
+#[derive(Clone)]
+pub struct LogFilter {
+    handle: reload::Handle<EnvFilter, Registry>,
+    initial: Arc<str>,
+    pending: Arc<Mutex<Pending>>,
+}
+
+/// The revert scheduled by the last temporary change
+#[derive(Default)]
+struct Pending {
+    /// Bumped by every change, so stale reverts can tell they were overtaken
+    generation: u64,
+    revert: Option<(String, Instant)>,
+}
+
+impl LogFilter {
+    /// The filter installed by `RustApi::new`
+    ///
+    /// `None` when another subscriber was installed first.
+    pub fn global() -> Option<Self> {
+        GLOBAL.get().cloned()
+    }
+
+    /// The active directives
+    pub fn current(&self) -> String {
+        self.handle
+            .with_current(|filter| filter.to_string())
+            .unwrap_or_else(|_| self.initial.to_string())
+    }
+
+    /// The directives the process started with
+    pub fn initial(&self) -> &str {
+        &self.initial
+    }
+
+    /// Time left until a temporary change is reverted
+    pub fn expires_in(&self) -> Option<Duration> {
+        let pending = self.pending.lock().unwrap_or_else(|e| e.into_inner());
+        pending
+            .revert
+            .as_ref()
+            .map(|(_, at)| at.saturating_duration_since(Instant::now()))
+    }
+
+    /// Replace the active directives
+    pub fn set(&self, directives: &str) -> Result<(), LogFilterError> {
+        let mut pending = self.pending.lock().unwrap_or_else(|e| e.into_inner());
+        self.reload(directives)?;
+        pending.generation += 1;
+        pending.revert = None;
+        tracing::info!(filter = %directives, "Log filter changed");
+        Ok(())
+    }
+
+    /// Replace the active directives for `duration`, then restore the previous ones
+    ///
+    /// Consecutive temporary changes restore the directives that were active
+    /// before the first of them. Must be called within a Tokio runtime.
+    pub fn set_for(&self, directives: &str, duration: Duration) -> Result<(), LogFilterError> {
+        let runtime = tokio::runtime::Handle::try_current()
+            .map_err(|e| LogFilterError::Unavailable(e.to_string()))?;
+        let mut pending = self.pending.lock().unwrap_or_else(|e| e.into_inner());
+        let previous = match &pending.revert {
+            Some((previous, _)) => previous.clone(),
+            None => self.current(),
+        };
+        self.reload(directives)?;
+        pending.generation += 1;
+        pending.revert = Some((previous.clone(), Instant::now() + duration));
+        tracing::info!(
+            filter = %directives,
+            revert_in_secs = duration.as_secs(),
+            "Log filter changed temporarily"
+        );
+
+        let generation = pending.generation;
+        let filter = self.clone();
+        runtime.spawn(async move {
+            tokio::time::sleep(duration).await;
+            filter.revert(generation, &previous);
+        });
+        Ok(())
+    }
+
+    /// Add one directive to the active ones, e.g. `my_app::orders=debug`
+    pub fn add_directive(&self, directive: &str) -> Result<(), LogFilterError> {
+        self.set(&self.with_directive(directive))
+    }
+
+    /// Add one directive for `duration`, then restore the previous directives
+    pub fn add_directive_for(
+        &self,
+        directive: &str,
+        duration: Duration,
+    ) -> Result<(), LogFilterError> {
+        self.set_for(&self.with_directive(directive), duration)
+    }
+
+    /// Go back to the directives the process started with
+    pub fn reset(&self) -> Result<(), LogFilterError> {
+        let initial = self.initial.clone();
+        self.set(&initial)
+    }
+
+    fn with_directive(&self, directive: &str) -> String {
+        let current = self.current();
+        if current.is_empty() {
+            directive.to_string()
+        } else {
+            format!("{},{}", current, directive)
+        }
+    }
+
+    fn reload(&self, directives: &str) -> Result<(), LogFilterError> {
+        let filter =
+            EnvFilter::try_new(directives).map_err(|e| LogFilterError::Invalid(e.to_string()))?;
+        self.handle
+            .reload(filter)
+            .map_err(|e| LogFilterError::Unavailable(e.to_string()))
+    }
+
+    /// Restore `previous` unless the filter changed again since `generation`
+    fn revert(&self, generation: u64, previous: &str) {
+        let mut pending = self.pending.lock().unwrap_or_else(|e| e.into_inner());
+        if pending.generation != generation {
+            return;
+        }
+        pending.revert = None;
+        match self.reload(previous) {
+            Ok(()) => tracing::info!(filter = %previous, "Temporary log filter expired"),
+            Err(e) => tracing::warn!(error = %e, "Could not restore the log filter"),
+        }
+    }
+}
+
+impl fmt::Debug for LogFilter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("LogFilter")
+            .field("current", &self.current())
+            .field("initial", &self.initial)
+            .field("expires_in", &self.expires_in())
+            .finish()
+    }
+}
+
+/// A reloadable filter layer and the handle to register once it is installed
+pub(crate) fn reloadable(filter: EnvFilter) -> (reload::Layer<EnvFilter, Registry>, LogFilter) {
+    let initial: Arc<str> = filter.to_string().into();
+    let (layer, handle) = reload::Layer::new(filter);
+    let filter = LogFilter {
+        handle,
+        initial,
+        pending: Arc::default(),
+    };
+    (layer, filter)
+}
+
+/// Make `filter` the handle returned by [`LogFilter::global`]
+pub(crate) fn install(filter: LogFilter) {
+    let _ = GLOBAL.set(filter);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tracing_subscriber::layer::SubscriberExt;
+
+    #[test]
+    fn set_and_reset_swap_the_active_directives() {
+        let (layer, filter) = reloadable(EnvFilter::new("info"));
+        let subscriber = tracing_subscriber::registry().with(layer);
+        let _guard = tracing::subscriber::set_default(subscriber);
+
+        assert!(!tracing::enabled!(target: "app::orders", tracing::Level::DEBUG));
+        filter.set("info,app::orders=debug").unwrap();
+        assert_eq!(filter.current(), "app::orders=debug,info");
+        tracing::callsite::rebuild_interest_cache();
+        assert!(tracing::enabled!(target: "app::orders", tracing::Level::DEBUG));
+
+        assert!(matches!(
+            filter.set("app=loud"),
+            Err(LogFilterError::Invalid(_))
+        ));
+        filter.reset().unwrap();
+        assert_eq!(filter.current(), "info");
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn temporary_changes_revert_to_the_directives_before_them() {
+        let (_layer, filter) = reloadable(EnvFilter::new("info"));
+
+        filter
+            .add_directive_for("app::orders=debug", Duration::from_secs(600))
+            .unwrap();
+        filter
+            .add_directive_for("app::payments=trace", Duration::from_secs(60))
+            .unwrap();
+        assert!(filter.current().contains("app::orders=debug"));
+        assert_eq!(filter.expires_in(), Some(Duration::from_secs(60)));
+
+        tokio::time::sleep(Duration::from_secs(61)).await;
+        assert_eq!(filter.current(), "info");
+        assert_eq!(filter.expires_in(), None);
+
+        filter.set_for("warn", Duration::from_secs(10)).unwrap();
+        filter.set("debug").unwrap();
+        tokio::time::sleep(Duration::from_secs(11)).await;
+        assert_eq!(filter.current(), "debug");
+    }
+}
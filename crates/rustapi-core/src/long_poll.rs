@@ -0,0 +1,311 @@
+//! Long polling
+//!
+//! Some corporate proxies buffer or cut Server-Sent Events and block
+//! WebSockets. Long polling works through them: the client sends a request,
+//! the server holds it until there is new data or a deadline passes, and the
+//! client immediately sends the next one.
+//!
+//! [`LongPoll`] parks a request on a tokio [`broadcast`] receiver or a
+//! [`Notify`] and resolves to a [`LongPollResponse`]: the data, `204 No
+//! Content` on timeout, or `503 Service Unavailable` when too many requests
+//! are already parked.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! use rustapi_rs::prelude::*;
+//! use rustapi_rs::{LongPoll, LongPollResponse};
+//! use tokio::sync::broadcast;
+//!
+//! #[derive(Clone)]
+//! struct Feed {
+//!     poll: LongPoll,
+//!     events: broadcast::Sender<Event>,
+//! }
+//!
+//! async fn next_event(State(feed): State<Feed>) -> LongPollResponse<Json<Event>> {
+//!     let mut events = feed.events.subscribe();
+//!     feed.poll.recv(&mut events).await.map(Json)
+//! }
+//!
+//! let feed = Feed {
+//!     poll: LongPoll::new(Duration::from_secs(30)).max_connections(10_000),
+//!     events: broadcast::channel(64).0,
+//! };
+//! ```
+//!
+//! With [`LongPoll::notified`] the handler checks its own state, e.g. for
+//! messages after a cursor the client sent, and waits for the next
+//! notification only when there are none.
+
+use crate::response::{IntoResponse, Response};
+use crate::ApiError;
+use http::StatusCode;
+use rustapi_openapi::{OpenApiSpec, Operation, ResponseModifier, ResponseSpec};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::{broadcast, Notify};
+use tokio::time::Instant;
+
+/// Default time a request is held before answering `204 No Content`
+pub const DEFAULT_LONG_POLL_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Default number of requests that may wait at the same time
+pub const DEFAULT_LONG_POLL_MAX_CONNECTIONS: usize = 1024;
+
+/// Holds requests until new data arrives or the timeout passes
+///
+/// Clones share the connection count, so keep one `LongPoll` per feed in the
+/// application state.
+#[derive(Debug, Clone)]
+pub struct LongPoll {
+    timeout: Duration,
+    max_connections: usize,
+    waiting: Arc<AtomicUsize>,
+}
+
+impl Default for LongPoll {
+    fn default() -> Self {
+        Self::new(DEFAULT_LONG_POLL_TIMEOUT)
+    }
+}
+
+impl LongPoll {
+    /// Hold requests for at most `timeout`
+    ///
+    /// Keep it below the idle timeout of proxies between client and server,
+    /// which is often 60 seconds.
+    pub fn new(timeout: Duration) -> Self {
+        Self {
+            timeout,
+            max_connections: DEFAULT_LONG_POLL_MAX_CONNECTIONS,
+            waiting: Arc::new(AtomicUsize::new(0)),
+        }
+    }
+
+    /// Maximum number of requests waiting at the same time
+    ///
+    /// Further requests get `503 Service Unavailable` with `Retry-After`.
+    pub fn max_connections(mut self, max: usize) -> Self {
+        self.max_connections = max;
+        self
+    }
+
+    /// Number of requests currently waiting
+    pub fn waiting(&self) -> usize {
+        self.waiting.load(Ordering::Relaxed)
+    }
+
+    /// Wait for the next message on `rx`
+    ///
+    /// Messages the receiver missed because it lagged behind are skipped. A
+    /// closed channel ends the wait like a timeout.
+    pub async fn recv<T: Clone>(&self, rx: &mut broadcast::Receiver<T>) -> LongPollResponse<T> {
+        let Some(_slot) = self.acquire() else {
+            return LongPollResponse::Busy;
+        };
+        let deadline = Instant::now() + self.timeout;
+        loop {
+            match tokio::time::timeout_at(deadline, rx.recv()).await {
+                Ok(Ok(value)) => return LongPollResponse::Data(value),
+                Ok(Err(broadcast::error::RecvError::Lagged(_))) => continue,
+                Ok(Err(broadcast::error::RecvError::Closed)) | Err(_) => {
+                    return LongPollResponse::Timeout
+                }
+            }
+        }
+    }
+
+    /// Return what `check` finds, waiting for `notify` while it finds nothing
+    ///
+    /// `check` runs once immediately and again after every notification, so
+    /// data published between two polls is not lost as long as `check` looks
+    /// at the application's state rather than at the notification.
+    pub async fn notified<T, F>(&self, notify: &Notify, mut check: F) -> LongPollResponse<T>
+    where
+        F: FnMut() -> Option<T>,
+    {
+        let Some(_slot) = self.acquire() else {
+            return LongPollResponse::Busy;
+        };
+        let deadline = Instant::now() + self.timeout;
+        loop {
+            // Register before checking so a notify_waiters() in between is not missed
+            let notified = notify.notified();
+            tokio::pin!(notified);
+            notified.as_mut().enable();
+
+            if let Some(value) = check() {
+                return LongPollResponse::Data(value);
+            }
+            if tokio::time::timeout_at(deadline, notified).await.is_err() {
+                return LongPollResponse::Timeout;
+            }
+        }
+    }
+
+    fn acquire(&self) -> Option<WaitingSlot> {
+        let previous = self.waiting.fetch_add(1, Ordering::AcqRel);
+        let slot = WaitingSlot(self.waiting.clone());
+        (previous < self.max_connections).then_some(slot)
+    }
+}
+
+/// Counts a waiting request until dropped
+struct WaitingSlot(Arc<AtomicUsize>);
+
+impl Drop for WaitingSlot {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::AcqRel);
+    }
+}
+
+/// Outcome of a long poll
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LongPollResponse<T> {
+    /// New data, answered like `T`
+    Data(T),
+    /// Nothing arrived before the timeout: `204 No Content`
+    Timeout,
+    /// Too many requests are waiting: `503 Service Unavailable`
+    Busy,
+}
+
+impl<T> LongPollResponse<T> {
+    /// Convert the data, e.g. with `.map(Json)`
+    pub fn map<U>(self, f: impl FnOnce(T) -> U) -> LongPollResponse<U> {
+        match self {
+            Self::Data(value) => LongPollResponse::Data(f(value)),
+            Self::Timeout => LongPollResponse::Timeout,
+            Self::Busy => LongPollResponse::Busy,
+        }
+    }
+
+    /// The data, if any arrived
+    pub fn into_data(self) -> Option<T> {
+        match self {
+            Self::Data(value) => Some(value),
+            _ => None,
+        }
+    }
+}
+
+impl<T: IntoResponse> IntoResponse for LongPollResponse<T> {
+    fn into_response(self) -> Response {
+        match self {
+            Self::Data(value) => value.into_response(),
+            Self::Timeout => StatusCode::NO_CONTENT.into_response(),
+            Self::Busy => {
+                let mut response =
+                    ApiError::service_unavailable("Too many waiting requests").into_response();
+                response.headers_mut().insert(
+                    http::header::RETRY_AFTER,
+                    http::HeaderValue::from_static("1"),
+                );
+                response
+            }
+        }
+    }
+}
+
+impl<T: ResponseModifier> ResponseModifier for LongPollResponse<T> {
+    fn update_response(op: &mut Operation) {
+        T::update_response(op);
+        op.responses.insert(
+            "204".to_string(),
+            ResponseSpec {
+                description: "No new data before the timeout; poll again".to_string(),
+                ..Default::default()
+            },
+        );
+        op.responses.insert(
+            "503".to_string(),
+            ResponseSpec {
+                description: "Too many waiting requests; retry after the Retry-After delay"
+                    .to_string(),
+                ..Default::default()
+            },
+        );
+    }
+
+    fn register_components(spec: &mut OpenApiSpec) {
+        T::register_components(spec);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test(start_paused = true)]
+    async fn recv_returns_data_or_times_out() {
+        let poll = LongPoll::new(Duration::from_secs(30));
+        let (tx, mut rx) = broadcast::channel(4);
+
+        let waiter = {
+            let poll = poll.clone();
+            tokio::spawn(async move { poll.recv(&mut rx).await })
+        };
+        tokio::task::yield_now().await;
+        assert_eq!(poll.waiting(), 1);
+        tx.send("pin added").unwrap();
+        assert_eq!(waiter.await.unwrap(), LongPollResponse::Data("pin added"));
+        assert_eq!(poll.waiting(), 0);
+
+        let mut rx = tx.subscribe();
+        let response = poll.recv(&mut rx).await;
+        assert_eq!(response, LongPollResponse::Timeout);
+        assert_eq!(response.into_response().status(), StatusCode::NO_CONTENT);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn notified_checks_state_before_waiting() {
+        let poll = LongPoll::new(Duration::from_secs(30));
+        let notify = Arc::new(Notify::new());
+        let messages = Arc::new(std::sync::Mutex::new(vec!["ready"]));
+
+        let check = || messages.lock().unwrap().pop();
+        assert_eq!(
+            poll.notified(&notify, check).await,
+            LongPollResponse::Data("ready")
+        );
+
+        let publisher = {
+            let (notify, messages) = (notify.clone(), messages.clone());
+            tokio::spawn(async move {
+                tokio::time::sleep(Duration::from_secs(5)).await;
+                messages.lock().unwrap().push("later");
+                notify.notify_waiters();
+            })
+        };
+        let check = || messages.lock().unwrap().pop();
+        assert_eq!(
+            poll.notified(&notify, check).await,
+            LongPollResponse::Data("later")
+        );
+        publisher.await.unwrap();
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn requests_over_the_limit_are_rejected() {
+        let poll = LongPoll::new(Duration::from_secs(30)).max_connections(1);
+        let (_tx, mut rx) = broadcast::channel::<()>(4);
+        let mut busy_rx = rx.resubscribe();
+
+        let waiter = {
+            let poll = poll.clone();
+            tokio::spawn(async move { poll.recv(&mut rx).await })
+        };
+        tokio::task::yield_now().await;
+
+        let response = poll.recv(&mut busy_rx).await;
+        assert_eq!(response, LongPollResponse::Busy);
+        let response = response.into_response();
+        assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(response.headers()["retry-after"], "1");
+
+        assert_eq!(waiter.await.unwrap(), LongPollResponse::Timeout);
+        assert_eq!(poll.waiting(), 0);
+    }
+}
@@ -86,7 +86,7 @@ impl MiddlewareLayer for BodyLimitLayer {
                         if length > limit {
                             return ApiError::new(
                                 StatusCode::PAYLOAD_TOO_LARGE,
-                                "payload_too_large",
+                                crate::error::codes::PAYLOAD_TOO_LARGE,
                                 format!("Request body exceeds limit of {} bytes", limit),
                             )
                             .into_response();
@@ -101,7 +101,7 @@ impl MiddlewareLayer for BodyLimitLayer {
                 if bytes.len() > limit {
                     return ApiError::new(
                         StatusCode::PAYLOAD_TOO_LARGE,
-                        "payload_too_large",
+                        crate::error::codes::PAYLOAD_TOO_LARGE,
                         format!("Request body exceeds limit of {} bytes", limit),
                     )
                     .into_response();
@@ -0,0 +1,541 @@
+//! Body transformation middleware for RustAPI
+//!
+//! [`BodyTransformLayer`] lets middleware read and rewrite whole request and
+//! response bodies, e.g. to wrap responses in an envelope, redact fields or
+//! translate a legacy format, without handling `Body::Streaming` itself.
+//!
+//! Bodies are buffered up to a limit before the transform runs. Larger bodies,
+//! and bodies of unknown size that grow past the limit, are passed through
+//! untouched: a response stream is forwarded from where buffering stopped, so
+//! nothing is lost. Compressed responses (with `Content-Encoding`),
+//! `text/event-stream` responses and responses without a body (to `HEAD`
+//! requests, `1xx`, `204` and `304`) are never buffered.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! use rustapi_core::middleware::BodyTransformLayer;
+//! use serde_json::json;
+//!
+//! // Wrap every JSON response in {"status": ..., "data": ...}
+//! let envelope = BodyTransformLayer::new()
+//!     .map_response_json(|status, data| json!({ "status": status.as_u16(), "data": data }));
+//!
+//! RustApi::new()
+//!     .layer(envelope)
+//!     .route("/users", get(list_users))
+//!     .run("127.0.0.1:8080")
+//!     .await
+//! ```
+
+use super::{BoxedNext, MiddlewareLayer};
+use crate::error::ApiError;
+use crate::request::{BodyVariant, Request};
+use crate::response::{Body, IntoResponse, Response};
+use bytes::Bytes;
+use http::{header, HeaderMap, StatusCode};
+use http_body::{Body as _, Frame, SizeHint};
+use http_body_util::BodyExt;
+use std::collections::VecDeque;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+
+/// Default buffering limit: 1MB
+pub const DEFAULT_TRANSFORM_LIMIT: usize = 1024 * 1024;
+
+type RequestMapper = Arc<dyn Fn(&Request, Bytes) -> Result<Bytes, ApiError> + Send + Sync>;
+type ResponseMapper = Arc<dyn Fn(&mut http::response::Parts, Bytes) -> Bytes + Send + Sync>;
+
+/// Middleware that rewrites request and response bodies
+///
+/// Each body is buffered (up to [`BodyTransformLayer::limit`]) and passed to
+/// the configured function. Bodies that do not fit are passed through.
+#[derive(Clone)]
+pub struct BodyTransformLayer {
+    limit: usize,
+    content_type: Option<String>,
+    request: Option<RequestMapper>,
+    response: Option<ResponseMapper>,
+}
+
+impl BodyTransformLayer {
+    /// Create a layer that leaves bodies unchanged until a mapper is set
+    pub fn new() -> Self {
+        Self {
+            limit: DEFAULT_TRANSFORM_LIMIT,
+            content_type: None,
+            request: None,
+            response: None,
+        }
+    }
+
+    /// Largest body buffered for a transform, in bytes (default: 1MB)
+    pub fn limit(mut self, limit: usize) -> Self {
+        self.limit = limit;
+        self
+    }
+
+    /// Only transform bodies whose `Content-Type` starts with `content_type`
+    ///
+    /// Bodies without a `Content-Type` are passed through as well.
+    pub fn content_type(mut self, content_type: impl Into<String>) -> Self {
+        self.content_type = Some(content_type.into().to_ascii_lowercase());
+        self
+    }
+
+    /// Rewrite request bodies before the handler reads them
+    ///
+    /// Returning an error rejects the request with that error.
+    pub fn map_request<F>(mut self, f: F) -> Self
+    where
+        F: Fn(&Request, Bytes) -> Result<Bytes, ApiError> + Send + Sync + 'static,
+    {
+        self.request = Some(Arc::new(f));
+        self
+    }
+
+    /// Rewrite response bodies; the response head can be changed as well
+    ///
+    /// `Content-Length` is updated to the new body afterwards.
+    pub fn map_response<F>(mut self, f: F) -> Self
+    where
+        F: Fn(&mut http::response::Parts, Bytes) -> Bytes + Send + Sync + 'static,
+    {
+        self.response = Some(Arc::new(f));
+        self
+    }
+
+    /// Rewrite JSON request bodies
+    ///
+    /// Bodies that are not JSON, by `Content-Type` or content, are passed
+    /// through for the handler to reject. A rewritten value that cannot be
+    /// serialized rejects the request with `500`.
+    pub fn map_request_json<F>(self, f: F) -> Self
+    where
+        F: Fn(serde_json::Value) -> serde_json::Value + Send + Sync + 'static,
+    {
+        self.map_request(move |req, body| {
+            if !is_json(req.headers()) {
+                return Ok(body);
+            }
+            match serde_json::from_slice(&body) {
+                Ok(value) => serde_json::to_vec(&f(value)).map(Bytes::from).map_err(|e| {
+                    ApiError::internal(format!("Failed to serialize request body: {}", e))
+                }),
+                Err(_) => Ok(body),
+            }
+        })
+    }
+
+    /// Rewrite JSON response bodies, given the response status
+    ///
+    /// A rewritten value that cannot be serialized leaves the body unchanged.
+    pub fn map_response_json<F>(self, f: F) -> Self
+    where
+        F: Fn(StatusCode, serde_json::Value) -> serde_json::Value + Send + Sync + 'static,
+    {
+        self.map_response(move |parts, body| {
+            if !is_json(&parts.headers) {
+                return body;
+            }
+            let Ok(value) = serde_json::from_slice(&body) else {
+                return body;
+            };
+            match serde_json::to_vec(&f(parts.status, value)) {
+                Ok(mapped) => Bytes::from(mapped),
+                Err(e) => {
+                    tracing::error!("Failed to serialize response body: {}", e);
+                    body
+                }
+            }
+        })
+    }
+
+    /// Whether a body with these headers should be transformed
+    fn matches(&self, headers: &HeaderMap) -> bool {
+        let content_type = headers
+            .get(header::CONTENT_TYPE)
+            .and_then(|v| v.to_str().ok())
+            .map(str::to_ascii_lowercase);
+        match (&self.content_type, content_type) {
+            (None, _) => true,
+            (Some(wanted), Some(actual)) => actual.starts_with(wanted.as_str()),
+            (Some(_), None) => false,
+        }
+    }
+
+    /// Rewrite the request body, or say why the request must be rejected
+    async fn transform_request(
+        &self,
+        mut req: Request,
+        mapper: &RequestMapper,
+    ) -> Result<Request, ApiError> {
+        if !self.matches(req.headers()) {
+            return Ok(req);
+        }
+        if let BodyVariant::Streaming(_) = req.body {
+            // Only streams known to fit are buffered
+            let length = req
+                .headers()
+                .get(header::CONTENT_LENGTH)
+                .and_then(|v| v.to_str().ok())
+                .and_then(|v| v.parse::<usize>().ok());
+            if length.is_none_or(|length| length > self.limit) {
+                return Ok(req);
+            }
+            req.load_body().await?;
+        }
+        match &req.body {
+            BodyVariant::Buffered(bytes) if bytes.len() <= self.limit => {
+                let body = bytes.clone();
+                let body = mapper(&req, body)?;
+                req.set_body(body);
+                Ok(req)
+            }
+            _ => Ok(req),
+        }
+    }
+
+    /// Rewrite the response body, or pass it through if it does not fit
+    async fn transform_response(
+        &self,
+        response: Response,
+        head: bool,
+        mapper: &ResponseMapper,
+    ) -> Response {
+        let (mut parts, body) = response.into_parts();
+        let opaque = head
+            || is_bodyless(parts.status)
+            || parts.headers.contains_key(header::CONTENT_ENCODING)
+            || is_event_stream(&parts.headers);
+        if opaque || !self.matches(&parts.headers) || body.size_hint().lower() > self.limit as u64 {
+            return http::Response::from_parts(parts, body);
+        }
+
+        let body = match buffer(body, self.limit).await {
+            Buffered::Complete(bytes) => bytes,
+            Buffered::Partial(body) => {
+                return http::Response::from_parts(parts, Body::Streaming(Box::pin(body)))
+            }
+            Buffered::Failed(error) => return error.into_response(),
+        };
+
+        let body = mapper(&mut parts, body);
+        parts.headers.remove(header::TRANSFER_ENCODING);
+        parts
+            .headers
+            .insert(header::CONTENT_LENGTH, body.len().into());
+        http::Response::from_parts(parts, Body::new(body))
+    }
+}
+
+impl Default for BodyTransformLayer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MiddlewareLayer for BodyTransformLayer {
+    fn call(
+        &self,
+        req: Request,
+        next: BoxedNext,
+    ) -> Pin<Box<dyn Future<Output = Response> + Send + 'static>> {
+        let layer = self.clone();
+
+        Box::pin(async move {
+            let req = match &layer.request {
+                Some(mapper) => match layer.transform_request(req, mapper).await {
+                    Ok(req) => req,
+                    Err(error) => return error.into_response(),
+                },
+                None => req,
+            };
+
+            let head = req.method() == http::Method::HEAD;
+            let response = next(req).await;
+
+            match &layer.response {
+                Some(mapper) => layer.transform_response(response, head, mapper).await,
+                None => response,
+            }
+        })
+    }
+
+    fn clone_box(&self) -> Box<dyn MiddlewareLayer> {
+        Box::new(self.clone())
+    }
+}
+
+fn is_json(headers: &HeaderMap) -> bool {
+    headers
+        .get(header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.split(';').next())
+        .is_some_and(|mime| {
+            let mime = mime.trim().to_ascii_lowercase();
+            mime == "application/json" || mime.ends_with("+json")
+        })
+}
+
+/// Statuses whose responses never carry a body
+fn is_bodyless(status: StatusCode) -> bool {
+    status.is_informational()
+        || status == StatusCode::NO_CONTENT
+        || status == StatusCode::NOT_MODIFIED
+}
+
+fn is_event_stream(headers: &HeaderMap) -> bool {
+    headers
+        .get(header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|v| v.starts_with("text/event-stream"))
+}
+
+/// Outcome of buffering a response body
+enum Buffered {
+    /// The whole body, within the limit
+    Complete(Bytes),
+    /// The body outgrew the limit; the frames read so far come first
+    Partial(ReplayBody),
+    /// Reading the body failed
+    Failed(ApiError),
+}
+
+/// Read `body` until it ends or exceeds `limit` bytes
+async fn buffer(body: Body, limit: usize) -> Buffered {
+    if let Body::Full(_) = body {
+        return match body.collect().await {
+            Ok(collected) => Buffered::Complete(collected.to_bytes()),
+            Err(error) => Buffered::Failed(error),
+        };
+    }
+
+    let mut body = body;
+    let mut frames = VecDeque::new();
+    let mut size = 0;
+    loop {
+        match body.frame().await {
+            None => break,
+            Some(Err(error)) => return Buffered::Failed(error),
+            Some(Ok(frame)) => {
+                let fits = match frame.data_ref() {
+                    Some(data) => {
+                        size += data.len();
+                        size <= limit
+                    }
+                    // Trailers cannot be kept in a buffered body
+                    None => false,
+                };
+                frames.push_back(frame);
+                if !fits {
+                    return Buffered::Partial(ReplayBody {
+                        frames,
+                        rest: Some(body),
+                    });
+                }
+            }
+        }
+    }
+
+    let mut bytes = bytes::BytesMut::with_capacity(size);
+    for frame in frames {
+        if let Ok(data) = frame.into_data() {
+            bytes.extend_from_slice(&data);
+        }
+    }
+    Buffered::Complete(bytes.freeze())
+}
+
+/// A stream that yields frames already read, then the rest of the body
+struct ReplayBody {
+    frames: VecDeque<Frame<Bytes>>,
+    rest: Option<Body>,
+}
+
+impl http_body::Body for ReplayBody {
+    type Data = Bytes;
+    type Error = ApiError;
+
+    fn poll_frame(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Result<Frame<Self::Data>, Self::Error>>> {
+        if let Some(frame) = self.frames.pop_front() {
+            return Poll::Ready(Some(Ok(frame)));
+        }
+        match self.rest.as_mut() {
+            Some(rest) => Pin::new(rest).poll_frame(cx),
+            None => Poll::Ready(None),
+        }
+    }
+
+    fn is_end_stream(&self) -> bool {
+        self.frames.is_empty() && self.rest.as_ref().is_none_or(|rest| rest.is_end_stream())
+    }
+
+    fn size_hint(&self) -> SizeHint {
+        SizeHint::default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::middleware::LayerStack;
+    use futures_util::stream;
+    use serde_json::json;
+
+    fn stack(layer: BodyTransformLayer) -> LayerStack {
+        let mut stack = LayerStack::new();
+        stack.push(Box::new(layer));
+        stack
+    }
+
+    fn json_request(body: &'static str) -> Request {
+        let req = http::Request::builder()
+            .method("POST")
+            .uri("/")
+            .header(header::CONTENT_TYPE, "application/json")
+            .header(header::CONTENT_LENGTH, body.len())
+            .body(())
+            .unwrap();
+        Request::from_http_request(req, Bytes::from_static(body.as_bytes()))
+    }
+
+    /// Handler answering with the JSON body it received
+    fn echo() -> BoxedNext {
+        Arc::new(|mut req: Request| {
+            Box::pin(async move {
+                let body = req.take_body().unwrap_or_default();
+                let length = req.headers()[header::CONTENT_LENGTH].clone();
+                let mut response = http::Response::builder()
+                    .header(header::CONTENT_TYPE, "application/json")
+                    .body(Body::new(body))
+                    .unwrap();
+                response.headers_mut().insert("x-seen-length", length);
+                response
+            }) as Pin<Box<dyn Future<Output = Response> + Send + 'static>>
+        })
+    }
+
+    async fn text(response: Response) -> String {
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        String::from_utf8(body.to_vec()).unwrap()
+    }
+
+    #[tokio::test]
+    async fn rewrites_json_requests_and_wraps_responses() {
+        let layer = BodyTransformLayer::new()
+            .map_request_json(|mut value| {
+                value["password"] = json!("***");
+                value
+            })
+            .map_response_json(|status, data| json!({ "status": status.as_u16(), "data": data }));
+
+        let response = stack(layer)
+            .execute(
+                json_request(r#"{"user":"ann","password":"hunter2"}"#),
+                echo(),
+            )
+            .await;
+        let seen_length = response.headers()["x-seen-length"].clone();
+        let content_length = response.headers()[header::CONTENT_LENGTH].clone();
+        let body = text(response).await;
+
+        assert_eq!(
+            serde_json::from_str::<serde_json::Value>(&body).unwrap(),
+            json!({ "status": 200, "data": { "user": "ann", "password": "***" } })
+        );
+        assert_eq!(
+            seen_length,
+            r#"{"password":"***","user":"ann"}"#.len().to_string()
+        );
+        assert_eq!(content_length, body.len().to_string());
+    }
+
+    #[tokio::test]
+    async fn request_mapper_errors_reject_the_request() {
+        let layer = BodyTransformLayer::new()
+            .map_request(|_, _| Err(ApiError::bad_request("legacy format required")));
+
+        let response = stack(layer).execute(json_request("{}"), echo()).await;
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    }
+
+    #[tokio::test]
+    async fn streams_past_the_limit_pass_through_unchanged() {
+        let streaming: BoxedNext = Arc::new(|_req: Request| {
+            Box::pin(async {
+                let chunks = ["aaaa", "bbbb", "cccc"]
+                    .map(|chunk| Ok::<_, ApiError>(Bytes::from_static(chunk.as_bytes())));
+                http::Response::builder()
+                    .body(Body::from_stream(stream::iter(chunks)))
+                    .unwrap()
+            }) as Pin<Box<dyn Future<Output = Response> + Send + 'static>>
+        });
+        let upper =
+            |_: &mut http::response::Parts, body: Bytes| Bytes::from(body.to_ascii_uppercase());
+
+        let response = stack(BodyTransformLayer::new().limit(6).map_response(upper))
+            .execute(json_request("{}"), streaming.clone())
+            .await;
+        assert_eq!(text(response).await, "aaaabbbbcccc");
+
+        let response = stack(BodyTransformLayer::new().limit(64).map_response(upper))
+            .execute(json_request("{}"), streaming)
+            .await;
+        assert_eq!(text(response).await, "AAAABBBBCCCC");
+    }
+
+    #[tokio::test]
+    async fn content_type_filter_skips_other_bodies() {
+        let layer = BodyTransformLayer::new()
+            .content_type("text/plain")
+            .map_response(|_, _| Bytes::from_static(b"changed"));
+
+        let response = stack(layer).execute(json_request("{}"), echo()).await;
+        assert_eq!(text(response).await, "{}");
+    }
+
+    #[tokio::test]
+    async fn bodyless_responses_are_not_rewritten() {
+        let empty = |status: StatusCode| -> BoxedNext {
+            Arc::new(move |_req: Request| {
+                Box::pin(async move {
+                    http::Response::builder()
+                        .status(status)
+                        .header(header::CONTENT_TYPE, "application/json")
+                        .header(header::CONTENT_LENGTH, "42")
+                        .body(Body::empty())
+                        .unwrap()
+                }) as Pin<Box<dyn Future<Output = Response> + Send + 'static>>
+            })
+        };
+        let layer = BodyTransformLayer::new().map_response(|_, _| Bytes::from_static(b"changed"));
+
+        for status in [StatusCode::NO_CONTENT, StatusCode::NOT_MODIFIED] {
+            let response = stack(layer.clone())
+                .execute(json_request("{}"), empty(status))
+                .await;
+            assert_eq!(response.headers()[header::CONTENT_LENGTH], "42");
+            assert_eq!(text(response).await, "");
+        }
+
+        let head = http::Request::builder()
+            .method("HEAD")
+            .uri("/")
+            .body(())
+            .unwrap();
+        let response = stack(layer)
+            .execute(
+                Request::from_http_request(head, Bytes::new()),
+                empty(StatusCode::OK),
+            )
+            .await;
+        assert_eq!(response.headers()[header::CONTENT_LENGTH], "42");
+        assert_eq!(text(response).await, "");
+    }
+}
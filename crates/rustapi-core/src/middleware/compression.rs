@@ -1,6 +1,14 @@
 //! Response compression middleware
 //!
-//! This module provides Gzip and Brotli compression for response bodies.
+//! This module provides Gzip, Deflate, Brotli and Zstandard compression for
+//! response bodies.
+//!
+//! Buffered bodies are compressed in one go. Streaming bodies are compressed
+//! chunk by chunk, flushing the encoder after every chunk so each one reaches
+//! the client without waiting for the rest. Responses are left alone when
+//! they are event streams, have an excluded (e.g. already compressed) content
+//! type, are already encoded or partial (`206`), are marked
+//! `Cache-Control: no-transform`, or carry the [`NoCompression`] extension.
 //!
 //! # Example
 //!
@@ -22,10 +30,24 @@ use bytes::Bytes;
 use flate2::write::{DeflateEncoder, GzEncoder};
 use flate2::Compression;
 use http::header;
+use http_body::{Body as _, Frame};
 use http_body_util::{BodyExt, Full};
 use std::future::Future;
 use std::io::Write;
 use std::pin::Pin;
+use std::task::{ready, Context, Poll};
+
+/// Response extension that keeps [`CompressionLayer`] from compressing a response
+///
+/// ```rust,ignore
+/// async fn export() -> Response {
+///     let mut response = stream_export().into_response();
+///     response.extensions_mut().insert(NoCompression);
+///     response
+/// }
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct NoCompression;
 
 /// Supported compression algorithms
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -37,11 +59,24 @@ pub enum CompressionAlgorithm {
     /// Brotli compression (if enabled)
     #[cfg(feature = "compression-brotli")]
     Brotli,
+    /// Zstandard compression (if enabled)
+    #[cfg(feature = "compression-zstd")]
+    Zstd,
     /// No compression
     Identity,
 }
 
 impl CompressionAlgorithm {
+    /// Server preference among encodings the client rates equally
+    const PREFERENCE: &'static [Self] = &[
+        #[cfg(feature = "compression-zstd")]
+        Self::Zstd,
+        #[cfg(feature = "compression-brotli")]
+        Self::Brotli,
+        Self::Gzip,
+        Self::Deflate,
+    ];
+
     /// Get the Content-Encoding header value
     pub fn content_encoding(&self) -> &'static str {
         match self {
@@ -49,53 +84,87 @@ impl CompressionAlgorithm {
             Self::Deflate => "deflate",
             #[cfg(feature = "compression-brotli")]
             Self::Brotli => "br",
+            #[cfg(feature = "compression-zstd")]
+            Self::Zstd => "zstd",
             Self::Identity => "identity",
         }
     }
 
     /// Parse from Accept-Encoding header
+    ///
+    /// Picks the highest-quality supported encoding; ties go to the server
+    /// preference (zstd, br, gzip, deflate) and `q=0` rules an encoding out.
     pub fn from_accept_encoding(header: &str) -> Self {
-        let encodings: Vec<(f32, &str)> = header
-            .split(',')
-            .map(|part| {
-                let part = part.trim();
-                let (encoding, quality) = if let Some((enc, q)) = part.split_once(";q=") {
-                    (enc.trim(), q.trim().parse().unwrap_or(1.0))
-                } else {
-                    (part, 1.0)
-                };
-                (quality, encoding)
-            })
-            .collect();
+        Self::negotiate(header, |_| true)
+    }
 
-        // Sort by quality (highest first)
-        let mut sorted = encodings;
-        sorted.sort_by(|a, b| b.0.partial_cmp(&a.0).unwrap_or(std::cmp::Ordering::Equal));
+    fn negotiate(header: &str, enabled: impl Fn(Self) -> bool) -> Self {
+        let mut best: Option<(f32, usize)> = None;
 
-        for (_, encoding) in sorted {
-            match encoding.to_lowercase().as_str() {
-                #[cfg(feature = "compression-brotli")]
-                "br" => return Self::Brotli,
-                "gzip" => return Self::Gzip,
-                "deflate" => return Self::Deflate,
-                "*" => return Self::Gzip, // Default to gzip for wildcard
-                _ => continue,
+        for part in header.split(',') {
+            let mut params = part.split(';');
+            let encoding = params
+                .next()
+                .unwrap_or_default()
+                .trim()
+                .to_ascii_lowercase();
+            let quality = params
+                .filter_map(|param| param.trim().strip_prefix("q="))
+                .find_map(|q| q.trim().parse::<f32>().ok())
+                .unwrap_or(1.0);
+            if quality <= 0.0 {
+                continue;
+            }
+
+            let candidates: Vec<Self> = if encoding == "*" {
+                // Default to gzip for wildcard
+                vec![Self::Gzip]
+            } else {
+                Self::PREFERENCE
+                    .iter()
+                    .copied()
+                    .filter(|alg| alg.content_encoding() == encoding)
+                    .collect()
+            };
+
+            for alg in candidates.into_iter().filter(|alg| enabled(*alg)) {
+                let rank = Self::PREFERENCE.iter().position(|p| *p == alg).unwrap_or(0);
+                let better = match best {
+                    None => true,
+                    Some((q, r)) => quality > q || (quality == q && rank < r),
+                };
+                if better {
+                    best = Some((quality, rank));
+                }
             }
         }
 
-        Self::Identity
+        best.map_or(Self::Identity, |(_, rank)| Self::PREFERENCE[rank])
     }
 }
 
 /// Configuration for compression middleware
+///
+/// Content types are matched by prefix against `content_types` and
+/// `exclude_content_types`; when both match, the longer (more specific) entry
+/// wins, so `image/svg+xml` can be allowed while `image/` is excluded.
+/// `text/event-stream` is never compressed.
+///
+/// Build it with [`CompressionConfig::new`] and the setters; new options may
+/// be added in minor releases.
 #[derive(Clone)]
+#[non_exhaustive]
 pub struct CompressionConfig {
     /// Minimum response size to compress (default: 1024 bytes)
     pub min_size: usize,
-    /// Compression level (0-9 for gzip/deflate, 0-11 for brotli)
+    /// Gzip and deflate compression level (0-9)
     pub level: u32,
     /// Content types to compress (empty = all compressible types)
     pub content_types: Vec<String>,
+    /// Content types never to compress
+    pub exclude_content_types: Vec<String>,
+    /// Compress streaming bodies chunk by chunk (default: true)
+    pub compress_streams: bool,
     /// Enable gzip compression
     pub gzip: bool,
     /// Enable deflate compression
@@ -103,6 +172,15 @@ pub struct CompressionConfig {
     /// Enable brotli compression
     #[cfg(feature = "compression-brotli")]
     pub brotli: bool,
+    /// Brotli quality (0-11, default: 11)
+    #[cfg(feature = "compression-brotli")]
+    pub brotli_quality: u32,
+    /// Enable zstd compression
+    #[cfg(feature = "compression-zstd")]
+    pub zstd: bool,
+    /// Zstd level (1-22, default: 3)
+    #[cfg(feature = "compression-zstd")]
+    pub zstd_level: i32,
 }
 
 impl Default for CompressionConfig {
@@ -117,10 +195,35 @@ impl Default for CompressionConfig {
                 "application/xml".to_string(),
                 "image/svg+xml".to_string(),
             ],
+            exclude_content_types: vec![
+                "text/event-stream".to_string(),
+                "image/".to_string(),
+                "audio/".to_string(),
+                "video/".to_string(),
+                "font/woff".to_string(),
+                "application/zip".to_string(),
+                "application/gzip".to_string(),
+                "application/x-gzip".to_string(),
+                "application/zstd".to_string(),
+                "application/x-bzip".to_string(),
+                "application/x-xz".to_string(),
+                "application/x-7z-compressed".to_string(),
+                "application/x-rar-compressed".to_string(),
+                "application/vnd.rar".to_string(),
+                "application/grpc".to_string(),
+                "application/octet-stream".to_string(),
+            ],
+            compress_streams: true,
             gzip: true,
             deflate: true,
             #[cfg(feature = "compression-brotli")]
             brotli: true,
+            #[cfg(feature = "compression-brotli")]
+            brotli_quality: 11,
+            #[cfg(feature = "compression-zstd")]
+            zstd: true,
+            #[cfg(feature = "compression-zstd")]
+            zstd_level: 3,
         }
     }
 }
@@ -137,7 +240,7 @@ impl CompressionConfig {
         self
     }
 
-    /// Set compression level (0-9)
+    /// Set gzip and deflate compression level (0-9)
     pub fn level(mut self, level: u32) -> Self {
         self.level = level.min(9);
         self
@@ -162,6 +265,27 @@ impl CompressionConfig {
         self
     }
 
+    /// Set brotli quality (0-11)
+    #[cfg(feature = "compression-brotli")]
+    pub fn brotli_quality(mut self, quality: u32) -> Self {
+        self.brotli_quality = quality.min(11);
+        self
+    }
+
+    /// Enable or disable zstd
+    #[cfg(feature = "compression-zstd")]
+    pub fn zstd(mut self, enabled: bool) -> Self {
+        self.zstd = enabled;
+        self
+    }
+
+    /// Set zstd level (1-22)
+    #[cfg(feature = "compression-zstd")]
+    pub fn zstd_level(mut self, level: i32) -> Self {
+        self.zstd_level = level.clamp(1, 22);
+        self
+    }
+
     /// Add a content type to compress
     pub fn add_content_type(mut self, content_type: impl Into<String>) -> Self {
         self.content_types.push(content_type.into());
@@ -174,14 +298,95 @@ impl CompressionConfig {
         self
     }
 
+    /// Add a content type never to compress
+    pub fn exclude_content_type(mut self, content_type: impl Into<String>) -> Self {
+        self.exclude_content_types.push(content_type.into());
+        self
+    }
+
+    /// Set content types never to compress
+    pub fn exclude_content_types(mut self, types: Vec<String>) -> Self {
+        self.exclude_content_types = types;
+        self
+    }
+
+    /// Enable or disable chunk-wise compression of streaming bodies
+    ///
+    /// When disabled, streaming bodies are sent uncompressed.
+    pub fn compress_streams(mut self, enabled: bool) -> Self {
+        self.compress_streams = enabled;
+        self
+    }
+
+    /// Pick the encoding for an `Accept-Encoding` header among the enabled
+    /// algorithms
+    pub fn negotiate(&self, accept_encoding: &str) -> CompressionAlgorithm {
+        CompressionAlgorithm::negotiate(accept_encoding, |alg| self.is_enabled(alg))
+    }
+
+    fn is_enabled(&self, algorithm: CompressionAlgorithm) -> bool {
+        match algorithm {
+            CompressionAlgorithm::Gzip => self.gzip,
+            CompressionAlgorithm::Deflate => self.deflate,
+            #[cfg(feature = "compression-brotli")]
+            CompressionAlgorithm::Brotli => self.brotli,
+            #[cfg(feature = "compression-zstd")]
+            CompressionAlgorithm::Zstd => self.zstd,
+            CompressionAlgorithm::Identity => false,
+        }
+    }
+
+    /// Check if a response may be compressed, ignoring its size
+    fn should_compress_response(&self, response: &Response) -> bool {
+        let headers = response.headers();
+        if response.status() == http::StatusCode::PARTIAL_CONTENT
+            || headers.contains_key(header::CONTENT_ENCODING)
+            || headers.contains_key(header::CONTENT_RANGE)
+            || response.extensions().get::<NoCompression>().is_some()
+        {
+            return false;
+        }
+        let no_transform = headers
+            .get_all(header::CACHE_CONTROL)
+            .iter()
+            .filter_map(|value| value.to_str().ok())
+            .flat_map(|value| value.split(','))
+            .any(|directive| directive.trim().eq_ignore_ascii_case("no-transform"));
+        if no_transform {
+            return false;
+        }
+
+        let content_type = headers
+            .get(header::CONTENT_TYPE)
+            .and_then(|v| v.to_str().ok())
+            .unwrap_or("");
+        self.should_compress_content_type(content_type)
+    }
+
     /// Check if a content type should be compressed
     fn should_compress_content_type(&self, content_type: &str) -> bool {
-        if self.content_types.is_empty() {
-            return true;
+        let content_type = content_type.trim().to_ascii_lowercase();
+        // Compressing event streams buffers them, which breaks delivery
+        if content_type.starts_with("text/event-stream") {
+            return false;
+        }
+
+        let longest_match = |types: &[String]| {
+            types
+                .iter()
+                .filter(|ct| content_type.starts_with(&ct.to_ascii_lowercase()))
+                .map(String::len)
+                .max()
+        };
+        let allowed = if self.content_types.is_empty() {
+            Some(0)
+        } else {
+            longest_match(&self.content_types)
+        };
+        match (allowed, longest_match(&self.exclude_content_types)) {
+            (Some(allow), Some(deny)) => allow > deny,
+            (allowed, _) => allowed.is_some(),
         }
-        self.content_types
-            .iter()
-            .any(|ct| content_type.starts_with(ct.as_str()))
     }
 }
 
@@ -239,10 +444,15 @@ impl CompressionLayer {
             CompressionAlgorithm::Brotli => {
                 use brotli::enc::BrotliEncoderParams;
                 let mut output = Vec::new();
-                let params = BrotliEncoderParams::default();
+                let params = BrotliEncoderParams {
+                    quality: self.config.brotli_quality as i32,
+                    ..Default::default()
+                };
                 brotli::BrotliCompress(&mut &data[..], &mut output, &params)?;
                 Ok(output)
             }
+            #[cfg(feature = "compression-zstd")]
+            CompressionAlgorithm::Zstd => zstd::bulk::compress(data, self.config.zstd_level),
             CompressionAlgorithm::Identity => Ok(data.to_vec()),
         }
     }
@@ -276,32 +486,47 @@ impl MiddlewareLayer for CompressionLayer {
             // Determine compression algorithm
             let algorithm = accept_encoding
                 .as_ref()
-                .map(|ae| CompressionAlgorithm::from_accept_encoding(ae))
+                .map(|ae| config.negotiate(ae))
                 .unwrap_or(CompressionAlgorithm::Identity);
 
-            // Check if we should compress
-            if algorithm == CompressionAlgorithm::Identity {
+            if algorithm == CompressionAlgorithm::Identity
+                || !config.should_compress_response(&response)
+            {
                 return response;
             }
 
-            // Check if response is already encoded
-            if response.headers().contains_key(header::CONTENT_ENCODING) {
-                return response;
-            }
-
-            // Check content type
-            let content_type = response
-                .headers()
-                .get(header::CONTENT_TYPE)
-                .and_then(|v| v.to_str().ok())
-                .unwrap_or("");
-
-            if !config.should_compress_content_type(content_type) {
-                return response;
+            let (mut parts, body) = response.into_parts();
+            let small = body
+                .size_hint()
+                .exact()
+                .is_some_and(|len| len < config.min_size as u64);
+            if let crate::response::Body::Streaming(_) = body {
+                if !config.compress_streams {
+                    return http::Response::from_parts(parts, body);
+                }
+                if !small {
+                    let encoder = match StreamEncoder::new(algorithm, &config) {
+                        Ok(encoder) => encoder,
+                        Err(_) => return http::Response::from_parts(parts, body),
+                    };
+                    parts.headers.insert(
+                        header::CONTENT_ENCODING,
+                        http::HeaderValue::from_static(algorithm.content_encoding()),
+                    );
+                    parts.headers.remove(header::CONTENT_LENGTH);
+                    let body = CompressedBody {
+                        inner: body,
+                        encoder: Some(encoder),
+                        trailers: None,
+                    };
+                    return http::Response::from_parts(
+                        parts,
+                        crate::response::Body::Streaming(Box::pin(body)),
+                    );
+                }
             }
 
-            // Get body
-            let (parts, body) = response.into_parts();
+            // Buffered bodies, and streams known to be below `min_size`
             let body_bytes = match body.collect().await {
                 Ok(collected) => collected.to_bytes(),
                 Err(_) => {
@@ -357,6 +582,134 @@ impl MiddlewareLayer for CompressionLayer {
     }
 }
 
+/// Incremental encoder for streaming bodies
+enum StreamEncoder {
+    Gzip(GzEncoder<Vec<u8>>),
+    Deflate(DeflateEncoder<Vec<u8>>),
+    #[cfg(feature = "compression-brotli")]
+    Brotli(Box<brotli::CompressorWriter<Vec<u8>>>),
+    #[cfg(feature = "compression-zstd")]
+    Zstd(zstd::stream::write::Encoder<'static, Vec<u8>>),
+}
+
+impl StreamEncoder {
+    fn new(algorithm: CompressionAlgorithm, config: &CompressionConfig) -> std::io::Result<Self> {
+        let level = Compression::new(config.level);
+        Ok(match algorithm {
+            CompressionAlgorithm::Gzip => Self::Gzip(GzEncoder::new(Vec::new(), level)),
+            CompressionAlgorithm::Deflate => Self::Deflate(DeflateEncoder::new(Vec::new(), level)),
+            #[cfg(feature = "compression-brotli")]
+            CompressionAlgorithm::Brotli => Self::Brotli(Box::new(brotli::CompressorWriter::new(
+                Vec::new(),
+                4096,
+                config.brotli_quality,
+                22,
+            ))),
+            #[cfg(feature = "compression-zstd")]
+            CompressionAlgorithm::Zstd => Self::Zstd(zstd::stream::write::Encoder::new(
+                Vec::new(),
+                config.zstd_level,
+            )?),
+            CompressionAlgorithm::Identity => {
+                return Err(std::io::Error::other("identity is not an encoding"))
+            }
+        })
+    }
+
+    /// Compress a chunk and flush, returning everything encoded so far
+    fn encode(&mut self, chunk: &[u8]) -> std::io::Result<Bytes> {
+        let output = match self {
+            Self::Gzip(encoder) => {
+                encoder.write_all(chunk)?;
+                encoder.flush()?;
+                encoder.get_mut()
+            }
+            Self::Deflate(encoder) => {
+                encoder.write_all(chunk)?;
+                encoder.flush()?;
+                encoder.get_mut()
+            }
+            #[cfg(feature = "compression-brotli")]
+            Self::Brotli(encoder) => {
+                encoder.write_all(chunk)?;
+                encoder.flush()?;
+                encoder.get_mut()
+            }
+            #[cfg(feature = "compression-zstd")]
+            Self::Zstd(encoder) => {
+                encoder.write_all(chunk)?;
+                encoder.flush()?;
+                encoder.get_mut()
+            }
+        };
+        Ok(Bytes::from(std::mem::take(output)))
+    }
+
+    /// End the stream, returning the remaining output
+    fn finish(self) -> std::io::Result<Bytes> {
+        let output = match self {
+            Self::Gzip(encoder) => encoder.finish()?,
+            Self::Deflate(encoder) => encoder.finish()?,
+            #[cfg(feature = "compression-brotli")]
+            Self::Brotli(encoder) => encoder.into_inner(),
+            #[cfg(feature = "compression-zstd")]
+            Self::Zstd(encoder) => encoder.finish()?,
+        };
+        Ok(Bytes::from(output))
+    }
+}
+
+/// Streaming body compressed as it is sent
+struct CompressedBody {
+    inner: crate::response::Body,
+    /// `None` once the inner body has ended
+    encoder: Option<StreamEncoder>,
+    /// Trailers of the inner body, sent after the last compressed data
+    trailers: Option<http::HeaderMap>,
+}
+
+impl http_body::Body for CompressedBody {
+    type Data = Bytes;
+    type Error = crate::error::ApiError;
+
+    fn poll_frame(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Result<Frame<Bytes>, Self::Error>>> {
+        let this = self.get_mut();
+        let compression_failed = |e: std::io::Error| {
+            crate::error::ApiError::internal(format!("Compression failed: {}", e))
+        };
+
+        loop {
+            let Some(encoder) = this.encoder.as_mut() else {
+                return Poll::Ready(this.trailers.take().map(|t| Ok(Frame::trailers(t))));
+            };
+            let data = match ready!(Pin::new(&mut this.inner).poll_frame(cx)) {
+                Some(Ok(frame)) => match frame.into_data() {
+                    Ok(chunk) => encoder.encode(&chunk).map_err(compression_failed)?,
+                    Err(frame) => {
+                        this.trailers = frame.into_trailers().ok();
+                        continue;
+                    }
+                },
+                Some(Err(e)) => return Poll::Ready(Some(Err(e))),
+                None => match this.encoder.take() {
+                    Some(encoder) => encoder.finish().map_err(compression_failed)?,
+                    None => continue,
+                },
+            };
+            if !data.is_empty() {
+                return Poll::Ready(Some(Ok(Frame::data(data))));
+            }
+        }
+    }
+
+    fn is_end_stream(&self) -> bool {
+        self.encoder.is_none() && self.trailers.is_none()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -413,6 +766,228 @@ mod tests {
         assert!(!config.should_compress_content_type("image/png"));
     }
 
+    #[test]
+    fn test_content_type_policy() {
+        let config = CompressionConfig::new();
+        // The more specific allow entry beats the `image/` exclusion
+        assert!(config.should_compress_content_type("image/svg+xml"));
+        assert!(config.should_compress_content_type("Application/JSON; charset=utf-8"));
+        assert!(!config.should_compress_content_type("text/event-stream"));
+
+        let config = CompressionConfig::new()
+            .content_types(Vec::new())
+            .exclude_content_type("application/vnd.custom");
+        assert!(config.should_compress_content_type("application/pdf"));
+        assert!(!config.should_compress_content_type("application/vnd.custom+json"));
+        assert!(!config.should_compress_content_type("video/mp4"));
+
+        assert!(!config.should_compress_content_type("application/x-7z-compressed"));
+
+        // Event streams stay uncompressed even without the default exclusions
+        let config = CompressionConfig::new().exclude_content_types(Vec::new());
+        assert!(!config.should_compress_content_type("text/event-stream"));
+    }
+
+    #[test]
+    fn test_negotiate_respects_quality_and_enabled_algorithms() {
+        let config = CompressionConfig::new();
+        assert_eq!(
+            config.negotiate("deflate, gzip"),
+            CompressionAlgorithm::Gzip
+        );
+        assert_eq!(
+            config.negotiate("gzip;q=0, deflate;q=0.5"),
+            CompressionAlgorithm::Deflate
+        );
+        assert_eq!(config.negotiate("*"), CompressionAlgorithm::Gzip);
+
+        let config = CompressionConfig::new().gzip(false);
+        assert_eq!(
+            config.negotiate("gzip, deflate;q=0.1"),
+            CompressionAlgorithm::Deflate
+        );
+        assert_eq!(config.negotiate("gzip"), CompressionAlgorithm::Identity);
+    }
+
+    #[cfg(feature = "compression-zstd")]
+    #[test]
+    fn test_zstd_negotiation_and_roundtrip() {
+        let config = CompressionConfig::new();
+        assert_eq!(
+            config.negotiate("gzip, deflate, br, zstd"),
+            CompressionAlgorithm::Zstd
+        );
+        assert_eq!(
+            config.clone().zstd(false).negotiate("zstd, gzip;q=0.5"),
+            CompressionAlgorithm::Gzip
+        );
+
+        let layer = CompressionLayer::with_config(config.zstd_level(19));
+        let data = "compress me ".repeat(100);
+        let compressed = layer
+            .compress(data.as_bytes(), CompressionAlgorithm::Zstd)
+            .unwrap();
+        assert!(compressed.len() < data.len());
+        assert_eq!(zstd::decode_all(&compressed[..]).unwrap(), data.as_bytes());
+    }
+
+    #[tokio::test]
+    async fn test_event_streams_are_never_compressed() {
+        let layer = CompressionLayer::new().min_size(0);
+        let next: BoxedNext = std::sync::Arc::new(|_req: Request| {
+            Box::pin(async {
+                http::Response::builder()
+                    .header(header::CONTENT_TYPE, "text/event-stream")
+                    .body(crate::response::Body::Full(Full::new(Bytes::from(
+                        "data: hello\n\n".repeat(200),
+                    ))))
+                    .unwrap()
+            }) as Pin<Box<dyn Future<Output = Response> + Send + 'static>>
+        });
+        let req = Request::from_http_request(
+            http::Request::builder()
+                .header(header::ACCEPT_ENCODING, "gzip")
+                .body(())
+                .unwrap(),
+            Bytes::new(),
+        );
+
+        let response = layer.call(req, next).await;
+        assert!(!response.headers().contains_key(header::CONTENT_ENCODING));
+    }
+
+    fn respond_with(response: impl Fn() -> Response + Send + Sync + 'static) -> BoxedNext {
+        std::sync::Arc::new(move |_req: Request| {
+            let response = response();
+            Box::pin(async move { response })
+                as Pin<Box<dyn Future<Output = Response> + Send + 'static>>
+        })
+    }
+
+    fn gzip_request() -> Request {
+        Request::from_http_request(
+            http::Request::builder()
+                .header(header::ACCEPT_ENCODING, "gzip")
+                .body(())
+                .unwrap(),
+            Bytes::new(),
+        )
+    }
+
+    #[tokio::test]
+    async fn test_flagged_and_no_transform_responses_are_left_alone() {
+        let layer = CompressionLayer::new().min_size(0);
+        fn text() -> String {
+            "plain text ".repeat(200)
+        }
+
+        let flagged = respond_with(|| {
+            let mut response = http::Response::new(crate::response::Body::from(text()));
+            response
+                .headers_mut()
+                .insert(header::CONTENT_TYPE, "text/plain".parse().unwrap());
+            response.extensions_mut().insert(NoCompression);
+            response
+        });
+        let response = layer.call(gzip_request(), flagged).await;
+        assert!(!response.headers().contains_key(header::CONTENT_ENCODING));
+
+        let no_transform = respond_with(|| {
+            http::Response::builder()
+                .header(header::CONTENT_TYPE, "text/plain")
+                .header(header::CACHE_CONTROL, "public, No-Transform")
+                .body(crate::response::Body::from(text()))
+                .unwrap()
+        });
+        let response = layer.call(gzip_request(), no_transform).await;
+        assert!(!response.headers().contains_key(header::CONTENT_ENCODING));
+
+        let plain = respond_with(|| {
+            http::Response::builder()
+                .header(header::CONTENT_TYPE, "text/plain")
+                .body(crate::response::Body::from(text()))
+                .unwrap()
+        });
+        let response = layer.call(gzip_request(), plain).await;
+        assert_eq!(response.headers()[header::CONTENT_ENCODING], "gzip");
+    }
+
+    #[tokio::test]
+    async fn test_streaming_bodies_are_compressed_chunk_by_chunk() {
+        use flate2::write::GzDecoder;
+
+        let (tx, rx) = tokio::sync::mpsc::channel::<Bytes>(4);
+        let rx = std::sync::Mutex::new(Some(rx));
+
+        let streaming = respond_with(move || {
+            let rx = rx.lock().unwrap().take().unwrap();
+            let stream = futures_util::stream::unfold(rx, |mut rx| async move {
+                let chunk = rx.recv().await?;
+                Some((Ok::<_, crate::error::ApiError>(chunk), rx))
+            });
+            let mut response = http::Response::new(crate::response::Body::from_stream(stream));
+            response
+                .headers_mut()
+                .insert(header::CONTENT_TYPE, "application/json".parse().unwrap());
+            response
+        });
+        let layer = CompressionLayer::new();
+        let response = layer.call(gzip_request(), streaming).await;
+        assert_eq!(response.headers()[header::CONTENT_ENCODING], "gzip");
+        let mut body = response.into_body();
+        let mut decoder = GzDecoder::new(Vec::new());
+
+        // The first chunk arrives while the stream is still open
+        tx.send(Bytes::from_static(b"[{\"id\":1},")).await.unwrap();
+        let frame = body.frame().await.unwrap().unwrap();
+        decoder.write_all(frame.data_ref().unwrap()).unwrap();
+        decoder.flush().unwrap();
+        assert_eq!(decoder.get_ref().as_slice(), b"[{\"id\":1},");
+
+        tx.send(Bytes::from_static(b"{\"id\":2}]")).await.unwrap();
+        drop(tx);
+        while let Some(frame) = body.frame().await {
+            decoder
+                .write_all(frame.unwrap().data_ref().unwrap())
+                .unwrap();
+        }
+        assert_eq!(decoder.finish().unwrap(), b"[{\"id\":1},{\"id\":2}]");
+    }
+
+    #[test]
+    fn test_stream_encoders_round_trip() {
+        use std::io::Read;
+
+        for &algorithm in CompressionAlgorithm::PREFERENCE {
+            let mut encoder = StreamEncoder::new(algorithm, &CompressionConfig::new()).unwrap();
+            let mut encoded = encoder.encode(b"first chunk, ").unwrap().to_vec();
+            assert!(!encoded.is_empty(), "{:?} held back a chunk", algorithm);
+            encoded.extend_from_slice(&encoder.encode(b"second chunk").unwrap());
+            encoded.extend_from_slice(&encoder.finish().unwrap());
+
+            let mut decoded = Vec::new();
+            match algorithm {
+                CompressionAlgorithm::Gzip => {
+                    flate2::read::GzDecoder::new(&encoded[..]).read_to_end(&mut decoded)
+                }
+                CompressionAlgorithm::Deflate => {
+                    flate2::read::DeflateDecoder::new(&encoded[..]).read_to_end(&mut decoded)
+                }
+                #[cfg(feature = "compression-brotli")]
+                CompressionAlgorithm::Brotli => {
+                    brotli::Decompressor::new(&encoded[..], 4096).read_to_end(&mut decoded)
+                }
+                #[cfg(feature = "compression-zstd")]
+                CompressionAlgorithm::Zstd => zstd::stream::read::Decoder::new(&encoded[..])
+                    .unwrap()
+                    .read_to_end(&mut decoded),
+                CompressionAlgorithm::Identity => unreachable!(),
+            }
+            .unwrap();
+            assert_eq!(decoded, b"first chunk, second chunk", "{:?}", algorithm);
+        }
+    }
+
     #[test]
     fn test_gzip_compression() {
         let layer = CompressionLayer::new();
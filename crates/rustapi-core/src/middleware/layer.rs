@@ -68,6 +68,14 @@ pub trait MiddlewareLayer: Send + Sync + 'static {
 
     /// Clone this middleware into a boxed trait object
     fn clone_box(&self) -> Box<dyn MiddlewareLayer>;
+
+    /// Name of this middleware in the route table (`cargo rustapi routes`)
+    ///
+    /// Defaults to the type name; wrappers around user-supplied layers may
+    /// override it to report the wrapped type.
+    fn name(&self) -> &'static str {
+        std::any::type_name::<Self>()
+    }
 }
 
 impl Clone for Box<dyn MiddlewareLayer> {
@@ -112,6 +120,11 @@ impl LayerStack {
         self.layers.len()
     }
 
+    /// Names of the layers, outermost first
+    pub fn names(&self) -> Vec<&'static str> {
+        self.layers.iter().map(|layer| layer.name()).collect()
+    }
+
     /// Execute the middleware stack with a final handler
     pub fn execute(
         &self,
@@ -0,0 +1,180 @@
+//! Request log sampling
+//!
+//! High-traffic services rarely need a log line for every successful request,
+//! but always want the failures and the slow ones. [`LogSampling`] decides per
+//! request, once the response is known:
+//!
+//! - errors (`4xx`/`5xx`) are logged at the error rate (all of them by default)
+//! - other responses are logged at the success rate
+//! - requests slower than the slow threshold are always logged, with full detail
+//!
+//! Sampling is deterministic: a rate of `0.01` logs every hundredth request of
+//! its class, so low-traffic routes are not starved by chance.
+//!
+//! ```rust,ignore
+//! use rustapi_core::middleware::{LogSampling, TracingLayer};
+//! use std::time::Duration;
+//!
+//! RustApi::new().layer(
+//!     TracingLayer::new().sampling(
+//!         LogSampling::new()
+//!             .success_rate(0.01)
+//!             .slow_threshold(Duration::from_millis(500)),
+//!     ),
+//! )
+//! ```
+
+use http::StatusCode;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Which requests the tracing and access-log layers write a line for
+///
+/// Clones share their counters, so the rates hold across clones of a layer.
+#[derive(Debug, Clone)]
+pub struct LogSampling {
+    success_rate: f64,
+    error_rate: f64,
+    slow_threshold: Option<Duration>,
+    seen: Arc<[AtomicU64; 2]>,
+}
+
+/// What to log for one request
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LogDecision {
+    /// Sampled out
+    Skip,
+    /// Log the usual line
+    Log,
+    /// Slower than the threshold: log with full detail
+    Slow,
+}
+
+impl LogDecision {
+    /// Whether anything is logged
+    pub fn should_log(self) -> bool {
+        self != Self::Skip
+    }
+
+    /// Whether the request exceeded the slow threshold
+    pub fn is_slow(self) -> bool {
+        self == Self::Slow
+    }
+}
+
+impl LogSampling {
+    /// Log every request, with no slow threshold
+    pub fn new() -> Self {
+        Self {
+            success_rate: 1.0,
+            error_rate: 1.0,
+            slow_threshold: None,
+            seen: Arc::default(),
+        }
+    }
+
+    /// Share of successful (non-`4xx`/`5xx`) requests to log, from `0.0` to `1.0`
+    pub fn success_rate(mut self, rate: f64) -> Self {
+        self.success_rate = rate.clamp(0.0, 1.0);
+        self
+    }
+
+    /// Share of `4xx`/`5xx` responses to log, from `0.0` to `1.0`
+    pub fn error_rate(mut self, rate: f64) -> Self {
+        self.error_rate = rate.clamp(0.0, 1.0);
+        self
+    }
+
+    /// Always log requests taking longer than `threshold`, with full detail
+    pub fn slow_threshold(mut self, threshold: Duration) -> Self {
+        self.slow_threshold = Some(threshold);
+        self
+    }
+
+    /// The slow threshold, if set
+    pub fn threshold(&self) -> Option<Duration> {
+        self.slow_threshold
+    }
+
+    /// Whether every request is logged, so nothing needs to wait for the response
+    pub fn logs_everything(&self) -> bool {
+        self.success_rate >= 1.0 && self.error_rate >= 1.0
+    }
+
+    /// Decide for a request that got `status` after `elapsed`
+    pub fn decide(&self, status: StatusCode, elapsed: Duration) -> LogDecision {
+        if self
+            .slow_threshold
+            .is_some_and(|threshold| elapsed > threshold)
+        {
+            return LogDecision::Slow;
+        }
+        let error = status.is_client_error() || status.is_server_error();
+        let (rate, seen) = if error {
+            (self.error_rate, &self.seen[1])
+        } else {
+            (self.success_rate, &self.seen[0])
+        };
+        if sampled(rate, seen) {
+            LogDecision::Log
+        } else {
+            LogDecision::Skip
+        }
+    }
+}
+
+impl Default for LogSampling {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Take the `n`th request when `n * rate` crosses an integer
+fn sampled(rate: f64, seen: &AtomicU64) -> bool {
+    if rate >= 1.0 {
+        return true;
+    }
+    if rate <= 0.0 {
+        return false;
+    }
+    let n = seen.fetch_add(1, Ordering::Relaxed) as f64;
+    ((n + 1.0) * rate).floor() > (n * rate).floor()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn success_rate_logs_a_share_and_errors_are_always_logged() {
+        let sampling = LogSampling::new().success_rate(0.01);
+        let fast = Duration::from_millis(5);
+
+        let logged = (0..1000)
+            .filter(|_| sampling.decide(StatusCode::OK, fast).should_log())
+            .count();
+        assert_eq!(logged, 10);
+
+        assert!((0..100).all(|_| sampling
+            .decide(StatusCode::INTERNAL_SERVER_ERROR, fast)
+            .should_log()));
+        assert!(sampling.clone().decide(StatusCode::OK, fast) == LogDecision::Skip);
+    }
+
+    #[test]
+    fn slow_requests_are_promoted_regardless_of_rate() {
+        let sampling = LogSampling::new()
+            .success_rate(0.0)
+            .slow_threshold(Duration::from_millis(500));
+
+        assert_eq!(
+            sampling.decide(StatusCode::OK, Duration::from_millis(20)),
+            LogDecision::Skip
+        );
+        assert_eq!(
+            sampling.decide(StatusCode::OK, Duration::from_millis(800)),
+            LogDecision::Slow
+        );
+    }
+}
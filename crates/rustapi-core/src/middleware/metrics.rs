@@ -8,6 +8,16 @@
 //!
 //! - `http_requests_total` - Counter with labels: method, path, status
 //! - `http_request_duration_seconds` - Histogram with labels: method, path
+//! - `http_requests_by_tenant_total` - Counter with labels: tenant, status
+//!   (requests a `TenantLayer` inside this layer resolved a tenant for)
+//! - `http_requests_by_canary_arm_total` - Counter with labels: canary, arm, status
+//!   (requests served by a `Canary` route)
+//! - `sse_active_streams` - Gauge with label: path (open SSE connections)
+//! - `sse_events_total` - Counter with label: path (SSE events sent)
+//! - `sse_dropped_events_total` - Counter with label: path (events skipped for
+//!   clients that fell behind a `sse_from_broadcast` channel)
+//! - `sse_event_latency_seconds` - Histogram with label: path (time from
+//!   creating an SSE event to sending it; `_sum / _count` is the average)
 //! - `rustapi_info` - Gauge with label: version
 //!
 //! # Example
@@ -29,12 +39,13 @@ use crate::request::Request;
 use crate::response::Response;
 use bytes::Bytes;
 use prometheus::{
-    Encoder, GaugeVec, HistogramOpts, HistogramVec, IntCounterVec, Opts, Registry, TextEncoder,
+    Encoder, GaugeVec, Histogram, HistogramOpts, HistogramVec, IntCounter, IntCounterVec, IntGauge,
+    IntGaugeVec, Opts, Registry, TextEncoder,
 };
 use std::future::Future;
 use std::pin::Pin;
 use std::sync::Arc;
-use std::time::Instant;
+use std::time::{Duration, Instant};
 
 /// Default histogram buckets for request duration (in seconds)
 const DEFAULT_BUCKETS: &[f64] = &[
@@ -49,6 +60,12 @@ const DEFAULT_BUCKETS: &[f64] = &[
 ///
 /// - `http_requests_total{method, path, status}` - Total number of HTTP requests
 /// - `http_request_duration_seconds{method, path}` - HTTP request duration histogram
+/// - `http_requests_by_tenant_total{tenant, status}` - Requests per tenant
+/// - `http_requests_by_canary_arm_total{canary, arm, status}` - Requests per canary arm
+/// - `sse_active_streams{path}` - Open SSE connections
+/// - `sse_events_total{path}` - SSE events sent
+/// - `sse_dropped_events_total{path}` - SSE events skipped for slow clients
+/// - `sse_event_latency_seconds{path}` - Time from creating an SSE event to sending it
 /// - `rustapi_info{version}` - RustAPI version information gauge
 #[derive(Clone)]
 pub struct MetricsLayer {
@@ -59,6 +76,12 @@ struct MetricsInner {
     registry: Registry,
     requests_total: IntCounterVec,
     request_duration: HistogramVec,
+    tenant_requests: IntCounterVec,
+    canary_requests: IntCounterVec,
+    sse_active: IntGaugeVec,
+    sse_events: IntCounterVec,
+    sse_dropped: IntCounterVec,
+    sse_latency: HistogramVec,
     #[allow(dead_code)]
     info_gauge: GaugeVec,
 }
@@ -94,6 +117,55 @@ impl MetricsLayer {
         )
         .expect("Failed to create http_request_duration_seconds metric");
 
+        // Create http_requests_by_tenant_total counter
+        let tenant_requests = IntCounterVec::new(
+            Opts::new(
+                "http_requests_by_tenant_total",
+                "Total number of HTTP requests per tenant",
+            ),
+            &["tenant", "status"],
+        )
+        .expect("Failed to create http_requests_by_tenant_total metric");
+
+        // Create http_requests_by_canary_arm_total counter
+        let canary_requests = IntCounterVec::new(
+            Opts::new(
+                "http_requests_by_canary_arm_total",
+                "Total number of HTTP requests per canary arm",
+            ),
+            &["canary", "arm", "status"],
+        )
+        .expect("Failed to create http_requests_by_canary_arm_total metric");
+
+        // Create SSE connection metrics
+        let sse_active = IntGaugeVec::new(
+            Opts::new("sse_active_streams", "Number of open SSE connections"),
+            &["path"],
+        )
+        .expect("Failed to create sse_active_streams metric");
+        let sse_events = IntCounterVec::new(
+            Opts::new("sse_events_total", "Total number of SSE events sent"),
+            &["path"],
+        )
+        .expect("Failed to create sse_events_total metric");
+        let sse_dropped = IntCounterVec::new(
+            Opts::new(
+                "sse_dropped_events_total",
+                "Total number of SSE events skipped for clients that fell behind",
+            ),
+            &["path"],
+        )
+        .expect("Failed to create sse_dropped_events_total metric");
+        let sse_latency = HistogramVec::new(
+            HistogramOpts::new(
+                "sse_event_latency_seconds",
+                "Time from creating an SSE event to sending it, in seconds",
+            )
+            .buckets(DEFAULT_BUCKETS.to_vec()),
+            &["path"],
+        )
+        .expect("Failed to create sse_event_latency_seconds metric");
+
         // Create rustapi_info gauge
         let info_gauge = GaugeVec::new(
             Opts::new("rustapi_info", "RustAPI version information"),
@@ -108,6 +180,24 @@ impl MetricsLayer {
         registry
             .register(Box::new(request_duration.clone()))
             .expect("Failed to register http_request_duration_seconds");
+        registry
+            .register(Box::new(tenant_requests.clone()))
+            .expect("Failed to register http_requests_by_tenant_total");
+        registry
+            .register(Box::new(canary_requests.clone()))
+            .expect("Failed to register http_requests_by_canary_arm_total");
+        registry
+            .register(Box::new(sse_active.clone()))
+            .expect("Failed to register sse_active_streams");
+        registry
+            .register(Box::new(sse_events.clone()))
+            .expect("Failed to register sse_events_total");
+        registry
+            .register(Box::new(sse_dropped.clone()))
+            .expect("Failed to register sse_dropped_events_total");
+        registry
+            .register(Box::new(sse_latency.clone()))
+            .expect("Failed to register sse_event_latency_seconds");
         registry
             .register(Box::new(info_gauge.clone()))
             .expect("Failed to register rustapi_info");
@@ -121,6 +211,12 @@ impl MetricsLayer {
                 registry,
                 requests_total,
                 request_duration,
+                tenant_requests,
+                canary_requests,
+                sse_active,
+                sse_events,
+                sse_dropped,
+                sse_latency,
                 info_gauge,
             }),
         }
@@ -386,6 +482,23 @@ impl MiddlewareLayer for MetricsLayer {
             let duration = start.elapsed().as_secs_f64();
             let status = response.status().as_u16();
             metrics.record_request(&method, &path, status, duration);
+            if let Some(tenant) = response.extensions().get::<crate::tenant::Tenant>() {
+                metrics
+                    .inner
+                    .tenant_requests
+                    .with_label_values(&[tenant.id(), &status.to_string()])
+                    .inc();
+            }
+            if let Some(tag) = response.extensions().get::<crate::canary::CanaryArm>() {
+                metrics
+                    .inner
+                    .canary_requests
+                    .with_label_values(&[&tag.name, tag.arm.as_str(), &status.to_string()])
+                    .inc();
+            }
+            if let Some(hooks) = response.extensions().get::<crate::sse::SseHooks>() {
+                hooks.observe(Arc::new(SseMetrics::new(&metrics.inner, &path)));
+            }
 
             response
         })
@@ -396,6 +509,46 @@ impl MiddlewareLayer for MetricsLayer {
     }
 }
 
+/// Metrics of one SSE connection
+struct SseMetrics {
+    active: IntGauge,
+    events: IntCounter,
+    dropped: IntCounter,
+    latency: Histogram,
+}
+
+impl SseMetrics {
+    fn new(inner: &MetricsInner, path: &str) -> Self {
+        Self {
+            active: inner.sse_active.with_label_values(&[path]),
+            events: inner.sse_events.with_label_values(&[path]),
+            dropped: inner.sse_dropped.with_label_values(&[path]),
+            latency: inner.sse_latency.with_label_values(&[path]),
+        }
+    }
+}
+
+impl crate::sse::SseObserver for SseMetrics {
+    fn opened(&self) {
+        self.active.inc();
+    }
+
+    fn sent(&self, latency: Option<Duration>) {
+        self.events.inc();
+        if let Some(latency) = latency {
+            self.latency.observe(latency.as_secs_f64());
+        }
+    }
+
+    fn dropped(&self, count: u64) {
+        self.dropped.inc_by(count);
+    }
+
+    fn closed(&self) {
+        self.active.dec();
+    }
+}
+
 /// Response type for the metrics endpoint
 pub struct MetricsResponse(Vec<u8>);
 
@@ -734,6 +887,144 @@ mod tests {
         });
     }
 
+    #[test]
+    fn test_metrics_layer_counts_requests_per_tenant() {
+        let rt = tokio::runtime::Runtime::new().unwrap();
+        rt.block_on(async {
+            let metrics = MetricsLayer::new();
+
+            let mut stack = LayerStack::new();
+            stack.push(Box::new(metrics.clone()));
+            stack
+                .push(Box::new(crate::tenant::TenantLayer::new().resolver(
+                    |_: &crate::request::Request| Some("acme".to_string()),
+                )));
+
+            let handler: BoxedNext = Arc::new(|_req: crate::request::Request| {
+                Box::pin(async {
+                    http::Response::builder()
+                        .status(StatusCode::OK)
+                        .body(crate::response::Body::Full(http_body_util::Full::new(
+                            Bytes::from("ok"),
+                        )))
+                        .unwrap()
+                }) as Pin<Box<dyn Future<Output = Response> + Send + 'static>>
+            });
+
+            let request = create_test_request(Method::GET, "/test");
+            stack.execute(request, handler).await;
+
+            let metric_families = metrics.registry().gather();
+            let by_tenant = metric_families
+                .iter()
+                .find(|mf| mf.name() == "http_requests_by_tenant_total")
+                .unwrap();
+            let metric = &by_tenant.get_metric()[0];
+            assert!(metric
+                .get_label()
+                .iter()
+                .any(|l| l.name() == "tenant" && l.value() == "acme"));
+            assert_eq!(metric.get_counter().get_value(), 1.0);
+        });
+    }
+
+    #[test]
+    fn test_metrics_layer_counts_requests_per_canary_arm() {
+        let rt = tokio::runtime::Runtime::new().unwrap();
+        rt.block_on(async {
+            let metrics = MetricsLayer::new();
+
+            let mut stack = LayerStack::new();
+            stack.push(Box::new(metrics.clone()));
+
+            let handler: BoxedNext = Arc::new(|_req: crate::request::Request| {
+                Box::pin(async {
+                    let mut response = http::Response::builder()
+                        .status(StatusCode::OK)
+                        .body(crate::response::Body::Full(http_body_util::Full::new(
+                            Bytes::from("ok"),
+                        )))
+                        .unwrap();
+                    response.extensions_mut().insert(crate::canary::CanaryArm {
+                        name: "orders".into(),
+                        arm: crate::canary::Arm::Canary,
+                    });
+                    response
+                }) as Pin<Box<dyn Future<Output = Response> + Send + 'static>>
+            });
+
+            let request = create_test_request(Method::GET, "/orders");
+            stack.execute(request, handler).await;
+
+            let metric_families = metrics.registry().gather();
+            let by_arm = metric_families
+                .iter()
+                .find(|mf| mf.name() == "http_requests_by_canary_arm_total")
+                .unwrap();
+            let metric = &by_arm.get_metric()[0];
+            let label = |name: &str| {
+                metric
+                    .get_label()
+                    .iter()
+                    .find(|l| l.name() == name)
+                    .map(|l| l.value().to_string())
+            };
+            assert_eq!(label("canary").as_deref(), Some("orders"));
+            assert_eq!(label("arm").as_deref(), Some("canary"));
+            assert_eq!(metric.get_counter().get_value(), 1.0);
+        });
+    }
+
+    #[test]
+    fn test_metrics_layer_tracks_sse_connections() {
+        use crate::response::IntoResponse;
+        use crate::sse::{sse_from_broadcast, SseEvent};
+        use http_body_util::BodyExt;
+
+        let rt = tokio::runtime::Runtime::new().unwrap();
+        rt.block_on(async {
+            let metrics = MetricsLayer::new();
+            let mut stack = LayerStack::new();
+            stack.push(Box::new(metrics.clone()));
+
+            // A client two events behind a channel of two skips the oldest two
+            let (tx, rx) = tokio::sync::broadcast::channel(2);
+            for i in 0..4 {
+                tx.send(SseEvent::new(i.to_string())).unwrap();
+            }
+            let rx = Arc::new(std::sync::Mutex::new(Some(rx)));
+            let handler: BoxedNext = Arc::new(move |_req: crate::request::Request| {
+                let rx = rx.lock().unwrap().take().unwrap();
+                Box::pin(async move { sse_from_broadcast(rx).into_response() })
+                    as Pin<Box<dyn Future<Output = Response> + Send + 'static>>
+            });
+
+            let response = stack
+                .execute(create_test_request(Method::GET, "/feed"), handler)
+                .await;
+            let value = |name: &str| {
+                let families = metrics.registry().gather();
+                let family = families.iter().find(|mf| mf.name() == name).unwrap();
+                let metric = &family.get_metric()[0];
+                match name {
+                    "sse_active_streams" => metric.get_gauge().get_value(),
+                    "sse_event_latency_seconds" => metric.get_histogram().get_sample_count() as f64,
+                    _ => metric.get_counter().get_value(),
+                }
+            };
+            assert_eq!(value("sse_active_streams"), 1.0);
+
+            drop(tx);
+            let body = response.into_body().collect().await.unwrap().to_bytes();
+            assert_eq!(body, "data: 2\n\ndata: 3\n\n");
+
+            assert_eq!(value("sse_active_streams"), 0.0);
+            assert_eq!(value("sse_events_total"), 2.0);
+            assert_eq!(value("sse_dropped_events_total"), 2.0);
+            assert_eq!(value("sse_event_latency_seconds"), 2.0);
+        });
+    }
+
     #[test]
     fn test_metrics_layer_with_multiple_requests() {
         let rt = tokio::runtime::Runtime::new().unwrap();
@@ -17,19 +17,26 @@
 //! ```
 
 mod body_limit;
+mod body_transform;
 #[cfg(feature = "compression")]
 mod compression;
 mod layer;
+mod log_sampling;
 #[cfg(feature = "metrics")]
 mod metrics;
 mod request_id;
+mod server_timing;
 mod tracing_layer;
 
 pub use body_limit::{BodyLimitLayer, DEFAULT_BODY_LIMIT};
+pub use body_transform::{BodyTransformLayer, DEFAULT_TRANSFORM_LIMIT};
 #[cfg(feature = "compression")]
-pub use compression::{CompressionAlgorithm, CompressionConfig, CompressionLayer};
+pub use compression::{CompressionAlgorithm, CompressionConfig, CompressionLayer, NoCompression};
 pub use layer::{BoxedNext, LayerStack, MiddlewareLayer};
+pub use log_sampling::{LogDecision, LogSampling};
 #[cfg(feature = "metrics")]
 pub use metrics::{CustomMetricsBuilder, MetricsLayer, MetricsResponse};
 pub use request_id::{RequestId, RequestIdLayer};
+pub(crate) use server_timing::PhaseTimer;
+pub use server_timing::{ServerTiming, ServerTimingLayer, ServerTimingSpan};
 pub use tracing_layer::TracingLayer;
@@ -0,0 +1,322 @@
+//! Server-Timing middleware
+//!
+//! Reports where the time of a request went in a `Server-Timing` response
+//! header, which browser developer tools show next to the request:
+//!
+//! ```text
+//! Server-Timing: routing;dur=0.004, extractors;dur=0.081, db;desc="load pins";dur=8.214,
+//!                handler;dur=8.532, serialization;dur=0.047, total;dur=8.901
+//! ```
+//!
+//! The `routing`, `extractors`, `handler` and `serialization` phases are
+//! recorded by the framework; handlers add their own with the [`ServerTiming`]
+//! extractor. Timings reveal internals, so the header is only sent outside
+//! production unless enabled explicitly.
+
+use super::layer::{BoxedNext, MiddlewareLayer};
+use crate::error::{ApiError, Environment, Result};
+use crate::extract::FromRequestParts;
+use crate::request::Request;
+use crate::response::{IntoResponse, Response};
+use std::fmt::Write;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+#[derive(Debug, Clone)]
+struct Metric {
+    name: String,
+    description: Option<String>,
+    duration: Duration,
+}
+
+/// Timings of the current request
+///
+/// Available as an extractor when [`ServerTimingLayer`] is enabled. Clones
+/// record into the same header.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// async fn list_pins(timing: ServerTiming, State(db): State<Db>) -> Json<Vec<Pin>> {
+///     let pins = {
+///         let _span = timing.start("db").description("load pins");
+///         db.pins().await
+///     };
+///     Json(pins)
+/// }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct ServerTiming {
+    metrics: Arc<Mutex<Vec<Metric>>>,
+}
+
+impl ServerTiming {
+    /// Record a measured duration
+    ///
+    /// `name` should be a token (letters, digits, `-`, `_`); other characters
+    /// are replaced with `_`.
+    pub fn record(&self, name: &str, duration: Duration) {
+        self.push(name, None, duration);
+    }
+
+    /// Record a measured duration with a description
+    pub fn record_with_description(&self, name: &str, description: &str, duration: Duration) {
+        self.push(name, Some(description.to_string()), duration);
+    }
+
+    /// Measure from now until the returned guard is dropped
+    pub fn start(&self, name: &str) -> ServerTimingSpan {
+        ServerTimingSpan {
+            timing: self.clone(),
+            name: name.to_string(),
+            description: None,
+            started: Instant::now(),
+        }
+    }
+
+    /// The timings recorded by a [`ServerTimingLayer`] that ran before this point
+    pub fn of(req: &Request) -> Option<&ServerTiming> {
+        req.extensions().get::<ServerTiming>()
+    }
+
+    fn push(&self, name: &str, description: Option<String>, duration: Duration) {
+        let name = name
+            .chars()
+            .map(|c| {
+                if c.is_ascii_alphanumeric() || c == '-' || c == '_' {
+                    c
+                } else {
+                    '_'
+                }
+            })
+            .collect();
+        if let Ok(mut metrics) = self.metrics.lock() {
+            metrics.push(Metric {
+                name,
+                description,
+                duration,
+            });
+        }
+    }
+
+    /// The `Server-Timing` header value
+    fn header_value(&self) -> String {
+        let metrics = self.metrics.lock().map(|m| m.clone()).unwrap_or_default();
+        let mut value = String::new();
+        for metric in metrics {
+            if !value.is_empty() {
+                value.push_str(", ");
+            }
+            value.push_str(&metric.name);
+            if let Some(description) = &metric.description {
+                let description = description.replace(['"', '\\'], "'");
+                let _ = write!(value, ";desc=\"{}\"", description);
+            }
+            let _ = write!(value, ";dur={:.3}", metric.duration.as_secs_f64() * 1000.0);
+        }
+        value
+    }
+}
+
+impl FromRequestParts for ServerTiming {
+    fn from_request_parts(req: &Request) -> Result<Self> {
+        ServerTiming::of(req).cloned().ok_or_else(|| {
+            ApiError::internal(
+                "ServerTiming not found. Did you forget to add ServerTimingLayer middleware?",
+            )
+        })
+    }
+}
+
+impl rustapi_openapi::OperationModifier for ServerTiming {
+    fn update_operation(_op: &mut rustapi_openapi::Operation) {}
+}
+
+/// A running measurement, recorded when dropped
+#[must_use = "the span is recorded when dropped"]
+pub struct ServerTimingSpan {
+    timing: ServerTiming,
+    name: String,
+    description: Option<String>,
+    started: Instant,
+}
+
+impl ServerTimingSpan {
+    /// Add a description shown next to the name
+    pub fn description(mut self, description: impl Into<String>) -> Self {
+        self.description = Some(description.into());
+        self
+    }
+}
+
+impl Drop for ServerTimingSpan {
+    fn drop(&mut self) {
+        self.timing
+            .push(&self.name, self.description.take(), self.started.elapsed());
+    }
+}
+
+/// Framework phases of a request, measured back to back
+///
+/// Does nothing unless the request carries a [`ServerTiming`].
+pub(crate) struct PhaseTimer {
+    timing: Option<(ServerTiming, Instant)>,
+}
+
+impl PhaseTimer {
+    pub(crate) fn start(req: &Request) -> Self {
+        Self {
+            timing: ServerTiming::of(req).map(|timing| (timing.clone(), Instant::now())),
+        }
+    }
+
+    /// Record the time since the previous phase ended as `name`
+    pub(crate) fn phase(&mut self, name: &str) {
+        if let Some((timing, last)) = &mut self.timing {
+            let now = Instant::now();
+            timing.record(name, now - *last);
+            *last = now;
+        }
+    }
+
+    /// Run a handler future, recording extractors, handler and serialization
+    pub(crate) async fn finish<Fut>(mut self, handler: Fut) -> Response
+    where
+        Fut: Future,
+        Fut::Output: IntoResponse,
+    {
+        self.phase("extractors");
+        let output = handler.await;
+        self.phase("handler");
+        let response = output.into_response();
+        self.phase("serialization");
+        response
+    }
+}
+
+/// Middleware adding a `Server-Timing` header to responses
+///
+/// Enabled by default unless the environment is production
+/// (`RUSTAPI_ENV=production` or a production [`Profile`](crate::Profile)).
+///
+/// # Example
+///
+/// ```rust,ignore
+/// RustApi::new()
+///     .layer(ServerTimingLayer::new())
+///     .route("/pins", get(list_pins))
+/// ```
+#[derive(Clone, Default)]
+pub struct ServerTimingLayer {
+    enabled: Option<bool>,
+}
+
+impl ServerTimingLayer {
+    /// Create a layer that is enabled outside production
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Enable or disable the header regardless of the environment
+    pub fn enabled(mut self, enabled: bool) -> Self {
+        self.enabled = Some(enabled);
+        self
+    }
+
+    fn is_enabled(&self) -> bool {
+        self.enabled
+            .unwrap_or_else(|| crate::error::get_environment() != Environment::Production)
+    }
+}
+
+impl MiddlewareLayer for ServerTimingLayer {
+    fn call(
+        &self,
+        mut req: Request,
+        next: BoxedNext,
+    ) -> Pin<Box<dyn Future<Output = Response> + Send + 'static>> {
+        if !self.is_enabled() {
+            return Box::pin(async move { next(req).await });
+        }
+
+        let timing = ServerTiming::default();
+        req.extensions_mut().insert(timing.clone());
+        Box::pin(async move {
+            let started = Instant::now();
+            let mut response = next(req).await;
+            timing.record("total", started.elapsed());
+
+            if let Ok(value) = http::HeaderValue::from_str(&timing.header_value()) {
+                response.headers_mut().append(
+                    http::header::HeaderName::from_static("server-timing"),
+                    value,
+                );
+            }
+            response
+        })
+    }
+
+    fn clone_box(&self) -> Box<dyn MiddlewareLayer> {
+        Box::new(self.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::middleware::layer::LayerStack;
+    use bytes::Bytes;
+
+    #[test]
+    fn header_lists_metrics_in_order() {
+        let timing = ServerTiming::default();
+        timing.record("routing", Duration::from_micros(12));
+        timing.record_with_description("db query", "load \"pins\"", Duration::from_millis(8));
+        assert_eq!(
+            timing.header_value(),
+            "routing;dur=0.012, db_query;desc=\"load 'pins'\";dur=8.000"
+        );
+    }
+
+    #[tokio::test]
+    async fn layer_adds_header_with_handler_spans() {
+        let mut stack = LayerStack::new();
+        stack.push(Box::new(ServerTimingLayer::new().enabled(true)));
+
+        let next: BoxedNext = Arc::new(|req: Request| {
+            Box::pin(async move {
+                let timing = ServerTiming::from_request_parts(&req).unwrap();
+                drop(timing.start("cache").description("miss"));
+                "ok".into_response()
+            }) as Pin<Box<dyn Future<Output = Response> + Send + 'static>>
+        });
+        let req = http::Request::builder().uri("/").body(()).unwrap();
+        let response = stack
+            .execute(Request::from_http_request(req, Bytes::new()), next)
+            .await;
+
+        let header = response.headers()["server-timing"].to_str().unwrap();
+        assert!(header.starts_with("cache;desc=\"miss\";dur="), "{header}");
+        assert!(header.contains(", total;dur="), "{header}");
+    }
+
+    #[tokio::test]
+    async fn disabled_layer_adds_nothing() {
+        let mut stack = LayerStack::new();
+        stack.push(Box::new(ServerTimingLayer::new().enabled(false)));
+
+        let next: BoxedNext = Arc::new(|req: Request| {
+            Box::pin(async move {
+                assert!(ServerTiming::of(&req).is_none());
+                "ok".into_response()
+            }) as Pin<Box<dyn Future<Output = Response> + Send + 'static>>
+        });
+        let req = http::Request::builder().uri("/").body(()).unwrap();
+        let response = stack
+            .execute(Request::from_http_request(req, Bytes::new()), next)
+            .await;
+        assert!(!response.headers().contains_key("server-timing"));
+    }
+}
@@ -1,9 +1,11 @@
 //! Enhanced Tracing middleware
 //!
 //! Logs request method, path, request_id, status code, and duration for each request.
-//! Supports custom fields that are included in all request spans.
+//! Supports custom fields that are included in all request spans, and
+//! [`LogSampling`] of the completion events.
 
 use super::layer::{BoxedNext, MiddlewareLayer};
+use super::log_sampling::{LogDecision, LogSampling};
 use super::request_id::RequestId;
 use crate::request::Request;
 use crate::response::Response;
@@ -20,8 +22,14 @@ use tracing::{info_span, Instrument, Level};
 /// - Request ID (if RequestIdLayer is applied)
 /// - Response status code
 /// - Request duration
+/// - Tenant (if a `TenantLayer` inside this layer resolved one)
 /// - Any custom fields configured via `with_field()`
 ///
+/// With [`sampling`](Self::sampling), only a share of the completion events is
+/// logged. Requests over the slow threshold are always logged as `Slow request`
+/// at WARN, and their span is promoted to full detail: it also records the
+/// query string, user agent, body sizes and `slow = true`.
+///
 /// # Example
 ///
 /// ```rust,ignore
@@ -37,15 +45,13 @@ use tracing::{info_span, Instrument, Level};
 pub struct TracingLayer {
     level: Level,
     custom_fields: Vec<(String, String)>,
+    sampling: LogSampling,
 }
 
 impl TracingLayer {
     /// Create a new TracingLayer with default INFO level
     pub fn new() -> Self {
-        Self {
-            level: Level::INFO,
-            custom_fields: Vec::new(),
-        }
+        Self::with_level(Level::INFO)
     }
 
     /// Create a TracingLayer with a specific log level
@@ -53,6 +59,7 @@ impl TracingLayer {
         Self {
             level,
             custom_fields: Vec::new(),
+            sampling: LogSampling::new(),
         }
     }
 
@@ -71,6 +78,15 @@ impl TracingLayer {
         self.custom_fields.push((key.into(), value.into()));
         self
     }
+
+    /// Sample the completion events and promote slow requests
+    ///
+    /// The span is created for every request either way, so logs emitted by
+    /// handlers keep their request context.
+    pub fn sampling(mut self, sampling: LogSampling) -> Self {
+        self.sampling = sampling;
+        self
+    }
 }
 
 impl Default for TracingLayer {
@@ -79,6 +95,29 @@ impl Default for TracingLayer {
     }
 }
 
+/// Request details recorded on slow requests, taken before the request moves on
+struct RequestDetail {
+    query: Option<String>,
+    user_agent: Option<String>,
+    request_bytes: Option<u64>,
+}
+
+impl RequestDetail {
+    fn of(req: &Request) -> Self {
+        let header = |name: http::header::HeaderName| {
+            req.headers()
+                .get(name)
+                .and_then(|value| value.to_str().ok())
+                .map(str::to_string)
+        };
+        Self {
+            query: req.uri().query().map(str::to_string),
+            user_agent: header(http::header::USER_AGENT),
+            request_bytes: header(http::header::CONTENT_LENGTH).and_then(|len| len.parse().ok()),
+        }
+    }
+}
+
 impl MiddlewareLayer for TracingLayer {
     fn call(
         &self,
@@ -89,6 +128,8 @@ impl MiddlewareLayer for TracingLayer {
         let method = req.method().to_string();
         let path = req.uri().path().to_string();
         let custom_fields = self.custom_fields.clone();
+        let sampling = self.sampling.clone();
+        let detail = RequestDetail::of(&req);
 
         // Extract request_id if available
         let request_id = req
@@ -110,6 +151,12 @@ impl MiddlewareLayer for TracingLayer {
                 status = tracing::field::Empty,
                 duration_ms = tracing::field::Empty,
                 error = tracing::field::Empty,
+                tenant = tracing::field::Empty,
+                slow = tracing::field::Empty,
+                query = tracing::field::Empty,
+                user_agent = tracing::field::Empty,
+                request_bytes = tracing::field::Empty,
+                response_bytes = tracing::field::Empty,
             );
 
             // Record custom fields in the span
@@ -125,6 +172,9 @@ impl MiddlewareLayer for TracingLayer {
             let status_code = status.as_u16();
 
             // Record response fields
+            if let Some(tenant) = response.extensions().get::<crate::tenant::Tenant>() {
+                span.record("tenant", tenant.id());
+            }
             span.record("status", status_code);
             span.record("duration_ms", duration.as_millis() as u64);
 
@@ -133,9 +183,38 @@ impl MiddlewareLayer for TracingLayer {
                 span.record("error", true);
             }
 
-            // Log based on status code and configured level
+            let decision = sampling.decide(status, duration);
             let _enter = span.enter();
-            if status.is_success() {
+            if decision == LogDecision::Slow {
+                let response_bytes = response
+                    .headers()
+                    .get(http::header::CONTENT_LENGTH)
+                    .and_then(|value| value.to_str().ok())
+                    .and_then(|value| value.parse::<u64>().ok());
+                span.record("slow", true);
+                span.record("query", detail.query.as_deref().unwrap_or(""));
+                span.record("user_agent", detail.user_agent.as_deref().unwrap_or(""));
+                if let Some(bytes) = detail.request_bytes {
+                    span.record("request_bytes", bytes);
+                }
+                if let Some(bytes) = response_bytes {
+                    span.record("response_bytes", bytes);
+                }
+                tracing::warn!(
+                    method = %method,
+                    path = %path,
+                    request_id = %request_id,
+                    status = %status_code,
+                    duration_ms = %duration.as_millis(),
+                    threshold_ms = sampling.threshold().map(|t| t.as_millis() as u64),
+                    query = detail.query.as_deref(),
+                    user_agent = detail.user_agent.as_deref(),
+                    request_bytes = detail.request_bytes,
+                    response_bytes,
+                    slow = true,
+                    "Slow request"
+                );
+            } else if decision.should_log() && status.is_success() {
                 match level {
                     Level::TRACE => tracing::trace!(
                         method = %method,
@@ -178,7 +257,7 @@ impl MiddlewareLayer for TracingLayer {
                         "Request completed"
                     ),
                 }
-            } else {
+            } else if decision.should_log() {
                 tracing::warn!(
                     method = %method,
                     path = %path,
@@ -574,4 +653,40 @@ mod tests {
             );
         });
     }
+
+    #[test]
+    fn test_tracing_layer_promotes_slow_requests() {
+        let rt = tokio::runtime::Runtime::new().unwrap();
+        rt.block_on(async {
+            let capture = SpanFieldCapture::new();
+            let subscriber = tracing_subscriber::registry().with(capture.clone());
+            let _guard = tracing::subscriber::set_default(subscriber);
+
+            let sampling = LogSampling::new()
+                .success_rate(0.0)
+                .slow_threshold(std::time::Duration::ZERO);
+            let mut stack = LayerStack::new();
+            stack.push(Box::new(TracingLayer::new().sampling(sampling)));
+
+            let handler: BoxedNext = Arc::new(|_req: crate::request::Request| {
+                Box::pin(async {
+                    http::Response::builder()
+                        .status(StatusCode::OK)
+                        .body(crate::response::Body::from("ok"))
+                        .unwrap()
+                }) as Pin<Box<dyn Future<Output = Response> + Send + 'static>>
+            });
+
+            let request = create_test_request(Method::GET, "/reports?year=2024");
+            stack.execute(request, handler).await;
+
+            let spans = capture.get_spans();
+            let span = spans.iter().find(|s| s.name == "http_request").unwrap();
+            assert_eq!(span.fields.get("slow").map(String::as_str), Some("true"));
+            assert_eq!(
+                span.fields.get("query").map(String::as_str),
+                Some("year=2024")
+            );
+        });
+    }
 }
@@ -27,8 +27,12 @@ use crate::stream::StreamingBody;
 use bytes::Bytes;
 use futures_util::stream;
 use http::StatusCode;
+use std::collections::{HashMap, HashSet};
 use std::error::Error as _;
+use std::future::Future;
 use std::path::Path;
+use std::pin::Pin;
+use std::sync::Arc;
 use tokio::io::AsyncWriteExt;
 
 /// Maximum file size (default: 10MB)
@@ -37,6 +41,9 @@ pub const DEFAULT_MAX_FILE_SIZE: usize = 10 * 1024 * 1024;
 /// Maximum number of fields in multipart form (default: 100)
 pub const DEFAULT_MAX_FIELDS: usize = 100;
 
+/// Bytes buffered from the start of a file before sniffing its type
+const SNIFF_LEN: usize = 16;
+
 /// Multipart form data extractor
 ///
 /// Parses `multipart/form-data` requests, commonly used for file uploads.
@@ -96,10 +103,16 @@ impl Multipart {
 /// parsing. It consumes the request body as a stream and yields one field at a time.
 ///
 /// If a [`MultipartConfig`] is present in app state, its size and content-type limits are applied.
+///
+/// Per-field [`FieldRule`]s are enforced as fields are read: size limits and
+/// sniffed file types while a field is consumed, required fields once the
+/// form ends. [`MultipartConfig::on_file`] hooks only run for the buffered
+/// [`Multipart`] extractor.
 pub struct StreamingMultipart {
     inner: multer::Multipart<'static>,
     config: MultipartConfig,
     field_count: usize,
+    seen: HashSet<String>,
 }
 
 impl StreamingMultipart {
@@ -108,6 +121,7 @@ impl StreamingMultipart {
             inner: multer::Multipart::new(stream, boundary),
             config,
             field_count: 0,
+            seen: HashSet::new(),
         }
     }
 
@@ -117,6 +131,7 @@ impl StreamingMultipart {
     pub async fn next_field(&mut self) -> Result<Option<StreamingMultipartField<'static>>> {
         let field = self.inner.next_field().await.map_err(map_multer_error)?;
         let Some(field) = field else {
+            self.config.check_required(&self.seen)?;
             return Ok(None);
         };
 
@@ -130,10 +145,22 @@ impl StreamingMultipart {
 
         validate_streaming_field(&field, &self.config)?;
 
-        Ok(Some(StreamingMultipartField::new(
-            field,
-            self.config.max_file_size,
-        )))
+        let rule = field.name().and_then(|name| self.config.fields.get(name));
+        let max_size = rule
+            .and_then(|rule| rule.max_size)
+            .map_or(self.config.max_file_size, |max| {
+                max.min(self.config.max_file_size)
+            });
+        let sniff = rule
+            .filter(|rule| field.file_name().is_some() && !rule.allowed_types.is_empty())
+            .map(|rule| rule.allowed_types.clone());
+        if let Some(name) = field.name() {
+            self.seen.insert(name.to_string());
+        }
+
+        let mut field = StreamingMultipartField::new(field, max_size);
+        field.sniff = sniff;
+        Ok(Some(field))
     }
 
     /// Number of fields yielded so far.
@@ -179,6 +206,8 @@ pub struct StreamingMultipartField<'a> {
     inner: multer::Field<'a>,
     max_file_size: usize,
     bytes_read: usize,
+    /// Allowed types still to be checked against the first bytes
+    sniff: Option<Vec<String>>,
 }
 
 impl<'a> StreamingMultipartField<'a> {
@@ -187,7 +216,22 @@ impl<'a> StreamingMultipartField<'a> {
             inner,
             max_file_size,
             bytes_read: 0,
+            sniff: None,
+        }
+    }
+
+    async fn next_raw_chunk(&mut self) -> Result<Option<Bytes>> {
+        let chunk = self.inner.chunk().await.map_err(map_multer_error)?;
+        let Some(chunk) = chunk else {
+            return Ok(None);
+        };
+
+        self.bytes_read += chunk.len();
+        if self.bytes_read > self.max_file_size {
+            return Err(file_size_limit_error(self.max_file_size));
         }
+
+        Ok(Some(chunk))
     }
 
     /// Get the field name.
@@ -217,17 +261,26 @@ impl<'a> StreamingMultipartField<'a> {
 
     /// Read the next chunk from the field stream.
     pub async fn chunk(&mut self) -> Result<Option<Bytes>> {
-        let chunk = self.inner.chunk().await.map_err(map_multer_error)?;
-        let Some(chunk) = chunk else {
-            return Ok(None);
+        let Some(allowed) = self.sniff.take() else {
+            return self.next_raw_chunk().await;
         };
 
-        self.bytes_read += chunk.len();
-        if self.bytes_read > self.max_file_size {
-            return Err(file_size_limit_error(self.max_file_size));
+        // Buffer enough of the file to recognise its signature
+        let mut head = bytes::BytesMut::new();
+        while head.len() < SNIFF_LEN {
+            match self.next_raw_chunk().await? {
+                Some(chunk) => head.extend_from_slice(&chunk),
+                None => break,
+            }
         }
+        let declared = self.content_type().map(str::to_string);
+        check_sniffed_type(self.name(), &head, declared.as_deref(), &allowed)?;
 
-        Ok(Some(chunk))
+        Ok(if head.is_empty() {
+            None
+        } else {
+            Some(head.freeze())
+        })
     }
 
     /// Collect the full field into memory.
@@ -459,6 +512,10 @@ impl FromRequest for Multipart {
         // Parse multipart
         let fields = parse_multipart(&body, &boundary)?;
 
+        if let Some(config) = req.state().get::<MultipartConfig>() {
+            config.check_fields(&fields).await?;
+        }
+
         Ok(Multipart::new(fields))
     }
 }
@@ -528,10 +585,122 @@ fn validate_streaming_field(field: &multer::Field<'_>, config: &MultipartConfig)
     )))
 }
 
+/// Detect a file's type from its leading bytes
+///
+/// Covers common binary formats only; text formats have no signature and
+/// yield `None`.
+pub fn sniff_content_type(data: &[u8]) -> Option<&'static str> {
+    const SIGNATURES: &[(&[u8], &str)] = &[
+        (b"\x89PNG\r\n\x1a\n", "image/png"),
+        (b"\xff\xd8\xff", "image/jpeg"),
+        (b"GIF87a", "image/gif"),
+        (b"GIF89a", "image/gif"),
+        (b"II*\x00", "image/tiff"),
+        (b"MM\x00*", "image/tiff"),
+        (b"\x00\x00\x01\x00", "image/x-icon"),
+        (b"%PDF-", "application/pdf"),
+        (b"PK\x03\x04", "application/zip"),
+        (b"\x1f\x8b", "application/gzip"),
+        (b"\x28\xb5\x2f\xfd", "application/zstd"),
+        (b"7z\xbc\xaf\x27\x1c", "application/x-7z-compressed"),
+        (b"\x00asm", "application/wasm"),
+        (b"\x1a\x45\xdf\xa3", "video/webm"),
+        (b"ID3", "audio/mpeg"),
+        (b"OggS", "audio/ogg"),
+        (b"fLaC", "audio/flac"),
+        (b"wOFF", "font/woff"),
+        (b"wOF2", "font/woff2"),
+    ];
+
+    if let Some((_, mime)) = SIGNATURES.iter().find(|(sig, _)| data.starts_with(sig)) {
+        return Some(mime);
+    }
+    if data.len() >= 12 && data.starts_with(b"RIFF") {
+        return match &data[8..12] {
+            b"WEBP" => Some("image/webp"),
+            b"WAVE" => Some("audio/wav"),
+            b"AVI " => Some("video/x-msvideo"),
+            _ => None,
+        };
+    }
+    if data.len() >= 12 && &data[4..8] == b"ftyp" {
+        return match &data[8..12] {
+            b"avif" | b"avis" => Some("image/avif"),
+            b"heic" | b"heix" | b"mif1" => Some("image/heic"),
+            b"qt  " => Some("video/quicktime"),
+            _ => Some("video/mp4"),
+        };
+    }
+    None
+}
+
+/// Whether `mime` matches an allow-list entry (`image/png` or `image/*`)
+fn mime_allowed(allowed: &[String], mime: &str) -> bool {
+    allowed.iter().any(|entry| {
+        entry.eq_ignore_ascii_case(mime)
+            || entry.strip_suffix("/*").is_some_and(|prefix| {
+                mime.split_once('/')
+                    .is_some_and(|(top, _)| top.eq_ignore_ascii_case(prefix))
+            })
+    })
+}
+
+/// Check a file's sniffed type (or, for unrecognised content, its declared
+/// type) against the allowed types
+///
+/// A declared type that has a signature must be backed by one, so a text
+/// file sent as `image/png` is rejected.
+fn check_sniffed_type(
+    name: Option<&str>,
+    head: &[u8],
+    declared: Option<&str>,
+    allowed: &[String],
+) -> Result<()> {
+    let name = name.unwrap_or("file");
+    let actual = match sniff_content_type(head) {
+        Some(sniffed) => sniffed,
+        None => match declared {
+            Some(declared) if !is_sniffable(declared) => declared,
+            _ => {
+                return Err(ApiError::bad_request(format!(
+                    "Could not determine the type of field '{}'",
+                    name
+                )))
+            }
+        },
+    };
+
+    if mime_allowed(allowed, actual) {
+        return Ok(());
+    }
+    Err(ApiError::bad_request(format!(
+        "Unsupported content type '{}' for field '{}'",
+        actual, name
+    )))
+}
+
+/// Whether files of this type carry a recognised signature
+fn is_sniffable(mime: &str) -> bool {
+    let mime = mime.trim().to_ascii_lowercase();
+    mime.starts_with("image/")
+        || mime.starts_with("audio/")
+        || mime.starts_with("video/")
+        || mime.starts_with("font/")
+        || matches!(
+            mime.as_str(),
+            "application/pdf"
+                | "application/zip"
+                | "application/gzip"
+                | "application/zstd"
+                | "application/x-7z-compressed"
+                | "application/wasm"
+        )
+}
+
 fn file_size_limit_error(limit: usize) -> ApiError {
     ApiError::new(
         StatusCode::PAYLOAD_TOO_LARGE,
-        "payload_too_large",
+        crate::error::codes::PAYLOAD_TOO_LARGE,
         format!("Multipart field exceeded limit of {} bytes", limit),
     )
 }
@@ -545,7 +714,11 @@ fn map_multer_error(error: multer::Error) -> ApiError {
 
     let message = error.to_string();
     if message.to_ascii_lowercase().contains("size limit") {
-        return ApiError::new(StatusCode::PAYLOAD_TOO_LARGE, "payload_too_large", message);
+        return ApiError::new(
+            StatusCode::PAYLOAD_TOO_LARGE,
+            crate::error::codes::PAYLOAD_TOO_LARGE,
+            message,
+        );
     }
 
     ApiError::bad_request(format!("Invalid multipart body: {}", message))
@@ -705,6 +878,10 @@ pub struct MultipartConfig {
     pub max_file_size: usize,
     /// Allowed content types for files (empty = all allowed)
     pub allowed_content_types: Vec<String>,
+    /// Per-field rules, keyed by field name
+    pub fields: HashMap<String, FieldRule>,
+    /// Hooks run on every completed file before the handler sees it
+    pub file_hooks: Vec<FileHook>,
 }
 
 impl Default for MultipartConfig {
@@ -714,10 +891,66 @@ impl Default for MultipartConfig {
             max_fields: DEFAULT_MAX_FIELDS,
             max_file_size: DEFAULT_MAX_FILE_SIZE,
             allowed_content_types: Vec::new(),
+            fields: HashMap::new(),
+            file_hooks: Vec::new(),
         }
     }
 }
 
+/// Async check run on a completed file upload, e.g. a virus scan
+///
+/// Returning an error rejects the whole request with that error.
+pub type FileHook =
+    Arc<dyn Fn(MultipartField) -> Pin<Box<dyn Future<Output = Result<()>> + Send>> + Send + Sync>;
+
+/// Constraints for a single named multipart field
+///
+/// # Example
+///
+/// ```rust,ignore
+/// use rustapi_core::multipart::{FieldRule, MultipartConfig};
+///
+/// let config = MultipartConfig::new()
+///     .field("avatar", FieldRule::new().required().max_size(2 * 1024 * 1024).allow_type("image/*"))
+///     .field("title", FieldRule::new().required().max_size(200));
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct FieldRule {
+    /// Reject the form when the field is missing
+    pub required: bool,
+    /// Maximum size of the field in bytes
+    pub max_size: Option<usize>,
+    /// Allowed file types, checked against the file's magic bytes
+    /// (`image/png` or `image/*`; empty = all allowed)
+    pub allowed_types: Vec<String>,
+}
+
+impl FieldRule {
+    /// Create an optional field rule without constraints
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Require the field to be present
+    pub fn required(mut self) -> Self {
+        self.required = true;
+        self
+    }
+
+    /// Limit the field size in bytes
+    pub fn max_size(mut self, size: usize) -> Self {
+        self.max_size = Some(size);
+        self
+    }
+
+    /// Allow a file type, detected from the content rather than the
+    /// client-supplied `Content-Type`
+    pub fn allow_type(mut self, mime: impl Into<String>) -> Self {
+        self.allowed_types.push(mime.into());
+        self
+    }
+}
+
 impl MultipartConfig {
     /// Create a new multipart config with default values
     pub fn new() -> Self {
@@ -753,6 +986,81 @@ impl MultipartConfig {
         self.allowed_content_types.push(content_type.into());
         self
     }
+
+    /// Set the rule for a named field
+    pub fn field(mut self, name: impl Into<String>, rule: FieldRule) -> Self {
+        self.fields.insert(name.into(), rule);
+        self
+    }
+
+    /// Run an async check on every completed file upload
+    ///
+    /// Hooks run in registration order, after the field rules pass and before
+    /// the handler runs. Only the buffered [`Multipart`] extractor runs them.
+    ///
+    /// ```rust,ignore
+    /// MultipartConfig::new().on_file(|file| async move {
+    ///     scanner.scan(&file.bytes().await?).await.map_err(|_| ApiError::bad_request("Infected upload"))
+    /// })
+    /// ```
+    pub fn on_file<F, Fut>(mut self, hook: F) -> Self
+    where
+        F: Fn(MultipartField) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<()>> + Send + 'static,
+    {
+        self.file_hooks
+            .push(Arc::new(move |field| Box::pin(hook(field))));
+        self
+    }
+
+    /// Reject the form if a required field was not seen
+    fn check_required(&self, seen: &HashSet<String>) -> Result<()> {
+        let mut missing: Vec<&str> = self
+            .fields
+            .iter()
+            .filter(|(name, rule)| rule.required && !seen.contains(name.as_str()))
+            .map(|(name, _)| name.as_str())
+            .collect();
+        if missing.is_empty() {
+            return Ok(());
+        }
+        missing.sort_unstable();
+        Err(ApiError::bad_request(format!(
+            "Missing required multipart field(s): {}",
+            missing.join(", ")
+        )))
+    }
+
+    /// Apply the field rules and file hooks to a buffered form
+    async fn check_fields(&self, fields: &[MultipartField]) -> Result<()> {
+        let mut seen = HashSet::new();
+        for field in fields {
+            let Some(name) = field.name() else {
+                continue;
+            };
+            seen.insert(name.to_string());
+            let Some(rule) = self.fields.get(name) else {
+                continue;
+            };
+            if let Some(max) = rule.max_size {
+                if field.size() > max {
+                    return Err(file_size_limit_error(max));
+                }
+            }
+            if field.is_file() && !rule.allowed_types.is_empty() {
+                let head = &field.data[..field.data.len().min(SNIFF_LEN)];
+                check_sniffed_type(Some(name), head, field.content_type(), &rule.allowed_types)?;
+            }
+        }
+        self.check_required(&seen)?;
+
+        for field in fields.iter().filter(|field| field.is_file()) {
+            for hook in &self.file_hooks {
+                hook(field.clone()).await?;
+            }
+        }
+        Ok(())
+    }
 }
 
 /// File data wrapper for convenient access to uploaded files
@@ -1057,4 +1365,185 @@ mod tests {
 
         tokio::fs::remove_dir_all(&temp_dir).await.unwrap();
     }
+
+    /// Field name, optional (filename, content type), data
+    type TestPart<'a> = (&'a str, Option<(&'a str, &'a str)>, &'a [u8]);
+
+    fn multipart_body(boundary: &str, parts: &[TestPart<'_>]) -> Bytes {
+        let mut body = Vec::new();
+        for (name, file, data) in parts {
+            body.extend_from_slice(format!("--{boundary}\r\n").as_bytes());
+            match file {
+                Some((filename, content_type)) => body.extend_from_slice(
+                    format!(
+                        "Content-Disposition: form-data; name=\"{name}\"; filename=\"{filename}\"\r\n\
+                         Content-Type: {content_type}\r\n\r\n"
+                    )
+                    .as_bytes(),
+                ),
+                None => body.extend_from_slice(
+                    format!("Content-Disposition: form-data; name=\"{name}\"\r\n\r\n").as_bytes(),
+                ),
+            }
+            body.extend_from_slice(data);
+            body.extend_from_slice(b"\r\n");
+        }
+        body.extend_from_slice(format!("--{boundary}--\r\n").as_bytes());
+        Bytes::from(body)
+    }
+
+    const PNG: &[u8] = b"\x89PNG\r\n\x1a\n\x00\x00\x00\rIHDR\x00\x00\x00\x01";
+
+    #[test]
+    fn test_sniff_content_type() {
+        assert_eq!(sniff_content_type(PNG), Some("image/png"));
+        assert_eq!(sniff_content_type(b"\xff\xd8\xff\xe0"), Some("image/jpeg"));
+        assert_eq!(sniff_content_type(b"%PDF-1.7"), Some("application/pdf"));
+        assert_eq!(
+            sniff_content_type(b"RIFF\x00\x00\x00\x00WEBPVP8 "),
+            Some("image/webp")
+        );
+        assert_eq!(
+            sniff_content_type(b"\x00\x00\x00\x1cftypavif"),
+            Some("image/avif")
+        );
+        assert_eq!(sniff_content_type(b"hello world"), None);
+
+        let images = vec!["image/*".to_string()];
+        assert!(mime_allowed(&images, "image/png"));
+        assert!(!mime_allowed(&images, "application/pdf"));
+    }
+
+    #[tokio::test]
+    async fn streaming_multipart_sniffs_file_types() {
+        let boundary = "----RustApiBoundary";
+        let config = MultipartConfig::new().field(
+            "avatar",
+            FieldRule::new()
+                .allow_type("image/png")
+                .allow_type("text/plain"),
+        );
+
+        // Real PNG bytes, delivered in 7-byte chunks
+        let body = multipart_body(boundary, &[("avatar", Some(("a.png", "image/png")), PNG)]);
+        let mut multipart = streaming_multipart_from_body(body, boundary, config.clone());
+        let mut field = multipart.next_field().await.unwrap().unwrap();
+        assert_eq!(field.bytes().await.unwrap(), PNG);
+
+        // Text claiming to be a PNG
+        let body = multipart_body(
+            boundary,
+            &[(
+                "avatar",
+                Some(("a.png", "image/png")),
+                b"<?php system($_GET['c']);",
+            )],
+        );
+        let mut multipart = streaming_multipart_from_body(body, boundary, config.clone());
+        let mut field = multipart.next_field().await.unwrap().unwrap();
+        let error = field.bytes().await.unwrap_err();
+        assert!(error.message.contains("Could not determine the type"));
+
+        // A PDF declared as text
+        let body = multipart_body(
+            boundary,
+            &[("avatar", Some(("a.txt", "text/plain")), b"%PDF-1.7 ...")],
+        );
+        let mut multipart = streaming_multipart_from_body(body, boundary, config.clone());
+        let mut field = multipart.next_field().await.unwrap().unwrap();
+        let error = field.bytes().await.unwrap_err();
+        assert!(error
+            .message
+            .contains("Unsupported content type 'application/pdf'"));
+
+        // Plain text has no signature and falls back to the declared type
+        let body = multipart_body(
+            boundary,
+            &[("avatar", Some(("a.txt", "text/plain")), b"just some notes")],
+        );
+        let mut multipart = streaming_multipart_from_body(body, boundary, config);
+        let mut field = multipart.next_field().await.unwrap().unwrap();
+        assert_eq!(field.text().await.unwrap(), "just some notes");
+    }
+
+    #[tokio::test]
+    async fn streaming_multipart_enforces_field_rules() {
+        let boundary = "----RustApiBoundary";
+        let config = MultipartConfig::new()
+            .field("title", FieldRule::new().required().max_size(5))
+            .field("file", FieldRule::new().required());
+
+        let body = multipart_body(boundary, &[("title", None, b"too long")]);
+        let mut multipart = streaming_multipart_from_body(body, boundary, config.clone());
+        let mut title = multipart.next_field().await.unwrap().unwrap();
+        let error = title.text().await.unwrap_err();
+        assert_eq!(error.status, StatusCode::PAYLOAD_TOO_LARGE);
+
+        let body = multipart_body(boundary, &[("title", None, b"ok")]);
+        let mut multipart = streaming_multipart_from_body(body, boundary, config);
+        let mut title = multipart.next_field().await.unwrap().unwrap();
+        assert_eq!(title.text().await.unwrap(), "ok");
+        drop(title);
+        let error = multipart.next_field().await.err().unwrap();
+        assert_eq!(error.status, StatusCode::BAD_REQUEST);
+        assert!(error
+            .message
+            .contains("Missing required multipart field(s): file"));
+    }
+
+    #[tokio::test]
+    async fn buffered_multipart_runs_rules_and_file_hooks() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        let boundary = "----RustApiBoundary";
+        let scanned = Arc::new(AtomicUsize::new(0));
+        let counter = scanned.clone();
+        let config = MultipartConfig::new()
+            .field("avatar", FieldRule::new().required().allow_type("image/*"))
+            .on_file(move |file| {
+                let counter = counter.clone();
+                async move {
+                    counter.fetch_add(1, Ordering::SeqCst);
+                    if file.bytes().await?.windows(5).any(|w| w == b"EICAR") {
+                        return Err(ApiError::bad_request("Upload failed the virus scan"));
+                    }
+                    Ok(())
+                }
+            });
+
+        let fields = parse_multipart(
+            &multipart_body(
+                boundary,
+                &[
+                    ("note", None, b"hi"),
+                    ("avatar", Some(("a.png", "image/png")), PNG),
+                ],
+            ),
+            boundary,
+        )
+        .unwrap();
+        config.check_fields(&fields).await.unwrap();
+        assert_eq!(scanned.load(Ordering::SeqCst), 1);
+
+        let mut infected = PNG.to_vec();
+        infected.extend_from_slice(b"EICAR");
+        let fields = parse_multipart(
+            &multipart_body(
+                boundary,
+                &[("avatar", Some(("a.png", "image/png")), &infected)],
+            ),
+            boundary,
+        )
+        .unwrap();
+        let error = config.check_fields(&fields).await.unwrap_err();
+        assert!(error.message.contains("virus scan"));
+
+        let fields = parse_multipart(
+            &multipart_body(boundary, &[("note", None, b"hi")]),
+            boundary,
+        )
+        .unwrap();
+        assert!(config.check_fields(&fields).await.is_err());
+        assert_eq!(scanned.load(Ordering::SeqCst), 2);
+    }
 }
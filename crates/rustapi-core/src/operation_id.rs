@@ -0,0 +1,270 @@
+//! OpenAPI operation ids
+//!
+//! Client generators name their methods after `operationId`, so ids should be
+//! stable and meaningful. By default routes have no id unless one is set with
+//! `#[rustapi::operation_id("listPins")]` or [`Route::operation_id`]. With
+//! [`RustApi::operation_ids`] every other documented operation gets one from
+//! an [`OperationIdStrategy`]:
+//!
+//! ```rust,ignore
+//! use rustapi_rs::prelude::*;
+//! use rustapi_rs::OperationIdStrategy;
+//!
+//! RustApi::config()
+//!     .operation_ids(OperationIdStrategy::HandlerName)
+//!     .run("0.0.0.0:8080")
+//!     .await
+//! ```
+//!
+//! Generated ids are unique: when an id is already used, the
+//! `METHOD_path` id is used instead.
+//!
+//! [`Route::operation_id`]: crate::Route::operation_id
+//! [`RustApi::operation_ids`]: crate::RustApi::operation_ids
+
+use std::fmt;
+use std::sync::Arc;
+
+/// The route an operation id is generated for
+#[derive(Debug, Clone, Copy)]
+pub struct OperationIdContext<'a> {
+    /// HTTP method (e.g. `GET`)
+    pub method: &'a str,
+    /// Path pattern (e.g. `/pins/{id}`)
+    pub path: &'a str,
+    /// Type name of the handler, if known
+    pub handler: Option<&'a str>,
+}
+
+impl OperationIdContext<'_> {
+    /// Name of the handler function (`list_pins`)
+    ///
+    /// `None` for closures and routes without a handler name.
+    pub fn handler_name(&self) -> Option<&str> {
+        let name = self.handler?.rsplit("::").next()?;
+        let is_ident = !name.is_empty()
+            && !name.starts_with(|c: char| c.is_ascii_digit())
+            && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+        is_ident.then_some(name)
+    }
+
+    /// Method and path joined by underscores (`GET_pins_id`)
+    pub fn method_path(&self) -> String {
+        let mut id = self.method.to_uppercase();
+        for segment in self.path.split('/') {
+            let segment: String = segment
+                .chars()
+                .filter(|c| !matches!(c, '{' | '}' | '*'))
+                .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+                .collect();
+            if !segment.is_empty() {
+                id.push('_');
+                id.push_str(&segment);
+            }
+        }
+        id
+    }
+}
+
+type OperationIdFn = Arc<dyn Fn(&OperationIdContext<'_>) -> String + Send + Sync>;
+
+/// How operation ids are generated for routes without an explicit one
+#[derive(Clone)]
+pub enum OperationIdStrategy {
+    /// Name of the handler function (`list_pins`)
+    ///
+    /// Closures fall back to [`MethodPath`](Self::MethodPath).
+    HandlerName,
+    /// Method and path (`GET_pins_id`)
+    MethodPath,
+    /// A custom function, see [`OperationIdStrategy::custom`]
+    Custom(OperationIdFn),
+}
+
+impl OperationIdStrategy {
+    /// Generate ids with a function
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// // listPins, getPin, ...
+    /// OperationIdStrategy::custom(|route| {
+    ///     let name = route.handler_name().map(str::to_string).unwrap_or_else(|| route.method_path());
+    ///     to_lower_camel_case(&name)
+    /// })
+    /// ```
+    pub fn custom<F>(f: F) -> Self
+    where
+        F: Fn(&OperationIdContext<'_>) -> String + Send + Sync + 'static,
+    {
+        Self::Custom(Arc::new(f))
+    }
+
+    /// The id for a route
+    pub fn generate(&self, route: &OperationIdContext<'_>) -> String {
+        match self {
+            Self::HandlerName => route
+                .handler_name()
+                .map(str::to_string)
+                .unwrap_or_else(|| route.method_path()),
+            Self::MethodPath => route.method_path(),
+            Self::Custom(f) => f(route),
+        }
+    }
+}
+
+impl fmt::Debug for OperationIdStrategy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::HandlerName => f.write_str("HandlerName"),
+            Self::MethodPath => f.write_str("MethodPath"),
+            Self::Custom(_) => f.write_str("Custom(..)"),
+        }
+    }
+}
+
+/// Give the operation at `path` and `method` an id, unless it has one
+pub(crate) fn assign(
+    spec: &mut rustapi_openapi::OpenApiSpec,
+    strategy: &OperationIdStrategy,
+    route: &OperationIdContext<'_>,
+) {
+    let has_id = spec
+        .paths
+        .get(route.path)
+        .and_then(|item| operation(item, route.method))
+        .map(|op| op.operation_id.is_some());
+    if has_id != Some(false) {
+        return;
+    }
+
+    let mut id = strategy.generate(route);
+    if is_taken(spec, &id) {
+        id = route.method_path();
+    }
+    if let Some(op) = spec
+        .paths
+        .get_mut(route.path)
+        .and_then(|item| operation_mut(item, route.method))
+    {
+        op.operation_id = Some(id);
+    }
+}
+
+fn is_taken(spec: &rustapi_openapi::OpenApiSpec, id: &str) -> bool {
+    spec.paths.values().any(|item| {
+        [
+            &item.get,
+            &item.put,
+            &item.post,
+            &item.delete,
+            &item.options,
+            &item.head,
+            &item.patch,
+            &item.trace,
+        ]
+        .into_iter()
+        .flatten()
+        .any(|op| op.operation_id.as_deref() == Some(id))
+    })
+}
+
+fn operation<'a>(
+    item: &'a rustapi_openapi::PathItem,
+    method: &str,
+) -> Option<&'a rustapi_openapi::Operation> {
+    match method {
+        "GET" => item.get.as_ref(),
+        "POST" => item.post.as_ref(),
+        "PUT" => item.put.as_ref(),
+        "PATCH" => item.patch.as_ref(),
+        "DELETE" => item.delete.as_ref(),
+        "HEAD" => item.head.as_ref(),
+        "OPTIONS" => item.options.as_ref(),
+        "TRACE" => item.trace.as_ref(),
+        _ => None,
+    }
+}
+
+fn operation_mut<'a>(
+    item: &'a mut rustapi_openapi::PathItem,
+    method: &str,
+) -> Option<&'a mut rustapi_openapi::Operation> {
+    match method {
+        "GET" => item.get.as_mut(),
+        "POST" => item.post.as_mut(),
+        "PUT" => item.put.as_mut(),
+        "PATCH" => item.patch.as_mut(),
+        "DELETE" => item.delete.as_mut(),
+        "HEAD" => item.head.as_mut(),
+        "OPTIONS" => item.options.as_mut(),
+        "TRACE" => item.trace.as_mut(),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn route<'a>(
+        method: &'a str,
+        path: &'a str,
+        handler: Option<&'a str>,
+    ) -> OperationIdContext<'a> {
+        OperationIdContext {
+            method,
+            path,
+            handler,
+        }
+    }
+
+    #[test]
+    fn strategies_generate_ids() {
+        let list = route("GET", "/pins/{id}/tags", Some("app::pins::list_pins"));
+        assert_eq!(
+            OperationIdStrategy::HandlerName.generate(&list),
+            "list_pins"
+        );
+        assert_eq!(
+            OperationIdStrategy::MethodPath.generate(&list),
+            "GET_pins_id_tags"
+        );
+        let custom = OperationIdStrategy::custom(|r| format!("{}Op", r.method.to_lowercase()));
+        assert_eq!(custom.generate(&list), "getOp");
+
+        let closure = route("POST", "/", Some("app::main::{{closure}}"));
+        assert_eq!(OperationIdStrategy::HandlerName.generate(&closure), "POST");
+    }
+
+    #[test]
+    fn explicit_and_duplicate_ids() {
+        let op = || rustapi_openapi::Operation::new();
+        let mut spec = rustapi_openapi::OpenApiSpec::new("Test", "1.0")
+            .path("/pins", "GET", op())
+            .path("/pins", "HEAD", op())
+            .path("/other", "GET", {
+                let mut op = op();
+                op.operation_id = Some("custom".to_string());
+                op
+            });
+
+        let strategy = OperationIdStrategy::HandlerName;
+        for (method, path) in [("GET", "/pins"), ("HEAD", "/pins"), ("GET", "/other")] {
+            assign(
+                &mut spec,
+                &strategy,
+                &route(method, path, Some("list_pins")),
+            );
+        }
+
+        let id = |path: &str, method| {
+            operation(&spec.paths[path], method)
+                .and_then(|op| op.operation_id.clone())
+                .unwrap()
+        };
+        assert_eq!(id("/pins", "GET"), "list_pins");
+        assert_eq!(id("/pins", "HEAD"), "HEAD_pins");
+        assert_eq!(id("/other", "GET"), "custom");
+    }
+}
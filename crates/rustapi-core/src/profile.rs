@@ -0,0 +1,229 @@
+//! Environment-driven defaults
+//!
+//! A [`Profile`] turns an [`Environment`] into the defaults used across the
+//! stack: error masking, docs exposure, log format, CORS strictness and replay
+//! recording. Every setting can be overridden on its own.
+//!
+//! The installed profile is process-wide. [`RustApiConfig::profile`] installs
+//! one when the app is built; without it, [`Profile::current`] falls back to
+//! the defaults for [`get_environment`].
+//!
+//! | Setting                  | Development | Production |
+//! |--------------------------|-------------|------------|
+//! | Mask 5xx error details   | no          | yes        |
+//! | Expose OpenAPI docs      | yes         | no         |
+//! | Log format               | pretty      | JSON lines |
+//! | Strict CORS              | no          | yes        |
+//! | Allow replay recording   | yes         | no         |
+//!
+//! [`RustApiConfig::profile`]: crate::RustApiConfig::profile
+
+use crate::error::{get_environment, Environment};
+use std::fmt;
+use std::sync::RwLock;
+use tracing::field::{Field, Visit};
+use tracing::{Event, Subscriber};
+use tracing_subscriber::fmt::format::Writer;
+use tracing_subscriber::fmt::{FmtContext, FormatEvent, FormatFields};
+use tracing_subscriber::registry::LookupSpan;
+
+static PROFILE: RwLock<Option<Profile>> = RwLock::new(None);
+
+/// Defaults derived from an [`Environment`]
+///
+/// # Example
+///
+/// ```
+/// use rustapi_core::{Environment, Profile};
+///
+/// let profile = Profile::for_environment(Environment::Production).expose_docs(true);
+/// assert!(profile.mask_internal_errors);
+/// assert!(profile.expose_docs);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Profile {
+    /// Environment the defaults were derived from
+    pub environment: Environment,
+    /// Replace 5xx error messages with a generic one
+    pub mask_internal_errors: bool,
+    /// Serve the OpenAPI docs
+    pub expose_docs: bool,
+    /// Log JSON lines instead of human-readable output
+    pub json_logs: bool,
+    /// Use restrictive CORS defaults
+    pub strict_cors: bool,
+    /// Let replay layers record traffic
+    pub replay_recording: bool,
+}
+
+impl Default for Profile {
+    fn default() -> Self {
+        Self::for_environment(Environment::default())
+    }
+}
+
+impl Profile {
+    /// Defaults for an environment
+    pub fn for_environment(environment: Environment) -> Self {
+        let production = environment.is_production();
+        Self {
+            environment,
+            mask_internal_errors: production,
+            expose_docs: !production,
+            json_logs: production,
+            strict_cors: production,
+            replay_recording: !production,
+        }
+    }
+
+    /// The installed profile, or the defaults for [`get_environment`]
+    pub fn current() -> Self {
+        installed().unwrap_or_else(|| Self::for_environment(get_environment()))
+    }
+
+    /// Make this the process-wide profile
+    ///
+    /// Replaces any profile installed earlier. The log format is chosen when
+    /// the first [`RustApi`](crate::RustApi) is created, so install the
+    /// profile before that.
+    pub fn install(self) {
+        *PROFILE.write().unwrap_or_else(|e| e.into_inner()) = Some(self);
+    }
+
+    /// Override error masking
+    pub fn mask_internal_errors(mut self, mask: bool) -> Self {
+        self.mask_internal_errors = mask;
+        self
+    }
+
+    /// Override docs exposure
+    pub fn expose_docs(mut self, expose: bool) -> Self {
+        self.expose_docs = expose;
+        self
+    }
+
+    /// Override the log format
+    pub fn json_logs(mut self, json: bool) -> Self {
+        self.json_logs = json;
+        self
+    }
+
+    /// Override CORS strictness
+    pub fn strict_cors(mut self, strict: bool) -> Self {
+        self.strict_cors = strict;
+        self
+    }
+
+    /// Override replay recording
+    pub fn replay_recording(mut self, enabled: bool) -> Self {
+        self.replay_recording = enabled;
+        self
+    }
+}
+
+/// The explicitly installed profile, if any
+pub(crate) fn installed() -> Option<Profile> {
+    *PROFILE.read().unwrap_or_else(|e| e.into_inner())
+}
+
+/// Formats each event as one JSON object per line
+pub(crate) struct JsonLines;
+
+impl<S, N> FormatEvent<S, N> for JsonLines
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+    N: for<'a> FormatFields<'a> + 'static,
+{
+    fn format_event(
+        &self,
+        ctx: &FmtContext<'_, S, N>,
+        mut writer: Writer<'_>,
+        event: &Event<'_>,
+    ) -> fmt::Result {
+        let metadata = event.metadata();
+        let timestamp = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|elapsed| elapsed.as_millis() as u64)
+            .unwrap_or_default();
+
+        let mut line = serde_json::Map::new();
+        line.insert("timestamp_ms".into(), timestamp.into());
+        line.insert("level".into(), metadata.level().as_str().into());
+        line.insert("target".into(), metadata.target().into());
+
+        let mut fields = FieldMap(serde_json::Map::new());
+        event.record(&mut fields);
+        if let Some(message) = fields.0.remove("message") {
+            line.insert("message".into(), message);
+        }
+        if !fields.0.is_empty() {
+            line.insert("fields".into(), fields.0.into());
+        }
+
+        if let Some(scope) = ctx.event_scope() {
+            let spans: Vec<serde_json::Value> =
+                scope.from_root().map(|span| span.name().into()).collect();
+            line.insert("spans".into(), spans.into());
+        }
+
+        writeln!(writer, "{}", serde_json::Value::Object(line))
+    }
+}
+
+struct FieldMap(serde_json::Map<String, serde_json::Value>);
+
+impl Visit for FieldMap {
+    fn record_i64(&mut self, field: &Field, value: i64) {
+        self.0.insert(field.name().into(), value.into());
+    }
+
+    fn record_u64(&mut self, field: &Field, value: u64) {
+        self.0.insert(field.name().into(), value.into());
+    }
+
+    fn record_bool(&mut self, field: &Field, value: bool) {
+        self.0.insert(field.name().into(), value.into());
+    }
+
+    fn record_str(&mut self, field: &Field, value: &str) {
+        self.0.insert(field.name().into(), value.into());
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        self.0
+            .insert(field.name().into(), format!("{:?}", value).into());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn production_defaults_are_locked_down() {
+        let production = Profile::for_environment(Environment::Production);
+        assert!(production.mask_internal_errors);
+        assert!(!production.expose_docs);
+        assert!(production.json_logs);
+        assert!(production.strict_cors);
+        assert!(!production.replay_recording);
+
+        let development = Profile::for_environment(Environment::Development);
+        assert!(!development.mask_internal_errors);
+        assert!(development.expose_docs);
+        assert!(!development.json_logs);
+        assert!(!development.strict_cors);
+        assert!(development.replay_recording);
+    }
+
+    #[test]
+    fn overrides_apply_per_setting() {
+        let profile = Profile::for_environment(Environment::Production)
+            .expose_docs(true)
+            .json_logs(false);
+        assert!(profile.expose_docs);
+        assert!(!profile.json_logs);
+        assert!(profile.mask_internal_errors);
+        assert!(profile.strict_cors);
+    }
+}
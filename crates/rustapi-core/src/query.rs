@@ -0,0 +1,582 @@
+//! Query string deserialization
+//!
+//! [`Query<T>`](crate::Query) parses query strings with [`from_str`], which
+//! extends plain `application/x-www-form-urlencoded` parsing with the forms
+//! browsers and HTTP clients use for lists and nested objects:
+//!
+//! | Query string | Rust field |
+//! |--------------|------------|
+//! | `?tag=a&tag=b` | `tag: Vec<String>` |
+//! | `?tag[]=a&tag[]=b` | `tag: Vec<String>` |
+//! | `?tag[0]=a&tag[1]=b` | `tag: Vec<String>` |
+//! | `?filter[status]=open` | `filter: Filter` with a `status` field |
+//! | `?tag=a,b` | `tag: Vec<String>` with [`comma_separated`] |
+//!
+//! A key given more than once for a single value uses the last occurrence.
+//! Like any serde struct, a missing list is an error unless the field has
+//! `#[serde(default)]`.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! #[derive(Deserialize, Schema)]
+//! struct Search {
+//!     #[serde(default)]
+//!     tag: Vec<String>,
+//!     #[serde(default, deserialize_with = "rustapi_rs::query::comma_separated")]
+//!     ids: Vec<u64>,
+//! }
+//!
+//! // GET /pins?tag=rust&tag=web&ids=1,2,3
+//! async fn search(Query(search): Query<Search>) -> Json<Vec<Pin>> { ... }
+//! ```
+
+use serde::de::{self, DeserializeOwned, Deserializer, IntoDeserializer, Visitor};
+use serde::Deserialize;
+use std::fmt;
+use std::str::FromStr;
+
+/// Error parsing a query string
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct QueryError(String);
+
+impl fmt::Display for QueryError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl std::error::Error for QueryError {}
+
+impl de::Error for QueryError {
+    fn custom<T: fmt::Display>(msg: T) -> Self {
+        Self(msg.to_string())
+    }
+}
+
+/// Deserialize a query string into `T`
+pub fn from_str<T: DeserializeOwned>(query: &str) -> Result<T, QueryError> {
+    let pairs: Vec<(String, String)> =
+        serde_urlencoded::from_str(query).map_err(|e| QueryError(e.to_string()))?;
+
+    let mut root = Vec::new();
+    for (key, value) in pairs {
+        insert(&mut root, &key_path(&key), value);
+    }
+    T::deserialize(MapDeserializer {
+        entries: root,
+        top_level: true,
+        ignore_case: false,
+    })
+}
+
+/// Deserialize request headers into `T`, as [`HeaderStruct`](crate::HeaderStruct) does
+///
+/// Header names match struct fields case-insensitively, a header sent more
+/// than once fills a list, and values that are not valid UTF-8 are skipped.
+pub(crate) fn from_headers<T: DeserializeOwned>(
+    headers: &http::HeaderMap,
+) -> Result<T, QueryError> {
+    let mut root = Vec::new();
+    for (name, value) in headers {
+        if let Ok(value) = value.to_str() {
+            insert(&mut root, &[name.as_str()], value.to_string());
+        }
+    }
+    T::deserialize(MapDeserializer {
+        entries: root,
+        top_level: true,
+        ignore_case: true,
+    })
+}
+
+/// Deserialize a list from repeated and comma-separated values
+///
+/// `?id=1,2&id=3` gives `[1, 2, 3]`. Use it with
+/// `#[serde(default, deserialize_with = "comma_separated")]`.
+pub fn comma_separated<'de, D, T>(deserializer: D) -> Result<Vec<T>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: FromStr,
+    T::Err: fmt::Display,
+{
+    Vec::<String>::deserialize(deserializer)?
+        .iter()
+        .flat_map(|value| value.split(','))
+        .map(str::trim)
+        .filter(|item| !item.is_empty())
+        .map(|item| item.parse().map_err(de::Error::custom))
+        .collect()
+}
+
+/// Parsed query values, keyed in order of first appearance
+#[derive(Debug)]
+enum Node {
+    Values(Vec<String>),
+    Map(Vec<(String, Node)>),
+}
+
+/// `a[b][]` → `["a", "b"]`; empty brackets only mark a list
+fn key_path(key: &str) -> Vec<&str> {
+    let Some(open) = key.find('[') else {
+        return vec![key];
+    };
+    if open == 0 || !key.ends_with(']') {
+        return vec![key];
+    }
+    let mut path = vec![&key[..open]];
+    for segment in key[open + 1..key.len() - 1].split("][") {
+        if segment.contains(['[', ']']) {
+            return vec![key];
+        }
+        if !segment.is_empty() {
+            path.push(segment);
+        }
+    }
+    path
+}
+
+fn insert(entries: &mut Vec<(String, Node)>, path: &[&str], value: String) {
+    let (name, rest) = match path.split_first() {
+        Some(split) => split,
+        None => return,
+    };
+    let index = match entries.iter().position(|(key, _)| key == name) {
+        Some(index) => index,
+        None => {
+            let node = if rest.is_empty() {
+                Node::Values(Vec::new())
+            } else {
+                Node::Map(Vec::new())
+            };
+            entries.push((name.to_string(), node));
+            entries.len() - 1
+        }
+    };
+
+    let node = &mut entries[index].1;
+    match (node, rest.is_empty()) {
+        (Node::Values(values), true) => values.push(value),
+        (Node::Map(children), false) => insert(children, rest, value),
+        // `a=1&a[b]=2`: the later form replaces the earlier one
+        (node, _) => {
+            *node = if rest.is_empty() {
+                Node::Values(vec![value])
+            } else {
+                let mut children = Vec::new();
+                insert(&mut children, rest, value);
+                Node::Map(children)
+            };
+        }
+    }
+}
+
+impl<'de> IntoDeserializer<'de, QueryError> for Node {
+    type Deserializer = NodeDeserializer;
+
+    fn into_deserializer(self) -> NodeDeserializer {
+        NodeDeserializer(self)
+    }
+}
+
+struct NodeDeserializer(Node);
+
+/// Deserialize methods reading the single value of a key
+macro_rules! scalar_value {
+    ($($method:ident)*) => {
+        $(
+            fn $method<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, QueryError> {
+                self.scalar()?.$method(visitor)
+            }
+        )*
+    };
+}
+
+impl<'de> Deserializer<'de> for NodeDeserializer {
+    type Error = QueryError;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, QueryError> {
+        match self.0 {
+            Node::Values(values) if values.len() > 1 => visitor.visit_seq(
+                de::value::SeqDeserializer::new(values.into_iter().map(ValueDeserializer)),
+            ),
+            Node::Values(values) => visitor.visit_string(last(values)),
+            Node::Map(entries) => MapDeserializer {
+                entries,
+                top_level: false,
+                ignore_case: false,
+            }
+            .deserialize_any(visitor),
+        }
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, QueryError> {
+        visitor.visit_some(self)
+    }
+
+    fn deserialize_seq<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, QueryError> {
+        match self.0 {
+            Node::Values(values) => visitor.visit_seq(de::value::SeqDeserializer::new(
+                values.into_iter().map(ValueDeserializer),
+            )),
+            Node::Map(entries) => MapDeserializer {
+                entries,
+                top_level: false,
+                ignore_case: false,
+            }
+            .deserialize_seq(visitor),
+        }
+    }
+
+    fn deserialize_tuple<V: Visitor<'de>>(
+        self,
+        _len: usize,
+        visitor: V,
+    ) -> Result<V::Value, QueryError> {
+        self.deserialize_seq(visitor)
+    }
+
+    fn deserialize_map<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, QueryError> {
+        match self.0 {
+            Node::Map(entries) => MapDeserializer {
+                entries,
+                top_level: false,
+                ignore_case: false,
+            }
+            .deserialize_map(visitor),
+            Node::Values(_) => Err(de::Error::invalid_type(
+                de::Unexpected::Str("value"),
+                &"nested keys like `key[field]=value`",
+            )),
+        }
+    }
+
+    fn deserialize_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, QueryError> {
+        self.deserialize_map(visitor)
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, QueryError> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        name: &'static str,
+        variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, QueryError> {
+        self.scalar()?.deserialize_enum(name, variants, visitor)
+    }
+
+    fn deserialize_ignored_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, QueryError> {
+        visitor.visit_unit()
+    }
+
+    scalar_value! {
+        deserialize_bool deserialize_i8 deserialize_i16 deserialize_i32 deserialize_i64
+        deserialize_i128 deserialize_u8 deserialize_u16 deserialize_u32 deserialize_u64
+        deserialize_u128 deserialize_f32 deserialize_f64 deserialize_char deserialize_str
+        deserialize_string deserialize_bytes deserialize_byte_buf deserialize_unit
+        deserialize_identifier
+    }
+
+    serde::forward_to_deserialize_any! {
+        <V: Visitor<'de>>
+        unit_struct tuple_struct
+    }
+}
+
+impl NodeDeserializer {
+    /// The value of a key used once, or its last value
+    fn scalar(self) -> Result<ValueDeserializer, QueryError> {
+        match self.0 {
+            Node::Values(values) => Ok(ValueDeserializer(last(values))),
+            Node::Map(_) => Err(de::Error::invalid_type(
+                de::Unexpected::Map,
+                &"a single value",
+            )),
+        }
+    }
+}
+
+fn last(mut values: Vec<String>) -> String {
+    values.pop().unwrap_or_default()
+}
+
+/// Keys of the query string or of a nested object
+struct MapDeserializer {
+    entries: Vec<(String, Node)>,
+    /// Top-level lists are `(key, value)` pairs, as with `serde_urlencoded`
+    top_level: bool,
+    /// Match keys to struct fields case-insensitively, for header names
+    ignore_case: bool,
+}
+
+impl<'de> Deserializer<'de> for MapDeserializer {
+    type Error = QueryError;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, QueryError> {
+        visitor.visit_map(de::value::MapDeserializer::new(self.entries.into_iter()))
+    }
+
+    fn deserialize_seq<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, QueryError> {
+        if self.top_level {
+            let pairs = self.entries.into_iter().flat_map(|(key, node)| match node {
+                Node::Values(values) => values
+                    .into_iter()
+                    .map(|value| (key.clone(), value))
+                    .collect::<Vec<_>>(),
+                Node::Map(_) => Vec::new(),
+            });
+            return visitor.visit_seq(de::value::MapDeserializer::new(
+                pairs.map(|(key, value)| (key, ValueDeserializer(value))),
+            ));
+        }
+
+        // `tag[0]=a&tag[1]=b`
+        let mut items = Vec::with_capacity(self.entries.len());
+        for (key, node) in self.entries {
+            let index: usize = key.parse().map_err(|_| {
+                de::Error::invalid_type(de::Unexpected::Map, &"a list or indexed keys")
+            })?;
+            items.push((index, node));
+        }
+        items.sort_by_key(|(index, _)| *index);
+        visitor.visit_seq(de::value::SeqDeserializer::new(
+            items.into_iter().map(|(_, node)| node),
+        ))
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, QueryError> {
+        visitor.visit_some(self)
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, QueryError> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_tuple<V: Visitor<'de>>(
+        self,
+        _len: usize,
+        visitor: V,
+    ) -> Result<V::Value, QueryError> {
+        self.deserialize_seq(visitor)
+    }
+
+    fn deserialize_struct<V: Visitor<'de>>(
+        mut self,
+        _name: &'static str,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, QueryError> {
+        if self.ignore_case {
+            for (key, _) in &mut self.entries {
+                if let Some(field) = fields.iter().find(|f| f.eq_ignore_ascii_case(key)) {
+                    *key = field.to_string();
+                }
+            }
+        }
+        self.deserialize_any(visitor)
+    }
+
+    serde::forward_to_deserialize_any! {
+        <V: Visitor<'de>>
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf unit unit_struct tuple_struct map enum identifier
+        ignored_any
+    }
+}
+
+/// A single query value, parsed into the requested type
+struct ValueDeserializer(String);
+
+impl<'de> IntoDeserializer<'de, QueryError> for ValueDeserializer {
+    type Deserializer = Self;
+
+    fn into_deserializer(self) -> Self {
+        self
+    }
+}
+
+macro_rules! parse_value {
+    ($($method:ident => $visit:ident,)*) => {
+        $(
+            fn $method<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, QueryError> {
+                match self.0.parse() {
+                    Ok(value) => visitor.$visit(value),
+                    Err(_) => Err(de::Error::invalid_value(
+                        de::Unexpected::Str(&self.0),
+                        &visitor,
+                    )),
+                }
+            }
+        )*
+    };
+}
+
+impl<'de> Deserializer<'de> for ValueDeserializer {
+    type Error = QueryError;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, QueryError> {
+        visitor.visit_string(self.0)
+    }
+
+    parse_value! {
+        deserialize_bool => visit_bool,
+        deserialize_i8 => visit_i8,
+        deserialize_i16 => visit_i16,
+        deserialize_i32 => visit_i32,
+        deserialize_i64 => visit_i64,
+        deserialize_i128 => visit_i128,
+        deserialize_u8 => visit_u8,
+        deserialize_u16 => visit_u16,
+        deserialize_u32 => visit_u32,
+        deserialize_u64 => visit_u64,
+        deserialize_u128 => visit_u128,
+        deserialize_f32 => visit_f32,
+        deserialize_f64 => visit_f64,
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, QueryError> {
+        visitor.visit_some(self)
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, QueryError> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        name: &'static str,
+        variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, QueryError> {
+        IntoDeserializer::<QueryError>::into_deserializer(self.0)
+            .deserialize_enum(name, variants, visitor)
+    }
+
+    fn deserialize_seq<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, QueryError> {
+        visitor.visit_seq(de::value::SeqDeserializer::new(std::iter::once(self)))
+    }
+
+    fn deserialize_unit<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, QueryError> {
+        visitor.visit_unit()
+    }
+
+    serde::forward_to_deserialize_any! {
+        <V: Visitor<'de>>
+        char str string bytes byte_buf unit_struct tuple tuple_struct map struct
+        identifier ignored_any
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct Search {
+        q: Option<String>,
+        #[serde(default)]
+        tag: Vec<String>,
+        #[serde(default, deserialize_with = "comma_separated")]
+        ids: Vec<u32>,
+        page: Option<u32>,
+        #[serde(default)]
+        filter: Option<Filter>,
+    }
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct Filter {
+        status: Status,
+        #[serde(default)]
+        owner: Vec<String>,
+    }
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    #[serde(rename_all = "lowercase")]
+    enum Status {
+        Open,
+        Closed,
+    }
+
+    #[test]
+    fn lists_from_repeated_bracketed_indexed_and_comma_separated_keys() {
+        let search: Search =
+            from_str("q=pins&tag=a&tag[]=b&ids=1,2&ids=3&page=2&filter[status]=open").unwrap();
+        assert_eq!(
+            search,
+            Search {
+                q: Some("pins".to_string()),
+                tag: vec!["a".to_string(), "b".to_string()],
+                ids: vec![1, 2, 3],
+                page: Some(2),
+                filter: Some(Filter {
+                    status: Status::Open,
+                    owner: Vec::new(),
+                }),
+            }
+        );
+
+        let search: Search =
+            from_str("tag[1]=b&tag[0]=a&filter[owner][]=x&filter[status]=closed").unwrap();
+        assert_eq!(search.tag, ["a", "b"]);
+        assert_eq!(search.filter.unwrap().owner, ["x"]);
+
+        let empty: Search = from_str("").unwrap();
+        assert!(empty.tag.is_empty() && empty.q.is_none() && empty.filter.is_none());
+    }
+
+    #[test]
+    fn plain_forms_behave_like_urlencoded() {
+        let map: HashMap<String, String> = from_str("a=1&b=two%20words&a=3").unwrap();
+        assert_eq!(map["a"], "3");
+        assert_eq!(map["b"], "two words");
+
+        let pairs: Vec<(String, String)> = from_str("a=1&b=2&a=3").unwrap();
+        assert_eq!(pairs.len(), 3);
+
+        let err = from_str::<Search>("page=two").unwrap_err();
+        assert!(err.to_string().contains("two"), "{err}");
+        assert_eq!(key_path("a[b"), ["a[b"]);
+    }
+
+    #[test]
+    fn headers_match_fields_ignoring_case_and_collect_repeats() {
+        #[derive(Debug, Deserialize)]
+        struct Forwarded {
+            #[serde(rename = "X-Forwarded-For")]
+            forwarded_for: Vec<String>,
+            #[serde(rename = "x-request-id")]
+            request_id: Option<u64>,
+        }
+
+        let mut headers = http::HeaderMap::new();
+        headers.append("x-forwarded-for", "10.0.0.1".parse().unwrap());
+        headers.append("x-forwarded-for", "10.0.0.2".parse().unwrap());
+        headers.insert("X-Request-Id", "7".parse().unwrap());
+        headers.insert("accept", "*/*".parse().unwrap());
+
+        let forwarded: Forwarded = from_headers(&headers).unwrap();
+        assert_eq!(forwarded.forwarded_for, ["10.0.0.1", "10.0.0.2"]);
+        assert_eq!(forwarded.request_id, Some(7));
+    }
+}
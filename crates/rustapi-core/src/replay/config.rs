@@ -3,8 +3,12 @@
 //! Provides [`ReplayConfig`] with a builder pattern for customizing
 //! replay behavior. Secure defaults: disabled, admin token required,
 //! sensitive headers redacted, TTL enforced.
+//!
+//! Path filters accept globs: `*` matches within one path segment and `**`
+//! matches any number of segments (`/api/*/orders`, `/internal/**`).
 
 use std::collections::HashSet;
+use std::ops::RangeInclusive;
 
 /// Configuration for the replay recording middleware.
 ///
@@ -24,6 +28,14 @@ use std::collections::HashSet;
 ///     .admin_token("my-secret-token")
 ///     .ttl_secs(3600)
 ///     .redact_header("x-custom-secret");
+///
+/// // Production: 1% of traffic, all server errors, never health checks
+/// let config = ReplayConfig::new()
+///     .enabled(true)
+///     .admin_token("my-secret-token")
+///     .sample_rate(0.01)
+///     .record_status(500..=599)
+///     .skip_path("/health/**");
 /// ```
 #[derive(Clone)]
 pub struct ReplayConfig {
@@ -33,15 +45,30 @@ pub struct ReplayConfig {
     /// Admin bearer token required for replay endpoints. Must be set.
     pub admin_token: Option<String>,
 
-    /// Paths to record (empty = all paths). Mutually exclusive with skip_paths.
+    /// Paths or path globs to record (empty = all paths).
     pub record_paths: HashSet<String>,
 
-    /// Paths to skip from recording.
+    /// Paths or path globs to skip from recording. Takes precedence over `record_paths`.
     pub skip_paths: HashSet<String>,
 
+    /// HTTP methods to record (empty = all methods).
+    pub record_methods: HashSet<String>,
+
+    /// HTTP methods to skip from recording.
+    pub skip_methods: HashSet<String>,
+
+    /// Response status ranges to record (empty = all statuses).
+    pub record_statuses: Vec<RangeInclusive<u16>>,
+
+    /// Response status ranges to skip from recording.
+    pub skip_statuses: Vec<RangeInclusive<u16>>,
+
     /// Path prefix for admin routes. Default: `"/__rustapi/replays"`.
     pub admin_route_prefix: String,
 
+    /// Serve the browser admin UI at `{admin_route_prefix}/ui`. Default: false.
+    pub admin_ui: bool,
+
     /// Maximum request body size to capture (bytes). Default: 64KB.
     pub max_request_body: usize,
 
@@ -57,6 +84,9 @@ pub struct ReplayConfig {
     /// Sampling rate (0.0-1.0). Default: 1.0 (all requests).
     pub sample_rate: f64,
 
+    /// Per-path sampling rates as `(glob, rate)`; the first match overrides `sample_rate`.
+    pub path_sample_rates: Vec<(String, f64)>,
+
     /// Headers to redact (values replaced with `[REDACTED]`).
     pub redact_headers: HashSet<String>,
 
@@ -104,12 +134,18 @@ impl ReplayConfig {
             admin_token: None,
             record_paths: HashSet::new(),
             skip_paths: HashSet::new(),
+            record_methods: HashSet::new(),
+            skip_methods: HashSet::new(),
+            record_statuses: Vec::new(),
+            skip_statuses: Vec::new(),
             admin_route_prefix: "/__rustapi/replays".to_string(),
+            admin_ui: false,
             max_request_body: 65_536,   // 64KB
             max_response_body: 262_144, // 256KB
             store_capacity: 500,
             ttl_secs: 3600,
             sample_rate: 1.0,
+            path_sample_rates: Vec::new(),
             redact_headers,
             redact_body_fields: HashSet::new(),
             capturable_content_types: capturable,
@@ -131,25 +167,61 @@ impl ReplayConfig {
         self
     }
 
-    /// Add a path to record. If any record paths are set,
-    /// only those paths will be recorded.
+    /// Add a path or path glob to record. If any record paths are set,
+    /// only matching paths will be recorded.
     pub fn record_path(mut self, path: impl Into<String>) -> Self {
         self.record_paths.insert(path.into());
         self
     }
 
-    /// Add a path to skip from recording.
+    /// Add a path or path glob to skip from recording.
     pub fn skip_path(mut self, path: impl Into<String>) -> Self {
         self.skip_paths.insert(path.into());
         self
     }
 
+    /// Add an HTTP method to record. If any record methods are set,
+    /// only those methods will be recorded.
+    pub fn record_method(mut self, method: impl Into<String>) -> Self {
+        self.record_methods.insert(method.into().to_uppercase());
+        self
+    }
+
+    /// Add an HTTP method to skip from recording.
+    pub fn skip_method(mut self, method: impl Into<String>) -> Self {
+        self.skip_methods.insert(method.into().to_uppercase());
+        self
+    }
+
+    /// Add a response status range to record, e.g. `500..=599` for server
+    /// errors only. If any ranges are set, only matching responses are stored.
+    pub fn record_status(mut self, statuses: RangeInclusive<u16>) -> Self {
+        self.record_statuses.push(statuses);
+        self
+    }
+
+    /// Add a response status range to skip from recording.
+    pub fn skip_status(mut self, statuses: RangeInclusive<u16>) -> Self {
+        self.skip_statuses.push(statuses);
+        self
+    }
+
     /// Set the admin route prefix. Default: `"/__rustapi/replays"`.
     pub fn admin_route_prefix(mut self, prefix: impl Into<String>) -> Self {
         self.admin_route_prefix = prefix.into();
         self
     }
 
+    /// Serve a browser UI for browsing, inspecting and diffing recordings
+    /// at `{admin_route_prefix}/ui`.
+    ///
+    /// The page itself holds no data; it calls the admin endpoints with the
+    /// token entered into it, so the admin token still guards every entry.
+    pub fn admin_ui(mut self, enabled: bool) -> Self {
+        self.admin_ui = enabled;
+        self
+    }
+
     /// Set the maximum request body size to capture (bytes).
     pub fn max_request_body(mut self, size: usize) -> Self {
         self.max_request_body = size;
@@ -180,6 +252,14 @@ impl ReplayConfig {
         self
     }
 
+    /// Set the sampling rate (0.0 to 1.0) for paths matching `glob`,
+    /// overriding [`sample_rate`](Self::sample_rate). The first matching glob wins.
+    pub fn sample_path(mut self, glob: impl Into<String>, rate: f64) -> Self {
+        self.path_sample_rates
+            .push((glob.into(), rate.clamp(0.0, 1.0)));
+        self
+    }
+
     /// Add a header name to redact (case-insensitive).
     pub fn redact_header(mut self, header: impl Into<String>) -> Self {
         self.redact_headers.insert(header.into().to_lowercase());
@@ -207,27 +287,59 @@ impl ReplayConfig {
         }
 
         // Skip explicitly skipped paths
-        if self.skip_paths.contains(path) {
+        if self.skip_paths.iter().any(|glob| glob_match(glob, path)) {
             return false;
         }
 
         // If record_paths is set, only record those
         if !self.record_paths.is_empty() {
-            return self.record_paths.contains(path);
+            return self.record_paths.iter().any(|glob| glob_match(glob, path));
         }
 
         true
     }
 
-    /// Check if this request should be sampled.
-    pub fn should_sample(&self) -> bool {
-        if self.sample_rate >= 1.0 {
-            return true;
+    /// Check if a request method should be recorded.
+    pub fn should_record_method(&self, method: &str) -> bool {
+        let method = method.to_uppercase();
+        if self.skip_methods.contains(&method) {
+            return false;
         }
-        if self.sample_rate <= 0.0 {
+        self.record_methods.is_empty() || self.record_methods.contains(&method)
+    }
+
+    /// Check if a response with this status should be recorded.
+    pub fn should_record_status(&self, status: u16) -> bool {
+        if self
+            .skip_statuses
+            .iter()
+            .any(|range| range.contains(&status))
+        {
             return false;
         }
-        rand_sample(self.sample_rate)
+        self.record_statuses.is_empty()
+            || self
+                .record_statuses
+                .iter()
+                .any(|range| range.contains(&status))
+    }
+
+    /// Sampling rate applying to `path`.
+    pub fn sample_rate_for(&self, path: &str) -> f64 {
+        self.path_sample_rates
+            .iter()
+            .find(|(glob, _)| glob_match(glob, path))
+            .map_or(self.sample_rate, |(_, rate)| *rate)
+    }
+
+    /// Check if this request should be sampled.
+    pub fn should_sample(&self) -> bool {
+        sample(self.sample_rate)
+    }
+
+    /// Check if a request to `path` should be sampled, honoring per-path rates.
+    pub fn should_sample_path(&self, path: &str) -> bool {
+        sample(self.sample_rate_for(path))
     }
 
     /// Check if a content type is capturable.
@@ -242,6 +354,54 @@ impl ReplayConfig {
     }
 }
 
+fn sample(rate: f64) -> bool {
+    if rate >= 1.0 {
+        return true;
+    }
+    if rate <= 0.0 {
+        return false;
+    }
+    rand_sample(rate)
+}
+
+/// Match a path against a glob where `*` matches within one segment and `**`
+/// matches any number of segments. Patterns without wildcards match exactly.
+fn glob_match(pattern: &str, path: &str) -> bool {
+    if !pattern.contains('*') {
+        return pattern == path;
+    }
+
+    fn segments(value: &str) -> Vec<&str> {
+        value.split('/').filter(|s| !s.is_empty()).collect()
+    }
+
+    fn match_segment(pattern: &str, segment: &str) -> bool {
+        match pattern.split_once('*') {
+            None => pattern == segment,
+            Some((prefix, rest)) => {
+                let Some(remaining) = segment.strip_prefix(prefix) else {
+                    return false;
+                };
+                (0..=remaining.len())
+                    .filter(|&i| remaining.is_char_boundary(i))
+                    .any(|i| match_segment(rest, &remaining[i..]))
+            }
+        }
+    }
+
+    fn match_segments(pattern: &[&str], path: &[&str]) -> bool {
+        match pattern.split_first() {
+            None => path.is_empty(),
+            Some((&"**", rest)) => (0..=path.len()).any(|i| match_segments(rest, &path[i..])),
+            Some((first, rest)) => path.split_first().is_some_and(|(segment, path)| {
+                match_segment(first, segment) && match_segments(rest, path)
+            }),
+        }
+    }
+
+    match_segments(&segments(pattern), &segments(path))
+}
+
 /// Simple random sampling based on rate.
 fn rand_sample(rate: f64) -> bool {
     use std::time::{SystemTime, UNIX_EPOCH};
@@ -262,12 +422,18 @@ impl std::fmt::Debug for ReplayConfig {
             .field("admin_token", &self.admin_token.as_ref().map(|_| "[SET]"))
             .field("record_paths", &self.record_paths)
             .field("skip_paths", &self.skip_paths)
+            .field("record_methods", &self.record_methods)
+            .field("skip_methods", &self.skip_methods)
+            .field("record_statuses", &self.record_statuses)
+            .field("skip_statuses", &self.skip_statuses)
             .field("admin_route_prefix", &self.admin_route_prefix)
+            .field("admin_ui", &self.admin_ui)
             .field("max_request_body", &self.max_request_body)
             .field("max_response_body", &self.max_response_body)
             .field("store_capacity", &self.store_capacity)
             .field("ttl_secs", &self.ttl_secs)
             .field("sample_rate", &self.sample_rate)
+            .field("path_sample_rates", &self.path_sample_rates)
             .field("redact_headers", &self.redact_headers)
             .field("redact_body_fields", &self.redact_body_fields)
             .finish()
@@ -289,6 +455,7 @@ mod tests {
         assert_eq!(config.ttl_secs, 3600);
         assert_eq!(config.sample_rate, 1.0);
         assert_eq!(config.admin_route_prefix, "/__rustapi/replays");
+        assert!(!config.admin_ui);
     }
 
     #[test]
@@ -358,6 +525,58 @@ mod tests {
         assert!(!config.should_record_path("/health"));
     }
 
+    #[test]
+    fn test_path_globs() {
+        let config = ReplayConfig::new()
+            .record_path("/api/**")
+            .skip_path("/api/*/health")
+            .skip_path("/api/internal-*");
+
+        assert!(config.should_record_path("/api/users"));
+        assert!(config.should_record_path("/api/users/42/orders"));
+        assert!(!config.should_record_path("/api/v1/health"));
+        assert!(!config.should_record_path("/api/internal-metrics"));
+        assert!(!config.should_record_path("/static/app.js"));
+
+        assert!(glob_match("/files/*.json", "/files/report.json"));
+        assert!(!glob_match("/files/*.json", "/files/a/report.json"));
+        assert!(glob_match("/**", "/"));
+    }
+
+    #[test]
+    fn test_method_and_status_filters() {
+        let config = ReplayConfig::new()
+            .record_method("post")
+            .record_method("PUT")
+            .record_status(500..=599)
+            .skip_status(503..=503);
+
+        assert!(config.should_record_method("POST"));
+        assert!(!config.should_record_method("GET"));
+        assert!(config.should_record_status(500));
+        assert!(!config.should_record_status(503));
+        assert!(!config.should_record_status(404));
+
+        let config = ReplayConfig::new().skip_method("OPTIONS");
+        assert!(config.should_record_method("GET"));
+        assert!(!config.should_record_method("options"));
+        assert!(config.should_record_status(200));
+    }
+
+    #[test]
+    fn test_path_sample_rates() {
+        let config = ReplayConfig::new()
+            .sample_rate(0.1)
+            .sample_path("/api/checkout/**", 1.0)
+            .sample_path("/api/**", 0.0);
+
+        assert_eq!(config.sample_rate_for("/api/checkout/pay"), 1.0);
+        assert_eq!(config.sample_rate_for("/api/users"), 0.0);
+        assert_eq!(config.sample_rate_for("/other"), 0.1);
+        assert!(config.should_sample_path("/api/checkout/pay"));
+        assert!(!config.should_sample_path("/api/users"));
+    }
+
     #[test]
     fn test_capturable_content_type() {
         let config = ReplayConfig::new();
@@ -6,7 +6,8 @@ use std::collections::HashMap;
 /// Metadata associated with a replay entry.
 ///
 /// Contains contextual information about the recorded request such as
-/// route pattern, processing duration, client IP, and custom tags.
+/// route pattern, processing duration, client IP, request and trace IDs, and
+/// custom tags.
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct ReplayMeta {
     /// Route pattern that matched (e.g., `"/users/{id}"`).
@@ -24,6 +25,14 @@ pub struct ReplayMeta {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub request_id: Option<String>,
 
+    /// W3C trace ID (32 hex characters) the request was recorded under.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub trace_id: Option<String>,
+
+    /// W3C span ID (16 hex characters) of the recorded request.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub span_id: Option<String>,
+
     /// Custom tags for categorization and filtering.
     #[serde(default, skip_serializing_if = "HashMap::is_empty")]
     pub tags: HashMap<String, String>,
@@ -63,6 +72,18 @@ impl ReplayMeta {
         self
     }
 
+    /// Set the trace ID.
+    pub fn with_trace_id(mut self, id: impl Into<String>) -> Self {
+        self.trace_id = Some(id.into());
+        self
+    }
+
+    /// Set the span ID.
+    pub fn with_span_id(mut self, id: impl Into<String>) -> Self {
+        self.span_id = Some(id.into());
+        self
+    }
+
     /// Add a custom tag.
     pub fn with_tag(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
         self.tags.insert(key.into(), value.into());
@@ -87,6 +108,8 @@ mod tests {
         assert_eq!(meta.duration_ms, 0);
         assert!(meta.client_ip.is_none());
         assert!(meta.request_id.is_none());
+        assert!(meta.trace_id.is_none());
+        assert!(meta.span_id.is_none());
         assert!(meta.tags.is_empty());
         assert!(meta.ttl_secs.is_none());
     }
@@ -98,6 +121,8 @@ mod tests {
             .with_duration_ms(42)
             .with_client_ip("192.168.1.1")
             .with_request_id("req-123")
+            .with_trace_id("4bf92f3577b34da6a3ce929d0e0e4736")
+            .with_span_id("00f067aa0ba902b7")
             .with_tag("env", "staging")
             .with_ttl_secs(3600);
 
@@ -105,6 +130,11 @@ mod tests {
         assert_eq!(meta.duration_ms, 42);
         assert_eq!(meta.client_ip.as_deref(), Some("192.168.1.1"));
         assert_eq!(meta.request_id.as_deref(), Some("req-123"));
+        assert_eq!(
+            meta.trace_id.as_deref(),
+            Some("4bf92f3577b34da6a3ce929d0e0e4736")
+        );
+        assert_eq!(meta.span_id.as_deref(), Some("00f067aa0ba902b7"));
         assert_eq!(meta.tags.get("env").map(|s| s.as_str()), Some("staging"));
         assert_eq!(meta.ttl_secs, Some(3600));
     }
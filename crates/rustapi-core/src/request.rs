@@ -99,6 +99,11 @@ impl Request {
         &self.parts.headers
     }
 
+    /// Get mutable headers
+    pub fn headers_mut(&mut self) -> &mut HeaderMap {
+        &mut self.parts.headers
+    }
+
     /// Get request extensions
     pub fn extensions(&self) -> &Extensions {
         &self.parts.extensions
@@ -133,6 +138,18 @@ impl Request {
         }
     }
 
+    /// Replace the body with `bytes`
+    ///
+    /// `Content-Length` is set to the new length, so middleware can rewrite
+    /// a body before the handler reads it.
+    pub fn set_body(&mut self, bytes: Bytes) {
+        self.parts.headers.remove(http::header::TRANSFER_ENCODING);
+        self.parts
+            .headers
+            .insert(http::header::CONTENT_LENGTH, bytes.len().into());
+        self.body = BodyVariant::Buffered(bytes);
+    }
+
     /// Take the body as a stream (can only be called once)
     pub fn take_stream(&mut self) -> Option<Incoming> {
         match std::mem::replace(&mut self.body, BodyVariant::Consumed) {
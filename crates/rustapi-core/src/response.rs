@@ -80,6 +80,7 @@ use rustapi_openapi::{MediaType, Operation, ResponseModifier, ResponseSpec, Sche
 use serde::Serialize;
 use std::collections::BTreeMap;
 use std::pin::Pin;
+use std::sync::Arc;
 use std::task::{Context, Poll};
 
 /// Unified response body type
@@ -273,11 +274,18 @@ impl IntoResponse for ApiError {
             br#"{"error":{"type":"internal_error","message":"Failed to serialize error"}}"#.to_vec()
         });
 
-        http::Response::builder()
+        let mut response = http::Response::builder()
             .status(status)
             .header(header::CONTENT_TYPE, "application/json")
             .body(Body::from(body))
-            .unwrap()
+            .unwrap();
+        // Keep the structured body so it can be re-encoded per `Accept`
+        if let Ok(value) = serde_json::to_value(&error_response) {
+            response
+                .extensions_mut()
+                .insert(crate::error_format::NegotiableError(Arc::new(value)));
+        }
+        response
     }
 }
 
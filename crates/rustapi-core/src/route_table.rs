@@ -0,0 +1,227 @@
+//! Route table
+//!
+//! The route table lists every route an application serves, with the handler
+//! behind it, its OpenAPI tags and operation id, and the middleware stack that
+//! wraps all routes. It helps explaining unexpected 404 and 405 responses:
+//! a typo in a path, a handler mounted under the wrong method or a route that
+//! was never registered shows up at a glance.
+//!
+//! The table is available in two ways:
+//!
+//! - Programmatically via [`RustApi::route_table`](crate::RustApi::route_table).
+//! - On stdout when the app is started with `RUSTAPI_DUMP_ROUTES=1`
+//!   (used by `cargo rustapi routes`). This table also contains the routes
+//!   added when the server starts (health endpoints, status page, dashboard).
+
+use serde::Serialize;
+
+/// A single route of the table.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct RouteTableEntry {
+    /// HTTP method (e.g. `GET`)
+    pub method: String,
+    /// Path pattern (e.g. `/users/{id}`)
+    pub path: String,
+    /// Type name of the handler, if known
+    ///
+    /// Routes registered from pre-boxed handlers (static files, docs) have
+    /// no handler name.
+    pub handler: Option<String>,
+    /// OpenAPI tags of the operation
+    pub tags: Vec<String>,
+    /// OpenAPI operation id, if set
+    pub operation_id: Option<String>,
+}
+
+/// All routes of an application and its middleware stack.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct RouteTable {
+    routes: Vec<RouteTableEntry>,
+    middleware: Vec<String>,
+}
+
+impl RouteTable {
+    /// Create a table, sorting the routes by path and method.
+    pub fn new(mut routes: Vec<RouteTableEntry>, middleware: Vec<String>) -> Self {
+        routes.sort_by(|a, b| {
+            (a.path.as_str(), method_rank(&a.method), a.method.as_str()).cmp(&(
+                b.path.as_str(),
+                method_rank(&b.method),
+                b.method.as_str(),
+            ))
+        });
+        Self { routes, middleware }
+    }
+
+    /// All routes, sorted by path and method.
+    pub fn routes(&self) -> &[RouteTableEntry] {
+        &self.routes
+    }
+
+    /// Type names of the middleware layers, outermost first.
+    ///
+    /// Middleware applies to every route.
+    pub fn middleware(&self) -> &[String] {
+        &self.middleware
+    }
+
+    /// Look up the route serving `method` on the path pattern `path`.
+    pub fn get(&self, method: &str, path: &str) -> Option<&RouteTableEntry> {
+        self.routes
+            .iter()
+            .find(|r| r.path == path && r.method.eq_ignore_ascii_case(method))
+    }
+
+    /// Serialize the table as `{"routes": [...], "middleware": [...]}`.
+    pub fn to_json(&self) -> serde_json::Value {
+        serde_json::to_value(self)
+            .unwrap_or_else(|_| serde_json::json!({ "routes": [], "middleware": [] }))
+    }
+}
+
+/// What [`RustApi::run`](crate::RustApi::run) does when two handlers are
+/// registered for the same method and path.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum DuplicateRoutePolicy {
+    /// Refuse to start, listing every duplicate (default)
+    #[default]
+    Error,
+    /// Log a warning per duplicate and serve the first registration
+    Warn,
+}
+
+/// The handler behind a route and where it was registered.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RouteOrigin {
+    /// Type name of the handler
+    pub handler: &'static str,
+    /// Source location of the route macro or `*_route` call
+    pub location: &'static std::panic::Location<'static>,
+}
+
+impl std::fmt::Display for RouteOrigin {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} ({})", self.handler, self.location)
+    }
+}
+
+/// Two handlers registered for the same method and path.
+///
+/// Paths that differ only in parameter names (`/users/{id}` and
+/// `/users/{user_id}`) count as the same path.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DuplicateRoute {
+    /// HTTP method (e.g. `GET`)
+    pub method: String,
+    /// Path pattern of the second registration
+    pub path: String,
+    /// The registration that is served
+    pub first: RouteOrigin,
+    /// The registration that was ignored
+    pub second: RouteOrigin,
+}
+
+/// Error returned when the app is started with duplicate routes.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DuplicateRouteError {
+    /// Every duplicate, in registration order
+    pub duplicates: Vec<DuplicateRoute>,
+}
+
+impl std::fmt::Display for DuplicateRouteError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "duplicate routes:")?;
+        for duplicate in &self.duplicates {
+            writeln!(f, "  {} {}", duplicate.method, duplicate.path)?;
+            writeln!(f, "    first:  {}", duplicate.first)?;
+            writeln!(f, "    second: {}", duplicate.second)?;
+        }
+        write!(
+            f,
+            "remove one handler of each pair, or use \
+             `on_duplicate_route(DuplicateRoutePolicy::Warn)` to serve the first one"
+        )
+    }
+}
+
+impl std::error::Error for DuplicateRouteError {}
+
+/// Conventional display order of methods; unknown methods sort last.
+fn method_rank(method: &str) -> usize {
+    const ORDER: [&str; 8] = [
+        "GET", "HEAD", "POST", "PUT", "PATCH", "DELETE", "OPTIONS", "TRACE",
+    ];
+    ORDER
+        .iter()
+        .position(|m| *m == method)
+        .unwrap_or(ORDER.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(method: &str, path: &str) -> RouteTableEntry {
+        RouteTableEntry {
+            method: method.to_string(),
+            path: path.to_string(),
+            handler: None,
+            tags: Vec::new(),
+            operation_id: None,
+        }
+    }
+
+    #[test]
+    fn duplicate_route_error_lists_both_handlers() {
+        let first = RouteOrigin {
+            handler: "app::users::list",
+            location: std::panic::Location::caller(),
+        };
+        let second = RouteOrigin {
+            handler: "app::admin::list",
+            ..first
+        };
+        let error = DuplicateRouteError {
+            duplicates: vec![DuplicateRoute {
+                method: "GET".to_string(),
+                path: "/users".to_string(),
+                first,
+                second,
+            }],
+        };
+        let message = error.to_string();
+        assert!(message.contains("GET /users"));
+        assert!(message.contains("first:  app::users::list ("));
+        assert!(message.contains("second: app::admin::list ("));
+        assert!(message.contains("route_table.rs:"));
+    }
+
+    #[test]
+    fn sorts_by_path_then_method() {
+        let table = RouteTable::new(
+            vec![
+                entry("DELETE", "/users/{id}"),
+                entry("POST", "/users"),
+                entry("GET", "/users/{id}"),
+                entry("GET", "/users"),
+            ],
+            Vec::new(),
+        );
+        let order: Vec<(&str, &str)> = table
+            .routes()
+            .iter()
+            .map(|r| (r.method.as_str(), r.path.as_str()))
+            .collect();
+        assert_eq!(
+            order,
+            vec![
+                ("GET", "/users"),
+                ("POST", "/users"),
+                ("GET", "/users/{id}"),
+                ("DELETE", "/users/{id}"),
+            ]
+        );
+        assert!(table.get("delete", "/users/{id}").is_some());
+        assert!(table.get("PUT", "/users/{id}").is_none());
+    }
+}
@@ -282,6 +282,12 @@ impl Router {
         self
     }
 
+    /// Forget a route so [`nest`](Self::nest) does not copy it into a parent
+    pub(crate) fn remove_route(&mut self, matchit_path: &str) {
+        self.registered_routes.remove(matchit_path);
+        self.method_routers.remove(matchit_path);
+    }
+
     /// Merge state from another router into this one
     ///
     /// This method allows explicit state merging when nesting routers.
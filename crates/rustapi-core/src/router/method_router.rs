@@ -5,9 +5,11 @@ use std::collections::HashMap;
 
 /// HTTP method router for a single path
 pub struct MethodRouter {
-    pub(super) handlers: HashMap<Method, BoxedHandler>,
+    pub(crate) handlers: HashMap<Method, BoxedHandler>,
     pub(crate) operations: HashMap<Method, Operation>,
     pub(crate) component_registrars: Vec<fn(&mut rustapi_openapi::OpenApiSpec)>,
+    /// Type names of the handlers, for the route table
+    pub(crate) handler_names: HashMap<Method, &'static str>,
 }
 
 impl Clone for MethodRouter {
@@ -16,6 +18,7 @@ impl Clone for MethodRouter {
             handlers: self.handlers.clone(),
             operations: self.operations.clone(),
             component_registrars: self.component_registrars.clone(),
+            handler_names: self.handler_names.clone(),
         }
     }
 }
@@ -27,6 +30,7 @@ impl MethodRouter {
             handlers: HashMap::new(),
             operations: HashMap::new(),
             component_registrars: Vec::new(),
+            handler_names: HashMap::new(),
         }
     }
 
@@ -35,9 +39,11 @@ impl MethodRouter {
         mut self,
         method: Method,
         handler: BoxedHandler,
+        handler_name: &'static str,
         operation: Operation,
         component_registrar: fn(&mut rustapi_openapi::OpenApiSpec),
     ) -> Self {
+        self.handler_names.insert(method.clone(), handler_name);
         self.handlers.insert(method.clone(), handler);
         self.operations.insert(method, operation);
         self.component_registrars.push(component_registrar);
@@ -60,6 +66,7 @@ impl MethodRouter {
             handlers,
             operations: HashMap::new(), // Operations lost when using raw boxed handlers for now
             component_registrars: Vec::new(),
+            handler_names: HashMap::new(),
         }
     }
 
@@ -70,6 +77,7 @@ impl MethodRouter {
         &mut self,
         method: Method,
         handler: BoxedHandler,
+        handler_name: &'static str,
         operation: Operation,
         component_registrar: fn(&mut rustapi_openapi::OpenApiSpec),
     ) {
@@ -80,11 +88,24 @@ impl MethodRouter {
             );
         }
 
+        self.handler_names.insert(method.clone(), handler_name);
         self.handlers.insert(method.clone(), handler);
         self.operations.insert(method, operation);
         self.component_registrars.push(component_registrar);
     }
 
+    /// Adjust the OpenAPI operation documented for `method`
+    ///
+    /// For routers built at runtime, where the handler's extractor types
+    /// cannot describe the request on their own. Does nothing if no handler
+    /// is registered for `method`.
+    pub fn operation(mut self, method: Method, update: impl FnOnce(&mut Operation)) -> Self {
+        if let Some(op) = self.operations.get_mut(&method) {
+            update(op);
+        }
+        self
+    }
+
     /// Add a GET handler
     pub fn get<H, T>(self, handler: H) -> Self
     where
@@ -96,6 +117,7 @@ impl MethodRouter {
         self.on(
             Method::GET,
             into_boxed_handler(handler),
+            std::any::type_name::<H>(),
             op,
             <H as Handler<T>>::register_components,
         )
@@ -112,6 +134,7 @@ impl MethodRouter {
         self.on(
             Method::POST,
             into_boxed_handler(handler),
+            std::any::type_name::<H>(),
             op,
             <H as Handler<T>>::register_components,
         )
@@ -128,6 +151,7 @@ impl MethodRouter {
         self.on(
             Method::PUT,
             into_boxed_handler(handler),
+            std::any::type_name::<H>(),
             op,
             <H as Handler<T>>::register_components,
         )
@@ -144,6 +168,7 @@ impl MethodRouter {
         self.on(
             Method::PATCH,
             into_boxed_handler(handler),
+            std::any::type_name::<H>(),
             op,
             <H as Handler<T>>::register_components,
         )
@@ -160,6 +185,7 @@ impl MethodRouter {
         self.on(
             Method::DELETE,
             into_boxed_handler(handler),
+            std::any::type_name::<H>(),
             op,
             <H as Handler<T>>::register_components,
         )
@@ -183,6 +209,7 @@ where
     MethodRouter::new().on(
         Method::GET,
         into_boxed_handler(handler),
+        std::any::type_name::<H>(),
         op,
         <H as Handler<T>>::register_components,
     )
@@ -199,6 +226,7 @@ where
     MethodRouter::new().on(
         Method::POST,
         into_boxed_handler(handler),
+        std::any::type_name::<H>(),
         op,
         <H as Handler<T>>::register_components,
     )
@@ -215,6 +243,7 @@ where
     MethodRouter::new().on(
         Method::PUT,
         into_boxed_handler(handler),
+        std::any::type_name::<H>(),
         op,
         <H as Handler<T>>::register_components,
     )
@@ -231,6 +260,7 @@ where
     MethodRouter::new().on(
         Method::PATCH,
         into_boxed_handler(handler),
+        std::any::type_name::<H>(),
         op,
         <H as Handler<T>>::register_components,
     )
@@ -247,6 +277,7 @@ where
     MethodRouter::new().on(
         Method::DELETE,
         into_boxed_handler(handler),
+        std::any::type_name::<H>(),
         op,
         <H as Handler<T>>::register_components,
     )
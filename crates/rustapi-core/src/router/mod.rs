@@ -9,9 +9,10 @@ mod match_;
 mod method_router;
 
 pub use core::Router;
-pub use match_::RouteMatch;
 #[cfg(test)]
-pub(crate) use match_::{convert_path_params, normalize_path_for_comparison, normalize_prefix};
+pub(crate) use match_::normalize_prefix;
+pub use match_::RouteMatch;
+pub(crate) use match_::{convert_path_params, normalize_path_for_comparison};
 pub use method_router::{delete, get, patch, post, put, MethodRouter};
 
 #[cfg(test)]
@@ -0,0 +1,432 @@
+//! Signed and private (encrypted) cookie jars
+//!
+//! [`SignedCookies`] authenticates cookie values with HMAC-SHA256, so clients
+//! can read but not alter them. [`PrivateCookies`] encrypts them with
+//! AES-256-GCM, so clients can neither read nor alter them. Both are backed by
+//! a [`CookieKeyring`] in app state: new cookies use the primary key, and
+//! cookies issued under a retired key are still accepted until they expire.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! use rustapi_core::secure_cookies::{Cookie, CookieKeyring, SignedCookies};
+//!
+//! async fn login(mut cookies: SignedCookies) -> (SignedCookies, &'static str) {
+//!     cookies.add(Cookie::build(("session_id", "abc123")).http_only(true).path("/"));
+//!     (cookies, "Logged in")
+//! }
+//!
+//! RustApi::new()
+//!     .state(CookieKeyring::from_secret(secret.as_bytes())?)
+//!     .route("/login", post(login));
+//! ```
+
+use crate::error::{ApiError, Result};
+use crate::extract::{parse_cookie_jar, FromRequestParts};
+use crate::request::Request;
+use crate::response::{IntoResponse, Response};
+use cookie::CookieJar;
+use http::{header, HeaderValue};
+use rustapi_openapi::{Operation, OperationModifier};
+use std::sync::Arc;
+
+pub use cookie::{Cookie, Key, KeyError, SameSite};
+
+/// Keys used to sign and encrypt cookies
+///
+/// The first key is the primary key used for new cookies. Retired keys are
+/// only used to accept cookies issued before a rotation.
+///
+/// # Rotation
+///
+/// ```rust,ignore
+/// let keyring = CookieKeyring::from_secret(new_secret)?.with_retired_secret(old_secret)?;
+/// ```
+#[derive(Clone)]
+pub struct CookieKeyring {
+    keys: Arc<Vec<Key>>,
+}
+
+impl std::fmt::Debug for CookieKeyring {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CookieKeyring")
+            .field("keys", &self.keys.len())
+            .finish()
+    }
+}
+
+impl CookieKeyring {
+    /// Create a keyring with a single primary key
+    pub fn new(primary: Key) -> Self {
+        Self {
+            keys: Arc::new(vec![primary]),
+        }
+    }
+
+    /// Create a keyring from a secret of at least 32 bytes
+    ///
+    /// Secrets of 64 bytes or more are used as the key directly; shorter ones
+    /// are expanded with HKDF.
+    pub fn from_secret(secret: &[u8]) -> std::result::Result<Self, KeyError> {
+        key_from_secret(secret).map(Self::new)
+    }
+
+    /// Create a keyring with a random primary key
+    ///
+    /// Cookies do not survive restarts or spread across replicas with a
+    /// generated key; use [`from_secret`](Self::from_secret) in production.
+    pub fn generate() -> Self {
+        Self::new(Key::generate())
+    }
+
+    /// Keep accepting cookies issued under a retired key
+    pub fn with_retired(mut self, key: Key) -> Self {
+        Arc::make_mut(&mut self.keys).push(key);
+        self
+    }
+
+    /// Keep accepting cookies issued under a retired secret
+    pub fn with_retired_secret(self, secret: &[u8]) -> std::result::Result<Self, KeyError> {
+        Ok(self.with_retired(key_from_secret(secret)?))
+    }
+
+    /// Make `key` the primary key, retiring the current one
+    pub fn rotate(&self, key: Key) -> Self {
+        let mut keys = Vec::with_capacity(self.keys.len() + 1);
+        keys.push(key);
+        keys.extend(self.keys.iter().cloned());
+        Self {
+            keys: Arc::new(keys),
+        }
+    }
+
+    /// The key used for new cookies
+    pub fn primary(&self) -> &Key {
+        &self.keys[0]
+    }
+
+    /// Number of keys, including the primary key
+    pub fn len(&self) -> usize {
+        self.keys.len()
+    }
+
+    /// Always `false`; a keyring has at least its primary key
+    pub fn is_empty(&self) -> bool {
+        false
+    }
+
+    fn from_request(req: &Request) -> Result<Self> {
+        req.state().get::<CookieKeyring>().cloned().ok_or_else(|| {
+            ApiError::internal("CookieKeyring not configured. Did you forget to call .state()?")
+        })
+    }
+}
+
+/// Use secrets of 64+ bytes directly and expand 32..64-byte ones with HKDF
+fn key_from_secret(secret: &[u8]) -> std::result::Result<Key, KeyError> {
+    match secret.len() {
+        len if len >= 64 => Key::try_from(secret),
+        len if len >= 32 => Ok(Key::derive_from(secret)),
+        len => Err(KeyError::TooShort(len)),
+    }
+}
+
+#[derive(Clone, Copy)]
+enum Protection {
+    Signed,
+    Private,
+}
+
+/// Shared implementation of the signed and private jars
+#[derive(Clone)]
+struct SecureJar {
+    jar: CookieJar,
+    keyring: CookieKeyring,
+    protection: Protection,
+}
+
+impl SecureJar {
+    fn verify(&self, key: &Key, name: &str) -> Option<Cookie<'static>> {
+        match self.protection {
+            Protection::Signed => self.jar.signed(key).get(name),
+            Protection::Private => self.jar.private(key).get(name),
+        }
+    }
+
+    /// Verified cookie and the index of the key that accepted it
+    fn get_with_key(&self, name: &str) -> Option<(Cookie<'static>, usize)> {
+        self.keyring
+            .keys
+            .iter()
+            .enumerate()
+            .find_map(|(index, key)| self.verify(key, name).map(|cookie| (cookie, index)))
+    }
+
+    fn add(&mut self, cookie: Cookie<'static>) {
+        let key = self.keyring.primary();
+        match self.protection {
+            Protection::Signed => self.jar.signed_mut(key).add(cookie),
+            Protection::Private => self.jar.private_mut(key).add(cookie),
+        }
+    }
+
+    fn remove(&mut self, cookie: Cookie<'static>) {
+        self.jar.remove(cookie);
+    }
+
+    fn set_cookie_headers(&self) -> Vec<HeaderValue> {
+        self.jar
+            .delta()
+            .filter_map(|cookie| HeaderValue::from_str(&cookie.to_string()).ok())
+            .collect()
+    }
+
+    fn append_to(&self, mut response: Response) -> Response {
+        for value in self.set_cookie_headers() {
+            response.headers_mut().append(header::SET_COOKIE, value);
+        }
+        response
+    }
+}
+
+macro_rules! secure_jar {
+    ($(#[$meta:meta])* $name:ident, $protection:expr) => {
+        $(#[$meta])*
+        #[derive(Clone)]
+        pub struct $name(SecureJar);
+
+        impl $name {
+            /// Create an empty jar backed by `keyring`
+            pub fn new(keyring: CookieKeyring) -> Self {
+                Self(SecureJar {
+                    jar: CookieJar::new(),
+                    keyring,
+                    protection: $protection,
+                })
+            }
+
+            /// Get a cookie whose value verified under any key in the keyring
+            ///
+            /// The returned cookie holds the plain value. Missing, tampered and
+            /// foreign cookies all yield `None`.
+            pub fn get(&self, name: &str) -> Option<Cookie<'static>> {
+                self.0.get_with_key(name).map(|(cookie, _)| cookie)
+            }
+
+            /// Whether a cookie verified only under a retired key
+            ///
+            /// [`add`](Self::add) it again to reissue it under the primary key.
+            pub fn is_stale(&self, name: &str) -> bool {
+                matches!(self.0.get_with_key(name), Some((_, index)) if index > 0)
+            }
+
+            /// Add a cookie, protected with the primary key
+            pub fn add(&mut self, cookie: impl Into<Cookie<'static>>) {
+                self.0.add(cookie.into());
+            }
+
+            /// Remove a cookie from the client
+            ///
+            /// Pass a cookie with the same path and domain it was set with.
+            pub fn remove(&mut self, cookie: impl Into<Cookie<'static>>) {
+                self.0.remove(cookie.into());
+            }
+
+            /// `Set-Cookie` values for the cookies added or removed so far
+            pub fn set_cookie_headers(&self) -> Vec<HeaderValue> {
+                self.0.set_cookie_headers()
+            }
+        }
+
+        impl std::fmt::Debug for $name {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                f.debug_struct(stringify!($name))
+                    .field("cookies", &self.0.jar.iter().count())
+                    .field("keyring", &self.0.keyring)
+                    .finish()
+            }
+        }
+
+        impl FromRequestParts for $name {
+            fn from_request_parts(req: &Request) -> Result<Self> {
+                let keyring = CookieKeyring::from_request(req)?;
+                Ok(Self(SecureJar {
+                    jar: parse_cookie_jar(req.headers()),
+                    keyring,
+                    protection: $protection,
+                }))
+            }
+        }
+
+        impl OperationModifier for $name {
+            fn update_operation(_op: &mut Operation) {}
+        }
+
+        impl IntoResponse for $name {
+            fn into_response(self) -> Response {
+                self.0.append_to(().into_response())
+            }
+        }
+
+        impl<R: IntoResponse> IntoResponse for ($name, R) {
+            fn into_response(self) -> Response {
+                self.0 .0.append_to(self.1.into_response())
+            }
+        }
+    };
+}
+
+secure_jar!(
+    /// Cookie jar whose values are signed with HMAC-SHA256
+    ///
+    /// Clients can read signed values but any change invalidates them. Return
+    /// the jar (alone or as `(jar, response)`) to send the cookies it added or
+    /// removed.
+    SignedCookies,
+    Protection::Signed
+);
+
+secure_jar!(
+    /// Cookie jar whose values are encrypted and authenticated with AES-256-GCM
+    ///
+    /// Use it for values the client must not read, such as flash messages
+    /// carrying internal state. Return the jar (alone or as
+    /// `(jar, response)`) to send the cookies it added or removed.
+    PrivateCookies,
+    Protection::Private
+);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bytes::Bytes;
+    use http::Extensions;
+
+    fn request(keyring: &CookieKeyring, cookie_header: Option<&str>) -> Request {
+        let mut builder = http::Request::builder().uri("/");
+        if let Some(value) = cookie_header {
+            builder = builder.header(header::COOKIE, value);
+        }
+        let (parts, _) = builder.body(()).unwrap().into_parts();
+        let mut state = Extensions::new();
+        state.insert(keyring.clone());
+        Request::new(
+            parts,
+            crate::request::BodyVariant::Buffered(Bytes::new()),
+            Arc::new(state),
+            crate::path_params::PathParams::new(),
+        )
+    }
+
+    /// `name=value` pairs the client would send back
+    fn cookie_header(headers: &[HeaderValue]) -> String {
+        headers
+            .iter()
+            .map(|value| {
+                let cookie = Cookie::parse(value.to_str().unwrap().to_string()).unwrap();
+                format!("{}={}", cookie.name(), cookie.value())
+            })
+            .collect::<Vec<_>>()
+            .join("; ")
+    }
+
+    #[test]
+    fn signed_cookies_round_trip_and_reject_tampering() {
+        let keyring = CookieKeyring::generate();
+        let mut jar = SignedCookies::new(keyring.clone());
+        jar.add(Cookie::new("session_id", "abc123"));
+        let header = cookie_header(&jar.set_cookie_headers());
+        assert!(header.contains("abc123"));
+
+        let jar = SignedCookies::from_request_parts(&request(&keyring, Some(&header))).unwrap();
+        assert_eq!(jar.get("session_id").unwrap().value(), "abc123");
+        assert!(!jar.is_stale("session_id"));
+
+        let tampered = header.replace("abc123", "admin1");
+        let jar = SignedCookies::from_request_parts(&request(&keyring, Some(&tampered))).unwrap();
+        assert!(jar.get("session_id").is_none());
+
+        let other = CookieKeyring::generate();
+        let jar = SignedCookies::from_request_parts(&request(&other, Some(&header))).unwrap();
+        assert!(jar.get("session_id").is_none());
+    }
+
+    #[test]
+    fn private_cookies_hide_values() {
+        let keyring = CookieKeyring::from_secret(&[7u8; 32]).unwrap();
+        let mut jar = PrivateCookies::new(keyring.clone());
+        jar.add(Cookie::new("flash", "Profile saved"));
+        let header = cookie_header(&jar.set_cookie_headers());
+        assert!(!header.contains("Profile saved"));
+
+        let jar = PrivateCookies::from_request_parts(&request(&keyring, Some(&header))).unwrap();
+        assert_eq!(jar.get("flash").unwrap().value(), "Profile saved");
+
+        // Encrypted values are bound to the cookie name
+        let renamed = header.replacen("flash", "other", 1);
+        let jar = PrivateCookies::from_request_parts(&request(&keyring, Some(&renamed))).unwrap();
+        assert!(jar.get("other").is_none());
+    }
+
+    #[test]
+    fn rotated_keys_still_verify() {
+        let old = CookieKeyring::generate();
+        let mut jar = SignedCookies::new(old.clone());
+        jar.add(Cookie::new("session_id", "abc123"));
+        let header = cookie_header(&jar.set_cookie_headers());
+
+        let rotated = old.rotate(Key::generate());
+        assert_eq!(rotated.len(), 2);
+        let mut jar = SignedCookies::from_request_parts(&request(&rotated, Some(&header))).unwrap();
+        assert_eq!(jar.get("session_id").unwrap().value(), "abc123");
+        assert!(jar.is_stale("session_id"));
+
+        jar.add(Cookie::new("session_id", "abc123"));
+        assert!(!jar.is_stale("session_id"));
+    }
+
+    #[test]
+    fn jar_sets_cookies_on_response() {
+        let keyring = CookieKeyring::generate();
+        let mut jar = SignedCookies::from_request_parts(&request(&keyring, Some("b=x"))).unwrap();
+        jar.add(Cookie::build(("a", "1")).path("/"));
+        jar.remove(Cookie::build("b").path("/"));
+
+        let response = (jar, "ok").into_response();
+        let cookies: Vec<_> = response
+            .headers()
+            .get_all(header::SET_COOKIE)
+            .iter()
+            .map(|v| v.to_str().unwrap().to_string())
+            .collect();
+        assert_eq!(cookies.len(), 2);
+        assert!(cookies.iter().any(|c| c.starts_with("a=")));
+        assert!(cookies.iter().any(|c| c.starts_with("b=;")));
+    }
+
+    #[test]
+    fn keyring_requires_state_and_long_secrets() {
+        assert!(matches!(
+            CookieKeyring::from_secret(b"short"),
+            Err(KeyError::TooShort(5))
+        ));
+        let keyring = CookieKeyring::from_secret(&[1u8; 64])
+            .unwrap()
+            .with_retired_secret(&[2u8; 40])
+            .unwrap();
+        assert_eq!(keyring.len(), 2);
+
+        let (parts, _) = http::Request::builder()
+            .uri("/")
+            .body(())
+            .unwrap()
+            .into_parts();
+        let req = Request::new(
+            parts,
+            crate::request::BodyVariant::Buffered(Bytes::new()),
+            Arc::new(Extensions::new()),
+            crate::path_params::PathParams::new(),
+        );
+        assert!(SignedCookies::from_request_parts(&req).is_err());
+    }
+}
@@ -1,6 +1,7 @@
 //! HTTP server implementation
 
 use crate::error::ApiError;
+use crate::handover::Handover;
 use crate::interceptor::InterceptorChain;
 use crate::middleware::{BoxedNext, LayerStack};
 use crate::request::Request;
@@ -16,13 +17,15 @@ use std::future::Future;
 use std::net::SocketAddr;
 use std::sync::Arc;
 use tokio::net::TcpListener;
-use tracing::{error, info};
+use tokio::sync::watch;
+use tracing::{error, info, warn};
 
 /// Internal server struct
 pub(crate) struct Server {
     router: Arc<Router>,
     layers: Arc<LayerStack>,
     interceptors: Arc<InterceptorChain>,
+    handover: Option<Handover>,
 }
 
 impl Server {
@@ -31,9 +34,16 @@ impl Server {
             router: Arc::new(router),
             layers: Arc::new(layers),
             interceptors: Arc::new(interceptors),
+            handover: None,
         }
     }
 
+    /// Bind and drain as configured by `handover`
+    pub fn with_handover(mut self, handover: Option<Handover>) -> Self {
+        self.handover = handover;
+        self
+    }
+
     #[cfg(feature = "http3")]
     pub fn from_shared(
         router: Arc<Router>,
@@ -44,6 +54,7 @@ impl Server {
             router,
             layers,
             interceptors,
+            handover: None,
         }
     }
 
@@ -62,10 +73,22 @@ impl Server {
         F: Future<Output = ()> + Send + 'static,
     {
         let addr: SocketAddr = addr.parse()?;
-        let listener = TcpListener::bind(addr).await?;
+        let listener = match &self.handover {
+            Some(handover) => {
+                let (listener, inherited) = handover.listen(addr)?;
+                if inherited {
+                    info!("Took over listener on {}", listener.local_addr()?);
+                }
+                listener
+            }
+            None => TcpListener::bind(addr).await?,
+        };
 
         info!("🚀 RustAPI server running on http://{}", addr);
 
+        // Each connection holds a receiver; the sender learns when all are gone
+        let (drain_tx, _) = watch::channel(());
+
         // Arc-wrap self for sharing across tasks
         let router = self.router;
         let layers = self.layers;
@@ -100,14 +123,24 @@ impl Server {
                     };
 
                     // Spawn connection handler as independent task
+                    let mut draining = drain_tx.subscribe();
                     tokio::spawn(async move {
-                        if let Err(err) = http1::Builder::new()
+                        let conn = http1::Builder::new()
                             .keep_alive(true)
                             .pipeline_flush(true) // Flush pipelined responses immediately
                             .serve_connection(io, conn_service)
-                            .with_upgrades()
-                            .await
-                        {
+                            .with_upgrades();
+                        tokio::pin!(conn);
+
+                        let result = tokio::select! {
+                            result = conn.as_mut() => result,
+                            Ok(()) = draining.changed() => {
+                                // Finish the current request, then close
+                                conn.as_mut().graceful_shutdown();
+                                conn.await
+                            }
+                        };
+                        if let Err(err) = result {
                             // Only log actual errors, not client disconnects
                             if !err.is_incomplete_message() {
                                 error!("Connection error: {}", err);
@@ -122,10 +155,90 @@ impl Server {
             }
         }
 
+        if let Some(handover) = &self.handover {
+            drop(listener);
+            let _ = drain_tx.send(());
+            let timeout = handover.drain_timeout_value();
+            if tokio::time::timeout(timeout, drain_tx.closed())
+                .await
+                .is_err()
+            {
+                warn!(
+                    "{} connections still open after {:?}; stopping anyway",
+                    drain_tx.receiver_count(),
+                    timeout
+                );
+            }
+        }
+
         Ok(())
     }
 }
 
+/// A prepared RustAPI application as a hyper service
+///
+/// Obtained via [`crate::RustApi::into_service`] for serving the app on a
+/// custom listener or connection builder (for example, sharing a port with
+/// another protocol). Cloning is cheap; use one clone per connection and set
+/// its peer address with [`with_remote_addr`](Self::with_remote_addr).
+#[derive(Clone)]
+pub struct RustApiService {
+    inner: ConnectionService,
+    shutdown_hooks: Arc<std::sync::Mutex<Vec<crate::events::LifecycleHook>>>,
+}
+
+impl RustApiService {
+    pub(crate) fn new(
+        router: Router,
+        layers: LayerStack,
+        interceptors: InterceptorChain,
+        shutdown_hooks: Vec<crate::events::LifecycleHook>,
+    ) -> Self {
+        Self {
+            inner: ConnectionService {
+                router: Arc::new(router),
+                layers: Arc::new(layers),
+                interceptors: Arc::new(interceptors),
+                remote_addr: SocketAddr::from(([0, 0, 0, 0], 0)),
+            },
+            shutdown_hooks: Arc::new(std::sync::Mutex::new(shutdown_hooks)),
+        }
+    }
+
+    /// Set the peer address of the connection this service handles
+    pub fn with_remote_addr(mut self, remote_addr: SocketAddr) -> Self {
+        self.inner.remote_addr = remote_addr;
+        self
+    }
+
+    /// Run the app's `on_shutdown` hooks
+    ///
+    /// Call this once the listener has stopped. Hooks run at most once across
+    /// all clones.
+    pub async fn shutdown(&self) {
+        let hooks = std::mem::take(
+            &mut *self
+                .shutdown_hooks
+                .lock()
+                .unwrap_or_else(|e| e.into_inner()),
+        );
+        for hook in hooks {
+            hook().await;
+        }
+    }
+}
+
+impl hyper::service::Service<hyper::Request<Incoming>> for RustApiService {
+    type Response = hyper::Response<Body>;
+    type Error = Infallible;
+    type Future = HandleRequestFuture;
+
+    #[inline]
+    fn call(&self, req: hyper::Request<Incoming>) -> Self::Future {
+        self.inner.call(req)
+    }
+}
+
 /// Connection-level service - avoids Arc cloning per request
 #[derive(Clone)]
 struct ConnectionService {
@@ -208,7 +321,7 @@ async fn handle_request(
     layers: Arc<LayerStack>,
     interceptors: Arc<InterceptorChain>,
     req: hyper::Request<Incoming>,
-    _remote_addr: SocketAddr,
+    remote_addr: SocketAddr,
 ) -> hyper::Response<Body> {
     // Extract method and path before consuming request
     // Clone method (cheap - just an enum) and path to owned string only when needed
@@ -242,7 +355,10 @@ async fn handle_request(
     };
 
     // Convert hyper request to our Request type
-    let (parts, body) = req.into_parts();
+    let (mut parts, body) = req.into_parts();
+    let accept = parts.headers.get(header::ACCEPT).cloned();
+    // Peer address, read by `ClientIp` and IP-based layers
+    parts.extensions.insert(remote_addr);
 
     // Build Request with empty path params (will be set after route matching)
     let request = Request::new(
@@ -284,6 +400,7 @@ async fn handle_request(
         let response = layers.execute(request, routing_handler).await;
         interceptors.intercept_response(response)
     };
+    let response = crate::error_format::negotiate_error_response(accept.as_ref(), response);
 
     #[cfg(feature = "tracing")]
     log_request(&method, &path, response.status(), start);
@@ -325,7 +442,7 @@ pub(crate) async fn route_request_direct(
             let allowed_str: Vec<&str> = allowed.iter().map(|m| m.as_str()).collect();
             let mut response = ApiError::new(
                 StatusCode::METHOD_NOT_ALLOWED,
-                "method_not_allowed",
+                crate::error::codes::METHOD_NOT_ALLOWED,
                 "Method not allowed",
             )
             .into_response();
@@ -345,7 +462,10 @@ pub(crate) async fn route_request(
     path: &str,
     method: &http::Method,
 ) -> hyper::Response<Body> {
-    match router.match_route(path, method) {
+    let mut timer = crate::middleware::PhaseTimer::start(&request);
+    let route = router.match_route(path, method);
+    timer.phase("routing");
+    match route {
         RouteMatch::Found { handler, params } => {
             request.set_path_params(params);
             handler(request).await
@@ -355,7 +475,7 @@ pub(crate) async fn route_request(
             let allowed_str: Vec<&str> = allowed.iter().map(|m| m.as_str()).collect();
             let mut response = ApiError::new(
                 StatusCode::METHOD_NOT_ALLOWED,
-                "method_not_allowed",
+                crate::error::codes::METHOD_NOT_ALLOWED,
                 "Method not allowed",
             )
             .into_response();
@@ -44,6 +44,29 @@
 //!             .text("ping"))
 //! }
 //! ```
+//!
+//! # Resuming with `Last-Event-ID`
+//!
+//! Browsers reconnect automatically and send the `id` of the last event they
+//! received in the `Last-Event-ID` header. The [`LastEventId`] extractor hands
+//! it to the handler so missed events can be replayed, and
+//! [`KeepAlive::retry`] tells clients how long to wait before reconnecting:
+//!
+//! ```rust,ignore
+//! use rustapi_core::sse::{KeepAlive, LastEventId, Sse, SseEvent};
+//! use std::time::Duration;
+//!
+//! async fn events(last_event_id: LastEventId) -> impl IntoResponse {
+//!     let since = last_event_id.parse::<u64>().unwrap_or(0);
+//!     let stream = futures_util::stream::iter(
+//!         store.events_after(since).map(|e| {
+//!             Ok::<_, std::convert::Infallible>(SseEvent::new(e.body).id(e.id.to_string()))
+//!         }),
+//!     );
+//!
+//!     Sse::new(stream).keep_alive(KeepAlive::new().retry(Duration::from_secs(3)))
+//! }
+//! ```
 
 use bytes::Bytes;
 use futures_util::Stream;
@@ -53,11 +76,19 @@ use pin_project_lite::pin_project;
 use rustapi_openapi::{MediaType, Operation, ResponseModifier, ResponseSpec, SchemaRef};
 use std::collections::BTreeMap;
 use std::fmt::Write;
+use std::future::Future;
 use std::pin::Pin;
+use std::sync::{Arc, OnceLock};
 use std::task::{Context, Poll};
-use std::time::Duration;
+use std::time::{Duration, Instant};
+use tokio::sync::broadcast;
 
+use crate::error::Result as ApiResult;
+use crate::extract::FromRequestParts;
+use crate::middleware::{BoxedNext, MiddlewareLayer};
+use crate::request::Request;
 use crate::response::{IntoResponse, Response};
+use rustapi_openapi::{OperationModifier, Parameter};
 
 /// A Server-Sent Event
 ///
@@ -80,6 +111,8 @@ pub struct SseEvent {
     pub retry: Option<u64>,
     /// Comment line
     comment: Option<String>,
+    /// When the event was created, for latency metrics
+    created: Option<Instant>,
 }
 
 impl SseEvent {
@@ -91,6 +124,7 @@ impl SseEvent {
             id: None,
             retry: None,
             comment: None,
+            created: Some(Instant::now()),
         }
     }
 
@@ -104,6 +138,7 @@ impl SseEvent {
             id: None,
             retry: None,
             comment: Some(text.into()),
+            created: Some(Instant::now()),
         }
     }
 
@@ -206,6 +241,8 @@ pub struct KeepAlive {
     interval: Duration,
     /// Text to send as keep-alive comment
     text: String,
+    /// Reconnection delay advertised when the stream starts
+    retry: Option<Duration>,
 }
 
 impl Default for KeepAlive {
@@ -213,6 +250,7 @@ impl Default for KeepAlive {
         Self {
             interval: Duration::from_secs(15),
             text: "keep-alive".to_string(),
+            retry: None,
         }
     }
 }
@@ -235,15 +273,144 @@ impl KeepAlive {
         self
     }
 
+    /// Set the reconnection delay sent to clients
+    ///
+    /// A `retry:` field is written as the first frame of the stream, so
+    /// clients that lose the connection wait this long before reconnecting
+    /// (with `Last-Event-ID`).
+    pub fn retry(mut self, retry: Duration) -> Self {
+        self.retry = Some(retry);
+        self
+    }
+
     /// Get the interval
     pub fn get_interval(&self) -> Duration {
         self.interval
     }
 
+    /// Get the reconnection delay, if configured
+    pub fn get_retry(&self) -> Option<Duration> {
+        self.retry
+    }
+
+    /// The `retry:` frame sent before the first event
+    fn retry_frame(&self) -> Option<Bytes> {
+        self.retry
+            .map(|retry| Bytes::from(format!("retry: {}\n\n", retry.as_millis())))
+    }
+
     /// Create the keep-alive event
     pub fn event(&self) -> SseEvent {
         SseEvent::comment(&self.text)
     }
+
+    /// Timer ticking at the keep-alive interval
+    fn timer(&self) -> tokio::time::Interval {
+        let mut interval = tokio::time::interval(self.interval);
+        interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+        interval
+    }
+}
+
+/// As a layer, `KeepAlive` sets the keep-alive of SSE responses that do not
+/// configure their own, e.g. per route:
+///
+/// ```rust,ignore
+/// get_route("/feed", feed).layer(KeepAlive::new().interval(Duration::from_secs(5)))
+/// ```
+///
+/// A route layer takes precedence over an app-wide one.
+impl MiddlewareLayer for KeepAlive {
+    fn call(
+        &self,
+        req: Request,
+        next: BoxedNext,
+    ) -> Pin<Box<dyn Future<Output = Response> + Send + 'static>> {
+        let keep_alive = self.clone();
+        Box::pin(async move {
+            let response = next(req).await;
+            if let Some(hooks) = response.extensions().get::<SseHooks>() {
+                hooks.default_keep_alive(keep_alive);
+            }
+            response
+        })
+    }
+
+    fn clone_box(&self) -> Box<dyn MiddlewareLayer> {
+        Box::new(self.clone())
+    }
+}
+
+/// Receives the lifecycle of an SSE connection (see `MetricsLayer`)
+#[cfg_attr(not(feature = "metrics"), allow(dead_code))]
+pub(crate) trait SseObserver: Send + Sync {
+    /// The connection was opened
+    fn opened(&self);
+    /// An event was sent, `latency` after it was created
+    fn sent(&self, latency: Option<Duration>);
+    /// `count` events were skipped for a subscriber that fell behind
+    fn dropped(&self, count: u64);
+    /// The connection was closed
+    fn closed(&self);
+}
+
+/// Hooks of an SSE response, stored in its extensions
+///
+/// Layers between the handler and the client set them before the stream is
+/// first polled.
+#[derive(Clone, Default)]
+pub(crate) struct SseHooks(Arc<Hooks>);
+
+#[derive(Default)]
+struct Hooks {
+    keep_alive: OnceLock<KeepAlive>,
+    observer: OnceLock<Arc<dyn SseObserver>>,
+}
+
+impl SseHooks {
+    /// Keep-alive for a response that does not configure one
+    pub(crate) fn default_keep_alive(&self, keep_alive: KeepAlive) {
+        let _ = self.0.keep_alive.set(keep_alive);
+    }
+
+    /// Report the connection to `observer`; the first observer wins
+    #[cfg_attr(not(feature = "metrics"), allow(dead_code))]
+    pub(crate) fn observe(&self, observer: Arc<dyn SseObserver>) {
+        if self.0.observer.set(observer.clone()).is_ok() {
+            observer.opened();
+        }
+    }
+
+    fn route_keep_alive(&self) -> Option<KeepAlive> {
+        self.0.keep_alive.get().cloned()
+    }
+
+    fn sent(&self, latency: Option<Duration>) {
+        if let Some(observer) = self.0.observer.get() {
+            observer.sent(latency);
+        }
+    }
+
+    fn dropped(&self, count: u64) {
+        if let Some(observer) = self.0.observer.get() {
+            observer.dropped(count);
+        }
+    }
+
+    fn closed(&self) {
+        if let Some(observer) = self.0.observer.get() {
+            observer.closed();
+        }
+    }
+}
+
+/// Reports the end of the connection when the stream is dropped
+struct Connection(SseHooks);
+
+impl Drop for Connection {
+    fn drop(&mut self) {
+        self.0.closed();
+    }
 }
 
 /// Server-Sent Events response wrapper
@@ -269,6 +436,7 @@ impl KeepAlive {
 pub struct Sse<S> {
     stream: S,
     keep_alive: Option<KeepAlive>,
+    hooks: SseHooks,
 }
 
 impl<S> Sse<S> {
@@ -277,12 +445,15 @@ impl<S> Sse<S> {
         Self {
             stream,
             keep_alive: None,
+            hooks: SseHooks::default(),
         }
     }
 
     /// Set the keep-alive configuration
     ///
     /// When set, the server will send periodic comments to keep the connection alive.
+    /// Overrides a keep-alive set for the route or app by adding [`KeepAlive`]
+    /// as a layer (e.g. `get_route("/feed", feed).layer(KeepAlive::new())`).
     ///
     /// # Example
     ///
@@ -311,8 +482,12 @@ pin_project! {
         #[pin]
         inner: S,
         keep_alive: Option<KeepAlive>,
+        // Frame to send before anything else (the `retry:` field)
+        prelude: Option<Bytes>,
+        started: bool,
         #[pin]
         keep_alive_timer: Option<tokio::time::Interval>,
+        connection: Connection,
     }
 }
 
@@ -323,11 +498,30 @@ where
     type Item = Result<Bytes, E>;
 
     fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
-        let this = self.project();
+        let mut this = self.project();
+
+        // Layers had their chance to set the route's keep-alive by now
+        if !*this.started {
+            *this.started = true;
+            if this.keep_alive.is_none() {
+                *this.keep_alive = this.connection.0.route_keep_alive();
+            }
+            if let Some(keep_alive) = this.keep_alive.as_ref() {
+                *this.prelude = keep_alive.retry_frame();
+                this.keep_alive_timer.set(Some(keep_alive.timer()));
+            }
+        }
+
+        if let Some(prelude) = this.prelude.take() {
+            return Poll::Ready(Some(Ok(prelude)));
+        }
 
         // First, check if there's an event ready from the inner stream
         match this.inner.poll_next(cx) {
             Poll::Ready(Some(Ok(event))) => {
+                this.connection
+                    .0
+                    .sent(event.created.map(|created| created.elapsed()));
                 return Poll::Ready(Some(Ok(event.to_bytes())));
             }
             Poll::Ready(Some(Err(e))) => {
@@ -362,16 +556,13 @@ where
     E: std::error::Error + Send + Sync + 'static,
 {
     fn into_response(self) -> Response {
-        let timer = self.keep_alive.as_ref().map(|k| {
-            let mut interval = tokio::time::interval(k.interval);
-            interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
-            interval
-        });
-
         let stream = SseStream {
             inner: self.stream,
             keep_alive: self.keep_alive,
-            keep_alive_timer: timer,
+            prelude: None,
+            started: false,
+            keep_alive_timer: None,
+            connection: Connection(self.hooks.clone()),
         };
 
         use futures_util::StreamExt;
@@ -379,14 +570,16 @@ where
             stream.map(|res| res.map_err(|e| crate::error::ApiError::internal(e.to_string())));
         let body = crate::response::Body::from_stream(stream);
 
-        http::Response::builder()
+        let mut response = http::Response::builder()
             .status(StatusCode::OK)
             .header(header::CONTENT_TYPE, "text/event-stream")
             .header(header::CACHE_CONTROL, "no-cache")
             .header(header::CONNECTION, "keep-alive")
             .header("X-Accel-Buffering", "no") // Disable nginx buffering
             .body(body)
-            .unwrap()
+            .unwrap();
+        response.extensions_mut().insert(self.hooks);
+        response
     }
 }
 
@@ -414,6 +607,76 @@ impl<S> ResponseModifier for Sse<S> {
     }
 }
 
+/// `Last-Event-ID` extractor for resuming SSE streams
+///
+/// Holds the `id` of the last event a reconnecting client received, or
+/// `None` on the first connection. Never rejects the request.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// use rustapi_core::sse::LastEventId;
+///
+/// async fn events(last_event_id: LastEventId) -> impl IntoResponse {
+///     match last_event_id.parse::<u64>() {
+///         Some(since) => replay_from(since),
+///         None => live_only(),
+///     }
+/// }
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LastEventId(pub Option<String>);
+
+impl LastEventId {
+    /// Header sent by reconnecting clients
+    pub const HEADER: &'static str = "last-event-id";
+
+    /// Get the last event ID, if the client sent one
+    pub fn get(&self) -> Option<&str> {
+        self.0.as_deref()
+    }
+
+    /// Parse the last event ID (e.g. as a numeric sequence)
+    ///
+    /// Returns `None` if the header is missing or does not parse.
+    pub fn parse<T: std::str::FromStr>(&self) -> Option<T> {
+        self.get()?.parse().ok()
+    }
+
+    /// Check whether the client is resuming a previous stream
+    pub fn is_resume(&self) -> bool {
+        self.0.is_some()
+    }
+}
+
+impl FromRequestParts for LastEventId {
+    fn from_request_parts(req: &Request) -> ApiResult<Self> {
+        let id = req
+            .headers()
+            .get(Self::HEADER)
+            .and_then(|value| value.to_str().ok())
+            .map(str::trim)
+            .filter(|value| !value.is_empty())
+            .map(str::to_string);
+        Ok(Self(id))
+    }
+}
+
+impl OperationModifier for LastEventId {
+    fn update_operation(op: &mut Operation) {
+        op.parameters.push(Parameter {
+            name: "Last-Event-ID".to_string(),
+            location: "header".to_string(),
+            description: Some("ID of the last event received, sent when reconnecting".to_string()),
+            required: false,
+            deprecated: None,
+            style: None,
+            explode: None,
+            schema: Some(SchemaRef::Inline(serde_json::json!({ "type": "string" }))),
+        });
+    }
+}
+
 /// Collect all SSE events from a stream into a single response body
 ///
 /// This is useful for testing or when you know the stream is finite.
@@ -469,6 +732,42 @@ where
         .unwrap()
 }
 
+/// Create an SSE response from a broadcast channel of events
+///
+/// Every client gets its own receiver (`sender.subscribe()`). A client that
+/// falls behind by more than the channel capacity skips the oldest events
+/// and carries on; `MetricsLayer` counts them as dropped. The stream ends
+/// when every sender is gone.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// use rustapi_core::sse::{sse_from_broadcast, KeepAlive};
+///
+/// async fn feed(State(events): State<broadcast::Sender<SseEvent>>) -> impl IntoResponse {
+///     sse_from_broadcast(events.subscribe()).keep_alive(KeepAlive::new())
+/// }
+/// ```
+pub fn sse_from_broadcast(
+    rx: broadcast::Receiver<SseEvent>,
+) -> Sse<impl Stream<Item = Result<SseEvent, std::convert::Infallible>> + Send + 'static> {
+    let hooks = SseHooks::default();
+    let stream = futures_util::stream::unfold((rx, hooks.clone()), |(mut rx, hooks)| async move {
+        loop {
+            match rx.recv().await {
+                Ok(event) => return Some((Ok(event), (rx, hooks))),
+                Err(broadcast::error::RecvError::Lagged(count)) => hooks.dropped(count),
+                Err(broadcast::error::RecvError::Closed) => return None,
+            }
+        }
+    });
+    Sse {
+        stream,
+        keep_alive: None,
+        hooks,
+    }
+}
+
 /// Helper function to create an SSE response from an iterator of events
 ///
 /// This is useful for simple cases where you have a fixed set of events.
@@ -567,6 +866,108 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn test_keep_alive_retry_is_sent_first() {
+        use futures_util::stream;
+        use http_body_util::BodyExt;
+
+        let events: Vec<Result<SseEvent, std::convert::Infallible>> =
+            vec![Ok(SseEvent::new("hello").id("7"))];
+        let response = Sse::new(stream::iter(events))
+            .keep_alive(KeepAlive::new().retry(Duration::from_secs(3)))
+            .into_response();
+
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        assert_eq!(body, "retry: 3000\n\nid: 7\ndata: hello\n\n");
+    }
+
+    #[tokio::test]
+    async fn test_keep_alive_layer_sets_the_route_default() {
+        use crate::middleware::LayerStack;
+        use futures_util::stream;
+        use http_body_util::BodyExt;
+
+        async fn first_frame(stack: &LayerStack, own: Option<KeepAlive>) -> Bytes {
+            let next: BoxedNext = Arc::new(move |_req: Request| {
+                let own = own.clone();
+                Box::pin(async move {
+                    let sse =
+                        Sse::new(stream::pending::<Result<SseEvent, std::convert::Infallible>>());
+                    match own {
+                        Some(keep_alive) => sse.keep_alive(keep_alive).into_response(),
+                        None => sse.into_response(),
+                    }
+                }) as Pin<Box<dyn Future<Output = Response> + Send + 'static>>
+            });
+            let req = http::Request::builder().uri("/feed").body(()).unwrap();
+            let response = stack
+                .execute(Request::from_http_request(req, Bytes::new()), next)
+                .await;
+            let mut body = response.into_body();
+            let frame = body.frame().await.unwrap().unwrap();
+            frame.into_data().unwrap()
+        }
+
+        // The inner (route) layer wins over the outer (app-wide) one
+        let mut stack = LayerStack::new();
+        stack.push(Box::new(KeepAlive::new().text("app")));
+        stack.push(Box::new(KeepAlive::new().text("route")));
+
+        assert_eq!(first_frame(&stack, None).await, ": route\n\n");
+        let own = KeepAlive::new().text("handler");
+        assert_eq!(first_frame(&stack, Some(own)).await, ": handler\n\n");
+    }
+
+    #[tokio::test]
+    async fn test_sse_from_broadcast_skips_missed_events() {
+        use http_body_util::BodyExt;
+
+        let (tx, rx) = broadcast::channel(2);
+        for i in 0..3 {
+            tx.send(SseEvent::new(i.to_string()).id(i.to_string()))
+                .unwrap();
+        }
+        drop(tx);
+
+        let body = sse_from_broadcast(rx)
+            .into_response()
+            .into_body()
+            .collect()
+            .await
+            .unwrap()
+            .to_bytes();
+        assert_eq!(body, "id: 1\ndata: 1\n\nid: 2\ndata: 2\n\n");
+    }
+
+    #[test]
+    fn test_last_event_id_extractor() {
+        fn request(last_event_id: Option<&str>) -> Request {
+            let mut builder = http::Request::builder();
+            if let Some(id) = last_event_id {
+                builder = builder.header("Last-Event-ID", id);
+            }
+            let (parts, _) = builder.body(()).unwrap().into_parts();
+            Request::new(
+                parts,
+                crate::request::BodyVariant::Buffered(Bytes::new()),
+                std::sync::Arc::new(http::Extensions::new()),
+                crate::path_params::PathParams::new(),
+            )
+        }
+
+        let resumed = LastEventId::from_request_parts(&request(Some("42"))).unwrap();
+        assert!(resumed.is_resume());
+        assert_eq!(resumed.get(), Some("42"));
+        assert_eq!(resumed.parse::<u64>(), Some(42));
+
+        let fresh = LastEventId::from_request_parts(&request(None)).unwrap();
+        assert_eq!(fresh, LastEventId(None));
+        assert_eq!(fresh.parse::<u64>(), None);
+
+        let blank = LastEventId::from_request_parts(&request(Some(" "))).unwrap();
+        assert!(!blank.is_resume());
+    }
+
     // **Feature: phase3-batteries-included, Property 20: SSE response format**
     //
     // For any stream of SseEvent items, `Sse<S>` SHALL produce a response with
@@ -0,0 +1,204 @@
+//! Startup report
+//!
+//! With [`RustApi::startup_report`](crate::RustApi::startup_report) enabled,
+//! the app reports what it is about to serve once it is ready to accept
+//! requests: the bound addresses, the environment, the enabled crate
+//! features, the middleware stack and the route table (including the routes
+//! added at startup, such as health endpoints).
+//!
+//! The format follows the [`Profile`](crate::Profile): a readable banner in
+//! development, and one JSON line in production, shaped like the other JSON
+//! log lines so log pipelines can index it.
+//!
+//! ```text
+//!   my-api v1.2.0 (rustapi 0.1.550, development)
+//!
+//!   Listening   http://127.0.0.1:3000
+//!   Features    swagger-ui, tracing
+//!   Middleware  BodyLimitLayer, RequestIdLayer
+//!
+//!   Routes (3)
+//!     GET     /health
+//!     GET     /users/{id}    get_user
+//!     POST    /users         create_user
+//! ```
+
+use crate::route_table::RouteTableEntry;
+use serde::Serialize;
+use std::fmt::Write as _;
+
+/// Log target of the startup report
+pub const STARTUP_TARGET: &str = "rustapi::startup";
+
+/// What an application serves, reported once at startup
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct StartupReport {
+    /// API title, from the OpenAPI info
+    pub name: String,
+    /// API version, from the OpenAPI info
+    pub version: String,
+    /// Version of RustAPI
+    pub rustapi_version: String,
+    /// `development` or `production`
+    pub environment: String,
+    /// Addresses the server listens on
+    pub addresses: Vec<String>,
+    /// Enabled `rustapi-core` features
+    pub features: Vec<String>,
+    /// Type names of the middleware layers, outermost first
+    pub middleware: Vec<String>,
+    /// Routes, sorted by path and method
+    pub routes: Vec<RouteTableEntry>,
+}
+
+impl StartupReport {
+    /// The banner printed in development
+    pub fn pretty(&self) -> String {
+        let mut out = String::new();
+        let _ = writeln!(
+            out,
+            "\n  {} v{} (rustapi {}, {})\n",
+            self.name, self.version, self.rustapi_version, self.environment
+        );
+        for address in &self.addresses {
+            let _ = writeln!(out, "  Listening   {}", url(address));
+        }
+        let _ = writeln!(out, "  Features    {}", list(&self.features));
+        let middleware: Vec<&str> = self.middleware.iter().map(|m| short_name(m)).collect();
+        let _ = writeln!(out, "  Middleware  {}", list(&middleware));
+
+        let _ = writeln!(out, "\n  Routes ({})", self.routes.len());
+        let width = self.routes.iter().map(|r| r.path.len()).max().unwrap_or(0);
+        for route in &self.routes {
+            let line = format!(
+                "    {:<7} {:<width$}  {}",
+                route.method,
+                route.path,
+                route.handler.as_deref().map(short_name).unwrap_or_default(),
+            );
+            let _ = writeln!(out, "{}", line.trim_end());
+        }
+        out
+    }
+
+    /// The JSON log line written in production
+    ///
+    /// `{"timestamp_ms", "level", "target", "message", "fields": {...}}`,
+    /// with the report as `fields`.
+    pub fn to_json(&self) -> serde_json::Value {
+        let timestamp = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|elapsed| elapsed.as_millis() as u64)
+            .unwrap_or_default();
+        serde_json::json!({
+            "timestamp_ms": timestamp,
+            "level": "INFO",
+            "target": STARTUP_TARGET,
+            "message": "Server starting",
+            "fields": self,
+        })
+    }
+
+    /// Write the report to stdout in the profile's format
+    pub(crate) fn emit(&self, json: bool) {
+        if json {
+            println!("{}", self.to_json());
+        } else {
+            println!("{}", self.pretty());
+        }
+    }
+}
+
+/// The `rustapi-core` features compiled in
+pub(crate) fn enabled_features() -> Vec<String> {
+    let features = [
+        ("swagger-ui", cfg!(feature = "swagger-ui")),
+        ("tracing", cfg!(feature = "tracing")),
+        ("cookies", cfg!(feature = "cookies")),
+        ("cookies-secure", cfg!(feature = "cookies-secure")),
+        ("legacy-validator", cfg!(feature = "legacy-validator")),
+        ("sqlx", cfg!(feature = "sqlx")),
+        ("metrics", cfg!(feature = "metrics")),
+        ("compression", cfg!(feature = "compression")),
+        ("compression-brotli", cfg!(feature = "compression-brotli")),
+        ("compression-zstd", cfg!(feature = "compression-zstd")),
+        ("simd-json", cfg!(feature = "simd-json")),
+        ("http3", cfg!(feature = "http3")),
+        ("http3-dev", cfg!(feature = "http3-dev")),
+        ("replay", cfg!(feature = "replay")),
+        ("dashboard", cfg!(feature = "dashboard")),
+    ];
+    features
+        .into_iter()
+        .filter(|(_, enabled)| *enabled)
+        .map(|(name, _)| name.to_string())
+        .collect()
+}
+
+/// `path::to::Type<Generics>` as `Type`
+fn short_name(name: &str) -> &str {
+    let path = name.split('<').next().unwrap_or(name);
+    path.rsplit("::").next().unwrap_or(path)
+}
+
+fn list<S: AsRef<str>>(items: &[S]) -> String {
+    if items.is_empty() {
+        return "none".to_string();
+    }
+    items
+        .iter()
+        .map(AsRef::as_ref)
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+fn url(address: &str) -> String {
+    if address.contains("://") {
+        address.to_string()
+    } else {
+        format!("http://{}", address)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn report() -> StartupReport {
+        StartupReport {
+            name: "orders".into(),
+            version: "2.1.0".into(),
+            rustapi_version: "0.1.0".into(),
+            environment: "production".into(),
+            addresses: vec!["0.0.0.0:8080".into()],
+            features: vec!["tracing".into()],
+            middleware: vec!["rustapi_core::middleware::body_limit::BodyLimitLayer".into()],
+            routes: vec![RouteTableEntry {
+                method: "GET".into(),
+                path: "/orders/{id}".into(),
+                handler: Some("orders::handlers::get_order".into()),
+                tags: vec!["orders".into()],
+                operation_id: None,
+            }],
+        }
+    }
+
+    #[test]
+    fn pretty_banner_lists_addresses_middleware_and_routes() {
+        let banner = report().pretty();
+        assert!(banner.contains("orders v2.1.0 (rustapi 0.1.0, production)"));
+        assert!(banner.contains("Listening   http://0.0.0.0:8080"));
+        assert!(banner.contains("Middleware  BodyLimitLayer\n"));
+        assert!(banner.contains("Routes (1)\n    GET     /orders/{id}  get_order\n"));
+    }
+
+    #[test]
+    fn json_line_carries_the_report_as_fields() {
+        let line = report().to_json();
+        assert_eq!(line["target"], STARTUP_TARGET);
+        assert_eq!(line["level"], "INFO");
+        assert_eq!(line["fields"]["addresses"][0], "0.0.0.0:8080");
+        assert_eq!(line["fields"]["routes"][0]["path"], "/orders/{id}");
+        assert!(!line.to_string().contains('\n'));
+    }
+}
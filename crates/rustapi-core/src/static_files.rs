@@ -19,10 +19,20 @@ use crate::response::{IntoResponse, Response};
 
 use http::{header, StatusCode};
 
+use std::collections::HashMap;
 use std::path::{Path, PathBuf};
+use std::sync::Arc;
 use std::time::SystemTime;
 use tokio::fs;
 
+/// Cache-Control for fingerprinted files: one year, never revalidated
+const IMMUTABLE_CACHE_CONTROL: &str = "public, max-age=31536000, immutable";
+
+/// Content codings looked up as sibling files (`app.js.br`), in the order
+/// preferred when the client accepts several with the same quality
+const PRECOMPRESSED_SIBLINGS: [(&str, &str); 3] =
+    [("br", ".br"), ("zstd", ".zst"), ("gzip", ".gz")];
+
 /// MIME type detection based on file extension
 fn mime_type_for_extension(extension: &str) -> &'static str {
     match extension.to_lowercase().as_str() {
@@ -167,6 +177,15 @@ pub struct StaticFileConfig {
     pub max_age: u64,
     /// Fallback file for SPA routing (e.g., "index.html")
     pub fallback: Option<String>,
+    /// Mark all responses `immutable` (for directories of fingerprinted files)
+    pub immutable: bool,
+    /// Fingerprinted paths (`app.3f2a9c1e.css`) mapped to the files they
+    /// serve (`app.css`); these get far-future cache headers
+    pub fingerprints: Option<Arc<HashMap<String, String>>>,
+    /// Serve `.br`/`.zst`/`.gz` siblings of a file to clients accepting them
+    pub precompressed: bool,
+    /// List directories without an index file (disabled by default)
+    pub directory_listing: Option<DirectoryListing>,
 }
 
 impl Default for StaticFileConfig {
@@ -180,6 +199,10 @@ impl Default for StaticFileConfig {
             last_modified: true,
             max_age: 3600, // 1 hour
             fallback: None,
+            immutable: false,
+            fingerprints: None,
+            precompressed: false,
+            directory_listing: None,
         }
     }
 }
@@ -229,6 +252,279 @@ impl StaticFileConfig {
         self.fallback = Some(file.into());
         self
     }
+
+    /// Add `immutable` to Cache-Control, for files whose names change with
+    /// their content
+    pub fn immutable(mut self, enabled: bool) -> Self {
+        self.immutable = enabled;
+        self
+    }
+
+    /// Serve fingerprinted paths from the files they map to, with
+    /// `max-age=31536000, immutable`
+    ///
+    /// Keys and values are relative to the root, e.g.
+    /// `"css/app.3f2a9c1e.css" -> "css/app.css"`. Other paths are served as usual.
+    pub fn fingerprints(mut self, fingerprints: HashMap<String, String>) -> Self {
+        self.fingerprints = Some(Arc::new(fingerprints));
+        self
+    }
+
+    /// Serve precompressed siblings (`app.js.br`, `app.js.zst`, `app.js.gz`)
+    /// when the client's `Accept-Encoding` allows it
+    ///
+    /// The uncompressed file must still exist; it is served to clients that
+    /// accept none of the available encodings. Responses carry
+    /// `Vary: Accept-Encoding`.
+    pub fn precompressed(mut self, enabled: bool) -> Self {
+        self.precompressed = enabled;
+        self
+    }
+
+    /// List the contents of directories that have no index file
+    ///
+    /// Only enable this for internal file-sharing endpoints; every file under
+    /// the root becomes discoverable.
+    pub fn directory_listing(mut self, listing: DirectoryListing) -> Self {
+        self.directory_listing = Some(listing);
+        self
+    }
+}
+
+/// Order of the entries in a directory listing
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ListingSort {
+    /// Alphabetical by name
+    #[default]
+    Name,
+    /// By modification time, oldest first
+    Modified,
+    /// By size, smallest first
+    Size,
+}
+
+/// Renders a directory index into an HTML page
+pub type ListingTemplate = Arc<dyn Fn(&DirectoryIndex) -> String + Send + Sync>;
+
+/// Directory listing options
+///
+/// # Example
+///
+/// ```rust,ignore
+/// use rustapi_core::static_files::{DirectoryListing, ListingSort, StaticFileConfig};
+///
+/// let config = StaticFileConfig::new("./shared", "/files")
+///     .directory_listing(DirectoryListing::new().sort(ListingSort::Modified).descending(true));
+/// ```
+#[derive(Clone, Default)]
+pub struct DirectoryListing {
+    /// Include dot-files and dot-directories
+    pub show_hidden: bool,
+    /// Sort key
+    pub sort: ListingSort,
+    /// Reverse the sort order
+    pub descending: bool,
+    /// List directories before files, regardless of the sort key
+    pub directories_first: bool,
+    /// Custom page renderer; the built-in HTML table is used when unset
+    pub template: Option<ListingTemplate>,
+}
+
+impl DirectoryListing {
+    /// Create listing options: sorted by name, directories first, hidden
+    /// entries filtered out
+    pub fn new() -> Self {
+        Self {
+            directories_first: true,
+            ..Default::default()
+        }
+    }
+
+    /// Include dot-files and dot-directories
+    pub fn show_hidden(mut self, enabled: bool) -> Self {
+        self.show_hidden = enabled;
+        self
+    }
+
+    /// Set the sort key
+    pub fn sort(mut self, sort: ListingSort) -> Self {
+        self.sort = sort;
+        self
+    }
+
+    /// Reverse the sort order
+    pub fn descending(mut self, enabled: bool) -> Self {
+        self.descending = enabled;
+        self
+    }
+
+    /// List directories before files
+    pub fn directories_first(mut self, enabled: bool) -> Self {
+        self.directories_first = enabled;
+        self
+    }
+
+    /// Render the page with a custom template
+    ///
+    /// The template is responsible for escaping names it puts into HTML.
+    pub fn template<F>(mut self, template: F) -> Self
+    where
+        F: Fn(&DirectoryIndex) -> String + Send + Sync + 'static,
+    {
+        self.template = Some(Arc::new(template));
+        self
+    }
+
+    /// Read, filter and sort the entries of `dir`
+    async fn read(&self, dir: &Path, url_path: String) -> Result<DirectoryIndex, ApiError> {
+        let mut reader = fs::read_dir(dir)
+            .await
+            .map_err(|e| ApiError::internal(format!("Failed to read directory: {}", e)))?;
+
+        let mut entries = Vec::new();
+        while let Some(entry) = reader
+            .next_entry()
+            .await
+            .map_err(|e| ApiError::internal(format!("Failed to read directory: {}", e)))?
+        {
+            let Ok(name) = entry.file_name().into_string() else {
+                continue;
+            };
+            if !self.show_hidden && name.starts_with('.') {
+                continue;
+            }
+            let Ok(metadata) = fs::metadata(entry.path()).await else {
+                continue;
+            };
+            let is_dir = metadata.is_dir();
+            entries.push(DirectoryEntry {
+                href: format!(
+                    "{}{}{}",
+                    url_path,
+                    percent_encode(&name),
+                    if is_dir { "/" } else { "" }
+                ),
+                name,
+                is_dir,
+                size: if is_dir { 0 } else { metadata.len() },
+                modified: metadata.modified().ok(),
+            });
+        }
+
+        entries.sort_by(|a, b| {
+            let order = match self.sort {
+                ListingSort::Name => a.name.cmp(&b.name),
+                ListingSort::Modified => a.modified.cmp(&b.modified),
+                ListingSort::Size => a.size.cmp(&b.size),
+            }
+            .then_with(|| a.name.cmp(&b.name));
+            let order = if self.descending {
+                order.reverse()
+            } else {
+                order
+            };
+            if self.directories_first {
+                b.is_dir.cmp(&a.is_dir).then(order)
+            } else {
+                order
+            }
+        });
+
+        Ok(DirectoryIndex {
+            path: url_path,
+            parent: None,
+            entries,
+        })
+    }
+}
+
+/// A listed directory, as passed to listing templates
+#[derive(Debug, Clone)]
+pub struct DirectoryIndex {
+    /// Request path of the directory, with a trailing slash
+    pub path: String,
+    /// Request path of the parent directory, unless this is the root
+    pub parent: Option<String>,
+    /// Filtered and sorted entries
+    pub entries: Vec<DirectoryEntry>,
+}
+
+/// A file or subdirectory in a directory listing
+#[derive(Debug, Clone)]
+pub struct DirectoryEntry {
+    /// File name
+    pub name: String,
+    /// Absolute, percent-encoded link to the entry
+    pub href: String,
+    /// Whether the entry is a directory
+    pub is_dir: bool,
+    /// Size in bytes (0 for directories)
+    pub size: u64,
+    /// Last modification time, when the platform reports it
+    pub modified: Option<SystemTime>,
+}
+
+/// Built-in listing page: a plain HTML table
+fn render_listing(index: &DirectoryIndex) -> String {
+    let title = escape_html(&index.path);
+    let mut rows = String::new();
+    if let Some(parent) = &index.parent {
+        rows.push_str(&format!(
+            "<tr><td><a href=\"{}\">../</a></td><td></td><td></td></tr>\n",
+            escape_html(parent)
+        ));
+    }
+    for entry in &index.entries {
+        let name = escape_html(&entry.name);
+        let suffix = if entry.is_dir { "/" } else { "" };
+        let size = if entry.is_dir {
+            "-".to_string()
+        } else {
+            entry.size.to_string()
+        };
+        let modified = entry.modified.map(format_http_date).unwrap_or_default();
+        rows.push_str(&format!(
+            "<tr><td><a href=\"{}\">{}{}</a></td><td>{}</td><td>{}</td></tr>\n",
+            escape_html(&entry.href),
+            name,
+            suffix,
+            size,
+            modified
+        ));
+    }
+    format!(
+        "<!DOCTYPE html>\n<html>\n<head><meta charset=\"utf-8\"><title>Index of {title}</title></head>\n\
+         <body>\n<h1>Index of {title}</h1>\n<table>\n\
+         <tr><th>Name</th><th>Size</th><th>Modified</th></tr>\n{rows}</table>\n</body>\n</html>\n"
+    )
+}
+
+fn escape_html(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Percent-encode a path segment, keeping RFC 3986 unreserved characters
+fn percent_encode(segment: &str) -> String {
+    let mut encoded = String::with_capacity(segment.len());
+    for byte in segment.bytes() {
+        if byte.is_ascii_alphanumeric() || matches!(byte, b'-' | b'.' | b'_' | b'~') {
+            encoded.push(byte as char);
+        } else {
+            encoded.push_str(&format!("%{:02X}", byte));
+        }
+    }
+    encoded
 }
 
 /// Static file response
@@ -252,9 +548,34 @@ impl StaticFile {
     pub async fn serve(
         relative_path: &str,
         config: &StaticFileConfig,
+    ) -> Result<Response, ApiError> {
+        Self::serve_encoded(relative_path, config, None).await
+    }
+
+    /// Serve a file from a path relative to the root, picking a precompressed
+    /// sibling allowed by `accept_encoding` when the config enables them
+    pub async fn serve_encoded(
+        relative_path: &str,
+        config: &StaticFileConfig,
+        accept_encoding: Option<&str>,
     ) -> Result<Response, ApiError> {
         // Sanitize path to prevent directory traversal
         let clean_path = sanitize_path(relative_path);
+
+        if let Some(file) = config
+            .fingerprints
+            .as_ref()
+            .and_then(|fingerprints| fingerprints.get(&clean_path))
+        {
+            let mut response =
+                Self::serve_file(&config.root.join(file), config, accept_encoding).await?;
+            response.headers_mut().insert(
+                header::CACHE_CONTROL,
+                header::HeaderValue::from_static(IMMUTABLE_CACHE_CONTROL),
+            );
+            return Ok(response);
+        }
+
         let file_path = config.root.join(&clean_path);
 
         // Check if it's a directory
@@ -262,26 +583,64 @@ impl StaticFile {
             if config.serve_index {
                 let index_path = file_path.join(&config.index_file);
                 if index_path.exists() {
-                    return Self::serve_file(&index_path, config).await;
+                    return Self::serve_file(&index_path, config, accept_encoding).await;
                 }
             }
+            if let Some(listing) = &config.directory_listing {
+                return Self::serve_listing(listing, &file_path, &clean_path, config).await;
+            }
             return Err(ApiError::not_found("Directory listing not allowed"));
         }
 
         // Try to serve the file
-        match Self::serve_file(&file_path, config).await {
+        match Self::serve_file(&file_path, config, accept_encoding).await {
             Ok(response) => Ok(response),
             Err(_) if config.fallback.is_some() => {
                 // Try fallback
                 let fallback_path = config.root.join(config.fallback.as_ref().unwrap());
-                Self::serve_file(&fallback_path, config).await
+                Self::serve_file(&fallback_path, config, accept_encoding).await
             }
             Err(e) => Err(e),
         }
     }
 
+    /// Render the listing of a directory without an index file
+    async fn serve_listing(
+        listing: &DirectoryListing,
+        dir: &Path,
+        clean_path: &str,
+        config: &StaticFileConfig,
+    ) -> Result<Response, ApiError> {
+        let mut url_path = config.prefix.trim_end_matches('/').to_string();
+        let mut parent = None;
+        for segment in clean_path.split('/').filter(|s| !s.is_empty()) {
+            parent = Some(format!("{}/", url_path));
+            url_path.push('/');
+            url_path.push_str(&percent_encode(segment));
+        }
+        url_path.push('/');
+
+        let mut index = listing.read(dir, url_path).await?;
+        index.parent = parent;
+        let page = match &listing.template {
+            Some(template) => template(&index),
+            None => render_listing(&index),
+        };
+
+        http::Response::builder()
+            .status(StatusCode::OK)
+            .header(header::CONTENT_TYPE, "text/html; charset=utf-8")
+            .header(header::CACHE_CONTROL, "no-cache")
+            .body(crate::response::Body::from(page))
+            .map_err(|e| ApiError::internal(format!("Failed to build response: {}", e)))
+    }
+
     /// Serve a specific file
-    async fn serve_file(path: &Path, config: &StaticFileConfig) -> Result<Response, ApiError> {
+    async fn serve_file(
+        path: &Path,
+        config: &StaticFileConfig,
+        accept_encoding: Option<&str>,
+    ) -> Result<Response, ApiError> {
         // Check if file exists
         let metadata = fs::metadata(path)
             .await
@@ -291,12 +650,26 @@ impl StaticFile {
             return Err(ApiError::not_found("Not a file"));
         }
 
+        // Prefer a precompressed sibling the client accepts
+        let precompressed = match accept_encoding {
+            Some(accept_encoding) if config.precompressed => {
+                find_precompressed(path, accept_encoding).await
+            }
+            _ => None,
+        };
+        let (content_path, metadata, encoding) = match precompressed {
+            Some((sibling, sibling_metadata, encoding)) => {
+                (sibling, sibling_metadata, Some(encoding))
+            }
+            None => (path.to_path_buf(), metadata, None),
+        };
+
         // Read file
-        let content = fs::read(path)
+        let content = fs::read(&content_path)
             .await
             .map_err(|e| ApiError::internal(format!("Failed to read file: {}", e)))?;
 
-        // Determine content type
+        // Determine content type from the original file, not the sibling
         let extension = path.extension().and_then(|e| e.to_str()).unwrap_or("");
         let content_type = mime_type_for_extension(extension);
 
@@ -306,6 +679,13 @@ impl StaticFile {
             .header(header::CONTENT_TYPE, content_type)
             .header(header::CONTENT_LENGTH, content.len());
 
+        if let Some(encoding) = encoding {
+            builder = builder.header(header::CONTENT_ENCODING, encoding);
+        }
+        if config.precompressed {
+            builder = builder.header(header::VARY, "accept-encoding");
+        }
+
         // Add ETag
         if config.etag {
             if let Ok(modified) = metadata.modified() {
@@ -324,9 +704,10 @@ impl StaticFile {
 
         // Add Cache-Control
         if config.max_age > 0 {
+            let immutable = if config.immutable { ", immutable" } else { "" };
             builder = builder.header(
                 header::CACHE_CONTROL,
-                format!("public, max-age={}", config.max_age),
+                format!("public, max-age={}{}", config.max_age, immutable),
             );
         }
 
@@ -336,6 +717,55 @@ impl StaticFile {
     }
 }
 
+/// Find the best precompressed sibling of `path` allowed by `accept_encoding`
+async fn find_precompressed(
+    path: &Path,
+    accept_encoding: &str,
+) -> Option<(PathBuf, std::fs::Metadata, &'static str)> {
+    let mut candidates: Vec<(f32, &'static str, &'static str)> = PRECOMPRESSED_SIBLINGS
+        .iter()
+        .map(|&(coding, suffix)| (encoding_quality(accept_encoding, coding), coding, suffix))
+        .filter(|(quality, _, _)| *quality > 0.0)
+        .collect();
+    // Stable sort keeps the preference order among equal qualities
+    candidates.sort_by(|a, b| b.0.total_cmp(&a.0));
+
+    for (_, coding, suffix) in candidates {
+        let mut sibling = path.as_os_str().to_owned();
+        sibling.push(suffix);
+        let sibling = PathBuf::from(sibling);
+        if let Ok(metadata) = fs::metadata(&sibling).await {
+            if metadata.is_file() {
+                return Some((sibling, metadata, coding));
+            }
+        }
+    }
+    None
+}
+
+/// Quality the client gives `coding` in an `Accept-Encoding` header
+///
+/// An explicit entry wins over `*`; codings not mentioned get 0.
+fn encoding_quality(accept_encoding: &str, coding: &str) -> f32 {
+    let mut wildcard = 0.0;
+    for part in accept_encoding.split(',') {
+        let mut params = part.split(';');
+        let name = params.next().unwrap_or("").trim();
+        let quality = params
+            .find_map(|param| param.trim().strip_prefix("q="))
+            .and_then(|q| q.trim().parse::<f32>().ok())
+            .unwrap_or(1.0);
+
+        if name.eq_ignore_ascii_case(coding) {
+            return quality;
+        }
+        if name == "*" {
+            wildcard = quality;
+        }
+    }
+    wildcard
+}
+
 /// Sanitize a file path to prevent directory traversal
 fn sanitize_path(path: &str) -> String {
     // Remove leading slashes
@@ -370,12 +800,19 @@ pub fn static_handler(
     move |req: crate::Request| {
         let config = config.clone();
         let path = req.uri().path().to_string();
+        let accept_encoding = req
+            .headers()
+            .get(header::ACCEPT_ENCODING)
+            .and_then(|v| v.to_str().ok())
+            .map(str::to_string);
 
         Box::pin(async move {
             // Strip prefix from path
             let relative_path = path.strip_prefix(&config.prefix).unwrap_or(&path);
 
-            match StaticFile::serve(relative_path, &config).await {
+            match StaticFile::serve_encoded(relative_path, &config, accept_encoding.as_deref())
+                .await
+            {
                 Ok(response) => response,
                 Err(err) => err.into_response(),
             }
@@ -464,6 +901,150 @@ mod tests {
         assert_eq!(config.fallback, Some("index.html".to_string()));
     }
 
+    #[tokio::test]
+    async fn test_fingerprinted_files_are_immutable() {
+        let root = std::env::temp_dir().join(format!("rustapi-static-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&root).unwrap();
+        std::fs::write(root.join("app.css"), "body {}").unwrap();
+
+        let config = StaticFileConfig::new(&root, "/assets").fingerprints(HashMap::from([(
+            "app.3f2a9c1e.css".to_string(),
+            "app.css".to_string(),
+        )]));
+
+        let hashed = StaticFile::serve("app.3f2a9c1e.css", &config)
+            .await
+            .unwrap();
+        assert_eq!(
+            hashed.headers()[header::CACHE_CONTROL],
+            IMMUTABLE_CACHE_CONTROL
+        );
+        assert_eq!(hashed.headers()[header::CONTENT_LENGTH], "7");
+
+        let plain = StaticFile::serve("app.css", &config).await.unwrap();
+        assert_eq!(
+            plain.headers()[header::CACHE_CONTROL],
+            "public, max-age=3600"
+        );
+
+        let immutable = StaticFile::serve("app.css", &config.clone().immutable(true))
+            .await
+            .unwrap();
+        assert_eq!(
+            immutable.headers()[header::CACHE_CONTROL],
+            "public, max-age=3600, immutable"
+        );
+
+        std::fs::remove_dir_all(&root).ok();
+    }
+
+    #[test]
+    fn test_encoding_quality() {
+        assert_eq!(encoding_quality("gzip, br", "br"), 1.0);
+        assert_eq!(encoding_quality("gzip;q=0.5, br;q=0", "br"), 0.0);
+        assert_eq!(encoding_quality("gzip;q=0.5, br;q=0", "gzip"), 0.5);
+        assert_eq!(encoding_quality("*;q=0.2", "zstd"), 0.2);
+        assert_eq!(encoding_quality("*, gzip;q=0", "gzip"), 0.0);
+        assert_eq!(encoding_quality("deflate", "gzip"), 0.0);
+    }
+
+    #[tokio::test]
+    async fn test_precompressed_siblings() {
+        let root = std::env::temp_dir().join(format!("rustapi-static-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&root).unwrap();
+        std::fs::write(root.join("app.js"), "console.log('hello');").unwrap();
+        std::fs::write(root.join("app.js.br"), "brotli").unwrap();
+        std::fs::write(root.join("app.js.gz"), "gz").unwrap();
+
+        let config = StaticFileConfig::new(&root, "/assets").precompressed(true);
+
+        let br = StaticFile::serve_encoded("app.js", &config, Some("gzip, br"))
+            .await
+            .unwrap();
+        assert_eq!(br.headers()[header::CONTENT_ENCODING], "br");
+        assert_eq!(br.headers()[header::CONTENT_LENGTH], "6");
+        assert_eq!(
+            br.headers()[header::CONTENT_TYPE],
+            "text/javascript; charset=utf-8"
+        );
+        assert_eq!(br.headers()[header::VARY], "accept-encoding");
+
+        let gzip = StaticFile::serve_encoded("app.js", &config, Some("br;q=0, gzip, zstd"))
+            .await
+            .unwrap();
+        assert_eq!(gzip.headers()[header::CONTENT_ENCODING], "gzip");
+        assert_ne!(gzip.headers()[header::ETAG], br.headers()[header::ETAG]);
+
+        let identity = StaticFile::serve_encoded("app.js", &config, Some("deflate"))
+            .await
+            .unwrap();
+        assert!(!identity.headers().contains_key(header::CONTENT_ENCODING));
+        assert_eq!(identity.headers()[header::CONTENT_LENGTH], "21");
+        assert_eq!(identity.headers()[header::VARY], "accept-encoding");
+
+        let disabled =
+            StaticFile::serve_encoded("app.js", &config.clone().precompressed(false), Some("br"))
+                .await
+                .unwrap();
+        assert!(!disabled.headers().contains_key(header::CONTENT_ENCODING));
+        assert!(!disabled.headers().contains_key(header::VARY));
+
+        std::fs::remove_dir_all(&root).ok();
+    }
+
+    #[tokio::test]
+    async fn test_directory_listing() {
+        use http_body_util::BodyExt;
+
+        let root = std::env::temp_dir().join(format!("rustapi-static-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(root.join("docs/sub dir")).unwrap();
+        std::fs::write(root.join("docs/b.txt"), "bb").unwrap();
+        std::fs::write(root.join("docs/a<1>.txt"), "aaaa").unwrap();
+        std::fs::write(root.join("docs/.secret"), "x").unwrap();
+
+        let config = StaticFileConfig::new(&root, "/files");
+        assert!(StaticFile::serve("docs", &config).await.is_err());
+
+        let body = |response: Response| async move {
+            let bytes = response.into_body().collect().await.unwrap().to_bytes();
+            String::from_utf8(bytes.to_vec()).unwrap()
+        };
+
+        let listed = config.clone().directory_listing(DirectoryListing::new());
+        let page = body(StaticFile::serve("docs", &listed).await.unwrap()).await;
+        assert!(page.contains("Index of /files/docs/"));
+        assert!(page.contains("href=\"/files/\">../</a>"));
+        assert!(page.contains("href=\"/files/docs/a%3C1%3E.txt\">a&lt;1&gt;.txt</a>"));
+        assert!(page.contains("href=\"/files/docs/sub%20dir/\""));
+        assert!(!page.contains(".secret"));
+        let (sub, a, b) = (
+            page.find("sub dir").unwrap(),
+            page.find("a&lt;1").unwrap(),
+            page.find("b.txt").unwrap(),
+        );
+        assert!(sub < a && a < b);
+
+        let by_size = config.clone().directory_listing(
+            DirectoryListing::new()
+                .show_hidden(true)
+                .directories_first(false)
+                .sort(ListingSort::Size)
+                .descending(true)
+                .template(|index| {
+                    index
+                        .entries
+                        .iter()
+                        .map(|e| e.name.as_str())
+                        .collect::<Vec<_>>()
+                        .join(",")
+                }),
+        );
+        let page = body(StaticFile::serve("docs", &by_size).await.unwrap()).await;
+        assert_eq!(page, "a<1>.txt,b.txt,.secret,sub dir");
+
+        std::fs::remove_dir_all(&root).ok();
+    }
+
     #[test]
     fn test_is_leap_year() {
         assert!(is_leap_year(2000)); // Divisible by 400
@@ -21,7 +21,9 @@
 use bytes::Bytes;
 use futures_util::Stream;
 use http::{header, StatusCode};
+use std::sync::Arc;
 
+use crate::error::ApiError;
 use crate::response::{IntoResponse, Response};
 
 /// A streaming body wrapper for HTTP responses
@@ -180,6 +182,77 @@ mod tests {
 
         assert_eq!(response.status(), StatusCode::OK);
     }
+
+    #[tokio::test]
+    async fn test_streaming_body_stops_after_limit() {
+        use futures_util::StreamExt;
+
+        let chunks: Vec<Result<Bytes, ApiError>> = vec![
+            Ok(Bytes::from("1234")),
+            Ok(Bytes::from("5678")),
+            Ok(Bytes::from("9")),
+        ];
+        let config = StreamingConfig::new()
+            .max_body_size(6)
+            .on_limit_exceeded(|limit| {
+                ApiError::new(
+                    StatusCode::PAYLOAD_TOO_LARGE,
+                    "upload_too_large",
+                    format!("Uploads are limited to {} bytes", limit),
+                )
+            });
+        let mut body = StreamingBody::from_stream(stream::iter(chunks), None).with_config(&config);
+
+        assert!(body.next().await.unwrap().is_ok());
+        let error = body.next().await.unwrap().unwrap_err();
+        assert_eq!(error.error_type, "upload_too_large");
+        assert!(body.next().await.is_none());
+        assert_eq!(body.bytes_read(), 8);
+    }
+
+    #[tokio::test]
+    async fn test_streaming_body_reports_progress() {
+        use futures_util::StreamExt;
+        use std::sync::Mutex;
+
+        let seen = Arc::new(Mutex::new(Vec::new()));
+        let recorder = seen.clone();
+        let config = StreamingConfig::new().on_progress(move |progress| {
+            recorder.lock().unwrap().push(progress.fraction().unwrap());
+        });
+
+        let chunks: Vec<Result<Bytes, ApiError>> =
+            vec![Ok(Bytes::from("ab")), Ok(Bytes::from("cd"))];
+        let mut body = StreamingBody::from_stream(stream::iter(chunks), None)
+            .with_config(&config)
+            .with_content_length(Some(4));
+
+        while let Some(chunk) = body.next().await {
+            chunk.unwrap();
+        }
+        assert_eq!(*seen.lock().unwrap(), vec![0.5, 1.0]);
+        assert_eq!(
+            body.progress(),
+            BodyProgress {
+                bytes_read: 4,
+                content_length: Some(4)
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn test_streaming_body_cancel() {
+        use futures_util::StreamExt;
+
+        let chunks: Vec<Result<Bytes, ApiError>> =
+            vec![Ok(Bytes::from("ab")), Ok(Bytes::from("cd"))];
+        let mut body = StreamingBody::from_stream(stream::iter(chunks), None);
+
+        assert!(body.next().await.unwrap().is_ok());
+        body.cancel();
+        assert!(body.next().await.is_none());
+        assert_eq!(body.bytes_read(), 2);
+    }
 }
 
 #[cfg(test)]
@@ -536,28 +609,142 @@ mod property_tests {
     }
 }
 
+/// Called with the running byte count as a streamed request body is read
+pub type ProgressCallback = Arc<dyn Fn(BodyProgress) + Send + Sync>;
+
+/// Builds the error returned once a streamed body exceeds its limit
+pub type LimitErrorFn = Arc<dyn Fn(usize) -> ApiError + Send + Sync>;
+
+/// Progress of a streamed request body
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BodyProgress {
+    /// Bytes received so far
+    pub bytes_read: usize,
+    /// Declared `Content-Length`, if the client sent one
+    pub content_length: Option<u64>,
+}
+
+impl BodyProgress {
+    /// Fraction of the declared body received so far, between 0.0 and 1.0
+    pub fn fraction(&self) -> Option<f64> {
+        match self.content_length {
+            Some(0) => Some(1.0),
+            Some(total) => Some((self.bytes_read as f64 / total as f64).min(1.0)),
+            None => None,
+        }
+    }
+}
+
 /// Configuration for streaming request bodies
-#[derive(Debug, Clone, Copy)]
+///
+/// Put it in app state to configure the [`BodyStream`](crate::BodyStream)
+/// extractor:
+///
+/// ```rust,ignore
+/// use rustapi_core::StreamingConfig;
+///
+/// RustApi::new().state(
+///     StreamingConfig::new()
+///         .max_body_size(512 * 1024 * 1024)
+///         .on_progress(|p| tracing::debug!(bytes = p.bytes_read, "upload progress")),
+/// );
+/// ```
+#[derive(Clone)]
 pub struct StreamingConfig {
     /// Maximum total body size in bytes
     pub max_body_size: Option<usize>,
+    /// Callback invoked after every received chunk
+    pub progress: Option<ProgressCallback>,
+    /// Custom error for bodies over the limit (default: 413 `payload_too_large`)
+    pub limit_error: Option<LimitErrorFn>,
 }
 
 impl Default for StreamingConfig {
     fn default() -> Self {
         Self {
             max_body_size: Some(10 * 1024 * 1024), // 10MB default
+            progress: None,
+            limit_error: None,
         }
     }
 }
 
+impl std::fmt::Debug for StreamingConfig {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("StreamingConfig")
+            .field("max_body_size", &self.max_body_size)
+            .field("progress", &self.progress.is_some())
+            .field("limit_error", &self.limit_error.is_some())
+            .finish()
+    }
+}
+
+impl StreamingConfig {
+    /// Create a configuration with the default 10MB limit
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the maximum total body size in bytes
+    pub fn max_body_size(mut self, bytes: usize) -> Self {
+        self.max_body_size = Some(bytes);
+        self
+    }
+
+    /// Remove the size limit
+    pub fn unlimited(mut self) -> Self {
+        self.max_body_size = None;
+        self
+    }
+
+    /// Report progress after every received chunk
+    pub fn on_progress<F>(mut self, callback: F) -> Self
+    where
+        F: Fn(BodyProgress) + Send + Sync + 'static,
+    {
+        self.progress = Some(Arc::new(callback));
+        self
+    }
+
+    /// Replace the 413 error returned when the limit is exceeded
+    ///
+    /// The closure receives the configured limit in bytes.
+    pub fn on_limit_exceeded<F>(mut self, error: F) -> Self
+    where
+        F: Fn(usize) -> ApiError + Send + Sync + 'static,
+    {
+        self.limit_error = Some(Arc::new(error));
+        self
+    }
+
+    /// The error for a body over `limit` bytes
+    pub(crate) fn limit_exceeded(&self, limit: usize) -> ApiError {
+        match &self.limit_error {
+            Some(error) => error(limit),
+            None => payload_too_large(limit),
+        }
+    }
+}
+
+fn payload_too_large(limit: usize) -> ApiError {
+    ApiError::new(
+        StatusCode::PAYLOAD_TOO_LARGE,
+        crate::error::codes::PAYLOAD_TOO_LARGE,
+        format!("Body size exceeded limit of {} bytes", limit),
+    )
+}
+
 /// A streaming request body wrapper
 ///
 /// Wraps the incoming hyper body stream or a generic stream and enforces limits.
+/// Once the limit is exceeded the stream yields the error and then ends, so the
+/// rest of the body is never read.
 pub struct StreamingBody {
     inner: StreamingInner,
     bytes_read: usize,
-    limit: Option<usize>,
+    config: StreamingConfig,
+    content_length: Option<u64>,
+    finished: bool,
 }
 
 enum StreamingInner {
@@ -576,11 +763,7 @@ enum StreamingInner {
 impl StreamingBody {
     /// Create a new StreamingBody from hyper Incoming
     pub fn new(inner: hyper::body::Incoming, limit: Option<usize>) -> Self {
-        Self {
-            inner: StreamingInner::Hyper(inner),
-            bytes_read: 0,
-            limit,
-        }
+        Self::with_inner(StreamingInner::Hyper(inner), limit)
     }
 
     /// Create from a generic stream
@@ -591,17 +774,72 @@ impl StreamingBody {
             + Sync
             + 'static,
     {
+        Self::with_inner(StreamingInner::Generic(Box::pin(stream)), limit)
+    }
+
+    fn with_inner(inner: StreamingInner, limit: Option<usize>) -> Self {
         Self {
-            inner: StreamingInner::Generic(Box::pin(stream)),
+            inner,
             bytes_read: 0,
-            limit,
+            config: StreamingConfig {
+                max_body_size: limit,
+                progress: None,
+                limit_error: None,
+            },
+            content_length: None,
+            finished: false,
         }
     }
 
+    /// Apply the limit, progress callback and limit error of `config`
+    pub fn with_config(mut self, config: &StreamingConfig) -> Self {
+        self.config = config.clone();
+        self
+    }
+
+    /// Record the declared `Content-Length`, reported in [`BodyProgress`]
+    pub fn with_content_length(mut self, content_length: Option<u64>) -> Self {
+        self.content_length = content_length;
+        self
+    }
+
     /// Get the number of bytes read so far
     pub fn bytes_read(&self) -> usize {
         self.bytes_read
     }
+
+    /// Size limit in bytes, if any
+    pub fn limit(&self) -> Option<usize> {
+        self.config.max_body_size
+    }
+
+    /// Current progress
+    pub fn progress(&self) -> BodyProgress {
+        BodyProgress {
+            bytes_read: self.bytes_read,
+            content_length: self.content_length,
+        }
+    }
+
+    /// Stop reading; later polls return `None`
+    pub fn cancel(&mut self) {
+        self.finished = true;
+    }
+
+    /// Count a received chunk, enforcing the limit and reporting progress
+    fn accept(&mut self, data: Bytes) -> Result<Bytes, ApiError> {
+        self.bytes_read += data.len();
+        if let Some(limit) = self.config.max_body_size {
+            if self.bytes_read > limit {
+                self.finished = true;
+                return Err(self.config.limit_exceeded(limit));
+            }
+        }
+        if let Some(progress) = &self.config.progress {
+            progress(self.progress());
+        }
+        Ok(data)
+    }
 }
 
 impl Stream for StreamingBody {
@@ -612,60 +850,36 @@ impl Stream for StreamingBody {
         cx: &mut std::task::Context<'_>,
     ) -> std::task::Poll<Option<Self::Item>> {
         use hyper::body::Body;
+        use std::task::Poll;
 
-        match &mut self.inner {
-            StreamingInner::Hyper(incoming) => {
-                loop {
-                    match std::pin::Pin::new(&mut *incoming).poll_frame(cx) {
-                        std::task::Poll::Ready(Some(Ok(frame))) => {
-                            if let Ok(data) = frame.into_data() {
-                                let len = data.len();
-                                self.bytes_read += len;
-                                if let Some(limit) = self.limit {
-                                    if self.bytes_read > limit {
-                                        return std::task::Poll::Ready(Some(Err(
-                                            crate::error::ApiError::new(
-                                                StatusCode::PAYLOAD_TOO_LARGE,
-                                                "payload_too_large",
-                                                format!(
-                                                    "Body size exceeded limit of {} bytes",
-                                                    limit
-                                                ),
-                                            ),
-                                        )));
-                                    }
-                                }
-                                return std::task::Poll::Ready(Some(Ok(data)));
-                            }
-                            continue; // Trailer
-                        }
-                        std::task::Poll::Ready(Some(Err(e))) => {
-                            return std::task::Poll::Ready(Some(Err(
-                                crate::error::ApiError::bad_request(e.to_string()),
-                            )));
+        if self.finished {
+            return Poll::Ready(None);
+        }
+
+        let data = match &mut self.inner {
+            StreamingInner::Hyper(incoming) => loop {
+                match std::pin::Pin::new(&mut *incoming).poll_frame(cx) {
+                    Poll::Ready(Some(Ok(frame))) => {
+                        if let Ok(data) = frame.into_data() {
+                            break data;
                         }
-                        std::task::Poll::Ready(None) => return std::task::Poll::Ready(None),
-                        std::task::Poll::Pending => return std::task::Poll::Pending,
+                        continue; // Trailer
                     }
-                }
-            }
-            StreamingInner::Generic(stream) => match stream.as_mut().poll_next(cx) {
-                std::task::Poll::Ready(Some(Ok(data))) => {
-                    let len = data.len();
-                    self.bytes_read += len;
-                    if let Some(limit) = self.limit {
-                        if self.bytes_read > limit {
-                            return std::task::Poll::Ready(Some(Err(crate::error::ApiError::new(
-                                StatusCode::PAYLOAD_TOO_LARGE,
-                                "payload_too_large",
-                                format!("Body size exceeded limit of {} bytes", limit),
-                            ))));
-                        }
+                    Poll::Ready(Some(Err(e))) => {
+                        return Poll::Ready(Some(Err(crate::error::ApiError::bad_request(
+                            e.to_string(),
+                        ))));
                     }
-                    std::task::Poll::Ready(Some(Ok(data)))
+                    Poll::Ready(None) => return Poll::Ready(None),
+                    Poll::Pending => return Poll::Pending,
                 }
-                other => other,
             },
-        }
+            StreamingInner::Generic(stream) => match stream.as_mut().poll_next(cx) {
+                Poll::Ready(Some(Ok(data))) => data,
+                other => return other,
+            },
+        };
+
+        Poll::Ready(Some(self.accept(data)))
     }
 }
@@ -0,0 +1,446 @@
+//! Multi-tenancy
+//!
+//! [`TenantLayer`] resolves the tenant of each request and stores it as a
+//! [`Tenant`], which handlers take as an extractor. Sources are tried in the
+//! order they were added:
+//!
+//! - [`from_subdomain`](TenantLayer::from_subdomain): `acme.api.example.com`
+//! - [`from_header`](TenantLayer::from_header): `X-Tenant-Id: acme`
+//! - [`from_claim`](TenantLayer::from_claim): a claim of the token recorded
+//!   by `JwtLayer` (add the tenant layer inside the JWT layer)
+//! - [`resolver`](TenantLayer::resolver): any [`TenantResolver`]
+//!
+//! Tenant IDs are limited to ASCII letters, digits, `-` and `_` (at most 63
+//! characters), so they are safe in log fields, metric labels and schema
+//! names. Requests without a tenant get `400 Bad Request` unless the layer is
+//! [`optional`](TenantLayer::optional). Sources like headers and subdomains
+//! are client input, so check resolved tenants with
+//! [`known`](TenantLayer::known): unknown tenants get `404 Not Found`.
+//!
+//! The handler runs inside a `tenant` tracing span carrying the ID, and the
+//! response carries the [`Tenant`] in its extensions, so outer layers can
+//! label by tenant (`TracingLayer` logs it; `MetricsLayer` counts
+//! `http_requests_by_tenant_total`).
+//!
+//! Per-tenant resources live in a [`TenantMap`], which only creates values
+//! for tenants its `init` accepts.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! use rustapi_core::tenant::{Tenant, TenantLayer, TenantMap};
+//!
+//! let pools = TenantMap::new(move |tenant: &Tenant| {
+//!     tenants.contains(tenant.id()).then(|| {
+//!         PgPool::connect_lazy(&format!("postgres://db/{}", tenant.id())).unwrap()
+//!     })
+//! });
+//!
+//! async fn orders(tenant: Tenant, State(pools): State<TenantMap<PgPool>>) -> Result<Json<Vec<Order>>> {
+//!     let pool = pools.get(&tenant)?; // 404 for unknown tenants
+//!     // ...
+//! }
+//!
+//! RustApi::new()
+//!     .state(pools)
+//!     .layer(
+//!         TenantLayer::new()
+//!             .from_subdomain("api.example.com")
+//!             .from_header("x-tenant-id")
+//!             .known(move |tenant: &Tenant| registry.contains(tenant.id())),
+//!     )
+//!     .route("/orders", get(orders));
+//! ```
+
+use crate::authz::TokenClaims;
+use crate::error::{ApiError, Result};
+use crate::extract::FromRequestParts;
+use crate::middleware::{BoxedNext, MiddlewareLayer};
+use crate::request::Request;
+use crate::response::{IntoResponse, Response};
+use rustapi_openapi::{Operation, OperationModifier};
+use std::collections::HashMap;
+use std::fmt;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::{Arc, RwLock};
+use tracing::Instrument;
+
+/// Longest accepted tenant ID
+const MAX_TENANT_ID_LEN: usize = 63;
+
+/// The tenant a request belongs to
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Tenant(String);
+
+impl Tenant {
+    /// Create a tenant, checking the ID
+    ///
+    /// Returns `None` unless the ID is 1-63 ASCII letters, digits, `-` or `_`.
+    pub fn new(id: impl Into<String>) -> Option<Self> {
+        let id = id.into();
+        let valid = !id.is_empty()
+            && id.len() <= MAX_TENANT_ID_LEN
+            && id
+                .bytes()
+                .all(|b| b.is_ascii_alphanumeric() || b == b'-' || b == b'_');
+        valid.then_some(Self(id))
+    }
+
+    /// The tenant ID
+    pub fn id(&self) -> &str {
+        &self.0
+    }
+
+    /// Schema name for schema-per-tenant databases
+    ///
+    /// `prefix` followed by the ID, with `-` replaced by `_` so the result is
+    /// a plain SQL identifier: `Tenant("acme-eu").schema_name("tenant_")` is
+    /// `tenant_acme_eu`.
+    pub fn schema_name(&self, prefix: &str) -> String {
+        format!("{}{}", prefix, self.0.replace('-', "_"))
+    }
+}
+
+impl fmt::Display for Tenant {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl FromRequestParts for Tenant {
+    fn from_request_parts(req: &Request) -> Result<Self> {
+        req.extensions().get::<Tenant>().cloned().ok_or_else(|| {
+            ApiError::internal("Tenant not found. Did you forget to add TenantLayer middleware?")
+        })
+    }
+}
+
+impl OperationModifier for Tenant {
+    fn update_operation(_op: &mut Operation) {}
+}
+
+/// Finds the tenant ID of a request
+///
+/// Implemented for closures taking the request. On a route layer, path
+/// parameters are available: `|req: &Request| req.path_param("tenant").cloned()`.
+pub trait TenantResolver: Send + Sync + 'static {
+    /// The tenant ID, or `None` if this resolver does not apply
+    fn resolve(&self, req: &Request) -> Option<String>;
+}
+
+impl<F> TenantResolver for F
+where
+    F: Fn(&Request) -> Option<String> + Send + Sync + 'static,
+{
+    fn resolve(&self, req: &Request) -> Option<String> {
+        self(req)
+    }
+}
+
+/// Checks whether a resolved tenant exists
+type KnownTenant = Arc<dyn Fn(&Tenant) -> bool + Send + Sync>;
+
+/// Middleware resolving the [`Tenant`] of each request
+#[derive(Clone, Default)]
+pub struct TenantLayer {
+    resolvers: Vec<Arc<dyn TenantResolver>>,
+    known: Option<KnownTenant>,
+    optional: bool,
+}
+
+impl TenantLayer {
+    /// Create a layer without sources
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Resolve from the leftmost label of hosts under `base_domain`
+    ///
+    /// With `api.example.com`, `acme.api.example.com` is tenant `acme`.
+    /// Deeper subdomains and the base domain itself do not resolve.
+    pub fn from_subdomain(self, base_domain: impl Into<String>) -> Self {
+        let suffix = format!(".{}", base_domain.into().to_ascii_lowercase());
+        self.resolver(move |req: &Request| {
+            let host = host(req)?.to_ascii_lowercase();
+            let label = host.strip_suffix(&suffix)?;
+            (!label.contains('.')).then(|| label.to_string())
+        })
+    }
+
+    /// Resolve from a request header
+    pub fn from_header(self, name: &'static str) -> Self {
+        self.resolver(move |req: &Request| {
+            let value = req.headers().get(name)?.to_str().ok()?.trim();
+            Some(value.to_string())
+        })
+    }
+
+    /// Resolve from a string claim of the authenticated token
+    ///
+    /// Reads the [`TokenClaims`] that `JwtLayer` records, so this layer must
+    /// run inside the JWT layer.
+    pub fn from_claim(self, claim: &'static str) -> Self {
+        self.resolver(move |req: &Request| {
+            let claims = req.extensions().get::<TokenClaims>()?;
+            claims.get(claim)?.as_str().map(str::to_string)
+        })
+    }
+
+    /// Resolve with a custom resolver
+    pub fn resolver(mut self, resolver: impl TenantResolver) -> Self {
+        self.resolvers.push(Arc::new(resolver));
+        self
+    }
+
+    /// Reject resolved tenants for which `check` returns `false` with
+    /// `404 Not Found`
+    ///
+    /// The check runs on every request, so it should be cheap, e.g. a lookup
+    /// in a set of provisioned tenants.
+    pub fn known(mut self, check: impl Fn(&Tenant) -> bool + Send + Sync + 'static) -> Self {
+        self.known = Some(Arc::new(check));
+        self
+    }
+
+    /// Let requests without a tenant through (handlers take `Option<Tenant>`)
+    pub fn optional(mut self) -> Self {
+        self.optional = true;
+        self
+    }
+
+    /// The tenant from the first source that resolves
+    ///
+    /// A resolved but malformed or unknown ID is an error rather than
+    /// falling through to the next source.
+    fn resolve(&self, req: &Request) -> Result<Option<Tenant>> {
+        let Some(id) = self.resolvers.iter().find_map(|r| r.resolve(req)) else {
+            return Ok(None);
+        };
+        let tenant = Tenant::new(id).ok_or_else(|| ApiError::bad_request("Invalid tenant"))?;
+        if self.known.as_ref().is_some_and(|known| !known(&tenant)) {
+            return Err(ApiError::not_found("Unknown tenant"));
+        }
+        Ok(Some(tenant))
+    }
+}
+
+impl MiddlewareLayer for TenantLayer {
+    fn call(
+        &self,
+        mut req: Request,
+        next: BoxedNext,
+    ) -> Pin<Box<dyn Future<Output = Response> + Send + 'static>> {
+        let resolved = self.resolve(&req);
+        let optional = self.optional;
+
+        Box::pin(async move {
+            let tenant = match resolved {
+                Ok(Some(tenant)) => tenant,
+                Ok(None) if optional => return next(req).await,
+                Ok(None) => {
+                    return ApiError::bad_request("Tenant could not be determined").into_response()
+                }
+                Err(err) => return err.into_response(),
+            };
+
+            req.extensions_mut().insert(tenant.clone());
+            let span = tracing::info_span!("tenant", tenant = %tenant);
+            let mut response = next(req).instrument(span).await;
+            response.extensions_mut().insert(tenant);
+            response
+        })
+    }
+
+    fn clone_box(&self) -> Box<dyn MiddlewareLayer> {
+        Box::new(self.clone())
+    }
+}
+
+/// `Host` header (or URI authority), without the port
+///
+/// IPv6 hosts keep their brackets (`[::1]`).
+fn host(req: &Request) -> Option<String> {
+    let authority = match req.headers().get(http::header::HOST) {
+        Some(value) => value.to_str().ok()?.parse::<http::uri::Authority>().ok()?,
+        None => req.uri().authority()?.clone(),
+    };
+    Some(authority.host().to_string())
+}
+
+/// Builds a tenant's value, `None` for unknown tenants
+type TenantInit<T> = Arc<dyn Fn(&Tenant) -> Option<T> + Send + Sync>;
+
+/// Per-tenant values, created on first use
+///
+/// Holds one value per tenant, such as a database pool or a client configured
+/// for the tenant. Clones share the same values, so the map can be app state.
+/// Values are only created for tenants `init` accepts, so tenant IDs taken
+/// from client input cannot grow the map without bound.
+pub struct TenantMap<T> {
+    init: TenantInit<T>,
+    values: Arc<RwLock<HashMap<String, Arc<T>>>>,
+}
+
+impl<T> Clone for TenantMap<T> {
+    fn clone(&self) -> Self {
+        Self {
+            init: self.init.clone(),
+            values: self.values.clone(),
+        }
+    }
+}
+
+impl<T> TenantMap<T> {
+    /// Create a map that builds a tenant's value with `init`
+    ///
+    /// `init` returns `None` for tenants that do not exist. It runs on the
+    /// request path, outside the map's lock, so it should not block; prefer
+    /// lazily connecting pools. Requests racing for a new tenant may each run
+    /// `init`, and all of them get the value stored first.
+    pub fn new(init: impl Fn(&Tenant) -> Option<T> + Send + Sync + 'static) -> Self {
+        Self {
+            init: Arc::new(init),
+            values: Arc::new(RwLock::new(HashMap::new())),
+        }
+    }
+
+    /// The tenant's value, creating it if needed
+    ///
+    /// Fails with `404 Not Found` if `init` does not know the tenant.
+    pub fn get(&self, tenant: &Tenant) -> Result<Arc<T>> {
+        if let Some(value) = self
+            .values
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .get(tenant.id())
+        {
+            return Ok(value.clone());
+        }
+
+        let value = (self.init)(tenant).ok_or_else(|| ApiError::not_found("Unknown tenant"))?;
+        Ok(self
+            .values
+            .write()
+            .unwrap_or_else(|e| e.into_inner())
+            .entry(tenant.id().to_string())
+            .or_insert_with(|| Arc::new(value))
+            .clone())
+    }
+
+    /// Set a tenant's value, replacing any existing one
+    pub fn insert(&self, tenant: &Tenant, value: T) {
+        self.values
+            .write()
+            .unwrap_or_else(|e| e.into_inner())
+            .insert(tenant.id().to_string(), Arc::new(value));
+    }
+
+    /// Drop a tenant's value (e.g. when the tenant is deprovisioned)
+    pub fn remove(&self, tenant: &Tenant) -> Option<Arc<T>> {
+        self.values
+            .write()
+            .unwrap_or_else(|e| e.into_inner())
+            .remove(tenant.id())
+    }
+
+    /// Number of tenants with a value
+    pub fn len(&self) -> usize {
+        self.values.read().unwrap_or_else(|e| e.into_inner()).len()
+    }
+
+    /// Whether no tenant has a value yet
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bytes::Bytes;
+
+    fn request(host: &str, header: Option<&str>) -> Request {
+        let mut builder = http::Request::builder().uri("/orders").header("host", host);
+        if let Some(tenant) = header {
+            builder = builder.header("x-tenant-id", tenant);
+        }
+        Request::from_http_request(builder.body(()).unwrap(), Bytes::new())
+    }
+
+    #[test]
+    fn sources_are_tried_in_order() {
+        let layer = TenantLayer::new()
+            .from_subdomain("api.example.com")
+            .from_header("x-tenant-id");
+
+        let tenant = |req: &Request| layer.resolve(req).unwrap().map(|t| t.0);
+        assert_eq!(
+            tenant(&request("acme.api.example.com:8443", Some("other"))),
+            Some("acme".to_string())
+        );
+        assert_eq!(
+            tenant(&request("api.example.com", Some("globex"))),
+            Some("globex".to_string())
+        );
+        assert_eq!(tenant(&request("a.b.api.example.com", None)), None);
+        assert!(layer
+            .resolve(&request("localhost", Some("../etc")))
+            .is_err());
+    }
+
+    #[test]
+    fn tenant_ids_are_checked() {
+        assert!(Tenant::new("acme-eu_1").is_some());
+        assert!(Tenant::new("").is_none());
+        assert!(Tenant::new("a b").is_none());
+        assert!(Tenant::new("x".repeat(64)).is_none());
+        assert_eq!(
+            Tenant::new("acme-eu").unwrap().schema_name("tenant_"),
+            "tenant_acme_eu"
+        );
+    }
+
+    #[test]
+    fn tenant_map_builds_once_per_tenant() {
+        let built = Arc::new(std::sync::atomic::AtomicUsize::new(0));
+        let counter = built.clone();
+        let map = TenantMap::new(move |tenant: &Tenant| {
+            counter.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            (tenant.id() != "unknown").then(|| format!("pool-{}", tenant))
+        });
+        let acme = Tenant::new("acme").unwrap();
+
+        assert_eq!(*map.get(&acme).unwrap(), "pool-acme");
+        assert_eq!(*map.clone().get(&acme).unwrap(), "pool-acme");
+        assert_eq!(built.load(std::sync::atomic::Ordering::SeqCst), 1);
+
+        let err = map.get(&Tenant::new("unknown").unwrap()).unwrap_err();
+        assert_eq!(err.status, http::StatusCode::NOT_FOUND);
+        assert_eq!(map.len(), 1);
+    }
+
+    #[test]
+    fn unknown_tenants_are_not_found() {
+        let layer = TenantLayer::new()
+            .from_header("x-tenant-id")
+            .known(|tenant: &Tenant| tenant.id() == "acme");
+
+        assert!(layer.resolve(&request("localhost", Some("acme"))).is_ok());
+        let err = layer
+            .resolve(&request("localhost", Some("globex")))
+            .unwrap_err();
+        assert_eq!(err.status, http::StatusCode::NOT_FOUND);
+    }
+
+    #[test]
+    fn host_ignores_the_port() {
+        assert_eq!(
+            host(&request("acme.api.example.com:8443", None)).as_deref(),
+            Some("acme.api.example.com")
+        );
+        assert_eq!(host(&request("[::1]:8080", None)).as_deref(), Some("[::1]"));
+        assert_eq!(host(&request("[::1]", None)).as_deref(), Some("[::1]"));
+        assert_eq!(host(&request("a b", None)), None);
+    }
+}
@@ -0,0 +1,393 @@
+//! Typed cookies with secure defaults
+//!
+//! [`TypedCookie<T>`] stores a serializable value in a single cookie, encoded
+//! as base64url JSON, and applies the cookie attributes from
+//! [`CookieValue::defaults`]. Values are readable by the client; put anything
+//! that must not be altered in a signed or private jar instead.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! use rustapi_core::typed_cookie::{CookieValue, TypedCookie};
+//!
+//! #[derive(Serialize, Deserialize)]
+//! struct Preferences {
+//!     theme: String,
+//! }
+//!
+//! impl CookieValue for Preferences {
+//!     const NAME: &'static str = "prefs";
+//! }
+//!
+//! async fn show(prefs: Option<TypedCookie<Preferences>>) -> String {
+//!     prefs.map_or("light".into(), |prefs| prefs.theme.clone())
+//! }
+//!
+//! async fn save(Json(prefs): Json<Preferences>) -> (TypedCookie<Preferences>, NoContent) {
+//!     (TypedCookie::new(prefs), NoContent)
+//! }
+//! ```
+
+use crate::error::{get_environment, ApiError, Environment, Result};
+use crate::extract::{parse_cookie_jar, FromRequestParts};
+use crate::request::Request;
+use crate::response::{IntoResponse, Response};
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use cookie::Cookie;
+use http::{header, HeaderValue};
+use rustapi_openapi::{Operation, OperationModifier};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::ops::{Deref, DerefMut};
+use std::time::Duration;
+
+pub use cookie::SameSite;
+
+/// A value stored in its own cookie
+pub trait CookieValue: Serialize + DeserializeOwned {
+    /// Cookie name
+    const NAME: &'static str;
+
+    /// Attributes used when the cookie is set
+    ///
+    /// Defaults to [`CookieDefaults::for_environment`] for the current
+    /// environment.
+    fn defaults() -> CookieDefaults {
+        CookieDefaults::for_environment(get_environment())
+    }
+}
+
+/// Attributes applied to typed cookies
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CookieDefaults {
+    /// `Path` attribute (default: `/`)
+    pub path: String,
+    /// `Domain` attribute
+    pub domain: Option<String>,
+    /// `SameSite` attribute (default: `Lax`)
+    pub same_site: SameSite,
+    /// `Secure` attribute (default: on in production)
+    pub secure: bool,
+    /// `HttpOnly` attribute (default: on)
+    pub http_only: bool,
+    /// `Max-Age` attribute; `None` makes a session cookie
+    pub max_age: Option<Duration>,
+}
+
+impl Default for CookieDefaults {
+    fn default() -> Self {
+        Self::for_environment(Environment::Production)
+    }
+}
+
+impl CookieDefaults {
+    /// Secure defaults for an environment
+    ///
+    /// Both environments use `Path=/`, `SameSite=Lax` and `HttpOnly`.
+    /// Production adds `Secure`; development leaves it off so cookies work
+    /// over plain `http://localhost`.
+    pub fn for_environment(environment: Environment) -> Self {
+        Self {
+            path: "/".to_string(),
+            domain: None,
+            same_site: SameSite::Lax,
+            secure: environment == Environment::Production,
+            http_only: true,
+            max_age: None,
+        }
+    }
+
+    /// Set the `Path` attribute
+    pub fn path(mut self, path: impl Into<String>) -> Self {
+        self.path = path.into();
+        self
+    }
+
+    /// Set the `Domain` attribute
+    pub fn domain(mut self, domain: impl Into<String>) -> Self {
+        self.domain = Some(domain.into());
+        self
+    }
+
+    /// Set the `SameSite` attribute
+    ///
+    /// `SameSite=None` also turns on `Secure`, as browsers require.
+    pub fn same_site(mut self, same_site: SameSite) -> Self {
+        self.same_site = same_site;
+        if same_site == SameSite::None {
+            self.secure = true;
+        }
+        self
+    }
+
+    /// Set the `Secure` attribute
+    pub fn secure(mut self, secure: bool) -> Self {
+        self.secure = secure;
+        self
+    }
+
+    /// Set the `HttpOnly` attribute
+    pub fn http_only(mut self, http_only: bool) -> Self {
+        self.http_only = http_only;
+        self
+    }
+
+    /// Set the `Max-Age` attribute
+    pub fn max_age(mut self, max_age: Duration) -> Self {
+        self.max_age = Some(max_age);
+        self
+    }
+
+    fn apply(&self, cookie: &mut Cookie<'static>) {
+        cookie.set_path(self.path.clone());
+        if let Some(domain) = &self.domain {
+            cookie.set_domain(domain.clone());
+        }
+        cookie.set_same_site(self.same_site);
+        cookie.set_secure(self.secure);
+        cookie.set_http_only(self.http_only);
+        if let Some(max_age) = self.max_age {
+            cookie.set_max_age(cookie::time::Duration::seconds(
+                max_age.as_secs().min(i64::MAX as u64) as i64,
+            ));
+        }
+    }
+}
+
+/// Typed cookie extractor and response part
+///
+/// As an extractor it decodes the `T::NAME` cookie and fails with 400 when it
+/// is missing or malformed; use `Option<TypedCookie<T>>` for optional
+/// cookies. Returned from a handler, alone or as `(cookie, response)`, it sets
+/// the cookie with `T::defaults()` unless overridden.
+#[derive(Debug, Clone)]
+pub struct TypedCookie<T: CookieValue> {
+    value: Option<T>,
+    attributes: CookieDefaults,
+}
+
+impl<T: CookieValue> TypedCookie<T> {
+    /// Wrap a value to be set with `T::defaults()`
+    pub fn new(value: T) -> Self {
+        Self {
+            value: Some(value),
+            attributes: T::defaults(),
+        }
+    }
+
+    /// A response part that deletes the cookie from the client
+    pub fn removal() -> Self {
+        Self {
+            value: None,
+            attributes: T::defaults(),
+        }
+    }
+
+    /// Override the attributes for this cookie
+    pub fn with_attributes(mut self, attributes: CookieDefaults) -> Self {
+        self.attributes = attributes;
+        self
+    }
+
+    /// Override `Max-Age` for this cookie
+    pub fn max_age(mut self, max_age: Duration) -> Self {
+        self.attributes.max_age = Some(max_age);
+        self
+    }
+
+    /// The wrapped value
+    ///
+    /// # Panics
+    ///
+    /// Panics on a cookie created with [`removal`](Self::removal).
+    pub fn into_inner(self) -> T {
+        self.value.expect("TypedCookie::removal has no value")
+    }
+
+    /// Build the `Set-Cookie` cookie
+    pub fn to_cookie(&self) -> Result<Cookie<'static>> {
+        let mut cookie = match &self.value {
+            Some(value) => Cookie::new(T::NAME, encode(value)?),
+            None => {
+                let mut cookie = Cookie::new(T::NAME, "");
+                cookie.make_removal();
+                cookie
+            }
+        };
+        let max_age = cookie.max_age();
+        self.attributes.apply(&mut cookie);
+        if self.value.is_none() {
+            // Keep the removal's zero max-age and past expiry
+            cookie.set_max_age(max_age);
+        }
+        Ok(cookie)
+    }
+
+    fn append_to(&self, mut response: Response) -> Response {
+        let header = self.to_cookie().and_then(|cookie| {
+            HeaderValue::from_str(&cookie.to_string())
+                .map_err(|e| ApiError::internal(format!("Invalid cookie: {}", e)))
+        });
+        match header {
+            Ok(value) => {
+                response.headers_mut().append(header::SET_COOKIE, value);
+                response
+            }
+            Err(err) => err.into_response(),
+        }
+    }
+}
+
+impl<T: CookieValue> Deref for TypedCookie<T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        self.value
+            .as_ref()
+            .expect("TypedCookie::removal has no value")
+    }
+}
+
+impl<T: CookieValue> DerefMut for TypedCookie<T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        self.value
+            .as_mut()
+            .expect("TypedCookie::removal has no value")
+    }
+}
+
+impl<T: CookieValue + Send + Sync + 'static> FromRequestParts for TypedCookie<T> {
+    fn from_request_parts(req: &Request) -> Result<Self> {
+        let jar = parse_cookie_jar(req.headers());
+        let cookie = jar
+            .get(T::NAME)
+            .ok_or_else(|| ApiError::bad_request(format!("Missing cookie '{}'", T::NAME)))?;
+        Ok(Self::new(decode(T::NAME, cookie.value())?))
+    }
+}
+
+impl<T: CookieValue> OperationModifier for TypedCookie<T> {
+    fn update_operation(_op: &mut Operation) {}
+}
+
+impl<T: CookieValue> IntoResponse for TypedCookie<T> {
+    fn into_response(self) -> Response {
+        self.append_to(().into_response())
+    }
+}
+
+impl<T: CookieValue, R: IntoResponse> IntoResponse for (TypedCookie<T>, R) {
+    fn into_response(self) -> Response {
+        self.0.append_to(self.1.into_response())
+    }
+}
+
+fn encode<T: Serialize>(value: &T) -> Result<String> {
+    let json = serde_json::to_vec(value)
+        .map_err(|e| ApiError::internal(format!("Failed to serialize cookie: {}", e)))?;
+    Ok(URL_SAFE_NO_PAD.encode(json))
+}
+
+fn decode<T: DeserializeOwned>(name: &str, value: &str) -> Result<T> {
+    URL_SAFE_NO_PAD
+        .decode(value)
+        .ok()
+        .and_then(|json| serde_json::from_slice(&json).ok())
+        .ok_or_else(|| ApiError::bad_request(format!("Malformed cookie '{}'", name)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bytes::Bytes;
+    use serde::Deserialize;
+
+    #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+    struct Preferences {
+        theme: String,
+        page_size: u32,
+    }
+
+    impl CookieValue for Preferences {
+        const NAME: &'static str = "prefs";
+
+        fn defaults() -> CookieDefaults {
+            CookieDefaults::for_environment(Environment::Production)
+                .max_age(Duration::from_secs(3600))
+        }
+    }
+
+    fn request(cookie_header: &str) -> Request {
+        Request::from_http_request(
+            http::Request::builder()
+                .header(header::COOKIE, cookie_header)
+                .body(())
+                .unwrap(),
+            Bytes::new(),
+        )
+    }
+
+    fn set_cookie(response: &Response) -> String {
+        response.headers()[header::SET_COOKIE]
+            .to_str()
+            .unwrap()
+            .to_string()
+    }
+
+    #[test]
+    fn typed_cookie_round_trip() {
+        let prefs = Preferences {
+            theme: "dark, \"high contrast\"".to_string(),
+            page_size: 50,
+        };
+        let response = (TypedCookie::new(prefs.clone()), "ok").into_response();
+        let header = set_cookie(&response);
+
+        assert!(header.starts_with("prefs="));
+        for attribute in [
+            "HttpOnly",
+            "SameSite=Lax",
+            "Secure",
+            "Path=/",
+            "Max-Age=3600",
+        ] {
+            assert!(header.contains(attribute), "{header} lacks {attribute}");
+        }
+
+        let pair = header.split(';').next().unwrap();
+        let cookie = TypedCookie::<Preferences>::from_request_parts(&request(pair)).unwrap();
+        assert_eq!(*cookie, prefs);
+    }
+
+    #[test]
+    fn missing_or_malformed_cookies_are_rejected() {
+        let missing = TypedCookie::<Preferences>::from_request_parts(&request("other=1"));
+        assert!(missing
+            .unwrap_err()
+            .message
+            .contains("Missing cookie 'prefs'"));
+
+        let malformed = TypedCookie::<Preferences>::from_request_parts(&request("prefs=%%%"));
+        assert!(malformed.unwrap_err().message.contains("Malformed cookie"));
+    }
+
+    #[test]
+    fn environment_defaults() {
+        let development = CookieDefaults::for_environment(Environment::Development);
+        assert!(!development.secure);
+        assert!(development.http_only);
+        assert_eq!(development.same_site, SameSite::Lax);
+
+        let cross_site = development.same_site(SameSite::None);
+        assert!(cross_site.secure);
+    }
+
+    #[test]
+    fn removal_clears_the_cookie() {
+        let header = set_cookie(&TypedCookie::<Preferences>::removal().into_response());
+        assert!(header.starts_with("prefs=;"));
+        assert!(header.contains("Max-Age=0"));
+        assert!(header.contains("Path=/"));
+    }
+}
@@ -7,6 +7,21 @@ use crate::error::{ApiError, FieldError};
 pub trait Validatable {
     /// Perform synchronous validation
     fn do_validate(&self) -> Result<(), ApiError>;
+
+    /// Perform synchronous validation with messages in `locale`
+    ///
+    /// Implemented by `#[derive(Validate)]` to translate message keys; the
+    /// default implementation ignores the locale.
+    fn do_validate_localized(&self, locale: Option<&str>) -> Result<(), ApiError> {
+        let _ = locale;
+        self.do_validate()
+    }
+
+    /// Normalize field values before validation.
+    ///
+    /// Implemented by `#[derive(Validate)]` from the `#[validate(sanitize(...))]` field
+    /// attributes; the default implementation leaves the value untouched.
+    fn do_sanitize(&mut self) {}
 }
 
 // Blanket implementation for types implementing the external validator::Validate trait
@@ -43,6 +58,14 @@ pub fn convert_validator_errors(errors: validator::ValidationErrors) -> ApiError
 
 /// Helper to convert rustapi_validate::v2::ValidationErrors to rustapi_core::error::ApiError
 pub fn convert_v2_errors(errors: rustapi_validate::v2::ValidationErrors) -> ApiError {
+    convert_v2_errors_localized(errors, None)
+}
+
+/// Like [`convert_v2_errors`], translating messages into `locale` when given
+pub fn convert_v2_errors_localized(
+    errors: rustapi_validate::v2::ValidationErrors,
+    locale: Option<&str>,
+) -> ApiError {
     let field_errors = errors
         .fields
         .iter()
@@ -51,7 +74,10 @@ pub fn convert_v2_errors(errors: rustapi_validate::v2::ValidationErrors) -> ApiE
             errs.iter().map(move |e| FieldError {
                 field: field_name.clone(),
                 code: e.code.to_string(),
-                message: e.message.clone(),
+                message: match locale {
+                    Some(locale) => e.interpolate_with_locale(Some(locale)),
+                    None => e.message.clone(),
+                },
             })
         })
         .collect();
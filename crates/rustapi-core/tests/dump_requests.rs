@@ -0,0 +1,34 @@
+//! Dump requests made through the environment (`cargo rustapi errors`, ...).
+//!
+//! The only test of this binary, so setting the variables races with nothing.
+
+use rustapi_core::{get, RustApi};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+async fn hello() -> &'static str {
+    "hello"
+}
+
+#[tokio::test]
+async fn dump_requests_skip_serving() {
+    let started = Arc::new(AtomicBool::new(false));
+    let app = || {
+        let started = started.clone();
+        RustApi::new()
+            .route("/hello", get(hello))
+            .on_start(move || async move { started.store(true, Ordering::SeqCst) })
+    };
+
+    std::env::set_var("RUSTAPI_DUMP_ERROR_CATALOG", "1");
+    assert!(app().into_service("127.0.0.1:0").await.unwrap().is_none());
+    app().run("127.0.0.1:0").await.unwrap();
+    std::env::remove_var("RUSTAPI_DUMP_ERROR_CATALOG");
+
+    std::env::set_var("RUSTAPI_DUMP_ROUTES", "1");
+    assert!(app().into_service("127.0.0.1:0").await.unwrap().is_none());
+    app().run("127.0.0.1:0").await.unwrap();
+    std::env::remove_var("RUSTAPI_DUMP_ROUTES");
+
+    assert!(!started.load(Ordering::SeqCst));
+}
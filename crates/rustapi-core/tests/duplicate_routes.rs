@@ -0,0 +1,97 @@
+use rustapi_core::{get, get_route, post, DuplicateRoutePolicy, Router, RustApi};
+
+async fn list_users() -> &'static str {
+    "users"
+}
+
+async fn list_members() -> &'static str {
+    "members"
+}
+
+fn app() -> RustApi {
+    RustApi::new()
+        .mount_route(get_route("/users/{id}", list_users))
+        .mount_route(get_route("/users/{user_id}", list_members))
+}
+
+#[test]
+fn duplicates_record_both_handlers_and_locations() {
+    let app = app();
+    let duplicates = app.duplicate_routes();
+    assert_eq!(duplicates.len(), 1);
+
+    let duplicate = &duplicates[0];
+    assert_eq!(duplicate.method, "GET");
+    assert_eq!(duplicate.path, "/users/{user_id}");
+    assert!(duplicate.first.handler.ends_with("list_users"));
+    assert!(duplicate.second.handler.ends_with("list_members"));
+    assert!(duplicate
+        .first
+        .location
+        .file()
+        .ends_with("duplicate_routes.rs"));
+    assert!(duplicate.first.location.line() < duplicate.second.location.line());
+}
+
+#[test]
+fn duplicates_added_with_route_are_recorded() {
+    let app = RustApi::new()
+        .route("/items", get(list_users).post(list_users))
+        .route("/items", get(list_members));
+    let duplicates = app.duplicate_routes();
+    assert_eq!(duplicates.len(), 1);
+    assert_eq!(
+        (duplicates[0].method.as_str(), duplicates[0].path.as_str()),
+        ("GET", "/items")
+    );
+    assert!(duplicates[0].first.handler.ends_with("list_users"));
+    assert!(duplicates[0].second.handler.ends_with("list_members"));
+}
+
+#[test]
+fn duplicates_from_nested_routers_are_recorded() {
+    let app = RustApi::new()
+        .mount_route(get_route("/api/users/{id}", list_users))
+        .nest(
+            "/api",
+            Router::new()
+                .route("/users/{user_id}", get(list_members))
+                .route("/members", post(list_members)),
+        );
+    let duplicates = app.duplicate_routes();
+    assert_eq!(duplicates.len(), 1);
+    assert_eq!(
+        (duplicates[0].method.as_str(), duplicates[0].path.as_str()),
+        ("GET", "/api/users/{user_id}")
+    );
+    assert!(app.route_table().get("POST", "/api/members").is_some());
+}
+
+#[tokio::test]
+async fn into_service_returns_the_duplicates() {
+    let err = app().into_service("127.0.0.1:0").await.err().unwrap();
+    assert_eq!(err.duplicates.len(), 1);
+    assert_eq!(err.duplicates[0].path, "/users/{user_id}");
+}
+
+#[tokio::test]
+async fn run_refuses_to_start_with_duplicates() {
+    let err = app().run("127.0.0.1:0").await.unwrap_err().to_string();
+    assert!(err.contains("GET /users/{user_id}"), "{err}");
+    assert!(err.contains("list_users"), "{err}");
+    assert!(err.contains("list_members"), "{err}");
+}
+
+#[tokio::test]
+async fn warn_policy_serves_the_first_handler() {
+    let service = app()
+        .on_duplicate_route(DuplicateRoutePolicy::Warn)
+        .into_service("127.0.0.1:0")
+        .await
+        .unwrap()
+        .unwrap();
+    service.shutdown().await;
+
+    let client = rustapi_testing::TestClient::new(app());
+    client.get("/users/1").await.assert_body_contains("users");
+}
@@ -593,6 +593,12 @@ async fn test_async_validated_json_with_state_context() {
         }
     }
 
+    impl Sanitize for TestUser {
+        fn sanitize(&mut self) {
+            self.email = self.email.trim().to_lowercase();
+        }
+    }
+
     #[async_trait]
     impl AsyncValidate for TestUser {
         async fn validate_async_with_group(
@@ -676,9 +682,9 @@ async fn test_async_validated_json_with_state_context() {
         result.err()
     );
 
-    // Test 3: With context in state (should fail validation logic)
+    // Test 3: With context in state (should fail validation logic once sanitized)
     let user_taken = TestUser {
-        email: "taken@example.com".to_string(),
+        email: "  Taken@Example.com ".to_string(),
     };
     let body_taken = serde_json::to_vec(&user_taken).unwrap();
 
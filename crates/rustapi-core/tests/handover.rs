@@ -0,0 +1,113 @@
+#![cfg(unix)]
+
+use rustapi_core::{get, Handover, RustApi};
+use std::time::Duration;
+use tokio::sync::{oneshot, Notify};
+
+/// Signalled when `/slow` has started, i.e. the request is in flight
+static STARTED: Notify = Notify::const_new();
+/// Lets `/slow` finish
+static RELEASE: Notify = Notify::const_new();
+
+fn app() -> RustApi {
+    async fn slow() -> &'static str {
+        STARTED.notify_one();
+        RELEASE.notified().await;
+        "done"
+    }
+
+    async fn index() -> &'static str {
+        "ok"
+    }
+
+    RustApi::new()
+        .handover(Handover::new().drain_timeout(Duration::from_secs(5)))
+        .route("/slow", get(slow))
+        .route("/", get(index))
+}
+
+async fn wait_until_listening(port: u16) {
+    for _ in 0..100 {
+        if tokio::net::TcpStream::connect(("127.0.0.1", port))
+            .await
+            .is_ok()
+        {
+            return;
+        }
+        tokio::time::sleep(Duration::from_millis(20)).await;
+    }
+    panic!("server did not start listening on port {}", port);
+}
+
+#[tokio::test]
+async fn test_new_process_binds_while_old_one_drains() {
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let port = listener.local_addr().unwrap().port();
+    drop(listener);
+    let addr = format!("127.0.0.1:{}", port);
+
+    let (old_tx, old_rx) = oneshot::channel::<()>();
+    let mut old = tokio::spawn({
+        let addr = addr.clone();
+        async move {
+            app()
+                .run_with_shutdown(&addr, async {
+                    old_rx.await.ok();
+                })
+                .await
+        }
+    });
+    wait_until_listening(port).await;
+
+    let in_flight = tokio::spawn(async move {
+        reqwest::get(format!("http://127.0.0.1:{}/slow", port))
+            .await
+            .expect("in-flight request failed")
+            .text()
+            .await
+            .unwrap()
+    });
+    tokio::time::timeout(Duration::from_secs(5), STARTED.notified())
+        .await
+        .expect("request did not reach the old server");
+
+    // The new process binds the same port before the old one stops
+    let (new_tx, new_rx) = oneshot::channel::<()>();
+    let new = tokio::spawn(async move {
+        app()
+            .run_with_shutdown(&addr, async {
+                new_rx.await.ok();
+            })
+            .await
+    });
+    tokio::time::sleep(Duration::from_millis(100)).await;
+    assert!(!new.is_finished(), "second bind on the port failed");
+
+    // The old server keeps running while its request is held open
+    old_tx.send(()).unwrap();
+    assert!(
+        tokio::time::timeout(Duration::from_millis(200), &mut old)
+            .await
+            .is_err(),
+        "old server stopped before draining"
+    );
+
+    RELEASE.notify_one();
+    assert_eq!(in_flight.await.unwrap(), "done");
+    tokio::time::timeout(Duration::from_secs(2), old)
+        .await
+        .expect("old server did not finish draining")
+        .unwrap()
+        .unwrap();
+
+    let body = reqwest::get(format!("http://127.0.0.1:{}/", port))
+        .await
+        .expect("request to the new server failed")
+        .text()
+        .await
+        .unwrap();
+    assert_eq!(body, "ok");
+
+    new_tx.send(()).unwrap();
+    let _ = tokio::time::timeout(Duration::from_secs(2), new).await;
+}
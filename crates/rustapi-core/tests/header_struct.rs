@@ -0,0 +1,61 @@
+use http::StatusCode;
+use rustapi_core::{get, HeaderStruct, RustApi};
+use rustapi_openapi::Schema;
+use rustapi_testing::{TestClient, TestRequest};
+use serde::Deserialize;
+
+#[derive(Debug, Deserialize, Schema)]
+#[serde(rename_all = "kebab-case")]
+struct ClientHeaders {
+    x_api_version: u32,
+    #[serde(rename = "X-Tenant")]
+    tenant: Option<String>,
+    #[serde(default)]
+    x_feature: Vec<String>,
+}
+
+async fn client(HeaderStruct(h): HeaderStruct<ClientHeaders>) -> String {
+    format!("{} {:?} {:?}", h.x_api_version, h.tenant, h.x_feature)
+}
+
+#[tokio::test]
+async fn headers_deserialize_by_renamed_field_names() {
+    let client = TestClient::new(RustApi::new().route("/client", get(client)));
+
+    let response = client
+        .request(
+            TestRequest::get("/client")
+                .header("X-Api-Version", "2")
+                .header("x-tenant", "acme")
+                .header("x-feature", "beta"),
+        )
+        .await;
+    response.assert_status(StatusCode::OK);
+    assert_eq!(response.text(), r#"2 Some("acme") ["beta"]"#);
+
+    client
+        .request(TestRequest::get("/client").header("x-api-version", "two"))
+        .await
+        .assert_status(StatusCode::BAD_REQUEST);
+    client
+        .get("/client")
+        .await
+        .assert_status(StatusCode::BAD_REQUEST);
+}
+
+#[test]
+fn header_struct_fields_are_documented_as_header_parameters() {
+    let app = RustApi::new().route("/client", get(client));
+    let op = app.openapi_spec().paths["/client"].get.clone().unwrap();
+
+    let param = |name: &str| {
+        op.parameters
+            .iter()
+            .find(|p| p.name == name)
+            .unwrap_or_else(|| panic!("missing parameter {name}"))
+    };
+    assert_eq!(param("x-api-version").location, "header");
+    assert!(param("x-api-version").required);
+    assert!(!param("X-Tenant").required);
+    assert_eq!(param("x-feature").location, "header");
+}
@@ -0,0 +1,49 @@
+use http::StatusCode;
+use rustapi_core::{get_environment, get_route, ApiError, Environment, Profile, RustApiConfig};
+use rustapi_testing::TestClient;
+
+async fn fail() -> Result<&'static str, ApiError> {
+    Err(ApiError::internal("connection to db-primary refused"))
+}
+
+// The profile is process-wide, so everything is checked in one test
+#[tokio::test]
+async fn production_profile_drives_defaults() {
+    let app = RustApiConfig::new()
+        .profile(Environment::Production)
+        .json_logs(false)
+        .build()
+        .mount_route(get_route("/fail", fail));
+
+    let profile = Profile::current();
+    assert_eq!(get_environment(), Environment::Production);
+    assert!(profile.mask_internal_errors);
+    assert!(profile.strict_cors);
+    assert!(!profile.replay_recording);
+    assert!(!profile.expose_docs);
+    assert!(!profile.json_logs);
+
+    let client = TestClient::new(app);
+    let response = client.get("/fail").await;
+    response.assert_status(StatusCode::INTERNAL_SERVER_ERROR);
+    let body = response.text();
+    assert!(!body.contains("db-primary"), "{body}");
+    assert!(body.contains("An internal error occurred"), "{body}");
+    client
+        .get("/docs")
+        .await
+        .assert_status(StatusCode::NOT_FOUND);
+
+    let app = RustApiConfig::new()
+        .mask_internal_errors(false)
+        .profile(Environment::Production)
+        .docs_enabled(true)
+        .build()
+        .mount_route(get_route("/fail", fail));
+    assert!(Profile::current().expose_docs);
+
+    let client = TestClient::new(app);
+    let body = client.get("/fail").await.text();
+    assert!(body.contains("db-primary"), "{body}");
+    client.get("/docs").await.assert_status(StatusCode::OK);
+}
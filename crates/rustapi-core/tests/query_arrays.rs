@@ -0,0 +1,52 @@
+use http::StatusCode;
+use rustapi_core::{get, Query, RustApi};
+use rustapi_openapi::Schema;
+use rustapi_testing::TestClient;
+use serde::Deserialize;
+
+#[derive(Debug, Deserialize, Schema)]
+struct Search {
+    q: Option<String>,
+    #[serde(default)]
+    tag: Vec<String>,
+    #[serde(default, deserialize_with = "rustapi_core::query::comma_separated")]
+    ids: Vec<u32>,
+}
+
+async fn search(Query(search): Query<Search>) -> String {
+    format!("{:?} {:?} {:?}", search.q, search.tag, search.ids)
+}
+
+#[tokio::test]
+async fn query_lists_from_repeated_bracketed_and_comma_separated_keys() {
+    let client = TestClient::new(RustApi::new().route("/pins", get(search)));
+
+    let response = client
+        .get("/pins?q=rust&tag=web&tag[]=api&ids=1,2&ids=3")
+        .await;
+    response.assert_status(StatusCode::OK);
+    assert_eq!(response.text(), r#"Some("rust") ["web", "api"] [1, 2, 3]"#);
+
+    client.get("/pins").await.assert_status(StatusCode::OK);
+    client
+        .get("/pins?ids=1,x")
+        .await
+        .assert_status(StatusCode::BAD_REQUEST);
+}
+
+#[test]
+fn array_query_parameters_are_documented_as_exploded_forms() {
+    let app = RustApi::new().route("/pins", get(search));
+    let op = app.openapi_spec().paths["/pins"].get.clone().unwrap();
+
+    let param = |name: &str| {
+        op.parameters
+            .iter()
+            .find(|p| p.name == name)
+            .unwrap_or_else(|| panic!("missing parameter {name}"))
+    };
+    assert_eq!(param("tag").style.as_deref(), Some("form"));
+    assert_eq!(param("tag").explode, Some(true));
+    assert_eq!(param("ids").style.as_deref(), Some("form"));
+    assert_eq!(param("q").style, None);
+}
@@ -0,0 +1,81 @@
+use http::StatusCode;
+use rustapi_core::middleware::{BoxedNext, MiddlewareLayer};
+use rustapi_core::{delete_route, Grants, Request, Response, RustApi};
+use rustapi_testing::{TestClient, TestRequest};
+use std::future::Future;
+use std::pin::Pin;
+
+/// Grants the roles listed in the `x-roles` header, standing in for JwtLayer
+#[derive(Clone)]
+struct HeaderGrants;
+
+impl MiddlewareLayer for HeaderGrants {
+    fn call(
+        &self,
+        mut req: Request,
+        next: BoxedNext,
+    ) -> Pin<Box<dyn Future<Output = Response> + Send + 'static>> {
+        if let Some(roles) = req.headers().get("x-roles") {
+            let roles = roles.to_str().unwrap_or_default().to_string();
+            let grants = roles
+                .split(',')
+                .fold(Grants::new().scope("pins:read"), |grants, role| {
+                    grants.role(role)
+                });
+            req.extensions_mut().insert(grants);
+        }
+        Box::pin(async move { next(req).await })
+    }
+
+    fn clone_box(&self) -> Box<dyn MiddlewareLayer> {
+        Box::new(self.clone())
+    }
+}
+
+async fn delete_pin() -> &'static str {
+    "deleted"
+}
+
+#[tokio::test]
+async fn role_is_checked_before_the_handler() {
+    let app = RustApi::new()
+        .layer(HeaderGrants)
+        .mount_route(delete_route("/pins/{id}", delete_pin).require_role("admin"));
+    let client = TestClient::new(app);
+
+    client
+        .request(TestRequest::delete("/pins/1"))
+        .await
+        .assert_status(StatusCode::UNAUTHORIZED);
+    client
+        .request(TestRequest::delete("/pins/1").header("x-roles", "user"))
+        .await
+        .assert_status(StatusCode::FORBIDDEN)
+        .assert_body_contains("Required role: admin");
+    client
+        .request(TestRequest::delete("/pins/1").header("x-roles", "user,admin"))
+        .await
+        .assert_status(StatusCode::OK)
+        .assert_body_contains("deleted");
+}
+
+#[test]
+fn requirements_are_documented() {
+    let app = RustApi::new().mount_route(
+        delete_route("/pins/{id}", delete_pin)
+            .require_role("admin")
+            .require_scope("pins:write"),
+    );
+    let spec = app.openapi_spec();
+
+    let operation = spec.paths["/pins/{id}"].delete.as_ref().unwrap();
+    assert_eq!(
+        operation.security[0]["bearerAuth"],
+        vec!["admin".to_string(), "pins:write".to_string()]
+    );
+    assert!(operation.responses.contains_key("401"));
+    assert!(operation.responses.contains_key("403"));
+
+    let schemes = &spec.components.as_ref().unwrap().security_schemes;
+    assert!(schemes.contains_key("bearerAuth"));
+}
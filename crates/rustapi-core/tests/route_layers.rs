@@ -0,0 +1,85 @@
+use http::{HeaderValue, StatusCode};
+use rustapi_core::middleware::{BoxedNext, MiddlewareLayer};
+use rustapi_core::{get_route, Request, Response, RustApi};
+use rustapi_testing::TestClient;
+use std::future::Future;
+use std::pin::Pin;
+
+/// Appends its name to the `x-trace` response header
+#[derive(Clone)]
+struct Trace(&'static str);
+
+impl MiddlewareLayer for Trace {
+    fn call(
+        &self,
+        req: Request,
+        next: BoxedNext,
+    ) -> Pin<Box<dyn Future<Output = Response> + Send + 'static>> {
+        let name = self.0;
+        Box::pin(async move {
+            let mut response = next(req).await;
+            let trace = match response.headers().get("x-trace") {
+                Some(inner) => format!("{},{}", name, inner.to_str().unwrap()),
+                None => name.to_string(),
+            };
+            response
+                .headers_mut()
+                .insert("x-trace", HeaderValue::from_str(&trace).unwrap());
+            response
+        })
+    }
+
+    fn clone_box(&self) -> Box<dyn MiddlewareLayer> {
+        Box::new(self.clone())
+    }
+}
+
+#[derive(Clone)]
+struct Deny;
+
+impl MiddlewareLayer for Deny {
+    fn call(
+        &self,
+        _req: Request,
+        _next: BoxedNext,
+    ) -> Pin<Box<dyn Future<Output = Response> + Send + 'static>> {
+        Box::pin(async move {
+            let mut response = Response::new(Default::default());
+            *response.status_mut() = StatusCode::FORBIDDEN;
+            response
+        })
+    }
+
+    fn clone_box(&self) -> Box<dyn MiddlewareLayer> {
+        Box::new(self.clone())
+    }
+}
+
+async fn handler() -> &'static str {
+    "ok"
+}
+
+#[tokio::test]
+async fn route_layers_wrap_only_their_route() {
+    let app = RustApi::new()
+        .layer(Trace("app"))
+        .mount_route(
+            get_route("/layered", handler)
+                .layer(Trace("outer"))
+                .layer(Trace("inner")),
+        )
+        .mount_route(get_route("/plain", handler))
+        .mount_route(get_route("/denied", handler).layer(Deny));
+    let client = TestClient::new(app);
+
+    client
+        .get("/layered")
+        .await
+        .assert_status(StatusCode::OK)
+        .assert_header("x-trace", "app,outer,inner");
+    client.get("/plain").await.assert_header("x-trace", "app");
+    client
+        .get("/denied")
+        .await
+        .assert_status(StatusCode::FORBIDDEN);
+}
@@ -0,0 +1,40 @@
+use http::StatusCode;
+use rustapi_core::{any_route, head_route, options_route, RustApi};
+use rustapi_testing::{TestClient, TestRequest};
+
+async fn handler() -> &'static str {
+    "ok"
+}
+
+#[tokio::test]
+async fn head_options_and_any_routes_dispatch() {
+    let app = RustApi::new()
+        .mount_route(head_route("/head", handler))
+        .mount_route(options_route("/options", handler))
+        .mount_route(any_route("/any", handler));
+    let client = TestClient::new(app);
+
+    client
+        .request(TestRequest::head("/head"))
+        .await
+        .assert_status(StatusCode::OK);
+    client
+        .get("/head")
+        .await
+        .assert_status(StatusCode::METHOD_NOT_ALLOWED);
+    client
+        .request(TestRequest::options("/options"))
+        .await
+        .assert_status(StatusCode::OK);
+
+    for request in [
+        TestRequest::get("/any"),
+        TestRequest::post("/any"),
+        TestRequest::put("/any"),
+        TestRequest::patch("/any"),
+        TestRequest::delete("/any"),
+        TestRequest::options("/any"),
+    ] {
+        client.request(request).await.assert_status(StatusCode::OK);
+    }
+}
@@ -0,0 +1,47 @@
+use rustapi_core::{
+    get_route, BodyVariant, Json, Request, RustApi, ServerTiming, ServerTimingLayer,
+};
+
+async fn list_pins(timing: ServerTiming) -> Json<Vec<u32>> {
+    let _span = timing.start("db").description("load pins");
+    Json(vec![1, 2, 3])
+}
+
+#[tokio::test]
+async fn header_contains_framework_phases_and_handler_spans() {
+    let app = RustApi::new()
+        .layer(ServerTimingLayer::new().enabled(true))
+        .mount_route(get_route("/pins", list_pins));
+    let dispatcher = app.request_dispatcher();
+
+    let (parts, ()) = http::Request::builder()
+        .uri("/pins")
+        .body(())
+        .unwrap()
+        .into_parts();
+    let req = Request::new(
+        parts,
+        BodyVariant::Buffered(Default::default()),
+        dispatcher.state_ref(),
+        Default::default(),
+    );
+    let response = dispatcher.dispatch(req).await;
+
+    let header = response.headers()["server-timing"].to_str().unwrap();
+    let names: Vec<&str> = header
+        .split(", ")
+        .map(|metric| metric.split(';').next().unwrap())
+        .collect();
+    assert_eq!(
+        names,
+        [
+            "routing",
+            "extractors",
+            "db",
+            "handler",
+            "serialization",
+            "total"
+        ]
+    );
+    assert!(header.contains("db;desc=\"load pins\";dur="));
+}
@@ -81,12 +81,59 @@ async fn test_openapi_snapshot() {
       },
       "components": {
         "schemas": {
+          "ApiErrorCode": {
+            "type": "string",
+            "description": "Stable error codes returned in the `error.type` field of error responses",
+            "oneOf": [
+              {
+                "description": "The request is malformed or could not be parsed (400)",
+                "const": "bad_request"
+              },
+              {
+                "description": "The request conflicts with the current state of the resource (409)",
+                "const": "conflict"
+              },
+              {
+                "description": "The request deadline passed before the handler finished (504)",
+                "const": "deadline_exceeded"
+              },
+              {
+                "description": "The caller is not allowed to perform this operation (403)",
+                "const": "forbidden"
+              },
+              {
+                "description": "An unexpected server error occurred (500)",
+                "const": "internal_error"
+              },
+              {
+                "description": "The route exists but does not accept this method; see the `Allow` header (405)",
+                "const": "method_not_allowed"
+              },
+              {
+                "description": "The requested resource does not exist (404)",
+                "const": "not_found"
+              },
+              {
+                "description": "The request body or an uploaded field exceeds the configured size limit (413)",
+                "const": "payload_too_large"
+              },
+              {
+                "description": "The service is temporarily unavailable (503)",
+                "const": "service_unavailable"
+              },
+              {
+                "description": "Authentication is required or the credentials are invalid (401)",
+                "const": "unauthorized"
+              },
+              {
+                "description": "Request validation failed; see `error.fields` for details (422)",
+                "const": "validation_error"
+              }
+            ]
+          },
           "ErrorBodySchema": {
             "type": "object",
             "properties": {
-              "error_type": {
-                "type": "string"
-              },
               "fields": {
                 "type": [
                   "array",
@@ -98,10 +145,13 @@ async fn test_openapi_snapshot() {
               },
               "message": {
                 "type": "string"
+              },
+              "type": {
+                "type": "string"
               }
             },
             "required": [
-              "error_type",
+              "type",
               "message"
             ]
           },
@@ -160,9 +210,6 @@ async fn test_openapi_snapshot() {
           "ValidationErrorBodySchema": {
             "type": "object",
             "properties": {
-              "error_type": {
-                "type": "string"
-              },
               "fields": {
                 "type": "array",
                 "items": {
@@ -171,10 +218,13 @@ async fn test_openapi_snapshot() {
               },
               "message": {
                 "type": "string"
+              },
+              "type": {
+                "type": "string"
               }
             },
             "required": [
-              "error_type",
+              "type",
               "message",
               "fields"
             ]
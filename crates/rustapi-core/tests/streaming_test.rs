@@ -4,7 +4,10 @@ use proptest::prelude::*;
 use rustapi_core::post;
 use rustapi_core::BodyStream;
 use rustapi_core::RustApi;
+use rustapi_core::{ApiError, StreamingConfig};
 use rustapi_testing::{TestClient, TestRequest};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
 
 #[tokio::test]
 async fn test_streaming_body_buffered_small() {
@@ -68,6 +71,63 @@ async fn test_streaming_body_buffered_large_fail() {
     response.assert_body_contains("payload_too_large");
 }
 
+#[tokio::test]
+async fn test_streaming_config_from_state() {
+    async fn handler(mut stream: BodyStream) -> String {
+        let mut size = 0;
+        while let Some(chunk) = stream.next().await {
+            match chunk {
+                Ok(b) => size += b.len(),
+                Err(e) => return format!("Error: {}", e),
+            }
+        }
+        format!("Size: {} of {:?}", size, stream.limit())
+    }
+
+    let uploaded = Arc::new(AtomicUsize::new(0));
+    let recorder = uploaded.clone();
+    let config = StreamingConfig::new()
+        .max_body_size(16)
+        .on_progress(move |progress| recorder.store(progress.bytes_read, Ordering::SeqCst))
+        .on_limit_exceeded(|limit| {
+            ApiError::new(
+                StatusCode::PAYLOAD_TOO_LARGE,
+                "upload_too_large",
+                format!("Uploads are limited to {} bytes", limit),
+            )
+        });
+    let app = RustApi::new().state(config).route("/stream", post(handler));
+    let client = TestClient::new(app);
+
+    let response = client
+        .request(TestRequest::post("/stream").body(vec![b'a'; 16]))
+        .await;
+    response.assert_status(StatusCode::OK);
+    assert_eq!(response.text(), "Size: 16 of Some(16)");
+    assert_eq!(uploaded.load(Ordering::SeqCst), 16);
+
+    // A declared Content-Length over the limit is rejected before reading
+    uploaded.store(0, Ordering::SeqCst);
+    let response = client
+        .request(
+            TestRequest::post("/stream")
+                .header("content-length", "17")
+                .body(vec![b'a'; 17]),
+        )
+        .await;
+    response.assert_status(StatusCode::PAYLOAD_TOO_LARGE);
+    response.assert_body_contains("upload_too_large");
+    assert_eq!(uploaded.load(Ordering::SeqCst), 0);
+
+    // Without one, the stream fails once the limit is crossed
+    let response = client
+        .request(TestRequest::post("/stream").body(vec![b'a'; 17]))
+        .await;
+    response.assert_status(StatusCode::OK);
+    response.assert_body_contains("upload_too_large");
+    assert_eq!(uploaded.load(Ordering::SeqCst), 0);
+}
+
 proptest! {
     #![proptest_config(ProptestConfig::with_cases(20))] // Fewer cases as these are async/heavy
 
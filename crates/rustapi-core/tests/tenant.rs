@@ -0,0 +1,71 @@
+use http::StatusCode;
+use rustapi_core::{get_route, Result, RustApi, State, Tenant, TenantLayer, TenantMap};
+use rustapi_testing::{TestClient, TestRequest};
+
+async fn whoami(tenant: Tenant, State(schemas): State<TenantMap<String>>) -> Result<String> {
+    Ok(format!("{} {}", tenant, schemas.get(&tenant)?))
+}
+
+fn app() -> RustApi {
+    // "initech" is registered but has no schema yet
+    let schemas = TenantMap::new(|tenant: &Tenant| {
+        (tenant.id() != "initech").then(|| tenant.schema_name("tenant_"))
+    });
+    RustApi::new()
+        .state(schemas)
+        .layer(
+            TenantLayer::new()
+                .from_subdomain("api.example.com")
+                .from_header("x-tenant-id")
+                .known(|tenant: &Tenant| tenant.id() != "umbrella"),
+        )
+        .mount_route(get_route("/whoami", whoami))
+}
+
+#[tokio::test]
+async fn tenant_is_resolved_from_subdomain_or_header() {
+    let client = TestClient::new(app());
+
+    client
+        .request(TestRequest::get("/whoami").header("host", "acme-eu.api.example.com"))
+        .await
+        .assert_status(StatusCode::OK)
+        .assert_body_contains("acme-eu tenant_acme_eu");
+    client
+        .request(TestRequest::get("/whoami").header("x-tenant-id", "globex"))
+        .await
+        .assert_status(StatusCode::OK)
+        .assert_body_contains("globex tenant_globex");
+}
+
+#[tokio::test]
+async fn unresolved_or_malformed_tenants_are_rejected() {
+    let client = TestClient::new(app());
+
+    client
+        .get("/whoami")
+        .await
+        .assert_status(StatusCode::BAD_REQUEST)
+        .assert_body_contains("Tenant could not be determined");
+    client
+        .request(TestRequest::get("/whoami").header("x-tenant-id", "acme; drop"))
+        .await
+        .assert_status(StatusCode::BAD_REQUEST)
+        .assert_body_contains("Invalid tenant");
+}
+
+#[tokio::test]
+async fn unknown_tenants_are_not_found() {
+    let client = TestClient::new(app());
+
+    client
+        .request(TestRequest::get("/whoami").header("x-tenant-id", "umbrella"))
+        .await
+        .assert_status(StatusCode::NOT_FOUND)
+        .assert_body_contains("Unknown tenant");
+    client
+        .request(TestRequest::get("/whoami").header("x-tenant-id", "initech"))
+        .await
+        .assert_status(StatusCode::NOT_FOUND)
+        .assert_body_contains("Unknown tenant");
+}
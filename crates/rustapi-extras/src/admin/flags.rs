@@ -0,0 +1,66 @@
+//! Runtime feature flags
+
+use std::collections::BTreeMap;
+use std::sync::{Arc, RwLock};
+
+/// Named on/off switches that can be flipped while the app runs
+///
+/// Clones share the flags. Register a clone as app state to read them in
+/// handlers, and give another to [`AdminApi`](super::AdminApi) to toggle them.
+///
+/// ```rust,ignore
+/// let flags = FeatureFlags::new().define("new_checkout", false);
+///
+/// async fn checkout(State(flags): State<FeatureFlags>) -> &'static str {
+///     if flags.is_enabled("new_checkout") { "new" } else { "old" }
+/// }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct FeatureFlags {
+    flags: Arc<RwLock<BTreeMap<String, bool>>>,
+}
+
+impl FeatureFlags {
+    /// Create an empty set of flags
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a flag with its initial value
+    pub fn define(self, name: impl Into<String>, enabled: bool) -> Self {
+        self.set(name, enabled);
+        self
+    }
+
+    /// Whether a flag is on; unknown flags are off
+    pub fn is_enabled(&self, name: &str) -> bool {
+        self.flags
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .get(name)
+            .copied()
+            .unwrap_or(false)
+    }
+
+    /// Turn a flag on or off, adding it if it is unknown
+    pub fn set(&self, name: impl Into<String>, enabled: bool) {
+        self.flags
+            .write()
+            .unwrap_or_else(|e| e.into_inner())
+            .insert(name.into(), enabled);
+    }
+
+    /// Forget a flag, returning whether it existed
+    pub fn remove(&self, name: &str) -> bool {
+        self.flags
+            .write()
+            .unwrap_or_else(|e| e.into_inner())
+            .remove(name)
+            .is_some()
+    }
+
+    /// All flags, sorted by name
+    pub fn flags(&self) -> BTreeMap<String, bool> {
+        self.flags.read().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+}
@@ -0,0 +1,159 @@
+//! Maintenance mode switch
+
+use rustapi_core::{
+    middleware::{BoxedNext, MiddlewareLayer},
+    ApiError, IntoResponse, Request, Response,
+};
+use serde::Serialize;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// State of a [`MaintenanceMode`] switch
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct MaintenanceStatus {
+    /// Whether requests are being turned away
+    pub enabled: bool,
+    /// Message returned to clients while enabled
+    pub message: Option<String>,
+    /// When maintenance was switched on, in seconds since the epoch
+    pub since: Option<u64>,
+}
+
+/// Middleware answering `503 Service Unavailable` while maintenance is on
+///
+/// Clones share the switch, so the layer added to the app and the one given
+/// to [`AdminApi`](super::AdminApi) control the same state. Health probes
+/// (`/health`, `/ready`, `/live`) always pass; add the admin prefix with
+/// [`allow_path`](Self::allow_path) so maintenance can be switched off again.
+#[derive(Clone)]
+pub struct MaintenanceMode {
+    status: Arc<RwLock<MaintenanceStatus>>,
+    allowed_paths: Vec<String>,
+    retry_after: Option<Duration>,
+}
+
+impl MaintenanceMode {
+    /// Create a switch that starts disabled
+    pub fn new() -> Self {
+        Self {
+            status: Arc::new(RwLock::new(MaintenanceStatus::default())),
+            allowed_paths: vec!["/health".into(), "/ready".into(), "/live".into()],
+            retry_after: None,
+        }
+    }
+
+    /// Keep serving this path and the paths below it (can be repeated)
+    ///
+    /// Matches whole segments: `/admin` allows `/admin` and `/admin/flags`,
+    /// not `/administrator`.
+    pub fn allow_path(mut self, prefix: impl Into<String>) -> Self {
+        self.allowed_paths.push(prefix.into());
+        self
+    }
+
+    /// Send `Retry-After` with this delay while maintenance is on
+    pub fn retry_after(mut self, delay: Duration) -> Self {
+        self.retry_after = Some(delay);
+        self
+    }
+
+    /// Turn requests away, optionally telling clients why
+    pub fn enable(&self, message: Option<String>) {
+        let since = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|elapsed| elapsed.as_secs())
+            .unwrap_or_default();
+        let mut status = self.status.write().unwrap_or_else(|e| e.into_inner());
+        *status = MaintenanceStatus {
+            enabled: true,
+            message,
+            since: status.since.filter(|_| status.enabled).or(Some(since)),
+        };
+    }
+
+    /// Serve requests again
+    pub fn disable(&self) {
+        *self.status.write().unwrap_or_else(|e| e.into_inner()) = MaintenanceStatus::default();
+    }
+
+    /// Whether requests are being turned away
+    pub fn is_enabled(&self) -> bool {
+        self.status
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .enabled
+    }
+
+    /// The current state
+    pub fn status(&self) -> MaintenanceStatus {
+        self.status
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone()
+    }
+}
+
+/// Whether `path` is `prefix` or below it, by whole segments
+fn under_prefix(path: &str, prefix: &str) -> bool {
+    let prefix = prefix.trim_end_matches('/');
+    match path.strip_prefix(prefix) {
+        Some(rest) => rest.is_empty() || rest.starts_with('/'),
+        None => false,
+    }
+}
+
+impl Default for MaintenanceMode {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MiddlewareLayer for MaintenanceMode {
+    fn call(
+        &self,
+        req: Request,
+        next: BoxedNext,
+    ) -> Pin<Box<dyn Future<Output = Response> + Send + 'static>> {
+        let status = self.status();
+        let allowed = !status.enabled
+            || self
+                .allowed_paths
+                .iter()
+                .any(|prefix| under_prefix(req.uri().path(), prefix));
+        if allowed {
+            return next(req);
+        }
+
+        let message = status
+            .message
+            .unwrap_or_else(|| "The service is down for maintenance".to_string());
+        let mut response = ApiError::service_unavailable(message).into_response();
+        if let Some(delay) = self.retry_after {
+            response
+                .headers_mut()
+                .insert(http::header::RETRY_AFTER, delay.as_secs().into());
+        }
+        Box::pin(async move { response })
+    }
+
+    fn clone_box(&self) -> Box<dyn MiddlewareLayer> {
+        Box::new(self.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn allowed_paths_match_whole_segments() {
+        assert!(under_prefix("/admin", "/admin"));
+        assert!(under_prefix("/admin/", "/admin"));
+        assert!(under_prefix("/admin/flags", "/admin/"));
+        assert!(!under_prefix("/administrator", "/admin"));
+        assert!(!under_prefix("/healthz", "/health"));
+        assert!(under_prefix("/anything", "/"));
+    }
+}
@@ -0,0 +1,687 @@
+//! Admin API for runtime controls
+//!
+//! One authenticated router for the knobs an operator reaches for during an
+//! incident, instead of a bespoke endpoint per concern:
+//!
+//! | Method | Path | Action |
+//! |--------|------|--------|
+//! | `GET` | `/` | Overview of everything below |
+//! | `GET`, `PUT` | `/maintenance` | Read or flip maintenance mode (`{"enabled": true, "message": "..."}`) |
+//! | `GET`, `PUT`, `DELETE` | `/log-level` | Read, change or reset the log filter |
+//! | `GET` | `/circuit-breakers` | Circuit breaker states and counters |
+//! | `POST` | `/circuit-breakers/{name}/reset` | Close a circuit breaker |
+//! | `GET` | `/caches` | Cache sizes |
+//! | `POST` | `/caches/{name}/invalidate` | Drop entries under `{"path": "/users"}`, or all entries without a body |
+//! | `GET` | `/flags` | Feature flags |
+//! | `PUT`, `DELETE` | `/flags/{name}` | Set (`{"enabled": true}`) or remove a flag |
+//!
+//! `PUT /log-level` takes `{"filter": "info,my_app=debug"}` to replace the
+//! directives or `{"directive": "my_app::orders=debug"}` to add one, plus an
+//! optional `"duration_secs"` after which the previous filter comes back.
+//!
+//! Only the endpoints for the controls handed to [`AdminApi`] are routed. The
+//! log filter is the one installed by `RustApi::new`. Every request needs
+//! `Authorization: Bearer <token>`, and every change is logged under the
+//! `rustapi::admin` target. Keep the mount prefix out of response caches and
+//! let it through maintenance mode, as in the example.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! use rustapi_core::RustApi;
+//! use rustapi_extras::admin::{AdminApi, FeatureFlags, MaintenanceMode};
+//! use rustapi_extras::{CacheLayer, CircuitBreakerLayer};
+//!
+//! let maintenance = MaintenanceMode::new().allow_path("/admin");
+//! let flags = FeatureFlags::new().define("new_checkout", false);
+//! let breaker = CircuitBreakerLayer::new();
+//! let (cache, cache_handle) = CacheLayer::with_handle().skip_path("/admin").build();
+//!
+//! let admin = AdminApi::new(std::env::var("ADMIN_TOKEN")?)
+//!     .maintenance(maintenance.clone())
+//!     .feature_flags(flags.clone())
+//!     .circuit_breaker("upstream", breaker.clone())
+//!     .cache("responses", cache_handle);
+//!
+//! RustApi::new()
+//!     .state(flags)
+//!     .layer(maintenance)
+//!     .layer(breaker)
+//!     .layer(cache)
+//!     .nest("/admin", admin.router())
+//!     .run("0.0.0.0:8080")
+//!     .await?;
+//! ```
+
+mod flags;
+mod maintenance;
+
+pub use flags::FeatureFlags;
+pub use maintenance::{MaintenanceMode, MaintenanceStatus};
+
+use crate::cache::CacheHandle;
+use crate::circuit_breaker::{CircuitBreakerLayer, CircuitState};
+use http::Method;
+use rustapi_core::{get, post, put};
+use rustapi_core::{ApiError, Body, Headers, Json, LogFilter, MethodRouter, Path, Router};
+use serde_json::{json, Value};
+use std::sync::Arc;
+use std::time::Duration;
+use subtle::ConstantTimeEq;
+
+/// Log target of admin changes
+const ADMIN_TARGET: &str = "rustapi::admin";
+
+type AdminResult = Result<Json<Value>, ApiError>;
+
+/// Authenticated router exposing runtime controls
+///
+/// Build it with the handles of the controls to expose, then mount
+/// [`router`](Self::router) under a prefix of your choice.
+#[derive(Clone)]
+pub struct AdminApi {
+    inner: Arc<Inner>,
+}
+
+#[derive(Clone, Default)]
+struct Inner {
+    token: String,
+    maintenance: Option<MaintenanceMode>,
+    flags: Option<FeatureFlags>,
+    log_filter: Option<LogFilter>,
+    circuit_breakers: Vec<(String, CircuitBreakerLayer)>,
+    caches: Vec<(String, CacheHandle)>,
+}
+
+impl AdminApi {
+    /// Create an admin API accepting `token` as bearer token
+    ///
+    /// With an empty token every request is refused.
+    pub fn new(token: impl Into<String>) -> Self {
+        Self {
+            inner: Arc::new(Inner {
+                token: token.into(),
+                log_filter: LogFilter::global(),
+                ..Inner::default()
+            }),
+        }
+    }
+
+    /// Expose a maintenance mode switch
+    pub fn maintenance(self, maintenance: MaintenanceMode) -> Self {
+        self.update(|inner| inner.maintenance = Some(maintenance))
+    }
+
+    /// Expose feature flags
+    pub fn feature_flags(self, flags: FeatureFlags) -> Self {
+        self.update(|inner| inner.flags = Some(flags))
+    }
+
+    /// Expose a log filter other than the global one
+    pub fn log_filter(self, filter: LogFilter) -> Self {
+        self.update(|inner| inner.log_filter = Some(filter))
+    }
+
+    /// Expose a circuit breaker under `name` (can be repeated)
+    pub fn circuit_breaker(self, name: impl Into<String>, breaker: CircuitBreakerLayer) -> Self {
+        self.update(|inner| inner.circuit_breakers.push((name.into(), breaker)))
+    }
+
+    /// Expose a response cache under `name` (can be repeated)
+    pub fn cache(self, name: impl Into<String>, handle: CacheHandle) -> Self {
+        self.update(|inner| inner.caches.push((name.into(), handle)))
+    }
+
+    /// The routes, to mount with `RustApi::nest`
+    pub fn router(&self) -> Router {
+        let admin = self.clone();
+        let mut router = Router::new().route(
+            "/",
+            document(
+                get(move |headers: Headers| async move {
+                    admin.authorize(&headers)?;
+                    Ok::<_, ApiError>(Json(admin.overview().await))
+                }),
+                Method::GET,
+                "Runtime controls overview",
+            ),
+        );
+
+        if self.inner.maintenance.is_some() {
+            let (read, write) = (self.clone(), self.clone());
+            let routes = get(move |headers: Headers| async move {
+                read.authorize(&headers)?;
+                Ok::<_, ApiError>(Json(read.maintenance_status()))
+            })
+            .put(move |headers: Headers, body: Body| async move {
+                write.authorize(&headers)?;
+                write.set_maintenance(json_body(&body.0)?)
+            });
+            router = router.route(
+                "/maintenance",
+                document_all(
+                    routes,
+                    &[
+                        (Method::GET, "Maintenance mode"),
+                        (Method::PUT, "Switch maintenance mode"),
+                    ],
+                ),
+            );
+        }
+
+        if self.inner.log_filter.is_some() {
+            let (read, write, reset) = (self.clone(), self.clone(), self.clone());
+            let routes = get(move |headers: Headers| async move {
+                read.authorize(&headers)?;
+                Ok::<_, ApiError>(Json(read.log_level()))
+            })
+            .put(move |headers: Headers, body: Body| async move {
+                write.authorize(&headers)?;
+                write.set_log_level(json_body(&body.0)?)
+            })
+            .delete(move |headers: Headers| async move {
+                reset.authorize(&headers)?;
+                reset.reset_log_level()
+            });
+            router = router.route(
+                "/log-level",
+                document_all(
+                    routes,
+                    &[
+                        (Method::GET, "Log filter"),
+                        (Method::PUT, "Change the log filter"),
+                        (Method::DELETE, "Reset the log filter"),
+                    ],
+                ),
+            );
+        }
+
+        if !self.inner.circuit_breakers.is_empty() {
+            let (read, reset) = (self.clone(), self.clone());
+            router = router
+                .route(
+                    "/circuit-breakers",
+                    document(
+                        get(move |headers: Headers| async move {
+                            read.authorize(&headers)?;
+                            Ok::<_, ApiError>(Json(read.circuit_breakers().await))
+                        }),
+                        Method::GET,
+                        "Circuit breaker states",
+                    ),
+                )
+                .route(
+                    "/circuit-breakers/{name}/reset",
+                    document(
+                        post(move |headers: Headers, name: Path<String>| async move {
+                            reset.authorize(&headers)?;
+                            reset.reset_circuit_breaker(&name).await
+                        }),
+                        Method::POST,
+                        "Close a circuit breaker",
+                    ),
+                );
+        }
+
+        if !self.inner.caches.is_empty() {
+            let (read, invalidate) = (self.clone(), self.clone());
+            router = router
+                .route(
+                    "/caches",
+                    document(
+                        get(move |headers: Headers| async move {
+                            read.authorize(&headers)?;
+                            Ok::<_, ApiError>(Json(read.caches()))
+                        }),
+                        Method::GET,
+                        "Cache sizes",
+                    ),
+                )
+                .route(
+                    "/caches/{name}/invalidate",
+                    document(
+                        post(
+                            move |headers: Headers, name: Path<String>, body: Body| async move {
+                                invalidate.authorize(&headers)?;
+                                invalidate.invalidate_cache(&name, &body.0)
+                            },
+                        ),
+                        Method::POST,
+                        "Invalidate cache entries",
+                    ),
+                );
+        }
+
+        if self.inner.flags.is_some() {
+            let (read, write, remove) = (self.clone(), self.clone(), self.clone());
+            let flag_routes = put(
+                move |headers: Headers, name: Path<String>, body: Body| async move {
+                    write.authorize(&headers)?;
+                    write.set_flag(&name, json_body(&body.0)?)
+                },
+            )
+            .delete(move |headers: Headers, name: Path<String>| async move {
+                remove.authorize(&headers)?;
+                remove.remove_flag(&name)
+            });
+            router = router
+                .route(
+                    "/flags",
+                    document(
+                        get(move |headers: Headers| async move {
+                            read.authorize(&headers)?;
+                            Ok::<_, ApiError>(Json(read.flags()))
+                        }),
+                        Method::GET,
+                        "Feature flags",
+                    ),
+                )
+                .route(
+                    "/flags/{name}",
+                    document_all(
+                        flag_routes,
+                        &[
+                            (Method::PUT, "Set a feature flag"),
+                            (Method::DELETE, "Remove a feature flag"),
+                        ],
+                    ),
+                );
+        }
+
+        router
+    }
+
+    fn update(mut self, change: impl FnOnce(&mut Inner)) -> Self {
+        change(Arc::make_mut(&mut self.inner));
+        self
+    }
+
+    fn authorize(&self, headers: &Headers) -> Result<(), ApiError> {
+        if self.inner.token.is_empty() {
+            return Err(ApiError::forbidden("Admin token not configured"));
+        }
+        let token = headers
+            .get("authorization")
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.split_once(' '))
+            .filter(|(scheme, _)| scheme.eq_ignore_ascii_case("Bearer"))
+            .map(|(_, token)| token.trim());
+        match token {
+            Some(token) if bool::from(token.as_bytes().ct_eq(self.inner.token.as_bytes())) => {
+                Ok(())
+            }
+            _ => Err(ApiError::unauthorized(
+                "Missing or invalid admin token. Use Authorization: Bearer <token>",
+            )),
+        }
+    }
+
+    async fn overview(&self) -> Value {
+        let inner = &self.inner;
+        json!({
+            "maintenance": inner.maintenance.as_ref().map(MaintenanceMode::status),
+            "log_filter": inner.log_filter.as_ref().map(LogFilter::current),
+            "circuit_breakers": self.circuit_breakers().await,
+            "caches": self.caches(),
+            "flags": inner.flags.as_ref().map(FeatureFlags::flags),
+        })
+    }
+
+    fn maintenance_status(&self) -> Value {
+        json!(self.inner.maintenance.as_ref().map(MaintenanceMode::status))
+    }
+
+    fn set_maintenance(&self, body: Value) -> AdminResult {
+        let maintenance = self.inner.maintenance.as_ref().ok_or_else(not_configured)?;
+        let enabled = body["enabled"]
+            .as_bool()
+            .ok_or_else(|| ApiError::bad_request("Expected {\"enabled\": bool}"))?;
+        if enabled {
+            let message = body["message"].as_str().map(str::to_string);
+            maintenance.enable(message);
+        } else {
+            maintenance.disable();
+        }
+        tracing::warn!(target: ADMIN_TARGET, enabled, "Maintenance mode switched");
+        Ok(Json(self.maintenance_status()))
+    }
+
+    fn log_level(&self) -> Value {
+        match &self.inner.log_filter {
+            Some(filter) => json!({
+                "filter": filter.current(),
+                "initial": filter.initial(),
+                "expires_in_secs": filter.expires_in().map(|left| left.as_secs()),
+            }),
+            None => Value::Null,
+        }
+    }
+
+    /// `{"filter": ...}` replaces the directives, `{"directive": ...}` adds
+    /// one; either can be made temporary with `"duration_secs"`
+    fn set_log_level(&self, body: Value) -> AdminResult {
+        let filter = self.inner.log_filter.as_ref().ok_or_else(not_configured)?;
+        let duration = match &body["duration_secs"] {
+            Value::Null => None,
+            secs => Some(secs.as_u64().map(Duration::from_secs).ok_or_else(|| {
+                ApiError::bad_request("Expected \"duration_secs\" to be a number of seconds")
+            })?),
+        };
+        let result = match (
+            body["filter"].as_str(),
+            body["directive"].as_str(),
+            duration,
+        ) {
+            (Some(directives), None, None) => filter.set(directives),
+            (Some(directives), None, Some(duration)) => filter.set_for(directives, duration),
+            (None, Some(directive), None) => filter.add_directive(directive),
+            (None, Some(directive), Some(duration)) => {
+                filter.add_directive_for(directive, duration)
+            }
+            _ => {
+                return Err(ApiError::bad_request(
+                    "Expected {\"filter\": string} or {\"directive\": string}",
+                ))
+            }
+        };
+        result.map_err(|e| ApiError::bad_request(e.to_string()))?;
+        tracing::warn!(
+            target: ADMIN_TARGET,
+            filter = %filter.current(),
+            duration_secs = duration.map(|d| d.as_secs()),
+            "Log filter changed"
+        );
+        Ok(Json(self.log_level()))
+    }
+
+    fn reset_log_level(&self) -> AdminResult {
+        let filter = self.inner.log_filter.as_ref().ok_or_else(not_configured)?;
+        filter
+            .reset()
+            .map_err(|e| ApiError::service_unavailable(e.to_string()))?;
+        tracing::warn!(target: ADMIN_TARGET, "Log filter reset");
+        Ok(Json(self.log_level()))
+    }
+
+    async fn circuit_breakers(&self) -> Value {
+        let mut breakers = serde_json::Map::new();
+        for (name, breaker) in &self.inner.circuit_breakers {
+            breakers.insert(name.clone(), breaker_json(breaker).await);
+        }
+        Value::Object(breakers)
+    }
+
+    async fn reset_circuit_breaker(&self, name: &str) -> AdminResult {
+        let breaker = find(&self.inner.circuit_breakers, name, "circuit breaker")?;
+        breaker.reset().await;
+        tracing::warn!(target: ADMIN_TARGET, breaker = %name, "Circuit breaker reset");
+        Ok(Json(breaker_json(breaker).await))
+    }
+
+    fn caches(&self) -> Value {
+        self.inner
+            .caches
+            .iter()
+            .map(|(name, handle)| (name.clone(), json!({ "entries": handle.len() })))
+            .collect::<serde_json::Map<_, _>>()
+            .into()
+    }
+
+    fn invalidate_cache(&self, name: &str, body: &[u8]) -> AdminResult {
+        let handle = find(&self.inner.caches, name, "cache")?;
+        let path = if body.is_empty() {
+            None
+        } else {
+            json_body(body)?["path"].as_str().map(str::to_string)
+        };
+        let before = handle.len();
+        match &path {
+            Some(path) => handle.invalidate(path),
+            None => handle.clear(),
+        }
+        let removed = before.saturating_sub(handle.len());
+        tracing::warn!(target: ADMIN_TARGET, cache = %name, path = ?path, removed, "Cache invalidated");
+        Ok(Json(json!({ "removed": removed, "entries": handle.len() })))
+    }
+
+    fn flags(&self) -> Value {
+        json!(self.inner.flags.as_ref().map(FeatureFlags::flags))
+    }
+
+    fn set_flag(&self, name: &str, body: Value) -> AdminResult {
+        let flags = self.inner.flags.as_ref().ok_or_else(not_configured)?;
+        let enabled = body["enabled"]
+            .as_bool()
+            .ok_or_else(|| ApiError::bad_request("Expected {\"enabled\": bool}"))?;
+        flags.set(name, enabled);
+        tracing::warn!(target: ADMIN_TARGET, flag = %name, enabled, "Feature flag set");
+        Ok(Json(json!({ "name": name, "enabled": enabled })))
+    }
+
+    fn remove_flag(&self, name: &str) -> AdminResult {
+        let flags = self.inner.flags.as_ref().ok_or_else(not_configured)?;
+        if !flags.remove(name) {
+            return Err(ApiError::not_found(format!(
+                "Unknown feature flag '{}'",
+                name
+            )));
+        }
+        tracing::warn!(target: ADMIN_TARGET, flag = %name, "Feature flag removed");
+        Ok(Json(json!({ "name": name, "removed": true })))
+    }
+}
+
+impl std::fmt::Debug for AdminApi {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("AdminApi")
+            .field("maintenance", &self.inner.maintenance.is_some())
+            .field("flags", &self.inner.flags.is_some())
+            .field("log_filter", &self.inner.log_filter.is_some())
+            .field("circuit_breakers", &names(&self.inner.circuit_breakers))
+            .field("caches", &names(&self.inner.caches))
+            .finish()
+    }
+}
+
+fn names<T>(items: &[(String, T)]) -> Vec<&str> {
+    items.iter().map(|(name, _)| name.as_str()).collect()
+}
+
+async fn breaker_json(breaker: &CircuitBreakerLayer) -> Value {
+    let stats = breaker.get_stats().await;
+    let state = match stats.state {
+        CircuitState::Closed => "closed",
+        CircuitState::Open => "open",
+        CircuitState::HalfOpen => "half_open",
+    };
+    json!({
+        "state": state,
+        "total_requests": stats.total_requests,
+        "total_failures": stats.total_failures,
+        "total_successes": stats.total_successes,
+        "failure_count": stats.failure_count,
+    })
+}
+
+fn find<'a, T>(items: &'a [(String, T)], name: &str, kind: &str) -> Result<&'a T, ApiError> {
+    items
+        .iter()
+        .find(|(item, _)| item == name)
+        .map(|(_, item)| item)
+        .ok_or_else(|| ApiError::not_found(format!("Unknown {} '{}'", kind, name)))
+}
+
+/// Parse a request body read as raw bytes
+///
+/// Handlers take the body as [`Body`] rather than `Json`, so the token is
+/// checked before the body is parsed and unauthenticated requests get `401`
+/// whatever they send.
+fn json_body(body: &[u8]) -> Result<Value, ApiError> {
+    serde_json::from_slice(body)
+        .map_err(|e| ApiError::bad_request(format!("Invalid JSON body: {}", e)))
+}
+
+fn not_configured() -> ApiError {
+    ApiError::not_found("Not configured")
+}
+
+fn document(route: MethodRouter, method: Method, summary: &str) -> MethodRouter {
+    document_all(route, &[(method, summary)])
+}
+
+/// Group the operations under the `admin` tag
+fn document_all(mut route: MethodRouter, operations: &[(Method, &str)]) -> MethodRouter {
+    for (method, summary) in operations {
+        route = route.operation(method.clone(), |op| {
+            op.tags = vec!["admin".to_string()];
+            op.summary = Some(summary.to_string());
+        });
+    }
+    route
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cache::CacheLayer;
+    use http::StatusCode;
+    use rustapi_core::{get_route, RustApi};
+    use rustapi_testing::{TestClient, TestRequest};
+
+    const TOKEN: &str = "s3cret";
+
+    fn admin_request(request: TestRequest) -> TestRequest {
+        request.header("authorization", &format!("Bearer {}", TOKEN))
+    }
+
+    async fn hello() -> &'static str {
+        "hello"
+    }
+
+    #[tokio::test]
+    async fn requests_without_the_token_are_rejected() {
+        let app = RustApi::new().nest("/admin", AdminApi::new(TOKEN).router());
+        let client = TestClient::new(app);
+
+        client
+            .get("/admin")
+            .await
+            .assert_status(StatusCode::UNAUTHORIZED);
+        client
+            .request(TestRequest::get("/admin").header("authorization", "Bearer wrong"))
+            .await
+            .assert_status(StatusCode::UNAUTHORIZED);
+        client
+            .request(admin_request(TestRequest::get("/admin")))
+            .await
+            .assert_status(StatusCode::OK);
+
+        // The token is checked before the body is parsed
+        let app = RustApi::new().nest(
+            "/admin",
+            AdminApi::new(TOKEN)
+                .maintenance(MaintenanceMode::new())
+                .router(),
+        );
+        TestClient::new(app)
+            .request(TestRequest::put("/admin/maintenance").body("not json"))
+            .await
+            .assert_status(StatusCode::UNAUTHORIZED);
+
+        let unset = RustApi::new().nest("/admin", AdminApi::new("").router());
+        TestClient::new(unset)
+            .request(TestRequest::get("/admin").header("authorization", "Bearer "))
+            .await
+            .assert_status(StatusCode::FORBIDDEN);
+    }
+
+    #[tokio::test]
+    async fn maintenance_mode_turns_requests_away_except_admin() {
+        let maintenance = MaintenanceMode::new()
+            .allow_path("/admin")
+            .retry_after(Duration::from_secs(120));
+        let admin = AdminApi::new(TOKEN).maintenance(maintenance.clone());
+        let app = RustApi::new()
+            .layer(maintenance.clone())
+            .mount_route(get_route("/hello", hello))
+            .nest("/admin", admin.router());
+        let client = TestClient::new(app);
+
+        client.get("/hello").await.assert_status(StatusCode::OK);
+
+        client
+            .request(admin_request(TestRequest::put("/admin/maintenance").json(
+                &json!({ "enabled": true, "message": "Upgrading the database" }),
+            )))
+            .await
+            .assert_status(StatusCode::OK);
+        assert!(maintenance.is_enabled());
+
+        let blocked = client.get("/hello").await;
+        blocked
+            .assert_status(StatusCode::SERVICE_UNAVAILABLE)
+            .assert_header("retry-after", "120")
+            .assert_body_contains("Upgrading the database");
+
+        client
+            .request(admin_request(
+                TestRequest::put("/admin/maintenance").json(&json!({ "enabled": false })),
+            ))
+            .await
+            .assert_status(StatusCode::OK);
+        client.get("/hello").await.assert_status(StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn flags_breakers_and_caches_are_controlled_by_name() {
+        let flags = FeatureFlags::new().define("new_checkout", false);
+        let breaker = CircuitBreakerLayer::new();
+        let (cache, handle) = CacheLayer::with_handle().skip_path("/admin").build();
+        let admin = AdminApi::new(TOKEN)
+            .feature_flags(flags.clone())
+            .circuit_breaker("upstream", breaker)
+            .cache("responses", handle.clone());
+        let app = RustApi::new()
+            .layer(cache)
+            .mount_route(get_route("/hello", hello))
+            .nest("/admin", admin.router());
+        let client = TestClient::new(app);
+
+        client
+            .request(admin_request(
+                TestRequest::put("/admin/flags/new_checkout").json(&json!({ "enabled": true })),
+            ))
+            .await
+            .assert_status(StatusCode::OK);
+        assert!(flags.is_enabled("new_checkout"));
+        client
+            .request(admin_request(TestRequest::delete("/admin/flags/missing")))
+            .await
+            .assert_status(StatusCode::NOT_FOUND);
+
+        let breakers: Value = client
+            .request(admin_request(TestRequest::get("/admin/circuit-breakers")))
+            .await
+            .json()
+            .unwrap();
+        assert_eq!(breakers["upstream"]["state"], "closed");
+        client
+            .request(admin_request(TestRequest::post(
+                "/admin/circuit-breakers/upstream/reset",
+            )))
+            .await
+            .assert_status(StatusCode::OK);
+
+        client.get("/hello").await.assert_status(StatusCode::OK);
+        assert_eq!(handle.len(), 1);
+        let invalidated: Value = client
+            .request(admin_request(TestRequest::post(
+                "/admin/caches/responses/invalidate",
+            )))
+            .await
+            .json()
+            .unwrap();
+        assert_eq!(invalidated["removed"], 1);
+        assert!(handle.is_empty());
+    }
+}
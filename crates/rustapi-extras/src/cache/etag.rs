@@ -0,0 +1,231 @@
+//! Automatic ETags and `304 Not Modified`
+//!
+//! [`EtagLayer`] hashes buffered response bodies of `GET` requests, sets the
+//! `ETag` header and answers a matching `If-None-Match` with an empty
+//! `304 Not Modified`. The handler still runs; only the body transfer is
+//! saved, which is what clients polling an unchanged resource need.
+//!
+//! `HEAD` responses carry no body to hash, so they are passed through;
+//! a tag computed from them would never match the `GET` representation.
+//!
+//! Streaming bodies (SSE, file downloads) are never buffered and pass through
+//! untouched. A handler that sets its own `ETag` keeps it; the layer only
+//! performs the conditional check.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! use rustapi_extras::cache::EtagLayer;
+//!
+//! // Everywhere except the admin API
+//! let app = RustApi::new().layer(EtagLayer::new().skip_path("/admin"));
+//!
+//! // Or only for one group of routes
+//! let app = RustApi::new().layer(EtagLayer::new().path_prefix("/api/feeds"));
+//! ```
+
+use super::generate_etag;
+use bytes::Bytes;
+use http::{header, HeaderValue, Method, StatusCode};
+use http_body_util::{BodyExt, Full};
+use rustapi_core::middleware::{BoxedNext, MiddlewareLayer};
+use rustapi_core::{Request, Response, ResponseBody};
+use std::future::Future;
+use std::pin::Pin;
+
+/// Middleware that adds `ETag` headers and answers `If-None-Match`
+#[derive(Clone)]
+pub struct EtagLayer {
+    path_prefixes: Vec<String>,
+    skip_paths: Vec<String>,
+    max_body_size: usize,
+}
+
+impl Default for EtagLayer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl EtagLayer {
+    /// Create a layer that covers every path
+    pub fn new() -> Self {
+        Self {
+            path_prefixes: Vec::new(),
+            skip_paths: Vec::new(),
+            max_body_size: 4 * 1024 * 1024,
+        }
+    }
+
+    /// Only handle paths starting with this prefix (can be repeated)
+    pub fn path_prefix(mut self, prefix: impl Into<String>) -> Self {
+        self.path_prefixes.push(prefix.into());
+        self
+    }
+
+    /// Leave paths starting with this prefix alone (can be repeated)
+    pub fn skip_path(mut self, prefix: impl Into<String>) -> Self {
+        self.skip_paths.push(prefix.into());
+        self
+    }
+
+    /// Largest body that is hashed (default: 4 MiB)
+    pub fn max_body_size(mut self, bytes: usize) -> Self {
+        self.max_body_size = bytes;
+        self
+    }
+
+    fn applies_to(&self, path: &str) -> bool {
+        (self.path_prefixes.is_empty() || self.path_prefixes.iter().any(|p| path.starts_with(p)))
+            && !self.skip_paths.iter().any(|p| path.starts_with(p))
+    }
+}
+
+impl MiddlewareLayer for EtagLayer {
+    fn call(
+        &self,
+        req: Request,
+        next: BoxedNext,
+    ) -> Pin<Box<dyn Future<Output = Response> + Send + 'static>> {
+        let layer = self.clone();
+
+        Box::pin(async move {
+            if req.method() != Method::GET || !layer.applies_to(req.uri().path()) {
+                return next(req).await;
+            }
+
+            let if_none_match = req
+                .headers()
+                .get(header::IF_NONE_MATCH)
+                .and_then(|value| value.to_str().ok())
+                .map(str::to_string);
+
+            let response = next(req).await;
+            if response.status() != StatusCode::OK || is_no_store(&response) {
+                return response;
+            }
+
+            let (mut parts, body) = response.into_parts();
+            let (etag, body) = match parts.headers.get(header::ETAG).cloned() {
+                Some(etag) => (etag, body),
+                None => {
+                    let bytes = match body {
+                        ResponseBody::Full(full) => full
+                            .collect()
+                            .await
+                            .map(|c| c.to_bytes())
+                            .unwrap_or_default(),
+                        streaming => return Response::from_parts(parts, streaming),
+                    };
+                    let body = ResponseBody::Full(Full::new(bytes.clone()));
+                    if bytes.len() > layer.max_body_size {
+                        return Response::from_parts(parts, body);
+                    }
+
+                    let etag = HeaderValue::from_str(&generate_etag(&bytes))
+                        .expect("hex ETag is a valid header value");
+                    parts.headers.insert(header::ETAG, etag.clone());
+                    (etag, body)
+                }
+            };
+
+            if !if_none_match.is_some_and(|candidates| etag_matches(&candidates, &etag)) {
+                return Response::from_parts(parts, body);
+            }
+
+            parts.status = StatusCode::NOT_MODIFIED;
+            parts.headers.remove(header::CONTENT_LENGTH);
+            parts.headers.remove(header::CONTENT_TYPE);
+            Response::from_parts(parts, ResponseBody::Full(Full::new(Bytes::new())))
+        })
+    }
+
+    fn clone_box(&self) -> Box<dyn MiddlewareLayer> {
+        Box::new(self.clone())
+    }
+}
+
+/// `Cache-Control: no-store` responses must not be revalidated
+fn is_no_store(response: &Response) -> bool {
+    response
+        .headers()
+        .get(header::CACHE_CONTROL)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|value| value.to_ascii_lowercase().contains("no-store"))
+}
+
+/// Weak comparison of an `If-None-Match` list against an entity tag
+fn etag_matches(candidates: &str, etag: &HeaderValue) -> bool {
+    let Ok(etag) = etag.to_str() else {
+        return false;
+    };
+    let opaque = |tag: &str| tag.trim().trim_start_matches("W/").to_string();
+    let etag = opaque(etag);
+    candidates
+        .split(',')
+        .any(|candidate| candidate.trim() == "*" || opaque(candidate) == etag)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn if_none_match_uses_weak_comparison() {
+        let etag = HeaderValue::from_static("\"abc\"");
+        assert!(etag_matches("\"abc\"", &etag));
+        assert!(etag_matches("W/\"abc\"", &etag));
+        assert!(etag_matches("\"x\", \"abc\"", &etag));
+        assert!(etag_matches("*", &etag));
+        assert!(!etag_matches("\"abcd\"", &etag));
+    }
+
+    #[test]
+    fn path_filters() {
+        let layer = EtagLayer::new().path_prefix("/api").skip_path("/api/admin");
+        assert!(layer.applies_to("/api/feeds"));
+        assert!(!layer.applies_to("/api/admin/users"));
+        assert!(!layer.applies_to("/health"));
+        assert!(EtagLayer::new().applies_to("/anything"));
+    }
+
+    #[tokio::test]
+    async fn unchanged_body_gets_304() {
+        use rustapi_core::{get_route, head_route, Json, RustApi};
+        use rustapi_testing::{TestClient, TestRequest};
+
+        async fn feed() -> Json<Vec<&'static str>> {
+            Json(vec!["first", "second"])
+        }
+
+        let app = RustApi::new()
+            .layer(EtagLayer::new())
+            .mount_route(get_route("/feed", feed))
+            .mount_route(head_route("/probe", feed));
+        let client = TestClient::new(app);
+
+        let first = client.get("/feed").await;
+        first.assert_status(StatusCode::OK);
+        let etag = first.headers()[header::ETAG].to_str().unwrap().to_string();
+
+        let revalidated = client
+            .request(TestRequest::get("/feed").header("if-none-match", &etag))
+            .await;
+        revalidated
+            .assert_status(StatusCode::NOT_MODIFIED)
+            .assert_header("etag", &etag);
+        assert!(revalidated.body().is_empty());
+
+        client
+            .request(TestRequest::get("/feed").header("if-none-match", "\"stale\""))
+            .await
+            .assert_status(StatusCode::OK)
+            .assert_body_contains("second");
+
+        let head = client
+            .request(TestRequest::head("/probe").header("if-none-match", &etag))
+            .await;
+        head.assert_status(StatusCode::OK);
+        assert!(head.headers().get(header::ETAG).is_none());
+    }
+}
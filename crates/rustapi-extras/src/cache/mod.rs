@@ -5,6 +5,7 @@
 //! - ETag / If-None-Match support (304 Not Modified)
 //! - Cache-Control header awareness (no-cache, no-store)
 //! - Path skip lists and vary-by-header support
+//! - [`EtagLayer`] for ETags and 304s without storing responses
 //! - Cache invalidation via `CacheHandle`
 //!
 //! Requires `cache` feature.
@@ -32,12 +33,16 @@
 //! }
 //! ```
 
+mod etag;
+
+pub use etag::EtagLayer;
+
 use bytes::Bytes;
 use dashmap::DashMap;
 use http_body_util::BodyExt;
 use rustapi_core::{
     middleware::{BoxedNext, MiddlewareLayer},
-    Request, Response, ResponseBody,
+    Clock, Request, Response, ResponseBody,
 };
 use std::collections::VecDeque;
 use std::future::Future;
@@ -391,6 +396,7 @@ impl MiddlewareLayer for CacheLayer {
         Box::pin(async move {
             let method = req.method().to_string();
             let uri = req.uri().to_string();
+            let clock = Clock::of(&req);
 
             // Check if cacheable
             if !config.methods.contains(&method)
@@ -412,7 +418,7 @@ impl MiddlewareLayer for CacheLayer {
 
             // Look up cached entry
             if let Some(entry) = store.get(&key) {
-                if entry.created_at.elapsed() < config.ttl {
+                if clock.instant().saturating_duration_since(entry.created_at) < config.ttl {
                     // ETag: return 304 Not Modified if client has the same ETag
                     if let (Some(ref etag), Some(ref client_etag)) = (&entry.etag, &if_none_match) {
                         if etag == client_etag {
@@ -471,7 +477,7 @@ impl MiddlewareLayer for CacheLayer {
                             headers: parts.headers.clone(),
                             body: bytes.clone(),
                             etag: etag.clone(),
-                            created_at: Instant::now(),
+                            created_at: clock.instant(),
                         };
 
                         store.insert(key, cached);
@@ -629,4 +635,43 @@ mod tests {
         assert!(!layer.config.etag);
         assert!(handle.is_empty());
     }
+
+    #[tokio::test]
+    async fn test_entries_expire_by_request_clock() {
+        use rustapi_core::middleware::LayerStack;
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        let calls = Arc::new(AtomicUsize::new(0));
+        let counter = calls.clone();
+        let handler: BoxedNext = Arc::new(move |_req: Request| {
+            counter.fetch_add(1, Ordering::SeqCst);
+            Box::pin(async {
+                http::Response::builder()
+                    .body(ResponseBody::Full(http_body_util::Full::new(Bytes::from(
+                        "fresh",
+                    ))))
+                    .unwrap()
+            }) as Pin<Box<dyn Future<Output = Response> + Send + 'static>>
+        });
+
+        let clock = Clock::manual();
+        let mut stack = LayerStack::new();
+        stack.push(Box::new(CacheLayer::new().ttl(Duration::from_secs(30))));
+        let send = || {
+            let req = http::Request::builder().uri("/items").body(()).unwrap();
+            let mut req = Request::from_http_request(req, Bytes::new());
+            req.extensions_mut().insert(clock.clone());
+            stack.execute(req, handler.clone())
+        };
+
+        send().await;
+        let hit = send().await;
+        assert_eq!(hit.headers().get("X-Cache").unwrap(), "HIT");
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+
+        clock.advance(Duration::from_secs(30));
+        let miss = send().await;
+        assert_eq!(miss.headers().get("X-Cache").unwrap(), "MISS");
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+    }
 }
@@ -154,6 +154,54 @@ impl CircuitBreakerLayer {
         let mut state = self.state.write().await;
         *state = CircuitBreakerState::default();
     }
+
+    /// Count a call and decide whether it may proceed; `false` means the
+    /// circuit is open and the call should fail fast.
+    pub(crate) async fn try_acquire(&self) -> bool {
+        let mut state = self.state.write().await;
+        state.total_requests += 1;
+
+        if state.state == CircuitState::Open {
+            match state.last_failure_time {
+                Some(last_failure) if last_failure.elapsed() < self.config.timeout => {
+                    return false;
+                }
+                _ => {
+                    tracing::info!("Circuit breaker transitioning to HalfOpen");
+                    state.state = CircuitState::HalfOpen;
+                    state.success_count = 0;
+                }
+            }
+        }
+        true
+    }
+
+    /// Update the state with the outcome of a call admitted by `try_acquire`.
+    pub(crate) async fn record(&self, success: bool) {
+        let mut state = self.state.write().await;
+        if !success {
+            record_failure(&mut state, &self.config);
+            return;
+        }
+
+        state.total_successes += 1;
+        match state.state {
+            CircuitState::HalfOpen => {
+                state.success_count += 1;
+                if state.success_count >= self.config.success_threshold {
+                    tracing::info!("Circuit breaker transitioning to Closed");
+                    state.state = CircuitState::Closed;
+                    state.failure_count = 0;
+                    state.success_count = 0;
+                }
+            }
+            CircuitState::Closed => {
+                // Reset failure count on success
+                state.failure_count = 0;
+            }
+            _ => {}
+        }
+    }
 }
 
 impl Default for CircuitBreakerLayer {
@@ -185,85 +233,33 @@ impl MiddlewareLayer for CircuitBreakerLayer {
         req: Request,
         next: BoxedNext,
     ) -> Pin<Box<dyn Future<Output = Response> + Send + 'static>> {
-        let config = self.config.clone();
-        let state = self.state.clone();
+        let breaker = self.clone();
 
         Box::pin(async move {
-            // Check current state
-            let mut state_guard = state.write().await;
-            state_guard.total_requests += 1;
-
-            match state_guard.state {
-                CircuitState::Open => {
-                    // Check if timeout has elapsed
-                    if let Some(last_failure) = state_guard.last_failure_time {
-                        if last_failure.elapsed() >= config.timeout {
-                            // Transition to half-open
-                            tracing::info!("Circuit breaker transitioning to HalfOpen");
-                            state_guard.state = CircuitState::HalfOpen;
-                            state_guard.success_count = 0;
-                        } else {
-                            // Still open, fail fast
-                            drop(state_guard);
-                            return http::Response::builder()
-                                .status(503)
-                                .header("Content-Type", "application/json")
-                                .body(ResponseBody::Full(http_body_util::Full::new(
-                                    bytes::Bytes::from(
-                                        serde_json::json!({
-                                            "error": {
-                                                "type": "service_unavailable",
-                                                "message": "Circuit breaker is OPEN"
-                                            }
-                                        })
-                                        .to_string(),
-                                    ),
-                                )))
-                                .unwrap();
-                        }
-                    }
-                }
-                CircuitState::HalfOpen => {
-                    // Allow request but monitor closely
-                }
-                CircuitState::Closed => {
-                    // Normal operation
-                }
+            if !breaker.try_acquire().await {
+                // Still open, fail fast
+                return http::Response::builder()
+                    .status(503)
+                    .header("Content-Type", "application/json")
+                    .body(ResponseBody::Full(http_body_util::Full::new(
+                        bytes::Bytes::from(
+                            serde_json::json!({
+                                "error": {
+                                    "type": "service_unavailable",
+                                    "message": "Circuit breaker is OPEN"
+                                }
+                            })
+                            .to_string(),
+                        ),
+                    )))
+                    .unwrap();
             }
 
-            drop(state_guard);
-
             // Execute request
             let response = next(req).await;
 
-            // Update state based on result
-            let mut state_guard = state.write().await;
-
-            // Check if response indicates success (2xx status)
-            if response.status().is_success() {
-                state_guard.total_successes += 1;
-
-                match state_guard.state {
-                    CircuitState::HalfOpen => {
-                        state_guard.success_count += 1;
-                        if state_guard.success_count >= config.success_threshold {
-                            // Transition to closed
-                            tracing::info!("Circuit breaker transitioning to Closed");
-                            state_guard.state = CircuitState::Closed;
-                            state_guard.failure_count = 0;
-                            state_guard.success_count = 0;
-                        }
-                    }
-                    CircuitState::Closed => {
-                        // Reset failure count on success
-                        state_guard.failure_count = 0;
-                    }
-                    _ => {}
-                }
-            } else {
-                // Non-2xx status is treated as failure
-                record_failure(&mut state_guard, &config);
-            }
+            // Non-2xx status is treated as failure
+            breaker.record(response.status().is_success()).await;
 
             response
         })
@@ -96,6 +96,18 @@ impl CorsLayer {
         Self::new()
     }
 
+    /// Create a CORS layer matching the current [`Profile`](rustapi_core::Profile).
+    ///
+    /// Restrictive when the profile asks for strict CORS (production by
+    /// default), permissive otherwise.
+    pub fn for_current_profile() -> Self {
+        if rustapi_core::Profile::current().strict_cors {
+            Self::restrictive()
+        } else {
+            Self::permissive()
+        }
+    }
+
     /// Allow any origin.
     pub fn allow_any_origin(mut self) -> Self {
         self.origins = AllowedOrigins::Any;
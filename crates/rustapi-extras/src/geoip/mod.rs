@@ -0,0 +1,289 @@
+//! GeoIP enrichment
+//!
+//! [`GeoIpLayer`] looks up the client address of each request and stores the
+//! result as a [`GeoIp`]: ISO country code and autonomous system. Handlers take
+//! it as an extractor; layers added inside the GeoIP layer read it with
+//! [`GeoIp::of`]:
+//!
+//! - `RateLimitLayer::limit_by` can give some countries or networks a stricter
+//!   limit
+//! - `StructuredLoggingLayer` adds `geo_country` and `geo_asn` fields
+//!
+//! Lookups come from MaxMind databases ([`MaxMindLookup`], GeoLite2 or GeoIP2
+//! Country/City and ASN files) or any [`GeoIpLookup`]. Addresses a database
+//! does not know, such as private ranges, get a `GeoIp` without data.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! use rustapi_extras::geoip::{GeoIp, GeoIpLayer, MaxMindLookup};
+//!
+//! let lookup = MaxMindLookup::open("GeoLite2-Country.mmdb")?.with_asn_db("GeoLite2-ASN.mmdb")?;
+//!
+//! async fn hello(geo: GeoIp) -> String {
+//!     format!("Hello from {}", geo.country().unwrap_or("somewhere"))
+//! }
+//!
+//! let app = RustApi::new()
+//!     .layer(GeoIpLayer::new(lookup))
+//!     .layer(RateLimitLayer::new(100, Duration::from_secs(60)).limit_by(|req: &Request| {
+//!         (GeoIp::of(req)?.country()? == "XX").then_some(10)
+//!     }))
+//!     .route("/", get(hello));
+//! ```
+
+use rustapi_core::middleware::{BoxedNext, MiddlewareLayer};
+use rustapi_core::{ApiError, ClientIp, FromRequestParts, Request, Response, Result};
+use rustapi_openapi::{Operation, OperationModifier};
+use std::fmt;
+use std::future::Future;
+use std::net::IpAddr;
+use std::path::Path;
+use std::pin::Pin;
+use std::sync::Arc;
+
+/// Location data for a client address
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GeoIp {
+    /// The address that was looked up
+    pub ip: IpAddr,
+    /// ISO 3166-1 alpha-2 country code
+    pub country: Option<String>,
+    /// Autonomous system number
+    pub asn: Option<u32>,
+    /// Autonomous system organization
+    pub as_organization: Option<String>,
+}
+
+impl GeoIp {
+    /// An address without location data
+    pub fn unknown(ip: IpAddr) -> Self {
+        Self {
+            ip,
+            country: None,
+            asn: None,
+            as_organization: None,
+        }
+    }
+
+    /// ISO country code, e.g. `DE`
+    pub fn country(&self) -> Option<&str> {
+        self.country.as_deref()
+    }
+
+    /// Autonomous system number
+    pub fn asn(&self) -> Option<u32> {
+        self.asn
+    }
+
+    /// The lookup stored by a [`GeoIpLayer`] that ran before this point
+    pub fn of(req: &Request) -> Option<&GeoIp> {
+        req.extensions().get::<GeoIp>()
+    }
+
+    /// Fields added to structured log entries
+    #[cfg_attr(not(feature = "structured-logging"), allow(dead_code))]
+    pub(crate) fn log_fields(&self) -> Vec<(String, String)> {
+        let mut fields = Vec::new();
+        if let Some(country) = &self.country {
+            fields.push(("geo_country".to_string(), country.clone()));
+        }
+        if let Some(asn) = self.asn {
+            fields.push(("geo_asn".to_string(), asn.to_string()));
+        }
+        fields
+    }
+}
+
+impl FromRequestParts for GeoIp {
+    fn from_request_parts(req: &Request) -> Result<Self> {
+        GeoIp::of(req).cloned().ok_or_else(|| {
+            ApiError::internal("GeoIp not found. Did you forget to add GeoIpLayer middleware?")
+        })
+    }
+}
+
+impl OperationModifier for GeoIp {
+    fn update_operation(_op: &mut Operation) {}
+}
+
+/// Resolves addresses to location data
+///
+/// Implemented for closures, which is handy for tests and static tables.
+pub trait GeoIpLookup: Send + Sync + 'static {
+    /// Location data for `ip`; [`GeoIp::unknown`] if there is none
+    fn lookup(&self, ip: IpAddr) -> GeoIp;
+}
+
+impl<F> GeoIpLookup for F
+where
+    F: Fn(IpAddr) -> GeoIp + Send + Sync + 'static,
+{
+    fn lookup(&self, ip: IpAddr) -> GeoIp {
+        self(ip)
+    }
+}
+
+/// Error opening a MaxMind database
+#[derive(Debug)]
+pub struct GeoIpError(maxminddb::MaxMindDBError);
+
+impl fmt::Display for GeoIpError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Failed to open GeoIP database: {}", self.0)
+    }
+}
+
+impl std::error::Error for GeoIpError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        Some(&self.0)
+    }
+}
+
+/// Lookups in MaxMind (`.mmdb`) databases
+///
+/// The files are read into memory when opened.
+pub struct MaxMindLookup {
+    country: maxminddb::Reader<Vec<u8>>,
+    asn: Option<maxminddb::Reader<Vec<u8>>>,
+}
+
+impl MaxMindLookup {
+    /// Open a Country or City database
+    pub fn open(path: impl AsRef<Path>) -> std::result::Result<Self, GeoIpError> {
+        Ok(Self {
+            country: maxminddb::Reader::open_readfile(path).map_err(GeoIpError)?,
+            asn: None,
+        })
+    }
+
+    /// Also look up autonomous systems in an ASN database
+    pub fn with_asn_db(mut self, path: impl AsRef<Path>) -> std::result::Result<Self, GeoIpError> {
+        self.asn = Some(maxminddb::Reader::open_readfile(path).map_err(GeoIpError)?);
+        Ok(self)
+    }
+}
+
+impl GeoIpLookup for MaxMindLookup {
+    fn lookup(&self, ip: IpAddr) -> GeoIp {
+        let mut geo = GeoIp::unknown(ip);
+        if let Ok(record) = self.country.lookup::<maxminddb::geoip2::Country>(ip) {
+            geo.country = record
+                .country
+                .and_then(|country| country.iso_code)
+                .map(str::to_string);
+        }
+        if let Some(Ok(record)) = self
+            .asn
+            .as_ref()
+            .map(|reader| reader.lookup::<maxminddb::geoip2::Asn>(ip))
+        {
+            geo.asn = record.autonomous_system_number;
+            geo.as_organization = record.autonomous_system_organization.map(str::to_string);
+        }
+        geo
+    }
+}
+
+/// Middleware storing a [`GeoIp`] lookup of the client address
+#[derive(Clone)]
+pub struct GeoIpLayer {
+    lookup: Arc<dyn GeoIpLookup>,
+    trust_forwarded_for: bool,
+}
+
+impl GeoIpLayer {
+    /// Create a layer using `lookup`
+    pub fn new(lookup: impl GeoIpLookup) -> Self {
+        Self {
+            lookup: Arc::new(lookup),
+            trust_forwarded_for: false,
+        }
+    }
+
+    /// Look up the first `X-Forwarded-For` address instead of the peer address
+    ///
+    /// Only enable this behind a proxy that overwrites the header.
+    pub fn trust_forwarded_for(mut self, trust: bool) -> Self {
+        self.trust_forwarded_for = trust;
+        self
+    }
+}
+
+impl MiddlewareLayer for GeoIpLayer {
+    fn call(
+        &self,
+        mut req: Request,
+        next: BoxedNext,
+    ) -> Pin<Box<dyn Future<Output = Response> + Send + 'static>> {
+        if let Ok(ClientIp(ip)) = ClientIp::extract_with_config(&req, self.trust_forwarded_for) {
+            let geo = self.lookup.lookup(ip.to_canonical());
+            req.extensions_mut().insert(geo);
+        }
+        Box::pin(async move { next(req).await })
+    }
+
+    fn clone_box(&self) -> Box<dyn MiddlewareLayer> {
+        Box::new(self.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bytes::Bytes;
+    use rustapi_core::middleware::LayerStack;
+    use rustapi_core::IntoResponse;
+    use std::net::SocketAddr;
+
+    fn table(ip: IpAddr) -> GeoIp {
+        let mut geo = GeoIp::unknown(ip);
+        if ip.to_string().starts_with("198.51.100.") {
+            geo.country = Some("NL".to_string());
+            geo.asn = Some(64500);
+        }
+        geo
+    }
+
+    #[tokio::test]
+    async fn layer_stores_the_lookup_for_handlers() {
+        let mut stack = LayerStack::new();
+        stack.push(Box::new(GeoIpLayer::new(table)));
+
+        let mut req = http::Request::builder().uri("/").body(()).unwrap();
+        req.extensions_mut()
+            .insert(SocketAddr::from(([198, 51, 100, 7], 4000)));
+        let req = Request::from_http_request(req, Bytes::new());
+
+        let next: BoxedNext = Arc::new(|req: Request| {
+            Box::pin(async move {
+                let geo = GeoIp::from_request_parts(&req).unwrap();
+                format!("{:?} {:?}", geo.country(), geo.asn()).into_response()
+            }) as Pin<Box<dyn Future<Output = Response> + Send + 'static>>
+        });
+        let response = stack.execute(req, next).await;
+        let body = http_body_util::BodyExt::collect(response.into_body())
+            .await
+            .unwrap()
+            .to_bytes();
+        assert_eq!(body, "Some(\"NL\") Some(64500)");
+    }
+
+    #[test]
+    fn log_fields_skip_missing_data() {
+        let ip: IpAddr = "198.51.100.7".parse().unwrap();
+        assert_eq!(
+            table(ip).log_fields(),
+            vec![
+                ("geo_country".to_string(), "NL".to_string()),
+                ("geo_asn".to_string(), "64500".to_string())
+            ]
+        );
+        assert!(GeoIp::unknown(ip).log_fields().is_empty());
+    }
+
+    #[test]
+    fn missing_database_is_an_error() {
+        assert!(MaxMindLookup::open("/nonexistent/GeoLite2-Country.mmdb").is_err());
+    }
+}
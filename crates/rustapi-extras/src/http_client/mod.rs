@@ -0,0 +1,757 @@
+//! Instrumented outbound HTTP client
+//!
+//! [`HttpClient`] wraps `reqwest` so calls to downstream services get the same
+//! treatment as inbound traffic:
+//!
+//! - the inbound request ID and W3C trace context are forwarded
+//!   (`x-request-id`, `traceparent`, `tracestate`), with a new span per call
+//! - per-request timeouts, [`RetryLayer`] backoff and a [`CircuitBreakerLayer`]
+//!   are applied around every call
+//! - the inbound request's [`Deadline`] caps the timeout of every attempt, so a
+//!   call never waits longer than the caller does
+//! - each call emits a tracing event and updates [`HttpClientStats`]
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! use rustapi_extras::http_client::{HttpClient, RequestContext};
+//! use rustapi_extras::{CircuitBreakerLayer, RetryLayer};
+//! use std::time::Duration;
+//!
+//! let users = HttpClient::new()
+//!     .base_url("http://users.internal")
+//!     .timeout(Duration::from_secs(2))
+//!     .retry(RetryLayer::new().max_attempts(2))
+//!     .circuit_breaker(CircuitBreakerLayer::new().failure_threshold(5));
+//!
+//! async fn handler(State(users): State<HttpClient>, ctx: RequestContext) -> Result<Json<User>> {
+//!     let user = users.get("/users/42").context(&ctx).send().await?.json().await?;
+//!     Ok(Json(user))
+//! }
+//! ```
+
+use crate::circuit_breaker::{CircuitBreakerLayer, CircuitState};
+use crate::retry::RetryLayer;
+use http::header::{HeaderName, HeaderValue};
+use http::{Method, StatusCode};
+use rustapi_core::{ApiError, Deadline, FromRequestParts, Request, RequestId};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// Error from an outbound call.
+#[derive(Debug)]
+pub enum HttpClientError {
+    /// The call did not complete within the configured timeout.
+    Timeout,
+    /// The circuit breaker is open; the call was not attempted.
+    CircuitOpen,
+    /// The URL could not be built from the base URL and path.
+    InvalidUrl(String),
+    /// Any other transport error.
+    Request(reqwest::Error),
+}
+
+impl std::fmt::Display for HttpClientError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Timeout => write!(f, "Outbound request timed out"),
+            Self::CircuitOpen => write!(f, "Circuit breaker is open"),
+            Self::InvalidUrl(url) => write!(f, "Invalid URL: {}", url),
+            Self::Request(e) => write!(f, "Outbound request failed: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for HttpClientError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Request(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<reqwest::Error> for HttpClientError {
+    fn from(e: reqwest::Error) -> Self {
+        if e.is_timeout() {
+            Self::Timeout
+        } else {
+            Self::Request(e)
+        }
+    }
+}
+
+impl From<HttpClientError> for ApiError {
+    fn from(e: HttpClientError) -> Self {
+        let (status, error_type) = match e {
+            HttpClientError::Timeout => (StatusCode::GATEWAY_TIMEOUT, "upstream_timeout"),
+            HttpClientError::CircuitOpen => {
+                (StatusCode::SERVICE_UNAVAILABLE, "upstream_unavailable")
+            }
+            HttpClientError::InvalidUrl(_) | HttpClientError::Request(_) => {
+                (StatusCode::BAD_GATEWAY, "upstream_error")
+            }
+        };
+        ApiError::new(status, error_type, e.to_string())
+    }
+}
+
+/// Request ID, trace context and deadline of an inbound request, forwarded
+/// on outbound calls.
+///
+/// Extract it in a handler and pass it to [`HttpRequest::context`]. The
+/// request ID comes from [`RequestIdLayer`](rustapi_core::RequestIdLayer) or
+/// the `x-request-id` header; the trace from `OtelLayer` (with the `otel`
+/// feature) or the `traceparent` header; the deadline from `TimeoutLayer`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RequestContext {
+    request_id: Option<String>,
+    trace_id: Option<String>,
+    trace_flags: Option<String>,
+    trace_state: Option<String>,
+    deadline: Option<Deadline>,
+}
+
+impl RequestContext {
+    /// Capture the context of `req`.
+    pub fn from_request(req: &Request) -> Self {
+        let header = |name: &str| {
+            req.headers()
+                .get(name)
+                .and_then(|value| value.to_str().ok())
+                .map(str::to_string)
+        };
+
+        let request_id = req
+            .extensions()
+            .get::<RequestId>()
+            .map(|id| id.to_string())
+            .or_else(|| header("x-request-id"));
+        let deadline = Deadline::of(req).copied();
+
+        #[cfg(feature = "otel")]
+        if let Some(trace) = req.extensions().get::<crate::otel::TraceContext>() {
+            return Self {
+                request_id,
+                trace_id: Some(trace.trace_id.clone()),
+                trace_flags: Some(format!("{:02x}", trace.trace_flags)),
+                trace_state: trace.trace_state.clone(),
+                deadline,
+            };
+        }
+
+        let (trace_id, trace_flags) = header("traceparent")
+            .and_then(|value| parse_traceparent(&value))
+            .unzip();
+        Self {
+            request_id,
+            trace_state: trace_id.as_ref().and_then(|_| header("tracestate")),
+            trace_id,
+            trace_flags,
+            deadline,
+        }
+    }
+
+    /// Request ID forwarded as `x-request-id`.
+    pub fn request_id(&self) -> Option<&str> {
+        self.request_id.as_deref()
+    }
+
+    /// Trace ID forwarded in `traceparent`.
+    pub fn trace_id(&self) -> Option<&str> {
+        self.trace_id.as_deref()
+    }
+
+    /// Deadline of the inbound request.
+    pub fn deadline(&self) -> Option<Deadline> {
+        self.deadline
+    }
+
+    /// Set outbound headers that the caller has not set explicitly.
+    fn apply(&self, headers: &mut http::HeaderMap) {
+        let mut set = |name: &'static str, value: String| {
+            if let Ok(value) = HeaderValue::try_from(value) {
+                headers
+                    .entry(HeaderName::from_static(name))
+                    .or_insert(value);
+            }
+        };
+
+        if let Some(id) = &self.request_id {
+            set("x-request-id", id.clone());
+        }
+        if let Some(trace_id) = &self.trace_id {
+            let flags = self.trace_flags.as_deref().unwrap_or("01");
+            set(
+                "traceparent",
+                format!("00-{trace_id}-{}-{flags}", new_span_id()),
+            );
+            if let Some(state) = &self.trace_state {
+                set("tracestate", state.clone());
+            }
+        }
+    }
+}
+
+impl FromRequestParts for RequestContext {
+    fn from_request_parts(req: &Request) -> rustapi_core::Result<Self> {
+        Ok(Self::from_request(req))
+    }
+}
+
+impl rustapi_openapi::OperationModifier for RequestContext {
+    fn update_operation(_op: &mut rustapi_openapi::Operation) {
+        // Read from headers and extensions set by middleware; nothing to document.
+    }
+}
+
+/// Trace ID and flags of a `traceparent` header, rejecting all-zero IDs.
+fn parse_traceparent(value: &str) -> Option<(String, String)> {
+    let mut parts = value.trim().split('-');
+    let (_version, trace_id, span_id, flags) =
+        (parts.next()?, parts.next()?, parts.next()?, parts.next()?);
+
+    let valid = |id: &str, len: usize| {
+        id.len() == len
+            && id.bytes().all(|b| b.is_ascii_hexdigit())
+            && id.bytes().any(|b| b != b'0')
+    };
+    (valid(trace_id, 32) && valid(span_id, 16) && flags.len() == 2)
+        .then(|| (trace_id.to_ascii_lowercase(), flags.to_ascii_lowercase()))
+}
+
+/// Random 64-bit span ID, hex encoded.
+fn new_span_id() -> String {
+    uuid::Uuid::new_v4().simple().to_string()[..16].to_string()
+}
+
+/// Counters shared by all clones of an [`HttpClient`].
+#[derive(Debug, Default)]
+struct Counters {
+    requests: AtomicU64,
+    successes: AtomicU64,
+    failures: AtomicU64,
+    retries: AtomicU64,
+    rejected: AtomicU64,
+    total_duration_us: AtomicU64,
+}
+
+/// Snapshot of an [`HttpClient`]'s call statistics.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct HttpClientStats {
+    /// Calls made, not counting retries.
+    pub requests: u64,
+    /// Calls that ended with a status below 500.
+    pub successes: u64,
+    /// Calls that ended with a transport error, timeout or 5xx status.
+    pub failures: u64,
+    /// Retry attempts made.
+    pub retries: u64,
+    /// Calls rejected by the open circuit breaker.
+    pub rejected: u64,
+    /// Total time spent in calls, including retries and backoff.
+    pub total_duration: Duration,
+}
+
+/// Outbound HTTP client with trace propagation, resilience and metrics.
+///
+/// Clones share the connection pool, circuit breaker and statistics, so one
+/// client per downstream service can be kept in application state.
+#[derive(Clone)]
+pub struct HttpClient {
+    http: reqwest::Client,
+    base_url: Option<String>,
+    timeout: Option<Duration>,
+    retry: Option<RetryLayer>,
+    circuit_breaker: Option<CircuitBreakerLayer>,
+    counters: Arc<Counters>,
+}
+
+impl std::fmt::Debug for HttpClient {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("HttpClient")
+            .field("base_url", &self.base_url)
+            .field("timeout", &self.timeout)
+            .field("retry", &self.retry.is_some())
+            .field("circuit_breaker", &self.circuit_breaker.is_some())
+            .finish_non_exhaustive()
+    }
+}
+
+impl HttpClient {
+    /// Create a client with a default `reqwest` client, a 30 second timeout,
+    /// no retries and no circuit breaker.
+    pub fn new() -> Self {
+        Self::with_client(reqwest::Client::new())
+    }
+
+    /// Create a client around an existing `reqwest` client.
+    pub fn with_client(http: reqwest::Client) -> Self {
+        Self {
+            http,
+            base_url: None,
+            timeout: Some(Duration::from_secs(30)),
+            retry: None,
+            circuit_breaker: None,
+            counters: Arc::new(Counters::default()),
+        }
+    }
+
+    /// Resolve relative request paths against `url`.
+    pub fn base_url(mut self, url: impl Into<String>) -> Self {
+        self.base_url = Some(url.into().trim_end_matches('/').to_string());
+        self
+    }
+
+    /// Set the timeout of each attempt. `None` disables it.
+    pub fn timeout(mut self, timeout: impl Into<Option<Duration>>) -> Self {
+        self.timeout = timeout.into();
+        self
+    }
+
+    /// Retry transport errors, timeouts and the layer's retryable statuses
+    /// with its backoff strategy.
+    pub fn retry(mut self, retry: RetryLayer) -> Self {
+        self.retry = Some(retry);
+        self
+    }
+
+    /// Fail fast while `breaker` is open. Transport errors, timeouts and 5xx
+    /// responses count as failures.
+    pub fn circuit_breaker(mut self, breaker: CircuitBreakerLayer) -> Self {
+        self.circuit_breaker = Some(breaker);
+        self
+    }
+
+    /// Start a request. `url` is joined to the base URL unless it is absolute.
+    pub fn request(&self, method: Method, url: &str) -> HttpRequest {
+        let builder = match self.resolve(url) {
+            Ok(url) => Ok(self.http.request(method, url)),
+            Err(e) => Err(e),
+        };
+        HttpRequest {
+            client: self.clone(),
+            builder,
+            context: None,
+            deadline: None,
+        }
+    }
+
+    /// Start a `GET` request.
+    pub fn get(&self, url: &str) -> HttpRequest {
+        self.request(Method::GET, url)
+    }
+
+    /// Start a `POST` request.
+    pub fn post(&self, url: &str) -> HttpRequest {
+        self.request(Method::POST, url)
+    }
+
+    /// Start a `PUT` request.
+    pub fn put(&self, url: &str) -> HttpRequest {
+        self.request(Method::PUT, url)
+    }
+
+    /// Start a `PATCH` request.
+    pub fn patch(&self, url: &str) -> HttpRequest {
+        self.request(Method::PATCH, url)
+    }
+
+    /// Start a `DELETE` request.
+    pub fn delete(&self, url: &str) -> HttpRequest {
+        self.request(Method::DELETE, url)
+    }
+
+    /// Call statistics since the client was created.
+    pub fn stats(&self) -> HttpClientStats {
+        let c = &self.counters;
+        HttpClientStats {
+            requests: c.requests.load(Ordering::Relaxed),
+            successes: c.successes.load(Ordering::Relaxed),
+            failures: c.failures.load(Ordering::Relaxed),
+            retries: c.retries.load(Ordering::Relaxed),
+            rejected: c.rejected.load(Ordering::Relaxed),
+            total_duration: Duration::from_micros(c.total_duration_us.load(Ordering::Relaxed)),
+        }
+    }
+
+    /// State of the circuit breaker, if one is configured.
+    pub async fn circuit_state(&self) -> Option<CircuitState> {
+        match &self.circuit_breaker {
+            Some(breaker) => Some(breaker.get_state().await),
+            None => None,
+        }
+    }
+
+    fn resolve(&self, url: &str) -> Result<reqwest::Url, HttpClientError> {
+        let joined = match &self.base_url {
+            Some(base) if !url.contains("://") => {
+                format!("{base}/{}", url.trim_start_matches('/'))
+            }
+            _ => url.to_string(),
+        };
+        reqwest::Url::parse(&joined).map_err(|_| HttpClientError::InvalidUrl(joined))
+    }
+
+    async fn execute(
+        &self,
+        request: reqwest::Request,
+        deadline: Option<Deadline>,
+    ) -> Result<reqwest::Response, HttpClientError> {
+        let started = Instant::now();
+        self.counters.requests.fetch_add(1, Ordering::Relaxed);
+        let result = self.execute_with_retries(request, deadline).await;
+
+        let elapsed = started.elapsed();
+        self.counters
+            .total_duration_us
+            .fetch_add(elapsed.as_micros() as u64, Ordering::Relaxed);
+        match &result {
+            Ok(response) if !response.status().is_server_error() => {
+                self.counters.successes.fetch_add(1, Ordering::Relaxed);
+            }
+            Err(HttpClientError::CircuitOpen) => {
+                self.counters.rejected.fetch_add(1, Ordering::Relaxed);
+            }
+            _ => {
+                self.counters.failures.fetch_add(1, Ordering::Relaxed);
+            }
+        }
+        result
+    }
+
+    async fn execute_with_retries(
+        &self,
+        request: reqwest::Request,
+        deadline: Option<Deadline>,
+    ) -> Result<reqwest::Response, HttpClientError> {
+        let max_attempts = self.retry.as_ref().map_or(0, |r| r.config().max_attempts);
+        let mut current = request;
+
+        for attempt in 0..=max_attempts {
+            // Streaming bodies cannot be cloned, so such requests are sent once
+            let retry_copy = if attempt < max_attempts {
+                current.try_clone()
+            } else {
+                None
+            };
+
+            // Each attempt only gets what is left of the caller's budget
+            if let Some(deadline) = deadline {
+                if deadline.is_expired() {
+                    return Err(HttpClientError::Timeout);
+                }
+                let timeout = match current.timeout() {
+                    Some(timeout) => deadline.bound(*timeout),
+                    None => deadline.remaining(),
+                };
+                *current.timeout_mut() = Some(timeout);
+            }
+
+            if let Some(breaker) = &self.circuit_breaker {
+                if !breaker.try_acquire().await {
+                    return Err(HttpClientError::CircuitOpen);
+                }
+            }
+
+            let method = current.method().clone();
+            let url = current.url().clone();
+            let started = Instant::now();
+            let result = self.http.execute(current).await;
+            let elapsed_ms = started.elapsed().as_millis() as u64;
+
+            let failed = match &result {
+                Ok(response) => response.status().is_server_error(),
+                Err(_) => true,
+            };
+            if let Some(breaker) = &self.circuit_breaker {
+                breaker.record(!failed).await;
+            }
+
+            let retryable = match &result {
+                Ok(response) => self.retry.as_ref().is_some_and(|r| {
+                    r.config()
+                        .retryable_statuses
+                        .contains(&response.status().as_u16())
+                }),
+                Err(e) => e.is_timeout() || e.is_connect(),
+            };
+
+            match &result {
+                Ok(response) => tracing::debug!(
+                    method = %method,
+                    url = %url,
+                    status = response.status().as_u16(),
+                    attempt = attempt + 1,
+                    duration_ms = elapsed_ms,
+                    "Outbound request completed"
+                ),
+                Err(e) => tracing::warn!(
+                    method = %method,
+                    url = %url,
+                    error = %e,
+                    attempt = attempt + 1,
+                    duration_ms = elapsed_ms,
+                    "Outbound request failed"
+                ),
+            }
+
+            if let (true, Some(next), Some(retry)) = (retryable, retry_copy, &self.retry) {
+                let backoff = retry.calculate_backoff(attempt);
+                if deadline.is_some_and(|deadline| deadline.remaining() <= backoff) {
+                    return result.map_err(HttpClientError::from);
+                }
+                self.counters.retries.fetch_add(1, Ordering::Relaxed);
+                tokio::time::sleep(backoff).await;
+                current = next;
+                continue;
+            }
+
+            return result.map_err(HttpClientError::from);
+        }
+
+        unreachable!("the final attempt always returns")
+    }
+}
+
+impl Default for HttpClient {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Request being built by an [`HttpClient`].
+pub struct HttpRequest {
+    client: HttpClient,
+    builder: Result<reqwest::RequestBuilder, HttpClientError>,
+    context: Option<RequestContext>,
+    deadline: Option<Deadline>,
+}
+
+impl HttpRequest {
+    fn map(mut self, f: impl FnOnce(reqwest::RequestBuilder) -> reqwest::RequestBuilder) -> Self {
+        self.builder = self.builder.map(f);
+        self
+    }
+
+    /// Forward the request ID and trace context of an inbound request, and
+    /// respect its deadline.
+    pub fn context(mut self, context: &RequestContext) -> Self {
+        self.context = Some(context.clone());
+        self
+    }
+
+    /// Give up when `deadline` passes, including retries.
+    ///
+    /// Overrides the deadline taken from [`context`](Self::context).
+    pub fn deadline(mut self, deadline: Deadline) -> Self {
+        self.deadline = Some(deadline);
+        self
+    }
+
+    /// Add a header.
+    pub fn header<K, V>(self, key: K, value: V) -> Self
+    where
+        HeaderName: TryFrom<K>,
+        <HeaderName as TryFrom<K>>::Error: Into<http::Error>,
+        HeaderValue: TryFrom<V>,
+        <HeaderValue as TryFrom<V>>::Error: Into<http::Error>,
+    {
+        self.map(|builder| builder.header(key, value))
+    }
+
+    /// Set a bearer token.
+    pub fn bearer_auth(self, token: impl std::fmt::Display) -> Self {
+        self.map(|builder| builder.bearer_auth(token))
+    }
+
+    /// Append query parameters.
+    pub fn query<T: serde::Serialize + ?Sized>(self, query: &T) -> Self {
+        self.map(|builder| builder.query(query))
+    }
+
+    /// Send `json` as the body.
+    pub fn json<T: serde::Serialize + ?Sized>(self, json: &T) -> Self {
+        self.map(|builder| builder.json(json))
+    }
+
+    /// Set the raw body.
+    pub fn body(self, body: impl Into<reqwest::Body>) -> Self {
+        self.map(|builder| builder.body(body))
+    }
+
+    /// Send the request.
+    pub async fn send(self) -> Result<reqwest::Response, HttpClientError> {
+        let mut request = self.builder?.build()?;
+        if let Some(context) = &self.context {
+            context.apply(request.headers_mut());
+        }
+        if request.timeout().is_none() {
+            *request.timeout_mut() = self.client.timeout;
+        }
+        let deadline = self
+            .deadline
+            .or_else(|| self.context.as_ref().and_then(|c| c.deadline));
+        self.client.execute(request, deadline).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::VecDeque;
+    use std::sync::Mutex;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+    use tokio::sync::mpsc;
+
+    /// Server answering with `statuses` in order (then 200), reporting each
+    /// request head it receives.
+    async fn serve(statuses: &[u16]) -> (String, mpsc::UnboundedReceiver<String>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let statuses = Arc::new(Mutex::new(
+            statuses.iter().copied().collect::<VecDeque<_>>(),
+        ));
+        let (tx, rx) = mpsc::unbounded_channel();
+
+        tokio::spawn(async move {
+            while let Ok((mut stream, _)) = listener.accept().await {
+                let mut head = Vec::new();
+                let mut buf = [0u8; 1024];
+                while !head.windows(4).any(|w| w == b"\r\n\r\n") {
+                    match stream.read(&mut buf).await {
+                        Ok(0) | Err(_) => break,
+                        Ok(n) => head.extend_from_slice(&buf[..n]),
+                    }
+                }
+                let _ = tx.send(String::from_utf8_lossy(&head).to_lowercase());
+                let status = statuses.lock().unwrap().pop_front().unwrap_or(200);
+                let response = format!(
+                    "HTTP/1.1 {status} X\r\ncontent-length: 0\r\nconnection: close\r\n\r\n"
+                );
+                let _ = stream.write_all(response.as_bytes()).await;
+            }
+        });
+
+        (format!("http://{addr}"), rx)
+    }
+
+    fn inbound(headers: &[(&str, &str)]) -> Request {
+        let mut builder = http::Request::builder().uri("/");
+        for (name, value) in headers {
+            builder = builder.header(*name, *value);
+        }
+        Request::from_http_request(builder.body(()).unwrap(), bytes::Bytes::new())
+    }
+
+    #[test]
+    fn context_reads_request_id_and_traceparent() {
+        let req = inbound(&[
+            ("x-request-id", "req-1"),
+            (
+                "traceparent",
+                "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01",
+            ),
+            ("tracestate", "vendor=1"),
+        ]);
+
+        let ctx = RequestContext::from_request(&req);
+        assert_eq!(ctx.request_id(), Some("req-1"));
+        assert_eq!(ctx.trace_id(), Some("4bf92f3577b34da6a3ce929d0e0e4736"));
+
+        let invalid = inbound(&[("traceparent", "00-0000-0000-01")]);
+        assert_eq!(RequestContext::from_request(&invalid).trace_id(), None);
+    }
+
+    #[tokio::test]
+    async fn forwards_request_id_and_trace_with_new_span() {
+        let (base, mut requests) = serve(&[]).await;
+        let ctx = RequestContext::from_request(&inbound(&[
+            ("x-request-id", "req-1"),
+            (
+                "traceparent",
+                "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01",
+            ),
+        ]));
+
+        let client = HttpClient::new().base_url(base);
+        let response = client.get("/users").context(&ctx).send().await.unwrap();
+        assert_eq!(response.status(), 200);
+
+        let head = requests.recv().await.unwrap();
+        assert!(head.starts_with("get /users "));
+        assert!(head.contains("x-request-id: req-1"));
+        assert!(head.contains("traceparent: 00-4bf92f3577b34da6a3ce929d0e0e4736-"));
+        assert!(!head.contains("00f067aa0ba902b7"));
+    }
+
+    #[tokio::test]
+    async fn retries_retryable_statuses() {
+        let (base, _requests) = serve(&[503, 503]).await;
+        let client = HttpClient::new()
+            .base_url(base)
+            .retry(RetryLayer::new().initial_backoff(Duration::from_millis(1)));
+
+        let response = client.get("/flaky").send().await.unwrap();
+        assert_eq!(response.status(), 200);
+
+        let stats = client.stats();
+        assert_eq!(stats.requests, 1);
+        assert_eq!(stats.retries, 2);
+        assert_eq!(stats.successes, 1);
+    }
+
+    #[tokio::test]
+    async fn open_circuit_fails_fast() {
+        let (base, _requests) = serve(&[500, 500]).await;
+        let client = HttpClient::new()
+            .base_url(base)
+            .circuit_breaker(CircuitBreakerLayer::new().failure_threshold(2));
+
+        for _ in 0..2 {
+            let response = client.get("/").send().await.unwrap();
+            assert_eq!(response.status(), 500);
+        }
+        assert_eq!(client.circuit_state().await, Some(CircuitState::Open));
+        assert!(matches!(
+            client.get("/").send().await,
+            Err(HttpClientError::CircuitOpen)
+        ));
+
+        let stats = client.stats();
+        assert_eq!((stats.failures, stats.rejected), (2, 1));
+        let error: ApiError = HttpClientError::CircuitOpen.into();
+        assert_eq!(error.status, StatusCode::SERVICE_UNAVAILABLE);
+    }
+
+    #[tokio::test]
+    async fn deadline_caps_the_client_timeout() {
+        // Accepts connections but never answers
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            let mut open = Vec::new();
+            while let Ok((stream, _)) = listener.accept().await {
+                open.push(stream);
+            }
+        });
+
+        let client = HttpClient::new().base_url(format!("http://{addr}"));
+        let started = Instant::now();
+        let result = client
+            .get("/slow")
+            .deadline(Deadline::after(Duration::from_millis(100)))
+            .send()
+            .await;
+        assert!(matches!(result, Err(HttpClientError::Timeout)));
+        assert!(started.elapsed() < Duration::from_secs(5));
+
+        let expired = Deadline::after(Duration::ZERO);
+        assert!(matches!(
+            client.get("/slow").deadline(expired).send().await,
+            Err(HttpClientError::Timeout)
+        ));
+    }
+}
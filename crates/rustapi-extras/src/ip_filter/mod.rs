@@ -0,0 +1,480 @@
+//! IP allow/deny lists
+//!
+//! [`IpFilterLayer`] rejects requests from addresses outside an allowlist, or
+//! inside a denylist, with `403 Forbidden`. Added with `RustApi::layer`, it
+//! runs before routing, so filtered clients cannot probe which routes exist.
+//!
+//! Rules are CIDR ranges (`10.0.0.0/8`, `2001:db8::/32`) or single addresses.
+//! The denylist wins over the allowlist, and an empty allowlist allows every
+//! address that is not denied.
+//!
+//! Rules can be loaded from configuration ([`IpFilterConfig`] deserializes
+//! from JSON files and, with the `config` feature, environment variables) and
+//! replaced at runtime through an [`IpFilterHandle`], including by watching a
+//! file.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! use rustapi_extras::ip_filter::IpFilterLayer;
+//! use std::time::Duration;
+//!
+//! // Admin routes only from the office and the VPN
+//! let filter = IpFilterLayer::new()
+//!     .allow("203.0.113.0/24")
+//!     .allow("10.8.0.0/16")
+//!     .path_prefix("/admin");
+//! filter.handle().watch_file("ip-filter.json", Duration::from_secs(10));
+//!
+//! let app = RustApi::new().layer(filter);
+//! ```
+
+use rustapi_core::middleware::{BoxedNext, MiddlewareLayer};
+use rustapi_core::{ApiError, ClientIp, IntoResponse, Request, Response};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::future::Future;
+use std::net::IpAddr;
+use std::path::{Path, PathBuf};
+use std::pin::Pin;
+use std::str::FromStr;
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, SystemTime};
+
+/// A CIDR range such as `10.0.0.0/8`, or a single address
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cidr {
+    network: IpAddr,
+    prefix: u8,
+}
+
+impl Cidr {
+    /// Whether the range contains `ip`
+    ///
+    /// IPv4-mapped IPv6 addresses (`::ffff:10.0.0.1`) match IPv4 ranges.
+    pub fn contains(&self, ip: IpAddr) -> bool {
+        match (self.network, ip.to_canonical()) {
+            (IpAddr::V4(network), IpAddr::V4(ip)) => {
+                let mask = u32::MAX.checked_shl(32 - self.prefix as u32).unwrap_or(0);
+                u32::from(network) & mask == u32::from(ip) & mask
+            }
+            (IpAddr::V6(network), IpAddr::V6(ip)) => {
+                let mask = u128::MAX.checked_shl(128 - self.prefix as u32).unwrap_or(0);
+                u128::from(network) & mask == u128::from(ip) & mask
+            }
+            _ => false,
+        }
+    }
+}
+
+impl FromStr for Cidr {
+    type Err = IpFilterError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || IpFilterError::InvalidCidr(s.to_string());
+        let (address, prefix) = match s.trim().split_once('/') {
+            Some((address, prefix)) => (address, Some(prefix)),
+            None => (s.trim(), None),
+        };
+        let network = IpAddr::from_str(address)
+            .map_err(|_| invalid())?
+            .to_canonical();
+        let max = if network.is_ipv4() { 32 } else { 128 };
+        let prefix = match prefix {
+            Some(prefix) => prefix.parse::<u8>().map_err(|_| invalid())?,
+            None => max,
+        };
+        if prefix > max {
+            return Err(invalid());
+        }
+        Ok(Self { network, prefix })
+    }
+}
+
+impl fmt::Display for Cidr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}/{}", self.network, self.prefix)
+    }
+}
+
+/// Error loading IP filter rules
+#[derive(Debug)]
+pub enum IpFilterError {
+    /// A rule is not a valid CIDR range or address
+    InvalidCidr(String),
+    /// The rules file could not be read
+    Io(std::io::Error),
+    /// The rules file is not valid JSON
+    Json(serde_json::Error),
+}
+
+impl fmt::Display for IpFilterError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidCidr(rule) => write!(f, "Invalid CIDR range: {}", rule),
+            Self::Io(e) => write!(f, "Failed to read IP filter rules: {}", e),
+            Self::Json(e) => write!(f, "Invalid IP filter rules: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for IpFilterError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::InvalidCidr(_) => None,
+            Self::Io(e) => Some(e),
+            Self::Json(e) => Some(e),
+        }
+    }
+}
+
+/// Allow and deny lists as written in configuration
+///
+/// ```json
+/// { "allow": ["203.0.113.0/24", "10.8.0.0/16"], "deny": ["10.8.5.7"] }
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct IpFilterConfig {
+    /// Allowed ranges; empty allows every address that is not denied
+    #[serde(default)]
+    pub allow: Vec<String>,
+    /// Denied ranges, checked first
+    #[serde(default)]
+    pub deny: Vec<String>,
+}
+
+impl IpFilterConfig {
+    /// Read a JSON rules file
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self, IpFilterError> {
+        let contents = std::fs::read(path).map_err(IpFilterError::Io)?;
+        serde_json::from_slice(&contents).map_err(IpFilterError::Json)
+    }
+}
+
+/// Parsed rules
+#[derive(Debug, Clone, Default)]
+struct Rules {
+    allow: Vec<Cidr>,
+    deny: Vec<Cidr>,
+}
+
+impl Rules {
+    fn from_config(config: &IpFilterConfig) -> Result<Self, IpFilterError> {
+        let parse = |rules: &[String]| {
+            rules
+                .iter()
+                .map(|rule| rule.parse())
+                .collect::<Result<Vec<Cidr>, _>>()
+        };
+        Ok(Self {
+            allow: parse(&config.allow)?,
+            deny: parse(&config.deny)?,
+        })
+    }
+
+    fn permits(&self, ip: IpAddr) -> bool {
+        if self.deny.iter().any(|range| range.contains(ip)) {
+            return false;
+        }
+        self.allow.is_empty() || self.allow.iter().any(|range| range.contains(ip))
+    }
+}
+
+/// Replaces the rules of an [`IpFilterLayer`] at runtime
+///
+/// Handles share the rules with the layer they came from, including clones of
+/// the layer already added to an app.
+#[derive(Clone)]
+pub struct IpFilterHandle {
+    rules: Arc<RwLock<Arc<Rules>>>,
+}
+
+impl IpFilterHandle {
+    /// Replace the rules
+    ///
+    /// Invalid rules are rejected and the current rules stay in effect.
+    pub fn reload(&self, config: &IpFilterConfig) -> Result<(), IpFilterError> {
+        let rules = Rules::from_config(config)?;
+        *self.rules.write().unwrap_or_else(|e| e.into_inner()) = Arc::new(rules);
+        Ok(())
+    }
+
+    /// Replace the rules with those in a JSON file
+    pub fn reload_from_file(&self, path: impl AsRef<Path>) -> Result<(), IpFilterError> {
+        self.reload(&IpFilterConfig::from_file(path)?)
+    }
+
+    /// Reload from a JSON file whenever it changes
+    ///
+    /// Checks the file's modification time every `interval`, starting with an
+    /// immediate load. Errors are logged and keep the current rules. Abort the
+    /// returned task to stop watching.
+    pub fn watch_file(
+        &self,
+        path: impl Into<PathBuf>,
+        interval: Duration,
+    ) -> tokio::task::JoinHandle<()> {
+        let handle = self.clone();
+        let path = path.into();
+        tokio::spawn(async move {
+            let mut loaded: Option<SystemTime> = None;
+            let mut ticker = tokio::time::interval(interval);
+            loop {
+                ticker.tick().await;
+                let modified = std::fs::metadata(&path).and_then(|m| m.modified()).ok();
+                if modified.is_none() || modified == loaded {
+                    continue;
+                }
+                loaded = modified;
+                match handle.reload_from_file(&path) {
+                    Ok(()) => tracing::info!(path = %path.display(), "Reloaded IP filter rules"),
+                    Err(e) => {
+                        tracing::warn!(path = %path.display(), error = %e, "Keeping previous IP filter rules")
+                    }
+                }
+            }
+        })
+    }
+}
+
+/// Middleware rejecting requests by client IP address
+#[derive(Clone)]
+pub struct IpFilterLayer {
+    rules: Arc<RwLock<Arc<Rules>>>,
+    path_prefixes: Vec<String>,
+    trust_forwarded_for: bool,
+}
+
+impl Default for IpFilterLayer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl IpFilterLayer {
+    /// Create a layer that allows every address until rules are added
+    pub fn new() -> Self {
+        Self {
+            rules: Arc::new(RwLock::new(Arc::new(Rules::default()))),
+            path_prefixes: Vec::new(),
+            trust_forwarded_for: false,
+        }
+    }
+
+    /// Create a layer from configuration
+    pub fn from_config(config: &IpFilterConfig) -> Result<Self, IpFilterError> {
+        let layer = Self::new();
+        layer.handle().reload(config)?;
+        Ok(layer)
+    }
+
+    /// Allow a range (can be repeated)
+    ///
+    /// # Panics
+    ///
+    /// Panics if `cidr` is not a valid range; use
+    /// [`from_config`](Self::from_config) for rules that are not literals.
+    pub fn allow(self, cidr: &str) -> Self {
+        let range = cidr.parse().unwrap_or_else(|e| panic!("{}", e));
+        self.update(|rules| rules.allow.push(range))
+    }
+
+    /// Deny a range (can be repeated)
+    ///
+    /// # Panics
+    ///
+    /// Panics if `cidr` is not a valid range.
+    pub fn deny(self, cidr: &str) -> Self {
+        let range = cidr.parse().unwrap_or_else(|e| panic!("{}", e));
+        self.update(|rules| rules.deny.push(range))
+    }
+
+    /// Only filter paths under this prefix (can be repeated)
+    ///
+    /// Prefixes match whole path segments: `/admin` covers `/admin` and
+    /// `/admin/users` but not `/administrator`.
+    pub fn path_prefix(mut self, prefix: impl Into<String>) -> Self {
+        self.path_prefixes.push(prefix.into());
+        self
+    }
+
+    /// Use the first `X-Forwarded-For` address instead of the peer address
+    ///
+    /// Only enable this behind a proxy that overwrites the header; otherwise
+    /// clients can choose the address that is checked.
+    pub fn trust_forwarded_for(mut self, trust: bool) -> Self {
+        self.trust_forwarded_for = trust;
+        self
+    }
+
+    /// A handle for replacing the rules at runtime
+    pub fn handle(&self) -> IpFilterHandle {
+        IpFilterHandle {
+            rules: self.rules.clone(),
+        }
+    }
+
+    fn update(self, change: impl FnOnce(&mut Rules)) -> Self {
+        {
+            let mut current = self.rules.write().unwrap_or_else(|e| e.into_inner());
+            let mut rules = Rules::clone(&current);
+            change(&mut rules);
+            *current = Arc::new(rules);
+        }
+        self
+    }
+
+    fn applies_to(&self, path: &str) -> bool {
+        self.path_prefixes.is_empty()
+            || self.path_prefixes.iter().any(|p| {
+                let p = p.trim_end_matches('/');
+                path == p || path.starts_with(p) && path[p.len()..].starts_with('/')
+            })
+    }
+}
+
+impl MiddlewareLayer for IpFilterLayer {
+    fn call(
+        &self,
+        req: Request,
+        next: BoxedNext,
+    ) -> Pin<Box<dyn Future<Output = Response> + Send + 'static>> {
+        let permitted = !self.applies_to(req.uri().path()) || {
+            let rules = self.rules.read().unwrap_or_else(|e| e.into_inner()).clone();
+            match ClientIp::extract_with_config(&req, self.trust_forwarded_for) {
+                Ok(ClientIp(ip)) => rules.permits(ip),
+                Err(_) => false,
+            }
+        };
+
+        Box::pin(async move {
+            if permitted {
+                next(req).await
+            } else {
+                ApiError::forbidden("Access denied").into_response()
+            }
+        })
+    }
+
+    fn clone_box(&self) -> Box<dyn MiddlewareLayer> {
+        Box::new(self.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bytes::Bytes;
+    use http::StatusCode;
+    use rustapi_core::middleware::LayerStack;
+    use std::net::SocketAddr;
+
+    fn ip(s: &str) -> IpAddr {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn cidr_matching() {
+        let office: Cidr = "203.0.113.0/24".parse().unwrap();
+        assert!(office.contains(ip("203.0.113.200")));
+        assert!(office.contains(ip("::ffff:203.0.113.7")));
+        assert!(!office.contains(ip("203.0.114.1")));
+
+        let v6: Cidr = "2001:db8::/32".parse().unwrap();
+        assert!(v6.contains(ip("2001:db8:1::1")));
+        assert!(!v6.contains(ip("2001:db9::1")));
+
+        let everything: Cidr = "0.0.0.0/0".parse().unwrap();
+        assert!(everything.contains(ip("8.8.8.8")));
+        let single: Cidr = "10.0.0.1".parse().unwrap();
+        assert_eq!(single.to_string(), "10.0.0.1/32");
+
+        assert!("10.0.0.0/33".parse::<Cidr>().is_err());
+        assert!("office".parse::<Cidr>().is_err());
+    }
+
+    #[test]
+    fn deny_wins_and_empty_allowlist_allows() {
+        let rules = Rules::from_config(&IpFilterConfig {
+            allow: vec!["10.0.0.0/8".into()],
+            deny: vec!["10.0.0.13".into()],
+        })
+        .unwrap();
+        assert!(rules.permits(ip("10.1.2.3")));
+        assert!(!rules.permits(ip("10.0.0.13")));
+        assert!(!rules.permits(ip("192.0.2.1")));
+
+        let open = Rules::from_config(&IpFilterConfig {
+            deny: vec!["192.0.2.0/24".into()],
+            ..Default::default()
+        })
+        .unwrap();
+        assert!(open.permits(ip("198.51.100.1")));
+        assert!(!open.permits(ip("192.0.2.1")));
+    }
+
+    #[test]
+    fn path_prefixes_match_whole_segments() {
+        let layer = IpFilterLayer::new().path_prefix("/admin");
+        assert!(layer.applies_to("/admin"));
+        assert!(layer.applies_to("/admin/users"));
+        assert!(!layer.applies_to("/administrator"));
+        assert!(!layer.applies_to("/public"));
+
+        let slashed = IpFilterLayer::new().path_prefix("/admin/");
+        assert!(slashed.applies_to("/admin/users"));
+        assert!(!slashed.applies_to("/administrator"));
+        assert!(IpFilterLayer::new().applies_to("/anything"));
+    }
+
+    #[tokio::test]
+    async fn rejects_before_the_handler_and_reloads() {
+        let layer = IpFilterLayer::new()
+            .allow("10.8.0.0/16")
+            .path_prefix("/admin");
+        let handle = layer.handle();
+        let mut stack = LayerStack::new();
+        stack.push(Box::new(layer));
+
+        let status = |path: &'static str, peer: &'static str| {
+            let stack = &stack;
+            async move {
+                let mut req = http::Request::builder().uri(path).body(()).unwrap();
+                req.extensions_mut()
+                    .insert(SocketAddr::new(ip(peer), 40000));
+                let req = Request::from_http_request(req, Bytes::new());
+                let next: BoxedNext = Arc::new(|_req: Request| {
+                    Box::pin(async { StatusCode::OK.into_response() })
+                        as Pin<Box<dyn Future<Output = Response> + Send + 'static>>
+                });
+                stack.execute(req, next).await.status()
+            }
+        };
+
+        assert_eq!(status("/admin/users", "10.8.3.4").await, StatusCode::OK);
+        assert_eq!(
+            status("/admin/users", "198.51.100.9").await,
+            StatusCode::FORBIDDEN
+        );
+        assert_eq!(status("/public", "198.51.100.9").await, StatusCode::OK);
+
+        let invalid = IpFilterConfig {
+            allow: vec!["not-a-range".into()],
+            ..Default::default()
+        };
+        assert!(handle.reload(&invalid).is_err());
+        assert_eq!(status("/admin/users", "10.8.3.4").await, StatusCode::OK);
+
+        handle
+            .reload(&IpFilterConfig {
+                allow: vec!["198.51.100.0/24".into()],
+                ..Default::default()
+            })
+            .unwrap();
+        assert_eq!(status("/admin/users", "198.51.100.9").await, StatusCode::OK);
+        assert_eq!(
+            status("/admin/users", "10.8.3.4").await,
+            StatusCode::FORBIDDEN
+        );
+    }
+}
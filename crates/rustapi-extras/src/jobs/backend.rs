@@ -46,6 +46,18 @@ pub trait JobBackend: Send + Sync {
     /// Should return None if no job is available or ready
     fn pop<'a>(&'a self) -> Pin<Box<dyn Future<Output = Result<Option<JobRequest>>> + Send + 'a>>;
 
+    /// Pop the next job that is ready at `now`
+    ///
+    /// Lets the queue schedule by its own [`Clock`](rustapi_core::Clock).
+    /// The default ignores `now` and calls [`pop`](Self::pop).
+    fn pop_ready<'a>(
+        &'a self,
+        now: DateTime<Utc>,
+    ) -> Pin<Box<dyn Future<Output = Result<Option<JobRequest>>> + Send + 'a>> {
+        let _ = now;
+        self.pop()
+    }
+
     /// Mark a job as completed successfully
     fn complete<'a>(
         &'a self,
@@ -34,13 +34,19 @@ impl JobBackend for InMemoryBackend {
     }
 
     fn pop<'a>(&'a self) -> Pin<Box<dyn Future<Output = Result<Option<JobRequest>>> + Send + 'a>> {
+        self.pop_ready(chrono::Utc::now())
+    }
+
+    fn pop_ready<'a>(
+        &'a self,
+        now: chrono::DateTime<chrono::Utc>,
+    ) -> Pin<Box<dyn Future<Output = Result<Option<JobRequest>>> + Send + 'a>> {
         Box::pin(async move {
             let mut q = self
                 .queue
                 .lock()
                 .map_err(|_| JobError::BackendError("Lock poisoned".to_string()))?;
 
-            let now = chrono::Utc::now();
             let mut index_to_remove = None;
 
             // Scan the queue for the first ready job
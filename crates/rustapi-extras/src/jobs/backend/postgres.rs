@@ -5,6 +5,8 @@ use std::future::Future;
 use std::pin::Pin;
 
 /// Postgres-backed job queue
+///
+/// Pushing a job whose id is still queued is a no-op.
 #[derive(Debug, Clone)]
 pub struct PostgresBackend {
     pool: Pool<Postgres>,
@@ -22,9 +24,12 @@ impl PostgresBackend {
 
     /// Initialize the database schema
     pub async fn ensure_schema(&self) -> Result<()> {
-        let query = format!(
-            r#"
-            CREATE TABLE IF NOT EXISTS {} (
+        let table = &self.table_name;
+        // One statement per query: prepared statements cannot hold several
+        let statements = [
+            format!(
+                r#"
+            CREATE TABLE IF NOT EXISTS {table} (
                 id TEXT PRIMARY KEY,
                 name TEXT NOT NULL,
                 payload JSONB NOT NULL,
@@ -33,72 +38,52 @@ impl PostgresBackend {
                 attempts INT DEFAULT 0,
                 max_attempts INT DEFAULT 3,
                 last_error TEXT
-            );
-            CREATE INDEX IF NOT EXISTS idx_{}_run_at ON {} (run_at);
-            "#,
-            self.table_name, self.table_name, self.table_name
-        );
-
-        sqlx::query(&query)
-            .execute(&self.pool)
-            .await
-            .map_err(|e| JobError::BackendError(e.to_string()))?;
-
-        Ok(())
-    }
-}
-
-impl JobBackend for PostgresBackend {
-    fn push<'a>(
-        &'a self,
-        job: JobRequest,
-    ) -> Pin<Box<dyn Future<Output = Result<()>> + Send + 'a>> {
-        Box::pin(async move {
-            let query = format!(
-                r#"
-            INSERT INTO {} (id, name, payload, created_at, run_at, attempts, max_attempts, last_error)
-            VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
-            "#,
-                self.table_name
-            );
+            )
+            "#
+            ),
+            format!("CREATE INDEX IF NOT EXISTS idx_{table}_run_at ON {table} (run_at)"),
+        ];
 
-            sqlx::query(&query)
-                .bind(&job.id)
-                .bind(&job.name)
-                .bind(&job.payload)
-                .bind(job.created_at)
-                .bind(job.run_at)
-                .bind(job.attempts as i32)
-                .bind(job.max_attempts as i32)
-                .bind(&job.last_error)
+        for statement in statements {
+            sqlx::query(&statement)
                 .execute(&self.pool)
                 .await
                 .map_err(|e| JobError::BackendError(e.to_string()))?;
+        }
 
-            Ok(())
-        })
+        Ok(())
     }
 
-    fn pop<'a>(&'a self) -> Pin<Box<dyn Future<Output = Result<Option<JobRequest>>> + Send + 'a>> {
+    /// Atomically pop the next job ready at `now` (the database time if `None`)
+    fn pop_where(
+        &self,
+        now: Option<chrono::DateTime<chrono::Utc>>,
+    ) -> Pin<Box<dyn Future<Output = Result<Option<JobRequest>>> + Send + '_>> {
         Box::pin(async move {
             // Atomic pop using DELETE ... RETURNING with locking
-            let query = format!(
+            let sql = format!(
                 r#"
             DELETE FROM {}
             WHERE id = (
                 SELECT id
                 FROM {}
-                WHERE (run_at IS NULL OR run_at <= NOW())
+                WHERE (run_at IS NULL OR run_at <= {})
                 ORDER BY run_at ASC, created_at ASC
                 FOR UPDATE SKIP LOCKED
                 LIMIT 1
             )
             RETURNING id, name, payload, created_at, run_at, attempts, max_attempts, last_error
             "#,
-                self.table_name, self.table_name
+                self.table_name,
+                self.table_name,
+                if now.is_some() { "$1" } else { "NOW()" }
             );
 
-            let row = sqlx::query(&query)
+            let mut query = sqlx::query(&sql);
+            if let Some(now) = now {
+                query = query.bind(now);
+            }
+            let row = query
                 .fetch_optional(&self.pool)
                 .await
                 .map_err(|e| JobError::BackendError(e.to_string()))?;
@@ -119,6 +104,50 @@ impl JobBackend for PostgresBackend {
             }
         })
     }
+}
+
+impl JobBackend for PostgresBackend {
+    fn push<'a>(
+        &'a self,
+        job: JobRequest,
+    ) -> Pin<Box<dyn Future<Output = Result<()>> + Send + 'a>> {
+        Box::pin(async move {
+            let query = format!(
+                r#"
+            INSERT INTO {} (id, name, payload, created_at, run_at, attempts, max_attempts, last_error)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+            ON CONFLICT (id) DO NOTHING
+            "#,
+                self.table_name
+            );
+
+            sqlx::query(&query)
+                .bind(&job.id)
+                .bind(&job.name)
+                .bind(&job.payload)
+                .bind(job.created_at)
+                .bind(job.run_at)
+                .bind(job.attempts as i32)
+                .bind(job.max_attempts as i32)
+                .bind(&job.last_error)
+                .execute(&self.pool)
+                .await
+                .map_err(|e| JobError::BackendError(e.to_string()))?;
+
+            Ok(())
+        })
+    }
+
+    fn pop<'a>(&'a self) -> Pin<Box<dyn Future<Output = Result<Option<JobRequest>>> + Send + 'a>> {
+        self.pop_where(None)
+    }
+
+    fn pop_ready<'a>(
+        &'a self,
+        now: chrono::DateTime<chrono::Utc>,
+    ) -> Pin<Box<dyn Future<Output = Result<Option<JobRequest>>> + Send + 'a>> {
+        self.pop_where(Some(now))
+    }
 
     fn complete<'a>(
         &'a self,
@@ -62,6 +62,13 @@ impl JobBackend for RedisBackend {
     }
 
     fn pop<'a>(&'a self) -> Pin<Box<dyn Future<Output = Result<Option<JobRequest>>> + Send + 'a>> {
+        self.pop_ready(chrono::Utc::now())
+    }
+
+    fn pop_ready<'a>(
+        &'a self,
+        now: chrono::DateTime<chrono::Utc>,
+    ) -> Pin<Box<dyn Future<Output = Result<Option<JobRequest>>> + Send + 'a>> {
         Box::pin(async move {
             let mut conn = self
                 .client
@@ -69,7 +76,7 @@ impl JobBackend for RedisBackend {
                 .await
                 .map_err(|e| JobError::BackendError(e.to_string()))?;
 
-            let now = chrono::Utc::now().timestamp() as f64;
+            let now = now.timestamp() as f64;
 
             let result: Option<String> = self
                 .pop_script
@@ -1,6 +1,7 @@
 use super::backend::{JobBackend, JobRequest};
-use super::error::Result;
+use super::error::{JobError, Result};
 use super::job::{Job, JobContext, JobHandler};
+use rustapi_core::Clock;
 use std::collections::HashMap;
 use std::sync::Arc;
 use tokio::sync::RwLock;
@@ -11,6 +12,7 @@ use uuid::Uuid;
 pub struct JobQueue {
     backend: Arc<dyn JobBackend>,
     handlers: Arc<RwLock<HashMap<String, Box<dyn JobHandler>>>>,
+    clock: Clock,
 }
 
 impl JobQueue {
@@ -19,9 +21,23 @@ impl JobQueue {
         Self {
             backend: Arc::new(backend),
             handlers: Arc::new(RwLock::new(HashMap::new())),
+            clock: Clock::system(),
         }
     }
 
+    /// Schedule by `clock` instead of the system clock
+    ///
+    /// With a manual clock, delayed jobs and retries become ready when the
+    /// clock is advanced past their run time.
+    pub fn clock(mut self, clock: Clock) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    fn now(&self) -> chrono::DateTime<chrono::Utc> {
+        self.clock.now().into()
+    }
+
     /// Register a job handler
     pub async fn register_job<J: Job + Clone>(&self, job: J) {
         let mut handlers = self.handlers.write().await;
@@ -41,17 +57,43 @@ impl JobQueue {
         opts: EnqueueOptions,
     ) -> Result<String> {
         let payload = serde_json::to_value(data)?;
-        let id = Uuid::new_v4().to_string();
+        self.enqueue_raw(J::NAME, payload, opts).await
+    }
+
+    /// Enqueue an already serialized payload for the job named `name`
+    ///
+    /// For callers that only know the job by name, such as the outbox relay.
+    /// Fails with [`JobError::ConfigError`] when the delay reaches past the
+    /// latest representable time.
+    pub async fn enqueue_raw(
+        &self,
+        name: &str,
+        payload: serde_json::Value,
+        opts: EnqueueOptions,
+    ) -> Result<String> {
+        let id = opts.id.unwrap_or_else(|| Uuid::new_v4().to_string());
+        let now = self.now();
+        let run_at = match opts.delay {
+            Some(delay) => Some(
+                chrono::Duration::from_std(delay)
+                    .ok()
+                    .and_then(|delay| now.checked_add_signed(delay))
+                    .ok_or_else(|| {
+                        JobError::ConfigError(format!("delay of {:?} is out of range", delay))
+                    })?,
+            ),
+            None => opts.run_at,
+        };
 
         let request = JobRequest {
             id: id.clone(),
-            name: J::NAME.to_string(),
+            name: name.to_string(),
             payload,
-            created_at: chrono::Utc::now(),
+            created_at: now,
             attempts: 0,
             max_attempts: opts.max_attempts,
             last_error: None,
-            run_at: opts.run_at,
+            run_at,
         };
 
         self.backend.push(request).await?;
@@ -60,7 +102,7 @@ impl JobQueue {
 
     /// Process a single job (for testing or manual control)
     pub async fn process_one(&self) -> Result<bool> {
-        if let Some(req) = self.backend.pop().await? {
+        if let Some(req) = self.backend.pop_ready(self.now()).await? {
             let handlers = self.handlers.read().await;
             if let Some(handler) = handlers.get(&req.name) {
                 let ctx = JobContext {
@@ -83,7 +125,7 @@ impl JobQueue {
                             // Exponential backoff: 2^attempts seconds (e.g. 2, 4, 8, 16...)
                             let backoff_secs = 2u64.saturating_pow(new_req.attempts).min(86400);
                             let retry_delay = chrono::Duration::seconds(backoff_secs as i64);
-                            new_req.run_at = Some(chrono::Utc::now() + retry_delay);
+                            new_req.run_at = Some(self.now() + retry_delay);
 
                             // Re-push the job for retry
                             self.backend.push(new_req).await?;
@@ -132,6 +174,10 @@ pub struct EnqueueOptions {
     pub max_attempts: u32,
     /// Earliest time the job should be executed (None = immediately).
     pub run_at: Option<chrono::DateTime<chrono::Utc>>,
+    /// Delay from enqueueing, by the queue's clock (takes precedence over `run_at`).
+    pub delay: Option<std::time::Duration>,
+    /// Job id to use instead of a fresh UUID.
+    pub id: Option<String>,
 }
 
 impl EnqueueOptions {
@@ -146,9 +192,21 @@ impl EnqueueOptions {
         self
     }
 
-    /// Delay execution by the given duration.
+    /// Run the job no earlier than `at`.
+    pub fn run_at(mut self, at: chrono::DateTime<chrono::Utc>) -> Self {
+        self.run_at = Some(at);
+        self
+    }
+
+    /// Delay execution by the given duration, counted from enqueueing.
     pub fn delay(mut self, duration: std::time::Duration) -> Self {
-        self.run_at = Some(chrono::Utc::now() + chrono::Duration::from_std(duration).unwrap());
+        self.delay = Some(duration);
+        self
+    }
+
+    /// Use `id` as the job id, so handlers can recognize a job enqueued twice.
+    pub fn id(mut self, id: impl Into<String>) -> Self {
+        self.id = Some(id.into());
         self
     }
 }
@@ -258,11 +316,11 @@ mod property_tests {
 
         /// Property 22: Failed jobs are retried with exponential backoff
         #[test]
-        #[ignore] // TODO: Requires time mocking
         fn prop_retry_behavior(value in -1000i32..1000i32, max_attempts in 2u32..5) {
             tokio::runtime::Runtime::new().unwrap().block_on(async {
                 let backend = MemoryBackend::new();
-                let queue = JobQueue::new(backend);
+                let clock = Clock::manual();
+                let queue = JobQueue::new(backend).clock(clock.clone());
 
                 let test_job = TestJob {
                     should_fail: Arc::new(RwLock::new(true)), // Always fail
@@ -284,6 +342,10 @@ mod property_tests {
 
                     let count = *test_job.execution_count.read().await;
                     prop_assert_eq!(count, attempt);
+
+                    // Not retried before the backoff has passed
+                    prop_assert!(!queue.process_one().await.unwrap());
+                    clock.advance(std::time::Duration::from_secs(2u64.pow(attempt)));
                 }
 
                 // After max_attempts, job should be failed permanently
@@ -339,7 +401,8 @@ mod property_tests {
         fn prop_delayed_jobs_not_immediate(value in -100i32..100) {
             tokio::runtime::Runtime::new().unwrap().block_on(async {
                 let backend = MemoryBackend::new();
-                let queue = JobQueue::new(backend);
+                let clock = Clock::manual();
+                let queue = JobQueue::new(backend).clock(clock.clone());
 
                 let test_job = TestJob {
                     should_fail: Arc::new(RwLock::new(false)),
@@ -363,6 +426,12 @@ mod property_tests {
                 let count = *test_job.execution_count.read().await;
                 prop_assert_eq!(count, 0);
 
+                // Ready once the delay has passed
+                clock.advance(std::time::Duration::from_secs(3600));
+                prop_assert!(queue.process_one().await.unwrap());
+                let count = *test_job.execution_count.read().await;
+                prop_assert_eq!(count, 1);
+
                 Ok(())
             })?;
         }
@@ -438,11 +507,11 @@ mod property_tests {
 
         /// Property 22: Max attempts limit is respected
         #[test]
-        #[ignore] // TODO: Requires time mocking
         fn prop_max_attempts_respected(value in -100i32..100, max_attempts in 1u32..5) {
             tokio::runtime::Runtime::new().unwrap().block_on(async {
                 let backend = MemoryBackend::new();
-                let queue = JobQueue::new(backend);
+                let clock = Clock::manual();
+                let queue = JobQueue::new(backend).clock(clock.clone());
 
                 let test_job = TestJob {
                     should_fail: Arc::new(RwLock::new(true)),
@@ -459,6 +528,7 @@ mod property_tests {
                 // Process until queue is empty
                 let mut process_count = 0;
                 while queue.process_one().await.unwrap() {
+                    clock.advance(std::time::Duration::from_secs(86_400));
                     process_count += 1;
                     // Safety limit
                     if process_count > max_attempts + 5 {
@@ -503,4 +573,15 @@ mod property_tests {
             prop_assert!(uncapped > 86400);
         }
     }
+
+    #[tokio::test]
+    async fn out_of_range_delay_is_rejected() {
+        let queue = JobQueue::new(MemoryBackend::new());
+        let opts = EnqueueOptions::new().delay(std::time::Duration::MAX);
+        let err = queue
+            .enqueue_raw("test_job", serde_json::json!(1), opts)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, JobError::ConfigError(_)));
+    }
 }
@@ -25,7 +25,9 @@ use http::StatusCode;
 use http_body_util::Full;
 use jsonwebtoken::{decode, DecodingKey, Validation};
 use rustapi_core::middleware::{BoxedNext, MiddlewareLayer};
-use rustapi_core::{ApiError, FromRequestParts, Request, Response, ResponseBody, Result};
+use rustapi_core::{
+    ApiError, Clock, FromRequestParts, Grants, Request, Response, ResponseBody, Result, TokenClaims,
+};
 use rustapi_openapi::{Operation, OperationModifier};
 use serde::de::DeserializeOwned;
 use serde::Serialize;
@@ -163,80 +165,124 @@ impl<T: DeserializeOwned + Clone + Send + Sync + 'static> JwtLayer<T> {
     }
 }
 
+impl<T: DeserializeOwned> JwtLayer<T> {
+    /// Authenticate an `Authorization: Bearer <token>` header value.
+    ///
+    /// Applies exactly the checks of the middleware (other transports such as
+    /// gRPC use it to enforce the same rules). On failure, returns the
+    /// message sent to the client in the `401 Unauthorized` response.
+    pub fn authorize(
+        &self,
+        authorization: Option<&http::HeaderValue>,
+    ) -> std::result::Result<T, &'static str> {
+        self.authorize_at(authorization, &Clock::system())
+    }
+
+    /// Like [`authorize`](Self::authorize), checking expiry against `clock`
+    ///
+    /// The middleware uses the request's clock, so tokens expire when a
+    /// test advances a manual clock.
+    pub fn authorize_at(
+        &self,
+        authorization: Option<&http::HeaderValue>,
+        clock: &Clock,
+    ) -> std::result::Result<T, &'static str> {
+        self.authorize_claims_at(authorization, clock)
+            .map(|(claims, _)| claims)
+    }
+
+    /// Like [`authorize_at`](Self::authorize_at), also returning the raw claims
+    fn authorize_claims_at(
+        &self,
+        authorization: Option<&http::HeaderValue>,
+        clock: &Clock,
+    ) -> std::result::Result<(T, serde_json::Value), &'static str> {
+        let header_value = authorization.ok_or("Missing Authorization header")?;
+        let header_str = header_value
+            .to_str()
+            .map_err(|_| "Invalid Authorization header encoding")?;
+
+        // Check for "Bearer " prefix
+        let token = header_str
+            .strip_prefix("Bearer ")
+            .or_else(|| header_str.strip_prefix("bearer "))
+            .ok_or("Invalid Authorization header format")?;
+
+        // Validate the token
+        self.decode_at(token, clock)
+            .map_err(|err| match err.kind() {
+                jsonwebtoken::errors::ErrorKind::ExpiredSignature => "Token has expired",
+                jsonwebtoken::errors::ErrorKind::InvalidToken => "Invalid token",
+                jsonwebtoken::errors::ErrorKind::InvalidSignature => "Invalid token signature",
+                jsonwebtoken::errors::ErrorKind::InvalidAlgorithm => "Invalid token algorithm",
+                _ => "Invalid or expired token",
+            })
+    }
+
+    /// Decode and validate a token, checking `exp` against `clock`
+    ///
+    /// Returns the typed claims together with the raw claims object.
+    fn decode_at(
+        &self,
+        token: &str,
+        clock: &Clock,
+    ) -> jsonwebtoken::errors::Result<(T, serde_json::Value)> {
+        use jsonwebtoken::errors::ErrorKind;
+
+        let decoding_key = DecodingKey::from_secret(self.secret.as_bytes());
+        let mut jwt_validation = self.validation.to_jsonwebtoken_validation();
+        if clock.is_manual() {
+            // jsonwebtoken reads the system time, so check `exp` here instead
+            jwt_validation.validate_exp = false;
+        }
+        let claims = decode::<serde_json::Value>(token, &decoding_key, &jwt_validation)?.claims;
+        if clock.is_manual() && self.validation.validate_exp {
+            let exp = claims
+                .get("exp")
+                .and_then(serde_json::Value::as_u64)
+                .ok_or(ErrorKind::InvalidToken)?;
+            if exp.saturating_add(self.validation.leeway) < clock.unix().as_secs() {
+                return Err(ErrorKind::ExpiredSignature.into());
+            }
+        }
+        let typed = T::deserialize(&claims).map_err(|e| ErrorKind::Json(Arc::new(e)))?;
+        Ok((typed, claims))
+    }
+}
+
 impl<T: DeserializeOwned + Clone + Send + Sync + 'static> MiddlewareLayer for JwtLayer<T> {
     fn call(
         &self,
         mut req: Request,
         next: BoxedNext,
     ) -> Pin<Box<dyn Future<Output = Response> + Send + 'static>> {
-        let secret = self.secret.clone();
-        let validation = self.validation.clone();
-        let skip_paths = self.skip_paths.clone();
+        let layer = self.clone();
 
         Box::pin(async move {
             // Check if this path should skip JWT validation
             let path = req.uri().path();
-            if skip_paths.iter().any(|skip| should_skip_path(path, skip)) {
+            if layer
+                .skip_paths
+                .iter()
+                .any(|skip| should_skip_path(path, skip))
+            {
                 return next(req).await;
             }
 
-            // Extract the Authorization header
-            let auth_header = req.headers().get(http::header::AUTHORIZATION);
-
-            let token = match auth_header {
-                Some(header_value) => {
-                    match header_value.to_str() {
-                        Ok(header_str) => {
-                            // Check for "Bearer " prefix
-                            if let Some(token) = header_str.strip_prefix("Bearer ") {
-                                token.to_string()
-                            } else if let Some(token) = header_str.strip_prefix("bearer ") {
-                                token.to_string()
-                            } else {
-                                return create_unauthorized_response(
-                                    "Invalid Authorization header format",
-                                );
-                            }
-                        }
-                        Err(_) => {
-                            return create_unauthorized_response(
-                                "Invalid Authorization header encoding",
-                            );
-                        }
-                    }
-                }
-                None => {
-                    return create_unauthorized_response("Missing Authorization header");
-                }
-            };
-
-            // Validate the token
-            let decoding_key = DecodingKey::from_secret(secret.as_bytes());
-            let jwt_validation = validation.to_jsonwebtoken_validation();
-
-            match decode::<T>(&token, &decoding_key, &jwt_validation) {
-                Ok(token_data) => {
-                    // Store the validated claims in request extensions
-                    req.extensions_mut()
-                        .insert(ValidatedClaims(token_data.claims));
+            let clock = Clock::of(&req);
+            match layer.authorize_claims_at(req.headers().get(http::header::AUTHORIZATION), &clock)
+            {
+                Ok((claims, raw)) => {
+                    // Store the validated claims, the roles and scopes they
+                    // grant and the raw claims in request extensions
+                    req.extensions_mut().insert(Grants::from_claims(&raw));
+                    req.extensions_mut().insert(TokenClaims(raw));
+                    req.extensions_mut().insert(ValidatedClaims(claims));
 
                     // Continue to the next handler
                     next(req).await
                 }
-                Err(err) => {
-                    let message = match err.kind() {
-                        jsonwebtoken::errors::ErrorKind::ExpiredSignature => "Token has expired",
-                        jsonwebtoken::errors::ErrorKind::InvalidToken => "Invalid token",
-                        jsonwebtoken::errors::ErrorKind::InvalidSignature => {
-                            "Invalid token signature"
-                        }
-                        jsonwebtoken::errors::ErrorKind::InvalidAlgorithm => {
-                            "Invalid token algorithm"
-                        }
-                        _ => "Invalid or expired token",
-                    };
-                    create_unauthorized_response(message)
-                }
+                Err(message) => create_unauthorized_response(message),
             }
         })
     }
@@ -416,7 +462,7 @@ mod tests {
     use rustapi_core::middleware::LayerStack;
     use serde::{Deserialize, Serialize};
     use std::sync::Arc;
-    use std::time::{SystemTime, UNIX_EPOCH};
+    use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
     /// Test claims structure
     #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -837,6 +883,36 @@ mod tests {
         assert_eq!(parts.len(), 3);
     }
 
+    #[test]
+    fn test_authorize_at_checks_expiry_against_clock() {
+        let layer = JwtLayer::<TestClaims>::new("secret");
+        let clock = Clock::manual();
+        let claims = TestClaims {
+            sub: "user123".to_string(),
+            exp: clock.unix().as_secs() + 60,
+            custom_field: None,
+        };
+        let header: http::HeaderValue =
+            format!("Bearer {}", create_token(&claims, "secret").unwrap())
+                .parse()
+                .unwrap();
+
+        assert_eq!(layer.authorize_at(Some(&header), &clock).unwrap(), claims);
+        clock.advance(Duration::from_secs(61));
+        assert_eq!(
+            layer.authorize_at(Some(&header), &clock).unwrap_err(),
+            "Token has expired"
+        );
+
+        let tampered: http::HeaderValue = format!("{}x", header.to_str().unwrap()).parse().unwrap();
+        assert_eq!(
+            layer
+                .authorize_at(Some(&tampered), &Clock::manual())
+                .unwrap_err(),
+            "Invalid token signature"
+        );
+    }
+
     #[test]
     fn test_validate_token_requires_exp_by_default() {
         let layer = JwtLayer::<NoExpClaims>::new("secret");
@@ -923,4 +999,43 @@ mod tests {
 
         assert!(result.is_err());
     }
+
+    #[tokio::test]
+    async fn test_jwt_layer_records_grants() {
+        let secret = "grants-secret";
+        let token = create_token(
+            &serde_json::json!({
+                "sub": "user123",
+                "exp": future_timestamp(3600),
+                "roles": ["admin"],
+                "scope": "pins:read pins:write",
+            }),
+            secret,
+        )
+        .unwrap();
+
+        let stack = setup_stack::<TestClaims>(secret);
+        let handler: rustapi_core::middleware::BoxedNext = Arc::new(|req: Request| {
+            let grants = req
+                .extensions()
+                .get::<Grants>()
+                .cloned()
+                .unwrap_or_default();
+            Box::pin(async move {
+                let status = if grants.has_role("admin") && grants.has_scope("pins:write") {
+                    StatusCode::OK
+                } else {
+                    StatusCode::FORBIDDEN
+                };
+                http::Response::builder()
+                    .status(status)
+                    .body(ResponseBody::Full(Full::new(Bytes::new())))
+                    .unwrap()
+            }) as Pin<Box<dyn Future<Output = Response> + Send + 'static>>
+        });
+
+        let request = create_test_request(Some(&format!("Bearer {}", token)));
+        let response = stack.execute(request, handler).await;
+        assert_eq!(response.status(), StatusCode::OK);
+    }
 }
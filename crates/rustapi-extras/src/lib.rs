@@ -106,6 +106,14 @@ pub mod security_headers;
 #[cfg(feature = "api-key")]
 pub mod api_key;
 
+// IP allow/deny lists
+#[cfg(feature = "ip-filter")]
+pub mod ip_filter;
+
+// GeoIP enrichment
+#[cfg(feature = "geoip")]
+pub mod geoip;
+
 // Response caching
 #[cfg(feature = "cache")]
 pub mod cache;
@@ -118,6 +126,26 @@ pub mod otel;
 #[cfg(feature = "structured-logging")]
 pub mod structured_logging;
 
+// Instrumented outbound HTTP client
+#[cfg(feature = "http-client")]
+pub mod http_client;
+
+// Shadow traffic
+#[cfg(feature = "shadow")]
+pub mod shadow;
+
+// Metrics exporters
+#[cfg(feature = "metrics-export")]
+pub mod metrics_export;
+
+// Admin API for runtime controls
+#[cfg(feature = "admin")]
+pub mod admin;
+
+// Transactional outbox
+#[cfg(feature = "outbox")]
+pub mod outbox;
+
 // Re-exports for convenience
 #[cfg(feature = "jwt")]
 pub use jwt::{create_token, AuthUser, JwtError, JwtLayer, JwtValidation, ValidatedClaims};
@@ -135,7 +163,9 @@ pub use config::{
 };
 
 #[cfg(feature = "sqlx")]
-pub use sqlx::{convert_sqlx_error, PoolError, SqlxErrorExt, SqlxPoolBuilder, SqlxPoolConfig};
+pub use sqlx::{
+    convert_sqlx_error, within_deadline, PoolError, SqlxErrorExt, SqlxPoolBuilder, SqlxPoolConfig,
+};
 
 #[cfg(feature = "diesel")]
 pub use diesel::{DieselPoolBuilder, DieselPoolConfig, DieselPoolError};
@@ -167,8 +197,14 @@ pub use security_headers::{HstsConfig, ReferrerPolicy, SecurityHeadersLayer, XFr
 #[cfg(feature = "api-key")]
 pub use api_key::ApiKeyLayer;
 
+#[cfg(feature = "ip-filter")]
+pub use ip_filter::{IpFilterConfig, IpFilterHandle, IpFilterLayer};
+
+#[cfg(feature = "geoip")]
+pub use geoip::{GeoIp, GeoIpLayer, GeoIpLookup, MaxMindLookup};
+
 #[cfg(feature = "cache")]
-pub use cache::{CacheBuilder, CacheConfig, CacheHandle, CacheLayer};
+pub use cache::{CacheBuilder, CacheConfig, CacheHandle, CacheLayer, EtagLayer};
 
 #[cfg(feature = "dedup")]
 pub use dedup::{DedupConfig, DedupLayer};
@@ -179,8 +215,9 @@ pub use sanitization::{sanitize_html, sanitize_json, strip_tags};
 // Phase 5: Observability re-exports
 #[cfg(feature = "otel")]
 pub use otel::{
-    extract_trace_context, inject_trace_context, propagate_trace_context, OtelConfig,
-    OtelConfigBuilder, OtelExporter, OtelLayer, TraceContext, TraceSampler,
+    extract_trace_context, extract_trace_context_from_headers, inject_trace_context,
+    propagate_trace_context, OtelConfig, OtelConfigBuilder, OtelExporter, OtelLayer, TraceContext,
+    TraceSampler,
 };
 
 #[cfg(feature = "structured-logging")]
@@ -245,3 +282,9 @@ pub use replay::{
     FsReplayStore, FsReplayStoreConfig, InMemoryReplayStore, ReplayAdminAuth, ReplayClient,
     ReplayLayer, RetentionJob,
 };
+
+#[cfg(feature = "replay-redis")]
+pub use replay::RedisReplayStore;
+
+#[cfg(feature = "replay-postgres")]
+pub use replay::SqlxReplayStore;
@@ -3,6 +3,10 @@
 //! This module provides detailed logging of HTTP requests and responses
 //! with support for correlation IDs, custom fields, and structured output.
 //!
+//! With [`LogSampling`], only a share of the requests is logged (errors are
+//! kept by default), and requests over the slow threshold are always logged
+//! as `slow request` at WARN with their request and response headers.
+//!
 //! # Example
 //!
 //! ```rust,no_run
@@ -20,9 +24,10 @@
 //! ```
 
 use rustapi_core::{
-    middleware::{BoxedNext, MiddlewareLayer},
+    middleware::{BoxedNext, LogDecision, LogSampling, MiddlewareLayer},
     Request, Response,
 };
+use std::collections::BTreeMap;
 use std::future::Future;
 use std::pin::Pin;
 use std::time::Instant;
@@ -49,6 +54,8 @@ pub struct LoggingConfig {
     pub log_response_headers: bool,
     /// Paths to skip logging
     pub skip_paths: Vec<String>,
+    /// Which requests to log; everything by default
+    pub sampling: LogSampling,
 }
 
 impl Default for LoggingConfig {
@@ -58,6 +65,7 @@ impl Default for LoggingConfig {
             log_request_headers: false,
             log_response_headers: false,
             skip_paths: vec!["/health".to_string(), "/metrics".to_string()],
+            sampling: LogSampling::new(),
         }
     }
 }
@@ -104,6 +112,15 @@ impl LoggingLayer {
         self.config.skip_paths.push(path.into());
         self
     }
+
+    /// Sample the logged requests and promote slow ones
+    ///
+    /// Unless every request is logged, the `incoming request` line is left
+    /// out, since whether to log is only known once the response is ready.
+    pub fn sampling(mut self, sampling: LogSampling) -> Self {
+        self.config.sampling = sampling;
+        self
+    }
 }
 
 impl Default for LoggingLayer {
@@ -138,9 +155,15 @@ impl MiddlewareLayer for LoggingLayer {
                 .unwrap_or_else(|| "N/A".to_string());
 
             let start = Instant::now();
+            let log_start = config.sampling.logs_everything();
+            let request_headers = config
+                .sampling
+                .threshold()
+                .map(|_| header_map(req.headers()));
 
             // Log request
             match config.format {
+                _ if !log_start => {}
                 LogFormat::Compact => {
                     tracing::info!(
                         request_id = %request_id,
@@ -191,6 +214,46 @@ impl MiddlewareLayer for LoggingLayer {
             let status = response.status().as_u16();
             let duration_ms = duration.as_millis();
 
+            let decision = config.sampling.decide(response.status(), duration);
+            if decision == LogDecision::Slow {
+                let threshold_ms = config.sampling.threshold().map(|t| t.as_millis());
+                let request_headers = request_headers.unwrap_or_default();
+                let response_headers = header_map(response.headers());
+                match config.format {
+                    LogFormat::Json => {
+                        let json = serde_json::json!({
+                            "type": "slow_request",
+                            "request_id": request_id,
+                            "method": method,
+                            "uri": uri,
+                            "version": version,
+                            "status": status,
+                            "duration_ms": duration_ms,
+                            "threshold_ms": threshold_ms,
+                            "request_headers": request_headers,
+                            "response_headers": response_headers,
+                        });
+                        tracing::warn!("{}", json);
+                    }
+                    _ => tracing::warn!(
+                        request_id = %request_id,
+                        method = %method,
+                        uri = %uri,
+                        version = %version,
+                        status = status,
+                        duration_ms = duration_ms,
+                        threshold_ms = ?threshold_ms,
+                        request_headers = ?request_headers,
+                        response_headers = ?response_headers,
+                        "slow request"
+                    ),
+                }
+                return response;
+            }
+            if !decision.should_log() {
+                return response;
+            }
+
             // Log response
             match config.format {
                 LogFormat::Compact => {
@@ -246,6 +309,20 @@ impl MiddlewareLayer for LoggingLayer {
     }
 }
 
+/// Headers for slow request logs, with credentials left out
+fn header_map(headers: &http::HeaderMap) -> BTreeMap<String, String> {
+    headers
+        .iter()
+        .map(|(name, value)| {
+            let value = match name.as_str() {
+                "authorization" | "cookie" | "set-cookie" | "proxy-authorization" => "[REDACTED]",
+                _ => value.to_str().unwrap_or("[non-utf8]"),
+            };
+            (name.as_str().to_string(), value.to_string())
+        })
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -300,4 +377,15 @@ mod tests {
         let response = layer.call(req, next).await;
         assert_eq!(response.status(), 200);
     }
+
+    #[test]
+    fn slow_request_headers_leave_out_credentials() {
+        let mut headers = http::HeaderMap::new();
+        headers.insert("authorization", "Bearer secret".parse().unwrap());
+        headers.insert("accept", "application/json".parse().unwrap());
+
+        let logged = header_map(&headers);
+        assert_eq!(logged["authorization"], "[REDACTED]");
+        assert_eq!(logged["accept"], "application/json");
+    }
 }
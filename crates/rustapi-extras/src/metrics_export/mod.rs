@@ -0,0 +1,687 @@
+//! Metrics exporters
+//!
+//! `MetricsLayer` serves its metrics for Prometheus to scrape, which misses
+//! short-lived jobs (batch runs, CLIs, scheduled tasks) that exit before the
+//! next scrape. A [`MetricsExporter`] decides how metrics leave the process:
+//!
+//! - [`MetricsExporter::Pull`] pushes nothing; mount `metrics.handler()` at
+//!   `/metrics` to be scraped (default)
+//! - [`MetricsExporter::PushGateway`] `PUT`s the Prometheus text format to a
+//!   push gateway
+//! - [`MetricsExporter::Otlp`] converts the metrics to OTLP and `POST`s them as
+//!   JSON to an OTLP/HTTP collector
+//!
+//! Push exporters send on an interval and once more from
+//! [`MetricsExportHandle::shutdown`], so the final values of a job are not lost.
+//!
+//! [`MetricsExporter::from_env`] selects the exporter at deploy time:
+//!
+//! | Variable | Meaning |
+//! |----------|---------|
+//! | `RUSTAPI_METRICS_EXPORTER` | `pull` (default), `push-gateway` or `otlp` |
+//! | `RUSTAPI_METRICS_PUSH_URL` | Push gateway URL (required for `push-gateway`) |
+//! | `RUSTAPI_METRICS_PUSH_JOB` | Push gateway job name (default: `rustapi`) |
+//! | `RUSTAPI_METRICS_INTERVAL` | Seconds between pushes (`0`: only on shutdown) |
+//! | `OTEL_EXPORTER_OTLP_ENDPOINT` | OTLP collector URL (default: `http://localhost:4318`) |
+//! | `OTEL_EXPORTER_OTLP_HEADERS` | OTLP headers, as `key=value,key=value` |
+//! | `OTEL_SERVICE_NAME` | `service.name` resource attribute |
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! use rustapi_core::middleware::MetricsLayer;
+//! use rustapi_extras::metrics_export::MetricsExporter;
+//!
+//! let metrics = MetricsLayer::new();
+//! let exporter = MetricsExporter::from_env()?.start(&metrics)?;
+//!
+//! RustApi::new()
+//!     .layer(metrics.clone())
+//!     .route("/metrics", get(metrics.handler()))
+//!     .on_shutdown(|| async move {
+//!         if let Err(e) = exporter.shutdown().await {
+//!             tracing::warn!(error = %e, "Final metrics push failed");
+//!         }
+//!     })
+//!     .run("127.0.0.1:8080")
+//!     .await
+//! ```
+
+mod otlp;
+
+use http::header::{HeaderMap, HeaderName, HeaderValue, CONTENT_TYPE};
+use http::StatusCode;
+use prometheus::{Encoder, Registry, TextEncoder};
+use reqwest::Url;
+use rustapi_core::MetricsLayer;
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+use tokio::task::JoinHandle;
+
+/// Default push gateway job name
+pub const DEFAULT_JOB: &str = "rustapi";
+
+/// Default OTLP/HTTP collector URL
+pub const DEFAULT_OTLP_ENDPOINT: &str = "http://localhost:4318";
+
+const DEFAULT_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Error from configuring or running a metrics exporter.
+#[derive(Debug)]
+pub enum MetricsExportError {
+    /// The exporter configuration is invalid.
+    Config(String),
+    /// The metrics could not be encoded.
+    Encode(String),
+    /// The metrics could not be sent.
+    Request(reqwest::Error),
+    /// The receiver answered with an error status.
+    Status(StatusCode),
+}
+
+impl std::fmt::Display for MetricsExportError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Config(msg) => write!(f, "Invalid metrics exporter config: {}", msg),
+            Self::Encode(msg) => write!(f, "Failed to encode metrics: {}", msg),
+            Self::Request(e) => write!(f, "Failed to push metrics: {}", e),
+            Self::Status(status) => write!(f, "Metrics receiver answered {}", status),
+        }
+    }
+}
+
+impl std::error::Error for MetricsExportError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Request(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<reqwest::Error> for MetricsExportError {
+    fn from(e: reqwest::Error) -> Self {
+        Self::Request(e)
+    }
+}
+
+/// Prometheus push gateway settings
+#[derive(Debug, Clone)]
+pub struct PushGatewayConfig {
+    url: String,
+    job: String,
+    grouping: Vec<(String, String)>,
+    headers: Vec<(String, String)>,
+    interval: Duration,
+    timeout: Duration,
+}
+
+impl PushGatewayConfig {
+    /// Push to the gateway at `url` under `job`
+    pub fn new(url: impl Into<String>, job: impl Into<String>) -> Self {
+        Self {
+            url: url.into(),
+            job: job.into(),
+            grouping: Vec::new(),
+            headers: Vec::new(),
+            interval: Duration::from_secs(15),
+            timeout: DEFAULT_TIMEOUT,
+        }
+    }
+
+    /// Add a grouping label, e.g. `instance`, to the push URL
+    ///
+    /// Pushes replace the metrics of the same job and grouping labels.
+    pub fn grouping(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.grouping.push((name.into(), value.into()));
+        self
+    }
+
+    /// Send a header with every push, e.g. `Authorization`
+    pub fn header(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.headers.push((name.into(), value.into()));
+        self
+    }
+
+    /// Time between pushes (default: 15s); zero pushes only on demand
+    pub fn interval(mut self, interval: Duration) -> Self {
+        self.interval = interval;
+        self
+    }
+
+    /// Timeout of each push (default: 10s)
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// `{url}/metrics/job/{job}/{label}/{value}...`, with segments escaped
+    fn push_url(&self) -> Result<Url, MetricsExportError> {
+        let mut url = parse_url(&self.url)?;
+        url.path_segments_mut()
+            .map_err(|_| MetricsExportError::Config(format!("invalid URL: {}", self.url)))?
+            .pop_if_empty()
+            .extend(["metrics", "job", self.job.as_str()])
+            .extend(
+                self.grouping
+                    .iter()
+                    .flat_map(|(name, value)| [name.as_str(), value.as_str()]),
+            );
+        Ok(url)
+    }
+}
+
+/// OTLP/HTTP metrics settings
+#[derive(Debug, Clone)]
+pub struct OtlpMetricsConfig {
+    endpoint: String,
+    service_name: String,
+    resource_attributes: Vec<(String, String)>,
+    headers: Vec<(String, String)>,
+    interval: Duration,
+    timeout: Duration,
+}
+
+impl OtlpMetricsConfig {
+    /// Export to the collector at `endpoint`, e.g. `http://localhost:4318`
+    ///
+    /// `/v1/metrics` is appended unless the URL already ends with it.
+    pub fn new(endpoint: impl Into<String>) -> Self {
+        Self {
+            endpoint: endpoint.into(),
+            service_name: "rustapi-service".to_string(),
+            resource_attributes: Vec::new(),
+            headers: Vec::new(),
+            interval: Duration::from_secs(60),
+            timeout: DEFAULT_TIMEOUT,
+        }
+    }
+
+    /// `service.name` resource attribute (default: `rustapi-service`)
+    pub fn service_name(mut self, name: impl Into<String>) -> Self {
+        self.service_name = name.into();
+        self
+    }
+
+    /// Add a resource attribute, e.g. `deployment.environment`
+    pub fn resource_attribute(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.resource_attributes.push((key.into(), value.into()));
+        self
+    }
+
+    /// Send a header with every export, e.g. an API key
+    pub fn header(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.headers.push((name.into(), value.into()));
+        self
+    }
+
+    /// Time between exports (default: 60s); zero exports only on demand
+    pub fn interval(mut self, interval: Duration) -> Self {
+        self.interval = interval;
+        self
+    }
+
+    /// Timeout of each export (default: 10s)
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    fn metrics_url(&self) -> Result<Url, MetricsExportError> {
+        let endpoint = self.endpoint.trim_end_matches('/');
+        if endpoint.ends_with("/v1/metrics") {
+            parse_url(endpoint)
+        } else {
+            parse_url(&format!("{}/v1/metrics", endpoint))
+        }
+    }
+
+    fn resource(&self) -> Vec<(String, String)> {
+        let mut resource = vec![("service.name".to_string(), self.service_name.clone())];
+        resource.extend(self.resource_attributes.iter().cloned());
+        resource
+    }
+}
+
+/// How metrics leave the process
+#[derive(Debug, Clone, Default)]
+pub enum MetricsExporter {
+    /// Served at `/metrics` for Prometheus to scrape; nothing is pushed
+    #[default]
+    Pull,
+    /// Pushed to a Prometheus push gateway
+    PushGateway(PushGatewayConfig),
+    /// Pushed to an OTLP/HTTP collector
+    Otlp(OtlpMetricsConfig),
+}
+
+impl MetricsExporter {
+    /// Select the exporter from environment variables (see the module docs)
+    pub fn from_env() -> Result<Self, MetricsExportError> {
+        Self::from_vars(|name| std::env::var(name).ok())
+    }
+
+    fn from_vars(var: impl Fn(&str) -> Option<String>) -> Result<Self, MetricsExportError> {
+        let var = |name: &str| var(name).filter(|value| !value.trim().is_empty());
+        let interval = match var("RUSTAPI_METRICS_INTERVAL") {
+            Some(secs) => Some(
+                secs.trim()
+                    .parse::<u64>()
+                    .map(Duration::from_secs)
+                    .map_err(|_| {
+                        MetricsExportError::Config(format!("RUSTAPI_METRICS_INTERVAL={}", secs))
+                    })?,
+            ),
+            None => None,
+        };
+
+        let exporter = var("RUSTAPI_METRICS_EXPORTER").unwrap_or_default();
+        match exporter.trim().to_ascii_lowercase().as_str() {
+            "" | "pull" | "prometheus" => Ok(Self::Pull),
+            "push-gateway" | "pushgateway" => {
+                let url = var("RUSTAPI_METRICS_PUSH_URL").ok_or_else(|| {
+                    MetricsExportError::Config("RUSTAPI_METRICS_PUSH_URL is not set".to_string())
+                })?;
+                let job = var("RUSTAPI_METRICS_PUSH_JOB").unwrap_or_else(|| DEFAULT_JOB.into());
+                let mut config = PushGatewayConfig::new(url, job);
+                if let Some(interval) = interval {
+                    config = config.interval(interval);
+                }
+                Ok(Self::PushGateway(config))
+            }
+            "otlp" => {
+                let endpoint = var("OTEL_EXPORTER_OTLP_ENDPOINT")
+                    .unwrap_or_else(|| DEFAULT_OTLP_ENDPOINT.into());
+                let mut config = OtlpMetricsConfig::new(endpoint);
+                if let Some(name) = var("OTEL_SERVICE_NAME") {
+                    config = config.service_name(name);
+                }
+                for pair in var("OTEL_EXPORTER_OTLP_HEADERS")
+                    .unwrap_or_default()
+                    .split(',')
+                {
+                    if let Some((name, value)) = pair.split_once('=') {
+                        config = config.header(name.trim(), value.trim());
+                    }
+                }
+                if let Some(interval) = interval {
+                    config = config.interval(interval);
+                }
+                Ok(Self::Otlp(config))
+            }
+            other => Err(MetricsExportError::Config(format!(
+                "unknown RUSTAPI_METRICS_EXPORTER `{}` (expected pull, push-gateway or otlp)",
+                other
+            ))),
+        }
+    }
+
+    /// Short name: `pull`, `push-gateway` or `otlp`
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Pull => "pull",
+            Self::PushGateway(_) => "push-gateway",
+            Self::Otlp(_) => "otlp",
+        }
+    }
+
+    /// Start exporting the metrics of `metrics`
+    ///
+    /// Push exporters with an interval spawn a task, so this must be called
+    /// inside a Tokio runtime.
+    pub fn start(self, metrics: &MetricsLayer) -> Result<MetricsExportHandle, MetricsExportError> {
+        self.start_with_registry(metrics.registry().clone())
+    }
+
+    /// Start exporting the metrics of a Prometheus registry
+    pub fn start_with_registry(
+        self,
+        registry: Registry,
+    ) -> Result<MetricsExportHandle, MetricsExportError> {
+        let (target, headers, interval, timeout) = match &self {
+            Self::Pull => {
+                return Ok(MetricsExportHandle {
+                    pusher: None,
+                    task: None,
+                })
+            }
+            Self::PushGateway(config) => (
+                Target::PushGateway(config.push_url()?),
+                &config.headers,
+                config.interval,
+                config.timeout,
+            ),
+            Self::Otlp(config) => (
+                Target::Otlp {
+                    url: config.metrics_url()?,
+                    resource: config.resource(),
+                    start: SystemTime::now(),
+                },
+                &config.headers,
+                config.interval,
+                config.timeout,
+            ),
+        };
+
+        let client = reqwest::Client::builder()
+            .timeout(timeout)
+            .build()
+            .map_err(|e| MetricsExportError::Config(e.to_string()))?;
+        let pusher = Arc::new(Pusher {
+            client,
+            registry,
+            headers: header_map(headers)?,
+            target,
+            exporter: self.name(),
+        });
+
+        let task = (!interval.is_zero()).then(|| {
+            let pusher = pusher.clone();
+            tokio::spawn(async move {
+                let mut ticker = tokio::time::interval(interval);
+                ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+                // The first tick completes immediately
+                ticker.tick().await;
+                loop {
+                    ticker.tick().await;
+                    if let Err(e) = pusher.push().await {
+                        tracing::warn!(exporter = pusher.exporter, error = %e, "Metrics push failed");
+                    }
+                }
+            })
+        });
+
+        Ok(MetricsExportHandle {
+            pusher: Some(pusher),
+            task,
+        })
+    }
+}
+
+/// A running metrics exporter
+///
+/// Dropping the handle leaves interval pushes running; call
+/// [`shutdown`](Self::shutdown) before the process exits to send the final
+/// values.
+pub struct MetricsExportHandle {
+    pusher: Option<Arc<Pusher>>,
+    task: Option<JoinHandle<()>>,
+}
+
+impl MetricsExportHandle {
+    /// Whether metrics are only served for scraping
+    pub fn is_pull(&self) -> bool {
+        self.pusher.is_none()
+    }
+
+    /// Push the current metrics now; does nothing for [`MetricsExporter::Pull`]
+    pub async fn push(&self) -> Result<(), MetricsExportError> {
+        match &self.pusher {
+            Some(pusher) => pusher.push().await,
+            None => Ok(()),
+        }
+    }
+
+    /// Stop interval pushes and push the final values
+    pub async fn shutdown(mut self) -> Result<(), MetricsExportError> {
+        if let Some(task) = self.task.take() {
+            task.abort();
+        }
+        self.push().await
+    }
+}
+
+impl std::fmt::Debug for MetricsExportHandle {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("MetricsExportHandle")
+            .field(
+                "exporter",
+                &self.pusher.as_ref().map_or("pull", |p| p.exporter),
+            )
+            .field("interval_task", &self.task.is_some())
+            .finish()
+    }
+}
+
+enum Target {
+    PushGateway(Url),
+    Otlp {
+        url: Url,
+        resource: Vec<(String, String)>,
+        start: SystemTime,
+    },
+}
+
+struct Pusher {
+    client: reqwest::Client,
+    registry: Registry,
+    headers: HeaderMap,
+    target: Target,
+    exporter: &'static str,
+}
+
+impl Pusher {
+    async fn push(&self) -> Result<(), MetricsExportError> {
+        let families = self.registry.gather();
+        let request = match &self.target {
+            Target::PushGateway(url) => {
+                let encoder = TextEncoder::new();
+                let mut body = Vec::new();
+                encoder
+                    .encode(&families, &mut body)
+                    .map_err(|e| MetricsExportError::Encode(e.to_string()))?;
+                self.client
+                    .put(url.clone())
+                    .header(CONTENT_TYPE, encoder.format_type())
+                    .body(body)
+            }
+            Target::Otlp {
+                url,
+                resource,
+                start,
+            } => {
+                let body = otlp::encode(&families, resource, *start, SystemTime::now());
+                self.client.post(url.clone()).json(&body)
+            }
+        };
+
+        let response = request.headers(self.headers.clone()).send().await?;
+        if !response.status().is_success() {
+            return Err(MetricsExportError::Status(response.status()));
+        }
+        tracing::debug!(
+            exporter = self.exporter,
+            families = families.len(),
+            "Pushed metrics"
+        );
+        Ok(())
+    }
+}
+
+fn parse_url(url: &str) -> Result<Url, MetricsExportError> {
+    Url::parse(url).map_err(|e| MetricsExportError::Config(format!("invalid URL `{}`: {}", url, e)))
+}
+
+fn header_map(headers: &[(String, String)]) -> Result<HeaderMap, MetricsExportError> {
+    let mut map = HeaderMap::new();
+    for (name, value) in headers {
+        let invalid = || MetricsExportError::Config(format!("invalid header `{}`", name));
+        map.append(
+            HeaderName::try_from(name.as_str()).map_err(|_| invalid())?,
+            HeaderValue::try_from(value.as_str()).map_err(|_| invalid())?,
+        );
+    }
+    Ok(map)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use prometheus::{Histogram, HistogramOpts, IntCounter, IntGauge};
+    use std::collections::HashMap;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+    use tokio::sync::mpsc;
+
+    /// A server that answers 200 and sends each request it gets through the channel
+    async fn serve() -> (String, mpsc::UnboundedReceiver<String>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let (tx, rx) = mpsc::unbounded_channel();
+
+        tokio::spawn(async move {
+            while let Ok((mut stream, _)) = listener.accept().await {
+                let mut received = Vec::new();
+                let mut buf = [0u8; 4096];
+                loop {
+                    let text = String::from_utf8_lossy(&received).to_string();
+                    if let Some((head, body)) = text.split_once("\r\n\r\n") {
+                        let length = head
+                            .lines()
+                            .find_map(|line| {
+                                line.to_lowercase()
+                                    .strip_prefix("content-length: ")
+                                    .map(str::to_string)
+                            })
+                            .and_then(|v| v.trim().parse::<usize>().ok())
+                            .unwrap_or(0);
+                        if body.len() >= length {
+                            break;
+                        }
+                    }
+                    match stream.read(&mut buf).await {
+                        Ok(0) | Err(_) => break,
+                        Ok(n) => received.extend_from_slice(&buf[..n]),
+                    }
+                }
+                let _ = tx.send(String::from_utf8_lossy(&received).to_string());
+                let response = "HTTP/1.1 200 OK\r\ncontent-length: 0\r\nconnection: close\r\n\r\n";
+                let _ = stream.write_all(response.as_bytes()).await;
+            }
+        });
+
+        (format!("http://{}", addr), rx)
+    }
+
+    #[tokio::test]
+    async fn push_gateway_receives_the_text_format_on_shutdown() {
+        let (url, mut rx) = serve().await;
+        let metrics = MetricsLayer::new();
+        metrics
+            .custom_metrics()
+            .counter("batch_rows_total", "Rows processed")
+            .inc_by(3.0);
+
+        let config = PushGatewayConfig::new(url, "nightly import")
+            .grouping("instance", "worker/1")
+            .header("Authorization", "Bearer secret")
+            .interval(Duration::ZERO);
+        let handle = MetricsExporter::PushGateway(config)
+            .start(&metrics)
+            .unwrap();
+        handle.shutdown().await.unwrap();
+
+        let request = rx.recv().await.unwrap();
+        assert!(
+            request.starts_with("PUT /metrics/job/nightly%20import/instance/worker%2F1 HTTP/1.1")
+        );
+        assert!(request
+            .to_lowercase()
+            .contains("authorization: bearer secret"));
+        assert!(request.contains("batch_rows_total 3"));
+    }
+
+    #[test]
+    fn otlp_encoding_maps_counters_gauges_and_histograms() {
+        let registry = Registry::new();
+        let counter = IntCounter::new("jobs_total", "Jobs run").unwrap();
+        let gauge = IntGauge::new("queue_depth", "Queued jobs").unwrap();
+        let histogram = Histogram::with_opts(
+            HistogramOpts::new("job_seconds", "Job time").buckets(vec![1.0, 5.0]),
+        )
+        .unwrap();
+        registry.register(Box::new(counter.clone())).unwrap();
+        registry.register(Box::new(gauge.clone())).unwrap();
+        registry.register(Box::new(histogram.clone())).unwrap();
+        counter.inc_by(2);
+        gauge.set(7);
+        for seconds in [0.5, 2.0, 3.0, 9.0] {
+            histogram.observe(seconds);
+        }
+
+        let body = otlp::encode(
+            &registry.gather(),
+            &[("service.name".to_string(), "jobs".to_string())],
+            SystemTime::UNIX_EPOCH,
+            SystemTime::UNIX_EPOCH + Duration::from_secs(1),
+        );
+        let resource = &body["resourceMetrics"][0];
+        assert_eq!(
+            resource["resource"]["attributes"][0]["value"]["stringValue"],
+            "jobs"
+        );
+        let metrics: HashMap<String, serde_json::Value> = resource["scopeMetrics"][0]["metrics"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|m| (m["name"].as_str().unwrap().to_string(), m.clone()))
+            .collect();
+
+        let sum = &metrics["jobs_total"]["sum"];
+        assert_eq!(sum["isMonotonic"], true);
+        assert_eq!(sum["dataPoints"][0]["asDouble"], 2.0);
+        assert_eq!(sum["dataPoints"][0]["timeUnixNano"], "1000000000");
+        assert_eq!(
+            metrics["queue_depth"]["gauge"]["dataPoints"][0]["asDouble"],
+            7.0
+        );
+
+        let point = &metrics["job_seconds"]["histogram"]["dataPoints"][0];
+        assert_eq!(point["count"], "4");
+        assert_eq!(point["explicitBounds"], serde_json::json!([1.0, 5.0]));
+        assert_eq!(point["bucketCounts"], serde_json::json!(["1", "2", "1"]));
+    }
+
+    #[test]
+    fn exporter_is_selected_from_environment_variables() {
+        let vars = |pairs: &'static [(&'static str, &'static str)]| {
+            move |name: &str| {
+                pairs
+                    .iter()
+                    .find(|(key, _)| *key == name)
+                    .map(|(_, value)| value.to_string())
+            }
+        };
+
+        assert!(matches!(
+            MetricsExporter::from_vars(vars(&[])).unwrap(),
+            MetricsExporter::Pull
+        ));
+        assert!(matches!(
+            MetricsExporter::from_vars(vars(&[("RUSTAPI_METRICS_EXPORTER", "push-gateway")])),
+            Err(MetricsExportError::Config(_))
+        ));
+        assert!(
+            MetricsExporter::from_vars(vars(&[("RUSTAPI_METRICS_EXPORTER", "statsd")])).is_err()
+        );
+
+        let exporter = MetricsExporter::from_vars(vars(&[
+            ("RUSTAPI_METRICS_EXPORTER", "otlp"),
+            ("RUSTAPI_METRICS_INTERVAL", "5"),
+            ("OTEL_EXPORTER_OTLP_ENDPOINT", "https://otlp.example.com/"),
+            ("OTEL_EXPORTER_OTLP_HEADERS", "x-api-key=abc, x-team=core"),
+            ("OTEL_SERVICE_NAME", "billing"),
+        ]))
+        .unwrap();
+        let MetricsExporter::Otlp(config) = exporter else {
+            panic!("expected the OTLP exporter");
+        };
+        assert_eq!(
+            config.metrics_url().unwrap().as_str(),
+            "https://otlp.example.com/v1/metrics"
+        );
+        assert_eq!(config.interval, Duration::from_secs(5));
+        assert_eq!(config.service_name, "billing");
+        assert_eq!(config.headers.len(), 2);
+    }
+}
@@ -0,0 +1,150 @@
+//! Conversion of gathered Prometheus metrics to OTLP/HTTP JSON
+
+use prometheus::proto::{LabelPair, Metric, MetricFamily, MetricType};
+use serde_json::{json, Value};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// `AGGREGATION_TEMPORALITY_CUMULATIVE`: Prometheus values count from process start
+const CUMULATIVE: u8 = 2;
+
+/// Build an `ExportMetricsServiceRequest` for `families`
+///
+/// `start` is reported as the start of every cumulative series.
+pub(super) fn encode(
+    families: &[MetricFamily],
+    resource: &[(String, String)],
+    start: SystemTime,
+    now: SystemTime,
+) -> Value {
+    let start = nanos(start);
+    let now = nanos(now);
+    let metrics: Vec<Value> = families
+        .iter()
+        .filter_map(|family| encode_family(family, &start, &now))
+        .collect();
+
+    json!({
+        "resourceMetrics": [{
+            "resource": {
+                "attributes": resource
+                    .iter()
+                    .map(|(key, value)| attribute(key, value))
+                    .collect::<Vec<_>>(),
+            },
+            "scopeMetrics": [{
+                "scope": { "name": "rustapi", "version": env!("CARGO_PKG_VERSION") },
+                "metrics": metrics,
+            }],
+        }],
+    })
+}
+
+fn encode_family(family: &MetricFamily, start: &str, now: &str) -> Option<Value> {
+    let points = |value: &dyn Fn(&Metric) -> Value| -> Vec<Value> {
+        family
+            .get_metric()
+            .iter()
+            .map(|metric| {
+                let mut point = value(metric);
+                point["attributes"] = attributes(metric.get_label());
+                point["startTimeUnixNano"] = json!(start);
+                point["timeUnixNano"] = match metric.timestamp_ms() {
+                    0 => json!(now),
+                    ms => json!((ms as u128 * 1_000_000).to_string()),
+                };
+                point
+            })
+            .collect()
+    };
+
+    let data = match family.get_field_type() {
+        MetricType::COUNTER => json!({
+            "sum": {
+                "dataPoints": points(&|m| json!({ "asDouble": m.get_counter().get_value() })),
+                "aggregationTemporality": CUMULATIVE,
+                "isMonotonic": true,
+            }
+        }),
+        MetricType::GAUGE => json!({
+            "gauge": {
+                "dataPoints": points(&|m| json!({ "asDouble": m.get_gauge().get_value() })),
+            }
+        }),
+        // Only foreign collectors produce untyped metrics, without a kind to map to
+        MetricType::UNTYPED => return None,
+        MetricType::HISTOGRAM => json!({
+            "histogram": {
+                "dataPoints": points(&histogram_point),
+                "aggregationTemporality": CUMULATIVE,
+            }
+        }),
+        MetricType::SUMMARY => json!({
+            "summary": {
+                "dataPoints": points(&|m| {
+                    let summary = m.get_summary();
+                    json!({
+                        "count": summary.sample_count().to_string(),
+                        "sum": summary.sample_sum(),
+                        "quantileValues": summary
+                            .get_quantile()
+                            .iter()
+                            .map(|q| json!({ "quantile": q.quantile(), "value": q.value() }))
+                            .collect::<Vec<_>>(),
+                    })
+                }),
+            }
+        }),
+    };
+
+    let mut metric = json!({ "name": family.name(), "description": family.help() });
+    if let (Some(metric), Value::Object(data)) = (metric.as_object_mut(), data) {
+        metric.extend(data);
+    }
+    Some(metric)
+}
+
+/// Prometheus buckets are cumulative and leave out `+Inf`; OTLP counts each
+/// bucket on its own and ends with the overflow bucket
+fn histogram_point(metric: &Metric) -> Value {
+    let histogram = metric.get_histogram();
+    let count = histogram.get_sample_count();
+    let mut bounds = Vec::new();
+    let mut counts = Vec::new();
+    let mut below = 0;
+    for bucket in histogram.get_bucket() {
+        if bucket.upper_bound().is_infinite() {
+            continue;
+        }
+        let cumulative = bucket.cumulative_count();
+        bounds.push(bucket.upper_bound());
+        counts.push(cumulative.saturating_sub(below).to_string());
+        below = cumulative;
+    }
+    counts.push(count.saturating_sub(below).to_string());
+
+    json!({
+        "count": count.to_string(),
+        "sum": histogram.get_sample_sum(),
+        "bucketCounts": counts,
+        "explicitBounds": bounds,
+    })
+}
+
+fn attributes(labels: &[LabelPair]) -> Value {
+    labels
+        .iter()
+        .map(|label| attribute(label.name(), label.value()))
+        .collect()
+}
+
+fn attribute(key: &str, value: &str) -> Value {
+    json!({ "key": key, "value": { "stringValue": value } })
+}
+
+/// Nanoseconds since the epoch; OTLP JSON writes 64-bit integers as strings
+fn nanos(time: SystemTime) -> String {
+    time.duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_nanos()
+        .to_string()
+}
@@ -34,5 +34,6 @@ mod propagation;
 pub use config::{OtelConfig, OtelConfigBuilder, OtelExporter, TraceSampler};
 pub use layer::OtelLayer;
 pub use propagation::{
-    extract_trace_context, inject_trace_context, propagate_trace_context, TraceContext,
+    extract_trace_context, extract_trace_context_from_headers, inject_trace_context,
+    propagate_trace_context, TraceContext,
 };
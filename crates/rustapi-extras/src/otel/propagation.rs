@@ -152,8 +152,11 @@ impl fmt::Display for TraceContext {
 
 /// Extract trace context from incoming request headers
 pub fn extract_trace_context(request: &Request) -> TraceContext {
-    let headers = request.headers();
+    extract_trace_context_from_headers(request.headers())
+}
 
+/// Extract trace context from a raw header map (e.g. gRPC metadata)
+pub fn extract_trace_context_from_headers(headers: &http::HeaderMap) -> TraceContext {
     // Try to extract traceparent header
     let mut context = headers
         .get(TRACEPARENT_HEADER)
@@ -0,0 +1,103 @@
+//! Transactional outbox
+//!
+//! Publishing an event after committing a business change loses the event if
+//! the process dies in between; publishing before commit announces changes
+//! that may be rolled back. The outbox avoids both: the event is written to an
+//! outbox table in the same sqlx transaction as the change, and an
+//! [`OutboxRelay`] publishes committed events in the background.
+//!
+//! Delivery is at least once. An event is marked published only after its
+//! publisher succeeded, so a crash in between publishes it again. Every event
+//! carries a stable id for consumers to drop such duplicates:
+//!
+//! - [`JobPublisher`] enqueues the event as a job named after its topic, with
+//!   the event id as job id ([`JobContext::job_id`](crate::jobs::JobContext))
+//! - [`WebhookPublisher`] `POST`s the event as JSON with an `Idempotency-Key`
+//!   header
+//!
+//! Writes can be deduplicated too: messages with the same
+//! [`dedup_key`](OutboxMessage::dedup_key) are written once.
+//!
+//! Failed publishes are retried with exponential backoff. After
+//! [`max_attempts`](OutboxRelay::max_attempts) the event stays in the table
+//! with its last error, for inspection.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! use rustapi_extras::outbox::{JobPublisher, Outbox, OutboxMessage, OutboxRelay};
+//!
+//! let outbox = Outbox::new(pool.clone());
+//! outbox.ensure_schema().await?;
+//!
+//! // In the handler
+//! let mut tx = pool.begin().await?;
+//! sqlx::query("INSERT INTO orders (id, total) VALUES ($1, $2)")
+//!     .bind(&order.id)
+//!     .bind(order.total)
+//!     .execute(&mut *tx)
+//!     .await?;
+//! let message = OutboxMessage::new("order_placed", &order)?.dedup_key(&order.id);
+//! outbox.write(&mut tx, &message).await?;
+//! tx.commit().await?;
+//!
+//! // At startup
+//! let relay = OutboxRelay::new(outbox).route("order_placed", JobPublisher::new(queue));
+//! tokio::spawn(async move { relay.run().await });
+//! ```
+
+mod publish;
+mod relay;
+mod store;
+
+pub use publish::{JobPublisher, OutboxPublisher, WebhookPublisher};
+pub use relay::OutboxRelay;
+pub use store::{Outbox, OutboxEvent, OutboxMessage};
+
+use std::fmt;
+
+/// Errors from writing or relaying outbox events.
+#[derive(Debug)]
+pub enum OutboxError {
+    /// The database query failed.
+    Database(sqlx::Error),
+    /// The event payload could not be serialized.
+    Serialization(serde_json::Error),
+    /// The publisher did not accept the event.
+    Publish(String),
+}
+
+impl fmt::Display for OutboxError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Database(e) => write!(f, "Outbox database error: {}", e),
+            Self::Serialization(e) => write!(f, "Outbox serialization error: {}", e),
+            Self::Publish(msg) => write!(f, "Outbox publish error: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for OutboxError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Database(e) => Some(e),
+            Self::Serialization(e) => Some(e),
+            Self::Publish(_) => None,
+        }
+    }
+}
+
+impl From<sqlx::Error> for OutboxError {
+    fn from(e: sqlx::Error) -> Self {
+        Self::Database(e)
+    }
+}
+
+impl From<serde_json::Error> for OutboxError {
+    fn from(e: serde_json::Error) -> Self {
+        Self::Serialization(e)
+    }
+}
+
+/// Specialized `Result` type for outbox operations.
+pub type Result<T> = std::result::Result<T, OutboxError>;
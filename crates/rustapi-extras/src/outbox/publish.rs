@@ -0,0 +1,175 @@
+//! Publishers the relay hands events to.
+
+use super::store::OutboxEvent;
+use super::{OutboxError, Result};
+use crate::insight::export::WebhookConfig;
+use crate::jobs::{EnqueueOptions, JobQueue};
+use std::future::Future;
+use std::pin::Pin;
+
+/// Destination for outbox events (dyn-compatible via boxed futures).
+///
+/// Returning `Ok` marks the event published; an error schedules a retry.
+pub trait OutboxPublisher: Send + Sync {
+    /// Publish one event.
+    fn publish<'a>(
+        &'a self,
+        event: &'a OutboxEvent,
+    ) -> Pin<Box<dyn Future<Output = Result<()>> + Send + 'a>>;
+}
+
+/// Enqueues events as background jobs.
+///
+/// The job is named after the event topic, so a [`Job`](crate::jobs::Job)
+/// with `NAME` equal to the topic handles it, receiving the payload as its
+/// data. The job id is the event id, which backends that keep jobs by id
+/// (like PostgreSQL) use to ignore a second enqueue of a queued event.
+#[derive(Clone)]
+pub struct JobPublisher {
+    queue: JobQueue,
+    max_attempts: u32,
+}
+
+impl JobPublisher {
+    /// Enqueue events on `queue`, with 3 attempts per job.
+    pub fn new(queue: JobQueue) -> Self {
+        Self {
+            queue,
+            max_attempts: 3,
+        }
+    }
+
+    /// Set the attempts of the enqueued jobs.
+    pub fn max_attempts(mut self, attempts: u32) -> Self {
+        self.max_attempts = attempts;
+        self
+    }
+}
+
+impl OutboxPublisher for JobPublisher {
+    fn publish<'a>(
+        &'a self,
+        event: &'a OutboxEvent,
+    ) -> Pin<Box<dyn Future<Output = Result<()>> + Send + 'a>> {
+        Box::pin(async move {
+            let opts = EnqueueOptions::new()
+                .id(event.id.clone())
+                .max_attempts(self.max_attempts);
+            self.queue
+                .enqueue_raw(&event.topic, event.payload.clone(), opts)
+                .await
+                .map_err(|e| OutboxError::Publish(e.to_string()))?;
+            Ok(())
+        })
+    }
+}
+
+/// `POST`s events as JSON to a webhook.
+///
+/// The body is the serialized [`OutboxEvent`], and the `Idempotency-Key`
+/// header carries the event id. Any non-2xx status is a failed publish.
+///
+/// # Example
+///
+/// ```ignore
+/// use rustapi_extras::insight::export::WebhookConfig;
+/// use rustapi_extras::outbox::WebhookPublisher;
+///
+/// let publisher = WebhookPublisher::new(
+///     WebhookConfig::new("https://hooks.example.com/orders").auth("Bearer my-token"),
+/// )?;
+/// ```
+#[derive(Clone)]
+pub struct WebhookPublisher {
+    config: WebhookConfig,
+    client: reqwest::Client,
+}
+
+impl WebhookPublisher {
+    /// Create a publisher from a webhook configuration.
+    pub fn new(config: WebhookConfig) -> Result<Self> {
+        let client = reqwest::Client::builder()
+            .timeout(std::time::Duration::from_secs(config.timeout_secs))
+            .build()
+            .map_err(|e| OutboxError::Publish(e.to_string()))?;
+        Ok(Self { config, client })
+    }
+}
+
+impl OutboxPublisher for WebhookPublisher {
+    fn publish<'a>(
+        &'a self,
+        event: &'a OutboxEvent,
+    ) -> Pin<Box<dyn Future<Output = Result<()>> + Send + 'a>> {
+        Box::pin(async move {
+            let mut request = self
+                .client
+                .post(&self.config.url)
+                .header("Idempotency-Key", &event.id)
+                .json(event);
+            if let Some(auth) = &self.config.auth_header {
+                request = request.header("Authorization", auth);
+            }
+            for (name, value) in &self.config.headers {
+                request = request.header(name, value);
+            }
+
+            let response = request
+                .send()
+                .await
+                .map_err(|e| OutboxError::Publish(e.to_string()))?;
+            if !response.status().is_success() {
+                return Err(OutboxError::Publish(format!(
+                    "Webhook returned status {}",
+                    response.status()
+                )));
+            }
+            Ok(())
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::jobs::{InMemoryBackend, Job, JobContext};
+    use std::sync::{Arc, Mutex};
+
+    #[derive(Clone, Default)]
+    struct OrderPlaced {
+        seen: Arc<Mutex<Vec<(String, u32)>>>,
+    }
+
+    impl Job for OrderPlaced {
+        const NAME: &'static str = "order_placed";
+        type Data = u32;
+
+        async fn execute(&self, ctx: JobContext, total: u32) -> crate::jobs::error::Result<()> {
+            self.seen.lock().unwrap().push((ctx.job_id, total));
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn job_publisher_enqueues_under_the_topic_with_the_event_id() {
+        let queue = JobQueue::new(InMemoryBackend::new());
+        let job = OrderPlaced::default();
+        queue.register_job(job.clone()).await;
+
+        let event = OutboxEvent {
+            id: "evt-1".into(),
+            topic: "order_placed".into(),
+            payload: serde_json::json!(42),
+            dedup_key: None,
+            created_at: chrono::Utc::now(),
+            attempts: 0,
+        };
+        JobPublisher::new(queue.clone())
+            .publish(&event)
+            .await
+            .unwrap();
+
+        assert!(queue.process_one().await.unwrap());
+        assert_eq!(*job.seen.lock().unwrap(), vec![("evt-1".to_string(), 42)]);
+    }
+}
@@ -0,0 +1,241 @@
+//! Background relay publishing committed outbox events.
+
+use super::publish::OutboxPublisher;
+use super::store::{Outbox, OutboxEvent};
+use super::{OutboxError, Result};
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Publishes committed outbox events to their topic's publisher.
+///
+/// Each pass leases a batch of due events in a short transaction (using
+/// `FOR UPDATE SKIP LOCKED`), then publishes them outside of any transaction
+/// and records each outcome on its own. Several relays (e.g. one per replica)
+/// can run against the same table without publishing an event twice at the
+/// same time, as long as a batch is published within the
+/// [`lease`](Self::lease). Events whose lease ends before they are marked,
+/// e.g. because the relay stopped, are published again.
+#[derive(Clone)]
+pub struct OutboxRelay {
+    outbox: Outbox,
+    routes: Routes,
+    batch_size: usize,
+    poll_interval: Duration,
+    max_attempts: u32,
+    max_backoff: Duration,
+    lease: Duration,
+}
+
+impl OutboxRelay {
+    /// Create a relay for `outbox` with no routes yet.
+    pub fn new(outbox: Outbox) -> Self {
+        Self {
+            outbox,
+            routes: Routes::default(),
+            batch_size: 100,
+            poll_interval: Duration::from_secs(1),
+            max_attempts: 10,
+            max_backoff: Duration::from_secs(3600),
+            lease: Duration::from_secs(300),
+        }
+    }
+
+    /// Publish events on `topic` with `publisher`.
+    pub fn route(
+        mut self,
+        topic: impl Into<String>,
+        publisher: impl OutboxPublisher + 'static,
+    ) -> Self {
+        self.routes.topics.insert(topic.into(), Arc::new(publisher));
+        self
+    }
+
+    /// Publish events on topics without a route with `publisher`.
+    ///
+    /// Without a fallback, such events fail like a rejected publish.
+    pub fn fallback(mut self, publisher: impl OutboxPublisher + 'static) -> Self {
+        self.routes.fallback = Some(Arc::new(publisher));
+        self
+    }
+
+    /// Maximum number of events published per pass (default: 100).
+    pub fn batch_size(mut self, size: usize) -> Self {
+        self.batch_size = size.max(1);
+        self
+    }
+
+    /// Wait between passes that found nothing to publish (default: 1s).
+    pub fn poll_interval(mut self, interval: Duration) -> Self {
+        self.poll_interval = interval;
+        self
+    }
+
+    /// Publish attempts before an event is left in the table (default: 10).
+    pub fn max_attempts(mut self, attempts: u32) -> Self {
+        self.max_attempts = attempts;
+        self
+    }
+
+    /// Longest wait between retries of one event (default: 1 hour).
+    pub fn max_backoff(mut self, backoff: Duration) -> Self {
+        self.max_backoff = backoff;
+        self
+    }
+
+    /// How long a claimed batch is hidden from other relays (default: 5
+    /// minutes).
+    ///
+    /// Keep it above the time publishing a batch can take.
+    pub fn lease(mut self, lease: Duration) -> Self {
+        self.lease = lease;
+        self
+    }
+
+    /// Publish one batch of due events, returning how many were published.
+    pub async fn run_once(&self) -> Result<usize> {
+        let events = self
+            .outbox
+            .claim(self.batch_size, self.max_attempts, self.lease)
+            .await?;
+
+        let mut published = 0;
+        for event in &events {
+            match self.publish(event).await {
+                Ok(()) => {
+                    self.outbox.mark_published(&event.id).await?;
+                    published += 1;
+                }
+                Err(e) => {
+                    let error = e.to_string();
+                    let attempts = event.attempts + 1;
+                    if attempts >= self.max_attempts {
+                        tracing::error!(
+                            event_id = %event.id,
+                            topic = %event.topic,
+                            attempts,
+                            error = %error,
+                            "Outbox event gave up"
+                        );
+                    } else {
+                        tracing::warn!(
+                            event_id = %event.id,
+                            topic = %event.topic,
+                            attempts,
+                            error = %error,
+                            "Outbox publish failed"
+                        );
+                    }
+                    let retry_in = backoff(attempts, self.max_backoff);
+                    self.outbox.mark_failed(&event.id, &error, retry_in).await?;
+                }
+            }
+        }
+
+        Ok(published)
+    }
+
+    /// Publish due events until the task is dropped.
+    pub async fn run(&self) {
+        loop {
+            match self.run_once().await {
+                Ok(0) => tokio::time::sleep(self.poll_interval).await,
+                Ok(_) => {}
+                Err(e) => {
+                    tracing::error!("Outbox relay error: {}", e);
+                    tokio::time::sleep(self.poll_interval).await;
+                }
+            }
+        }
+    }
+
+    async fn publish(&self, event: &OutboxEvent) -> Result<()> {
+        match self.routes.get(&event.topic) {
+            Some(publisher) => publisher.publish(event).await,
+            None => Err(OutboxError::Publish(format!(
+                "No publisher for topic: {}",
+                event.topic
+            ))),
+        }
+    }
+}
+
+/// Publishers by topic.
+#[derive(Clone, Default)]
+struct Routes {
+    topics: HashMap<String, Arc<dyn OutboxPublisher>>,
+    fallback: Option<Arc<dyn OutboxPublisher>>,
+}
+
+impl Routes {
+    fn get(&self, topic: &str) -> Option<&Arc<dyn OutboxPublisher>> {
+        self.topics.get(topic).or(self.fallback.as_ref())
+    }
+}
+
+/// Wait before the next attempt: 2^attempts seconds, up to `max`.
+fn backoff(attempts: u32, max: Duration) -> Duration {
+    Duration::from_secs(2u64.saturating_pow(attempts)).min(max)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::future::Future;
+    use std::pin::Pin;
+
+    struct Named(&'static str);
+
+    impl OutboxPublisher for Named {
+        fn publish<'a>(
+            &'a self,
+            _event: &'a OutboxEvent,
+        ) -> Pin<Box<dyn Future<Output = Result<()>> + Send + 'a>> {
+            Box::pin(async { Err(OutboxError::Publish(self.0.to_string())) })
+        }
+    }
+
+    fn name_of(routes: &Routes, topic: &str) -> Option<String> {
+        let event = OutboxEvent {
+            id: "evt".into(),
+            topic: topic.into(),
+            payload: serde_json::Value::Null,
+            dedup_key: None,
+            created_at: chrono::Utc::now(),
+            attempts: 0,
+        };
+        let publisher = routes.get(topic)?;
+        let rt = tokio::runtime::Builder::new_current_thread()
+            .build()
+            .unwrap();
+        match rt.block_on(publisher.publish(&event)) {
+            Err(OutboxError::Publish(name)) => Some(name),
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn routes_by_topic_then_fallback() {
+        let mut routes = Routes::default();
+        routes
+            .topics
+            .insert("order_placed".into(), Arc::new(Named("orders")));
+        assert_eq!(name_of(&routes, "order_placed").as_deref(), Some("orders"));
+        assert_eq!(name_of(&routes, "user_created"), None);
+
+        routes.fallback = Some(Arc::new(Named("fallback")));
+        assert_eq!(
+            name_of(&routes, "user_created").as_deref(),
+            Some("fallback")
+        );
+    }
+
+    #[test]
+    fn backoff_doubles_up_to_the_cap() {
+        let max = Duration::from_secs(60);
+        assert_eq!(backoff(1, max), Duration::from_secs(2));
+        assert_eq!(backoff(4, max), Duration::from_secs(16));
+        assert_eq!(backoff(6, max), max);
+        assert_eq!(backoff(u32::MAX, max), max);
+    }
+}
@@ -0,0 +1,251 @@
+//! Outbox table and the events written to it.
+
+use super::Result;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use sqlx::{Pool, Postgres, Row, Transaction};
+use std::time::Duration;
+use uuid::Uuid;
+
+/// An event to write to the outbox.
+#[derive(Debug, Clone)]
+pub struct OutboxMessage {
+    id: String,
+    topic: String,
+    payload: serde_json::Value,
+    dedup_key: Option<String>,
+}
+
+impl OutboxMessage {
+    /// Create a message on `topic` with a fresh id.
+    pub fn new<T: Serialize + ?Sized>(topic: impl Into<String>, payload: &T) -> Result<Self> {
+        Ok(Self {
+            id: Uuid::new_v4().to_string(),
+            topic: topic.into(),
+            payload: serde_json::to_value(payload)?,
+            dedup_key: None,
+        })
+    }
+
+    /// Write the message only if no event with the same key was written.
+    pub fn dedup_key(mut self, key: impl Into<String>) -> Self {
+        self.dedup_key = Some(key.into());
+        self
+    }
+
+    /// The event id consumers see.
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    /// The topic the message is routed by.
+    pub fn topic(&self) -> &str {
+        &self.topic
+    }
+}
+
+/// An event read back from the outbox by the relay.
+#[derive(Debug, Clone, Serialize)]
+pub struct OutboxEvent {
+    /// Event id, stable across publish attempts.
+    pub id: String,
+    /// Topic the event is routed by.
+    pub topic: String,
+    /// Event payload.
+    pub payload: serde_json::Value,
+    /// Key the write was deduplicated by, if any.
+    pub dedup_key: Option<String>,
+    /// When the event was written.
+    pub created_at: DateTime<Utc>,
+    /// Failed publish attempts so far.
+    pub attempts: u32,
+}
+
+/// PostgreSQL outbox table.
+///
+/// Call [`ensure_schema`](Self::ensure_schema) once at startup to create the
+/// table, or create it with a migration.
+#[derive(Debug, Clone)]
+pub struct Outbox {
+    pool: Pool<Postgres>,
+    table_name: String,
+}
+
+impl Outbox {
+    /// Create an outbox writing to the `rustapi_outbox` table.
+    pub fn new(pool: Pool<Postgres>) -> Self {
+        Self {
+            pool,
+            table_name: "rustapi_outbox".to_string(),
+        }
+    }
+
+    /// Override the table name.
+    pub fn table_name(mut self, name: impl Into<String>) -> Self {
+        self.table_name = name.into();
+        self
+    }
+
+    /// Create the table and its pending-events index if they do not exist.
+    pub async fn ensure_schema(&self) -> Result<()> {
+        let table = &self.table_name;
+        let statements = [
+            format!(
+                "CREATE TABLE IF NOT EXISTS {table} (
+                    seq BIGSERIAL,
+                    id TEXT PRIMARY KEY,
+                    topic TEXT NOT NULL,
+                    payload JSONB NOT NULL,
+                    dedup_key TEXT UNIQUE,
+                    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+                    attempts INT NOT NULL DEFAULT 0,
+                    next_attempt_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+                    last_error TEXT,
+                    published_at TIMESTAMPTZ
+                )"
+            ),
+            format!(
+                "CREATE INDEX IF NOT EXISTS idx_{table}_pending ON {table} (next_attempt_at, seq)
+                    WHERE published_at IS NULL"
+            ),
+        ];
+
+        for statement in statements {
+            sqlx::query(&statement).execute(&self.pool).await?;
+        }
+        Ok(())
+    }
+
+    /// Write `message` as part of `tx`.
+    ///
+    /// The event becomes visible to the relay when `tx` commits, and is
+    /// dropped with it on rollback. Returns `false` if an event with the same
+    /// id or dedup key was already written.
+    pub async fn write(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        message: &OutboxMessage,
+    ) -> Result<bool> {
+        let sql = format!(
+            "INSERT INTO {} (id, topic, payload, dedup_key) VALUES ($1, $2, $3, $4)
+                ON CONFLICT DO NOTHING",
+            self.table_name
+        );
+        let result = sqlx::query(&sql)
+            .bind(&message.id)
+            .bind(&message.topic)
+            .bind(&message.payload)
+            .bind(&message.dedup_key)
+            .execute(&mut **tx)
+            .await?;
+        Ok(result.rows_affected() == 1)
+    }
+
+    /// Delete events published more than `age` ago, returning how many.
+    pub async fn purge_published(&self, age: Duration) -> Result<u64> {
+        let sql = format!(
+            "DELETE FROM {} WHERE published_at < NOW() - make_interval(secs => $1)",
+            self.table_name
+        );
+        let result = sqlx::query(&sql)
+            .bind(age.as_secs_f64())
+            .execute(&self.pool)
+            .await?;
+        Ok(result.rows_affected())
+    }
+
+    /// Lease up to `limit` due events for `lease`, oldest first.
+    ///
+    /// The claim commits on its own: the events are not due again until the
+    /// lease ends, so other relays skip them while this one publishes, and
+    /// they are retried if this relay stops before marking them.
+    pub(super) async fn claim(
+        &self,
+        limit: usize,
+        max_attempts: u32,
+        lease: Duration,
+    ) -> Result<Vec<OutboxEvent>> {
+        let table = &self.table_name;
+        let sql = format!(
+            "UPDATE {table} SET next_attempt_at = NOW() + make_interval(secs => $3)
+                WHERE id IN (
+                    SELECT id FROM {table}
+                    WHERE published_at IS NULL AND attempts < $1 AND next_attempt_at <= NOW()
+                    ORDER BY seq
+                    LIMIT $2
+                    FOR UPDATE SKIP LOCKED
+                )
+                RETURNING seq, id, topic, payload, dedup_key, created_at, attempts"
+        );
+        let mut rows = sqlx::query(&sql)
+            .bind(i32::try_from(max_attempts).unwrap_or(i32::MAX))
+            .bind(i64::try_from(limit).unwrap_or(i64::MAX))
+            .bind(lease.as_secs_f64())
+            .fetch_all(&self.pool)
+            .await?;
+        rows.sort_by_key(|row| row.get::<i64, _>("seq"));
+
+        Ok(rows
+            .into_iter()
+            .map(|row| OutboxEvent {
+                id: row.get("id"),
+                topic: row.get("topic"),
+                payload: row.get("payload"),
+                dedup_key: row.get("dedup_key"),
+                created_at: row.get("created_at"),
+                attempts: row.get::<i32, _>("attempts") as u32,
+            })
+            .collect())
+    }
+
+    pub(super) async fn mark_published(&self, id: &str) -> Result<()> {
+        let sql = format!(
+            "UPDATE {} SET published_at = NOW(), last_error = NULL WHERE id = $1",
+            self.table_name
+        );
+        sqlx::query(&sql).bind(id).execute(&self.pool).await?;
+        Ok(())
+    }
+
+    pub(super) async fn mark_failed(
+        &self,
+        id: &str,
+        error: &str,
+        retry_in: Duration,
+    ) -> Result<()> {
+        let sql = format!(
+            "UPDATE {} SET attempts = attempts + 1, last_error = $2,
+                next_attempt_at = NOW() + make_interval(secs => $3)
+                WHERE id = $1",
+            self.table_name
+        );
+        sqlx::query(&sql)
+            .bind(id)
+            .bind(error)
+            .bind(retry_in.as_secs_f64())
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn message_serializes_the_payload_and_keeps_its_id() {
+        let message = OutboxMessage::new("order_placed", &serde_json::json!({ "total": 42 }))
+            .unwrap()
+            .dedup_key("order-7");
+
+        assert_eq!(message.topic(), "order_placed");
+        assert_eq!(message.payload["total"], 42);
+        assert_eq!(message.dedup_key.as_deref(), Some("order-7"));
+        assert!(Uuid::parse_str(message.id()).is_ok());
+        assert_ne!(
+            message.id(),
+            OutboxMessage::new("order_placed", &()).unwrap().id()
+        );
+    }
+}
@@ -18,13 +18,13 @@ use dashmap::DashMap;
 use http::StatusCode;
 use http_body_util::Full;
 use rustapi_core::middleware::{BoxedNext, MiddlewareLayer};
-use rustapi_core::{Request, Response, ResponseBody};
+use rustapi_core::{Clock, Request, Response, ResponseBody};
 use std::collections::VecDeque;
 use std::future::Future;
 use std::net::IpAddr;
 use std::pin::Pin;
 use std::sync::Arc;
-use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use std::time::{Duration, Instant};
 
 /// Internal entry for tracking rate limit state per client.
 #[derive(Debug, Clone)]
@@ -73,8 +73,9 @@ impl RateLimitStore {
         max_requests: u32,
         window: Duration,
         strategy: RateLimitStrategy,
+        clock: &Clock,
     ) -> (bool, u32, u32, u64) {
-        let now = Instant::now();
+        let now = clock.instant();
         let mut entry = self
             .entries
             .entry(ip)
@@ -154,11 +155,11 @@ impl RateLimitStore {
             (entry, _) => {
                 *entry = RateLimitStore::new_entry(strategy, max_requests, now);
                 let _ = entry;
-                return self.check_and_update(ip, max_requests, window, strategy);
+                return self.check_and_update(ip, max_requests, window, strategy, clock);
             }
         };
 
-        let reset = unix_timestamp_after(decision.retry_after);
+        let reset = unix_timestamp_after(clock, decision.retry_after);
         (
             decision.is_allowed,
             max_requests.saturating_sub(decision.remaining),
@@ -175,8 +176,9 @@ impl RateLimitStore {
         max_requests: u32,
         window: Duration,
         strategy: RateLimitStrategy,
+        clock: &Clock,
     ) -> Option<RateLimitInfo> {
-        let now = Instant::now();
+        let now = clock.instant();
 
         self.entries
             .get(&ip)
@@ -198,6 +200,7 @@ impl RateLimitStore {
                         limit: max_requests,
                         remaining: max_requests.saturating_sub(current_count),
                         reset: unix_timestamp_after(
+                            clock,
                             window.saturating_sub(now.duration_since(*window_start)),
                         ),
                     }
@@ -216,7 +219,7 @@ impl RateLimitStore {
                     RateLimitInfo {
                         limit: max_requests,
                         remaining: max_requests.saturating_sub(active.len() as u32),
-                        reset: unix_timestamp_after(retry_after),
+                        reset: unix_timestamp_after(clock, retry_after),
                     }
                 }
                 (
@@ -234,13 +237,13 @@ impl RateLimitStore {
                     RateLimitInfo {
                         limit: max_requests,
                         remaining: available.floor().max(0.0).min(max_requests as f64) as u32,
-                        reset: unix_timestamp_after(retry_after),
+                        reset: unix_timestamp_after(clock, retry_after),
                     }
                 }
                 _ => RateLimitInfo {
                     limit: max_requests,
                     remaining: max_requests,
-                    reset: unix_timestamp_after(Duration::ZERO),
+                    reset: unix_timestamp_after(clock, Duration::ZERO),
                 },
             })
     }
@@ -277,15 +280,8 @@ fn next_token_after(tokens: f64, max_requests: u32, refill_rate: f64) -> Duratio
     Duration::from_secs_f64((needed / refill_rate).max(0.0))
 }
 
-fn unix_timestamp_after(duration: Duration) -> u64 {
-    unix_now_secs() + duration_to_header_secs(duration)
-}
-
-fn unix_now_secs() -> u64 {
-    SystemTime::now()
-        .duration_since(UNIX_EPOCH)
-        .unwrap_or_default()
-        .as_secs()
+fn unix_timestamp_after(clock: &Clock, duration: Duration) -> u64 {
+    clock.unix().as_secs() + duration_to_header_secs(duration)
 }
 
 fn duration_to_header_secs(duration: Duration) -> u64 {
@@ -317,8 +313,12 @@ pub struct RateLimitLayer {
     window: Duration,
     strategy: RateLimitStrategy,
     store: Arc<RateLimitStore>,
+    limit_by: Option<LimitFn>,
 }
 
+/// Per-request limit override, see [`RateLimitLayer::limit_by`]
+type LimitFn = Arc<dyn Fn(&Request) -> Option<u32> + Send + Sync>;
+
 impl RateLimitLayer {
     /// Create a new rate limit layer.
     ///
@@ -342,6 +342,7 @@ impl RateLimitLayer {
             window,
             strategy: RateLimitStrategy::FixedWindow,
             store: Arc::new(RateLimitStore::new()),
+            limit_by: None,
         }
     }
 
@@ -352,6 +353,7 @@ impl RateLimitLayer {
             window,
             strategy: RateLimitStrategy::SlidingWindow,
             store: Arc::new(RateLimitStore::new()),
+            limit_by: None,
         }
     }
 
@@ -362,9 +364,32 @@ impl RateLimitLayer {
             window: refill_window,
             strategy: RateLimitStrategy::TokenBucket,
             store: Arc::new(RateLimitStore::new()),
+            limit_by: None,
         }
     }
 
+    /// Choose the limit per request.
+    ///
+    /// `policy` returns the limit for a request, or `None` for the default.
+    /// Requests are still counted per client IP, so the policy should give
+    /// the same limit to every request of a client, e.g. based on its
+    /// country (see `GeoIp`) or network.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// // 10 requests per minute from one network, 100 from everyone else
+    /// RateLimitLayer::new(100, Duration::from_secs(60))
+    ///     .limit_by(|req: &Request| (GeoIp::of(req)?.asn()? == 64500).then_some(10))
+    /// ```
+    pub fn limit_by<F>(mut self, policy: F) -> Self
+    where
+        F: Fn(&Request) -> Option<u32> + Send + Sync + 'static,
+    {
+        self.limit_by = Some(Arc::new(policy));
+        self
+    }
+
     /// Get the configured request limit.
     pub fn requests(&self) -> u32 {
         self.requests
@@ -426,19 +451,24 @@ impl MiddlewareLayer for RateLimitLayer {
         next: BoxedNext,
     ) -> Pin<Box<dyn Future<Output = Response> + Send + 'static>> {
         let store = self.store.clone();
-        let max_requests = self.requests;
+        let max_requests = self
+            .limit_by
+            .as_ref()
+            .and_then(|policy| policy(&req))
+            .unwrap_or(self.requests);
         let window = self.window;
         let strategy = self.strategy;
 
         Box::pin(async move {
             let client_ip = RateLimitLayer::extract_client_ip(&req);
+            let clock = Clock::of(&req);
 
             let (is_allowed, _count, remaining, reset) =
-                store.check_and_update(client_ip, max_requests, window, strategy);
+                store.check_and_update(client_ip, max_requests, window, strategy, &clock);
 
             if !is_allowed {
                 // Calculate Retry-After in seconds
-                let now_secs = unix_now_secs();
+                let now_secs = clock.unix().as_secs();
                 let retry_after = reset.saturating_sub(now_secs);
 
                 // Return 429 Too Many Requests
@@ -959,4 +989,62 @@ mod tests {
             assert_eq!(response.status(), StatusCode::OK);
         });
     }
+
+    #[test]
+    fn test_window_resets_with_manual_clock() {
+        let rt = tokio::runtime::Runtime::new().unwrap();
+        rt.block_on(async {
+            let clock = Clock::manual();
+            let layer = RateLimitLayer::new(1, Duration::from_secs(60));
+            let mut stack = LayerStack::new();
+            stack.push(Box::new(layer));
+
+            let send = |stack: &LayerStack| {
+                let mut request = create_test_request(Some("10.0.0.4"));
+                request.extensions_mut().insert(clock.clone());
+                stack.execute(request, create_success_handler())
+            };
+
+            assert_eq!(send(&stack).await.status(), StatusCode::OK);
+            let response = send(&stack).await;
+            assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+            assert_eq!(response.headers().get("Retry-After").unwrap(), "60");
+
+            clock.advance(Duration::from_secs(59));
+            assert_eq!(send(&stack).await.status(), StatusCode::TOO_MANY_REQUESTS);
+            clock.advance(Duration::from_secs(1));
+            assert_eq!(send(&stack).await.status(), StatusCode::OK);
+        });
+    }
+
+    #[test]
+    fn test_limit_by_overrides_the_limit_per_request() {
+        let rt = tokio::runtime::Runtime::new().unwrap();
+        rt.block_on(async {
+            let layer =
+                RateLimitLayer::new(3, Duration::from_secs(60)).limit_by(|req: &Request| {
+                    req.headers()
+                        .get("X-Forwarded-For")?
+                        .to_str()
+                        .ok()?
+                        .starts_with("203.0.113.")
+                        .then_some(1)
+                });
+            let mut stack = LayerStack::new();
+            stack.push(Box::new(layer));
+
+            let send = |stack: &LayerStack, ip: &str| {
+                stack.execute(create_test_request(Some(ip)), create_success_handler())
+            };
+
+            assert_eq!(send(&stack, "203.0.113.5").await.status(), StatusCode::OK);
+            let response = send(&stack, "203.0.113.5").await;
+            assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+            assert_eq!(response.headers().get("X-RateLimit-Limit").unwrap(), "1");
+
+            for _ in 0..3 {
+                assert_eq!(send(&stack, "192.0.2.5").await.status(), StatusCode::OK);
+            }
+        });
+    }
 }
@@ -5,6 +5,7 @@ use http_body_util::Full;
 use rustapi_core::Response;
 use rustapi_core::ResponseBody;
 use serde_json::json;
+use subtle::ConstantTimeEq;
 
 /// Admin authentication check for replay endpoints.
 pub struct ReplayAdminAuth;
@@ -46,20 +47,7 @@ fn bearer_token_matches(value: &str, expected_token: &str) -> bool {
     };
 
     scheme.eq_ignore_ascii_case("Bearer")
-        && constant_time_eq(token.trim().as_bytes(), expected_token.as_bytes())
-}
-
-fn constant_time_eq(left: &[u8], right: &[u8]) -> bool {
-    let max_len = left.len().max(right.len());
-    let mut diff = left.len() ^ right.len();
-
-    for idx in 0..max_len {
-        let left_byte = left.get(idx).copied().unwrap_or(0);
-        let right_byte = right.get(idx).copied().unwrap_or(0);
-        diff |= (left_byte ^ right_byte) as usize;
-    }
-
-    diff == 0
+        && bool::from(token.trim().as_bytes().ct_eq(expected_token.as_bytes()))
 }
 
 #[cfg(test)]
@@ -0,0 +1,396 @@
+//! Batch replay against an alternate base URL with an aggregated diff report.
+
+use super::client::ReplayClient;
+use futures_util::stream::{self, StreamExt};
+use rustapi_core::replay::{compute_diff, DiffField, DiffResult, ReplayEntry};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::time::Instant;
+
+/// Options for [`ReplayClient::replay_batch`].
+#[derive(Debug, Clone)]
+pub struct BatchReplayOptions {
+    /// Number of requests in flight at once. Default: 8.
+    pub concurrency: usize,
+    /// Headers left out of the comparison (case-insensitive).
+    /// Default: `date`, `x-request-id`, `x-correlation-id`, `server`.
+    pub ignore_headers: Vec<String>,
+    /// Maximum replayed response body to capture (bytes). Default: 256KB.
+    pub max_response_body: Option<usize>,
+}
+
+impl Default for BatchReplayOptions {
+    fn default() -> Self {
+        Self {
+            concurrency: 8,
+            ignore_headers: vec![
+                "date".to_string(),
+                "x-request-id".to_string(),
+                "x-correlation-id".to_string(),
+                "server".to_string(),
+            ],
+            max_response_body: Some(262_144),
+        }
+    }
+}
+
+impl BatchReplayOptions {
+    /// Create options with the defaults.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the number of requests in flight at once.
+    pub fn concurrency(mut self, concurrency: usize) -> Self {
+        self.concurrency = concurrency.max(1);
+        self
+    }
+
+    /// Leave an additional header out of the comparison.
+    pub fn ignore_header(mut self, header: impl Into<String>) -> Self {
+        self.ignore_headers.push(header.into());
+        self
+    }
+
+    /// Set the maximum replayed response body to capture.
+    pub fn max_response_body(mut self, size: Option<usize>) -> Self {
+        self.max_response_body = size;
+        self
+    }
+}
+
+/// Result of replaying one entry as part of a batch.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReplayOutcome {
+    /// ID of the replayed entry.
+    pub id: String,
+    /// Recorded request method.
+    pub method: String,
+    /// Recorded request path.
+    pub path: String,
+    /// Request ID of the recorded request.
+    pub request_id: Option<String>,
+    /// Trace ID the request was recorded under.
+    pub original_trace_id: Option<String>,
+    /// Trace ID the replay was sent under, if the replay succeeded.
+    pub replay_trace_id: Option<String>,
+    /// Recorded response status.
+    pub original_status: u16,
+    /// Status returned by the target, if the replay succeeded.
+    pub replayed_status: Option<u16>,
+    /// Recorded processing time.
+    pub original_duration_ms: u64,
+    /// Round trip to the target, if the replay succeeded.
+    pub replayed_duration_ms: Option<u64>,
+    /// Differences from the recorded response, if the replay succeeded.
+    pub diff: Option<DiffResult>,
+    /// Why the replay failed.
+    pub error: Option<String>,
+}
+
+impl ReplayOutcome {
+    /// Replayed minus recorded duration, in milliseconds.
+    pub fn latency_delta_ms(&self) -> Option<i64> {
+        self.replayed_duration_ms
+            .map(|replayed| replayed as i64 - self.original_duration_ms as i64)
+    }
+}
+
+/// Aggregated result of replaying a batch of entries against one target.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BatchReplayReport {
+    /// Base URL the batch was replayed against.
+    pub target: String,
+    /// Number of entries replayed.
+    pub total: usize,
+    /// Entries whose replayed response matched the recording.
+    pub matched: usize,
+    /// Entries whose replayed response differed.
+    pub diffed: usize,
+    /// Entries that could not be replayed.
+    pub failed: usize,
+    /// Entries whose status changed.
+    pub status_mismatches: usize,
+    /// How many entries each field changed in, keyed by
+    /// `status`, `header:<name>`, `body:<path>` or `body`.
+    pub changed_fields: BTreeMap<String, usize>,
+    /// Latency comparison across successful replays.
+    pub latency: LatencySummary,
+    /// Per-entry results, in the order the entries were given.
+    pub outcomes: Vec<ReplayOutcome>,
+}
+
+/// Recorded vs replayed latency across a batch, in milliseconds.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct LatencySummary {
+    /// Median recorded processing time.
+    pub original_p50_ms: u64,
+    /// Median replay round trip.
+    pub replayed_p50_ms: u64,
+    /// 99th percentile recorded processing time.
+    pub original_p99_ms: u64,
+    /// 99th percentile replay round trip.
+    pub replayed_p99_ms: u64,
+    /// Mean of the per-entry deltas; positive means the target is slower.
+    pub mean_delta_ms: f64,
+}
+
+impl BatchReplayReport {
+    /// Aggregate per-entry outcomes into a report.
+    pub fn from_outcomes(target: impl Into<String>, outcomes: Vec<ReplayOutcome>) -> Self {
+        let mut report = Self {
+            target: target.into(),
+            total: outcomes.len(),
+            matched: 0,
+            diffed: 0,
+            failed: 0,
+            status_mismatches: 0,
+            changed_fields: BTreeMap::new(),
+            latency: LatencySummary::default(),
+            outcomes: Vec::new(),
+        };
+
+        let mut original = Vec::new();
+        let mut replayed = Vec::new();
+        let mut delta_sum = 0i64;
+        for outcome in &outcomes {
+            let Some(diff) = &outcome.diff else {
+                report.failed += 1;
+                continue;
+            };
+            if diff.has_diff {
+                report.diffed += 1;
+            } else {
+                report.matched += 1;
+            }
+            if diff.status_diff.is_some() {
+                report.status_mismatches += 1;
+            }
+            for field in changed_fields(diff) {
+                *report.changed_fields.entry(field).or_default() += 1;
+            }
+            if let Some(ms) = outcome.replayed_duration_ms {
+                original.push(outcome.original_duration_ms);
+                replayed.push(ms);
+                delta_sum += outcome.latency_delta_ms().unwrap_or_default();
+            }
+        }
+
+        if !replayed.is_empty() {
+            original.sort_unstable();
+            replayed.sort_unstable();
+            report.latency = LatencySummary {
+                original_p50_ms: percentile(&original, 50.0),
+                replayed_p50_ms: percentile(&replayed, 50.0),
+                original_p99_ms: percentile(&original, 99.0),
+                replayed_p99_ms: percentile(&replayed, 99.0),
+                mean_delta_ms: delta_sum as f64 / replayed.len() as f64,
+            };
+        }
+        report.outcomes = outcomes;
+        report
+    }
+
+    /// Whether every entry replayed and matched its recording.
+    pub fn is_clean(&self) -> bool {
+        self.matched == self.total
+    }
+}
+
+/// Report keys of the fields that differ, each listed once.
+fn changed_fields(diff: &DiffResult) -> Vec<String> {
+    let mut fields = Vec::new();
+    if diff.status_diff.is_some() {
+        fields.push("status".to_string());
+    }
+    let body_diffs = diff.body_diff.iter().flat_map(|body| &body.field_diffs);
+    for field_diff in diff.header_diffs.iter().chain(body_diffs) {
+        fields.push(match &field_diff.field {
+            DiffField::Status => "status".to_string(),
+            DiffField::Header(name) => format!("header:{}", name.to_ascii_lowercase()),
+            DiffField::BodyField(path) => format!("body:{path}"),
+            DiffField::BodyRaw => "body".to_string(),
+        });
+    }
+    if diff
+        .body_diff
+        .as_ref()
+        .is_some_and(|body| body.raw_diff_summary.is_some())
+    {
+        fields.push("body".to_string());
+    }
+    fields.sort();
+    fields.dedup();
+    fields
+}
+
+/// Nearest-rank percentile of sorted samples.
+fn percentile(sorted: &[u64], percentile: f64) -> u64 {
+    let rank = (percentile / 100.0 * sorted.len() as f64).ceil() as usize;
+    sorted[rank.clamp(1, sorted.len()) - 1]
+}
+
+impl ReplayClient {
+    /// Replay `entries` against `target_base_url` concurrently and aggregate
+    /// the differences into a [`BatchReplayReport`].
+    ///
+    /// Failed replays are reported per entry rather than aborting the batch.
+    pub async fn replay_batch(
+        &self,
+        entries: &[ReplayEntry],
+        target_base_url: &str,
+        options: &BatchReplayOptions,
+    ) -> BatchReplayReport {
+        let outcomes = stream::iter(entries)
+            .map(|entry| self.replay_outcome(entry, target_base_url, options))
+            .buffered(options.concurrency.max(1))
+            .collect()
+            .await;
+
+        BatchReplayReport::from_outcomes(target_base_url, outcomes)
+    }
+
+    async fn replay_outcome(
+        &self,
+        entry: &ReplayEntry,
+        target_base_url: &str,
+        options: &BatchReplayOptions,
+    ) -> ReplayOutcome {
+        let mut outcome = ReplayOutcome {
+            id: entry.id.clone(),
+            method: entry.request.method.clone(),
+            path: entry.request.path.clone(),
+            request_id: entry.meta.request_id.clone(),
+            original_trace_id: entry.meta.trace_id.clone(),
+            replay_trace_id: None,
+            original_status: entry.response.status,
+            replayed_status: None,
+            original_duration_ms: entry.meta.duration_ms,
+            replayed_duration_ms: None,
+            diff: None,
+            error: None,
+        };
+
+        let started = Instant::now();
+        match self
+            .replay_traced(entry, target_base_url, options.max_response_body)
+            .await
+        {
+            Ok(replayed) => {
+                outcome.replayed_duration_ms = Some(started.elapsed().as_millis() as u64);
+                outcome.replayed_status = Some(replayed.response.status);
+                outcome.replay_trace_id = Some(replayed.trace_id);
+                outcome.diff = Some(compute_diff(
+                    &entry.response,
+                    &replayed.response,
+                    &options.ignore_headers,
+                ));
+            }
+            Err(e) => outcome.error = Some(e.to_string()),
+        }
+        outcome
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rustapi_core::replay::{
+        BodyDiff, FieldDiff, RecordedRequest, RecordedResponse, ReplayMeta,
+    };
+
+    fn outcome(original_ms: u64, replayed_ms: u64, diff: DiffResult) -> ReplayOutcome {
+        ReplayOutcome {
+            id: "id".to_string(),
+            method: "GET".to_string(),
+            path: "/users".to_string(),
+            request_id: None,
+            original_trace_id: None,
+            replay_trace_id: Some("4bf92f3577b34da6a3ce929d0e0e4736".to_string()),
+            original_status: 200,
+            replayed_status: Some(diff.status_diff.map_or(200, |(_, to)| to)),
+            original_duration_ms: original_ms,
+            replayed_duration_ms: Some(replayed_ms),
+            diff: Some(diff),
+            error: None,
+        }
+    }
+
+    fn no_diff() -> DiffResult {
+        DiffResult {
+            has_diff: false,
+            status_diff: None,
+            header_diffs: Vec::new(),
+            body_diff: None,
+        }
+    }
+
+    #[test]
+    fn report_aggregates_changed_fields_and_latency() {
+        let body_change = DiffResult {
+            has_diff: true,
+            status_diff: Some((200, 500)),
+            header_diffs: vec![FieldDiff {
+                field: DiffField::Header("Content-Type".to_string()),
+                original: Some("application/json".to_string()),
+                replayed: Some("text/plain".to_string()),
+            }],
+            body_diff: Some(BodyDiff {
+                field_diffs: vec![FieldDiff {
+                    field: DiffField::BodyField("user.name".to_string()),
+                    original: Some("\"a\"".to_string()),
+                    replayed: None,
+                }],
+                raw_diff_summary: None,
+            }),
+        };
+        let mut failed = outcome(5, 0, no_diff());
+        failed.diff = None;
+        failed.replayed_duration_ms = None;
+        failed.replay_trace_id = None;
+        failed.error = Some("connection refused".to_string());
+
+        let report = BatchReplayReport::from_outcomes(
+            "http://staging",
+            vec![
+                outcome(10, 12, no_diff()),
+                outcome(20, 40, body_change.clone()),
+                outcome(30, 36, body_change),
+                failed,
+            ],
+        );
+
+        assert_eq!(report.total, 4);
+        assert_eq!((report.matched, report.diffed, report.failed), (1, 2, 1));
+        assert_eq!(report.status_mismatches, 2);
+        assert_eq!(report.changed_fields["status"], 2);
+        assert_eq!(report.changed_fields["header:content-type"], 2);
+        assert_eq!(report.changed_fields["body:user.name"], 2);
+        assert_eq!(report.latency.original_p50_ms, 20);
+        assert_eq!(report.latency.replayed_p50_ms, 36);
+        assert_eq!(report.latency.mean_delta_ms, 28.0 / 3.0);
+        assert!(!report.is_clean());
+    }
+
+    #[tokio::test]
+    async fn unreachable_target_is_reported_per_entry() {
+        let entry = ReplayEntry::new(
+            RecordedRequest::new("GET", "/users", "/users"),
+            RecordedResponse::new(200),
+            ReplayMeta::new(),
+        );
+
+        let report = ReplayClient::new()
+            .replay_batch(
+                &[entry.clone(), entry],
+                "http://127.0.0.1:1",
+                &BatchReplayOptions::new().concurrency(2),
+            )
+            .await;
+
+        assert_eq!(report.total, 2);
+        assert_eq!(report.failed, 2);
+        assert!(report.outcomes.iter().all(|o| o.error.is_some()));
+        assert_eq!(report.latency, LatencySummary::default());
+    }
+}
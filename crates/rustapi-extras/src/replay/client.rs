@@ -37,10 +37,23 @@ impl From<reqwest::Error> for ReplayClientError {
     }
 }
 
+/// A replayed response and the trace it was sent under.
+#[derive(Debug, Clone)]
+pub struct TracedReplay {
+    /// The target's response.
+    pub response: RecordedResponse,
+    /// Trace ID sent in the replayed request's `traceparent` header.
+    pub trace_id: String,
+}
+
 /// HTTP client for replaying recorded requests against a target server.
 ///
 /// Takes a [`ReplayEntry`] and sends the recorded request to a target URL,
 /// capturing the response as a [`RecordedResponse`].
+///
+/// Every replay starts a fresh W3C trace: the recorded `traceparent` and
+/// `tracestate` headers are replaced, and `x-replay-of` carries the entry ID,
+/// so the target's logs and traces can be matched to the recording.
 pub struct ReplayClient {
     http: reqwest::Client,
 }
@@ -81,6 +94,19 @@ impl ReplayClient {
         target_base_url: &str,
         max_response_body: Option<usize>,
     ) -> Result<RecordedResponse, ReplayClientError> {
+        self.replay_traced(entry, target_base_url, max_response_body)
+            .await
+            .map(|replay| replay.response)
+    }
+
+    /// Replay a request and return the response together with the trace ID
+    /// the replay was sent under.
+    pub async fn replay_traced(
+        &self,
+        entry: &ReplayEntry,
+        target_base_url: &str,
+        max_response_body: Option<usize>,
+    ) -> Result<TracedReplay, ReplayClientError> {
         let url = replay_url(target_base_url, &entry.request.uri)?;
         let method: reqwest::Method = entry.request.method.parse().map_err(|_| {
             ReplayClientError::InvalidUrl(format!("Invalid method: {}", entry.request.method))
@@ -88,15 +114,24 @@ impl ReplayClient {
 
         let mut builder = self.http.request(method, &url);
 
-        // Add recorded headers (skip host, content-length as reqwest manages these)
+        // Add recorded headers (skip host, content-length as reqwest manages these,
+        // and the trace context, which is replaced by a fresh trace)
         for (key, value) in &entry.request.headers {
             let key_lower = key.to_lowercase();
-            if key_lower == "host" || key_lower == "content-length" {
+            if matches!(
+                key_lower.as_str(),
+                "host" | "content-length" | "traceparent" | "tracestate" | "x-replay-of"
+            ) {
                 continue;
             }
             builder = builder.header(key, value);
         }
 
+        let trace_id = new_trace_id();
+        builder = builder
+            .header("traceparent", traceparent(&trace_id))
+            .header("x-replay-of", &entry.id);
+
         // Add recorded body
         if let Some(ref body) = entry.request.body {
             builder = builder.body(body.clone());
@@ -116,16 +151,30 @@ impl ReplayClient {
         let (body, body_size, body_truncated) =
             response_body_from_bytes(&body_bytes, max_response_body);
 
-        Ok(RecordedResponse {
-            status,
-            headers,
-            body,
-            body_size,
-            body_truncated,
+        Ok(TracedReplay {
+            response: RecordedResponse {
+                status,
+                headers,
+                body,
+                body_size,
+                body_truncated,
+            },
+            trace_id,
         })
     }
 }
 
+/// Random 128-bit trace ID, hex encoded.
+fn new_trace_id() -> String {
+    uuid::Uuid::new_v4().simple().to_string()
+}
+
+/// Sampled `traceparent` header for `trace_id` with a random span ID.
+fn traceparent(trace_id: &str) -> String {
+    let span_id = uuid::Uuid::new_v4().simple().to_string();
+    format!("00-{trace_id}-{}-01", &span_id[..16])
+}
+
 fn replay_url(target_base_url: &str, recorded_uri: &str) -> Result<String, ReplayClientError> {
     let trimmed = target_base_url.trim();
     let parsed = reqwest::Url::parse(trimmed)
@@ -255,4 +304,15 @@ mod tests {
         assert_eq!(size, 6);
         assert!(!truncated);
     }
+
+    #[test]
+    fn replays_start_a_fresh_sampled_trace() {
+        let trace_id = new_trace_id();
+        let header = traceparent(&trace_id);
+
+        let (parsed_trace, _) = crate::replay::layer::parse_traceparent(&header).unwrap();
+        assert_eq!(parsed_trace, trace_id);
+        assert!(header.starts_with("00-") && header.ends_with("-01"));
+        assert_ne!(new_trace_id(), trace_id);
+    }
 }
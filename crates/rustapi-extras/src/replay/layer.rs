@@ -13,7 +13,7 @@ use rustapi_core::replay::{
     redact_body, redact_headers, truncate_body, RecordedRequest, RecordedResponse, ReplayConfig,
     ReplayEntry, ReplayMeta, ReplayStore,
 };
-use rustapi_core::{Request, Response, ResponseBody};
+use rustapi_core::{Clock, Request, RequestId, Response, ResponseBody};
 use std::collections::HashMap;
 use std::future::Future;
 use std::net::IpAddr;
@@ -30,6 +30,8 @@ use std::time::{Duration, Instant};
 /// # Security
 ///
 /// - Recording disabled by default (`enabled: false`)
+/// - Never records when the current `Profile` turns replay recording off
+///   (production by default)
 /// - Admin token required for all replay endpoints
 /// - Sensitive headers redacted automatically
 /// - Configurable body field redaction
@@ -57,6 +59,7 @@ pub struct ReplayLayer {
     config: Arc<ReplayConfig>,
     store: Arc<dyn ReplayStore>,
     retention_started: Arc<AtomicBool>,
+    clock: Clock,
 }
 
 impl ReplayLayer {
@@ -69,6 +72,7 @@ impl ReplayLayer {
             config: Arc::new(config),
             store: Arc::new(store),
             retention_started: Arc::new(AtomicBool::new(false)),
+            clock: Clock::system(),
         }
     }
 
@@ -78,6 +82,13 @@ impl ReplayLayer {
         self
     }
 
+    /// Use `clock` for retention and for timestamps of requests that do not
+    /// carry a clock of their own.
+    pub fn clock(mut self, clock: Clock) -> Self {
+        self.clock = clock;
+        self
+    }
+
     /// Get a reference to the replay store.
     pub fn store(&self) -> &Arc<dyn ReplayStore> {
         &self.store
@@ -113,8 +124,11 @@ impl ReplayLayer {
         "127.0.0.1".to_string()
     }
 
-    /// Extract request ID from headers.
+    /// Extract request ID from `RequestIdLayer` or the request headers.
     fn extract_request_id(req: &Request) -> Option<String> {
+        if let Some(id) = req.extensions().get::<RequestId>() {
+            return Some(id.to_string());
+        }
         for header_name in &["x-request-id", "x-correlation-id", "x-trace-id"] {
             if let Some(value) = req.headers().get(*header_name) {
                 if let Ok(id) = value.to_str() {
@@ -125,6 +139,20 @@ impl ReplayLayer {
         None
     }
 
+    /// Extract the trace and span IDs the request is handled under.
+    ///
+    /// Prefers the context `OtelLayer` stored for the request, falling back
+    /// to an incoming W3C `traceparent` header.
+    fn extract_trace(req: &Request) -> Option<(String, String)> {
+        #[cfg(feature = "otel")]
+        if let Some(context) = req.extensions().get::<crate::otel::TraceContext>() {
+            return Some((context.trace_id.clone(), context.span_id.clone()));
+        }
+
+        let value = req.headers().get("traceparent")?.to_str().ok()?;
+        parse_traceparent(value)
+    }
+
     /// Capture all request headers into a HashMap.
     fn capture_headers(headers: &http::HeaderMap) -> HashMap<String, String> {
         let mut captured = HashMap::new();
@@ -152,7 +180,7 @@ impl ReplayLayer {
             let store = self.store.clone();
             let ttl_secs = self.config.ttl_secs;
             let interval = Duration::from_secs(ttl_secs.max(60) / 2);
-            RetentionJob::spawn(store, ttl_secs, interval);
+            RetentionJob::spawn_with_clock(store, ttl_secs, interval, self.clock.clone());
         }
     }
 }
@@ -165,6 +193,11 @@ impl MiddlewareLayer for ReplayLayer {
     ) -> Pin<Box<dyn Future<Output = Response> + Send + 'static>> {
         let config = self.config.clone();
         let store = self.store.clone();
+        let clock = req
+            .extensions()
+            .get::<Clock>()
+            .cloned()
+            .unwrap_or_else(|| self.clock.clone());
 
         // Start retention job on first request
         self.ensure_retention_started();
@@ -190,18 +223,18 @@ impl MiddlewareLayer for ReplayLayer {
                 }
             }
 
-            // If recording is disabled, pass through
-            if !config.enabled {
+            // If recording is disabled, or the profile forbids it, pass through
+            if !config.enabled || !rustapi_core::Profile::current().replay_recording {
                 return next(req).await;
             }
 
-            // Check path filter
-            if !config.should_record_path(&path) {
+            // Check path and method filters
+            if !config.should_record_path(&path) || !config.should_record_method(&method) {
                 return next(req).await;
             }
 
             // Check sampling
-            if !config.should_sample() {
+            if !config.should_sample_path(&path) {
                 return next(req).await;
             }
 
@@ -213,6 +246,7 @@ impl MiddlewareLayer for ReplayLayer {
             let query = req.uri().query().map(|q| q.to_string());
             let client_ip = ReplayLayer::extract_client_ip(&req);
             let request_id = ReplayLayer::extract_request_id(&req);
+            let trace = ReplayLayer::extract_trace(&req);
 
             // Capture and redact request headers
             let raw_headers = ReplayLayer::capture_headers(req.headers());
@@ -264,6 +298,11 @@ impl MiddlewareLayer for ReplayLayer {
             let duration = start.elapsed();
             let status = response.status().as_u16();
 
+            // Check status filter before buffering the response body
+            if !config.should_record_status(status) {
+                return response;
+            }
+
             // Capture and redact response headers
             let raw_resp_headers = ReplayLayer::capture_headers(response.headers());
             let resp_headers = redact_headers(&raw_resp_headers, &config.redact_headers);
@@ -331,9 +370,13 @@ impl MiddlewareLayer for ReplayLayer {
             if let Some(req_id) = request_id {
                 meta = meta.with_request_id(req_id);
             }
+            if let Some((trace_id, span_id)) = trace {
+                meta = meta.with_trace_id(trace_id).with_span_id(span_id);
+            }
 
             // Create and store the entry
-            let entry = ReplayEntry::new(recorded_request, recorded_response, meta);
+            let mut entry = ReplayEntry::new(recorded_request, recorded_response, meta);
+            entry.recorded_at = clock.unix().as_millis() as u64;
 
             // Store asynchronously (fire and forget, don't block the response)
             let store_clone = store.clone();
@@ -353,6 +396,22 @@ impl MiddlewareLayer for ReplayLayer {
     }
 }
 
+/// Trace and span IDs of a `traceparent` header
+/// (`{version}-{trace_id}-{span_id}-{flags}`), rejecting all-zero IDs.
+pub(crate) fn parse_traceparent(value: &str) -> Option<(String, String)> {
+    let mut parts = value.trim().split('-');
+    let (_version, trace_id, span_id) = (parts.next()?, parts.next()?, parts.next()?);
+    parts.next()?;
+
+    let valid = |id: &str, len: usize| {
+        id.len() == len
+            && id.bytes().all(|b| b.is_ascii_hexdigit())
+            && id.bytes().any(|b| b != b'0')
+    };
+    (valid(trace_id, 32) && valid(span_id, 16))
+        .then(|| (trace_id.to_ascii_lowercase(), span_id.to_ascii_lowercase()))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -386,4 +445,112 @@ mod tests {
         let layer = ReplayLayer::new(config).with_store(store);
         assert!(layer.config().enabled);
     }
+
+    #[tokio::test]
+    async fn test_status_and_method_filters() {
+        use rustapi_core::replay::ReplayQuery;
+        use rustapi_core::{get, post, ApiError, RustApi};
+        use rustapi_testing::{TestClient, TestRequest};
+
+        async fn ok() -> &'static str {
+            "ok"
+        }
+        async fn fail() -> Result<&'static str, ApiError> {
+            Err(ApiError::internal("boom"))
+        }
+
+        let store = InMemoryReplayStore::new(10);
+        let config = ReplayConfig::new()
+            .enabled(true)
+            .record_method("GET")
+            .record_status(500..=599);
+        let app = RustApi::new()
+            .layer(ReplayLayer::new(config).with_store(store.clone()))
+            .route("/ok", get(ok))
+            .route("/fail", get(fail))
+            .route("/fail-post", post(fail));
+        let client = TestClient::new(app);
+
+        client.request(TestRequest::get("/ok")).await;
+        client.request(TestRequest::get("/fail")).await;
+        client.request(TestRequest::post("/fail-post")).await;
+
+        // Entries are stored in the background
+        for _ in 0..50 {
+            if store.count().await.unwrap() > 0 {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        tokio::time::sleep(Duration::from_millis(20)).await;
+
+        let entries = store.list(&ReplayQuery::new()).await.unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].request.method, "GET");
+        assert_eq!(entries[0].request.path, "/fail");
+        assert_eq!(entries[0].response.status, 500);
+    }
+
+    #[test]
+    fn test_parse_traceparent() {
+        assert_eq!(
+            parse_traceparent("00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01"),
+            Some((
+                "4bf92f3577b34da6a3ce929d0e0e4736".to_string(),
+                "00f067aa0ba902b7".to_string()
+            ))
+        );
+        for invalid in [
+            "",
+            "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7",
+            "00-00000000000000000000000000000000-00f067aa0ba902b7-01",
+            "00-4bf92f3577b34da6a3ce929d0e0e4736-zzf067aa0ba902b7-01",
+        ] {
+            assert_eq!(parse_traceparent(invalid), None);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_records_request_and_trace_ids() {
+        use rustapi_core::replay::ReplayQuery;
+        use rustapi_core::{get, RustApi};
+        use rustapi_testing::{TestClient, TestRequest};
+
+        async fn ok() -> &'static str {
+            "ok"
+        }
+
+        let store = InMemoryReplayStore::new(10);
+        let app = RustApi::new()
+            .layer(ReplayLayer::new(ReplayConfig::new().enabled(true)).with_store(store.clone()))
+            .route("/ok", get(ok));
+        let client = TestClient::new(app);
+
+        client
+            .request(
+                TestRequest::get("/ok")
+                    .header("x-request-id", "req-42")
+                    .header(
+                        "traceparent",
+                        "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01",
+                    ),
+            )
+            .await;
+
+        for _ in 0..50 {
+            if store.count().await.unwrap() > 0 {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+
+        let entries = store.list(&ReplayQuery::new()).await.unwrap();
+        let meta = &entries[0].meta;
+        assert_eq!(meta.request_id.as_deref(), Some("req-42"));
+        assert_eq!(
+            meta.trace_id.as_deref(),
+            Some("4bf92f3577b34da6a3ce929d0e0e4736")
+        );
+        assert_eq!(meta.span_id.as_deref(), Some("00f067aa0ba902b7"));
+    }
 }
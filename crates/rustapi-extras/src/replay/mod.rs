@@ -5,7 +5,10 @@
 //! - [`ReplayLayer`] - Middleware that records request/response pairs
 //! - [`InMemoryReplayStore`] - In-memory bounded ring buffer store
 //! - [`FsReplayStore`] - Filesystem-backed store (JSON Lines)
-//! - [`ReplayClient`] - HTTP client for replaying recorded requests
+//! - `RedisReplayStore` - Redis-backed store shared across replicas (`replay-redis` feature)
+//! - `SqlxReplayStore` - PostgreSQL-backed store (`replay-postgres` feature)
+//! - [`ReplayClient`] - HTTP client for replaying recorded requests, one at a
+//!   time or as a batch with a [`BatchReplayReport`]
 //! - [`RetentionJob`] - Background TTL cleanup task
 //! - [`ReplayAdminAuth`] - Bearer token authentication for admin endpoints
 //!
@@ -29,16 +32,26 @@
 //! ```
 
 mod auth;
+mod batch;
 mod client;
 mod fs_store;
 mod layer;
 mod memory_store;
+#[cfg(feature = "replay-redis")]
+mod redis_store;
 mod retention;
 mod routes;
+#[cfg(feature = "replay-postgres")]
+mod sqlx_store;
 
 pub use auth::ReplayAdminAuth;
-pub use client::{ReplayClient, ReplayClientError};
+pub use batch::{BatchReplayOptions, BatchReplayReport, LatencySummary, ReplayOutcome};
+pub use client::{ReplayClient, ReplayClientError, TracedReplay};
 pub use fs_store::{FsReplayStore, FsReplayStoreConfig};
 pub use layer::ReplayLayer;
 pub use memory_store::InMemoryReplayStore;
+#[cfg(feature = "replay-redis")]
+pub use redis_store::RedisReplayStore;
 pub use retention::RetentionJob;
+#[cfg(feature = "replay-postgres")]
+pub use sqlx_store::SqlxReplayStore;
@@ -0,0 +1,259 @@
+//! Redis-backed replay store.
+//!
+//! Entries are kept as JSON in a hash keyed by entry ID, with a sorted set
+//! scored by `recorded_at` as the time index. Recordings survive restarts and
+//! are shared by every replica pointing at the same Redis instance.
+
+use async_trait::async_trait;
+use redis::aio::MultiplexedConnection;
+use redis::AsyncCommands;
+use rustapi_core::replay::{
+    ReplayEntry, ReplayQuery, ReplayStore, ReplayStoreError, ReplayStoreResult,
+};
+
+/// Number of IDs fetched from the time index per round trip while listing.
+const PAGE_SIZE: usize = 200;
+
+/// Redis-backed replay store.
+///
+/// # Example
+///
+/// ```ignore
+/// use rustapi_extras::replay::{RedisReplayStore, ReplayLayer};
+///
+/// let store = RedisReplayStore::from_url("redis://127.0.0.1/")?.key_prefix("myapp:replay:");
+/// let layer = ReplayLayer::new(config).with_store(store);
+/// ```
+#[derive(Clone)]
+pub struct RedisReplayStore {
+    client: redis::Client,
+    key_prefix: String,
+}
+
+impl std::fmt::Debug for RedisReplayStore {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RedisReplayStore")
+            .field("key_prefix", &self.key_prefix)
+            .finish_non_exhaustive()
+    }
+}
+
+impl RedisReplayStore {
+    /// Create a Redis replay store from an existing client.
+    pub fn new(client: redis::Client) -> Self {
+        Self {
+            client,
+            key_prefix: "rustapi:replay:".to_string(),
+        }
+    }
+
+    /// Create a Redis replay store from a connection URL.
+    pub fn from_url(url: &str) -> ReplayStoreResult<Self> {
+        let client =
+            redis::Client::open(url).map_err(|e| ReplayStoreError::Other(e.to_string()))?;
+        Ok(Self::new(client))
+    }
+
+    /// Override the prefix of the keys holding the entries and the time index.
+    pub fn key_prefix(mut self, value: impl Into<String>) -> Self {
+        self.key_prefix = value.into();
+        self
+    }
+
+    fn entries_key(&self) -> String {
+        format!("{}entries", self.key_prefix)
+    }
+
+    fn index_key(&self) -> String {
+        format!("{}index", self.key_prefix)
+    }
+
+    async fn connection(&self) -> ReplayStoreResult<MultiplexedConnection> {
+        self.client
+            .get_multiplexed_async_connection()
+            .await
+            .map_err(io_error)
+    }
+
+    /// Remove `ids` from both the entries hash and the time index.
+    async fn remove(
+        &self,
+        conn: &mut MultiplexedConnection,
+        ids: &[String],
+    ) -> ReplayStoreResult<usize> {
+        if ids.is_empty() {
+            return Ok(0);
+        }
+        let (removed, _): (usize, usize) = redis::pipe()
+            .atomic()
+            .hdel(self.entries_key(), ids)
+            .zrem(self.index_key(), ids)
+            .query_async(conn)
+            .await
+            .map_err(io_error)?;
+        Ok(removed)
+    }
+}
+
+#[async_trait]
+impl ReplayStore for RedisReplayStore {
+    async fn store(&self, entry: ReplayEntry) -> ReplayStoreResult<()> {
+        let payload = serde_json::to_string(&entry)
+            .map_err(|e| ReplayStoreError::Serialization(e.to_string()))?;
+        let mut conn = self.connection().await?;
+
+        redis::pipe()
+            .atomic()
+            .hset(self.entries_key(), &entry.id, payload)
+            .ignore()
+            .zadd(self.index_key(), &entry.id, entry.recorded_at)
+            .ignore()
+            .query_async::<()>(&mut conn)
+            .await
+            .map_err(io_error)
+    }
+
+    async fn get(&self, id: &str) -> ReplayStoreResult<Option<ReplayEntry>> {
+        let mut conn = self.connection().await?;
+        let payload: Option<String> = conn.hget(self.entries_key(), id).await.map_err(io_error)?;
+        payload.as_deref().map(deserialize).transpose()
+    }
+
+    async fn list(&self, query: &ReplayQuery) -> ReplayStoreResult<Vec<ReplayEntry>> {
+        let mut conn = self.connection().await?;
+        let min = score_bound(query.from_timestamp, "-inf");
+        let max = score_bound(query.to_timestamp, "+inf");
+        let limit = query.limit.unwrap_or(usize::MAX);
+        let mut skip = query.offset.unwrap_or(0);
+        let mut results = Vec::new();
+        let mut page_start = 0;
+
+        // Walk the time index page by page; the remaining filters are applied
+        // to the entries themselves, exactly like the in-memory store does.
+        while results.len() < limit {
+            let ids: Vec<String> = if query.newest_first {
+                conn.zrevrangebyscore_limit(
+                    self.index_key(),
+                    &max,
+                    &min,
+                    page_start,
+                    PAGE_SIZE as isize,
+                )
+                .await
+            } else {
+                conn.zrangebyscore_limit(
+                    self.index_key(),
+                    &min,
+                    &max,
+                    page_start,
+                    PAGE_SIZE as isize,
+                )
+                .await
+            }
+            .map_err(io_error)?;
+            if ids.is_empty() {
+                break;
+            }
+            page_start += ids.len() as isize;
+
+            let payloads: Vec<Option<String>> = conn
+                .hmget(self.entries_key(), &ids)
+                .await
+                .map_err(io_error)?;
+            for payload in payloads.into_iter().flatten() {
+                let entry = deserialize(&payload)?;
+                if !query.matches(&entry) {
+                    continue;
+                }
+                if skip > 0 {
+                    skip -= 1;
+                    continue;
+                }
+                results.push(entry);
+                if results.len() == limit {
+                    break;
+                }
+            }
+
+            if ids.len() < PAGE_SIZE {
+                break;
+            }
+        }
+
+        Ok(results)
+    }
+
+    async fn delete(&self, id: &str) -> ReplayStoreResult<bool> {
+        let mut conn = self.connection().await?;
+        Ok(self.remove(&mut conn, &[id.to_string()]).await? > 0)
+    }
+
+    async fn count(&self) -> ReplayStoreResult<usize> {
+        let mut conn = self.connection().await?;
+        conn.hlen(self.entries_key()).await.map_err(io_error)
+    }
+
+    async fn clear(&self) -> ReplayStoreResult<()> {
+        let mut conn = self.connection().await?;
+        conn.del::<_, ()>(&[self.entries_key(), self.index_key()])
+            .await
+            .map_err(io_error)
+    }
+
+    async fn delete_before(&self, timestamp_ms: u64) -> ReplayStoreResult<usize> {
+        let mut conn = self.connection().await?;
+        let ids: Vec<String> = conn
+            .zrangebyscore(self.index_key(), "-inf", format!("({timestamp_ms}"))
+            .await
+            .map_err(io_error)?;
+
+        let mut deleted = 0;
+        for chunk in ids.chunks(PAGE_SIZE) {
+            deleted += self.remove(&mut conn, chunk).await?;
+        }
+        Ok(deleted)
+    }
+
+    fn clone_store(&self) -> Box<dyn ReplayStore> {
+        Box::new(self.clone())
+    }
+}
+
+fn io_error(error: redis::RedisError) -> ReplayStoreError {
+    ReplayStoreError::Io(error.to_string())
+}
+
+fn deserialize(payload: &str) -> ReplayStoreResult<ReplayEntry> {
+    serde_json::from_str(payload).map_err(|e| ReplayStoreError::Serialization(e.to_string()))
+}
+
+/// Inclusive sorted-set score bound, or `open` when unbounded.
+fn score_bound(timestamp_ms: Option<u64>, open: &str) -> String {
+    timestamp_ms.map_or_else(|| open.to_string(), |ts| ts.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keys_use_configurable_prefix() {
+        let store = RedisReplayStore::from_url("redis://127.0.0.1/")
+            .unwrap()
+            .key_prefix("custom:replay:");
+
+        assert_eq!(store.entries_key(), "custom:replay:entries");
+        assert_eq!(store.index_key(), "custom:replay:index");
+    }
+
+    #[test]
+    fn score_bounds_are_inclusive_or_open() {
+        assert_eq!(score_bound(None, "-inf"), "-inf");
+        assert_eq!(score_bound(Some(1500), "+inf"), "1500");
+    }
+
+    #[test]
+    fn invalid_url_is_rejected() {
+        assert!(RedisReplayStore::from_url("not a url").is_err());
+    }
+}
@@ -1,6 +1,7 @@
 //! Background retention cleanup job for replay entries.
 
-use rustapi_core::replay::ReplayStore;
+use rustapi_core::replay::{ReplayStore, ReplayStoreResult};
+use rustapi_core::Clock;
 use std::sync::Arc;
 use std::time::Duration;
 
@@ -19,19 +20,22 @@ impl RetentionJob {
         store: Arc<dyn ReplayStore>,
         ttl_secs: u64,
         check_interval: Duration,
+    ) -> tokio::task::JoinHandle<()> {
+        Self::spawn_with_clock(store, ttl_secs, check_interval, Clock::system())
+    }
+
+    /// Like [`spawn`](Self::spawn), judging entry age by `clock`.
+    pub fn spawn_with_clock(
+        store: Arc<dyn ReplayStore>,
+        ttl_secs: u64,
+        check_interval: Duration,
+        clock: Clock,
     ) -> tokio::task::JoinHandle<()> {
         tokio::spawn(async move {
             loop {
                 tokio::time::sleep(check_interval).await;
 
-                let now_ms = std::time::SystemTime::now()
-                    .duration_since(std::time::UNIX_EPOCH)
-                    .unwrap_or_default()
-                    .as_millis() as u64;
-
-                let cutoff = now_ms.saturating_sub(ttl_secs * 1000);
-
-                match store.delete_before(cutoff).await {
+                match Self::run_once(store.as_ref(), ttl_secs, &clock).await {
                     Ok(count) if count > 0 => {
                         tracing::info!(deleted = count, "Replay retention cleanup");
                     }
@@ -43,4 +47,46 @@ impl RetentionJob {
             }
         })
     }
+
+    /// Delete the entries older than `ttl_secs` once, returning how many
+    /// were deleted.
+    ///
+    /// Tests can call this after advancing a manual clock instead of
+    /// waiting for the background task.
+    pub async fn run_once(
+        store: &dyn ReplayStore,
+        ttl_secs: u64,
+        clock: &Clock,
+    ) -> ReplayStoreResult<usize> {
+        let now_ms = clock.unix().as_millis() as u64;
+        let cutoff = now_ms.saturating_sub(ttl_secs * 1000);
+        store.delete_before(cutoff).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::replay::InMemoryReplayStore;
+    use rustapi_core::replay::{RecordedRequest, RecordedResponse, ReplayEntry, ReplayMeta};
+
+    #[tokio::test]
+    async fn test_run_once_uses_clock() {
+        let clock = Clock::manual();
+        let store = InMemoryReplayStore::new(10);
+        let mut entry = ReplayEntry::new(
+            RecordedRequest::new("GET", "/a", "/a"),
+            RecordedResponse::new(200),
+            ReplayMeta::new(),
+        );
+        entry.recorded_at = clock.unix().as_millis() as u64;
+        store.store(entry).await.unwrap();
+
+        clock.advance(Duration::from_secs(60));
+        assert_eq!(RetentionJob::run_once(&store, 60, &clock).await.unwrap(), 0);
+
+        clock.advance(Duration::from_secs(1));
+        assert_eq!(RetentionJob::run_once(&store, 60, &clock).await.unwrap(), 1);
+        assert_eq!(store.count().await.unwrap(), 0);
+    }
 }
@@ -1,9 +1,10 @@
 //! HTTP admin route handlers for replay endpoints.
 //!
-//! Handles `/__rustapi/replays` admin API routes.
+//! Handles `/__rustapi/replays` admin API routes and, when enabled, the
+//! browser UI at `/__rustapi/replays/ui`.
 
 use super::auth::ReplayAdminAuth;
-use super::client::ReplayClient;
+use super::client::{ReplayClient, TracedReplay};
 use bytes::Bytes;
 use http::StatusCode;
 use http_body_util::Full;
@@ -12,6 +13,18 @@ use rustapi_core::Response;
 use rustapi_core::ResponseBody;
 use serde_json::json;
 
+static ADMIN_UI_HTML: &str = include_str!("ui.html");
+const ADMIN_UI_CSP: &str = concat!(
+    "default-src 'none'; ",
+    "base-uri 'none'; ",
+    "form-action 'none'; ",
+    "frame-ancestors 'none'; ",
+    "object-src 'none'; ",
+    "script-src 'unsafe-inline'; ",
+    "style-src 'unsafe-inline'; ",
+    "connect-src 'self'"
+);
+
 /// Dispatch a replay admin request based on path and method.
 ///
 /// Returns `Some(Response)` if the path matches a replay admin route,
@@ -24,6 +37,16 @@ pub async fn dispatch(
     config: &ReplayConfig,
     path_suffix: &str,
 ) -> Option<Response> {
+    // Trim leading slash
+    let suffix = path_suffix.trim_start_matches('/');
+
+    // The UI page is served without auth: browsers can't easily send Bearer
+    // headers on navigation, and the page carries no data of its own. Every
+    // entry it shows is fetched from the token-protected endpoints below.
+    if config.admin_ui && method == "GET" && suffix == "ui" {
+        return Some(serve_ui());
+    }
+
     // Check admin token
     if let Some(ref token) = config.admin_token {
         if let Err(resp) = ReplayAdminAuth::check(headers, token) {
@@ -37,9 +60,6 @@ pub async fn dispatch(
         ));
     }
 
-    // Trim leading slash
-    let suffix = path_suffix.trim_start_matches('/');
-
     match (method, suffix) {
         // GET /__rustapi/replays - list entries
         ("GET", "") => Some(handle_list(uri, store).await),
@@ -210,15 +230,17 @@ async fn handle_run(
 
     let client = ReplayClient::new();
     match client
-        .replay_with_limit(&entry, &target_url, Some(max_response_body))
+        .replay_traced(&entry, &target_url, Some(max_response_body))
         .await
     {
         Ok(replayed) => json_response(
             StatusCode::OK,
             json!({
                 "original_response": entry.response,
-                "replayed_response": replayed,
+                "replayed_response": replayed.response,
                 "target": target_url,
+                "original_trace_id": entry.meta.trace_id,
+                "replay_trace_id": replayed.trace_id,
             }),
         ),
         Err(e) => json_response(
@@ -257,10 +279,13 @@ async fn handle_diff(
 
     let client = ReplayClient::new();
     match client
-        .replay_with_limit(&entry, &target_url, Some(max_response_body))
+        .replay_traced(&entry, &target_url, Some(max_response_body))
         .await
     {
-        Ok(replayed) => {
+        Ok(TracedReplay {
+            response: replayed,
+            trace_id,
+        }) => {
             let ignore_headers = vec![
                 "date".to_string(),
                 "x-request-id".to_string(),
@@ -276,6 +301,8 @@ async fn handle_diff(
                     "original_response": entry.response,
                     "replayed_response": replayed,
                     "target": target_url,
+                    "original_trace_id": entry.meta.trace_id,
+                    "replay_trace_id": trace_id,
                 }),
             )
         }
@@ -303,6 +330,20 @@ async fn handle_delete(id: &str, store: &dyn ReplayStore) -> Response {
     }
 }
 
+fn serve_ui() -> Response {
+    http::Response::builder()
+        .status(StatusCode::OK)
+        .header(http::header::CONTENT_TYPE, "text/html; charset=utf-8")
+        .header(http::header::CACHE_CONTROL, "no-store")
+        .header(http::header::REFERRER_POLICY, "no-referrer")
+        .header(http::header::CONTENT_SECURITY_POLICY, ADMIN_UI_CSP)
+        .header("x-content-type-options", "nosniff")
+        .body(ResponseBody::Full(Full::new(Bytes::from_static(
+            ADMIN_UI_HTML.as_bytes(),
+        ))))
+        .unwrap()
+}
+
 /// Helper to extract a query parameter value from a URI.
 fn extract_query_param(uri: &http::Uri, key: &str) -> Option<String> {
     let query = uri.query()?;
@@ -356,8 +397,11 @@ fn json_response(status: StatusCode, body: serde_json::Value) -> Response {
 #[cfg(test)]
 mod tests {
     use super::{
-        extract_query_param, invalid_target_response, parse_target_url, replay_query_from_uri,
+        dispatch, extract_query_param, invalid_target_response, parse_target_url,
+        replay_query_from_uri,
     };
+    use crate::replay::InMemoryReplayStore;
+    use rustapi_core::replay::ReplayConfig;
 
     #[test]
     fn replay_query_from_uri_supports_ui_filters() {
@@ -428,4 +472,37 @@ mod tests {
             assert_eq!(response.status(), http::StatusCode::BAD_REQUEST);
         }
     }
+
+    #[tokio::test]
+    async fn admin_ui_is_served_only_when_enabled() {
+        let store = InMemoryReplayStore::new(10);
+        let headers = http::HeaderMap::new();
+        let uri: http::Uri = "/__rustapi/replays/ui".parse().unwrap();
+
+        let config = ReplayConfig::new().admin_token("secret");
+        let response = dispatch(&headers, "GET", &uri, &store, &config, "/ui")
+            .await
+            .unwrap();
+        assert_eq!(response.status(), http::StatusCode::UNAUTHORIZED);
+
+        let config = config.admin_ui(true);
+        let response = dispatch(&headers, "GET", &uri, &store, &config, "/ui")
+            .await
+            .unwrap();
+        assert_eq!(response.status(), http::StatusCode::OK);
+        assert!(response.headers()["content-type"]
+            .to_str()
+            .unwrap()
+            .starts_with("text/html"));
+        assert!(response
+            .headers()
+            .contains_key(http::header::CONTENT_SECURITY_POLICY));
+
+        // The data endpoints the page calls still require the token
+        let uri: http::Uri = "/__rustapi/replays".parse().unwrap();
+        let response = dispatch(&headers, "GET", &uri, &store, &config, "")
+            .await
+            .unwrap();
+        assert_eq!(response.status(), http::StatusCode::UNAUTHORIZED);
+    }
 }
@@ -0,0 +1,270 @@
+//! PostgreSQL-backed replay store using SQLx.
+//!
+//! Every entry is a row holding the full entry as JSONB next to the columns
+//! queries filter on, so [`ReplayQuery`] filters, ordering and pagination run
+//! in the database. Recordings survive restarts and are shared by replicas.
+
+use async_trait::async_trait;
+use rustapi_core::replay::{
+    ReplayEntry, ReplayQuery, ReplayStore, ReplayStoreError, ReplayStoreResult,
+};
+use sqlx::{Pool, Postgres, QueryBuilder, Row};
+
+/// PostgreSQL-backed replay store.
+///
+/// Call [`ensure_schema`](Self::ensure_schema) once at startup to create the
+/// table, or create it with a migration.
+///
+/// # Example
+///
+/// ```ignore
+/// use rustapi_extras::replay::{ReplayLayer, SqlxReplayStore};
+///
+/// let store = SqlxReplayStore::new(pool).table_name("replay_entries");
+/// store.ensure_schema().await?;
+/// let layer = ReplayLayer::new(config).with_store(store);
+/// ```
+#[derive(Debug, Clone)]
+pub struct SqlxReplayStore {
+    pool: Pool<Postgres>,
+    table_name: String,
+}
+
+impl SqlxReplayStore {
+    /// Create a store writing to the `rustapi_replays` table.
+    pub fn new(pool: Pool<Postgres>) -> Self {
+        Self {
+            pool,
+            table_name: "rustapi_replays".to_string(),
+        }
+    }
+
+    /// Override the table name.
+    pub fn table_name(mut self, name: impl Into<String>) -> Self {
+        self.table_name = name.into();
+        self
+    }
+
+    /// Create the table and its time index if they do not exist.
+    pub async fn ensure_schema(&self) -> ReplayStoreResult<()> {
+        let table = &self.table_name;
+        let statements = [
+            format!(
+                "CREATE TABLE IF NOT EXISTS {table} (
+                    seq BIGSERIAL,
+                    id TEXT PRIMARY KEY,
+                    recorded_at BIGINT NOT NULL,
+                    method TEXT NOT NULL,
+                    path TEXT NOT NULL,
+                    status INT NOT NULL,
+                    entry JSONB NOT NULL
+                )"
+            ),
+            format!(
+                "CREATE INDEX IF NOT EXISTS idx_{table}_recorded_at ON {table} (recorded_at, seq)"
+            ),
+        ];
+
+        for statement in statements {
+            sqlx::query(&statement)
+                .execute(&self.pool)
+                .await
+                .map_err(db_error)?;
+        }
+        Ok(())
+    }
+
+    /// `SELECT` for the entries matching `query`, newest or oldest first.
+    fn select(&self, query: &ReplayQuery) -> QueryBuilder<'static, Postgres> {
+        let mut sql = QueryBuilder::new(format!(
+            "SELECT entry::text AS entry FROM {} WHERE TRUE",
+            self.table_name
+        ));
+
+        if let Some(method) = &query.method {
+            sql.push(" AND method = ").push_bind(method.clone());
+        }
+        if let Some(path) = &query.path_contains {
+            sql.push(" AND strpos(path, ")
+                .push_bind(path.clone())
+                .push(") > 0");
+        }
+        if let Some(min) = query.status_min {
+            sql.push(" AND status >= ").push_bind(i32::from(min));
+        }
+        if let Some(max) = query.status_max {
+            sql.push(" AND status <= ").push_bind(i32::from(max));
+        }
+        if let Some(from) = query.from_timestamp {
+            sql.push(" AND recorded_at >= ").push_bind(to_i64(from));
+        }
+        if let Some(to) = query.to_timestamp {
+            sql.push(" AND recorded_at <= ").push_bind(to_i64(to));
+        }
+        if let Some((key, value)) = &query.tag {
+            sql.push(" AND entry->'meta'->'tags'->>")
+                .push_bind(key.clone())
+                .push(" = ")
+                .push_bind(value.clone());
+        }
+
+        let order = if query.newest_first { "DESC" } else { "ASC" };
+        sql.push(format!(" ORDER BY recorded_at {order}, seq {order}"));
+        if let Some(limit) = query.limit {
+            sql.push(" LIMIT ").push_bind(to_i64(limit as u64));
+        }
+        if let Some(offset) = query.offset {
+            sql.push(" OFFSET ").push_bind(to_i64(offset as u64));
+        }
+        sql
+    }
+}
+
+#[async_trait]
+impl ReplayStore for SqlxReplayStore {
+    async fn store(&self, entry: ReplayEntry) -> ReplayStoreResult<()> {
+        let payload = serde_json::to_string(&entry)
+            .map_err(|e| ReplayStoreError::Serialization(e.to_string()))?;
+        let sql = format!(
+            "INSERT INTO {} (id, recorded_at, method, path, status, entry)
+             VALUES ($1, $2, $3, $4, $5, $6::jsonb)
+             ON CONFLICT (id) DO UPDATE SET entry = EXCLUDED.entry",
+            self.table_name
+        );
+
+        sqlx::query(&sql)
+            .bind(&entry.id)
+            .bind(to_i64(entry.recorded_at))
+            .bind(&entry.request.method)
+            .bind(&entry.request.path)
+            .bind(i32::from(entry.response.status))
+            .bind(payload)
+            .execute(&self.pool)
+            .await
+            .map_err(db_error)?;
+        Ok(())
+    }
+
+    async fn get(&self, id: &str) -> ReplayStoreResult<Option<ReplayEntry>> {
+        let sql = format!(
+            "SELECT entry::text AS entry FROM {} WHERE id = $1",
+            self.table_name
+        );
+        let row = sqlx::query(&sql)
+            .bind(id)
+            .fetch_optional(&self.pool)
+            .await
+            .map_err(db_error)?;
+
+        row.map(|row| deserialize(row.get("entry"))).transpose()
+    }
+
+    async fn list(&self, query: &ReplayQuery) -> ReplayStoreResult<Vec<ReplayEntry>> {
+        let rows = self
+            .select(query)
+            .build()
+            .fetch_all(&self.pool)
+            .await
+            .map_err(db_error)?;
+
+        rows.iter()
+            .map(|row| deserialize(row.get("entry")))
+            .collect()
+    }
+
+    async fn delete(&self, id: &str) -> ReplayStoreResult<bool> {
+        let sql = format!("DELETE FROM {} WHERE id = $1", self.table_name);
+        let result = sqlx::query(&sql)
+            .bind(id)
+            .execute(&self.pool)
+            .await
+            .map_err(db_error)?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn count(&self) -> ReplayStoreResult<usize> {
+        let sql = format!("SELECT COUNT(*) FROM {}", self.table_name);
+        let count: i64 = sqlx::query_scalar(&sql)
+            .fetch_one(&self.pool)
+            .await
+            .map_err(db_error)?;
+        Ok(count as usize)
+    }
+
+    async fn clear(&self) -> ReplayStoreResult<()> {
+        let sql = format!("DELETE FROM {}", self.table_name);
+        sqlx::query(&sql)
+            .execute(&self.pool)
+            .await
+            .map_err(db_error)?;
+        Ok(())
+    }
+
+    async fn delete_before(&self, timestamp_ms: u64) -> ReplayStoreResult<usize> {
+        let sql = format!("DELETE FROM {} WHERE recorded_at < $1", self.table_name);
+        let result = sqlx::query(&sql)
+            .bind(to_i64(timestamp_ms))
+            .execute(&self.pool)
+            .await
+            .map_err(db_error)?;
+        Ok(result.rows_affected() as usize)
+    }
+
+    fn clone_store(&self) -> Box<dyn ReplayStore> {
+        Box::new(self.clone())
+    }
+}
+
+fn db_error(error: sqlx::Error) -> ReplayStoreError {
+    ReplayStoreError::Io(error.to_string())
+}
+
+fn deserialize(payload: &str) -> ReplayStoreResult<ReplayEntry> {
+    serde_json::from_str(payload).map_err(|e| ReplayStoreError::Serialization(e.to_string()))
+}
+
+/// `BIGINT` value for an unsigned count or timestamp, saturating at `i64::MAX`.
+fn to_i64(value: u64) -> i64 {
+    i64::try_from(value).unwrap_or(i64::MAX)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sqlx::postgres::PgPoolOptions;
+
+    fn store() -> SqlxReplayStore {
+        let pool = PgPoolOptions::new()
+            .connect_lazy("postgres://localhost/replays")
+            .unwrap();
+        SqlxReplayStore::new(pool).table_name("replays")
+    }
+
+    #[tokio::test]
+    async fn select_pushes_filters_into_sql() {
+        let query = ReplayQuery::new()
+            .method("POST")
+            .path_contains("/users")
+            .status_min(400)
+            .from_timestamp(1000)
+            .tag("tenant", "acme")
+            .limit(10)
+            .offset(20);
+
+        assert_eq!(
+            store().select(&query).sql(),
+            "SELECT entry::text AS entry FROM replays WHERE TRUE AND method = $1 \
+             AND strpos(path, $2) > 0 AND status >= $3 AND recorded_at >= $4 \
+             AND entry->'meta'->'tags'->>$5 = $6 ORDER BY recorded_at DESC, seq DESC \
+             LIMIT $7 OFFSET $8"
+        );
+    }
+
+    #[tokio::test]
+    async fn select_orders_oldest_first_on_request() {
+        let sql = store()
+            .select(&ReplayQuery::new().newest_first(false))
+            .into_sql();
+        assert!(sql.ends_with("ORDER BY recorded_at ASC, seq ASC"));
+    }
+}
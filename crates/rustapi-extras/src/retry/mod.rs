@@ -115,8 +115,13 @@ impl RetryLayer {
         self
     }
 
+    /// The retry configuration
+    pub(crate) fn config(&self) -> &RetryConfig {
+        &self.config
+    }
+
     /// Calculate backoff duration for a given attempt number
-    fn calculate_backoff(&self, attempt: u32) -> Duration {
+    pub(crate) fn calculate_backoff(&self, attempt: u32) -> Duration {
         let base = self.config.initial_backoff;
 
         let calculated = match self.config.strategy {
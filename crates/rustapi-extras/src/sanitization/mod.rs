@@ -2,6 +2,15 @@
 //!
 //! Provides functions to sanitize user input against XSS and injection attacks.
 //! NOTE: This is a basic implementation. For production high-risk apps, use a dedicated crate like `ammonia`.
+//!
+//! The string primitives are shared with the `#[validate(sanitize(...))]` field attribute
+//! of `#[derive(Validate)]`, so `ValidatedJson`/`ValidatedForm` normalize input
+//! exactly like manual calls to this module do.
+
+pub use rustapi_validate::v2::sanitize::{
+    collapse_whitespace, escape_html, lowercase, strip_html, trim, uppercase, Sanitize,
+    SanitizeField,
+};
 
 /// Sanitizes a string by escaping HTML special characters.
 ///
@@ -12,36 +21,12 @@
 /// - `"` -> `&quot;`
 /// - `'` -> `&#x27;`
 pub fn sanitize_html(input: &str) -> String {
-    let mut output = String::with_capacity(input.len());
-    for c in input.chars() {
-        match c {
-            '&' => output.push_str("&amp;"),
-            '<' => output.push_str("&lt;"),
-            '>' => output.push_str("&gt;"),
-            '"' => output.push_str("&quot;"),
-            '\'' => output.push_str("&#x27;"),
-            _ => output.push(c),
-        }
-    }
-    output
+    escape_html(input)
 }
 
 /// Strip all HTML tags from a string.
 pub fn strip_tags(input: &str) -> String {
-    let mut output = String::with_capacity(input.len());
-    let mut inside_tag = false;
-
-    for c in input.chars() {
-        if c == '<' {
-            inside_tag = true;
-        } else if c == '>' {
-            inside_tag = false;
-        } else if !inside_tag {
-            output.push(c);
-        }
-    }
-
-    output
+    strip_html(input)
 }
 
 /// Recursively sanitizes string fields in a JSON value.
@@ -0,0 +1,413 @@
+//! Shadow traffic
+//!
+//! [`ShadowLayer`] copies a sample of incoming requests to a second service,
+//! such as a new version running next to the current one, so it can be tried
+//! with real traffic. Copies are sent in the background after the request is
+//! passed on; their responses are discarded and their failures never reach the
+//! client.
+//!
+//! A copy keeps the method, path, query, headers and body of the original, with
+//! an `x-shadow: 1` header added so the second service can tell copies apart
+//! (e.g. to skip side effects such as emails or payments). Requests whose body
+//! is larger than [`ShadowLayer::max_body_size`], or of unknown size, are not
+//! copied, and neither are requests arriving while
+//! [`ShadowLayer::max_in_flight`] copies are still pending.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! use rustapi_extras::shadow::ShadowLayer;
+//! use std::time::Duration;
+//!
+//! // Send 10% of traffic to the v2 deployment
+//! let shadow = ShadowLayer::new("http://orders-v2.internal")
+//!     .sample_rate(0.1)
+//!     .timeout(Duration::from_secs(2));
+//!
+//! let app = RustApi::new().layer(shadow);
+//! ```
+
+use bytes::Bytes;
+use http::header::{self, HeaderName};
+use http::{HeaderMap, Method};
+use rustapi_core::middleware::{BoxedNext, MiddlewareLayer};
+use rustapi_core::{Request, Response};
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Header added to shadowed requests
+pub const SHADOW_HEADER: &str = "x-shadow";
+
+/// Headers that belong to one connection and are not copied
+const HOP_BY_HOP: [HeaderName; 8] = [
+    header::CONNECTION,
+    header::HOST,
+    header::CONTENT_LENGTH,
+    header::TRANSFER_ENCODING,
+    header::TE,
+    header::TRAILER,
+    header::UPGRADE,
+    header::PROXY_AUTHORIZATION,
+];
+
+/// Snapshot of a [`ShadowLayer`]'s statistics
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ShadowStats {
+    /// Copies sent that got a response, whatever its status
+    pub sent: u64,
+    /// Copies that ended with a transport error or timeout
+    pub failed: u64,
+    /// Sampled requests not copied because of their body or the in-flight limit
+    pub skipped: u64,
+}
+
+#[derive(Default)]
+struct Counters {
+    seen: AtomicU64,
+    sent: AtomicU64,
+    failed: AtomicU64,
+    skipped: AtomicU64,
+    in_flight: AtomicUsize,
+}
+
+/// Middleware that mirrors a sample of requests to a secondary service
+///
+/// Clones share the HTTP client and statistics.
+#[derive(Clone)]
+pub struct ShadowLayer {
+    base_url: String,
+    sample_rate: f64,
+    timeout: Duration,
+    max_body_size: usize,
+    max_in_flight: usize,
+    methods: Option<Vec<Method>>,
+    client: Option<reqwest::Client>,
+    counters: Arc<Counters>,
+}
+
+impl ShadowLayer {
+    /// Mirror requests to `base_url` (e.g. `http://orders-v2.internal`)
+    ///
+    /// The request path and query are appended to it.
+    pub fn new(base_url: impl Into<String>) -> Self {
+        Self {
+            base_url: base_url.into().trim_end_matches('/').to_string(),
+            sample_rate: 1.0,
+            timeout: Duration::from_secs(5),
+            max_body_size: 1024 * 1024,
+            max_in_flight: 100,
+            methods: None,
+            client: None,
+            counters: Arc::new(Counters::default()),
+        }
+    }
+
+    /// Fraction of requests to mirror, from 0.0 to 1.0 (default: 1.0)
+    ///
+    /// Sampling is spread evenly: with 0.25, every fourth request is copied.
+    pub fn sample_rate(mut self, rate: f64) -> Self {
+        self.sample_rate = rate.clamp(0.0, 1.0);
+        self
+    }
+
+    /// Timeout for each copy (default: 5 seconds)
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Largest body copied, in bytes (default: 1 MiB)
+    pub fn max_body_size(mut self, bytes: usize) -> Self {
+        self.max_body_size = bytes;
+        self
+    }
+
+    /// Most copies pending at once; more are skipped (default: 100)
+    pub fn max_in_flight(mut self, limit: usize) -> Self {
+        self.max_in_flight = limit;
+        self
+    }
+
+    /// Only mirror requests with these methods (default: all)
+    pub fn methods(mut self, methods: impl IntoIterator<Item = Method>) -> Self {
+        self.methods = Some(methods.into_iter().collect());
+        self
+    }
+
+    /// Use `client` to send copies instead of a default one
+    ///
+    /// [`ShadowLayer::timeout`] still applies to each copy.
+    pub fn client(mut self, client: reqwest::Client) -> Self {
+        self.client = Some(client);
+        self
+    }
+
+    /// Current statistics
+    pub fn stats(&self) -> ShadowStats {
+        ShadowStats {
+            sent: self.counters.sent.load(Ordering::Relaxed),
+            failed: self.counters.failed.load(Ordering::Relaxed),
+            skipped: self.counters.skipped.load(Ordering::Relaxed),
+        }
+    }
+
+    /// Whether the next request falls in the sample
+    fn sampled(&self) -> bool {
+        let n = self.counters.seen.fetch_add(1, Ordering::Relaxed) as f64;
+        ((n + 1.0) * self.sample_rate).floor() > (n * self.sample_rate).floor()
+    }
+
+    fn applies_to(&self, method: &Method) -> bool {
+        self.methods
+            .as_ref()
+            .is_none_or(|methods| methods.contains(method))
+    }
+
+    /// Whether the body is known to fit in `max_body_size`
+    fn body_fits(&self, headers: &HeaderMap) -> bool {
+        let length = headers
+            .get(header::CONTENT_LENGTH)
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.parse::<usize>().ok());
+        match length {
+            Some(length) => length <= self.max_body_size,
+            None => !headers.contains_key(header::TRANSFER_ENCODING),
+        }
+    }
+
+    fn skip(&self) {
+        self.counters.skipped.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Send a copy in the background, if the in-flight limit allows it
+    fn spawn(&self, method: Method, url: String, headers: HeaderMap, body: Bytes) {
+        let counters = self.counters.clone();
+        if counters.in_flight.fetch_add(1, Ordering::AcqRel) >= self.max_in_flight {
+            counters.in_flight.fetch_sub(1, Ordering::AcqRel);
+            self.skip();
+            return;
+        }
+
+        let client = self.client.clone().unwrap_or_else(default_client);
+        let timeout = self.timeout;
+        tokio::spawn(async move {
+            let result = client
+                .request(method, &url)
+                .headers(headers)
+                .body(body)
+                .timeout(timeout)
+                .send()
+                .await;
+            match result {
+                Ok(response) => {
+                    counters.sent.fetch_add(1, Ordering::Relaxed);
+                    tracing::debug!(url = %url, status = response.status().as_u16(), "shadow request sent");
+                }
+                Err(e) => {
+                    counters.failed.fetch_add(1, Ordering::Relaxed);
+                    tracing::debug!(url = %url, error = %e, "shadow request failed");
+                }
+            }
+            counters.in_flight.fetch_sub(1, Ordering::AcqRel);
+        });
+    }
+}
+
+/// Shared client used when none is configured
+fn default_client() -> reqwest::Client {
+    static CLIENT: std::sync::OnceLock<reqwest::Client> = std::sync::OnceLock::new();
+    CLIENT.get_or_init(reqwest::Client::new).clone()
+}
+
+impl MiddlewareLayer for ShadowLayer {
+    fn call(
+        &self,
+        mut req: Request,
+        next: BoxedNext,
+    ) -> Pin<Box<dyn Future<Output = Response> + Send + 'static>> {
+        if !self.applies_to(req.method()) || !self.sampled() {
+            return next(req);
+        }
+        let layer = self.clone();
+
+        Box::pin(async move {
+            if !layer.body_fits(req.headers()) || req.load_body().await.is_err() {
+                layer.skip();
+                return next(req).await;
+            }
+            let body = match req.try_clone().and_then(|mut copy| copy.take_body()) {
+                Some(body) if body.len() <= layer.max_body_size => body,
+                _ => {
+                    layer.skip();
+                    return next(req).await;
+                }
+            };
+
+            let mut headers = req.headers().clone();
+            for name in &HOP_BY_HOP {
+                headers.remove(name);
+            }
+            headers.insert(SHADOW_HEADER, http::HeaderValue::from_static("1"));
+            let path = req
+                .uri()
+                .path_and_query()
+                .map(|pq| pq.as_str())
+                .unwrap_or("/");
+            let url = format!("{}{}", layer.base_url, path);
+
+            layer.spawn(req.method().clone(), url, headers, body);
+            next(req).await
+        })
+    }
+
+    fn clone_box(&self) -> Box<dyn MiddlewareLayer> {
+        Box::new(self.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use http::StatusCode;
+    use rustapi_core::middleware::LayerStack;
+    use rustapi_core::IntoResponse;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+    use tokio::sync::mpsc;
+
+    /// A server that sends each request it gets through the channel
+    async fn serve() -> (String, mpsc::UnboundedReceiver<String>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let (tx, rx) = mpsc::unbounded_channel();
+
+        tokio::spawn(async move {
+            while let Ok((mut stream, _)) = listener.accept().await {
+                let mut received = Vec::new();
+                let mut buf = [0u8; 1024];
+                loop {
+                    let text = String::from_utf8_lossy(&received).to_lowercase();
+                    if let Some((head, body)) = text.split_once("\r\n\r\n") {
+                        let length = head
+                            .lines()
+                            .find_map(|line| line.strip_prefix("content-length: "))
+                            .and_then(|v| v.trim().parse::<usize>().ok())
+                            .unwrap_or(0);
+                        if body.len() >= length {
+                            break;
+                        }
+                    }
+                    match stream.read(&mut buf).await {
+                        Ok(0) | Err(_) => break,
+                        Ok(n) => received.extend_from_slice(&buf[..n]),
+                    }
+                }
+                let _ = tx.send(String::from_utf8_lossy(&received).to_lowercase());
+                let response = "HTTP/1.1 500 X\r\ncontent-length: 0\r\nconnection: close\r\n\r\n";
+                let _ = stream.write_all(response.as_bytes()).await;
+            }
+        });
+
+        (format!("http://{addr}"), rx)
+    }
+
+    fn request(method: &str, uri: &str, body: &'static str) -> Request {
+        let req = http::Request::builder()
+            .method(method)
+            .uri(uri)
+            .header("content-type", "application/json")
+            .header("content-length", body.len())
+            .header("host", "api.example.com")
+            .body(())
+            .unwrap();
+        Request::from_http_request(req, Bytes::from_static(body.as_bytes()))
+    }
+
+    fn stack(layer: &ShadowLayer) -> LayerStack {
+        let mut stack = LayerStack::new();
+        stack.push(Box::new(layer.clone()));
+        stack
+    }
+
+    fn echo() -> BoxedNext {
+        Arc::new(|mut req: Request| {
+            Box::pin(async move {
+                let body = req.take_body().unwrap_or_default();
+                (
+                    StatusCode::CREATED,
+                    String::from_utf8_lossy(&body).into_owned(),
+                )
+                    .into_response()
+            }) as Pin<Box<dyn Future<Output = Response> + Send + 'static>>
+        })
+    }
+
+    #[tokio::test]
+    async fn copies_requests_without_affecting_the_response() {
+        let (url, mut received) = serve().await;
+        let layer = ShadowLayer::new(format!("{url}/"));
+
+        let response = stack(&layer)
+            .execute(request("POST", "/orders?dry=1", r#"{"id":7}"#), echo())
+            .await;
+        assert_eq!(response.status(), StatusCode::CREATED);
+
+        let copy = tokio::time::timeout(Duration::from_secs(5), received.recv())
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(copy.starts_with("post /orders?dry=1 http/1.1"));
+        assert!(copy.contains("x-shadow: 1"));
+        assert!(copy.contains("content-type: application/json"));
+        assert!(!copy.contains("api.example.com"));
+        assert!(copy.ends_with(r#"{"id":7}"#));
+
+        for _ in 0..100 {
+            if layer.stats().sent == 1 {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        assert_eq!(
+            layer.stats(),
+            ShadowStats {
+                sent: 1,
+                ..Default::default()
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn skips_filtered_and_oversized_requests() {
+        let layer = ShadowLayer::new("http://127.0.0.1:9")
+            .methods([Method::GET])
+            .max_body_size(4);
+
+        let response = stack(&layer)
+            .execute(request("POST", "/orders", "{}"), echo())
+            .await;
+        assert_eq!(response.status(), StatusCode::CREATED);
+        assert_eq!(layer.stats(), ShadowStats::default());
+
+        stack(&layer)
+            .execute(request("GET", "/orders", "too large"), echo())
+            .await;
+        assert_eq!(layer.stats().skipped, 1);
+    }
+
+    #[test]
+    fn samples_evenly() {
+        let layer = ShadowLayer::new("http://localhost").sample_rate(0.25);
+        let sampled: Vec<bool> = (0..8).map(|_| layer.sampled()).collect();
+        assert_eq!(
+            sampled,
+            [false, false, false, true, false, false, false, true]
+        );
+
+        let none = ShadowLayer::new("http://localhost").sample_rate(0.0);
+        assert!((0..10).all(|_| !none.sampled()));
+    }
+}
@@ -47,6 +47,20 @@
 //!         .map_err(|e| e.into_api_error())
 //! }
 //! ```
+//!
+//! ## Deadline Example
+//!
+//! With `TimeoutLayer`, [`within_deadline`] gives a query only what is left
+//! of the request's budget and answers `504` when it runs out:
+//!
+//! ```rust,ignore
+//! use rustapi_extras::sqlx::within_deadline;
+//!
+//! async fn get_user(deadline: Deadline, State(pool): State<PgPool>) -> Result<Json<User>> {
+//!     let query = sqlx::query_as!(User, "SELECT * FROM users WHERE id = $1", 42).fetch_one(&pool);
+//!     Ok(Json(within_deadline(deadline, query).await?))
+//! }
+//! ```
 
 #[cfg(any(
     feature = "sqlx-postgres",
@@ -54,7 +68,7 @@
     feature = "sqlx-sqlite"
 ))]
 use rustapi_core::health::{HealthCheck, HealthCheckBuilder, HealthStatus};
-use rustapi_core::ApiError;
+use rustapi_core::{ApiError, Deadline};
 #[cfg(any(
     feature = "sqlx-postgres",
     feature = "sqlx-mysql",
@@ -372,6 +386,18 @@ impl SqlxErrorExt for sqlx::Error {
     }
 }
 
+/// Run a query until `deadline` passes
+///
+/// SQLx errors are converted with [`convert_sqlx_error`]; a passed deadline
+/// becomes `504 Gateway Timeout`. The query future is dropped at the deadline,
+/// which cancels it.
+pub async fn within_deadline<T, F>(deadline: Deadline, query: F) -> Result<T, ApiError>
+where
+    F: std::future::Future<Output = Result<T, sqlx::Error>>,
+{
+    deadline.run(query).await?.map_err(convert_sqlx_error)
+}
+
 /// Convert a SQLx error to an appropriate ApiError
 ///
 /// This function maps SQLx error types to HTTP status codes:
@@ -513,6 +539,23 @@ mod tests {
         assert_eq!(api_err.error_type, "not_found");
     }
 
+    #[tokio::test]
+    async fn test_within_deadline_converts_errors_and_expiry() {
+        let deadline = Deadline::after(Duration::from_millis(100));
+        let value = within_deadline(deadline, async { Ok::<_, sqlx::Error>(1) }).await;
+        assert_eq!(value.unwrap(), 1);
+
+        let missing = within_deadline(deadline, async { Err::<(), _>(sqlx::Error::RowNotFound) });
+        assert_eq!(missing.await.unwrap_err().status, StatusCode::NOT_FOUND);
+
+        let slow = async {
+            tokio::time::sleep(Duration::from_secs(1)).await;
+            Ok::<_, sqlx::Error>(())
+        };
+        let err = within_deadline(deadline, slow).await.unwrap_err();
+        assert_eq!(err.status, StatusCode::GATEWAY_TIMEOUT);
+    }
+
     // Unit tests for SqlxPoolBuilder
     #[test]
     fn test_builder_default_values() {
@@ -1,5 +1,6 @@
 //! Structured logging configuration types
 
+use rustapi_core::middleware::LogSampling;
 use std::collections::HashSet;
 
 /// Log output format
@@ -57,6 +58,8 @@ pub struct StructuredLoggingConfig {
     pub log_request_end: bool,
     /// Whether to include caller info (file, line)
     pub include_caller_info: bool,
+    /// Which requests to log; everything by default
+    pub sampling: LogSampling,
 }
 
 impl Default for StructuredLoggingConfig {
@@ -86,6 +89,7 @@ impl Default for StructuredLoggingConfig {
             log_request_start: true,
             log_request_end: true,
             include_caller_info: false,
+            sampling: LogSampling::new(),
         }
     }
 }
@@ -276,6 +280,16 @@ impl StructuredLoggingConfigBuilder {
         self
     }
 
+    /// Sample the logged requests and promote slow ones
+    ///
+    /// Unless every request is logged, no `request started` entry is written.
+    /// Slow requests are logged at warn (or error) with their headers and
+    /// `slow` and `threshold_ms` fields.
+    pub fn sampling(mut self, sampling: LogSampling) -> Self {
+        self.config.sampling = sampling;
+        self
+    }
+
     /// Build the configuration
     pub fn build(self) -> StructuredLoggingConfig {
         self.config
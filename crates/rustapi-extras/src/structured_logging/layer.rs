@@ -203,6 +203,16 @@ impl StructuredLoggingLayer {
     }
 }
 
+/// `geo_country` and `geo_asn` fields, if a `GeoIpLayer` ran first
+#[cfg_attr(not(feature = "geoip"), allow(unused_variables))]
+fn geo_fields(req: &Request) -> Vec<(String, String)> {
+    #[cfg(feature = "geoip")]
+    if let Some(geo) = crate::geoip::GeoIp::of(req) {
+        return geo.log_fields();
+    }
+    Vec::new()
+}
+
 impl MiddlewareLayer for StructuredLoggingLayer {
     fn call(
         &self,
@@ -230,19 +240,29 @@ impl MiddlewareLayer for StructuredLoggingLayer {
             .get("user-agent")
             .and_then(|v| v.to_str().ok())
             .map(String::from);
+        let geo_fields = geo_fields(&req);
 
-        let request_headers = if config.include_request_headers {
-            Some(self.extract_headers(req.headers()))
-        } else {
-            None
-        };
+        // Turning on `structured=debug` (e.g. temporarily through `LogFilter`)
+        // adds the headers to every entry while it lasts
+        let verbose = tracing::enabled!(target: "structured", tracing::Level::DEBUG);
+
+        let sampling = config.sampling.clone();
+        let slow_threshold = sampling.threshold();
+
+        let request_headers =
+            if config.include_request_headers || verbose || slow_threshold.is_some() {
+                Some(self.extract_headers(req.headers()))
+            } else {
+                None
+            };
 
         let service_name = config.service_name.clone();
         let service_version = config.service_version.clone();
         let environment = config.environment.clone();
         let static_fields = config.static_fields.clone();
-        let include_response_headers = config.include_response_headers;
-        let log_request_start = config.log_request_start;
+        let include_request_headers = config.include_request_headers || verbose;
+        let include_response_headers = config.include_response_headers || verbose;
+        let log_request_start = config.log_request_start && sampling.logs_everything();
         let log_request_end = config.log_request_end;
         let include_timing = config.include_timing;
         let redact_headers = config.redact_headers.clone();
@@ -267,9 +287,9 @@ impl MiddlewareLayer for StructuredLoggingLayer {
                     entry = entry.service_name(sn);
                 }
 
-                entry.request_headers = request_headers.clone();
+                entry.request_headers = request_headers.clone().filter(|_| include_request_headers);
 
-                for (key, value) in &static_fields {
+                for (key, value) in static_fields.iter().chain(&geo_fields) {
                     entry.custom_fields.insert(key.clone(), value.clone());
                 }
 
@@ -279,8 +299,11 @@ impl MiddlewareLayer for StructuredLoggingLayer {
             // Call next middleware/handler
             let response = next(req).await;
 
+            let decision = sampling.decide(response.status(), start.elapsed());
+
             // Log request end if configured
-            if log_request_end {
+            if log_request_end && decision.should_log() {
+                let slow = decision.is_slow();
                 let duration_ms = if include_timing {
                     Some(start.elapsed().as_millis() as u64)
                 } else {
@@ -289,7 +312,8 @@ impl MiddlewareLayer for StructuredLoggingLayer {
 
                 let status = response.status().as_u16();
 
-                let response_headers = if include_response_headers {
+                let request_headers = request_headers.filter(|_| include_request_headers || slow);
+                let response_headers = if include_response_headers || slow {
                     let mut headers = HashMap::new();
                     for (name, value) in response.headers() {
                         let name_str = name.as_str().to_lowercase();
@@ -307,7 +331,7 @@ impl MiddlewareLayer for StructuredLoggingLayer {
 
                 let level = if status >= 500 {
                     "error"
-                } else if status >= 400 {
+                } else if status >= 400 || slow {
                     "warn"
                 } else {
                     "info"
@@ -337,9 +361,15 @@ impl MiddlewareLayer for StructuredLoggingLayer {
                     error: None,
                 };
 
-                for (key, value) in &static_fields {
+                for (key, value) in static_fields.iter().chain(&geo_fields) {
                     entry.custom_fields.insert(key.clone(), value.clone());
                 }
+                if let (true, Some(threshold)) = (slow, slow_threshold) {
+                    entry.custom_fields.insert("slow".into(), "true".into());
+                    entry
+                        .custom_fields
+                        .insert("threshold_ms".into(), threshold.as_millis().to_string());
+                }
 
                 let formatted = formatter.format(&entry);
 
@@ -6,6 +6,19 @@
 //! - Configurable field inclusion
 //! - Log level filtering
 //!
+//! Request entries are logged under the `structured` target. While that target
+//! is enabled at `debug`, entries also carry the (redacted) request and
+//! response headers, so detail can be switched on at runtime without a
+//! restart:
+//!
+//! ```rust,ignore
+//! use rustapi_core::LogFilter;
+//!
+//! if let Some(filter) = LogFilter::global() {
+//!     filter.add_directive_for("structured=debug", Duration::from_secs(600))?;
+//! }
+//! ```
+//!
 //! # Example
 //!
 //! ```rust,no_run
@@ -3,6 +3,11 @@
 //! This module provides a middleware that enforces timeouts on request handling.
 //! If a request takes longer than the specified duration, it will be aborted with a 408 Request Timeout error.
 //!
+//! The layer also stores a [`Deadline`](rustapi_core::Deadline) in the request,
+//! so handlers and outbound calls can use the remaining budget instead of their
+//! own full timeouts. When an outer layer already set an earlier deadline, that
+//! one is kept.
+//!
 //! # Example
 //!
 //! ```rust,no_run
@@ -21,7 +26,7 @@
 //! ```
 
 use rustapi_core::{
-    middleware::BoxedNext, middleware::MiddlewareLayer, Request, Response, ResponseBody,
+    middleware::BoxedNext, middleware::MiddlewareLayer, Deadline, Request, Response, ResponseBody,
 };
 use std::future::Future;
 use std::pin::Pin;
@@ -78,14 +83,15 @@ impl TimeoutLayer {
 impl MiddlewareLayer for TimeoutLayer {
     fn call(
         &self,
-        req: Request,
+        mut req: Request,
         next: BoxedNext,
     ) -> Pin<Box<dyn Future<Output = Response> + Send + 'static>> {
         let timeout = self.timeout;
+        let deadline = Deadline::after(timeout).apply(&mut req);
 
         Box::pin(async move {
             // Use tokio::time::timeout to enforce the timeout
-            match tokio::time::timeout(timeout, next(req)).await {
+            match deadline.run(next(req)).await {
                 Ok(response) => response,
                 Err(_) => {
                     // Timeout occurred - return 408 Request Timeout
@@ -176,4 +182,47 @@ mod tests {
         let response = timeout_layer.call(req, next).await;
         assert_eq!(response.status(), 200);
     }
+
+    #[tokio::test]
+    async fn handler_sees_the_remaining_budget() {
+        let timeout_layer = TimeoutLayer::from_secs(30);
+
+        let next: BoxedNext = Arc::new(|req: Request| {
+            Box::pin(async move {
+                let deadline = Deadline::of(&req).copied().unwrap();
+                let status = if deadline.remaining() <= Duration::from_secs(30) {
+                    200
+                } else {
+                    500
+                };
+                http::Response::builder()
+                    .status(status)
+                    .body(ResponseBody::Full(http_body_util::Full::new(
+                        bytes::Bytes::new(),
+                    )))
+                    .unwrap()
+            }) as Pin<Box<dyn Future<Output = Response> + Send + 'static>>
+        });
+
+        let req = http::Request::builder().uri("/").body(()).unwrap();
+        let mut req = Request::from_http_request(req, Bytes::new());
+        // A shorter deadline set further out wins
+        Deadline::after(Duration::from_millis(50)).apply(&mut req);
+        let slow: BoxedNext = Arc::new(|_req: Request| {
+            Box::pin(async {
+                sleep(Duration::from_millis(200)).await;
+                http::Response::builder()
+                    .status(200)
+                    .body(ResponseBody::Full(http_body_util::Full::new(
+                        bytes::Bytes::new(),
+                    )))
+                    .unwrap()
+            }) as Pin<Box<dyn Future<Output = Response> + Send + 'static>>
+        });
+        assert_eq!(timeout_layer.call(req, slow).await.status(), 408);
+
+        let req = http::Request::builder().uri("/").body(()).unwrap();
+        let req = Request::from_http_request(req, Bytes::new());
+        assert_eq!(timeout_layer.call(req, next).await.status(), 200);
+    }
 }
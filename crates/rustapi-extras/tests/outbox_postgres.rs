@@ -0,0 +1,225 @@
+//! Outbox and PostgreSQL job backend tests against a real database.
+//!
+//! They run when `DATABASE_URL` points at a PostgreSQL database and are
+//! skipped otherwise. Every test uses its own tables.
+
+#![cfg(feature = "outbox")]
+
+use rustapi_extras::outbox::{
+    Outbox, OutboxError, OutboxEvent, OutboxMessage, OutboxPublisher, OutboxRelay,
+};
+use sqlx::{PgPool, Row};
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::sync::Notify;
+
+async fn pool() -> Option<PgPool> {
+    let Ok(url) = std::env::var("DATABASE_URL") else {
+        eprintln!("DATABASE_URL not set, skipping");
+        return None;
+    };
+    Some(
+        PgPool::connect(&url)
+            .await
+            .expect("connect to DATABASE_URL"),
+    )
+}
+
+fn table(prefix: &str) -> String {
+    format!("{}_{}", prefix, uuid::Uuid::new_v4().simple())
+}
+
+async fn outbox(pool: &PgPool) -> Outbox {
+    outbox_in(pool, &table("outbox_test")).await
+}
+
+async fn outbox_in(pool: &PgPool, table: &str) -> Outbox {
+    let outbox = Outbox::new(pool.clone()).table_name(table);
+    outbox.ensure_schema().await.unwrap();
+    outbox
+}
+
+/// Records published event ids, failing while `fail` is set.
+#[derive(Clone, Default)]
+struct Recorder {
+    published: Arc<Mutex<Vec<String>>>,
+    fail: bool,
+}
+
+impl OutboxPublisher for Recorder {
+    fn publish<'a>(
+        &'a self,
+        event: &'a OutboxEvent,
+    ) -> Pin<Box<dyn Future<Output = Result<(), OutboxError>> + Send + 'a>> {
+        Box::pin(async move {
+            if self.fail {
+                return Err(OutboxError::Publish("unavailable".into()));
+            }
+            self.published.lock().unwrap().push(event.id.clone());
+            Ok(())
+        })
+    }
+}
+
+/// Holds every publish until released.
+struct Gate {
+    started: Arc<Notify>,
+    release: Arc<Notify>,
+}
+
+impl OutboxPublisher for Gate {
+    fn publish<'a>(
+        &'a self,
+        _event: &'a OutboxEvent,
+    ) -> Pin<Box<dyn Future<Output = Result<(), OutboxError>> + Send + 'a>> {
+        Box::pin(async move {
+            self.started.notify_one();
+            self.release.notified().await;
+            Ok(())
+        })
+    }
+}
+
+#[tokio::test]
+async fn committed_events_are_published_once() {
+    let Some(pool) = pool().await else { return };
+    let outbox = outbox(&pool).await;
+
+    let committed = OutboxMessage::new("order_placed", &serde_json::json!({ "id": 1 })).unwrap();
+    let mut tx = pool.begin().await.unwrap();
+    assert!(outbox.write(&mut tx, &committed).await.unwrap());
+    tx.commit().await.unwrap();
+
+    let rolled_back = OutboxMessage::new("order_placed", &()).unwrap();
+    let mut tx = pool.begin().await.unwrap();
+    outbox.write(&mut tx, &rolled_back).await.unwrap();
+    tx.rollback().await.unwrap();
+
+    let recorder = Recorder::default();
+    let relay = OutboxRelay::new(outbox).route("order_placed", recorder.clone());
+    assert_eq!(relay.run_once().await.unwrap(), 1);
+    assert_eq!(relay.run_once().await.unwrap(), 0);
+    assert_eq!(*recorder.published.lock().unwrap(), vec![committed.id()]);
+}
+
+#[tokio::test]
+async fn duplicate_ids_and_dedup_keys_are_not_written_twice() {
+    let Some(pool) = pool().await else { return };
+    let outbox = outbox(&pool).await;
+
+    let first = OutboxMessage::new("order_placed", &())
+        .unwrap()
+        .dedup_key("order-7");
+    let second = OutboxMessage::new("order_placed", &())
+        .unwrap()
+        .dedup_key("order-7");
+    let mut tx = pool.begin().await.unwrap();
+    assert!(outbox.write(&mut tx, &first).await.unwrap());
+    assert!(!outbox.write(&mut tx, &first).await.unwrap());
+    assert!(!outbox.write(&mut tx, &second).await.unwrap());
+    tx.commit().await.unwrap();
+
+    let recorder = Recorder::default();
+    let relay = OutboxRelay::new(outbox).fallback(recorder.clone());
+    assert_eq!(relay.run_once().await.unwrap(), 1);
+}
+
+#[tokio::test]
+async fn failed_publishes_are_retried_after_the_backoff() {
+    let Some(pool) = pool().await else { return };
+    let outbox = outbox(&pool).await;
+
+    let message = OutboxMessage::new("order_placed", &()).unwrap();
+    let mut tx = pool.begin().await.unwrap();
+    outbox.write(&mut tx, &message).await.unwrap();
+    tx.commit().await.unwrap();
+
+    let failing = OutboxRelay::new(outbox.clone()).fallback(Recorder {
+        fail: true,
+        ..Recorder::default()
+    });
+    assert_eq!(failing.run_once().await.unwrap(), 0);
+
+    // Not due again until the backoff has passed
+    let recorder = Recorder::default();
+    let relay = OutboxRelay::new(outbox).fallback(recorder.clone());
+    assert_eq!(relay.run_once().await.unwrap(), 0);
+    assert!(recorder.published.lock().unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn claimed_events_are_hidden_from_other_relays_while_publishing() {
+    let Some(pool) = pool().await else { return };
+    let table = table("outbox_test");
+    let outbox = outbox_in(&pool, &table).await;
+
+    let mut tx = pool.begin().await.unwrap();
+    for _ in 0..2 {
+        let message = OutboxMessage::new("order_placed", &()).unwrap();
+        outbox.write(&mut tx, &message).await.unwrap();
+    }
+    tx.commit().await.unwrap();
+
+    let started = Arc::new(Notify::new());
+    let release = Arc::new(Notify::new());
+    let slow = OutboxRelay::new(outbox.clone()).fallback(Gate {
+        started: started.clone(),
+        release: release.clone(),
+    });
+    let first = tokio::spawn(async move { slow.run_once().await });
+    started.notified().await;
+
+    // The claim has committed, so no rows stay locked while publishing...
+    let mut tx = pool.begin().await.unwrap();
+    let locked = sqlx::query(&format!("SELECT id FROM {} FOR UPDATE NOWAIT", table))
+        .fetch_all(&mut *tx)
+        .await
+        .expect("outbox rows are locked while publishing");
+    assert_eq!(locked.len(), 2);
+    tx.rollback().await.unwrap();
+
+    // ...and the lease keeps a second relay from publishing them again
+    let recorder = Recorder::default();
+    let other = OutboxRelay::new(outbox).fallback(recorder.clone());
+    let found = tokio::time::timeout(Duration::from_secs(5), other.run_once())
+        .await
+        .expect("second relay blocked on the first one")
+        .unwrap();
+    assert_eq!(found, 0);
+
+    release.notify_one();
+    started.notified().await;
+    release.notify_one();
+    assert_eq!(first.await.unwrap().unwrap(), 2);
+}
+
+#[cfg(feature = "jobs-postgres")]
+#[tokio::test]
+async fn postgres_backend_ignores_a_second_push_of_a_queued_id() {
+    use rustapi_extras::jobs::backend::postgres::PostgresBackend;
+    use rustapi_extras::jobs::{EnqueueOptions, JobQueue};
+
+    let Some(pool) = pool().await else { return };
+    let table = table("jobs_test");
+    let backend = PostgresBackend::new(pool.clone(), &table);
+    backend.ensure_schema().await.unwrap();
+    let queue = JobQueue::new(backend);
+
+    for payload in [1, 2] {
+        let opts = EnqueueOptions::new().id("evt-1");
+        let id = queue
+            .enqueue_raw("order_placed", serde_json::json!(payload), opts)
+            .await
+            .unwrap();
+        assert_eq!(id, "evt-1");
+    }
+
+    let rows = sqlx::query(&format!("SELECT payload FROM {}", table))
+        .fetch_all(&pool)
+        .await
+        .unwrap();
+    assert_eq!(rows.len(), 1);
+    assert_eq!(rows[0].get::<serde_json::Value, _>("payload"), 1);
+}
@@ -0,0 +1,152 @@
+//! JWT authentication for gRPC services.
+//!
+//! [`jwt_interceptor`] turns the [`JwtLayer`] protecting the HTTP routes into
+//! a Tonic interceptor, so both surfaces accept exactly the same tokens. The
+//! token is read from the `authorization` metadata (`Bearer <token>`); on
+//! success the claims are stored in the request extensions, where
+//! [`jwt_claims`] retrieves them. Failures return `UNAUTHENTICATED` with the
+//! message the HTTP layer would send.
+//!
+//! ```rust,ignore
+//! use rustapi_rs::extras::jwt::JwtLayer;
+//! use rustapi_rs::grpc::{jwt_claims, jwt_interceptor};
+//!
+//! let jwt = JwtLayer::<Claims>::new(secret).skip_paths(vec!["/health"]);
+//!
+//! let app = RustApi::new().layer(jwt.clone()).route("/me", get(me));
+//! let greeter = GreeterServer::with_interceptor(MyGreeter, jwt_interceptor(jwt));
+//!
+//! // In the service implementation:
+//! let claims = jwt_claims::<Claims, _>(&request).expect("set by the interceptor");
+//! ```
+//!
+//! `skip_paths` only applies to HTTP; register public gRPC services without
+//! the interceptor instead.
+
+use rustapi_extras::jwt::{JwtLayer, ValidatedClaims};
+use serde::de::DeserializeOwned;
+use tonic::service::Interceptor;
+use tonic::{Request, Status};
+
+/// Tonic interceptor validating JWTs with the rules of a [`JwtLayer`].
+///
+/// Created by [`jwt_interceptor`].
+#[derive(Clone)]
+pub struct JwtInterceptor<T> {
+    layer: JwtLayer<T>,
+}
+
+/// Build a Tonic interceptor enforcing the same token rules as `layer`.
+pub fn jwt_interceptor<T>(layer: JwtLayer<T>) -> JwtInterceptor<T>
+where
+    T: DeserializeOwned + Clone + Send + Sync + 'static,
+{
+    JwtInterceptor { layer }
+}
+
+impl<T> Interceptor for JwtInterceptor<T>
+where
+    T: DeserializeOwned + Clone + Send + Sync + 'static,
+{
+    fn call(&mut self, mut request: Request<()>) -> Result<Request<()>, Status> {
+        let authorization = request
+            .metadata()
+            .get("authorization")
+            .and_then(|value| http::HeaderValue::from_bytes(value.as_bytes()).ok());
+
+        let claims = self
+            .layer
+            .authorize(authorization.as_ref())
+            .map_err(Status::unauthenticated)?;
+        request.extensions_mut().insert(ValidatedClaims(claims));
+        Ok(request)
+    }
+}
+
+/// Get the claims validated by a [`JwtInterceptor`] for this request.
+pub fn jwt_claims<T, M>(request: &Request<M>) -> Option<&T>
+where
+    T: Clone + Send + Sync + 'static,
+{
+    request
+        .extensions()
+        .get::<ValidatedClaims<T>>()
+        .map(|claims| &claims.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rustapi_extras::jwt::create_token;
+    use serde::{Deserialize, Serialize};
+    use tonic::Code;
+
+    #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+    struct Claims {
+        sub: String,
+        exp: u64,
+    }
+
+    fn request(authorization: Option<&str>) -> Request<()> {
+        let mut request = Request::new(());
+        if let Some(value) = authorization {
+            request
+                .metadata_mut()
+                .insert("authorization", value.parse().unwrap());
+        }
+        request
+    }
+
+    #[test]
+    fn valid_token_stores_claims() {
+        let claims = Claims {
+            sub: "alice".to_string(),
+            exp: 9_999_999_999,
+        };
+        let token = create_token(&claims, "secret").unwrap();
+        let mut interceptor = jwt_interceptor(JwtLayer::<Claims>::new("secret"));
+
+        let request = interceptor
+            .call(request(Some(&format!("Bearer {}", token))))
+            .unwrap();
+        assert_eq!(jwt_claims::<Claims, _>(&request), Some(&claims));
+    }
+
+    #[test]
+    fn rejections_match_http_messages() {
+        let mut interceptor = jwt_interceptor(JwtLayer::<Claims>::new("secret"));
+
+        let status = interceptor.call(request(None)).unwrap_err();
+        assert_eq!(status.code(), Code::Unauthenticated);
+        assert_eq!(status.message(), "Missing Authorization header");
+
+        let status = interceptor.call(request(Some("Basic abc"))).unwrap_err();
+        assert_eq!(status.message(), "Invalid Authorization header format");
+
+        let expired = create_token(
+            &Claims {
+                sub: "alice".to_string(),
+                exp: 1,
+            },
+            "secret",
+        )
+        .unwrap();
+        let status = interceptor
+            .call(request(Some(&format!("Bearer {}", expired))))
+            .unwrap_err();
+        assert_eq!(status.message(), "Token has expired");
+
+        let forged = create_token(
+            &Claims {
+                sub: "mallory".to_string(),
+                exp: 9_999_999_999,
+            },
+            "other-secret",
+        )
+        .unwrap();
+        let status = interceptor
+            .call(request(Some(&format!("Bearer {}", forged))))
+            .unwrap_err();
+        assert_eq!(status.message(), "Invalid token signature");
+    }
+}
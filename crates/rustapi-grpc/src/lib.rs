@@ -27,10 +27,57 @@
 //!     Ok(())
 //! }
 //! ```
+//!
+//! ## Single port
+//!
+//! Behind a plain L4 load balancer, serve both on one port instead:
+//! requests with `content-type: application/grpc` go to the Tonic services.
+//!
+//! ```rust,ignore
+//! use rustapi_rs::grpc::{run_rustapi_and_grpc_multiplexed, tonic};
+//!
+//! let grpc = tonic::service::Routes::new(MyGreeterServer::new(MyGreeter::default()));
+//! run_rustapi_and_grpc_multiplexed(http_app, "0.0.0.0:8080", grpc).await?;
+//! ```
 
 #![warn(missing_docs)]
 #![warn(rustdoc::missing_crate_level_docs)]
 
+#[cfg(feature = "jwt")]
+mod jwt;
+mod multiplex;
+#[cfg(feature = "otel")]
+mod otel;
+#[cfg(feature = "replay")]
+mod replay;
+#[cfg(feature = "sse")]
+mod sse;
+mod standard;
+mod trace;
+#[cfg(feature = "transcode")]
+mod transcode;
+#[cfg(feature = "web")]
+mod web;
+
+#[cfg(feature = "jwt")]
+pub use jwt::{jwt_claims, jwt_interceptor, JwtInterceptor};
+pub use multiplex::{
+    is_grpc_request, run_rustapi_and_grpc_multiplexed,
+    run_rustapi_and_grpc_multiplexed_with_shutdown, GrpcServices, MultiplexBody, MultiplexService,
+};
+#[cfg(feature = "otel")]
+pub use otel::{grpc_trace_context, GrpcOtel, GrpcOtelLayer};
+#[cfg(feature = "replay")]
+pub use replay::{GrpcReplay, GrpcReplayBody, GrpcReplayLayer};
+#[cfg(feature = "sse")]
+pub use sse::{grpc_stream_from_sse, sse_from_grpc, GrpcEvents, SseMessages, SSE_ERROR_EVENT};
+pub use standard::{standard_services, GrpcHealth, StandardServices, DEFAULT_HEALTH_POLL_INTERVAL};
+pub use trace::{GrpcTracing, GrpcTracingLayer};
+#[cfg(feature = "transcode")]
+pub use transcode::{HttpRule, Transcoder};
+#[cfg(feature = "web")]
+pub use web::{is_grpc_web_request, GrpcWebConfig};
+
 use rustapi_core::RustApi;
 use std::error::Error;
 use std::future::Future;
@@ -52,6 +99,13 @@ pub use tonic;
 /// Re-export `prost` for protobuf message derives and runtime types.
 pub use prost;
 
+/// Re-export `prost_reflect` for the descriptors `GrpcReplayLayer` decodes messages with.
+#[cfg(feature = "replay")]
+pub use prost_reflect;
+
+/// Re-export `tonic_health` for per-service health reporting.
+pub use tonic_health;
+
 fn to_boxed_error<E>(err: E) -> BoxError
 where
     E: Error + Send + Sync + 'static,
@@ -0,0 +1,339 @@
+//! Single-port HTTP + gRPC serving.
+//!
+//! [`run_rustapi_and_grpc_multiplexed`] serves RustAPI routes and Tonic
+//! services on one listener. Connections speak HTTP/1.1 or HTTP/2 (detected
+//! per connection), and each request is routed by its `content-type`: gRPC
+//! calls (`application/grpc`, `application/grpc+proto`, ...) go to the Tonic
+//! services, everything else to the RustAPI app. Deployments behind a plain
+//! L4 load balancer then only need to expose a single port.
+//!
+//! ```rust,ignore
+//! use rustapi_rs::grpc::{run_rustapi_and_grpc_multiplexed, tonic};
+//! use rustapi_rs::prelude::*;
+//!
+//! #[tokio::main]
+//! async fn main() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+//!     let app = RustApi::new().route("/health", get(health));
+//!     let grpc = tonic::service::Routes::new(MyGreeterServer::new(MyGreeter::default()));
+//!
+//!     run_rustapi_and_grpc_multiplexed(app, "0.0.0.0:8080", grpc).await
+//! }
+//! ```
+
+use crate::Result;
+use http::header::CONTENT_TYPE;
+use http_body_util::Either;
+use hyper::body::Incoming;
+use hyper_util::rt::{TokioExecutor, TokioIo};
+use hyper_util::server::conn::auto;
+use rustapi_core::{ResponseBody, RustApi, RustApiService};
+use std::convert::Infallible;
+use std::future::Future;
+use std::net::SocketAddr;
+use std::pin::Pin;
+use tokio::net::TcpListener;
+use tonic::service::Routes;
+
+/// Response body of a [`MultiplexService`]: either a RustAPI or a gRPC body.
+pub type MultiplexBody = Either<ResponseBody, tonic::body::Body>;
+
+/// Check whether a request is a gRPC call, based on its `content-type`.
+///
+/// Matches `application/grpc` and its `+proto`/`+json` variants, but not
+/// gRPC-Web (`application/grpc-web`), which needs translation first.
+pub fn is_grpc_request<B>(req: &http::Request<B>) -> bool {
+    req.headers()
+        .get(CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|content_type| {
+            content_type == "application/grpc" || content_type.starts_with("application/grpc+")
+        })
+}
+
+/// Tonic services served next to a RustAPI app.
+///
+/// Created from Tonic [`Routes`]; with the `web` feature, browser clients can
+/// also be accepted through [`grpc_web`](Self::grpc_web).
+#[derive(Clone)]
+pub struct GrpcServices {
+    routes: Routes,
+    #[cfg(feature = "web")]
+    web: Option<crate::web::GrpcWebConfig>,
+}
+
+impl GrpcServices {
+    /// Serve the given Tonic routes.
+    pub fn new(routes: Routes) -> Self {
+        Self {
+            routes,
+            #[cfg(feature = "web")]
+            web: None,
+        }
+    }
+
+    /// Trace every call with `layer` (gRPC-Web calls included).
+    pub fn tracing(mut self, layer: crate::trace::GrpcTracingLayer) -> Self {
+        let router = std::mem::take(self.routes.axum_router_mut());
+        *self.routes.axum_router_mut() = router.layer(layer);
+        self
+    }
+
+    /// Apply an OpenTelemetry configuration to every call (gRPC-Web calls included).
+    #[cfg(feature = "otel")]
+    pub fn otel(mut self, layer: crate::otel::GrpcOtelLayer) -> Self {
+        let router = std::mem::take(self.routes.axum_router_mut());
+        *self.routes.axum_router_mut() = router.layer(layer);
+        self
+    }
+
+    /// Record every call for replay with `layer` (gRPC-Web calls included).
+    #[cfg(feature = "replay")]
+    pub fn replay(mut self, layer: crate::replay::GrpcReplayLayer) -> Self {
+        let router = std::mem::take(self.routes.axum_router_mut());
+        *self.routes.axum_router_mut() = router.layer(layer);
+        self
+    }
+
+    /// Also accept gRPC-Web calls (`application/grpc-web*`) from browsers.
+    ///
+    /// Requests are translated to gRPC for the same Tonic services, and CORS
+    /// preflights for gRPC-Web calls are answered according to `config`.
+    #[cfg(feature = "web")]
+    pub fn grpc_web(mut self, config: crate::web::GrpcWebConfig) -> Self {
+        self.web = Some(config);
+        self
+    }
+}
+
+impl From<Routes> for GrpcServices {
+    fn from(routes: Routes) -> Self {
+        Self::new(routes)
+    }
+}
+
+/// Hyper service routing each request to Tonic or RustAPI by `content-type`.
+///
+/// Use it directly to serve on a custom listener; otherwise see
+/// [`run_rustapi_and_grpc_multiplexed`].
+#[derive(Clone)]
+pub struct MultiplexService {
+    http: RustApiService,
+    grpc: Routes,
+    #[cfg(feature = "web")]
+    web: Option<crate::web::GrpcWebBridge>,
+}
+
+impl MultiplexService {
+    /// Create a service from a prepared RustAPI app and Tonic services.
+    pub fn new(http: RustApiService, grpc: impl Into<GrpcServices>) -> Self {
+        let grpc = grpc.into();
+        let routes = grpc.routes.prepare();
+        Self {
+            http,
+            #[cfg(feature = "web")]
+            web: grpc
+                .web
+                .map(|config| crate::web::GrpcWebBridge::new(config, routes.clone())),
+            grpc: routes,
+        }
+    }
+
+    /// Set the peer address of the connection this service handles.
+    pub fn with_remote_addr(mut self, remote_addr: SocketAddr) -> Self {
+        self.http = self.http.with_remote_addr(remote_addr);
+        self
+    }
+}
+
+impl hyper::service::Service<hyper::Request<Incoming>> for MultiplexService {
+    type Response = hyper::Response<MultiplexBody>;
+    type Error = Infallible;
+    type Future =
+        Pin<Box<dyn Future<Output = std::result::Result<Self::Response, Infallible>> + Send>>;
+
+    fn call(&self, req: hyper::Request<Incoming>) -> Self::Future {
+        #[cfg(feature = "web")]
+        if let Some(web) = &self.web {
+            if web.accepts(&req) {
+                let web = web.clone();
+                return Box::pin(async move { Ok(web.call(req).await.map(Either::Right)) });
+            }
+        }
+
+        if is_grpc_request(&req) {
+            let grpc = self.grpc.clone();
+            Box::pin(async move { Ok(call_tonic(grpc, req).await.map(Either::Right)) })
+        } else {
+            let response = self.http.call(req);
+            Box::pin(async move { Ok(response.await?.map(Either::Left)) })
+        }
+    }
+}
+
+/// Drive a Tonic service (which never fails) for a single request.
+pub(crate) async fn call_tonic<S, B>(
+    mut service: S,
+    req: hyper::Request<B>,
+) -> hyper::Response<tonic::body::Body>
+where
+    S: tower_service::Service<
+        hyper::Request<B>,
+        Response = hyper::Response<tonic::body::Body>,
+        Error = Infallible,
+    >,
+{
+    let Ok(()) = std::future::poll_fn(|cx| service.poll_ready(cx)).await;
+    let Ok(response) = service.call(req).await;
+    response
+}
+
+/// Serve a `RustApi` app and Tonic services on a single port.
+///
+/// Runs until the listener fails; see
+/// [`run_rustapi_and_grpc_multiplexed_with_shutdown`] for graceful shutdown.
+pub async fn run_rustapi_and_grpc_multiplexed(
+    app: RustApi,
+    addr: impl AsRef<str>,
+    grpc: impl Into<GrpcServices>,
+) -> Result<()> {
+    run_rustapi_and_grpc_multiplexed_with_shutdown(app, addr, grpc, std::future::pending()).await
+}
+
+/// Serve a `RustApi` app and Tonic services on a single port until
+/// `shutdown_signal` completes.
+///
+/// The app's `on_shutdown` hooks run after the listener stops.
+pub async fn run_rustapi_and_grpc_multiplexed_with_shutdown<SF>(
+    app: RustApi,
+    addr: impl AsRef<str>,
+    grpc: impl Into<GrpcServices>,
+    shutdown_signal: SF,
+) -> Result<()>
+where
+    SF: Future<Output = ()> + Send,
+{
+    let addr = addr.as_ref();
+    let socket_addr = addr.parse::<SocketAddr>()?;
+    let Some(http) = app.into_service(addr).await? else {
+        return Ok(());
+    };
+    let listener = TcpListener::bind(socket_addr).await?;
+    let service = MultiplexService::new(http.clone(), grpc);
+
+    tokio::pin!(shutdown_signal);
+    loop {
+        tokio::select! {
+            accepted = listener.accept() => {
+                let Ok((stream, remote_addr)) = accepted else {
+                    continue;
+                };
+                let _ = stream.set_nodelay(true);
+                let service = service.clone().with_remote_addr(remote_addr);
+
+                tokio::spawn(async move {
+                    // Errors here are per-connection (resets, protocol errors)
+                    let _ = auto::Builder::new(TokioExecutor::new())
+                        .serve_connection_with_upgrades(TokioIo::new(stream), service)
+                        .await;
+                });
+            }
+            _ = &mut shutdown_signal => break,
+        }
+    }
+
+    http.shutdown().await;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rustapi_core::get;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpStream;
+    use tokio::sync::oneshot;
+    use tokio::time::{sleep, timeout, Duration};
+    use tonic_health::pb::health_check_response::ServingStatus;
+    use tonic_health::pb::health_client::HealthClient;
+    use tonic_health::pb::HealthCheckRequest;
+
+    #[test]
+    fn grpc_requests_are_detected_by_content_type() {
+        let request = |content_type: &str| {
+            http::Request::builder()
+                .header(CONTENT_TYPE, content_type)
+                .body(())
+                .unwrap()
+        };
+
+        assert!(is_grpc_request(&request("application/grpc")));
+        assert!(is_grpc_request(&request("application/grpc+proto")));
+        assert!(!is_grpc_request(&request("application/grpc-web")));
+        assert!(!is_grpc_request(&request("application/json")));
+        assert!(!is_grpc_request(&http::Request::new(())));
+    }
+
+    #[tokio::test]
+    async fn serves_http_and_grpc_on_one_port() {
+        async fn health() -> &'static str {
+            "ok"
+        }
+
+        let addr = {
+            let probe = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+            probe.local_addr().unwrap()
+        };
+        let (reporter, health_service) = tonic_health::server::health_reporter();
+        reporter
+            .set_service_status("greeter", tonic_health::ServingStatus::Serving)
+            .await;
+
+        let app = RustApi::new().route("/health", get(health));
+        let (tx, rx) = oneshot::channel::<()>();
+        let server = tokio::spawn(run_rustapi_and_grpc_multiplexed_with_shutdown(
+            app,
+            addr.to_string(),
+            GrpcServices::new(Routes::new(health_service)).tracing(crate::GrpcTracingLayer::new()),
+            async move {
+                let _ = rx.await;
+            },
+        ));
+
+        // Wait for the listener
+        let mut stream = loop {
+            match TcpStream::connect(addr).await {
+                Ok(stream) => break stream,
+                Err(_) => sleep(Duration::from_millis(10)).await,
+            }
+        };
+
+        stream
+            .write_all(b"GET /health HTTP/1.1\r\nhost: localhost\r\nconnection: close\r\n\r\n")
+            .await
+            .unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).await.unwrap();
+        assert!(response.starts_with("HTTP/1.1 200"), "{}", response);
+        assert!(response.ends_with("ok"));
+
+        let channel = tonic::transport::Endpoint::from_shared(format!("http://{}", addr))
+            .unwrap()
+            .connect()
+            .await
+            .unwrap();
+        let mut client = HealthClient::new(channel);
+        let status = client
+            .check(HealthCheckRequest {
+                service: "greeter".to_string(),
+            })
+            .await
+            .unwrap()
+            .into_inner()
+            .status;
+        assert_eq!(status, ServingStatus::Serving as i32);
+
+        tx.send(()).unwrap();
+        let result = timeout(Duration::from_secs(3), server).await;
+        assert!(result.expect("server should stop").unwrap().is_ok());
+    }
+}
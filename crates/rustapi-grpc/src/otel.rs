@@ -0,0 +1,275 @@
+//! OpenTelemetry-style tracing for gRPC calls.
+//!
+//! [`GrpcOtelLayer`] applies an [`OtelConfig`] to gRPC calls the way
+//! `OtelLayer` does for HTTP: W3C trace context is read from the
+//! `traceparent`/`tracestate` metadata, the [`TraceContext`] is stored in the
+//! request extensions, spans and events carry the same `otel_*`/`http_*`
+//! fields, and `x-trace-id`/`x-correlation-id` are sent back when context
+//! propagation is enabled. `exclude_paths` matches the gRPC path
+//! (`/package.Service/Method`).
+//!
+//! gRPC calls also record the `rpc_*` attributes of the OpenTelemetry RPC
+//! conventions, and map the final gRPC status to the span status.
+//!
+//! ```rust,ignore
+//! use rustapi_rs::extras::otel::OtelConfig;
+//! use rustapi_rs::grpc::GrpcOtelLayer;
+//!
+//! let config = OtelConfig::builder().service_name("users").build();
+//!
+//! let app = RustApi::new().layer(OtelLayer::new(config.clone()));
+//! tonic::transport::Server::builder()
+//!     .layer(GrpcOtelLayer::new(config))
+//!     .add_service(UsersServer::new(MyUsers::default()));
+//! ```
+
+use crate::trace::traced_response;
+use crate::BoxError;
+use bytes::Bytes;
+use http::{Request, Response};
+use http_body::Body;
+use rustapi_extras::otel::{
+    extract_trace_context_from_headers, propagate_trace_context, OtelConfig, TraceContext,
+};
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use std::time::Instant;
+use tonic::Code;
+use tower_service::Service;
+use tracing::Instrument;
+
+/// Tower layer applying an [`OtelConfig`] to gRPC calls
+///
+/// Mirrors the spans, events and propagation of RustAPI's HTTP `OtelLayer`.
+#[derive(Clone)]
+pub struct GrpcOtelLayer {
+    config: Arc<OtelConfig>,
+}
+
+impl GrpcOtelLayer {
+    /// Create a new GrpcOtelLayer with the given configuration
+    pub fn new(config: OtelConfig) -> Self {
+        Self {
+            config: Arc::new(config),
+        }
+    }
+}
+
+impl<S> tower_layer::Layer<S> for GrpcOtelLayer {
+    type Service = GrpcOtel<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        GrpcOtel {
+            inner,
+            config: self.config.clone(),
+        }
+    }
+}
+
+/// Service created by [`GrpcOtelLayer`].
+#[derive(Clone)]
+pub struct GrpcOtel<S> {
+    inner: S,
+    config: Arc<OtelConfig>,
+}
+
+impl<S, B, ResBody> Service<Request<B>> for GrpcOtel<S>
+where
+    S: Service<Request<B>, Response = Response<ResBody>>,
+    S::Future: Send + 'static,
+    ResBody: Body<Data = Bytes> + Send + 'static,
+    ResBody::Error: Into<BoxError>,
+{
+    type Response = Response<tonic::body::Body>;
+    type Error = S::Error;
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response, S::Error>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), S::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, mut req: Request<B>) -> Self::Future {
+        let config = self.config.clone();
+        let path = req.uri().path().to_string();
+        if config
+            .exclude_paths
+            .iter()
+            .any(|excluded| path.starts_with(excluded))
+        {
+            let future = self.inner.call(req);
+            return Box::pin(async move {
+                let response = future.await?;
+                Ok(response.map(tonic::body::Body::new))
+            });
+        }
+
+        let method = req.method().to_string();
+        let uri = req.uri().to_string();
+        let (rpc_service, rpc_method) = path
+            .trim_start_matches('/')
+            .split_once('/')
+            .map(|(service, method)| (service.to_string(), method.to_string()))
+            .unwrap_or_default();
+        let trace_context = extract_trace_context_from_headers(req.headers());
+        let trace_headers: Vec<(String, String)> = config
+            .trace_headers
+            .iter()
+            .filter_map(|name| {
+                let value = req.headers().get(name.as_str())?.to_str().ok()?;
+                Some((name.clone(), value.to_string()))
+            })
+            .collect();
+        req.extensions_mut().insert(trace_context.clone());
+
+        let span = tracing::info_span!(
+            "grpc_request",
+            otel_name = %path.trim_start_matches('/'),
+            http_method = %method,
+            http_url = %uri,
+            http_route = %path,
+            rpc_system = "grpc",
+            rpc_service = %rpc_service,
+            rpc_method = %rpc_method,
+            trace_id = %trace_context.trace_id,
+            span_id = %trace_context.span_id,
+            parent_span_id = trace_context.parent_span_id.as_deref().unwrap_or("none"),
+            service_name = %config.service_name,
+        );
+
+        let start = Instant::now();
+        let future = self.inner.call(req).instrument(span.clone());
+        Box::pin(async move {
+            let mut response = future.await?;
+            let status = response.status().as_u16();
+            if config.propagate_context {
+                propagate_trace_context(response.headers_mut(), &trace_context);
+            }
+
+            Ok(traced_response(response, start, move |code, duration| {
+                let (span_status, error) = span_status(code);
+                let _enter = span.enter();
+                tracing::info!(
+                    target: "otel",
+                    trace_id = %trace_context.trace_id,
+                    span_id = %trace_context.span_id,
+                    http_method = %method,
+                    http_url = %uri,
+                    http_status_code = status,
+                    rpc_grpc_status_code = code as i32,
+                    duration_ms = duration.as_millis() as u64,
+                    otel_status = span_status,
+                    error = error,
+                    service_name = %config.service_name,
+                    "request completed"
+                );
+
+                for (name, value) in &trace_headers {
+                    tracing::debug!(
+                        target: "otel",
+                        trace_id = %trace_context.trace_id,
+                        header_name = %name,
+                        header_value = %value,
+                        "traced header"
+                    );
+                }
+            }))
+        })
+    }
+}
+
+/// Span status of a finished call, following the OpenTelemetry gRPC server
+/// conventions: only server-side failures mark the span as an error.
+fn span_status(code: Code) -> (&'static str, bool) {
+    match code {
+        Code::Ok => ("OK", false),
+        Code::Unknown
+        | Code::DeadlineExceeded
+        | Code::Unimplemented
+        | Code::Internal
+        | Code::Unavailable
+        | Code::DataLoss => ("ERROR", true),
+        _ => ("UNSET", false),
+    }
+}
+
+/// Get the trace context stored by a [`GrpcOtelLayer`] for this request.
+pub fn grpc_trace_context<M>(request: &tonic::Request<M>) -> Option<&TraceContext> {
+    request.extensions().get::<TraceContext>()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::convert::Infallible;
+    use std::sync::Mutex;
+    use tower_layer::Layer;
+
+    /// Records the trace context seen by the service.
+    #[derive(Clone, Default)]
+    struct Echo(Arc<Mutex<Option<TraceContext>>>);
+
+    impl Service<Request<()>> for Echo {
+        type Response = Response<tonic::body::Body>;
+        type Error = Infallible;
+        type Future = std::future::Ready<Result<Self::Response, Infallible>>;
+
+        fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Infallible>> {
+            Poll::Ready(Ok(()))
+        }
+
+        fn call(&mut self, req: Request<()>) -> Self::Future {
+            *self.0.lock().unwrap() = req.extensions().get::<TraceContext>().cloned();
+            std::future::ready(Ok(tonic::Status::ok("").into_http()))
+        }
+    }
+
+    async fn call(layer: &GrpcOtelLayer, echo: &Echo, path: &str) -> Response<tonic::body::Body> {
+        let request = Request::builder()
+            .uri(path)
+            .header(
+                "traceparent",
+                "00-0af7651916cd43dd8448eb211c80319c-b7ad6b7169203331-01",
+            )
+            .body(())
+            .unwrap();
+        let Ok(response) = layer.layer(echo.clone()).call(request).await;
+        response
+    }
+
+    #[tokio::test]
+    async fn propagates_trace_context_like_http() {
+        let echo = Echo::default();
+        let layer = GrpcOtelLayer::new(
+            OtelConfig::builder()
+                .service_name("users")
+                .exclude_path("/grpc.health.v1.Health")
+                .build(),
+        );
+
+        let response = call(&layer, &echo, "/users.v1.Users/GetUser").await;
+        assert_eq!(
+            response.headers()["x-trace-id"],
+            "0af7651916cd43dd8448eb211c80319c"
+        );
+        let seen = echo
+            .0
+            .lock()
+            .unwrap()
+            .take()
+            .expect("context in extensions");
+        assert_eq!(seen.trace_id, "0af7651916cd43dd8448eb211c80319c");
+
+        let response = call(&layer, &echo, "/grpc.health.v1.Health/Check").await;
+        assert!(response.headers().get("x-trace-id").is_none());
+        assert!(echo.0.lock().unwrap().is_none());
+    }
+
+    #[test]
+    fn only_server_failures_mark_errors() {
+        assert_eq!(span_status(Code::Ok), ("OK", false));
+        assert_eq!(span_status(Code::NotFound), ("UNSET", false));
+        assert_eq!(span_status(Code::Internal), ("ERROR", true));
+    }
+}
@@ -0,0 +1,686 @@
+//! Replay recording for gRPC calls.
+//!
+//! [`GrpcReplayLayer`] is the gRPC counterpart of the HTTP `ReplayLayer`: it
+//! records each call as a [`ReplayEntry`] in the same [`ReplayStore`], so gRPC
+//! traffic can be listed, inspected and diffed next to HTTP requests.
+//!
+//! An entry holds the method path (`/package.Service/Method`), the request
+//! and response metadata (redacted like HTTP headers, trailers included) and
+//! the call duration. Entries are tagged `protocol=grpc` and with the final
+//! `grpc_status`. Messages are stored as JSON when a [`DescriptorPool`] with
+//! the service is set; a streaming side is stored as an array of messages.
+//! Without descriptors, or for compressed messages, only body sizes are kept.
+//!
+//! The entry is written when the response stream ends (or is dropped), so
+//! streaming calls are recorded whole, up to the configured body limits.
+//!
+//! ```rust,ignore
+//! use rustapi_rs::extras::replay::ReplayLayer;
+//! use rustapi_rs::grpc::{GrpcReplayLayer, GrpcServices};
+//!
+//! let config = ReplayConfig::new().enabled(true).admin_token("secret");
+//! let http_replay = ReplayLayer::new(config.clone());
+//!
+//! // Share the store so the admin API lists both protocols
+//! let descriptors = DescriptorPool::decode(users::FILE_DESCRIPTOR_SET)?;
+//! let grpc_replay = GrpcReplayLayer::new(config, http_replay.store().clone())
+//!     .descriptors(descriptors);
+//!
+//! let grpc = GrpcServices::new(routes).replay(grpc_replay);
+//! ```
+
+use crate::BoxError;
+use bytes::{Buf, Bytes, BytesMut};
+use http::{HeaderMap, Request, Response};
+use http_body::{Body, Frame, SizeHint};
+use prost_reflect::{DescriptorPool, DynamicMessage, Kind, MapKey, MethodDescriptor};
+use rustapi_core::middleware::RequestId;
+use rustapi_core::replay::{
+    redact_body, redact_headers, RecordedRequest, RecordedResponse, ReplayConfig, ReplayEntry,
+    ReplayMeta, ReplayStore,
+};
+use serde_json::{Map, Value};
+use std::collections::HashMap;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll};
+use std::time::Instant;
+use tonic::{Code, Status};
+use tower_service::Service;
+
+/// Tower layer recording gRPC calls for replay
+///
+/// Uses the same [`ReplayConfig`] as the HTTP `ReplayLayer`: recording must
+/// be enabled and allowed by the current `Profile`, and the path filters,
+/// sampling, status filters, body limits and redaction rules apply.
+/// Retention is left to the HTTP layer sharing the store.
+#[derive(Clone)]
+pub struct GrpcReplayLayer {
+    config: Arc<ReplayConfig>,
+    store: Arc<dyn ReplayStore>,
+    descriptors: Option<DescriptorPool>,
+}
+
+impl GrpcReplayLayer {
+    /// Record calls into `store` according to `config`
+    pub fn new(config: ReplayConfig, store: Arc<dyn ReplayStore>) -> Self {
+        Self {
+            config: Arc::new(config),
+            store,
+            descriptors: None,
+        }
+    }
+
+    /// Decode messages to JSON with the services described in `pool`
+    pub fn descriptors(mut self, pool: DescriptorPool) -> Self {
+        self.descriptors = Some(pool);
+        self
+    }
+
+    /// Get a reference to the replay store.
+    pub fn store(&self) -> &Arc<dyn ReplayStore> {
+        &self.store
+    }
+
+    /// Whether a call to `path` should be recorded
+    fn should_record(&self, path: &str) -> bool {
+        self.config.enabled
+            && rustapi_core::Profile::current().replay_recording
+            && self.config.should_record_path(path)
+            && self.config.should_record_method("POST")
+            && self.config.should_sample_path(path)
+    }
+
+    /// Descriptor of the method at `/package.Service/Method`
+    fn method(&self, path: &str) -> Option<MethodDescriptor> {
+        let (service, method) = path.trim_start_matches('/').split_once('/')?;
+        self.descriptors
+            .as_ref()?
+            .get_service_by_name(service)?
+            .methods()
+            .find(|candidate| candidate.name() == method)
+    }
+}
+
+impl<S> tower_layer::Layer<S> for GrpcReplayLayer {
+    type Service = GrpcReplay<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        GrpcReplay {
+            inner,
+            layer: self.clone(),
+        }
+    }
+}
+
+/// Service created by [`GrpcReplayLayer`].
+#[derive(Clone)]
+pub struct GrpcReplay<S> {
+    inner: S,
+    layer: GrpcReplayLayer,
+}
+
+mod sealed {
+    pub trait Sealed {}
+
+    impl Sealed for tonic::body::Body {}
+    impl Sealed for tonic::service::AxumBody {}
+}
+
+/// Request bodies [`GrpcReplay`] can record: Tonic's, for
+/// `Server::builder().layer(..)`, and the one behind
+/// [`GrpcServices`](crate::GrpcServices).
+pub trait GrpcReplayBody: Body<Data = Bytes> + sealed::Sealed + Sized {
+    #[doc(hidden)]
+    fn wrap<B>(body: B) -> Self
+    where
+        B: Body<Data = Bytes> + Send + 'static,
+        B::Error: Into<BoxError>;
+}
+
+impl GrpcReplayBody for tonic::body::Body {
+    fn wrap<B>(body: B) -> Self
+    where
+        B: Body<Data = Bytes> + Send + 'static,
+        B::Error: Into<BoxError>,
+    {
+        tonic::body::Body::new(body)
+    }
+}
+
+impl GrpcReplayBody for tonic::service::AxumBody {
+    fn wrap<B>(body: B) -> Self
+    where
+        B: Body<Data = Bytes> + Send + 'static,
+        B::Error: Into<BoxError>,
+    {
+        tonic::service::AxumBody::new(body)
+    }
+}
+
+impl<S, B, ResBody> Service<Request<B>> for GrpcReplay<S>
+where
+    S: Service<Request<B>, Response = Response<ResBody>>,
+    S::Future: Send + 'static,
+    B: GrpcReplayBody + Send + 'static,
+    B::Error: Into<BoxError>,
+    ResBody: Body<Data = Bytes> + Send + 'static,
+    ResBody::Error: Into<BoxError>,
+{
+    type Response = Response<tonic::body::Body>;
+    type Error = S::Error;
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response, S::Error>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), S::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, req: Request<B>) -> Self::Future {
+        let path = req.uri().path().to_string();
+        if !self.layer.should_record(&path) {
+            let future = self.inner.call(req);
+            return Box::pin(async move { Ok(future.await?.map(tonic::body::Body::new)) });
+        }
+
+        let config = &self.layer.config;
+        let request_id = req
+            .extensions()
+            .get::<RequestId>()
+            .map(|id| id.as_str().to_string())
+            .or_else(|| header_str(req.headers(), "x-request-id"));
+        let client_ip = req
+            .extensions()
+            .get::<tonic::transport::server::TcpConnectInfo>()
+            .and_then(|info| info.remote_addr())
+            .map(|addr| addr.ip().to_string());
+        let request = RecordedRequest {
+            method: req.method().to_string(),
+            uri: req.uri().to_string(),
+            path: path.clone(),
+            query: None,
+            headers: redact_headers(&capture_headers(req.headers()), &config.redact_headers),
+            body: None,
+            body_size: 0,
+            body_truncated: false,
+        };
+
+        let shared = Arc::new(Mutex::new(Messages {
+            request: Capture::new(config.max_request_body),
+            response: Capture::new(config.max_response_body),
+        }));
+        let req = req.map(|body| {
+            B::wrap(CaptureBody {
+                inner: Box::pin(body),
+                messages: shared.clone(),
+            })
+        });
+
+        let mut recording = Recording {
+            layer: self.layer.clone(),
+            method: self.layer.method(&path),
+            request,
+            request_id,
+            client_ip,
+            start: Instant::now(),
+            status: 0,
+            headers: HeaderMap::new(),
+            code: None,
+            messages: shared,
+        };
+
+        let future = self.inner.call(req);
+        Box::pin(async move {
+            let response = future.await?;
+            let (parts, body) = response.into_parts();
+            recording.status = parts.status.as_u16();
+            recording.headers = parts.headers.clone();
+            // Trailers-only responses carry the status in the headers
+            recording.observe(&parts.headers);
+
+            Ok(Response::from_parts(
+                parts,
+                tonic::body::Body::new(RecordingBody {
+                    inner: Box::pin(body),
+                    recording: Some(recording),
+                }),
+            ))
+        })
+    }
+}
+
+fn header_str(headers: &HeaderMap, name: &str) -> Option<String> {
+    headers
+        .get(name)
+        .and_then(|value| value.to_str().ok())
+        .map(str::to_string)
+}
+
+fn capture_headers(headers: &HeaderMap) -> HashMap<String, String> {
+    headers
+        .iter()
+        .filter_map(|(name, value)| Some((name.to_string(), value.to_str().ok()?.to_string())))
+        .collect()
+}
+
+/// Raw bytes of one direction of a call, kept up to a limit
+struct Capture {
+    bytes: BytesMut,
+    size: usize,
+    limit: usize,
+}
+
+impl Capture {
+    fn new(limit: usize) -> Self {
+        Self {
+            bytes: BytesMut::new(),
+            size: 0,
+            limit,
+        }
+    }
+
+    fn push(&mut self, data: &Bytes) {
+        self.size += data.len();
+        let room = self.limit.saturating_sub(self.bytes.len());
+        self.bytes.extend_from_slice(&data[..data.len().min(room)]);
+    }
+
+    fn truncated(&self) -> bool {
+        self.size > self.bytes.len()
+    }
+
+    /// The complete length-prefixed messages, or `None` if one is compressed
+    fn messages(&self) -> Option<Vec<Bytes>> {
+        let mut buf = self.bytes.clone().freeze();
+        let mut messages = Vec::new();
+        while buf.len() >= 5 {
+            let compressed = buf[0] != 0;
+            let len = u32::from_be_bytes([buf[1], buf[2], buf[3], buf[4]]) as usize;
+            if buf.len() < 5 + len {
+                break;
+            }
+            if compressed {
+                return None;
+            }
+            buf.advance(5);
+            messages.push(buf.split_to(len));
+        }
+        Some(messages)
+    }
+}
+
+/// Message bytes of both directions of a call
+struct Messages {
+    request: Capture,
+    response: Capture,
+}
+
+/// Request body copying the messages it yields
+struct CaptureBody<B> {
+    inner: Pin<Box<B>>,
+    messages: Arc<Mutex<Messages>>,
+}
+
+impl<B: Body<Data = Bytes>> Body for CaptureBody<B> {
+    type Data = Bytes;
+    type Error = B::Error;
+
+    fn poll_frame(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Result<Frame<Self::Data>, Self::Error>>> {
+        let polled = self.inner.as_mut().poll_frame(cx);
+        if let Poll::Ready(Some(Ok(frame))) = &polled {
+            if let Some(data) = frame.data_ref() {
+                lock(&self.messages).request.push(data);
+            }
+        }
+        polled
+    }
+
+    fn is_end_stream(&self) -> bool {
+        self.inner.is_end_stream()
+    }
+
+    fn size_hint(&self) -> SizeHint {
+        self.inner.size_hint()
+    }
+}
+
+fn lock(messages: &Mutex<Messages>) -> std::sync::MutexGuard<'_, Messages> {
+    messages.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+/// A call being recorded; stored exactly once, when dropped
+struct Recording {
+    layer: GrpcReplayLayer,
+    method: Option<MethodDescriptor>,
+    request: RecordedRequest,
+    request_id: Option<String>,
+    client_ip: Option<String>,
+    start: Instant,
+    status: u16,
+    headers: HeaderMap,
+    code: Option<Code>,
+    messages: Arc<Mutex<Messages>>,
+}
+
+impl Recording {
+    fn observe(&mut self, headers: &HeaderMap) {
+        if let Some(status) = Status::from_header_map(headers) {
+            self.code = Some(status.code());
+        }
+    }
+
+    /// JSON of the captured messages of one direction, redacted
+    fn body(&self, capture: &Capture, request: bool) -> Option<String> {
+        let method = self.method.as_ref()?;
+        let (descriptor, streaming) = if request {
+            (method.input(), method.is_client_streaming())
+        } else {
+            (method.output(), method.is_server_streaming())
+        };
+
+        let mut values = Vec::new();
+        for message in capture.messages()? {
+            let message = DynamicMessage::decode(descriptor.clone(), message).ok()?;
+            values.push(message_json(&message));
+        }
+        let value = match (streaming, values.len()) {
+            (true, _) => Value::Array(values),
+            (false, 1) => values.pop()?,
+            (false, _) => return None,
+        };
+
+        let body = value.to_string();
+        let fields = &self.layer.config.redact_body_fields;
+        if fields.is_empty() {
+            Some(body)
+        } else {
+            redact_body(&body, fields, "[REDACTED]")
+        }
+    }
+
+    /// Store the call, unless its status is filtered out
+    fn finish(self) {
+        if !self.layer.config.should_record_status(self.status) {
+            return;
+        }
+        let Ok(runtime) = tokio::runtime::Handle::try_current() else {
+            return;
+        };
+
+        let store = self.layer.store.clone();
+        let entry = self.into_entry();
+        // Store asynchronously, without holding up the response stream
+        runtime.spawn(async move {
+            if let Err(e) = store.store(entry).await {
+                tracing::warn!(error = %e, "Failed to store replay entry");
+            }
+        });
+    }
+
+    fn into_entry(mut self) -> ReplayEntry {
+        let code = self.code.unwrap_or(Code::Cancelled);
+        let messages = self.messages.clone();
+        let messages = lock(&messages);
+        let config = &self.layer.config;
+
+        self.request.body = self.body(&messages.request, true);
+        self.request.body_size = messages.request.size;
+        self.request.body_truncated = messages.request.truncated();
+        let response = RecordedResponse {
+            status: self.status,
+            headers: redact_headers(&capture_headers(&self.headers), &config.redact_headers),
+            body: self.body(&messages.response, false),
+            body_size: messages.response.size,
+            body_truncated: messages.response.truncated(),
+        };
+
+        let mut meta = ReplayMeta::new()
+            .with_duration_ms(self.start.elapsed().as_millis() as u64)
+            .with_route_pattern(self.request.path.clone())
+            .with_ttl_secs(config.ttl_secs)
+            .with_tag("protocol", "grpc")
+            .with_tag("grpc_status", (code as i32).to_string());
+        if let Some(request_id) = self.request_id.take() {
+            meta = meta.with_request_id(request_id);
+        }
+        if let Some(client_ip) = self.client_ip.take() {
+            meta = meta.with_client_ip(client_ip);
+        }
+
+        drop(messages);
+        ReplayEntry::new(self.request, response, meta)
+    }
+}
+
+/// Response body recording the call once its stream ends (or is dropped)
+struct RecordingBody<B> {
+    inner: Pin<Box<B>>,
+    recording: Option<Recording>,
+}
+
+impl<B> RecordingBody<B> {
+    fn finish(&mut self) {
+        if let Some(recording) = self.recording.take() {
+            recording.finish();
+        }
+    }
+}
+
+impl<B: Body<Data = Bytes>> Body for RecordingBody<B> {
+    type Data = Bytes;
+    type Error = B::Error;
+
+    fn poll_frame(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Result<Frame<Self::Data>, Self::Error>>> {
+        let polled = self.inner.as_mut().poll_frame(cx);
+        match &polled {
+            Poll::Ready(Some(Ok(frame))) => {
+                if let Some(recording) = self.recording.as_mut() {
+                    if let Some(data) = frame.data_ref() {
+                        lock(&recording.messages).response.push(data);
+                    }
+                    if let Some(trailers) = frame.trailers_ref() {
+                        recording.observe(trailers);
+                        recording.headers.extend(trailers.clone());
+                    }
+                }
+            }
+            Poll::Ready(Some(Err(_))) => {
+                if let Some(recording) = self.recording.as_mut() {
+                    recording.code = Some(Code::Unknown);
+                }
+                self.finish();
+            }
+            Poll::Ready(None) => self.finish(),
+            Poll::Pending => {}
+        }
+        polled
+    }
+
+    fn is_end_stream(&self) -> bool {
+        self.inner.is_end_stream()
+    }
+
+    fn size_hint(&self) -> SizeHint {
+        self.inner.size_hint()
+    }
+}
+
+impl<B> Drop for RecordingBody<B> {
+    fn drop(&mut self) {
+        // A stream dropped before its trailers was cancelled
+        self.finish();
+    }
+}
+
+/// A message in the protobuf JSON mapping (64-bit integers as strings,
+/// bytes as base64, enums by name)
+fn message_json(message: &DynamicMessage) -> Value {
+    let object = message
+        .fields()
+        .map(|(field, value)| {
+            (
+                field.json_name().to_string(),
+                value_json(value, &field.kind()),
+            )
+        })
+        .collect();
+    Value::Object(object)
+}
+
+fn value_json(value: &prost_reflect::Value, kind: &Kind) -> Value {
+    use base64::Engine;
+    use prost_reflect::Value as V;
+
+    match value {
+        V::Bool(v) => Value::Bool(*v),
+        V::I32(v) => Value::from(*v),
+        V::U32(v) => Value::from(*v),
+        V::I64(v) => Value::String(v.to_string()),
+        V::U64(v) => Value::String(v.to_string()),
+        V::F32(v) => float_json(f64::from(*v)),
+        V::F64(v) => float_json(*v),
+        V::String(v) => Value::String(v.clone()),
+        V::Bytes(v) => Value::String(base64::engine::general_purpose::STANDARD.encode(v)),
+        V::EnumNumber(number) => kind
+            .as_enum()
+            .and_then(|descriptor| descriptor.get_value(*number))
+            .map(|value| Value::String(value.name().to_string()))
+            .unwrap_or_else(|| Value::from(*number)),
+        V::Message(message) => message_json(message),
+        V::List(items) => Value::Array(items.iter().map(|item| value_json(item, kind)).collect()),
+        V::Map(entries) => {
+            let value_kind = kind
+                .as_message()
+                .map(|entry| entry.map_entry_value_field().kind())
+                .unwrap_or_else(|| kind.clone());
+            let object: Map<String, Value> = entries
+                .iter()
+                .map(|(key, value)| (map_key(key), value_json(value, &value_kind)))
+                .collect();
+            Value::Object(object)
+        }
+    }
+}
+
+fn float_json(v: f64) -> Value {
+    serde_json::Number::from_f64(v)
+        .map(Value::Number)
+        .unwrap_or_else(|| Value::String(v.to_string()))
+}
+
+fn map_key(key: &MapKey) -> String {
+    match key {
+        MapKey::Bool(v) => v.to_string(),
+        MapKey::I32(v) => v.to_string(),
+        MapKey::I64(v) => v.to_string(),
+        MapKey::U32(v) => v.to_string(),
+        MapKey::U64(v) => v.to_string(),
+        MapKey::String(v) => v.clone(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use http_body_util::BodyExt;
+    use prost::Message;
+    use rustapi_core::replay::ReplayQuery;
+    use rustapi_extras::replay::InMemoryReplayStore;
+    use tonic::service::Routes;
+    use tonic_health::pb::HealthCheckRequest;
+    use tonic_health::server::health_reporter;
+    use tower_layer::Layer;
+
+    fn frame(message: &impl Message) -> Bytes {
+        let encoded = message.encode_to_vec();
+        let mut frame = BytesMut::with_capacity(5 + encoded.len());
+        frame.extend_from_slice(&[0]);
+        frame.extend_from_slice(&(encoded.len() as u32).to_be_bytes());
+        frame.extend_from_slice(&encoded);
+        frame.freeze()
+    }
+
+    /// Make a call through a recorded health service and return its entry
+    async fn record(layer: GrpcReplayLayer, path: &str) -> ReplayEntry {
+        let (reporter, health) = health_reporter();
+        reporter
+            .set_service_status("users", tonic_health::ServingStatus::Serving)
+            .await;
+        let mut service = layer.layer(Routes::new(health));
+
+        let request = HealthCheckRequest {
+            service: "users".into(),
+        };
+        let req = Request::builder()
+            .method("POST")
+            .uri(format!("http://localhost{path}"))
+            .header("content-type", "application/grpc")
+            .header("authorization", "Bearer secret")
+            .header("x-request-id", "req-1")
+            .body(tonic::body::Body::new(http_body_util::Full::new(frame(
+                &request,
+            ))))
+            .unwrap();
+        let response = crate::multiplex::call_tonic(&mut service, req).await;
+        response.into_body().collect().await.unwrap();
+
+        let store = layer.store().clone();
+        for _ in 0..100 {
+            let entries = store.list(&ReplayQuery::new()).await.unwrap();
+            if let Some(entry) = entries.into_iter().next() {
+                return entry;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+        }
+        panic!("no replay entry was stored");
+    }
+
+    fn layer() -> GrpcReplayLayer {
+        GrpcReplayLayer::new(
+            ReplayConfig::new().enabled(true),
+            Arc::new(InMemoryReplayStore::new(10)),
+        )
+    }
+
+    #[tokio::test]
+    async fn records_calls_with_messages_decoded_by_descriptors() {
+        let pool = DescriptorPool::decode(tonic_health::pb::FILE_DESCRIPTOR_SET).unwrap();
+        let entry = record(layer().descriptors(pool), "/grpc.health.v1.Health/Check").await;
+
+        assert_eq!(entry.request.path, "/grpc.health.v1.Health/Check");
+        assert_eq!(
+            entry.request.body.as_deref(),
+            Some(r#"{"service":"users"}"#)
+        );
+        assert_eq!(entry.request.headers["authorization"], "[REDACTED]");
+        assert_eq!(
+            entry.response.body.as_deref(),
+            Some(r#"{"status":"SERVING"}"#)
+        );
+        assert_eq!(entry.response.headers["grpc-status"], "0");
+        assert_eq!(entry.meta.tags["protocol"], "grpc");
+        assert_eq!(entry.meta.tags["grpc_status"], "0");
+        assert_eq!(entry.meta.request_id.as_deref(), Some("req-1"));
+    }
+
+    #[tokio::test]
+    async fn records_sizes_and_status_without_descriptors() {
+        let entry = record(layer(), "/grpc.health.v1.Health/Check").await;
+        assert_eq!(entry.request.body, None);
+        assert_eq!(entry.request.body_size, 12);
+        assert_eq!(entry.response.body, None);
+        assert_eq!(entry.response.body_size, 7);
+
+        let entry = record(layer(), "/grpc.health.v1.Health/Missing").await;
+        assert_eq!(
+            entry.meta.tags["grpc_status"],
+            (Code::Unimplemented as i32).to_string()
+        );
+    }
+}
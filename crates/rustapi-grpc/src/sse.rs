@@ -0,0 +1,337 @@
+//! Bridging gRPC server streams and Server-Sent Events.
+//!
+//! Browsers without a gRPC-Web client can still follow a server-streaming
+//! RPC: [`sse_from_grpc`] turns the stream of messages into an [`Sse`]
+//! response, one JSON `data:` event per message with sequential `id`s. A
+//! failing stream ends with an `error` event carrying the gRPC status
+//! (`{"code": 5, "message": "..."}`) instead of a dropped connection.
+//!
+//! ```rust,ignore
+//! use rustapi_rs::grpc::{sse_from_grpc, GrpcEvents};
+//! use rustapi_rs::prelude::*;
+//!
+//! // Server side: call the service implementation directly...
+//! async fn quotes(State(svc): State<QuoteService>) -> impl IntoResponse {
+//!     let stream = svc.subscribe(tonic::Request::new(Filter::default())).await?.into_inner();
+//!     Ok::<_, ApiError>(sse_from_grpc(stream))
+//! }
+//!
+//! // ...or relay a remote service, resuming after `Last-Event-ID`
+//! async fn relay(last: LastEventId) -> impl IntoResponse {
+//!     let stream = client.subscribe(Filter::default()).await?.into_inner();
+//!     let first_id = last.parse::<u64>().map_or(1, |id| id + 1);
+//!     Ok::<_, ApiError>(Sse::new(GrpcEvents::new(stream).event("quote").first_id(first_id)))
+//! }
+//! ```
+//!
+//! On the client side, [`grpc_stream_from_sse`] reads such an event stream
+//! back into the `Result<T, Status>` items a Tonic client would yield, so
+//! the same consumer code works over both transports.
+
+use bytes::Bytes;
+use futures_util::Stream;
+use rustapi_core::{Sse, SseEvent};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::convert::Infallible;
+use std::fmt::Display;
+use std::marker::PhantomData;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use tonic::{Code, Status};
+
+/// SSE event name used for a terminal gRPC status.
+pub const SSE_ERROR_EVENT: &str = "error";
+
+/// Payload of the terminal `error` event.
+#[derive(Serialize, Deserialize)]
+struct StatusPayload {
+    code: i32,
+    message: String,
+}
+
+/// Serve a gRPC message stream as Server-Sent Events.
+///
+/// Shorthand for `Sse::new(GrpcEvents::new(stream))`.
+pub fn sse_from_grpc<S, T>(stream: S) -> Sse<GrpcEvents<S>>
+where
+    S: Stream<Item = Result<T, Status>>,
+    T: Serialize,
+{
+    Sse::new(GrpcEvents::new(stream))
+}
+
+/// SSE events for a gRPC message stream.
+///
+/// Created by [`GrpcEvents::new`]; usually wrapped in [`Sse`].
+pub struct GrpcEvents<S> {
+    inner: Pin<Box<S>>,
+    event: Option<String>,
+    next_id: Option<u64>,
+    finished: bool,
+}
+
+impl<S> GrpcEvents<S> {
+    /// Convert each message of `stream` to a JSON `data:` event.
+    pub fn new(stream: S) -> Self {
+        Self {
+            inner: Box::pin(stream),
+            event: None,
+            next_id: Some(1),
+            finished: false,
+        }
+    }
+
+    /// Name message events (`event: <name>`) instead of the default `message`
+    pub fn event(mut self, name: impl Into<String>) -> Self {
+        self.event = Some(name.into());
+        self
+    }
+
+    /// Number events from `id` instead of 1 (e.g. after a `Last-Event-ID`)
+    pub fn first_id(mut self, id: u64) -> Self {
+        self.next_id = Some(id);
+        self
+    }
+
+    /// Send events without an `id`
+    pub fn without_ids(mut self) -> Self {
+        self.next_id = None;
+        self
+    }
+
+    fn status_event(&mut self, status: &Status) -> SseEvent {
+        self.finished = true;
+        let payload = StatusPayload {
+            code: status.code() as i32,
+            message: status.message().to_string(),
+        };
+        SseEvent::json_data(&payload)
+            .unwrap_or_else(|_| SseEvent::new(status.message()))
+            .event(SSE_ERROR_EVENT)
+    }
+}
+
+impl<S, T> Stream for GrpcEvents<S>
+where
+    S: Stream<Item = Result<T, Status>>,
+    T: Serialize,
+{
+    type Item = Result<SseEvent, Infallible>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        if self.finished {
+            return Poll::Ready(None);
+        }
+
+        let message = match self.inner.as_mut().poll_next(cx) {
+            Poll::Ready(Some(Ok(message))) => message,
+            Poll::Ready(Some(Err(status))) => {
+                return Poll::Ready(Some(Ok(self.status_event(&status))));
+            }
+            Poll::Ready(None) => {
+                self.finished = true;
+                return Poll::Ready(None);
+            }
+            Poll::Pending => return Poll::Pending,
+        };
+
+        let mut event = match SseEvent::json_data(&message) {
+            Ok(event) => event,
+            Err(e) => {
+                let status = Status::internal(format!("Failed to encode message: {}", e));
+                return Poll::Ready(Some(Ok(self.status_event(&status))));
+            }
+        };
+        if let Some(name) = &self.event {
+            event = event.event(name.clone());
+        }
+        if let Some(id) = self.next_id {
+            event = event.id(id.to_string());
+            self.next_id = Some(id + 1);
+        }
+        Poll::Ready(Some(Ok(event)))
+    }
+}
+
+/// Read a Server-Sent Events byte stream produced by [`sse_from_grpc`] back
+/// into gRPC-style messages.
+///
+/// `events` is the response body as a byte stream (e.g. `bytes_stream()` of
+/// an HTTP client response). Each `data:` event is decoded from JSON; an
+/// `error` event ends the stream with its status, and transport errors end
+/// it with `UNAVAILABLE`.
+pub fn grpc_stream_from_sse<T, S, E>(events: S) -> SseMessages<S, T>
+where
+    S: Stream<Item = Result<Bytes, E>>,
+    E: Display,
+    T: DeserializeOwned,
+{
+    SseMessages {
+        inner: Box::pin(events),
+        buffer: Vec::new(),
+        event: None,
+        data: None,
+        finished: false,
+        _message: PhantomData,
+    }
+}
+
+/// Messages decoded from a Server-Sent Events stream.
+///
+/// Created by [`grpc_stream_from_sse`].
+pub struct SseMessages<S, T> {
+    inner: Pin<Box<S>>,
+    buffer: Vec<u8>,
+    event: Option<String>,
+    data: Option<String>,
+    finished: bool,
+    _message: PhantomData<fn() -> T>,
+}
+
+impl<S, T: DeserializeOwned> SseMessages<S, T> {
+    /// Parse buffered lines until an event is complete.
+    fn next_event(&mut self) -> Option<Result<T, Status>> {
+        while let Some(end) = self.buffer.iter().position(|&b| b == b'\n') {
+            let line: Vec<u8> = self.buffer.drain(..=end).collect();
+            let line = String::from_utf8_lossy(&line);
+            let line = line.trim_end_matches(['\n', '\r']);
+
+            if line.is_empty() {
+                let event = self.event.take();
+                let Some(data) = self.data.take() else {
+                    continue;
+                };
+                return Some(self.decode(event.as_deref(), &data));
+            }
+
+            let (field, value) = line.split_once(':').unwrap_or((line, ""));
+            let value = value.strip_prefix(' ').unwrap_or(value);
+            match field {
+                "event" => self.event = Some(value.to_string()),
+                "data" => match &mut self.data {
+                    Some(data) => {
+                        data.push('\n');
+                        data.push_str(value);
+                    }
+                    None => self.data = Some(value.to_string()),
+                },
+                // Comments (keep-alive), `id` and `retry` carry no message
+                _ => {}
+            }
+        }
+        None
+    }
+
+    fn decode(&mut self, event: Option<&str>, data: &str) -> Result<T, Status> {
+        if event == Some(SSE_ERROR_EVENT) {
+            self.finished = true;
+            let status = match serde_json::from_str::<StatusPayload>(data) {
+                Ok(payload) => Status::new(Code::from_i32(payload.code), payload.message),
+                Err(_) => Status::unknown(data.to_string()),
+            };
+            return Err(status);
+        }
+        serde_json::from_str(data)
+            .map_err(|e| Status::internal(format!("Failed to decode message: {}", e)))
+    }
+}
+
+impl<S, T, E> Stream for SseMessages<S, T>
+where
+    S: Stream<Item = Result<Bytes, E>>,
+    E: Display,
+    T: DeserializeOwned,
+{
+    type Item = Result<T, Status>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        loop {
+            if self.finished {
+                return Poll::Ready(None);
+            }
+            if let Some(message) = self.next_event() {
+                return Poll::Ready(Some(message));
+            }
+
+            match self.inner.as_mut().poll_next(cx) {
+                Poll::Ready(Some(Ok(chunk))) => self.buffer.extend_from_slice(&chunk),
+                Poll::Ready(Some(Err(e))) => {
+                    self.finished = true;
+                    return Poll::Ready(Some(Err(Status::unavailable(e.to_string()))));
+                }
+                // An incomplete trailing event is discarded, as in browsers
+                Poll::Ready(None) => {
+                    self.finished = true;
+                    return Poll::Ready(None);
+                }
+                Poll::Pending => return Poll::Pending,
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures_util::{stream, StreamExt};
+    use http_body_util::BodyExt;
+    use rustapi_core::IntoResponse;
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Quote {
+        symbol: String,
+        price: u32,
+    }
+
+    fn quote(price: u32) -> Quote {
+        Quote {
+            symbol: "RUST".to_string(),
+            price,
+        }
+    }
+
+    async fn sse_body(events: Vec<Result<Quote, Status>>) -> Bytes {
+        let response = sse_from_grpc(stream::iter(events)).into_response();
+        assert_eq!(response.headers()["content-type"], "text/event-stream");
+        response.into_body().collect().await.unwrap().to_bytes()
+    }
+
+    #[tokio::test]
+    async fn grpc_stream_becomes_sse_events() {
+        let body = sse_body(vec![
+            Ok(quote(1)),
+            Ok(quote(2)),
+            Err(Status::not_found("no more quotes")),
+            Ok(quote(3)),
+        ])
+        .await;
+
+        assert_eq!(
+            body,
+            "id: 1\ndata: {\"symbol\":\"RUST\",\"price\":1}\n\n\
+             id: 2\ndata: {\"symbol\":\"RUST\",\"price\":2}\n\n\
+             event: error\ndata: {\"code\":5,\"message\":\"no more quotes\"}\n\n"
+        );
+    }
+
+    #[tokio::test]
+    async fn sse_events_round_trip_to_grpc_messages() {
+        let body = sse_body(vec![Ok(quote(1)), Err(Status::unavailable("gone"))]).await;
+
+        // A keep-alive comment and an event split mid-line
+        let chunks = vec![
+            Bytes::from_static(b": ping\n\n"),
+            body.slice(..10),
+            body.slice(10..),
+        ];
+        let bytes = stream::iter(chunks.into_iter().map(Ok::<_, Infallible>));
+
+        let messages: Vec<Result<Quote, Status>> = grpc_stream_from_sse(bytes).collect().await;
+        assert_eq!(messages.len(), 2);
+        assert_eq!(messages[0].as_ref().unwrap(), &quote(1));
+        let status = messages[1].as_ref().unwrap_err();
+        assert_eq!(status.code(), Code::Unavailable);
+        assert_eq!(status.message(), "gone");
+    }
+}
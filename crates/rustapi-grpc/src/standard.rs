@@ -0,0 +1,344 @@
+//! Standard gRPC services: health checking and server reflection.
+//!
+//! Almost every gRPC binary registers `grpc.health.v1.Health` and the
+//! reflection service, then keeps the health status in sync by hand.
+//! [`standard_services`] builds both as Tonic [`Routes`] and returns a
+//! [`GrpcHealth`] handle that:
+//!
+//! - follows a RustAPI [`HealthCheck`]: `NOT_SERVING` while it reports
+//!   unhealthy, `SERVING` otherwise (degraded still serves);
+//! - switches every service to `NOT_SERVING` while draining, so load
+//!   balancers stop routing new calls before the server exits.
+//!
+//! ```rust,ignore
+//! use rustapi_rs::grpc::{run_rustapi_and_grpc_with_shutdown, standard_services, tonic};
+//! use std::time::Duration;
+//!
+//! let (routes, health) = standard_services()
+//!     .file_descriptor_set(proto::FILE_DESCRIPTOR_SET)
+//!     .serving::<GreeterServer<MyGreeter>>()
+//!     .health_check(health_check.clone())
+//!     .build()
+//!     .await?;
+//! let routes = routes.add_service(GreeterServer::new(MyGreeter::default()));
+//! let grpc_addr = "0.0.0.0:50051".parse()?;
+//!
+//! run_rustapi_and_grpc_with_shutdown(
+//!     app.with_health_check(health_check),
+//!     "0.0.0.0:8080",
+//!     health.draining_signal(async { let _ = tokio::signal::ctrl_c().await; }, Duration::from_secs(5)),
+//!     move |shutdown| {
+//!         tonic::transport::Server::builder()
+//!             .add_routes(routes)
+//!             .serve_with_shutdown(grpc_addr, shutdown)
+//!     },
+//! )
+//! .await?;
+//! ```
+
+use crate::Result;
+use rustapi_core::health::HealthCheck;
+use std::future::Future;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tonic::server::NamedService;
+use tonic::service::Routes;
+use tonic_health::server::HealthReporter;
+use tonic_health::ServingStatus;
+
+/// Default interval between RustAPI health check evaluations.
+pub const DEFAULT_HEALTH_POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Start configuring the standard health and reflection services.
+pub fn standard_services<'a>() -> StandardServices<'a> {
+    StandardServices::default()
+}
+
+/// Builder for the standard health and reflection services.
+///
+/// Created by [`standard_services`].
+pub struct StandardServices<'a> {
+    descriptor_sets: Vec<&'a [u8]>,
+    service_names: Vec<String>,
+    reflection: bool,
+    health_check: Option<HealthCheck>,
+    poll_interval: Duration,
+}
+
+impl Default for StandardServices<'_> {
+    fn default() -> Self {
+        Self {
+            descriptor_sets: Vec::new(),
+            service_names: Vec::new(),
+            reflection: true,
+            health_check: None,
+            poll_interval: DEFAULT_HEALTH_POLL_INTERVAL,
+        }
+    }
+}
+
+impl<'a> StandardServices<'a> {
+    /// Register an encoded `FileDescriptorSet` for reflection.
+    ///
+    /// Generated by `tonic-prost-build` with `file_descriptor_set_path`, and
+    /// usually included with `tonic::include_file_descriptor_set!`.
+    pub fn file_descriptor_set(mut self, encoded: &'a [u8]) -> Self {
+        self.descriptor_sets.push(encoded);
+        self
+    }
+
+    /// Report health for the Tonic service `S` (by its gRPC name).
+    pub fn serving<S: NamedService>(self) -> Self {
+        self.service_name(S::NAME)
+    }
+
+    /// Report health for a service by its fully-qualified gRPC name.
+    pub fn service_name(mut self, name: impl Into<String>) -> Self {
+        self.service_names.push(name.into());
+        self
+    }
+
+    /// Disable the reflection service (enabled by default).
+    pub fn without_reflection(mut self) -> Self {
+        self.reflection = false;
+        self
+    }
+
+    /// Derive the serving status from a RustAPI health check.
+    ///
+    /// The check is evaluated once during [`build`](Self::build) and then
+    /// every [`poll_interval`](Self::poll_interval) until draining starts.
+    pub fn health_check(mut self, health_check: HealthCheck) -> Self {
+        self.health_check = Some(health_check);
+        self
+    }
+
+    /// Set how often the health check is evaluated.
+    pub fn poll_interval(mut self, interval: Duration) -> Self {
+        self.poll_interval = interval;
+        self
+    }
+
+    /// Build the services as Tonic routes, plus a handle to their health.
+    ///
+    /// Add your own services with [`Routes::add_service`], then serve the
+    /// routes with `Server::builder().add_routes(routes)` or the single-port
+    /// server. Fails if a file descriptor set cannot be decoded.
+    pub async fn build(self) -> Result<(Routes, GrpcHealth)> {
+        let (reporter, health_service) = tonic_health::server::health_reporter();
+        let mut routes = Routes::new(health_service);
+
+        if self.reflection {
+            let reflection = self
+                .descriptor_sets
+                .iter()
+                .fold(
+                    tonic_reflection::server::Builder::configure()
+                        .register_encoded_file_descriptor_set(
+                            tonic_health::pb::FILE_DESCRIPTOR_SET,
+                        ),
+                    |builder, set| builder.register_encoded_file_descriptor_set(set),
+                )
+                .build_v1()?;
+            routes = routes.add_service(reflection);
+        }
+
+        let health = GrpcHealth {
+            reporter,
+            service_names: Arc::new(self.service_names),
+            draining: Arc::new(AtomicBool::new(false)),
+        };
+        health.set_all(ServingStatus::Serving).await;
+
+        if let Some(health_check) = self.health_check {
+            health.sync(&health_check).await;
+
+            let handle = health.clone();
+            let interval = self.poll_interval;
+            tokio::spawn(async move {
+                let mut ticker = tokio::time::interval(interval);
+                ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+                ticker.tick().await;
+                loop {
+                    ticker.tick().await;
+                    if handle.is_draining() {
+                        break;
+                    }
+                    handle.sync(&health_check).await;
+                }
+            });
+        }
+
+        Ok((routes, health))
+    }
+}
+
+/// Handle to the serving status reported by the standard health service.
+///
+/// Cheap to clone; all clones share the same state.
+#[derive(Clone, Debug)]
+pub struct GrpcHealth {
+    reporter: HealthReporter,
+    service_names: Arc<Vec<String>>,
+    draining: Arc<AtomicBool>,
+}
+
+impl GrpcHealth {
+    /// The underlying Tonic health reporter, for per-service updates.
+    pub fn reporter(&self) -> &HealthReporter {
+        &self.reporter
+    }
+
+    /// Check whether draining has started.
+    pub fn is_draining(&self) -> bool {
+        self.draining.load(Ordering::SeqCst)
+    }
+
+    /// Report every registered service (and the server) as `NOT_SERVING`.
+    ///
+    /// Draining is permanent: health check results no longer change the
+    /// status afterwards.
+    pub async fn drain(&self) {
+        self.draining.store(true, Ordering::SeqCst);
+        self.set_all(ServingStatus::NotServing).await;
+    }
+
+    /// Wrap a shutdown signal so the services drain before shutting down.
+    ///
+    /// When `signal` completes, the status switches to `NOT_SERVING` and the
+    /// returned future completes `grace` later, giving load balancers time to
+    /// stop routing new calls while in-flight ones finish.
+    pub fn draining_signal<F>(&self, signal: F, grace: Duration) -> impl Future<Output = ()> + Send
+    where
+        F: Future<Output = ()> + Send,
+    {
+        let health = self.clone();
+        async move {
+            signal.await;
+            health.drain().await;
+            tokio::time::sleep(grace).await;
+        }
+    }
+
+    async fn sync(&self, health_check: &HealthCheck) {
+        let status = if health_check.execute().await.status.is_unhealthy() {
+            ServingStatus::NotServing
+        } else {
+            ServingStatus::Serving
+        };
+        // A drain may have started while the check was running
+        if !self.is_draining() {
+            self.set_all(status).await;
+        }
+    }
+
+    async fn set_all(&self, status: ServingStatus) {
+        self.reporter.set_service_status("", status).await;
+        for name in self.service_names.iter() {
+            self.reporter.set_service_status(name, status).await;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rustapi_core::health::{HealthCheckBuilder, HealthStatus};
+    use tokio::time::{sleep, timeout};
+    use tonic_health::pb::health_server::Health;
+    use tonic_health::pb::HealthCheckRequest;
+    use tonic_health::server::HealthService;
+
+    async fn status(health: &GrpcHealth, service: &str) -> i32 {
+        HealthService::from_health_reporter(health.reporter().clone())
+            .check(tonic::Request::new(HealthCheckRequest {
+                service: service.to_string(),
+            }))
+            .await
+            .unwrap()
+            .into_inner()
+            .status
+    }
+
+    #[tokio::test]
+    async fn status_follows_rustapi_health_check() {
+        let healthy = Arc::new(AtomicBool::new(false));
+        let flag = healthy.clone();
+        let health_check = HealthCheckBuilder::new(false)
+            .add_check("db", move || {
+                let healthy = flag.load(Ordering::SeqCst);
+                async move {
+                    if healthy {
+                        HealthStatus::healthy()
+                    } else {
+                        HealthStatus::unhealthy("down")
+                    }
+                }
+            })
+            .build();
+
+        let (_routes, health) = standard_services()
+            .service_name("demo.Greeter")
+            .health_check(health_check)
+            .poll_interval(Duration::from_millis(10))
+            .build()
+            .await
+            .unwrap();
+
+        let not_serving = ServingStatus::NotServing as i32;
+        assert_eq!(status(&health, "").await, not_serving);
+        assert_eq!(status(&health, "demo.Greeter").await, not_serving);
+
+        healthy.store(true, Ordering::SeqCst);
+        timeout(Duration::from_secs(2), async {
+            while status(&health, "demo.Greeter").await != ServingStatus::Serving as i32 {
+                sleep(Duration::from_millis(5)).await;
+            }
+        })
+        .await
+        .expect("status should switch to serving");
+    }
+
+    #[tokio::test]
+    async fn draining_overrides_health_check() {
+        let health_check = HealthCheckBuilder::new(true).build();
+        let (_routes, health) = standard_services()
+            .service_name("demo.Greeter")
+            .health_check(health_check)
+            .poll_interval(Duration::from_millis(10))
+            .build()
+            .await
+            .unwrap();
+        assert_eq!(
+            status(&health, "demo.Greeter").await,
+            ServingStatus::Serving as i32
+        );
+
+        let signal = health.draining_signal(async {}, Duration::from_millis(20));
+        timeout(Duration::from_secs(1), signal).await.unwrap();
+        assert!(health.is_draining());
+
+        sleep(Duration::from_millis(30)).await;
+        assert_eq!(status(&health, "").await, ServingStatus::NotServing as i32);
+        assert_eq!(
+            status(&health, "demo.Greeter").await,
+            ServingStatus::NotServing as i32
+        );
+    }
+
+    #[tokio::test]
+    async fn invalid_descriptor_set_is_rejected() {
+        let result = standard_services()
+            .file_descriptor_set(b"not a descriptor set")
+            .build()
+            .await;
+        assert!(result.is_err());
+
+        assert!(standard_services()
+            .without_reflection()
+            .build()
+            .await
+            .is_ok());
+    }
+}
@@ -0,0 +1,361 @@
+//! Tracing for gRPC calls.
+//!
+//! [`GrpcTracingLayer`] is the gRPC counterpart of RustAPI's `TracingLayer`:
+//! each call gets a span with the same fields (`method`, `path`,
+//! `request_id`, `status`, `duration_ms`, `error`, plus any custom fields)
+//! and the same `Request completed` / `Request failed` events, so mixed
+//! HTTP/gRPC traffic reads the same in the logs. gRPC calls additionally
+//! record `grpc_status`.
+//!
+//! The call is finished when the response stream ends (or is dropped), so
+//! `duration_ms` covers streaming responses and `grpc_status` is taken from
+//! the trailers.
+//!
+//! ```rust,ignore
+//! use rustapi_rs::grpc::{GrpcServices, GrpcTracingLayer};
+//!
+//! // Standalone Tonic server
+//! tonic::transport::Server::builder()
+//!     .layer(GrpcTracingLayer::new().with_field("service", "users"))
+//!     .add_service(UsersServer::new(MyUsers::default()));
+//!
+//! // Single-port server
+//! let grpc = GrpcServices::new(routes).tracing(GrpcTracingLayer::new());
+//! ```
+
+use crate::BoxError;
+use bytes::Bytes;
+use http::{HeaderMap, Request, Response};
+use http_body::{Body, Frame, SizeHint};
+use rustapi_core::middleware::RequestId;
+use std::future::Future;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use std::time::{Duration, Instant};
+use tonic::{Code, Status};
+use tower_service::Service;
+use tracing::{info_span, Instrument, Level};
+
+/// Tower layer creating tracing spans for gRPC calls
+///
+/// Mirrors the fields and events of RustAPI's HTTP `TracingLayer`.
+#[derive(Clone)]
+pub struct GrpcTracingLayer {
+    level: Level,
+    custom_fields: Vec<(String, String)>,
+}
+
+impl GrpcTracingLayer {
+    /// Create a new GrpcTracingLayer with default INFO level
+    pub fn new() -> Self {
+        Self {
+            level: Level::INFO,
+            custom_fields: Vec::new(),
+        }
+    }
+
+    /// Create a GrpcTracingLayer with a specific log level
+    pub fn with_level(level: Level) -> Self {
+        Self {
+            level,
+            custom_fields: Vec::new(),
+        }
+    }
+
+    /// Add a custom field to all call spans
+    pub fn with_field(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.custom_fields.push((key.into(), value.into()));
+        self
+    }
+}
+
+impl Default for GrpcTracingLayer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<S> tower_layer::Layer<S> for GrpcTracingLayer {
+    type Service = GrpcTracing<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        GrpcTracing {
+            inner,
+            layer: self.clone(),
+        }
+    }
+}
+
+/// Service created by [`GrpcTracingLayer`].
+#[derive(Clone)]
+pub struct GrpcTracing<S> {
+    inner: S,
+    layer: GrpcTracingLayer,
+}
+
+impl<S, B, ResBody> Service<Request<B>> for GrpcTracing<S>
+where
+    S: Service<Request<B>, Response = Response<ResBody>>,
+    S::Future: Send + 'static,
+    ResBody: Body<Data = Bytes> + Send + 'static,
+    ResBody::Error: Into<BoxError>,
+{
+    type Response = Response<tonic::body::Body>;
+    type Error = S::Error;
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response, S::Error>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), S::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, req: Request<B>) -> Self::Future {
+        let level = self.layer.level;
+        let method = req.method().to_string();
+        let path = req.uri().path().to_string();
+        let request_id = req
+            .extensions()
+            .get::<RequestId>()
+            .map(|id| id.as_str().to_string())
+            .or_else(|| header_str(req.headers(), "x-request-id"))
+            .unwrap_or_else(|| "unknown".to_string());
+
+        let span = info_span!(
+            "grpc_request",
+            method = %method,
+            path = %path,
+            request_id = %request_id,
+            status = tracing::field::Empty,
+            grpc_status = tracing::field::Empty,
+            duration_ms = tracing::field::Empty,
+            error = tracing::field::Empty,
+        );
+        for (key, value) in &self.layer.custom_fields {
+            span.record(key.as_str(), value.as_str());
+        }
+
+        let start = Instant::now();
+        let future = self.inner.call(req).instrument(span.clone());
+        Box::pin(async move {
+            let response = future.await?;
+            let status = response.status().as_u16();
+
+            Ok(traced_response(response, start, move |code, duration| {
+                span.record("status", status);
+                span.record("grpc_status", code as i32);
+                span.record("duration_ms", duration.as_millis() as u64);
+                let failed = code != Code::Ok;
+                if failed {
+                    span.record("error", true);
+                }
+
+                let _enter = span.enter();
+                let grpc_status = code as i32;
+                let duration_ms = duration.as_millis();
+                if failed {
+                    tracing::warn!(
+                        method = %method,
+                        path = %path,
+                        request_id = %request_id,
+                        status = %status,
+                        grpc_status,
+                        duration_ms = %duration_ms,
+                        error = true,
+                        "Request failed"
+                    );
+                    return;
+                }
+                macro_rules! completed {
+                    ($event:ident) => {
+                        tracing::$event!(
+                            method = %method,
+                            path = %path,
+                            request_id = %request_id,
+                            status = %status,
+                            grpc_status,
+                            duration_ms = %duration_ms,
+                            "Request completed"
+                        )
+                    };
+                }
+                match level {
+                    Level::TRACE => completed!(trace),
+                    Level::DEBUG => completed!(debug),
+                    Level::INFO => completed!(info),
+                    Level::WARN => completed!(warn),
+                    Level::ERROR => completed!(error),
+                }
+            }))
+        })
+    }
+}
+
+fn header_str(headers: &HeaderMap, name: &str) -> Option<String> {
+    headers
+        .get(name)
+        .and_then(|value| value.to_str().ok())
+        .map(str::to_string)
+}
+
+type OnFinish = Box<dyn FnOnce(Code, Duration) + Send>;
+
+/// Wrap a gRPC response so `on_finish` runs once its stream ends (or is
+/// dropped) with the final gRPC status and the call duration.
+pub(crate) fn traced_response<B>(
+    response: Response<B>,
+    start: Instant,
+    on_finish: impl FnOnce(Code, Duration) + Send + 'static,
+) -> Response<tonic::body::Body>
+where
+    B: Body<Data = Bytes> + Send + 'static,
+    B::Error: Into<BoxError>,
+{
+    let (parts, body) = response.into_parts();
+    let mut call = CallGuard {
+        start,
+        code: None,
+        on_finish: Some(Box::new(on_finish)),
+    };
+    // Trailers-only responses carry the status in the headers
+    call.observe(&parts.headers);
+
+    Response::from_parts(
+        parts,
+        tonic::body::Body::new(TracedBody {
+            inner: Box::pin(body),
+            call: Some(call),
+        }),
+    )
+}
+
+/// Reports the end of a call exactly once.
+struct CallGuard {
+    start: Instant,
+    code: Option<Code>,
+    on_finish: Option<OnFinish>,
+}
+
+impl CallGuard {
+    fn observe(&mut self, headers: &HeaderMap) {
+        if let Some(status) = Status::from_header_map(headers) {
+            self.code = Some(status.code());
+        }
+    }
+}
+
+impl Drop for CallGuard {
+    fn drop(&mut self) {
+        if let Some(on_finish) = self.on_finish.take() {
+            // A stream dropped before its trailers was cancelled
+            on_finish(self.code.unwrap_or(Code::Cancelled), self.start.elapsed());
+        }
+    }
+}
+
+struct TracedBody<B> {
+    inner: Pin<Box<B>>,
+    call: Option<CallGuard>,
+}
+
+impl<B: Body<Data = Bytes>> Body for TracedBody<B> {
+    type Data = Bytes;
+    type Error = B::Error;
+
+    fn poll_frame(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Result<Frame<Self::Data>, Self::Error>>> {
+        let polled = self.inner.as_mut().poll_frame(cx);
+        match &polled {
+            Poll::Ready(Some(Ok(frame))) => {
+                if let (Some(trailers), Some(call)) = (frame.trailers_ref(), self.call.as_mut()) {
+                    call.observe(trailers);
+                }
+            }
+            Poll::Ready(Some(Err(_))) => {
+                if let Some(call) = self.call.as_mut() {
+                    call.code = Some(Code::Unknown);
+                }
+                self.call = None;
+            }
+            Poll::Ready(None) => self.call = None,
+            Poll::Pending => {}
+        }
+        polled
+    }
+
+    fn is_end_stream(&self) -> bool {
+        self.inner.is_end_stream()
+    }
+
+    fn size_hint(&self) -> SizeHint {
+        self.inner.size_hint()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::VecDeque;
+    use std::sync::{Arc, Mutex};
+
+    struct Frames(VecDeque<Frame<Bytes>>);
+
+    impl Body for Frames {
+        type Data = Bytes;
+        type Error = std::convert::Infallible;
+
+        fn poll_frame(
+            mut self: Pin<&mut Self>,
+            _cx: &mut Context<'_>,
+        ) -> Poll<Option<Result<Frame<Bytes>, Self::Error>>> {
+            Poll::Ready(self.0.pop_front().map(Ok))
+        }
+    }
+
+    fn status_headers(code: Code) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert("grpc-status", (code as i32).into());
+        headers
+    }
+
+    fn reply(trailers: Option<Code>) -> Response<Frames> {
+        let mut frames = VecDeque::from([Frame::data(Bytes::from_static(b"\0\0\0\0\0"))]);
+        if let Some(code) = trailers {
+            frames.push_back(Frame::trailers(status_headers(code)));
+        }
+        Response::new(Frames(frames))
+    }
+
+    fn recorder() -> (Arc<Mutex<Option<Code>>>, impl FnOnce(Code, Duration) + Send) {
+        let finished = Arc::new(Mutex::new(None));
+        let slot = finished.clone();
+        (finished, move |code, _| *slot.lock().unwrap() = Some(code))
+    }
+
+    #[tokio::test]
+    async fn call_finishes_with_trailer_status() {
+        use http_body_util::BodyExt;
+
+        let (finished, on_finish) = recorder();
+        let response = traced_response(reply(Some(Code::NotFound)), Instant::now(), on_finish);
+        let collected = response.into_body().collect().await.unwrap();
+        assert!(collected.trailers().is_some());
+        assert_eq!(*finished.lock().unwrap(), Some(Code::NotFound));
+
+        // Trailers-only responses report the header status
+        let (finished, on_finish) = recorder();
+        let mut trailers_only = Response::new(Frames(VecDeque::new()));
+        *trailers_only.headers_mut() = status_headers(Code::PermissionDenied);
+        drop(traced_response(trailers_only, Instant::now(), on_finish));
+        assert_eq!(*finished.lock().unwrap(), Some(Code::PermissionDenied));
+    }
+
+    #[test]
+    fn dropped_stream_is_cancelled() {
+        let (finished, on_finish) = recorder();
+        let response = traced_response(reply(Some(Code::Ok)), Instant::now(), on_finish);
+        assert_eq!(*finished.lock().unwrap(), None);
+        drop(response);
+        assert_eq!(*finished.lock().unwrap(), Some(Code::Cancelled));
+    }
+}
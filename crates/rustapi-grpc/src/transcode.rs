@@ -0,0 +1,694 @@
+//! HTTP/JSON transcoding to gRPC services.
+//!
+//! A [`Transcoder`] exposes unary methods of Tonic services as REST routes,
+//! following the `google.api.http` annotation model: each [`HttpRule`] gives
+//! an HTTP method, a path template and an optional body mapping. A JSON
+//! request is turned into the protobuf request message, the method is called
+//! in-process, and the reply (or the gRPC status) is sent back as JSON.
+//!
+//! - path variables (`/v1/users/{id}`) bind to the fields of the same name;
+//! - the body binds to the whole message (`body("*")`) or to one field
+//!   (`body("user")`);
+//! - remaining fields are read from the query string, unless the body maps
+//!   the whole message.
+//!
+//! Every rule is documented in the OpenAPI spec from the message schemas.
+//! Messages need `serde` and `Schema` derives next to `prost::Message`, which
+//! `tonic-build` adds with `type_attribute`.
+//!
+//! ```rust,ignore
+//! use rustapi_rs::grpc::{HttpRule, Transcoder};
+//!
+//! let grpc = tonic::service::Routes::new(UsersServer::new(MyUsers::default()));
+//!
+//! let app = Transcoder::new(grpc.clone())
+//!     .rule::<GetUserRequest, User>("/users.v1.Users/GetUser", HttpRule::get("/v1/users/{id}"))
+//!     .rule::<CreateUserRequest, User>(
+//!         "/users.v1.Users/CreateUser",
+//!         HttpRule::post("/v1/users").body("user"),
+//!     )
+//!     .mount(RustApi::new());
+//!
+//! run_rustapi_and_grpc_multiplexed(app, "0.0.0.0:8080", grpc).await?;
+//! ```
+
+use crate::multiplex::call_tonic;
+use bytes::{BufMut, Bytes, BytesMut};
+use http::header::CONTENT_TYPE;
+use http::{Method, StatusCode};
+use http_body_util::{BodyExt, Full};
+use rustapi_core::{ApiError, FromRequest, Json, MethodRouter, Request, RustApi};
+use rustapi_openapi::schema::{RustApiSchema, SchemaCtx};
+use rustapi_openapi::{
+    MediaType, OpenApiSpec, Operation, OperationModifier, Parameter, RequestBody,
+};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::{Map, Value};
+use std::collections::{BTreeMap, HashSet};
+use std::marker::PhantomData;
+use std::sync::Arc;
+use tonic::service::Routes;
+use tonic::{Code, Status};
+
+/// HTTP binding of a gRPC method, as in a `google.api.http` annotation.
+#[derive(Debug, Clone)]
+pub struct HttpRule {
+    method: Method,
+    path: String,
+    body: Option<String>,
+}
+
+impl HttpRule {
+    fn new(method: Method, path: impl Into<String>) -> Self {
+        Self {
+            method,
+            path: path.into(),
+            body: None,
+        }
+    }
+
+    /// Bind a `GET` route
+    pub fn get(path: impl Into<String>) -> Self {
+        Self::new(Method::GET, path)
+    }
+
+    /// Bind a `POST` route
+    pub fn post(path: impl Into<String>) -> Self {
+        Self::new(Method::POST, path)
+    }
+
+    /// Bind a `PUT` route
+    pub fn put(path: impl Into<String>) -> Self {
+        Self::new(Method::PUT, path)
+    }
+
+    /// Bind a `PATCH` route
+    pub fn patch(path: impl Into<String>) -> Self {
+        Self::new(Method::PATCH, path)
+    }
+
+    /// Bind a `DELETE` route
+    pub fn delete(path: impl Into<String>) -> Self {
+        Self::new(Method::DELETE, path)
+    }
+
+    /// Map the JSON body to the whole message (`"*"`) or to a single field
+    pub fn body(mut self, field: impl Into<String>) -> Self {
+        self.body = Some(field.into());
+        self
+    }
+
+    /// Path variables of the template (`{id}`)
+    fn variables(&self) -> Vec<String> {
+        self.path
+            .split('{')
+            .skip(1)
+            .filter_map(|rest| rest.split_once('}'))
+            .map(|(name, _)| name.to_string())
+            .collect()
+    }
+}
+
+/// REST facade over Tonic services.
+///
+/// Add one [`rule`](Self::rule) per exposed method, then
+/// [`mount`](Self::mount) the routes on a `RustApi` app.
+pub struct Transcoder {
+    grpc: Routes,
+    routes: Vec<(String, MethodRouter)>,
+}
+
+impl Transcoder {
+    /// Transcode to the given Tonic services.
+    pub fn new(grpc: Routes) -> Self {
+        Self {
+            grpc: grpc.prepare(),
+            routes: Vec::new(),
+        }
+    }
+
+    /// Expose the unary method `grpc_method` (`/package.Service/Method`)
+    /// through `rule`.
+    ///
+    /// Panics if `rule` maps the body to a field `Req` does not have.
+    pub fn rule<Req, Res>(mut self, grpc_method: &str, rule: HttpRule) -> Self
+    where
+        Req: prost::Message + DeserializeOwned + RustApiSchema + Send + 'static,
+        Res: prost::Message + Default + Serialize + RustApiSchema + Send + 'static,
+    {
+        let fields = field_kinds::<Req>();
+        if let Some(field) = rule.body.as_deref().filter(|field| *field != "*") {
+            assert!(
+                fields.contains_key(field),
+                "body field `{}` is not a field of the request message",
+                field
+            );
+        }
+
+        let binding = Arc::new(Binding {
+            grpc_method: grpc_method.to_string(),
+            variables: rule.variables(),
+            body: rule.body.clone(),
+            fields,
+        });
+        let grpc = self.grpc.clone();
+        let handler = {
+            let binding = binding.clone();
+            move |input: TranscodeInput<Req>| {
+                let grpc = grpc.clone();
+                let binding = binding.clone();
+                async move { transcode::<Req, Res>(grpc, &binding, input).await }
+            }
+        };
+
+        let index = match self.routes.iter().position(|(path, _)| *path == rule.path) {
+            Some(index) => index,
+            None => {
+                self.routes.push((rule.path.clone(), MethodRouter::new()));
+                self.routes.len() - 1
+            }
+        };
+        let router = std::mem::take(&mut self.routes[index].1);
+        let router = match rule.method {
+            Method::GET => router.get(handler),
+            Method::POST => router.post(handler),
+            Method::PUT => router.put(handler),
+            Method::PATCH => router.patch(handler),
+            Method::DELETE => router.delete(handler),
+            _ => unreachable!("HttpRule only binds GET, POST, PUT, PATCH and DELETE"),
+        };
+        self.routes[index].1 = router.operation(rule.method, |op| describe::<Req>(op, &binding));
+        self
+    }
+
+    /// Register the transcoded routes (and their OpenAPI operations) on `app`.
+    pub fn mount(self, app: RustApi) -> RustApi {
+        self.routes
+            .into_iter()
+            .fold(app, |app, (path, router)| app.route(&path, router))
+    }
+}
+
+/// JSON type of a request message field, used to convert path and query
+/// strings.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum FieldKind {
+    Integer,
+    Number,
+    Boolean,
+    String,
+    Repeated(&'static FieldKind),
+    Other,
+}
+
+struct Binding {
+    grpc_method: String,
+    variables: Vec<String>,
+    body: Option<String>,
+    fields: BTreeMap<String, FieldKind>,
+}
+
+impl Binding {
+    fn maps_whole_body(&self) -> bool {
+        self.body.as_deref() == Some("*")
+    }
+
+    /// Fields read from the query string
+    fn query_fields(&self) -> impl Iterator<Item = (&String, &FieldKind)> {
+        self.fields.iter().filter(move |(name, _)| {
+            !self.maps_whole_body()
+                && !self.variables.contains(name)
+                && self.body.as_ref() != Some(*name)
+        })
+    }
+}
+
+fn field_kinds<T: RustApiSchema>() -> BTreeMap<String, FieldKind> {
+    let mut ctx = SchemaCtx::new();
+    T::field_schemas(&mut ctx)
+        .unwrap_or_default()
+        .into_iter()
+        .map(|(name, schema)| {
+            let schema = serde_json::to_value(schema).unwrap_or_default();
+            (name, kind_of(&schema))
+        })
+        .collect()
+}
+
+fn kind_of(schema: &Value) -> FieldKind {
+    // Optional fields are `["integer", "null"]`
+    let schema_type = match schema.get("type") {
+        Some(Value::String(schema_type)) => Some(schema_type.as_str()),
+        Some(Value::Array(types)) => types
+            .iter()
+            .filter_map(Value::as_str)
+            .find(|schema_type| *schema_type != "null"),
+        _ => None,
+    };
+    match schema_type {
+        Some("integer") => FieldKind::Integer,
+        Some("number") => FieldKind::Number,
+        Some("boolean") => FieldKind::Boolean,
+        Some("string") => FieldKind::String,
+        Some("array") => match schema.get("items").map(kind_of) {
+            Some(FieldKind::Integer) => FieldKind::Repeated(&FieldKind::Integer),
+            Some(FieldKind::Number) => FieldKind::Repeated(&FieldKind::Number),
+            Some(FieldKind::Boolean) => FieldKind::Repeated(&FieldKind::Boolean),
+            _ => FieldKind::Repeated(&FieldKind::String),
+        },
+        _ => FieldKind::Other,
+    }
+}
+
+/// Convert a path or query string to the JSON value of a field.
+fn coerce(kind: FieldKind, raw: &str) -> Value {
+    let parsed = match kind {
+        FieldKind::Integer => raw.parse::<i64>().ok().map(Value::from),
+        FieldKind::Number => raw.parse::<f64>().ok().map(Value::from),
+        FieldKind::Boolean => raw.parse::<bool>().ok().map(Value::from),
+        _ => None,
+    };
+    // Unparseable values are left as strings for serde to reject
+    parsed.unwrap_or_else(|| Value::String(raw.to_string()))
+}
+
+/// Raw request parts of a transcoded call; registers the request message
+/// schema for the OpenAPI operation.
+struct TranscodeInput<Req> {
+    path: Vec<(String, String)>,
+    query: Vec<(String, String)>,
+    body: Bytes,
+    _message: PhantomData<fn() -> Req>,
+}
+
+impl<Req> FromRequest for TranscodeInput<Req> {
+    async fn from_request(req: &mut Request) -> rustapi_core::Result<Self> {
+        let path = req
+            .path_params()
+            .iter()
+            .map(|(name, value)| (name.clone(), value.clone()))
+            .collect();
+        let query = serde_urlencoded::from_str(req.query_string().unwrap_or(""))
+            .map_err(|e| ApiError::bad_request(format!("Invalid query string: {}", e)))?;
+        req.load_body().await?;
+        let body = req.take_body().unwrap_or_default();
+
+        Ok(Self {
+            path,
+            query,
+            body,
+            _message: PhantomData,
+        })
+    }
+}
+
+impl<Req: RustApiSchema> OperationModifier for TranscodeInput<Req> {
+    fn update_operation(_op: &mut Operation) {}
+
+    fn register_components(spec: &mut OpenApiSpec) {
+        spec.register_in_place::<Req>();
+    }
+}
+
+/// Document the query parameters and body mapping of a rule.
+fn describe<Req: RustApiSchema>(op: &mut Operation, binding: &Binding) {
+    let (service, method) = binding
+        .grpc_method
+        .trim_start_matches('/')
+        .split_once('/')
+        .unwrap_or(("", binding.grpc_method.as_str()));
+    if !service.is_empty() {
+        op.tags.push(service.to_string());
+    }
+    op.operation_id = Some(method.to_string());
+    op.description = Some(format!("Transcoded to gRPC `{}`", binding.grpc_method));
+
+    let mut ctx = SchemaCtx::new();
+    let mut schemas = Req::field_schemas(&mut ctx).unwrap_or_default();
+    for (name, _) in binding.query_fields() {
+        op.parameters.push(Parameter {
+            name: name.clone(),
+            location: "query".to_string(),
+            description: None,
+            required: false,
+            deprecated: None,
+            style: None,
+            explode: None,
+            schema: schemas.get(name).cloned(),
+        });
+    }
+
+    let schema = match binding.body.as_deref() {
+        None => return,
+        Some("*") => Req::schema(&mut ctx),
+        Some(field) => match schemas.remove(field) {
+            Some(schema) => schema,
+            None => return,
+        },
+    };
+    op.request_body = Some(RequestBody {
+        description: None,
+        required: Some(true),
+        content: BTreeMap::from([(
+            "application/json".to_string(),
+            MediaType {
+                schema: Some(schema),
+                example: None,
+            },
+        )]),
+    });
+}
+
+async fn transcode<Req, Res>(
+    grpc: Routes,
+    binding: &Binding,
+    input: TranscodeInput<Req>,
+) -> Result<Json<Res>, ApiError>
+where
+    Req: prost::Message + DeserializeOwned,
+    Res: prost::Message + Default,
+{
+    let mut message = Map::new();
+
+    let query_fields: HashSet<&String> = binding.query_fields().map(|(name, _)| name).collect();
+    for (name, raw) in &input.query {
+        let Some(name) = query_fields.get(name) else {
+            continue;
+        };
+        match binding.fields[*name] {
+            FieldKind::Repeated(kind) => {
+                let values = message
+                    .entry(name.to_string())
+                    .or_insert_with(|| Value::Array(Vec::new()));
+                if let Value::Array(values) = values {
+                    values.push(coerce(*kind, raw));
+                }
+            }
+            kind => {
+                message.insert(name.to_string(), coerce(kind, raw));
+            }
+        }
+    }
+
+    if let Some(field) = &binding.body {
+        if !input.body.is_empty() {
+            let body: Value = serde_json::from_slice(&input.body)
+                .map_err(|e| ApiError::bad_request(format!("Invalid JSON body: {}", e)))?;
+            if field == "*" {
+                let Value::Object(body) = body else {
+                    return Err(ApiError::bad_request("Request body must be a JSON object"));
+                };
+                message.extend(body);
+            } else {
+                message.insert(field.clone(), body);
+            }
+        }
+    }
+
+    for (name, raw) in &input.path {
+        let kind = binding
+            .fields
+            .get(name)
+            .copied()
+            .unwrap_or(FieldKind::String);
+        message.insert(name.clone(), coerce(kind, raw));
+    }
+
+    let request: Req = serde_json::from_value(Value::Object(message))
+        .map_err(|e| ApiError::bad_request(format!("Invalid request message: {}", e)))?;
+    let reply = call_unary(grpc, &binding.grpc_method, request.encode_to_vec()).await?;
+    Res::decode(reply)
+        .map(Json)
+        .map_err(|e| ApiError::new(StatusCode::BAD_GATEWAY, "bad_gateway", e.to_string()))
+}
+
+/// Call a unary gRPC method in-process, returning the reply message bytes.
+async fn call_unary(grpc: Routes, method: &str, message: Vec<u8>) -> Result<Bytes, ApiError> {
+    let mut frame = BytesMut::with_capacity(5 + message.len());
+    frame.put_u8(0);
+    frame.put_u32(message.len() as u32);
+    frame.put_slice(&message);
+
+    let request = http::Request::builder()
+        .method(Method::POST)
+        .uri(method)
+        .header(CONTENT_TYPE, "application/grpc")
+        .header("te", "trailers")
+        .body(Full::new(frame.freeze()))
+        .map_err(|e| ApiError::internal(e.to_string()))?;
+
+    let (parts, body) = call_tonic(grpc, request).await.into_parts();
+    // Trailers-only replies carry the status in the headers
+    if let Some(status) = Status::from_header_map(&parts.headers) {
+        if status.code() != Code::Ok {
+            return Err(status_error(&status));
+        }
+    }
+    let collected = body
+        .collect()
+        .await
+        .map_err(|status| status_error(&status))?;
+    if let Some(status) = collected.trailers().and_then(Status::from_header_map) {
+        if status.code() != Code::Ok {
+            return Err(status_error(&status));
+        }
+    }
+
+    let data = collected.to_bytes();
+    let bad_frame = || {
+        ApiError::new(
+            StatusCode::BAD_GATEWAY,
+            "bad_gateway",
+            "Malformed gRPC reply",
+        )
+    };
+    if data.len() < 5 || data[0] != 0 {
+        return Err(bad_frame());
+    }
+    let len = u32::from_be_bytes([data[1], data[2], data[3], data[4]]) as usize;
+    if data.len() < 5 + len {
+        return Err(bad_frame());
+    }
+    Ok(data.slice(5..5 + len))
+}
+
+/// Map a gRPC status to the HTTP error of the `google.api.http` mapping.
+fn status_error(status: &Status) -> ApiError {
+    let (http_status, error_type) = match status.code() {
+        Code::Ok => (StatusCode::OK, "ok"),
+        Code::Cancelled => (
+            StatusCode::from_u16(499).unwrap_or(StatusCode::BAD_REQUEST),
+            "cancelled",
+        ),
+        Code::Unknown => (StatusCode::INTERNAL_SERVER_ERROR, "unknown"),
+        Code::InvalidArgument => (StatusCode::BAD_REQUEST, "invalid_argument"),
+        Code::DeadlineExceeded => (StatusCode::GATEWAY_TIMEOUT, "deadline_exceeded"),
+        Code::NotFound => (StatusCode::NOT_FOUND, "not_found"),
+        Code::AlreadyExists => (StatusCode::CONFLICT, "already_exists"),
+        Code::PermissionDenied => (StatusCode::FORBIDDEN, "permission_denied"),
+        Code::ResourceExhausted => (StatusCode::TOO_MANY_REQUESTS, "resource_exhausted"),
+        Code::FailedPrecondition => (StatusCode::BAD_REQUEST, "failed_precondition"),
+        Code::Aborted => (StatusCode::CONFLICT, "aborted"),
+        Code::OutOfRange => (StatusCode::BAD_REQUEST, "out_of_range"),
+        Code::Unimplemented => (StatusCode::NOT_IMPLEMENTED, "unimplemented"),
+        Code::Internal => (StatusCode::INTERNAL_SERVER_ERROR, "internal"),
+        Code::Unavailable => (StatusCode::SERVICE_UNAVAILABLE, "unavailable"),
+        Code::DataLoss => (StatusCode::INTERNAL_SERVER_ERROR, "data_loss"),
+        Code::Unauthenticated => (StatusCode::UNAUTHORIZED, "unauthenticated"),
+    };
+    ApiError::new(http_status, error_type, status.message())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rustapi_macros::Schema;
+    use rustapi_testing::{TestClient, TestRequest};
+    use serde::Deserialize;
+    use std::convert::Infallible;
+    use std::future::Future;
+    use std::pin::Pin;
+    use std::task::{Context, Poll};
+    use tonic::server::{Grpc, NamedService, UnaryService};
+    use tonic_prost::ProstCodec;
+
+    #[derive(Clone, PartialEq, prost::Message, Serialize, Deserialize, Schema)]
+    #[serde(default)]
+    struct GetUserRequest {
+        #[prost(uint64, tag = "1")]
+        id: u64,
+        #[prost(bool, tag = "2")]
+        verbose: bool,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message, Serialize, Deserialize, Schema)]
+    #[serde(default)]
+    struct User {
+        #[prost(uint64, tag = "1")]
+        id: u64,
+        #[prost(string, tag = "2")]
+        name: String,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message, Serialize, Deserialize, Schema)]
+    #[serde(default)]
+    struct CreateUserRequest {
+        #[prost(string, tag = "1")]
+        team: String,
+        #[prost(message, optional, tag = "2")]
+        user: Option<User>,
+    }
+
+    type BoxFuture<T> = Pin<Box<dyn Future<Output = T> + Send>>;
+
+    struct GetUser;
+
+    impl UnaryService<GetUserRequest> for GetUser {
+        type Response = User;
+        type Future = BoxFuture<Result<tonic::Response<User>, Status>>;
+
+        fn call(&mut self, request: tonic::Request<GetUserRequest>) -> Self::Future {
+            let request = request.into_inner();
+            Box::pin(async move {
+                if request.id == 0 {
+                    return Err(Status::not_found("no such user"));
+                }
+                let name = if request.verbose {
+                    "Alice (admin)"
+                } else {
+                    "Alice"
+                };
+                Ok(tonic::Response::new(User {
+                    id: request.id,
+                    name: name.to_string(),
+                }))
+            })
+        }
+    }
+
+    struct CreateUser;
+
+    impl UnaryService<CreateUserRequest> for CreateUser {
+        type Response = User;
+        type Future = BoxFuture<Result<tonic::Response<User>, Status>>;
+
+        fn call(&mut self, request: tonic::Request<CreateUserRequest>) -> Self::Future {
+            let request = request.into_inner();
+            Box::pin(async move {
+                let user = request.user.unwrap_or_default();
+                Ok(tonic::Response::new(User {
+                    id: 7,
+                    name: format!("{}/{}", request.team, user.name),
+                }))
+            })
+        }
+    }
+
+    /// Hand-written equivalent of a `tonic-build` server.
+    #[derive(Clone)]
+    struct Users;
+
+    impl NamedService for Users {
+        const NAME: &'static str = "users.v1.Users";
+    }
+
+    impl tower_service::Service<http::Request<tonic::body::Body>> for Users {
+        type Response = http::Response<tonic::body::Body>;
+        type Error = Infallible;
+        type Future = BoxFuture<Result<Self::Response, Infallible>>;
+
+        fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Infallible>> {
+            Poll::Ready(Ok(()))
+        }
+
+        fn call(&mut self, req: http::Request<tonic::body::Body>) -> Self::Future {
+            Box::pin(async move {
+                Ok(match req.uri().path() {
+                    "/users.v1.Users/GetUser" => {
+                        Grpc::new(ProstCodec::default()).unary(GetUser, req).await
+                    }
+                    "/users.v1.Users/CreateUser" => {
+                        Grpc::new(ProstCodec::default())
+                            .unary(CreateUser, req)
+                            .await
+                    }
+                    _ => Status::unimplemented("").into_http(),
+                })
+            })
+        }
+    }
+
+    fn app() -> RustApi {
+        Transcoder::new(Routes::new(Users))
+            .rule::<GetUserRequest, User>("/users.v1.Users/GetUser", HttpRule::get("/users/{id}"))
+            .rule::<CreateUserRequest, User>(
+                "/users.v1.Users/CreateUser",
+                HttpRule::post("/teams/{team}/users").body("user"),
+            )
+            .rule::<GetUserRequest, User>(
+                "/users.v1.Users/DeleteUser",
+                HttpRule::delete("/users/{id}"),
+            )
+            .mount(RustApi::new())
+    }
+
+    #[tokio::test]
+    async fn transcodes_path_query_and_body() {
+        let client = TestClient::new(app());
+
+        let response = client.get("/users/42?verbose=true").await;
+        response.assert_status(StatusCode::OK);
+        let user: serde_json::Value = response.json().unwrap();
+        assert_eq!(
+            user,
+            serde_json::json!({ "id": 42, "name": "Alice (admin)" })
+        );
+
+        let response = client
+            .post_json("/teams/core/users", &serde_json::json!({ "name": "Bob" }))
+            .await;
+        response.assert_status(StatusCode::OK);
+        let user: serde_json::Value = response.json().unwrap();
+        assert_eq!(user["name"], "core/Bob");
+
+        // gRPC statuses map to HTTP errors
+        client
+            .get("/users/0")
+            .await
+            .assert_status(StatusCode::NOT_FOUND);
+        client
+            .request(TestRequest::delete("/users/1"))
+            .await
+            .assert_status(StatusCode::NOT_IMPLEMENTED);
+        client
+            .get("/users/abc")
+            .await
+            .assert_status(StatusCode::BAD_REQUEST);
+    }
+
+    #[test]
+    fn rules_are_documented() {
+        let app = app();
+        let spec = serde_json::to_value(app.openapi_spec()).unwrap();
+
+        let get = &spec["paths"]["/users/{id}"]["get"];
+        assert_eq!(get["operationId"], "GetUser");
+        assert_eq!(get["tags"][0], "users.v1.Users");
+        let params: Vec<(&str, &str)> = get["parameters"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|p| (p["name"].as_str().unwrap(), p["in"].as_str().unwrap()))
+            .collect();
+        assert!(params.contains(&("verbose", "query")));
+        assert!(params.contains(&("id", "path")));
+        assert!(!params.contains(&("id", "query")));
+        assert!(get.get("requestBody").is_none());
+        assert!(spec["paths"]["/users/{id}"].get("delete").is_some());
+
+        let post = &spec["paths"]["/teams/{team}/users"]["post"];
+        assert!(post["requestBody"]["content"]["application/json"]["schema"].is_object());
+        assert!(post.get("parameters").unwrap().as_array().unwrap().len() == 1);
+    }
+}
@@ -0,0 +1,327 @@
+//! gRPC-Web bridge for the single-port server.
+//!
+//! Browsers cannot speak raw gRPC, so gRPC-Web clients send
+//! `application/grpc-web` (or `-text`) requests over HTTP/1.1 or HTTP/2.
+//! [`GrpcServices::grpc_web`](crate::GrpcServices::grpc_web) translates them
+//! with [`tonic_web`] for the same Tonic services that serve native gRPC, and
+//! answers the CORS preflights browsers send before each cross-origin call.
+//!
+//! ```rust,ignore
+//! use rustapi_rs::grpc::{run_rustapi_and_grpc_multiplexed, tonic, GrpcServices, GrpcWebConfig};
+//!
+//! let grpc = GrpcServices::new(tonic::service::Routes::new(greeter))
+//!     .grpc_web(GrpcWebConfig::new().allow_origin("https://app.example.com"));
+//!
+//! run_rustapi_and_grpc_multiplexed(app, "0.0.0.0:8080", grpc).await?;
+//! ```
+
+use crate::multiplex::call_tonic;
+use http::header::{self, HeaderValue};
+use http::{Method, StatusCode};
+use hyper::body::Incoming;
+use std::sync::Arc;
+use std::time::Duration;
+use tonic::body::Body;
+use tonic::service::Routes;
+use tonic_web::{GrpcWebLayer, GrpcWebService};
+use tower_layer::Layer;
+
+/// Headers gRPC-Web clients need to read from responses.
+const EXPOSED_HEADERS: &str = "grpc-status, grpc-message, grpc-status-details-bin";
+
+/// Headers gRPC-Web clients send by default.
+const DEFAULT_ALLOWED_HEADERS: &str =
+    "content-type, x-grpc-web, x-user-agent, grpc-timeout, authorization";
+
+/// CORS settings for gRPC-Web calls.
+///
+/// By default any origin is allowed and preflights are cached for 24 hours.
+#[derive(Debug, Clone)]
+pub struct GrpcWebConfig {
+    allowed_origins: Option<Vec<String>>,
+    allowed_headers: Option<Vec<String>>,
+    allow_credentials: bool,
+    max_age: Duration,
+}
+
+impl Default for GrpcWebConfig {
+    fn default() -> Self {
+        Self {
+            allowed_origins: None,
+            allowed_headers: None,
+            allow_credentials: false,
+            max_age: Duration::from_secs(24 * 60 * 60),
+        }
+    }
+}
+
+impl GrpcWebConfig {
+    /// Create a config allowing any origin.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Only allow the given origin (can be called multiple times).
+    ///
+    /// Requests from other origins are rejected with `403 Forbidden`.
+    pub fn allow_origin(mut self, origin: impl Into<String>) -> Self {
+        self.allowed_origins
+            .get_or_insert_with(Vec::new)
+            .push(origin.into());
+        self
+    }
+
+    /// Allow an extra request header, such as custom metadata (`x-tenant-id`).
+    pub fn allow_header(mut self, header: impl Into<String>) -> Self {
+        self.allowed_headers
+            .get_or_insert_with(Vec::new)
+            .push(header.into().to_ascii_lowercase());
+        self
+    }
+
+    /// Allow cookies and HTTP authentication on cross-origin calls.
+    pub fn allow_credentials(mut self, allow: bool) -> Self {
+        self.allow_credentials = allow;
+        self
+    }
+
+    /// How long browsers may cache preflight results.
+    pub fn max_age(mut self, max_age: Duration) -> Self {
+        self.max_age = max_age;
+        self
+    }
+
+    fn is_origin_allowed(&self, origin: &HeaderValue) -> bool {
+        match &self.allowed_origins {
+            None => true,
+            Some(origins) => origins.iter().any(|allowed| allowed.as_bytes() == origin),
+        }
+    }
+
+    fn allowed_headers(&self) -> String {
+        let mut headers = DEFAULT_ALLOWED_HEADERS.to_string();
+        for header in self.allowed_headers.iter().flatten() {
+            headers.push_str(", ");
+            headers.push_str(header);
+        }
+        headers
+    }
+}
+
+/// Check whether a request is a gRPC-Web call, based on its `content-type`.
+pub fn is_grpc_web_request<B>(req: &http::Request<B>) -> bool {
+    req.headers()
+        .get(header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|content_type| content_type.starts_with("application/grpc-web"))
+}
+
+/// Check whether a request is a CORS preflight for a gRPC-Web call.
+///
+/// gRPC-Web clients always send `x-grpc-web`, so it shows up in
+/// `access-control-request-headers`.
+fn is_grpc_web_preflight<B>(req: &http::Request<B>) -> bool {
+    req.method() == Method::OPTIONS
+        && req
+            .headers()
+            .get(header::ACCESS_CONTROL_REQUEST_HEADERS)
+            .and_then(|value| value.to_str().ok())
+            .is_some_and(|headers| {
+                headers
+                    .split(',')
+                    .any(|h| h.trim().eq_ignore_ascii_case("x-grpc-web"))
+            })
+}
+
+/// gRPC-Web translation and CORS handling in front of Tonic routes.
+#[derive(Clone)]
+pub(crate) struct GrpcWebBridge {
+    config: Arc<GrpcWebConfig>,
+    service: GrpcWebService<Routes>,
+}
+
+impl GrpcWebBridge {
+    pub(crate) fn new(config: GrpcWebConfig, routes: Routes) -> Self {
+        Self {
+            config: Arc::new(config),
+            service: GrpcWebLayer::new().layer(routes),
+        }
+    }
+
+    /// Whether this request is handled by the bridge rather than by RustAPI.
+    pub(crate) fn accepts<B>(&self, req: &http::Request<B>) -> bool {
+        is_grpc_web_request(req) || is_grpc_web_preflight(req)
+    }
+
+    pub(crate) async fn call(self, req: hyper::Request<Incoming>) -> http::Response<Body> {
+        let origin = req.headers().get(header::ORIGIN).cloned();
+        if let Some(origin) = &origin {
+            if !self.config.is_origin_allowed(origin) {
+                return status(StatusCode::FORBIDDEN);
+            }
+        }
+
+        let mut response = if is_grpc_web_preflight(&req) {
+            self.preflight()
+        } else {
+            let mut response = call_tonic(self.service, req).await;
+            response.headers_mut().insert(
+                header::ACCESS_CONTROL_EXPOSE_HEADERS,
+                HeaderValue::from_static(EXPOSED_HEADERS),
+            );
+            response
+        };
+
+        if let Some(origin) = origin {
+            let headers = response.headers_mut();
+            headers.insert(header::ACCESS_CONTROL_ALLOW_ORIGIN, origin);
+            headers.append(header::VARY, HeaderValue::from_static("origin"));
+            if self.config.allow_credentials {
+                headers.insert(
+                    header::ACCESS_CONTROL_ALLOW_CREDENTIALS,
+                    HeaderValue::from_static("true"),
+                );
+            }
+        }
+        response
+    }
+
+    fn preflight(&self) -> http::Response<Body> {
+        let mut response = status(StatusCode::NO_CONTENT);
+        let headers = response.headers_mut();
+        headers.insert(
+            header::ACCESS_CONTROL_ALLOW_METHODS,
+            HeaderValue::from_static("POST, OPTIONS"),
+        );
+        if let Ok(allowed) = HeaderValue::from_str(&self.config.allowed_headers()) {
+            headers.insert(header::ACCESS_CONTROL_ALLOW_HEADERS, allowed);
+        }
+        headers.insert(
+            header::ACCESS_CONTROL_MAX_AGE,
+            HeaderValue::from(self.config.max_age.as_secs()),
+        );
+        response
+    }
+}
+
+fn status(status: StatusCode) -> http::Response<Body> {
+    let mut response = http::Response::new(Body::empty());
+    *response.status_mut() = status;
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{run_rustapi_and_grpc_multiplexed_with_shutdown, GrpcServices};
+    use prost::Message;
+    use rustapi_core::RustApi;
+    use std::net::SocketAddr;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpStream;
+    use tokio::sync::oneshot;
+    use tokio::time::{sleep, Duration};
+    use tonic_health::pb::HealthCheckRequest;
+
+    async fn exchange(addr: SocketAddr, request: &[u8]) -> String {
+        let mut stream = loop {
+            match TcpStream::connect(addr).await {
+                Ok(stream) => break stream,
+                Err(_) => sleep(Duration::from_millis(10)).await,
+            }
+        };
+        stream.write_all(request).await.unwrap();
+        let mut response = Vec::new();
+        stream.read_to_end(&mut response).await.unwrap();
+        String::from_utf8_lossy(&response).to_lowercase()
+    }
+
+    #[test]
+    fn preflight_detection_requires_grpc_web_header() {
+        let preflight = |headers: &str| {
+            http::Request::builder()
+                .method(Method::OPTIONS)
+                .header(header::ACCESS_CONTROL_REQUEST_HEADERS, headers)
+                .body(())
+                .unwrap()
+        };
+        assert!(is_grpc_web_preflight(&preflight("content-type,X-Grpc-Web")));
+        assert!(!is_grpc_web_preflight(&preflight("content-type")));
+
+        let call = http::Request::builder()
+            .header(header::CONTENT_TYPE, "application/grpc-web-text+proto")
+            .body(())
+            .unwrap();
+        assert!(is_grpc_web_request(&call));
+    }
+
+    #[tokio::test]
+    async fn browser_clients_call_grpc_services() {
+        let addr = {
+            let probe = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+            probe.local_addr().unwrap()
+        };
+        let (reporter, health_service) = tonic_health::server::health_reporter();
+        reporter
+            .set_service_status("greeter", tonic_health::ServingStatus::Serving)
+            .await;
+
+        let grpc = GrpcServices::new(Routes::new(health_service))
+            .grpc_web(GrpcWebConfig::new().allow_origin("https://app.example.com"));
+        let (tx, rx) = oneshot::channel::<()>();
+        let server = tokio::spawn(run_rustapi_and_grpc_multiplexed_with_shutdown(
+            RustApi::new(),
+            addr.to_string(),
+            grpc,
+            async move {
+                let _ = rx.await;
+            },
+        ));
+
+        let preflight = exchange(
+            addr,
+            b"OPTIONS /grpc.health.v1.Health/Check HTTP/1.1\r\nhost: localhost\r\n\
+              origin: https://app.example.com\r\naccess-control-request-method: POST\r\n\
+              access-control-request-headers: content-type,x-grpc-web\r\nconnection: close\r\n\r\n",
+        )
+        .await;
+        assert!(preflight.starts_with("http/1.1 204"), "{}", preflight);
+        assert!(preflight.contains("access-control-allow-origin: https://app.example.com"));
+        assert!(preflight.contains("access-control-allow-headers: content-type, x-grpc-web"));
+
+        // Length-prefixed message frame: flag byte, u32 length, protobuf payload
+        let message = HealthCheckRequest {
+            service: "greeter".to_string(),
+        }
+        .encode_to_vec();
+        let mut body = vec![0u8];
+        body.extend_from_slice(&(message.len() as u32).to_be_bytes());
+        body.extend_from_slice(&message);
+
+        let mut call = format!(
+            "POST /grpc.health.v1.Health/Check HTTP/1.1\r\nhost: localhost\r\n\
+             origin: https://app.example.com\r\ncontent-type: application/grpc-web+proto\r\n\
+             x-grpc-web: 1\r\ncontent-length: {}\r\nconnection: close\r\n\r\n",
+            body.len()
+        )
+        .into_bytes();
+        call.extend_from_slice(&body);
+        let response = exchange(addr, &call).await;
+        assert!(response.starts_with("http/1.1 200"), "{}", response);
+        assert!(response.contains("content-type: application/grpc-web+proto"));
+        assert!(response.contains("access-control-expose-headers: grpc-status"));
+        assert!(response.contains("grpc-status:0"));
+
+        let forbidden = exchange(
+            addr,
+            b"POST /grpc.health.v1.Health/Check HTTP/1.1\r\nhost: localhost\r\n\
+              origin: https://evil.example.com\r\ncontent-type: application/grpc-web\r\n\
+              content-length: 0\r\nconnection: close\r\n\r\n",
+        )
+        .await;
+        assert!(forbidden.starts_with("http/1.1 403"), "{}", forbidden);
+
+        tx.send(()).unwrap();
+        assert!(server.await.unwrap().is_ok());
+    }
+}
@@ -1,5 +1,5 @@
 use proc_macro_crate::{crate_name, FoundCrate};
-use quote::quote;
+use quote::{format_ident, quote};
 use syn::{parse_macro_input, Data, DeriveInput, Expr, Lit, Meta};
 
 fn get_core_path() -> proc_macro2::TokenStream {
@@ -43,6 +43,7 @@ pub fn expand_derive_api_error(input: proc_macro::TokenStream) -> proc_macro::To
     };
 
     let mut match_arms = Vec::new();
+    let mut code_registrations = Vec::new();
 
     for variant in variants {
         let variant_name = &variant.ident;
@@ -52,6 +53,7 @@ pub fn expand_derive_api_error(input: proc_macro::TokenStream) -> proc_macro::To
         let mut status = None;
         let mut code = None;
         let mut message = None;
+        let mut description = None;
 
         for attr in attrs {
             if attr.path().is_ident("error") {
@@ -78,6 +80,12 @@ pub fn expand_derive_api_error(input: proc_macro::TokenStream) -> proc_macro::To
                                         message = Some(s.value());
                                     }
                                 }
+                            } else if nv.path.is_ident("description") {
+                                if let Expr::Lit(lit) = &nv.value {
+                                    if let Lit::Str(s) = &lit.lit {
+                                        description = Some(s.value());
+                                    }
+                                }
                             }
                         }
                     }
@@ -89,8 +97,27 @@ pub fn expand_derive_api_error(input: proc_macro::TokenStream) -> proc_macro::To
         let code = code.unwrap_or_else(|| "internal_server_error".to_string());
         let message = message.unwrap_or_else(|| "Internal Server Error".to_string());
 
+        let description = description.unwrap_or_else(|| message.clone());
+        let source = format!("{}::{}", name, variant_name);
+        let static_name = format_ident!(
+            "__RUSTAPI_ERROR_CODE_{}_{}",
+            name.to_string().to_uppercase(),
+            variant_name.to_string().to_uppercase()
+        );
+
+        // Register the code in the error catalog at link time.
+        code_registrations.push(quote! {
+            #[doc(hidden)]
+            #[allow(non_upper_case_globals)]
+            #[#core_path::__private::linkme::distributed_slice(#core_path::__private::ERROR_CODES)]
+            #[linkme(crate = #core_path::__private::linkme)]
+            static #static_name: #core_path::ErrorCodeEntry =
+                #core_path::ErrorCodeEntry::new_static(#code, #status, #description, #source);
+        });
+
+        // `{ .. }` matches unit, tuple and struct variants alike.
         match_arms.push(quote! {
-            #name::#variant_name => {
+            #name::#variant_name { .. } => {
                 #core_path::ApiError::new(
                     #core_path::StatusCode::from_u16(#status).unwrap(),
                     #code,
@@ -108,6 +135,8 @@ pub fn expand_derive_api_error(input: proc_macro::TokenStream) -> proc_macro::To
                 }
             }
         }
+
+        #(#code_registrations)*
     };
 
     expanded.into()
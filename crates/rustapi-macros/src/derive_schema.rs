@@ -1,7 +1,7 @@
 use proc_macro2::TokenStream;
 use proc_macro_crate::{crate_name, FoundCrate};
 use quote::quote;
-use syn::{Data, DataEnum, DataStruct, Fields, Ident};
+use syn::{Attribute, Data, DataEnum, DataStruct, Expr, Fields, Ident, LitStr};
 
 /// Determine the path to rustapi_openapi module based on the user's dependencies.
 ///
@@ -98,10 +98,20 @@ pub fn expand_derive_schema(input: syn::DeriveInput) -> TokenStream {
         }
     };
 
+    let rename_all = serde_str(&input.attrs, "rename_all");
+    let rename_all_fields = serde_str(&input.attrs, "rename_all_fields");
     let (schema_impl, field_schemas_impl) = match input.data {
-        Data::Struct(data) => impl_struct_schema_bodies(&openapi_path, &serde_json_path, data),
+        Data::Struct(data) => {
+            impl_struct_schema_bodies(&openapi_path, &serde_json_path, data, rename_all.as_deref())
+        }
         Data::Enum(data) => (
-            impl_enum_schema(&openapi_path, &serde_json_path, data),
+            impl_enum_schema(
+                &openapi_path,
+                &serde_json_path,
+                data,
+                rename_all.as_deref(),
+                rename_all_fields.as_deref(),
+            ),
             quote! { None },
         ),
         Data::Union(_) => {
@@ -135,6 +145,7 @@ fn impl_struct_schema_bodies(
     openapi_path: &TokenStream,
     serde_json_path: &TokenStream,
     data: DataStruct,
+    rename_all: Option<&str>,
 ) -> (TokenStream, TokenStream) {
     let mut field_logic = Vec::new();
     let mut field_schemas_logic = Vec::new();
@@ -142,8 +153,7 @@ fn impl_struct_schema_bodies(
     match data.fields {
         Fields::Named(fields) => {
             for field in fields.named {
-                let field_name = field.ident.unwrap();
-                let field_name_str = field_name.to_string();
+                let field_name_str = serialized_field_name(&field, rename_all);
                 let field_type = field.ty;
 
                 let is_option = if let syn::Type::Path(tp) = &field_type {
@@ -227,10 +237,131 @@ fn impl_struct_schema_bodies(
     (schema_body, field_schemas_body)
 }
 
+/// Read a `#[serde(key = "...")]` attribute of a type, variant or field
+///
+/// The `key(serialize = "...")` form yields the serialize value, since the
+/// schema describes what the API sends. Serde validates its own attributes;
+/// only the one asked for is read, anything unparseable is left to serde.
+fn serde_str(attrs: &[Attribute], key: &str) -> Option<String> {
+    let mut found = None;
+    for attr in attrs.iter().filter(|a| a.path().is_ident("serde")) {
+        let _ = attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident(key) && meta.input.peek(syn::Token![=]) {
+                if let Ok(value) = meta.value()?.parse::<LitStr>() {
+                    found = Some(value.value());
+                }
+            } else if meta.path.is_ident(key) && meta.input.peek(syn::token::Paren) {
+                meta.parse_nested_meta(|inner| {
+                    let value = inner.value()?.parse::<LitStr>()?;
+                    if inner.path.is_ident("serialize") {
+                        found = Some(value.value());
+                    }
+                    Ok(())
+                })?;
+            } else if meta.input.peek(syn::Token![=]) {
+                meta.value()?.parse::<Expr>()?;
+            } else if meta.input.peek(syn::token::Paren) {
+                meta.parse_nested_meta(|_| Ok(()))?;
+            }
+            Ok(())
+        });
+    }
+    found
+}
+
+/// The serialized name of a named field under an optional `rename_all` rule
+fn serialized_field_name(field: &syn::Field, rename_all: Option<&str>) -> String {
+    serde_str(&field.attrs, "rename").unwrap_or_else(|| {
+        let ident = field.ident.as_ref().expect("named field");
+        let name = ident.to_string().trim_start_matches("r#").to_string();
+        match rename_all {
+            Some(rule) => apply_rename_rule(rule, &name),
+            None => name,
+        }
+    })
+}
+
+/// The serialized name of an enum variant under an optional `rename_all` rule
+fn serialized_variant_name(variant: &syn::Variant, rename_all: Option<&str>) -> String {
+    serde_str(&variant.attrs, "rename").unwrap_or_else(|| {
+        let name = variant
+            .ident
+            .to_string()
+            .trim_start_matches("r#")
+            .to_string();
+        match rename_all {
+            Some(rule) => apply_variant_rename_rule(rule, &name),
+            None => name,
+        }
+    })
+}
+
+/// Rename a PascalCase variant the way serde's `rename_all` does
+fn apply_variant_rename_rule(rule: &str, name: &str) -> String {
+    let snake = || {
+        let mut snake = String::with_capacity(name.len() + 4);
+        for (i, c) in name.char_indices() {
+            if c.is_uppercase() && i > 0 {
+                snake.push('_');
+            }
+            snake.push(c.to_ascii_lowercase());
+        }
+        snake
+    };
+    match rule {
+        "lowercase" => name.to_ascii_lowercase(),
+        "UPPERCASE" => name.to_ascii_uppercase(),
+        "camelCase" => {
+            let mut chars = name.chars();
+            match chars.next() {
+                Some(first) => first.to_ascii_lowercase().to_string() + chars.as_str(),
+                None => String::new(),
+            }
+        }
+        "snake_case" => snake(),
+        "SCREAMING_SNAKE_CASE" => snake().to_ascii_uppercase(),
+        "kebab-case" => snake().replace('_', "-"),
+        "SCREAMING-KEBAB-CASE" => snake().to_ascii_uppercase().replace('_', "-"),
+        _ => name.to_string(),
+    }
+}
+
+/// Rename a snake_case field the way serde's `rename_all` does
+fn apply_rename_rule(rule: &str, name: &str) -> String {
+    let pascal = || {
+        name.split('_')
+            .map(|word| {
+                let mut chars = word.chars();
+                match chars.next() {
+                    Some(first) => first.to_ascii_uppercase().to_string() + chars.as_str(),
+                    None => String::new(),
+                }
+            })
+            .collect::<String>()
+    };
+    match rule {
+        "UPPERCASE" | "SCREAMING_SNAKE_CASE" => name.to_ascii_uppercase(),
+        "PascalCase" => pascal(),
+        "camelCase" => {
+            let pascal = pascal();
+            let mut chars = pascal.chars();
+            match chars.next() {
+                Some(first) => first.to_ascii_lowercase().to_string() + chars.as_str(),
+                None => pascal,
+            }
+        }
+        "kebab-case" => name.replace('_', "-"),
+        "SCREAMING-KEBAB-CASE" => name.to_ascii_uppercase().replace('_', "-"),
+        _ => name.to_string(),
+    }
+}
+
 fn impl_enum_schema(
     openapi_path: &TokenStream,
     serde_json_path: &TokenStream,
     data: DataEnum,
+    rename_all: Option<&str>,
+    rename_all_fields: Option<&str>,
 ) -> TokenStream {
     let is_string_enum = data
         .variants
@@ -238,7 +369,11 @@ fn impl_enum_schema(
         .all(|v| matches!(v.fields, Fields::Unit));
 
     if is_string_enum {
-        let variants: Vec<String> = data.variants.iter().map(|v| v.ident.to_string()).collect();
+        let variants: Vec<String> = data
+            .variants
+            .iter()
+            .map(|v| serialized_variant_name(v, rename_all))
+            .collect();
         let push_variants = variants.iter().map(|v| quote! { #v.into() });
 
         return quote! {
@@ -261,14 +396,16 @@ fn impl_enum_schema(
     let mut one_of_logic = Vec::new();
 
     for variant in data.variants {
-        let variant_name = variant.ident.to_string();
+        let variant_name = serialized_variant_name(&variant, rename_all);
+        let fields_rename_all = serde_str(&variant.attrs, "rename_all");
+        let fields_rename_all = fields_rename_all.as_deref().or(rename_all_fields);
         let fields = variant.fields;
 
         match fields {
             Fields::Named(named) => {
                 let mut props_logic = Vec::new();
                 for field in named.named {
-                    let fname = field.ident.unwrap().to_string();
+                    let fname = serialized_field_name(&field, fields_rename_all);
                     let fty = field.ty;
                     props_logic.push(quote! {
                         let fs_ref = <#fty as #openapi_path::schema::RustApiSchema>::schema(ctx);
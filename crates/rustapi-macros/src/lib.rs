@@ -147,7 +147,7 @@ fn extract_schema_types(ty: &Type, out: &mut Vec<Type>, allow_leaf: bool) {
 
             match ident.as_str() {
                 // Request/response wrappers
-                "Json" | "ValidatedJson" | "Created" => {
+                "Json" | "ValidatedJson" | "ValidatedForm" | "Created" => {
                     unwrap_first_generic(out);
                 }
                 // WithStatus<T, CODE>
@@ -461,7 +461,7 @@ pub fn main(_attr: TokenStream, item: TokenStream) -> TokenStream {
     TokenStream::from(expanded)
 }
 
-/// Check if a type is a body-consuming extractor (Json, Body, ValidatedJson, etc.)
+/// Check if a type is a body-consuming extractor (Json, Body, WebSocket, etc.)
 ///
 /// Body-consuming extractors implement `FromRequest` (not `FromRequestParts`)
 /// and consume the request body. They MUST be the last parameter in a handler
@@ -472,7 +472,13 @@ fn is_body_consuming_type(ty: &Type) -> bool {
             if let Some(seg) = tp.path.segments.last() {
                 matches!(
                     seg.ident.to_string().as_str(),
-                    "Json" | "Body" | "ValidatedJson" | "AsyncValidatedJson" | "Multipart"
+                    "Json"
+                        | "Body"
+                        | "ValidatedJson"
+                        | "ValidatedForm"
+                        | "AsyncValidatedJson"
+                        | "Multipart"
+                        | "WebSocket"
                 )
             } else {
                 false
@@ -482,6 +488,21 @@ fn is_body_consuming_type(ty: &Type) -> bool {
     }
 }
 
+/// Check whether a handler takes the `WebSocket` extractor
+fn has_websocket_param(input: &ItemFn) -> bool {
+    input.sig.inputs.iter().any(|arg| match arg {
+        FnArg::Typed(pat_ty) => match &*pat_ty.ty {
+            Type::Path(tp) => tp
+                .path
+                .segments
+                .last()
+                .is_some_and(|seg| seg.ident == "WebSocket"),
+            _ => false,
+        },
+        FnArg::Receiver(_) => false,
+    })
+}
+
 /// Validate that body-consuming extractors are the last parameter(s) in a handler.
 ///
 /// This prevents a common runtime error where the request body is consumed
@@ -538,7 +559,7 @@ fn validate_extractor_order(input: &ItemFn) -> Result<(), syn::Error> {
                      \n\
                      Found `{}` before non-body extractor(s).\n\
                      \n\
-                     Body extractors (Json, Body, ValidatedJson, AsyncValidatedJson, Multipart) \
+                     Body extractors (Json, Body, ValidatedJson, ValidatedForm, AsyncValidatedJson, Multipart, WebSocket) \
                      consume the request body, which can only be read once. Place them after all \
                      non-body extractors (State, Path, Query, Headers, etc.).\n\
                      \n\
@@ -561,8 +582,8 @@ fn validate_extractor_order(input: &ItemFn) -> Result<(), syn::Error> {
             &second_body_param.ty,
             "Multiple body-consuming extractors detected.\n\
              \n\
-             Only ONE body-consuming extractor (Json, Body, ValidatedJson, AsyncValidatedJson, \
-             Multipart) is allowed per handler, because the request body can only be consumed once.\n\
+             Only ONE body-consuming extractor (Json, Body, ValidatedJson, ValidatedForm, AsyncValidatedJson, \
+             Multipart, WebSocket) is allowed per handler, because the request body can only be consumed once.\n\
              \n\
              Remove the extra body extractor or combine the data into a single type.",
         ));
@@ -600,6 +621,16 @@ fn generate_route_handler(method: &str, attr: TokenStream, item: TokenStream) ->
         return err.to_compile_error().into();
     }
 
+    if method == "WS" && !has_websocket_param(&input) {
+        return syn::Error::new_spanned(
+            &input.sig,
+            "#[ws] handlers must take a `WebSocket` extractor and return its upgrade, \
+             e.g. `async fn chat(Path(room): Path<String>, ws: WebSocket) -> WebSocketUpgrade`",
+        )
+        .to_compile_error()
+        .into();
+    }
+
     // Generate a companion module with route info
     let route_fn_name = syn::Ident::new(&format!("{}_route", fn_name), fn_name.span());
     // Generate unique name for auto-registration static
@@ -617,6 +648,10 @@ fn generate_route_handler(method: &str, attr: TokenStream, item: TokenStream) ->
         "PUT" => quote!(#rustapi_path::put_route),
         "PATCH" => quote!(#rustapi_path::patch_route),
         "DELETE" => quote!(#rustapi_path::delete_route),
+        "HEAD" => quote!(#rustapi_path::head_route),
+        "OPTIONS" => quote!(#rustapi_path::options_route),
+        "ANY" => quote!(#rustapi_path::any_route),
+        "WS" => quote!(#rustapi_path::get_route),
         _ => quote!(#rustapi_path::get_route),
     };
 
@@ -625,6 +660,9 @@ fn generate_route_handler(method: &str, attr: TokenStream, item: TokenStream) ->
 
     // Extract metadata from attributes to chain builder methods
     let mut chained_calls = quote!();
+    // Role/scope checks, added last so they run inside #[middleware] layers
+    // (which may be the ones authenticating the caller)
+    let mut requirement_calls = quote!();
 
     // Add auto-detected parameters first (can be overridden by attributes)
     for (name, schema) in auto_params {
@@ -646,6 +684,11 @@ fn generate_route_handler(method: &str, attr: TokenStream, item: TokenStream) ->
                     let val = lit.value();
                     chained_calls = quote! { #chained_calls .summary(#val) };
                 }
+            } else if ident_str == "operation_id" {
+                if let Ok(lit) = attr.parse_args::<LitStr>() {
+                    let val = lit.value();
+                    chained_calls = quote! { #chained_calls .operation_id(#val) };
+                }
             } else if ident_str == "description" {
                 if let Ok(lit) = attr.parse_args::<LitStr>() {
                     let val = lit.value();
@@ -700,6 +743,30 @@ fn generate_route_handler(method: &str, attr: TokenStream, item: TokenStream) ->
                         })
                     };
                 }
+            } else if ident_str == "middleware" {
+                // Parse #[middleware(JwtLayer::new(..), RateLimitLayer::new(..))].
+                // Unlike the documentation attributes, a malformed list is an
+                // error: silently dropping an auth layer would expose the route.
+                match attr.parse_args_with(
+                    syn::punctuated::Punctuated::<Expr, syn::Token![,]>::parse_terminated,
+                ) {
+                    Ok(layers) => {
+                        for layer in layers {
+                            chained_calls = quote! { #chained_calls .layer(#layer) };
+                        }
+                    }
+                    Err(err) => return err.to_compile_error().into(),
+                }
+            } else if ident_str == "require_role" || ident_str == "require_scope" {
+                // #[require_role("admin")] / #[require_scope("pins:write")].
+                // Like #[middleware], a malformed requirement must not be dropped.
+                match attr.parse_args::<LitStr>() {
+                    Ok(lit) => {
+                        let method = syn::Ident::new(ident_str.as_str(), lit.span());
+                        requirement_calls = quote! { #requirement_calls .#method(#lit) };
+                    }
+                    Err(err) => return err.to_compile_error().into(),
+                }
             } else if ident_str == "param" {
                 // Parse #[param(name, schema = "type")] or #[param(name = "type")]
                 if let Ok(param_args) = attr.parse_args_with(
@@ -822,6 +889,13 @@ fn generate_route_handler(method: &str, attr: TokenStream, item: TokenStream) ->
         }
     }
 
+    chained_calls = quote! { #chained_calls #requirement_calls };
+
+    // WebSocket upgrades have no OpenAPI representation
+    if method == "WS" {
+        chained_calls = quote! { #chained_calls .undocumented() };
+    }
+
     let expanded = quote! {
         // The original handler function
         #(#fn_attrs)*
@@ -910,6 +984,65 @@ pub fn delete(attr: TokenStream, item: TokenStream) -> TokenStream {
     generate_route_handler("DELETE", attr, item)
 }
 
+/// WebSocket route handler macro
+///
+/// Registers the handler for `GET` upgrade requests and leaves it out of the
+/// OpenAPI spec. The handler must take a `WebSocket` extractor as its last
+/// parameter; other extractors (`Path`, `State`, `Query`, ...) run before the
+/// upgrade, so a failing extractor rejects the handshake with a normal HTTP
+/// error.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// use rustapi_rs::protocol::ws::{Message, WebSocket, WebSocketUpgrade};
+///
+/// #[rustapi::ws("/chat/{room}")]
+/// async fn chat(Path(room): Path<String>, ws: WebSocket) -> WebSocketUpgrade {
+///     ws.on_upgrade(move |mut socket| async move {
+///         while let Some(Ok(Message::Text(text))) = socket.recv().await {
+///             let _ = socket.send(Message::text(format!("[{room}] {text}"))).await;
+///         }
+///     })
+/// }
+/// ```
+#[proc_macro_attribute]
+pub fn ws(attr: TokenStream, item: TokenStream) -> TokenStream {
+    generate_route_handler("WS", attr, item)
+}
+
+/// HEAD route handler macro
+///
+/// Handlers should return headers only; any body they produce is still sent.
+#[proc_macro_attribute]
+pub fn head(attr: TokenStream, item: TokenStream) -> TokenStream {
+    generate_route_handler("HEAD", attr, item)
+}
+
+/// OPTIONS route handler macro
+#[proc_macro_attribute]
+pub fn options(attr: TokenStream, item: TokenStream) -> TokenStream {
+    generate_route_handler("OPTIONS", attr, item)
+}
+
+/// Route handler macro for every standard method
+///
+/// Registers the same handler for GET, HEAD, POST, PUT, PATCH, DELETE, OPTIONS
+/// and TRACE, and documents it under each of them.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// #[rustapi::any("/ping")]
+/// async fn ping() -> &'static str {
+///     "pong"
+/// }
+/// ```
+#[proc_macro_attribute]
+pub fn any(attr: TokenStream, item: TokenStream) -> TokenStream {
+    generate_route_handler("ANY", attr, item)
+}
+
 // ============================================
 // Route Metadata Macros
 // ============================================
@@ -978,6 +1111,27 @@ pub fn summary(attr: TokenStream, item: TokenStream) -> TokenStream {
     TokenStream::from(expanded)
 }
 
+/// Operation id macro for OpenAPI documentation
+///
+/// Sets the `operationId` client generators use as the method name. It takes
+/// precedence over ids generated by `RustApi::operation_ids`.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// #[rustapi::get("/pins")]
+/// #[rustapi::operation_id("listPins")]
+/// async fn list_pins() -> Json<Vec<Pin>> {
+///     Json(vec![])
+/// }
+/// ```
+#[proc_macro_attribute]
+pub fn operation_id(_attr: TokenStream, item: TokenStream) -> TokenStream {
+    // This is a passthrough. The route macros (get/post/...) read the
+    // attribute and call `Route::operation_id` on the generated route.
+    item
+}
+
 /// Description macro for detailed endpoint description in OpenAPI documentation
 ///
 /// # Example
@@ -1037,6 +1191,75 @@ pub fn mcp(_attr: TokenStream, item: TokenStream) -> TokenStream {
     item
 }
 
+/// Per-route middleware macro
+///
+/// Wraps the route generated by `#[rustapi::get]`, `#[rustapi::post]`, etc.
+/// in the given layers. Each argument is an expression evaluating to a
+/// `MiddlewareLayer`; layers run in the order listed (outermost first),
+/// inside any app-wide layers.
+///
+/// Place it below the route attribute, like the other route metadata macros.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// #[rustapi::post("/login")]
+/// #[rustapi::middleware(RateLimitLayer::new(5, Duration::from_secs(60)))]
+/// async fn login(Json(body): Json<Credentials>) -> Result<Json<Token>> { ... }
+///
+/// #[rustapi::get("/admin/stats")]
+/// #[rustapi::middleware(JwtLayer::<Claims>::new(SECRET), RequestIdLayer::new())]
+/// async fn stats() -> Json<Stats> { ... }
+/// ```
+#[proc_macro_attribute]
+pub fn middleware(_attr: TokenStream, item: TokenStream) -> TokenStream {
+    // This is a passthrough. The route macros (get/post/...) read the
+    // #[middleware(...)] attribute and add the layers to the generated route.
+    item
+}
+
+/// Role requirement macro
+///
+/// Rejects requests whose `Grants` (recorded by an authentication layer such
+/// as `JwtLayer`) lack the role: `401 Unauthorized` without grants,
+/// `403 Forbidden` without the role. The handler body does not run. The
+/// requirement is listed in the operation's OpenAPI `security` section.
+///
+/// Place it below the route attribute, like the other route metadata macros.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// #[rustapi::get("/admin/stats")]
+/// #[rustapi::require_role("admin")]
+/// async fn stats() -> Json<Stats> { ... }
+/// ```
+#[proc_macro_attribute]
+pub fn require_role(_attr: TokenStream, item: TokenStream) -> TokenStream {
+    // This is a passthrough. The route macros (get/post/...) read the
+    // attribute and call `Route::require_role` on the generated route.
+    item
+}
+
+/// Scope requirement macro
+///
+/// Like [`macro@require_role`], for a scope (`scope`, `scp`, `scopes` or
+/// `permissions` claim).
+///
+/// # Example
+///
+/// ```rust,ignore
+/// #[rustapi::delete("/pins/{id}")]
+/// #[rustapi::require_scope("pins:write")]
+/// async fn delete_pin(Path(id): Path<u64>) -> NoContent { ... }
+/// ```
+#[proc_macro_attribute]
+pub fn require_scope(_attr: TokenStream, item: TokenStream) -> TokenStream {
+    // This is a passthrough. The route macros (get/post/...) read the
+    // attribute and call `Route::require_scope` on the generated route.
+    item
+}
+
 /// Path parameter schema macro for OpenAPI documentation
 ///
 /// Use this to specify the OpenAPI schema type for a path parameter when
@@ -1105,6 +1328,33 @@ pub fn errors(_attr: TokenStream, item: TokenStream) -> TokenStream {
     item
 }
 
+/// Database model marker for `cargo rustapi migrate generate --from-models`
+///
+/// The CLI diffs the fields of marked structs against the live database
+/// schema and writes the difference as a candidate migration. `table`
+/// defaults to the snake_case struct name plus `s`. Columns follow sqlx
+/// conventions: `#[sqlx(rename = "...")]` renames a column, `#[sqlx(skip)]`
+/// leaves a field out, `Option<T>` is nullable and a field named `id` is the
+/// primary key.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// #[rustapi::model(table = "todos")]
+/// #[derive(sqlx::FromRow)]
+/// pub struct Todo {
+///     pub id: i64,
+///     pub title: String,
+///     pub done: bool,
+///     pub due: Option<chrono::NaiveDate>,
+/// }
+/// ```
+#[proc_macro_attribute]
+pub fn model(_attr: TokenStream, item: TokenStream) -> TokenStream {
+    // Only read by the CLI; the struct is left unchanged
+    item
+}
+
 // ============================================
 // Validation Derive Macro
 // ============================================
@@ -1127,7 +1377,7 @@ fn parse_validate_attrs(attrs: &[Attribute]) -> Vec<ValidationRuleInfo> {
             continue;
         }
 
-        // Parse the validate attribute
+        // Parse the validate attribute (`sanitize(...)` entries are handled separately)
         if let Ok(meta) = attr.parse_args::<Meta>() {
             if let Some(rule) = parse_validate_meta(&meta) {
                 rules.push(rule);
@@ -1143,6 +1393,7 @@ fn parse_validate_attrs(attrs: &[Attribute]) -> Vec<ValidationRuleInfo> {
         }
     }
 
+    rules.retain(|rule| rule.rule_type != "sanitize");
     rules
 }
 
@@ -1770,6 +2021,94 @@ fn generate_async_rule_validation(
     }
 }
 
+/// Sanitizers recognised by `#[validate(sanitize(...))]`, mapped to their function in
+/// `rustapi_validate::v2::sanitize`.
+const SANITIZERS: &[&str] = &[
+    "trim",
+    "lowercase",
+    "uppercase",
+    "collapse_whitespace",
+    "strip_html",
+    "escape_html",
+];
+
+/// Parse `#[validate(sanitize(...))]` entries from a field into sanitizer function paths.
+///
+/// Built-in sanitizers are referenced by name (`trim`, `lowercase`, ...);
+/// custom ones use `custom = "path::to::fn"` with signature `fn(&str) -> String`.
+/// The list lives inside `#[validate]` because `sanitize` is a reserved
+/// built-in attribute name.
+fn parse_sanitize_attrs(
+    attrs: &[Attribute],
+    validate_path: &proc_macro2::TokenStream,
+) -> Result<Vec<proc_macro2::TokenStream>, syn::Error> {
+    let mut sanitizers = Vec::new();
+
+    for attr in attrs {
+        if !attr.path().is_ident("validate") {
+            continue;
+        }
+
+        let Ok(nested) = attr
+            .parse_args_with(syn::punctuated::Punctuated::<Meta, syn::Token![,]>::parse_terminated)
+        else {
+            continue;
+        };
+
+        for meta in nested {
+            let Meta::List(list) = &meta else {
+                continue;
+            };
+            if !list.path.is_ident("sanitize") {
+                continue;
+            }
+
+            let steps = list.parse_args_with(
+                syn::punctuated::Punctuated::<Meta, syn::Token![,]>::parse_terminated,
+            )?;
+
+            for step in steps {
+                match &step {
+                    Meta::Path(path) => {
+                        let name = path.get_ident().map(|i| i.to_string()).unwrap_or_default();
+                        if !SANITIZERS.contains(&name.as_str()) {
+                            return Err(syn::Error::new_spanned(
+                                path,
+                                format!(
+                                    "unknown sanitizer `{}`; expected one of: {}, or custom = \"path\"",
+                                    name,
+                                    SANITIZERS.join(", ")
+                                ),
+                            ));
+                        }
+                        let ident = syn::Ident::new(&name, proc_macro2::Span::call_site());
+                        sanitizers.push(quote! { #validate_path::v2::sanitize::#ident });
+                    }
+                    Meta::NameValue(nv) if nv.path.is_ident("custom") => {
+                        let func = expr_to_string(&nv.value)
+                            .and_then(|s| syn::parse_str::<syn::Path>(&s).ok())
+                            .ok_or_else(|| {
+                                syn::Error::new_spanned(
+                                    &nv.value,
+                                    "custom sanitizer must be a string containing a function path",
+                                )
+                            })?;
+                        sanitizers.push(quote! { #func });
+                    }
+                    _ => {
+                        return Err(syn::Error::new_spanned(
+                            &step,
+                            "unsupported sanitize syntax; use sanitize(trim, lowercase) or sanitize(custom = \"path\")",
+                        ));
+                    }
+                }
+            }
+        }
+    }
+
+    Ok(sanitizers)
+}
+
 /// Check if a rule is async
 fn is_async_rule(rule: &ValidationRuleInfo) -> bool {
     matches!(
@@ -1780,6 +2119,13 @@ fn is_async_rule(rule: &ValidationRuleInfo) -> bool {
 
 /// Derive macro for implementing Validate and AsyncValidate traits
 ///
+/// Fields may also declare sanitizers with `#[validate(sanitize(...))]`
+/// (`trim`, `lowercase`, `uppercase`, `collapse_whitespace`, `strip_html`,
+/// `escape_html`, or `custom = "path::to::fn"`). Sanitizers run in declaration
+/// order before validation when the value is extracted with
+/// `ValidatedJson`/`ValidatedForm`. They are not a separate `#[sanitize]`
+/// attribute because rustc reserves that name for a built-in attribute.
+///
 /// # Example
 ///
 /// ```rust,ignore
@@ -1787,6 +2133,7 @@ fn is_async_rule(rule: &ValidationRuleInfo) -> bool {
 ///
 /// #[derive(Validate)]
 /// struct CreateUser {
+///     #[validate(sanitize(trim, lowercase))]
 ///     #[validate(email, message = "Invalid email format")]
 ///     email: String,
 ///     
@@ -1836,12 +2183,25 @@ pub fn derive_validate(input: TokenStream) -> TokenStream {
     let mut sync_validations = Vec::new();
     let mut async_validations = Vec::new();
     let mut has_async_rules = false;
+    let mut sanitize_steps = Vec::new();
 
     for field in fields {
         let field_name = field.ident.as_ref().unwrap().to_string();
+        let field_ident = field.ident.as_ref().unwrap();
         let field_type = &field.ty;
         let rules = parse_validate_attrs(&field.attrs);
 
+        match parse_sanitize_attrs(&field.attrs, &validate_path) {
+            Ok(sanitizers) => {
+                for sanitizer in sanitizers {
+                    sanitize_steps.push(quote! {
+                        #validate_path::v2::SanitizeField::apply_sanitizer(&mut self.#field_ident, #sanitizer);
+                    });
+                }
+            }
+            Err(err) => return err.to_compile_error().into(),
+        }
+
         for rule in &rules {
             if is_async_rule(rule) {
                 has_async_rules = true;
@@ -1894,6 +2254,15 @@ pub fn derive_validate(input: TokenStream) -> TokenStream {
         }
     };
 
+    // Generate the Sanitize impl (a no-op when no field declares sanitizers)
+    let sanitize_impl = quote! {
+        impl #impl_generics #validate_path::v2::Sanitize for #name #ty_generics #where_clause {
+            fn sanitize(&mut self) {
+                #(#sanitize_steps)*
+            }
+        }
+    };
+
     // Generate the Validatable impl for rustapi-core integration (exposed via rustapi-rs)
     // Paths are resolved dynamically so this works from both rustapi-rs and internal crates.
     let validatable_impl = quote! {
@@ -1904,12 +2273,24 @@ pub fn derive_validate(input: TokenStream) -> TokenStream {
                     Err(e) => Err(#core_path::validation::convert_v2_errors(e)),
                 }
             }
+
+            fn do_validate_localized(&self, locale: Option<&str>) -> Result<(), #core_path::ApiError> {
+                match #validate_path::v2::Validate::validate(self) {
+                    Ok(_) => Ok(()),
+                    Err(e) => Err(#core_path::validation::convert_v2_errors_localized(e, locale)),
+                }
+            }
+
+            fn do_sanitize(&mut self) {
+                #validate_path::v2::Sanitize::sanitize(self)
+            }
         }
     };
 
     let expanded = quote! {
         #validate_impl
         #async_validate_impl
+        #sanitize_impl
         #validatable_impl
     };
 
@@ -1924,6 +2305,9 @@ pub fn derive_validate(input: TokenStream) -> TokenStream {
 
 /// Derive macro for implementing IntoResponse for error enums
 ///
+/// Every variant's code is also registered in the error catalog
+/// (`rustapi_core::error_catalog`), with `description` falling back to `message`.
+///
 /// # Example
 ///
 /// ```rust,ignore
@@ -1934,6 +2318,10 @@ pub fn derive_validate(input: TokenStream) -> TokenStream {
 ///     
 ///     #[error(status = 400, code = "validation_error")]
 ///     InvalidInput(String),
+///
+///     #[error(status = 409, code = "user_exists", message = "User already exists",
+///             description = "A user with the same email is already registered")]
+///     Exists { email: String },
 /// }
 /// ```
 #[proc_macro_derive(ApiError, attributes(error))]
@@ -1957,6 +2345,10 @@ pub fn derive_api_error(input: TokenStream) -> TokenStream {
 ///     post_id: String,
 /// }
 /// ```
+///
+/// Every `{param}` in the path must have a field of the same (serde) name,
+/// and every field must appear in the path unless it is `#[serde(skip)]` or
+/// `#[serde(default)]`; mismatches are compile errors.
 #[proc_macro_derive(TypedPath, attributes(typed_path))]
 pub fn derive_typed_path(input: TokenStream) -> TokenStream {
     let input = parse_macro_input!(input as DeriveInput);
@@ -1966,16 +2358,16 @@ pub fn derive_typed_path(input: TokenStream) -> TokenStream {
     let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
 
     // Find the #[typed_path("...")] attribute
-    let mut path_str = None;
+    let mut path_lit = None;
     for attr in &input.attrs {
         if attr.path().is_ident("typed_path") {
             if let Ok(lit) = attr.parse_args::<LitStr>() {
-                path_str = Some(lit.value());
+                path_lit = Some(lit);
             }
         }
     }
 
-    let path = match path_str {
+    let path_lit = match path_lit {
         Some(p) => p,
         None => {
             return syn::Error::new_spanned(
@@ -1986,16 +2378,17 @@ pub fn derive_typed_path(input: TokenStream) -> TokenStream {
             .into();
         }
     };
+    let path = path_lit.value();
 
     // Validate path syntax
-    if let Err(err) = validate_path_syntax(&path, proc_macro2::Span::call_site()) {
+    if let Err(err) = validate_path_syntax(&path, path_lit.span()) {
         return err.to_compile_error().into();
     }
 
     // Generate to_uri implementation
     // We need to parse the path and replace {param} with self.param
     let mut format_string = String::new();
-    let mut format_args = Vec::new();
+    let mut params = Vec::new();
 
     let mut chars = path.chars().peekable();
     while let Some(ch) = chars.next() {
@@ -2019,13 +2412,52 @@ pub fn derive_typed_path(input: TokenStream) -> TokenStream {
             }
 
             format_string.push_str("{}");
-            let ident = syn::Ident::new(&param_name, proc_macro2::Span::call_site());
-            format_args.push(quote! { self.#ident });
+            params.push(param_name);
         } else {
             format_string.push(ch);
         }
     }
 
+    // The pattern's parameters and the struct's fields must match
+    let fields = match typed_path_fields(&input) {
+        Ok(fields) => fields,
+        Err(err) => return err.to_compile_error().into(),
+    };
+    let mut format_args = Vec::new();
+    for param in &params {
+        match fields.iter().find(|field| &field.name == param) {
+            Some(field) => {
+                let ident = &field.ident;
+                format_args.push(quote! { self.#ident });
+            }
+            None => {
+                return syn::Error::new(
+                    path_lit.span(),
+                    format!(
+                        "path parameter `{{{}}}` has no matching field in `{}`",
+                        param, name
+                    ),
+                )
+                .to_compile_error()
+                .into();
+            }
+        }
+    }
+    for field in &fields {
+        if !field.optional && !params.contains(&field.name) {
+            return syn::Error::new_spanned(
+                &field.ident,
+                format!(
+                    "field `{}` is not a parameter of the path \"{}\"; \
+                     add `{{{}}}` to the path or mark the field `#[serde(skip)]`",
+                    field.name, path, field.name
+                ),
+            )
+            .to_compile_error()
+            .into();
+        }
+    }
+
     let expanded = quote! {
         impl #impl_generics #rustapi_path::prelude::TypedPath for #name #ty_generics #where_clause {
             const PATH: &'static str = #path;
@@ -2039,3 +2471,64 @@ pub fn derive_typed_path(input: TokenStream) -> TokenStream {
     debug_output("TypedPath derive", &expanded);
     TokenStream::from(expanded)
 }
+
+/// A field of a `#[derive(TypedPath)]` struct
+struct TypedPathField {
+    ident: syn::Ident,
+    /// Name in the path (the serde name)
+    name: String,
+    /// Not required in the path (`#[serde(skip)]` / `#[serde(default)]`)
+    optional: bool,
+}
+
+fn typed_path_fields(input: &DeriveInput) -> Result<Vec<TypedPathField>, syn::Error> {
+    let fields = match &input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(fields) => &fields.named,
+            Fields::Unit => return Ok(Vec::new()),
+            Fields::Unnamed(_) => {
+                return Err(syn::Error::new_spanned(
+                    input,
+                    "#[derive(TypedPath)] requires named fields",
+                ))
+            }
+        },
+        _ => {
+            return Err(syn::Error::new_spanned(
+                input,
+                "#[derive(TypedPath)] can only be used on structs",
+            ))
+        }
+    };
+
+    let mut result = Vec::new();
+    for field in fields {
+        let ident = field.ident.clone().expect("named field");
+        let mut name = ident.to_string().trim_start_matches("r#").to_string();
+        let mut optional = false;
+        for attr in field.attrs.iter().filter(|a| a.path().is_ident("serde")) {
+            // Serde validates its own attributes; only the ones that matter
+            // here are read, anything unparseable is left to serde
+            let _ = attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("rename") {
+                    name = meta.value()?.parse::<LitStr>()?.value();
+                } else if meta.path.is_ident("skip")
+                    || meta.path.is_ident("skip_deserializing")
+                    || meta.path.is_ident("default")
+                {
+                    optional = true;
+                }
+                if meta.input.peek(syn::Token![=]) {
+                    meta.value()?.parse::<Expr>()?;
+                }
+                Ok(())
+            });
+        }
+        result.push(TypedPathField {
+            ident,
+            name,
+            optional,
+        });
+    }
+    Ok(result)
+}
@@ -0,0 +1,986 @@
+//! OpenAPI spec diffing
+//!
+//! [`SpecDiff`] compares two OpenAPI documents and classifies every change as
+//! breaking or non-breaking for existing clients, so API compatibility can be
+//! checked in CI (`cargo rustapi openapi diff`).
+//!
+//! Breaking changes are the ones an existing client can trip over:
+//!
+//! - an operation or a success response is removed
+//! - a required parameter, request property or request body is added, or an
+//!   optional one becomes required
+//! - a request accepts fewer types, enum values or media types
+//! - a response property is removed, stops being required, or returns new
+//!   types or enum values
+//! - an operation starts requiring authentication
+//!
+//! Everything else (new operations, optional parameters, extra response
+//! properties, ...) is reported as non-breaking.
+//!
+//! # Example
+//!
+//! ```rust
+//! use rustapi_openapi::diff::SpecDiff;
+//! use serde_json::json;
+//!
+//! let old = json!({"openapi": "3.1.0", "paths": {"/users": {"get": {"responses": {"200": {}}}}}});
+//! let new = json!({"openapi": "3.1.0", "paths": {}});
+//!
+//! let diff = SpecDiff::new(&old, &new);
+//! assert!(diff.has_breaking_changes());
+//! assert_eq!(diff.to_string(), "[breaking] GET /users: operation removed\n");
+//! ```
+
+use crate::OpenApiSpec;
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+
+const METHODS: [&str; 8] = [
+    "get", "put", "post", "delete", "options", "head", "patch", "trace",
+];
+
+/// Maximum schema nesting followed when comparing schemas
+const MAX_DEPTH: usize = 32;
+
+/// Whether a change can break existing clients
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ChangeKind {
+    /// Existing clients may fail
+    Breaking,
+    /// Existing clients keep working
+    NonBreaking,
+}
+
+/// A single difference between two specs
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct SpecChange {
+    /// Whether the change is breaking
+    pub kind: ChangeKind,
+    /// The affected operation (e.g. `GET /users/{id}`)
+    pub operation: String,
+    /// What changed
+    pub message: String,
+}
+
+impl fmt::Display for SpecChange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let kind = match self.kind {
+            ChangeKind::Breaking => "breaking",
+            ChangeKind::NonBreaking => "non-breaking",
+        };
+        write!(f, "[{}] {}: {}", kind, self.operation, self.message)
+    }
+}
+
+/// Differences between two OpenAPI documents, breaking changes first
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct SpecDiff {
+    changes: Vec<SpecChange>,
+}
+
+impl SpecDiff {
+    /// Compare two OpenAPI documents given as JSON
+    pub fn new(old: &Value, new: &Value) -> Self {
+        let mut differ = Differ {
+            old,
+            new,
+            changes: Vec::new(),
+            refs: Vec::new(),
+        };
+        differ.operations();
+        let mut changes = differ.changes;
+        // Stable: keeps the path order within each kind
+        changes.sort_by_key(|c| c.kind);
+        Self { changes }
+    }
+
+    /// Compare two generated specs
+    pub fn between(old: &OpenApiSpec, new: &OpenApiSpec) -> Self {
+        Self::new(&old.to_json(), &new.to_json())
+    }
+
+    /// All changes, breaking changes first
+    pub fn changes(&self) -> &[SpecChange] {
+        &self.changes
+    }
+
+    /// The breaking changes
+    pub fn breaking(&self) -> impl Iterator<Item = &SpecChange> {
+        self.changes
+            .iter()
+            .filter(|c| c.kind == ChangeKind::Breaking)
+    }
+
+    /// Whether any change is breaking
+    pub fn has_breaking_changes(&self) -> bool {
+        self.breaking().next().is_some()
+    }
+
+    /// Whether the specs describe the same API
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+
+    /// Serialize the diff as `{"changes": [...]}`
+    pub fn to_json(&self) -> Value {
+        serde_json::to_value(self).unwrap_or_else(|_| serde_json::json!({ "changes": [] }))
+    }
+}
+
+impl fmt::Display for SpecDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for change in &self.changes {
+            writeln!(f, "{}", change)?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Direction {
+    Request,
+    Response,
+}
+
+/// An operation of a spec with the parameters declared on its path item
+struct OperationRef<'a> {
+    label: String,
+    operation: &'a Value,
+    path_parameters: Option<&'a Value>,
+}
+
+struct Differ<'a> {
+    old: &'a Value,
+    new: &'a Value,
+    changes: Vec<SpecChange>,
+    /// `$ref` pairs being compared, to stop on recursive schemas
+    refs: Vec<(String, String)>,
+}
+
+impl<'a> Differ<'a> {
+    fn push(&mut self, kind: ChangeKind, operation: &str, message: String) {
+        self.changes.push(SpecChange {
+            kind,
+            operation: operation.to_string(),
+            message,
+        });
+    }
+
+    fn operations(&mut self) {
+        let old_ops = operations(self.old);
+        let new_ops = operations(self.new);
+
+        for (key, old_op) in &old_ops {
+            match new_ops.get(key) {
+                Some(new_op) => self.operation(old_op, new_op),
+                None => self.push(
+                    ChangeKind::Breaking,
+                    &old_op.label,
+                    "operation removed".to_string(),
+                ),
+            }
+        }
+        for (key, new_op) in &new_ops {
+            if !old_ops.contains_key(key) {
+                self.push(
+                    ChangeKind::NonBreaking,
+                    &new_op.label,
+                    "operation added".to_string(),
+                );
+            }
+        }
+    }
+
+    fn operation(&mut self, old: &OperationRef<'a>, new: &OperationRef<'a>) {
+        let label = new.label.as_str();
+
+        if !flag(old.operation, "deprecated") && flag(new.operation, "deprecated") {
+            self.push(
+                ChangeKind::NonBreaking,
+                label,
+                "operation deprecated".to_string(),
+            );
+        }
+
+        let old_secured = secured(self.old, old.operation);
+        let new_secured = secured(self.new, new.operation);
+        if !old_secured && new_secured {
+            self.push(
+                ChangeKind::Breaking,
+                label,
+                "authentication is now required".to_string(),
+            );
+        } else if old_secured && !new_secured {
+            self.push(
+                ChangeKind::NonBreaking,
+                label,
+                "authentication is no longer required".to_string(),
+            );
+        }
+
+        self.parameters(label, old, new);
+        self.request_body(label, old.operation, new.operation);
+        self.responses(label, old.operation, new.operation);
+    }
+
+    fn parameters(&mut self, label: &str, old: &OperationRef<'a>, new: &OperationRef<'a>) {
+        let old_params = parameters(self.old, old);
+        let new_params = parameters(self.new, new);
+
+        for ((location, name), old_param) in &old_params {
+            let Some(new_param) = new_params.get(&(location.clone(), name.clone())) else {
+                self.push(
+                    ChangeKind::NonBreaking,
+                    label,
+                    format!("{} parameter `{}` removed", location, name),
+                );
+                continue;
+            };
+            let (old_required, new_required) =
+                (flag(old_param, "required"), flag(new_param, "required"));
+            if !old_required && new_required {
+                self.push(
+                    ChangeKind::Breaking,
+                    label,
+                    format!("{} parameter `{}` is now required", location, name),
+                );
+            } else if old_required && !new_required {
+                self.push(
+                    ChangeKind::NonBreaking,
+                    label,
+                    format!("{} parameter `{}` is now optional", location, name),
+                );
+            }
+            if let (Some(old_schema), Some(new_schema)) =
+                (old_param.get("schema"), new_param.get("schema"))
+            {
+                self.schema(
+                    Direction::Request,
+                    label,
+                    &format!("{} parameter `{}`", location, name),
+                    old_schema,
+                    new_schema,
+                    0,
+                );
+            }
+        }
+        for ((location, name), new_param) in &new_params {
+            if old_params.contains_key(&(location.clone(), name.clone())) {
+                continue;
+            }
+            if flag(new_param, "required") {
+                self.push(
+                    ChangeKind::Breaking,
+                    label,
+                    format!("required {} parameter `{}` added", location, name),
+                );
+            } else {
+                self.push(
+                    ChangeKind::NonBreaking,
+                    label,
+                    format!("optional {} parameter `{}` added", location, name),
+                );
+            }
+        }
+    }
+
+    fn request_body(&mut self, label: &str, old: &Value, new: &Value) {
+        let old_body = old
+            .get("requestBody")
+            .map(|b| resolve(self.old, b, "requestBodies"));
+        let new_body = new
+            .get("requestBody")
+            .map(|b| resolve(self.new, b, "requestBodies"));
+
+        match (old_body, new_body) {
+            (None, None) => {}
+            (None, Some(body)) => {
+                if flag(body, "required") {
+                    self.push(
+                        ChangeKind::Breaking,
+                        label,
+                        "required request body added".to_string(),
+                    );
+                } else {
+                    self.push(
+                        ChangeKind::NonBreaking,
+                        label,
+                        "optional request body added".to_string(),
+                    );
+                }
+            }
+            (Some(_), None) => self.push(
+                ChangeKind::NonBreaking,
+                label,
+                "request body removed".to_string(),
+            ),
+            (Some(old_body), Some(new_body)) => {
+                if !flag(old_body, "required") && flag(new_body, "required") {
+                    self.push(
+                        ChangeKind::Breaking,
+                        label,
+                        "request body is now required".to_string(),
+                    );
+                }
+                self.content(
+                    Direction::Request,
+                    label,
+                    "request body",
+                    old_body,
+                    new_body,
+                );
+            }
+        }
+    }
+
+    fn responses(&mut self, label: &str, old: &Value, new: &Value) {
+        let empty = serde_json::Map::new();
+        let old_responses = old
+            .get("responses")
+            .and_then(Value::as_object)
+            .unwrap_or(&empty);
+        let new_responses = new
+            .get("responses")
+            .and_then(Value::as_object)
+            .unwrap_or(&empty);
+
+        for (status, old_response) in old_responses {
+            let Some(new_response) = new_responses.get(status) else {
+                let kind = if status.starts_with('2') {
+                    ChangeKind::Breaking
+                } else {
+                    ChangeKind::NonBreaking
+                };
+                self.push(kind, label, format!("response {} removed", status));
+                continue;
+            };
+            let old_response = resolve(self.old, old_response, "responses");
+            let new_response = resolve(self.new, new_response, "responses");
+            self.content(
+                Direction::Response,
+                label,
+                &format!("response {}", status),
+                old_response,
+                new_response,
+            );
+        }
+        for status in new_responses.keys() {
+            if !old_responses.contains_key(status) {
+                self.push(
+                    ChangeKind::NonBreaking,
+                    label,
+                    format!("response {} added", status),
+                );
+            }
+        }
+    }
+
+    /// Compare the `content` of a request body or response
+    fn content(
+        &mut self,
+        direction: Direction,
+        label: &str,
+        context: &str,
+        old: &Value,
+        new: &Value,
+    ) {
+        let empty = serde_json::Map::new();
+        let old_content = old
+            .get("content")
+            .and_then(Value::as_object)
+            .unwrap_or(&empty);
+        let new_content = new
+            .get("content")
+            .and_then(Value::as_object)
+            .unwrap_or(&empty);
+
+        let body_context = match direction {
+            Direction::Request => context.to_string(),
+            Direction::Response => format!("{} body", context),
+        };
+        for (media_type, old_media) in old_content {
+            match new_content.get(media_type) {
+                Some(new_media) => {
+                    if let (Some(old_schema), Some(new_schema)) =
+                        (old_media.get("schema"), new_media.get("schema"))
+                    {
+                        self.schema(direction, label, &body_context, old_schema, new_schema, 0);
+                    }
+                }
+                None => {
+                    let message = match direction {
+                        Direction::Request => {
+                            format!("{} no longer accepts `{}`", context, media_type)
+                        }
+                        Direction::Response => {
+                            format!("{} no longer returns `{}`", context, media_type)
+                        }
+                    };
+                    self.push(ChangeKind::Breaking, label, message);
+                }
+            }
+        }
+        for media_type in new_content.keys() {
+            if !old_content.contains_key(media_type) {
+                let message = match direction {
+                    Direction::Request => format!("{} also accepts `{}`", context, media_type),
+                    Direction::Response => format!("{} also returns `{}`", context, media_type),
+                };
+                self.push(ChangeKind::NonBreaking, label, message);
+            }
+        }
+    }
+
+    fn schema(
+        &mut self,
+        direction: Direction,
+        label: &str,
+        context: &str,
+        old: &Value,
+        new: &Value,
+        depth: usize,
+    ) {
+        if depth > MAX_DEPTH {
+            return;
+        }
+        let ref_pair = match (schema_ref(old), schema_ref(new)) {
+            (Some(o), Some(n)) => Some((o.to_string(), n.to_string())),
+            _ => None,
+        };
+        if let Some(pair) = &ref_pair {
+            if self.refs.contains(pair) {
+                return;
+            }
+            self.refs.push(pair.clone());
+        }
+        let old = resolve(self.old, old, "schemas");
+        let new = resolve(self.new, new, "schemas");
+
+        self.types(direction, label, context, old, new);
+        self.enums(direction, label, context, old, new);
+        self.properties(direction, label, context, old, new, depth);
+        if let (Some(old_items), Some(new_items)) = (old.get("items"), new.get("items")) {
+            self.schema(
+                direction,
+                label,
+                &format!("{}[]", context),
+                old_items,
+                new_items,
+                depth + 1,
+            );
+        }
+
+        if ref_pair.is_some() {
+            self.refs.pop();
+        }
+    }
+
+    fn types(
+        &mut self,
+        direction: Direction,
+        label: &str,
+        context: &str,
+        old: &Value,
+        new: &Value,
+    ) {
+        let (old_types, new_types) = (types(old), types(new));
+        if old_types.is_empty() || new_types.is_empty() || old_types == new_types {
+            return;
+        }
+        // Requests must keep accepting every old type, responses must not
+        // return new ones
+        let breaking = match direction {
+            Direction::Request => !old_types.is_subset(&new_types),
+            Direction::Response => !new_types.is_subset(&old_types),
+        };
+        self.push(
+            if breaking {
+                ChangeKind::Breaking
+            } else {
+                ChangeKind::NonBreaking
+            },
+            label,
+            format!(
+                "{}: type changed from {} to {}",
+                context,
+                join(&old_types),
+                join(&new_types)
+            ),
+        );
+    }
+
+    fn enums(
+        &mut self,
+        direction: Direction,
+        label: &str,
+        context: &str,
+        old: &Value,
+        new: &Value,
+    ) {
+        let (Some(old_values), Some(new_values)) = (
+            old.get("enum").and_then(Value::as_array),
+            new.get("enum").and_then(Value::as_array),
+        ) else {
+            return;
+        };
+        let removed: Vec<String> = old_values
+            .iter()
+            .filter(|v| !new_values.contains(v))
+            .map(Value::to_string)
+            .collect();
+        let added: Vec<String> = new_values
+            .iter()
+            .filter(|v| !old_values.contains(v))
+            .map(Value::to_string)
+            .collect();
+
+        if !removed.is_empty() {
+            let kind = match direction {
+                Direction::Request => ChangeKind::Breaking,
+                Direction::Response => ChangeKind::NonBreaking,
+            };
+            self.push(
+                kind,
+                label,
+                format!("{}: enum values removed: {}", context, removed.join(", ")),
+            );
+        }
+        if !added.is_empty() {
+            let kind = match direction {
+                Direction::Request => ChangeKind::NonBreaking,
+                Direction::Response => ChangeKind::Breaking,
+            };
+            self.push(
+                kind,
+                label,
+                format!("{}: enum values added: {}", context, added.join(", ")),
+            );
+        }
+    }
+
+    fn properties(
+        &mut self,
+        direction: Direction,
+        label: &str,
+        context: &str,
+        old: &Value,
+        new: &Value,
+        depth: usize,
+    ) {
+        let empty = serde_json::Map::new();
+        let old_props = old
+            .get("properties")
+            .and_then(Value::as_object)
+            .unwrap_or(&empty);
+        let new_props = new
+            .get("properties")
+            .and_then(Value::as_object)
+            .unwrap_or(&empty);
+        let (old_required, new_required) = (required(old), required(new));
+
+        for (name, old_prop) in old_props {
+            let Some(new_prop) = new_props.get(name) else {
+                let kind = match direction {
+                    Direction::Request => ChangeKind::NonBreaking,
+                    Direction::Response => ChangeKind::Breaking,
+                };
+                self.push(
+                    kind,
+                    label,
+                    format!("{}: property `{}` removed", context, name),
+                );
+                continue;
+            };
+            let (was_required, is_required) = (
+                old_required.contains(name.as_str()),
+                new_required.contains(name.as_str()),
+            );
+            match direction {
+                Direction::Request if !was_required && is_required => self.push(
+                    ChangeKind::Breaking,
+                    label,
+                    format!("{}: property `{}` is now required", context, name),
+                ),
+                Direction::Response if was_required && !is_required => self.push(
+                    ChangeKind::Breaking,
+                    label,
+                    format!("{}: property `{}` is no longer required", context, name),
+                ),
+                _ => {}
+            }
+            self.schema(
+                direction,
+                label,
+                &format!("{}.{}", context, name),
+                old_prop,
+                new_prop,
+                depth + 1,
+            );
+        }
+        for name in new_props.keys() {
+            if old_props.contains_key(name) {
+                continue;
+            }
+            if direction == Direction::Request && new_required.contains(name.as_str()) {
+                self.push(
+                    ChangeKind::Breaking,
+                    label,
+                    format!("{}: required property `{}` added", context, name),
+                );
+            } else {
+                self.push(
+                    ChangeKind::NonBreaking,
+                    label,
+                    format!("{}: property `{}` added", context, name),
+                );
+            }
+        }
+    }
+}
+
+/// The operations of a spec keyed by method and path template, with path
+/// parameter names erased so renaming `{id}` to `{user_id}` is no change
+fn operations(spec: &Value) -> BTreeMap<(String, String), OperationRef<'_>> {
+    let mut operations = BTreeMap::new();
+    let Some(paths) = spec.get("paths").and_then(Value::as_object) else {
+        return operations;
+    };
+    for (path, item) in paths {
+        for method in METHODS {
+            if let Some(operation) = item.get(method) {
+                let upper = method.to_ascii_uppercase();
+                operations.insert(
+                    (normalize_path(path), upper.clone()),
+                    OperationRef {
+                        label: format!("{} {}", upper, path),
+                        operation,
+                        path_parameters: item.get("parameters"),
+                    },
+                );
+            }
+        }
+    }
+    operations
+}
+
+fn normalize_path(path: &str) -> String {
+    let mut normalized = String::with_capacity(path.len());
+    let mut in_param = false;
+    for c in path.chars() {
+        match c {
+            '{' => {
+                in_param = true;
+                normalized.push_str("{}");
+            }
+            '}' => in_param = false,
+            _ if !in_param => normalized.push(c),
+            _ => {}
+        }
+    }
+    normalized
+}
+
+/// Parameters by (location, name); operation parameters override path ones
+fn parameters<'a>(
+    spec: &'a Value,
+    operation: &OperationRef<'a>,
+) -> BTreeMap<(String, String), &'a Value> {
+    let mut parameters = BTreeMap::new();
+    let declared = operation
+        .path_parameters
+        .and_then(Value::as_array)
+        .into_iter()
+        .chain(
+            operation
+                .operation
+                .get("parameters")
+                .and_then(Value::as_array),
+        )
+        .flatten();
+    for parameter in declared {
+        let parameter = resolve(spec, parameter, "parameters");
+        if let (Some(location), Some(name)) = (
+            parameter.get("in").and_then(Value::as_str),
+            parameter.get("name").and_then(Value::as_str),
+        ) {
+            parameters.insert((location.to_string(), name.to_string()), parameter);
+        }
+    }
+    parameters
+}
+
+fn schema_ref(value: &Value) -> Option<&str> {
+    value.get("$ref").and_then(Value::as_str)
+}
+
+/// Follow `$ref`s into `#/components/<section>/...`
+fn resolve<'a>(spec: &'a Value, value: &'a Value, section: &str) -> &'a Value {
+    let mut current = value;
+    for _ in 0..MAX_DEPTH {
+        let Some(reference) = schema_ref(current) else {
+            break;
+        };
+        let prefix = format!("#/components/{}/", section);
+        match reference
+            .strip_prefix(&prefix)
+            .and_then(|name| spec.get("components")?.get(section)?.get(name))
+        {
+            Some(target) => current = target,
+            None => break,
+        }
+    }
+    current
+}
+
+fn flag(value: &Value, key: &str) -> bool {
+    value.get(key).and_then(Value::as_bool).unwrap_or(false)
+}
+
+/// Whether the operation requires any security scheme
+fn secured(spec: &Value, operation: &Value) -> bool {
+    let requirements = operation
+        .get("security")
+        .or_else(|| spec.get("security"))
+        .and_then(Value::as_array);
+    match requirements {
+        // An empty requirement object makes authentication optional
+        Some(requirements) => {
+            !requirements.is_empty()
+                && requirements
+                    .iter()
+                    .all(|r| r.as_object().is_some_and(|r| !r.is_empty()))
+        }
+        None => false,
+    }
+}
+
+fn types(schema: &Value) -> BTreeSet<String> {
+    let mut types: BTreeSet<String> = match schema.get("type") {
+        Some(Value::String(t)) => BTreeSet::from([t.clone()]),
+        Some(Value::Array(ts)) => ts
+            .iter()
+            .filter_map(Value::as_str)
+            .map(str::to_string)
+            .collect(),
+        _ => BTreeSet::new(),
+    };
+    if !types.is_empty() && flag(schema, "nullable") {
+        types.insert("null".to_string());
+    }
+    types
+}
+
+fn required(schema: &Value) -> BTreeSet<&str> {
+    schema
+        .get("required")
+        .and_then(Value::as_array)
+        .map(|r| r.iter().filter_map(Value::as_str).collect())
+        .unwrap_or_default()
+}
+
+fn join(types: &BTreeSet<String>) -> String {
+    types.iter().cloned().collect::<Vec<_>>().join("|")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn spec(paths: Value) -> Value {
+        json!({
+            "openapi": "3.1.0",
+            "info": {"title": "Test", "version": "1.0.0"},
+            "paths": paths,
+            "components": {"schemas": {
+                "User": {
+                    "type": "object",
+                    "properties": {
+                        "id": {"type": "integer"},
+                        "name": {"type": "string"},
+                        "role": {"type": "string", "enum": ["admin", "member"]}
+                    },
+                    "required": ["id", "name"]
+                }
+            }}
+        })
+    }
+
+    fn user_api(user: Value, query: Value) -> Value {
+        let mut spec = spec(json!({
+            "/users/{id}": {
+                "get": {
+                    "parameters": [
+                        {"name": "id", "in": "path", "required": true, "schema": {"type": "integer"}},
+                        query
+                    ],
+                    "responses": {"200": {"description": "OK", "content": {
+                        "application/json": {"schema": {"$ref": "#/components/schemas/User"}}
+                    }}}
+                }
+            }
+        }));
+        spec["components"]["schemas"]["User"] = user;
+        spec
+    }
+
+    fn messages(diff: &SpecDiff, kind: ChangeKind) -> Vec<String> {
+        diff.changes()
+            .iter()
+            .filter(|c| c.kind == kind)
+            .map(|c| c.message.clone())
+            .collect()
+    }
+
+    #[test]
+    fn identical_specs_have_no_changes() {
+        let old = spec(json!({"/users": {"get": {"responses": {"200": {}}}}}));
+        assert!(SpecDiff::new(&old, &old).is_empty());
+    }
+
+    #[test]
+    fn added_and_removed_operations() {
+        let old = spec(json!({"/users": {"get": {"responses": {"200": {}}}}}));
+        let new = spec(json!({"/users/{user_id}": {"get": {"responses": {"200": {}}}}}));
+        let diff = SpecDiff::new(&old, &new);
+        assert_eq!(
+            diff.to_string(),
+            "[breaking] GET /users: operation removed\n\
+             [non-breaking] GET /users/{user_id}: operation added\n"
+        );
+
+        let renamed = spec(json!({"/users/{id}": {"get": {"responses": {"200": {}}}}}));
+        assert!(SpecDiff::new(&new, &renamed).is_empty());
+    }
+
+    #[test]
+    fn response_schema_changes() {
+        let query = json!({"name": "verbose", "in": "query", "schema": {"type": "boolean"}});
+        let old = user_api(
+            spec(json!({}))["components"]["schemas"]["User"].clone(),
+            query.clone(),
+        );
+        let new = user_api(
+            json!({
+                "type": "object",
+                "properties": {
+                    "id": {"type": "string"},
+                    "name": {"type": "string"},
+                    "role": {"type": "string", "enum": ["admin", "member", "guest"]},
+                    "email": {"type": "string"}
+                },
+                "required": ["id"]
+            }),
+            query,
+        );
+        let diff = SpecDiff::new(&old, &new);
+        assert_eq!(
+            messages(&diff, ChangeKind::Breaking),
+            vec![
+                "response 200 body.id: type changed from integer to string",
+                "response 200 body: property `name` is no longer required",
+                "response 200 body.role: enum values added: \"guest\"",
+            ]
+        );
+        assert_eq!(
+            messages(&diff, ChangeKind::NonBreaking),
+            vec!["response 200 body: property `email` added"]
+        );
+    }
+
+    #[test]
+    fn parameter_changes() {
+        let user = spec(json!({}))["components"]["schemas"]["User"].clone();
+        let old = user_api(
+            user.clone(),
+            json!({"name": "verbose", "in": "query", "schema": {"type": "boolean"}}),
+        );
+        let new = user_api(
+            user,
+            json!({"name": "verbose", "in": "query", "required": true, "schema": {"type": ["boolean", "string"]}}),
+        );
+        let diff = SpecDiff::new(&old, &new);
+        assert_eq!(
+            messages(&diff, ChangeKind::Breaking),
+            vec!["query parameter `verbose` is now required"]
+        );
+        assert_eq!(
+            messages(&diff, ChangeKind::NonBreaking),
+            vec!["query parameter `verbose`: type changed from boolean to boolean|string"]
+        );
+    }
+
+    #[test]
+    fn request_body_changes() {
+        let body = |required: Value, media: &str| {
+            json!({"/users": {"post": {
+                "requestBody": {"content": {media: {"schema": {
+                    "type": "object",
+                    "properties": {"name": {"type": "string"}, "age": {"type": "integer"}},
+                    "required": required
+                }}}},
+                "responses": {"201": {"description": "Created"}}
+            }}})
+        };
+        let old = spec(body(json!(["name"]), "application/json"));
+        let new = spec(body(json!(["name", "age"]), "application/json"));
+        assert_eq!(
+            messages(&SpecDiff::new(&old, &new), ChangeKind::Breaking),
+            vec!["request body: property `age` is now required"]
+        );
+
+        let toon = spec(body(json!(["name"]), "application/toon"));
+        let diff = SpecDiff::new(&old, &toon);
+        assert_eq!(
+            messages(&diff, ChangeKind::Breaking),
+            vec!["request body no longer accepts `application/json`"]
+        );
+        assert!(diff.has_breaking_changes());
+    }
+
+    #[test]
+    fn security_and_responses() {
+        let old = spec(json!({"/me": {"get": {"responses": {"200": {}, "404": {}}}}}));
+        let new = spec(json!({"/me": {"get": {
+            "security": [{"bearer": []}],
+            "responses": {"200": {}, "401": {}}
+        }}}));
+        let diff = SpecDiff::new(&old, &new);
+        assert_eq!(
+            messages(&diff, ChangeKind::Breaking),
+            vec!["authentication is now required"]
+        );
+        assert_eq!(
+            messages(&diff, ChangeKind::NonBreaking),
+            vec!["response 404 removed", "response 401 added"]
+        );
+    }
+
+    #[test]
+    fn recursive_schemas_terminate() {
+        let tree = |extra: bool| {
+            let mut node = json!({
+                "type": "object",
+                "properties": {"children": {"type": "array", "items": {"$ref": "#/components/schemas/Node"}}}
+            });
+            if extra {
+                node["properties"]["label"] = json!({"type": "string"});
+            }
+            json!({
+                "openapi": "3.1.0",
+                "paths": {"/tree": {"get": {"responses": {"200": {"content": {
+                    "application/json": {"schema": {"$ref": "#/components/schemas/Node"}}
+                }}}}}},
+                "components": {"schemas": {"Node": node}}
+            })
+        };
+        let diff = SpecDiff::new(&tree(false), &tree(true));
+        assert_eq!(
+            messages(&diff, ChangeKind::NonBreaking),
+            vec!["response 200 body: property `label` added"]
+        );
+    }
+}
@@ -62,6 +62,7 @@
 extern crate self as rustapi_openapi;
 
 mod config;
+pub mod diff;
 #[cfg(feature = "redoc")]
 mod redoc;
 pub mod schema;
@@ -82,7 +83,7 @@ pub use schemas::{
 };
 pub use spec::{
     ApiInfo, Components, McpOperation, MediaType, OpenApiSpec, Operation, OperationModifier,
-    Parameter, PathItem, RequestBody, ResponseModifier, ResponseSpec, SchemaRef,
+    Parameter, PathItem, RequestBody, ResponseModifier, ResponseSpec, SchemaRef, SecurityScheme,
 };
 
 // Re-export Schema derive macro
@@ -540,6 +540,12 @@ pub struct Parameter {
     pub required: bool,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub deprecated: Option<bool>,
+    /// Serialization style, e.g. `form` for query arrays
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub style: Option<String>,
+    /// Whether arrays are sent as repeated parameters
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub explode: Option<bool>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub schema: Option<SchemaRef>,
 }
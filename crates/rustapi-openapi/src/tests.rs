@@ -113,6 +113,100 @@ mod tests {
         assert!(schema.one_of.is_some());
     }
 
+    #[derive(Schema, serde::Serialize)]
+    #[serde(rename_all = "camelCase")]
+    #[allow(dead_code)]
+    struct RenamedFields {
+        user_id: i64,
+        #[serde(rename = "mail")]
+        email: String,
+        #[serde(rename(serialize = "shown", deserialize = "given"))]
+        display_name: String,
+    }
+
+    #[test]
+    fn test_derive_schema_serde_field_renames() {
+        let mut ctx = SchemaCtx::new();
+        let _ = RenamedFields::schema(&mut ctx);
+
+        let schema = ctx.components.get("RenamedFields").unwrap();
+        let props = schema.properties.as_ref().unwrap();
+        let names: Vec<_> = props.keys().map(String::as_str).collect();
+        assert_eq!(names, ["mail", "shown", "userId"]);
+    }
+
+    #[derive(Schema, serde::Serialize)]
+    #[serde(rename_all = "snake_case")]
+    #[allow(dead_code)]
+    enum RenamedStatus {
+        InReview,
+        #[serde(rename = "done")]
+        Finished,
+        #[serde(rename(serialize = "gone"))]
+        Archived,
+    }
+
+    #[derive(Schema, serde::Serialize)]
+    #[serde(rename_all = "kebab-case", rename_all_fields = "camelCase")]
+    #[allow(dead_code)]
+    enum RenamedEvent {
+        UserCreated {
+            user_id: i64,
+        },
+        #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+        UserDeleted {
+            user_id: i64,
+            #[serde(rename = "why")]
+            reason: String,
+        },
+        Ping(i64),
+    }
+
+    #[test]
+    fn test_derive_schema_serde_variant_renames() {
+        let mut ctx = SchemaCtx::new();
+        let _ = RenamedStatus::schema(&mut ctx);
+        let _ = RenamedEvent::schema(&mut ctx);
+
+        let status = ctx.components.get("RenamedStatus").unwrap();
+        assert_eq!(
+            status.enum_values.as_ref().unwrap(),
+            &vec![
+                serde_json::json!("in_review"),
+                serde_json::json!("done"),
+                serde_json::json!("gone")
+            ]
+        );
+
+        let event = ctx.components.get("RenamedEvent").unwrap();
+        let variants: Vec<_> = event
+            .one_of
+            .as_ref()
+            .unwrap()
+            .iter()
+            .map(|v| {
+                let (name, inner) = v.properties.as_ref().unwrap().iter().next().unwrap();
+                let fields: Vec<_> = inner
+                    .properties
+                    .iter()
+                    .flat_map(|p| p.keys().cloned())
+                    .collect();
+                (name.clone(), fields)
+            })
+            .collect();
+        assert_eq!(
+            variants,
+            [
+                ("user-created".to_string(), vec!["userId".to_string()]),
+                (
+                    "user-deleted".to_string(),
+                    vec!["USER_ID".to_string(), "why".to_string()]
+                ),
+                ("ping".to_string(), vec![]),
+            ]
+        );
+    }
+
     #[derive(Schema)]
     #[allow(dead_code)]
     struct Wrapper<T: RustApiSchema> {
@@ -204,6 +298,8 @@ mod tests {
             required: false,
             description: None,
             deprecated: None,
+            style: None,
+            explode: None,
             schema: Some(SchemaRef::Ref {
                 reference: "#/components/schemas/TestUser".to_string(),
             }),
@@ -228,6 +324,8 @@ mod tests {
             required: false,
             description: None,
             deprecated: None,
+            style: None,
+            explode: None,
             schema: Some(SchemaRef::Ref {
                 reference: "#/components/schemas/NonExistent".to_string(),
             }),
@@ -256,6 +354,8 @@ mod tests {
                 required: false,
                 description: None,
                 deprecated: None,
+                style: None,
+                explode: None,
                 schema: Some(SchemaRef::Ref {
                     reference: "#/components/schemas/NonExistent".to_string(),
                 }),
@@ -88,13 +88,9 @@ async fn logout(session: Session) -> NoContent {
 #[cfg(any(feature = "extras-session", feature = "session"))]
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
-    println!("Starting session auth example...");
-    println!(" -> POST   http://127.0.0.1:3000/auth/login   {{\"user_id\":\"demo-user\"}}");
-    println!(" -> GET    http://127.0.0.1:3000/auth/me");
-    println!(" -> POST   http://127.0.0.1:3000/auth/refresh");
-    println!(" -> POST   http://127.0.0.1:3000/auth/logout");
-
+    // Log in with: POST /auth/login {"user_id":"demo-user"}
     RustApi::new()
+        .startup_report(true)
         .layer(SessionLayer::new(
             MemorySessionStore::new(),
             SessionConfig::new()
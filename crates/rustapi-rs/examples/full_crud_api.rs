@@ -99,14 +99,8 @@ async fn delete_todo(State(state): State<AppState>, Path(id): Path<u64>) -> NoCo
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
-    println!("Starting CRUD example...");
-    println!(" -> GET    http://127.0.0.1:3000/todos");
-    println!(" -> POST   http://127.0.0.1:3000/todos");
-    println!(" -> GET    http://127.0.0.1:3000/todos/1");
-    println!(" -> PUT    http://127.0.0.1:3000/todos/1");
-    println!(" -> DELETE http://127.0.0.1:3000/todos/1");
-
     RustApi::new()
+        .startup_report(true)
         .state(AppState {
             next_id: Arc::new(AtomicU64::new(1)),
             todos: Arc::new(RwLock::new(HashMap::new())),
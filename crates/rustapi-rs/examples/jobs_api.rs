@@ -110,11 +110,6 @@ async fn queue_stats(State(state): State<AppState>) -> Json<WorkerResponse> {
 #[cfg(any(feature = "extras-jobs", feature = "jobs"))]
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
-    println!("Starting jobs example...");
-    println!(" -> POST http://127.0.0.1:3000/jobs/email");
-    println!(" -> POST http://127.0.0.1:3000/jobs/process-next");
-    println!(" -> GET  http://127.0.0.1:3000/jobs/stats");
-
     let processed_jobs = Arc::new(AtomicU64::new(0));
     let queue = JobQueue::new(InMemoryBackend::new());
     queue
@@ -124,6 +119,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
         .await;
 
     RustApi::new()
+        .startup_report(true)
         .state(AppState {
             processed_jobs,
             queue,
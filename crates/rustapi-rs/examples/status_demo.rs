@@ -39,14 +39,10 @@ async fn main() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
         }
     }
 
-    // 2. Build the app with status page enabled
-    println!("Starting Status Page Demo...");
-    println!(" -> Open http://127.0.0.1:3000/status to see the dashboard");
-    println!(" -> Visit http://127.0.0.1:3000/fast to generate traffic");
-    println!(" -> Visit http://127.0.0.1:3000/slow to generate latency");
-    println!(" -> Visit http://127.0.0.1:3000/flaky to generate errors");
-
+    // 2. Build the app with status page enabled; open /status to see the
+    //    dashboard and call /fast, /slow and /flaky to generate traffic
     RustApi::auto()
+        .startup_report(true)
         .status_page() // <--- Enable Status Page
         .route("/fast", get(fast_handler))
         .route("/slow", get(slow_handler))
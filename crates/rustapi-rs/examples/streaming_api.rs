@@ -45,10 +45,8 @@ async fn progress_feed(
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
-    println!("Starting streaming example...");
-    println!(" -> GET http://127.0.0.1:3000/events");
-
     RustApi::new()
+        .startup_report(true)
         .route("/events", get(progress_feed))
         .run("127.0.0.1:3000")
         .await
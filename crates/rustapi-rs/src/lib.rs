@@ -23,7 +23,7 @@ pub mod __private {
     /// See `rustapi_core::__private::linkme` for more details.
     pub use rustapi_core::__private::linkme;
 
-    pub use rustapi_core::__private::{AUTO_ROUTES, AUTO_SCHEMAS};
+    pub use rustapi_core::__private::{AUTO_ROUTES, AUTO_SCHEMAS, ERROR_CODES};
     pub use rustapi_openapi as openapi;
     pub use rustapi_validate as validate;
     pub use serde_json;
@@ -33,32 +33,50 @@ pub mod __private {
 pub mod core {
     pub use rustapi_core::validation::Validatable;
     pub use rustapi_core::EventBus;
-    pub use rustapi_core::{auto_route_count, collect_auto_routes};
     pub use rustapi_core::{
-        delete, delete_route, get, get_route, patch, patch_route, post, post_route, put, put_route,
-        route, serve_dir, sse_from_iter, sse_response, ApiError, AsyncValidatedJson, Body,
-        BodyLimitLayer, BodyStream, BodyVariant, ClientIp, Created, CursorPaginate,
-        CursorPaginated, Environment, Extension, FieldError, FromRequest, FromRequestParts,
-        Handler, HandlerService, HeaderValue, Headers, HealthCheck, HealthCheckBuilder,
-        HealthCheckResult, HealthEndpointConfig, HealthStatus, Html, IntoResponse, Json, KeepAlive,
-        MethodRouter, Multipart, MultipartConfig, MultipartField, NoContent, Paginate, Paginated,
-        Path, ProductionDefaultsConfig, Query, Redirect, Request, RequestDispatcher, RequestId,
-        RequestIdLayer, Response, ResponseBody, Result, Route, RouteHandler, RouteMatch, Router,
-        RustApi, RustApiConfig, Sse, SseEvent, State, StaticFile, StaticFileConfig, StatusCode,
-        StreamBody, StreamingMultipart, StreamingMultipartField, TracingLayer, Typed, TypedPath,
-        UploadedFile, ValidatedJson, WithStatus,
+        any_route, delete, delete_route, get, get_route, head_route, options_route, patch,
+        patch_route, post, post_route, put, put_route, route, serve_dir, sse_from_broadcast,
+        sse_from_iter, sse_response, ApiError, Arm, ArmStats, AsyncValidatedJson, Body,
+        BodyLimitLayer, BodyStream, BodyTransformLayer, BodyVariant, Canary, CanaryArm,
+        CanaryStats, ClientIp, Clock, Created, CursorPaginate, CursorPaginated, Deadline,
+        DeadlineExceeded, DirectoryListing, DuplicateRoute, DuplicateRouteError,
+        DuplicateRoutePolicy, Environment, ErrorCatalog, ErrorCodeEntry, Extension, FieldError,
+        FieldRule, FromRequest, FromRequestParts, Handler, HandlerService, Handover, HeaderStruct,
+        HeaderValue, Headers, HealthCheck, HealthCheckBuilder, HealthCheckResult,
+        HealthEndpointConfig, HealthStatus, Html, IntoResponse, Json, KeepAlive, LastEventId,
+        ListingSort, Locale, LocaleConfig, LogFilter, LogFilterError, LogSampling, LongPoll,
+        LongPollResponse, MethodRouter, Multipart, MultipartConfig, MultipartField, NoContent,
+        OperationIdContext, OperationIdStrategy, Paginate, Paginated, Path,
+        ProductionDefaultsConfig, Profile, Query, ReadinessGate, Redirect, Request,
+        RequestDispatcher, RequestId, RequestIdLayer, RequireGrant, Response, ResponseBody, Result,
+        Route, RouteHandler, RouteMatch, RouteOrigin, RouteTable, RouteTableEntry, Router, RustApi,
+        RustApiConfig, RustApiService, ServerTiming, ServerTimingLayer, Sse, SseEvent,
+        StartupReport, State, StaticFile, StaticFileConfig, StatusCode, StreamBody,
+        StreamingMultipart, StreamingMultipartField, Tenant, TenantLayer, TenantMap,
+        TenantResolver, TokenClaims, TracingLayer, Typed, TypedPath, UploadedFile, ValidatedForm,
+        ValidatedJson, WithStatus,
     };
+    pub use rustapi_core::{auto_route_count, collect_auto_routes};
 
+    pub use rustapi_core::canary;
+    pub use rustapi_core::error_format::{register_error_formatter, ErrorFormatter};
     pub use rustapi_core::get_environment;
+    pub use rustapi_core::locale;
+    pub use rustapi_core::query;
 
+    #[cfg(any(feature = "core-cookies", feature = "cookies"))]
+    pub use rustapi_core::typed_cookie::{CookieDefaults, CookieValue, SameSite, TypedCookie};
     #[cfg(any(feature = "core-cookies", feature = "cookies"))]
     pub use rustapi_core::Cookies;
 
+    #[cfg(feature = "core-cookies-secure")]
+    pub use rustapi_core::secure_cookies::{Cookie, CookieKeyring, PrivateCookies, SignedCookies};
+
     #[cfg(any(feature = "core-compression", feature = "compression"))]
     pub use rustapi_core::CompressionLayer;
 
     #[cfg(any(feature = "core-compression", feature = "compression"))]
-    pub use rustapi_core::middleware::{CompressionAlgorithm, CompressionConfig};
+    pub use rustapi_core::middleware::{CompressionAlgorithm, CompressionConfig, NoCompression};
 
     #[cfg(any(feature = "core-http3", feature = "protocol-http3", feature = "http3"))]
     pub use rustapi_core::{Http3Config, Http3Server};
@@ -133,7 +151,7 @@ pub mod extras {
 
     #[cfg(any(feature = "extras-sqlx", feature = "sqlx"))]
     pub mod sqlx {
-        pub use rustapi_extras::{convert_sqlx_error, SqlxErrorExt};
+        pub use rustapi_extras::{convert_sqlx_error, within_deadline, SqlxErrorExt};
     }
 
     #[cfg(any(feature = "extras-insight", feature = "insight"))]
@@ -176,6 +194,16 @@ pub mod extras {
         pub use rustapi_extras::api_key;
     }
 
+    #[cfg(any(feature = "extras-ip-filter", feature = "ip-filter"))]
+    pub mod ip_filter {
+        pub use rustapi_extras::ip_filter;
+    }
+
+    #[cfg(any(feature = "extras-geoip", feature = "geoip"))]
+    pub mod geoip {
+        pub use rustapi_extras::geoip;
+    }
+
     #[cfg(any(feature = "extras-cache", feature = "cache"))]
     pub mod cache {
         pub use rustapi_extras::cache;
@@ -209,8 +237,55 @@ pub mod extras {
         };
         pub use rustapi_extras::replay;
         pub use rustapi_extras::replay::{
-            FsReplayStore, FsReplayStoreConfig, InMemoryReplayStore, ReplayAdminAuth, ReplayClient,
-            ReplayClientError, ReplayLayer, RetentionJob,
+            BatchReplayOptions, BatchReplayReport, FsReplayStore, FsReplayStoreConfig,
+            InMemoryReplayStore, LatencySummary, ReplayAdminAuth, ReplayClient, ReplayClientError,
+            ReplayLayer, ReplayOutcome, RetentionJob, TracedReplay,
+        };
+
+        #[cfg(feature = "extras-replay-redis")]
+        pub use rustapi_extras::replay::RedisReplayStore;
+
+        #[cfg(feature = "extras-replay-postgres")]
+        pub use rustapi_extras::replay::SqlxReplayStore;
+    }
+
+    #[cfg(feature = "extras-http-client")]
+    pub mod http_client {
+        pub use rustapi_extras::http_client;
+        pub use rustapi_extras::http_client::{
+            HttpClient, HttpClientError, HttpClientStats, HttpRequest, RequestContext,
+        };
+    }
+
+    #[cfg(any(feature = "extras-shadow", feature = "shadow"))]
+    pub mod shadow {
+        pub use rustapi_extras::shadow;
+        pub use rustapi_extras::shadow::{ShadowLayer, ShadowStats, SHADOW_HEADER};
+    }
+
+    #[cfg(feature = "extras-metrics-export")]
+    pub mod metrics_export {
+        pub use rustapi_extras::metrics_export;
+        pub use rustapi_extras::metrics_export::{
+            MetricsExportError, MetricsExportHandle, MetricsExporter, OtlpMetricsConfig,
+            PushGatewayConfig,
+        };
+    }
+
+    #[cfg(feature = "extras-admin")]
+    pub mod admin {
+        pub use rustapi_extras::admin;
+        pub use rustapi_extras::admin::{
+            AdminApi, FeatureFlags, MaintenanceMode, MaintenanceStatus,
+        };
+    }
+
+    #[cfg(feature = "extras-outbox")]
+    pub mod outbox {
+        pub use rustapi_extras::outbox;
+        pub use rustapi_extras::outbox::{
+            JobPublisher, Outbox, OutboxError, OutboxEvent, OutboxMessage, OutboxPublisher,
+            OutboxRelay, WebhookPublisher,
         };
     }
 
@@ -303,7 +378,7 @@ pub use rustapi_extras::{
 };
 
 #[cfg(any(feature = "extras-sqlx", feature = "sqlx"))]
-pub use rustapi_extras::{convert_sqlx_error, SqlxErrorExt};
+pub use rustapi_extras::{convert_sqlx_error, within_deadline, SqlxErrorExt};
 
 #[cfg(any(feature = "extras-api-key", feature = "api-key"))]
 pub use rustapi_extras::api_key;
@@ -313,8 +388,12 @@ pub use rustapi_extras::cache;
 pub use rustapi_extras::circuit_breaker;
 #[cfg(any(feature = "extras-dedup", feature = "dedup"))]
 pub use rustapi_extras::dedup;
+#[cfg(any(feature = "extras-geoip", feature = "geoip"))]
+pub use rustapi_extras::geoip;
 #[cfg(any(feature = "extras-guard", feature = "guard"))]
 pub use rustapi_extras::guard;
+#[cfg(any(feature = "extras-ip-filter", feature = "ip-filter"))]
+pub use rustapi_extras::ip_filter;
 #[cfg(any(feature = "extras-logging", feature = "logging"))]
 pub use rustapi_extras::logging;
 #[cfg(any(feature = "extras-otel", feature = "otel"))]
@@ -327,6 +406,8 @@ pub use rustapi_extras::retry;
 pub use rustapi_extras::sanitization;
 #[cfg(any(feature = "extras-security-headers", feature = "security-headers"))]
 pub use rustapi_extras::security_headers;
+#[cfg(any(feature = "extras-shadow", feature = "shadow"))]
+pub use rustapi_extras::shadow;
 #[cfg(any(feature = "extras-structured-logging", feature = "structured-logging"))]
 pub use rustapi_extras::structured_logging;
 #[cfg(any(feature = "extras-timeout", feature = "timeout"))]
@@ -361,24 +442,33 @@ pub mod prelude {
     pub use crate::core::EventBus;
     pub use crate::core::Validatable;
     pub use crate::core::{
-        auto_route_count, collect_auto_routes, delete, delete_route, get, get_route, patch,
-        patch_route, post, post_route, put, put_route, route, serve_dir, sse_from_iter,
-        sse_response, ApiError, AsyncValidatedJson, Body, BodyLimitLayer, ClientIp, Created,
-        CursorPaginate, CursorPaginated, Extension, HeaderValue, Headers, HealthCheck,
+        any_route, auto_route_count, collect_auto_routes, delete, delete_route, get, get_route,
+        head_route, options_route, patch, patch_route, post, post_route, put, put_route, route,
+        serve_dir, sse_from_broadcast, sse_from_iter, sse_response, ApiError, AsyncValidatedJson,
+        Body, BodyLimitLayer, BodyTransformLayer, Canary, ClientIp, Clock, Created, CursorPaginate,
+        CursorPaginated, Deadline, Extension, HeaderStruct, HeaderValue, Headers, HealthCheck,
         HealthCheckBuilder, HealthCheckResult, HealthEndpointConfig, HealthStatus, Html,
-        IntoResponse, Json, KeepAlive, Multipart, MultipartConfig, MultipartField, NoContent,
-        Paginate, Paginated, Path, ProductionDefaultsConfig, Query, Redirect, Request,
-        RequestDispatcher, RequestId, RequestIdLayer, Response, Result, Route, Router, RustApi,
-        RustApiConfig, Sse, SseEvent, State, StaticFile, StaticFileConfig, StatusCode, StreamBody,
-        StreamingMultipart, StreamingMultipartField, TracingLayer, Typed, TypedPath, UploadedFile,
+        IntoResponse, Json, KeepAlive, LastEventId, Locale, LocaleConfig, Multipart,
+        MultipartConfig, MultipartField, NoContent, Paginate, Paginated, Path,
+        ProductionDefaultsConfig, Query, Redirect, Request, RequestDispatcher, RequestId,
+        RequestIdLayer, Response, Result, Route, Router, RustApi, RustApiConfig, Sse, SseEvent,
+        State, StaticFile, StaticFileConfig, StatusCode, StreamBody, StreamingMultipart,
+        StreamingMultipartField, TracingLayer, Typed, TypedPath, UploadedFile, ValidatedForm,
         ValidatedJson, WithStatus,
     };
 
     #[cfg(any(feature = "core-compression", feature = "compression"))]
-    pub use crate::core::{CompressionAlgorithm, CompressionConfig, CompressionLayer};
+    pub use crate::core::{
+        CompressionAlgorithm, CompressionConfig, CompressionLayer, NoCompression,
+    };
 
     #[cfg(any(feature = "core-cookies", feature = "cookies"))]
     pub use crate::core::Cookies;
+    #[cfg(any(feature = "core-cookies", feature = "cookies"))]
+    pub use crate::core::{CookieDefaults, CookieValue, SameSite, TypedCookie};
+
+    #[cfg(feature = "core-cookies-secure")]
+    pub use crate::core::{Cookie, CookieKeyring, PrivateCookies, SignedCookies};
 
     #[cfg(feature = "core-dashboard")]
     pub use crate::{DashboardConfig, DashboardMetrics, DashboardSnapshot};
@@ -446,7 +536,8 @@ pub mod prelude {
 
     #[cfg(any(feature = "protocol-grpc", feature = "grpc"))]
     pub use crate::protocol::grpc::{
-        run_concurrently, run_rustapi_and_grpc, run_rustapi_and_grpc_with_shutdown,
+        run_concurrently, run_rustapi_and_grpc, run_rustapi_and_grpc_multiplexed,
+        run_rustapi_and_grpc_multiplexed_with_shutdown, run_rustapi_and_grpc_with_shutdown,
     };
 
     #[cfg(any(feature = "protocol-mcp", feature = "mcp"))]
@@ -226,3 +226,101 @@ fn test_auto_registers_openapi_components_for_body_refs() {
         other => panic!("expected request body schema ref, got {other:?}"),
     }
 }
+
+#[get("/with-middleware")]
+#[rustapi_rs::middleware(RequestIdLayer::new(), BodyLimitLayer::new(1024))]
+async fn with_middleware() -> &'static str {
+    "layered"
+}
+
+#[test]
+fn test_middleware_attribute_keeps_route_metadata() {
+    let route = with_middleware_route();
+    assert_eq!(route.path(), "/with-middleware");
+    assert_eq!(route.method(), "GET");
+
+    let app = RustApi::new().mount_route(with_middleware_route());
+    assert!(app.openapi_spec().paths.contains_key("/with-middleware"));
+}
+
+#[rustapi_rs::delete("/pins/{id}")]
+#[rustapi_rs::require_scope("pins:write")]
+async fn delete_pin(Path(id): Path<u64>) -> String {
+    format!("deleted {}", id)
+}
+
+#[test]
+fn test_require_scope_attribute() {
+    let app = RustApi::new().mount_route(delete_pin_route());
+    let spec = app.openapi_spec();
+    let operation = spec.paths["/pins/{id}"].delete.as_ref().unwrap();
+    assert_eq!(operation.security[0]["bearerAuth"], vec!["pins:write"]);
+    assert!(operation.responses.contains_key("403"));
+    assert!(spec
+        .components
+        .as_ref()
+        .unwrap()
+        .security_schemes
+        .contains_key("bearerAuth"));
+}
+
+#[rustapi_rs::get("/pins")]
+#[rustapi_rs::operation_id("listPins")]
+async fn list_pins() -> &'static str {
+    "pins"
+}
+
+#[rustapi_rs::get("/pins/{id}")]
+async fn get_pin(Path(id): Path<u64>) -> String {
+    format!("pin {}", id)
+}
+
+#[test]
+fn test_operation_id_attribute_and_strategy() {
+    let app = RustApi::new()
+        .mount_route(list_pins_route())
+        .mount_route(probe_head_route())
+        .operation_ids(rustapi_rs::OperationIdStrategy::HandlerName)
+        .mount_route(get_pin_route());
+    let spec = app.openapi_spec();
+    let id = |path: &str| spec.paths[path].get.as_ref().unwrap().operation_id.clone();
+    assert_eq!(id("/pins").as_deref(), Some("listPins"));
+    assert_eq!(id("/pins/{id}").as_deref(), Some("get_pin"));
+    let head = spec.paths["/probe-head"].head.as_ref().unwrap();
+    assert_eq!(head.operation_id.as_deref(), Some("probe_head"));
+}
+
+#[rustapi_rs::head("/probe-head")]
+async fn probe_head() -> NoContent {
+    NoContent
+}
+
+#[rustapi_rs::options("/probe-options")]
+async fn probe_options() -> NoContent {
+    NoContent
+}
+
+#[rustapi_rs::any("/ping-any")]
+async fn ping_any() -> &'static str {
+    "pong"
+}
+
+#[test]
+fn test_head_options_and_any_macros() {
+    assert_eq!(probe_head_route().method(), "HEAD");
+    assert_eq!(probe_options_route().method(), "OPTIONS");
+    assert_eq!(ping_any_route().method(), "ANY");
+
+    let app = RustApi::new()
+        .mount_route(probe_head_route())
+        .mount_route(probe_options_route())
+        .mount_route(ping_any_route());
+    let spec = app.openapi_spec();
+
+    assert!(spec.paths["/probe-head"].head.is_some());
+    assert!(spec.paths["/probe-options"].options.is_some());
+
+    let ping = spec.paths.get("/ping-any").expect("/ping-any should exist");
+    assert!(ping.get.is_some() && ping.post.is_some() && ping.delete.is_some());
+    assert!(ping.head.is_some() && ping.options.is_some() && ping.trace.is_some());
+}
@@ -0,0 +1,45 @@
+use rustapi_rs::prelude::*;
+
+mod users {
+    #[rustapi_rs::get("/users")]
+    pub async fn list() -> &'static str {
+        "users"
+    }
+}
+
+mod admin {
+    #[rustapi_rs::get("/users")]
+    pub async fn list() -> &'static str {
+        "admin"
+    }
+}
+
+#[test]
+fn auto_routes_report_duplicates_with_attribute_locations() {
+    let app = RustApi::auto();
+    let duplicates = app.duplicate_routes();
+    assert_eq!(duplicates.len(), 1);
+
+    let duplicate = &duplicates[0];
+    assert_eq!(
+        (duplicate.method.as_str(), duplicate.path.as_str()),
+        ("GET", "/users")
+    );
+
+    let mut handlers = [duplicate.first.handler, duplicate.second.handler];
+    handlers.sort();
+    assert!(handlers[0].contains("admin::list"));
+    assert!(handlers[1].contains("users::list"));
+
+    assert!(duplicate
+        .first
+        .location
+        .file()
+        .ends_with("duplicate_routes.rs"));
+}
+
+#[tokio::test]
+async fn auto_app_refuses_to_start() {
+    let err = RustApi::auto().run("127.0.0.1:0").await.unwrap_err();
+    assert!(err.to_string().contains("GET /users"));
+}
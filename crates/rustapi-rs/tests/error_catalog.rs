@@ -0,0 +1,70 @@
+use rustapi_rs::core::{ErrorCatalog, RustApi};
+use rustapi_rs::prelude::*;
+
+#[allow(dead_code)]
+#[derive(Debug, ApiError)]
+enum OrderError {
+    #[error(status = 404, code = "order_not_found", message = "Order not found")]
+    NotFound,
+
+    #[error(
+        status = 409,
+        code = "order_locked",
+        message = "Order is locked",
+        description = "The order is being processed and cannot be modified"
+    )]
+    Locked { order_id: u64 },
+
+    #[error(status = 402, code = "payment_required", message = "Payment required")]
+    Payment(String),
+}
+
+#[test]
+fn derived_variants_are_registered() {
+    let catalog = ErrorCatalog::collect();
+
+    let not_found = catalog.get("order_not_found").expect("order_not_found");
+    assert_eq!(not_found.status, 404);
+    assert_eq!(not_found.description, "Order not found");
+    assert_eq!(not_found.source, "OrderError::NotFound");
+
+    let locked = catalog.get("order_locked").expect("order_locked");
+    assert_eq!(locked.status, 409);
+    assert_eq!(
+        locked.description,
+        "The order is being processed and cannot be modified"
+    );
+
+    assert!(catalog.get("payment_required").is_some());
+    assert!(catalog.get("not_found").is_some());
+}
+
+#[test]
+fn variants_map_to_status() {
+    let resp = OrderError::NotFound.into_response();
+    assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+
+    let resp = OrderError::Locked { order_id: 7 }.into_response();
+    assert_eq!(resp.status(), StatusCode::CONFLICT);
+
+    let resp = OrderError::Payment("card declined".into()).into_response();
+    assert_eq!(resp.status(), StatusCode::PAYMENT_REQUIRED);
+}
+
+#[test]
+fn catalog_is_included_in_openapi() {
+    let app = RustApi::new().error_code("quota_exceeded", 429, "Quota exceeded");
+    assert_eq!(app.error_codes().get("quota_exceeded").unwrap().status, 429);
+
+    let json = app.openapi_spec().to_json();
+    let codes: Vec<&str> = json["components"]["schemas"]["ApiErrorCode"]["oneOf"]
+        .as_array()
+        .expect("ApiErrorCode schema")
+        .iter()
+        .filter_map(|v| v["const"].as_str())
+        .collect();
+
+    assert!(codes.contains(&"order_locked"));
+    assert!(codes.contains(&"quota_exceeded"));
+    assert!(codes.contains(&"validation_error"));
+}
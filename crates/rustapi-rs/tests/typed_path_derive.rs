@@ -14,6 +14,16 @@ struct ProductPath {
     item_id: String,
 }
 
+#[derive(Debug, Serialize, Deserialize, TypedPath)]
+#[typed_path("/orgs/{org}/files/{name}")]
+struct OrgFilePath {
+    #[serde(rename = "org")]
+    organization: String,
+    name: String,
+    #[serde(skip)]
+    cached: bool,
+}
+
 #[test]
 fn test_typed_path_constants() {
     assert_eq!(UserDetailsPath::PATH, "/users/{id}/details");
@@ -30,6 +40,14 @@ fn test_typed_path_to_uri() {
         item_id: "phone-1".to_string(),
     };
     assert_eq!(product_path.to_uri(), "/products/electronics/phone-1");
+
+    let file_path = OrgFilePath {
+        organization: "acme".to_string(),
+        name: "readme.md".to_string(),
+        cached: false,
+    };
+    assert_eq!(file_path.to_uri(), "/orgs/acme/files/readme.md");
+    assert!(!file_path.cached);
 }
 
 // Test compilation of handler signature
@@ -62,6 +62,22 @@ fn test_v2_validate_macro() {
     assert_eq!(fields[0].field, "name");
 }
 
+#[test]
+fn test_v2_validate_localized_messages() {
+    let invalid_user = V2User {
+        name: "Al".to_string(),
+    };
+
+    let err = invalid_user.do_validate_localized(Some("tr")).unwrap_err();
+    let fields = err.fields.unwrap();
+    assert_eq!(fields[0].message, "Uzunluk en az 3 karakter olmalıdır");
+
+    // Without a locale messages stay as they were
+    let plain = invalid_user.do_validate().unwrap_err().fields.unwrap();
+    let unlocalized = invalid_user.do_validate_localized(None).unwrap_err();
+    assert_eq!(unlocalized.fields.unwrap()[0].message, plain[0].message);
+}
+
 // ============================================================================
 // Async Validation Tests
 // ============================================================================
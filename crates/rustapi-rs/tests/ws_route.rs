@@ -0,0 +1,31 @@
+#![cfg(feature = "protocol-ws")]
+
+use rustapi_rs::prelude::*;
+use rustapi_rs::protocol::ws::{Message, WebSocket, WebSocketUpgrade};
+
+#[rustapi_rs::ws("/chat/{room}")]
+async fn chat(Path(room): Path<String>, ws: WebSocket) -> WebSocketUpgrade {
+    ws.on_upgrade(move |mut socket| async move {
+        while let Some(Ok(Message::Text(text))) = socket.recv().await {
+            let _ = socket.send(Message::text(format!("[{room}] {text}"))).await;
+        }
+    })
+}
+
+#[test]
+fn ws_route_is_a_get_route_hidden_from_docs() {
+    let route = chat_route();
+    assert_eq!(route.method(), "GET");
+    assert_eq!(route.path(), "/chat/{room}");
+    assert!(!route.is_documented());
+
+    let app = RustApi::new().mount_route(chat_route());
+    assert!(!app.openapi_spec().paths.contains_key("/chat/{room}"));
+}
+
+#[test]
+fn auto_registration_hides_ws_routes_from_docs() {
+    let app = RustApi::auto();
+    assert!(!app.openapi_spec().paths.contains_key("/chat/{room}"));
+    assert!(app.route_table().get("GET", "/chat/{room}").is_some());
+}
@@ -24,23 +24,34 @@
 //! }
 //! ```
 
+use crate::cookies::CookieJar;
+use crate::fuzz::SpecFuzzer;
+use crate::sse::TestEventStream;
 use bytes::Bytes;
 use http::{header, HeaderMap, HeaderValue, Method, StatusCode};
 use http_body_util::BodyExt;
 use rustapi_core::middleware::{BodyLimitLayer, BoxedNext, LayerStack, DEFAULT_BODY_LIMIT};
-use rustapi_core::{ApiError, BodyVariant, IntoResponse, Request, Response, RouteMatch, Router};
+use rustapi_core::{
+    ApiError, BodyVariant, Clock, IntoResponse, Request, Response, RouteMatch, Router,
+};
+use rustapi_openapi::OpenApiSpec;
 use serde::{de::DeserializeOwned, Serialize};
 use std::future::Future;
 use std::pin::Pin;
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
 
 /// Test client for integration testing without network binding
 ///
 /// TestClient wraps a RustApi instance and allows sending simulated HTTP requests
 /// through the full middleware and handler pipeline.
+#[derive(Clone)]
 pub struct TestClient {
     router: Arc<Router>,
     layers: Arc<LayerStack>,
+    spec: Arc<OpenApiSpec>,
+    cookies: Option<Arc<Mutex<CookieJar>>>,
+    clock: Arc<Mutex<Option<Clock>>>,
 }
 
 impl TestClient {
@@ -53,32 +64,185 @@ impl TestClient {
     /// let client = TestClient::new(app);
     /// ```
     pub fn new(app: rustapi_core::RustApi) -> Self {
-        // Get the router and layers from the app
+        Self::with_body_limit(app, DEFAULT_BODY_LIMIT)
+    }
+
+    /// Create a new test client with custom body limit
+    pub fn with_body_limit(app: rustapi_core::RustApi, limit: usize) -> Self {
+        // Get the router, layers and generated spec from the app
         let layers = app.layers().clone();
+        let spec = app.openapi_spec().clone();
         let router = app.into_router();
 
         // Apply body limit layer if not already present
         let mut layers = layers;
-        layers.prepend(Box::new(BodyLimitLayer::new(DEFAULT_BODY_LIMIT)));
+        layers.prepend(Box::new(BodyLimitLayer::new(limit)));
 
         Self {
             router: Arc::new(router),
             layers: Arc::new(layers),
+            spec: Arc::new(spec),
+            cookies: None,
+            clock: Arc::new(Mutex::new(None)),
         }
     }
 
-    /// Create a new test client with custom body limit
-    pub fn with_body_limit(app: rustapi_core::RustApi, limit: usize) -> Self {
-        let layers = app.layers().clone();
-        let router = app.into_router();
+    /// Keep cookies across requests
+    ///
+    /// Cookies set by responses are stored and sent with later requests
+    /// (clones of the client share the jar). See the
+    /// [`cookies`](crate::cookies) module.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// let client = TestClient::new(app).with_cookies();
+    /// client.post_json("/login", &credentials).await;
+    /// client.get("/me").await.assert_status(StatusCode::OK);
+    /// ```
+    pub fn with_cookies(mut self) -> Self {
+        self.cookies = Some(Arc::new(Mutex::new(CookieJar::default())));
+        self
+    }
 
-        let mut layers = layers;
-        layers.prepend(Box::new(BodyLimitLayer::new(limit)));
+    fn jar(&self) -> std::sync::MutexGuard<'_, CookieJar> {
+        self.cookies
+            .as_ref()
+            .expect("the cookie jar is disabled; create the client with `with_cookies()`")
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+    }
 
-        Self {
-            router: Arc::new(router),
-            layers: Arc::new(layers),
+    /// Value of a cookie in the jar
+    pub fn cookie(&self, name: &str) -> Option<String> {
+        self.jar().get(name, self.clock().now())
+    }
+
+    /// All cookies in the jar as (name, value)
+    pub fn cookies(&self) -> Vec<(String, String)> {
+        self.jar().all(self.clock().now())
+    }
+
+    /// Put a cookie into the jar (sent on every path)
+    pub fn set_cookie(&self, name: &str, value: &str) -> &Self {
+        self.jar().set(name, value);
+        self
+    }
+
+    /// Remove a cookie from the jar
+    pub fn remove_cookie(&self, name: &str) -> &Self {
+        self.jar().remove(name);
+        self
+    }
+
+    /// Remove all cookies from the jar (e.g. to act as a logged-out user)
+    pub fn clear_cookies(&self) -> &Self {
+        self.jar().clear();
+        self
+    }
+
+    /// Add the jar's cookies to a request's `Cookie` header
+    pub(crate) fn attach_cookies(&self, mut req: TestRequest) -> TestRequest {
+        if self.cookies.is_none() {
+            return req;
         }
+        let path = req.path.split('?').next().unwrap_or("/").to_string();
+        let now = self.clock().now();
+        if let Some(jar_cookies) = self.jar().header_for(&path, now) {
+            let value = match req.headers.get(header::COOKIE) {
+                Some(existing) => HeaderValue::from_bytes(
+                    &[existing.as_bytes(), b"; ", jar_cookies.as_bytes()].concat(),
+                )
+                .unwrap_or(jar_cookies),
+                None => jar_cookies,
+            };
+            req.headers.insert(header::COOKIE, value);
+        }
+        req
+    }
+
+    /// Store the cookies a response sets, if the jar is enabled
+    pub(crate) fn store_cookies(&self, path: &str, headers: &HeaderMap) {
+        if self.cookies.is_some() {
+            let now = self.clock().now();
+            self.jar().store(path, headers, now);
+        }
+    }
+
+    /// Use `clock` as the time source of the app
+    ///
+    /// The clock is put into the extensions of every request, where
+    /// middleware such as rate limiting, caching and JWT validation (and
+    /// handlers taking a `Clock`) read it. Share it with components that run
+    /// outside requests, like a job queue, to move them in step.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// let clock = Clock::manual();
+    /// let queue = JobQueue::new(InMemoryBackend::new()).clock(clock.clone());
+    /// let client = TestClient::new(app(queue)).with_clock(clock);
+    /// ```
+    pub fn with_clock(self, clock: Clock) -> Self {
+        *self.clock.lock().unwrap_or_else(|e| e.into_inner()) = Some(clock);
+        self
+    }
+
+    /// The clock requests are served with (the system clock until
+    /// [`with_clock`](Self::with_clock) or [`advance_time`](Self::advance_time))
+    pub fn clock(&self) -> Clock {
+        self.clock
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone()
+            .unwrap_or_default()
+    }
+
+    /// Move the app's time forward without sleeping
+    ///
+    /// Switches to a manual clock first if none is set; from then on time
+    /// only moves when advanced. Cookies in the jar expire by this clock too.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// client.get("/limited").await.assert_status(StatusCode::TOO_MANY_REQUESTS);
+    /// client.advance_time(Duration::from_secs(60));
+    /// client.get("/limited").await.assert_status(StatusCode::OK);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if the client was given the system clock.
+    pub fn advance_time(&self, by: Duration) -> &Self {
+        self.clock
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .get_or_insert_with(Clock::manual)
+            .advance(by);
+        self
+    }
+
+    /// The OpenAPI spec generated for the app
+    ///
+    /// For [`TestResponse::assert_matches_openapi`].
+    pub fn openapi_spec(&self) -> &OpenApiSpec {
+        &self.spec
+    }
+
+    /// Fuzz the app's endpoints from its OpenAPI spec
+    ///
+    /// Sends random valid and invalid requests to every documented
+    /// operation and reports responses that are server errors or have an
+    /// undocumented status. See the [`fuzz`](crate::fuzz) module.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// client.fuzz().cases(100).run().await.assert_ok();
+    /// ```
+    pub fn fuzz(&self) -> SpecFuzzer<'_> {
+        SpecFuzzer::new(self)
     }
 
     /// Send a GET request
@@ -103,6 +267,18 @@ impl TestClient {
         self.request(TestRequest::post(path).json(body)).await
     }
 
+    /// Send a POST request with a TOON body
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// let response = client.post_toon("/users", &CreateUser { name: "Alice" }).await;
+    /// ```
+    #[cfg(feature = "toon")]
+    pub async fn post_toon<T: Serialize>(&self, path: &str, body: &T) -> TestResponse {
+        self.request(TestRequest::post(path).toon(body)).await
+    }
+
     /// Send a request with full control
     ///
     /// # Example
@@ -115,17 +291,87 @@ impl TestClient {
     /// ).await;
     /// ```
     pub async fn request(&self, req: TestRequest) -> TestResponse {
-        let method = req.method.clone();
-        let path = req.path.clone();
+        let (parts, body_bytes) = self.request_parts(req);
+        let request = (parts.method.clone(), parts.uri.path().to_string());
+
+        let response = self
+            .dispatch(parts, BodyVariant::Buffered(body_bytes))
+            .await;
+        let mut response = TestResponse::from_response(response).await;
+        self.store_cookies(&request.1, &response.headers);
+        response.request = Some(request);
+        response
+    }
+
+    /// Open a Server-Sent Events stream with a GET request
+    ///
+    /// Unlike [`get`](Self::get), the body is not collected first, so
+    /// endless streams can be read event by event.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// let mut events = client.sse("/events").await;
+    /// events.assert_next_event("ready").await;
+    /// ```
+    pub async fn sse(&self, path: &str) -> TestEventStream {
+        self.sse_request(TestRequest::get(path).header("Accept", "text/event-stream"))
+            .await
+    }
+
+    /// Send a request and read its response as a Server-Sent Events stream
+    pub async fn sse_request(&self, req: TestRequest) -> TestEventStream {
+        let (parts, body_bytes) = self.request_parts(req);
+        let path = parts.uri.path().to_string();
+
+        let response = self
+            .dispatch(parts, BodyVariant::Buffered(body_bytes))
+            .await;
+        self.store_cookies(&path, response.headers());
+        TestEventStream::from_response(response)
+    }
+
+    /// Build the request head and body, with cookies from the jar attached
+    fn request_parts(&self, req: TestRequest) -> (http::request::Parts, Bytes) {
+        let req = self.attach_cookies(req);
+
+        // Build the internal Request
+        let uri: http::Uri = req.path.parse().unwrap_or_else(|_| "/".parse().unwrap());
+        let mut builder = http::Request::builder().method(req.method).uri(uri);
+
+        // Add headers
+        for (key, value) in req.headers.iter() {
+            builder = builder.header(key, value);
+        }
+
+        let (parts, _) = builder.body(()).unwrap().into_parts();
+        (parts, req.body.unwrap_or_default())
+    }
+
+    /// Route a request through the middleware stack and matched handler
+    ///
+    /// Error responses are negotiated against the `Accept` header, as the
+    /// server does.
+    pub(crate) async fn dispatch(
+        &self,
+        parts: http::request::Parts,
+        body: BodyVariant,
+    ) -> Response {
+        let accept = parts.headers.get(header::ACCEPT).cloned();
+        let response = self.route(parts, body).await;
+        rustapi_core::error_format::negotiate_error_response(accept.as_ref(), response)
+    }
+
+    async fn route(&self, parts: http::request::Parts, body: BodyVariant) -> Response {
+        let method = parts.method.clone();
+        let path = parts.uri.path().to_string();
 
         // Match the route to get path params
         let (handler, params) = match self.router.match_route(&path, &method) {
             RouteMatch::Found { handler, params } => (handler.clone(), params),
             RouteMatch::NotFound => {
-                let response =
-                    ApiError::not_found(format!("No route found for {} {}", method, path))
-                        .into_response();
-                return TestResponse::from_response(response).await;
+                return ApiError::not_found(format!("No route found for {} {}", method, path))
+                    .into_response();
             }
             RouteMatch::MethodNotAllowed { allowed } => {
                 let allowed_str: Vec<&str> = allowed.iter().map(|m| m.as_str()).collect();
@@ -139,31 +385,15 @@ impl TestClient {
                 response
                     .headers_mut()
                     .insert(header::ALLOW, allowed_str.join(", ").parse().unwrap());
-                return TestResponse::from_response(response).await;
+                return response;
             }
         };
 
-        // Build the internal Request
-        let uri: http::Uri = path.parse().unwrap_or_else(|_| "/".parse().unwrap());
-        let mut builder = http::Request::builder().method(method).uri(uri);
-
-        // Add headers
-        for (key, value) in req.headers.iter() {
-            builder = builder.header(key, value);
+        let mut request = Request::new(parts, body, self.router.state_ref(), params);
+        if let Some(clock) = self.clock.lock().unwrap_or_else(|e| e.into_inner()).clone() {
+            request.extensions_mut().insert(clock);
         }
 
-        let http_req = builder.body(()).unwrap();
-        let (parts, _) = http_req.into_parts();
-
-        let body_bytes = req.body.unwrap_or_default();
-
-        let request = Request::new(
-            parts,
-            BodyVariant::Buffered(body_bytes),
-            self.router.state_ref(),
-            params,
-        );
-
         // Create the final handler as a BoxedNext
         let final_handler: BoxedNext = Arc::new(move |req: Request| {
             let handler = handler.clone();
@@ -172,9 +402,7 @@ impl TestClient {
         });
 
         // Execute through middleware stack
-        let response = self.layers.execute(request, final_handler).await;
-
-        TestResponse::from_response(response).await
+        self.layers.execute(request, final_handler).await
     }
 }
 
@@ -184,15 +412,15 @@ impl TestClient {
 /// headers, and body content.
 #[derive(Debug, Clone)]
 pub struct TestRequest {
-    method: Method,
-    path: String,
-    headers: HeaderMap,
-    body: Option<Bytes>,
+    pub(crate) method: Method,
+    pub(crate) path: String,
+    pub(crate) headers: HeaderMap,
+    pub(crate) body: Option<Bytes>,
 }
 
 impl TestRequest {
     /// Create a new request with the given method and path
-    fn new(method: Method, path: &str) -> Self {
+    pub(crate) fn new(method: Method, path: &str) -> Self {
         Self {
             method,
             path: path.to_string(),
@@ -226,6 +454,16 @@ impl TestRequest {
         Self::new(Method::DELETE, path)
     }
 
+    /// Create a HEAD request
+    pub fn head(path: &str) -> Self {
+        Self::new(Method::HEAD, path)
+    }
+
+    /// Create an OPTIONS request
+    pub fn options(path: &str) -> Self {
+        Self::new(Method::OPTIONS, path)
+    }
+
     /// Add a header to the request
     ///
     /// # Example
@@ -271,6 +509,32 @@ impl TestRequest {
         self
     }
 
+    /// Set the request body as TOON
+    ///
+    /// This sets the Content-Type header to `application/toon`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `body` cannot be encoded as TOON.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// let req = TestRequest::put("/users/1")
+    ///     .toon(&UpdateUser { name: "Bob" });
+    /// ```
+    #[cfg(feature = "toon")]
+    pub fn toon<T: Serialize>(mut self, body: &T) -> Self {
+        let encoded = rustapi_toon::encode_default(body)
+            .unwrap_or_else(|e| panic!("Failed to encode request body as TOON: {}", e));
+        self.body = Some(Bytes::from(encoded));
+        self.headers.insert(
+            header::CONTENT_TYPE,
+            HeaderValue::from_static(rustapi_toon::TOON_CONTENT_TYPE),
+        );
+        self
+    }
+
     /// Set the request body as raw bytes
     ///
     /// # Example
@@ -288,6 +552,23 @@ impl TestRequest {
     pub fn content_type(self, content_type: &str) -> Self {
         self.header("content-type", content_type)
     }
+
+    /// Add a cookie to the request's `Cookie` header
+    pub fn cookie(mut self, name: &str, value: &str) -> Self {
+        let pair = format!("{}={}", name, value);
+        let value = match self
+            .headers
+            .get(header::COOKIE)
+            .and_then(|v| v.to_str().ok())
+        {
+            Some(existing) => format!("{}; {}", existing, pair),
+            None => pair,
+        };
+        if let Ok(value) = HeaderValue::from_str(&value) {
+            self.headers.insert(header::COOKIE, value);
+        }
+        self
+    }
 }
 
 /// Test response with assertion helpers
@@ -298,9 +579,22 @@ pub struct TestResponse {
     status: StatusCode,
     headers: HeaderMap,
     body: Bytes,
+    /// Method and path of the request, for contract checks
+    request: Option<(Method, String)>,
 }
 
 impl TestResponse {
+    /// Create a TestResponse from its parts
+    #[cfg_attr(not(feature = "ws"), allow(dead_code))]
+    pub(crate) fn from_parts(status: StatusCode, headers: HeaderMap, body: Bytes) -> Self {
+        Self {
+            status,
+            headers,
+            body,
+            request: None,
+        }
+    }
+
     /// Create a TestResponse from an HTTP response
     async fn from_response(response: Response) -> Self {
         let (parts, body) = response.into_parts();
@@ -314,6 +608,7 @@ impl TestResponse {
             status: parts.status,
             headers: parts.headers,
             body: body_bytes,
+            request: None,
         }
     }
 
@@ -350,6 +645,18 @@ impl TestResponse {
         serde_json::from_slice(&self.body)
     }
 
+    /// Parse the response body as TOON
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// let user: User = response.toon().unwrap();
+    /// ```
+    #[cfg(feature = "toon")]
+    pub fn toon<T: DeserializeOwned>(&self) -> Result<T, rustapi_toon::ToonError> {
+        Ok(rustapi_toon::decode_default(&self.text())?)
+    }
+
     /// Assert that the response has the expected status code
     ///
     /// # Panics
@@ -421,6 +728,45 @@ impl TestResponse {
         self
     }
 
+    /// Assert that the response is TOON and decodes to the expected value
+    ///
+    /// # Panics
+    ///
+    /// Panics if the `Content-Type` is not `application/toon` or `text/toon`,
+    /// or the body can't be decoded or doesn't match.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// response.assert_toon_eq(&User { id: 1, name: "Alice".to_string() });
+    /// ```
+    #[cfg(feature = "toon")]
+    pub fn assert_toon_eq<T: DeserializeOwned + PartialEq + std::fmt::Debug>(
+        &self,
+        expected: &T,
+    ) -> &Self {
+        let content_type = self
+            .headers
+            .get(header::CONTENT_TYPE)
+            .and_then(|v| v.to_str().ok())
+            .unwrap_or("");
+        assert!(
+            content_type.starts_with(rustapi_toon::TOON_CONTENT_TYPE)
+                || content_type.starts_with(rustapi_toon::TOON_CONTENT_TYPE_TEXT),
+            "Expected a TOON response, got Content-Type '{}'",
+            content_type
+        );
+        let actual: T = self.toon().unwrap_or_else(|e| {
+            panic!(
+                "Failed to decode response body as TOON ({}): {}",
+                e,
+                self.text()
+            )
+        });
+        assert_eq!(&actual, expected, "TOON body mismatch");
+        self
+    }
+
     /// Assert that the response body contains the expected string
     ///
     /// # Panics
@@ -436,4 +782,112 @@ impl TestResponse {
         );
         self
     }
+
+    /// Assert that the response matches the OpenAPI operation of its request
+    ///
+    /// Checks that the status code is documented, that the `Content-Type`
+    /// is a documented media type, and that a JSON body matches the
+    /// documented schema. See the [`contract`](crate::contract) module.
+    ///
+    /// # Panics
+    ///
+    /// Panics listing every mismatch, or if the response was not produced
+    /// by [`TestClient`].
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// let response = client.get("/users/1").await;
+    /// response.assert_matches_openapi(client.openapi_spec());
+    /// ```
+    pub fn assert_matches_openapi(&self, spec: &OpenApiSpec) -> &Self {
+        let (method, path) = self
+            .request
+            .as_ref()
+            .expect("assert_matches_openapi needs a response from TestClient");
+        let errors = crate::contract::check_response(
+            spec,
+            method,
+            path,
+            self.status,
+            &self.headers,
+            &self.body,
+        );
+        assert!(
+            errors.is_empty(),
+            "Response to {} {} does not match the OpenAPI spec:\n  - {}\nBody: {}",
+            method,
+            path,
+            errors.join("\n  - "),
+            self.text()
+        );
+        self
+    }
+
+    /// Assert that the JSON body matches the stored snapshot `name`
+    ///
+    /// The body is pretty-printed with UUIDs and timestamps redacted. See
+    /// the [`snapshot`](crate::snapshot) module for where snapshots live and
+    /// how to update them.
+    ///
+    /// # Panics
+    ///
+    /// Panics with a line diff if the snapshot differs, or if the body is
+    /// not JSON.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// client.get("/users/1").await.assert_json_snapshot("get_user");
+    /// ```
+    pub fn assert_json_snapshot(&self, name: &str) -> &Self {
+        self.assert_json_snapshot_redacted(name, &[])
+    }
+
+    /// Assert that the JSON body matches the stored snapshot `name`, with
+    /// the values at the given JSON pointers replaced by `"[redacted]"`
+    ///
+    /// A `*` segment matches every element, e.g. `/items/*/id`.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// response.assert_json_snapshot_redacted("login", &["/token"]);
+    /// ```
+    pub fn assert_json_snapshot_redacted(&self, name: &str, pointers: &[&str]) -> &Self {
+        let value: serde_json::Value = self.json().unwrap_or_else(|e| {
+            panic!(
+                "Snapshot '{}': body is not JSON ({}): {}",
+                name,
+                e,
+                self.text()
+            )
+        });
+        crate::snapshot::assert_snapshot(name, &crate::snapshot::redact_json(value, pointers));
+        self
+    }
+
+    /// Assert that the body text matches the stored snapshot `name`
+    ///
+    /// UUIDs and timestamps are redacted.
+    pub fn assert_body_snapshot(&self, name: &str) -> &Self {
+        crate::snapshot::assert_snapshot(name, &crate::snapshot::redact_text(&self.text()));
+        self
+    }
+
+    /// Parse the body as Server-Sent Events
+    ///
+    /// For responses whose stream ends, e.g. ones built with
+    /// `sse_response`; use [`TestClient::sse`] for endless streams.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// let mut events = client.get("/feed").await.sse_events();
+    /// events.assert_next_data("first").await;
+    /// events.assert_end().await;
+    /// ```
+    pub fn sse_events(&self) -> TestEventStream {
+        TestEventStream::from_body(self.status, self.headers.clone(), &self.body)
+    }
 }
@@ -0,0 +1,464 @@
+//! OpenAPI contract checks for test responses
+//!
+//! [`TestResponse::assert_matches_openapi`](crate::TestResponse::assert_matches_openapi)
+//! checks a response against the operation documented for its request:
+//! the status code must be documented, the `Content-Type` must be one of the
+//! documented media types, and a JSON body must match the documented schema.
+//! Tests then fail as soon as the docs and the runtime behaviour drift apart.
+//!
+//! ```rust,ignore
+//! let client = TestClient::new(app);
+//! client
+//!     .get("/users/1")
+//!     .await
+//!     .assert_status(StatusCode::OK)
+//!     .assert_matches_openapi(client.openapi_spec());
+//! ```
+//!
+//! Schemas are checked for `type`, `properties`, `required`,
+//! `additionalProperties`, `items`, `enum`, `const`, `allOf`/`anyOf`/`oneOf`,
+//! `nullable`, string lengths, numeric bounds and `$ref` to components.
+//! Other keywords (e.g. `format`, `pattern`) are not checked.
+
+use http::{header, HeaderMap, Method, StatusCode};
+use rustapi_openapi::OpenApiSpec;
+use serde_json::Value;
+
+/// Check a response against `spec`, returning every mismatch found
+pub(crate) fn check_response(
+    spec: &OpenApiSpec,
+    method: &Method,
+    path: &str,
+    status: StatusCode,
+    headers: &HeaderMap,
+    body: &[u8],
+) -> Vec<String> {
+    let spec = spec.to_json();
+    let (template, response) = match documented_response(&spec, method, path, status) {
+        Ok(found) => found,
+        Err(error) => return vec![error],
+    };
+    let code = status.as_str();
+
+    // Responses documented without content make no claims about the body
+    let Some(content) = response["content"].as_object().filter(|c| !c.is_empty()) else {
+        return Vec::new();
+    };
+    if body.is_empty() {
+        return Vec::new();
+    }
+
+    let content_type = headers
+        .get(header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .unwrap_or("")
+        .split(';')
+        .next()
+        .unwrap_or("")
+        .trim()
+        .to_ascii_lowercase();
+    let media = content.iter().find_map(|(documented, media)| {
+        media_type_matches(documented, &content_type).then_some(media)
+    });
+    let Some(media) = media else {
+        let documented: Vec<&str> = content.keys().map(String::as_str).collect();
+        return vec![format!(
+            "Content-Type '{}' is not documented for {} {} {} (documented: {})",
+            content_type,
+            method,
+            template,
+            code,
+            documented.join(", ")
+        )];
+    };
+
+    let is_json = content_type == "application/json" || content_type.ends_with("+json");
+    let Some(schema) = media.get("schema").filter(|_| is_json) else {
+        return Vec::new();
+    };
+    let body: Value = match serde_json::from_slice(body) {
+        Ok(body) => body,
+        Err(e) => return vec![format!("Body is not valid JSON: {}", e)],
+    };
+
+    let mut errors = Vec::new();
+    validate(&spec, schema, &body, "$", &mut errors);
+    errors
+}
+
+/// The response documented for `status` by the operation matching `method`
+/// and `path`, with the path template it was found under
+pub(crate) fn documented_response<'a>(
+    spec: &'a Value,
+    method: &Method,
+    path: &str,
+    status: StatusCode,
+) -> Result<(&'a str, &'a Value), String> {
+    let Some((template, item)) = find_path(spec, path) else {
+        return Err(format!("No path in the spec matches {}", path));
+    };
+    let operation = &item[method.as_str().to_ascii_lowercase()];
+    if !operation.is_object() {
+        return Err(format!("{} {} is not documented", method, template));
+    }
+
+    let responses = &operation["responses"];
+    let code = status.as_str();
+    let range = format!("{}XX", &code[..1]);
+    [code, range.as_str(), &range.to_ascii_lowercase(), "default"]
+        .iter()
+        .find_map(|key| responses.get(*key))
+        .map(|response| (template, resolve(spec, response)))
+        .ok_or_else(|| {
+            format!(
+                "Status {} is not documented for {} {}",
+                code, method, template
+            )
+        })
+}
+
+/// The spec path matching `path`, preferring the most literal segments
+fn find_path<'a>(spec: &'a Value, path: &str) -> Option<(&'a str, &'a Value)> {
+    let segments: Vec<&str> = path.trim_matches('/').split('/').collect();
+    spec["paths"]
+        .as_object()?
+        .iter()
+        .filter_map(|(template, item)| {
+            let parts: Vec<&str> = template.trim_matches('/').split('/').collect();
+            if parts.len() != segments.len() {
+                return None;
+            }
+            let mut literal = 0;
+            for (part, segment) in parts.iter().zip(&segments) {
+                if part.starts_with('{') && part.ends_with('}') {
+                    continue;
+                }
+                if part != segment {
+                    return None;
+                }
+                literal += 1;
+            }
+            Some((literal, template.as_str(), item))
+        })
+        .max_by_key(|(literal, _, _)| *literal)
+        .map(|(_, template, item)| (template, item))
+}
+
+/// `application/json` matches `application/json`, `application/*` and `*/*`
+fn media_type_matches(documented: &str, actual: &str) -> bool {
+    let documented = documented.to_ascii_lowercase();
+    match documented.split_once('/') {
+        _ if documented == actual || documented == "*/*" => true,
+        Some((kind, "*")) => actual.split('/').next() == Some(kind),
+        _ => false,
+    }
+}
+
+/// Follow a local `$ref`
+pub(crate) fn resolve<'a>(spec: &'a Value, value: &'a Value) -> &'a Value {
+    let mut value = value;
+    // Bounded to avoid cycles of pure references
+    for _ in 0..32 {
+        match value["$ref"].as_str() {
+            Some(reference) => match spec.pointer(reference.trim_start_matches('#')) {
+                Some(target) => value = target,
+                None => break,
+            },
+            None => break,
+        }
+    }
+    value
+}
+
+fn validate(spec: &Value, schema: &Value, value: &Value, at: &str, errors: &mut Vec<String>) {
+    if let Some(reference) = schema["$ref"].as_str() {
+        if spec.pointer(reference.trim_start_matches('#')).is_none() {
+            errors.push(format!("{}: unresolved schema reference {}", at, reference));
+            return;
+        }
+    }
+    let schema = resolve(spec, schema);
+    let Some(keywords) = schema.as_object() else {
+        // `true` (or an empty schema) accepts anything; `false` nothing
+        if schema == &Value::Bool(false) {
+            errors.push(format!("{}: no value is allowed here", at));
+        }
+        return;
+    };
+
+    if value.is_null() && schema["nullable"] == Value::Bool(true) {
+        return;
+    }
+
+    if let Some(types) = keywords.get("type") {
+        let allowed: Vec<&str> = match types {
+            Value::String(t) => vec![t.as_str()],
+            Value::Array(ts) => ts.iter().filter_map(Value::as_str).collect(),
+            _ => Vec::new(),
+        };
+        if !allowed.is_empty() && !allowed.iter().any(|t| has_type(value, t)) {
+            errors.push(format!(
+                "{}: expected {}, got {}",
+                at,
+                allowed.join(" or "),
+                type_name(value)
+            ));
+            return;
+        }
+    }
+
+    if let Some(options) = keywords.get("enum").and_then(Value::as_array) {
+        if !options.contains(value) {
+            errors.push(format!(
+                "{}: {} is not one of {}",
+                at,
+                value,
+                Value::from(options.clone())
+            ));
+        }
+    }
+    if let Some(expected) = keywords.get("const") {
+        if expected != value {
+            errors.push(format!("{}: expected {}, got {}", at, expected, value));
+        }
+    }
+
+    if let Some(all) = keywords.get("allOf").and_then(Value::as_array) {
+        for sub in all {
+            validate(spec, sub, value, at, errors);
+        }
+    }
+    for (keyword, exactly_one) in [("anyOf", false), ("oneOf", true)] {
+        if let Some(options) = keywords.get(keyword).and_then(Value::as_array) {
+            let matching = options
+                .iter()
+                .filter(|sub| {
+                    let mut sub_errors = Vec::new();
+                    validate(spec, sub, value, at, &mut sub_errors);
+                    sub_errors.is_empty()
+                })
+                .count();
+            if matching == 0 || (exactly_one && matching > 1) {
+                errors.push(format!(
+                    "{}: value matches {} of the {} schemas (expected {})",
+                    at,
+                    matching,
+                    keyword,
+                    if exactly_one {
+                        "exactly one"
+                    } else {
+                        "at least one"
+                    }
+                ));
+            }
+        }
+    }
+
+    match value {
+        Value::Object(object) => {
+            let properties = keywords.get("properties").and_then(Value::as_object);
+            if let Some(required) = keywords.get("required").and_then(Value::as_array) {
+                for name in required.iter().filter_map(Value::as_str) {
+                    if !object.contains_key(name) {
+                        errors.push(format!("{}: missing required property '{}'", at, name));
+                    }
+                }
+            }
+            for (name, field) in object {
+                let path = format!("{}.{}", at, name);
+                match (
+                    properties.and_then(|p| p.get(name)),
+                    keywords.get("additionalProperties"),
+                ) {
+                    (Some(sub), _) => validate(spec, sub, field, &path, errors),
+                    (None, Some(Value::Bool(false))) => {
+                        errors.push(format!("{}: property is not documented", path))
+                    }
+                    (None, Some(sub @ Value::Object(_))) => {
+                        validate(spec, sub, field, &path, errors)
+                    }
+                    (None, _) => {}
+                }
+            }
+        }
+        Value::Array(items) => {
+            if let Some(sub) = keywords.get("items") {
+                for (i, item) in items.iter().enumerate() {
+                    validate(spec, sub, item, &format!("{}[{}]", at, i), errors);
+                }
+            }
+            check_bound(keywords, "minItems", items.len(), at, errors, |n, min| {
+                n >= min
+            });
+            check_bound(keywords, "maxItems", items.len(), at, errors, |n, max| {
+                n <= max
+            });
+        }
+        Value::String(s) => {
+            let len = s.chars().count();
+            check_bound(keywords, "minLength", len, at, errors, |n, min| n >= min);
+            check_bound(keywords, "maxLength", len, at, errors, |n, max| n <= max);
+        }
+        Value::Number(n) => {
+            let n = n.as_f64().unwrap_or_default();
+            if let Some(min) = keywords.get("minimum").and_then(Value::as_f64) {
+                if n < min {
+                    errors.push(format!("{}: {} is less than the minimum {}", at, n, min));
+                }
+            }
+            if let Some(max) = keywords.get("maximum").and_then(Value::as_f64) {
+                if n > max {
+                    errors.push(format!("{}: {} is greater than the maximum {}", at, n, max));
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+fn check_bound(
+    keywords: &serde_json::Map<String, Value>,
+    keyword: &str,
+    actual: usize,
+    at: &str,
+    errors: &mut Vec<String>,
+    ok: impl Fn(usize, usize) -> bool,
+) {
+    if let Some(bound) = keywords.get(keyword).and_then(Value::as_u64) {
+        if !ok(actual, bound as usize) {
+            errors.push(format!(
+                "{}: {} {} violated ({})",
+                at, keyword, bound, actual
+            ));
+        }
+    }
+}
+
+pub(crate) fn has_type(value: &Value, ty: &str) -> bool {
+    match ty {
+        "null" => value.is_null(),
+        "boolean" => value.is_boolean(),
+        "object" => value.is_object(),
+        "array" => value.is_array(),
+        "string" => value.is_string(),
+        "number" => value.is_number(),
+        "integer" => {
+            value.is_i64() || value.is_u64() || value.as_f64().is_some_and(|f| f.fract() == 0.0)
+        }
+        _ => true,
+    }
+}
+
+fn type_name(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(_) => "number",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn spec() -> OpenApiSpec {
+        serde_json::from_value(json!({
+            "openapi": "3.1.0",
+            "info": { "title": "Test", "version": "1.0" },
+            "paths": {
+                "/users/{id}": {
+                    "get": {
+                        "responses": {
+                            "200": {
+                                "description": "User",
+                                "content": {
+                                    "application/json": {
+                                        "schema": { "$ref": "#/components/schemas/User" }
+                                    }
+                                }
+                            },
+                            "4XX": { "description": "Error" }
+                        }
+                    }
+                },
+                "/users/me": { "get": { "responses": { "204": { "description": "Me" } } } }
+            },
+            "components": {
+                "schemas": {
+                    "User": {
+                        "type": "object",
+                        "required": ["id", "name"],
+                        "properties": {
+                            "id": { "type": "integer" },
+                            "name": { "type": "string" },
+                            "role": { "type": "string", "enum": ["admin", "member"] },
+                            "tags": { "type": "array", "items": { "type": "string" } }
+                        }
+                    }
+                }
+            }
+        }))
+        .unwrap()
+    }
+
+    fn check(path: &str, status: u16, content_type: &str, body: Value) -> Vec<String> {
+        let mut headers = HeaderMap::new();
+        headers.insert(header::CONTENT_TYPE, content_type.parse().unwrap());
+        check_response(
+            &spec(),
+            &Method::GET,
+            path,
+            StatusCode::from_u16(status).unwrap(),
+            &headers,
+            body.to_string().as_bytes(),
+        )
+    }
+
+    #[test]
+    fn accepts_documented_responses() {
+        let user = json!({ "id": 1, "name": "Ann", "role": "admin", "tags": ["a"] });
+        assert_eq!(
+            check("/users/1", 200, "application/json; charset=utf-8", user),
+            Vec::<String>::new()
+        );
+        assert!(check("/users/1", 404, "application/json", json!({ "error": {} })).is_empty());
+    }
+
+    #[test]
+    fn reports_drift() {
+        let errors = check(
+            "/users/1",
+            200,
+            "application/json",
+            json!({ "id": "1", "role": "root", "tags": [1] }),
+        );
+        assert_eq!(
+            errors,
+            vec![
+                "$: missing required property 'name'",
+                "$.id: expected integer, got string",
+                "$.role: \"root\" is not one of [\"admin\",\"member\"]",
+                "$.tags[0]: expected string, got number",
+            ]
+        );
+
+        assert_eq!(
+            check("/users/1", 500, "application/json", json!({})),
+            vec!["Status 500 is not documented for GET /users/{id}"]
+        );
+        assert_eq!(check("/users/1", 200, "text/plain", json!("hi")).len(), 1);
+        // The literal path wins over the template
+        assert_eq!(
+            check("/users/me", 200, "application/json", json!({})),
+            vec!["Status 200 is not documented for GET /users/me"]
+        );
+        assert_eq!(
+            check("/posts", 200, "application/json", json!({})),
+            vec!["No path in the spec matches /posts"]
+        );
+    }
+}
@@ -0,0 +1,301 @@
+//! Cookie jar for the test client
+//!
+//! With [`TestClient::with_cookies`](crate::TestClient::with_cookies) the
+//! client keeps the cookies set by responses and sends them with later
+//! requests, like a browser would. Login-then-act flows against session or
+//! cookie-based JWT auth can then be tested as a sequence of plain requests.
+//!
+//! ```rust,ignore
+//! let client = TestClient::new(app).with_cookies();
+//! client.post_json("/login", &credentials).await.assert_status(StatusCode::OK);
+//! assert!(client.cookie("session").is_some());
+//!
+//! client.get("/me").await.assert_status(StatusCode::OK);
+//! client.clear_cookies();
+//! client.get("/me").await.assert_status(StatusCode::UNAUTHORIZED);
+//! ```
+//!
+//! The jar honours `Path`, `Max-Age` and `Expires`, judged by the client's
+//! clock (see [`TestClient::advance_time`](crate::TestClient::advance_time)).
+//! All requests go to the same host, so `Domain` and `Secure` are ignored.
+
+use http::{header, HeaderMap, HeaderValue};
+use std::collections::BTreeMap;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// A cookie stored in the jar
+#[derive(Debug, Clone)]
+struct StoredCookie {
+    value: String,
+    path: String,
+    expires: Option<SystemTime>,
+}
+
+/// Cookies kept across requests, keyed by (name, path)
+#[derive(Debug, Default)]
+pub(crate) struct CookieJar {
+    cookies: BTreeMap<(String, String), StoredCookie>,
+}
+
+impl CookieJar {
+    /// Store the cookies of `Set-Cookie` response headers
+    pub(crate) fn store(&mut self, request_path: &str, headers: &HeaderMap, now: SystemTime) {
+        for value in headers.get_all(header::SET_COOKIE) {
+            if let Some((name, cookie)) = value
+                .to_str()
+                .ok()
+                .and_then(|v| parse(v, request_path, now))
+            {
+                let key = (name, cookie.path.clone());
+                if cookie.expires.is_some_and(|at| at <= now) {
+                    self.cookies.remove(&key);
+                } else {
+                    self.cookies.insert(key, cookie);
+                }
+            }
+        }
+    }
+
+    /// The `Cookie` header value for a request to `path`
+    pub(crate) fn header_for(&self, path: &str, now: SystemTime) -> Option<HeaderValue> {
+        let mut matching: Vec<(&str, &StoredCookie)> = self
+            .cookies
+            .iter()
+            .filter(|(_, c)| c.expires.is_none_or(|at| at > now) && path_matches(&c.path, path))
+            .map(|((name, _), c)| (name.as_str(), c))
+            .collect();
+        if matching.is_empty() {
+            return None;
+        }
+        // More specific paths first (RFC 6265, section 5.4)
+        matching.sort_by_key(|(_, c)| std::cmp::Reverse(c.path.len()));
+        let value = matching
+            .iter()
+            .map(|(name, c)| format!("{}={}", name, c.value))
+            .collect::<Vec<_>>()
+            .join("; ");
+        HeaderValue::from_str(&value).ok()
+    }
+
+    /// The value of the cookie `name` (the most specific path wins)
+    pub(crate) fn get(&self, name: &str, now: SystemTime) -> Option<String> {
+        self.cookies
+            .iter()
+            .filter(|((n, _), c)| n == name && c.expires.is_none_or(|at| at > now))
+            .max_by_key(|((_, path), _)| path.len())
+            .map(|(_, c)| c.value.clone())
+    }
+
+    /// All live cookies as (name, value)
+    pub(crate) fn all(&self, now: SystemTime) -> Vec<(String, String)> {
+        self.cookies
+            .iter()
+            .filter(|(_, c)| c.expires.is_none_or(|at| at > now))
+            .map(|((name, _), c)| (name.clone(), c.value.clone()))
+            .collect()
+    }
+
+    /// Set a cookie for all paths
+    pub(crate) fn set(&mut self, name: &str, value: &str) {
+        self.cookies.insert(
+            (name.to_string(), "/".to_string()),
+            StoredCookie {
+                value: value.to_string(),
+                path: "/".to_string(),
+                expires: None,
+            },
+        );
+    }
+
+    /// Remove the cookie `name` on every path
+    pub(crate) fn remove(&mut self, name: &str) {
+        self.cookies.retain(|(n, _), _| n != name);
+    }
+
+    pub(crate) fn clear(&mut self) {
+        self.cookies.clear();
+    }
+}
+
+/// Parse a `Set-Cookie` value
+fn parse(set_cookie: &str, request_path: &str, now: SystemTime) -> Option<(String, StoredCookie)> {
+    let mut parts = set_cookie.split(';');
+    let (name, value) = parts.next()?.split_once('=')?;
+    let name = name.trim();
+    if name.is_empty() {
+        return None;
+    }
+
+    let mut cookie = StoredCookie {
+        value: value.trim().trim_matches('"').to_string(),
+        path: default_path(request_path),
+        expires: None,
+    };
+    let mut max_age = None;
+    for attribute in parts {
+        let (key, value) = match attribute.split_once('=') {
+            Some((key, value)) => (key.trim(), value.trim()),
+            None => (attribute.trim(), ""),
+        };
+        if key.eq_ignore_ascii_case("path") && value.starts_with('/') {
+            cookie.path = value.to_string();
+        } else if key.eq_ignore_ascii_case("max-age") {
+            max_age = value.parse::<i64>().ok();
+        } else if key.eq_ignore_ascii_case("expires") {
+            cookie.expires = parse_http_date(value);
+        }
+    }
+    // Max-Age takes precedence over Expires; one past the representable
+    // range keeps the cookie for the session
+    if let Some(seconds) = max_age {
+        cookie.expires = if seconds <= 0 {
+            Some(UNIX_EPOCH)
+        } else {
+            now.checked_add(Duration::from_secs(seconds as u64))
+        };
+    }
+    Some((name.to_string(), cookie))
+}
+
+/// Default cookie path: the request path up to its last `/`
+fn default_path(request_path: &str) -> String {
+    match request_path.rfind('/') {
+        Some(0) | None => "/".to_string(),
+        Some(i) => request_path[..i].to_string(),
+    }
+}
+
+fn path_matches(cookie_path: &str, request_path: &str) -> bool {
+    request_path == cookie_path
+        || (request_path.starts_with(cookie_path)
+            && (cookie_path.ends_with('/')
+                || request_path.as_bytes().get(cookie_path.len()) == Some(&b'/')))
+}
+
+/// Parse an IMF-fixdate (`Wed, 21 Oct 2015 07:28:00 GMT`)
+fn parse_http_date(value: &str) -> Option<SystemTime> {
+    let mut fields = value.split_whitespace().skip(1);
+    let day: u64 = fields.next()?.parse().ok()?;
+    let month = match fields.next()?.to_ascii_lowercase().as_str() {
+        "jan" => 1,
+        "feb" => 2,
+        "mar" => 3,
+        "apr" => 4,
+        "may" => 5,
+        "jun" => 6,
+        "jul" => 7,
+        "aug" => 8,
+        "sep" => 9,
+        "oct" => 10,
+        "nov" => 11,
+        "dec" => 12,
+        _ => return None,
+    };
+    // HTTP dates have four-digit years
+    let year: i64 = fields
+        .next()?
+        .parse()
+        .ok()
+        .filter(|y| (0..=9999).contains(y))?;
+    let mut time = fields.next()?.split(':').map(|n| n.parse::<u64>().ok());
+    let (hour, minute, second) = (time.next()??, time.next()??, time.next()??);
+
+    // Days since the epoch for a proleptic Gregorian date
+    let (y, m) = if month <= 2 {
+        (year - 1, month + 9)
+    } else {
+        (year, month - 3)
+    };
+    let era = y.div_euclid(400);
+    let yoe = y - era * 400;
+    let doy = (153 * m + 2) / 5 + day as i64 - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    let days = era * 146_097 + doe - 719_468;
+
+    let seconds = days * 86_400 + (hour * 3600 + minute * 60 + second) as i64;
+    if seconds < 0 {
+        return Some(UNIX_EPOCH);
+    }
+    UNIX_EPOCH.checked_add(Duration::from_secs(seconds as u64))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn headers(values: &[&str]) -> HeaderMap {
+        let mut map = HeaderMap::new();
+        for value in values {
+            map.append(header::SET_COOKIE, value.parse().unwrap());
+        }
+        map
+    }
+
+    #[test]
+    fn stores_and_sends_cookies_by_path() {
+        let now = SystemTime::now();
+        let mut jar = CookieJar::default();
+        jar.store(
+            "/auth/login",
+            &headers(&[
+                "session=abc; Path=/; HttpOnly",
+                "csrf=\"xyz\"",
+                "admin=1; Path=/admin",
+            ]),
+            now,
+        );
+
+        assert_eq!(jar.get("session", now).as_deref(), Some("abc"));
+        assert_eq!(jar.get("csrf", now).as_deref(), Some("xyz"));
+        assert_eq!(jar.header_for("/users", now).unwrap(), "session=abc");
+        assert_eq!(
+            jar.header_for("/admin/users", now).unwrap(),
+            "admin=1; session=abc"
+        );
+        assert_eq!(
+            jar.header_for("/auth/refresh", now).unwrap(),
+            "csrf=xyz; session=abc"
+        );
+        assert_eq!(
+            jar.header_for("/administrator", now).unwrap(),
+            "session=abc"
+        );
+    }
+
+    #[test]
+    fn expired_cookies_are_removed() {
+        let now = SystemTime::now();
+        let mut jar = CookieJar::default();
+        jar.store("/", &headers(&["a=1", "b=2", "c=3"]), now);
+        jar.store(
+            "/",
+            &headers(&["a=; Max-Age=0", "b=; Expires=Thu, 01 Jan 1970 00:00:00 GMT"]),
+            now,
+        );
+        assert_eq!(jar.all(now), vec![("c".to_string(), "3".to_string())]);
+
+        jar.store(
+            "/",
+            &headers(&["d=4; Expires=Fri, 01 Jan 2100 00:00:00 GMT"]),
+            now,
+        );
+        assert_eq!(jar.get("d", now).as_deref(), Some("4"));
+
+        jar.store("/", &headers(&["e=5; Max-Age=60"]), now);
+        assert_eq!(jar.get("e", now).as_deref(), Some("5"));
+        assert_eq!(jar.get("e", now + Duration::from_secs(60)), None);
+
+        // Too far in the future to represent: kept for the session
+        jar.store("/", &headers(&["f=6; Max-Age=9223372036854775807"]), now);
+        assert_eq!(jar.get("f", now).as_deref(), Some("6"));
+    }
+
+    #[test]
+    fn parses_http_dates() {
+        assert_eq!(
+            parse_http_date("Wed, 21 Oct 2015 07:28:00 GMT"),
+            Some(UNIX_EPOCH + Duration::from_secs(1_445_412_480))
+        );
+        assert_eq!(parse_http_date("tomorrow"), None);
+    }
+}
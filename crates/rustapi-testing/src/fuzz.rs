@@ -0,0 +1,1145 @@
+//! Spec-driven fuzzing of endpoints
+//!
+//! [`TestClient::fuzz`](crate::TestClient::fuzz) reads the app's generated
+//! OpenAPI spec and sends randomized requests to every documented
+//! operation: valid ones built from the parameter and request body schemas,
+//! and invalid ones with a single defect (a missing required property, a
+//! wrong type, a value just outside its bounds, malformed JSON, ...). Every
+//! response must have a documented status code and none may be a server
+//! error, so handlers that fall over on unexpected input are found in tests.
+//!
+//! ```rust,ignore
+//! TestClient::new(app())
+//!     .fuzz()
+//!     .cases(200)
+//!     .skip(Method::DELETE, "/users/{id}")
+//!     .run()
+//!     .await
+//!     .assert_ok();
+//! ```
+//!
+//! Runs are seeded. A failing run reports its seed; setting
+//! `RUSTAPI_FUZZ_SEED` to it (or calling [`SpecFuzzer::seed`]) sends the
+//! same requests again. Request bodies are generated for JSON and form
+//! media types; schema keywords beyond those checked by the
+//! [`contract`](crate::contract) module (e.g. `pattern`) are ignored.
+
+use crate::client::{TestClient, TestRequest};
+use crate::contract::{documented_response, has_type, resolve};
+use http::{Method, StatusCode};
+use serde_json::{json, Map, Value};
+use std::fmt;
+
+/// Environment variable that fixes the seed of fuzzing runs
+pub const FUZZ_SEED_ENV: &str = "RUSTAPI_FUZZ_SEED";
+
+/// Default number of requests per operation
+pub const DEFAULT_CASES: usize = 50;
+
+/// Failures listed by [`FuzzReport::assert_ok`]
+const MAX_LISTED_FAILURES: usize = 20;
+
+/// Nesting depth after which optional properties and items are left out
+const MAX_DEPTH: usize = 6;
+
+const METHODS: [Method; 7] = [
+    Method::GET,
+    Method::POST,
+    Method::PUT,
+    Method::PATCH,
+    Method::DELETE,
+    Method::HEAD,
+    Method::OPTIONS,
+];
+
+/// A fuzzing run over the operations of a [`TestClient`]'s spec
+pub struct SpecFuzzer<'a> {
+    client: &'a TestClient,
+    cases: usize,
+    seed: Option<u64>,
+    skipped: Vec<(Method, String)>,
+}
+
+impl<'a> SpecFuzzer<'a> {
+    pub(crate) fn new(client: &'a TestClient) -> Self {
+        Self {
+            client,
+            cases: DEFAULT_CASES,
+            seed: None,
+            skipped: Vec::new(),
+        }
+    }
+
+    /// Set the number of requests per operation; half of them are invalid
+    pub fn cases(mut self, cases: usize) -> Self {
+        self.cases = cases;
+        self
+    }
+
+    /// Use a fixed seed instead of `RUSTAPI_FUZZ_SEED` or a random one
+    pub fn seed(mut self, seed: u64) -> Self {
+        self.seed = Some(seed);
+        self
+    }
+
+    /// Leave out an operation, given by method and path template
+    pub fn skip(mut self, method: Method, path: &str) -> Self {
+        self.skipped.push((method, path.to_string()));
+        self
+    }
+
+    /// Send the requests and collect the failures
+    pub async fn run(self) -> FuzzReport {
+        let seed = self.seed.unwrap_or_else(|| {
+            std::env::var(FUZZ_SEED_ENV)
+                .ok()
+                .and_then(|s| s.trim().parse().ok())
+                .unwrap_or_else(random_seed)
+        });
+        let mut rng = Rng::new(seed);
+        let spec = self.client.openapi_spec().to_json();
+        let mut report = FuzzReport {
+            seed,
+            requests: 0,
+            failures: Vec::new(),
+        };
+
+        let mut templates: Vec<&String> = spec["paths"]
+            .as_object()
+            .map(|paths| paths.keys().collect())
+            .unwrap_or_default();
+        templates.sort();
+
+        for template in templates {
+            let item = &spec["paths"][template.as_str()];
+            for method in METHODS {
+                let operation = &item[method.as_str().to_ascii_lowercase()];
+                let skipped = self
+                    .skipped
+                    .iter()
+                    .any(|(m, path)| *m == method && path == template);
+                if !operation.is_object() || skipped {
+                    continue;
+                }
+
+                let operation = Operation::new(&spec, template, item, operation);
+                for case in 0..self.cases {
+                    let mut generator = Generator {
+                        spec: &spec,
+                        rng: &mut rng,
+                    };
+                    let mut request = generator.request(&operation);
+                    let description = if case % 2 == 0 {
+                        "valid request".to_string()
+                    } else {
+                        generator.break_request(&operation, &mut request)
+                    };
+
+                    let path = request.path.clone();
+                    let body = request.body.as_ref().map(|(_, body)| body.clone());
+                    let response = self.client.request(request.build(method.clone())).await;
+                    report.requests += 1;
+
+                    let status = response.status();
+                    let path_only = path.split('?').next().unwrap_or(&path);
+                    let reason = if status.is_server_error() {
+                        Some(format!("server error: {}", response.text()))
+                    } else {
+                        documented_response(&spec, &method, path_only, status).err()
+                    };
+                    if let Some(reason) = reason {
+                        report.failures.push(FuzzFailure {
+                            method: method.clone(),
+                            path,
+                            case: description,
+                            body,
+                            status,
+                            reason,
+                        });
+                    }
+                }
+            }
+        }
+        report
+    }
+}
+
+/// Outcome of a fuzzing run
+#[derive(Debug, Clone)]
+pub struct FuzzReport {
+    seed: u64,
+    requests: usize,
+    failures: Vec<FuzzFailure>,
+}
+
+impl FuzzReport {
+    /// The seed the requests were generated with
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    /// Number of requests sent
+    pub fn requests(&self) -> usize {
+        self.requests
+    }
+
+    /// Requests answered with a server error or an undocumented status
+    pub fn failures(&self) -> &[FuzzFailure] {
+        &self.failures
+    }
+
+    /// Whether every response was documented and not a server error
+    pub fn is_ok(&self) -> bool {
+        self.failures.is_empty()
+    }
+
+    /// Assert that no request failed
+    ///
+    /// # Panics
+    ///
+    /// Panics listing the failing requests and the seed to replay them.
+    pub fn assert_ok(&self) -> &Self {
+        if self.failures.is_empty() {
+            return self;
+        }
+        let listed: Vec<String> = self
+            .failures
+            .iter()
+            .take(MAX_LISTED_FAILURES)
+            .map(|failure| format!("  {}", failure))
+            .collect();
+        panic!(
+            "Fuzzing found {} failing requests out of {} (seed {}, replay with {}={}):\n{}{}",
+            self.failures.len(),
+            self.requests,
+            self.seed,
+            FUZZ_SEED_ENV,
+            self.seed,
+            listed.join("\n"),
+            if self.failures.len() > MAX_LISTED_FAILURES {
+                format!(
+                    "\n  ... and {} more",
+                    self.failures.len() - MAX_LISTED_FAILURES
+                )
+            } else {
+                String::new()
+            }
+        );
+    }
+}
+
+/// A request that got a server error or an undocumented status
+#[derive(Debug, Clone)]
+pub struct FuzzFailure {
+    /// Request method
+    pub method: Method,
+    /// Request path with query string
+    pub path: String,
+    /// What was generated: `valid request` or the defect of an invalid one
+    pub case: String,
+    /// Request body, if any
+    pub body: Option<String>,
+    /// Response status
+    pub status: StatusCode,
+    /// Why the response is a failure
+    pub reason: String,
+}
+
+impl fmt::Display for FuzzFailure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} {} ({}) -> {}: {}",
+            self.method, self.path, self.case, self.status, self.reason
+        )?;
+        if let Some(body) = &self.body {
+            write!(f, "\n    body: {}", body)?;
+        }
+        Ok(())
+    }
+}
+
+/// A documented parameter
+struct Parameter<'s> {
+    name: String,
+    location: String,
+    required: bool,
+    schema: &'s Value,
+}
+
+/// The parts of an operation requests are generated from
+struct Operation<'s> {
+    template: String,
+    parameters: Vec<Parameter<'s>>,
+    /// Media type and schema of the request body
+    body: Option<(String, &'s Value)>,
+    body_required: bool,
+}
+
+impl<'s> Operation<'s> {
+    fn new(spec: &'s Value, template: &str, item: &'s Value, operation: &'s Value) -> Self {
+        let mut parameters: Vec<Parameter<'s>> = Vec::new();
+        // Operation parameters override path-level ones of the same name
+        for list in [&operation["parameters"], &item["parameters"]] {
+            for parameter in list.as_array().into_iter().flatten() {
+                let parameter = resolve(spec, parameter);
+                let (Some(name), Some(location)) =
+                    (parameter["name"].as_str(), parameter["in"].as_str())
+                else {
+                    continue;
+                };
+                if parameters
+                    .iter()
+                    .any(|p| p.name == name && p.location == location)
+                {
+                    continue;
+                }
+                parameters.push(Parameter {
+                    name: name.to_string(),
+                    location: location.to_string(),
+                    required: location == "path" || parameter["required"] == Value::Bool(true),
+                    schema: &parameter["schema"],
+                });
+            }
+        }
+        // Template segments the spec forgot to declare
+        for segment in template.split('/') {
+            if let Some(name) = segment.strip_prefix('{').and_then(|s| s.strip_suffix('}')) {
+                if !parameters
+                    .iter()
+                    .any(|p| p.location == "path" && p.name == name)
+                {
+                    parameters.push(Parameter {
+                        name: name.to_string(),
+                        location: "path".to_string(),
+                        required: true,
+                        schema: &Value::Null,
+                    });
+                }
+            }
+        }
+
+        let request_body = resolve(spec, &operation["requestBody"]);
+        let body = request_body["content"].as_object().and_then(|content| {
+            content.iter().find_map(|(media_type, media)| {
+                let base = media_type.split(';').next().unwrap_or("").trim();
+                let supported = base == "application/json"
+                    || base.ends_with("+json")
+                    || base == "application/x-www-form-urlencoded";
+                supported.then(|| (base.to_string(), &media["schema"]))
+            })
+        });
+
+        Self {
+            template: template.to_string(),
+            parameters,
+            body,
+            body_required: request_body["required"] == Value::Bool(true),
+        }
+    }
+}
+
+/// A generated request, kept as values so an invalid variant can be derived
+struct FuzzRequest {
+    path: String,
+    path_values: Vec<(String, String)>,
+    query: Vec<(String, Value)>,
+    headers: Vec<(String, String)>,
+    body_value: Option<Value>,
+    /// Content type and encoded body
+    body: Option<(String, String)>,
+}
+
+impl FuzzRequest {
+    /// Encode the path and body from the generated values
+    fn render(&mut self, template: &str, media_type: Option<&str>) {
+        let mut path = template.to_string();
+        for (name, value) in &self.path_values {
+            path = path.replace(&format!("{{{}}}", name), &encode(value));
+        }
+        let mut pairs = Vec::new();
+        for (name, value) in &self.query {
+            for value in param_strings(value) {
+                pairs.push(format!("{}={}", encode(name), encode(&value)));
+            }
+        }
+        if !pairs.is_empty() {
+            path = format!("{}?{}", path, pairs.join("&"));
+        }
+        self.path = path;
+
+        self.body = match (&self.body_value, media_type) {
+            (Some(value), Some("application/x-www-form-urlencoded")) => {
+                let fields: Vec<String> = match value {
+                    Value::Object(map) => map
+                        .iter()
+                        .flat_map(|(k, v)| {
+                            param_strings(v)
+                                .into_iter()
+                                .map(move |v| format!("{}={}", encode(k), encode(&v)))
+                        })
+                        .collect(),
+                    _ => Vec::new(),
+                };
+                Some((
+                    "application/x-www-form-urlencoded".to_string(),
+                    fields.join("&"),
+                ))
+            }
+            (Some(value), Some(media_type)) => Some((media_type.to_string(), value.to_string())),
+            _ => None,
+        };
+    }
+
+    fn build(self, method: Method) -> TestRequest {
+        let mut request = TestRequest::new(method, &self.path);
+        for (name, value) in &self.headers {
+            request = request.header(name, value);
+        }
+        if let Some((content_type, body)) = self.body {
+            request = request.content_type(&content_type).body(body);
+        }
+        request
+    }
+}
+
+/// Random values shaped by schemas
+struct Generator<'g> {
+    spec: &'g Value,
+    rng: &'g mut Rng,
+}
+
+impl Generator<'_> {
+    /// A request that satisfies the documented schemas
+    fn request(&mut self, operation: &Operation<'_>) -> FuzzRequest {
+        let mut request = FuzzRequest {
+            path: String::new(),
+            path_values: Vec::new(),
+            query: Vec::new(),
+            headers: Vec::new(),
+            body_value: None,
+            body: None,
+        };
+        for parameter in &operation.parameters {
+            if !parameter.required && self.rng.chance(2) {
+                continue;
+            }
+            let value = match parameter.location.as_str() {
+                // Untyped path parameters are usually identifiers
+                "path" if is_plain_string(self.spec, parameter.schema) && self.rng.chance(2) => {
+                    Value::String(self.rng.below(1_000_000).to_string())
+                }
+                _ => self.value(parameter.schema, 0),
+            };
+            match parameter.location.as_str() {
+                "path" => {
+                    let value = param_strings(&value).join(",");
+                    let value = if value.is_empty() { "0".into() } else { value };
+                    request.path_values.push((parameter.name.clone(), value));
+                }
+                "query" if !value.is_null() => request.query.push((parameter.name.clone(), value)),
+                "header" if !value.is_null() => request
+                    .headers
+                    .push((parameter.name.clone(), param_strings(&value).join(","))),
+                _ => {}
+            }
+        }
+        if let Some((_, schema)) = &operation.body {
+            if operation.body_required || !self.rng.chance(4) {
+                request.body_value = Some(self.value(schema, 0));
+            }
+        }
+        request.render(&operation.template, operation.media_type());
+        request
+    }
+
+    /// Give `request` one defect, returning its description
+    fn break_request(&mut self, operation: &Operation<'_>, request: &mut FuzzRequest) -> String {
+        let mut defects: Vec<Defect> = Vec::new();
+        if let (Some((media_type, schema)), Some(value)) = (&operation.body, &request.body_value) {
+            let mut mutations = Vec::new();
+            self.mutations(schema, value, "", 0, &mut mutations);
+            defects.extend(mutations.into_iter().map(Defect::Body));
+            if media_type != "application/x-www-form-urlencoded" {
+                defects.push(Defect::MalformedBody);
+            }
+        }
+        if operation.body.is_some() && operation.body_required {
+            defects.push(Defect::MissingBody);
+        }
+        for (i, parameter) in operation.parameters.iter().enumerate() {
+            if parameter.required && parameter.location != "path" {
+                defects.push(Defect::MissingParameter(i));
+            }
+            let schema = resolve(self.spec, parameter.schema);
+            if let Some(bad) = self.wrong_scalar(schema) {
+                defects.push(Defect::Parameter(i, bad, "wrong type".to_string()));
+            }
+            for (bad, what) in self.out_of_bounds(schema) {
+                defects.push(Defect::Parameter(i, bad, what));
+            }
+        }
+
+        if defects.is_empty() {
+            return "valid request (no defect applies)".to_string();
+        }
+        let defect = defects.swap_remove(self.rng.below(defects.len() as u64) as usize);
+        let media_type = operation.media_type();
+        let description = match defect {
+            Defect::Body(mutation) => {
+                let value = request.body_value.as_mut().expect("body value");
+                let description = mutation.apply(value);
+                request.render(&operation.template, media_type);
+                description
+            }
+            Defect::MalformedBody => {
+                let body = request.body_value.as_ref().map(Value::to_string);
+                let body = body.unwrap_or_default();
+                let truncated = &body[..body.len() / 2];
+                request.body = Some((
+                    media_type.unwrap_or("application/json").to_string(),
+                    format!("{}{{\"", truncated),
+                ));
+                "malformed body".to_string()
+            }
+            Defect::MissingBody => {
+                request.body_value = None;
+                request.render(&operation.template, media_type);
+                "missing request body".to_string()
+            }
+            Defect::MissingParameter(i) => {
+                let parameter = &operation.parameters[i];
+                request.query.retain(|(name, _)| *name != parameter.name);
+                request.headers.retain(|(name, _)| *name != parameter.name);
+                request.render(&operation.template, media_type);
+                format!(
+                    "missing required {} parameter '{}'",
+                    parameter.location, parameter.name
+                )
+            }
+            Defect::Parameter(i, bad, what) => {
+                let parameter = &operation.parameters[i];
+                let text = param_strings(&bad).join(",");
+                match parameter.location.as_str() {
+                    "path" => set_entry(&mut request.path_values, &parameter.name, text.clone()),
+                    "query" => set_entry(&mut request.query, &parameter.name, bad.clone()),
+                    "header" => set_entry(&mut request.headers, &parameter.name, text.clone()),
+                    _ => {}
+                }
+                request.render(&operation.template, media_type);
+                format!(
+                    "{} parameter '{}' = {} ({})",
+                    parameter.location, parameter.name, bad, what
+                )
+            }
+        };
+        format!("invalid: {}", description)
+    }
+
+    /// Ways to make `value` violate `schema` at one place
+    fn mutations(
+        &self,
+        schema: &Value,
+        value: &Value,
+        pointer: &str,
+        depth: usize,
+        out: &mut Vec<Mutation>,
+    ) {
+        let schema = resolve(self.spec, schema);
+        if depth > MAX_DEPTH || !schema.is_object() {
+            return;
+        }
+        let at = display_pointer(pointer);
+
+        if let Some(bad) = self
+            .wrong_scalar(schema)
+            .or_else(|| self.wrong_type(schema))
+        {
+            out.push(Mutation::Replace(
+                pointer.to_string(),
+                bad,
+                format!("wrong type at {}", at),
+            ));
+        }
+        for (bad, what) in self.out_of_bounds(schema) {
+            out.push(Mutation::Replace(
+                pointer.to_string(),
+                bad,
+                format!("{} at {}", what, at),
+            ));
+        }
+
+        match value {
+            Value::Object(object) => {
+                let required = self.collect_keyword(schema, "required");
+                for name in required.iter().filter_map(Value::as_str) {
+                    if object.contains_key(name) {
+                        out.push(Mutation::Remove(
+                            pointer.to_string(),
+                            name.to_string(),
+                            format!("missing required property '{}' at {}", name, at),
+                        ));
+                    }
+                }
+                let properties = self.properties(schema);
+                for (name, field) in object {
+                    if let Some(sub) = properties.get(name) {
+                        let child = format!("{}/{}", pointer, escape_pointer(name));
+                        self.mutations(sub, field, &child, depth + 1, out);
+                    }
+                }
+            }
+            Value::Array(items) => {
+                if let (Some(sub), Some(first)) = (schema.get("items"), items.first()) {
+                    self.mutations(sub, first, &format!("{}/0", pointer), depth + 1, out);
+                }
+                if let Some(min) = schema["minItems"].as_u64().filter(|&min| min > 0) {
+                    let mut fewer = items.clone();
+                    fewer.truncate(min as usize - 1);
+                    out.push(Mutation::Replace(
+                        pointer.to_string(),
+                        Value::Array(fewer),
+                        format!("fewer than {} items at {}", min, at),
+                    ));
+                }
+                if let (Some(max), Some(item)) = (schema["maxItems"].as_u64(), items.first()) {
+                    out.push(Mutation::Replace(
+                        pointer.to_string(),
+                        Value::Array(vec![item.clone(); max as usize + 1]),
+                        format!("more than {} items at {}", max, at),
+                    ));
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// `required` (or another array keyword) including `allOf` members
+    fn collect_keyword(&self, schema: &Value, keyword: &str) -> Vec<Value> {
+        let mut values: Vec<Value> = schema[keyword].as_array().cloned().unwrap_or_default();
+        for sub in schema["allOf"].as_array().into_iter().flatten() {
+            values.extend(self.collect_keyword(resolve(self.spec, sub), keyword));
+        }
+        values
+    }
+
+    /// `properties` including `allOf` members
+    fn properties(&self, schema: &Value) -> Map<String, Value> {
+        let mut properties = schema["properties"]
+            .as_object()
+            .cloned()
+            .unwrap_or_default();
+        for sub in schema["allOf"].as_array().into_iter().flatten() {
+            properties.extend(self.properties(resolve(self.spec, sub)));
+        }
+        properties
+    }
+
+    /// A value of a type the schema does not allow
+    fn wrong_type(&self, schema: &Value) -> Option<Value> {
+        let types = types_of(schema);
+        if types.is_empty() {
+            return None;
+        }
+        [
+            json!("fuzz"),
+            json!(12345),
+            json!(true),
+            json!({ "fuzz": 1 }),
+            json!(["fuzz"]),
+        ]
+        .into_iter()
+        .find(|candidate| !types.iter().any(|t| has_type(candidate, t)))
+    }
+
+    /// A non-numeric string for numeric or boolean schemas; these make
+    /// parameter defects that survive being sent as text
+    fn wrong_scalar(&self, schema: &Value) -> Option<Value> {
+        let types = types_of(schema);
+        let scalar = types
+            .iter()
+            .any(|t| matches!(*t, "integer" | "number" | "boolean"));
+        (scalar && !types.contains(&"string")).then(|| json!("not-a-number"))
+    }
+
+    /// Values just outside the bounds of a schema
+    fn out_of_bounds(&self, schema: &Value) -> Vec<(Value, String)> {
+        let mut out = Vec::new();
+        let types = types_of(schema);
+        if types.contains(&"integer") {
+            let (lo, hi) = integer_bounds(schema);
+            if let Some(lo) = lo {
+                out.extend(int_value(lo - 1).map(|v| (v, format!("below the minimum {}", lo))));
+            }
+            if let Some(hi) = hi {
+                out.extend(int_value(hi + 1).map(|v| (v, format!("above the maximum {}", hi))));
+            }
+        } else if types.contains(&"number") {
+            if let Some(min) = schema["minimum"].as_f64() {
+                out.push((json!(min - 1.0), format!("below the minimum {}", min)));
+            }
+            if let Some(max) = schema["maximum"].as_f64() {
+                out.push((json!(max + 1.0), format!("above the maximum {}", max)));
+            }
+        }
+        if types.contains(&"string") {
+            if let Some(min) = schema["minLength"].as_u64().filter(|&min| min > 0) {
+                out.push((
+                    Value::String("x".repeat(min as usize - 1)),
+                    format!("shorter than {} characters", min),
+                ));
+            }
+            if let Some(max) = schema["maxLength"].as_u64() {
+                out.push((
+                    Value::String("x".repeat(max as usize + 1)),
+                    format!("longer than {} characters", max),
+                ));
+            }
+        }
+        if schema["enum"].is_array() {
+            out.push((json!("__not_a_member__"), "not one of the enum".to_string()));
+        }
+        out
+    }
+
+    /// A random value valid for `schema`
+    fn value(&mut self, schema: &Value, depth: usize) -> Value {
+        let schema = resolve(self.spec, schema);
+        if depth > 2 * MAX_DEPTH {
+            return Value::Null;
+        }
+        if let Some(value) = schema.get("const") {
+            return value.clone();
+        }
+        if let Some(options) = schema["enum"].as_array().filter(|o| !o.is_empty()) {
+            return options[self.rng.below(options.len() as u64) as usize].clone();
+        }
+        for keyword in ["oneOf", "anyOf"] {
+            if let Some(options) = schema[keyword].as_array().filter(|o| !o.is_empty()) {
+                let choice = &options[self.rng.below(options.len() as u64) as usize];
+                return self.value(choice, depth + 1);
+            }
+        }
+        if let Some(parts) = schema["allOf"].as_array() {
+            let mut merged = match self.value_of_type(schema, depth) {
+                Value::Object(map) => map,
+                _ => Map::new(),
+            };
+            for part in parts {
+                if let Value::Object(map) = self.value(part, depth + 1) {
+                    merged.extend(map);
+                }
+            }
+            return Value::Object(merged);
+        }
+        self.value_of_type(schema, depth)
+    }
+
+    fn value_of_type(&mut self, schema: &Value, depth: usize) -> Value {
+        let mut types = types_of(schema);
+        if types.is_empty() {
+            types = if schema.get("properties").is_some() {
+                vec!["object"]
+            } else if schema.get("items").is_some() {
+                vec!["array"]
+            } else {
+                vec!["string"]
+            };
+        }
+        let non_null: Vec<&str> = types.iter().copied().filter(|t| *t != "null").collect();
+        if non_null.is_empty() || (non_null.len() < types.len() && self.rng.chance(8)) {
+            return Value::Null;
+        }
+        match non_null[self.rng.below(non_null.len() as u64) as usize] {
+            "boolean" => Value::Bool(self.rng.chance(2)),
+            "integer" => self.integer(schema),
+            "number" => self.number(schema),
+            "array" => self.array(schema, depth),
+            "object" => self.object(schema, depth),
+            _ => Value::String(self.string(schema)),
+        }
+    }
+
+    fn integer(&mut self, schema: &Value) -> Value {
+        let (lo, hi) = integer_bounds(schema);
+        let lo = lo.unwrap_or(i64::MIN as i128);
+        let hi = hi.unwrap_or(u64::MAX as i128);
+        let mut candidates = vec![lo, hi];
+        if lo <= 0 && hi >= 0 {
+            candidates.push(0);
+        }
+        let value = if self.rng.chance(4) {
+            candidates[self.rng.below(candidates.len() as u64) as usize]
+        } else {
+            // Mostly small numbers, like real input
+            let (small_lo, small_hi) = (lo.max(-1000), hi.min(1000));
+            if small_lo <= small_hi {
+                small_lo + self.rng.below((small_hi - small_lo + 1) as u64) as i128
+            } else {
+                lo + (self.rng.next() as i128).rem_euclid(hi - lo + 1)
+            }
+        };
+        int_value(value).unwrap_or(Value::Null)
+    }
+
+    fn number(&mut self, schema: &Value) -> Value {
+        let lo = schema["minimum"].as_f64().unwrap_or(-1000.0);
+        let hi = schema["maximum"].as_f64().unwrap_or(1000.0).max(lo);
+        let value = if self.rng.chance(4) {
+            if self.rng.chance(2) {
+                lo
+            } else {
+                hi
+            }
+        } else {
+            lo + (hi - lo) * (self.rng.below(1_000_001) as f64 / 1_000_000.0)
+        };
+        json!(value)
+    }
+
+    fn string(&mut self, schema: &Value) -> String {
+        let n = self.rng.below(10_000);
+        match schema["format"].as_str() {
+            Some("email") => return format!("user{}@example.com", n),
+            Some("uuid") => {
+                let hex = format!("{:016x}{:016x}", self.rng.next(), self.rng.next());
+                return format!(
+                    "{}-{}-4{}-a{}-{}",
+                    &hex[..8],
+                    &hex[8..12],
+                    &hex[13..16],
+                    &hex[17..20],
+                    &hex[20..32]
+                );
+            }
+            Some("date-time") => {
+                return format!("2024-{:02}-{:02}T12:00:00Z", n % 12 + 1, n % 28 + 1)
+            }
+            Some("date") => return format!("2024-{:02}-{:02}", n % 12 + 1, n % 28 + 1),
+            Some("uri") | Some("url") => return format!("https://example.com/{}", n),
+            Some("ipv4") => return format!("192.0.2.{}", n % 256),
+            _ => {}
+        }
+
+        let min = schema["minLength"].as_u64().unwrap_or(0);
+        let max = schema["maxLength"].as_u64().unwrap_or(min + 12).max(min);
+        let len = if self.rng.chance(4) {
+            if self.rng.chance(2) {
+                min
+            } else {
+                max
+            }
+        } else {
+            min + self.rng.below(max.min(min + 64) - min + 1)
+        };
+        const CHARS: &[u8] = b"abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ0123456789 -_";
+        (0..len)
+            .map(|_| CHARS[self.rng.below(CHARS.len() as u64) as usize] as char)
+            .collect()
+    }
+
+    fn array(&mut self, schema: &Value, depth: usize) -> Value {
+        let min = schema["minItems"].as_u64().unwrap_or(0);
+        let max = schema["maxItems"].as_u64().unwrap_or(min + 3).max(min);
+        let len = if depth >= MAX_DEPTH {
+            min
+        } else {
+            min + self.rng.below(max.min(min + 8) - min + 1)
+        };
+        let items = &schema["items"];
+        Value::Array((0..len).map(|_| self.value(items, depth + 1)).collect())
+    }
+
+    fn object(&mut self, schema: &Value, depth: usize) -> Value {
+        let required: Vec<String> = self
+            .collect_keyword(schema, "required")
+            .iter()
+            .filter_map(|v| v.as_str().map(str::to_string))
+            .collect();
+        let mut object = Map::new();
+        for (name, sub) in self.properties(schema) {
+            let include = required.contains(&name) || (depth < MAX_DEPTH && self.rng.chance(2));
+            if include {
+                let value = self.value(&sub, depth + 1);
+                object.insert(name, value);
+            }
+        }
+        Value::Object(object)
+    }
+}
+
+impl Operation<'_> {
+    fn media_type(&self) -> Option<&str> {
+        self.body
+            .as_ref()
+            .map(|(media_type, _)| media_type.as_str())
+    }
+}
+
+enum Defect {
+    Body(Mutation),
+    MalformedBody,
+    MissingBody,
+    /// Index into the operation's parameters
+    MissingParameter(usize),
+    Parameter(usize, Value, String),
+}
+
+/// A change to a JSON body at a pointer
+enum Mutation {
+    Replace(String, Value, String),
+    /// Remove a property of the object at the pointer
+    Remove(String, String, String),
+}
+
+impl Mutation {
+    /// Apply the change, returning its description
+    fn apply(self, value: &mut Value) -> String {
+        match self {
+            Mutation::Replace(pointer, bad, description) => {
+                if let Some(target) = value.pointer_mut(&pointer) {
+                    *target = bad;
+                }
+                description
+            }
+            Mutation::Remove(pointer, name, description) => {
+                if let Some(Value::Object(object)) = value.pointer_mut(&pointer) {
+                    object.remove(&name);
+                }
+                description
+            }
+        }
+    }
+}
+
+/// Small deterministic PRNG (SplitMix64)
+struct Rng(u64);
+
+impl Rng {
+    fn new(seed: u64) -> Self {
+        Self(seed)
+    }
+
+    fn next(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// A number in `0..n` (`n` > 0)
+    fn below(&mut self, n: u64) -> u64 {
+        self.next() % n.max(1)
+    }
+
+    /// True with a probability of 1 in `n`
+    fn chance(&mut self, n: u64) -> bool {
+        self.below(n) == 0
+    }
+}
+
+fn random_seed() -> u64 {
+    let nanos = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_nanos() as u64)
+        .unwrap_or_default();
+    Rng::new(nanos).next()
+}
+
+fn types_of(schema: &Value) -> Vec<&str> {
+    match &schema["type"] {
+        Value::String(t) => vec![t.as_str()],
+        Value::Array(ts) => ts.iter().filter_map(Value::as_str).collect(),
+        _ => Vec::new(),
+    }
+}
+
+/// Whether a schema is an unconstrained string (or missing)
+fn is_plain_string(spec: &Value, schema: &Value) -> bool {
+    let schema = resolve(spec, schema);
+    let types = types_of(schema);
+    (types.is_empty() || types == ["string"])
+        && ["enum", "format", "minLength", "maxLength", "const"]
+            .iter()
+            .all(|keyword| schema.get(keyword).is_none())
+}
+
+/// Inclusive integer bounds from `minimum`/`maximum`, their exclusive
+/// forms and the integer `format`
+fn integer_bounds(schema: &Value) -> (Option<i128>, Option<i128>) {
+    let (mut lo, mut hi) = match schema["format"].as_str() {
+        Some("int8") => (Some(i8::MIN as i128), Some(i8::MAX as i128)),
+        Some("uint8") => (Some(0), Some(u8::MAX as i128)),
+        Some("int16") => (Some(i16::MIN as i128), Some(i16::MAX as i128)),
+        Some("uint16") => (Some(0), Some(u16::MAX as i128)),
+        Some("int32") => (Some(i32::MIN as i128), Some(i32::MAX as i128)),
+        Some("uint32") => (Some(0), Some(u32::MAX as i128)),
+        Some("int64") => (Some(i64::MIN as i128), Some(i64::MAX as i128)),
+        Some("uint64") => (Some(0), Some(u64::MAX as i128)),
+        _ => (None, None),
+    };
+    let bound = |keyword: &str| schema[keyword].as_f64().map(|f| f as i128);
+    if let Some(min) = bound("minimum") {
+        lo = Some(lo.map_or(min, |lo| lo.max(min)));
+    }
+    if let Some(min) = bound("exclusiveMinimum") {
+        lo = Some(lo.map_or(min + 1, |lo| lo.max(min + 1)));
+    }
+    if let Some(max) = bound("maximum") {
+        hi = Some(hi.map_or(max, |hi| hi.min(max)));
+    }
+    if let Some(max) = bound("exclusiveMaximum") {
+        hi = Some(hi.map_or(max - 1, |hi| hi.min(max - 1)));
+    }
+    (lo, hi)
+}
+
+/// A JSON integer, if representable
+fn int_value(n: i128) -> Option<Value> {
+    i64::try_from(n)
+        .map(Value::from)
+        .or_else(|_| u64::try_from(n).map(Value::from))
+        .ok()
+}
+
+/// A value as parameter text; arrays give one string per item
+fn param_strings(value: &Value) -> Vec<String> {
+    match value {
+        Value::Null => Vec::new(),
+        Value::String(s) => vec![s.clone()],
+        Value::Array(items) => items.iter().flat_map(param_strings).collect(),
+        other => vec![other.to_string()],
+    }
+}
+
+fn set_entry<V>(entries: &mut Vec<(String, V)>, name: &str, value: V) {
+    match entries.iter_mut().find(|(n, _)| n == name) {
+        Some(entry) => entry.1 = value,
+        None => entries.push((name.to_string(), value)),
+    }
+}
+
+/// Percent-encode everything but unreserved characters
+fn encode(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for byte in text.bytes() {
+        if byte.is_ascii_alphanumeric() || matches!(byte, b'-' | b'.' | b'_' | b'~') {
+            out.push(byte as char);
+        } else {
+            out.push_str(&format!("%{:02X}", byte));
+        }
+    }
+    out
+}
+
+fn escape_pointer(name: &str) -> String {
+    name.replace('~', "~0").replace('/', "~1")
+}
+
+/// `/user/tags/0` as `$.user.tags[0]`
+fn display_pointer(pointer: &str) -> String {
+    let mut out = String::from("$");
+    for segment in pointer.split('/').skip(1) {
+        let segment = segment.replace("~1", "/").replace("~0", "~");
+        if segment.parse::<usize>().is_ok() {
+            out.push_str(&format!("[{}]", segment));
+        } else {
+            out.push('.');
+            out.push_str(&segment);
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn spec() -> Value {
+        json!({
+            "components": {
+                "schemas": {
+                    "User": {
+                        "type": "object",
+                        "required": ["name", "age"],
+                        "properties": {
+                            "name": { "type": "string", "minLength": 1, "maxLength": 8 },
+                            "age": { "type": "integer", "format": "uint8" },
+                            "role": { "type": "string", "enum": ["admin", "member"] },
+                            "email": { "type": ["string", "null"], "format": "email" },
+                            "tags": { "type": "array", "items": { "type": "string" }, "maxItems": 2 }
+                        }
+                    }
+                }
+            }
+        })
+    }
+
+    #[test]
+    fn generated_values_match_their_schema() {
+        let spec = spec();
+        let schema = json!({ "$ref": "#/components/schemas/User" });
+        let mut rng = Rng::new(7);
+        for _ in 0..200 {
+            let mut generator = Generator {
+                spec: &spec,
+                rng: &mut rng,
+            };
+            let value = generator.value(&schema, 0);
+            let name = value["name"].as_str().unwrap();
+            assert!((1..=8).contains(&name.chars().count()), "{}", value);
+            assert!(value["age"].as_u64().unwrap() <= 255, "{}", value);
+            if let Some(role) = value.get("role") {
+                assert!(role == "admin" || role == "member");
+            }
+            assert!(value
+                .get("tags")
+                .is_none_or(|t| t.as_array().unwrap().len() <= 2));
+        }
+    }
+
+    #[test]
+    fn mutations_break_one_rule() {
+        let spec = spec();
+        let schema = json!({ "$ref": "#/components/schemas/User" });
+        let value = json!({ "name": "ann", "age": 30, "tags": ["a"] });
+        let mut rng = Rng::new(1);
+        let generator = Generator {
+            spec: &spec,
+            rng: &mut rng,
+        };
+        let mut mutations = Vec::new();
+        generator.mutations(&schema, &value, "", 0, &mut mutations);
+        let descriptions: Vec<String> = mutations
+            .into_iter()
+            .map(|m| m.apply(&mut value.clone()))
+            .collect();
+
+        for expected in [
+            "wrong type at $",
+            "missing required property 'name' at $",
+            "missing required property 'age' at $",
+            "shorter than 1 characters at $.name",
+            "longer than 8 characters at $.name",
+            "below the minimum 0 at $.age",
+            "above the maximum 255 at $.age",
+            "wrong type at $.tags[0]",
+            "more than 2 items at $.tags",
+        ] {
+            assert!(
+                descriptions.iter().any(|d| d == expected),
+                "missing '{}' in {:?}",
+                expected,
+                descriptions
+            );
+        }
+    }
+
+    #[test]
+    fn encodes_pointers_and_params() {
+        assert_eq!(display_pointer("/user/tags/0"), "$.user.tags[0]");
+        assert_eq!(encode("a b/ü"), "a%20b%2F%C3%BC");
+        assert_eq!(int_value(u64::MAX as i128 + 1), None);
+    }
+}
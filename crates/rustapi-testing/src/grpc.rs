@@ -0,0 +1,125 @@
+//! In-process gRPC testing
+//!
+//! [`TestGrpc`] serves a RustAPI app and Tonic services the way
+//! `run_rustapi_and_grpc_multiplexed` does, but over in-memory connections
+//! instead of a TCP listener. [`TestGrpc::channel`] returns a Tonic
+//! [`Channel`] for generated clients, so gRPC services (and the middleware
+//! configured through [`GrpcServices`]) can be tested without binding ports.
+//!
+//! ```rust,ignore
+//! use rustapi_grpc::tonic::service::Routes;
+//!
+//! let grpc = TestGrpc::start(app(), Routes::new(GreeterServer::new(MyGreeter))).await;
+//! let mut client = GreeterClient::new(grpc.channel());
+//!
+//! let reply = client.say_hello(HelloRequest { name: "Ann".into() }).await.unwrap();
+//! assert_eq!(reply.into_inner().message, "Hello Ann");
+//! ```
+
+use hyper_util::rt::{TokioExecutor, TokioIo};
+use hyper_util::server::conn::auto;
+use rustapi_core::{RustApi, RustApiService};
+use rustapi_grpc::tonic::transport::{Channel, Endpoint, Uri};
+use rustapi_grpc::{GrpcServices, MultiplexService};
+use std::future::Future;
+use std::net::{Ipv4Addr, SocketAddr};
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+/// Peer address reported to the app for in-memory connections
+const PEER_ADDR: SocketAddr = SocketAddr::new(std::net::IpAddr::V4(Ipv4Addr::LOCALHOST), 0);
+
+/// Buffer size of each in-memory connection
+const CONNECTION_BUFFER: usize = 64 * 1024;
+
+/// An in-process HTTP + gRPC server reachable through Tonic channels
+pub struct TestGrpc {
+    http: RustApiService,
+    service: MultiplexService,
+    channel: Channel,
+}
+
+impl TestGrpc {
+    /// Serve `app` and the Tonic services `grpc` on in-memory connections
+    ///
+    /// The app is prepared like for `run` (its `on_start` hooks run);
+    /// requests are routed to gRPC or the app by `content-type`. Panics if
+    /// the app has duplicate routes or only a dump of it was requested.
+    pub async fn start(app: RustApi, grpc: impl Into<GrpcServices>) -> Self {
+        let http = app
+            .into_service("in-process")
+            .await
+            .unwrap_or_else(|err| panic!("{}", err))
+            .expect("a dump was requested instead of serving the app");
+        let service = MultiplexService::new(http.clone(), grpc).with_remote_addr(PEER_ADDR);
+        let channel = connect(service.clone()).await;
+        Self {
+            http,
+            service,
+            channel,
+        }
+    }
+
+    /// Serve only Tonic services
+    pub async fn services(grpc: impl Into<GrpcServices>) -> Self {
+        Self::start(RustApi::new(), grpc).await
+    }
+
+    /// A channel to the server, for generated Tonic clients
+    ///
+    /// Clones share one HTTP/2 connection.
+    pub fn channel(&self) -> Channel {
+        self.channel.clone()
+    }
+
+    /// A channel on a new connection
+    pub async fn connect(&self) -> Channel {
+        connect(self.service.clone()).await
+    }
+
+    /// Run the app's `on_shutdown` hooks
+    pub async fn shutdown(&self) {
+        self.http.shutdown().await;
+    }
+}
+
+impl std::fmt::Debug for TestGrpc {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("TestGrpc").finish_non_exhaustive()
+    }
+}
+
+async fn connect(service: MultiplexService) -> Channel {
+    Endpoint::from_static("http://rustapi.test")
+        .connect_with_connector(InMemoryConnector { service })
+        .await
+        .expect("in-memory connections cannot fail")
+}
+
+/// Tonic connector that opens an in-memory connection to the server
+#[derive(Clone)]
+struct InMemoryConnector {
+    service: MultiplexService,
+}
+
+impl tower_service::Service<Uri> for InMemoryConnector {
+    type Response = TokioIo<tokio::io::DuplexStream>;
+    type Error = std::io::Error;
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>> + Send>>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, _uri: Uri) -> Self::Future {
+        let (client_io, server_io) = tokio::io::duplex(CONNECTION_BUFFER);
+        let service = self.service.clone();
+        tokio::spawn(async move {
+            // Errors end only this connection, like on a real listener
+            let _ = auto::Builder::new(TokioExecutor::new())
+                .serve_connection_with_upgrades(TokioIo::new(server_io), service)
+                .await;
+        });
+        Box::pin(async move { Ok(TokioIo::new(client_io)) })
+    }
+}
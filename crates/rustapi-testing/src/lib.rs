@@ -5,11 +5,28 @@
 //! The `MockServer` allows you to mock HTTP services for integration testing.
 
 pub mod client;
+pub mod contract;
+pub mod cookies;
 pub mod expectation;
+pub mod fuzz;
+#[cfg(feature = "grpc")]
+pub mod grpc;
+pub mod load;
 pub mod matcher;
 pub mod server;
+pub mod snapshot;
+pub mod sse;
+#[cfg(feature = "ws")]
+pub mod ws;
 
 pub use client::{TestClient, TestRequest, TestResponse};
 pub use expectation::{Expectation, MockResponse, Times};
+pub use fuzz::{FuzzFailure, FuzzReport, SpecFuzzer};
+#[cfg(feature = "grpc")]
+pub use grpc::TestGrpc;
+pub use load::{LoadReport, LoadTarget, LoadTest};
 pub use matcher::RequestMatcher;
 pub use server::{MockServer, RecordedRequest};
+pub use sse::TestEventStream;
+#[cfg(feature = "ws")]
+pub use ws::TestWebSocket;
@@ -0,0 +1,538 @@
+//! Load testing harness
+//!
+//! [`LoadTest`] sends requests from a number of concurrent workers for a
+//! fixed duration (or up to a number of requests) and reports latency
+//! percentiles, throughput and the error rate. The target is either an
+//! in-process app, through a [`TestClient`], or a running server by URL, so
+//! the same scenario can run under `cargo test --release` and against a
+//! deployed benchmark server.
+//!
+//! ```rust,ignore
+//! let report = LoadTest::new(TestClient::new(app()))
+//!     .request(TestRequest::get("/hello"))
+//!     .concurrency(32)
+//!     .duration(Duration::from_secs(5))
+//!     .run()
+//!     .await;
+//!
+//! println!("{}", report);
+//! report
+//!     .assert_error_rate_at_most(0.001)
+//!     .assert_p99_below(Duration::from_millis(20));
+//! ```
+//!
+//! A response counts as an error unless its status is 2xx or 3xx; failed
+//! connections count too. Only plain `http://` URLs are supported. Latencies
+//! of debug builds say little, so run load tests with `--release`.
+
+use crate::client::{TestClient, TestRequest};
+use bytes::Bytes;
+use http::{header, HeaderValue, StatusCode, Uri};
+use http_body_util::{BodyExt, Full};
+use hyper::client::conn::http1::SendRequest;
+use hyper_util::rt::TokioIo;
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// Default number of concurrent workers
+pub const DEFAULT_CONCURRENCY: usize = 10;
+
+/// Default measuring duration
+pub const DEFAULT_DURATION: Duration = Duration::from_secs(5);
+
+/// What a load test sends requests to
+#[derive(Clone)]
+pub enum LoadTarget {
+    /// An in-process app
+    Client(TestClient),
+    /// A running server, e.g. `http://127.0.0.1:8080`
+    Url(String),
+}
+
+impl From<TestClient> for LoadTarget {
+    fn from(client: TestClient) -> Self {
+        Self::Client(client)
+    }
+}
+
+impl From<&str> for LoadTarget {
+    fn from(url: &str) -> Self {
+        Self::Url(url.to_string())
+    }
+}
+
+impl From<String> for LoadTarget {
+    fn from(url: String) -> Self {
+        Self::Url(url)
+    }
+}
+
+/// A load test scenario
+///
+/// Requests are sent round-robin from the list given with
+/// [`request`](Self::request); without one, `GET /` is sent.
+pub struct LoadTest {
+    target: LoadTarget,
+    requests: Vec<TestRequest>,
+    concurrency: usize,
+    duration: Duration,
+    max_requests: Option<u64>,
+    warmup: Duration,
+}
+
+impl LoadTest {
+    /// Create a load test against an in-process app or a URL
+    pub fn new(target: impl Into<LoadTarget>) -> Self {
+        Self {
+            target: target.into(),
+            requests: Vec::new(),
+            concurrency: DEFAULT_CONCURRENCY,
+            duration: DEFAULT_DURATION,
+            max_requests: None,
+            warmup: Duration::ZERO,
+        }
+    }
+
+    /// Add a request to the rotation
+    pub fn request(mut self, request: TestRequest) -> Self {
+        self.requests.push(request);
+        self
+    }
+
+    /// Set the number of concurrent workers (at least 1)
+    pub fn concurrency(mut self, workers: usize) -> Self {
+        self.concurrency = workers.max(1);
+        self
+    }
+
+    /// Set how long to measure
+    pub fn duration(mut self, duration: Duration) -> Self {
+        self.duration = duration;
+        self
+    }
+
+    /// Stop after `n` requests, even if the duration has not passed
+    pub fn max_requests(mut self, n: u64) -> Self {
+        self.max_requests = Some(n);
+        self
+    }
+
+    /// Send requests for `warmup` before measuring, discarding the results
+    pub fn warmup(mut self, warmup: Duration) -> Self {
+        self.warmup = warmup;
+        self
+    }
+
+    /// Run the scenario and report the measured phase
+    ///
+    /// # Panics
+    ///
+    /// Panics if the target URL is not a valid `http://` URL.
+    pub async fn run(self) -> LoadReport {
+        let target = Arc::new(Target::new(self.target));
+        let requests: Arc<[TestRequest]> = if self.requests.is_empty() {
+            vec![TestRequest::get("/")].into()
+        } else {
+            self.requests.into()
+        };
+
+        if !self.warmup.is_zero() {
+            run_phase(&target, &requests, self.concurrency, self.warmup, None).await;
+        }
+        run_phase(
+            &target,
+            &requests,
+            self.concurrency,
+            self.duration,
+            self.max_requests,
+        )
+        .await
+    }
+}
+
+/// Run workers until the duration has passed or `max_requests` were sent
+async fn run_phase(
+    target: &Arc<Target>,
+    requests: &Arc<[TestRequest]>,
+    concurrency: usize,
+    duration: Duration,
+    max_requests: Option<u64>,
+) -> LoadReport {
+    let issued = Arc::new(AtomicU64::new(0));
+    let start = Instant::now();
+    let deadline = start + duration;
+
+    let workers: Vec<_> = (0..concurrency)
+        .map(|_| {
+            let (target, requests, issued) = (target.clone(), requests.clone(), issued.clone());
+            tokio::spawn(async move {
+                let mut samples = Samples::default();
+                let mut connection = None;
+                while Instant::now() < deadline {
+                    let n = issued.fetch_add(1, Ordering::Relaxed);
+                    if max_requests.is_some_and(|max| n >= max) {
+                        break;
+                    }
+                    let request = requests[(n % requests.len() as u64) as usize].clone();
+                    let sent = Instant::now();
+                    let status = target.send(request, &mut connection).await;
+                    samples.record(sent.elapsed(), status);
+                }
+                samples
+            })
+        })
+        .collect();
+
+    let mut samples = Samples::default();
+    for worker in workers {
+        samples.merge(worker.await.expect("load test worker panicked"));
+    }
+    samples.into_report(start.elapsed())
+}
+
+/// Resolved target of a running test
+enum Target {
+    Client(TestClient),
+    Url {
+        authority: String,
+        base_path: String,
+    },
+}
+
+impl Target {
+    fn new(target: LoadTarget) -> Self {
+        match target {
+            LoadTarget::Client(client) => Self::Client(client),
+            LoadTarget::Url(url) => {
+                let uri: Uri = url
+                    .parse()
+                    .unwrap_or_else(|e| panic!("Invalid load test URL '{}': {}", url, e));
+                assert!(
+                    uri.scheme_str() == Some("http"),
+                    "Load test URLs must use http://, got '{}'",
+                    url
+                );
+                let authority = uri
+                    .authority()
+                    .unwrap_or_else(|| panic!("Load test URL '{}' has no host", url));
+                let authority = match authority.port() {
+                    Some(_) => authority.to_string(),
+                    None => format!("{}:80", authority),
+                };
+                Self::Url {
+                    authority,
+                    base_path: uri.path().trim_end_matches('/').to_string(),
+                }
+            }
+        }
+    }
+
+    /// Send one request; `None` if it failed without a response
+    async fn send(
+        &self,
+        request: TestRequest,
+        connection: &mut Option<SendRequest<Full<Bytes>>>,
+    ) -> Option<StatusCode> {
+        match self {
+            Self::Client(client) => Some(client.request(request).await.status()),
+            Self::Url {
+                authority,
+                base_path,
+            } => {
+                let result = send_http(authority, base_path, request, connection).await;
+                if result.is_none() {
+                    // Reconnect for the next request
+                    *connection = None;
+                }
+                result
+            }
+        }
+    }
+}
+
+/// Send a request over a kept-alive HTTP/1.1 connection, reading the whole
+/// response body
+async fn send_http(
+    authority: &str,
+    base_path: &str,
+    request: TestRequest,
+    connection: &mut Option<SendRequest<Full<Bytes>>>,
+) -> Option<StatusCode> {
+    if connection.is_none() {
+        let stream = tokio::net::TcpStream::connect(authority).await.ok()?;
+        let (sender, conn) = hyper::client::conn::http1::handshake(TokioIo::new(stream))
+            .await
+            .ok()?;
+        tokio::spawn(async move {
+            let _ = conn.await;
+        });
+        *connection = Some(sender);
+    }
+    let sender = connection.as_mut()?;
+    sender.ready().await.ok()?;
+
+    let mut builder = http::Request::builder()
+        .method(request.method)
+        .uri(format!("{}{}", base_path, request.path));
+    if let Some(headers) = builder.headers_mut() {
+        *headers = request.headers;
+        if !headers.contains_key(header::HOST) {
+            headers.insert(header::HOST, HeaderValue::from_str(authority).ok()?);
+        }
+    }
+    let http_request = builder
+        .body(Full::new(request.body.unwrap_or_default()))
+        .ok()?;
+
+    let response = sender.send_request(http_request).await.ok()?;
+    let status = response.status();
+    response.into_body().collect().await.ok()?;
+    Some(status)
+}
+
+/// Results collected by a worker
+#[derive(Default)]
+struct Samples {
+    latencies: Vec<Duration>,
+    statuses: BTreeMap<u16, u64>,
+    failed: u64,
+}
+
+impl Samples {
+    fn record(&mut self, latency: Duration, status: Option<StatusCode>) {
+        self.latencies.push(latency);
+        match status {
+            Some(status) => *self.statuses.entry(status.as_u16()).or_default() += 1,
+            None => self.failed += 1,
+        }
+    }
+
+    fn merge(&mut self, other: Samples) {
+        self.latencies.extend(other.latencies);
+        for (status, count) in other.statuses {
+            *self.statuses.entry(status).or_default() += count;
+        }
+        self.failed += other.failed;
+    }
+
+    fn into_report(mut self, elapsed: Duration) -> LoadReport {
+        self.latencies.sort_unstable();
+        LoadReport {
+            latencies: self.latencies,
+            statuses: self.statuses,
+            failed: self.failed,
+            elapsed,
+        }
+    }
+}
+
+/// Results of a load test
+#[derive(Debug, Clone)]
+pub struct LoadReport {
+    /// Sorted latencies of all requests
+    latencies: Vec<Duration>,
+    statuses: BTreeMap<u16, u64>,
+    failed: u64,
+    elapsed: Duration,
+}
+
+impl LoadReport {
+    /// Number of requests sent
+    pub fn requests(&self) -> u64 {
+        self.latencies.len() as u64
+    }
+
+    /// Number of requests without a 2xx or 3xx response
+    pub fn errors(&self) -> u64 {
+        let bad_status: u64 = self
+            .statuses
+            .iter()
+            .filter(|(status, _)| !(200..400).contains(*status))
+            .map(|(_, count)| count)
+            .sum();
+        bad_status + self.failed
+    }
+
+    /// Number of requests that failed without a response
+    pub fn connection_errors(&self) -> u64 {
+        self.failed
+    }
+
+    /// Share of requests that were errors, from 0.0 to 1.0
+    pub fn error_rate(&self) -> f64 {
+        if self.latencies.is_empty() {
+            return 0.0;
+        }
+        self.errors() as f64 / self.requests() as f64
+    }
+
+    /// Number of responses per status code
+    pub fn status_counts(&self) -> &BTreeMap<u16, u64> {
+        &self.statuses
+    }
+
+    /// Wall-clock time of the measured phase
+    pub fn elapsed(&self) -> Duration {
+        self.elapsed
+    }
+
+    /// Requests per second
+    pub fn rps(&self) -> f64 {
+        let seconds = self.elapsed.as_secs_f64();
+        if seconds == 0.0 {
+            return 0.0;
+        }
+        self.requests() as f64 / seconds
+    }
+
+    /// Latency at percentile `p` (0–100), by the nearest-rank method
+    pub fn percentile(&self, p: f64) -> Duration {
+        if self.latencies.is_empty() {
+            return Duration::ZERO;
+        }
+        let rank = (p.clamp(0.0, 100.0) / 100.0 * self.latencies.len() as f64).ceil() as usize;
+        self.latencies[rank.clamp(1, self.latencies.len()) - 1]
+    }
+
+    /// Median latency
+    pub fn p50(&self) -> Duration {
+        self.percentile(50.0)
+    }
+
+    /// 95th percentile latency
+    pub fn p95(&self) -> Duration {
+        self.percentile(95.0)
+    }
+
+    /// 99th percentile latency
+    pub fn p99(&self) -> Duration {
+        self.percentile(99.0)
+    }
+
+    /// Slowest request
+    pub fn max(&self) -> Duration {
+        self.latencies.last().copied().unwrap_or_default()
+    }
+
+    /// Mean latency
+    pub fn mean(&self) -> Duration {
+        if self.latencies.is_empty() {
+            return Duration::ZERO;
+        }
+        self.latencies.iter().sum::<Duration>() / self.latencies.len() as u32
+    }
+
+    /// Assert that at most `max_rate` (0.0 to 1.0) of the requests failed
+    pub fn assert_error_rate_at_most(&self, max_rate: f64) -> &Self {
+        assert!(
+            self.error_rate() <= max_rate,
+            "Error rate {:.2}% exceeds {:.2}% ({} of {} requests, statuses {:?}, {} connection errors)",
+            self.error_rate() * 100.0,
+            max_rate * 100.0,
+            self.errors(),
+            self.requests(),
+            self.statuses,
+            self.failed
+        );
+        self
+    }
+
+    /// Assert that the latency at percentile `p` is at most `max`
+    pub fn assert_percentile_below(&self, p: f64, max: Duration) -> &Self {
+        let actual = self.percentile(p);
+        assert!(
+            actual <= max,
+            "p{} latency {:?} exceeds {:?}",
+            p,
+            actual,
+            max
+        );
+        self
+    }
+
+    /// Assert that the 95th percentile latency is at most `max`
+    pub fn assert_p95_below(&self, max: Duration) -> &Self {
+        self.assert_percentile_below(95.0, max)
+    }
+
+    /// Assert that the 99th percentile latency is at most `max`
+    pub fn assert_p99_below(&self, max: Duration) -> &Self {
+        self.assert_percentile_below(99.0, max)
+    }
+
+    /// Assert a throughput of at least `min` requests per second
+    pub fn assert_rps_above(&self, min: f64) -> &Self {
+        assert!(
+            self.rps() >= min,
+            "Throughput {:.1} req/s is below {:.1} req/s",
+            self.rps(),
+            min
+        );
+        self
+    }
+}
+
+impl std::fmt::Display for LoadReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(
+            f,
+            "requests: {} in {:.2?} ({:.1} req/s)",
+            self.requests(),
+            self.elapsed,
+            self.rps()
+        )?;
+        writeln!(
+            f,
+            "latency:  p50 {:.2?}  p95 {:.2?}  p99 {:.2?}  max {:.2?}  mean {:.2?}",
+            self.p50(),
+            self.p95(),
+            self.p99(),
+            self.max(),
+            self.mean()
+        )?;
+        write!(
+            f,
+            "errors:   {} ({:.2}%)",
+            self.errors(),
+            self.error_rate() * 100.0
+        )?;
+        for (status, count) in &self.statuses {
+            write!(f, "  [{}] {}", status, count)?;
+        }
+        if self.failed > 0 {
+            write!(f, "  [connection] {}", self.failed)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn computes_percentiles_and_error_rate() {
+        let mut samples = Samples::default();
+        for ms in 1..=100 {
+            let status = match ms {
+                1..=97 => Some(StatusCode::OK),
+                98 | 99 => Some(StatusCode::SERVICE_UNAVAILABLE),
+                _ => None,
+            };
+            samples.record(Duration::from_millis(101 - ms), status);
+        }
+        let report = samples.into_report(Duration::from_secs(2));
+
+        assert_eq!(report.requests(), 100);
+        assert_eq!(report.p50(), Duration::from_millis(50));
+        assert_eq!(report.p99(), Duration::from_millis(99));
+        assert_eq!(report.max(), Duration::from_millis(100));
+        assert_eq!(report.percentile(0.0), Duration::from_millis(1));
+        assert_eq!(report.errors(), 3);
+        assert_eq!(report.connection_errors(), 1);
+        assert_eq!(report.rps(), 50.0);
+        report.assert_error_rate_at_most(0.03);
+    }
+}
@@ -1,14 +1,39 @@
 use http::{HeaderMap, Method};
+use regex::Regex;
 use serde_json::Value;
 
 /// Matcher for HTTP requests
+///
+/// All criteria must hold. Besides exact comparisons, requests can be matched
+/// by regex (path, headers), query parameters, partial JSON bodies, JSONPath
+/// expressions, and combinations of other matchers:
+///
+/// ```rust,ignore
+/// let matcher = RequestMatcher::new()
+///     .method(Method::POST)
+///     .path_regex(r"^/users/\d+/orders$")
+///     .header_regex("authorization", "^Bearer ")
+///     .query_param("dry_run", "false")
+///     .body_json_partial(json!({ "currency": "EUR" }))
+///     .body_json_path("$.items[*].sku", json!("A-1"))
+///     .and(!RequestMatcher::new().header("x-test", "skip"));
+/// ```
 #[derive(Debug, Clone, Default)]
 pub struct RequestMatcher {
     pub(crate) method: Option<Method>,
     pub(crate) path: Option<String>,
+    pub(crate) path_regex: Option<Regex>,
     pub(crate) headers: Vec<(String, String)>,
+    pub(crate) header_regexes: Vec<(String, Regex)>,
+    pub(crate) query: Vec<(String, String)>,
     pub(crate) body_json: Option<Value>,
+    pub(crate) body_json_partial: Option<Value>,
+    pub(crate) body_json_paths: Vec<(String, Option<Value>)>,
     pub(crate) body_string: Option<String>,
+    pub(crate) body_contains: Option<String>,
+    pub(crate) all: Vec<RequestMatcher>,
+    pub(crate) any: Vec<Vec<RequestMatcher>>,
+    pub(crate) none: Vec<RequestMatcher>,
 }
 
 impl RequestMatcher {
@@ -17,6 +42,28 @@ impl RequestMatcher {
         Self::default()
     }
 
+    /// Match when every matcher matches
+    pub fn all(matchers: impl IntoIterator<Item = RequestMatcher>) -> Self {
+        Self {
+            all: matchers.into_iter().collect(),
+            ..Self::default()
+        }
+    }
+
+    /// Match when at least one matcher matches
+    pub fn any(matchers: impl IntoIterator<Item = RequestMatcher>) -> Self {
+        Self {
+            any: vec![matchers.into_iter().collect()],
+            ..Self::default()
+        }
+    }
+
+    /// Additionally require `other` to match
+    pub fn and(mut self, other: RequestMatcher) -> Self {
+        self.all.push(other);
+        self
+    }
+
     /// Match a specific HTTP method
     pub fn method(mut self, method: Method) -> Self {
         self.method = Some(method);
@@ -29,12 +76,49 @@ impl RequestMatcher {
         self
     }
 
+    /// Match paths against a regular expression
+    ///
+    /// # Panics
+    ///
+    /// Panics if `pattern` is not a valid regex.
+    pub fn path_regex(mut self, pattern: &str) -> Self {
+        self.path_regex = Some(compile(pattern));
+        self
+    }
+
     /// Match a specific header
     pub fn header(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
         self.headers.push((key.into(), value.into()));
         self
     }
 
+    /// Match a header whose value (any of them, if repeated) matches `pattern`
+    ///
+    /// # Panics
+    ///
+    /// Panics if `pattern` is not a valid regex.
+    pub fn header_regex(mut self, key: impl Into<String>, pattern: &str) -> Self {
+        self.header_regexes.push((key.into(), compile(pattern)));
+        self
+    }
+
+    /// Match a query parameter value (other parameters are allowed)
+    pub fn query_param(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.query.push((key.into(), value.into()));
+        self
+    }
+
+    /// Match a set of query parameters (other parameters are allowed)
+    pub fn query_params<K, V>(mut self, params: impl IntoIterator<Item = (K, V)>) -> Self
+    where
+        K: Into<String>,
+        V: Into<String>,
+    {
+        self.query
+            .extend(params.into_iter().map(|(k, v)| (k.into(), v.into())));
+        self
+    }
+
     /// Match exact JSON body
     pub fn body_json(mut self, body: impl serde::Serialize) -> Self {
         self.body_json =
@@ -42,14 +126,56 @@ impl RequestMatcher {
         self
     }
 
+    /// Match a JSON body containing `subset`
+    ///
+    /// Objects may have extra members; arrays must have the same length,
+    /// with each element containing the expected one.
+    pub fn body_json_partial(mut self, subset: impl serde::Serialize) -> Self {
+        self.body_json_partial =
+            Some(serde_json::to_value(subset).expect("Failed to serialize body matcher"));
+        self
+    }
+
+    /// Match a JSON body where a value selected by a JSONPath expression
+    /// equals `expected`
+    ///
+    /// Supports `$`, `.name`, `['name']`, `[index]` and `[*]`/`.*`
+    /// wildcards; with wildcards, any selected value may match.
+    pub fn body_json_path(
+        mut self,
+        path: impl Into<String>,
+        expected: impl serde::Serialize,
+    ) -> Self {
+        let expected = serde_json::to_value(expected).expect("Failed to serialize body matcher");
+        self.body_json_paths.push((path.into(), Some(expected)));
+        self
+    }
+
+    /// Match a JSON body where a JSONPath expression selects something
+    pub fn body_json_path_exists(mut self, path: impl Into<String>) -> Self {
+        self.body_json_paths.push((path.into(), None));
+        self
+    }
+
     /// Match exact string body
     pub fn body_string(mut self, body: impl Into<String>) -> Self {
         self.body_string = Some(body.into());
         self
     }
 
+    /// Match a body containing `text`
+    pub fn body_contains(mut self, text: impl Into<String>) -> Self {
+        self.body_contains = Some(text.into());
+        self
+    }
+
     /// Check if the matcher matches a request
+    ///
+    /// `path` may include a `?query`, which is used by query parameter
+    /// matching.
     pub fn matches(&self, method: &Method, path: &str, headers: &HeaderMap, body: &[u8]) -> bool {
+        let (path_only, query) = path.split_once('?').unwrap_or((path, ""));
+
         if let Some(m) = &self.method {
             if m != method {
                 return false;
@@ -57,7 +183,13 @@ impl RequestMatcher {
         }
 
         if let Some(p) = &self.path {
-            if p != path {
+            if p != path_only {
+                return false;
+            }
+        }
+
+        if let Some(re) = &self.path_regex {
+            if !re.is_match(path_only) {
                 return false;
             }
         }
@@ -73,13 +205,53 @@ impl RequestMatcher {
             }
         }
 
-        if let Some(expected_json) = &self.body_json {
-            if let Ok(actual_json) = serde_json::from_slice::<Value>(body) {
-                if &actual_json != expected_json {
+        for (k, re) in &self.header_regexes {
+            let matched = headers
+                .get_all(k.as_str())
+                .iter()
+                .any(|v| v.to_str().is_ok_and(|v| re.is_match(v)));
+            if !matched {
+                return false;
+            }
+        }
+
+        if !self.query.is_empty() {
+            let actual = parse_query(query);
+            let all_present = self
+                .query
+                .iter()
+                .all(|(k, v)| actual.iter().any(|(ak, av)| ak == k && av == v));
+            if !all_present {
+                return false;
+            }
+        }
+
+        let needs_json = self.body_json.is_some()
+            || self.body_json_partial.is_some()
+            || !self.body_json_paths.is_empty();
+        if needs_json {
+            let Ok(actual_json) = serde_json::from_slice::<Value>(body) else {
+                return false;
+            };
+            if self.body_json.as_ref().is_some_and(|e| e != &actual_json) {
+                return false;
+            }
+            if let Some(subset) = &self.body_json_partial {
+                if !json_contains(&actual_json, subset) {
+                    return false;
+                }
+            }
+            for (path, expected) in &self.body_json_paths {
+                let Some(selected) = json_path(&actual_json, path) else {
+                    return false;
+                };
+                let matched = match expected {
+                    Some(expected) => selected.contains(&expected),
+                    None => !selected.is_empty(),
+                };
+                if !matched {
                     return false;
                 }
-            } else {
-                return false;
             }
         }
 
@@ -93,7 +265,258 @@ impl RequestMatcher {
             }
         }
 
-        true
+        if let Some(text) = &self.body_contains {
+            if !String::from_utf8_lossy(body).contains(text.as_str()) {
+                return false;
+            }
+        }
+
+        self.all
+            .iter()
+            .all(|m| m.matches(method, path, headers, body))
+            && self
+                .any
+                .iter()
+                .all(|group| group.iter().any(|m| m.matches(method, path, headers, body)))
+            && !self
+                .none
+                .iter()
+                .any(|m| m.matches(method, path, headers, body))
+    }
+}
+
+/// `!matcher` matches when `matcher` does not
+impl std::ops::Not for RequestMatcher {
+    type Output = RequestMatcher;
+
+    fn not(self) -> Self::Output {
+        Self {
+            none: vec![self],
+            ..Self::default()
+        }
+    }
+}
+
+fn compile(pattern: &str) -> Regex {
+    Regex::new(pattern).unwrap_or_else(|e| panic!("Invalid regex {:?}: {}", pattern, e))
+}
+
+/// Decoded `key=value` pairs of a query string
+fn parse_query(query: &str) -> Vec<(String, String)> {
+    query
+        .split('&')
+        .filter(|pair| !pair.is_empty())
+        .map(|pair| {
+            let (k, v) = pair.split_once('=').unwrap_or((pair, ""));
+            (percent_decode(k), percent_decode(v))
+        })
+        .collect()
+}
+
+fn percent_decode(s: &str) -> String {
+    let bytes = s.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'+' => out.push(b' '),
+            b'%' if i + 2 < bytes.len() => {
+                let hex = std::str::from_utf8(&bytes[i + 1..i + 3]).unwrap_or("");
+                match u8::from_str_radix(hex, 16) {
+                    Ok(byte) => {
+                        out.push(byte);
+                        i += 2;
+                    }
+                    Err(_) => out.push(b'%'),
+                }
+            }
+            b => out.push(b),
+        }
+        i += 1;
+    }
+    String::from_utf8_lossy(&out).into_owned()
+}
+
+/// Whether `actual` contains everything in `expected`
+fn json_contains(actual: &Value, expected: &Value) -> bool {
+    match (actual, expected) {
+        (Value::Object(actual), Value::Object(expected)) => expected
+            .iter()
+            .all(|(k, v)| actual.get(k).is_some_and(|a| json_contains(a, v))),
+        (Value::Array(actual), Value::Array(expected)) => {
+            actual.len() == expected.len()
+                && actual
+                    .iter()
+                    .zip(expected)
+                    .all(|(a, e)| json_contains(a, e))
+        }
+        _ => actual == expected,
+    }
+}
+
+/// Values selected by a JSONPath expression; `None` if it is malformed
+fn json_path<'a>(root: &'a Value, path: &str) -> Option<Vec<&'a Value>> {
+    let rest = path.trim().strip_prefix('$')?;
+    let mut current = vec![root];
+    let mut chars = rest.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        let segment = match c {
+            '.' => {
+                let mut name = String::new();
+                while let Some(&c) = chars.peek() {
+                    if c == '.' || c == '[' {
+                        break;
+                    }
+                    name.push(c);
+                    chars.next();
+                }
+                if name.is_empty() {
+                    return None;
+                }
+                name
+            }
+            '[' => {
+                let mut inner = String::new();
+                loop {
+                    match chars.next()? {
+                        ']' => break,
+                        c => inner.push(c),
+                    }
+                }
+                let inner = inner.trim();
+                match inner
+                    .strip_prefix('\'')
+                    .and_then(|s| s.strip_suffix('\''))
+                    .or_else(|| inner.strip_prefix('"').and_then(|s| s.strip_suffix('"')))
+                {
+                    Some(name) => format!("'{}", name),
+                    None => inner.to_string(),
+                }
+            }
+            _ => return None,
+        };
+
+        current = current
+            .into_iter()
+            .flat_map(|value| select(value, &segment))
+            .collect();
+    }
+    Some(current)
+}
+
+/// Children of `value` selected by one path segment (`'name` is a quoted key)
+fn select<'a>(value: &'a Value, segment: &str) -> Vec<&'a Value> {
+    if segment == "*" {
+        return match value {
+            Value::Object(map) => map.values().collect(),
+            Value::Array(items) => items.iter().collect(),
+            _ => Vec::new(),
+        };
+    }
+    let key = segment.strip_prefix('\'').unwrap_or(segment);
+    match value {
+        Value::Object(map) => map.get(key).into_iter().collect(),
+        Value::Array(items) if segment == key => key
+            .parse::<usize>()
+            .ok()
+            .and_then(|i| items.get(i))
+            .into_iter()
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn body(value: Value) -> Vec<u8> {
+        serde_json::to_vec(&value).unwrap()
+    }
+
+    #[test]
+    fn regex_and_query_matching() {
+        let matcher = RequestMatcher::new()
+            .path_regex(r"^/users/\d+$")
+            .header_regex("authorization", "^Bearer ")
+            .query_params([("q", "a b"), ("page", "2")]);
+        let mut headers = HeaderMap::new();
+        headers.insert("authorization", "Bearer abc".parse().unwrap());
+
+        assert!(matcher.matches(&Method::GET, "/users/7?page=2&q=a+b&x=1", &headers, b""));
+        assert!(matcher.matches(&Method::GET, "/users/7?q=a%20b&page=2", &headers, b""));
+        assert!(!matcher.matches(&Method::GET, "/users/7?page=2", &headers, b""));
+        assert!(!matcher.matches(&Method::GET, "/users/me?page=2&q=a+b", &headers, b""));
+        assert!(!matcher.matches(
+            &Method::GET,
+            "/users/7?page=2&q=a+b",
+            &HeaderMap::new(),
+            b""
+        ));
+    }
+
+    #[test]
+    fn partial_json_and_json_path() {
+        let order = body(json!({
+            "id": 1,
+            "customer": { "name": "Ada", "tier": "gold" },
+            "items": [{ "sku": "A-1", "qty": 2 }, { "sku": "B-2", "qty": 1 }]
+        }));
+        let headers = HeaderMap::new();
+        let check = |m: RequestMatcher| m.matches(&Method::POST, "/orders", &headers, &order);
+
+        assert!(check(
+            RequestMatcher::new().body_json_partial(json!({ "customer": { "tier": "gold" } }))
+        ));
+        assert!(!check(
+            RequestMatcher::new().body_json_partial(json!({ "customer": { "tier": "silver" } }))
+        ));
+        assert!(!check(
+            RequestMatcher::new().body_json_partial(json!({ "items": [{ "sku": "A-1" }] }))
+        ));
+
+        assert!(check(
+            RequestMatcher::new().body_json_path("$.customer.name", "Ada")
+        ));
+        assert!(check(
+            RequestMatcher::new().body_json_path("$['items'][1].sku", "B-2")
+        ));
+        assert!(check(
+            RequestMatcher::new().body_json_path("$.items[*].qty", 1)
+        ));
+        assert!(!check(
+            RequestMatcher::new().body_json_path("$.items[*].qty", 3)
+        ));
+        assert!(check(
+            RequestMatcher::new().body_json_path_exists("$.customer.*")
+        ));
+        assert!(!check(
+            RequestMatcher::new().body_json_path_exists("$.missing")
+        ));
+        assert!(!check(RequestMatcher::new().body_json_path_exists("items")));
+        assert!(check(RequestMatcher::new().body_contains("\"A-1\"")));
+    }
+
+    #[test]
+    fn combinators() {
+        let headers = HeaderMap::new();
+        let get = RequestMatcher::new().method(Method::GET);
+        let users = RequestMatcher::new().path("/users");
+
+        let either = RequestMatcher::any([get.clone(), users.clone()]);
+        assert!(either.matches(&Method::GET, "/other", &headers, b""));
+        assert!(either.matches(&Method::POST, "/users", &headers, b""));
+        assert!(!either.matches(&Method::POST, "/other", &headers, b""));
+
+        let both = RequestMatcher::all([get.clone(), users.clone()]);
+        assert!(both.matches(&Method::GET, "/users", &headers, b""));
+        assert!(!both.matches(&Method::GET, "/other", &headers, b""));
+
+        let not_get = users.and(!get);
+        assert!(not_get.matches(&Method::DELETE, "/users", &headers, b""));
+        assert!(!not_get.matches(&Method::GET, "/users", &headers, b""));
     }
 }
 
@@ -220,8 +220,12 @@ async fn handle_request(
         .enumerate()
         .rev()
         .find(|(_, exp)| {
+            let path = parts
+                .uri
+                .path_and_query()
+                .map_or(parts.uri.path(), |pq| pq.as_str());
             exp.matcher
-                .matches(&parts.method, parts.uri.path(), &parts.headers, &body_bytes)
+                .matches(&parts.method, path, &parts.headers, &body_bytes)
         })
         .map(|(i, _)| i);
 
@@ -0,0 +1,319 @@
+//! Snapshot assertions for test responses
+//!
+//! [`TestResponse::assert_json_snapshot`](crate::TestResponse::assert_json_snapshot)
+//! and [`TestResponse::assert_body_snapshot`](crate::TestResponse::assert_body_snapshot)
+//! compare a response body with a file stored next to the tests, so
+//! regressions show up as a readable line diff.
+//!
+//! - Snapshots live in `tests/snapshots/<name>.snap` of the crate under test.
+//! - A missing snapshot is written on the first run and the assertion passes.
+//! - Set `RUSTAPI_UPDATE_SNAPSHOTS=1` to overwrite snapshots that differ
+//!   after an intended change.
+//! - UUIDs and RFC 3339 timestamps are replaced with `[uuid]` and
+//!   `[timestamp]` before comparing; JSON values at given pointers can be
+//!   redacted too.
+//!
+//! ```rust,ignore
+//! client.get("/users/1").await.assert_json_snapshot("get_user");
+//! client
+//!     .post_json("/sessions", &login)
+//!     .await
+//!     .assert_json_snapshot_redacted("login", &["/token", "/user/last_login_ip"]);
+//! ```
+
+use serde_json::Value;
+use std::path::PathBuf;
+
+/// Environment variable that makes snapshot assertions overwrite snapshots
+pub const UPDATE_SNAPSHOTS_ENV: &str = "RUSTAPI_UPDATE_SNAPSHOTS";
+
+/// Compare `actual` with the stored snapshot `name`, panicking with a diff
+pub(crate) fn assert_snapshot(name: &str, actual: &str) {
+    assert!(
+        !name.is_empty()
+            && name
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.')),
+        "Invalid snapshot name {:?}: use letters, digits, '-', '_' and '.'",
+        name
+    );
+
+    let path = snapshot_dir().join(format!("{}.snap", name));
+    let mut actual = actual.to_string();
+    if !actual.ends_with('\n') {
+        actual.push('\n');
+    }
+
+    let update = std::env::var(UPDATE_SNAPSHOTS_ENV)
+        .is_ok_and(|value| matches!(value.as_str(), "1" | "true" | "yes"));
+    let expected = match std::fs::read_to_string(&path) {
+        Ok(expected) if !update => expected.replace("\r\n", "\n"),
+        _ => {
+            write_snapshot(&path, &actual);
+            return;
+        }
+    };
+
+    if expected != actual {
+        panic!(
+            "Snapshot '{}' does not match ({})\n{}\nRun with {}=1 to update it.",
+            name,
+            path.display(),
+            line_diff(&expected, &actual),
+            UPDATE_SNAPSHOTS_ENV
+        );
+    }
+}
+
+fn write_snapshot(path: &std::path::Path, content: &str) {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)
+            .unwrap_or_else(|e| panic!("Failed to create {}: {}", parent.display(), e));
+    }
+    std::fs::write(path, content)
+        .unwrap_or_else(|e| panic!("Failed to write snapshot {}: {}", path.display(), e));
+}
+
+/// `tests/snapshots` of the crate being tested
+fn snapshot_dir() -> PathBuf {
+    std::env::var_os("CARGO_MANIFEST_DIR")
+        .map(PathBuf::from)
+        .unwrap_or_default()
+        .join("tests")
+        .join("snapshots")
+}
+
+/// Pretty JSON with default and pointer redactions applied
+pub(crate) fn redact_json(mut value: Value, pointers: &[&str]) -> String {
+    for pointer in pointers {
+        redact_pointer(&mut value, pointer);
+    }
+    redact_strings(&mut value);
+    serde_json::to_string_pretty(&sort_keys(value)).unwrap_or_default()
+}
+
+/// Sort object keys, so snapshots do not depend on serde_json's
+/// `preserve_order` feature
+fn sort_keys(value: Value) -> Value {
+    match value {
+        Value::Object(map) => {
+            let mut entries: Vec<(String, Value)> = map.into_iter().collect();
+            entries.sort_by(|a, b| a.0.cmp(&b.0));
+            Value::Object(
+                entries
+                    .into_iter()
+                    .map(|(k, v)| (k, sort_keys(v)))
+                    .collect(),
+            )
+        }
+        Value::Array(items) => Value::Array(items.into_iter().map(sort_keys).collect()),
+        other => other,
+    }
+}
+
+/// Replace the value at a JSON pointer with `"[redacted]"`; a `*` segment
+/// matches every array element or object member
+fn redact_pointer(value: &mut Value, pointer: &str) {
+    fn walk(value: &mut Value, segments: &[String]) {
+        let Some((first, rest)) = segments.split_first() else {
+            *value = Value::String("[redacted]".to_string());
+            return;
+        };
+        match value {
+            Value::Object(map) if first == "*" => {
+                map.values_mut().for_each(|v| walk(v, rest));
+            }
+            Value::Array(items) if first == "*" => {
+                items.iter_mut().for_each(|v| walk(v, rest));
+            }
+            Value::Object(map) => {
+                if let Some(v) = map.get_mut(first.as_str()) {
+                    walk(v, rest);
+                }
+            }
+            Value::Array(items) => {
+                if let Some(v) = first.parse::<usize>().ok().and_then(|i| items.get_mut(i)) {
+                    walk(v, rest);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    let segments: Vec<String> = pointer
+        .trim_start_matches('/')
+        .split('/')
+        .filter(|s| !s.is_empty())
+        .map(|s| s.replace("~1", "/").replace("~0", "~"))
+        .collect();
+    walk(value, &segments);
+}
+
+fn redact_strings(value: &mut Value) {
+    match value {
+        Value::String(s) => *s = redact_text(s),
+        Value::Array(items) => items.iter_mut().for_each(redact_strings),
+        Value::Object(map) => map.values_mut().for_each(redact_strings),
+        _ => {}
+    }
+}
+
+/// Replace UUIDs and RFC 3339 timestamps in text
+pub(crate) fn redact_text(text: &str) -> String {
+    let bytes = text.as_bytes();
+    let mut out = String::with_capacity(text.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let boundary = i == 0 || !bytes[i - 1].is_ascii_alphanumeric();
+        if boundary {
+            if let Some(len) = uuid_len(&bytes[i..]) {
+                out.push_str("[uuid]");
+                i += len;
+                continue;
+            }
+            if let Some(len) = timestamp_len(&bytes[i..]) {
+                out.push_str("[timestamp]");
+                i += len;
+                continue;
+            }
+        }
+        // Copy one (possibly multi-byte) character
+        let ch = text[i..].chars().next().unwrap_or_default();
+        out.push(ch);
+        i += ch.len_utf8().max(1);
+    }
+    out
+}
+
+/// `8-4-4-4-12` hex digits
+fn uuid_len(bytes: &[u8]) -> Option<usize> {
+    const GROUPS: [usize; 5] = [8, 4, 4, 4, 12];
+    let mut i = 0;
+    for (n, group) in GROUPS.iter().enumerate() {
+        if n > 0 {
+            (bytes.get(i) == Some(&b'-')).then_some(())?;
+            i += 1;
+        }
+        let digits = bytes.get(i..i + group)?;
+        digits.iter().all(u8::is_ascii_hexdigit).then_some(())?;
+        i += group;
+    }
+    (!bytes.get(i).is_some_and(u8::is_ascii_alphanumeric)).then_some(i)
+}
+
+/// `YYYY-MM-DDTHH:MM:SS[.frac][Z|±HH:MM]` (also with a space separator)
+fn timestamp_len(bytes: &[u8]) -> Option<usize> {
+    let digits = |at: usize, n: usize| {
+        bytes
+            .get(at..at + n)
+            .is_some_and(|d| d.iter().all(u8::is_ascii_digit))
+    };
+    let is = |at: usize, c: u8| bytes.get(at) == Some(&c);
+
+    let date_time = digits(0, 4)
+        && is(4, b'-')
+        && digits(5, 2)
+        && is(7, b'-')
+        && digits(8, 2)
+        && (is(10, b'T') || is(10, b' '))
+        && digits(11, 2)
+        && is(13, b':')
+        && digits(14, 2)
+        && is(16, b':')
+        && digits(17, 2);
+    if !date_time {
+        return None;
+    }
+
+    let mut i = 19;
+    if is(i, b'.') && digits(i + 1, 1) {
+        i += 1;
+        while digits(i, 1) {
+            i += 1;
+        }
+    }
+    if is(i, b'Z') {
+        i += 1;
+    } else if (is(i, b'+') || is(i, b'-'))
+        && digits(i + 1, 2)
+        && is(i + 3, b':')
+        && digits(i + 4, 2)
+    {
+        i += 6;
+    }
+    Some(i)
+}
+
+/// Line diff of two texts: `-` expected, `+` actual, ` ` unchanged
+fn line_diff(expected: &str, actual: &str) -> String {
+    let old: Vec<&str> = expected.lines().collect();
+    let new: Vec<&str> = actual.lines().collect();
+
+    // Longest common subsequence table
+    let mut lcs = vec![vec![0usize; new.len() + 1]; old.len() + 1];
+    for i in (0..old.len()).rev() {
+        for j in (0..new.len()).rev() {
+            lcs[i][j] = if old[i] == new[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+
+    let mut out = String::new();
+    let (mut i, mut j) = (0, 0);
+    while i < old.len() || j < new.len() {
+        if i < old.len() && j < new.len() && old[i] == new[j] {
+            out.push_str(&format!("  {}\n", old[i]));
+            i += 1;
+            j += 1;
+        } else if i < old.len() && (j == new.len() || lcs[i + 1][j] >= lcs[i][j + 1]) {
+            out.push_str(&format!("- {}\n", old[i]));
+            i += 1;
+        } else {
+            out.push_str(&format!("+ {}\n", new[j]));
+            j += 1;
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn redacts_uuids_timestamps_and_pointers() {
+        let value = json!({
+            "id": "6f1c2a3b-9d4e-4f5a-8b7c-1d2e3f4a5b6c",
+            "created": "2024-05-01T12:30:00.123Z",
+            "note": "at 2024-05-01 12:30:00+02:00 by 6F1C2A3B-9D4E-4F5A-8B7C-1D2E3F4A5B6C",
+            "token": "secret",
+            "items": [{ "key": "a" }, { "key": "b" }]
+        });
+        let redacted: Value =
+            serde_json::from_str(&redact_json(value, &["/token", "/items/*/key"])).unwrap();
+        assert_eq!(
+            redacted,
+            json!({
+                "id": "[uuid]",
+                "created": "[timestamp]",
+                "note": "at [timestamp] by [uuid]",
+                "token": "[redacted]",
+                "items": [{ "key": "[redacted]" }, { "key": "[redacted]" }]
+            })
+        );
+        assert_eq!(
+            redact_text("v2024-05-01T12:30:00 x"),
+            "v2024-05-01T12:30:00 x"
+        );
+    }
+
+    #[test]
+    fn diffs_lines() {
+        let diff = line_diff("a\nb\nc\n", "a\nB\nc\nd\n");
+        assert_eq!(diff, "  a\n- b\n+ B\n  c\n+ d\n");
+    }
+}
@@ -0,0 +1,286 @@
+//! Server-Sent Events assertions
+//!
+//! [`TestClient::sse`](crate::TestClient::sse) opens an event stream without
+//! waiting for it to end, so endless streams (`Sse` with keep-alive, broadcast
+//! feeds) can be tested too. [`TestResponse::sse_events`](crate::TestResponse::sse_events)
+//! parses a finished response, e.g. one built with `sse_response`.
+//!
+//! ```rust,ignore
+//! let mut events = client.sse("/events").await;
+//! events.assert_status(StatusCode::OK);
+//!
+//! events.assert_next_event("ready").await;
+//! let update: Update = events.next_json().await;
+//! assert_eq!(update.progress, 100);
+//! ```
+//!
+//! Comments (keep-alive lines) are skipped. Waiting for an event panics
+//! after the timeout (5 seconds by default, see [`TestEventStream::timeout`]).
+
+use bytes::Bytes;
+use http::{HeaderMap, StatusCode};
+use http_body_util::BodyExt;
+use rustapi_core::{Response, ResponseBody, SseEvent};
+use serde::de::DeserializeOwned;
+use std::collections::VecDeque;
+use std::time::Duration;
+
+/// Default time to wait for the next event
+pub const DEFAULT_EVENT_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// A stream of parsed Server-Sent Events
+pub struct TestEventStream {
+    status: StatusCode,
+    headers: HeaderMap,
+    body: Option<ResponseBody>,
+    buffer: String,
+    pending: VecDeque<SseEvent>,
+    timeout: Duration,
+}
+
+impl std::fmt::Debug for TestEventStream {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("TestEventStream")
+            .field("status", &self.status)
+            .field("pending", &self.pending)
+            .field("timeout", &self.timeout)
+            .finish_non_exhaustive()
+    }
+}
+
+impl TestEventStream {
+    /// Read events from a streaming response body
+    pub(crate) fn from_response(response: Response) -> Self {
+        let (parts, body) = response.into_parts();
+        Self {
+            status: parts.status,
+            headers: parts.headers,
+            body: Some(body),
+            buffer: String::new(),
+            pending: VecDeque::new(),
+            timeout: DEFAULT_EVENT_TIMEOUT,
+        }
+    }
+
+    /// Parse events from a complete body
+    pub(crate) fn from_body(status: StatusCode, headers: HeaderMap, body: &Bytes) -> Self {
+        let mut stream = Self {
+            status,
+            headers,
+            body: None,
+            buffer: String::new(),
+            pending: VecDeque::new(),
+            timeout: DEFAULT_EVENT_TIMEOUT,
+        };
+        stream.feed(&String::from_utf8_lossy(body));
+        // A final event without its blank line still counts
+        stream.feed("\n\n");
+        stream
+    }
+
+    /// Set how long [`next_event`](Self::next_event) waits
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// The response status code
+    pub fn status(&self) -> StatusCode {
+        self.status
+    }
+
+    /// The response headers
+    pub fn headers(&self) -> &HeaderMap {
+        &self.headers
+    }
+
+    /// Assert the response status and that it is an event stream
+    ///
+    /// # Panics
+    ///
+    /// Panics if the status differs or the `Content-Type` is not
+    /// `text/event-stream`.
+    pub fn assert_status<S: Into<StatusCode>>(&self, expected: S) -> &Self {
+        let expected = expected.into();
+        assert_eq!(
+            self.status, expected,
+            "Expected status {}, got {}",
+            expected, self.status
+        );
+        let content_type = self
+            .headers
+            .get(http::header::CONTENT_TYPE)
+            .and_then(|v| v.to_str().ok())
+            .unwrap_or("");
+        assert!(
+            content_type.starts_with("text/event-stream"),
+            "Expected Content-Type text/event-stream, got '{}'",
+            content_type
+        );
+        self
+    }
+
+    /// The next event, or `None` when the stream has ended
+    ///
+    /// # Panics
+    ///
+    /// Panics if no event arrives within the timeout.
+    pub async fn next_event(&mut self) -> Option<SseEvent> {
+        let timeout = self.timeout;
+        match tokio::time::timeout(timeout, self.read_event()).await {
+            Ok(event) => event,
+            Err(_) => panic!("No SSE event received within {:?}", timeout),
+        }
+    }
+
+    /// The data of the next event, parsed as JSON
+    ///
+    /// # Panics
+    ///
+    /// Panics if the stream ends, times out or the data is not valid JSON
+    /// for `T`.
+    pub async fn next_json<T: DeserializeOwned>(&mut self) -> T {
+        let event = self.expect_event().await;
+        serde_json::from_str(&event.data).unwrap_or_else(|e| {
+            panic!(
+                "SSE event data is not the expected JSON ({}): {}",
+                e, event.data
+            )
+        })
+    }
+
+    /// Assert that the next event has the type `event`, returning it
+    pub async fn assert_next_event(&mut self, event: &str) -> SseEvent {
+        let next = self.expect_event().await;
+        assert_eq!(
+            next.event.as_deref(),
+            Some(event),
+            "Expected SSE event '{}', got {:?}",
+            event,
+            next
+        );
+        next
+    }
+
+    /// Assert that the next event carries `data`, returning it
+    pub async fn assert_next_data(&mut self, data: &str) -> SseEvent {
+        let next = self.expect_event().await;
+        assert_eq!(next.data, data, "Unexpected SSE event data: {:?}", next);
+        next
+    }
+
+    /// Assert that the stream ends without further events
+    pub async fn assert_end(&mut self) {
+        if let Some(event) = self.next_event().await {
+            panic!("Expected the SSE stream to end, got {:?}", event);
+        }
+    }
+
+    /// Collect the next `n` events
+    pub async fn take(&mut self, n: usize) -> Vec<SseEvent> {
+        let mut events = Vec::with_capacity(n);
+        for _ in 0..n {
+            events.push(self.expect_event().await);
+        }
+        events
+    }
+
+    async fn expect_event(&mut self) -> SseEvent {
+        self.next_event()
+            .await
+            .expect("SSE stream ended before the expected event")
+    }
+
+    async fn read_event(&mut self) -> Option<SseEvent> {
+        loop {
+            if let Some(event) = self.pending.pop_front() {
+                return Some(event);
+            }
+            let frame = self.body.as_mut()?.frame().await;
+            match frame {
+                Some(Ok(frame)) => {
+                    if let Ok(data) = frame.into_data() {
+                        self.feed(&String::from_utf8_lossy(&data));
+                    }
+                }
+                Some(Err(_)) | None => {
+                    self.body = None;
+                    self.feed("\n\n");
+                }
+            }
+        }
+    }
+
+    /// Append text and parse every complete event
+    fn feed(&mut self, text: &str) {
+        self.buffer
+            .push_str(&text.replace("\r\n", "\n").replace('\r', "\n"));
+        while let Some(end) = self.buffer.find("\n\n") {
+            let block: String = self.buffer.drain(..end + 2).collect();
+            if let Some(event) = parse_event(&block) {
+                self.pending.push_back(event);
+            }
+        }
+    }
+}
+
+/// Parse one event block; `None` for comment-only blocks
+fn parse_event(block: &str) -> Option<SseEvent> {
+    let mut data: Option<String> = None;
+    let mut event = SseEvent::new("");
+    let mut has_fields = false;
+
+    for line in block.lines() {
+        if line.is_empty() || line.starts_with(':') {
+            continue;
+        }
+        let (field, value) = match line.split_once(':') {
+            Some((field, value)) => (field, value.strip_prefix(' ').unwrap_or(value)),
+            None => (line, ""),
+        };
+        has_fields = true;
+        match field {
+            "data" => match &mut data {
+                Some(data) => {
+                    data.push('\n');
+                    data.push_str(value);
+                }
+                None => data = Some(value.to_string()),
+            },
+            "event" => event.event = Some(value.to_string()),
+            "id" => event.id = Some(value.to_string()),
+            "retry" => event.retry = value.parse().ok(),
+            _ => {}
+        }
+    }
+
+    if !has_fields {
+        return None;
+    }
+    event.data = data.unwrap_or_default();
+    Some(event)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn parses_events_and_skips_comments() {
+        let body = Bytes::from(
+            ": keep-alive\n\n\
+             event: greeting\nid: 1\ndata: hello\n\n\
+             data: line one\r\ndata: line two\r\n\r\n\
+             retry: 3000\ndata: {\"n\":1}",
+        );
+        let mut events = TestEventStream::from_body(StatusCode::OK, HeaderMap::new(), &body);
+
+        let first = events.assert_next_event("greeting").await;
+        assert_eq!(first.id.as_deref(), Some("1"));
+        assert_eq!(first.data, "hello");
+        events.assert_next_data("line one\nline two").await;
+        let value: serde_json::Value = events.next_json().await;
+        assert_eq!(value["n"], 1);
+        events.assert_end().await;
+    }
+}
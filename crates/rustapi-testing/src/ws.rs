@@ -0,0 +1,249 @@
+//! In-process WebSocket testing
+//!
+//! [`TestClient::websocket`] performs a real WebSocket handshake against the
+//! app over an in-memory connection (no TCP), so handlers using the
+//! `WebSocket` extractor, `Broadcast` and rooms can be covered by integration
+//! tests.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! use rustapi_testing::TestClient;
+//! use rustapi_ws::Message;
+//!
+//! #[tokio::test]
+//! async fn test_echo() {
+//!     let client = TestClient::new(RustApi::new().route("/ws", get(echo)));
+//!     let mut socket = client.websocket("/ws").await;
+//!
+//!     socket.send_text("hello").await;
+//!     assert_eq!(socket.recv_text().await, "Echo: hello");
+//!     socket.close().await;
+//! }
+//! ```
+
+use crate::client::{TestClient, TestRequest, TestResponse};
+use bytes::Bytes;
+use futures_util::{SinkExt, StreamExt};
+use http::{HeaderMap, StatusCode};
+use hyper::body::Incoming;
+use hyper::server::conn::http1;
+use hyper_util::rt::TokioIo;
+use rustapi_core::BodyVariant;
+use rustapi_ws::Message;
+use serde::{de::DeserializeOwned, Serialize};
+use std::convert::Infallible;
+use std::time::Duration;
+use tokio::io::DuplexStream;
+use tokio_tungstenite::tungstenite::client::IntoClientRequest;
+use tokio_tungstenite::tungstenite::{self, Error as WsError};
+use tokio_tungstenite::WebSocketStream;
+
+/// Default time [`TestWebSocket::recv`] waits for a message
+pub const DEFAULT_RECV_TIMEOUT: Duration = Duration::from_secs(5);
+
+impl TestClient {
+    /// Open a WebSocket connection to the app
+    ///
+    /// # Panics
+    ///
+    /// Panics if the handshake is rejected (for example with `401` or `400`);
+    /// use [`websocket_request`](Self::websocket_request) to assert on the
+    /// rejection instead.
+    pub async fn websocket(&self, path: &str) -> TestWebSocket {
+        match self.websocket_request(TestRequest::get(path)).await {
+            Ok(socket) => socket,
+            Err(response) => panic!(
+                "WebSocket handshake for {} was rejected with {}: {}",
+                path,
+                response.status(),
+                response.text()
+            ),
+        }
+    }
+
+    /// Open a WebSocket connection with custom headers (e.g. auth tokens,
+    /// cookies or `Sec-WebSocket-Protocol`)
+    ///
+    /// Returns the HTTP response if the app does not accept the upgrade.
+    pub async fn websocket_request(&self, req: TestRequest) -> Result<TestWebSocket, TestResponse> {
+        let req = self.attach_cookies(req);
+        let (client_io, server_io) = tokio::io::duplex(64 * 1024);
+
+        let app = self.clone();
+        let service = hyper::service::service_fn(move |req: hyper::Request<Incoming>| {
+            let app = app.clone();
+            async move {
+                let (parts, body) = req.into_parts();
+                Ok::<_, Infallible>(app.dispatch(parts, BodyVariant::Streaming(body)).await)
+            }
+        });
+        tokio::spawn(async move {
+            let _ = http1::Builder::new()
+                .serve_connection(TokioIo::new(server_io), service)
+                .with_upgrades()
+                .await;
+        });
+
+        let mut request = format!("ws://localhost{}", req.path)
+            .into_client_request()
+            .expect("invalid WebSocket test path");
+        for (name, value) in req.headers.iter() {
+            request.headers_mut().insert(name, value.clone());
+        }
+
+        match tokio_tungstenite::client_async(request, client_io).await {
+            Ok((stream, response)) => Ok(TestWebSocket {
+                stream,
+                headers: response.headers().clone(),
+                timeout: DEFAULT_RECV_TIMEOUT,
+            }),
+            Err(WsError::Http(response)) => {
+                let (parts, body) = response.into_parts();
+                Err(TestResponse::from_parts(
+                    parts.status,
+                    parts.headers,
+                    Bytes::from(body.unwrap_or_default()),
+                ))
+            }
+            Err(e) => Err(TestResponse::from_parts(
+                StatusCode::BAD_GATEWAY,
+                HeaderMap::new(),
+                Bytes::from(e.to_string()),
+            )),
+        }
+    }
+}
+
+/// Client side of an in-process WebSocket connection
+pub struct TestWebSocket {
+    stream: WebSocketStream<DuplexStream>,
+    headers: HeaderMap,
+    timeout: Duration,
+}
+
+impl TestWebSocket {
+    /// Headers of the `101 Switching Protocols` response
+    pub fn headers(&self) -> &HeaderMap {
+        &self.headers
+    }
+
+    /// Subprotocol selected by the server, if any
+    pub fn protocol(&self) -> Option<&str> {
+        self.headers
+            .get("sec-websocket-protocol")
+            .and_then(|v| v.to_str().ok())
+    }
+
+    /// Set how long `recv` waits for a message
+    pub fn recv_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Send a message
+    ///
+    /// # Panics
+    ///
+    /// Panics if the connection is closed.
+    pub async fn send(&mut self, msg: Message) {
+        self.stream
+            .send(msg.into())
+            .await
+            .expect("failed to send WebSocket message");
+    }
+
+    /// Send a text message
+    pub async fn send_text(&mut self, text: impl Into<String>) {
+        self.send(Message::text(text)).await
+    }
+
+    /// Send a JSON text message
+    pub async fn send_json<T: Serialize>(&mut self, value: &T) {
+        self.send(Message::json(value).expect("failed to serialize JSON message"))
+            .await
+    }
+
+    /// Receive the next message, skipping pings and pongs
+    ///
+    /// Returns `None` if the connection ended without a close frame. A close
+    /// frame is returned as [`Message::Close`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if no message arrives within the receive timeout (5 seconds by
+    /// default).
+    pub async fn recv(&mut self) -> Option<Message> {
+        let timeout = self.timeout;
+        tokio::time::timeout(timeout, async {
+            loop {
+                match self.stream.next().await {
+                    Some(Ok(msg)) if msg.is_ping() || msg.is_pong() => {}
+                    Some(Ok(tungstenite::Message::Frame(_))) => {}
+                    Some(Ok(msg)) => return Some(Message::from(msg)),
+                    Some(Err(_)) | None => return None,
+                }
+            }
+        })
+        .await
+        .unwrap_or_else(|_| panic!("no WebSocket message received within {:?}", timeout))
+    }
+
+    /// Receive the next message and return its text
+    ///
+    /// # Panics
+    ///
+    /// Panics if the next message is not a text message.
+    pub async fn recv_text(&mut self) -> String {
+        match self.recv().await {
+            Some(Message::Text(text)) => text,
+            other => panic!("expected a text message, got {:?}", other),
+        }
+    }
+
+    /// Receive the next message and deserialize it as JSON
+    ///
+    /// # Panics
+    ///
+    /// Panics if the next message is not valid JSON for `T`.
+    pub async fn recv_json<T: DeserializeOwned>(&mut self) -> T {
+        let data = match self.recv().await {
+            Some(Message::Text(text)) => text.into_bytes(),
+            Some(Message::Binary(data)) => data,
+            other => panic!("expected a JSON message, got {:?}", other),
+        };
+        serde_json::from_slice(&data).expect("failed to parse WebSocket message as JSON")
+    }
+
+    /// Assert that no message arrives within `duration`
+    ///
+    /// # Panics
+    ///
+    /// Panics if a message (other than ping/pong) is received.
+    pub async fn assert_no_message(&mut self, duration: Duration) {
+        let received = tokio::time::timeout(duration, async {
+            loop {
+                match self.stream.next().await {
+                    Some(Ok(msg)) if msg.is_ping() || msg.is_pong() => {}
+                    other => return other,
+                }
+            }
+        })
+        .await;
+        if let Ok(Some(Ok(msg))) = received {
+            panic!(
+                "expected no WebSocket message, got {:?}",
+                Message::from(msg)
+            );
+        }
+    }
+
+    /// Send a close frame and wait for the server to finish the connection
+    pub async fn close(mut self) {
+        let _ = self.stream.close(None).await;
+        let _ = tokio::time::timeout(self.timeout, async {
+            while let Some(Ok(_)) = self.stream.next().await {}
+        })
+        .await;
+    }
+}
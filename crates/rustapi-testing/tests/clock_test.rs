@@ -0,0 +1,62 @@
+use http::{header, StatusCode};
+use rustapi_core::{get, Clock, IntoResponse, Response, RustApi};
+use rustapi_testing::TestClient;
+use std::time::Duration;
+
+async fn now(clock: Clock) -> String {
+    clock.unix().as_secs().to_string()
+}
+
+async fn login() -> Response {
+    let mut response = "logged in".into_response();
+    response.headers_mut().insert(
+        header::SET_COOKIE,
+        "session=alice; Path=/; Max-Age=3600".parse().unwrap(),
+    );
+    response
+}
+
+fn app() -> RustApi {
+    RustApi::new()
+        .route("/now", get(now))
+        .route("/login", get(login))
+}
+
+async fn server_time(client: &TestClient) -> u64 {
+    client
+        .get("/now")
+        .await
+        .assert_status(StatusCode::OK)
+        .text()
+        .parse()
+        .unwrap()
+}
+
+#[tokio::test]
+async fn advance_time_moves_the_app_clock() {
+    let client = TestClient::new(app());
+    assert!(!client.clock().is_manual());
+
+    client.advance_time(Duration::ZERO);
+    assert!(client.clock().is_manual());
+    let start = server_time(&client).await;
+
+    client.advance_time(Duration::from_secs(90));
+    assert_eq!(server_time(&client).await, start + 90);
+}
+
+#[tokio::test]
+async fn shared_clock_expires_cookies() {
+    let clock = Clock::manual();
+    let client = TestClient::new(app())
+        .with_cookies()
+        .with_clock(clock.clone());
+
+    client.get("/login").await;
+    assert_eq!(client.cookie("session").as_deref(), Some("alice"));
+
+    clock.advance(Duration::from_secs(3599));
+    assert!(client.cookie("session").is_some());
+    client.advance_time(Duration::from_secs(1));
+    assert_eq!(client.cookie("session"), None);
+}
@@ -0,0 +1,56 @@
+use http::StatusCode;
+use rustapi_core::{get, Json, Path, RustApi};
+use rustapi_openapi::Schema;
+use rustapi_testing::TestClient;
+use serde::Serialize;
+
+#[derive(Serialize, Schema)]
+struct User {
+    id: i64,
+    name: String,
+}
+
+#[derive(Serialize, Schema)]
+struct RenamedUser {
+    id: i64,
+    username: String,
+}
+
+async fn get_user(Path(id): Path<i64>) -> Json<User> {
+    Json(User {
+        id,
+        name: "Alice".to_string(),
+    })
+}
+
+async fn get_renamed_user(Path(id): Path<i64>) -> Json<RenamedUser> {
+    Json(RenamedUser {
+        id,
+        username: "alice".to_string(),
+    })
+}
+
+#[tokio::test]
+async fn response_matches_generated_spec() {
+    let client = TestClient::new(RustApi::new().route("/users/{id}", get(get_user)));
+
+    client
+        .get("/users/1")
+        .await
+        .assert_status(StatusCode::OK)
+        .assert_matches_openapi(client.openapi_spec());
+}
+
+#[tokio::test]
+#[should_panic(expected = "missing required property 'name'")]
+async fn drift_from_documented_schema_fails() {
+    let documented = TestClient::new(RustApi::new().route("/users/{id}", get(get_user)))
+        .openapi_spec()
+        .clone();
+    let client = TestClient::new(RustApi::new().route("/users/{id}", get(get_renamed_user)));
+
+    client
+        .get("/users/1")
+        .await
+        .assert_matches_openapi(&documented);
+}
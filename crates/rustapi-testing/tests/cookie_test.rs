@@ -0,0 +1,77 @@
+use http::{header, StatusCode};
+use rustapi_core::{get, post, Headers, IntoResponse, Response, RustApi};
+use rustapi_testing::{TestClient, TestRequest};
+
+async fn login() -> Response {
+    let mut response = "logged in".into_response();
+    response.headers_mut().insert(
+        header::SET_COOKIE,
+        "session=alice; Path=/; HttpOnly".parse().unwrap(),
+    );
+    response
+}
+
+async fn logout() -> Response {
+    let mut response = StatusCode::NO_CONTENT.into_response();
+    response.headers_mut().insert(
+        header::SET_COOKIE,
+        "session=; Path=/; Max-Age=0".parse().unwrap(),
+    );
+    response
+}
+
+async fn me(Headers(headers): Headers) -> Response {
+    match headers.get(header::COOKIE).and_then(|v| v.to_str().ok()) {
+        Some(cookies) if cookies.contains("session=alice") => cookies.to_string().into_response(),
+        _ => StatusCode::UNAUTHORIZED.into_response(),
+    }
+}
+
+fn app() -> RustApi {
+    RustApi::new()
+        .route("/login", post(login))
+        .route("/logout", post(logout))
+        .route("/me", get(me))
+}
+
+#[tokio::test]
+async fn login_then_act() {
+    let client = TestClient::new(app()).with_cookies();
+    client
+        .get("/me")
+        .await
+        .assert_status(StatusCode::UNAUTHORIZED);
+
+    client.request(TestRequest::post("/login")).await;
+    assert_eq!(client.cookie("session").as_deref(), Some("alice"));
+    client
+        .request(TestRequest::get("/me").cookie("theme", "dark"))
+        .await
+        .assert_status(StatusCode::OK)
+        .assert_body_contains("theme=dark; session=alice");
+
+    client.request(TestRequest::post("/logout")).await;
+    assert!(client.cookies().is_empty());
+    client
+        .get("/me")
+        .await
+        .assert_status(StatusCode::UNAUTHORIZED);
+
+    client.set_cookie("session", "alice");
+    client.get("/me").await.assert_status(StatusCode::OK);
+    client.clear_cookies();
+    client
+        .get("/me")
+        .await
+        .assert_status(StatusCode::UNAUTHORIZED);
+}
+
+#[tokio::test]
+async fn cookies_are_not_kept_by_default() {
+    let client = TestClient::new(app());
+    client.request(TestRequest::post("/login")).await;
+    client
+        .get("/me")
+        .await
+        .assert_status(StatusCode::UNAUTHORIZED);
+}
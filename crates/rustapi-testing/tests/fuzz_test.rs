@@ -0,0 +1,99 @@
+use http::Method;
+use rustapi_core::{get, post, ApiError, Json, Path, RustApi};
+use rustapi_openapi::Schema;
+use rustapi_testing::TestClient;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Serialize, Deserialize, Schema)]
+struct NewUser {
+    name: String,
+    age: u8,
+    tags: Vec<String>,
+    nickname: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Schema)]
+struct Ratio {
+    a: i32,
+    b: i32,
+}
+
+#[derive(Debug, Serialize, Schema)]
+struct Quotient {
+    value: i32,
+}
+
+async fn create_user(Json(user): Json<NewUser>) -> Result<Json<NewUser>, ApiError> {
+    if user.name.is_empty() {
+        return Err(ApiError::bad_request("name must not be empty"));
+    }
+    Ok(Json(user))
+}
+
+async fn get_user(Path(id): Path<String>) -> Result<String, ApiError> {
+    let id: u64 = id
+        .parse()
+        .map_err(|_| ApiError::bad_request("id must be a number"))?;
+    Ok(format!("user {}", id))
+}
+
+/// Forgets that `b` can be zero
+async fn divide(Json(ratio): Json<Ratio>) -> Result<Json<Quotient>, ApiError> {
+    if ratio.b == 0 {
+        return Err(ApiError::internal("division by zero"));
+    }
+    Ok(Json(Quotient {
+        value: ratio.a.wrapping_div(ratio.b),
+    }))
+}
+
+fn app() -> RustApi {
+    RustApi::new()
+        .route("/users", post(create_user))
+        .route("/users/{id}", get(get_user))
+        .route("/divide", post(divide))
+}
+
+#[tokio::test]
+async fn robust_endpoints_pass() {
+    let client = TestClient::new(app());
+    let report = client
+        .fuzz()
+        .cases(100)
+        .seed(7)
+        .skip(Method::POST, "/divide")
+        .run()
+        .await;
+
+    assert_eq!(report.requests(), 200);
+    report.assert_ok();
+}
+
+#[tokio::test]
+async fn finds_server_errors() {
+    let client = TestClient::new(app());
+    let report = client.fuzz().cases(200).seed(7).run().await;
+
+    assert!(!report.is_ok());
+    let failure = &report.failures()[0];
+    assert_eq!(failure.method, Method::POST);
+    assert_eq!(failure.path, "/divide");
+    assert!(failure.reason.starts_with("server error"), "{}", failure);
+    assert!(
+        failure.body.as_deref().unwrap().contains("\"b\":0"),
+        "{}",
+        failure
+    );
+}
+
+#[tokio::test]
+#[should_panic(expected = "replay with RUSTAPI_FUZZ_SEED=3")]
+async fn assert_ok_reports_the_seed() {
+    TestClient::new(app())
+        .fuzz()
+        .cases(200)
+        .seed(3)
+        .run()
+        .await
+        .assert_ok();
+}
@@ -0,0 +1,73 @@
+#![cfg(feature = "grpc")]
+
+use rustapi_core::RustApi;
+use rustapi_grpc::tonic::service::Routes;
+use rustapi_grpc::tonic_health::pb::health_check_response::ServingStatus;
+use rustapi_grpc::tonic_health::pb::health_client::HealthClient;
+use rustapi_grpc::tonic_health::pb::HealthCheckRequest;
+use rustapi_grpc::{tonic_health, GrpcServices, GrpcTracingLayer};
+use rustapi_testing::TestGrpc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+async fn check(grpc: &TestGrpc, service: &str) -> i32 {
+    HealthClient::new(grpc.channel())
+        .check(HealthCheckRequest {
+            service: service.to_string(),
+        })
+        .await
+        .unwrap()
+        .into_inner()
+        .status
+}
+
+#[tokio::test]
+async fn calls_services_without_a_port() {
+    let (reporter, health) = tonic_health::server::health_reporter();
+    reporter
+        .set_service_status("greeter", tonic_health::ServingStatus::Serving)
+        .await;
+
+    let grpc =
+        TestGrpc::services(GrpcServices::new(Routes::new(health)).tracing(GrpcTracingLayer::new()))
+            .await;
+    assert_eq!(check(&grpc, "greeter").await, ServingStatus::Serving as i32);
+
+    reporter
+        .set_service_status("greeter", tonic_health::ServingStatus::NotServing)
+        .await;
+    let mut second = HealthClient::new(grpc.connect().await);
+    let status = second
+        .check(HealthCheckRequest {
+            service: "greeter".to_string(),
+        })
+        .await
+        .unwrap()
+        .into_inner()
+        .status;
+    assert_eq!(status, ServingStatus::NotServing as i32);
+
+    let missing = HealthClient::new(grpc.channel())
+        .check(HealthCheckRequest {
+            service: "unknown".to_string(),
+        })
+        .await
+        .unwrap_err();
+    assert_eq!(missing.code(), rustapi_grpc::tonic::Code::NotFound);
+}
+
+#[tokio::test]
+async fn runs_app_lifecycle_hooks() {
+    let stopped = Arc::new(AtomicBool::new(false));
+    let flag = stopped.clone();
+    let app = RustApi::new().on_shutdown(move || async move {
+        flag.store(true, Ordering::SeqCst);
+    });
+    let (_reporter, health) = tonic_health::server::health_reporter();
+
+    let grpc = TestGrpc::start(app, Routes::new(health)).await;
+    assert_eq!(check(&grpc, "").await, ServingStatus::Serving as i32);
+
+    grpc.shutdown().await;
+    assert!(stopped.load(Ordering::SeqCst));
+}
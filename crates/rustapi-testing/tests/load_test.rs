@@ -0,0 +1,72 @@
+//! Load harness tests
+//!
+//! The benchmark workflow runs these with `--release -- --nocapture` and
+//! keeps the printed reports.
+
+use http::StatusCode;
+use rustapi_core::{get, post, Json, RustApi};
+use rustapi_testing::{
+    LoadTest, MockResponse, MockServer, RequestMatcher, TestClient, TestRequest,
+};
+use std::time::Duration;
+
+async fn hello() -> &'static str {
+    "Hello, World!"
+}
+
+async fn echo(Json(body): Json<serde_json::Value>) -> Json<serde_json::Value> {
+    Json(body)
+}
+
+fn app() -> RustApi {
+    RustApi::new()
+        .route("/hello", get(hello))
+        .route("/echo", post(echo))
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn load_in_process_app() {
+    let report = LoadTest::new(TestClient::new(app()))
+        .request(TestRequest::get("/hello"))
+        .request(TestRequest::post("/echo").json(&serde_json::json!({ "n": 1 })))
+        .concurrency(8)
+        .warmup(Duration::from_millis(50))
+        .duration(Duration::from_millis(300))
+        .run()
+        .await;
+
+    println!("in-process:\n{}", report);
+    assert!(report.requests() > 0);
+    assert_eq!(report.status_counts().get(&200), Some(&report.requests()));
+    report
+        .assert_error_rate_at_most(0.0)
+        .assert_p99_below(Duration::from_secs(1));
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn load_url_stops_at_max_requests() {
+    let server = MockServer::start().await;
+    server
+        .expect(RequestMatcher::new().path("/ok"))
+        .respond_with(MockResponse::new().body("ok"));
+    server
+        .expect(RequestMatcher::new().path("/fail"))
+        .respond_with(MockResponse::new().status(StatusCode::SERVICE_UNAVAILABLE));
+
+    let report = LoadTest::new(server.base_url())
+        .request(TestRequest::get("/ok"))
+        .request(TestRequest::get("/ok"))
+        .request(TestRequest::get("/ok"))
+        .request(TestRequest::get("/fail"))
+        .concurrency(4)
+        .duration(Duration::from_secs(30))
+        .max_requests(200)
+        .run()
+        .await;
+
+    println!("url:\n{}", report);
+    assert_eq!(report.requests(), 200);
+    assert_eq!(report.errors(), 50);
+    assert_eq!(report.connection_errors(), 0);
+    report.assert_error_rate_at_most(0.25);
+}
@@ -88,3 +88,54 @@ async fn test_verification_failure() {
     // No call made
     server.verify(); // Should panic
 }
+
+#[tokio::test]
+async fn test_advanced_matching() {
+    let server = MockServer::start().await;
+
+    server
+        .expect(
+            RequestMatcher::new()
+                .method(Method::POST)
+                .path_regex(r"^/accounts/\d+/payments$")
+                .query_param("idempotent", "true")
+                .body_json_partial(json!({"currency": "EUR"}))
+                .body_json_path("$.lines[*].amount", 250)
+                .and(!RequestMatcher::new().header("x-dry-run", "1")),
+        )
+        .respond_with(MockResponse::new().status(StatusCode::ACCEPTED))
+        .times(1);
+
+    let client = reqwest::Client::new();
+    let payment = json!({
+        "currency": "EUR",
+        "reference": "INV-7",
+        "lines": [{"amount": 100}, {"amount": 250}]
+    });
+
+    let resp = client
+        .post(format!(
+            "{}/accounts/42/payments?idempotent=true",
+            server.base_url()
+        ))
+        .json(&payment)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), StatusCode::ACCEPTED);
+
+    // Excluded by the negated header matcher
+    let resp = client
+        .post(format!(
+            "{}/accounts/42/payments?idempotent=true",
+            server.base_url()
+        ))
+        .header("x-dry-run", "1")
+        .json(&payment)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+
+    server.verify();
+}
@@ -0,0 +1,37 @@
+use rustapi_core::{get, Json, RustApi};
+use rustapi_testing::TestClient;
+use serde_json::{json, Value};
+
+async fn session() -> Json<Value> {
+    Json(json!({
+        "id": "3b241101-e2bb-4255-8caf-4136c566a962",
+        "user": { "name": "Alice", "roles": ["admin"] },
+        "token": "d41d8cd98f00b204",
+        "expires_at": "2030-01-01T00:00:00Z"
+    }))
+}
+
+async fn banner() -> &'static str {
+    "Welcome! Server time: 2030-01-01 08:00:00+02:00"
+}
+
+fn client() -> TestClient {
+    TestClient::new(
+        RustApi::new()
+            .route("/session", get(session))
+            .route("/banner", get(banner)),
+    )
+}
+
+#[tokio::test]
+async fn json_snapshot_with_redactions() {
+    client()
+        .get("/session")
+        .await
+        .assert_json_snapshot_redacted("session", &["/token"]);
+}
+
+#[tokio::test]
+async fn body_snapshot() {
+    client().get("/banner").await.assert_body_snapshot("banner");
+}
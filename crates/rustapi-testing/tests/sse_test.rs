@@ -0,0 +1,64 @@
+use futures_util::stream::{self, StreamExt};
+use http::StatusCode;
+use rustapi_core::{get, sse_response, KeepAlive, Response, RustApi, Sse, SseEvent};
+use rustapi_testing::TestClient;
+use std::convert::Infallible;
+use std::time::Duration;
+
+async fn feed() -> Response {
+    sse_response(vec![
+        SseEvent::new("first").id("1"),
+        SseEvent::new("second").event("update").id("2"),
+    ])
+}
+
+async fn live() -> Sse<impl futures_util::Stream<Item = Result<SseEvent, Infallible>>> {
+    let events = stream::iter(vec![
+        Ok(SseEvent::new("ready").event("status")),
+        Ok(SseEvent::json_data(&serde_json::json!({ "progress": 50 })).unwrap()),
+    ]);
+    // Never ends, like a broadcast feed
+    Sse::new(events.chain(stream::pending()))
+        .keep_alive(KeepAlive::new().interval(Duration::from_millis(10)))
+}
+
+fn app() -> RustApi {
+    RustApi::new()
+        .route("/feed", get(feed))
+        .route("/live", get(live))
+}
+
+#[tokio::test]
+async fn parses_a_finished_stream() {
+    let client = TestClient::new(app());
+    let response = client.get("/feed").await;
+    let mut events = response.sse_events();
+    events.assert_status(StatusCode::OK);
+
+    let first = events.assert_next_data("first").await;
+    assert_eq!(first.id.as_deref(), Some("1"));
+    events.assert_next_event("update").await;
+    events.assert_end().await;
+}
+
+#[tokio::test]
+async fn reads_an_endless_stream() {
+    let client = TestClient::new(app());
+    let mut events = client
+        .sse("/live")
+        .await
+        .timeout(Duration::from_millis(200));
+    events.assert_status(StatusCode::OK);
+
+    events.assert_next_event("status").await;
+    let progress: serde_json::Value = events.next_json().await;
+    assert_eq!(progress["progress"], 50);
+}
+
+#[tokio::test]
+#[should_panic(expected = "No SSE event received")]
+async fn times_out_waiting_for_an_event() {
+    let client = TestClient::new(app());
+    let mut events = client.sse("/live").await.timeout(Duration::from_millis(50));
+    events.take(3).await;
+}
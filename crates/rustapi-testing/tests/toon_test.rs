@@ -0,0 +1,82 @@
+#![cfg(feature = "toon")]
+
+use http::StatusCode;
+use rustapi_core::{get, post, RustApi};
+use rustapi_openapi::Schema;
+use rustapi_testing::{TestClient, TestRequest};
+use rustapi_toon::Toon;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Schema)]
+struct User {
+    id: u64,
+    name: String,
+    tags: Vec<String>,
+}
+
+async fn get_user() -> Toon<User> {
+    Toon(User {
+        id: 1,
+        name: "Alice".to_string(),
+        tags: vec!["admin".to_string(), "ops".to_string()],
+    })
+}
+
+async fn rename(Toon(mut user): Toon<User>) -> Toon<User> {
+    user.name = user.name.to_uppercase();
+    Toon(user)
+}
+
+fn app() -> RustApi {
+    RustApi::new()
+        .route("/users/1", get(get_user))
+        .route("/users", post(rename))
+}
+
+#[tokio::test]
+async fn decodes_and_asserts_toon_responses() {
+    let client = TestClient::new(app());
+    let response = client.get("/users/1").await;
+
+    response
+        .assert_status(StatusCode::OK)
+        .assert_toon_eq(&User {
+            id: 1,
+            name: "Alice".to_string(),
+            tags: vec!["admin".to_string(), "ops".to_string()],
+        });
+    let user: User = response.toon().unwrap();
+    assert_eq!(user.tags.len(), 2);
+}
+
+#[tokio::test]
+async fn sends_toon_requests() {
+    let client = TestClient::new(app());
+    let bob = User {
+        id: 2,
+        name: "Bob".to_string(),
+        tags: vec![],
+    };
+
+    let response = client.post_toon("/users", &bob).await;
+    response.assert_status(StatusCode::OK);
+    assert_eq!(response.toon::<User>().unwrap().name, "BOB");
+
+    client
+        .request(TestRequest::post("/users").toon(&bob))
+        .await
+        .assert_toon_eq(&User {
+            name: "BOB".to_string(),
+            ..bob
+        });
+}
+
+#[tokio::test]
+#[should_panic(expected = "Expected a TOON response")]
+async fn assert_toon_eq_rejects_other_content_types() {
+    let app = RustApi::new().route("/json", get(|| async { "plain" }));
+    TestClient::new(app)
+        .get("/json")
+        .await
+        .assert_toon_eq(&"plain".to_string());
+}
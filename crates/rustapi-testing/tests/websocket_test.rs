@@ -0,0 +1,131 @@
+#![cfg(feature = "ws")]
+
+use http::StatusCode;
+use rustapi_core::{get, RustApi, State};
+use rustapi_testing::{TestClient, TestRequest};
+use rustapi_ws::auth::{Claims, StaticTokenValidator, TokenExtractor, WsAuthConfig};
+use rustapi_ws::{Broadcast, Message, WebSocket, WebSocketUpgrade};
+use serde_json::json;
+use std::sync::Arc;
+use std::time::Duration;
+
+async fn echo(ws: WebSocket) -> WebSocketUpgrade {
+    ws.on_upgrade(|mut socket| async move {
+        while let Some(Ok(msg)) = socket.recv().await {
+            match msg {
+                Message::Text(text) => {
+                    let _ = socket.send_text(format!("Echo: {}", text)).await;
+                }
+                Message::Close(_) => break,
+                _ => {}
+            }
+        }
+    })
+}
+
+async fn chat(ws: WebSocket, State(broadcast): State<Arc<Broadcast>>) -> WebSocketUpgrade {
+    let user = ws
+        .claims()
+        .map(|c| c.subject().to_string())
+        .unwrap_or_default();
+    ws.on_upgrade(move |mut socket| async move {
+        let mut room = broadcast.join("lobby", user.clone());
+        broadcast
+            .send_json_to_room("lobby", &json!({ "joined": user }))
+            .ok();
+        loop {
+            tokio::select! {
+                msg = socket.recv() => match msg {
+                    Some(Ok(Message::Text(text))) => {
+                        broadcast.send_text_to_room("lobby", format!("{}: {}", user, text));
+                    }
+                    Some(Ok(Message::Close(_))) | Some(Err(_)) | None => break,
+                    _ => {}
+                },
+                Some(Ok(msg)) = room.recv() => {
+                    if socket.send(msg).await.is_err() {
+                        break;
+                    }
+                }
+            }
+        }
+        // Leave before announcing it, so members seeing the event see the room without us
+        drop(room);
+        broadcast
+            .send_json_to_room("lobby", &json!({ "left": user }))
+            .ok();
+    })
+}
+
+fn chat_app(broadcast: Arc<Broadcast>) -> TestClient {
+    let auth = WsAuthConfig::new(
+        StaticTokenValidator::new()
+            .add_token("alice-token", Claims::new("alice"))
+            .add_token("bob-token", Claims::new("bob")),
+    )
+    .extractor(TokenExtractor::query("token"));
+
+    TestClient::new(
+        RustApi::new()
+            .state(broadcast)
+            .state(auth)
+            .route("/chat", get(chat)),
+    )
+}
+
+#[tokio::test]
+async fn test_websocket_echo() {
+    let client = TestClient::new(RustApi::new().route("/ws", get(echo)));
+    let mut socket = client.websocket("/ws").await;
+
+    socket.send_text("hello").await;
+    assert_eq!(socket.recv_text().await, "Echo: hello");
+
+    socket.send(Message::text("again")).await;
+    assert_eq!(socket.recv().await, Some(Message::text("Echo: again")));
+    socket.close().await;
+}
+
+#[tokio::test]
+async fn test_websocket_rooms_and_broadcast() {
+    let broadcast = Arc::new(Broadcast::new());
+    let client = chat_app(broadcast.clone());
+
+    let mut alice = client.websocket("/chat?token=alice-token").await;
+    assert_eq!(
+        alice.recv_json::<serde_json::Value>().await,
+        json!({ "joined": "alice" })
+    );
+
+    let mut bob = client.websocket("/chat?token=bob-token").await;
+    assert_eq!(
+        alice.recv_json::<serde_json::Value>().await,
+        json!({ "joined": "bob" })
+    );
+    bob.recv_json::<serde_json::Value>().await;
+    assert_eq!(broadcast.room_members("lobby"), vec!["alice", "bob"]);
+
+    bob.send_text("hi").await;
+    assert_eq!(alice.recv_text().await, "bob: hi");
+    assert_eq!(bob.recv_text().await, "bob: hi");
+
+    bob.close().await;
+    assert_eq!(
+        alice.recv_json::<serde_json::Value>().await,
+        json!({ "left": "bob" })
+    );
+    assert_eq!(broadcast.room_members("lobby"), vec!["alice"]);
+    alice.assert_no_message(Duration::from_millis(50)).await;
+}
+
+#[tokio::test]
+async fn test_websocket_rejected_handshake() {
+    let client = chat_app(Arc::new(Broadcast::new()));
+
+    let response = client
+        .websocket_request(TestRequest::get("/chat?token=wrong"))
+        .await
+        .err()
+        .expect("handshake should be rejected");
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+}
@@ -0,0 +1,387 @@
+//! # Batched Operations
+//!
+//! `ToonBatch<T>` carries a homogeneous collection as a single TOON tabular
+//! array, where field names are written once in the header and every row is
+//! one line:
+//!
+//! ```text
+//! users[3]{id,name,email}:
+//!   1,Alice,alice@example.com
+//!   2,Bob,bob@example.com
+//!   3,Carol,carol@example.com
+//! ```
+//!
+//! As an extractor it requires a tabular array (at the root or under a single
+//! key) and checks the row count declared in the header against the rows
+//! actually sent, so truncated uploads are rejected instead of silently
+//! processing a partial batch. Rows that do not match `T` are reported with
+//! their index (`users[1].email`).
+//!
+//! ## Example
+//!
+//! ```rust,ignore
+//! use rustapi_rs::prelude::*;
+//! use rustapi_rs::toon::ToonBatch;
+//!
+//! #[derive(Deserialize, Serialize, Schema)]
+//! struct User {
+//!     id: u64,
+//!     name: String,
+//! }
+//!
+//! async fn import_users(batch: ToonBatch<User>) -> ToonBatch<User> {
+//!     let created = db.insert_all(batch.items).await;
+//!     ToonBatch::new(created).key("users")
+//! }
+//! ```
+
+use crate::error::{deserialize_field_error, serde_field};
+use crate::extractor::{decode_error, read_toon_body};
+use crate::openapi::ToonSchema;
+use crate::{ToonError, TOON_CONTENT_TYPE};
+use http::{header, StatusCode};
+use rustapi_core::{ApiError, FieldError, FromRequest, IntoResponse, Request, Response, Result};
+use rustapi_openapi::{OpenApiSpec, Operation, OperationModifier, ResponseModifier, ResponseSpec};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::ops::{Deref, DerefMut};
+
+/// Batch request/response encoded as a TOON tabular array.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ToonBatch<T> {
+    /// The rows of the batch
+    pub items: Vec<T>,
+    /// Root key of the array (`users[N]{...}:`), or `None` for a root array
+    pub key: Option<String>,
+}
+
+impl<T> ToonBatch<T> {
+    /// Create a batch encoded as a root array (`[N]{...}:`)
+    pub fn new(items: Vec<T>) -> Self {
+        Self { items, key: None }
+    }
+
+    /// Encode the array under a root key (`key[N]{...}:`)
+    pub fn key(mut self, key: impl Into<String>) -> Self {
+        self.key = Some(key.into());
+        self
+    }
+
+    /// Consume the batch, returning its rows
+    pub fn into_inner(self) -> Vec<T> {
+        self.items
+    }
+}
+
+impl<T> Deref for ToonBatch<T> {
+    type Target = Vec<T>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.items
+    }
+}
+
+impl<T> DerefMut for ToonBatch<T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.items
+    }
+}
+
+impl<T> From<Vec<T>> for ToonBatch<T> {
+    fn from(items: Vec<T>) -> Self {
+        Self::new(items)
+    }
+}
+
+impl<T> IntoIterator for ToonBatch<T> {
+    type Item = T;
+    type IntoIter = std::vec::IntoIter<T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.items.into_iter()
+    }
+}
+
+/// Header of a tabular array: `key[N<delim>]{fields}:`.
+struct TableHeader {
+    key: Option<String>,
+    declared: usize,
+    indent: usize,
+    line: usize,
+}
+
+/// Find and parse the tabular array header (the first non-blank line).
+fn parse_header(input: &str) -> std::result::Result<TableHeader, ApiError> {
+    let not_tabular = || {
+        ApiError::bad_request(
+            "Expected a TOON tabular array (`key[N]{fields}:` followed by one row per line)",
+        )
+    };
+
+    let (index, raw) = input
+        .lines()
+        .enumerate()
+        .find(|(_, line)| !line.trim().is_empty())
+        .ok_or_else(not_tabular)?;
+    let line = raw.trim();
+
+    let fields_start = line.find("]{").ok_or_else(not_tabular)?;
+    if !line.ends_with("}:") {
+        return Err(not_tabular());
+    }
+    let head = &line[..fields_start];
+    let bracket = head.rfind('[').ok_or_else(not_tabular)?;
+
+    let declared = head[bracket + 1..]
+        .trim_start_matches('#')
+        .trim_end_matches(['|', '\t', ','])
+        .parse::<usize>()
+        .map_err(|_| not_tabular())?;
+    let key = match head[..bracket].trim() {
+        "" => None,
+        key => Some(key.trim_matches('"').to_string()),
+    };
+
+    Ok(TableHeader {
+        key,
+        declared,
+        indent: raw.len() - raw.trim_start().len(),
+        line: index + 1,
+    })
+}
+
+/// Count the row lines following the header (lines indented deeper than it).
+fn count_rows(input: &str, header: &TableHeader) -> usize {
+    input
+        .lines()
+        .skip(header.line)
+        .filter(|line| !line.trim().is_empty())
+        .take_while(|line| line.len() - line.trim_start().len() > header.indent)
+        .count()
+}
+
+impl<T: DeserializeOwned + Send> FromRequest for ToonBatch<T> {
+    async fn from_request(req: &mut Request) -> Result<Self> {
+        let (body, config) = read_toon_body(req).await?;
+
+        let header = parse_header(&body)?;
+        let label = header.key.clone().unwrap_or_default();
+        let found = count_rows(&body, &header);
+        if found != header.declared {
+            return Err(ApiError::validation(vec![FieldError {
+                field: if label.is_empty() {
+                    "body".to_string()
+                } else {
+                    label
+                },
+                code: "row_count".to_string(),
+                message: format!(
+                    "Header declares {} rows but {} were sent",
+                    header.declared, found
+                ),
+            }]));
+        }
+
+        let value: serde_json::Value = toon_format::decode(&body, &config.decode_options)
+            .map_err(|e| decode_error(&body, e))?;
+        let rows = match (value, &header.key) {
+            (serde_json::Value::Array(rows), None) => rows,
+            (serde_json::Value::Object(mut object), Some(key)) if object.len() == 1 => {
+                match object.remove(key) {
+                    Some(serde_json::Value::Array(rows)) => rows,
+                    _ => return Err(ApiError::bad_request("Batch must contain a single array")),
+                }
+            }
+            _ => return Err(ApiError::bad_request("Batch must contain a single array")),
+        };
+
+        let mut items = Vec::with_capacity(rows.len());
+        let mut errors = Vec::new();
+        for (index, row) in rows.into_iter().enumerate() {
+            match serde_json::from_value::<T>(row) {
+                Ok(item) => items.push(item),
+                Err(e) => {
+                    let message = e.to_string();
+                    let row_field = format!("{}[{}]", label, index);
+                    let field = match serde_field(&message) {
+                        Some(name) => format!("{}.{}", row_field, name),
+                        None => row_field,
+                    };
+                    errors.push(deserialize_field_error(field, message));
+                }
+            }
+        }
+        if !errors.is_empty() {
+            return Err(ApiError::validation(errors));
+        }
+
+        Ok(Self {
+            items,
+            key: header.key,
+        })
+    }
+}
+
+impl<T: Serialize> IntoResponse for ToonBatch<T> {
+    fn into_response(self) -> Response {
+        let encoded = match &self.key {
+            Some(key) => toon_format::encode_default(&BTreeMap::from([(key, &self.items)])),
+            None => toon_format::encode_default(&self.items),
+        };
+        match encoded {
+            Ok(body) => http::Response::builder()
+                .status(StatusCode::OK)
+                .header(header::CONTENT_TYPE, TOON_CONTENT_TYPE)
+                .body(rustapi_core::ResponseBody::from(body))
+                .unwrap(),
+            Err(err) => ApiError::from(ToonError::from(err)).into_response(),
+        }
+    }
+}
+
+impl<T: ToonSchema + Send> OperationModifier for ToonBatch<T>
+where
+    Vec<T>: ToonSchema,
+{
+    fn update_operation(op: &mut Operation) {
+        let mut content = BTreeMap::new();
+        content.insert(TOON_CONTENT_TYPE.to_string(), <Vec<T>>::toon_media_type());
+
+        op.request_body = Some(rustapi_openapi::RequestBody {
+            description: Some(
+                "Batch of rows as a TOON tabular array; the declared row count must match"
+                    .to_string(),
+            ),
+            required: Some(true),
+            content,
+        });
+    }
+
+    fn register_components(spec: &mut OpenApiSpec) {
+        <T as ToonSchema>::register_components(spec);
+    }
+}
+
+impl<T: ToonSchema + Serialize> ResponseModifier for ToonBatch<T>
+where
+    Vec<T>: ToonSchema,
+{
+    fn update_response(op: &mut Operation) {
+        let mut content = BTreeMap::new();
+        content.insert(TOON_CONTENT_TYPE.to_string(), <Vec<T>>::toon_media_type());
+
+        let response = ResponseSpec {
+            description: "Batch of rows as a TOON tabular array".to_string(),
+            content,
+            headers: BTreeMap::new(),
+        };
+        op.responses.insert("200".to_string(), response);
+    }
+
+    fn register_components(spec: &mut OpenApiSpec) {
+        <T as ToonSchema>::register_components(spec);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bytes::Bytes;
+    use http_body_util::BodyExt;
+    use rustapi_core::{BodyVariant, PathParams};
+    use serde::Deserialize;
+    use std::sync::Arc;
+
+    #[derive(Debug, Serialize, Deserialize, PartialEq)]
+    struct User {
+        id: u64,
+        name: String,
+    }
+
+    fn request(body: &'static str) -> Request {
+        let (parts, _) = http::Request::builder()
+            .header(header::CONTENT_TYPE, TOON_CONTENT_TYPE)
+            .body(())
+            .unwrap()
+            .into_parts();
+        Request::new(
+            parts,
+            BodyVariant::Buffered(Bytes::from_static(body.as_bytes())),
+            Arc::new(http::Extensions::new()),
+            PathParams::new(),
+        )
+    }
+
+    #[tokio::test]
+    async fn test_extracts_keyed_batch() {
+        let batch = ToonBatch::<User>::from_request(&mut request(
+            "users[2]{id,name}:\n  1,Alice\n  2,Bob\n",
+        ))
+        .await
+        .unwrap();
+        assert_eq!(batch.key.as_deref(), Some("users"));
+        assert_eq!(batch.len(), 2);
+        assert_eq!(batch[1].name, "Bob");
+    }
+
+    #[tokio::test]
+    async fn test_row_count_mismatch_is_rejected() {
+        let err =
+            ToonBatch::<User>::from_request(&mut request("users[3]{id,name}:\n  1,Alice\n  2,Bob"))
+                .await
+                .unwrap_err();
+        assert_eq!(err.status, StatusCode::UNPROCESSABLE_ENTITY);
+        let fields = err.fields.unwrap();
+        assert_eq!(fields[0].field, "users");
+        assert_eq!(fields[0].code, "row_count");
+        assert!(fields[0].message.contains("declares 3 rows but 2"));
+
+        let err =
+            ToonBatch::<User>::from_request(&mut request("[1]{id,name}:\n  1,Alice\n  2,Bob"))
+                .await
+                .unwrap_err();
+        assert_eq!(err.fields.unwrap()[0].code, "row_count");
+    }
+
+    #[tokio::test]
+    async fn test_non_tabular_body_is_rejected() {
+        let err = ToonBatch::<User>::from_request(&mut request("id: 1\nname: Alice"))
+            .await
+            .unwrap_err();
+        assert_eq!(err.status, StatusCode::BAD_REQUEST);
+    }
+
+    #[tokio::test]
+    async fn test_row_errors_carry_index() {
+        let err = ToonBatch::<User>::from_request(&mut request(
+            "users[2]{id,title}:\n  1,Alice\n  2,Bob",
+        ))
+        .await
+        .unwrap_err();
+        let fields = err.fields.unwrap();
+        assert_eq!(fields.len(), 2);
+        assert_eq!(fields[1].field, "users[1].name");
+        assert_eq!(fields[1].code, "required");
+    }
+
+    #[tokio::test]
+    async fn test_response_is_tabular() {
+        let batch = ToonBatch::new(vec![
+            User {
+                id: 1,
+                name: "Alice".to_string(),
+            },
+            User {
+                id: 2,
+                name: "Bob".to_string(),
+            },
+        ])
+        .key("users");
+        let response = batch.into_response();
+        assert_eq!(response.headers()[header::CONTENT_TYPE], TOON_CONTENT_TYPE);
+
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        assert_eq!(body, "users[2]{id,name}:\n  1,Alice\n  2,Bob");
+    }
+}
@@ -1,6 +1,7 @@
 //! TOON Error types and conversions
 
-use rustapi_core::ApiError;
+use http::StatusCode;
+use rustapi_core::{ApiError, FieldError};
 use std::fmt;
 
 /// Error type for TOON operations
@@ -10,10 +11,30 @@ pub enum ToonError {
     Encode(String),
     /// Error during TOON decoding (parsing/deserialization)
     Decode(String),
+    /// Malformed TOON document at a known position (1-based)
+    Syntax {
+        /// Line of the error
+        line: usize,
+        /// Column of the error
+        column: usize,
+        /// What went wrong
+        message: String,
+    },
+    /// Well-formed TOON that does not match the target type
+    Deserialize {
+        /// Offending field, when known
+        field: Option<String>,
+        /// Serde error message
+        message: String,
+    },
+    /// Request body exceeds the configured limit (in bytes)
+    PayloadTooLarge(usize),
     /// Invalid content type for TOON request
     InvalidContentType,
     /// Empty body provided
     EmptyBody,
+    /// Tokenizer could not be loaded
+    Tokenizer(String),
 }
 
 impl fmt::Display for ToonError {
@@ -21,11 +42,27 @@ impl fmt::Display for ToonError {
         match self {
             Self::Encode(msg) => write!(f, "TOON encoding error: {}", msg),
             Self::Decode(msg) => write!(f, "TOON decoding error: {}", msg),
+            Self::Syntax {
+                line,
+                column,
+                message,
+            } => write!(
+                f,
+                "TOON syntax error at line {}, column {}: {}",
+                line, column, message
+            ),
+            Self::Deserialize { message, .. } => {
+                write!(f, "TOON deserialization error: {}", message)
+            }
+            Self::PayloadTooLarge(limit) => {
+                write!(f, "Request body exceeds limit of {} bytes", limit)
+            }
             Self::InvalidContentType => write!(
                 f,
                 "Invalid content type: expected application/toon or text/toon"
             ),
             Self::EmptyBody => write!(f, "Empty request body"),
+            Self::Tokenizer(msg) => write!(f, "Tokenizer error: {}", msg),
         }
     }
 }
@@ -34,8 +71,32 @@ impl std::error::Error for ToonError {}
 
 impl From<toon_format::ToonError> for ToonError {
     fn from(err: toon_format::ToonError) -> Self {
-        match &err {
+        match err {
             toon_format::ToonError::SerializationError(_) => ToonError::Encode(err.to_string()),
+            toon_format::ToonError::ParseError {
+                line,
+                column,
+                message,
+                context,
+            } => {
+                let message = match context.and_then(|c| c.suggestion) {
+                    Some(suggestion) => format!("{} ({})", message, suggestion),
+                    None => message,
+                };
+                ToonError::Syntax {
+                    line,
+                    column,
+                    message,
+                }
+            }
+            toon_format::ToonError::DeserializationError(message) => ToonError::Deserialize {
+                field: serde_field(&message),
+                message,
+            },
+            toon_format::ToonError::TypeMismatch { .. } => ToonError::Deserialize {
+                field: None,
+                message: err.to_string(),
+            },
             _ => ToonError::Decode(err.to_string()),
         }
     }
@@ -46,10 +107,62 @@ impl From<ToonError> for ApiError {
         match err {
             ToonError::Encode(msg) => ApiError::internal(format!("Failed to encode TOON: {}", msg)),
             ToonError::Decode(msg) => ApiError::bad_request(format!("Invalid TOON: {}", msg)),
+            ToonError::Syntax {
+                line,
+                column,
+                message,
+            } => {
+                let mut error = ApiError::bad_request(format!(
+                    "Invalid TOON at line {}, column {}: {}",
+                    line, column, message
+                ));
+                error.fields = Some(vec![FieldError {
+                    field: format!("body:{}:{}", line, column),
+                    code: "syntax".to_string(),
+                    message,
+                }]);
+                error
+            }
+            ToonError::Deserialize { field, message } => {
+                ApiError::validation(vec![deserialize_field_error(
+                    field.unwrap_or_else(|| "body".to_string()),
+                    message,
+                )])
+            }
+            ToonError::PayloadTooLarge(limit) => ApiError::new(
+                StatusCode::PAYLOAD_TOO_LARGE,
+                "payload_too_large",
+                format!("Request body exceeds limit of {} bytes", limit),
+            ),
             ToonError::InvalidContentType => ApiError::bad_request(
                 "Invalid content type: expected application/toon or text/toon",
             ),
             ToonError::EmptyBody => ApiError::bad_request("Empty request body"),
+            ToonError::Tokenizer(msg) => ApiError::internal(format!("Tokenizer error: {}", msg)),
         }
     }
 }
+
+/// Field error for a serde deserialization message, classified by its wording.
+pub(crate) fn deserialize_field_error(field: String, message: String) -> FieldError {
+    let code = if message.starts_with("missing field") {
+        "required"
+    } else if message.starts_with("unknown field") {
+        "unknown_field"
+    } else {
+        "invalid_type"
+    };
+    FieldError {
+        field,
+        code: code.to_string(),
+        message,
+    }
+}
+
+/// Field named in serde's "missing field `x`" / "unknown field `x`" messages.
+pub(crate) fn serde_field(message: &str) -> Option<String> {
+    let rest = message
+        .strip_prefix("missing field `")
+        .or_else(|| message.strip_prefix("unknown field `"))?;
+    rest.split('`').next().map(str::to_string)
+}
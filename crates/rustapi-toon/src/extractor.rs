@@ -1,16 +1,19 @@
 //! TOON extractor and response types
 
 use crate::error::ToonError;
+use crate::openapi::ToonSchema;
 use crate::{TOON_CONTENT_TYPE, TOON_CONTENT_TYPE_TEXT};
+use bytes::{Bytes, BytesMut};
+use futures_util::StreamExt;
 use http::{header, StatusCode};
-use rustapi_core::{ApiError, FromRequest, IntoResponse, Request, Response, Result};
-use rustapi_openapi::{
-    MediaType, Operation, OperationModifier, ResponseModifier, ResponseSpec, SchemaRef,
-};
+use rustapi_core::middleware::DEFAULT_BODY_LIMIT;
+use rustapi_core::{ApiError, FromRequest, IntoResponse, Request, Response, Result, StreamingBody};
+use rustapi_openapi::{OpenApiSpec, Operation, OperationModifier, ResponseModifier, ResponseSpec};
 use serde::de::DeserializeOwned;
 use serde::Serialize;
 use std::collections::BTreeMap;
 use std::ops::{Deref, DerefMut};
+use toon_format::DecodeOptions;
 
 /// TOON body extractor and response type
 ///
@@ -24,13 +27,18 @@ use std::ops::{Deref, DerefMut};
 /// - `application/toon`
 /// - `text/toon`
 ///
+/// Bodies larger than [`ToonConfig::max_body_size`] are rejected with
+/// `413 Payload Too Large`. Malformed documents return `400 Bad Request` with
+/// the line and column of the error; documents that do not match `T` (missing
+/// fields, wrong types) return `422 Unprocessable Entity` with field details.
+///
 /// # Example - Extractor
 ///
 /// ```rust,ignore
 /// use rustapi_rs::prelude::*;
 /// use rustapi_rs::toon::Toon;
 ///
-/// #[derive(Deserialize)]
+/// #[derive(Deserialize, Schema)]
 /// struct CreateUser {
 ///     name: String,
 ///     email: String,
@@ -48,7 +56,7 @@ use std::ops::{Deref, DerefMut};
 /// use rustapi_rs::prelude::*;
 /// use rustapi_rs::toon::Toon;
 ///
-/// #[derive(Serialize)]
+/// #[derive(Serialize, Schema)]
 /// struct User {
 ///     id: u64,
 ///     name: String,
@@ -66,34 +74,142 @@ pub struct Toon<T>(pub T);
 
 impl<T: DeserializeOwned + Send> FromRequest for Toon<T> {
     async fn from_request(req: &mut Request) -> Result<Self> {
-        // Check content type (optional - if provided, must be toon)
-        if let Some(content_type) = req.headers().get(header::CONTENT_TYPE) {
-            let content_type_str = content_type.to_str().unwrap_or("");
-            let is_toon = content_type_str.starts_with(TOON_CONTENT_TYPE)
-                || content_type_str.starts_with(TOON_CONTENT_TYPE_TEXT);
-
-            if !is_toon && !content_type_str.is_empty() {
-                return Err(ToonError::InvalidContentType.into());
-            }
+        let (body, config) = read_toon_body(req).await?;
+
+        let value: T = toon_format::decode(&body, &config.decode_options)
+            .map_err(|e| decode_error(&body, e))?;
+
+        Ok(Toon(value))
+    }
+}
+
+/// Check the content type and read a TOON request body as text, applying
+/// the [`ToonConfig`] found in app state.
+pub(crate) async fn read_toon_body(req: &mut Request) -> Result<(String, ToonConfig)> {
+    // Check content type (optional - if provided, must be toon)
+    if let Some(content_type) = req.headers().get(header::CONTENT_TYPE) {
+        let content_type_str = content_type.to_str().unwrap_or("");
+        let is_toon = content_type_str.starts_with(TOON_CONTENT_TYPE)
+            || content_type_str.starts_with(TOON_CONTENT_TYPE_TEXT);
+
+        if !is_toon && !content_type_str.is_empty() {
+            return Err(ToonError::InvalidContentType.into());
         }
+    }
+
+    let config = req.state().get::<ToonConfig>().cloned().unwrap_or_default();
+
+    let body = read_body(req, config.max_body_size).await?;
+    if body.is_empty() {
+        return Err(ToonError::EmptyBody.into());
+    }
+
+    let body =
+        String::from_utf8(body.to_vec()).map_err(|e| ApiError::bad_request(e.to_string()))?;
+    Ok((body, config))
+}
 
-        // Get body bytes
-        let body = req
-            .take_body()
-            .ok_or_else(|| ApiError::internal("Body already consumed"))?;
+/// Buffer the request body, rejecting it once it grows past `limit` bytes.
+async fn read_body(req: &mut Request, limit: usize) -> Result<Bytes> {
+    let too_large = || ApiError::from(ToonError::PayloadTooLarge(limit));
 
-        if body.is_empty() {
-            return Err(ToonError::EmptyBody.into());
+    let declared = req
+        .headers()
+        .get(header::CONTENT_LENGTH)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.parse::<usize>().ok());
+    if declared.is_some_and(|len| len > limit) {
+        return Err(too_large());
+    }
+
+    if let Some(incoming) = req.take_stream() {
+        let mut stream = StreamingBody::new(incoming, Some(limit));
+        let mut body = BytesMut::new();
+        while let Some(chunk) = stream.next().await {
+            body.extend_from_slice(&chunk?);
         }
+        return Ok(body.freeze());
+    }
 
-        // Parse TOON
-        let body_str =
-            std::str::from_utf8(&body).map_err(|e| ApiError::bad_request(e.to_string()))?;
+    let body = req
+        .take_body()
+        .ok_or_else(|| ApiError::internal("Body already consumed"))?;
+    if body.len() > limit {
+        return Err(too_large());
+    }
+    Ok(body)
+}
 
-        let value: T =
-            toon_format::decode_default(body_str).map_err(|e| ToonError::Decode(e.to_string()))?;
+/// Map a decode failure to an API error, locating errors that only carry a
+/// position (or none, for a truncated document) in the input.
+pub(crate) fn decode_error(input: &str, err: toon_format::ToonError) -> ApiError {
+    let (line, column) = match &err {
+        toon_format::ToonError::UnexpectedEof => end_position(input),
+        toon_format::ToonError::InvalidCharacter { position, .. } => {
+            let prefix: String = input.chars().take(*position).collect();
+            end_position(&prefix)
+        }
+        _ => return ToonError::from(err).into(),
+    };
+    ToonError::Syntax {
+        line,
+        column,
+        message: err.to_string(),
+    }
+    .into()
+}
 
-        Ok(Toon(value))
+/// 1-based line and column just past the end of `text`.
+fn end_position(text: &str) -> (usize, usize) {
+    let line = text.split('\n').count();
+    let column = text.rsplit('\n').next().map_or(0, |l| l.chars().count()) + 1;
+    (line, column)
+}
+
+/// Configuration for the [`Toon`] extractor
+///
+/// Register it as app state to override the defaults:
+///
+/// ```rust,ignore
+/// use rustapi_rs::toon::{DecodeOptions, ToonConfig};
+///
+/// RustApi::new()
+///     .state(ToonConfig::new().max_body_size(256 * 1024))
+///     .route("/items", post(create_item));
+/// ```
+#[derive(Debug, Clone)]
+pub struct ToonConfig {
+    /// Maximum request body size in bytes (default: 1MB)
+    pub max_body_size: usize,
+    /// Options passed to the TOON decoder (default: strict mode)
+    pub decode_options: DecodeOptions,
+}
+
+impl Default for ToonConfig {
+    fn default() -> Self {
+        Self {
+            max_body_size: DEFAULT_BODY_LIMIT,
+            decode_options: DecodeOptions::default(),
+        }
+    }
+}
+
+impl ToonConfig {
+    /// Create a new config with default values
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the maximum request body size
+    pub fn max_body_size(mut self, size: usize) -> Self {
+        self.max_body_size = size;
+        self
+    }
+
+    /// Set the decoder options
+    pub fn decode_options(mut self, options: DecodeOptions) -> Self {
+        self.decode_options = options;
+        self
     }
 }
 
@@ -134,42 +250,30 @@ impl<T: Serialize> IntoResponse for Toon<T> {
 }
 
 // OpenAPI support: OperationModifier for Toon extractor
-impl<T: Send> OperationModifier for Toon<T> {
+impl<T: ToonSchema + Send> OperationModifier for Toon<T> {
     fn update_operation(op: &mut Operation) {
         let mut content = BTreeMap::new();
-        content.insert(
-            TOON_CONTENT_TYPE.to_string(),
-            MediaType {
-                schema: Some(SchemaRef::Inline(serde_json::json!({
-                    "type": "string",
-                    "description": "TOON (Token-Oriented Object Notation) formatted request body"
-                }))),
-                example: None,
-            },
-        );
+        content.insert(TOON_CONTENT_TYPE.to_string(), T::toon_media_type());
 
         op.request_body = Some(rustapi_openapi::RequestBody {
-            description: None,
+            description: Some(
+                "TOON (Token-Oriented Object Notation) formatted request body".to_string(),
+            ),
             required: Some(true),
             content,
         });
     }
+
+    fn register_components(spec: &mut OpenApiSpec) {
+        <T as ToonSchema>::register_components(spec);
+    }
 }
 
 // OpenAPI support: ResponseModifier for Toon response
-impl<T: Serialize> ResponseModifier for Toon<T> {
+impl<T: ToonSchema + Serialize> ResponseModifier for Toon<T> {
     fn update_response(op: &mut Operation) {
         let mut content = BTreeMap::new();
-        content.insert(
-            TOON_CONTENT_TYPE.to_string(),
-            MediaType {
-                schema: Some(SchemaRef::Inline(serde_json::json!({
-                    "type": "string",
-                    "description": "TOON (Token-Oriented Object Notation) formatted response"
-                }))),
-                example: None,
-            },
-        );
+        content.insert(TOON_CONTENT_TYPE.to_string(), T::toon_media_type());
 
         let response = ResponseSpec {
             description: "TOON formatted response - token-optimized for LLMs".to_string(),
@@ -178,11 +282,18 @@ impl<T: Serialize> ResponseModifier for Toon<T> {
         };
         op.responses.insert("200".to_string(), response);
     }
+
+    fn register_components(spec: &mut OpenApiSpec) {
+        <T as ToonSchema>::register_components(spec);
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    use serde::{Deserialize, Serialize};
+    use super::*;
+    use rustapi_core::{BodyVariant, PathParams};
+    use serde::Deserialize;
+    use std::sync::Arc;
 
     #[derive(Debug, Serialize, Deserialize, PartialEq)]
     struct User {
@@ -225,4 +336,78 @@ mod tests {
 
         assert_eq!(original, decoded);
     }
+
+    fn toon_request(body: &'static str, config: Option<ToonConfig>) -> Request {
+        let (parts, _) = http::Request::builder()
+            .header(header::CONTENT_TYPE, TOON_CONTENT_TYPE)
+            .body(())
+            .unwrap()
+            .into_parts();
+        let mut state = http::Extensions::new();
+        if let Some(config) = config {
+            state.insert(config);
+        }
+        Request::new(
+            parts,
+            BodyVariant::Buffered(Bytes::from_static(body.as_bytes())),
+            Arc::new(state),
+            PathParams::new(),
+        )
+    }
+
+    #[tokio::test]
+    async fn test_extractor_enforces_body_limit() {
+        let config = ToonConfig::new().max_body_size(8);
+        let err =
+            Toon::<User>::from_request(&mut toon_request("name: Alice\nage: 30", Some(config)))
+                .await
+                .unwrap_err();
+        assert_eq!(err.status, StatusCode::PAYLOAD_TOO_LARGE);
+        assert_eq!(err.error_type, "payload_too_large");
+
+        let Toon(user) =
+            Toon::<User>::from_request(&mut toon_request("name: Alice\nage: 30", None))
+                .await
+                .unwrap();
+        assert_eq!(user.age, 30);
+    }
+
+    #[tokio::test]
+    async fn test_syntax_error_reports_position() {
+        let err = Toon::<Vec<String>>::from_request(&mut toon_request("[2]: a", None))
+            .await
+            .unwrap_err();
+        assert_eq!(err.status, StatusCode::BAD_REQUEST);
+        assert!(err.message.contains("line 1, column"), "{}", err.message);
+
+        let fields = err.fields.unwrap();
+        assert_eq!(fields[0].code, "syntax");
+        assert!(fields[0].field.starts_with("body:1:"));
+    }
+
+    #[tokio::test]
+    async fn test_unexpected_eof_reports_end_position() {
+        let err = Toon::<User>::from_request(&mut toon_request("age: 30\nname: \"Al", None))
+            .await
+            .unwrap_err();
+        assert_eq!(err.status, StatusCode::BAD_REQUEST);
+        assert_eq!(err.fields.unwrap()[0].field, "body:2:10");
+    }
+
+    #[tokio::test]
+    async fn test_type_mismatch_is_validation_error() {
+        let err = Toon::<User>::from_request(&mut toon_request("name: Alice", None))
+            .await
+            .unwrap_err();
+        assert_eq!(err.status, StatusCode::UNPROCESSABLE_ENTITY);
+        let fields = err.fields.unwrap();
+        assert_eq!(fields[0].field, "age");
+        assert_eq!(fields[0].code, "required");
+
+        let err = Toon::<User>::from_request(&mut toon_request("name: Alice\nage: old", None))
+            .await
+            .unwrap_err();
+        assert_eq!(err.status, StatusCode::UNPROCESSABLE_ENTITY);
+        assert_eq!(err.fields.unwrap()[0].code, "invalid_type");
+    }
 }
@@ -0,0 +1,212 @@
+//! # Pluggable Output Formats
+//!
+//! JSON and TOON are built in. Other crates can add formats (MessagePack,
+//! CBOR, YAML, ...) by implementing [`FormatEncoder`] and adding it to a
+//! [`FormatRegistry`] that is registered as application state. Registered
+//! formats then take part in everything that uses [`OutputFormat`]:
+//!
+//! - `AcceptHeader` negotiation (after JSON and TOON, in registration order)
+//! - `Negotiate<T>` and `LlmResponse<T>` encoding
+//! - `LlmResponse` token accounting (`X-Token-Count-<NAME>` for text formats)
+//!
+//! ```rust,ignore
+//! use rustapi_rs::toon::{FormatEncoder, FormatRegistry, ToonError};
+//! use std::sync::Arc;
+//!
+//! struct Yaml;
+//!
+//! impl FormatEncoder for Yaml {
+//!     fn name(&self) -> &'static str {
+//!         "yaml"
+//!     }
+//!
+//!     fn content_type(&self) -> &'static str {
+//!         "application/yaml"
+//!     }
+//!
+//!     fn media_types(&self) -> &'static [&'static str] {
+//!         &["application/x-yaml", "text/yaml"]
+//!     }
+//!
+//!     fn encode(&self, value: &serde_json::Value) -> Result<Vec<u8>, ToonError> {
+//!         serde_yaml::to_string(value)
+//!             .map(String::into_bytes)
+//!             .map_err(|e| ToonError::Encode(e.to_string()))
+//!     }
+//! }
+//!
+//! RustApi::new()
+//!     .state(FormatRegistry::new().register(Arc::new(Yaml)))
+//!     .route("/users", get(list_users))
+//!     .run("127.0.0.1:8080")
+//!     .await
+//! ```
+
+use crate::{OutputFormat, ToonError};
+use std::fmt;
+use std::sync::Arc;
+
+/// Encodes response data into an additional output format.
+///
+/// Data is handed over as a `serde_json::Value`, so any `Serialize` type can
+/// be encoded by any registered format.
+pub trait FormatEncoder: Send + Sync {
+    /// Short, unique format name (e.g. `msgpack`), reported in `X-Format-Used`
+    fn name(&self) -> &'static str;
+
+    /// Media type sent as `Content-Type`
+    fn content_type(&self) -> &'static str;
+
+    /// Additional media types that select this format in `Accept` headers
+    fn media_types(&self) -> &'static [&'static str] {
+        &[]
+    }
+
+    /// Encode a value into the response body
+    fn encode(&self, value: &serde_json::Value) -> Result<Vec<u8>, ToonError>;
+}
+
+/// A registered format, carried by [`OutputFormat::Custom`].
+///
+/// Two custom formats are equal when their names are.
+#[derive(Clone)]
+pub struct CustomFormat(Arc<dyn FormatEncoder>);
+
+impl CustomFormat {
+    /// Wrap an encoder.
+    pub fn new(encoder: Arc<dyn FormatEncoder>) -> Self {
+        Self(encoder)
+    }
+
+    /// The encoder producing this format.
+    pub fn encoder(&self) -> &Arc<dyn FormatEncoder> {
+        &self.0
+    }
+}
+
+impl fmt::Debug for CustomFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("CustomFormat").field(&self.0.name()).finish()
+    }
+}
+
+impl PartialEq for CustomFormat {
+    fn eq(&self, other: &Self) -> bool {
+        self.0.name() == other.0.name()
+    }
+}
+
+impl Eq for CustomFormat {}
+
+/// The output formats an application can produce besides JSON and TOON.
+///
+/// Register it as state (`RustApi::new().state(registry)`); the
+/// `AcceptHeader` extractor reads it from there. Without it only JSON and
+/// TOON are offered.
+#[derive(Clone, Default)]
+pub struct FormatRegistry {
+    formats: Vec<CustomFormat>,
+}
+
+impl FormatRegistry {
+    /// Create a registry with only the built-in formats.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add an output format; a format with the same name is replaced.
+    ///
+    /// `json` and `toon` are built in and cannot be replaced.
+    pub fn register(mut self, encoder: Arc<dyn FormatEncoder>) -> Self {
+        let name = encoder.name();
+        if name.eq_ignore_ascii_case("json") || name.eq_ignore_ascii_case("toon") {
+            tracing::warn!("Ignoring registration of built-in format '{}'", name);
+            return self;
+        }
+
+        let format = CustomFormat::new(encoder);
+        match self.formats.iter_mut().find(|f| f.0.name() == name) {
+            Some(existing) => *existing = format,
+            None => self.formats.push(format),
+        }
+        self
+    }
+
+    /// Look up a format by name, built-in formats included.
+    pub fn find(&self, name: &str) -> Option<OutputFormat> {
+        self.formats().into_iter().find(|f| f.name() == name)
+    }
+
+    /// All formats the server can produce: JSON, TOON, then registered formats.
+    pub fn formats(&self) -> Vec<OutputFormat> {
+        let mut formats = vec![OutputFormat::Json, OutputFormat::Toon];
+        formats.extend(self.formats.iter().cloned().map(OutputFormat::Custom));
+        formats
+    }
+}
+
+impl fmt::Debug for FormatRegistry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list()
+            .entries(self.formats.iter().map(|format| format.0.name()))
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Lines;
+
+    impl FormatEncoder for Lines {
+        fn name(&self) -> &'static str {
+            "lines"
+        }
+
+        fn content_type(&self) -> &'static str {
+            "text/x-lines"
+        }
+
+        fn encode(&self, value: &serde_json::Value) -> Result<Vec<u8>, ToonError> {
+            Ok(value.to_string().into_bytes())
+        }
+    }
+
+    #[test]
+    fn test_register_and_find_format() {
+        let registry = FormatRegistry::new().register(Arc::new(Lines));
+        let format = registry.find("lines").unwrap();
+        assert_eq!(format.content_type(), "text/x-lines");
+        assert_eq!(registry.find("toon"), Some(OutputFormat::Toon));
+
+        let formats = registry.formats();
+        assert_eq!(&formats[..2], &[OutputFormat::Json, OutputFormat::Toon]);
+        assert_eq!(formats.len(), 3);
+
+        // Registries are independent of each other
+        assert!(FormatRegistry::new().find("lines").is_none());
+    }
+
+    #[test]
+    fn test_builtin_formats_cannot_be_replaced() {
+        struct FakeJson;
+
+        impl FormatEncoder for FakeJson {
+            fn name(&self) -> &'static str {
+                "json"
+            }
+
+            fn content_type(&self) -> &'static str {
+                "text/plain"
+            }
+
+            fn encode(&self, _value: &serde_json::Value) -> Result<Vec<u8>, ToonError> {
+                Ok(Vec::new())
+            }
+        }
+
+        let registry = FormatRegistry::new().register(Arc::new(FakeJson));
+        assert_eq!(registry.formats().len(), 2);
+    }
+}
@@ -41,7 +41,7 @@
 //! use rustapi_rs::prelude::*;
 //! use rustapi_rs::toon::Toon;
 //!
-//! #[derive(Deserialize)]
+//! #[derive(Deserialize, Schema)]
 //! struct CreateUser {
 //!     name: String,
 //!     email: String,
@@ -60,7 +60,7 @@
 //! use rustapi_rs::prelude::*;
 //! use rustapi_rs::toon::Toon;
 //!
-//! #[derive(Serialize)]
+//! #[derive(Serialize, Schema)]
 //! struct User {
 //!     id: u64,
 //!     name: String,
@@ -74,26 +74,83 @@
 //! }
 //! ```
 //!
+//! ### Streaming Responses
+//!
+//! Encode large collections row by row with `ToonStream`:
+//!
+//! ```rust,ignore
+//! use rustapi_rs::toon::ToonStream;
+//!
+//! async fn export() -> ToonStream<impl Stream<Item = Result<User, DbError>>> {
+//!     ToonStream::new(db.stream_users(), db.count_users().await).key("users")
+//! }
+//! ```
+//!
+//! ### Batches
+//!
+//! Send and receive homogeneous collections as tabular arrays with
+//! `ToonBatch`; the extractor checks the declared row count:
+//!
+//! ```rust,ignore
+//! use rustapi_rs::toon::ToonBatch;
+//!
+//! async fn import(batch: ToonBatch<User>) -> ToonBatch<User> {
+//!     ToonBatch::new(save_all(batch.items).await).key("users")
+//! }
+//! ```
+//!
+//! ### Server-Sent Events
+//!
+//! Stream TOON-encoded events with `SseToon`:
+//!
+//! ```rust,ignore
+//! use rustapi_rs::toon::{SseToon, ToonEvent};
+//!
+//! async fn updates() -> SseToon<impl Stream<Item = Result<ToonEvent<Update>, Infallible>>> {
+//!     SseToon::new(feed().map(|u| Ok(ToonEvent::new(u).event("update"))))
+//! }
+//! ```
+//!
 //! ## Content Types
 //!
 //! - Request: `application/toon` or `text/toon`
 //! - Response: `application/toon`
 
+mod batch;
 mod error;
 mod extractor;
+mod format;
 mod llm_response;
 mod negotiate;
 mod openapi;
+mod sse;
+mod stream;
+pub mod tokenizer;
 
+pub use batch::ToonBatch;
 pub use error::ToonError;
-pub use extractor::Toon;
+pub use extractor::{Toon, ToonConfig};
+pub use format::{CustomFormat, FormatEncoder, FormatRegistry};
 pub use llm_response::{
     LlmResponse, X_FORMAT_USED, X_TOKEN_COUNT_JSON, X_TOKEN_COUNT_TOON, X_TOKEN_SAVINGS,
 };
-pub use negotiate::{AcceptHeader, MediaTypeEntry, Negotiate, OutputFormat, JSON_CONTENT_TYPE};
+pub use negotiate::{
+    negotiate_errors, AcceptHeader, MediaTypeEntry, Negotiate, NegotiatedErrors, OutputFormat,
+    JSON_CONTENT_TYPE,
+};
 pub use openapi::{
     api_description_with_toon, format_comparison_example, token_headers_schema, toon_extension,
-    toon_schema, TOON_FORMAT_DESCRIPTION,
+    toon_schema, ToonSchema, TOON_FORMAT_DESCRIPTION,
+};
+pub use sse::{SseToon, ToonEvent};
+pub use stream::ToonStream;
+#[cfg(feature = "hf-tokenizers")]
+pub use tokenizer::HfTokenizer;
+#[cfg(feature = "tiktoken")]
+pub use tokenizer::TiktokenTokenizer;
+pub use tokenizer::{
+    default_tokenizer, find_tokenizer, register_tokenizer, set_default_tokenizer,
+    HeuristicTokenizer, TargetTokenizer, Tokenizer, X_TOKENIZER,
 };
 
 // Re-export toon-format types for advanced usage
@@ -6,14 +6,19 @@
 //! ## Features
 //!
 //! - Automatic content negotiation (JSON vs TOON)
+//! - Smallest-format selection by token count (`LlmResponse::auto`)
 //! - Token counting headers
 //! - Token savings calculation
+//! - Pluggable tokenizers (see [`crate::tokenizer`])
 //!
 //! ## Response Headers
 //!
-//! - `X-Token-Count-JSON`: Estimated token count in JSON format
-//! - `X-Token-Count-TOON`: Estimated token count in TOON format
+//! - `X-Token-Count-JSON`: Token count in JSON format
+//! - `X-Token-Count-TOON`: Token count in TOON format
 //! - `X-Token-Savings`: Percentage of tokens saved with TOON
+//! - `X-Token-Count-<NAME>`: Token count in a [registered format](crate::FormatRegistry),
+//!   when that format is used and produces text
+//! - `X-Tokenizer`: Tokenizer used for the counts
 //!
 //! ## Example
 //!
@@ -21,7 +26,7 @@
 //! use rustapi_rs::prelude::*;
 //! use rustapi_rs::toon::{LlmResponse, AcceptHeader};
 //!
-//! #[derive(Serialize)]
+//! #[derive(Serialize, Schema)]
 //! struct ChatResponse {
 //!     messages: Vec<Message>,
 //! }
@@ -34,14 +39,16 @@
 //! }
 //! ```
 
-use crate::{OutputFormat, JSON_CONTENT_TYPE, TOON_CONTENT_TYPE};
+use crate::negotiate::serialization_error;
+use crate::openapi::{add_token_headers, negotiated_content, ToonSchema};
+use crate::tokenizer::{default_tokenizer, Tokenizer, X_TOKENIZER};
+use crate::{OutputFormat, ToonError};
 use http::{header, StatusCode};
-use rustapi_core::{ApiError, IntoResponse, Response};
-use rustapi_openapi::{
-    MediaType, Operation, OperationModifier, ResponseModifier, ResponseSpec, SchemaRef,
-};
+use rustapi_core::{IntoResponse, Response};
+use rustapi_openapi::{OpenApiSpec, Operation, OperationModifier, ResponseModifier, ResponseSpec};
 use serde::Serialize;
 use std::collections::BTreeMap;
+use std::sync::Arc;
 
 /// Header name for JSON token count
 pub const X_TOKEN_COUNT_JSON: &str = "x-token-count-json";
@@ -59,12 +66,11 @@ pub const X_FORMAT_USED: &str = "x-format-used";
 /// 2. Calculates estimated token counts for both formats
 /// 3. Adds informative headers about token usage
 ///
-/// ## Token Estimation
+/// ## Token Counting
 ///
-/// Token counts are estimated using a simple heuristic:
-/// - ~4 characters per token (GPT-3/4 average)
-///
-/// For more accurate counts, use a proper tokenizer.
+/// Tokens are counted with the [default tokenizer](crate::default_tokenizer),
+/// which estimates ~4 characters per token unless replaced. Use
+/// [`with_tokenizer`](Self::with_tokenizer) to count for a specific model.
 ///
 /// ## Example
 ///
@@ -72,7 +78,7 @@ pub const X_FORMAT_USED: &str = "x-format-used";
 /// use rustapi_rs::prelude::*;
 /// use rustapi_rs::toon::{LlmResponse, AcceptHeader, OutputFormat};
 ///
-/// #[derive(Serialize)]
+/// #[derive(Serialize, Schema)]
 /// struct ApiData {
 ///     items: Vec<Item>,
 /// }
@@ -89,11 +95,26 @@ pub const X_FORMAT_USED: &str = "x-format-used";
 ///     LlmResponse::toon(data)
 /// }
 /// ```
-#[derive(Debug, Clone)]
+#[derive(Clone)]
 pub struct LlmResponse<T> {
     data: T,
     format: OutputFormat,
     include_token_headers: bool,
+    tokenizer: Option<Arc<dyn Tokenizer>>,
+    /// Minimum TOON savings (percent) for auto mode; `None` uses `format`
+    min_savings: Option<f64>,
+}
+
+impl<T: std::fmt::Debug> std::fmt::Debug for LlmResponse<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("LlmResponse")
+            .field("data", &self.data)
+            .field("format", &self.format)
+            .field("include_token_headers", &self.include_token_headers)
+            .field("tokenizer", &self.tokenizer.as_ref().map(|t| t.name()))
+            .field("min_savings", &self.min_savings)
+            .finish()
+    }
 }
 
 impl<T> LlmResponse<T> {
@@ -103,6 +124,21 @@ impl<T> LlmResponse<T> {
             data,
             format,
             include_token_headers: true,
+            tokenizer: None,
+            min_savings: None,
+        }
+    }
+
+    /// Create an LLM response that picks the format with fewer tokens.
+    ///
+    /// Both JSON and TOON are encoded and counted with the response's
+    /// tokenizer; TOON is used only when it saves at least the
+    /// [minimum savings](Self::with_min_savings) (0% by default, i.e. whenever
+    /// it is smaller). The decision is reported in `X-Format-Used`.
+    pub fn auto(data: T) -> Self {
+        Self {
+            min_savings: Some(0.0),
+            ..Self::json(data)
         }
     }
 
@@ -127,15 +163,23 @@ impl<T> LlmResponse<T> {
         self.include_token_headers = true;
         self
     }
-}
 
-/// Estimate token count using simple character-based heuristic.
-/// ~4 characters per token (GPT-3/4 average)
-fn estimate_tokens(text: &str) -> usize {
-    // Simple heuristic: ~4 chars per token
-    // Accounts for whitespace and punctuation overhead
-    let char_count = text.len();
-    char_count.div_ceil(4) // Round up
+    /// Minimum token savings (in percent) TOON must reach to be chosen in
+    /// [auto mode](Self::auto); below it the response falls back to JSON.
+    ///
+    /// Has no effect on responses with a fixed format.
+    pub fn with_min_savings(mut self, percent: f64) -> Self {
+        if self.min_savings.is_some() {
+            self.min_savings = Some(percent);
+        }
+        self
+    }
+
+    /// Count tokens with a specific tokenizer instead of the default one.
+    pub fn with_tokenizer(mut self, tokenizer: Arc<dyn Tokenizer>) -> Self {
+        self.tokenizer = Some(tokenizer);
+        self
+    }
 }
 
 /// Calculate token savings percentage.
@@ -152,64 +196,81 @@ impl<T: Serialize> IntoResponse for LlmResponse<T> {
         // Always serialize to both formats for token counting
         let json_result = serde_json::to_string(&self.data);
         let toon_result = toon_format::encode_default(&self.data);
+        let custom_result = match &self.format {
+            OutputFormat::Custom(_) => Some(self.format.encode(&self.data)),
+            _ => None,
+        };
 
-        // Calculate token counts if enabled
-        let (json_tokens, toon_tokens, savings) = if self.include_token_headers {
+        // Calculate token counts if enabled or needed to pick the format
+        let token_counts = if self.include_token_headers || self.min_savings.is_some() {
+            let tokenizer = self.tokenizer.unwrap_or_else(default_tokenizer);
             let json_tokens = json_result
                 .as_ref()
-                .map(|s| estimate_tokens(s))
+                .map(|s| tokenizer.count_tokens(s))
                 .unwrap_or(0);
             let toon_tokens = toon_result
                 .as_ref()
-                .map(|s| estimate_tokens(s))
+                .map(|s| tokenizer.count_tokens(s))
                 .unwrap_or(0);
+            // Registered formats are counted when their output is text
+            let custom_tokens = custom_result
+                .as_ref()
+                .and_then(|r| r.as_ref().ok())
+                .and_then(|body| std::str::from_utf8(body).ok())
+                .map(|text| tokenizer.count_tokens(text));
             let savings = calculate_savings(json_tokens, toon_tokens);
-            (Some(json_tokens), Some(toon_tokens), Some(savings))
+            Some((json_tokens, toon_tokens, custom_tokens, savings, tokenizer))
         } else {
-            (None, None, None)
+            None
+        };
+
+        // In auto mode, use TOON only if it is smaller by at least `min_savings`
+        let used = match (self.min_savings, &token_counts) {
+            (Some(min_savings), Some((json_tokens, toon_tokens, _, savings, _)))
+                if toon_result.is_ok() && toon_tokens < json_tokens && *savings >= min_savings =>
+            {
+                OutputFormat::Toon
+            }
+            (Some(_), _) => OutputFormat::Json,
+            (None, _) => self.format,
         };
 
         // Serialize to the requested format
-        let (body, content_type) = match self.format {
-            OutputFormat::Json => match json_result {
-                Ok(json) => (json, JSON_CONTENT_TYPE),
-                Err(e) => {
-                    tracing::error!("Failed to serialize to JSON: {}", e);
-                    return ApiError::internal(format!("JSON serialization error: {}", e))
-                        .into_response();
-                }
-            },
-            OutputFormat::Toon => match toon_result {
-                Ok(toon) => (toon, TOON_CONTENT_TYPE),
-                Err(e) => {
-                    tracing::error!("Failed to serialize to TOON: {}", e);
-                    return ApiError::internal(format!("TOON serialization error: {}", e))
-                        .into_response();
-                }
-            },
+        let body = match used {
+            OutputFormat::Json => json_result
+                .map(String::into_bytes)
+                .map_err(|e| ToonError::Encode(e.to_string())),
+            OutputFormat::Toon => toon_result.map(String::into_bytes).map_err(ToonError::from),
+            OutputFormat::Custom(_) => custom_result.unwrap_or_else(|| used.encode(&self.data)),
+        };
+        let body = match body {
+            Ok(body) => body,
+            Err(e) => return serialization_error(used, e).into_response(),
         };
 
         // Build response with headers
         let mut builder = http::Response::builder()
             .status(StatusCode::OK)
-            .header(header::CONTENT_TYPE, content_type)
-            .header(
-                X_FORMAT_USED,
-                match self.format {
-                    OutputFormat::Json => "json",
-                    OutputFormat::Toon => "toon",
-                },
-            );
+            .header(header::CONTENT_TYPE, used.content_type())
+            .header(X_FORMAT_USED, used.name());
 
         // Token counting headers
-        if let Some(json_tokens) = json_tokens {
-            builder = builder.header(X_TOKEN_COUNT_JSON, json_tokens.to_string());
-        }
-        if let Some(toon_tokens) = toon_tokens {
-            builder = builder.header(X_TOKEN_COUNT_TOON, toon_tokens.to_string());
-        }
-        if let Some(savings) = savings {
-            builder = builder.header(X_TOKEN_SAVINGS, format!("{:.2}%", savings));
+        let token_counts = token_counts.filter(|_| self.include_token_headers);
+        if let Some((json_tokens, toon_tokens, custom_tokens, savings, tokenizer)) = token_counts {
+            builder = builder
+                .header(X_TOKEN_COUNT_JSON, json_tokens.to_string())
+                .header(X_TOKEN_COUNT_TOON, toon_tokens.to_string())
+                .header(X_TOKEN_SAVINGS, format!("{:.2}%", savings));
+            if let Some(tokens) = custom_tokens {
+                let name = format!("x-token-count-{}", used.name());
+                if let Ok(name) = http::HeaderName::from_bytes(name.to_ascii_lowercase().as_bytes())
+                {
+                    builder = builder.header(name, tokens.to_string());
+                }
+            }
+            if let Ok(name) = http::HeaderValue::from_str(tokenizer.name()) {
+                builder = builder.header(X_TOKENIZER, name);
+            }
         }
 
         builder
@@ -225,41 +286,20 @@ impl<T: Send> OperationModifier for LlmResponse<T> {
     }
 }
 
-impl<T: Serialize> ResponseModifier for LlmResponse<T> {
+impl<T: ToonSchema + Serialize> ResponseModifier for LlmResponse<T> {
     fn update_response(op: &mut Operation) {
-        let mut content = BTreeMap::new();
-
-        // JSON response
-        content.insert(
-            JSON_CONTENT_TYPE.to_string(),
-            MediaType {
-                schema: Some(SchemaRef::Inline(serde_json::json!({
-                    "type": "object",
-                    "description": "JSON formatted response with token counting headers"
-                }))),
-                example: None,
-            },
-        );
-
-        // TOON response
-        content.insert(
-            TOON_CONTENT_TYPE.to_string(),
-            MediaType {
-                schema: Some(SchemaRef::Inline(serde_json::json!({
-                    "type": "string",
-                    "description": "TOON (Token-Oriented Object Notation) formatted response with token counting headers"
-                }))),
-                example: None,
-            },
-        );
-
-        let response = ResponseSpec {
+        let mut response = ResponseSpec {
             description: "LLM-optimized response with token counting headers (X-Token-Count-JSON, X-Token-Count-TOON, X-Token-Savings)".to_string(),
-            content,
+            content: negotiated_content::<T>(),
             headers: BTreeMap::new(),
         };
+        add_token_headers(&mut response);
         op.responses.insert("200".to_string(), response);
     }
+
+    fn register_components(spec: &mut OpenApiSpec) {
+        <T as ToonSchema>::register_components(spec);
+    }
 }
 
 #[cfg(test)]
@@ -274,15 +314,6 @@ mod tests {
         active: bool,
     }
 
-    #[test]
-    fn test_estimate_tokens() {
-        // ~4 chars per token
-        assert_eq!(estimate_tokens(""), 0);
-        assert_eq!(estimate_tokens("test"), 1); // 4 chars = 1 token
-        assert_eq!(estimate_tokens("hello world"), 3); // 11 chars = ~3 tokens
-        assert_eq!(estimate_tokens("a"), 1); // rounds up
-    }
-
     #[test]
     fn test_calculate_savings() {
         assert_eq!(calculate_savings(100, 70), 30.0);
@@ -336,4 +367,115 @@ mod tests {
             .with_token_headers();
         assert!(response.include_token_headers);
     }
+
+    #[test]
+    fn test_llm_response_uses_custom_tokenizer() {
+        struct CharTokenizer;
+
+        impl Tokenizer for CharTokenizer {
+            fn name(&self) -> &str {
+                "chars"
+            }
+
+            fn count_tokens(&self, text: &str) -> usize {
+                text.len()
+            }
+        }
+
+        let data = TestData {
+            id: 1,
+            name: "Test".to_string(),
+            active: true,
+        };
+        let json_len = serde_json::to_string(&data).unwrap().len();
+        let response = LlmResponse::json(data)
+            .with_tokenizer(Arc::new(CharTokenizer))
+            .into_response();
+
+        let headers = response.headers();
+        assert_eq!(headers[X_TOKENIZER], "chars");
+        assert_eq!(headers[X_TOKEN_COUNT_JSON], json_len.to_string().as_str());
+    }
+
+    #[test]
+    fn test_llm_response_counts_registered_format_tokens() {
+        struct Csv;
+
+        impl crate::FormatEncoder for Csv {
+            fn name(&self) -> &'static str {
+                "csv"
+            }
+
+            fn content_type(&self) -> &'static str {
+                "text/csv"
+            }
+
+            fn encode(&self, value: &serde_json::Value) -> Result<Vec<u8>, ToonError> {
+                let object = value.as_object().unwrap();
+                let header: Vec<&str> = object.keys().map(String::as_str).collect();
+                let row: Vec<String> = object.values().map(|v| v.to_string()).collect();
+                Ok(format!("{}\n{}", header.join(","), row.join(",")).into_bytes())
+            }
+        }
+
+        let csv = crate::FormatRegistry::new()
+            .register(Arc::new(Csv))
+            .find("csv")
+            .unwrap();
+        let data = TestData {
+            id: 1,
+            name: "Test".to_string(),
+            active: true,
+        };
+        let response = LlmResponse::new(data, csv).into_response();
+
+        let headers = response.headers();
+        assert_eq!(headers[header::CONTENT_TYPE], "text/csv");
+        assert_eq!(headers[X_FORMAT_USED], "csv");
+        assert!(headers.contains_key("x-token-count-csv"));
+        assert!(headers.contains_key(X_TOKEN_COUNT_JSON));
+    }
+
+    #[derive(Serialize)]
+    struct Row {
+        id: u64,
+        name: String,
+    }
+
+    fn rows(n: u64) -> Vec<Row> {
+        (0..n)
+            .map(|id| Row {
+                id,
+                name: format!("row{}", id),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_llm_response_auto_picks_smaller_format() {
+        // Tabular data is much smaller as TOON
+        let response = LlmResponse::auto(rows(20)).into_response();
+        let headers = response.headers();
+        assert_eq!(headers[X_FORMAT_USED], "toon");
+        assert_eq!(headers[header::CONTENT_TYPE], crate::TOON_CONTENT_TYPE);
+
+        // A bare string is the same size in both formats
+        let response = LlmResponse::auto("hi").into_response();
+        assert_eq!(response.headers()[X_FORMAT_USED], "json");
+    }
+
+    #[test]
+    fn test_llm_response_auto_respects_min_savings() {
+        let response = LlmResponse::auto(rows(20))
+            .with_min_savings(99.0)
+            .into_response();
+        assert_eq!(response.headers()[X_FORMAT_USED], "json");
+
+        // Token headers can be disabled while still choosing by token count
+        let response = LlmResponse::auto(rows(20))
+            .without_token_headers()
+            .into_response();
+        assert_eq!(response.headers()[X_FORMAT_USED], "toon");
+        assert!(!response.headers().contains_key(X_TOKEN_COUNT_JSON));
+    }
 }
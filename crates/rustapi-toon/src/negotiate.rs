@@ -3,26 +3,31 @@
 //! This module provides `Negotiate<T>` - a response wrapper that automatically
 //! chooses between JSON and TOON format based on the client's `Accept` header.
 
-use crate::{TOON_CONTENT_TYPE, TOON_CONTENT_TYPE_TEXT};
+use crate::format::{CustomFormat, FormatRegistry};
+use crate::openapi::{negotiated_content, ToonSchema};
+use crate::{ToonError, TOON_CONTENT_TYPE, TOON_CONTENT_TYPE_TEXT};
 use http::{header, StatusCode};
+use rustapi_core::error_format::{register_error_formatter, ErrorFormatter};
 use rustapi_core::{ApiError, FromRequestParts, IntoResponse, Request, Response};
-use rustapi_openapi::{
-    MediaType, Operation, OperationModifier, ResponseModifier, ResponseSpec, SchemaRef,
-};
+use rustapi_openapi::{OpenApiSpec, Operation, OperationModifier, ResponseModifier, ResponseSpec};
 use serde::Serialize;
 use std::collections::BTreeMap;
+use std::sync::{Arc, Once};
 
 /// JSON Content-Type
 pub const JSON_CONTENT_TYPE: &str = "application/json";
 
 /// Supported output formats
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+#[non_exhaustive]
 pub enum OutputFormat {
     /// JSON format (default)
     #[default]
     Json,
     /// TOON format (token-optimized)
     Toon,
+    /// A format added to the application's [`FormatRegistry`]
+    Custom(CustomFormat),
 }
 
 impl OutputFormat {
@@ -31,17 +36,67 @@ impl OutputFormat {
         match self {
             OutputFormat::Json => JSON_CONTENT_TYPE,
             OutputFormat::Toon => TOON_CONTENT_TYPE,
+            OutputFormat::Custom(format) => format.encoder().content_type(),
+        }
+    }
+
+    /// Short name of the format (`json`, `toon` or the registered name)
+    pub fn name(&self) -> &'static str {
+        match self {
+            OutputFormat::Json => "json",
+            OutputFormat::Toon => "toon",
+            OutputFormat::Custom(format) => format.encoder().name(),
+        }
+    }
+
+    /// All media types selecting this format in an `Accept` header
+    pub fn media_types(&self) -> Vec<&'static str> {
+        match self {
+            OutputFormat::Json => vec![JSON_CONTENT_TYPE, "text/json"],
+            OutputFormat::Toon => vec![TOON_CONTENT_TYPE, TOON_CONTENT_TYPE_TEXT],
+            OutputFormat::Custom(format) => {
+                let encoder = format.encoder();
+                let mut types = vec![encoder.content_type()];
+                types.extend_from_slice(encoder.media_types());
+                types
+            }
+        }
+    }
+
+    /// Serialize a value in this format
+    pub fn encode<T: Serialize>(&self, data: &T) -> Result<Vec<u8>, ToonError> {
+        match self {
+            OutputFormat::Json => {
+                serde_json::to_vec(data).map_err(|e| ToonError::Encode(e.to_string()))
+            }
+            OutputFormat::Toon => Ok(toon_format::encode_default(data)?.into_bytes()),
+            OutputFormat::Custom(format) => {
+                let value =
+                    serde_json::to_value(data).map_err(|e| ToonError::Encode(e.to_string()))?;
+                format.encoder().encode(&value)
+            }
         }
     }
 }
 
 /// Parsed Accept header with quality values
 ///
+/// Implements RFC 7231 §5.3.2 negotiation between JSON, TOON and the formats
+/// of the application's [`FormatRegistry`] state:
+/// - `q` quality factors (`q=0` marks a type as not acceptable)
+/// - `*/*` and `type/*` wildcards, with the most specific matching range
+///   deciding the quality of a media type
+/// - a missing or empty header accepts everything
+///
 /// Parses `Accept` headers like:
 /// - `application/json`
 /// - `application/toon`
 /// - `application/json, application/toon;q=0.9`
+/// - `application/*;q=0.5, application/toon`
 /// - `*/*`
+///
+/// When extracted from a request, a header that accepts none of the available
+/// formats is rejected with `406 Not Acceptable`.
 #[derive(Debug, Clone)]
 pub struct AcceptHeader {
     /// Preferred format based on Accept header parsing
@@ -59,12 +114,39 @@ pub struct MediaTypeEntry {
     pub quality: f32,
 }
 
+impl MediaTypeEntry {
+    /// Whether this media range matches a concrete media type (e.g. `application/*`
+    /// matches `application/toon`).
+    pub fn matches(&self, media_type: &str) -> bool {
+        let range = self.media_type.to_ascii_lowercase();
+        let media_type = media_type.to_ascii_lowercase();
+        if range == "*/*" || range == media_type {
+            return true;
+        }
+        match (range.strip_suffix("/*"), media_type.split_once('/')) {
+            (Some(range_type), Some((main_type, _))) => range_type == main_type,
+            _ => false,
+        }
+    }
+
+    /// Specificity of the range: 0 for `*/*`, 1 for `type/*`, 2 for `type/subtype`.
+    pub fn specificity(&self) -> u8 {
+        if self.media_type == "*/*" {
+            0
+        } else if self.media_type.ends_with("/*") {
+            1
+        } else {
+            2
+        }
+    }
+}
+
 impl Default for AcceptHeader {
     fn default() -> Self {
         Self {
             preferred: OutputFormat::Json,
             media_types: vec![MediaTypeEntry {
-                media_type: JSON_CONTENT_TYPE.to_string(),
+                media_type: "*/*".to_string(),
                 quality: 1.0,
             }],
         }
@@ -72,27 +154,30 @@ impl Default for AcceptHeader {
 }
 
 impl AcceptHeader {
-    /// Parse an Accept header value
+    /// Parse an Accept header value, preferring one of the built-in formats
     pub fn parse(header_value: &str) -> Self {
+        Self::parse_with(header_value, &FormatRegistry::default())
+    }
+
+    /// Parse an Accept header value, preferring one of the formats in `registry`
+    pub fn parse_with(header_value: &str, registry: &FormatRegistry) -> Self {
         let mut entries: Vec<MediaTypeEntry> = header_value
             .split(',')
             .filter_map(|part| {
-                let part = part.trim();
-                if part.is_empty() {
+                let mut params = part.split(';');
+                let media_type = params.next()?.trim().to_ascii_lowercase();
+                if media_type.is_empty() {
                     return None;
                 }
 
-                let (media_type, quality) = if let Some(q_pos) = part.find(";q=") {
-                    let (mt, q_part) = part.split_at(q_pos);
-                    let q_str = q_part.trim_start_matches(";q=").trim();
-                    let quality = q_str.parse::<f32>().unwrap_or(1.0).clamp(0.0, 1.0);
-                    (mt.trim().to_string(), quality)
-                } else if let Some(semi_pos) = part.find(';') {
-                    // Handle other parameters, ignore them
-                    (part[..semi_pos].trim().to_string(), 1.0)
-                } else {
-                    (part.to_string(), 1.0)
-                };
+                // Only the `q` parameter matters for negotiation; malformed values
+                // fall back to the default of 1.0.
+                let quality = params
+                    .filter_map(|param| param.split_once('='))
+                    .find(|(name, _)| name.trim().eq_ignore_ascii_case("q"))
+                    .map(|(_, value)| value.trim().parse::<f32>().unwrap_or(1.0))
+                    .unwrap_or(1.0)
+                    .clamp(0.0, 1.0);
 
                 Some(MediaTypeEntry {
                     media_type,
@@ -101,80 +186,130 @@ impl AcceptHeader {
             })
             .collect();
 
-        // Sort by quality (descending)
+        if entries.is_empty() {
+            entries.push(MediaTypeEntry {
+                media_type: "*/*".to_string(),
+                quality: 1.0,
+            });
+        }
+
+        // Sort by quality (descending), more specific ranges first on ties.
+        // The sort is stable, so header order breaks remaining ties.
         entries.sort_by(|a, b| {
             b.quality
                 .partial_cmp(&a.quality)
                 .unwrap_or(std::cmp::Ordering::Equal)
+                .then_with(|| b.specificity().cmp(&a.specificity()))
         });
 
-        // Determine preferred format
-        let preferred = Self::determine_format(&entries);
-
-        Self {
-            preferred,
+        let mut accept = Self {
+            preferred: OutputFormat::Json,
             media_types: entries,
-        }
+        };
+        accept.preferred = accept.negotiate(&registry.formats()).unwrap_or_default();
+        accept
     }
 
-    /// Determine the output format based on media type entries
-    fn determine_format(entries: &[MediaTypeEntry]) -> OutputFormat {
-        for entry in entries {
-            let mt = entry.media_type.to_lowercase();
-
-            // Check for TOON
-            if mt == TOON_CONTENT_TYPE || mt == TOON_CONTENT_TYPE_TEXT {
-                return OutputFormat::Toon;
-            }
+    /// Quality the client assigns to a concrete media type.
+    ///
+    /// The most specific matching range wins (`application/toon` over
+    /// `application/*` over `*/*`); `0.0` means not acceptable.
+    pub fn quality_of(&self, media_type: &str) -> f32 {
+        self.media_types
+            .iter()
+            .filter(|entry| entry.matches(media_type))
+            .max_by_key(|entry| entry.specificity())
+            .map(|entry| entry.quality)
+            .unwrap_or(0.0)
+    }
 
-            // Check for JSON
-            if mt == JSON_CONTENT_TYPE || mt == "application/json" || mt == "text/json" {
-                return OutputFormat::Json;
-            }
+    /// Quality of an output format, including its alternative media types
+    /// (`text/json`, `text/toon`).
+    pub fn quality_of_format(&self, format: &OutputFormat) -> f32 {
+        format
+            .media_types()
+            .iter()
+            .map(|mt| self.quality_of(mt))
+            .fold(0.0, f32::max)
+    }
 
-            // Wildcard accepts anything, default to JSON
-            if mt == "*/*" || mt == "application/*" || mt == "text/*" {
-                return OutputFormat::Json;
+    /// Pick the best format out of those the server can produce.
+    ///
+    /// Formats are compared by client quality; ties go to the earlier entry of
+    /// `offered`. Returns `None` when no offered format is acceptable.
+    pub fn negotiate(&self, offered: &[OutputFormat]) -> Option<OutputFormat> {
+        let mut best: Option<(&OutputFormat, f32)> = None;
+        for format in offered {
+            let quality = self.quality_of_format(format);
+            if quality > 0.0 && best.is_none_or(|(_, q)| quality > q) {
+                best = Some((format, quality));
             }
         }
-
-        // Default to JSON
-        OutputFormat::Json
+        best.map(|(format, _)| format.clone())
     }
 
     /// Check if TOON format is acceptable
     pub fn accepts_toon(&self) -> bool {
-        self.media_types.iter().any(|e| {
-            let mt = e.media_type.to_lowercase();
-            mt == TOON_CONTENT_TYPE
-                || mt == TOON_CONTENT_TYPE_TEXT
-                || mt == "*/*"
-                || mt == "application/*"
-        })
+        self.quality_of_format(&OutputFormat::Toon) > 0.0
     }
 
     /// Check if JSON format is acceptable
     pub fn accepts_json(&self) -> bool {
-        self.media_types.iter().any(|e| {
-            let mt = e.media_type.to_lowercase();
-            mt == JSON_CONTENT_TYPE || mt == "text/json" || mt == "*/*" || mt == "application/*"
-        })
+        self.quality_of_format(&OutputFormat::Json) > 0.0
     }
 }
 
 impl FromRequestParts for AcceptHeader {
     fn from_request_parts(req: &Request) -> rustapi_core::Result<Self> {
-        let accept = req
+        let registry = req
+            .state()
+            .get::<FormatRegistry>()
+            .cloned()
+            .unwrap_or_default();
+        let header = req
             .headers()
             .get(header::ACCEPT)
             .and_then(|v| v.to_str().ok())
-            .map(AcceptHeader::parse)
             .unwrap_or_default();
+        let accept = AcceptHeader::parse_with(header, &registry);
+
+        let formats = registry.formats();
+        if accept.negotiate(&formats).is_none() {
+            return Err(not_acceptable(&formats));
+        }
 
         Ok(accept)
     }
 }
 
+/// `406 Not Acceptable` error listing the formats this server can produce.
+fn not_acceptable(formats: &[OutputFormat]) -> ApiError {
+    ApiError::new(
+        StatusCode::NOT_ACCEPTABLE,
+        "not_acceptable",
+        format!(
+            "None of the requested media types can be produced; available: {}",
+            formats
+                .iter()
+                .map(OutputFormat::content_type)
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+    )
+}
+
+#[doc(hidden)]
+#[allow(non_upper_case_globals)]
+#[rustapi_core::__private::linkme::distributed_slice(rustapi_core::__private::ERROR_CODES)]
+#[linkme(crate = rustapi_core::__private::linkme)]
+static __RUSTAPI_ERROR_CODE_NOT_ACCEPTABLE: rustapi_core::ErrorCodeEntry =
+    rustapi_core::ErrorCodeEntry::new_static(
+        "not_acceptable",
+        406,
+        "The Accept header allows none of the available response formats",
+        "rustapi_toon::AcceptHeader",
+    );
+
 /// Content-negotiated response wrapper
 ///
 /// Automatically serializes to JSON or TOON based on the client's `Accept` header.
@@ -187,7 +322,7 @@ impl FromRequestParts for AcceptHeader {
 /// use rustapi_rs::prelude::*;
 /// use rustapi_rs::toon::{Negotiate, AcceptHeader};
 ///
-/// #[derive(Serialize)]
+/// #[derive(Serialize, Schema)]
 /// struct User {
 ///     id: u64,
 ///     name: String,
@@ -238,39 +373,35 @@ impl<T> Negotiate<T> {
 
     /// Get the output format
     pub fn format(&self) -> OutputFormat {
-        self.format
+        self.format.clone()
     }
 }
 
 impl<T: Serialize> IntoResponse for Negotiate<T> {
     fn into_response(self) -> Response {
-        match self.format {
-            OutputFormat::Json => match serde_json::to_vec(&self.data) {
-                Ok(body) => http::Response::builder()
-                    .status(StatusCode::OK)
-                    .header(header::CONTENT_TYPE, JSON_CONTENT_TYPE)
-                    .body(rustapi_core::ResponseBody::from(body))
-                    .unwrap(),
-                Err(err) => {
-                    let error = ApiError::internal(format!("JSON serialization error: {}", err));
-                    error.into_response()
-                }
-            },
-            OutputFormat::Toon => match toon_format::encode_default(&self.data) {
-                Ok(body) => http::Response::builder()
-                    .status(StatusCode::OK)
-                    .header(header::CONTENT_TYPE, TOON_CONTENT_TYPE)
-                    .body(rustapi_core::ResponseBody::from(body))
-                    .unwrap(),
-                Err(err) => {
-                    let error = ApiError::internal(format!("TOON serialization error: {}", err));
-                    error.into_response()
-                }
-            },
+        match self.format.encode(&self.data) {
+            Ok(body) => http::Response::builder()
+                .status(StatusCode::OK)
+                .header(header::CONTENT_TYPE, self.format.content_type())
+                .header(header::VARY, "accept")
+                .body(rustapi_core::ResponseBody::from(body))
+                .unwrap(),
+            Err(err) => serialization_error(self.format, err).into_response(),
         }
     }
 }
 
+/// `500` error for a response that could not be encoded in `format`.
+pub(crate) fn serialization_error(format: OutputFormat, err: ToonError) -> ApiError {
+    let detail = match err {
+        ToonError::Encode(msg) => msg,
+        other => other.to_string(),
+    };
+    let format = format.name().to_uppercase();
+    tracing::error!("Failed to serialize to {}: {}", format, detail);
+    ApiError::internal(format!("{} serialization error: {}", format, detail))
+}
+
 // OpenAPI support
 impl<T: Send> OperationModifier for Negotiate<T> {
     fn update_operation(_op: &mut Operation) {
@@ -278,42 +409,66 @@ impl<T: Send> OperationModifier for Negotiate<T> {
     }
 }
 
-impl<T: Serialize> ResponseModifier for Negotiate<T> {
+impl<T: ToonSchema + Serialize> ResponseModifier for Negotiate<T> {
     fn update_response(op: &mut Operation) {
-        let mut content = BTreeMap::new();
-
-        // JSON response
-        content.insert(
-            JSON_CONTENT_TYPE.to_string(),
-            MediaType {
-                schema: Some(SchemaRef::Inline(serde_json::json!({
-                    "type": "object",
-                    "description": "JSON formatted response"
-                }))),
-                example: None,
-            },
-        );
-
-        // TOON response
-        content.insert(
-            TOON_CONTENT_TYPE.to_string(),
-            MediaType {
-                schema: Some(SchemaRef::Inline(serde_json::json!({
-                    "type": "string",
-                    "description": "TOON (Token-Oriented Object Notation) formatted response"
-                }))),
-                example: None,
-            },
-        );
-
         let response = ResponseSpec {
             description: "Content-negotiated response (JSON or TOON based on Accept header)"
                 .to_string(),
-            content,
+            content: negotiated_content::<T>(),
             headers: BTreeMap::new(),
         };
         op.responses.insert("200".to_string(), response);
     }
+
+    fn register_components(spec: &mut OpenApiSpec) {
+        <T as ToonSchema>::register_components(spec);
+    }
+}
+
+/// Error formatter that encodes `ApiError` bodies in every non-JSON
+/// [`OutputFormat`] of a [`FormatRegistry`]
+///
+/// Install it with [`negotiate_errors`], or with
+/// [`register_error_formatter`] to include registered formats.
+#[derive(Debug, Clone, Default)]
+pub struct NegotiatedErrors {
+    registry: FormatRegistry,
+}
+
+impl NegotiatedErrors {
+    /// Negotiate errors between the built-in formats and those of `registry`
+    pub fn new(registry: FormatRegistry) -> Self {
+        Self { registry }
+    }
+}
+
+impl ErrorFormatter for NegotiatedErrors {
+    fn media_types(&self) -> Vec<&'static str> {
+        self.registry
+            .formats()
+            .into_iter()
+            .filter(|format| *format != OutputFormat::Json)
+            .flat_map(|format| format.media_types())
+            .collect()
+    }
+
+    fn format(&self, media_type: &str, error: &serde_json::Value) -> Option<Vec<u8>> {
+        self.registry
+            .formats()
+            .into_iter()
+            .find(|format| format.media_types().contains(&media_type))?
+            .encode(error)
+            .ok()
+    }
+}
+
+/// Negotiate error responses the way [`Negotiate`] negotiates data
+///
+/// After this call a client sending `Accept: application/toon` receives errors
+/// in TOON. Calling it more than once has no further effect.
+pub fn negotiate_errors() {
+    static INSTALLED: Once = Once::new();
+    INSTALLED.call_once(|| register_error_formatter(Arc::new(NegotiatedErrors::default())));
 }
 
 // Also implement for AcceptHeader extractor
@@ -327,6 +482,21 @@ impl OperationModifier for AcceptHeader {
 mod tests {
     use super::*;
 
+    #[test]
+    fn errors_are_negotiated_as_toon() {
+        negotiate_errors();
+        let response = ApiError::not_found("Order not found").into_response();
+        let response = rustapi_core::error_format::negotiate_error_response(
+            Some(&http::HeaderValue::from_static("application/toon")),
+            response,
+        );
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+        assert_eq!(
+            response.headers()[header::CONTENT_TYPE],
+            crate::TOON_CONTENT_TYPE
+        );
+    }
+
     #[test]
     fn test_accept_header_parse_json() {
         let accept = AcceptHeader::parse("application/json");
@@ -366,6 +536,107 @@ mod tests {
         assert_eq!(accept.preferred, OutputFormat::Json);
     }
 
+    #[test]
+    fn test_accept_header_quality_with_spaces_and_params() {
+        let accept =
+            AcceptHeader::parse("application/json; charset=utf-8; q=0.4, text/toon ; q=0.6");
+        assert_eq!(accept.preferred, OutputFormat::Toon);
+        assert_eq!(accept.quality_of_format(&OutputFormat::Json), 0.4);
+        assert_eq!(accept.quality_of_format(&OutputFormat::Toon), 0.6);
+    }
+
+    #[test]
+    fn test_accept_header_specific_range_overrides_wildcard() {
+        // TOON explicitly excluded even though application/* allows it
+        let accept = AcceptHeader::parse("application/*, application/toon;q=0");
+        assert!(!accept.accepts_toon());
+        assert!(accept.accepts_json());
+        assert_eq!(accept.preferred, OutputFormat::Json);
+
+        // Wildcard with lower quality than the explicit TOON entry
+        let accept = AcceptHeader::parse("*/*;q=0.1, application/toon");
+        assert_eq!(accept.preferred, OutputFormat::Toon);
+        assert_eq!(accept.quality_of_format(&OutputFormat::Json), 0.1);
+    }
+
+    #[test]
+    fn test_accept_header_type_wildcard() {
+        let accept = AcceptHeader::parse("text/*");
+        assert!(accept.accepts_json()); // text/json
+        assert!(accept.accepts_toon()); // text/toon
+        assert_eq!(accept.preferred, OutputFormat::Json);
+
+        let accept = AcceptHeader::parse("image/*");
+        assert!(!accept.accepts_json());
+        assert!(!accept.accepts_toon());
+    }
+
+    #[test]
+    fn test_negotiate_ties_follow_server_order() {
+        let accept = AcceptHeader::parse("application/toon, application/json");
+        assert_eq!(
+            accept.negotiate(&[OutputFormat::Json, OutputFormat::Toon]),
+            Some(OutputFormat::Json)
+        );
+        assert_eq!(
+            accept.negotiate(&[OutputFormat::Toon, OutputFormat::Json]),
+            Some(OutputFormat::Toon)
+        );
+        assert_eq!(accept.negotiate(&[]), None);
+    }
+
+    #[test]
+    fn test_negotiate_nothing_acceptable() {
+        let accept = AcceptHeader::parse("application/json;q=0, text/html");
+        assert_eq!(
+            accept.negotiate(&[OutputFormat::Json, OutputFormat::Toon]),
+            None
+        );
+    }
+
+    #[test]
+    fn test_extractor_rejects_unacceptable_with_406() {
+        use bytes::Bytes;
+        use rustapi_core::{BodyVariant, PathParams};
+        use std::sync::Arc;
+
+        let request = |accept: &str| {
+            let (parts, _) = http::Request::builder()
+                .header(header::ACCEPT, accept)
+                .body(())
+                .unwrap()
+                .into_parts();
+            Request::new(
+                parts,
+                BodyVariant::Buffered(Bytes::new()),
+                Arc::new(http::Extensions::new()),
+                PathParams::new(),
+            )
+        };
+
+        let err = AcceptHeader::from_request_parts(&request("text/html")).unwrap_err();
+        assert_eq!(err.status, StatusCode::NOT_ACCEPTABLE);
+
+        let accept =
+            AcceptHeader::from_request_parts(&request("text/html, application/toon;q=0.5"))
+                .unwrap();
+        assert_eq!(accept.preferred, OutputFormat::Toon);
+    }
+
+    #[test]
+    fn test_not_acceptable_is_in_error_catalog() {
+        let catalog = rustapi_core::ErrorCatalog::collect();
+        assert_eq!(catalog.get("not_acceptable").unwrap().status, 406);
+    }
+
+    #[test]
+    fn test_accept_header_empty_accepts_everything() {
+        let accept = AcceptHeader::parse("");
+        assert!(accept.accepts_json());
+        assert!(accept.accepts_toon());
+        assert_eq!(accept.preferred, OutputFormat::Json);
+    }
+
     #[test]
     fn test_accept_header_default() {
         let accept = AcceptHeader::default();
@@ -377,4 +648,79 @@ mod tests {
         assert_eq!(OutputFormat::Json.content_type(), "application/json");
         assert_eq!(OutputFormat::Toon.content_type(), "application/toon");
     }
+
+    struct KeyValue;
+
+    impl crate::FormatEncoder for KeyValue {
+        fn name(&self) -> &'static str {
+            "kv"
+        }
+
+        fn content_type(&self) -> &'static str {
+            "text/x-kv"
+        }
+
+        fn encode(&self, value: &serde_json::Value) -> Result<Vec<u8>, ToonError> {
+            let object = value
+                .as_object()
+                .ok_or_else(|| ToonError::Encode("expected an object".to_string()))?;
+            let lines: Vec<String> = object.iter().map(|(k, v)| format!("{}={}", k, v)).collect();
+            Ok(lines.join("\n").into_bytes())
+        }
+    }
+
+    #[test]
+    fn test_registered_format_participates_in_negotiation() {
+        let registry = crate::FormatRegistry::new().register(Arc::new(KeyValue));
+        let kv = registry.find("kv").unwrap();
+
+        let accept = AcceptHeader::parse_with("application/json;q=0.5, text/x-kv", &registry);
+        assert_eq!(accept.preferred, kv);
+        assert_eq!(kv.content_type(), "text/x-kv");
+
+        // Built-in formats still win ties
+        assert_eq!(
+            AcceptHeader::parse_with("*/*", &registry).preferred,
+            OutputFormat::Json
+        );
+        // Formats of another registry are not offered
+        assert_eq!(
+            AcceptHeader::parse("application/json;q=0.5, text/x-kv").preferred,
+            OutputFormat::Json
+        );
+
+        let response =
+            Negotiate::new(serde_json::json!({ "id": 1 }), accept.preferred).into_response();
+        assert_eq!(response.headers()[header::CONTENT_TYPE], "text/x-kv");
+    }
+
+    #[test]
+    fn test_extractor_offers_formats_from_state() {
+        use bytes::Bytes;
+        use rustapi_core::{BodyVariant, PathParams};
+
+        let mut state = http::Extensions::new();
+        state.insert(crate::FormatRegistry::new().register(Arc::new(KeyValue)));
+        let (parts, _) = http::Request::builder()
+            .header(header::ACCEPT, "text/x-kv")
+            .body(())
+            .unwrap()
+            .into_parts();
+        let request = Request::new(
+            parts,
+            BodyVariant::Buffered(Bytes::new()),
+            Arc::new(state),
+            PathParams::new(),
+        );
+
+        let accept = AcceptHeader::from_request_parts(&request).unwrap();
+        assert_eq!(accept.preferred.name(), "kv");
+    }
+
+    #[test]
+    fn test_custom_format_encode_failure_is_500() {
+        let registry = crate::FormatRegistry::new().register(Arc::new(KeyValue));
+        let response = Negotiate::new(1, registry.find("kv").unwrap()).into_response();
+        assert_eq!(response.status(), StatusCode::INTERNAL_SERVER_ERROR);
+    }
 }
@@ -2,8 +2,77 @@
 //!
 //! This module provides OpenAPI schema definitions and documentation helpers
 //! for TOON format responses.
+//!
+//! Handlers taking or returning `Toon<T>`, `Negotiate<T>` or `LlmResponse<T>`
+//! get `application/toon` content entries automatically with the
+//! [`toon_schema`] schema. With the `typed-schemas` feature, JSON content
+//! references `T`'s schema and the TOON example is that schema rendered as
+//! TOON; `T` then has to implement `Schema` (see [`ToonSchema`]).
+
+use crate::{OutputFormat, TOON_CONTENT_TYPE};
+#[cfg(feature = "typed-schemas")]
+use rustapi_openapi::schema::{RustApiSchema, SchemaCtx};
+use rustapi_openapi::{MediaType, OpenApiSpec, ResponseSpec, SchemaRef};
+#[cfg(feature = "typed-schemas")]
+use serde_json::Value;
+use std::collections::BTreeMap;
+
+/// Nesting depth after which sample generation stops (recursive schemas).
+#[cfg(feature = "typed-schemas")]
+const MAX_SAMPLE_DEPTH: usize = 8;
+
+/// How the body type of a TOON extractor or response is documented
+///
+/// Every type implements it. By default the content is documented with
+/// untyped schemas. With the `typed-schemas` feature the implementation uses
+/// the type's `Schema`, so only types deriving `Schema` can be used.
+pub trait ToonSchema {
+    /// `application/json` media type for this type
+    fn json_media_type() -> MediaType;
+
+    /// `application/toon` media type for this type
+    fn toon_media_type() -> MediaType;
+
+    /// Register the component schemas this type references
+    fn register_components(spec: &mut OpenApiSpec);
+}
+
+#[cfg(not(feature = "typed-schemas"))]
+impl<T: ?Sized> ToonSchema for T {
+    fn json_media_type() -> MediaType {
+        MediaType {
+            schema: Some(SchemaRef::Inline(serde_json::json!({
+                "type": "object",
+                "description": "JSON formatted data"
+            }))),
+            example: None,
+        }
+    }
+
+    fn toon_media_type() -> MediaType {
+        MediaType {
+            schema: Some(SchemaRef::Inline(toon_schema())),
+            example: None,
+        }
+    }
+
+    fn register_components(_spec: &mut OpenApiSpec) {}
+}
+
+#[cfg(feature = "typed-schemas")]
+impl<T: RustApiSchema> ToonSchema for T {
+    fn json_media_type() -> MediaType {
+        json_media_type::<T>()
+    }
+
+    fn toon_media_type() -> MediaType {
+        toon_media_type::<T>()
+    }
 
-use crate::TOON_CONTENT_TYPE;
+    fn register_components(spec: &mut OpenApiSpec) {
+        spec.register_in_place::<T>();
+    }
+}
 
 /// TOON format description for OpenAPI
 pub const TOON_FORMAT_DESCRIPTION: &str = r#"
@@ -147,6 +216,146 @@ pub fn api_description_with_toon(base_description: &str) -> String {
     )
 }
 
+/// `application/json` media type referencing `T`'s schema.
+#[cfg(feature = "typed-schemas")]
+fn json_media_type<T: RustApiSchema>() -> MediaType {
+    let mut ctx = SchemaCtx::new();
+    MediaType {
+        schema: Some(T::schema(&mut ctx)),
+        example: None,
+    }
+}
+
+/// `application/toon` media type for `T`: [`toon_schema`] with a TOON
+/// example built from `T`'s schema via [`format_comparison_example`].
+#[cfg(feature = "typed-schemas")]
+fn toon_media_type<T: RustApiSchema>() -> MediaType {
+    let mut ctx = SchemaCtx::new();
+    let schema = T::schema(&mut ctx);
+    let components = ctx
+        .components
+        .into_iter()
+        .filter_map(|(name, schema)| Some((name, serde_json::to_value(schema).ok()?)))
+        .collect::<BTreeMap<_, _>>();
+
+    let example = serde_json::to_value(schema)
+        .ok()
+        .map(|schema| sample_value(&schema, &components, 0))
+        .map(|sample| format_comparison_example(&sample)["toon"]["content"].clone())
+        .filter(|content| content.as_str().is_some_and(|s| !s.is_empty()));
+
+    MediaType {
+        schema: Some(SchemaRef::Inline(toon_schema())),
+        example,
+    }
+}
+
+/// Document the headers emitted by `LlmResponse` (see [`token_headers_schema`]).
+pub(crate) fn add_token_headers(response: &mut ResponseSpec) {
+    if let Ok(headers) = serde_json::from_value(token_headers_schema()) {
+        response.headers = headers;
+    }
+}
+
+/// Content map with `application/json` and `application/toon` entries for `T`.
+pub(crate) fn negotiated_content<T: ToonSchema>() -> BTreeMap<String, MediaType> {
+    BTreeMap::from([
+        (
+            OutputFormat::Json.content_type().to_string(),
+            T::json_media_type(),
+        ),
+        (
+            OutputFormat::Toon.content_type().to_string(),
+            T::toon_media_type(),
+        ),
+    ])
+}
+
+/// Build a representative value for a JSON schema.
+#[cfg(feature = "typed-schemas")]
+fn sample_value(schema: &Value, components: &BTreeMap<String, Value>, depth: usize) -> Value {
+    if depth > MAX_SAMPLE_DEPTH {
+        return Value::Null;
+    }
+    for key in ["example", "default", "const"] {
+        if let Some(value) = schema.get(key) {
+            return value.clone();
+        }
+    }
+    if let Some(first) = schema.get("enum").and_then(|e| e.get(0)) {
+        return first.clone();
+    }
+    if let Some(reference) = schema.get("$ref").and_then(Value::as_str) {
+        let name = reference.rsplit('/').next().unwrap_or(reference);
+        return components
+            .get(name)
+            .map_or(Value::Null, |s| sample_value(s, components, depth + 1));
+    }
+    for key in ["oneOf", "anyOf"] {
+        if let Some(first) = schema.get(key).and_then(|v| v.get(0)) {
+            return sample_value(first, components, depth + 1);
+        }
+    }
+    if let Some(parts) = schema.get("allOf").and_then(Value::as_array) {
+        let mut merged = serde_json::Map::new();
+        for part in parts {
+            if let Value::Object(fields) = sample_value(part, components, depth + 1) {
+                merged.extend(fields);
+            }
+        }
+        return Value::Object(merged);
+    }
+
+    let ty = match schema.get("type") {
+        Some(Value::String(ty)) => ty.as_str(),
+        Some(Value::Array(types)) => types
+            .iter()
+            .filter_map(Value::as_str)
+            .find(|ty| *ty != "null")
+            .unwrap_or("null"),
+        _ if schema.get("properties").is_some() => "object",
+        _ => return Value::Null,
+    };
+
+    match ty {
+        "object" => Value::Object(
+            schema
+                .get("properties")
+                .and_then(Value::as_object)
+                .map(|props| {
+                    props
+                        .iter()
+                        .map(|(name, prop)| {
+                            (name.clone(), sample_value(prop, components, depth + 1))
+                        })
+                        .collect()
+                })
+                .unwrap_or_default(),
+        ),
+        "array" => {
+            let item = schema.get("items").map_or(Value::Null, |items| {
+                sample_value(items, components, depth + 1)
+            });
+            Value::Array(vec![item.clone(), item])
+        }
+        "string" => Value::String(
+            match schema.get("format").and_then(Value::as_str) {
+                Some("date-time") => "2024-01-01T00:00:00Z",
+                Some("date") => "2024-01-01",
+                Some("uuid") => "00000000-0000-0000-0000-000000000000",
+                Some("email") => "user@example.com",
+                Some("uri") => "https://example.com",
+                _ => "string",
+            }
+            .to_string(),
+        ),
+        "integer" => Value::from(0),
+        "number" => Value::from(0.0),
+        "boolean" => Value::Bool(true),
+        _ => Value::Null,
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -209,4 +418,61 @@ mod tests {
         assert!(desc.contains("TOON Format Support"));
         assert!(desc.contains("40-60% token savings"));
     }
+
+    #[cfg(feature = "typed-schemas")]
+    #[derive(rustapi_openapi::Schema)]
+    #[allow(dead_code)]
+    struct Team {
+        name: String,
+        members: Vec<Member>,
+    }
+
+    #[cfg(feature = "typed-schemas")]
+    #[derive(rustapi_openapi::Schema)]
+    #[allow(dead_code)]
+    struct Member {
+        id: u64,
+        active: bool,
+    }
+
+    #[cfg(feature = "typed-schemas")]
+    #[test]
+    fn test_toon_media_type_renders_example_from_schema() {
+        let media = toon_media_type::<Team>();
+        let Some(SchemaRef::Inline(schema)) = &media.schema else {
+            panic!("expected inline TOON schema");
+        };
+        assert_eq!(schema["format"], "toon");
+
+        let example = media.example.unwrap();
+        let example = example.as_str().unwrap();
+        assert!(example.contains("name: string"), "{}", example);
+        assert!(example.contains("members[2]{"), "{}", example);
+    }
+
+    #[cfg(not(feature = "typed-schemas"))]
+    #[test]
+    fn test_untyped_media_types_accept_any_type() {
+        struct Opaque;
+
+        let media = Opaque::toon_media_type();
+        let Some(SchemaRef::Inline(schema)) = &media.schema else {
+            panic!("expected inline TOON schema");
+        };
+        assert_eq!(schema["format"], "toon");
+        assert!(media.example.is_none());
+        assert!(Opaque::json_media_type().schema.is_some());
+    }
+
+    #[test]
+    fn test_token_headers_are_documented() {
+        let mut response = ResponseSpec {
+            description: String::new(),
+            content: BTreeMap::new(),
+            headers: BTreeMap::new(),
+        };
+        add_token_headers(&mut response);
+        assert!(response.headers.contains_key("X-Token-Savings"));
+        assert!(response.headers["X-Format-Used"].schema.is_some());
+    }
 }
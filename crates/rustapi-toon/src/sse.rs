@@ -0,0 +1,296 @@
+//! # TOON-encoded Server-Sent Events
+//!
+//! `SseToon<S>` streams events whose payloads are TOON instead of JSON, on top
+//! of the regular [`Sse`] response (keep-alive included). Each payload is
+//! encoded with `toon_format` and sent as (multi-line) `data:` fields, with
+//! `event:`, `id:` and `retry:` metadata taken from [`ToonEvent`].
+//!
+//! ```text
+//! event: prices
+//! data: items[2]{symbol,price}:
+//! data:   BTC,64000
+//! data:   ETH,3100
+//! ```
+//!
+//! ## Example
+//!
+//! ```rust,ignore
+//! use rustapi_rs::prelude::*;
+//! use rustapi_rs::toon::{SseToon, ToonEvent};
+//!
+//! async fn prices() -> SseToon<impl Stream<Item = Result<ToonEvent<Prices>, Infallible>>> {
+//!     let updates = price_feed().map(|p| Ok(ToonEvent::new(p).event("prices")));
+//!     SseToon::new(updates).keep_alive(KeepAlive::new())
+//! }
+//!
+//! // Plain payloads use a default event name
+//! async fn ticks() -> SseToon<impl Stream<Item = Result<ToonEvent<Tick>, Infallible>>> {
+//!     SseToon::data(tick_stream()).event("tick")
+//! }
+//! ```
+
+use crate::ToonError;
+use futures_util::{Stream, StreamExt};
+use rustapi_core::sse::{KeepAlive, Sse, SseEvent};
+use rustapi_core::{IntoResponse, Response};
+use rustapi_openapi::{MediaType, Operation, ResponseModifier, ResponseSpec, SchemaRef};
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+/// A Server-Sent Event whose payload is encoded as TOON.
+#[derive(Debug, Clone)]
+pub struct ToonEvent<T> {
+    /// The event payload
+    pub data: T,
+    /// The event type/name
+    pub event: Option<String>,
+    /// The event ID
+    pub id: Option<String>,
+    /// Reconnection time in milliseconds
+    pub retry: Option<u64>,
+}
+
+impl<T> ToonEvent<T> {
+    /// Create a new event with the given payload
+    pub fn new(data: T) -> Self {
+        Self {
+            data,
+            event: None,
+            id: None,
+            retry: None,
+        }
+    }
+
+    /// Set the event type/name
+    pub fn event(mut self, event: impl Into<String>) -> Self {
+        self.event = Some(event.into());
+        self
+    }
+
+    /// Set the event ID
+    pub fn id(mut self, id: impl Into<String>) -> Self {
+        self.id = Some(id.into());
+        self
+    }
+
+    /// Set the reconnection time in milliseconds
+    pub fn retry(mut self, retry: u64) -> Self {
+        self.retry = Some(retry);
+        self
+    }
+}
+
+impl<T: Serialize> ToonEvent<T> {
+    /// Encode the payload as TOON and build the corresponding [`SseEvent`].
+    pub fn into_sse_event(self) -> Result<SseEvent, ToonError> {
+        let mut event = SseEvent::new(toon_format::encode_default(&self.data)?);
+        event.event = self.event;
+        event.id = self.id;
+        event.retry = self.retry;
+        Ok(event)
+    }
+}
+
+/// Server-Sent Events response with TOON-encoded payloads.
+pub struct SseToon<S> {
+    stream: S,
+    event: Option<String>,
+    keep_alive: Option<KeepAlive>,
+}
+
+impl<S> SseToon<S> {
+    /// Create a TOON SSE response from a stream of [`ToonEvent`]s
+    pub fn new(stream: S) -> Self {
+        Self {
+            stream,
+            event: None,
+            keep_alive: None,
+        }
+    }
+
+    /// Default `event:` name for events that do not set one
+    pub fn event(mut self, event: impl Into<String>) -> Self {
+        self.event = Some(event.into());
+        self
+    }
+
+    /// Set the keep-alive configuration
+    pub fn keep_alive(mut self, config: KeepAlive) -> Self {
+        self.keep_alive = Some(config);
+        self
+    }
+}
+
+impl SseToon<()> {
+    /// Create a TOON SSE response from a stream of plain payloads
+    #[allow(clippy::type_complexity)]
+    pub fn data<D, T, E>(
+        stream: D,
+    ) -> SseToon<futures_util::stream::Map<D, fn(Result<T, E>) -> Result<ToonEvent<T>, E>>>
+    where
+        D: Stream<Item = Result<T, E>>,
+    {
+        SseToon::new(stream.map(
+            (|item: Result<T, E>| item.map(ToonEvent::new)) as fn(_) -> Result<ToonEvent<T>, E>,
+        ))
+    }
+}
+
+impl<S, T, E> IntoResponse for SseToon<S>
+where
+    S: Stream<Item = Result<ToonEvent<T>, E>> + Send + 'static,
+    T: Serialize + Send + 'static,
+    E: std::error::Error + Send + Sync + 'static,
+{
+    fn into_response(self) -> Response {
+        let default_event = self.event;
+        let events = self.stream.map(move |item| {
+            let mut event = item.map_err(std::io::Error::other)?;
+            if event.event.is_none() {
+                event.event = default_event.clone();
+            }
+            event.into_sse_event().map_err(|e| {
+                tracing::error!("Failed to encode SSE event as TOON: {}", e);
+                std::io::Error::other(e)
+            })
+        });
+
+        let sse = Sse::new(events);
+        match self.keep_alive {
+            Some(keep_alive) => sse.keep_alive(keep_alive).into_response(),
+            None => sse.into_response(),
+        }
+    }
+}
+
+impl<S> ResponseModifier for SseToon<S> {
+    fn update_response(op: &mut Operation) {
+        let mut content = BTreeMap::new();
+        content.insert(
+            "text/event-stream".to_string(),
+            MediaType {
+                schema: Some(SchemaRef::Inline(serde_json::json!({
+                    "type": "string",
+                    "description": "Server-Sent Events stream with TOON-encoded payloads: 'event: <type>\\ndata: <toon line>\\n...\\n\\n'",
+                }))),
+                example: Some(serde_json::json!(
+                    "event: update\ndata: users[1]{id,name}:\ndata:   1,Alice\n\n"
+                )),
+            },
+        );
+
+        let response = ResponseSpec {
+            description: "Server-Sent Events stream with TOON payloads (token-optimized for LLMs)"
+                .to_string(),
+            content,
+            headers: BTreeMap::new(),
+        };
+        op.responses.insert("200".to_string(), response);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures_util::stream;
+    use http_body_util::BodyExt;
+    use serde::Deserialize;
+    use std::convert::Infallible;
+
+    #[derive(Debug, Serialize, Deserialize, PartialEq)]
+    struct User {
+        id: u64,
+        name: String,
+    }
+
+    #[derive(Debug, Serialize, Deserialize, PartialEq)]
+    struct Users {
+        users: Vec<User>,
+    }
+
+    fn users() -> Users {
+        Users {
+            users: vec![
+                User {
+                    id: 1,
+                    name: "Alice".to_string(),
+                },
+                User {
+                    id: 2,
+                    name: "Bob".to_string(),
+                },
+            ],
+        }
+    }
+
+    async fn body_of(response: Response) -> String {
+        let bytes = response.into_body().collect().await.unwrap().to_bytes();
+        String::from_utf8(bytes.to_vec()).unwrap()
+    }
+
+    /// Rebuild event payloads the way an SSE client does (joining `data:` lines).
+    fn parse_data(body: &str) -> Vec<String> {
+        body.split("\n\n")
+            .filter(|block| !block.is_empty())
+            .map(|block| {
+                block
+                    .lines()
+                    .filter_map(|line| line.strip_prefix("data: "))
+                    .collect::<Vec<_>>()
+                    .join("\n")
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_toon_event_metadata() {
+        let event = ToonEvent::new(users())
+            .event("snapshot")
+            .id("42")
+            .retry(1000)
+            .into_sse_event()
+            .unwrap();
+        let text = event.to_sse_string();
+
+        assert!(text.starts_with("event: snapshot\nid: 42\nretry: 1000\n"));
+        assert!(text.contains("data: users[2]{id,name}:\n"));
+        assert!(text.contains("data:   1,Alice\n"));
+    }
+
+    #[tokio::test]
+    async fn test_sse_toon_roundtrip() {
+        let events = stream::iter(vec![Ok::<_, Infallible>(
+            ToonEvent::new(users()).event("snapshot"),
+        )]);
+        let response = SseToon::new(events).into_response();
+        assert_eq!(
+            response.headers()[http::header::CONTENT_TYPE],
+            "text/event-stream"
+        );
+
+        let body = body_of(response).await;
+        let payloads = parse_data(&body);
+        assert_eq!(payloads.len(), 1);
+
+        let decoded: Users = toon_format::decode_default(&payloads[0]).unwrap();
+        assert_eq!(decoded, users());
+    }
+
+    #[tokio::test]
+    async fn test_sse_toon_default_event_name() {
+        let items = stream::iter(vec![
+            Ok::<_, Infallible>(User {
+                id: 1,
+                name: "Alice".to_string(),
+            }),
+            Ok(User {
+                id: 2,
+                name: "Bob".to_string(),
+            }),
+        ]);
+        let body = body_of(SseToon::data(items).event("user").into_response()).await;
+
+        assert_eq!(body.matches("event: user\n").count(), 2);
+        assert!(body.contains("data: id: 2\ndata: name: Bob\n"));
+    }
+}
@@ -0,0 +1,318 @@
+//! # Streaming TOON Responses
+//!
+//! `ToonStream<S>` encodes a stream of rows into the tabular TOON array form
+//! incrementally: the header line is sent as soon as the first row arrives,
+//! then one line per row. Large datasets never need to be buffered.
+//!
+//! ```text
+//! users[3]{id,name,active}:
+//!   1,Alice,true
+//!   2,Bob,false
+//!   3,Carol,true
+//! ```
+//!
+//! TOON array headers carry the row count, so the number of rows must be
+//! known up front (e.g. from a `COUNT(*)` query). If the stream yields a
+//! different number of rows, the response body is aborted with an error
+//! instead of sending an invalid document.
+//!
+//! ## Example
+//!
+//! ```rust,ignore
+//! use rustapi_rs::prelude::*;
+//! use rustapi_rs::toon::ToonStream;
+//!
+//! #[derive(Serialize)]
+//! struct User {
+//!     id: u64,
+//!     name: String,
+//! }
+//!
+//! async fn export_users(State(db): State<Db>) -> ToonStream<impl Stream<Item = Result<User, DbError>>> {
+//!     let count = db.count_users().await;
+//!     ToonStream::new(db.stream_users(), count).key("users")
+//! }
+//! ```
+
+use crate::{ToonError, TOON_CONTENT_TYPE};
+use bytes::Bytes;
+use futures_util::{Stream, StreamExt};
+use http::{header, StatusCode};
+use rustapi_core::{ApiError, IntoResponse, Response};
+use rustapi_openapi::{MediaType, Operation, ResponseModifier, ResponseSpec, SchemaRef};
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+/// Streaming response that encodes rows as a tabular TOON array.
+///
+/// Rows must serialize to flat objects with the same fields and primitive
+/// values; anything else aborts the body with an encoding error.
+pub struct ToonStream<S> {
+    stream: S,
+    len: usize,
+    key: Option<String>,
+}
+
+impl<S> ToonStream<S> {
+    /// Create a streaming TOON response for `len` rows.
+    pub fn new(stream: S, len: usize) -> Self {
+        Self {
+            stream,
+            len,
+            key: None,
+        }
+    }
+
+    /// Emit the array under a root key (`key[N]{...}:`) instead of as a root array.
+    pub fn key(mut self, key: impl Into<String>) -> Self {
+        self.key = Some(key.into());
+        self
+    }
+}
+
+/// Incremental encoder for the tabular TOON array form.
+struct TabularEncoder {
+    len: usize,
+    key: String,
+    fields: Option<String>,
+    rows: usize,
+}
+
+impl TabularEncoder {
+    fn new(len: usize, key: Option<String>) -> Self {
+        let key = match key {
+            Some(key) if toon_format::is_valid_unquoted_key(&key) => key,
+            Some(key) => toon_format::utils::quote_string(&key),
+            None => String::new(),
+        };
+        Self {
+            len,
+            key,
+            fields: None,
+            rows: 0,
+        }
+    }
+
+    /// Encode one row, prefixed by the header line if it is the first one.
+    fn encode_row<T: Serialize>(&mut self, row: &T) -> Result<String, ToonError> {
+        if self.rows == self.len {
+            return Err(ToonError::Encode(format!(
+                "stream yielded more than the declared {} rows",
+                self.len
+            )));
+        }
+
+        // Encoding a one-element array yields `[1]{fields}:\n  values`, which
+        // gives us the exact field list and value quoting of the encoder.
+        let encoded = toon_format::encode_default(&[row])?;
+        let (header, values) = encoded
+            .split_once('\n')
+            .filter(|(header, values)| header.ends_with("}:") && !values.contains('\n'))
+            .ok_or_else(|| {
+                ToonError::Encode(
+                    "rows must be flat objects with primitive values to stream as a TOON table"
+                        .to_string(),
+                )
+            })?;
+        let fields = header.trim_start_matches("[1]");
+
+        let mut out = String::with_capacity(values.len() + 1);
+        match &self.fields {
+            None => {
+                out.push_str(&format!("{}[{}]{}", self.key, self.len, fields));
+                self.fields = Some(fields.to_string());
+            }
+            Some(expected) if expected != fields => {
+                return Err(ToonError::Encode(format!(
+                    "row {} has fields {} but the table header is {}",
+                    self.rows + 1,
+                    fields.trim_end_matches(':'),
+                    expected.trim_end_matches(':')
+                )));
+            }
+            Some(_) => {}
+        }
+        out.push('\n');
+        out.push_str(values);
+        self.rows += 1;
+        Ok(out)
+    }
+
+    /// Check the row count once the stream has ended; returns any trailing output.
+    fn finish(&self) -> Result<Option<String>, ToonError> {
+        if self.rows != self.len {
+            return Err(ToonError::Encode(format!(
+                "stream yielded {} rows but {} were declared",
+                self.rows, self.len
+            )));
+        }
+        if self.rows == 0 {
+            return Ok(Some(format!("{}[0]:", self.key)));
+        }
+        Ok(None)
+    }
+}
+
+impl<S, T, E> IntoResponse for ToonStream<S>
+where
+    S: Stream<Item = Result<T, E>> + Send + 'static,
+    T: Serialize + Send + 'static,
+    E: std::error::Error + Send + Sync + 'static,
+{
+    fn into_response(self) -> Response {
+        let encoder = TabularEncoder::new(self.len, self.key);
+        let stream = self.stream.boxed();
+
+        let body = futures_util::stream::unfold(
+            (stream, encoder, false),
+            |(mut stream, mut encoder, done)| async move {
+                if done {
+                    return None;
+                }
+                match stream.next().await {
+                    Some(Ok(row)) => {
+                        let chunk = encoder.encode_row(&row).map(Bytes::from);
+                        let done = chunk.is_err();
+                        Some((chunk, (stream, encoder, done)))
+                    }
+                    Some(Err(e)) => Some((
+                        Err(ToonError::Encode(e.to_string())),
+                        (stream, encoder, true),
+                    )),
+                    None => match encoder.finish() {
+                        Ok(Some(tail)) => Some((Ok(Bytes::from(tail)), (stream, encoder, true))),
+                        Ok(None) => None,
+                        Err(e) => Some((Err(e), (stream, encoder, true))),
+                    },
+                }
+            },
+        )
+        .map(|chunk| {
+            chunk.map_err(|e| {
+                tracing::error!("TOON stream aborted: {}", e);
+                ApiError::from(e)
+            })
+        });
+
+        http::Response::builder()
+            .status(StatusCode::OK)
+            .header(header::CONTENT_TYPE, TOON_CONTENT_TYPE)
+            .body(rustapi_core::ResponseBody::from_stream(body))
+            .unwrap()
+    }
+}
+
+impl<S> ResponseModifier for ToonStream<S> {
+    fn update_response(op: &mut Operation) {
+        let mut content = BTreeMap::new();
+        content.insert(
+            TOON_CONTENT_TYPE.to_string(),
+            MediaType {
+                schema: Some(SchemaRef::Inline(serde_json::json!({
+                    "type": "string",
+                    "description": "Streamed TOON tabular array (header line followed by one line per row)"
+                }))),
+                example: None,
+            },
+        );
+
+        let response = ResponseSpec {
+            description: "Streamed TOON response - token-optimized for LLMs".to_string(),
+            content,
+            headers: BTreeMap::new(),
+        };
+        op.responses.insert("200".to_string(), response);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures_util::stream;
+    use http_body_util::BodyExt;
+    use serde::Deserialize;
+    use std::convert::Infallible;
+
+    #[derive(Debug, Serialize, Deserialize, PartialEq)]
+    struct Row {
+        id: u64,
+        name: String,
+        active: bool,
+    }
+
+    fn rows(n: u64) -> Vec<Result<Row, Infallible>> {
+        (1..=n)
+            .map(|id| {
+                Ok(Row {
+                    id,
+                    name: format!("user, {}", id),
+                    active: id % 2 == 1,
+                })
+            })
+            .collect()
+    }
+
+    async fn body_of(response: Response) -> Result<String, ApiError> {
+        let bytes = response.into_body().collect().await?.to_bytes();
+        Ok(String::from_utf8(bytes.to_vec()).unwrap())
+    }
+
+    #[tokio::test]
+    async fn test_stream_matches_buffered_encoding() {
+        let response = ToonStream::new(stream::iter(rows(3)), 3)
+            .key("users")
+            .into_response();
+        assert_eq!(response.headers()[header::CONTENT_TYPE], TOON_CONTENT_TYPE);
+
+        let body = body_of(response).await.unwrap();
+        let expected = toon_format::encode_default(&serde_json::json!({
+            "users": rows(3).into_iter().map(Result::unwrap).collect::<Vec<_>>()
+        }))
+        .unwrap();
+        assert_eq!(body, expected);
+    }
+
+    #[tokio::test]
+    async fn test_root_array_roundtrip() {
+        let response = ToonStream::new(stream::iter(rows(2)), 2).into_response();
+        let body = body_of(response).await.unwrap();
+        assert!(body.starts_with("[2]{id,name,active}:\n"));
+
+        let decoded: Vec<Row> = toon_format::decode_default(&body).unwrap();
+        assert_eq!(decoded.len(), 2);
+        assert_eq!(decoded[0].name, "user, 1");
+    }
+
+    #[tokio::test]
+    async fn test_empty_stream() {
+        let response = ToonStream::new(stream::iter(rows(0)), 0)
+            .key("users")
+            .into_response();
+        assert_eq!(body_of(response).await.unwrap(), "users[0]:");
+    }
+
+    #[tokio::test]
+    async fn test_row_count_mismatch_aborts() {
+        let response = ToonStream::new(stream::iter(rows(2)), 3).into_response();
+        assert!(body_of(response).await.is_err());
+
+        let response = ToonStream::new(stream::iter(rows(3)), 2).into_response();
+        assert!(body_of(response).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_nested_rows_are_rejected() {
+        #[derive(Serialize)]
+        struct Nested {
+            id: u64,
+            tags: Vec<u32>,
+        }
+
+        let items = vec![Ok::<_, Infallible>(Nested {
+            id: 1,
+            tags: vec![1, 2],
+        })];
+        let response = ToonStream::new(stream::iter(items), 1).into_response();
+        assert!(body_of(response).await.is_err());
+    }
+}
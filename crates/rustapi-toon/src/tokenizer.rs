@@ -0,0 +1,335 @@
+//! # Tokenizers for Token Counting Headers
+//!
+//! `LlmResponse` reports how many tokens the JSON and TOON encodings of a
+//! response take. By default counts are estimated (~4 characters per token);
+//! enabling a tokenizer feature makes the counts match the target model:
+//!
+//! | Feature          | Tokenizer                                          |
+//! |------------------|----------------------------------------------------|
+//! | *(none)*         | [`HeuristicTokenizer`] (`heuristic`)               |
+//! | `tiktoken`       | `TiktokenTokenizer` (`cl100k_base`, `o200k_base`, OpenAI model names) |
+//! | `hf-tokenizers`  | `HfTokenizer` (any `tokenizer.json`, e.g. Llama BPE) |
+//!
+//! ## Selecting a Tokenizer
+//!
+//! - Per app: [`set_default_tokenizer`] replaces the process-wide default.
+//! - Per request: clients send `X-Tokenizer: <name>` (a registered tokenizer
+//!   or, with `tiktoken`, an OpenAI model name such as `gpt-4o`). Extract it
+//!   with [`TargetTokenizer`] and pass it to `LlmResponse::with_tokenizer`.
+//!
+//! ```rust,ignore
+//! use rustapi_rs::toon::{
+//!     register_tokenizer, set_default_tokenizer, AcceptHeader, HfTokenizer, LlmResponse,
+//!     TargetTokenizer, TiktokenTokenizer,
+//! };
+//! use std::sync::Arc;
+//!
+//! // At startup
+//! set_default_tokenizer(Arc::new(TiktokenTokenizer::o200k_base()));
+//! register_tokenizer(Arc::new(HfTokenizer::from_file("llama3", "tokenizer.json")?));
+//!
+//! async fn items(accept: AcceptHeader, tokenizer: TargetTokenizer) -> LlmResponse<Items> {
+//!     LlmResponse::new(load_items(), accept.preferred).with_tokenizer(tokenizer.0)
+//! }
+//! ```
+
+use http::HeaderValue;
+use rustapi_core::{ApiError, FromRequestParts, Request};
+use std::collections::HashMap;
+use std::sync::{Arc, OnceLock, RwLock};
+
+/// Request header naming the tokenizer (or model) to count tokens for
+pub const X_TOKENIZER: &str = "x-tokenizer";
+
+/// Counts tokens the way a target model would.
+pub trait Tokenizer: Send + Sync {
+    /// Name of the tokenizer (reported in the `X-Tokenizer` response header)
+    fn name(&self) -> &str;
+
+    /// Number of tokens `text` encodes to
+    fn count_tokens(&self, text: &str) -> usize;
+}
+
+/// Character-based estimate: ~4 characters per token (GPT-3/4 average).
+#[derive(Debug, Clone, Copy, Default)]
+pub struct HeuristicTokenizer;
+
+impl Tokenizer for HeuristicTokenizer {
+    fn name(&self) -> &str {
+        "heuristic"
+    }
+
+    fn count_tokens(&self, text: &str) -> usize {
+        text.len().div_ceil(4)
+    }
+}
+
+/// OpenAI BPE tokenizer backed by `tiktoken-rs`.
+#[cfg(feature = "tiktoken")]
+#[derive(Clone)]
+pub struct TiktokenTokenizer {
+    name: String,
+    bpe: &'static tiktoken_rs::CoreBPE,
+}
+
+#[cfg(feature = "tiktoken")]
+impl TiktokenTokenizer {
+    /// `cl100k_base` encoding (GPT-3.5, GPT-4)
+    pub fn cl100k_base() -> Self {
+        Self {
+            name: "cl100k_base".to_string(),
+            bpe: tiktoken_rs::cl100k_base_singleton(),
+        }
+    }
+
+    /// `o200k_base` encoding (GPT-4o, o1, o3)
+    pub fn o200k_base() -> Self {
+        Self {
+            name: "o200k_base".to_string(),
+            bpe: tiktoken_rs::o200k_base_singleton(),
+        }
+    }
+
+    /// Tokenizer for an OpenAI model name (e.g. `gpt-4o`, `gpt-4`).
+    ///
+    /// Returns `None` for unknown models and for models using encodings other
+    /// than `cl100k_base` or `o200k_base`.
+    pub fn for_model(model: &str) -> Option<Self> {
+        use tiktoken_rs::tokenizer::{get_tokenizer, Tokenizer as Encoding};
+
+        let bpe = match get_tokenizer(model)? {
+            Encoding::Cl100kBase => tiktoken_rs::cl100k_base_singleton(),
+            Encoding::O200kBase => tiktoken_rs::o200k_base_singleton(),
+            _ => return None,
+        };
+        Some(Self {
+            name: model.to_string(),
+            bpe,
+        })
+    }
+}
+
+#[cfg(feature = "tiktoken")]
+impl std::fmt::Debug for TiktokenTokenizer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("TiktokenTokenizer")
+            .field("name", &self.name)
+            .finish()
+    }
+}
+
+#[cfg(feature = "tiktoken")]
+impl Tokenizer for TiktokenTokenizer {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn count_tokens(&self, text: &str) -> usize {
+        self.bpe.encode_with_special_tokens(text).len()
+    }
+}
+
+/// Hugging Face `tokenizers` tokenizer (e.g. Llama BPE) loaded from a `tokenizer.json`.
+#[cfg(feature = "hf-tokenizers")]
+pub struct HfTokenizer {
+    name: String,
+    inner: tokenizers::Tokenizer,
+}
+
+#[cfg(feature = "hf-tokenizers")]
+impl HfTokenizer {
+    /// Load a tokenizer from a `tokenizer.json` file.
+    pub fn from_file(
+        name: impl Into<String>,
+        path: impl AsRef<std::path::Path>,
+    ) -> Result<Self, crate::ToonError> {
+        let inner = tokenizers::Tokenizer::from_file(path)
+            .map_err(|e| crate::ToonError::Tokenizer(e.to_string()))?;
+        Ok(Self {
+            name: name.into(),
+            inner,
+        })
+    }
+
+    /// Load a tokenizer from the contents of a `tokenizer.json` file.
+    pub fn from_bytes(
+        name: impl Into<String>,
+        bytes: impl AsRef<[u8]>,
+    ) -> Result<Self, crate::ToonError> {
+        let inner = tokenizers::Tokenizer::from_bytes(bytes)
+            .map_err(|e| crate::ToonError::Tokenizer(e.to_string()))?;
+        Ok(Self {
+            name: name.into(),
+            inner,
+        })
+    }
+}
+
+#[cfg(feature = "hf-tokenizers")]
+impl std::fmt::Debug for HfTokenizer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("HfTokenizer")
+            .field("name", &self.name)
+            .finish()
+    }
+}
+
+#[cfg(feature = "hf-tokenizers")]
+impl Tokenizer for HfTokenizer {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn count_tokens(&self, text: &str) -> usize {
+        match self.inner.encode(text, false) {
+            Ok(encoding) => encoding.len(),
+            Err(e) => {
+                tracing::warn!("Tokenizer '{}' failed, estimating: {}", self.name, e);
+                HeuristicTokenizer.count_tokens(text)
+            }
+        }
+    }
+}
+
+struct Registry {
+    default: Arc<dyn Tokenizer>,
+    named: HashMap<String, Arc<dyn Tokenizer>>,
+}
+
+fn registry() -> &'static RwLock<Registry> {
+    static REGISTRY: OnceLock<RwLock<Registry>> = OnceLock::new();
+    REGISTRY.get_or_init(|| {
+        let mut named: HashMap<String, Arc<dyn Tokenizer>> = HashMap::new();
+        named.insert("heuristic".to_string(), Arc::new(HeuristicTokenizer));
+        #[cfg(feature = "tiktoken")]
+        {
+            named.insert(
+                "cl100k_base".to_string(),
+                Arc::new(TiktokenTokenizer::cl100k_base()),
+            );
+            named.insert(
+                "o200k_base".to_string(),
+                Arc::new(TiktokenTokenizer::o200k_base()),
+            );
+        }
+        RwLock::new(Registry {
+            default: Arc::new(HeuristicTokenizer),
+            named,
+        })
+    })
+}
+
+/// Register a tokenizer under its [`Tokenizer::name`] so clients can select it
+/// with the `X-Tokenizer` request header.
+pub fn register_tokenizer(tokenizer: Arc<dyn Tokenizer>) {
+    let mut registry = registry().write().unwrap_or_else(|e| e.into_inner());
+    registry
+        .named
+        .insert(tokenizer.name().to_string(), tokenizer);
+}
+
+/// Replace the tokenizer used when a response does not select one.
+pub fn set_default_tokenizer(tokenizer: Arc<dyn Tokenizer>) {
+    let mut registry = registry().write().unwrap_or_else(|e| e.into_inner());
+    registry.default = tokenizer;
+}
+
+/// The current default tokenizer ([`HeuristicTokenizer`] unless replaced).
+pub fn default_tokenizer() -> Arc<dyn Tokenizer> {
+    registry()
+        .read()
+        .unwrap_or_else(|e| e.into_inner())
+        .default
+        .clone()
+}
+
+/// Look up a tokenizer by registered name or, with `tiktoken`, by OpenAI model name.
+pub fn find_tokenizer(name: &str) -> Option<Arc<dyn Tokenizer>> {
+    let name = name.trim();
+    if let Some(tokenizer) = registry()
+        .read()
+        .unwrap_or_else(|e| e.into_inner())
+        .named
+        .get(name)
+    {
+        return Some(tokenizer.clone());
+    }
+
+    #[cfg(feature = "tiktoken")]
+    if let Some(tokenizer) = TiktokenTokenizer::for_model(name) {
+        return Some(Arc::new(tokenizer));
+    }
+
+    None
+}
+
+/// Tokenizer selected by the `X-Tokenizer` request header.
+///
+/// Falls back to the [default tokenizer](default_tokenizer) when the header is
+/// absent. An unknown tokenizer name is rejected with `400 Bad Request`.
+#[derive(Clone)]
+pub struct TargetTokenizer(pub Arc<dyn Tokenizer>);
+
+impl std::fmt::Debug for TargetTokenizer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("TargetTokenizer")
+            .field(&self.0.name())
+            .finish()
+    }
+}
+
+impl FromRequestParts for TargetTokenizer {
+    fn from_request_parts(req: &Request) -> rustapi_core::Result<Self> {
+        let Some(name) = req.headers().get(X_TOKENIZER).map(HeaderValue::to_str) else {
+            return Ok(Self(default_tokenizer()));
+        };
+        let name = name.map_err(|_| ApiError::bad_request("Invalid X-Tokenizer header"))?;
+
+        find_tokenizer(name)
+            .map(Self)
+            .ok_or_else(|| ApiError::bad_request(format!("Unknown tokenizer '{}'", name)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct WordTokenizer;
+
+    impl Tokenizer for WordTokenizer {
+        fn name(&self) -> &str {
+            "words"
+        }
+
+        fn count_tokens(&self, text: &str) -> usize {
+            text.split_whitespace().count()
+        }
+    }
+
+    #[test]
+    fn test_heuristic_tokenizer() {
+        assert_eq!(HeuristicTokenizer.count_tokens(""), 0);
+        assert_eq!(HeuristicTokenizer.count_tokens("test"), 1);
+        assert_eq!(HeuristicTokenizer.count_tokens("hello world"), 3);
+    }
+
+    #[test]
+    fn test_registry_lookup() {
+        assert!(find_tokenizer("heuristic").is_some());
+        assert!(find_tokenizer("does-not-exist").is_none());
+
+        register_tokenizer(Arc::new(WordTokenizer));
+        let words = find_tokenizer("words").unwrap();
+        assert_eq!(words.count_tokens("one two three"), 3);
+    }
+
+    #[cfg(feature = "tiktoken")]
+    #[test]
+    fn test_tiktoken_counts() {
+        let cl100k = TiktokenTokenizer::cl100k_base();
+        assert_eq!(cl100k.count_tokens("hello world"), 2);
+        assert!(TiktokenTokenizer::for_model("gpt-4o").is_some());
+        assert_eq!(find_tokenizer("gpt-4").unwrap().name(), "gpt-4");
+    }
+}
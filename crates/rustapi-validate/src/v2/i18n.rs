@@ -23,6 +23,11 @@ pub fn translate(key: &str, locale: Option<&str>) -> String {
     }
 }
 
+/// Locales with translation files (e.g. `["en", "tr"]`).
+pub fn available_locales() -> Vec<&'static str> {
+    rust_i18n::available_locales!()
+}
+
 /// Helper to translate with arguments.
 pub fn translate_with_args(key: &str, locale: Option<&str>, _args: &[(&str, &str)]) -> String {
     if let Some(locale) = locale {
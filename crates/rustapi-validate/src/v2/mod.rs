@@ -10,6 +10,7 @@
 //! - Custom error messages with interpolation
 //! - Validation groups (Create, Update, Custom)
 //! - Serializable validation rules
+//! - Field sanitizers applied before validation
 //!
 //! ## Example
 //!
@@ -43,6 +44,7 @@ mod error;
 mod group;
 pub mod i18n;
 mod rules;
+pub mod sanitize;
 mod traits;
 
 #[cfg(test)]
@@ -52,6 +54,7 @@ pub use context::{DatabaseValidator, HttpValidator, ValidationContext, Validatio
 pub use error::{RuleError, ValidationErrors};
 pub use group::{GroupedRule, GroupedRules, ValidationGroup};
 pub use rules::*;
+pub use sanitize::{Sanitize, SanitizeField};
 pub use traits::{AsyncValidate, AsyncValidationRule, SerializableRule, Validate, ValidationRule};
 
 /// Prelude module for v2 validation
@@ -62,6 +65,7 @@ pub mod prelude {
     pub use super::error::{RuleError, ValidationErrors};
     pub use super::group::{GroupedRule, GroupedRules, ValidationGroup};
     pub use super::rules::*;
+    pub use super::sanitize::{Sanitize, SanitizeField};
     pub use super::traits::{
         AsyncValidate, AsyncValidationRule, SerializableRule, Validate, ValidationRule,
     };
@@ -0,0 +1,125 @@
+//! Field sanitizers applied before validation.
+//!
+//! Sanitizers normalize raw input (trimming whitespace, lowercasing, removing
+//! markup) so validation rules and handlers always see a canonical value.
+//! They are usually applied through the `#[validate(sanitize(...))]` field attribute
+//! of `#[derive(Validate)]`:
+//!
+//! ```rust,ignore
+//! use rustapi_validate::v2::prelude::*;
+//!
+//! #[derive(Deserialize, Validate)]
+//! struct Signup {
+//!     #[validate(sanitize(trim, lowercase))]
+//!     #[validate(email)]
+//!     email: String,
+//!
+//!     #[validate(sanitize(trim, strip_html))]
+//!     #[validate(length(max = 280))]
+//!     bio: String,
+//! }
+//! ```
+//!
+//! `ValidatedJson`, `ValidatedForm` and `AsyncValidatedJson` run
+//! [`Sanitize::sanitize`] right after deserialization and before validation.
+
+/// Trait for types whose fields can be normalized in place.
+///
+/// Derived automatically by `#[derive(Validate)]`; fields without a
+/// `sanitize(...)` entry are left untouched.
+pub trait Sanitize {
+    /// Apply all configured sanitizers to the value in place.
+    fn sanitize(&mut self);
+}
+
+/// Field types a string sanitizer can be applied to.
+///
+/// Implemented for `String` and recursively for `Option<T>` and `Vec<T>`,
+/// so `Option<String>` and `Vec<String>` fields can be sanitized as well.
+pub trait SanitizeField {
+    /// Replace every string in the field with `sanitizer(value)`.
+    fn apply_sanitizer(&mut self, sanitizer: fn(&str) -> String);
+}
+
+impl SanitizeField for String {
+    fn apply_sanitizer(&mut self, sanitizer: fn(&str) -> String) {
+        *self = sanitizer(self);
+    }
+}
+
+impl<T: SanitizeField> SanitizeField for Option<T> {
+    fn apply_sanitizer(&mut self, sanitizer: fn(&str) -> String) {
+        if let Some(value) = self {
+            value.apply_sanitizer(sanitizer);
+        }
+    }
+}
+
+impl<T: SanitizeField> SanitizeField for Vec<T> {
+    fn apply_sanitizer(&mut self, sanitizer: fn(&str) -> String) {
+        for value in self {
+            value.apply_sanitizer(sanitizer);
+        }
+    }
+}
+
+/// Remove leading and trailing whitespace.
+pub fn trim(input: &str) -> String {
+    input.trim().to_string()
+}
+
+/// Convert to lowercase.
+pub fn lowercase(input: &str) -> String {
+    input.to_lowercase()
+}
+
+/// Convert to uppercase.
+pub fn uppercase(input: &str) -> String {
+    input.to_uppercase()
+}
+
+/// Collapse runs of whitespace into a single space and trim the ends.
+pub fn collapse_whitespace(input: &str) -> String {
+    input.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Strip all HTML tags from a string.
+pub fn strip_html(input: &str) -> String {
+    let mut output = String::with_capacity(input.len());
+    let mut inside_tag = false;
+
+    for c in input.chars() {
+        if c == '<' {
+            inside_tag = true;
+        } else if c == '>' {
+            inside_tag = false;
+        } else if !inside_tag {
+            output.push(c);
+        }
+    }
+
+    output
+}
+
+/// Escape HTML special characters.
+///
+/// Replaces:
+/// - `&` -> `&amp;`
+/// - `<` -> `&lt;`
+/// - `>` -> `&gt;`
+/// - `"` -> `&quot;`
+/// - `'` -> `&#x27;`
+pub fn escape_html(input: &str) -> String {
+    let mut output = String::with_capacity(input.len());
+    for c in input.chars() {
+        match c {
+            '&' => output.push_str("&amp;"),
+            '<' => output.push_str("&lt;"),
+            '>' => output.push_str("&gt;"),
+            '"' => output.push_str("&quot;"),
+            '\'' => output.push_str("&#x27;"),
+            _ => output.push(c),
+        }
+    }
+    output
+}
@@ -120,3 +120,10 @@ fn test_fallback_to_english() {
     // Let's assume fallback to "en".
     assert_eq!(msg, "Invalid email format");
 }
+
+#[test]
+fn test_available_locales() {
+    let locales = rustapi_validate::v2::i18n::available_locales();
+    assert!(locales.contains(&"en"));
+    assert!(locales.contains(&"tr"));
+}
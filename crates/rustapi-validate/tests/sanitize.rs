@@ -0,0 +1,148 @@
+//! Integration tests for `#[validate(sanitize(...))]` field attributes and the
+//! sanitize-then-validate pipeline of the validated extractors.
+
+use bytes::Bytes;
+use http::{Extensions, Method};
+use rustapi_core::{BodyVariant, FromRequest, PathParams, Request, ValidatedForm, ValidatedJson};
+use rustapi_macros::Validate;
+use rustapi_validate::v2::{Sanitize, Validate as _};
+use serde::Deserialize;
+use std::sync::Arc;
+
+fn shout(input: &str) -> String {
+    format!("{}!", input)
+}
+
+#[derive(Debug, Deserialize, Validate)]
+struct Signup {
+    #[validate(sanitize(trim, lowercase))]
+    #[validate(email)]
+    email: String,
+
+    #[validate(sanitize(strip_html, collapse_whitespace))]
+    #[validate(length(max = 20))]
+    bio: String,
+
+    #[validate(sanitize(trim))]
+    nickname: Option<String>,
+
+    #[validate(sanitize(trim, uppercase))]
+    tags: Vec<String>,
+
+    #[validate(sanitize(custom = "shout"))]
+    greeting: String,
+
+    untouched: String,
+}
+
+fn signup() -> Signup {
+    Signup {
+        email: "  Alice@Example.COM ".to_string(),
+        bio: "<b>Rust</b>   <i>fan</i>".to_string(),
+        nickname: Some("  al ".to_string()),
+        tags: vec![" a ".to_string(), "b".to_string()],
+        greeting: "hi".to_string(),
+        untouched: "  Keep Me  ".to_string(),
+    }
+}
+
+fn request(content_type: &str, body: &str) -> Request {
+    let req = http::Request::builder()
+        .method(Method::POST)
+        .uri("/signup")
+        .header("content-type", content_type)
+        .body(())
+        .unwrap();
+    let (parts, _) = req.into_parts();
+
+    Request::new(
+        parts,
+        BodyVariant::Buffered(Bytes::from(body.to_string())),
+        Arc::new(Extensions::new()),
+        PathParams::new(),
+    )
+}
+
+#[test]
+fn sanitizers_apply_in_declaration_order() {
+    let mut value = signup();
+    value.sanitize();
+
+    assert_eq!(value.email, "alice@example.com");
+    assert_eq!(value.bio, "Rust fan");
+    assert_eq!(value.nickname.as_deref(), Some("al"));
+    assert_eq!(value.tags, vec!["A".to_string(), "B".to_string()]);
+    assert_eq!(value.greeting, "hi!");
+    assert_eq!(value.untouched, "  Keep Me  ");
+}
+
+#[test]
+fn sanitized_value_passes_validation() {
+    let mut value = signup();
+    assert!(value.validate().is_err());
+
+    value.sanitize();
+    assert!(value.validate().is_ok());
+}
+
+#[tokio::test]
+async fn validated_json_sanitizes_before_validating() {
+    let mut req = request(
+        "application/json",
+        r#"{"email":" Bob@Example.com ","bio":"<p>hi</p>","nickname":null,"tags":[],"greeting":"yo","untouched":"x"}"#,
+    );
+
+    let ValidatedJson(value) = ValidatedJson::<Signup>::from_request(&mut req)
+        .await
+        .expect("sanitized email should validate");
+
+    assert_eq!(value.email, "bob@example.com");
+    assert_eq!(value.bio, "hi");
+    assert_eq!(value.greeting, "yo!");
+}
+
+#[tokio::test]
+async fn validated_form_sanitizes_before_validating() {
+    let mut req = request(
+        "application/x-www-form-urlencoded",
+        "email=%20Carol%40Example.com%20&greeting=hey&untouched=y",
+    );
+
+    #[derive(Debug, Deserialize, Validate)]
+    struct Login {
+        #[validate(sanitize(trim, lowercase))]
+        #[validate(email)]
+        email: String,
+        #[validate(sanitize(custom = "shout"))]
+        greeting: String,
+    }
+
+    let ValidatedForm(value) = ValidatedForm::<Login>::from_request(&mut req)
+        .await
+        .expect("sanitized form should validate");
+
+    assert_eq!(value.email, "carol@example.com");
+    assert_eq!(value.greeting, "hey!");
+}
+
+#[tokio::test]
+async fn validated_form_reports_validation_errors() {
+    #[derive(Debug, Deserialize, Validate)]
+    struct Login {
+        #[validate(sanitize(trim))]
+        #[validate(email)]
+        email: String,
+    }
+
+    let mut req = request("application/x-www-form-urlencoded", "email=not-an-email");
+    let err = ValidatedForm::<Login>::from_request(&mut req)
+        .await
+        .unwrap_err();
+    assert_eq!(err.status, http::StatusCode::UNPROCESSABLE_ENTITY);
+
+    let mut req = request("application/x-www-form-urlencoded", "%%%");
+    let err = ValidatedForm::<Login>::from_request(&mut req)
+        .await
+        .unwrap_err();
+    assert_eq!(err.status, http::StatusCode::BAD_REQUEST);
+}
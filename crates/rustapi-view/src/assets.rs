@@ -0,0 +1,306 @@
+//! Fingerprinted static assets
+//!
+//! [`Assets`] gives every file in an asset directory a content-hashed name
+//! (`css/app.css` -> `css/app.3f2a9c1e.css`), so it can be cached forever
+//! and still change on deploy. Templates link to the hashed URL with the
+//! `asset` function, and [`Assets::mount`] serves the files through
+//! `serve_static` with `Cache-Control: public, max-age=31536000, immutable`.
+//!
+//! Fingerprints are computed at startup with [`Assets::new`], or ahead of
+//! time with [`Assets::build`] (e.g. in `build.rs` or a release script) and
+//! loaded with [`Assets::from_manifest`].
+//!
+//! ```rust,ignore
+//! use rustapi_rs::protocol::view::{Assets, Templates};
+//!
+//! let assets = Assets::new("./static", "/assets")?;
+//! let templates = Templates::new("templates/**/*.html")?;
+//! templates.assets(assets.clone());
+//!
+//! let app = assets.mount(RustApi::new().state(templates));
+//! ```
+//!
+//! ```html
+//! <link rel="stylesheet" href="{{ asset(path='css/app.css') }}">
+//! <!-- <link rel="stylesheet" href="/assets/css/app.3f2a9c1e.css"> -->
+//! ```
+
+use crate::{Templates, ViewError};
+use rustapi_core::{RustApi, StaticFileConfig};
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, RwLock};
+use tera::{Tera, Value};
+
+/// Name of the manifest written by [`Assets::build`]
+pub const ASSET_MANIFEST: &str = "manifest.json";
+
+/// Length of the content hash in file names (hex characters)
+const HASH_LEN: usize = 8;
+
+/// Fingerprinted asset files and the URL prefix they are served under
+#[derive(Debug, Clone)]
+pub struct Assets {
+    root: PathBuf,
+    prefix: String,
+    /// Logical path -> fingerprinted path, both relative to the root
+    manifest: Arc<BTreeMap<String, String>>,
+    /// Whether the files on disk already carry their fingerprinted names
+    prebuilt: bool,
+}
+
+impl Assets {
+    /// Fingerprint the files under `root` at startup
+    ///
+    /// Files keep their names on disk; requests for the fingerprinted
+    /// names are mapped back to them when serving.
+    pub fn new(root: impl Into<PathBuf>, prefix: impl Into<String>) -> Result<Self, ViewError> {
+        let root = root.into();
+        let mut manifest = BTreeMap::new();
+        for (logical, path) in asset_files(&root)? {
+            let content = std::fs::read(&path)?;
+            manifest.insert(logical.clone(), fingerprinted_name(&logical, &content));
+        }
+
+        Ok(Self {
+            root,
+            prefix: normalize_prefix(prefix.into()),
+            manifest: Arc::new(manifest),
+            prebuilt: false,
+        })
+    }
+
+    /// Copy the files under `src` to `out_dir` with fingerprinted names
+    ///
+    /// Also writes [`ASSET_MANIFEST`] to `out_dir`, mapping logical to
+    /// fingerprinted paths, for [`Assets::from_manifest`].
+    pub fn build(src: impl AsRef<Path>, out_dir: impl AsRef<Path>) -> Result<(), ViewError> {
+        let out_dir = out_dir.as_ref();
+        let mut manifest = BTreeMap::new();
+        for (logical, path) in asset_files(src.as_ref())? {
+            let content = std::fs::read(&path)?;
+            let hashed = fingerprinted_name(&logical, &content);
+            let target = out_dir.join(&hashed);
+            if let Some(parent) = target.parent() {
+                std::fs::create_dir_all(parent)?;
+            }
+            std::fs::write(target, content)?;
+            manifest.insert(logical, hashed);
+        }
+
+        std::fs::create_dir_all(out_dir)?;
+        let json = serde_json::to_string_pretty(&manifest)
+            .map_err(|e| ViewError::serialization_error(e.to_string()))?;
+        std::fs::write(out_dir.join(ASSET_MANIFEST), json)?;
+        Ok(())
+    }
+
+    /// Load assets built by [`Assets::build`] from `dir`
+    pub fn from_manifest(
+        dir: impl Into<PathBuf>,
+        prefix: impl Into<String>,
+    ) -> Result<Self, ViewError> {
+        let root = dir.into();
+        let json = std::fs::read_to_string(root.join(ASSET_MANIFEST))?;
+        let manifest = serde_json::from_str(&json)
+            .map_err(|e| ViewError::parse_error(format!("Invalid asset manifest: {}", e)))?;
+
+        Ok(Self {
+            root,
+            prefix: normalize_prefix(prefix.into()),
+            manifest: Arc::new(manifest),
+            prebuilt: true,
+        })
+    }
+
+    /// Fingerprinted path of an asset, relative to the root
+    pub fn get(&self, path: &str) -> Option<&str> {
+        self.manifest
+            .get(path.trim_start_matches('/'))
+            .map(String::as_str)
+    }
+
+    /// URL of an asset (`css/app.css` -> `/assets/css/app.3f2a9c1e.css`)
+    ///
+    /// Unknown paths are returned unhashed under the prefix.
+    pub fn url(&self, path: &str) -> String {
+        let path = path.trim_start_matches('/');
+        format!("{}/{}", self.prefix, self.get(path).unwrap_or(path))
+    }
+
+    /// Static file configuration serving these assets with far-future
+    /// cache headers
+    pub fn static_config(&self) -> StaticFileConfig {
+        let config = StaticFileConfig::new(&self.root, self.prefix.clone());
+        if self.prebuilt {
+            config.max_age(31_536_000).immutable(true)
+        } else {
+            config.fingerprints(
+                self.manifest
+                    .iter()
+                    .map(|(logical, hashed)| (hashed.clone(), logical.clone()))
+                    .collect::<HashMap<_, _>>(),
+            )
+        }
+    }
+
+    /// Serve the assets under their prefix
+    pub fn mount(&self, app: RustApi) -> RustApi {
+        app.serve_static_with_config(self.static_config())
+    }
+}
+
+impl Templates {
+    /// Resolve `asset(path="...")` in templates with these assets
+    pub fn assets(&self, assets: Assets) -> &Self {
+        *self.assets.write().unwrap_or_else(|e| e.into_inner()) = Some(assets);
+        self
+    }
+}
+
+pub(crate) type SharedAssets = Arc<RwLock<Option<Assets>>>;
+
+/// Register the `asset` template function
+pub(crate) fn register(tera: &mut Tera, assets: SharedAssets) {
+    tera.register_function("asset", move |args: &HashMap<String, Value>| {
+        let path = match args.get("path") {
+            Some(path) => tera::try_get_value!("asset", "path", String, path),
+            None => {
+                return Err(tera::Error::msg(
+                    "Function `asset` requires a `path` argument",
+                ))
+            }
+        };
+        match &*assets.read().unwrap_or_else(|e| e.into_inner()) {
+            Some(assets) => Ok(Value::String(assets.url(&path))),
+            None => Err(tera::Error::msg(
+                "Function `asset` needs assets; call `Templates::assets` first",
+            )),
+        }
+    })
+}
+
+/// `css/app.css` + content -> `css/app.<hash>.css`
+fn fingerprinted_name(logical: &str, content: &[u8]) -> String {
+    let digest = Sha256::digest(content);
+    let hash: String = digest
+        .iter()
+        .take(HASH_LEN / 2)
+        .map(|byte| format!("{:02x}", byte))
+        .collect();
+
+    let (dir, file) = match logical.rsplit_once('/') {
+        Some((dir, file)) => (format!("{}/", dir), file),
+        None => (String::new(), logical),
+    };
+    match file.rsplit_once('.') {
+        Some((stem, ext)) if !stem.is_empty() => format!("{}{}.{}.{}", dir, stem, hash, ext),
+        _ => format!("{}{}.{}", dir, file, hash),
+    }
+}
+
+/// All non-hidden files under `root`, as (`/`-separated relative path, path)
+fn asset_files(root: &Path) -> Result<Vec<(String, PathBuf)>, ViewError> {
+    let mut files = Vec::new();
+    let mut dirs = vec![root.to_path_buf()];
+    while let Some(dir) = dirs.pop() {
+        for entry in std::fs::read_dir(&dir)? {
+            let path = entry?.path();
+            let hidden = path
+                .file_name()
+                .and_then(|name| name.to_str())
+                .is_none_or(|name| name.starts_with('.'));
+            if hidden {
+                continue;
+            }
+            if path.is_dir() {
+                dirs.push(path);
+            } else if let Ok(relative) = path.strip_prefix(root) {
+                let logical = relative
+                    .components()
+                    .map(|c| c.as_os_str().to_string_lossy())
+                    .collect::<Vec<_>>()
+                    .join("/");
+                files.push((logical, path));
+            }
+        }
+    }
+    Ok(files)
+}
+
+fn normalize_prefix(prefix: String) -> String {
+    format!("/{}", prefix.trim_matches('/'))
+        .trim_end_matches('/')
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn asset_dir(name: &str) -> PathBuf {
+        let dir =
+            std::env::temp_dir().join(format!("rustapi-assets-{}-{}", name, std::process::id()));
+        std::fs::create_dir_all(dir.join("css")).unwrap();
+        std::fs::write(dir.join("css/app.css"), "body { color: red }").unwrap();
+        std::fs::write(dir.join("app.js"), "console.log(1)").unwrap();
+        dir
+    }
+
+    #[tokio::test]
+    async fn fingerprints_at_startup() {
+        let dir = asset_dir("startup");
+        let assets = Assets::new(&dir, "/assets/").unwrap();
+
+        let css = assets.url("css/app.css");
+        assert!(css.starts_with("/assets/css/app."), "{}", css);
+        assert!(css.ends_with(".css"));
+        assert_eq!(css.len(), "/assets/css/app..css".len() + HASH_LEN);
+        assert_eq!(assets.url("missing.png"), "/assets/missing.png");
+
+        let config = assets.static_config();
+        let hashed = css.trim_start_matches("/assets/");
+        assert_eq!(config.fingerprints.as_ref().unwrap()[hashed], "css/app.css");
+
+        let templates = Templates::empty();
+        templates.assets(assets);
+        templates
+            .add_template("page", "{{ asset(path='app.js') }}")
+            .await
+            .unwrap();
+        let html = templates
+            .render("page", &tera::Context::new())
+            .await
+            .unwrap();
+        assert!(
+            html.starts_with("/assets/app.") && html.ends_with(".js"),
+            "{}",
+            html
+        );
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn build_and_load_manifest() {
+        let src = asset_dir("src");
+        let out = src.with_file_name(format!("rustapi-assets-out-{}", std::process::id()));
+        Assets::build(&src, &out).unwrap();
+
+        let assets = Assets::from_manifest(&out, "static").unwrap();
+        let hashed = assets.get("css/app.css").unwrap().to_string();
+        assert!(out.join(&hashed).is_file());
+        assert_eq!(assets.url("css/app.css"), format!("/static/{}", hashed));
+
+        // Same content, same name
+        assert_eq!(
+            Assets::new(&src, "/static").unwrap().get("css/app.css"),
+            Some(hashed.as_str())
+        );
+        assert!(assets.static_config().immutable);
+
+        std::fs::remove_dir_all(&src).ok();
+        std::fs::remove_dir_all(&out).ok();
+    }
+}
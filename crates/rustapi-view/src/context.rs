@@ -19,8 +19,26 @@ use tera::Context;
 ///     .insert_if("admin", true, |_| user.is_admin())
 ///     .build();
 /// ```
+///
+/// Request-wide values have dedicated helpers, so layouts can rely on them:
+///
+/// ```rust,ignore
+/// let context = ContextBuilder::new()
+///     .current_user(&user)          // {{ current_user.name }}
+///     .flash("success", "Saved!")   // {% for f in flash %}{{ f.level }}: {{ f.message }}{% endfor %}
+///     .build();
+/// ```
 pub struct ContextBuilder {
     context: Context,
+    flash: Vec<FlashMessage>,
+}
+
+/// A flash message, exposed to templates as `flash` (a list of
+/// `{ level, message }` objects)
+#[derive(Debug, Clone, Serialize)]
+struct FlashMessage {
+    level: String,
+    message: String,
 }
 
 impl ContextBuilder {
@@ -28,9 +46,29 @@ impl ContextBuilder {
     pub fn new() -> Self {
         Self {
             context: Context::new(),
+            flash: Vec::new(),
         }
     }
 
+    /// Insert the current user as `current_user`
+    pub fn current_user<T: Serialize + ?Sized>(self, user: &T) -> Self {
+        self.insert("current_user", user)
+    }
+
+    /// Set the locale used by the `t` template function (`i18n` feature)
+    pub fn locale(self, locale: impl Into<String>) -> Self {
+        self.insert("locale", &locale.into())
+    }
+
+    /// Add a flash message (`level` is e.g. `success`, `error`, `info`)
+    pub fn flash(mut self, level: impl Into<String>, message: impl Into<String>) -> Self {
+        self.flash.push(FlashMessage {
+            level: level.into(),
+            message: message.into(),
+        });
+        self
+    }
+
     /// Insert a value into the context
     pub fn insert<T: Serialize + ?Sized>(mut self, key: impl Into<String>, value: &T) -> Self {
         self.context.insert(key.into(), value);
@@ -75,7 +113,10 @@ impl ContextBuilder {
     }
 
     /// Build the context
-    pub fn build(self) -> Context {
+    pub fn build(mut self) -> Context {
+        if !self.flash.is_empty() {
+            self.context.insert("flash", &self.flash);
+        }
         self.context
     }
 }
@@ -119,6 +160,20 @@ mod tests {
         assert!(!context.contains_key("hidden"));
     }
 
+    #[test]
+    fn test_current_user_and_flash() {
+        let context = ContextBuilder::new()
+            .current_user(&serde_json::json!({ "name": "Alice" }))
+            .flash("success", "Saved")
+            .flash("info", "Welcome back")
+            .build();
+
+        let json = context.into_json();
+        assert_eq!(json["current_user"]["name"], "Alice");
+        assert_eq!(json["flash"][0]["level"], "success");
+        assert_eq!(json["flash"][1]["message"], "Welcome back");
+    }
+
     #[test]
     fn test_insert_some() {
         let name: Option<&str> = Some("Alice");
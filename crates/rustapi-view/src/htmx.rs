@@ -0,0 +1,299 @@
+//! htmx support
+//!
+//! [`HxRequest`] exposes the `HX-*` headers htmx sends, and [`HxResponse`]
+//! adds `HX-*` response headers (events to trigger, URL to push, redirects,
+//! retargeting) to any response. [`Fragment`] renders a partial template for
+//! a swap.
+//!
+//! ```rust,ignore
+//! use rustapi_rs::protocol::view::{Fragment, HxRequest, HxResponse, Templates, View};
+//!
+//! async fn create_user(
+//!     State(templates): State<Templates>,
+//!     hx: HxRequest,
+//!     Form(input): Form<NewUser>,
+//! ) -> HxResponse<View<User>> {
+//!     let user = save(input).await;
+//!     if !hx.is_htmx() {
+//!         return HxResponse::new(View::render(&templates, "users/show", user).await);
+//!     }
+//!     let url = format!("/users/{}", user.id);
+//!     Fragment::render(&templates, "users/row", user)
+//!         .await
+//!         .trigger("userCreated")
+//!         .push_url(url)
+//! }
+//! ```
+
+use crate::{Templates, View};
+use http::{HeaderMap, HeaderName, HeaderValue};
+use rustapi_core::{FromRequestParts, IntoResponse, Request, Response, Result};
+use rustapi_openapi::{Operation, OperationModifier, ResponseModifier};
+use serde::Serialize;
+use serde_json::Value;
+
+/// htmx request headers
+///
+/// Extracting never fails; for regular requests [`HxRequest::is_htmx`] is
+/// `false` and the other fields are empty.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct HxRequest {
+    /// `HX-Request`: the request was made by htmx
+    pub request: bool,
+    /// `HX-Boosted`: the request comes from an `hx-boost` element
+    pub boosted: bool,
+    /// `HX-History-Restore-Request`: history restoration after a cache miss
+    pub history_restore_request: bool,
+    /// `HX-Current-URL`: the current URL of the browser
+    pub current_url: Option<String>,
+    /// `HX-Prompt`: the user's response to an `hx-prompt`
+    pub prompt: Option<String>,
+    /// `HX-Target`: the `id` of the target element
+    pub target: Option<String>,
+    /// `HX-Trigger`: the `id` of the triggered element
+    pub trigger: Option<String>,
+    /// `HX-Trigger-Name`: the `name` of the triggered element
+    pub trigger_name: Option<String>,
+}
+
+impl HxRequest {
+    /// Read the htmx headers of a request
+    pub fn from_headers(headers: &HeaderMap) -> Self {
+        let text = |name: &str| {
+            headers
+                .get(name)
+                .and_then(|value| value.to_str().ok())
+                .map(str::to_string)
+        };
+        let flag = |name: &str| text(name).is_some_and(|value| value == "true");
+
+        Self {
+            request: flag("hx-request"),
+            boosted: flag("hx-boosted"),
+            history_restore_request: flag("hx-history-restore-request"),
+            current_url: text("hx-current-url"),
+            prompt: text("hx-prompt"),
+            target: text("hx-target"),
+            trigger: text("hx-trigger"),
+            trigger_name: text("hx-trigger-name"),
+        }
+    }
+
+    /// Whether the request was made by htmx (and not boosted), so a
+    /// fragment rather than a full page should be returned
+    pub fn is_htmx(&self) -> bool {
+        self.request && !self.boosted
+    }
+}
+
+impl FromRequestParts for HxRequest {
+    fn from_request_parts(req: &Request) -> Result<Self> {
+        Ok(Self::from_headers(req.headers()))
+    }
+}
+
+impl OperationModifier for HxRequest {
+    fn update_operation(_op: &mut Operation) {}
+}
+
+/// A response with htmx response headers
+///
+/// Wraps any response; header values that are not valid header text are
+/// skipped with a warning.
+pub struct HxResponse<R> {
+    inner: R,
+    headers: Vec<(&'static str, String)>,
+    triggers: Vec<(String, Option<Value>)>,
+}
+
+/// A partial template rendered for an htmx swap
+pub type Fragment<T = ()> = HxResponse<View<T>>;
+
+impl<R> HxResponse<R> {
+    /// Wrap a response
+    pub fn new(inner: R) -> Self {
+        Self {
+            inner,
+            headers: Vec::new(),
+            triggers: Vec::new(),
+        }
+    }
+
+    fn header(mut self, name: &'static str, value: impl Into<String>) -> Self {
+        self.headers.push((name, value.into()));
+        self
+    }
+
+    /// Trigger a client-side event (`HX-Trigger`)
+    pub fn trigger(mut self, event: impl Into<String>) -> Self {
+        self.triggers.push((event.into(), None));
+        self
+    }
+
+    /// Trigger a client-side event with a detail value (`HX-Trigger`)
+    pub fn trigger_with<T: Serialize>(mut self, event: impl Into<String>, detail: &T) -> Self {
+        let detail = serde_json::to_value(detail).unwrap_or(Value::Null);
+        self.triggers.push((event.into(), Some(detail)));
+        self
+    }
+
+    /// Push a URL into the browser history (`HX-Push-Url`)
+    pub fn push_url(self, url: impl Into<String>) -> Self {
+        self.header("hx-push-url", url)
+    }
+
+    /// Replace the current URL without a history entry (`HX-Replace-Url`)
+    pub fn replace_url(self, url: impl Into<String>) -> Self {
+        self.header("hx-replace-url", url)
+    }
+
+    /// Redirect with a full page load (`HX-Redirect`)
+    pub fn redirect(self, url: impl Into<String>) -> Self {
+        self.header("hx-redirect", url)
+    }
+
+    /// Navigate without a full page load (`HX-Location`)
+    pub fn location(self, url: impl Into<String>) -> Self {
+        self.header("hx-location", url)
+    }
+
+    /// Reload the whole page (`HX-Refresh`)
+    pub fn refresh(self) -> Self {
+        self.header("hx-refresh", "true")
+    }
+
+    /// Swap into another element (`HX-Retarget`, a CSS selector)
+    pub fn retarget(self, selector: impl Into<String>) -> Self {
+        self.header("hx-retarget", selector)
+    }
+
+    /// Change the swap strategy (`HX-Reswap`, e.g. `outerHTML`)
+    pub fn reswap(self, swap: impl Into<String>) -> Self {
+        self.header("hx-reswap", swap)
+    }
+
+    /// Swap only part of the response (`HX-Reselect`, a CSS selector)
+    pub fn reselect(self, selector: impl Into<String>) -> Self {
+        self.header("hx-reselect", selector)
+    }
+
+    /// `HX-Trigger` value: a plain list, or a JSON object if any event has
+    /// a detail
+    fn trigger_header(&self) -> Option<String> {
+        if self.triggers.is_empty() {
+            return None;
+        }
+        if self.triggers.iter().all(|(_, detail)| detail.is_none()) {
+            let events: Vec<&str> = self.triggers.iter().map(|(e, _)| e.as_str()).collect();
+            return Some(events.join(", "));
+        }
+        let events: serde_json::Map<String, Value> = self
+            .triggers
+            .iter()
+            .map(|(event, detail)| (event.clone(), detail.clone().unwrap_or(Value::Null)))
+            .collect();
+        Some(Value::Object(events).to_string())
+    }
+}
+
+impl<T: Serialize> HxResponse<View<T>> {
+    /// Render a partial template as a fragment
+    ///
+    /// See [`Templates::render_partial`] for how `partial` is resolved.
+    pub async fn render(templates: &Templates, partial: &str, context: T) -> Self {
+        Self::new(View::partial(templates, partial, context).await)
+    }
+}
+
+impl<R: IntoResponse> IntoResponse for HxResponse<R> {
+    fn into_response(self) -> Response {
+        let trigger = self.trigger_header();
+        let mut response = self.inner.into_response();
+        let headers = self
+            .headers
+            .iter()
+            .map(|(name, value)| (*name, value.as_str()))
+            .chain(trigger.as_deref().map(|value| ("hx-trigger", value)));
+        for (name, value) in headers {
+            match HeaderValue::from_str(value) {
+                Ok(value) => {
+                    response
+                        .headers_mut()
+                        .insert(HeaderName::from_static(name), value);
+                }
+                Err(_) => tracing::warn!("Skipping invalid {} header value: {:?}", name, value),
+            }
+        }
+        response
+    }
+}
+
+impl<R: ResponseModifier> ResponseModifier for HxResponse<R> {
+    fn update_response(op: &mut Operation) {
+        R::update_response(op);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use http::StatusCode;
+
+    #[test]
+    fn reads_htmx_headers() {
+        let mut headers = HeaderMap::new();
+        headers.insert("hx-request", HeaderValue::from_static("true"));
+        headers.insert("hx-target", HeaderValue::from_static("user-list"));
+        let hx = HxRequest::from_headers(&headers);
+        assert!(hx.is_htmx());
+        assert_eq!(hx.target.as_deref(), Some("user-list"));
+        assert_eq!(hx.trigger, None);
+
+        headers.insert("hx-boosted", HeaderValue::from_static("true"));
+        assert!(!HxRequest::from_headers(&headers).is_htmx());
+        assert!(!HxRequest::from_headers(&HeaderMap::new()).request);
+    }
+
+    #[test]
+    fn sets_response_headers() {
+        let response = HxResponse::new(StatusCode::CREATED)
+            .trigger("userCreated")
+            .trigger("listChanged")
+            .push_url("/users/1")
+            .reswap("outerHTML")
+            .into_response();
+        assert_eq!(response.status(), StatusCode::CREATED);
+        assert_eq!(response.headers()["hx-trigger"], "userCreated, listChanged");
+        assert_eq!(response.headers()["hx-push-url"], "/users/1");
+        assert_eq!(response.headers()["hx-reswap"], "outerHTML");
+
+        let response = HxResponse::new(())
+            .trigger("saved")
+            .trigger_with("notify", &serde_json::json!({ "level": "info" }))
+            .into_response();
+        let trigger: Value =
+            serde_json::from_str(response.headers()["hx-trigger"].to_str().unwrap()).unwrap();
+        assert_eq!(
+            trigger,
+            serde_json::json!({ "saved": null, "notify": { "level": "info" } })
+        );
+    }
+
+    #[tokio::test]
+    async fn renders_fragment() {
+        let templates = Templates::from_raw([("users/_row.html", "<tr>{{ name }}</tr>")]).unwrap();
+        let response = Fragment::render(
+            &templates,
+            "users/row",
+            serde_json::json!({ "name": "Ann" }),
+        )
+        .await
+        .redirect("/users")
+        .into_response();
+        assert_eq!(response.headers()["hx-redirect"], "/users");
+        assert_eq!(
+            response.headers()["content-type"],
+            "text/html; charset=utf-8"
+        );
+    }
+}
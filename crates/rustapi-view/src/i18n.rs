@@ -0,0 +1,270 @@
+//! Template translations
+//!
+//! With the `i18n` feature every template can call `t(key="...")`. By
+//! default keys are looked up in the same rust-i18n locale files that
+//! `rustapi-validate` uses for validation messages, so pages and validation
+//! errors share one translation source. Apps with their own rust-i18n
+//! locales plug them in with [`Templates::translator`].
+//!
+//! The locale is the `locale` variable of the render context (usually from
+//! the [`RequestLocale`] extractor), or an explicit `locale` argument. Other
+//! arguments fill `%{name}` placeholders:
+//!
+//! ```html
+//! <p>{{ t(key="greeting", name=user.name) }}</p>
+//! <p>{{ t(key="validation.email.invalid", locale="tr") }}</p>
+//! ```
+//!
+//! ```rust,ignore
+//! use rustapi_rs::protocol::view::{ContextBuilder, RequestLocale, Templates, View};
+//!
+//! async fn home(State(templates): State<Templates>, locale: RequestLocale) -> View<()> {
+//!     let ctx = ContextBuilder::new().locale(locale.as_str()).build();
+//!     View::render_context(&templates, "home.html", &ctx).await
+//! }
+//! ```
+
+use crate::Templates;
+use rustapi_core::{FromRequestParts, Locale, LocaleConfig, Request, Result};
+use rustapi_openapi::{Operation, OperationModifier, Parameter, SchemaRef};
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+use tera::{Tera, Value};
+
+/// Translation function: `(key, locale) -> text`
+pub(crate) type Translator = Arc<dyn Fn(&str, Option<&str>) -> String + Send + Sync>;
+
+/// Translation settings shared by a [`Templates`] instance and its `t` function
+pub(crate) struct I18n {
+    pub(crate) translator: Translator,
+    pub(crate) locales: Vec<String>,
+}
+
+impl Default for I18n {
+    fn default() -> Self {
+        Self {
+            translator: Arc::new(rustapi_validate::v2::i18n::translate),
+            locales: rustapi_validate::v2::i18n::available_locales()
+                .into_iter()
+                .map(String::from)
+                .collect(),
+        }
+    }
+}
+
+pub(crate) type SharedI18n = Arc<RwLock<I18n>>;
+
+thread_local! {
+    /// Locale of the template being rendered on this thread
+    static RENDER_LOCALE: RefCell<Option<String>> = const { RefCell::new(None) };
+}
+
+/// Run a (synchronous) Tera render with `locale` as the default for `t`.
+pub(crate) fn with_locale<R>(locale: Option<&str>, render: impl FnOnce() -> R) -> R {
+    let previous = RENDER_LOCALE.with(|cell| cell.replace(locale.map(String::from)));
+    let result = render();
+    RENDER_LOCALE.with(|cell| *cell.borrow_mut() = previous);
+    result
+}
+
+/// Register the `t` template function
+pub(crate) fn register(tera: &mut Tera, i18n: SharedI18n) {
+    tera.register_function("t", move |args: &HashMap<String, Value>| {
+        let key = match args.get("key") {
+            Some(key) => tera::try_get_value!("t", "key", String, key),
+            None => return Err(tera::Error::msg("Function `t` requires a `key` argument")),
+        };
+        let locale = match args.get("locale") {
+            Some(locale) => Some(tera::try_get_value!("t", "locale", String, locale)),
+            None => RENDER_LOCALE.with(|cell| cell.borrow().clone()),
+        };
+
+        let translator = i18n
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .translator
+            .clone();
+        let mut text = translator(&key, locale.as_deref());
+        for (name, value) in args {
+            if name == "key" || name == "locale" {
+                continue;
+            }
+            let replacement = match value {
+                Value::String(s) => s.clone(),
+                other => other.to_string(),
+            };
+            text = text.replace(&format!("%{{{}}}", name), &replacement);
+        }
+        Ok(Value::String(text))
+    });
+}
+
+impl Templates {
+    /// Use a custom translation function for `t`
+    ///
+    /// For apps with their own rust-i18n locale files:
+    ///
+    /// ```rust,ignore
+    /// rust_i18n::i18n!("locales");
+    ///
+    /// templates.translator(|key, locale| match locale {
+    ///     Some(locale) => rust_i18n::t!(key, locale = locale).to_string(),
+    ///     None => rust_i18n::t!(key).to_string(),
+    /// });
+    /// ```
+    pub fn translator<F>(&self, translator: F) -> &Self
+    where
+        F: Fn(&str, Option<&str>) -> String + Send + Sync + 'static,
+    {
+        self.i18n
+            .write()
+            .unwrap_or_else(|e| e.into_inner())
+            .translator = Arc::new(translator);
+        self
+    }
+
+    /// Set the locales [`RequestLocale`] can choose from
+    ///
+    /// Defaults to the locales of `rustapi-validate`.
+    pub fn locales<I, S>(&self, locales: I) -> &Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.i18n.write().unwrap_or_else(|e| e.into_inner()).locales =
+            locales.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Translate `key` the way the `t` template function does
+    pub fn translate(&self, key: &str, locale: Option<&str>) -> String {
+        let translator = self
+            .i18n
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .translator
+            .clone();
+        translator(key, locale)
+    }
+}
+
+/// Locale of the request, negotiated from `Accept-Language`
+///
+/// With a [`LocaleConfig`] in app state this is the core [`Locale`], including
+/// its header and cookie overrides. Otherwise it picks the preferred language
+/// (by quality) that has translations: the locales configured with
+/// [`Templates::locales`] when `Templates` is app state, otherwise the locales
+/// of `rustapi-validate`. `en-US` matches `en`. Falls back to `en` if
+/// available, else the first supported locale.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RequestLocale(pub String);
+
+impl RequestLocale {
+    /// The locale tag (e.g. `en`)
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// Choose a locale from an `Accept-Language` value
+    pub fn negotiate(accept_language: &str, supported: &[String]) -> Self {
+        Locale::negotiate(accept_language, supported)
+            .or_else(|| Locale::negotiate("en", supported))
+            .map(Self::from)
+            .or_else(|| supported.first().cloned().map(Self))
+            .unwrap_or_else(|| Self("en".to_string()))
+    }
+}
+
+impl From<Locale> for RequestLocale {
+    fn from(locale: Locale) -> Self {
+        Self(locale.0)
+    }
+}
+
+impl FromRequestParts for RequestLocale {
+    fn from_request_parts(req: &Request) -> Result<Self> {
+        if let Some(locale) = Locale::configured(req) {
+            return Ok(locale.into());
+        }
+        let supported = match req.state().get::<Templates>() {
+            Some(templates) => templates
+                .i18n
+                .read()
+                .unwrap_or_else(|e| e.into_inner())
+                .locales
+                .clone(),
+            None => I18n::default().locales,
+        };
+        Ok(Locale::resolve(req, &LocaleConfig::new(supported)).into())
+    }
+}
+
+impl OperationModifier for RequestLocale {
+    fn update_operation(op: &mut Operation) {
+        op.parameters.push(Parameter {
+            name: "Accept-Language".to_string(),
+            location: "header".to_string(),
+            description: Some("Preferred languages for the response".to_string()),
+            required: false,
+            deprecated: None,
+            style: None,
+            explode: None,
+            schema: Some(SchemaRef::Inline(serde_json::json!({ "type": "string" }))),
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ContextBuilder;
+
+    fn supported() -> Vec<String> {
+        vec!["en".to_string(), "tr".to_string()]
+    }
+
+    #[test]
+    fn negotiates_by_quality() {
+        let locale = RequestLocale::negotiate("de-DE, tr;q=0.8, en;q=0.5", &supported());
+        assert_eq!(locale.as_str(), "tr");
+
+        let locale = RequestLocale::negotiate("tr-TR;q=0.4, en-GB;q=0.9", &supported());
+        assert_eq!(locale.as_str(), "en");
+
+        assert_eq!(RequestLocale::negotiate("", &supported()).as_str(), "en");
+    }
+
+    #[tokio::test]
+    async fn t_shares_validation_translations() {
+        let templates = Templates::empty();
+        templates
+            .add_template("page", r#"{{ t(key="validation.email.invalid") }}"#)
+            .await
+            .unwrap();
+
+        let ctx = ContextBuilder::new().locale("tr").build();
+        let html = templates.render("page", &ctx).await.unwrap();
+        assert_eq!(
+            html,
+            templates.translate("validation.email.invalid", Some("tr"))
+        );
+        assert_ne!(html, "validation.email.invalid");
+    }
+
+    #[tokio::test]
+    async fn custom_translator_with_arguments() {
+        let templates = Templates::empty();
+        templates.translator(|key, locale| format!("{}:{}, %{{name}}", locale.unwrap_or("-"), key));
+        templates
+            .add_template("page", r#"{{ t(key="hello", name="Alice", locale="de") }}"#)
+            .await
+            .unwrap();
+
+        let html = templates
+            .render("page", &tera::Context::new())
+            .await
+            .unwrap();
+        assert_eq!(html, "de:hello, Alice");
+    }
+}
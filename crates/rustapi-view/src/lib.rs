@@ -11,7 +11,13 @@
 //! - **Type-Safe Context**: Build template context from Rust structs via serde
 //! - **Auto-Reload**: Development mode can auto-reload templates on change
 //! - **Response Types**: `View<T>` response type for rendering templates
-//! - **Layout Support**: Template inheritance with blocks
+//! - **Layout Support**: Template inheritance with blocks, or runtime layouts via `View::with_layout`
+//! - **Assets**: Content-hashed asset URLs via `asset(path="...")`, served with far-future caching
+//! - **Page data**: `Page` embeds loaded data for hydration and applies a per-route `CachePolicy`
+//! - **Streaming**: `StreamingView` flushes the page shell first and streams slow sections in later
+//! - **htmx**: `HxRequest` extractor, `HxResponse` headers and `Fragment` partial responses
+//! - **Translations**: `t(key="...")` in templates, sharing `rustapi-validate` locales (`i18n` feature)
+//! - **Partials & Globals**: Render fragments with `View::partial`; share values with `Templates::global`
 //!
 //! ## Quick Start
 //!
@@ -48,13 +54,25 @@
 #![warn(missing_docs)]
 #![warn(rustdoc::missing_crate_level_docs)]
 
+mod assets;
 mod context;
 mod error;
+mod htmx;
+#[cfg(feature = "i18n")]
+mod i18n;
+mod page;
+mod streaming;
 mod templates;
 mod view;
 
+pub use assets::{Assets, ASSET_MANIFEST};
 pub use context::ContextBuilder;
 pub use error::ViewError;
+pub use htmx::{Fragment, HxRequest, HxResponse};
+#[cfg(feature = "i18n")]
+pub use i18n::RequestLocale;
+pub use page::{CachePolicy, Page, PAGE_DATA_ID};
+pub use streaming::StreamingView;
 pub use templates::{Templates, TemplatesConfig};
 pub use view::View;
 
@@ -63,5 +81,7 @@ pub use tera::Context;
 
 /// Prelude module for convenient imports
 pub mod prelude {
-    pub use crate::{Context, ContextBuilder, Templates, TemplatesConfig, View, ViewError};
+    #[cfg(feature = "i18n")]
+    pub use crate::RequestLocale;
+    pub use crate::{Assets, Context, ContextBuilder, Templates, TemplatesConfig, View, ViewError};
 }
@@ -0,0 +1,287 @@
+//! Pages with loaded data
+//!
+//! A [`Page`] renders a template with the data a handler loaded and also
+//! embeds that data as JSON, so client-side code can hydrate from it
+//! without fetching it again. A [`CachePolicy`] per route sets
+//! `Cache-Control`, including stale-while-revalidate for pages that may be
+//! served slightly out of date.
+//!
+//! ```rust,ignore
+//! use rustapi_rs::protocol::view::{CachePolicy, Page, Templates};
+//!
+//! // templates/posts/show.html:
+//! //   <h1>{{ data.title }}</h1>
+//! //   {{ page_data_script | safe }}
+//! #[rustapi_rs::get("/posts/{slug}")]
+//! async fn show(State(templates): State<Templates>, State(db): State<Db>, Path(slug): Path<String>) -> Result<Page<Post>> {
+//!     let post = db.post(&slug).await?;
+//!     Ok(Page::render(&templates, "posts/show.html", post)
+//!         .await
+//!         .cache(CachePolicy::revalidate(Duration::from_secs(60))))
+//! }
+//! ```
+//!
+//! The page context holds the data as `data` and a ready
+//! `<script id="__RUSTAPI_DATA__" type="application/json">` element as
+//! `page_data_script`.
+//!
+//! Pages can also receive their matched route params as a typed struct.
+//! `#[derive(TypedPath)]` checks at compile time that the pattern and the
+//! struct fields agree; [`Page::render_with_params`] exposes the struct to
+//! the template as `params`:
+//!
+//! ```rust,ignore
+//! #[derive(Serialize, Deserialize, TypedPath)]
+//! #[typed_path("/posts/{slug}")]
+//! struct PostPath {
+//!     slug: String,
+//! }
+//!
+//! // templates/posts/show.html: <a href="/posts/{{ params.slug }}">{{ data.title }}</a>
+//! async fn show(State(templates): State<Templates>, State(db): State<Db>, Path(path): Path<PostPath>) -> Result<Page<Post>> {
+//!     let post = db.post(&path.slug).await?;
+//!     Ok(Page::render_with_params(&templates, "posts/show.html", &path, post).await)
+//! }
+//!
+//! RustApi::new().typed::<PostPath>(get(show))
+//! ```
+
+use crate::{Templates, View, ViewError};
+use http::{header, HeaderValue};
+use rustapi_core::{IntoResponse, TypedPath};
+use rustapi_openapi::{Operation, ResponseModifier};
+use serde::Serialize;
+use std::marker::PhantomData;
+use std::time::Duration;
+
+/// `id` of the script element holding the page data
+pub const PAGE_DATA_ID: &str = "__RUSTAPI_DATA__";
+
+/// `Cache-Control` policy of a page
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CachePolicy {
+    /// Never cache (`no-store`)
+    NoStore,
+    /// Cache in the browser only (`private, max-age=N`)
+    Private(Duration),
+    /// Cache anywhere (`public, max-age=N`)
+    Public(Duration),
+    /// Serve from shared caches for `fresh`, then serve stale for up to
+    /// `stale` while revalidating in the background
+    Revalidate {
+        /// How long the page is fresh
+        fresh: Duration,
+        /// How long a stale page may be served while revalidating
+        stale: Duration,
+    },
+}
+
+impl CachePolicy {
+    /// Fresh for `fresh`, then served stale for as long again while
+    /// revalidating
+    pub fn revalidate(fresh: Duration) -> Self {
+        Self::Revalidate {
+            fresh,
+            stale: fresh,
+        }
+    }
+
+    /// The `Cache-Control` header value
+    pub fn header_value(&self) -> String {
+        match self {
+            Self::NoStore => "no-store".to_string(),
+            Self::Private(max_age) => format!("private, max-age={}", max_age.as_secs()),
+            Self::Public(max_age) => format!("public, max-age={}", max_age.as_secs()),
+            Self::Revalidate { fresh, stale } => format!(
+                "public, max-age=0, s-maxage={}, stale-while-revalidate={}",
+                fresh.as_secs(),
+                stale.as_secs()
+            ),
+        }
+    }
+}
+
+/// A rendered page with its data embedded for hydration
+pub struct Page<D> {
+    view: View<()>,
+    cache: Option<CachePolicy>,
+    _data: PhantomData<D>,
+}
+
+impl<D: Serialize> Page<D> {
+    /// Render `template` with `data`
+    pub async fn render(templates: &Templates, template: &str, data: D) -> Self {
+        Self::render_with_context(templates, template, &tera::Context::new(), data).await
+    }
+
+    /// Render `template` with `data` and the matched route params, available
+    /// to the template as `params`
+    pub async fn render_with_params<P: TypedPath>(
+        templates: &Templates,
+        template: &str,
+        params: &P,
+        data: D,
+    ) -> Self {
+        let mut context = tera::Context::new();
+        context.insert("params", params);
+        Self::render_with_context(templates, template, &context, data).await
+    }
+
+    /// Render `template` with `data` on top of a base context (e.g. one
+    /// built with [`ContextBuilder`](crate::ContextBuilder))
+    pub async fn render_with_context(
+        templates: &Templates,
+        template: &str,
+        context: &tera::Context,
+        data: D,
+    ) -> Self {
+        let view = match page_context(context, &data) {
+            Ok(context) => View::render_context(templates, template, &context).await,
+            Err(err) => View::error(err),
+        };
+        Self {
+            view,
+            cache: None,
+            _data: PhantomData,
+        }
+    }
+}
+
+impl<D> Page<D> {
+    /// Set the `Cache-Control` policy
+    pub fn cache(mut self, policy: CachePolicy) -> Self {
+        self.cache = Some(policy);
+        self
+    }
+}
+
+/// The base context plus `data` and `page_data_script`
+fn page_context<D: Serialize>(base: &tera::Context, data: &D) -> Result<tera::Context, ViewError> {
+    let json =
+        serde_json::to_string(data).map_err(|e| ViewError::serialization_error(e.to_string()))?;
+    let mut context = base.clone();
+    context.insert("data", data);
+    context.insert(
+        "page_data_script",
+        &format!(
+            "<script id=\"{}\" type=\"application/json\">{}</script>",
+            PAGE_DATA_ID,
+            escape_script_json(&json)
+        ),
+    );
+    Ok(context)
+}
+
+/// Escape JSON so it cannot close the surrounding `<script>` element
+fn escape_script_json(json: &str) -> String {
+    json.replace('<', "\\u003c")
+        .replace('>', "\\u003e")
+        .replace('&', "\\u0026")
+        .replace('\u{2028}', "\\u2028")
+        .replace('\u{2029}', "\\u2029")
+}
+
+impl<D> IntoResponse for Page<D> {
+    fn into_response(self) -> rustapi_core::Response {
+        let mut response = self.view.into_response();
+        if let (Some(policy), true) = (self.cache, response.status().is_success()) {
+            if let Ok(value) = HeaderValue::from_str(&policy.header_value()) {
+                response.headers_mut().insert(header::CACHE_CONTROL, value);
+            }
+        }
+        response
+    }
+}
+
+impl<D> ResponseModifier for Page<D> {
+    fn update_response(op: &mut Operation) {
+        View::<()>::update_response(op);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Serialize)]
+    struct Post {
+        title: String,
+    }
+
+    #[tokio::test]
+    async fn embeds_data_and_sets_cache_policy() {
+        let templates = Templates::from_raw([(
+            "post.html",
+            "<h1>{{ data.title }}</h1>{{ page_data_script | safe }}",
+        )])
+        .unwrap();
+        let post = Post {
+            title: "</script><b>".to_string(),
+        };
+
+        let response = Page::render(&templates, "post.html", post)
+            .await
+            .cache(CachePolicy::revalidate(Duration::from_secs(60)))
+            .into_response();
+        assert_eq!(
+            response.headers()[header::CACHE_CONTROL],
+            "public, max-age=0, s-maxage=60, stale-while-revalidate=60"
+        );
+
+        let body = http_body_util::BodyExt::collect(response.into_body())
+            .await
+            .unwrap()
+            .to_bytes();
+        let html = String::from_utf8(body.to_vec()).unwrap();
+        assert_eq!(
+            html,
+            "<h1>&lt;&#x2F;script&gt;&lt;b&gt;</h1>\
+             <script id=\"__RUSTAPI_DATA__\" type=\"application/json\">\
+             {\"title\":\"\\u003c/script\\u003e\\u003cb\\u003e\"}</script>"
+        );
+    }
+
+    #[derive(Serialize, serde::Deserialize)]
+    struct PostPath {
+        slug: String,
+    }
+
+    impl TypedPath for PostPath {
+        const PATH: &'static str = "/posts/{slug}";
+
+        fn to_uri(&self) -> String {
+            format!("/posts/{}", self.slug)
+        }
+    }
+
+    #[tokio::test]
+    async fn exposes_typed_params() {
+        let templates =
+            Templates::from_raw([("post.html", "{{ params.slug }}: {{ data.title }}")]).unwrap();
+        let path = PostPath {
+            slug: "hello".to_string(),
+        };
+        let post = Post {
+            title: "Hello".to_string(),
+        };
+
+        let response = Page::render_with_params(&templates, "post.html", &path, post)
+            .await
+            .into_response();
+        let body = http_body_util::BodyExt::collect(response.into_body())
+            .await
+            .unwrap()
+            .to_bytes();
+        assert_eq!(&body[..], b"hello: Hello");
+    }
+
+    #[tokio::test]
+    async fn errors_are_not_cached() {
+        let response = Page::render(&Templates::empty(), "missing.html", ())
+            .await
+            .cache(CachePolicy::Public(Duration::from_secs(300)))
+            .into_response();
+        assert_eq!(response.status(), http::StatusCode::INTERNAL_SERVER_ERROR);
+        assert!(response.headers().get(header::CACHE_CONTROL).is_none());
+    }
+}
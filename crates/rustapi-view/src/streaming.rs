@@ -0,0 +1,233 @@
+//! Streaming server-side rendering
+//!
+//! [`StreamingView`] sends the page shell as soon as it is rendered, with
+//! placeholders for sections whose data is still loading. Each section is
+//! rendered when its data arrives and streamed in a later chunk, together
+//! with a small inline script that swaps it into its placeholder, so slow
+//! queries no longer hold back the first paint.
+//!
+//! Streaming is opt-in per route: handlers that return a `StreamingView`
+//! stream, everything else keeps rendering with [`View`](crate::View).
+//!
+//! ```rust,ignore
+//! use rustapi_rs::protocol::view::{StreamingView, Templates};
+//!
+//! // templates/dashboard.html:
+//! //   <h1>{{ title }}</h1>
+//! //   <div id="orders"><p>Loading orders…</p></div>
+//! async fn dashboard(State(templates): State<Templates>, State(db): State<Db>) -> StreamingView {
+//!     let ctx = ContextBuilder::new().insert("title", "Dashboard").build();
+//!     StreamingView::new(&templates, "dashboard.html", &ctx)
+//!         .await
+//!         .section("orders", "dashboard/_orders.html", async move {
+//!             db.recent_orders().await // Result<Orders, DbError>
+//!         })
+//! }
+//! ```
+//!
+//! Sections are sent in the order they finish. A section whose data or
+//! template fails keeps its placeholder content and the error is logged.
+
+use crate::{Templates, ViewError};
+use bytes::Bytes;
+use futures_util::future::BoxFuture;
+use futures_util::stream::{self, FuturesUnordered, StreamExt};
+use futures_util::FutureExt;
+use http::{header, Response, StatusCode};
+use rustapi_core::{ApiError, IntoResponse, ResponseBody};
+use rustapi_openapi::{Operation, ResponseModifier};
+use serde::Serialize;
+use std::fmt::Display;
+use std::future::Future;
+
+/// A page whose shell is sent first and whose sections stream in later
+///
+/// Created with [`StreamingView::new`]; add sections with
+/// [`StreamingView::section`].
+pub struct StreamingView {
+    templates: Templates,
+    context: tera::Context,
+    /// Rendered shell, split before `</body>`
+    shell: Result<(String, String), ViewError>,
+    sections: Vec<BoxFuture<'static, Option<String>>>,
+}
+
+impl StreamingView {
+    /// Render the shell template
+    ///
+    /// `context` is also the base context of every section.
+    pub async fn new(templates: &Templates, shell: &str, context: &tera::Context) -> Self {
+        let shell = templates.render(shell, context).await.map(|html| {
+            let split = html.rfind("</body>").unwrap_or(html.len());
+            let tail = html[split..].to_string();
+            let mut head = html;
+            head.truncate(split);
+            (head, tail)
+        });
+        Self {
+            templates: templates.clone(),
+            context: context.clone(),
+            shell,
+            sections: Vec::new(),
+        }
+    }
+
+    /// Stream `template` into the element with id `id` once `data` resolves
+    ///
+    /// `data` must serialize to an object; its fields are added to the shell
+    /// context when rendering `template`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `id` contains characters other than ASCII letters, digits,
+    /// `-` and `_`.
+    pub fn section<F, T, E>(mut self, id: &str, template: &str, data: F) -> Self
+    where
+        F: Future<Output = Result<T, E>> + Send + 'static,
+        T: Serialize,
+        E: Display,
+    {
+        assert!(
+            !id.is_empty()
+                && id
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_'),
+            "Invalid streaming section id: {:?}",
+            id
+        );
+
+        let templates = self.templates.clone();
+        let mut context = self.context.clone();
+        let id = id.to_string();
+        let template = template.to_string();
+        self.sections.push(
+            async move {
+                let extra = match data.await {
+                    Ok(data) => tera::Context::from_serialize(&data)
+                        .map_err(|e| ViewError::serialization_error(e.to_string())),
+                    Err(e) => Err(ViewError::render_error(e.to_string())),
+                };
+                let rendered = match extra {
+                    Ok(extra) => {
+                        context.extend(extra);
+                        templates.render(&template, &context).await
+                    }
+                    Err(err) => Err(err),
+                };
+                match rendered {
+                    Ok(html) => Some(section_chunk(&id, &html)),
+                    Err(err) => {
+                        tracing::error!("Streaming section `{}` failed: {}", id, err);
+                        None
+                    }
+                }
+            }
+            .boxed(),
+        );
+        self
+    }
+}
+
+/// The chunk delivering a section: its HTML in an inert `<template>` and a
+/// script moving it into the placeholder
+fn section_chunk(id: &str, html: &str) -> String {
+    format!(
+        "<template id=\"rustapi-section-{id}\">{html}</template>\
+         <script>(function(){{var t=document.getElementById(\"rustapi-section-{id}\"),\
+         p=document.getElementById(\"{id}\");if(p){{p.replaceChildren(t.content)}}t.remove()}})()</script>",
+        id = id,
+        html = html
+    )
+}
+
+impl IntoResponse for StreamingView {
+    fn into_response(self) -> rustapi_core::Response {
+        let (head, tail) = match self.shell {
+            Ok(shell) => shell,
+            Err(err) => return crate::View::<()>::error(err).into_response(),
+        };
+
+        let sections: FuturesUnordered<_> = self.sections.into_iter().collect();
+        let chunks = stream::once(async move { Some(head) })
+            .chain(sections)
+            .chain(stream::once(async move { Some(tail) }))
+            .filter_map(|chunk| async move { chunk.map(|c| Ok::<_, ApiError>(Bytes::from(c))) });
+
+        Response::builder()
+            .status(StatusCode::OK)
+            .header(header::CONTENT_TYPE, "text/html; charset=utf-8")
+            .header("X-Accel-Buffering", "no") // Disable nginx buffering
+            .body(ResponseBody::from_stream(chunks))
+            .unwrap()
+    }
+}
+
+impl ResponseModifier for StreamingView {
+    fn update_response(op: &mut Operation) {
+        crate::View::<()>::update_response(op);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use http_body_util::BodyExt;
+    use std::time::Duration;
+
+    #[derive(Serialize)]
+    struct Orders {
+        count: u32,
+    }
+
+    #[tokio::test]
+    async fn streams_shell_then_sections() {
+        let templates = Templates::from_raw([
+            (
+                "page.html",
+                "<body><h1>{{ title }}</h1><div id=\"orders\">Loading</div><div id=\"fast\"></div></body>",
+            ),
+            ("orders.html", "<p>{{ title }}: {{ count }}</p>"),
+        ])
+        .unwrap();
+        let ctx = crate::ContextBuilder::new().insert("title", "Shop").build();
+
+        let response = StreamingView::new(&templates, "page.html", &ctx)
+            .await
+            .section("orders", "orders.html", async {
+                tokio::time::sleep(Duration::from_millis(20)).await;
+                Ok::<_, ViewError>(Orders { count: 3 })
+            })
+            .section("fast", "orders.html", async {
+                Ok::<_, ViewError>(Orders { count: 1 })
+            })
+            .section("broken", "orders.html", async {
+                Err::<Orders, _>("database down")
+            })
+            .into_response();
+
+        let mut body = response.into_body();
+        let mut chunks = Vec::new();
+        while let Some(frame) = body.frame().await {
+            let data = frame.unwrap().into_data().unwrap();
+            chunks.push(String::from_utf8(data.to_vec()).unwrap());
+        }
+
+        assert_eq!(chunks.len(), 4);
+        assert_eq!(
+            chunks[0],
+            "<body><h1>Shop</h1><div id=\"orders\">Loading</div><div id=\"fast\"></div>"
+        );
+        assert!(chunks[1].contains("<template id=\"rustapi-section-fast\"><p>Shop: 1</p>"));
+        assert!(chunks[2].contains("<template id=\"rustapi-section-orders\"><p>Shop: 3</p>"));
+        assert_eq!(chunks[3], "</body>");
+    }
+
+    #[tokio::test]
+    async fn shell_errors_are_500() {
+        let templates = Templates::empty();
+        let response = StreamingView::new(&templates, "missing.html", &tera::Context::new())
+            .await
+            .into_response();
+        assert_eq!(response.status(), StatusCode::INTERNAL_SERVER_ERROR);
+    }
+}
@@ -1,8 +1,9 @@
 //! Template engine wrapper
 
 use crate::ViewError;
+use serde::Serialize;
 use std::sync::Arc;
-use tera::Tera;
+use tera::{Context, Tera};
 use tokio::sync::RwLock;
 
 /// Configuration for the template engine
@@ -60,9 +61,29 @@ impl TemplatesConfig {
 ///
 /// let templates = Templates::new("templates/**/*.html")?;
 /// ```
+///
+/// # Layouts and partials
+///
+/// Besides Tera's own `{% extends %}`, a page can be wrapped in a layout at
+/// render time: [`Templates::render_in_layout`] renders the page first and
+/// exposes the HTML to the layout as `content` (`{{ content | safe }}`).
+/// [`Templates::render_partial`] renders a fragment on its own.
+///
+/// Template names may omit the `.html` extension, and partial names may
+/// omit the leading underscore of the file name (`users/card` finds
+/// `users/_card.html`).
+///
+/// # Globals
+///
+/// Values set with [`Templates::global`] (site name, asset URLs, ...) are
+/// available in every template; the render context takes precedence.
 #[derive(Clone)]
 pub struct Templates {
     inner: Arc<RwLock<Tera>>,
+    globals: Arc<std::sync::RwLock<Context>>,
+    pub(crate) assets: crate::assets::SharedAssets,
+    #[cfg(feature = "i18n")]
+    pub(crate) i18n: crate::i18n::SharedI18n,
     config: TemplatesConfig,
 }
 
@@ -89,18 +110,12 @@ impl Templates {
         // Register custom filters/functions
         register_builtin_filters(&mut tera);
 
-        Ok(Self {
-            inner: Arc::new(RwLock::new(tera)),
-            config,
-        })
+        Ok(Self::from_parts(tera, config))
     }
 
     /// Create an empty template engine (for adding templates programmatically)
     pub fn empty() -> Self {
-        Self {
-            inner: Arc::new(RwLock::new(Tera::default())),
-            config: TemplatesConfig::default(),
-        }
+        Self::from_parts(Tera::default(), TemplatesConfig::default())
     }
 
     /// Load templates from in-memory sources (e.g. `include_str!` for portable deploys).
@@ -114,14 +129,32 @@ impl Templates {
             tera.add_raw_template(name.as_ref(), content.as_ref())?;
         }
         register_builtin_filters(&mut tera);
-        Ok(Self {
-            inner: Arc::new(RwLock::new(tera)),
-            config: TemplatesConfig {
+        Ok(Self::from_parts(
+            tera,
+            TemplatesConfig {
                 glob: "embedded://".into(),
                 auto_reload: false,
                 strict_mode: false,
             },
-        })
+        ))
+    }
+
+    fn from_parts(mut tera: Tera, config: TemplatesConfig) -> Self {
+        let assets = crate::assets::SharedAssets::default();
+        crate::assets::register(&mut tera, assets.clone());
+        #[cfg(feature = "i18n")]
+        let i18n = crate::i18n::SharedI18n::default();
+        #[cfg(feature = "i18n")]
+        crate::i18n::register(&mut tera, i18n.clone());
+
+        Self {
+            inner: Arc::new(RwLock::new(tera)),
+            globals: Arc::default(),
+            assets,
+            #[cfg(feature = "i18n")]
+            i18n,
+            config,
+        }
     }
 
     /// Add a template from a string
@@ -151,7 +184,67 @@ impl Templates {
         }
 
         let tera = self.inner.read().await;
-        tera.render(template, context).map_err(ViewError::from)
+        let template = resolve_name(&tera, template, false);
+        let context = self.with_globals(context);
+        #[cfg(feature = "i18n")]
+        {
+            let locale = context.get("locale").and_then(|value| value.as_str());
+            crate::i18n::with_locale(locale, || tera.render(&template, &context))
+                .map_err(ViewError::from)
+        }
+        #[cfg(not(feature = "i18n"))]
+        tera.render(&template, &context).map_err(ViewError::from)
+    }
+
+    /// Render `template` inside `layout`
+    ///
+    /// The page is rendered first; the layout is then rendered with the same
+    /// context plus the page HTML as `content`.
+    pub async fn render_in_layout(
+        &self,
+        layout: &str,
+        template: &str,
+        context: &tera::Context,
+    ) -> Result<String, ViewError> {
+        let content = self.render(template, context).await?;
+        let mut context = context.clone();
+        context.insert("content", &content);
+        self.render(layout, &context).await
+    }
+
+    /// Render a partial (a template fragment)
+    ///
+    /// `users/card` resolves to `users/card`, `users/card.html` or
+    /// `users/_card.html`, whichever exists first.
+    pub async fn render_partial(
+        &self,
+        partial: &str,
+        context: &tera::Context,
+    ) -> Result<String, ViewError> {
+        let tera = self.inner.read().await;
+        let name = resolve_name(&tera, partial, true);
+        drop(tera);
+        self.render(&name, context).await
+    }
+
+    /// Set a value available to every template
+    pub fn global<T: Serialize + ?Sized>(&self, key: impl Into<String>, value: &T) -> &Self {
+        self.globals
+            .write()
+            .unwrap_or_else(|e| e.into_inner())
+            .insert(key.into(), value);
+        self
+    }
+
+    /// Globals merged with `context` (context values win)
+    fn with_globals(&self, context: &tera::Context) -> Context {
+        let mut merged = self
+            .globals
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone();
+        merged.extend(context.clone());
+        merged
     }
 
     /// Render a template with a serializable context
@@ -191,6 +284,31 @@ impl Templates {
     }
 }
 
+/// Find the registered template for `name`, trying the `.html` extension
+/// and, for partials, a leading underscore on the file name.
+fn resolve_name(tera: &Tera, name: &str, partial: bool) -> String {
+    let exists = |candidate: &str| tera.get_template_names().any(|n| n == candidate);
+    if exists(name) {
+        return name.to_string();
+    }
+
+    let mut candidates = vec![format!("{}.html", name)];
+    if partial {
+        let (dir, file) = match name.rsplit_once('/') {
+            Some((dir, file)) => (format!("{}/", dir), file),
+            None => (String::new(), name),
+        };
+        if !file.starts_with('_') {
+            candidates.push(format!("{}_{}", dir, file));
+            candidates.push(format!("{}_{}.html", dir, file));
+        }
+    }
+    candidates
+        .into_iter()
+        .find(|candidate| exists(candidate))
+        .unwrap_or_else(|| name.to_string())
+}
+
 /// Register built-in template filters
 fn register_builtin_filters(tera: &mut Tera) {
     // JSON filter for debugging
@@ -267,6 +385,43 @@ mod tests {
         assert!(html.contains("Hi"));
     }
 
+    #[tokio::test]
+    async fn test_layout_and_partials() {
+        let templates = Templates::from_raw([
+            (
+                "layouts/base.html",
+                "<title>{{ site }}</title><main>{{ content | safe }}</main>",
+            ),
+            ("users/show.html", "<h1>{{ name }}</h1>"),
+            ("users/_card.html", "<p>{{ name }}</p>"),
+        ])
+        .unwrap();
+        templates.global("site", "Example");
+
+        let ctx = crate::ContextBuilder::new().insert("name", "Alice").build();
+        let html = templates
+            .render_in_layout("layouts/base", "users/show", &ctx)
+            .await
+            .unwrap();
+        assert_eq!(html, "<title>Example</title><main><h1>Alice</h1></main>");
+
+        let card = templates.render_partial("users/card", &ctx).await.unwrap();
+        assert_eq!(card, "<p>Alice</p>");
+    }
+
+    #[tokio::test]
+    async fn test_context_overrides_globals() {
+        let templates = Templates::empty();
+        templates.add_template("test", "{{ title }}").await.unwrap();
+        templates.global("title", "Default");
+
+        let result = templates.render("test", &tera::Context::new()).await;
+        assert_eq!(result.unwrap(), "Default");
+
+        let ctx = crate::ContextBuilder::new().insert("title", "Page").build();
+        assert_eq!(templates.render("test", &ctx).await.unwrap(), "Page");
+    }
+
     #[tokio::test]
     async fn test_render_with_struct() {
         #[derive(serde::Serialize)]
@@ -67,6 +67,46 @@ impl<T: Serialize> View<T> {
         }
     }
 
+    /// Create a view by rendering `template` inside `layout`
+    ///
+    /// The layout receives the rendered page as `content`:
+    ///
+    /// ```rust,ignore
+    /// // templates/layouts/base.html: <main>{{ content | safe }}</main>
+    /// View::with_layout(&templates, "layouts/base", "users/show", user).await
+    /// ```
+    pub async fn with_layout(
+        templates: &Templates,
+        layout: &str,
+        template: &str,
+        context: T,
+    ) -> Self {
+        let content = match tera::Context::from_serialize(&context) {
+            Ok(context) => templates.render_in_layout(layout, template, &context).await,
+            Err(e) => Err(ViewError::serialization_error(e.to_string())),
+        };
+        Self {
+            content,
+            status: StatusCode::OK,
+            _phantom: PhantomData,
+        }
+    }
+
+    /// Create a view from a partial (e.g. a fragment for a dynamic update)
+    ///
+    /// See [`Templates::render_partial`] for how `partial` is resolved.
+    pub async fn partial(templates: &Templates, partial: &str, context: T) -> Self {
+        let content = match tera::Context::from_serialize(&context) {
+            Ok(context) => templates.render_partial(partial, &context).await,
+            Err(e) => Err(ViewError::serialization_error(e.to_string())),
+        };
+        Self {
+            content,
+            status: StatusCode::OK,
+            _phantom: PhantomData,
+        }
+    }
+
     /// Create a view from pre-rendered HTML
     pub fn from_html(html: impl Into<String>) -> Self {
         Self {
@@ -106,6 +146,21 @@ impl View<()> {
             _phantom: PhantomData,
         }
     }
+
+    /// Create a view by rendering `template` inside `layout` with a tera Context
+    pub async fn render_context_with_layout(
+        templates: &Templates,
+        layout: &str,
+        template: &str,
+        context: &tera::Context,
+    ) -> Self {
+        let content = templates.render_in_layout(layout, template, context).await;
+        Self {
+            content,
+            status: StatusCode::OK,
+            _phantom: PhantomData,
+        }
+    }
 }
 
 impl<T> IntoResponse for View<T> {
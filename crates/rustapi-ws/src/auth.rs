@@ -121,14 +121,24 @@ impl Claims {
 }
 
 /// Specifies where to extract the authentication token from
+///
+/// Browsers cannot set custom headers on WebSocket handshakes, so tokens are
+/// usually passed in a query parameter, a cookie, or `Sec-WebSocket-Protocol`.
 #[derive(Debug, Clone)]
 pub enum TokenExtractor {
     /// Extract from a header (e.g., "Authorization")
     Header(String),
     /// Extract from a query parameter (e.g., "token")
     Query(String),
+    /// Extract from a cookie (e.g., "session")
+    Cookie(String),
     /// Extract from the Sec-WebSocket-Protocol header
+    ///
+    /// Accepts `bearer, <token>` / `access_token, <token>` pairs, a single
+    /// `bearer.<token>` entry, or the whole header value.
     Protocol,
+    /// Try each extractor in order and use the first token found
+    Any(Vec<TokenExtractor>),
 }
 
 impl Default for TokenExtractor {
@@ -148,41 +158,89 @@ impl TokenExtractor {
         Self::Query(name.into())
     }
 
+    /// Create a cookie extractor
+    pub fn cookie(name: impl Into<String>) -> Self {
+        Self::Cookie(name.into())
+    }
+
     /// Create a protocol extractor
     pub fn protocol() -> Self {
         Self::Protocol
     }
 
+    /// Create an extractor that tries each of `extractors` in order
+    pub fn any(extractors: impl IntoIterator<Item = TokenExtractor>) -> Self {
+        Self::Any(extractors.into_iter().collect())
+    }
+
     /// Extract the token from an HTTP request
     pub fn extract<B>(&self, req: &http::Request<B>) -> Option<String> {
+        self.extract_parts(req.headers(), req.uri())
+    }
+
+    /// Extract the token from request headers and URI
+    pub fn extract_parts(&self, headers: &http::HeaderMap, uri: &http::Uri) -> Option<String> {
         match self {
             TokenExtractor::Header(name) => {
-                req.headers()
-                    .get(name)
-                    .and_then(|v| v.to_str().ok())
-                    .map(|s| {
-                        // Strip "Bearer " prefix if present
-                        if let Some(token) = s.strip_prefix("Bearer ") {
-                            token.to_string()
-                        } else {
-                            s.to_string()
-                        }
-                    })
+                headers.get(name).and_then(|v| v.to_str().ok()).map(|s| {
+                    // Strip "Bearer " prefix if present
+                    if let Some(token) = s.strip_prefix("Bearer ") {
+                        token.to_string()
+                    } else {
+                        s.to_string()
+                    }
+                })
             }
-            TokenExtractor::Query(name) => req.uri().query().and_then(|query| {
+            TokenExtractor::Query(name) => uri.query().and_then(|query| {
                 url::form_urlencoded::parse(query.as_bytes())
                     .find(|(key, _)| key == name)
                     .map(|(_, value)| value.into_owned())
             }),
-            TokenExtractor::Protocol => req
-                .headers()
+            TokenExtractor::Cookie(name) => headers
+                .get_all(http::header::COOKIE)
+                .iter()
+                .filter_map(|v| v.to_str().ok())
+                .flat_map(|v| v.split(';'))
+                .filter_map(|pair| pair.trim().split_once('='))
+                .find(|(key, _)| key == name)
+                .map(|(_, value)| value.trim_matches('"').to_string()),
+            TokenExtractor::Protocol => headers
                 .get("Sec-WebSocket-Protocol")
                 .and_then(|v| v.to_str().ok())
-                .map(|s| s.to_string()),
+                .and_then(protocol_token),
+            TokenExtractor::Any(extractors) => extractors
+                .iter()
+                .find_map(|extractor| extractor.extract_parts(headers, uri)),
         }
     }
 }
 
+/// Find the token in a `Sec-WebSocket-Protocol` value.
+fn protocol_token(value: &str) -> Option<String> {
+    let protocols: Vec<&str> = value.split(',').map(str::trim).collect();
+    if let [single] = protocols.as_slice() {
+        return Some(strip_protocol_prefix(single).unwrap_or(single).to_string())
+            .filter(|token| !token.is_empty());
+    }
+
+    protocols
+        .iter()
+        .position(|p| p.eq_ignore_ascii_case("bearer") || p.eq_ignore_ascii_case("access_token"))
+        .and_then(|i| protocols.get(i + 1))
+        .or_else(|| {
+            protocols
+                .iter()
+                .find(|p| strip_protocol_prefix(p).is_some())
+        })
+        .map(|p| strip_protocol_prefix(p).unwrap_or(p).to_string())
+}
+
+fn strip_protocol_prefix(protocol: &str) -> Option<&str> {
+    protocol
+        .strip_prefix("bearer.")
+        .or_else(|| protocol.strip_prefix("access_token."))
+}
+
 /// Trait for validating authentication tokens
 ///
 /// Implement this trait to provide custom token validation logic.
@@ -192,7 +250,53 @@ pub trait TokenValidator: Send + Sync {
     async fn validate(&self, token: &str) -> Result<Claims, AuthError>;
 }
 
+/// A token validator backed by a function
+///
+/// Useful to reuse existing token checks, such as `JwtLayer::validate_token`,
+/// during the handshake.
+pub struct FnValidator<F>(F);
+
+impl<F> FnValidator<F>
+where
+    F: Fn(&str) -> Result<Claims, AuthError> + Send + Sync,
+{
+    /// Create a validator from a function
+    pub fn new(f: F) -> Self {
+        Self(f)
+    }
+}
+
+#[async_trait::async_trait]
+impl<F> TokenValidator for FnValidator<F>
+where
+    F: Fn(&str) -> Result<Claims, AuthError> + Send + Sync,
+{
+    async fn validate(&self, token: &str) -> Result<Claims, AuthError> {
+        (self.0)(token)
+    }
+}
+
 /// Configuration for WebSocket authentication
+///
+/// Register the configuration as application state to authenticate every
+/// [`WebSocket`](crate::WebSocket) handshake: the token is validated before
+/// the upgrade is accepted, failures are rejected with `401 Unauthorized`
+/// (`400` for malformed tokens, `403` for insufficient permissions), and the claims are available from
+/// [`WebSocket::claims`](crate::WebSocket::claims).
+///
+/// ```rust,ignore
+/// let auth = WsAuthConfig::from_fn(move |token| {
+///     let claims = jwt.validate_token(token).map_err(|e| AuthError::validation_failed(e.to_string()))?;
+///     Ok(Claims::new(claims.sub))
+/// })
+/// .extractor(TokenExtractor::any([
+///     TokenExtractor::query("token"),
+///     TokenExtractor::cookie("session"),
+///     TokenExtractor::protocol(),
+/// ]));
+///
+/// RustApi::new().state(auth).route("/ws", get(ws_handler));
+/// ```
 #[derive(Clone)]
 pub struct WsAuthConfig {
     /// Token extractor configuration
@@ -213,6 +317,14 @@ impl WsAuthConfig {
         }
     }
 
+    /// Create a configuration validating tokens with a function
+    pub fn from_fn<F>(f: F) -> Self
+    where
+        F: Fn(&str) -> Result<Claims, AuthError> + Send + Sync + 'static,
+    {
+        Self::new(FnValidator::new(f))
+    }
+
     /// Set the token extractor
     pub fn extractor(mut self, extractor: TokenExtractor) -> Self {
         self.extractor = extractor;
@@ -230,7 +342,16 @@ impl WsAuthConfig {
         &self,
         req: &http::Request<B>,
     ) -> Result<Option<Claims>, AuthError> {
-        match self.extractor.extract(req) {
+        self.authenticate_parts(req.headers(), req.uri()).await
+    }
+
+    /// Extract and validate a token from request headers and URI
+    pub async fn authenticate_parts(
+        &self,
+        headers: &http::HeaderMap,
+        uri: &http::Uri,
+    ) -> Result<Option<Claims>, AuthError> {
+        match self.extractor.extract_parts(headers, uri) {
             Some(token) => {
                 let claims = self.validator.validate(&token).await?;
                 Ok(Some(claims))
@@ -369,6 +490,74 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_token_extractor_protocol_pair() {
+        let extractor = TokenExtractor::protocol();
+
+        let req = Request::builder()
+            .header("Sec-WebSocket-Protocol", "chat, bearer, abc.def")
+            .body(())
+            .unwrap();
+        assert_eq!(extractor.extract(&req), Some("abc.def".to_string()));
+
+        let req = Request::builder()
+            .header("Sec-WebSocket-Protocol", "chat, bearer.abc")
+            .body(())
+            .unwrap();
+        assert_eq!(extractor.extract(&req), Some("abc".to_string()));
+    }
+
+    #[test]
+    fn test_token_extractor_cookie() {
+        let extractor = TokenExtractor::cookie("session");
+
+        let req = Request::builder()
+            .header("Cookie", "theme=dark; session=cookie-token")
+            .body(())
+            .unwrap();
+
+        assert_eq!(extractor.extract(&req), Some("cookie-token".to_string()));
+    }
+
+    #[test]
+    fn test_token_extractor_any() {
+        let extractor =
+            TokenExtractor::any([TokenExtractor::query("token"), TokenExtractor::cookie("s")]);
+
+        let req = Request::builder()
+            .uri("/ws")
+            .header("Cookie", "s=from-cookie")
+            .body(())
+            .unwrap();
+        assert_eq!(extractor.extract(&req), Some("from-cookie".to_string()));
+
+        let req = Request::builder()
+            .uri("/ws?token=from-query")
+            .header("Cookie", "s=from-cookie")
+            .body(())
+            .unwrap();
+        assert_eq!(extractor.extract(&req), Some("from-query".to_string()));
+    }
+
+    #[tokio::test]
+    async fn test_fn_validator() {
+        let config = WsAuthConfig::from_fn(|token| match token {
+            "good" => Ok(Claims::new("user-1")),
+            _ => Err(AuthError::TokenExpired),
+        })
+        .extractor(TokenExtractor::query("token"));
+
+        let req = Request::builder().uri("/ws?token=good").body(()).unwrap();
+        let claims = config.authenticate(&req).await.unwrap().unwrap();
+        assert_eq!(claims.subject(), "user-1");
+
+        let req = Request::builder().uri("/ws?token=bad").body(()).unwrap();
+        assert!(matches!(
+            config.authenticate(&req).await,
+            Err(AuthError::TokenExpired)
+        ));
+    }
+
     #[test]
     fn test_token_extractor_missing() {
         let extractor = TokenExtractor::header("Authorization");
@@ -1,8 +1,29 @@
 //! Broadcast channel for WebSocket messages
+//!
+//! Besides the channel-wide `send`, a [`Broadcast`] manages named rooms:
+//! connections `join` a room under a member id and receive only the messages
+//! sent to that room. Membership is tied to the returned [`RoomSubscription`],
+//! so a connection leaves its rooms automatically when its task ends and the
+//! subscription is dropped; empty rooms are removed.
+//!
+//! Every subscriber reads from a bounded queue of `capacity` messages. When a
+//! subscriber falls further behind, the oldest messages are overwritten for
+//! it and the configured [`OverflowPolicy`] decides what the subscriber sees,
+//! so a slow client never makes the broadcast buffer grow.
+//!
+//! With [`Broadcast::history`], each room keeps its last messages in a ring
+//! buffer keyed by monotonically increasing message ids. A client that
+//! reconnects with the last id it saw (e.g. `?since=42`) joins with
+//! [`Broadcast::join_since`] and first receives the messages it missed.
+//! A room whose last member left keeps its history for
+//! [`Broadcast::history_ttl`], then it is dropped.
 
 use crate::Message;
-use std::sync::atomic::{AtomicUsize, Ordering};
-use std::sync::Arc;
+use std::collections::{HashMap, VecDeque};
+use std::ops::{Deref, DerefMut};
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, Instant};
 use tokio::sync::broadcast;
 
 /// A broadcast channel for sending messages to multiple WebSocket clients
@@ -27,10 +48,158 @@ use tokio::sync::broadcast;
 /// // Receive the message
 /// let msg = rx.recv().await.unwrap();
 /// ```
+///
+/// # Rooms
+///
+/// ```rust,ignore
+/// ws.on_upgrade(move |socket| async move {
+///     let (mut sender, mut receiver) = socket.split();
+///     let mut room = broadcast.join("lobby", user_id.clone());
+///
+///     broadcast.send_text_to_room("lobby", format!("{} joined", user_id));
+///     println!("online: {:?}", broadcast.room_members("lobby"));
+///
+///     // Forward room messages until the client disconnects; dropping
+///     // `room` at the end removes `user_id` from "lobby".
+///     while let Some(Ok(msg)) = room.recv().await {
+///         if sender.send(msg).await.is_err() {
+///             break;
+///         }
+///     }
+/// })
+/// ```
 #[derive(Clone)]
 pub struct Broadcast {
     sender: broadcast::Sender<Message>,
     subscriber_count: Arc<AtomicUsize>,
+    rooms: Arc<RwLock<Rooms>>,
+    capacity: usize,
+    overflow: OverflowPolicy,
+    dropped: Arc<AtomicU64>,
+    history: usize,
+}
+
+/// What a subscriber sees when it falls more than `capacity` messages behind
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum OverflowPolicy {
+    /// Return [`BroadcastRecvError::Lagged`] once, then continue with the
+    /// oldest message still queued
+    #[default]
+    LagError,
+    /// Silently skip the overwritten messages
+    DropOldest,
+    /// End the subscription: `recv` returns `None` (rooms are left), so the
+    /// handler's forwarding loop ends and the connection can be closed
+    Disconnect,
+}
+
+/// Outcome of a receive on a bounded queue.
+enum Received<T> {
+    Message(T),
+    Lagged(u64),
+    Closed,
+    Empty,
+}
+
+/// Apply the overflow policy; `None` means "keep receiving".
+fn apply_policy<T>(
+    received: Received<T>,
+    policy: OverflowPolicy,
+    dropped: &AtomicU64,
+    disconnect: impl FnOnce(),
+) -> Option<Option<Result<T, BroadcastRecvError>>> {
+    match received {
+        Received::Message(msg) => Some(Some(Ok(msg))),
+        Received::Closed | Received::Empty => Some(None),
+        Received::Lagged(count) => {
+            dropped.fetch_add(count, Ordering::Relaxed);
+            match policy {
+                OverflowPolicy::LagError => Some(Some(Err(BroadcastRecvError::Lagged(count)))),
+                OverflowPolicy::DropOldest => {
+                    tracing::debug!("Broadcast subscriber skipped {} messages", count);
+                    None
+                }
+                OverflowPolicy::Disconnect => {
+                    tracing::debug!(
+                        "Disconnecting broadcast subscriber lagging by {} messages",
+                        count
+                    );
+                    disconnect();
+                    Some(None)
+                }
+            }
+        }
+    }
+}
+
+fn received<T>(result: Result<T, broadcast::error::RecvError>) -> Received<T> {
+    match result {
+        Ok(msg) => Received::Message(msg),
+        Err(broadcast::error::RecvError::Closed) => Received::Closed,
+        Err(broadcast::error::RecvError::Lagged(count)) => Received::Lagged(count),
+    }
+}
+
+fn try_received<T>(result: Result<T, broadcast::error::TryRecvError>) -> Received<T> {
+    match result {
+        Ok(msg) => Received::Message(msg),
+        Err(broadcast::error::TryRecvError::Empty) => Received::Empty,
+        Err(broadcast::error::TryRecvError::Closed) => Received::Closed,
+        Err(broadcast::error::TryRecvError::Lagged(count)) => Received::Lagged(count),
+    }
+}
+
+/// The rooms of a broadcast, by name.
+struct Rooms {
+    rooms: HashMap<String, Room>,
+    /// How long an empty room keeps its history
+    history_ttl: Duration,
+    last_sweep: Instant,
+}
+
+impl Rooms {
+    /// Drop empty rooms whose history outlived the TTL.
+    ///
+    /// Runs at most once per TTL, so expired rooms are gone within twice
+    /// the TTL without scanning all rooms on every call.
+    fn sweep(&mut self) {
+        if self.last_sweep.elapsed() < self.history_ttl {
+            return;
+        }
+        self.last_sweep = Instant::now();
+        let ttl = self.history_ttl;
+        self.rooms.retain(|_, room| {
+            room.emptied_at
+                .is_none_or(|emptied| emptied.elapsed() < ttl)
+        });
+    }
+}
+
+impl Deref for Rooms {
+    type Target = HashMap<String, Room>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.rooms
+    }
+}
+
+impl DerefMut for Rooms {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.rooms
+    }
+}
+
+/// A named room: its own channel plus the current members.
+struct Room {
+    sender: broadcast::Sender<(u64, Message)>,
+    /// Member id -> active flag of the subscription that holds the membership
+    members: HashMap<String, Arc<AtomicBool>>,
+    /// Id of the last message sent to the room
+    last_id: u64,
+    /// Most recent messages, oldest first (empty unless history is enabled)
+    history: VecDeque<(u64, Message)>,
+    /// When the last member left, for rooms kept for their history
+    emptied_at: Option<Instant>,
 }
 
 impl Broadcast {
@@ -40,20 +209,82 @@ impl Broadcast {
     }
 
     /// Create a new broadcast channel with specified capacity
+    ///
+    /// The capacity bounds how many messages each subscriber (of the channel
+    /// and of every room) can fall behind before the overflow policy applies.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `capacity` is 0.
     pub fn with_capacity(capacity: usize) -> Self {
         let (sender, _) = broadcast::channel(capacity);
         Self {
             sender,
             subscriber_count: Arc::new(AtomicUsize::new(0)),
+            rooms: Arc::new(RwLock::new(Rooms {
+                rooms: HashMap::new(),
+                history_ttl: DEFAULT_HISTORY_TTL,
+                last_sweep: Instant::now(),
+            })),
+            capacity,
+            overflow: OverflowPolicy::default(),
+            dropped: Arc::new(AtomicU64::new(0)),
+            history: 0,
         }
     }
 
+    /// Keep the last `size` messages of every room for replay
+    ///
+    /// Rooms with retained messages outlive their last member for
+    /// [`history_ttl`](Self::history_ttl), so clients reconnecting after a
+    /// short outage can catch up with [`join_since`](Self::join_since).
+    /// Disabled (0) by default.
+    pub fn history(mut self, size: usize) -> Self {
+        self.history = size;
+        self
+    }
+
+    /// How long a room without members keeps its history (default: 60s)
+    ///
+    /// After that the room and its history are dropped, so rooms nobody
+    /// rejoins do not accumulate.
+    pub fn history_ttl(self, ttl: Duration) -> Self {
+        self.rooms
+            .write()
+            .unwrap_or_else(|e| e.into_inner())
+            .history_ttl = ttl;
+        self
+    }
+
+    /// Set the policy applied when a subscriber falls behind
+    pub fn overflow(mut self, policy: OverflowPolicy) -> Self {
+        self.overflow = policy;
+        self
+    }
+
+    /// Get the overflow policy
+    pub fn overflow_policy(&self) -> OverflowPolicy {
+        self.overflow
+    }
+
+    /// Get the per-subscriber queue capacity
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Total number of messages missed by lagging subscribers
+    pub fn dropped_count(&self) -> u64 {
+        self.dropped.load(Ordering::Relaxed)
+    }
+
     /// Subscribe to receive broadcast messages
     pub fn subscribe(&self) -> BroadcastReceiver {
         self.subscriber_count.fetch_add(1, Ordering::SeqCst);
         BroadcastReceiver {
-            inner: self.sender.subscribe(),
+            inner: Some(self.sender.subscribe()),
             subscriber_count: self.subscriber_count.clone(),
+            overflow: self.overflow,
+            dropped: self.dropped.clone(),
         }
     }
 
@@ -88,8 +319,235 @@ impl Broadcast {
     pub fn has_subscribers(&self) -> bool {
         self.subscriber_count() > 0
     }
+
+    /// Join a room as `member`, creating the room if needed
+    ///
+    /// The membership lasts until [`leave`](Self::leave) is called or the
+    /// returned subscription is dropped. Joining a room again with the same
+    /// member id replaces the previous membership.
+    pub fn join(&self, room: impl Into<String>, member: impl Into<String>) -> RoomSubscription {
+        self.join_inner(room.into(), member.into(), None)
+    }
+
+    /// Join a room and replay the retained messages with an id above `since`
+    ///
+    /// Replayed messages are delivered before live ones, without gaps or
+    /// duplicates. If messages after `since` have already been evicted from
+    /// the history, the available ones are replayed and
+    /// [`RoomSubscription::missed_messages`] reports the loss, so the client
+    /// can resynchronize.
+    pub fn join_since(
+        &self,
+        room: impl Into<String>,
+        member: impl Into<String>,
+        since: u64,
+    ) -> RoomSubscription {
+        self.join_inner(room.into(), member.into(), Some(since))
+    }
+
+    fn join_inner(&self, room: String, member: String, since: Option<u64>) -> RoomSubscription {
+        let active = Arc::new(AtomicBool::new(true));
+
+        // Sends take the write lock too, so the replay snapshot and the new
+        // receiver line up exactly.
+        let mut rooms = self.rooms.write().unwrap_or_else(|e| e.into_inner());
+        rooms.sweep();
+        let entry = rooms.entry(room.clone()).or_insert_with(|| Room {
+            sender: broadcast::channel(self.capacity).0,
+            members: HashMap::new(),
+            last_id: 0,
+            history: VecDeque::new(),
+            emptied_at: None,
+        });
+        entry.emptied_at = None;
+        if let Some(previous) = entry.members.insert(member.clone(), active.clone()) {
+            previous.store(false, Ordering::SeqCst);
+        }
+
+        let mut pending = VecDeque::new();
+        let mut missed_messages = false;
+        let mut last_id = entry.last_id;
+        if let Some(since) = since {
+            pending.extend(entry.history.iter().filter(|(id, _)| *id > since).cloned());
+            let oldest = entry.history.front().map(|(id, _)| *id);
+            missed_messages = since < entry.last_id
+                && oldest.is_none_or(|oldest| oldest > since.saturating_add(1));
+            last_id = since.min(entry.last_id);
+        }
+
+        RoomSubscription {
+            inner: entry.sender.subscribe(),
+            rooms: self.rooms.clone(),
+            room,
+            member,
+            active,
+            overflow: self.overflow,
+            dropped: self.dropped.clone(),
+            pending,
+            last_id,
+            missed_messages,
+        }
+    }
+
+    /// Id of the last message sent to a room (0 if none)
+    pub fn last_message_id(&self, room: &str) -> u64 {
+        let rooms = self.rooms.read().unwrap_or_else(|e| e.into_inner());
+        rooms.get(room).map(|r| r.last_id).unwrap_or(0)
+    }
+
+    /// Remove `member` from a room
+    ///
+    /// The member's subscription stops receiving messages. Returns `false` if
+    /// the member was not in the room.
+    pub fn leave(&self, room: &str, member: &str) -> bool {
+        let mut rooms = self.rooms.write().unwrap_or_else(|e| e.into_inner());
+        remove_member(&mut rooms, room, member, None)
+    }
+
+    /// Remove `member` from every room it has joined
+    ///
+    /// Returns the number of rooms left.
+    pub fn leave_all(&self, member: &str) -> usize {
+        let mut rooms = self.rooms.write().unwrap_or_else(|e| e.into_inner());
+        let joined: Vec<String> = rooms
+            .iter()
+            .filter(|(_, r)| r.members.contains_key(member))
+            .map(|(name, _)| name.clone())
+            .collect();
+        for room in &joined {
+            remove_member(&mut rooms, room, member, None);
+        }
+        joined.len()
+    }
+
+    /// Send a message to all members of a room
+    ///
+    /// Returns the number of subscriptions that received the message, or 0 if
+    /// the room does not exist.
+    pub fn send_to_room(&self, room: &str, msg: Message) -> usize {
+        let mut rooms = self.rooms.write().unwrap_or_else(|e| e.into_inner());
+        rooms.sweep();
+        let Some(entry) = rooms.get_mut(room) else {
+            return 0;
+        };
+
+        entry.last_id += 1;
+        if self.history > 0 {
+            if entry.history.len() == self.history {
+                entry.history.pop_front();
+            }
+            entry.history.push_back((entry.last_id, msg.clone()));
+        }
+        entry.sender.send((entry.last_id, msg)).unwrap_or(0)
+    }
+
+    /// Send a text message to all members of a room
+    pub fn send_text_to_room(&self, room: &str, text: impl Into<String>) -> usize {
+        self.send_to_room(room, Message::text(text))
+    }
+
+    /// Send a JSON message to all members of a room
+    pub fn send_json_to_room<T: serde::Serialize>(
+        &self,
+        room: &str,
+        value: &T,
+    ) -> Result<usize, crate::WebSocketError> {
+        let msg = Message::json(value)?;
+        Ok(self.send_to_room(room, msg))
+    }
+
+    /// Names of all rooms that currently have members
+    pub fn rooms(&self) -> Vec<String> {
+        let rooms = self.rooms.read().unwrap_or_else(|e| e.into_inner());
+        let mut names: Vec<String> = rooms
+            .iter()
+            .filter(|(_, r)| !r.members.is_empty())
+            .map(|(name, _)| name.clone())
+            .collect();
+        names.sort();
+        names
+    }
+
+    /// Number of rooms that currently have members
+    pub fn room_count(&self) -> usize {
+        let rooms = self.rooms.read().unwrap_or_else(|e| e.into_inner());
+        rooms.values().filter(|r| !r.members.is_empty()).count()
+    }
+
+    /// Member ids of a room (empty if the room does not exist)
+    pub fn room_members(&self, room: &str) -> Vec<String> {
+        let rooms = self.rooms.read().unwrap_or_else(|e| e.into_inner());
+        let mut members: Vec<String> = rooms
+            .get(room)
+            .map(|r| r.members.keys().cloned().collect())
+            .unwrap_or_default();
+        members.sort();
+        members
+    }
+
+    /// Number of members in a room
+    pub fn room_size(&self, room: &str) -> usize {
+        let rooms = self.rooms.read().unwrap_or_else(|e| e.into_inner());
+        rooms.get(room).map(|r| r.members.len()).unwrap_or(0)
+    }
+
+    /// Check whether `member` is in a room
+    pub fn is_member(&self, room: &str, member: &str) -> bool {
+        let rooms = self.rooms.read().unwrap_or_else(|e| e.into_inner());
+        rooms
+            .get(room)
+            .is_some_and(|r| r.members.contains_key(member))
+    }
+
+    /// Rooms `member` has joined
+    pub fn member_rooms(&self, member: &str) -> Vec<String> {
+        let rooms = self.rooms.read().unwrap_or_else(|e| e.into_inner());
+        let mut names: Vec<String> = rooms
+            .iter()
+            .filter(|(_, r)| r.members.contains_key(member))
+            .map(|(name, _)| name.clone())
+            .collect();
+        names.sort();
+        names
+    }
+}
+
+/// Remove a membership, dropping the room once it is empty (rooms with
+/// retained history are dropped by the sweep after the TTL).
+///
+/// With `only`, the membership is removed only if it is still held by that
+/// subscription (so a stale subscription cannot evict a newer join).
+fn remove_member(
+    rooms: &mut Rooms,
+    room: &str,
+    member: &str,
+    only: Option<&Arc<AtomicBool>>,
+) -> bool {
+    let Some(entry) = rooms.get_mut(room) else {
+        return false;
+    };
+    match entry.members.get(member) {
+        Some(active) if only.is_none_or(|own| Arc::ptr_eq(own, active)) => {
+            active.store(false, Ordering::SeqCst);
+            entry.members.remove(member);
+        }
+        _ => return false,
+    }
+    // Rooms with retained history stay around for reconnecting clients
+    if entry.members.is_empty() {
+        if entry.history.is_empty() {
+            rooms.remove(room);
+        } else {
+            entry.emptied_at = Some(Instant::now());
+        }
+    }
+    rooms.sweep();
+    true
 }
 
+/// Default time an empty room keeps its history
+const DEFAULT_HISTORY_TTL: Duration = Duration::from_secs(60);
+
 impl Default for Broadcast {
     fn default() -> Self {
         Self::new()
@@ -98,41 +556,208 @@ impl Default for Broadcast {
 
 /// Receiver for broadcast messages
 pub struct BroadcastReceiver {
-    inner: broadcast::Receiver<Message>,
+    /// `None` once disconnected by [`OverflowPolicy::Disconnect`]
+    inner: Option<broadcast::Receiver<Message>>,
     subscriber_count: Arc<AtomicUsize>,
+    overflow: OverflowPolicy,
+    dropped: Arc<AtomicU64>,
 }
 
 impl BroadcastReceiver {
     /// Receive the next broadcast message
     ///
-    /// Returns `None` if the broadcast channel is closed.
-    /// Returns `Err` if messages were missed due to slow consumption.
+    /// Returns `None` if the broadcast channel is closed, or if the receiver
+    /// was disconnected for lagging ([`OverflowPolicy::Disconnect`]).
+    /// Returns `Err` if messages were missed due to slow consumption
+    /// ([`OverflowPolicy::LagError`]).
     pub async fn recv(&mut self) -> Option<Result<Message, BroadcastRecvError>> {
-        match self.inner.recv().await {
-            Ok(msg) => Some(Ok(msg)),
-            Err(broadcast::error::RecvError::Closed) => None,
-            Err(broadcast::error::RecvError::Lagged(count)) => {
-                Some(Err(BroadcastRecvError::Lagged(count)))
+        loop {
+            let result = received(self.inner.as_mut()?.recv().await);
+            if let Some(result) = self.apply(result) {
+                return result;
             }
         }
     }
 
     /// Try to receive a message without waiting
     pub fn try_recv(&mut self) -> Option<Result<Message, BroadcastRecvError>> {
-        match self.inner.try_recv() {
-            Ok(msg) => Some(Ok(msg)),
-            Err(broadcast::error::TryRecvError::Empty) => None,
-            Err(broadcast::error::TryRecvError::Closed) => None,
-            Err(broadcast::error::TryRecvError::Lagged(count)) => {
-                Some(Err(BroadcastRecvError::Lagged(count)))
+        loop {
+            let result = try_received(self.inner.as_mut()?.try_recv());
+            if let Some(result) = self.apply(result) {
+                return result;
             }
         }
     }
+
+    /// Check whether the receiver was disconnected for lagging
+    pub fn is_disconnected(&self) -> bool {
+        self.inner.is_none()
+    }
+
+    fn apply(
+        &mut self,
+        result: Received<Message>,
+    ) -> Option<Option<Result<Message, BroadcastRecvError>>> {
+        let mut disconnected = false;
+        let outcome = apply_policy(result, self.overflow, &self.dropped, || {
+            disconnected = true;
+        });
+        if disconnected {
+            self.inner = None;
+            self.subscriber_count.fetch_sub(1, Ordering::SeqCst);
+        }
+        outcome
+    }
 }
 
 impl Drop for BroadcastReceiver {
     fn drop(&mut self) {
-        self.subscriber_count.fetch_sub(1, Ordering::SeqCst);
+        if self.inner.is_some() {
+            self.subscriber_count.fetch_sub(1, Ordering::SeqCst);
+        }
+    }
+}
+
+/// Membership of a room, receiving the messages sent to it
+///
+/// Dropping the subscription removes the member from the room.
+pub struct RoomSubscription {
+    inner: broadcast::Receiver<(u64, Message)>,
+    rooms: Arc<RwLock<Rooms>>,
+    room: String,
+    member: String,
+    active: Arc<AtomicBool>,
+    overflow: OverflowPolicy,
+    dropped: Arc<AtomicU64>,
+    /// Replayed messages delivered before live ones
+    pending: VecDeque<(u64, Message)>,
+    /// Id of the last message delivered (or skipped as already seen)
+    last_id: u64,
+    missed_messages: bool,
+}
+
+impl RoomSubscription {
+    /// Name of the room
+    pub fn room(&self) -> &str {
+        &self.room
+    }
+
+    /// Member id this subscription was joined with
+    pub fn member(&self) -> &str {
+        &self.member
+    }
+
+    /// Check whether the membership is still active
+    ///
+    /// Returns `false` once the member has left the room or joined it again
+    /// with another subscription.
+    pub fn is_active(&self) -> bool {
+        self.active.load(Ordering::SeqCst)
+    }
+
+    /// Id of the last message received
+    ///
+    /// Clients can store it and reconnect with it to resume through
+    /// [`Broadcast::join_since`].
+    pub fn last_id(&self) -> u64 {
+        self.last_id
+    }
+
+    /// Check whether messages after the requested `since` id were no longer
+    /// retained when joining with [`Broadcast::join_since`]
+    pub fn missed_messages(&self) -> bool {
+        self.missed_messages
+    }
+
+    /// Receive the next message sent to the room
+    ///
+    /// Returns `None` once the member has left the room (including being
+    /// disconnected for lagging with [`OverflowPolicy::Disconnect`]).
+    /// Returns `Err` if messages were missed due to slow consumption.
+    pub async fn recv(&mut self) -> Option<Result<Message, BroadcastRecvError>> {
+        self.recv_with_id()
+            .await
+            .map(|result| result.map(|(_, msg)| msg))
+    }
+
+    /// Receive the next message together with its id
+    pub async fn recv_with_id(&mut self) -> Option<Result<(u64, Message), BroadcastRecvError>> {
+        loop {
+            if !self.is_active() {
+                return None;
+            }
+            if let Some(replayed) = self.next_pending() {
+                return Some(Ok(replayed));
+            }
+            let result = received(self.inner.recv().await);
+            if !self.is_active() {
+                return None;
+            }
+            if let Some(result) = self.apply(result) {
+                return result;
+            }
+        }
+    }
+
+    /// Try to receive a message without waiting
+    pub fn try_recv(&mut self) -> Option<Result<Message, BroadcastRecvError>> {
+        self.try_recv_with_id()
+            .map(|result| result.map(|(_, msg)| msg))
+    }
+
+    /// Try to receive a message together with its id without waiting
+    pub fn try_recv_with_id(&mut self) -> Option<Result<(u64, Message), BroadcastRecvError>> {
+        loop {
+            if !self.is_active() {
+                return None;
+            }
+            if let Some(replayed) = self.next_pending() {
+                return Some(Ok(replayed));
+            }
+            let result = try_received(self.inner.try_recv());
+            if let Some(result) = self.apply(result) {
+                return result;
+            }
+        }
+    }
+
+    fn next_pending(&mut self) -> Option<(u64, Message)> {
+        let (id, msg) = self.pending.pop_front()?;
+        self.last_id = id;
+        Some((id, msg))
+    }
+
+    fn apply(
+        &mut self,
+        result: Received<(u64, Message)>,
+    ) -> Option<Option<Result<(u64, Message), BroadcastRecvError>>> {
+        let outcome = apply_policy(result, self.overflow, &self.dropped, || self.release());
+        match outcome {
+            // Already delivered from the replay snapshot
+            Some(Some(Ok((id, _)))) if id <= self.last_id => None,
+            Some(Some(Ok((id, msg)))) => {
+                self.last_id = id;
+                Some(Some(Ok((id, msg))))
+            }
+            other => other,
+        }
+    }
+
+    /// Give up the membership held by this subscription.
+    fn release(&self) {
+        let mut rooms = self.rooms.write().unwrap_or_else(|e| e.into_inner());
+        remove_member(&mut rooms, &self.room, &self.member, Some(&self.active));
+    }
+
+    /// Leave the room now
+    pub fn leave(self) {
+        // Membership is released by `Drop`
+    }
+}
+
+impl Drop for RoomSubscription {
+    fn drop(&mut self) {
+        self.release();
     }
 }
 
@@ -152,3 +777,226 @@ impl std::fmt::Display for BroadcastRecvError {
 }
 
 impl std::error::Error for BroadcastRecvError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn text(msg: Option<Result<Message, BroadcastRecvError>>) -> String {
+        match msg {
+            Some(Ok(Message::Text(text))) => text,
+            other => panic!(
+                "expected a text message, got {:?}",
+                other.map(|r| r.is_ok())
+            ),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_room_scoped_broadcast() {
+        let broadcast = Broadcast::new();
+        let mut alice = broadcast.join("lobby", "alice");
+        let mut bob = broadcast.join("lobby", "bob");
+        let mut carol = broadcast.join("games", "carol");
+
+        assert_eq!(broadcast.send_text_to_room("lobby", "hi"), 2);
+        assert_eq!(text(alice.recv().await), "hi");
+        assert_eq!(text(bob.recv().await), "hi");
+        assert!(carol.try_recv().is_none());
+
+        assert_eq!(broadcast.send_text_to_room("nowhere", "hi"), 0);
+    }
+
+    #[test]
+    fn test_membership_queries() {
+        let broadcast = Broadcast::new();
+        let _a = broadcast.join("lobby", "alice");
+        let _b = broadcast.join("lobby", "bob");
+        let _c = broadcast.join("games", "alice");
+
+        assert_eq!(broadcast.rooms(), vec!["games", "lobby"]);
+        assert_eq!(broadcast.room_count(), 2);
+        assert_eq!(broadcast.room_members("lobby"), vec!["alice", "bob"]);
+        assert_eq!(broadcast.room_size("games"), 1);
+        assert!(broadcast.is_member("games", "alice"));
+        assert!(!broadcast.is_member("games", "bob"));
+        assert_eq!(broadcast.member_rooms("alice"), vec!["games", "lobby"]);
+    }
+
+    #[test]
+    fn test_drop_leaves_and_removes_empty_rooms() {
+        let broadcast = Broadcast::new();
+        let alice = broadcast.join("lobby", "alice");
+        let bob = broadcast.join("lobby", "bob");
+
+        drop(alice);
+        assert_eq!(broadcast.room_members("lobby"), vec!["bob"]);
+
+        bob.leave();
+        assert_eq!(broadcast.room_count(), 0);
+        assert!(broadcast.room_members("lobby").is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_explicit_leave_ends_subscription() {
+        let broadcast = Broadcast::new();
+        let mut alice = broadcast.join("lobby", "alice");
+        let _games = broadcast.join("games", "alice");
+
+        assert!(broadcast.leave("lobby", "alice"));
+        assert!(!broadcast.leave("lobby", "alice"));
+        assert!(!alice.is_active());
+        assert!(alice.recv().await.is_none());
+
+        assert_eq!(broadcast.leave_all("alice"), 1);
+        assert_eq!(broadcast.room_count(), 0);
+    }
+
+    #[test]
+    fn test_lag_error_policy() {
+        let broadcast = Broadcast::with_capacity(2);
+        let mut rx = broadcast.subscribe();
+        for i in 0..5 {
+            broadcast.send_text(i.to_string());
+        }
+
+        assert!(matches!(
+            rx.try_recv(),
+            Some(Err(BroadcastRecvError::Lagged(3)))
+        ));
+        assert_eq!(text(rx.try_recv()), "3");
+        assert_eq!(broadcast.dropped_count(), 3);
+    }
+
+    #[test]
+    fn test_drop_oldest_policy() {
+        let broadcast = Broadcast::with_capacity(2).overflow(OverflowPolicy::DropOldest);
+        let mut rx = broadcast.subscribe();
+        for i in 0..5 {
+            broadcast.send_text(i.to_string());
+        }
+
+        assert_eq!(text(rx.try_recv()), "3");
+        assert_eq!(text(rx.try_recv()), "4");
+        assert!(rx.try_recv().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_disconnect_policy() {
+        let broadcast = Broadcast::with_capacity(2).overflow(OverflowPolicy::Disconnect);
+        let mut slow = broadcast.subscribe();
+        let mut fast = broadcast.subscribe();
+        let mut member = broadcast.join("lobby", "alice");
+
+        for i in 0..3 {
+            broadcast.send_text(i.to_string());
+            broadcast.send_text_to_room("lobby", i.to_string());
+            assert_eq!(text(fast.recv().await), i.to_string());
+        }
+
+        assert!(slow.recv().await.is_none());
+        assert!(slow.is_disconnected());
+        assert_eq!(broadcast.subscriber_count(), 1);
+
+        assert!(member.recv().await.is_none());
+        assert!(!broadcast.is_member("lobby", "alice"));
+        assert_eq!(broadcast.room_count(), 0);
+    }
+
+    #[test]
+    fn test_join_since_replays_missed_messages() {
+        let broadcast = Broadcast::new().history(10);
+        let mut alice = broadcast.join("lobby", "alice");
+        for i in 1..=3 {
+            broadcast.send_text_to_room("lobby", format!("m{}", i));
+        }
+        assert!(matches!(alice.try_recv_with_id(), Some(Ok((1, _)))));
+        let seen = alice.last_id();
+        assert_eq!(seen, 1);
+        drop(alice);
+
+        // The room outlives its last member while history is retained
+        assert_eq!(broadcast.room_count(), 0);
+        broadcast.send_text_to_room("lobby", "m4");
+        assert_eq!(broadcast.last_message_id("lobby"), 4);
+
+        let mut alice = broadcast.join_since("lobby", "alice", seen);
+        assert!(!alice.missed_messages());
+        broadcast.send_text_to_room("lobby", "m5");
+
+        let received: Vec<(u64, String)> = std::iter::from_fn(|| alice.try_recv_with_id())
+            .map(|r| {
+                let (id, msg) = r.unwrap();
+                (id, msg.into_text().unwrap())
+            })
+            .collect();
+        assert_eq!(
+            received,
+            vec![
+                (2, "m2".to_string()),
+                (3, "m3".to_string()),
+                (4, "m4".to_string()),
+                (5, "m5".to_string())
+            ]
+        );
+        assert_eq!(alice.last_id(), 5);
+    }
+
+    #[test]
+    fn test_join_since_reports_evicted_messages() {
+        let broadcast = Broadcast::new().history(2);
+        let _keep = broadcast.join("lobby", "bob");
+        for i in 1..=5 {
+            broadcast.send_text_to_room("lobby", i.to_string());
+        }
+
+        let mut alice = broadcast.join_since("lobby", "alice", 1);
+        assert!(alice.missed_messages());
+        assert_eq!(text(alice.try_recv()), "4");
+        assert_eq!(text(alice.try_recv()), "5");
+        assert!(alice.try_recv().is_none());
+
+        let up_to_date = broadcast.join_since("lobby", "carol", 5);
+        assert!(!up_to_date.missed_messages());
+    }
+
+    #[test]
+    fn test_rooms_without_history_are_removed() {
+        let broadcast = Broadcast::new();
+        let alice = broadcast.join("lobby", "alice");
+        broadcast.send_text_to_room("lobby", "hi");
+        drop(alice);
+        assert_eq!(broadcast.last_message_id("lobby"), 0);
+        assert_eq!(broadcast.send_text_to_room("lobby", "hi"), 0);
+    }
+
+    #[test]
+    fn test_empty_rooms_drop_their_history_after_the_ttl() {
+        let broadcast = Broadcast::new().history(10).history_ttl(Duration::ZERO);
+        let alice = broadcast.join("lobby", "alice");
+        broadcast.send_text_to_room("lobby", "hi");
+        assert_eq!(broadcast.last_message_id("lobby"), 1);
+
+        drop(alice);
+        assert_eq!(broadcast.last_message_id("lobby"), 0);
+        assert_eq!(broadcast.send_text_to_room("lobby", "hi"), 0);
+
+        // Rooms with members are kept
+        let _bob = broadcast.join("games", "bob");
+        broadcast.send_text_to_room("games", "hi");
+        drop(broadcast.join("lobby", "carol"));
+        assert_eq!(broadcast.last_message_id("games"), 1);
+    }
+
+    #[test]
+    fn test_stale_subscription_does_not_evict_rejoin() {
+        let broadcast = Broadcast::new();
+        let first = broadcast.join("lobby", "alice");
+        let second = broadcast.join("lobby", "alice");
+        assert!(!first.is_active());
+
+        drop(first);
+        assert!(broadcast.is_member("lobby", "alice"));
+        assert!(second.is_active());
+    }
+}
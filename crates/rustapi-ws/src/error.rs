@@ -4,6 +4,7 @@ use std::fmt;
 
 /// Error type for WebSocket operations
 #[derive(Debug)]
+#[non_exhaustive]
 pub enum WebSocketError {
     /// Invalid WebSocket upgrade request
     InvalidUpgrade(String),
@@ -21,6 +22,15 @@ pub enum WebSocketError {
     DeserializationError(String),
     /// Protocol error
     ProtocolError(String),
+    /// Presence store error
+    PresenceStore(String),
+    /// Message exceeds the configured size limit
+    MessageTooLarge {
+        /// Size of the received message in bytes
+        size: usize,
+        /// Configured limit in bytes
+        limit: usize,
+    },
     /// IO error
     IoError(std::io::Error),
     /// Tungstenite error
@@ -38,6 +48,12 @@ impl fmt::Display for WebSocketError {
             Self::SerializationError(msg) => write!(f, "Message serialization error: {}", msg),
             Self::DeserializationError(msg) => write!(f, "Message deserialization error: {}", msg),
             Self::ProtocolError(msg) => write!(f, "WebSocket protocol error: {}", msg),
+            Self::PresenceStore(msg) => write!(f, "Presence store error: {}", msg),
+            Self::MessageTooLarge { size, limit } => write!(
+                f,
+                "Message of {} bytes exceeds the limit of {} bytes",
+                size, limit
+            ),
             Self::IoError(e) => write!(f, "IO error: {}", e),
             Self::Tungstenite(e) => write!(f, "WebSocket error: {}", e),
         }
@@ -62,7 +78,16 @@ impl From<std::io::Error> for WebSocketError {
 
 impl From<tungstenite::Error> for WebSocketError {
     fn from(e: tungstenite::Error) -> Self {
-        Self::Tungstenite(e)
+        match e {
+            tungstenite::Error::Capacity(tungstenite::error::CapacityError::MessageTooLong {
+                size,
+                max_size,
+            }) => Self::MessageTooLarge {
+                size,
+                limit: max_size,
+            },
+            e => Self::Tungstenite(e),
+        }
     }
 }
 
@@ -101,6 +126,11 @@ impl WebSocketError {
     pub fn protocol_error(msg: impl Into<String>) -> Self {
         Self::ProtocolError(msg.into())
     }
+
+    /// Create a presence store error
+    pub fn presence_store(msg: impl Into<String>) -> Self {
+        Self::PresenceStore(msg.into())
+    }
 }
 
 impl From<WebSocketError> for rustapi_core::ApiError {
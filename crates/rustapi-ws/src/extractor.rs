@@ -1,6 +1,9 @@
 //! WebSocket extractor
 
+use crate::auth::{Claims, WsAuthConfig};
+use crate::limits::{WsConnectionLimits, WsConnectionPermit};
 use crate::upgrade::{validate_upgrade_request, WebSocketUpgrade};
+use crate::WsHeartbeatConfig;
 use hyper::upgrade::OnUpgrade;
 use rustapi_core::{ApiError, FromRequest, Request, Result};
 use rustapi_openapi::{Operation, OperationModifier};
@@ -33,11 +36,51 @@ use rustapi_openapi::{Operation, OperationModifier};
 ///     })
 /// }
 /// ```
+///
+/// # Heartbeat
+///
+/// Ping intervals, pong timeouts and idle reaping are configured with
+/// [`heartbeat`](Self::heartbeat), or for all WebSocket routes by registering
+/// a [`WsHeartbeatConfig`] as application state. Dead or idle connections are
+/// closed, which ends the handler's receive loop.
+///
+/// # Authentication
+///
+/// When a [`WsAuthConfig`] is registered as application state, the token is
+/// validated during extraction, so invalid handshakes are rejected with
+/// `401 Unauthorized` before the connection is upgraded. The validated claims
+/// are available through [`claims`](Self::claims):
+///
+/// ```rust,ignore
+/// async fn ws_handler(ws: WebSocket) -> impl IntoResponse {
+///     let user = ws.claims().map(|c| c.subject().to_string());
+///     ws.on_upgrade(move |socket| async move {
+///         // `user` is the authenticated subject
+///     })
+/// }
+/// ```
+///
+/// # Connection Limits
+///
+/// When a [`WsConnectionLimits`] is registered as application state, upgrades
+/// over its global or per-IP limit are rejected during extraction. The
+/// connection counts against the limits until the upgrade callback returns.
+///
+/// # Subprotocols
+///
+/// Clients may carry an access token in `Sec-WebSocket-Protocol`, so
+/// requested protocols are not echoed back unless the handler names the ones
+/// it speaks with [`accept_protocols`](Self::accept_protocols).
 pub struct WebSocket {
     sec_key: String,
     protocols: Vec<String>,
+    accepted_protocols: Vec<String>,
     extensions: Option<String>,
     on_upgrade: Option<OnUpgrade>,
+    claims: Option<Claims>,
+    heartbeat: Option<WsHeartbeatConfig>,
+    max_message_size: Option<usize>,
+    permit: Option<WsConnectionPermit>,
 }
 
 impl WebSocket {
@@ -50,23 +93,81 @@ impl WebSocket {
         F: FnOnce(crate::WebSocketStream) -> Fut + Send + 'static,
         Fut: std::future::Future<Output = ()> + Send + 'static,
     {
-        let upgrade = WebSocketUpgrade::new(self.sec_key, self.extensions, self.on_upgrade.take());
+        let protocol = self.selected_protocol().map(str::to_string);
+        let mut upgrade =
+            WebSocketUpgrade::new(self.sec_key, self.extensions, self.on_upgrade.take());
+        if let Some(heartbeat) = self.heartbeat {
+            upgrade = upgrade.heartbeat(heartbeat);
+        }
+        if let Some(bytes) = self.max_message_size {
+            upgrade = upgrade.max_message_size(bytes);
+        }
+        if let Some(permit) = self.permit.take() {
+            upgrade = upgrade.permit(permit);
+        }
 
-        // If protocols were requested, select the first one
-        let upgrade = if let Some(protocol) = self.protocols.first() {
-            upgrade.protocol(protocol)
-        } else {
-            upgrade
+        let upgrade = match protocol {
+            Some(protocol) => upgrade.protocol(&protocol),
+            None => upgrade,
         };
 
         upgrade.on_upgrade(callback)
     }
 
+    /// Subprotocols this handler speaks, in order of preference
+    ///
+    /// The upgrade response selects the first of them the client requested.
+    /// Only these names are ever echoed, never other requested entries such
+    /// as `bearer.<token>`. With `Sec-WebSocket-Protocol: bearer, <token>`,
+    /// accept `"bearer"` so browsers complete the handshake.
+    pub fn accept_protocols<I, S>(mut self, protocols: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.accepted_protocols = protocols.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// The accepted protocol the upgrade response will select, if any
+    pub fn selected_protocol(&self) -> Option<&str> {
+        self.accepted_protocols
+            .iter()
+            .find(|accepted| self.has_protocol(accepted))
+            .map(String::as_str)
+    }
+
+    /// Enable heartbeat pings, pong timeouts and idle reaping
+    ///
+    /// Overrides a [`WsHeartbeatConfig`] registered as application state.
+    pub fn heartbeat(mut self, config: WsHeartbeatConfig) -> Self {
+        self.heartbeat = Some(config);
+        self
+    }
+
+    /// Limit the size of incoming messages and frames in bytes
+    ///
+    /// Enforced while frames are read, so oversized messages are never
+    /// buffered. [`typed`](crate::WebSocketStream::typed) sockets inherit the
+    /// limit.
+    pub fn max_message_size(mut self, bytes: usize) -> Self {
+        self.max_message_size = Some(bytes);
+        self
+    }
+
     /// Get the requested protocols
     pub fn protocols(&self) -> &[String] {
         &self.protocols
     }
 
+    /// Claims of the authenticated client
+    ///
+    /// `None` when no [`WsAuthConfig`] is registered, or when authentication
+    /// is optional and the client sent no token.
+    pub fn claims(&self) -> Option<&Claims> {
+        self.claims.as_ref()
+    }
+
     /// Check if a specific protocol was requested
     pub fn has_protocol(&self, protocol: &str) -> bool {
         self.protocols.iter().any(|p| p == protocol)
@@ -97,6 +198,26 @@ impl FromRequest for WebSocket {
             .and_then(|v| v.to_str().ok())
             .map(|s| s.to_string());
 
+        // Count the connection before doing any more work for it
+        let permit = match req.state().get::<WsConnectionLimits>() {
+            Some(limits) => Some(limits.acquire_for(req)?),
+            None => None,
+        };
+
+        // Authenticate before accepting the upgrade
+        let claims = match req.state().get::<WsAuthConfig>() {
+            Some(auth) => auth
+                .authenticate_parts(req.headers(), req.uri())
+                .await
+                .map_err(|e| {
+                    tracing::debug!("WebSocket authentication failed: {}", e);
+                    ApiError::from(e)
+                })?,
+            None => None,
+        };
+
+        let heartbeat = req.state().get::<WsHeartbeatConfig>().copied();
+
         // Capture OnUpgrade future
         let on_upgrade = req.extensions_mut().remove::<OnUpgrade>();
 
@@ -109,8 +230,13 @@ impl FromRequest for WebSocket {
         Ok(Self {
             sec_key,
             protocols,
+            accepted_protocols: Vec::new(),
             extensions,
             on_upgrade,
+            claims,
+            heartbeat,
+            max_message_size: None,
+            permit,
         })
     }
 }
@@ -121,3 +247,144 @@ impl OperationModifier for WebSocket {
         // The upgrade is indicated by the response
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::auth::{StaticTokenValidator, TokenExtractor};
+    use bytes::Bytes;
+    use http::StatusCode;
+    use rustapi_core::{BodyVariant, PathParams};
+    use std::sync::Arc;
+
+    fn upgrade_request(uri: &str, auth: Option<WsAuthConfig>) -> Request {
+        upgrade_request_with(uri, auth, None)
+    }
+
+    fn upgrade_request_with(
+        uri: &str,
+        auth: Option<WsAuthConfig>,
+        limits: Option<WsConnectionLimits>,
+    ) -> Request {
+        let (parts, _) = http::Request::builder()
+            .uri(uri)
+            .header("Upgrade", "websocket")
+            .header("Connection", "Upgrade")
+            .header("Sec-WebSocket-Key", "dGhlIHNhbXBsZSBub25jZQ==")
+            .header("Sec-WebSocket-Version", "13")
+            .body(())
+            .unwrap()
+            .into_parts();
+        let mut state = http::Extensions::new();
+        if let Some(auth) = auth {
+            state.insert(auth);
+        }
+        if let Some(limits) = limits {
+            state.insert(limits);
+        }
+        Request::new(
+            parts,
+            BodyVariant::Buffered(Bytes::new()),
+            Arc::new(state),
+            PathParams::new(),
+        )
+    }
+
+    fn auth() -> WsAuthConfig {
+        WsAuthConfig::new(StaticTokenValidator::new().add_token("secret", Claims::new("user-1")))
+            .extractor(TokenExtractor::query("token"))
+    }
+
+    #[tokio::test]
+    async fn test_authenticated_upgrade_exposes_claims() {
+        let ws = WebSocket::from_request(&mut upgrade_request("/ws?token=secret", Some(auth())))
+            .await
+            .unwrap();
+        assert_eq!(ws.claims().unwrap().subject(), "user-1");
+    }
+
+    #[tokio::test]
+    async fn test_unauthenticated_upgrade_is_rejected() {
+        let err = WebSocket::from_request(&mut upgrade_request("/ws", Some(auth())))
+            .await
+            .err()
+            .unwrap();
+        assert_eq!(err.status, StatusCode::UNAUTHORIZED);
+
+        let err = WebSocket::from_request(&mut upgrade_request("/ws?token=wrong", Some(auth())))
+            .await
+            .err()
+            .unwrap();
+        assert_eq!(err.status, StatusCode::UNAUTHORIZED);
+    }
+
+    #[tokio::test]
+    async fn test_upgrade_without_auth_config() {
+        let ws = WebSocket::from_request(&mut upgrade_request("/ws", None))
+            .await
+            .unwrap();
+        assert!(ws.claims().is_none());
+    }
+
+    async fn upgrade_with_protocols(protocols: &str, accept: &[&str]) -> Option<String> {
+        let mut request = upgrade_request("/ws", None);
+        request
+            .headers_mut()
+            .insert("Sec-WebSocket-Protocol", protocols.parse().unwrap());
+        let ws = WebSocket::from_request(&mut request)
+            .await
+            .unwrap()
+            .accept_protocols(accept.iter().copied());
+        let response = ws.on_upgrade(|_| async {}).into_response_inner();
+        response
+            .headers()
+            .get("Sec-WebSocket-Protocol")
+            .map(|v| v.to_str().unwrap().to_string())
+    }
+
+    #[tokio::test]
+    async fn test_only_accepted_protocols_are_echoed() {
+        assert_eq!(
+            upgrade_with_protocols("bearer, secret-token", &[]).await,
+            None
+        );
+        assert_eq!(
+            upgrade_with_protocols("bearer.secret-token", &[]).await,
+            None
+        );
+        assert_eq!(
+            upgrade_with_protocols("bearer, secret-token", &["bearer"])
+                .await
+                .as_deref(),
+            Some("bearer")
+        );
+        assert_eq!(
+            upgrade_with_protocols(
+                "bearer.secret-token, chat.v2, chat.v1",
+                &["chat.v1", "chat.v2"]
+            )
+            .await
+            .as_deref(),
+            Some("chat.v1")
+        );
+        assert_eq!(
+            upgrade_with_protocols("graphql-ws", &["chat.v1"]).await,
+            None
+        );
+    }
+
+    #[tokio::test]
+    async fn test_connection_limit_rejects_upgrades_until_released() {
+        let limits = WsConnectionLimits::new().max_connections(1);
+        let request = || upgrade_request_with("/ws", None, Some(limits.clone()));
+
+        let ws = WebSocket::from_request(&mut request()).await.unwrap();
+        let err = WebSocket::from_request(&mut request()).await.err().unwrap();
+        assert_eq!(err.status, StatusCode::TOO_MANY_REQUESTS);
+        assert_eq!(limits.stats().active, 1);
+
+        drop(ws);
+        assert!(WebSocket::from_request(&mut request()).await.is_ok());
+        assert_eq!(limits.stats().rejected_global, 1);
+    }
+}
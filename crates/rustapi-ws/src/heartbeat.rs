@@ -1,6 +1,7 @@
 //! WebSocket heartbeat configuration
 //!
-//! This module provides configuration for WebSocket heartbeats (ping/pong).
+//! This module provides configuration for WebSocket heartbeats (ping/pong)
+//! and idle connection reaping.
 
 use std::time::Duration;
 
@@ -11,6 +12,9 @@ pub struct WsHeartbeatConfig {
     pub interval: Duration,
     /// Timeout for waiting for a pong response
     pub timeout: Duration,
+    /// Close the connection after this long without text/binary messages
+    /// in either direction (`None` disables idle reaping)
+    pub max_idle: Option<Duration>,
 }
 
 impl Default for WsHeartbeatConfig {
@@ -18,6 +22,7 @@ impl Default for WsHeartbeatConfig {
         Self {
             interval: Duration::from_secs(30),
             timeout: Duration::from_secs(10),
+            max_idle: None,
         }
     }
 }
@@ -39,6 +44,23 @@ impl WsHeartbeatConfig {
         self.timeout = timeout;
         self
     }
+
+    /// Close connections that exchange no text/binary messages for `max_idle`
+    ///
+    /// Pings and pongs keep a connection alive but do not count as activity.
+    pub fn max_idle(mut self, max_idle: Duration) -> Self {
+        self.max_idle = Some(max_idle);
+        self
+    }
+
+    /// How often the connection checks for pong and idle timeouts
+    pub(crate) fn check_period(&self) -> Duration {
+        match self.max_idle {
+            Some(max_idle) => self.timeout.min(max_idle),
+            None => self.timeout,
+        }
+        .max(Duration::from_millis(10))
+    }
 }
 
 #[cfg(test)]
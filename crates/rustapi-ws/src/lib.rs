@@ -9,9 +9,12 @@
 //!
 //! - **WebSocket Upgrade**: Seamless HTTP to WebSocket upgrade via the `WebSocket` extractor
 //! - **Message Types**: Support for Text, Binary, Ping/Pong messages
-//! - **Type-Safe JSON**: Serialize/deserialize JSON messages with serde
+//! - **Type-Safe JSON**: Serialize/deserialize JSON messages with serde, or use
+//!   `TypedSocket<In, Out>` for typed messages with size limits
 //! - **Connection Management**: Clean connection lifecycle with proper close handling
 //! - **Broadcast Support**: Send messages to multiple connected clients
+//! - **Rooms**: Named rooms with join/leave, room-scoped broadcast and membership queries
+//! - **Presence**: Who is online per room, with join/leave events and pluggable stores
 //!
 //! ## Quick Start
 //!
@@ -55,27 +58,41 @@ mod compression;
 mod error;
 mod extractor;
 mod heartbeat;
+mod limits;
 mod message;
+mod presence;
 mod socket;
+mod typed;
 mod upgrade;
 
 /// Authentication support for WebSocket connections
 pub mod auth;
 
-pub use broadcast::Broadcast;
+pub use broadcast::{
+    Broadcast, BroadcastReceiver, BroadcastRecvError, OverflowPolicy, RoomSubscription,
+};
 pub use compression::WsCompressionConfig;
 pub use error::WebSocketError;
 pub use extractor::WebSocket;
 pub use heartbeat::WsHeartbeatConfig;
+pub use limits::{WsConnectionLimits, WsConnectionPermit, WsConnectionStats};
 pub use message::{CloseCode, CloseFrame, Message};
+pub use presence::{
+    InMemoryPresenceStore, Presence, PresenceEvent, PresenceInfo, PresenceStore,
+    PresenceSubscription,
+};
 pub use socket::{WebSocketReceiver, WebSocketSender, WebSocketStream};
+pub use typed::{TypedSocket, DEFAULT_MAX_MESSAGE_SIZE};
 pub use upgrade::WebSocketUpgrade;
 
 /// Prelude module for convenient imports
 pub mod prelude {
-    pub use crate::auth::{AuthError, Claims, TokenExtractor, TokenValidator, WsAuthConfig};
+    pub use crate::auth::{
+        AuthError, Claims, FnValidator, TokenExtractor, TokenValidator, WsAuthConfig,
+    };
     pub use crate::{
-        Broadcast, CloseCode, CloseFrame, Message, WebSocket, WebSocketError, WebSocketReceiver,
-        WebSocketSender, WebSocketStream, WebSocketUpgrade, WsCompressionConfig,
+        Broadcast, BroadcastReceiver, CloseCode, CloseFrame, Message, RoomSubscription,
+        TypedSocket, WebSocket, WebSocketError, WebSocketReceiver, WebSocketSender,
+        WebSocketStream, WebSocketUpgrade, WsCompressionConfig, WsConnectionLimits,
     };
 }
@@ -0,0 +1,275 @@
+//! WebSocket connection limits
+//!
+//! [`WsConnectionLimits`] caps how many WebSocket connections are open at
+//! once, in total and per client IP, so one client cannot exhaust the server
+//! or a broadcast hub. Register it as application state; the [`WebSocket`]
+//! extractor then rejects upgrades over a limit before the handshake, with
+//! `429 Too Many Requests` unless another status is configured.
+//!
+//! A connection counts from the upgrade request until its handler returns.
+//! [`WsConnectionLimits::stats`] reports open connections and rejections.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! use rustapi_ws::WsConnectionLimits;
+//!
+//! let limits = WsConnectionLimits::new()
+//!     .max_connections(10_000)
+//!     .max_per_ip(20);
+//!
+//! RustApi::new()
+//!     .state(limits.clone())
+//!     .route("/ws", get(ws_handler))
+//! ```
+//!
+//! [`WebSocket`]: crate::WebSocket
+
+use http::StatusCode;
+use rustapi_core::{ApiError, ClientIp, Request};
+use std::collections::HashMap;
+use std::net::IpAddr;
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+
+/// Snapshot of WebSocket connection counts
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct WsConnectionStats {
+    /// Connections open now
+    pub active: usize,
+    /// Most connections open at once
+    pub peak: usize,
+    /// Connections accepted
+    pub accepted: u64,
+    /// Upgrades rejected by the global limit
+    pub rejected_global: u64,
+    /// Upgrades rejected by the per-IP limit
+    pub rejected_per_ip: u64,
+}
+
+#[derive(Default)]
+struct Counters {
+    active: AtomicUsize,
+    peak: AtomicUsize,
+    accepted: AtomicU64,
+    rejected_global: AtomicU64,
+    rejected_per_ip: AtomicU64,
+    per_ip: Mutex<HashMap<IpAddr, usize>>,
+}
+
+/// Global and per-IP limits on concurrent WebSocket connections
+///
+/// Clones share the connection counts.
+#[derive(Clone)]
+pub struct WsConnectionLimits {
+    max_connections: Option<usize>,
+    max_per_ip: Option<usize>,
+    status: StatusCode,
+    trust_forwarded_for: bool,
+    counters: Arc<Counters>,
+}
+
+impl WsConnectionLimits {
+    /// Create limits that allow any number of connections until set
+    pub fn new() -> Self {
+        Self {
+            max_connections: None,
+            max_per_ip: None,
+            status: StatusCode::TOO_MANY_REQUESTS,
+            trust_forwarded_for: false,
+            counters: Arc::new(Counters::default()),
+        }
+    }
+
+    /// Most connections open at once, in total
+    pub fn max_connections(mut self, max: usize) -> Self {
+        self.max_connections = Some(max);
+        self
+    }
+
+    /// Most connections open at once from one client IP
+    ///
+    /// Requests whose client IP is unknown are only held to the global limit.
+    pub fn max_per_ip(mut self, max: usize) -> Self {
+        self.max_per_ip = Some(max);
+        self
+    }
+
+    /// Status of rejected upgrades (default: `429 Too Many Requests`)
+    pub fn rejection_status(mut self, status: StatusCode) -> Self {
+        self.status = status;
+        self
+    }
+
+    /// Take the client IP from `X-Forwarded-For` instead of the peer address
+    ///
+    /// Only safe behind a proxy that overwrites the header.
+    pub fn trust_forwarded_for(mut self, trust: bool) -> Self {
+        self.trust_forwarded_for = trust;
+        self
+    }
+
+    /// Current connection counts
+    pub fn stats(&self) -> WsConnectionStats {
+        WsConnectionStats {
+            active: self.counters.active.load(Ordering::Relaxed),
+            peak: self.counters.peak.load(Ordering::Relaxed),
+            accepted: self.counters.accepted.load(Ordering::Relaxed),
+            rejected_global: self.counters.rejected_global.load(Ordering::Relaxed),
+            rejected_per_ip: self.counters.rejected_per_ip.load(Ordering::Relaxed),
+        }
+    }
+
+    /// Connections open now from `ip`
+    pub fn active_for(&self, ip: IpAddr) -> usize {
+        self.per_ip().get(&ip).copied().unwrap_or(0)
+    }
+
+    /// Count a new connection from `ip`, unless it would exceed a limit
+    ///
+    /// The connection counts until the returned permit is dropped.
+    pub fn try_acquire(&self, ip: Option<IpAddr>) -> Result<WsConnectionPermit, ApiError> {
+        // Holding the per-IP lock keeps both checks and increments atomic
+        let mut per_ip = self.per_ip();
+
+        let active = self.counters.active.load(Ordering::Relaxed);
+        if self.max_connections.is_some_and(|max| active >= max) {
+            self.counters
+                .rejected_global
+                .fetch_add(1, Ordering::Relaxed);
+            tracing::debug!(active, "WebSocket connection limit reached");
+            return Err(self.rejection("Too many WebSocket connections"));
+        }
+        if let (Some(ip), Some(max)) = (ip, self.max_per_ip) {
+            if per_ip.get(&ip).is_some_and(|count| *count >= max) {
+                self.counters
+                    .rejected_per_ip
+                    .fetch_add(1, Ordering::Relaxed);
+                tracing::debug!(%ip, "WebSocket per-IP connection limit reached");
+                return Err(self.rejection("Too many WebSocket connections from this client"));
+            }
+        }
+
+        if let Some(ip) = ip {
+            *per_ip.entry(ip).or_insert(0) += 1;
+        }
+        let active = self.counters.active.fetch_add(1, Ordering::Relaxed) + 1;
+        self.counters.peak.fetch_max(active, Ordering::Relaxed);
+        self.counters.accepted.fetch_add(1, Ordering::Relaxed);
+
+        Ok(WsConnectionPermit {
+            counters: self.counters.clone(),
+            ip,
+        })
+    }
+
+    /// Count the connection of an upgrade request
+    pub(crate) fn acquire_for(&self, req: &Request) -> Result<WsConnectionPermit, ApiError> {
+        let ip = ClientIp::extract_with_config(req, self.trust_forwarded_for)
+            .ok()
+            .map(|ClientIp(ip)| ip);
+        self.try_acquire(ip)
+    }
+
+    fn rejection(&self, message: &str) -> ApiError {
+        ApiError::new(self.status, "websocket_limit", message)
+    }
+
+    fn per_ip(&self) -> std::sync::MutexGuard<'_, HashMap<IpAddr, usize>> {
+        self.counters
+            .per_ip
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+impl Default for WsConnectionLimits {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// An open connection counted by [`WsConnectionLimits`]
+///
+/// Dropping the permit frees its place.
+pub struct WsConnectionPermit {
+    counters: Arc<Counters>,
+    ip: Option<IpAddr>,
+}
+
+impl std::fmt::Debug for WsConnectionPermit {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("WsConnectionPermit")
+            .field("ip", &self.ip)
+            .finish()
+    }
+}
+
+impl Drop for WsConnectionPermit {
+    fn drop(&mut self) {
+        let mut per_ip = self
+            .counters
+            .per_ip
+            .lock()
+            .unwrap_or_else(|e| e.into_inner());
+        if let Some(ip) = self.ip {
+            if let Some(count) = per_ip.get_mut(&ip) {
+                *count -= 1;
+                if *count == 0 {
+                    per_ip.remove(&ip);
+                }
+            }
+        }
+        self.counters.active.fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ip(s: &str) -> Option<IpAddr> {
+        Some(s.parse().unwrap())
+    }
+
+    #[test]
+    fn per_ip_limit_rejects_only_the_busy_client() {
+        let limits = WsConnectionLimits::new().max_per_ip(2);
+
+        let first = limits.try_acquire(ip("10.0.0.1")).unwrap();
+        let _second = limits.try_acquire(ip("10.0.0.1")).unwrap();
+        let err = limits.try_acquire(ip("10.0.0.1")).unwrap_err();
+        assert_eq!(err.status, StatusCode::TOO_MANY_REQUESTS);
+        assert!(limits.try_acquire(ip("10.0.0.2")).is_ok());
+        assert!(limits.try_acquire(None).is_ok());
+
+        drop(first);
+        assert_eq!(limits.active_for("10.0.0.1".parse().unwrap()), 1);
+        assert!(limits.try_acquire(ip("10.0.0.1")).is_ok());
+    }
+
+    #[test]
+    fn global_limit_uses_the_configured_status_and_counts() {
+        let limits = WsConnectionLimits::new()
+            .max_connections(1)
+            .rejection_status(StatusCode::SERVICE_UNAVAILABLE);
+
+        let permit = limits.try_acquire(ip("10.0.0.1")).unwrap();
+        let err = limits.try_acquire(ip("10.0.0.2")).unwrap_err();
+        assert_eq!(err.status, StatusCode::SERVICE_UNAVAILABLE);
+        drop(permit);
+        let _permit = limits.try_acquire(ip("10.0.0.2")).unwrap();
+
+        assert_eq!(
+            limits.stats(),
+            WsConnectionStats {
+                active: 1,
+                peak: 1,
+                accepted: 2,
+                rejected_global: 1,
+                rejected_per_ip: 0,
+            }
+        );
+        assert_eq!(limits.active_for("10.0.0.1".parse().unwrap()), 0);
+    }
+}
@@ -70,6 +70,29 @@ impl Message {
         }
     }
 
+    /// Deserialize a text or binary message as JSON
+    pub(crate) fn decode_json<T: DeserializeOwned>(&self) -> Result<T, crate::WebSocketError> {
+        match self {
+            Self::Binary(data) => serde_json::from_slice(data)
+                .map_err(|e| crate::WebSocketError::deserialization_error(e.to_string())),
+            _ => self.as_json(),
+        }
+    }
+
+    /// Payload length in bytes
+    pub fn len(&self) -> usize {
+        match self {
+            Self::Text(text) => text.len(),
+            Self::Binary(data) | Self::Ping(data) | Self::Pong(data) => data.len(),
+            Self::Close(frame) => frame.as_ref().map(|f| 2 + f.reason.len()).unwrap_or(0),
+        }
+    }
+
+    /// Check whether the payload is empty
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
     /// Check if this is a text message
     pub fn is_text(&self) -> bool {
         matches!(self, Self::Text(_))
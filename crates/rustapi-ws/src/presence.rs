@@ -0,0 +1,484 @@
+//! Presence tracking for WebSocket rooms
+//!
+//! [`Presence`] layers "who is online" on top of [`Broadcast`] rooms. Joining
+//! through `Presence` records the member (with `joined_at` and arbitrary
+//! metadata such as a username) in a [`PresenceStore`] and sends a `join`
+//! event to the room; when the returned [`PresenceSubscription`] is dropped
+//! or [`left`](PresenceSubscription::leave), the entry is removed and a
+//! `leave` event is sent.
+//!
+//! Every join gets its own session token. When a member reconnects before
+//! its old connection is cleaned up, the old subscription no longer owns the
+//! entry, so dropping it neither removes the new entry nor announces a leave.
+//!
+//! Events are JSON text messages:
+//!
+//! ```text
+//! {"type":"join","room":"lobby","member":"u1","joined_at":1760000000000,"metadata":{"name":"Alice"}}
+//! {"type":"leave","room":"lobby","member":"u1"}
+//! ```
+//!
+//! The default [`InMemoryPresenceStore`] only sees the current process. For
+//! multi-instance deployments, implement [`PresenceStore`] over a shared
+//! backend (Redis, a database, ...) so `online()` reports members connected
+//! to any instance.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! use rustapi_ws::{Broadcast, Presence};
+//!
+//! let presence = Presence::new(Broadcast::new());
+//!
+//! ws.on_upgrade(move |mut socket| async move {
+//!     let mut sub = presence
+//!         .join("lobby", user.id.clone(), json!({ "name": user.name }))
+//!         .await?;
+//!     let online = presence.online("lobby").await?;
+//!     socket.send_json(&online).await?;
+//!
+//!     while let Some(Ok(msg)) = sub.recv().await {
+//!         // join/leave events and room messages
+//!         socket.send(msg).await?;
+//!     }
+//! })
+//! ```
+
+use crate::{Broadcast, RoomSubscription, WebSocketError};
+use serde::{Deserialize, Serialize};
+use std::collections::hash_map::RandomState;
+use std::collections::HashMap;
+use std::hash::{BuildHasher, Hasher};
+use std::ops::{Deref, DerefMut};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, OnceLock, RwLock};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// A member's presence in a room
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PresenceInfo {
+    /// Room name
+    pub room: String,
+    /// Member id
+    pub member: String,
+    /// Join time as a Unix timestamp in milliseconds
+    pub joined_at: u64,
+    /// Application metadata (e.g. username, avatar)
+    pub metadata: serde_json::Value,
+}
+
+/// Presence change sent to room members
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum PresenceEvent {
+    /// A member joined the room
+    Join(PresenceInfo),
+    /// A member left the room
+    Leave {
+        /// Room name
+        room: String,
+        /// Member id
+        member: String,
+    },
+}
+
+/// Storage backend for presence entries
+///
+/// Each entry is owned by the session that recorded it. A store must only
+/// remove an entry on `leave` when the session still matches, so a stale
+/// connection cannot remove the entry of a newer one.
+#[async_trait::async_trait]
+pub trait PresenceStore: Send + Sync {
+    /// Record a member as present for `session`, replacing any previous entry
+    async fn join(&self, info: PresenceInfo, session: &str) -> Result<(), WebSocketError>;
+
+    /// Remove a member if its entry belongs to `session`, returning the
+    /// removed entry
+    async fn leave(
+        &self,
+        room: &str,
+        member: &str,
+        session: &str,
+    ) -> Result<Option<PresenceInfo>, WebSocketError>;
+
+    /// Members present in a room
+    async fn members(&self, room: &str) -> Result<Vec<PresenceInfo>, WebSocketError>;
+}
+
+/// In-process presence store
+#[derive(Debug, Default)]
+pub struct InMemoryPresenceStore {
+    rooms: RwLock<HashMap<String, RoomPresence>>,
+}
+
+/// Member id -> (owning session, entry)
+type RoomPresence = HashMap<String, (String, PresenceInfo)>;
+
+impl InMemoryPresenceStore {
+    /// Create an empty store
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait::async_trait]
+impl PresenceStore for InMemoryPresenceStore {
+    async fn join(&self, info: PresenceInfo, session: &str) -> Result<(), WebSocketError> {
+        let mut rooms = self.rooms.write().unwrap_or_else(|e| e.into_inner());
+        rooms
+            .entry(info.room.clone())
+            .or_default()
+            .insert(info.member.clone(), (session.to_string(), info));
+        Ok(())
+    }
+
+    async fn leave(
+        &self,
+        room: &str,
+        member: &str,
+        session: &str,
+    ) -> Result<Option<PresenceInfo>, WebSocketError> {
+        let mut rooms = self.rooms.write().unwrap_or_else(|e| e.into_inner());
+        let Some(members) = rooms.get_mut(room) else {
+            return Ok(None);
+        };
+        if members
+            .get(member)
+            .is_none_or(|(owner, _)| owner != session)
+        {
+            return Ok(None);
+        }
+        let removed = members.remove(member).map(|(_, info)| info);
+        if members.is_empty() {
+            rooms.remove(room);
+        }
+        Ok(removed)
+    }
+
+    async fn members(&self, room: &str) -> Result<Vec<PresenceInfo>, WebSocketError> {
+        let rooms = self.rooms.read().unwrap_or_else(|e| e.into_inner());
+        let mut members: Vec<PresenceInfo> = rooms
+            .get(room)
+            .map(|m| m.values().map(|(_, info)| info.clone()).collect())
+            .unwrap_or_default();
+        members.sort_by(|a, b| (a.joined_at, &a.member).cmp(&(b.joined_at, &b.member)));
+        Ok(members)
+    }
+}
+
+/// Presence tracking on top of [`Broadcast`] rooms
+#[derive(Clone)]
+pub struct Presence {
+    broadcast: Broadcast,
+    store: Arc<dyn PresenceStore>,
+}
+
+impl Presence {
+    /// Track presence for the rooms of `broadcast` in an in-memory store
+    pub fn new(broadcast: Broadcast) -> Self {
+        Self {
+            broadcast,
+            store: Arc::new(InMemoryPresenceStore::new()),
+        }
+    }
+
+    /// Use a custom presence store
+    pub fn with_store<S: PresenceStore + 'static>(mut self, store: S) -> Self {
+        self.store = Arc::new(store);
+        self
+    }
+
+    /// The underlying broadcast channel
+    pub fn broadcast(&self) -> &Broadcast {
+        &self.broadcast
+    }
+
+    /// Join a room, record presence and announce the join to the room
+    ///
+    /// The member is subscribed before the event is sent, so it receives its
+    /// own `join` event.
+    pub async fn join(
+        &self,
+        room: impl Into<String>,
+        member: impl Into<String>,
+        metadata: impl Serialize,
+    ) -> Result<PresenceSubscription, WebSocketError> {
+        let metadata = serde_json::to_value(metadata)
+            .map_err(|e| WebSocketError::serialization_error(e.to_string()))?;
+        let info = PresenceInfo {
+            room: room.into(),
+            member: member.into(),
+            joined_at: now_millis(),
+            metadata,
+        };
+
+        let session = new_session();
+        self.store.join(info.clone(), &session).await?;
+        let subscription = self.broadcast.join(info.room.clone(), info.member.clone());
+        self.broadcast
+            .send_json_to_room(subscription.room(), &PresenceEvent::Join(info))?;
+
+        Ok(PresenceSubscription {
+            subscription: Some(subscription),
+            presence: self.clone(),
+            session,
+        })
+    }
+
+    /// Members present in a room, oldest first
+    pub async fn online(&self, room: &str) -> Result<Vec<PresenceInfo>, WebSocketError> {
+        self.store.members(room).await
+    }
+
+    /// Check whether `member` is present in a room
+    pub async fn is_online(&self, room: &str, member: &str) -> Result<bool, WebSocketError> {
+        Ok(self
+            .store
+            .members(room)
+            .await?
+            .iter()
+            .any(|info| info.member == member))
+    }
+
+    /// Remove the presence entry owned by `session` and announce the leave
+    /// to the room.
+    async fn remove(&self, room: &str, member: &str, session: &str) -> Result<(), WebSocketError> {
+        if self.store.leave(room, member, session).await?.is_some() {
+            self.broadcast.send_json_to_room(
+                room,
+                &PresenceEvent::Leave {
+                    room: room.to_string(),
+                    member: member.to_string(),
+                },
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Room membership with presence
+///
+/// Derefs to the underlying [`RoomSubscription`] to receive messages.
+/// Dropping it removes the presence entry in the background and sends a
+/// `leave` event; call [`leave`](Self::leave) to do it before continuing.
+pub struct PresenceSubscription {
+    /// `None` once the presence has been removed explicitly
+    subscription: Option<RoomSubscription>,
+    presence: Presence,
+    session: String,
+}
+
+impl PresenceSubscription {
+    /// Leave the room, remove the presence entry and announce the leave
+    pub async fn leave(mut self) -> Result<(), WebSocketError> {
+        let subscription = self
+            .subscription
+            .take()
+            .expect("presence subscription is present until dropped");
+        let (room, member) = (
+            subscription.room().to_string(),
+            subscription.member().to_string(),
+        );
+        drop(subscription);
+        self.presence.remove(&room, &member, &self.session).await
+    }
+}
+
+impl Deref for PresenceSubscription {
+    type Target = RoomSubscription;
+
+    fn deref(&self) -> &Self::Target {
+        self.subscription
+            .as_ref()
+            .expect("presence subscription is present until dropped")
+    }
+}
+
+impl DerefMut for PresenceSubscription {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        self.subscription
+            .as_mut()
+            .expect("presence subscription is present until dropped")
+    }
+}
+
+impl Drop for PresenceSubscription {
+    fn drop(&mut self) {
+        let Some(subscription) = self.subscription.take() else {
+            return;
+        };
+        let (room, member) = (
+            subscription.room().to_string(),
+            subscription.member().to_string(),
+        );
+        drop(subscription);
+
+        let presence = self.presence.clone();
+        let session = std::mem::take(&mut self.session);
+        match tokio::runtime::Handle::try_current() {
+            Ok(handle) => {
+                handle.spawn(async move {
+                    if let Err(e) = presence.remove(&room, &member, &session).await {
+                        tracing::warn!(
+                            "Failed to remove presence of {} in {}: {}",
+                            member,
+                            room,
+                            e
+                        );
+                    }
+                });
+            }
+            Err(_) => tracing::warn!(
+                "Presence of {} in {} not removed: no Tokio runtime",
+                member,
+                room
+            ),
+        }
+    }
+}
+
+/// A token unique to one join, also across instances sharing a store
+fn new_session() -> String {
+    static SEED: OnceLock<u64> = OnceLock::new();
+    static COUNTER: AtomicU64 = AtomicU64::new(0);
+
+    let seed = *SEED.get_or_init(|| RandomState::new().build_hasher().finish());
+    format!(
+        "{:016x}-{:x}",
+        seed,
+        COUNTER.fetch_add(1, Ordering::Relaxed)
+    )
+}
+
+fn now_millis() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Message;
+    use serde_json::json;
+
+    async fn next_event(sub: &mut PresenceSubscription) -> PresenceEvent {
+        match sub.recv().await {
+            Some(Ok(msg)) => msg.as_json().unwrap(),
+            other => panic!(
+                "expected a presence event, got {:?}",
+                other.map(|r| r.is_ok())
+            ),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_join_records_presence_and_announces() {
+        let presence = Presence::new(Broadcast::new());
+        let mut alice = presence
+            .join("lobby", "alice", json!({ "name": "Alice" }))
+            .await
+            .unwrap();
+
+        match next_event(&mut alice).await {
+            PresenceEvent::Join(info) => {
+                assert_eq!(info.member, "alice");
+                assert_eq!(info.metadata["name"], "Alice");
+                assert!(info.joined_at > 0);
+            }
+            other => panic!("unexpected event {:?}", other),
+        }
+
+        let _bob = presence.join("lobby", "bob", ()).await.unwrap();
+        assert!(matches!(
+            next_event(&mut alice).await,
+            PresenceEvent::Join(PresenceInfo { member, .. }) if member == "bob"
+        ));
+
+        let online: Vec<String> = presence
+            .online("lobby")
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|info| info.member)
+            .collect();
+        assert_eq!(online, vec!["alice", "bob"]);
+        assert!(presence.is_online("lobby", "bob").await.unwrap());
+        assert!(!presence.is_online("games", "bob").await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_leave_removes_presence_and_announces() {
+        let presence = Presence::new(Broadcast::new());
+        let mut alice = presence.join("lobby", "alice", ()).await.unwrap();
+        let bob = presence.join("lobby", "bob", ()).await.unwrap();
+        next_event(&mut alice).await;
+        next_event(&mut alice).await;
+
+        bob.leave().await.unwrap();
+        assert_eq!(
+            next_event(&mut alice).await,
+            PresenceEvent::Leave {
+                room: "lobby".to_string(),
+                member: "bob".to_string()
+            }
+        );
+        assert!(!presence.is_online("lobby", "bob").await.unwrap());
+        assert!(!presence.broadcast().is_member("lobby", "bob"));
+    }
+
+    #[tokio::test]
+    async fn test_drop_removes_presence() {
+        let presence = Presence::new(Broadcast::new());
+        let mut alice = presence.join("lobby", "alice", ()).await.unwrap();
+        next_event(&mut alice).await;
+
+        drop(presence.join("lobby", "bob", ()).await.unwrap());
+        next_event(&mut alice).await; // bob joined
+        assert!(matches!(
+            next_event(&mut alice).await,
+            PresenceEvent::Leave { member, .. } if member == "bob"
+        ));
+        assert_eq!(presence.online("lobby").await.unwrap().len(), 1);
+        assert_eq!(alice.room(), "lobby");
+    }
+
+    #[tokio::test]
+    async fn test_stale_subscription_does_not_remove_reconnected_member() {
+        let presence = Presence::new(Broadcast::new());
+        let mut alice = presence.join("lobby", "alice", ()).await.unwrap();
+        next_event(&mut alice).await;
+
+        let old = presence.join("lobby", "bob", ()).await.unwrap();
+        let _new = presence.join("lobby", "bob", ()).await.unwrap();
+        next_event(&mut alice).await; // bob joined
+        next_event(&mut alice).await; // bob reconnected
+
+        // The old connection is cleaned up after the reconnect; on the
+        // current-thread test runtime, yielding runs the removal task
+        drop(old);
+        tokio::task::yield_now().await;
+        assert!(presence.is_online("lobby", "bob").await.unwrap());
+        assert!(presence.broadcast().is_member("lobby", "bob"));
+
+        presence.broadcast().send_text_to_room("lobby", "ping");
+        assert_eq!(
+            alice.recv().await.unwrap().unwrap(),
+            Message::text("ping"),
+            "no leave event is sent for the stale subscription"
+        );
+    }
+
+    #[test]
+    fn test_event_format() {
+        let event = PresenceEvent::Leave {
+            room: "lobby".to_string(),
+            member: "alice".to_string(),
+        };
+        assert_eq!(
+            Message::json(&event).unwrap(),
+            Message::text(r#"{"type":"leave","room":"lobby","member":"alice"}"#)
+        );
+    }
+}
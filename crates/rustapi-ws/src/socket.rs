@@ -1,6 +1,6 @@
 //! WebSocket stream implementation
 
-use crate::{Message, WebSocketError, WsHeartbeatConfig};
+use crate::{CloseCode, Message, WebSocketError, WsHeartbeatConfig};
 use futures_util::{
     stream::{SplitSink, SplitStream},
     SinkExt, Stream, StreamExt,
@@ -9,7 +9,9 @@ use hyper::upgrade::Upgraded;
 use hyper_util::rt::TokioIo;
 use std::pin::Pin;
 use std::task::{Context, Poll};
+use tokio::io::{AsyncRead, AsyncWrite};
 use tokio::sync::mpsc;
+use tokio::time::Instant;
 use tokio_tungstenite::WebSocketStream as TungsteniteStream;
 
 /// Type alias for the upgraded connection
@@ -30,6 +32,8 @@ enum StreamImpl {
 /// A WebSocket stream
 pub struct WebSocketStream {
     inner: StreamImpl,
+    /// Message size limit enforced by the protocol layer, if any
+    max_message_size: Option<usize>,
 }
 
 impl WebSocketStream {
@@ -37,117 +41,35 @@ impl WebSocketStream {
     pub(crate) fn new(inner: UpgradedConnection) -> Self {
         Self {
             inner: StreamImpl::Direct(inner),
+            max_message_size: None,
         }
     }
 
     /// Create a new managed WebSocket stream with heartbeat
-    pub(crate) fn new_managed(inner: UpgradedConnection, config: WsHeartbeatConfig) -> Self {
-        let (mut sender, mut receiver) = inner.split();
-        let (user_tx, mut internal_rx) = mpsc::channel::<Message>(32);
+    pub(crate) fn new_managed<S>(inner: TungsteniteStream<S>, config: WsHeartbeatConfig) -> Self
+    where
+        S: AsyncRead + AsyncWrite + Unpin + Send + 'static,
+    {
+        let (user_tx, internal_rx) = mpsc::channel::<Message>(32);
         let (internal_tx, user_rx) = mpsc::channel::<Result<Message, WebSocketError>>(32);
 
-        // Spawn management task
-        tokio::spawn(async move {
-            let mut heartbeat_interval = tokio::time::interval(config.interval);
-            // First tick finishes immediately
-            heartbeat_interval.tick().await;
-
-            // For pong tracking, we can just track last activity or strictly check pongs.
-            // Simplified: we rely on TCP checks and ping writing success.
-            // If we want to enforce timeout, we need to track "last pong".
-
-            // Tungstenite handles Pong responses to our Pings automatically IF we poll the stream.
-            // But we are polling the stream in the select loop below.
-
-            // Note: Tungstenite returns Pongs as messages. We should filter them out mostly,
-            // or pass them if the user wants them?
-            // Usually heartbeat Pongs are an implementation detail.
-
-            let mut last_heartbeat = tokio::time::Instant::now();
-            let mut timeout_check = tokio::time::interval(config.timeout);
-
-            loop {
-                tokio::select! {
-                    // 1. Receive message from socket
-                    msg = receiver.next() => {
-                        match msg {
-                            Some(Ok(msg)) => {
-                                last_heartbeat = tokio::time::Instant::now();
-                                if msg.is_pong() {
-                                    // Received a pong (response to our ping)
-                                    continue;
-                                }
-                                if msg.is_ping() {
-                                    // Received a ping (from client)
-                                    // Tungstenite might have auto-replied if we used the right callback,
-                                    // but default poll_next does reply to pings by queueing a pong.
-                                    // We need to ensure that queued pong is sent.
-                                    // However, we are in a split stream.
-                                    // `receiver` is Stream. `sender` is Sink.
-                                    // Tungstenite's split separates them.
-                                    // The `receiver` will NOT automatically write to `sender`.
-                                    // WE must handle Ping replies if split?
-                                    // `tokio-tungstenite` docs: "You must handle Pings manually when split?"
-                                    // No, the `tungstenite` protocol handler is shared? No.
-
-                                    // If we receive a Ping, we should send a Pong.
-                                    let _ = sender.send(Message::Pong(msg.into_data()).into()).await;
-                                    continue;
-                                }
-
-                                // Forward other messages to user
-                                if internal_tx.send(Ok(Message::from(msg))).await.is_err() {
-                                    break; // User dropped receiver
-                                }
-                            }
-                            Some(Err(e)) => {
-                                let _ = internal_tx.send(Err(WebSocketError::from(e))).await;
-                                break;
-                            }
-                            None => break, // Connection closed
-                        }
-                    }
-
-                    // 2. Receive message from user to send
-                    msg = internal_rx.recv() => {
-                        match msg {
-                            Some(msg) => {
-                                if sender.send(msg.into()).await.is_err() {
-                                    break; // Connection closed
-                                }
-                            }
-                            None => break, // User dropped sender
-                        }
-                    }
-
-                    // 3. Send Ping
-                    _ = heartbeat_interval.tick() => {
-                         if sender.send(Message::Ping(vec![]).into()).await.is_err() {
-                             break;
-                         }
-                    }
-
-                    // 4. Check timeout
-                    _ = timeout_check.tick() => {
-                        if last_heartbeat.elapsed() > config.interval + config.timeout {
-                            // Timeout
-                            break;
-                            // This drops 'sender', closing the connection
-                        }
-                    }
-                }
-            }
-            // Loop break drops sender/receiver, closing connection
-        });
+        tokio::spawn(run_managed(inner, config, internal_tx, internal_rx));
 
         Self {
             inner: StreamImpl::Managed {
                 tx: user_tx,
                 rx: user_rx,
             },
+            max_message_size: None,
         }
     }
 
+    /// Record the message size limit the underlying socket was configured with
+    pub(crate) fn with_max_message_size(mut self, limit: Option<usize>) -> Self {
+        self.max_message_size = limit;
+        self
+    }
+
     /// Split the stream into sender and receiver halves
     pub fn split(self) -> (WebSocketSender, WebSocketReceiver) {
         match self.inner {
@@ -174,6 +96,119 @@ impl WebSocketStream {
     }
 }
 
+/// Drive a managed connection: forward messages between the socket and the
+/// user channels, send pings, and close dead or idle connections.
+///
+/// Returning drops both socket halves and the user channels, so the user's
+/// receive loop ends and anything it holds (e.g. broadcast receivers) is
+/// released.
+async fn run_managed<S>(
+    inner: TungsteniteStream<S>,
+    config: WsHeartbeatConfig,
+    internal_tx: mpsc::Sender<Result<Message, WebSocketError>>,
+    mut internal_rx: mpsc::Receiver<Message>,
+) where
+    S: AsyncRead + AsyncWrite + Unpin + Send + 'static,
+{
+    let (mut sender, mut receiver) = inner.split();
+
+    let mut heartbeat_interval = tokio::time::interval(config.interval);
+    // First tick finishes immediately
+    heartbeat_interval.tick().await;
+    let mut timeout_check = tokio::time::interval(config.check_period());
+
+    // Any frame from the peer proves it is alive; only text/binary messages
+    // count as activity for idle reaping.
+    let mut last_heartbeat = Instant::now();
+    let mut last_activity = Instant::now();
+
+    let close = loop {
+        tokio::select! {
+            // 1. Receive message from socket
+            msg = receiver.next() => {
+                match msg {
+                    Some(Ok(msg)) => {
+                        last_heartbeat = Instant::now();
+                        if msg.is_pong() {
+                            continue;
+                        }
+                        if msg.is_ping() {
+                            // The split stream does not answer pings on its own
+                            let _ = sender.send(Message::Pong(msg.into_data()).into()).await;
+                            continue;
+                        }
+                        if msg.is_text() || msg.is_binary() {
+                            last_activity = Instant::now();
+                        }
+                        if internal_tx.send(Ok(Message::from(msg))).await.is_err() {
+                            break None; // User dropped receiver
+                        }
+                    }
+                    Some(Err(e)) => {
+                        let err = WebSocketError::from(e);
+                        let close = matches!(err, WebSocketError::MessageTooLarge { .. })
+                            .then(|| Message::close_with(CloseCode::Size, "message too large"));
+                        let _ = internal_tx.send(Err(err)).await;
+                        break close;
+                    }
+                    None => break None, // Connection closed
+                }
+            }
+
+            // 2. Receive message from user to send
+            msg = internal_rx.recv() => {
+                match msg {
+                    Some(msg) => {
+                        if msg.is_text() || msg.is_binary() {
+                            last_activity = Instant::now();
+                        }
+                        if sender.send(msg.into()).await.is_err() {
+                            break None;
+                        }
+                    }
+                    None => break None, // User dropped sender
+                }
+            }
+
+            // 3. Send Ping
+            _ = heartbeat_interval.tick() => {
+                if sender.send(Message::Ping(vec![]).into()).await.is_err() {
+                    break None;
+                }
+            }
+
+            // 4. Check pong and idle timeouts
+            _ = timeout_check.tick() => {
+                if last_heartbeat.elapsed() > config.interval + config.timeout {
+                    tracing::debug!("Closing WebSocket connection: heartbeat timeout");
+                    break Some(Message::close_with(CloseCode::Away, "heartbeat timeout"));
+                }
+                if config.max_idle.is_some_and(|max| last_activity.elapsed() > max) {
+                    tracing::debug!("Closing WebSocket connection: idle timeout");
+                    break Some(Message::close_with(CloseCode::Away, "idle timeout"));
+                }
+            }
+        }
+    };
+
+    if let Some(close) = close {
+        if sender.send(close.into()).await.is_ok() {
+            // Finish the closing handshake: keep reading until the peer
+            // answers with its own close frame, so frames it sent in the
+            // meantime (e.g. pongs) do not fail with a broken pipe
+            let _ = tokio::time::timeout(config.timeout, async {
+                while let Some(Ok(msg)) = receiver.next().await {
+                    if msg.is_close() {
+                        break;
+                    }
+                }
+            })
+            .await;
+        }
+        let _ = sender.close().await;
+    }
+}
+
 // Implement helper methods directly on WebSocketStream for convenience
 impl WebSocketStream {
     /// Send a message
@@ -215,6 +250,46 @@ impl WebSocketStream {
     ) -> Result<(), WebSocketError> {
         self.send(Message::json(value)?).await
     }
+
+    /// Receive the next text/binary message and deserialize it as JSON
+    ///
+    /// Ping and pong frames are skipped. Returns `None` when the connection
+    /// is closed.
+    pub async fn recv_json<T: serde::de::DeserializeOwned>(
+        &mut self,
+    ) -> Option<Result<T, WebSocketError>> {
+        loop {
+            if let Some(result) = decode_next(self.recv().await?) {
+                return result;
+            }
+        }
+    }
+
+    /// Wrap the stream in a typed JSON codec
+    ///
+    /// The codec inherits the limit set with
+    /// [`WebSocket::max_message_size`](crate::WebSocket::max_message_size).
+    pub fn typed<In, Out>(self) -> crate::TypedSocket<In, Out> {
+        let limit = self.max_message_size;
+        let socket = crate::TypedSocket::new(self);
+        match limit {
+            Some(limit) => socket.max_message_size(limit),
+            None => socket,
+        }
+    }
+}
+
+/// Decode a received item for `recv_json`, or `None` to keep receiving
+/// (`Some(None)` means the connection is closed).
+fn decode_next<T: serde::de::DeserializeOwned>(
+    item: Result<Message, WebSocketError>,
+) -> Option<Option<Result<T, WebSocketError>>> {
+    match item {
+        Ok(Message::Close(_)) => Some(None),
+        Ok(Message::Ping(_) | Message::Pong(_)) => None,
+        Ok(msg) => Some(Some(msg.decode_json())),
+        Err(e) => Some(Some(Err(e))),
+    }
 }
 
 // Inner implementations for Sender/Receiver
@@ -292,6 +367,20 @@ impl WebSocketReceiver {
             ReceiverImpl::Managed(s) => s.recv().await,
         }
     }
+
+    /// Receive the next text/binary message and deserialize it as JSON
+    ///
+    /// Ping and pong frames are skipped. Returns `None` when the connection
+    /// is closed.
+    pub async fn recv_json<T: serde::de::DeserializeOwned>(
+        &mut self,
+    ) -> Option<Result<T, WebSocketError>> {
+        loop {
+            if let Some(result) = decode_next(self.recv().await?) {
+                return result;
+            }
+        }
+    }
 }
 
 impl Stream for WebSocketReceiver {
@@ -309,3 +398,108 @@ impl Stream for WebSocketReceiver {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+    use tokio::io::DuplexStream;
+    use tokio_tungstenite::tungstenite::protocol::Role;
+
+    async fn pair(config: WsHeartbeatConfig) -> (WebSocketStream, TungsteniteStream<DuplexStream>) {
+        let (server, client) = tokio::io::duplex(4096);
+        let server = TungsteniteStream::from_raw_socket(server, Role::Server, None).await;
+        let client = TungsteniteStream::from_raw_socket(client, Role::Client, None).await;
+        (WebSocketStream::new_managed(server, config), client)
+    }
+
+    fn close_reason(msg: tungstenite::Message) -> String {
+        match msg {
+            tungstenite::Message::Close(Some(frame)) => frame.reason.to_string(),
+            other => panic!("expected a close frame, got {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_managed_stream_sends_pings() {
+        let config = WsHeartbeatConfig::new()
+            .interval(Duration::from_millis(20))
+            .timeout(Duration::from_secs(5));
+        let (_socket, mut client) = pair(config).await;
+
+        let msg = tokio::time::timeout(Duration::from_secs(2), client.next())
+            .await
+            .unwrap()
+            .unwrap()
+            .unwrap();
+        assert!(msg.is_ping());
+    }
+
+    #[tokio::test]
+    async fn test_recv_json_skips_control_frames() {
+        let config = WsHeartbeatConfig::new().interval(Duration::from_secs(60));
+        let (mut socket, mut client) = pair(config).await;
+
+        client
+            .send(tungstenite::Message::Ping(vec![]))
+            .await
+            .unwrap();
+        client
+            .send(tungstenite::Message::text("[1,2]"))
+            .await
+            .unwrap();
+        client
+            .send(tungstenite::Message::Close(None))
+            .await
+            .unwrap();
+
+        let value: Vec<u32> = socket.recv_json().await.unwrap().unwrap();
+        assert_eq!(value, vec![1, 2]);
+        assert!(socket.recv_json::<Vec<u32>>().await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_idle_connection_is_closed() {
+        let config = WsHeartbeatConfig::new()
+            .interval(Duration::from_millis(20))
+            .timeout(Duration::from_secs(5))
+            .max_idle(Duration::from_millis(100));
+        let (mut socket, mut client) = pair(config).await;
+
+        client.send(tungstenite::Message::text("hi")).await.unwrap();
+        assert_eq!(socket.recv().await.unwrap().unwrap(), Message::text("hi"));
+
+        // Answer pings (the client stream queues pongs) but send no data
+        let closed = tokio::time::timeout(Duration::from_secs(2), async {
+            loop {
+                match client.next().await {
+                    Some(Ok(msg)) if msg.is_close() => return msg,
+                    Some(Ok(_)) => {}
+                    other => panic!("unexpected {:?}", other),
+                }
+            }
+        })
+        .await
+        .unwrap();
+        assert_eq!(close_reason(closed), "idle timeout");
+
+        // Reading on flushes the client's close reply, ending the handshake
+        assert!(client.next().await.is_none());
+        assert!(socket.recv().await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_unresponsive_peer_is_closed() {
+        let config = WsHeartbeatConfig::new()
+            .interval(Duration::from_millis(20))
+            .timeout(Duration::from_millis(40));
+        let (mut socket, client) = pair(config).await;
+
+        // The client never reads, so it never answers pings
+        let result = tokio::time::timeout(Duration::from_secs(2), socket.recv())
+            .await
+            .unwrap();
+        assert!(result.is_none());
+        drop(client);
+    }
+}
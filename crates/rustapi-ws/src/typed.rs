@@ -0,0 +1,300 @@
+//! Typed JSON messages over a WebSocket
+//!
+//! [`TypedSocket<In, Out>`] decodes incoming text/binary messages into `In`
+//! and encodes outgoing `Out` values as JSON text. Messages larger than the
+//! configured limit are rejected with close code `1009` (message too big), and
+//! messages that do not decode are rejected with `1007` (invalid payload), so
+//! clients get a structured reason instead of a silently dropped connection.
+
+use crate::{CloseCode, Message, WebSocketError, WebSocketStream};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::marker::PhantomData;
+
+/// Default maximum size of an incoming message (1 MiB)
+pub const DEFAULT_MAX_MESSAGE_SIZE: usize = 1024 * 1024;
+
+/// A WebSocket exchanging typed JSON messages
+///
+/// # Example
+///
+/// ```rust,ignore
+/// use rustapi_ws::{TypedSocket, WebSocket};
+///
+/// #[derive(Deserialize)]
+/// enum ClientMsg { Join { room: String }, Say { text: String } }
+///
+/// #[derive(Serialize)]
+/// enum ServerMsg { Said { user: String, text: String } }
+///
+/// async fn chat(ws: WebSocket) -> impl IntoResponse {
+///     ws.on_upgrade(|socket| async move {
+///         let mut socket: TypedSocket<ClientMsg, ServerMsg> =
+///             socket.typed().max_message_size(16 * 1024);
+///
+///         while let Some(msg) = socket.recv().await {
+///             match msg {
+///                 Ok(ClientMsg::Say { text }) => {
+///                     let _ = socket.send(&ServerMsg::Said { user: "me".into(), text }).await;
+///                 }
+///                 Ok(_) => {}
+///                 // The connection was closed with 1007/1009
+///                 Err(_) => break,
+///             }
+///         }
+///     })
+/// }
+/// ```
+pub struct TypedSocket<In, Out> {
+    stream: WebSocketStream,
+    max_message_size: usize,
+    closed: bool,
+    _marker: PhantomData<fn(Out) -> In>,
+}
+
+impl<In, Out> TypedSocket<In, Out> {
+    /// Wrap a WebSocket stream
+    pub fn new(stream: WebSocketStream) -> Self {
+        Self {
+            stream,
+            max_message_size: DEFAULT_MAX_MESSAGE_SIZE,
+            closed: false,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Set the maximum size of an incoming message in bytes
+    ///
+    /// This is checked once a message has been received. Set
+    /// [`WebSocket::max_message_size`](crate::WebSocket::max_message_size)
+    /// to stop oversized messages while they are read; typed sockets inherit
+    /// that limit.
+    pub fn max_message_size(mut self, bytes: usize) -> Self {
+        self.max_message_size = bytes;
+        self
+    }
+
+    /// Close the connection with a close code and reason
+    pub async fn close(mut self, code: CloseCode, reason: &str) -> Result<(), WebSocketError> {
+        self.close_with(code, reason).await
+    }
+
+    /// Unwrap the underlying stream
+    pub fn into_inner(self) -> WebSocketStream {
+        self.stream
+    }
+
+    async fn close_with(&mut self, code: CloseCode, reason: &str) -> Result<(), WebSocketError> {
+        self.closed = true;
+        self.stream
+            .send(Message::close_with(code, truncate_reason(reason)))
+            .await
+    }
+}
+
+impl<In: DeserializeOwned, Out> TypedSocket<In, Out> {
+    /// Receive the next message
+    ///
+    /// Returns `None` once the connection is closed. Oversized or malformed
+    /// messages close the connection and are returned as errors
+    /// ([`WebSocketError::MessageTooLarge`] or
+    /// [`WebSocketError::DeserializationError`]).
+    pub async fn recv(&mut self) -> Option<Result<In, WebSocketError>> {
+        if self.closed {
+            return None;
+        }
+        loop {
+            let msg = match self.stream.recv().await {
+                Some(Ok(msg)) => msg,
+                Some(Err(e @ WebSocketError::MessageTooLarge { .. })) => {
+                    let _ = self.close_with(CloseCode::Size, "message too large").await;
+                    return Some(Err(e));
+                }
+                Some(Err(e)) => return Some(Err(e)),
+                None => {
+                    self.closed = true;
+                    return None;
+                }
+            };
+
+            match msg {
+                Message::Ping(_) | Message::Pong(_) => continue,
+                Message::Close(_) => {
+                    self.closed = true;
+                    return None;
+                }
+                msg if msg.len() > self.max_message_size => {
+                    let limit = self.max_message_size;
+                    let _ = self.close_with(CloseCode::Size, "message too large").await;
+                    return Some(Err(WebSocketError::MessageTooLarge {
+                        size: msg.len(),
+                        limit,
+                    }));
+                }
+                msg => {
+                    let decoded = msg.decode_json();
+                    if let Err(e) = &decoded {
+                        let reason = format!("invalid message: {}", e);
+                        let _ = self.close_with(CloseCode::Invalid, &reason).await;
+                    }
+                    return Some(decoded);
+                }
+            }
+        }
+    }
+}
+
+impl<In, Out: Serialize> TypedSocket<In, Out> {
+    /// Send a message as JSON text
+    pub async fn send(&mut self, value: &Out) -> Result<(), WebSocketError> {
+        if self.closed {
+            return Err(WebSocketError::ConnectionClosed);
+        }
+        self.stream.send_json(value).await
+    }
+}
+
+/// Close reasons are limited to 123 bytes by the protocol.
+fn truncate_reason(reason: &str) -> &str {
+    if reason.len() <= 123 {
+        return reason;
+    }
+    let mut end = 123;
+    while !reason.is_char_boundary(end) {
+        end -= 1;
+    }
+    &reason[..end]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::upgrade::protocol_config;
+    use crate::WsHeartbeatConfig;
+    use futures_util::{SinkExt, StreamExt};
+    use serde::Deserialize;
+    use std::time::Duration;
+    use tokio::io::DuplexStream;
+    use tokio_tungstenite::tungstenite::protocol::Role;
+    use tokio_tungstenite::WebSocketStream as Client;
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct Ping {
+        seq: u32,
+    }
+
+    #[derive(Debug, Serialize)]
+    struct Pong {
+        seq: u32,
+    }
+
+    async fn pair() -> (TypedSocket<Ping, Pong>, Client<DuplexStream>) {
+        let (server, client) = tokio::io::duplex(64 * 1024);
+        let server = Client::from_raw_socket(server, Role::Server, None).await;
+        let client = Client::from_raw_socket(client, Role::Client, None).await;
+        let config = WsHeartbeatConfig::new().interval(Duration::from_secs(60));
+        (WebSocketStream::new_managed(server, config).typed(), client)
+    }
+
+    async fn limited_pair(limit: usize) -> (TypedSocket<Ping, Pong>, Client<DuplexStream>) {
+        let (server, client) = tokio::io::duplex(64 * 1024);
+        let server =
+            Client::from_raw_socket(server, Role::Server, protocol_config(Some(limit))).await;
+        let client = Client::from_raw_socket(client, Role::Client, None).await;
+        let config = WsHeartbeatConfig::new().interval(Duration::from_secs(60));
+        let stream =
+            WebSocketStream::new_managed(server, config).with_max_message_size(Some(limit));
+        (stream.typed(), client)
+    }
+
+    async fn close_code(client: &mut Client<DuplexStream>) -> u16 {
+        loop {
+            match client.next().await {
+                Some(Ok(tungstenite::Message::Close(Some(frame)))) => return frame.code.into(),
+                Some(Ok(_)) => {}
+                other => panic!("expected a close frame, got {:?}", other),
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_typed_roundtrip() {
+        let (mut socket, mut client) = pair().await;
+
+        client
+            .send(tungstenite::Message::text(r#"{"seq":1}"#))
+            .await
+            .unwrap();
+        client
+            .send(tungstenite::Message::binary(br#"{"seq":2}"#.to_vec()))
+            .await
+            .unwrap();
+        assert_eq!(socket.recv().await.unwrap().unwrap(), Ping { seq: 1 });
+        assert_eq!(socket.recv().await.unwrap().unwrap(), Ping { seq: 2 });
+
+        socket.send(&Pong { seq: 3 }).await.unwrap();
+        let reply = client.next().await.unwrap().unwrap();
+        assert_eq!(reply.into_text().unwrap(), r#"{"seq":3}"#);
+    }
+
+    #[tokio::test]
+    async fn test_decode_failure_closes_with_invalid_payload() {
+        let (mut socket, mut client) = pair().await;
+
+        client
+            .send(tungstenite::Message::text(r#"{"seq":"x"}"#))
+            .await
+            .unwrap();
+        assert!(matches!(
+            socket.recv().await,
+            Some(Err(WebSocketError::DeserializationError(_)))
+        ));
+        assert_eq!(close_code(&mut client).await, 1007);
+        assert!(socket.recv().await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_oversized_message_closes_with_size() {
+        let (socket, mut client) = pair().await;
+        let mut socket = socket.max_message_size(16);
+
+        client
+            .send(tungstenite::Message::text(format!(
+                r#"{{"seq":1,"pad":"{}"}}"#,
+                "x".repeat(32)
+            )))
+            .await
+            .unwrap();
+        assert!(matches!(
+            socket.recv().await,
+            Some(Err(WebSocketError::MessageTooLarge { limit: 16, .. }))
+        ));
+        assert_eq!(close_code(&mut client).await, 1009);
+    }
+
+    #[tokio::test]
+    async fn test_protocol_limit_stops_oversized_frames() {
+        let (mut socket, mut client) = limited_pair(16).await;
+
+        client
+            .send(tungstenite::Message::text(format!(
+                r#"{{"seq":1,"pad":"{}"}}"#,
+                "x".repeat(32)
+            )))
+            .await
+            .unwrap();
+        assert!(matches!(
+            socket.recv().await,
+            Some(Err(WebSocketError::MessageTooLarge { limit: 16, .. }))
+        ));
+        assert_eq!(close_code(&mut client).await, 1009);
+    }
+
+    #[test]
+    fn test_close_reason_is_truncated() {
+        let reason = "é".repeat(100);
+        let truncated = truncate_reason(&reason);
+        assert!(truncated.len() <= 123);
+        assert!(reason.starts_with(truncated));
+    }
+}
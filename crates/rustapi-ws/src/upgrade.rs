@@ -1,5 +1,6 @@
 //! WebSocket upgrade response
 
+use crate::limits::WsConnectionPermit;
 use crate::{WebSocketError, WebSocketStream, WsHeartbeatConfig};
 use http::{header, Response, StatusCode};
 use hyper::upgrade::OnUpgrade;
@@ -9,7 +10,7 @@ use rustapi_openapi::{Operation, ResponseModifier, ResponseSpec};
 use std::collections::BTreeMap;
 use std::future::Future;
 use std::pin::Pin;
-use tokio_tungstenite::tungstenite::protocol::Role;
+use tokio_tungstenite::tungstenite::protocol::{Role, WebSocketConfig};
 
 /// Type alias for WebSocket upgrade callback
 type UpgradeCallback =
@@ -39,8 +40,12 @@ pub struct WebSocketUpgrade {
     compression: Option<WsCompressionConfig>,
     /// Configured heartbeat
     heartbeat: Option<WsHeartbeatConfig>,
+    /// Maximum size of an incoming message or frame
+    max_message_size: Option<usize>,
     /// OnUpgrade future from hyper
     on_upgrade_fut: Option<OnUpgrade>,
+    /// Place of the connection under `WsConnectionLimits`
+    permit: Option<WsConnectionPermit>,
 }
 
 impl WebSocketUpgrade {
@@ -69,7 +74,9 @@ impl WebSocketUpgrade {
             client_extensions,
             compression: None,
             heartbeat: None,
+            max_message_size: None,
             on_upgrade_fut,
+            permit: None,
         }
     }
 
@@ -79,6 +86,21 @@ impl WebSocketUpgrade {
         self
     }
 
+    /// Limit the size of incoming messages and frames in bytes
+    ///
+    /// The limit is enforced while frames are read, so larger messages are
+    /// never buffered; the connection is closed with `1009` (message too big).
+    pub fn max_message_size(mut self, bytes: usize) -> Self {
+        self.max_message_size = Some(bytes);
+        self
+    }
+
+    /// Hold a connection-limit permit for the lifetime of the connection
+    pub(crate) fn permit(mut self, permit: WsConnectionPermit) -> Self {
+        self.permit = Some(permit);
+        self
+    }
+
     /// Enable WebSocket compression
     pub fn compress(mut self, config: WsCompressionConfig) -> Self {
         self.compression = Some(config);
@@ -147,6 +169,8 @@ impl IntoResponse for WebSocketUpgrade {
             (self.on_upgrade_fut.take(), self.on_upgrade.take())
         {
             let heartbeat = self.heartbeat;
+            let max_message_size = self.max_message_size;
+            let permit = self.permit.take();
 
             // TODO: Apply compression config to WebSocketConfig if/when supported by from_raw_socket
             // Currently tungstenite negotiation logic in handshake is separate from stream config
@@ -157,7 +181,7 @@ impl IntoResponse for WebSocketUpgrade {
                         let ws_stream = tokio_tungstenite::WebSocketStream::from_raw_socket(
                             TokioIo::new(upgraded),
                             Role::Server,
-                            None,
+                            protocol_config(max_message_size),
                         )
                         .await;
 
@@ -165,9 +189,11 @@ impl IntoResponse for WebSocketUpgrade {
                             WebSocketStream::new_managed(ws_stream, hb_config)
                         } else {
                             WebSocketStream::new(ws_stream)
-                        };
+                        }
+                        .with_max_message_size(max_message_size);
 
                         callback(socket).await;
+                        drop(permit);
                     }
                     Err(e) => {
                         tracing::error!("WebSocket upgrade failed: {:?}", e);
@@ -184,6 +210,16 @@ impl IntoResponse for WebSocketUpgrade {
     }
 }
 
+/// Protocol settings for a socket limited to `max_message_size` bytes per message
+pub(crate) fn protocol_config(max_message_size: Option<usize>) -> Option<WebSocketConfig> {
+    let limit = max_message_size?;
+    Some(WebSocketConfig {
+        max_message_size: Some(limit),
+        max_frame_size: Some(limit),
+        ..Default::default()
+    })
+}
+
 impl ResponseModifier for WebSocketUpgrade {
     fn update_response(op: &mut Operation) {
         op.responses.insert(